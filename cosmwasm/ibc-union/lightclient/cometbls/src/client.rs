@@ -286,10 +286,9 @@ fn update_state<T: ZkpVerifier>(
     mut consensus_state: ConsensusState,
     header: Header,
 ) -> Result<StateUpdate<CometblsLightClient<T>>, Error> {
-    let untrusted_height = Height::new_with_revision(
-        header.trusted_height.revision(),
-        header.signed_header.height.inner() as u64,
-    );
+    let untrusted_height = header
+        .trusted_height
+        .with_height(header.signed_header.height.inner() as u64);
 
     consensus_state.app_hash = MerkleRoot {
         hash: header.signed_header.app_hash.into_encoding(),
@@ -1,15 +1,17 @@
+use std::cmp::Ordering;
+
 use cometbft_types::{
     crypto::public_key::PublicKey,
     types::{commit::Commit, signed_header::SignedHeader, validator_set::ValidatorSet},
 };
-use cosmwasm_std::{Addr, Empty};
+use cosmwasm_std::{Addr, Deps, Empty};
 use ibc_union_light_client::{
     ClientCreationResult, IbcClient, IbcClientCtx, IbcClientError, StateUpdate,
 };
 use ibc_union_msg::lightclient::Status;
 use ibc_union_spec::path::IBC_UNION_COSMWASM_COMMITMENT_PREFIX;
 use ics23::ibc_api::SDK_SPECS;
-use tendermint_light_client_types::{ClientState, ConsensusState, Header};
+use tendermint_light_client_types::{ClientState, ConsensusState, Header, Misbehaviour};
 use tendermint_verifier::types::{HostFns, SignatureVerifier};
 use unionlabs::{
     bounded::BoundedI64,
@@ -25,7 +27,7 @@ use unionlabs::{
 use crate::{
     errors::{
         Error, IbcHeightTooLargeForTendermintHeight, InvalidChainId, InvalidHeaderError,
-        MathOverflow, RevisionNumberMismatch, TrustedValidatorsMismatch,
+        InvalidMisbehaviourError, MathOverflow, RevisionNumberMismatch, TrustedValidatorsMismatch,
     },
     verifier::Ed25519Verifier,
 };
@@ -39,8 +41,7 @@ impl IbcClient for TendermintLightClient {
 
     type Header = Header;
 
-    // TODO(aeryz): Change this to appropriate misbehavior type when it is implemented
-    type Misbehaviour = Header;
+    type Misbehaviour = Misbehaviour;
 
     type ClientState = ClientState;
 
@@ -98,33 +99,47 @@ impl IbcClient for TendermintLightClient {
     ) -> Result<StateUpdate<Self>, IbcClientError<Self>> {
         let client_state = ctx.read_self_client_state()?;
         let consensus_state = ctx.read_self_consensus_state(header.trusted_height.height())?;
-        match header.validator_set.validators.first().map(|v| &v.pub_key) {
-            #[cfg(feature = "bls")]
-            Some(PublicKey::Bls12_381(_)) => Ok(verify_header(
-                client_state,
-                consensus_state,
-                header,
-                ctx.env.block.time,
-                &SignatureVerifier::new(crate::verifier::bls::Bls12Verifier::new(ctx.deps)),
-            )?),
-            Some(PublicKey::Ed25519(_)) => Ok(verify_header(
-                client_state,
-                consensus_state,
-                header,
-                ctx.env.block.time,
-                &SignatureVerifier::new(Ed25519Verifier::new(ctx.deps)),
-            )?),
-            _ => Err(Error::InvalidValidatorSet.into()),
-        }
+
+        Ok(verify_header_with_host(
+            ctx.deps,
+            client_state,
+            consensus_state,
+            header,
+            ctx.env.block.time,
+        )?)
     }
 
     fn misbehaviour(
-        _ctx: IbcClientCtx<Self>,
+        ctx: IbcClientCtx<Self>,
         _caller: Addr,
-        _misbehaviour: Self::Misbehaviour,
+        misbehaviour: Self::Misbehaviour,
         _relayer: Addr,
     ) -> Result<Self::ClientState, IbcClientError<Self>> {
-        Err(Error::Unimplemented.into())
+        let mut client_state = ctx.read_self_client_state()?;
+
+        if client_state.frozen_height.unwrap_or_default().height() != 0 {
+            return Err(Error::ClientFrozen.into());
+        }
+
+        check_conflicting_headers(&misbehaviour)?;
+
+        // both headers must be valid updates from a trusted state for the evidence to hold, the
+        // trusted states may differ
+        for header in [misbehaviour.header_1, misbehaviour.header_2] {
+            let consensus_state = ctx.read_self_consensus_state(header.trusted_height.height())?;
+
+            verify_header_with_host(
+                ctx.deps,
+                client_state.clone(),
+                consensus_state,
+                header,
+                ctx.env.block.time,
+            )?;
+        }
+
+        client_state.frozen_height = Some(FROZEN_HEIGHT);
+
+        Ok(client_state)
     }
 
     fn status(ctx: IbcClientCtx<Self>, client_state: &Self::ClientState) -> Status {
@@ -170,6 +185,78 @@ impl IbcClient for TendermintLightClient {
     }
 }
 
+/// The height a client is frozen at after misbehaviour, matching ibc-go.
+pub const FROZEN_HEIGHT: Height = Height::new_with_revision(0, 1);
+
+/// Verify `header` with the signature verifier for the key type of its validator set.
+fn verify_header_with_host(
+    deps: Deps,
+    client_state: ClientState,
+    consensus_state: ConsensusState,
+    header: Header,
+    block_timestamp: cosmwasm_std::Timestamp,
+) -> Result<StateUpdate<TendermintLightClient>, Error> {
+    match header.validator_set.validators.first().map(|v| &v.pub_key) {
+        #[cfg(feature = "bls")]
+        Some(PublicKey::Bls12_381(_)) => verify_header(
+            client_state,
+            consensus_state,
+            header,
+            block_timestamp,
+            &SignatureVerifier::new(crate::verifier::bls::Bls12Verifier::new(deps)),
+        ),
+        Some(PublicKey::Ed25519(_)) => verify_header(
+            client_state,
+            consensus_state,
+            header,
+            block_timestamp,
+            &SignatureVerifier::new(Ed25519Verifier::new(deps)),
+        ),
+        _ => Err(Error::InvalidValidatorSet),
+    }
+}
+
+/// Check that the headers of `misbehaviour` conflict, as in ibc-go: either they are for the same
+/// height but commit to different blocks, or `header_1` is for a higher height than `header_2` but
+/// its time is not after the time of `header_2`, which violates the monotonicity of BFT time.
+pub fn check_conflicting_headers(misbehaviour: &Misbehaviour) -> Result<(), Error> {
+    let header_1 = &misbehaviour.header_1.signed_header;
+    let header_2 = &misbehaviour.header_2.signed_header;
+
+    match header_1.header.height.cmp(&header_2.header.height) {
+        Ordering::Equal => {
+            if header_1.commit.block_id.hash == header_2.commit.block_id.hash {
+                return Err(InvalidMisbehaviourError::SameBlock(
+                    header_1
+                        .commit
+                        .block_id
+                        .hash
+                        .map(|hash| hash.into_encoding()),
+                )
+                .into());
+            }
+        }
+        Ordering::Greater => {
+            if header_1.header.time > header_2.header.time {
+                return Err(InvalidMisbehaviourError::MonotonicTime {
+                    height_1: header_1.header.height.inner(),
+                    height_2: header_2.header.height.inner(),
+                }
+                .into());
+            }
+        }
+        Ordering::Less => {
+            return Err(InvalidMisbehaviourError::HeaderOrder {
+                height_1: header_1.header.height.inner(),
+                height_2: header_2.header.height.inner(),
+            }
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
 pub fn verify_header<V: HostFns>(
     mut client_state: ClientState,
     consensus_state: ConsensusState,
@@ -339,8 +426,7 @@ pub fn is_client_expired(
 /// revision height. This function is a utility to generate a `Height` type out
 /// of the update data.
 pub fn height_from_header(header: &Header) -> Height {
-    Height::new_with_revision(
-        header.trusted_height.revision(),
+    header.trusted_height.with_height(
         // SAFETY: height's bounds are [0..i64::MAX]
         header.signed_header.header.height.inner() as u64,
     )
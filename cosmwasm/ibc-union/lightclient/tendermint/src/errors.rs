@@ -60,6 +60,12 @@ pub enum Error {
 
     #[error("invalid or empty validator set, supported keys are: bls12381 and ed25519")]
     InvalidValidatorSet,
+
+    #[error("invalid misbehaviour")]
+    InvalidMisbehaviour(#[from] InvalidMisbehaviourError),
+
+    #[error("client is frozen")]
+    ClientFrozen,
 }
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
@@ -85,6 +91,16 @@ pub enum InvalidHeaderError {
     },
 }
 
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum InvalidMisbehaviourError {
+    #[error("the height of the first header ({height_1}) must be at least the height of the second header ({height_2})")]
+    HeaderOrder { height_1: i64, height_2: i64 },
+    #[error("the headers commit to the same block ({0:?})")]
+    SameBlock(Option<H256>),
+    #[error("the time of the header at height {height_1} is after the time of the header at height {height_2}, so the headers do not violate monotonic bft time")]
+    MonotonicTime { height_1: i64, height_2: i64 },
+}
+
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub enum MigrateClientStoreError {
     #[error("substitute client is frozen")]
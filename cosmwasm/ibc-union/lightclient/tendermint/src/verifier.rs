@@ -53,8 +53,8 @@ impl HostFns for Ed25519Verifier<'_> {
 pub mod bls {
     use cometbft_types::crypto::public_key::PublicKey;
     use cosmwasm_std::{Deps, BLS12_381_G1_GENERATOR};
-    use sha2::Digest;
     use tendermint_verifier::types::HostFns;
+    use unionlabs::primitives::hash::{HashFunction, Sha256};
 
     pub struct Bls12Verifier<'a> {
         pub(crate) deps: Deps<'a>,
@@ -76,7 +76,7 @@ pub mod bls {
             match pubkey {
                 PublicKey::Bls12_381(ref pubkey) => {
                     let msg = if msg.len() > 32 {
-                        sha2::Sha256::new().chain_update(msg).finalize().to_vec()
+                        Sha256::hash(msg).into()
                     } else {
                         msg.to_vec()
                     };
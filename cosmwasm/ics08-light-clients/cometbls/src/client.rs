@@ -210,10 +210,9 @@ impl<T: ZkpVerifier> IbcClient for CometblsLightClient<T> {
             read_consensus_state(deps.as_ref(), &header.trusted_height)?
                 .ok_or(Error::ConsensusStateNotFound(header.trusted_height))?;
 
-        let untrusted_height = Height::new_with_revision(
-            header.trusted_height.revision(),
-            header.signed_header.height.inner() as u64,
-        );
+        let untrusted_height = header
+            .trusted_height
+            .with_height(header.signed_header.height.inner() as u64);
 
         if untrusted_height > client_state.latest_height {
             client_state.latest_height = untrusted_height;
@@ -448,8 +447,7 @@ fn is_client_expired(
 /// revision height. This function is a utility to generate a `Height` type out
 /// of the update data.
 fn height_from_header(header: &Header) -> Height {
-    Height::new_with_revision(
-        header.trusted_height.revision(),
+    header.trusted_height.with_height(
         // SAFETY: height's bounds are [0..i64::MAX]
         header.signed_header.height.inner() as u64,
     )
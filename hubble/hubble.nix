@@ -65,6 +65,22 @@
           type = types.path;
           default = "";
         };
+        databases-file = mkOption {
+          description = lib.mdDoc ''
+            Path to a file containing a json object of additional database names to database urls.
+          '';
+          example = "/run/keys/hubble-databases.json";
+          type = types.nullOr types.path;
+          default = null;
+        };
+        database-max-connections = mkOption {
+          description = lib.mdDoc ''
+            Maximum number of connections of the pool of each database (default: 40).
+          '';
+          example = 80;
+          type = types.nullOr types.int;
+          default = null;
+        };
         nats-url-file = mkOption {
           description = lib.mdDoc ''
             Path to a file containing the nats connect string (e.g. nats://localhost).
@@ -97,6 +113,65 @@
           type = types.nullOr types.str;
           default = null;
         };
+        max-concurrent-fetches = mkOption {
+          description = lib.mdDoc ''
+            Maximum number of ranges of blocks fetched at the same time over all indexers, when catching up or fixing blocks (unlimited when null).
+          '';
+          example = 4;
+          type = types.nullOr types.int;
+          default = null;
+        };
+        pricing = mkOption {
+          description = lib.mdDoc ''
+            Price providers used to annotate transfers with their usd value (disabled when null).
+          '';
+          example = {
+            coingecko.url = "https://api.coingecko.com/api/v3";
+            pyth.url = "https://hermes.pyth.network";
+          };
+          type = types.nullOr types.attrs;
+          default = null;
+        };
+        token-bucket-alerts = mkOption {
+          description = lib.mdDoc ''
+            Webhook that is notified when the estimated capacity of a token bucket drops below a threshold (no alerts when null).
+          '';
+          example = {
+            webhook = "https://alerts.example.com/token-buckets";
+            threshold = 0.1;
+          };
+          type = types.nullOr types.attrs;
+          default = null;
+        };
+        lag-slo = mkOption {
+          description = lib.mdDoc ''
+            Ingestion lag slo of the indexers, alerting when its error budget burns too fast (lag is not monitored when null).
+          '';
+          example = {
+            target_lag_seconds = 60;
+            objective = 0.99;
+            burn_rate_threshold = 14.4;
+            webhook = "https://alerts.example.com/ingestion-lag";
+          };
+          type = types.nullOr types.attrs;
+          default = null;
+        };
+        webhook-secret-file = mkOption {
+          description = lib.mdDoc ''
+            Path to a file containing the secret that webhook payloads are signed with (unsigned when null).
+          '';
+          example = "/run/keys/hubble-webhook.key";
+          type = types.nullOr types.path;
+          default = null;
+        };
+        coingecko-api-key-file = mkOption {
+          description = lib.mdDoc ''
+            Path to a file containing the coingecko api key.
+          '';
+          example = "/run/keys/coingecko.key";
+          type = types.nullOr types.path;
+          default = null;
+        };
         indexers = mkOption {
           type = types.listOf (
             types.submodule {
@@ -143,6 +218,26 @@
                 default = null;
                 description = "Drain events: stop emitting events, so all events can be read from the stream (default false)";
               };
+              options.database = mkOption {
+                type = types.nullOr types.str;
+                default = null;
+                description = "Name of the database (see databases-file) to write to (default: the main database)";
+              };
+              options.max_connections = mkOption {
+                type = types.nullOr types.int;
+                default = null;
+                example = 10;
+                description = "Size of a connection pool dedicated to this indexer (default: share the pool of its database)";
+              };
+              options.finality = mkOption {
+                description = "how to determine the last finalized block of ethereum and tendermint chains (default: the finality of the chain)";
+                example = {
+                  type = "confirmation_depth";
+                  confirmation_depth = 64;
+                };
+                default = null;
+                type = types.nullOr types.attrs;
+              };
               options.tx_search_max_page_size = mkOption {
                 type = types.int;
                 description = "Maximum number of transactions to fetch in one page";
@@ -236,20 +331,42 @@
                   filterNullValues = lib.attrsets.filterAttrsRecursive (_n: v: v != null);
                   indexersWithoutNulls = map filterNullValues cfg.indexers;
                   indexersJson = builtins.toJSON indexersWithoutNulls;
+                  databasesArg = if cfg.databases-file != null then "--databases @${cfg.databases-file}" else "";
+                  databaseMaxConnectionsArg =
+                    if cfg.database-max-connections != null then "--database-max-connections ${toString cfg.database-max-connections}" else "";
                   natsUrlArg = if cfg.nats-url-file != null then "--nats-url @${cfg.nats-url-file}" else "";
                   natsUsernameArg =
                     if cfg.nats-username-file != null then "--nats-username @${cfg.nats-username-file}" else "";
                   natsPasswordArg =
                     if cfg.nats-password-file != null then "--nats-password @${cfg.nats-password-file}" else "";
                   natsConsumerArg = if cfg.nats-consumer != null then "--nats-consumer ${cfg.nats-consumer}" else "";
+                  maxConcurrentFetchesArg =
+                    if cfg.max-concurrent-fetches != null then "--max-concurrent-fetches ${toString cfg.max-concurrent-fetches}" else "";
+                  pricingArg = if cfg.pricing != null then "--pricing '${builtins.toJSON cfg.pricing}'" else "";
+                  tokenBucketAlertsArg =
+                    if cfg.token-bucket-alerts != null then "--token-bucket-alerts '${builtins.toJSON cfg.token-bucket-alerts}'" else "";
+                  lagSloArg =
+                    if cfg.lag-slo != null then "--lag-slo '${builtins.toJSON cfg.lag-slo}'" else "";
+                  webhookSecretArg =
+                    if cfg.webhook-secret-file != null then "--webhook-secret @${cfg.webhook-secret-file}" else "";
+                  coingeckoApiKeyArg =
+                    if cfg.coingecko-api-key-file != null then "--coingecko-api-key @${cfg.coingecko-api-key-file}" else "";
                 in
                 ''
                   ${pkgs.lib.getExe cfg.package}  \
                     --database-url "$(head -n 1 ${cfg.api-key-file})" \
+                    ${databasesArg} \
+                    ${databaseMaxConnectionsArg} \
                     ${natsUrlArg} \
                     ${natsUsernameArg} \
                     ${natsPasswordArg} \
                     ${natsConsumerArg} \
+                    ${maxConcurrentFetchesArg} \
+                    ${pricingArg} \
+                    ${tokenBucketAlertsArg} \
+                    ${lagSloArg} \
+                    ${webhookSecretArg} \
+                    ${coingeckoApiKeyArg} \
                     --log-format ${cfg.log-format} \
                     --metrics-addr ${cfg.metrics-addr} \
                     --indexers '${indexersJson}'
@@ -0,0 +1,541 @@
+use std::sync::{Arc, Mutex};
+
+use duckdb::{params, types::Type, Connection};
+use time::OffsetDateTime;
+use tracing::info;
+
+use crate::api::{
+    postgres::{get_packets_to_archive, get_transfers_to_archive, insert_archived_partition},
+    to_hex, Databases, Packet, PacketStatus, Transfer,
+};
+
+/// `hubble.archived_partitions.table_name` of archived packet sends.
+pub const PACKET_SEND_PARTITIONS: &str = "packet_send";
+/// `hubble.archived_partitions.table_name` of archived transfers.
+pub const TRANSFER_PARTITIONS: &str = "transfer";
+
+/// The records that can be archived, by their `hubble.archived_partitions.table_name`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ArchivedTable {
+    PacketSend,
+    Transfer,
+}
+
+/// A packet send as it is stored in an archived partition.
+pub struct ArchivedPacket {
+    pub packet_hash: Vec<u8>,
+    pub source_channel_id: i32,
+    pub destination_channel_id: i32,
+    pub transaction_hash: Vec<u8>,
+    pub height: i64,
+    pub event_index: i32,
+    pub timestamp: OffsetDateTime,
+    pub status: PacketStatus,
+}
+
+/// A transfer as it is stored in an archived partition. The canonical addresses are stored to look
+/// up the transfers of an address.
+pub struct ArchivedTransfer {
+    pub universal_chain_id: String,
+    pub counterparty_universal_chain_id: String,
+    pub source_channel_id: i32,
+    pub destination_channel_id: i32,
+    pub packet_hash: Vec<u8>,
+    pub transaction_hash: Vec<u8>,
+    pub height: i64,
+    pub timestamp: OffsetDateTime,
+    pub sender_canonical: Vec<u8>,
+    pub receiver_canonical: Vec<u8>,
+    pub sender_display: String,
+    pub receiver_display: String,
+    pub base_token: Vec<u8>,
+    pub base_token_symbol: String,
+    pub base_amount: String,
+    pub quote_token: Vec<u8>,
+    pub quote_amount: String,
+    pub amount_usd: Option<String>,
+    pub sort_order: String,
+    pub transfer_index: i32,
+}
+
+/// Write the packet sends or transfers of a chain in `[start_height, end_height)` to a parquet file
+/// at `uri`, and list it in `hubble.archived_partitions` of the database of the chain. The rows are
+/// not pruned from postgres, which is left to the operator once the partition is listed (until
+/// then, the api skips the archived copies). Packets are archived with their status at the time of
+/// archiving, so only ranges of settled packets should be archived.
+pub async fn archive_partition(
+    databases: &Databases,
+    table: ArchivedTable,
+    universal_chain_id: &str,
+    start_height: i64,
+    end_height: i64,
+    uri: String,
+) -> color_eyre::Result<()> {
+    let db = databases.chain(universal_chain_id);
+    let cold_storage = ColdStorage::open()?;
+
+    let (table_name, rows) = match table {
+        ArchivedTable::PacketSend => {
+            let packets =
+                get_packets_to_archive(db, universal_chain_id, start_height, end_height).await?;
+            let rows = packets.len();
+            cold_storage.write_packets(packets, uri.clone()).await?;
+            (PACKET_SEND_PARTITIONS, rows)
+        }
+        ArchivedTable::Transfer => {
+            let transfers =
+                get_transfers_to_archive(db, universal_chain_id, start_height, end_height).await?;
+            let rows = transfers.len();
+            cold_storage.write_transfers(transfers, uri.clone()).await?;
+            (TRANSFER_PARTITIONS, rows)
+        }
+    };
+
+    insert_archived_partition(
+        db,
+        table_name,
+        universal_chain_id,
+        start_height,
+        end_height,
+        &uri,
+    )
+    .await?;
+
+    info!("archived {rows} {table_name} rows of {universal_chain_id} in [{start_height}, {end_height}) to {uri}");
+
+    Ok(())
+}
+
+/// Reads and writes archived partitions: parquet files with rows that were pruned from postgres,
+/// listed in `hubble.archived_partitions`. Queries run on an embedded duckdb, which reads the files
+/// from any location it supports (local paths or urls).
+#[derive(Clone)]
+pub struct ColdStorage {
+    /// Every query runs on its own connection to this database, such that queries run
+    /// concurrently. The lock is only held to open the connection.
+    database: Arc<Mutex<Connection>>,
+}
+
+impl ColdStorage {
+    pub fn open() -> duckdb::Result<Self> {
+        Ok(Self {
+            database: Arc::new(Mutex::new(Connection::open_in_memory()?)),
+        })
+    }
+
+    fn connect(&self) -> duckdb::Result<Connection> {
+        self.database
+            .lock()
+            .expect("cold storage lock is not poisoned")
+            .try_clone()
+    }
+
+    /// Packets sent on a channel in the `uris` partitions, newest first. Pages continue before
+    /// (`height`, `event_index`). The status of an archived packet is final, so it is stored in
+    /// the partition instead of being derived from the other packet events.
+    pub async fn get_packets_by_channel(
+        &self,
+        uris: Vec<String>,
+        channel_id: i32,
+        before: Option<(i64, i32)>,
+        limit: i64,
+    ) -> duckdb::Result<Vec<(Packet, i32)>> {
+        let cold_storage = self.clone();
+        let (before_height, before_event_index) = before.unwrap_or((i64::MAX, i32::MAX));
+
+        tokio::task::spawn_blocking(move || {
+            let connection = cold_storage.connect()?;
+
+            let mut statement = connection.prepare(&format!(
+                r#"
+                SELECT
+                    packet_hash,
+                    source_channel_id,
+                    destination_channel_id,
+                    transaction_hash,
+                    height,
+                    event_index,
+                    epoch_us(timestamp),
+                    status
+                FROM read_parquet({})
+                WHERE source_channel_id = ?
+                AND (height < ? OR (height = ? AND event_index < ?))
+                ORDER BY height DESC, event_index DESC
+                LIMIT ?
+                "#,
+                parquet_files(&uris),
+            ))?;
+
+            let packets = statement
+                .query_map(
+                    params![
+                        channel_id,
+                        before_height,
+                        before_height,
+                        before_event_index,
+                        limit
+                    ],
+                    |row| {
+                        let timestamp = row.get::<_, i64>(6)?;
+                        let status = row.get::<_, String>(7)?;
+
+                        Ok((
+                            Packet {
+                                packet_hash: to_hex(&row.get::<_, Vec<u8>>(0)?),
+                                source_channel_id: row.get(1)?,
+                                destination_channel_id: row.get(2)?,
+                                transaction_hash: to_hex(&row.get::<_, Vec<u8>>(3)?),
+                                height: row.get(4)?,
+                                timestamp: from_epoch_us(6, timestamp)?,
+                                status: parse_status(&status).ok_or_else(|| {
+                                    duckdb::Error::InvalidColumnType(7, status, Type::Text)
+                                })?,
+                            },
+                            row.get(5)?,
+                        ))
+                    },
+                )?
+                .collect::<duckdb::Result<Vec<_>>>()?;
+
+            Ok(packets)
+        })
+        .await
+        .expect("cold storage query does not panic")
+    }
+
+    /// Transfers from or to the canonical `address` in the `uris` partitions, newest first. Pages
+    /// continue before `before_sort_order`.
+    pub async fn get_transfers_by_address(
+        &self,
+        uris: Vec<String>,
+        address: Vec<u8>,
+        before_sort_order: Option<String>,
+        limit: i64,
+    ) -> duckdb::Result<Vec<Transfer>> {
+        let cold_storage = self.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let connection = cold_storage.connect()?;
+
+            let mut statement = connection.prepare(&format!(
+                r#"
+                SELECT
+                    universal_chain_id,
+                    counterparty_universal_chain_id,
+                    source_channel_id,
+                    destination_channel_id,
+                    packet_hash,
+                    transaction_hash,
+                    height,
+                    epoch_us(timestamp),
+                    sender_display,
+                    receiver_display,
+                    base_token,
+                    base_token_symbol,
+                    base_amount,
+                    quote_token,
+                    quote_amount,
+                    amount_usd,
+                    sort_order,
+                    transfer_index
+                FROM read_parquet({})
+                WHERE (sender_canonical = ? OR receiver_canonical = ?)
+                AND (?::TEXT IS NULL OR sort_order < ?)
+                ORDER BY sort_order DESC
+                LIMIT ?
+                "#,
+                parquet_files(&uris),
+            ))?;
+
+            let transfers = statement
+                .query_map(
+                    params![
+                        address,
+                        address,
+                        before_sort_order,
+                        before_sort_order,
+                        limit
+                    ],
+                    |row| {
+                        Ok(Transfer {
+                            universal_chain_id: row.get(0)?,
+                            counterparty_universal_chain_id: row.get(1)?,
+                            source_channel_id: row.get(2)?,
+                            destination_channel_id: row.get(3)?,
+                            packet_hash: to_hex(&row.get::<_, Vec<u8>>(4)?),
+                            transaction_hash: to_hex(&row.get::<_, Vec<u8>>(5)?),
+                            height: row.get(6)?,
+                            timestamp: from_epoch_us(7, row.get(7)?)?,
+                            sender: row.get(8)?,
+                            receiver: row.get(9)?,
+                            base_token: to_hex(&row.get::<_, Vec<u8>>(10)?),
+                            base_token_symbol: row.get(11)?,
+                            base_amount: row.get(12)?,
+                            quote_token: to_hex(&row.get::<_, Vec<u8>>(13)?),
+                            quote_amount: row.get(14)?,
+                            amount_usd: row.get(15)?,
+                            sort_order: row.get(16)?,
+                            transfer_index: row.get(17)?,
+                        })
+                    },
+                )?
+                .collect::<duckdb::Result<Vec<_>>>()?;
+
+            Ok(transfers)
+        })
+        .await
+        .expect("cold storage query does not panic")
+    }
+
+    /// Write `packets` to a parquet file at `uri`, in the layout read by
+    /// [`Self::get_packets_by_channel`].
+    pub async fn write_packets(
+        &self,
+        packets: Vec<ArchivedPacket>,
+        uri: String,
+    ) -> duckdb::Result<()> {
+        let cold_storage = self.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let connection = cold_storage.connect()?;
+
+            connection.execute_batch(
+                r#"
+                CREATE TEMP TABLE archived_packets (
+                    packet_hash BLOB NOT NULL,
+                    source_channel_id INTEGER NOT NULL,
+                    destination_channel_id INTEGER NOT NULL,
+                    transaction_hash BLOB NOT NULL,
+                    height BIGINT NOT NULL,
+                    event_index INTEGER NOT NULL,
+                    timestamp_us BIGINT NOT NULL,
+                    status TEXT NOT NULL
+                );
+                "#,
+            )?;
+
+            {
+                let mut appender = connection.appender("archived_packets")?;
+
+                for packet in packets {
+                    appender.append_row(params![
+                        packet.packet_hash,
+                        packet.source_channel_id,
+                        packet.destination_channel_id,
+                        packet.transaction_hash,
+                        packet.height,
+                        packet.event_index,
+                        to_epoch_us(packet.timestamp),
+                        status_name(packet.status),
+                    ])?;
+                }
+            }
+
+            connection.execute_batch(&format!(
+                r#"
+                COPY (
+                    SELECT * EXCLUDE (timestamp_us), make_timestamp(timestamp_us) AS timestamp
+                    FROM archived_packets
+                ) TO {} (FORMAT PARQUET);
+                DROP TABLE archived_packets;
+                "#,
+                quote(&uri),
+            ))
+        })
+        .await
+        .expect("cold storage query does not panic")
+    }
+
+    /// Write `transfers` to a parquet file at `uri`, in the layout read by
+    /// [`Self::get_transfers_by_address`].
+    pub async fn write_transfers(
+        &self,
+        transfers: Vec<ArchivedTransfer>,
+        uri: String,
+    ) -> duckdb::Result<()> {
+        let cold_storage = self.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let connection = cold_storage.connect()?;
+
+            connection.execute_batch(
+                r#"
+                CREATE TEMP TABLE archived_transfers (
+                    universal_chain_id TEXT NOT NULL,
+                    counterparty_universal_chain_id TEXT NOT NULL,
+                    source_channel_id INTEGER NOT NULL,
+                    destination_channel_id INTEGER NOT NULL,
+                    packet_hash BLOB NOT NULL,
+                    transaction_hash BLOB NOT NULL,
+                    height BIGINT NOT NULL,
+                    timestamp_us BIGINT NOT NULL,
+                    sender_canonical BLOB NOT NULL,
+                    receiver_canonical BLOB NOT NULL,
+                    sender_display TEXT NOT NULL,
+                    receiver_display TEXT NOT NULL,
+                    base_token BLOB NOT NULL,
+                    base_token_symbol TEXT NOT NULL,
+                    base_amount TEXT NOT NULL,
+                    quote_token BLOB NOT NULL,
+                    quote_amount TEXT NOT NULL,
+                    amount_usd TEXT,
+                    sort_order TEXT NOT NULL,
+                    transfer_index INTEGER NOT NULL
+                );
+                "#,
+            )?;
+
+            {
+                let mut appender = connection.appender("archived_transfers")?;
+
+                for transfer in transfers {
+                    appender.append_row(params![
+                        transfer.universal_chain_id,
+                        transfer.counterparty_universal_chain_id,
+                        transfer.source_channel_id,
+                        transfer.destination_channel_id,
+                        transfer.packet_hash,
+                        transfer.transaction_hash,
+                        transfer.height,
+                        to_epoch_us(transfer.timestamp),
+                        transfer.sender_canonical,
+                        transfer.receiver_canonical,
+                        transfer.sender_display,
+                        transfer.receiver_display,
+                        transfer.base_token,
+                        transfer.base_token_symbol,
+                        transfer.base_amount,
+                        transfer.quote_token,
+                        transfer.quote_amount,
+                        transfer.amount_usd,
+                        transfer.sort_order,
+                        transfer.transfer_index,
+                    ])?;
+                }
+            }
+
+            connection.execute_batch(&format!(
+                r#"
+                COPY (
+                    SELECT * EXCLUDE (timestamp_us), make_timestamp(timestamp_us) AS timestamp
+                    FROM archived_transfers
+                ) TO {} (FORMAT PARQUET);
+                DROP TABLE archived_transfers;
+                "#,
+                quote(&uri),
+            ))
+        })
+        .await
+        .expect("cold storage query does not panic")
+    }
+}
+
+fn to_epoch_us(timestamp: OffsetDateTime) -> i64 {
+    (timestamp.unix_timestamp_nanos() / 1000) as i64
+}
+
+fn from_epoch_us(column: usize, timestamp: i64) -> duckdb::Result<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp_nanos(i128::from(timestamp) * 1000)
+        .map_err(|_| duckdb::Error::IntegralValueOutOfRange(column, timestamp))
+}
+
+/// A duckdb string literal.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// A duckdb list literal of the files to read.
+fn parquet_files(uris: &[String]) -> String {
+    format!(
+        "[{}]",
+        uris.iter()
+            .map(String::as_str)
+            .map(quote)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn status_name(status: PacketStatus) -> &'static str {
+    match status {
+        PacketStatus::Sent => "sent",
+        PacketStatus::Received => "received",
+        PacketStatus::Acknowledged => "acknowledged",
+        PacketStatus::TimedOut => "timed_out",
+    }
+}
+
+fn parse_status(status: &str) -> Option<PacketStatus> {
+    match status {
+        "sent" => Some(PacketStatus::Sent),
+        "received" => Some(PacketStatus::Received),
+        "acknowledged" => Some(PacketStatus::Acknowledged),
+        "timed_out" => Some(PacketStatus::TimedOut),
+        _ => None,
+    }
+}
+
+/// Merges a page of live rows with a page of archived rows, newest first by `key`. Rows that were
+/// archived but not pruned yet are in both pages, and are only returned once.
+pub fn merge_newest_first<T, K: Ord>(
+    live: Vec<T>,
+    archived: Vec<T>,
+    key: impl Fn(&T) -> K,
+    limit: i64,
+) -> Vec<T> {
+    let mut rows = live.into_iter().chain(archived).collect::<Vec<_>>();
+
+    // the sort is stable, so the live row is kept for duplicates
+    rows.sort_by(|a, b| key(b).cmp(&key(a)));
+    rows.dedup_by(|a, b| key(a) == key(b));
+    rows.truncate(limit as usize);
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_skips_rows_that_are_not_pruned_yet() {
+        let live = vec![(10, "live"), (9, "live"), (8, "live")];
+        let archived = vec![(8, "archived"), (7, "archived"), (6, "archived")];
+
+        assert_eq!(
+            merge_newest_first(live, archived, |(height, _)| *height, 4),
+            vec![(10, "live"), (9, "live"), (8, "live"), (7, "archived")]
+        );
+    }
+
+    #[test]
+    fn merge_without_live_rows() {
+        assert_eq!(
+            merge_newest_first(vec![], vec![5, 3, 1], |height| *height, 2),
+            vec![5, 3]
+        );
+    }
+
+    #[test]
+    fn parquet_files_are_quoted() {
+        assert_eq!(
+            parquet_files(&[
+                "s3://archive/packets.parquet".to_owned(),
+                "it's.parquet".to_owned()
+            ]),
+            "['s3://archive/packets.parquet', 'it''s.parquet']"
+        );
+    }
+
+    #[test]
+    fn archived_status_roundtrips() {
+        for status in [
+            PacketStatus::Sent,
+            PacketStatus::Received,
+            PacketStatus::Acknowledged,
+            PacketStatus::TimedOut,
+        ] {
+            assert_eq!(parse_status(status_name(status)), Some(status));
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use std::{collections::HashSet, str::FromStr, sync::Arc};
+
+use axum::{
+    extract::State,
+    http::{header::AUTHORIZATION, Request},
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::api::routes::ApiError;
+
+/// Tracing target of the audit log of the admin api.
+pub const AUDIT_TARGET: &str = "hubble::audit";
+
+/// Roles of api tokens, from least to most privileged. A role is allowed the actions of the roles
+/// before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// The role of [`ApiKey`]s, which cannot be given to admin tokens.
+    #[serde(skip_deserializing)]
+    Consumer,
+    ReadOnly,
+    Operator,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Consumer => "consumer",
+            Role::ReadOnly => "read_only",
+            Role::Operator => "operator",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+/// A bearer token for the admin api. The name identifies the holder in the audit log.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdminToken {
+    pub name: String,
+    pub role: Role,
+    pub token: String,
+}
+
+/// The admin api tokens, as a json array of `{"name": ..., "role": ..., "token": ...}`.
+#[derive(Clone, Debug, Default)]
+pub struct AdminTokens(pub Vec<AdminToken>);
+
+impl FromStr for AdminTokens {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = serde_json::from_str::<Vec<AdminToken>>(s).map_err(|e| e.to_string())?;
+
+        check_tokens(&tokens)?;
+
+        Ok(Self(tokens))
+    }
+}
+
+/// A key of an api consumer, which identifies it to save and apply its filters, without access to
+/// the admin api. The name owns the saved filters of the consumer.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+    pub name: String,
+    pub key: String,
+}
+
+impl From<ApiKey> for AdminToken {
+    fn from(api_key: ApiKey) -> Self {
+        AdminToken {
+            name: api_key.name,
+            role: Role::Consumer,
+            token: api_key.key,
+        }
+    }
+}
+
+/// The api consumer keys, as a json array of `{"name": ..., "key": ...}`.
+#[derive(Clone, Debug, Default)]
+pub struct ApiKeys(pub Vec<ApiKey>);
+
+impl FromStr for ApiKeys {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = serde_json::from_str::<Vec<ApiKey>>(s).map_err(|e| e.to_string())?;
+
+        check_tokens(&keys.iter().cloned().map(Into::into).collect::<Vec<_>>())?;
+
+        Ok(Self(keys))
+    }
+}
+
+/// Checks that every token is set, and that the names and tokens are unique. Names own saved
+/// filters, so admin tokens and api keys are checked together.
+pub fn check_tokens(tokens: &[AdminToken]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    let mut values = HashSet::new();
+
+    for token in tokens {
+        if token.token.is_empty() {
+            return Err(format!("token {} is empty", token.name));
+        }
+        if !names.insert(&token.name) {
+            return Err(format!("token name {} is not unique", token.name));
+        }
+        if !values.insert(&token.token) {
+            return Err(format!("token {} is not unique", token.name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Actions of the admin api.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    ListWebhookDeliveries,
+    RedriveWebhookDelivery,
+    RedriveFailedWebhookDeliveries,
+    ListSavedFilters,
+    ManageSavedFilters,
+    ListIndexRecommendations,
+    DismissIndexRecommendation,
+    ApproveIndexRecommendation,
+    ListFailedEvents,
+    RedriveFailedEvent,
+    RedriveFailedEvents,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::ListWebhookDeliveries => "list_webhook_deliveries",
+            Action::RedriveWebhookDelivery => "redrive_webhook_delivery",
+            Action::RedriveFailedWebhookDeliveries => "redrive_failed_webhook_deliveries",
+            Action::ListSavedFilters => "list_saved_filters",
+            Action::ManageSavedFilters => "manage_saved_filters",
+            Action::ListIndexRecommendations => "list_index_recommendations",
+            Action::DismissIndexRecommendation => "dismiss_index_recommendation",
+            Action::ApproveIndexRecommendation => "approve_index_recommendation",
+            Action::ListFailedEvents => "list_failed_events",
+            Action::RedriveFailedEvent => "redrive_failed_event",
+            Action::RedriveFailedEvents => "redrive_failed_events",
+        }
+    }
+
+    /// The least privileged role that is allowed this action.
+    pub fn required_role(&self) -> Role {
+        match self {
+            // saved filters are owned by the caller, so every role can manage its own
+            Action::ListSavedFilters | Action::ManageSavedFilters => Role::Consumer,
+            Action::ListWebhookDeliveries
+            | Action::ListIndexRecommendations
+            | Action::ListFailedEvents => Role::ReadOnly,
+            Action::RedriveWebhookDelivery
+            | Action::DismissIndexRecommendation
+            | Action::RedriveFailedEvent => Role::Operator,
+            // approved indexes are built on the production tables
+            Action::RedriveFailedWebhookDeliveries
+            | Action::ApproveIndexRecommendation
+            | Action::RedriveFailedEvents => Role::Admin,
+        }
+    }
+}
+
+/// The holder of the token of an api request, added to the request by [`authenticate`].
+#[derive(Clone, Debug)]
+pub struct Caller {
+    pub name: Arc<str>,
+    pub role: Role,
+}
+
+impl Caller {
+    /// Checks whether the caller is allowed `action`. Both allowed and denied actions are written
+    /// to the audit log.
+    pub fn authorize(&self, action: Action) -> Result<(), ApiError> {
+        let allowed = self.role >= action.required_role();
+
+        info!(
+            target: AUDIT_TARGET,
+            caller = %self.name,
+            role = self.role.as_str(),
+            action = action.as_str(),
+            allowed,
+            "admin api action"
+        );
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden)
+        }
+    }
+}
+
+/// Rejects requests without `Authorization: Bearer <token>` of one of `tokens`, and adds the
+/// [`Caller`] to the others.
+pub async fn authenticate<B>(
+    State(tokens): State<Arc<[AdminToken]>>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ApiError> {
+    match caller(&tokens, &request) {
+        Some(caller) => {
+            request.extensions_mut().insert(caller);
+            Ok(next.run(request).await)
+        }
+        None => {
+            warn!(
+                target: AUDIT_TARGET,
+                path = %request.uri().path(),
+                "unauthorized admin api request"
+            );
+            Err(ApiError::Unauthorized)
+        }
+    }
+}
+
+/// Rejects requests of callers that are not allowed `action`. Layered on every route of the admin
+/// api (after [`authenticate`]), so that no handler can be served without a permission check.
+pub async fn require<B>(
+    State(action): State<Action>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ApiError> {
+    request
+        .extensions()
+        .get::<Caller>()
+        .ok_or(ApiError::Unauthorized)?
+        .authorize(action)?;
+
+    Ok(next.run(request).await)
+}
+
+/// Adds the [`Caller`] to requests with `Authorization: Bearer <token>` of one of the api tokens,
+/// without rejecting the others. Used for the public api, which applies the saved filters of the
+/// caller.
+pub async fn identify<B>(
+    State(tokens): State<Arc<[AdminToken]>>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if let Some(caller) = caller(&tokens, &request) {
+        request.extensions_mut().insert(caller);
+    }
+
+    next.run(request).await
+}
+
+fn caller<B>(tokens: &[AdminToken], request: &Request<B>) -> Option<Caller> {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))?;
+
+    // every token is compared, so that the response time does not depend on which one matches
+    tokens.iter().fold(None, |caller, admin_token| {
+        match constant_time_eq(token.as_bytes(), admin_token.token.as_bytes()) {
+            true => Some(Caller {
+                name: admin_token.name.as_str().into(),
+                role: admin_token.role,
+            }),
+            false => caller,
+        }
+    })
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_are_ordered_by_privilege() {
+        let caller = |role| Caller {
+            name: "test".into(),
+            role,
+        };
+
+        assert!(caller(Role::ReadOnly)
+            .authorize(Action::ListWebhookDeliveries)
+            .is_ok());
+        assert!(caller(Role::ReadOnly)
+            .authorize(Action::RedriveWebhookDelivery)
+            .is_err());
+        assert!(caller(Role::Operator)
+            .authorize(Action::RedriveWebhookDelivery)
+            .is_ok());
+        assert!(caller(Role::Operator)
+            .authorize(Action::RedriveFailedWebhookDeliveries)
+            .is_err());
+        assert!(caller(Role::Admin)
+            .authorize(Action::RedriveFailedWebhookDeliveries)
+            .is_ok());
+        assert!(caller(Role::Operator)
+            .authorize(Action::RedriveFailedEvent)
+            .is_ok());
+        assert!(caller(Role::Operator)
+            .authorize(Action::RedriveFailedEvents)
+            .is_err());
+        assert!(caller(Role::Consumer)
+            .authorize(Action::ManageSavedFilters)
+            .is_ok());
+        assert!(caller(Role::Consumer)
+            .authorize(Action::ListWebhookDeliveries)
+            .is_err());
+    }
+
+    #[test]
+    fn parse_admin_tokens() {
+        let tokens = r#"[
+            {"name": "dashboard", "role": "read_only", "token": "a"},
+            {"name": "oncall", "role": "operator", "token": "b"}
+        ]"#
+        .parse::<AdminTokens>()
+        .unwrap();
+
+        assert_eq!(tokens.0.len(), 2);
+        assert_eq!(tokens.0[1].role, Role::Operator);
+
+        assert!(r#"[
+            {"name": "dashboard", "role": "read_only", "token": "a"},
+            {"name": "oncall", "role": "operator", "token": "a"}
+        ]"#
+        .parse::<AdminTokens>()
+        .is_err());
+
+        assert!(r#"[{"name": "dashboard", "role": "owner", "token": "a"}]"#
+            .parse::<AdminTokens>()
+            .is_err());
+
+        assert!(
+            r#"[{"name": "dashboard", "role": "consumer", "token": "a"}]"#
+                .parse::<AdminTokens>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_api_keys() {
+        let keys = r#"[
+            {"name": "explorer", "key": "a"},
+            {"name": "wallet", "key": "b"}
+        ]"#
+        .parse::<ApiKeys>()
+        .unwrap();
+
+        assert_eq!(keys.0.len(), 2);
+        assert_eq!(AdminToken::from(keys.0[0].clone()).role, Role::Consumer);
+
+        assert!(r#"[
+            {"name": "explorer", "key": "a"},
+            {"name": "explorer", "key": "b"}
+        ]"#
+        .parse::<ApiKeys>()
+        .is_err());
+
+        assert!(r#"[{"name": "explorer", "key": ""}]"#.parse::<ApiKeys>().is_err());
+    }
+}
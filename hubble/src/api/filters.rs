@@ -0,0 +1,212 @@
+//! Saved filters: named sets of query parameters of a list endpoint, saved per api consumer (the
+//! name of its token) and applied server-side with `?filter=<name>`, so frontends don't need to
+//! send the same (large) set of parameters with every request.
+
+use std::collections::BTreeMap;
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, MatchedPath, Query},
+    http::{request::Parts, Uri},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::PgPool;
+use time::OffsetDateTime;
+
+use crate::api::{
+    auth::Caller,
+    postgres::{get_default_filter, get_saved_filter},
+    routes::ApiError,
+};
+
+/// The query parameter that selects the saved filter applied to a request.
+pub const FILTER_PARAM: &str = "filter";
+
+/// The routes of which the query parameters can be saved as filters.
+pub const FILTERABLE_PATHS: &[&str] = &[
+    "/v1/transfers",
+    "/v1/packets",
+    "/v1/tokens/stats",
+    "/v1/assets/stats",
+    "/v1/prices/daily",
+    "/v1/events/stats",
+    "/v1/governance/proposals",
+    "/v1/chains/:universal_chain_id/timeline",
+];
+
+/// Query parameters that cannot be saved, since they are specific to a single request.
+const UNSAVED_PARAMS: &[&str] = &[FILTER_PARAM, "cursor"];
+
+/// A named set of query parameters of the route `path`. Filters are private to their owner unless
+/// they are `shared`, and the `default` filter of a route is applied to all requests of its owner
+/// to that route that don't select a filter.
+#[derive(Clone, Debug, Serialize)]
+pub struct SavedFilter {
+    pub owner: String,
+    pub name: String,
+    pub path: String,
+    pub params: BTreeMap<String, String>,
+    pub shared: bool,
+    pub default: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// The body of `PUT /v1/filters/:name`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SaveFilter {
+    pub path: String,
+    pub params: BTreeMap<String, String>,
+    #[serde(default)]
+    pub shared: bool,
+    #[serde(default)]
+    pub default: bool,
+}
+
+impl SaveFilter {
+    pub(super) fn validate(&self, name: &str) -> Result<(), ApiError> {
+        if name.is_empty() || name.contains('/') {
+            return Err(ApiError::BadRequest(format!(
+                "invalid filter name {name}, which must be non-empty and not contain /"
+            )));
+        }
+
+        if !FILTERABLE_PATHS.contains(&self.path.as_str()) {
+            return Err(ApiError::BadRequest(format!(
+                "filters cannot be saved for {}, only for {}",
+                self.path,
+                FILTERABLE_PATHS.join(", ")
+            )));
+        }
+
+        match self
+            .params
+            .keys()
+            .find(|param| UNSAVED_PARAMS.contains(&param.as_str()))
+        {
+            Some(param) => Err(ApiError::BadRequest(format!(
+                "the query parameter {param} cannot be saved"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// [`Query`] with a saved filter applied: the filter selected with `?filter=<name>` (or
+/// `<owner>/<name>` for filters shared by others), or the default filter of the caller for the
+/// route. Parameters of the request take precedence over the saved ones, and `?filter=` (empty)
+/// skips the default filter.
+///
+/// Requests without a token of the api are never filtered.
+#[derive(Debug)]
+pub(super) struct FilteredQuery<T>(pub T);
+
+#[async_trait]
+impl<T: DeserializeOwned> FromRequestParts<PgPool> for FilteredQuery<T> {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, db: &PgPool) -> Result<Self, Self::Rejection> {
+        let mut params =
+            url::form_urlencoded::parse(parts.uri.query().unwrap_or_default().as_bytes())
+                .into_owned()
+                .collect::<Vec<(String, String)>>();
+
+        let filter = params
+            .iter()
+            .position(|(key, _)| key == FILTER_PARAM)
+            .map(|index| params.remove(index).1);
+
+        let path = parts
+            .extensions
+            .get::<MatchedPath>()
+            .map_or_else(|| parts.uri.path(), MatchedPath::as_str);
+
+        let saved_filter = match (filter.as_deref(), parts.extensions.get::<Caller>()) {
+            (Some(""), _) | (None, None) => None,
+            (Some(_), None) => return Err(ApiError::Unauthorized),
+            (Some(name), Some(caller)) => {
+                let (owner, name) = name.split_once('/').unwrap_or((&*caller.name, name));
+
+                let saved_filter = get_saved_filter(db, owner, name, &caller.name)
+                    .await?
+                    .ok_or_else(|| ApiError::BadRequest(format!("unknown filter {name}")))?;
+
+                if saved_filter.path != path {
+                    return Err(ApiError::BadRequest(format!(
+                        "filter {name} is saved for {}",
+                        saved_filter.path
+                    )));
+                }
+
+                Some(saved_filter)
+            }
+            (None, Some(caller)) => get_default_filter(db, &caller.name, path).await?,
+        };
+
+        if let Some(saved_filter) = saved_filter {
+            let saved_params = saved_filter
+                .params
+                .into_iter()
+                .filter(|(key, _)| !params.iter().any(|(param, _)| param == key))
+                .collect::<Vec<_>>();
+
+            params.splice(0..0, saved_params);
+        }
+
+        let uri = Uri::builder()
+            .path_and_query(format!(
+                "/?{}",
+                url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(params)
+                    .finish()
+            ))
+            .build()
+            .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+
+        Query::try_from_uri(&uri)
+            .map(|Query(query)| FilteredQuery(query))
+            .map_err(|rejection| ApiError::BadRequest(rejection.body_text()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(path: &str, params: &[(&str, &str)]) -> SaveFilter {
+        SaveFilter {
+            path: path.to_owned(),
+            params: params
+                .iter()
+                .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+                .collect(),
+            shared: false,
+            default: false,
+        }
+    }
+
+    #[test]
+    fn validate_save_filter() {
+        assert!(
+            filter("/v1/transfers", &[("universal_chain_id", "union.union-1")])
+                .validate("mine")
+                .is_ok()
+        );
+        assert!(filter("/v1/chains/:universal_chain_id/timeline", &[])
+            .validate("mine")
+            .is_ok());
+
+        assert!(filter("/v1/transfers", &[]).validate("").is_err());
+        assert!(filter("/v1/transfers", &[]).validate("team/mine").is_err());
+        assert!(filter("/admin/v1/webhooks/deliveries", &[])
+            .validate("mine")
+            .is_err());
+        assert!(filter("/v1/transfers", &[("cursor", "1")])
+            .validate("mine")
+            .is_err());
+        assert!(filter("/v1/transfers", &[("filter", "other")])
+            .validate("mine")
+            .is_err());
+    }
+}
@@ -0,0 +1,426 @@
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
+
+use serde::Serialize;
+use sqlx::postgres::PgPoolOptions;
+use time::OffsetDateTime;
+use tokio::sync::broadcast;
+use tracing::info;
+
+use crate::api::archive::ColdStorage;
+
+mod archive;
+mod auth;
+mod filters;
+mod postgres;
+mod routes;
+mod stream;
+
+pub use archive::{archive_partition, ArchivedTable};
+pub use auth::{check_tokens, AdminToken, AdminTokens, ApiKeys, Role};
+
+/// The databases the indexers write to. Chains that are indexed into another database than the
+/// default one (see `--databases`) are read from that database.
+#[derive(Clone, Debug)]
+pub struct Databases {
+    /// The default database followed by all other databases, once each.
+    all: Vec<sqlx::PgPool>,
+    /// Universal chain id => index in `all` of the database the chain is indexed into.
+    chains: HashMap<String, usize>,
+}
+
+impl Databases {
+    pub fn new(
+        default: sqlx::PgPool,
+        databases: &HashMap<String, sqlx::PgPool>,
+        chain_databases: impl IntoIterator<Item = (String, String)>,
+    ) -> color_eyre::Result<Self> {
+        let names = databases.keys().collect::<Vec<_>>();
+
+        let chains = chain_databases
+            .into_iter()
+            .map(|(universal_chain_id, name)| {
+                let index = names.iter().position(|n| **n == name).ok_or_else(|| {
+                    color_eyre::eyre::eyre!(
+                        "chain {universal_chain_id} is configured with unknown database {name}"
+                    )
+                })?;
+
+                Ok((universal_chain_id, index + 1))
+            })
+            .collect::<color_eyre::Result<_>>()?;
+
+        let all = [default]
+            .into_iter()
+            .chain(names.iter().map(|name| databases[*name].clone()))
+            .collect();
+
+        Ok(Self { all, chains })
+    }
+
+    /// The same databases, with every statement of the api limited to `statement_timeout`. The
+    /// pools are separate from the pools of `self`, so other statements (e.g. of the index
+    /// advisor) are not limited.
+    pub fn with_statement_timeout(&self, statement_timeout: Duration) -> Self {
+        let statement_timeout = format!("{}ms", statement_timeout.as_millis());
+
+        Self {
+            all: self
+                .all
+                .iter()
+                .map(|pool| {
+                    PgPoolOptions::new()
+                        .max_connections(pool.options().get_max_connections())
+                        .connect_lazy_with(
+                            (*pool.connect_options())
+                                .clone()
+                                .options([("statement_timeout", &statement_timeout)]),
+                        )
+                })
+                .collect(),
+            chains: self.chains.clone(),
+        }
+    }
+
+    fn default(&self) -> &sqlx::PgPool {
+        &self.all[0]
+    }
+
+    /// The database holding the records of the chain.
+    fn chain(&self, universal_chain_id: &str) -> &sqlx::PgPool {
+        self.chains
+            .get(universal_chain_id)
+            .map_or(self.default(), |index| &self.all[*index])
+    }
+
+    fn all(&self) -> &[sqlx::PgPool] {
+        &self.all
+    }
+}
+
+/// Number of items returned when the request does not specify a limit.
+const DEFAULT_LIMIT: i64 = 50;
+/// Maximum number of items returned in one page.
+const MAX_LIMIT: i64 = 500;
+
+/// Serve the read api (`hubble serve`) until the server fails. With `stream`, new records
+/// published by the indexers are pushed to websocket subscribers. With `tokens`, the admin api is
+/// served to requests authorized with one of the admin tokens, limited to the actions of its role,
+/// and the saved filters to requests authorized with any token (including api keys).
+/// With `cold_storage`, packets and transfers that were pruned from postgres are read from their
+/// archived partitions. Records of a chain are read from the database of the chain in `databases`; the
+/// aggregated stats (tokens, assets, prices) and the admin api only read the default database.
+pub async fn serve(
+    databases: Databases,
+    addr: SocketAddr,
+    stream: bool,
+    tokens: Vec<AdminToken>,
+    cold_storage: bool,
+) -> color_eyre::Result<()> {
+    info!("serving api on {addr}");
+
+    let sender = stream.then(|| broadcast::channel(stream::STREAM_CAPACITY).0);
+    let cold_storage = cold_storage.then(ColdStorage::open).transpose()?;
+
+    let server = axum::Server::bind(&addr).serve(
+        routes::router(
+            databases.default().clone(),
+            databases.clone(),
+            sender.clone(),
+            tokens,
+            cold_storage,
+        )
+        .into_make_service(),
+    );
+
+    match sender {
+        Some(sender) => {
+            tokio::try_join!(
+                async { server.await.map_err(color_eyre::Report::from) },
+                futures::future::try_join_all(
+                    databases
+                        .all()
+                        .iter()
+                        .map(|db| stream::listen(db.clone(), sender.clone())),
+                ),
+            )?;
+        }
+        None => server.await?,
+    }
+
+    Ok(())
+}
+
+/// A page of items. `next_cursor` is set when there may be more items, and is passed as
+/// `cursor` to fetch the next page.
+#[derive(Clone, Debug, Serialize)]
+pub struct Page<T> {
+    items: Vec<T>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Transfer {
+    universal_chain_id: String,
+    counterparty_universal_chain_id: String,
+    source_channel_id: i32,
+    destination_channel_id: i32,
+    packet_hash: String,
+    transaction_hash: String,
+    height: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    timestamp: OffsetDateTime,
+    sender: String,
+    receiver: String,
+    base_token: String,
+    base_token_symbol: String,
+    base_amount: String,
+    quote_token: String,
+    quote_amount: String,
+    amount_usd: Option<String>,
+    sort_order: String,
+    /// Position of the transfer in its packet, which contains more than one transfer for batches.
+    transfer_index: i32,
+}
+
+/// Status of a transfer. zkgm batches are executed atomically, so the transfers of a packet share
+/// the acknowledgement (or timeout) of the packet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    /// The packet is not acknowledged or timed out yet.
+    Pending,
+    Succeeded,
+    /// The packet was acknowledged with a failure or timed out, which refunds the sender.
+    Refunded,
+}
+
+impl TransferStatus {
+    fn new(acknowledgement: Option<&[u8]>, timed_out: bool) -> Self {
+        match acknowledgement {
+            _ if timed_out => Self::Refunded,
+            // the tag is the first word of a zkgm acknowledgement, and zero for failures
+            Some(ack) if ack.get(..32).is_some_and(|tag| tag.iter().any(|b| *b != 0)) => {
+                Self::Succeeded
+            }
+            Some(_) => Self::Refunded,
+            None => Self::Pending,
+        }
+    }
+}
+
+/// Number of transfers of a packet per status.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TransferRollup {
+    total: usize,
+    succeeded: usize,
+    refunded: usize,
+    pending: usize,
+}
+
+impl FromIterator<TransferStatus> for TransferRollup {
+    fn from_iter<I: IntoIterator<Item = TransferStatus>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::default(), |mut rollup, status| {
+                rollup.total += 1;
+
+                match status {
+                    TransferStatus::Pending => rollup.pending += 1,
+                    TransferStatus::Succeeded => rollup.succeeded += 1,
+                    TransferStatus::Refunded => rollup.refunded += 1,
+                }
+
+                rollup
+            })
+    }
+}
+
+/// A transfer of a packet, with its status.
+#[derive(Clone, Debug, Serialize)]
+pub struct PacketTransfer {
+    #[serde(flatten)]
+    transfer: Transfer,
+    status: TransferStatus,
+}
+
+/// The transfers of a packet, and the number of them per status.
+#[derive(Clone, Debug, Serialize)]
+pub struct PacketTransfers {
+    packet_hash: String,
+    rollup: TransferRollup,
+    transfers: Vec<PacketTransfer>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PacketStatus {
+    Sent,
+    Received,
+    Acknowledged,
+    TimedOut,
+}
+
+/// A packet sent on a channel, with its current status.
+#[derive(Clone, Debug, Serialize)]
+pub struct Packet {
+    packet_hash: String,
+    source_channel_id: i32,
+    destination_channel_id: i32,
+    transaction_hash: String,
+    height: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    timestamp: OffsetDateTime,
+    status: PacketStatus,
+}
+
+/// The events of a packet on both chains, ordered by time.
+#[derive(Clone, Debug, Serialize)]
+pub struct PacketTrace {
+    packet_hash: String,
+    events: Vec<PacketEvent>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PacketEvent {
+    /// One of `packet_send`, `packet_recv`, `write_ack`, `packet_ack` or `packet_timeout`.
+    kind: String,
+    universal_chain_id: String,
+    channel_id: i32,
+    transaction_hash: String,
+    height: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    timestamp: OffsetDateTime,
+}
+
+/// Transfer volume of a token sent from a chain.
+#[derive(Clone, Debug, Serialize)]
+pub struct TokenStats {
+    universal_chain_id: String,
+    base_token: String,
+    base_token_symbol: String,
+    base_token_decimals: Option<i32>,
+    transfers: i64,
+    base_amount: String,
+    amount_usd: Option<String>,
+}
+
+/// Transfer volume of an asset, over all chains and wrap levels.
+#[derive(Clone, Debug, Serialize)]
+pub struct AssetStats {
+    asset_id: String,
+    transfers: i64,
+    amount_usd: Option<String>,
+}
+
+/// The pinned usd price of a token on a (utc) day, used to value the transfers of that day in the
+/// stats endpoints.
+#[derive(Clone, Debug, Serialize)]
+pub struct DailyPrice {
+    universal_chain_id: String,
+    denom: String,
+    /// One of `coingecko`, `pyth` or `oracle`.
+    provider: String,
+    feed_id: String,
+    /// `YYYY-MM-DD`.
+    day: String,
+    price_usd: String,
+}
+
+/// Number of indexed events of a flow on a chain. Generic events of chains with event sampling are
+/// only indexed for one in `sampling_rate` blocks, so `estimated_events` extrapolates them.
+#[derive(Clone, Debug, Serialize)]
+pub struct EventStats {
+    flow: String,
+    indexed_events: i64,
+    estimated_events: i64,
+    sampled: bool,
+    sampling_rate: Option<i64>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    /// A cosmos gov proposal, of which the outcome is not indexed.
+    Submitted,
+    /// A timelock call that is not executed yet.
+    Scheduled,
+    Executed,
+    Cancelled,
+}
+
+/// A governance proposal (cosmos gov) or timelock operation (evm) touching bridge parameters or
+/// contracts, with the clients and channels on its chain that it affects.
+#[derive(Clone, Debug, Serialize)]
+pub struct GovernanceProposal {
+    universal_chain_id: String,
+    proposal_id: String,
+    status: ProposalStatus,
+    height: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    submitted_at: OffsetDateTime,
+    /// When a scheduled timelock call can be executed.
+    #[serde(with = "time::serde::rfc3339::option")]
+    executable_at: Option<OffsetDateTime>,
+    message_types: Vec<String>,
+    target_contracts: Vec<String>,
+    affected_client_ids: Vec<i64>,
+    affected_channel_ids: Vec<i32>,
+}
+
+/// An event of a chain that could not be handled by its indexer, as recorded in
+/// `hubble.failed_events`. The retrier attempts it again at `next_attempt_at`.
+#[derive(Clone, Debug, Serialize)]
+pub struct FailedEvent {
+    id: i64,
+    height: i64,
+    event: serde_json::Value,
+    error: String,
+    attempts: i32,
+    #[serde(with = "time::serde::rfc3339")]
+    next_attempt_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+/// An annotation of the block timeline of a chain, for overlaying operational context on charts.
+#[derive(Clone, Debug, Serialize)]
+pub struct TimelineEntry {
+    #[serde(with = "time::serde::rfc3339")]
+    started_at: OffsetDateTime,
+    /// `None` for reorgs, pending upgrades and ongoing incidents.
+    #[serde(with = "time::serde::rfc3339::option")]
+    ended_at: Option<OffsetDateTime>,
+    #[serde(flatten)]
+    annotation: TimelineAnnotation,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimelineAnnotation {
+    /// No block was produced after `height` until the annotation ended.
+    Halt { height: i64 },
+    /// A governance proposal upgrading the chain or its contracts, which ends when it is executed.
+    Upgrade {
+        proposal_id: String,
+        height: i64,
+        message_types: Vec<String>,
+    },
+    /// The blocks above `fork_height` up to `tip_height` were replaced, at the time the indexer
+    /// detected it.
+    Reorg {
+        fork_height: i64,
+        tip_height: i64,
+        old_tip_hash: String,
+        new_tip_hash: String,
+    },
+    /// A lag alert of the indexer of the chain, with its peak burn rates.
+    IndexerIncident {
+        target_lag_seconds: f64,
+        objective: f64,
+        short_burn_rate: f64,
+        long_burn_rate: f64,
+    },
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
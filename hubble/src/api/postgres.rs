@@ -0,0 +1,1232 @@
+use sqlx::PgPool;
+use time::OffsetDateTime;
+
+use crate::api::{
+    archive::{ArchivedPacket, ArchivedTransfer},
+    filters::{SaveFilter, SavedFilter},
+    to_hex, AssetStats, DailyPrice, EventStats, FailedEvent, GovernanceProposal, Packet,
+    PacketEvent, PacketStatus, PacketTransfer, ProposalStatus, TimelineAnnotation, TimelineEntry,
+    TokenStats, Transfer, TransferStatus,
+};
+
+pub async fn get_transfers_by_address(
+    db: &PgPool,
+    address: &[u8],
+    universal_chain_id: Option<&str>,
+    before_sort_order: Option<&str>,
+    limit: i64,
+) -> sqlx::Result<Vec<Transfer>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            universal_chain_id,
+            counterparty_universal_chain_id,
+            source_channel_id,
+            destination_channel_id,
+            packet_hash,
+            transaction_hash,
+            height,
+            timestamp,
+            sender_display,
+            receiver_display,
+            base_token,
+            base_token_symbol,
+            base_amount,
+            quote_token,
+            quote_amount,
+            amount_usd,
+            sort_order,
+            transfer_index
+        FROM v2_sync.packet_send_transfers_sync
+        WHERE (sender_canonical = $1 OR receiver_canonical = $1)
+        AND ($2::TEXT IS NULL OR universal_chain_id = $2)
+        AND ($3::TEXT IS NULL OR sort_order < $3)
+        ORDER BY sort_order DESC
+        LIMIT $4
+        "#,
+        address,
+        universal_chain_id,
+        before_sort_order,
+        limit,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| Transfer {
+        universal_chain_id: record.universal_chain_id,
+        counterparty_universal_chain_id: record.counterparty_universal_chain_id,
+        source_channel_id: record.source_channel_id,
+        destination_channel_id: record.destination_channel_id,
+        packet_hash: to_hex(&record.packet_hash),
+        transaction_hash: to_hex(&record.transaction_hash),
+        height: record.height,
+        timestamp: record.timestamp,
+        sender: record.sender_display,
+        receiver: record.receiver_display,
+        base_token: to_hex(&record.base_token),
+        base_token_symbol: record.base_token_symbol,
+        base_amount: record.base_amount.to_string(),
+        quote_token: to_hex(&record.quote_token),
+        quote_amount: record.quote_amount.to_string(),
+        amount_usd: record.amount_usd.map(|amount_usd| amount_usd.to_string()),
+        sort_order: record.sort_order,
+        transfer_index: record.transfer_index,
+    })
+    .collect())
+}
+
+/// The transfers of a packet, ordered by their position in the packet.
+pub async fn get_packet_transfers(
+    db: &PgPool,
+    packet_hash: &[u8],
+) -> sqlx::Result<Vec<PacketTransfer>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            transfer.universal_chain_id,
+            transfer.counterparty_universal_chain_id,
+            transfer.source_channel_id,
+            transfer.destination_channel_id,
+            transfer.packet_hash,
+            transfer.transaction_hash,
+            transfer.height,
+            transfer.timestamp,
+            transfer.sender_display,
+            transfer.receiver_display,
+            transfer.base_token,
+            transfer.base_token_symbol,
+            transfer.base_amount,
+            transfer.quote_token,
+            transfer.quote_amount,
+            transfer.amount_usd,
+            transfer.sort_order,
+            transfer.transfer_index,
+            (SELECT ack.acknowledgement FROM v2_sync.packet_ack_sync ack WHERE ack.packet_hash = transfer.packet_hash LIMIT 1) AS acknowledgement,
+            EXISTS (SELECT 1 FROM v2_sync.packet_timeout_sync timeout WHERE timeout.packet_hash = transfer.packet_hash) AS "timed_out!"
+        FROM v2_sync.packet_send_transfers_sync transfer
+        WHERE transfer.packet_hash = $1
+        ORDER BY transfer.transfer_index
+        "#,
+        packet_hash,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| PacketTransfer {
+        status: TransferStatus::new(record.acknowledgement.as_deref(), record.timed_out),
+        transfer: Transfer {
+            universal_chain_id: record.universal_chain_id,
+            counterparty_universal_chain_id: record.counterparty_universal_chain_id,
+            source_channel_id: record.source_channel_id,
+            destination_channel_id: record.destination_channel_id,
+            packet_hash: to_hex(&record.packet_hash),
+            transaction_hash: to_hex(&record.transaction_hash),
+            height: record.height,
+            timestamp: record.timestamp,
+            sender: record.sender_display,
+            receiver: record.receiver_display,
+            base_token: to_hex(&record.base_token),
+            base_token_symbol: record.base_token_symbol,
+            base_amount: record.base_amount.to_string(),
+            quote_token: to_hex(&record.quote_token),
+            quote_amount: record.quote_amount.to_string(),
+            amount_usd: record.amount_usd.map(|amount_usd| amount_usd.to_string()),
+            sort_order: record.sort_order,
+            transfer_index: record.transfer_index,
+        },
+    })
+    .collect())
+}
+
+/// Packets sent on a channel, newest first. Pages continue before (`height`, `event_index`).
+pub async fn get_packets_by_channel(
+    db: &PgPool,
+    universal_chain_id: &str,
+    channel_id: i32,
+    before: Option<(i64, i32)>,
+    limit: i64,
+) -> sqlx::Result<Vec<(Packet, i32)>> {
+    let (before_height, before_event_index) = before.unzip();
+
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            send.packet_hash,
+            send.source_channel_id,
+            send.destination_channel_id,
+            send.transaction_hash,
+            send.height,
+            send.event_index,
+            send.timestamp,
+            EXISTS (SELECT 1 FROM v2_sync.packet_recv_sync recv WHERE recv.packet_hash = send.packet_hash) AS "received!",
+            EXISTS (SELECT 1 FROM v2_sync.packet_ack_sync ack WHERE ack.packet_hash = send.packet_hash) AS "acknowledged!",
+            EXISTS (SELECT 1 FROM v2_sync.packet_timeout_sync timeout WHERE timeout.packet_hash = send.packet_hash) AS "timed_out!"
+        FROM v2_sync.packet_send_sync send
+        JOIN config.chains c ON c.id = send.internal_chain_id
+        WHERE c.family || '.' || c.chain_id = $1
+        AND send.source_channel_id = $2
+        AND ($3::INT8 IS NULL OR (send.height, send.event_index) < ($3, $4))
+        ORDER BY send.height DESC, send.event_index DESC
+        LIMIT $5
+        "#,
+        universal_chain_id,
+        channel_id,
+        before_height,
+        before_event_index,
+        limit,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| {
+        let status = match (record.timed_out, record.acknowledged, record.received) {
+            (true, _, _) => PacketStatus::TimedOut,
+            (_, true, _) => PacketStatus::Acknowledged,
+            (_, _, true) => PacketStatus::Received,
+            _ => PacketStatus::Sent,
+        };
+
+        (
+            Packet {
+                packet_hash: to_hex(&record.packet_hash),
+                source_channel_id: record.source_channel_id,
+                destination_channel_id: record.destination_channel_id,
+                transaction_hash: to_hex(&record.transaction_hash),
+                height: record.height,
+                timestamp: record.timestamp,
+                status,
+            },
+            record.event_index,
+        )
+    })
+    .collect())
+}
+
+pub async fn get_packet_events(db: &PgPool, packet_hash: &[u8]) -> sqlx::Result<Vec<PacketEvent>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            events.kind AS "kind!",
+            c.family || '.' || c.chain_id AS "universal_chain_id!",
+            events.channel_id AS "channel_id!",
+            events.transaction_hash AS "transaction_hash!",
+            events.height AS "height!",
+            events.timestamp AS "timestamp!"
+        FROM (
+            SELECT 'packet_send' AS kind, internal_chain_id, channel_id, transaction_hash, height, timestamp
+            FROM v2_sync.packet_send_sync WHERE packet_hash = $1
+            UNION ALL
+            SELECT 'packet_recv', internal_chain_id, channel_id, transaction_hash, height, timestamp
+            FROM v2_sync.packet_recv_sync WHERE packet_hash = $1
+            UNION ALL
+            SELECT 'write_ack', internal_chain_id, channel_id, transaction_hash, height, timestamp
+            FROM v2_sync.write_ack_sync WHERE packet_hash = $1
+            UNION ALL
+            SELECT 'packet_ack', internal_chain_id, channel_id, transaction_hash, height, timestamp
+            FROM v2_sync.packet_ack_sync WHERE packet_hash = $1
+            UNION ALL
+            SELECT 'packet_timeout', internal_chain_id, channel_id, transaction_hash, height, timestamp
+            FROM v2_sync.packet_timeout_sync WHERE packet_hash = $1
+        ) events
+        JOIN config.chains c ON c.id = events.internal_chain_id
+        ORDER BY events.timestamp
+        "#,
+        packet_hash,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| PacketEvent {
+        kind: record.kind,
+        universal_chain_id: record.universal_chain_id,
+        channel_id: record.channel_id,
+        transaction_hash: to_hex(&record.transaction_hash),
+        height: record.height,
+        timestamp: record.timestamp,
+    })
+    .collect())
+}
+
+/// Transfer volume per token. Usd volume is summed over `report_amount_usd` of
+/// `token.transfer_values`, which values transfers of completed (utc) days at the pinned daily
+/// price of their day (`token.daily_prices`), so the usd volume of a past range is the same for
+/// every caller and does not change later. Transfers of the current day, and transfers of days
+/// without a pinned price, are valued at the price at the time of the transfer.
+pub async fn get_token_stats(
+    db: &PgPool,
+    universal_chain_id: Option<&str>,
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+    offset: i64,
+    limit: i64,
+) -> sqlx::Result<Vec<TokenStats>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            universal_chain_id AS "universal_chain_id!",
+            base_token AS "base_token!",
+            MAX(base_token_symbol) AS "base_token_symbol!",
+            MAX(base_token_decimals) AS base_token_decimals,
+            COUNT(*) AS "transfers!",
+            SUM(base_amount) AS "base_amount!",
+            SUM(report_amount_usd) AS amount_usd
+        FROM token.transfer_values
+        WHERE ($1::TEXT IS NULL OR universal_chain_id = $1)
+        AND ($2::TIMESTAMPTZ IS NULL OR timestamp >= $2)
+        AND ($3::TIMESTAMPTZ IS NULL OR timestamp < $3)
+        GROUP BY universal_chain_id, base_token
+        ORDER BY COUNT(*) DESC, universal_chain_id, base_token
+        OFFSET $4
+        LIMIT $5
+        "#,
+        universal_chain_id,
+        since,
+        until,
+        offset,
+        limit,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| TokenStats {
+        universal_chain_id: record.universal_chain_id,
+        base_token: to_hex(&record.base_token),
+        base_token_symbol: record.base_token_symbol,
+        base_token_decimals: record.base_token_decimals,
+        transfers: record.transfers,
+        base_amount: record.base_amount.to_string(),
+        amount_usd: record.amount_usd.map(|amount_usd| amount_usd.to_string()),
+    })
+    .collect())
+}
+
+/// Transfer volume per asset, over all chains and wrap levels. Transfers of tokens that are not
+/// mapped to an asset are not counted. Usd volume is valued as in [`get_token_stats`].
+pub async fn get_asset_stats(
+    db: &PgPool,
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+    offset: i64,
+    limit: i64,
+) -> sqlx::Result<Vec<AssetStats>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            a.asset_id,
+            COUNT(*) AS "transfers!",
+            SUM(t.report_amount_usd) AS amount_usd
+        FROM token.transfer_values t
+        JOIN token.asset_tokens a ON a.internal_chain_id = t.internal_chain_id AND a.denom = t.base_token
+        WHERE ($1::TIMESTAMPTZ IS NULL OR t.timestamp >= $1)
+        AND ($2::TIMESTAMPTZ IS NULL OR t.timestamp < $2)
+        GROUP BY a.asset_id
+        ORDER BY COUNT(*) DESC, a.asset_id
+        OFFSET $3
+        LIMIT $4
+        "#,
+        since,
+        until,
+        offset,
+        limit,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| AssetStats {
+        asset_id: record.asset_id,
+        transfers: record.transfers,
+        amount_usd: record.amount_usd.map(|amount_usd| amount_usd.to_string()),
+    })
+    .collect())
+}
+
+/// Pinned daily prices of all price feeds, newest first.
+pub async fn get_daily_prices(
+    db: &PgPool,
+    universal_chain_id: Option<&str>,
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+    offset: i64,
+    limit: i64,
+) -> sqlx::Result<Vec<DailyPrice>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            c.family || '.' || c.chain_id AS "universal_chain_id!",
+            f.denom,
+            f.provider,
+            f.feed_id,
+            d.day,
+            d.price_usd
+        FROM token.daily_prices d
+        JOIN token.price_feeds f ON f.id = d.price_feed_id
+        JOIN config.chains c ON c.id = f.internal_chain_id
+        WHERE ($1::TEXT IS NULL OR c.family || '.' || c.chain_id = $1)
+        AND ($2::TIMESTAMPTZ IS NULL OR d.day >= ($2 AT TIME ZONE 'UTC')::DATE)
+        AND ($3::TIMESTAMPTZ IS NULL OR d.day < ($3 AT TIME ZONE 'UTC')::DATE)
+        ORDER BY d.day DESC, f.id
+        OFFSET $4
+        LIMIT $5
+        "#,
+        universal_chain_id,
+        since,
+        until,
+        offset,
+        limit,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| DailyPrice {
+        universal_chain_id: record.universal_chain_id,
+        denom: to_hex(&record.denom),
+        provider: record.provider,
+        feed_id: record.feed_id,
+        day: record.day.to_string(),
+        price_usd: record.price_usd.to_string(),
+    })
+    .collect())
+}
+
+/// Indexed events per flow of a chain. Events of flows that are sampled on the chain (see
+/// `hubble.event_sampling`) are flagged, and assumed to be sampled at the current rate.
+pub async fn get_event_stats(
+    db: &PgPool,
+    universal_chain_id: &str,
+    since: OffsetDateTime,
+    until: OffsetDateTime,
+) -> sqlx::Result<Vec<EventStats>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            e.flow,
+            COUNT(*) AS "indexed_events!",
+            MAX(s.rate) AS sampling_rate
+        FROM v2_cosmos.events e
+        JOIN config.chains c ON c.id = e.internal_chain_id
+        LEFT JOIN hubble.event_sampling s ON s.internal_chain_id = e.internal_chain_id AND NOT e.flow = ANY(s.full_flows)
+        WHERE c.family || '.' || c.chain_id = $1
+        AND e.time >= $2
+        AND e.time < $3
+        GROUP BY e.flow
+        ORDER BY e.flow
+        "#,
+        universal_chain_id,
+        since,
+        until,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| EventStats {
+        flow: record.flow,
+        indexed_events: record.indexed_events,
+        estimated_events: record.indexed_events * record.sampling_rate.unwrap_or(1),
+        sampled: record.sampling_rate.is_some(),
+        sampling_rate: record.sampling_rate,
+    })
+    .collect())
+}
+
+/// Bridge related governance proposals, newest first by the height they were submitted (or
+/// scheduled) at. Pages continue before (`height`, `proposal_id`). Only the proposals of the page
+/// are aggregated, not all proposals that were ever indexed.
+///
+/// `pending` only selects timelock operations: the outcome of cosmos gov proposals is not
+/// indexed, so they are neither pending nor finished as far as the indexer knows.
+///
+/// A proposal that calls contracts used as the port of channels only affects those channels.
+/// Any other proposal (such as an ibc parameter change, client recovery or chain upgrade) affects
+/// all clients and channels on its chain.
+pub async fn get_governance_proposals(
+    db: &PgPool,
+    universal_chain_id: Option<&str>,
+    pending: Option<bool>,
+    before: Option<(i64, &str)>,
+    limit: i64,
+) -> sqlx::Result<Vec<GovernanceProposal>> {
+    let (before_height, before_proposal_id) = before.unzip();
+
+    Ok(sqlx::query!(
+        r#"
+        WITH page AS (
+            SELECT DISTINCT ON (g.height, g.proposal_id, g.internal_chain_id)
+                g.internal_chain_id,
+                g.proposal_id,
+                g.height
+            FROM v2_sync.governance_proposal_sync g
+            JOIN config.chains c ON c.id = g.internal_chain_id
+            WHERE g.action IN ('submitted', 'scheduled')
+            AND ($1::TEXT IS NULL OR c.family || '.' || c.chain_id = $1)
+            AND ($2::BOOL IS NULL OR (
+                g.action = 'scheduled'
+                AND NOT EXISTS (
+                    SELECT 1 FROM v2_sync.governance_proposal_sync e
+                    WHERE e.internal_chain_id = g.internal_chain_id
+                    AND e.proposal_id = g.proposal_id
+                    AND e.action IN ('executed', 'cancelled')
+                ) = $2
+            ))
+            AND ($3::INT8 IS NULL OR (g.height, g.proposal_id) < ($3, $4))
+            ORDER BY g.height DESC, g.proposal_id DESC, g.internal_chain_id
+            LIMIT $5
+        ),
+        proposals AS (
+            SELECT
+                page.internal_chain_id,
+                page.proposal_id,
+                page.height,
+                MIN(g.timestamp) AS submitted_at,
+                MAX(g.timestamp + make_interval(secs => g.delay)) AS executable_at,
+                BOOL_OR(g.action = 'executed') AS executed,
+                BOOL_OR(g.action = 'cancelled') AS cancelled,
+                ARRAY_AGG(DISTINCT g.target_contract_canonical) FILTER (WHERE g.target_contract_canonical IS NOT NULL) AS target_contracts
+            FROM page
+            JOIN v2_sync.governance_proposal_sync g
+                ON g.internal_chain_id = page.internal_chain_id
+                AND g.proposal_id = page.proposal_id
+            GROUP BY page.internal_chain_id, page.proposal_id, page.height
+        ),
+        channels AS (
+            SELECT internal_chain_id, channel_id, port_id FROM v2_sync.channel_open_init_sync
+            WHERE internal_chain_id IN (SELECT internal_chain_id FROM page)
+            UNION
+            SELECT internal_chain_id, channel_id, port_id FROM v2_sync.channel_open_try_sync
+            WHERE internal_chain_id IN (SELECT internal_chain_id FROM page)
+        ),
+        scoped AS (
+            SELECT
+                p.*,
+                EXISTS (
+                    SELECT 1 FROM channels ch
+                    WHERE ch.internal_chain_id = p.internal_chain_id
+                    AND ch.port_id = ANY(p.target_contracts)
+                ) AS app_scoped
+            FROM proposals p
+        )
+        SELECT
+            c.family || '.' || c.chain_id AS "universal_chain_id!",
+            p.proposal_id AS "proposal_id!",
+            p.height AS "height!",
+            p.submitted_at AS "submitted_at!",
+            p.executable_at,
+            p.executed AS "executed!",
+            p.cancelled AS "cancelled!",
+            ARRAY(
+                SELECT DISTINCT message_type
+                FROM v2_sync.governance_proposal_sync g, UNNEST(g.message_types) message_type
+                WHERE g.internal_chain_id = p.internal_chain_id AND g.proposal_id = p.proposal_id
+                ORDER BY message_type
+            ) AS "message_types!",
+            COALESCE(p.target_contracts, '{}') AS "target_contracts!",
+            ARRAY(
+                SELECT cc.client_id
+                FROM v2_sync.create_client_sync cc
+                WHERE cc.internal_chain_id = p.internal_chain_id AND NOT p.app_scoped
+                ORDER BY cc.client_id
+            ) AS "affected_client_ids!",
+            ARRAY(
+                SELECT ch.channel_id
+                FROM channels ch
+                WHERE ch.internal_chain_id = p.internal_chain_id
+                AND (NOT p.app_scoped OR ch.port_id = ANY(p.target_contracts))
+                ORDER BY ch.channel_id
+            ) AS "affected_channel_ids!"
+        FROM scoped p
+        JOIN config.chains c ON c.id = p.internal_chain_id
+        ORDER BY p.height DESC, p.proposal_id DESC
+        "#,
+        universal_chain_id,
+        pending,
+        before_height,
+        before_proposal_id,
+        limit,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| {
+        let status = match (record.cancelled, record.executed, record.executable_at) {
+            (true, _, _) => ProposalStatus::Cancelled,
+            (_, true, _) => ProposalStatus::Executed,
+            (_, _, Some(_)) => ProposalStatus::Scheduled,
+            _ => ProposalStatus::Submitted,
+        };
+
+        GovernanceProposal {
+            universal_chain_id: record.universal_chain_id,
+            proposal_id: record.proposal_id,
+            status,
+            height: record.height,
+            submitted_at: record.submitted_at,
+            executable_at: record.executable_at,
+            message_types: record.message_types,
+            target_contracts: record
+                .target_contracts
+                .iter()
+                .map(|contract| to_hex(contract))
+                .collect(),
+            affected_client_ids: record.affected_client_ids,
+            affected_channel_ids: record.affected_channel_ids,
+        }
+    })
+    .collect())
+}
+
+/// Uris of the archived partitions of `table_name` for a chain (or all chains), newest first. With
+/// `before_height`, only partitions that start at or below it.
+pub async fn get_archived_partitions(
+    db: &PgPool,
+    table_name: &str,
+    universal_chain_id: Option<&str>,
+    before_height: Option<i64>,
+) -> sqlx::Result<Vec<String>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT uri
+        FROM hubble.archived_partitions
+        WHERE table_name = $1
+        AND ($2::TEXT IS NULL OR universal_chain_id = $2)
+        AND ($3::INT8 IS NULL OR start_height <= $3)
+        ORDER BY start_height DESC
+        "#,
+        table_name,
+        universal_chain_id,
+        before_height,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| record.uri)
+    .collect())
+}
+
+/// List an archived partition of `table_name` for a chain, such that the api reads it.
+pub async fn insert_archived_partition(
+    db: &PgPool,
+    table_name: &str,
+    universal_chain_id: &str,
+    start_height: i64,
+    end_height: i64,
+    uri: &str,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO hubble.archived_partitions (table_name, universal_chain_id, start_height, end_height, uri)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        table_name,
+        universal_chain_id,
+        start_height,
+        end_height,
+        uri,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// The packets sent by a chain in `[start_height, end_height)`, with their current status.
+pub async fn get_packets_to_archive(
+    db: &PgPool,
+    universal_chain_id: &str,
+    start_height: i64,
+    end_height: i64,
+) -> sqlx::Result<Vec<ArchivedPacket>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            send.packet_hash,
+            send.source_channel_id,
+            send.destination_channel_id,
+            send.transaction_hash,
+            send.height,
+            send.event_index,
+            send.timestamp,
+            EXISTS (SELECT 1 FROM v2_sync.packet_recv_sync recv WHERE recv.packet_hash = send.packet_hash) AS "received!",
+            EXISTS (SELECT 1 FROM v2_sync.packet_ack_sync ack WHERE ack.packet_hash = send.packet_hash) AS "acknowledged!",
+            EXISTS (SELECT 1 FROM v2_sync.packet_timeout_sync timeout WHERE timeout.packet_hash = send.packet_hash) AS "timed_out!"
+        FROM v2_sync.packet_send_sync send
+        JOIN config.chains c ON c.id = send.internal_chain_id
+        WHERE c.family || '.' || c.chain_id = $1
+        AND send.height >= $2
+        AND send.height < $3
+        "#,
+        universal_chain_id,
+        start_height,
+        end_height,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| ArchivedPacket {
+        packet_hash: record.packet_hash,
+        source_channel_id: record.source_channel_id,
+        destination_channel_id: record.destination_channel_id,
+        transaction_hash: record.transaction_hash,
+        height: record.height,
+        event_index: record.event_index,
+        timestamp: record.timestamp,
+        status: match (record.timed_out, record.acknowledged, record.received) {
+            (true, _, _) => PacketStatus::TimedOut,
+            (_, true, _) => PacketStatus::Acknowledged,
+            (_, _, true) => PacketStatus::Received,
+            _ => PacketStatus::Sent,
+        },
+    })
+    .collect())
+}
+
+/// The transfers sent by a chain in `[start_height, end_height)`.
+pub async fn get_transfers_to_archive(
+    db: &PgPool,
+    universal_chain_id: &str,
+    start_height: i64,
+    end_height: i64,
+) -> sqlx::Result<Vec<ArchivedTransfer>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            universal_chain_id,
+            counterparty_universal_chain_id,
+            source_channel_id,
+            destination_channel_id,
+            packet_hash,
+            transaction_hash,
+            height,
+            timestamp,
+            sender_canonical,
+            receiver_canonical,
+            sender_display,
+            receiver_display,
+            base_token,
+            base_token_symbol,
+            base_amount,
+            quote_token,
+            quote_amount,
+            amount_usd,
+            sort_order,
+            transfer_index
+        FROM v2_sync.packet_send_transfers_sync
+        WHERE universal_chain_id = $1
+        AND height >= $2
+        AND height < $3
+        "#,
+        universal_chain_id,
+        start_height,
+        end_height,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| ArchivedTransfer {
+        universal_chain_id: record.universal_chain_id,
+        counterparty_universal_chain_id: record.counterparty_universal_chain_id,
+        source_channel_id: record.source_channel_id,
+        destination_channel_id: record.destination_channel_id,
+        packet_hash: record.packet_hash,
+        transaction_hash: record.transaction_hash,
+        height: record.height,
+        timestamp: record.timestamp,
+        sender_canonical: record.sender_canonical,
+        receiver_canonical: record.receiver_canonical,
+        sender_display: record.sender_display,
+        receiver_display: record.receiver_display,
+        base_token: record.base_token,
+        base_token_symbol: record.base_token_symbol,
+        base_amount: record.base_amount.to_string(),
+        quote_token: record.quote_token,
+        quote_amount: record.quote_amount.to_string(),
+        amount_usd: record.amount_usd.map(|amount_usd| amount_usd.to_string()),
+        sort_order: record.sort_order,
+        transfer_index: record.transfer_index,
+    })
+    .collect())
+}
+
+/// Message types of governance proposals that upgrade a chain or its bridge contracts: cosmos
+/// software upgrades and contract migrations (gov v1 and v1beta1), and timelock calls to
+/// `upgradeTo(address)` and `upgradeToAndCall(address,bytes)` of upgradeable evm contracts.
+const UPGRADE_MESSAGE_TYPES: [&str; 8] = [
+    "/cosmos.upgrade.v1beta1.MsgSoftwareUpgrade",
+    "/ibc.core.client.v1.MsgIBCSoftwareUpgrade",
+    "/cosmwasm.wasm.v1.MsgMigrateContract",
+    "SoftwareUpgrade",
+    "IBCUpgrade",
+    "MigrateContract",
+    "0x3659cfe6",
+    "0x4f1ef286",
+];
+
+/// Halts, upgrades, reorgs and indexer incidents of a chain that overlap `since`..`until`, ordered
+/// by the time they started, at most `limit` of them.
+///
+/// Halts are gaps of at least `min_halt_seconds` between the times of consecutive blocks, which
+/// are only known for Tendermint chains. The last block before `since` and the first block from
+/// `until` are included, so halts that span either end of the range are reported too. Gaps between
+/// heights that are not both indexed (such as with event sampling) are not reported. Reorgs and
+/// indexer incidents are looked up by indexer id, which is the universal chain id of the chain.
+///
+/// `after` is the start time of the last entry of the previous page, with the number of entries
+/// that started at that time and were already returned. Entries that start at the same time are
+/// ordered by their annotation.
+pub async fn get_timeline(
+    db: &PgPool,
+    universal_chain_id: &str,
+    since: OffsetDateTime,
+    until: OffsetDateTime,
+    min_halt_seconds: f64,
+    after: Option<(OffsetDateTime, i64)>,
+    limit: i64,
+) -> sqlx::Result<Vec<TimelineEntry>> {
+    let from = after.map(|(started_at, _)| started_at);
+    // every kind fetches whole groups of entries that started at the same time, so that the first
+    // `limit` entries after the skipped ones are the same whichever page they are fetched from
+    let fetch = limit + after.map_or(0, |(_, skipped)| skipped);
+
+    let mut timeline = Vec::new();
+
+    timeline.extend(
+        sqlx::query!(
+            r#"
+            WITH chain AS (
+                SELECT id FROM config.chains WHERE family || '.' || chain_id = $1
+            ),
+            blocks AS (
+                SELECT b.height, b.time
+                FROM v2_cosmos.blocks b, chain
+                WHERE b.internal_chain_id = chain.id
+                AND b.time >= $2
+                AND b.time < $3
+                UNION ALL
+                (
+                    SELECT b.height, b.time
+                    FROM v2_cosmos.blocks b, chain
+                    WHERE b.internal_chain_id = chain.id
+                    AND b.time < $2
+                    ORDER BY b.time DESC
+                    LIMIT 1
+                )
+                UNION ALL
+                (
+                    SELECT b.height, b.time
+                    FROM v2_cosmos.blocks b, chain
+                    WHERE b.internal_chain_id = chain.id
+                    AND b.time >= $3
+                    ORDER BY b.time
+                    LIMIT 1
+                )
+            )
+            SELECT
+                height AS "height!",
+                time AS "started_at!",
+                next_time AS "ended_at!"
+            FROM (
+                SELECT
+                    height,
+                    time,
+                    LEAD(height) OVER (ORDER BY height) AS next_height,
+                    LEAD(time) OVER (ORDER BY height) AS next_time
+                FROM blocks
+            ) blocks
+            WHERE next_height = height + 1
+            AND next_time - time >= make_interval(secs => $4)
+            AND ($5::TIMESTAMPTZ IS NULL OR time >= $5)
+            ORDER BY time
+            FETCH FIRST $6 ROWS WITH TIES
+            "#,
+            universal_chain_id,
+            since,
+            until,
+            min_halt_seconds,
+            from,
+            fetch,
+        )
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|record| TimelineEntry {
+            started_at: record.started_at,
+            ended_at: Some(record.ended_at),
+            annotation: TimelineAnnotation::Halt {
+                height: record.height,
+            },
+        }),
+    );
+
+    timeline.extend(
+        sqlx::query!(
+            r#"
+            SELECT
+                g.proposal_id,
+                MIN(g.height) AS "height!",
+                MIN(g.timestamp) AS "started_at!",
+                MAX(g.timestamp) FILTER (WHERE g.action = 'executed') AS executed_at,
+                ARRAY(
+                    SELECT DISTINCT message_type
+                    FROM v2_sync.governance_proposal_sync m, UNNEST(m.message_types) message_type
+                    WHERE m.internal_chain_id = g.internal_chain_id AND m.proposal_id = g.proposal_id
+                    ORDER BY message_type
+                ) AS "message_types!"
+            FROM v2_sync.governance_proposal_sync g
+            JOIN config.chains c ON c.id = g.internal_chain_id
+            WHERE c.family || '.' || c.chain_id = $1
+            GROUP BY g.internal_chain_id, g.proposal_id
+            HAVING BOOL_OR(g.message_types && $2)
+            AND NOT BOOL_OR(g.action = 'cancelled')
+            AND COALESCE(MAX(g.timestamp) FILTER (WHERE g.action = 'executed'), 'infinity') >= $3
+            AND MIN(g.timestamp) < $4
+            AND ($5::TIMESTAMPTZ IS NULL OR MIN(g.timestamp) >= $5)
+            ORDER BY MIN(g.timestamp)
+            FETCH FIRST $6 ROWS WITH TIES
+            "#,
+            universal_chain_id,
+            &UPGRADE_MESSAGE_TYPES[..],
+            since,
+            until,
+            from,
+            fetch,
+        )
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|record| TimelineEntry {
+            started_at: record.started_at,
+            ended_at: record.executed_at,
+            annotation: TimelineAnnotation::Upgrade {
+                proposal_id: record.proposal_id,
+                height: record.height,
+                message_types: record.message_types,
+            },
+        }),
+    );
+
+    timeline.extend(
+        sqlx::query!(
+            r#"
+            SELECT fork_height, tip_height, old_tip_hash, new_tip_hash, detected_at
+            FROM hubble.reorgs
+            WHERE indexer_id = $1
+            AND detected_at >= $2
+            AND detected_at < $3
+            AND ($4::TIMESTAMPTZ IS NULL OR detected_at >= $4)
+            ORDER BY detected_at
+            FETCH FIRST $5 ROWS WITH TIES
+            "#,
+            universal_chain_id,
+            since,
+            until,
+            from,
+            fetch,
+        )
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|record| TimelineEntry {
+            started_at: record.detected_at,
+            ended_at: None,
+            annotation: TimelineAnnotation::Reorg {
+                fork_height: record.fork_height,
+                tip_height: record.tip_height,
+                old_tip_hash: record.old_tip_hash,
+                new_tip_hash: record.new_tip_hash,
+            },
+        }),
+    );
+
+    timeline.extend(
+        sqlx::query!(
+            r#"
+            SELECT
+                target_lag_seconds,
+                objective,
+                short_burn_rate,
+                long_burn_rate,
+                started_at,
+                ended_at
+            FROM hubble.lag_alerts
+            WHERE indexer_id = $1
+            AND (ended_at IS NULL OR ended_at >= $2)
+            AND started_at < $3
+            AND ($4::TIMESTAMPTZ IS NULL OR started_at >= $4)
+            ORDER BY started_at
+            FETCH FIRST $5 ROWS WITH TIES
+            "#,
+            universal_chain_id,
+            since,
+            until,
+            from,
+            fetch,
+        )
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|record| TimelineEntry {
+            started_at: record.started_at,
+            ended_at: record.ended_at,
+            annotation: TimelineAnnotation::IndexerIncident {
+                target_lag_seconds: record.target_lag_seconds,
+                objective: record.objective,
+                short_burn_rate: record.short_burn_rate,
+                long_burn_rate: record.long_burn_rate,
+            },
+        }),
+    );
+
+    timeline.sort_by_cached_key(|entry| {
+        (
+            entry.started_at,
+            serde_json::to_string(&entry.annotation)
+                .expect("annotations should be json serializable"),
+        )
+    });
+
+    if let Some((started_at, skipped)) = after {
+        let skipped = timeline
+            .iter()
+            .take_while(|entry| entry.started_at == started_at)
+            .count()
+            .min(skipped as usize);
+        timeline.drain(..skipped);
+    }
+
+    timeline.truncate(limit as usize);
+
+    Ok(timeline)
+}
+
+/// A row of `hubble.saved_filters`.
+struct SavedFilterRow {
+    owner: String,
+    name: String,
+    path: String,
+    params: serde_json::Value,
+    shared: bool,
+    is_default: bool,
+    updated_at: OffsetDateTime,
+}
+
+impl From<SavedFilterRow> for SavedFilter {
+    fn from(row: SavedFilterRow) -> Self {
+        Self {
+            owner: row.owner,
+            name: row.name,
+            path: row.path,
+            params: serde_json::from_value(row.params).unwrap_or_default(),
+            shared: row.shared,
+            default: row.is_default,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+/// The filters saved by `owner`, and the filters shared by others.
+pub async fn get_saved_filters(db: &PgPool, owner: &str) -> sqlx::Result<Vec<SavedFilter>> {
+    Ok(sqlx::query_as!(
+        SavedFilterRow,
+        r#"
+        SELECT owner, name, path, params, shared, is_default, updated_at
+        FROM hubble.saved_filters
+        WHERE owner = $1 OR shared
+        ORDER BY owner <> $1, owner, name
+        "#,
+        owner,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(Into::into)
+    .collect())
+}
+
+/// The filter `name` of `owner`, if it is visible to `caller` (it is the owner, or the filter is
+/// shared).
+pub async fn get_saved_filter(
+    db: &PgPool,
+    owner: &str,
+    name: &str,
+    caller: &str,
+) -> sqlx::Result<Option<SavedFilter>> {
+    Ok(sqlx::query_as!(
+        SavedFilterRow,
+        r#"
+        SELECT owner, name, path, params, shared, is_default, updated_at
+        FROM hubble.saved_filters
+        WHERE owner = $1
+        AND name = $2
+        AND (owner = $3 OR shared)
+        "#,
+        owner,
+        name,
+        caller,
+    )
+    .fetch_optional(db)
+    .await?
+    .map(Into::into))
+}
+
+/// The default filter of `owner` for the route `path`.
+pub async fn get_default_filter(
+    db: &PgPool,
+    owner: &str,
+    path: &str,
+) -> sqlx::Result<Option<SavedFilter>> {
+    Ok(sqlx::query_as!(
+        SavedFilterRow,
+        r#"
+        SELECT owner, name, path, params, shared, is_default, updated_at
+        FROM hubble.saved_filters
+        WHERE owner = $1
+        AND path = $2
+        AND is_default
+        "#,
+        owner,
+        path,
+    )
+    .fetch_optional(db)
+    .await?
+    .map(Into::into))
+}
+
+/// Save the filter `name` of `owner`, replacing the filter with the same name. A default filter
+/// replaces the previous default filter of the owner for its route.
+pub async fn upsert_saved_filter(
+    db: &PgPool,
+    owner: &str,
+    name: &str,
+    filter: &SaveFilter,
+) -> sqlx::Result<SavedFilter> {
+    let mut tx = db.begin().await?;
+
+    if filter.default {
+        sqlx::query!(
+            r#"
+            UPDATE hubble.saved_filters
+            SET is_default = false
+            WHERE owner = $1
+            AND path = $2
+            AND name <> $3
+            AND is_default
+            "#,
+            owner,
+            filter.path,
+            name,
+        )
+        .execute(tx.as_mut())
+        .await?;
+    }
+
+    let record = sqlx::query_as!(
+        SavedFilterRow,
+        r#"
+        INSERT INTO hubble.saved_filters (owner, name, path, params, shared, is_default)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (owner, name) DO UPDATE SET
+            path = excluded.path,
+            params = excluded.params,
+            shared = excluded.shared,
+            is_default = excluded.is_default,
+            updated_at = now()
+        RETURNING owner, name, path, params, shared, is_default, updated_at
+        "#,
+        owner,
+        name,
+        filter.path,
+        serde_json::to_value(&filter.params).expect("params can be serialized"),
+        filter.shared,
+        filter.default,
+    )
+    .fetch_one(tx.as_mut())
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(record.into())
+}
+
+/// Delete the filter `name` of `owner`. Returns whether it existed.
+pub async fn delete_saved_filter(db: &PgPool, owner: &str, name: &str) -> sqlx::Result<bool> {
+    Ok(sqlx::query!(
+        r#"
+        DELETE FROM hubble.saved_filters
+        WHERE owner = $1
+        AND name = $2
+        "#,
+        owner,
+        name,
+    )
+    .execute(db)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+/// Failed events of a chain, newest first. Pages continue before `before_id`.
+pub async fn get_failed_events(
+    db: &PgPool,
+    universal_chain_id: &str,
+    before_id: Option<i64>,
+    limit: i64,
+) -> sqlx::Result<Vec<FailedEvent>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            f.id,
+            f.height,
+            f.event,
+            f.error,
+            f.attempts,
+            f.next_attempt_at,
+            f.created_at
+        FROM hubble.failed_events f
+        JOIN config.chains c ON c.id = f.internal_chain_id
+        WHERE c.family || '.' || c.chain_id = $1
+        AND ($2::BIGINT IS NULL OR f.id < $2)
+        ORDER BY f.id DESC
+        LIMIT $3
+        "#,
+        universal_chain_id,
+        before_id,
+        limit,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| FailedEvent {
+        id: record.id,
+        height: record.height,
+        event: record.event,
+        error: record.error,
+        attempts: record.attempts,
+        next_attempt_at: record.next_attempt_at,
+        created_at: record.created_at,
+    })
+    .collect())
+}
+
+/// Schedules the failed events of a chain (or only the one with `id`) to be retried right away,
+/// instead of after their backoff. Returns the number of scheduled events.
+pub async fn redrive_failed_events(
+    db: &PgPool,
+    universal_chain_id: &str,
+    id: Option<i64>,
+) -> sqlx::Result<u64> {
+    Ok(sqlx::query!(
+        r#"
+        UPDATE hubble.failed_events f
+        SET
+            next_attempt_at = now(),
+            updated_at = now()
+        FROM config.chains c
+        WHERE c.id = f.internal_chain_id
+        AND c.family || '.' || c.chain_id = $1
+        AND ($2::BIGINT IS NULL OR f.id = $2)
+        "#,
+        universal_chain_id,
+        id,
+    )
+    .execute(db)
+    .await?
+    .rows_affected())
+}
@@ -0,0 +1,1079 @@
+use std::{future::Future, sync::Arc};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post, put, MethodRouter},
+    Extension, Json, Router,
+};
+use futures::future::try_join_all;
+use serde::Deserialize;
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use tracing::{error, info};
+
+use crate::{
+    api::{
+        archive::{merge_newest_first, ColdStorage, PACKET_SEND_PARTITIONS, TRANSFER_PARTITIONS},
+        auth::{authenticate, identify, require, Action, AdminToken, Caller, Role, AUDIT_TARGET},
+        filters::{FilteredQuery, SaveFilter, SavedFilter},
+        postgres::{
+            delete_saved_filter, get_archived_partitions, get_asset_stats, get_daily_prices,
+            get_event_stats, get_failed_events, get_governance_proposals, get_packet_events,
+            get_packet_transfers, get_packets_by_channel, get_saved_filters, get_timeline,
+            get_token_stats, get_transfers_by_address, redrive_failed_events, upsert_saved_filter,
+        },
+        stream::{subscribe, RecordSender},
+        to_hex, AssetStats, DailyPrice, Databases, EventStats, FailedEvent, GovernanceProposal,
+        Packet, PacketTrace, PacketTransfers, Page, TimelineEntry, TokenStats, Transfer,
+        DEFAULT_LIMIT, MAX_LIMIT,
+    },
+    index_advisor::{
+        approve_recommendation, dismiss_recommendation, get_recommendations, IndexRecommendation,
+    },
+    indexer::normalization::parse_address,
+    webhook::{get_deliveries, redrive_delivery, redrive_failed_deliveries, WebhookDelivery},
+};
+
+pub fn router(
+    db: PgPool,
+    databases: Databases,
+    stream: Option<RecordSender>,
+    tokens: Vec<AdminToken>,
+    cold_storage: Option<ColdStorage>,
+) -> Router {
+    let router = Router::new()
+        .route("/v1/transfers", get(transfers))
+        .route("/v1/packets", get(packets))
+        .route("/v1/packets/:packet_hash", get(packet_trace))
+        .route("/v1/packets/:packet_hash/transfers", get(packet_transfers))
+        .route("/v1/tokens/stats", get(token_stats))
+        .route("/v1/assets/stats", get(asset_stats))
+        .route("/v1/prices/daily", get(daily_prices))
+        .route("/v1/events/stats", get(event_stats))
+        .route("/v1/governance/proposals", get(governance_proposals))
+        .route("/v1/chains/:universal_chain_id/timeline", get(timeline));
+
+    let tokens = Arc::<[AdminToken]>::from(tokens);
+    // api keys only give access to the saved filters
+    let admin_tokens = tokens
+        .iter()
+        .filter(|token| token.role != Role::Consumer)
+        .cloned()
+        .collect::<Arc<[_]>>();
+
+    // identify the callers with a token, to apply their saved filters
+    let router = match tokens.is_empty() {
+        true => router,
+        false => router.route_layer(middleware::from_fn_with_state(tokens.clone(), identify)),
+    }
+    .layer(Extension(databases.clone()))
+    .layer(Extension(cold_storage))
+    .with_state(db.clone());
+
+    let router = match stream {
+        Some(sender) => router.merge(
+            Router::new()
+                .route("/v1/stream", get(subscribe))
+                .with_state(sender),
+        ),
+        None => router,
+    };
+
+    let router = match tokens.is_empty() {
+        true => router,
+        false => router.merge(
+            Router::new()
+                .route(
+                    "/v1/filters",
+                    admin(Action::ListSavedFilters, get(saved_filters)),
+                )
+                .route(
+                    "/v1/filters/:name",
+                    admin(
+                        Action::ManageSavedFilters,
+                        put(save_filter).delete(delete_filter),
+                    ),
+                )
+                .route_layer(middleware::from_fn_with_state(tokens, authenticate))
+                .with_state(db.clone()),
+        ),
+    };
+
+    if admin_tokens.is_empty() {
+        return router;
+    }
+
+    router.merge(
+        Router::new()
+            .route(
+                "/admin/v1/webhooks/deliveries",
+                admin(Action::ListWebhookDeliveries, get(webhook_deliveries)),
+            )
+            .route(
+                "/admin/v1/webhooks/deliveries/redrive",
+                admin(
+                    Action::RedriveFailedWebhookDeliveries,
+                    post(redrive_webhook_deliveries),
+                ),
+            )
+            .route(
+                "/admin/v1/webhooks/deliveries/:id/redrive",
+                admin(
+                    Action::RedriveWebhookDelivery,
+                    post(redrive_webhook_delivery),
+                ),
+            )
+            .route(
+                "/admin/v1/index-recommendations",
+                admin(Action::ListIndexRecommendations, get(index_recommendations)),
+            )
+            .route(
+                "/admin/v1/index-recommendations/:id/approve",
+                admin(
+                    Action::ApproveIndexRecommendation,
+                    post(approve_index_recommendation),
+                ),
+            )
+            .route(
+                "/admin/v1/index-recommendations/:id/dismiss",
+                admin(
+                    Action::DismissIndexRecommendation,
+                    post(dismiss_index_recommendation),
+                ),
+            )
+            .route(
+                "/admin/v1/chains/:universal_chain_id/failed-events",
+                admin(Action::ListFailedEvents, get(failed_events)),
+            )
+            .route(
+                "/admin/v1/chains/:universal_chain_id/failed-events/redrive",
+                admin(
+                    Action::RedriveFailedEvents,
+                    post(redrive_chain_failed_events),
+                ),
+            )
+            .route(
+                "/admin/v1/chains/:universal_chain_id/failed-events/:id/redrive",
+                admin(Action::RedriveFailedEvent, post(redrive_chain_failed_event)),
+            )
+            .route_layer(middleware::from_fn_with_state(admin_tokens, authenticate))
+            .layer(Extension(databases))
+            .with_state(db),
+    )
+}
+
+/// A route that requires a token, only served to callers that are allowed `action`.
+fn admin(action: Action, route: MethodRouter<PgPool>) -> MethodRouter<PgPool> {
+    route.route_layer(middleware::from_fn_with_state(action, require))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(super) enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("not found")]
+    NotFound,
+    #[error("unauthorized")]
+    Unauthorized,
+    #[error("forbidden")]
+    Forbidden,
+    #[error("the query exceeded its time budget, narrow the filters of the request (e.g. `universal_chain_id` or `since`/`until`)")]
+    QueryBudgetExceeded,
+    #[error("database error")]
+    Database(sqlx::Error),
+    #[error("cold storage error")]
+    ColdStorage(#[from] duckdb::Error),
+}
+
+/// SQLSTATE of a statement that was canceled, ie. because it ran longer than `statement_timeout`.
+const QUERY_CANCELED: &str = "57014";
+
+impl From<sqlx::Error> for ApiError {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::Database(database_error)
+                if database_error.code().as_deref() == Some(QUERY_CANCELED) =>
+            {
+                ApiError::QueryBudgetExceeded
+            }
+            _ => ApiError::Database(error),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::NotFound => StatusCode::NOT_FOUND,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden => StatusCode::FORBIDDEN,
+            ApiError::QueryBudgetExceeded => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Database(error) => {
+                error!("error querying database: {error:?}");
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            ApiError::ColdStorage(error) => {
+                error!("error querying cold storage: {error:?}");
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        (
+            status,
+            Json(serde_json::json!({ "error": self.to_string() })),
+        )
+            .into_response()
+    }
+}
+
+fn limit(limit: Option<i64>) -> Result<i64, ApiError> {
+    match limit {
+        None => Ok(DEFAULT_LIMIT),
+        Some(limit) if (1..=MAX_LIMIT).contains(&limit) => Ok(limit),
+        Some(limit) => Err(ApiError::BadRequest(format!(
+            "limit {limit} is not between 1 and {MAX_LIMIT}"
+        ))),
+    }
+}
+
+fn page<T>(items: Vec<T>, limit: i64, cursor: impl FnOnce(&T) -> String) -> Page<T> {
+    let next_cursor = match items.last() {
+        Some(last) if items.len() as i64 == limit => Some(cursor(last)),
+        _ => None,
+    };
+
+    Page { items, next_cursor }
+}
+
+/// Runs the query on every database, and merges the rows newest first by `key`, for requests that
+/// are not limited to one chain.
+async fn query_all<T, K: Ord, F: Future<Output = sqlx::Result<Vec<T>>>>(
+    databases: &Databases,
+    query: impl Fn(PgPool) -> F,
+    key: impl Fn(&T) -> K,
+    limit: i64,
+) -> Result<Vec<T>, ApiError> {
+    Ok(try_join_all(databases.all().iter().cloned().map(query))
+        .await?
+        .into_iter()
+        .fold(vec![], |merged, rows| {
+            merge_newest_first(merged, rows, &key, limit)
+        }))
+}
+
+#[derive(Debug, Deserialize)]
+struct TransfersQuery {
+    /// Sender or receiver, in any format (e.g. bech32 or 0x-prefixed hex) since transfers are
+    /// looked up by canonical address.
+    address: String,
+    universal_chain_id: Option<String>,
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+async fn transfers(
+    Extension(databases): Extension<Databases>,
+    Extension(cold_storage): Extension<Option<ColdStorage>>,
+    FilteredQuery(query): FilteredQuery<TransfersQuery>,
+) -> Result<Json<Page<Transfer>>, ApiError> {
+    let limit = limit(query.limit)?;
+    let address = parse_address(&query.address)
+        .map_err(|_| ApiError::BadRequest(format!("invalid address {}", query.address)))?;
+
+    let address = &address.0;
+    let before = query.cursor.as_deref();
+
+    let mut transfers = match query.universal_chain_id.as_deref() {
+        Some(universal_chain_id) => {
+            get_transfers_by_address(
+                databases.chain(universal_chain_id),
+                address,
+                Some(universal_chain_id),
+                before,
+                limit,
+            )
+            .await?
+        }
+        None => query_all(
+            &databases,
+            |db| async move { get_transfers_by_address(&db, address, None, before, limit).await },
+            |transfer| transfer.sort_order.clone(),
+            limit,
+        )
+        .await?,
+    };
+
+    // pruned transfers are older than the transfers in postgres, so the archive is only read once
+    // the page runs past them
+    if let Some(cold_storage) = cold_storage.filter(|_| (transfers.len() as i64) < limit) {
+        let uris = match query.universal_chain_id.as_deref() {
+            Some(universal_chain_id) => {
+                get_archived_partitions(
+                    databases.chain(universal_chain_id),
+                    TRANSFER_PARTITIONS,
+                    Some(universal_chain_id),
+                    None,
+                )
+                .await?
+            }
+            None => try_join_all(
+                databases
+                    .all()
+                    .iter()
+                    .map(|db| get_archived_partitions(db, TRANSFER_PARTITIONS, None, None)),
+            )
+            .await?
+            .concat(),
+        };
+
+        if !uris.is_empty() {
+            let archived = cold_storage
+                .get_transfers_by_address(
+                    uris,
+                    address.to_vec(),
+                    before.map(ToOwned::to_owned),
+                    limit,
+                )
+                .await?;
+
+            transfers = merge_newest_first(
+                transfers,
+                archived,
+                |transfer| transfer.sort_order.clone(),
+                limit,
+            );
+        }
+    }
+
+    Ok(Json(page(transfers, limit, |transfer| {
+        transfer.sort_order.clone()
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct PacketsQuery {
+    universal_chain_id: String,
+    channel_id: i32,
+    /// `<height>.<event index>` of the last packet of the previous page.
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+async fn packets(
+    Extension(databases): Extension<Databases>,
+    Extension(cold_storage): Extension<Option<ColdStorage>>,
+    FilteredQuery(query): FilteredQuery<PacketsQuery>,
+) -> Result<Json<Page<Packet>>, ApiError> {
+    let limit = limit(query.limit)?;
+
+    let before = query
+        .cursor
+        .as_deref()
+        .map(|cursor| {
+            cursor
+                .split_once('.')
+                .and_then(|(height, event_index)| {
+                    Some((height.parse().ok()?, event_index.parse().ok()?))
+                })
+                .ok_or_else(|| ApiError::BadRequest(format!("invalid cursor {cursor}")))
+        })
+        .transpose()?;
+
+    let db = databases.chain(&query.universal_chain_id);
+
+    let mut packets = get_packets_by_channel(
+        db,
+        &query.universal_chain_id,
+        query.channel_id,
+        before,
+        limit,
+    )
+    .await?;
+
+    // pruned packets are older than the packets in postgres, so the archive is only read once the
+    // page runs past them
+    if let Some(cold_storage) = cold_storage.filter(|_| (packets.len() as i64) < limit) {
+        let uris = get_archived_partitions(
+            db,
+            PACKET_SEND_PARTITIONS,
+            Some(&query.universal_chain_id),
+            before.map(|(height, _)| height),
+        )
+        .await?;
+
+        if !uris.is_empty() {
+            let archived = cold_storage
+                .get_packets_by_channel(uris, query.channel_id, before, limit)
+                .await?;
+
+            packets = merge_newest_first(
+                packets,
+                archived,
+                |(packet, event_index)| (packet.height, *event_index),
+                limit,
+            );
+        }
+    }
+
+    let page = page(packets, limit, |(packet, event_index)| {
+        format!("{}.{event_index}", packet.height)
+    });
+
+    Ok(Json(Page {
+        items: page.items.into_iter().map(|(packet, _)| packet).collect(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+fn parse_packet_hash(packet_hash: &str) -> Result<Vec<u8>, ApiError> {
+    hex::decode(packet_hash.strip_prefix("0x").unwrap_or(packet_hash))
+        .map_err(|_| ApiError::BadRequest(format!("invalid packet hash {packet_hash}")))
+}
+
+async fn packet_trace(
+    Extension(databases): Extension<Databases>,
+    Path(packet_hash): Path<String>,
+) -> Result<Json<PacketTrace>, ApiError> {
+    let bytes = parse_packet_hash(&packet_hash)?;
+
+    // the events of a packet are indexed by the chains on both ends, which may be indexed into
+    // different databases
+    let mut events = try_join_all(
+        databases
+            .all()
+            .iter()
+            .map(|db| get_packet_events(db, &bytes)),
+    )
+    .await?
+    .concat();
+
+    events.sort_by_key(|event| event.timestamp);
+
+    if events.is_empty() {
+        return Err(ApiError::NotFound);
+    }
+
+    Ok(Json(PacketTrace {
+        packet_hash: to_hex(&bytes),
+        events,
+    }))
+}
+
+async fn packet_transfers(
+    Extension(databases): Extension<Databases>,
+    Path(packet_hash): Path<String>,
+) -> Result<Json<PacketTransfers>, ApiError> {
+    let bytes = parse_packet_hash(&packet_hash)?;
+
+    // the transfers of a packet are decoded on its source chain only
+    let transfers = try_join_all(
+        databases
+            .all()
+            .iter()
+            .map(|db| get_packet_transfers(db, &bytes)),
+    )
+    .await?
+    .into_iter()
+    .find(|transfers| !transfers.is_empty())
+    .unwrap_or_default();
+
+    if transfers.is_empty() {
+        return Err(ApiError::NotFound);
+    }
+
+    Ok(Json(PacketTransfers {
+        packet_hash: to_hex(&bytes),
+        rollup: transfers.iter().map(|transfer| transfer.status).collect(),
+        transfers,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenStatsQuery {
+    universal_chain_id: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    until: Option<OffsetDateTime>,
+    /// Number of tokens to skip.
+    cursor: Option<i64>,
+    limit: Option<i64>,
+}
+
+async fn token_stats(
+    State(db): State<PgPool>,
+    FilteredQuery(query): FilteredQuery<TokenStatsQuery>,
+) -> Result<Json<Page<TokenStats>>, ApiError> {
+    let limit = limit(query.limit)?;
+    let offset = query.cursor.unwrap_or_default().max(0);
+
+    let stats = get_token_stats(
+        &db,
+        query.universal_chain_id.as_deref(),
+        query.since,
+        query.until,
+        offset,
+        limit,
+    )
+    .await?;
+
+    let next_offset = offset + limit;
+
+    Ok(Json(page(stats, limit, |_| next_offset.to_string())))
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetStatsQuery {
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    until: Option<OffsetDateTime>,
+    /// Number of assets to skip.
+    cursor: Option<i64>,
+    limit: Option<i64>,
+}
+
+async fn asset_stats(
+    State(db): State<PgPool>,
+    FilteredQuery(query): FilteredQuery<AssetStatsQuery>,
+) -> Result<Json<Page<AssetStats>>, ApiError> {
+    let limit = limit(query.limit)?;
+    let offset = query.cursor.unwrap_or_default().max(0);
+
+    let stats = get_asset_stats(&db, query.since, query.until, offset, limit).await?;
+
+    let next_offset = offset + limit;
+
+    Ok(Json(page(stats, limit, |_| next_offset.to_string())))
+}
+
+#[derive(Debug, Deserialize)]
+struct DailyPricesQuery {
+    universal_chain_id: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    until: Option<OffsetDateTime>,
+    /// Number of prices to skip.
+    cursor: Option<i64>,
+    limit: Option<i64>,
+}
+
+async fn daily_prices(
+    State(db): State<PgPool>,
+    FilteredQuery(query): FilteredQuery<DailyPricesQuery>,
+) -> Result<Json<Page<DailyPrice>>, ApiError> {
+    let limit = limit(query.limit)?;
+    let offset = query.cursor.unwrap_or_default().max(0);
+
+    let prices = get_daily_prices(
+        &db,
+        query.universal_chain_id.as_deref(),
+        query.since,
+        query.until,
+        offset,
+        limit,
+    )
+    .await?;
+
+    let next_offset = offset + limit;
+
+    Ok(Json(page(prices, limit, |_| next_offset.to_string())))
+}
+
+#[derive(Debug, Deserialize)]
+struct EventStatsQuery {
+    universal_chain_id: String,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    until: Option<OffsetDateTime>,
+}
+
+async fn event_stats(
+    Extension(databases): Extension<Databases>,
+    FilteredQuery(query): FilteredQuery<EventStatsQuery>,
+) -> Result<Json<Vec<EventStats>>, ApiError> {
+    let (since, until) = bounded_range(query.since, query.until, OffsetDateTime::now_utc())?;
+
+    Ok(Json(
+        get_event_stats(
+            databases.chain(&query.universal_chain_id),
+            &query.universal_chain_id,
+            since,
+            until,
+        )
+        .await?,
+    ))
+}
+
+/// The range of aggregates over the indexed rows of a chain without `since`.
+const DEFAULT_RANGE: Duration = Duration::days(1);
+/// The widest range of aggregates over the indexed rows of a chain.
+const MAX_RANGE: Duration = Duration::days(31);
+
+/// The `since`/`until` range of an aggregate, such that it never scans all rows of a chain. `until`
+/// defaults to `now`, and `since` to one day before `until`.
+fn bounded_range(
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+    now: OffsetDateTime,
+) -> Result<(OffsetDateTime, OffsetDateTime), ApiError> {
+    let until = until.unwrap_or(now);
+    let since = since.unwrap_or(until - DEFAULT_RANGE);
+
+    if until - since > MAX_RANGE {
+        return Err(ApiError::BadRequest(format!(
+            "the range from since to until is wider than {} days",
+            MAX_RANGE.whole_days()
+        )));
+    }
+
+    Ok((since, until))
+}
+
+#[derive(Debug, Deserialize)]
+struct GovernanceProposalsQuery {
+    universal_chain_id: Option<String>,
+    /// Only timelock operations that are (not) executed or cancelled yet. Cosmos gov proposals are
+    /// never selected, as their outcome is not indexed.
+    pending: Option<bool>,
+    /// `<height>.<proposal id>` of the last proposal of the previous page.
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+async fn governance_proposals(
+    Extension(databases): Extension<Databases>,
+    FilteredQuery(query): FilteredQuery<GovernanceProposalsQuery>,
+) -> Result<Json<Page<GovernanceProposal>>, ApiError> {
+    let limit = limit(query.limit)?;
+
+    let before = query
+        .cursor
+        .as_deref()
+        .map(|cursor| {
+            cursor
+                .split_once('.')
+                .and_then(|(height, proposal_id)| Some((height.parse().ok()?, proposal_id)))
+                .ok_or_else(|| ApiError::BadRequest(format!("invalid cursor {cursor}")))
+        })
+        .transpose()?;
+
+    let proposals = match query.universal_chain_id.as_deref() {
+        Some(universal_chain_id) => {
+            get_governance_proposals(
+                databases.chain(universal_chain_id),
+                Some(universal_chain_id),
+                query.pending,
+                before,
+                limit,
+            )
+            .await?
+        }
+        None => {
+            let pending = query.pending;
+
+            query_all(
+                &databases,
+                |db| async move {
+                    get_governance_proposals(&db, None, pending, before, limit).await
+                },
+                |proposal| (proposal.height, proposal.proposal_id.clone()),
+                limit,
+            )
+            .await?
+        }
+    };
+
+    Ok(Json(page(proposals, limit, |proposal| {
+        format!("{}.{}", proposal.height, proposal.proposal_id)
+    })))
+}
+
+/// Minimum gap between two blocks reported as a halt when the request does not specify one.
+const DEFAULT_MIN_HALT_SECONDS: f64 = 60.0;
+
+#[derive(Debug, Deserialize)]
+struct TimelineQuery {
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    until: Option<OffsetDateTime>,
+    min_halt_seconds: Option<f64>,
+    /// `<started at in unix nanoseconds>.<entries returned that started then>` of the last entry
+    /// of the previous page.
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+async fn timeline(
+    Extension(databases): Extension<Databases>,
+    Path(universal_chain_id): Path<String>,
+    FilteredQuery(query): FilteredQuery<TimelineQuery>,
+) -> Result<Json<Page<TimelineEntry>>, ApiError> {
+    let min_halt_seconds = match query.min_halt_seconds {
+        None => DEFAULT_MIN_HALT_SECONDS,
+        Some(seconds) if seconds > 0.0 => seconds,
+        Some(seconds) => {
+            return Err(ApiError::BadRequest(format!(
+                "min_halt_seconds {seconds} is not positive"
+            )))
+        }
+    };
+
+    let (since, until) = bounded_range(query.since, query.until, OffsetDateTime::now_utc())?;
+    let limit = limit(query.limit)?;
+
+    let after = query
+        .cursor
+        .as_deref()
+        .map(|cursor| {
+            cursor
+                .split_once('.')
+                .and_then(|(started_at, returned)| {
+                    Some((
+                        OffsetDateTime::from_unix_timestamp_nanos(started_at.parse().ok()?).ok()?,
+                        i64::from(returned.parse::<u32>().ok()?),
+                    ))
+                })
+                .ok_or_else(|| ApiError::BadRequest(format!("invalid cursor {cursor}")))
+        })
+        .transpose()?;
+
+    let timeline = get_timeline(
+        databases.chain(&universal_chain_id),
+        &universal_chain_id,
+        since,
+        until,
+        min_halt_seconds,
+        after,
+        limit,
+    )
+    .await?;
+
+    // entries that started at the same time as the last one, including those of previous pages
+    let returned = timeline.last().map_or(0, |last| {
+        after
+            .filter(|(started_at, _)| *started_at == last.started_at)
+            .map_or(0, |(_, returned)| returned)
+            + timeline
+                .iter()
+                .filter(|entry| entry.started_at == last.started_at)
+                .count() as i64
+    });
+
+    Ok(Json(page(timeline, limit, |entry| {
+        format!("{}.{returned}", entry.started_at.unix_timestamp_nanos())
+    })))
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookDeliveriesQuery {
+    /// Only deliveries to this endpoint url.
+    endpoint: Option<String>,
+    /// Only deliveries with this status (`pending`, `delivered` or `failed`).
+    status: Option<String>,
+    /// Id of the last delivery of the previous page.
+    cursor: Option<i64>,
+    limit: Option<i64>,
+}
+
+async fn webhook_deliveries(
+    State(db): State<PgPool>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+) -> Result<Json<Page<WebhookDelivery>>, ApiError> {
+    let limit = limit(query.limit)?;
+
+    let deliveries = get_deliveries(
+        &db,
+        query.endpoint.as_deref(),
+        query.status.as_deref(),
+        query.cursor,
+        limit,
+    )
+    .await?;
+
+    Ok(Json(page(deliveries, limit, |delivery| {
+        delivery.id.to_string()
+    })))
+}
+
+async fn redrive_webhook_delivery(
+    State(db): State<PgPool>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !redrive_delivery(&db, id).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    info!(target: AUDIT_TARGET, caller = %caller.name, id, "redriven webhook delivery");
+
+    Ok(Json(serde_json::json!({ "redriven": 1 })))
+}
+
+#[derive(Debug, Deserialize)]
+struct RedriveWebhookDeliveriesQuery {
+    /// Only failed deliveries to this endpoint url.
+    endpoint: Option<String>,
+}
+
+async fn redrive_webhook_deliveries(
+    State(db): State<PgPool>,
+    Extension(caller): Extension<Caller>,
+    Query(query): Query<RedriveWebhookDeliveriesQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let redriven = redrive_failed_deliveries(&db, query.endpoint.as_deref()).await?;
+
+    info!(
+        target: AUDIT_TARGET,
+        caller = %caller.name,
+        endpoint = query.endpoint.as_deref(),
+        redriven,
+        "redriven failed webhook deliveries"
+    );
+
+    Ok(Json(serde_json::json!({ "redriven": redriven })))
+}
+
+#[derive(Debug, Deserialize)]
+struct IndexRecommendationsQuery {
+    /// Only recommendations with this status (`proposed`, `approved`, `dismissed`, `creating`,
+    /// `created` or `failed`).
+    status: Option<String>,
+}
+
+async fn index_recommendations(
+    State(db): State<PgPool>,
+    Query(query): Query<IndexRecommendationsQuery>,
+) -> Result<Json<Vec<IndexRecommendation>>, ApiError> {
+    Ok(Json(
+        get_recommendations(&db, query.status.as_deref()).await?,
+    ))
+}
+
+async fn approve_index_recommendation(
+    State(db): State<PgPool>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !approve_recommendation(&db, id).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    info!(target: AUDIT_TARGET, caller = %caller.name, id, "approved index recommendation");
+
+    Ok(Json(serde_json::json!({ "approved": 1 })))
+}
+
+async fn dismiss_index_recommendation(
+    State(db): State<PgPool>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !dismiss_recommendation(&db, id).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    info!(target: AUDIT_TARGET, caller = %caller.name, id, "dismissed index recommendation");
+
+    Ok(Json(serde_json::json!({ "dismissed": 1 })))
+}
+
+#[derive(Debug, Deserialize)]
+struct FailedEventsQuery {
+    /// Id of the last failed event of the previous page.
+    cursor: Option<i64>,
+    limit: Option<i64>,
+}
+
+async fn failed_events(
+    Extension(databases): Extension<Databases>,
+    Path(universal_chain_id): Path<String>,
+    Query(query): Query<FailedEventsQuery>,
+) -> Result<Json<Page<FailedEvent>>, ApiError> {
+    let limit = limit(query.limit)?;
+
+    let failed_events = get_failed_events(
+        databases.chain(&universal_chain_id),
+        &universal_chain_id,
+        query.cursor,
+        limit,
+    )
+    .await?;
+
+    Ok(Json(page(failed_events, limit, |failed_event| {
+        failed_event.id.to_string()
+    })))
+}
+
+async fn redrive_chain_failed_event(
+    Extension(databases): Extension<Databases>,
+    Extension(caller): Extension<Caller>,
+    Path((universal_chain_id, id)): Path<(String, i64)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if redrive_failed_events(
+        databases.chain(&universal_chain_id),
+        &universal_chain_id,
+        Some(id),
+    )
+    .await?
+        == 0
+    {
+        return Err(ApiError::NotFound);
+    }
+
+    info!(
+        target: AUDIT_TARGET,
+        caller = %caller.name,
+        universal_chain_id = %universal_chain_id,
+        id,
+        "redriven failed event"
+    );
+
+    Ok(Json(serde_json::json!({ "redriven": 1 })))
+}
+
+async fn redrive_chain_failed_events(
+    Extension(databases): Extension<Databases>,
+    Extension(caller): Extension<Caller>,
+    Path(universal_chain_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let redriven = redrive_failed_events(
+        databases.chain(&universal_chain_id),
+        &universal_chain_id,
+        None,
+    )
+    .await?;
+
+    info!(
+        target: AUDIT_TARGET,
+        caller = %caller.name,
+        universal_chain_id = %universal_chain_id,
+        redriven,
+        "redriven failed events"
+    );
+
+    Ok(Json(serde_json::json!({ "redriven": redriven })))
+}
+
+async fn saved_filters(
+    State(db): State<PgPool>,
+    Extension(caller): Extension<Caller>,
+) -> Result<Json<Vec<SavedFilter>>, ApiError> {
+    Ok(Json(get_saved_filters(&db, &caller.name).await?))
+}
+
+async fn save_filter(
+    State(db): State<PgPool>,
+    Extension(caller): Extension<Caller>,
+    Path(name): Path<String>,
+    Json(filter): Json<SaveFilter>,
+) -> Result<Json<SavedFilter>, ApiError> {
+    filter.validate(&name)?;
+
+    let saved_filter = upsert_saved_filter(&db, &caller.name, &name, &filter).await?;
+
+    info!(
+        target: AUDIT_TARGET,
+        caller = %caller.name,
+        name = %name,
+        path = %filter.path,
+        shared = filter.shared,
+        default = filter.default,
+        "saved filter"
+    );
+
+    Ok(Json(saved_filter))
+}
+
+async fn delete_filter(
+    State(db): State<PgPool>,
+    Extension(caller): Extension<Caller>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if !delete_saved_filter(&db, &caller.name, &name).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    info!(target: AUDIT_TARGET, caller = %caller.name, name = %name, "deleted filter");
+
+    Ok(Json(serde_json::json!({ "deleted": 1 })))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, error::Error};
+
+    use sqlx::error::{DatabaseError, ErrorKind};
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("database error {0}")]
+    struct TestDatabaseError(&'static str);
+
+    impl DatabaseError for TestDatabaseError {
+        fn message(&self) -> &str {
+            "test"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(self.0.into())
+        }
+
+        fn as_error(&self) -> &(dyn Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    fn status(code: &'static str) -> StatusCode {
+        ApiError::from(sqlx::Error::Database(Box::new(TestDatabaseError(code))))
+            .into_response()
+            .status()
+    }
+
+    #[test]
+    fn query_budget_exceeded() {
+        assert_eq!(status(QUERY_CANCELED), StatusCode::UNPROCESSABLE_ENTITY);
+        // e.g. a unique violation is not caused by the filters of the request
+        assert_eq!(status("23505"), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn ranges_are_bounded() {
+        let now = datetime!(2025-06-10 00:00 UTC);
+
+        assert_eq!(
+            bounded_range(None, None, now).unwrap(),
+            (datetime!(2025-06-09 00:00 UTC), now)
+        );
+        assert_eq!(
+            bounded_range(None, Some(datetime!(2025-06-01 00:00 UTC)), now).unwrap(),
+            (
+                datetime!(2025-05-31 00:00 UTC),
+                datetime!(2025-06-01 00:00 UTC)
+            )
+        );
+        assert!(bounded_range(Some(datetime!(2025-05-10 00:00 UTC)), None, now).is_ok());
+        assert!(bounded_range(Some(datetime!(2025-05-09 00:00 UTC)), None, now).is_err());
+    }
+}
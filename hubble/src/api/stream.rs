@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::Response,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info, warn};
+
+use crate::indexer::RECORD_NOTIFICATION_CHANNEL;
+
+/// Number of records buffered per subscriber. Subscribers that fall further behind skip records.
+pub const STREAM_CAPACITY: usize = 1024;
+
+pub type RecordSender = broadcast::Sender<Arc<str>>;
+
+/// Forward the records published by the indexers to the subscribers.
+pub async fn listen(db: sqlx::PgPool, sender: RecordSender) -> color_eyre::Result<()> {
+    let mut listener = PgListener::connect_with(&db).await?;
+    listener.listen(RECORD_NOTIFICATION_CHANNEL).await?;
+
+    info!("listening for records on {RECORD_NOTIFICATION_CHANNEL}");
+
+    loop {
+        let notification = listener.recv().await?;
+
+        // sending only fails when there are no subscribers
+        let _ = sender.send(notification.payload().into());
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamFilter {
+    /// Only push records of this type (`transfer` or `packet_event`).
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    /// Only push records of this chain (or transfers to this chain).
+    universal_chain_id: Option<String>,
+}
+
+impl StreamFilter {
+    fn matches(&self, record: &Value) -> bool {
+        self.kind
+            .as_ref()
+            .is_none_or(|kind| record["type"].as_str() == Some(kind))
+            && self
+                .universal_chain_id
+                .as_ref()
+                .is_none_or(|universal_chain_id| {
+                    record["universal_chain_id"].as_str() == Some(universal_chain_id)
+                        || record["counterparty_universal_chain_id"].as_str()
+                            == Some(universal_chain_id)
+                })
+    }
+}
+
+pub async fn subscribe(
+    State(sender): State<RecordSender>,
+    Query(filter): Query<StreamFilter>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let receiver = sender.subscribe();
+
+    ws.on_upgrade(move |socket| push(socket, receiver, filter))
+}
+
+async fn push(
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<Arc<str>>,
+    filter: StreamFilter,
+) {
+    loop {
+        let record = match receiver.recv().await {
+            Ok(record) => record,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("subscriber is behind, skipped {skipped} records");
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+
+        match serde_json::from_str::<Value>(&record) {
+            Ok(value) if filter.matches(&value) => {}
+            Ok(_) => continue,
+            Err(error) => {
+                warn!("ignoring invalid record {record}: {error}");
+                continue;
+            }
+        }
+
+        if socket
+            .send(Message::Text(record.to_string()))
+            .await
+            .is_err()
+        {
+            debug!("subscriber disconnected");
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::StreamFilter;
+
+    #[test]
+    fn filters_records() {
+        let transfer = json!({
+            "type": "transfer",
+            "universal_chain_id": "union.union-1",
+            "counterparty_universal_chain_id": "ethereum.1",
+        });
+        let packet_event = json!({
+            "type": "packet_event",
+            "universal_chain_id": "ethereum.1",
+        });
+
+        assert!(StreamFilter::default().matches(&transfer));
+
+        let transfers = StreamFilter {
+            kind: Some("transfer".to_string()),
+            universal_chain_id: None,
+        };
+        assert!(transfers.matches(&transfer));
+        assert!(!transfers.matches(&packet_event));
+
+        let ethereum = StreamFilter {
+            kind: None,
+            universal_chain_id: Some("ethereum.1".to_string()),
+        };
+        assert!(ethereum.matches(&transfer));
+        assert!(ethereum.matches(&packet_event));
+
+        let babylon = StreamFilter {
+            kind: None,
+            universal_chain_id: Some("babylon.bbn-1".to_string()),
+        };
+        assert!(!babylon.matches(&transfer));
+    }
+}
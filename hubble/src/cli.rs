@@ -1,11 +1,26 @@
-use std::{fs, net::SocketAddr, path::Path, str::FromStr};
+use std::{
+    collections::HashMap,
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use clap::{builder::ValueParser, ArgGroup, Parser};
+use clap::{builder::ValueParser, ArgGroup, Parser, Subcommand};
 use tracing::{info_span, Instrument};
 
 use crate::{
-    indexer::{self, nats::NatsConnection},
+    api::{AdminTokens, ApiKeys, ArchivedTable},
+    index_advisor::IndexAdvisorConfig,
+    indexer::{
+        self, enrich::pricing::PricingConfig, event::types::UniversalChainId, nats::NatsConnection,
+        scheduler::FetchScheduler,
+    },
+    lag_monitor::LagSloConfig,
     logging::LogFormat,
+    stuck_packets::StuckPacketsConfig,
+    token_bucket_monitor::TokenBucketAlertConfig,
+    token_metadata,
 };
 
 fn parse_string_or_file_source(input: &str) -> Result<String, String> {
@@ -19,6 +34,24 @@ fn parse_string_or_file_source(input: &str) -> Result<String, String> {
     }
 }
 
+fn parse_admin_tokens(input: &str) -> Result<AdminTokens, String> {
+    parse_string_or_file_source(input)?
+        .parse()
+        .map_err(|e| format!("Failed to parse admin tokens: {}", e))
+}
+
+fn parse_api_keys(input: &str) -> Result<ApiKeys, String> {
+    parse_string_or_file_source(input)?
+        .parse()
+        .map_err(|e| format!("Failed to parse api keys: {}", e))
+}
+
+fn parse_databases(input: &str) -> Result<Databases, String> {
+    parse_string_or_file_source(input)?
+        .parse()
+        .map_err(|e| format!("Failed to parse databases: {}", e))
+}
+
 /// Hubble is state machine observer.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -27,6 +60,7 @@ fn parse_string_or_file_source(input: &str) -> Result<String, String> {
         .required(false)
         .requires_all(["nats-consumer", "nats-url", "nats-username", "nats-password"])
 ))]
+#[command(subcommand_negates_reqs = true)]
 pub struct Args {
     /// The database url used to connect with timescaledb.
     #[arg(
@@ -38,17 +72,74 @@ pub struct Args {
     )]
     pub database_url: Option<String>,
 
+    /// Additional databases as a json object of name to database url (or @file containing the
+    /// object). Indexers select one by name, so heavy chains can be moved to their own cluster.
+    #[arg(long, env = "HUBBLE_DATABASES", value_parser = ValueParser::new(parse_databases))]
+    pub databases: Option<Databases>,
+
+    /// Maximum number of connections of the pool of each database (the default database and each
+    /// of `--databases`). Indexers with `max_connections` get a dedicated pool instead.
+    #[arg(long, env = "HUBBLE_DATABASE_MAX_CONNECTIONS", default_value_t = 40)]
+    pub database_max_connections: u32,
+
+    /// Publish inserted transfers and packet events on the `hubble_records` postgres channel, to
+    /// be pushed to the subscribers of `hubble serve --stream`.
+    #[arg(long, env = "HUBBLE_PUBLISH_RECORDS", default_value_t = false)]
+    pub publish_records: bool,
+
     #[command(flatten)]
     pub nats: Option<Nats>,
 
     /// Indexer configurations to start.
-    #[arg(short, long, env = "HUBBLE_INDEXERS")]
-    pub indexers: Indexers,
+    #[arg(short, long, env = "HUBBLE_INDEXERS", required = true)]
+    pub indexers: Option<Indexers>,
 
     /// Indexer configurations to start.
     #[arg(short, long, env = "HUBBLE_METRICS_PORT")]
     pub metrics_addr: Option<SocketAddr>,
 
+    /// Maximum number of ranges of blocks fetched at the same time over all indexers, when catching
+    /// up or fixing blocks. Unlimited if not set.
+    #[arg(long, env = "HUBBLE_MAX_CONCURRENT_FETCHES")]
+    pub max_concurrent_fetches: Option<usize>,
+
+    /// Price providers used to annotate transfers with their usd value. Pricing is disabled when
+    /// not configured.
+    #[arg(long, env = "HUBBLE_PRICING")]
+    pub pricing: Option<PricingConfig>,
+
+    /// Webhook that is notified when the estimated capacity of a token bucket drops below a
+    /// threshold. Buckets are monitored without alerting when not configured.
+    #[arg(long, env = "HUBBLE_TOKEN_BUCKET_ALERTS")]
+    pub token_bucket_alerts: Option<TokenBucketAlertConfig>,
+
+    /// Ingestion lag slo of the indexers, from which lag alerts are derived. The lag is not
+    /// monitored when not configured.
+    #[arg(long, env = "HUBBLE_LAG_SLO")]
+    pub lag_slo: Option<LagSloConfig>,
+
+    /// Detection of packets that are neither acknowledged nor timed out after a minimum age,
+    /// recorded in `hubble.stuck_packets`. Packets are not checked when not configured.
+    #[arg(long, env = "HUBBLE_STUCK_PACKETS")]
+    pub stuck_packets: Option<StuckPacketsConfig>,
+
+    /// Secret (or @file containing the secret) that webhook payloads are signed with, in the
+    /// `x-hubble-signature` header. Payloads are not signed when not configured.
+    #[arg(
+        long,
+        env = "HUBBLE_WEBHOOK_SECRET",
+        value_parser = ValueParser::new(parse_string_or_file_source),
+    )]
+    pub webhook_secret: Option<String>,
+
+    /// Coingecko api key (or @file containing the key).
+    #[arg(
+        long,
+        env = "HUBBLE_COINGECKO_API_KEY",
+        value_parser = ValueParser::new(parse_string_or_file_source),
+    )]
+    pub coingecko_api_key: Option<String>,
+
     /// The log format for Hubble.
     #[arg(
         global = true,
@@ -58,6 +149,191 @@ pub struct Args {
         default_value = "json"
     )]
     pub log_format: LogFormat,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// One-off maintenance commands. Hubble exits after the command completes instead of indexing.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Enrichment maintenance.
+    #[command(subcommand)]
+    Enrich(EnrichCommand),
+    /// Backfills of block ranges, which are run by the indexers.
+    #[command(subcommand)]
+    Backfill(BackfillCommand),
+    /// Migrations of indexed records.
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Serve a read-only REST api over the indexed transfers, packets and tokens instead of
+    /// indexing.
+    Serve {
+        /// The address to listen on.
+        #[arg(long, env = "HUBBLE_API_ADDR", default_value = "0.0.0.0:8080")]
+        addr: SocketAddr,
+        /// Push new transfers and packet events to websocket subscribers of `/v1/stream`.
+        #[arg(long, default_value_t = false)]
+        stream: bool,
+        /// Bearer token (or @file containing the token) for the admin api under `/admin`, with
+        /// the `admin` role. The admin api is disabled when no admin tokens are configured.
+        #[arg(
+            long,
+            env = "HUBBLE_ADMIN_TOKEN",
+            value_parser = ValueParser::new(parse_string_or_file_source),
+        )]
+        admin_token: Option<String>,
+        /// Bearer tokens for the admin api as a json array of `{"name": ..., "role": ...,
+        /// "token": ...}` (or @file containing the array), where the role is one of `read_only`,
+        /// `operator` or `admin`. Actions are logged with the name of the token.
+        #[arg(
+            long,
+            env = "HUBBLE_ADMIN_TOKENS",
+            value_parser = ValueParser::new(parse_admin_tokens),
+        )]
+        admin_tokens: Option<AdminTokens>,
+        /// Bearer keys of api consumers as a json array of `{"name": ..., "key": ...}` (or @file
+        /// containing the array). Consumers can save and apply filters under the name of their
+        /// key, without access to the admin api.
+        #[arg(
+            long,
+            env = "HUBBLE_API_KEYS",
+            value_parser = ValueParser::new(parse_api_keys),
+        )]
+        api_keys: Option<ApiKeys>,
+        /// Read packets and transfers that were pruned from postgres from the parquet files of
+        /// their archived partitions (`hubble.archived_partitions`), and merge them with the live
+        /// records.
+        #[arg(long, env = "HUBBLE_COLD_STORAGE", default_value_t = false)]
+        cold_storage: bool,
+        /// Recommend indexes for the slowest statements of the api (from `pg_stat_statements`),
+        /// and create the approved ones in the maintenance window. Indexes are not advised when
+        /// not configured.
+        #[arg(long, env = "HUBBLE_INDEX_ADVISOR")]
+        index_advisor: Option<IndexAdvisorConfig>,
+        /// Maximum time in milliseconds a statement of the api may run (`statement_timeout`).
+        /// Requests with a statement that exceeds it fail with `422`, asking to narrow the filters,
+        /// instead of scanning at the expense of the writes of the indexers.
+        #[arg(long, env = "HUBBLE_QUERY_BUDGET_MS", default_value_t = 10_000)]
+        query_budget_ms: u64,
+    },
+    /// Archive the packet sends or transfers of a chain in a range of heights to a parquet file,
+    /// and list it in `hubble.archived_partitions`, such that the records can be pruned from
+    /// postgres and stay available through the api (with `serve --cold-storage`).
+    Archive {
+        /// The records to archive.
+        #[arg(long, value_enum)]
+        table: ArchivedTable,
+        /// The chain of the records (e.g. `cosmos.union-1`).
+        #[arg(long)]
+        universal_chain_id: String,
+        /// First height to archive (inclusive).
+        #[arg(long)]
+        start_height: u64,
+        /// Last height to archive (exclusive).
+        #[arg(long)]
+        end_height: u64,
+        /// Path or url to write the parquet file to, which must be readable by the api.
+        #[arg(long)]
+        uri: String,
+    },
+    /// Generate the indexer config and migration to onboard a chain. The chain family is
+    /// detected by probing the rpc endpoint, and the contracts are looked up in the registry.
+    OnboardChain {
+        /// Rpc endpoint of the chain (evm or cometbft).
+        #[arg(long)]
+        rpc_url: url::Url,
+        /// Path or http(s) url of the deployments registry (`deployments/deployments.json`).
+        #[arg(long)]
+        registry: String,
+        /// Family of the chain, required when the chain id is used by multiple families.
+        #[arg(long)]
+        family: Option<String>,
+        /// Register the chain as a testnet.
+        #[arg(long, default_value_t = false)]
+        testnet: bool,
+        /// File to write the rows that register the chain and its contracts to.
+        #[arg(long)]
+        migration_file: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EnrichCommand {
+    /// Recalculate the wrap direction of transfers indexed before wrapped-token enrichment existed.
+    Backfill {
+        /// Only backfill transfers of this chain (internal chain id).
+        #[arg(long)]
+        internal_chain_id: Option<i32>,
+        /// First height to backfill (inclusive).
+        #[arg(long)]
+        from_height: u64,
+        /// Last height to backfill (exclusive).
+        #[arg(long)]
+        to_height: u64,
+        /// Number of heights processed per transaction.
+        #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(1..))]
+        batch_size: u64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BackfillCommand {
+    /// Schedule a backfill of a range of blocks for an indexer. The indexer backfills the range
+    /// while running, recording its progress such that it resumes after a restart.
+    Schedule {
+        /// The indexer that backfills the range.
+        #[arg(long)]
+        indexer_id: String,
+        /// First height to backfill (inclusive).
+        #[arg(long)]
+        start_height: u64,
+        /// Last height to backfill (exclusive).
+        #[arg(long)]
+        end_height: u64,
+    },
+    /// Report the progress of the scheduled backfills.
+    Status {
+        /// Only report the backfills of this indexer.
+        #[arg(long)]
+        indexer_id: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MigrateCommand {
+    /// Set the decoder version of records indexed before records were tagged with it, from the
+    /// versions of the contracts that emitted the events of their transaction.
+    DecoderVersions {
+        /// Only migrate records of this chain (internal chain id).
+        #[arg(long)]
+        internal_chain_id: Option<i32>,
+        /// First height to migrate (inclusive).
+        #[arg(long)]
+        from_height: u64,
+        /// Last height to migrate (exclusive).
+        #[arg(long)]
+        to_height: u64,
+        /// Number of heights processed per transaction.
+        #[arg(long, default_value_t = 10000, value_parser = clap::value_parser!(u64).range(1..))]
+        batch_size: u64,
+    },
+    /// Register the tokens of transfers enriched before tokens were registered when enriching, such
+    /// that their metadata is fetched.
+    TokenMetadata {
+        /// Only migrate transfers of this chain (internal chain id).
+        #[arg(long)]
+        internal_chain_id: Option<i32>,
+        /// First height to migrate (inclusive).
+        #[arg(long)]
+        from_height: u64,
+        /// Last height to migrate (exclusive).
+        #[arg(long)]
+        to_height: u64,
+        /// Number of heights processed per transaction.
+        #[arg(long, default_value_t = 10000, value_parser = clap::value_parser!(u64).range(1..))]
+        batch_size: u64,
+    },
 }
 
 #[derive(Parser, Debug)]
@@ -97,6 +373,39 @@ pub struct Nats {
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct Indexers(Vec<IndexerConfig>);
 
+impl Indexers {
+    /// Rpc endpoints of the indexed chains, used to fetch token metadata.
+    pub fn token_metadata_endpoints(
+        &self,
+    ) -> impl Iterator<Item = (UniversalChainId, token_metadata::Endpoint)> + '_ {
+        self.0
+            .iter()
+            .filter_map(IndexerConfig::token_metadata_endpoint)
+    }
+
+    /// Names of the databases of the chains that are not indexed into the default database.
+    pub fn chain_databases(&self) -> impl Iterator<Item = (UniversalChainId, String)> + '_ {
+        self.0.iter().filter_map(|indexer| {
+            Some((
+                indexer.universal_chain_id().clone(),
+                indexer.database()?.to_owned(),
+            ))
+        })
+    }
+
+    /// Rpc endpoints of the indexed evm chains, used to read onchain price oracles.
+    pub fn ethereum_rpc_urls(
+        &self,
+    ) -> impl Iterator<Item = (UniversalChainId, Vec<url::Url>)> + '_ {
+        self.0.iter().filter_map(|indexer| match indexer {
+            IndexerConfig::Ethereum(cfg) => {
+                Some((cfg.universal_chain_id.clone(), cfg.rpc_urls.clone()))
+            }
+            _ => None,
+        })
+    }
+}
+
 impl IntoIterator for Indexers {
     type Item = IndexerConfig;
 
@@ -129,11 +438,57 @@ impl IndexerConfig {
     }
 }
 
+impl IndexerConfig {
+    /// Name of the database the indexer writes to, `None` for the default database.
+    pub fn database(&self) -> Option<&str> {
+        match &self {
+            Self::Dummy(cfg) => cfg.database.as_deref(),
+            Self::Ethereum(cfg) => cfg.database.as_deref(),
+            Self::Tendermint(cfg) => cfg.database.as_deref(),
+        }
+    }
+
+    pub fn universal_chain_id(&self) -> &UniversalChainId {
+        match &self {
+            Self::Dummy(cfg) => &cfg.universal_chain_id,
+            Self::Ethereum(cfg) => &cfg.universal_chain_id,
+            Self::Tendermint(cfg) => &cfg.universal_chain_id,
+        }
+    }
+
+    /// Size of the connection pool dedicated to the indexer, `None` to share the pool of its
+    /// database.
+    pub fn max_connections(&self) -> Option<u32> {
+        match &self {
+            Self::Dummy(cfg) => cfg.max_connections,
+            Self::Ethereum(cfg) => cfg.max_connections,
+            Self::Tendermint(cfg) => cfg.max_connections,
+        }
+    }
+}
+
+impl IndexerConfig {
+    pub fn token_metadata_endpoint(&self) -> Option<(UniversalChainId, token_metadata::Endpoint)> {
+        match &self {
+            Self::Dummy(_) => None,
+            Self::Ethereum(cfg) => Some((
+                cfg.universal_chain_id.clone(),
+                token_metadata::Endpoint::Ethereum(cfg.rpc_urls.clone()),
+            )),
+            Self::Tendermint(cfg) => Some((
+                cfg.universal_chain_id.clone(),
+                token_metadata::Endpoint::Tendermint(cfg.rpc_urls.clone()),
+            )),
+        }
+    }
+}
+
 impl IndexerConfig {
     pub async fn index(
         self,
         db: sqlx::PgPool,
         nats: Option<NatsConnection>,
+        fetch_scheduler: FetchScheduler,
     ) -> Result<(), color_eyre::eyre::Report> {
         let label = self.label();
 
@@ -142,7 +497,7 @@ impl IndexerConfig {
 
         match self {
             Self::Dummy(cfg) => {
-                cfg.build(db, nats)
+                cfg.build(db, nats, fetch_scheduler)
                     .instrument(initializer_span)
                     .await?
                     .index()
@@ -150,7 +505,7 @@ impl IndexerConfig {
                     .await
             }
             Self::Ethereum(cfg) => {
-                cfg.build(db, nats)
+                cfg.build(db, nats, fetch_scheduler)
                     .instrument(initializer_span)
                     .await?
                     .index()
@@ -158,7 +513,7 @@ impl IndexerConfig {
                     .await
             }
             Self::Tendermint(cfg) => {
-                cfg.build(db, nats)
+                cfg.build(db, nats, fetch_scheduler)
                     .instrument(initializer_span)
                     .await?
                     .index()
@@ -177,6 +532,27 @@ impl FromStr for Indexers {
     }
 }
 
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Databases(HashMap<String, String>);
+
+impl IntoIterator for Databases {
+    type Item = (String, String);
+
+    type IntoIter = std::collections::hash_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromStr for Databases {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(item: &str) -> Result<Self, <Self as FromStr>::Err> {
+        serde_json::from_str(item).map_err(Into::into)
+    }
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct TokensUrls(Vec<String>);
 
@@ -0,0 +1,159 @@
+use sqlx::{Executor, PgPool};
+use time::OffsetDateTime;
+use tracing::{debug, error, info, warn};
+
+use crate::index_advisor::{
+    plan::{
+        create_index_statement, drop_index_statement, filter_columns, filtered_seq_scans,
+        index_name, is_covered,
+    },
+    postgres::{
+        claim_approved_recommendation, complete_recommendation, explain, get_slow_statements,
+        get_table_info, is_invalid_index, upsert_recommendation, NewRecommendation, SlowStatement,
+    },
+    IndexAdvisorConfig,
+};
+
+/// Number of the slowest statements (by total execution time) that are explained in every run.
+const MAX_SAMPLED_STATEMENTS: i64 = 20;
+
+/// Samples the slowest statements of the api from `pg_stat_statements`, and recommends an index
+/// for every sequential scan of a large table in their plans that filters on columns without an
+/// index. Recommendations are recorded in `hubble.index_recommendations` until they are approved
+/// (or dismissed) by an operator, and approved ones are created within the maintenance window.
+pub async fn advise_indexes(db: &PgPool, config: &IndexAdvisorConfig) -> color_eyre::Result<()> {
+    let statements = get_slow_statements(
+        db,
+        config.min_calls,
+        config.min_mean_exec_ms,
+        MAX_SAMPLED_STATEMENTS,
+    )
+    .await?;
+
+    debug!("advisor: sampled {} slow statements", statements.len());
+
+    for statement in &statements {
+        match advise_statement(db, config, statement).await {
+            Ok(()) => debug!("advisor: {} => success", statement.queryid),
+            Err(error) => warn!("advisor: {} => error: {error:?}", statement.queryid),
+        }
+    }
+
+    if let Some(window) = config.maintenance_window {
+        if window.contains(OffsetDateTime::now_utc().hour()) {
+            create_approved_indexes(db).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn advise_statement(
+    db: &PgPool,
+    config: &IndexAdvisorConfig,
+    statement: &SlowStatement,
+) -> color_eyre::Result<()> {
+    // statements can fail to plan without their parameter types, or be truncated by
+    // pg_stat_statements, in which case there is nothing to advise
+    let plan = match explain(db, &statement.query).await {
+        Ok(plan) => plan,
+        Err(error) => {
+            debug!("advisor: {}: cannot explain: {error}", statement.queryid);
+            return Ok(());
+        }
+    };
+
+    for scan in filtered_seq_scans(&plan) {
+        let Some(table) = get_table_info(db, &scan.schema, &scan.table).await? else {
+            continue;
+        };
+
+        if table.rows < config.min_table_rows {
+            continue;
+        }
+
+        let columns = filter_columns(&scan.filter, &table.columns);
+
+        if columns.is_empty() || is_covered(&table.indexes, &columns) {
+            continue;
+        }
+
+        let create_statement = create_index_statement(&scan.schema, &scan.table, &columns);
+
+        let (id, inserted) = upsert_recommendation(
+            db,
+            &NewRecommendation {
+                schema_name: &scan.schema,
+                table_name: &scan.table,
+                columns: &columns,
+                statement: &create_statement,
+                sample: statement,
+                table_rows: table.rows,
+            },
+        )
+        .await?;
+
+        if inserted {
+            info!(
+                "advisor: recommended index {id} on {}.{} ({}) for statement {} ({} calls, {:.1}ms mean)",
+                scan.schema,
+                scan.table,
+                columns.join(", "),
+                statement.queryid,
+                statement.calls,
+                statement.mean_exec_ms,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the approved indexes one by one. Indexes are created concurrently, so they don't block
+/// the indexers writing to the table, but every build scans the whole table.
+async fn create_approved_indexes(db: &PgPool) -> color_eyre::Result<()> {
+    while let Some((id, schema, table, columns)) = claim_approved_recommendation(db).await? {
+        let statement = create_index_statement(&schema, &table, &columns);
+
+        info!("advisor: creating index {id}: {statement}");
+
+        // CREATE INDEX CONCURRENTLY cannot run in a transaction
+        match create_index(db, &schema, &table, &columns, &statement).await {
+            Ok(()) => {
+                complete_recommendation(db, id, None).await?;
+
+                info!("advisor: created index {id}");
+            }
+            Err(err) => {
+                // the invalid index left behind by a failed build is dropped before the next one
+                complete_recommendation(db, id, Some(&err.to_string())).await?;
+
+                error!("advisor: failed to create index {id}: {err:?}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the `CREATE INDEX CONCURRENTLY IF NOT EXISTS` `statement`, after dropping the invalid index
+/// of an earlier failed build, which would otherwise be kept as the existing index.
+async fn create_index(
+    db: &PgPool,
+    schema: &str,
+    table: &str,
+    columns: &[String],
+    statement: &str,
+) -> sqlx::Result<()> {
+    if is_invalid_index(db, schema, &index_name(table, columns)).await? {
+        let drop_statement = drop_index_statement(schema, table, columns);
+
+        warn!("advisor: dropping the invalid index of a failed build: {drop_statement}");
+
+        db.execute(&*drop_statement).await?;
+    }
+
+    db.execute(statement).await?;
+
+    Ok(())
+}
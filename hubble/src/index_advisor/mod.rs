@@ -0,0 +1,126 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+use time::OffsetDateTime;
+
+mod advisor;
+mod plan;
+mod postgres;
+
+pub use crate::index_advisor::{
+    advisor::advise_indexes,
+    postgres::{approve_recommendation, dismiss_recommendation, get_recommendations},
+};
+
+/// Index advisor of the api, passed as json (e.g.
+/// `{"min_mean_exec_ms": 100, "maintenance_window": {"start_hour": 2, "end_hour": 4}}`).
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexAdvisorConfig {
+    /// Statements that take at least this long on average are sampled.
+    #[serde(default = "default_min_mean_exec_ms")]
+    pub min_mean_exec_ms: f64,
+    /// Statements that ran less often than this are not sampled, so one-off queries don't get
+    /// indexes.
+    #[serde(default = "default_min_calls")]
+    pub min_calls: i64,
+    /// Tables with fewer (estimated) rows than this are not indexed, since scanning them is cheap.
+    #[serde(default = "default_min_table_rows")]
+    pub min_table_rows: i64,
+    /// Hours in which approved recommendations are created. Recommendations are only reported when
+    /// not configured.
+    #[serde(default)]
+    pub maintenance_window: Option<MaintenanceWindow>,
+}
+
+fn default_min_mean_exec_ms() -> f64 {
+    100.0
+}
+
+fn default_min_calls() -> i64 {
+    10
+}
+
+fn default_min_table_rows() -> i64 {
+    100_000
+}
+
+impl FromStr for IndexAdvisorConfig {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(item: &str) -> Result<Self, <Self as FromStr>::Err> {
+        serde_json::from_str(item).map_err(Into::into)
+    }
+}
+
+/// The (utc) hours from `start_hour` up to (excluding) `end_hour`, wrapping around midnight when
+/// `start_hour` is after `end_hour`.
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MaintenanceWindow {
+    pub start_hour: u8,
+    pub end_hour: u8,
+}
+
+impl MaintenanceWindow {
+    pub fn contains(&self, hour: u8) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// An index on `columns` of a table that is scanned sequentially by a slow statement of the api,
+/// as recorded in `hubble.index_recommendations`.
+#[derive(Clone, Debug, Serialize)]
+pub struct IndexRecommendation {
+    pub id: i64,
+    pub schema_name: String,
+    pub table_name: String,
+    pub columns: Vec<String>,
+    /// The `CREATE INDEX` statement that creates the index.
+    pub statement: String,
+    /// The statement (as normalized by `pg_stat_statements`) that scans the table.
+    pub sample_query: String,
+    pub calls: i64,
+    pub mean_exec_ms: f64,
+    pub table_rows: i64,
+    /// One of `proposed`, `approved`, `dismissed`, `creating`, `created` or `failed`.
+    pub status: String,
+    pub error: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub applied_at: Option<OffsetDateTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maintenance_window_contains() {
+        let window = MaintenanceWindow {
+            start_hour: 2,
+            end_hour: 4,
+        };
+
+        assert!(!window.contains(1));
+        assert!(window.contains(2));
+        assert!(window.contains(3));
+        assert!(!window.contains(4));
+
+        let overnight = MaintenanceWindow {
+            start_hour: 22,
+            end_hour: 2,
+        };
+
+        assert!(overnight.contains(23));
+        assert!(overnight.contains(1));
+        assert!(!overnight.contains(12));
+    }
+}
@@ -0,0 +1,255 @@
+use serde_json::Value;
+
+/// Indexes are recommended on at most this many columns.
+const MAX_INDEX_COLUMNS: usize = 3;
+
+/// Postgres truncates identifiers to this many bytes.
+const MAX_IDENTIFIER_LEN: usize = 63;
+
+const SYSTEM_SCHEMAS: &[&str] = &["pg_catalog", "information_schema", "pg_toast"];
+
+/// A sequential scan of a table that filters its rows, which an index on the filtered columns can
+/// replace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FilteredSeqScan {
+    pub schema: String,
+    pub table: String,
+    pub filter: String,
+}
+
+/// The filtered sequential scans of user tables in a plan, as returned by
+/// `EXPLAIN (VERBOSE, FORMAT JSON)`.
+pub fn filtered_seq_scans(plan: &Value) -> Vec<FilteredSeqScan> {
+    fn go(node: &Value, scans: &mut Vec<FilteredSeqScan>) {
+        let field = |name: &str| node.get(name).and_then(Value::as_str);
+
+        if let (Some("Seq Scan"), Some(schema), Some(table), Some(filter)) = (
+            field("Node Type"),
+            field("Schema"),
+            field("Relation Name"),
+            field("Filter"),
+        ) {
+            if !SYSTEM_SCHEMAS.contains(&schema) {
+                scans.push(FilteredSeqScan {
+                    schema: schema.to_owned(),
+                    table: table.to_owned(),
+                    filter: filter.to_owned(),
+                });
+            }
+        }
+
+        for child in node
+            .get("Plans")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            go(child, scans);
+        }
+    }
+
+    let mut scans = vec![];
+
+    for statement in plan.as_array().into_iter().flatten() {
+        if let Some(node) = statement.get("Plan") {
+            go(node, &mut scans);
+        }
+    }
+
+    scans
+}
+
+/// The columns of the table (out of `table_columns`) that `filter` compares, those compared for
+/// equality first, each in the order they appear in the filter.
+pub fn filter_columns(filter: &str, table_columns: &[String]) -> Vec<String> {
+    let mut equality = vec![];
+    let mut other = vec![];
+
+    for condition in filter.split(" AND ") {
+        let columns = if condition.contains(" = ") {
+            &mut equality
+        } else {
+            &mut other
+        };
+
+        for identifier in condition.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_')) {
+            if table_columns.iter().any(|column| column == identifier)
+                && !columns.iter().any(|column| column == identifier)
+            {
+                columns.push(identifier.to_owned());
+            }
+        }
+    }
+
+    other.retain(|column| !equality.contains(column));
+
+    equality
+        .into_iter()
+        .chain(other)
+        .take(MAX_INDEX_COLUMNS)
+        .collect()
+}
+
+/// Whether one of `indexes` (by their columns) starts with `columns`, such that an index on
+/// `columns` would not add anything.
+pub fn is_covered(indexes: &[Vec<String>], columns: &[String]) -> bool {
+    indexes.iter().any(|index| index.starts_with(columns))
+}
+
+pub fn index_name(table: &str, columns: &[String]) -> String {
+    let mut name = format!("{table}_{}_advised_idx", columns.join("_"));
+    name.truncate(MAX_IDENTIFIER_LEN);
+    name
+}
+
+/// The statement that creates an index on `columns`, without blocking writes to the table.
+pub fn create_index_statement(schema: &str, table: &str, columns: &[String]) -> String {
+    format!(
+        "CREATE INDEX CONCURRENTLY IF NOT EXISTS {} ON {}.{} ({})",
+        quote_identifier(&index_name(table, columns)),
+        quote_identifier(schema),
+        quote_identifier(table),
+        columns
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+/// The statement that drops the index on `columns` (i.e. an invalid index of a failed build),
+/// without blocking the table.
+pub fn drop_index_statement(schema: &str, table: &str, columns: &[String]) -> String {
+    format!(
+        "DROP INDEX CONCURRENTLY IF EXISTS {}.{}",
+        quote_identifier(schema),
+        quote_identifier(&index_name(table, columns)),
+    )
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn columns(columns: &[&str]) -> Vec<String> {
+        columns.iter().map(|column| (*column).to_owned()).collect()
+    }
+
+    #[test]
+    fn finds_filtered_seq_scans() {
+        let plan = json!([{
+            "Plan": {
+                "Node Type": "Limit",
+                "Plans": [{
+                    "Node Type": "Nested Loop",
+                    "Plans": [
+                        {
+                            "Node Type": "Seq Scan",
+                            "Schema": "v2_sync",
+                            "Relation Name": "packet_send_sync",
+                            "Alias": "ps",
+                            "Filter": "((ps.universal_chain_id = $1) AND (ps.height < $2))"
+                        },
+                        {
+                            "Node Type": "Index Scan",
+                            "Schema": "v2_sync",
+                            "Relation Name": "packet_recv_sync",
+                            "Index Cond": "(pr.packet_hash = ps.packet_hash)"
+                        },
+                        {
+                            "Node Type": "Seq Scan",
+                            "Schema": "hubble",
+                            "Relation Name": "chains"
+                        },
+                        {
+                            "Node Type": "Seq Scan",
+                            "Schema": "pg_catalog",
+                            "Relation Name": "pg_class",
+                            "Filter": "(relname = $3)"
+                        }
+                    ]
+                }]
+            }
+        }]);
+
+        assert_eq!(
+            filtered_seq_scans(&plan),
+            vec![FilteredSeqScan {
+                schema: "v2_sync".to_owned(),
+                table: "packet_send_sync".to_owned(),
+                filter: "((ps.universal_chain_id = $1) AND (ps.height < $2))".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn equality_columns_come_first() {
+        let table_columns = columns(&["universal_chain_id", "channel_id", "height", "timestamp"]);
+
+        assert_eq!(
+            filter_columns(
+                "((ps.height < $2) AND ((ps.universal_chain_id)::text = $1) AND (ps.channel_id = $3))",
+                &table_columns,
+            ),
+            columns(&["universal_chain_id", "channel_id", "height"])
+        );
+        assert_eq!(
+            filter_columns("(ps.timestamp >= $1)", &table_columns),
+            columns(&["timestamp"])
+        );
+        assert_eq!(
+            filter_columns("(ps.unknown = $1)", &table_columns),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn covered_by_index_prefix() {
+        let indexes = vec![columns(&["universal_chain_id", "height", "event_index"])];
+
+        assert!(is_covered(&indexes, &columns(&["universal_chain_id"])));
+        assert!(is_covered(
+            &indexes,
+            &columns(&["universal_chain_id", "height"])
+        ));
+        assert!(!is_covered(&indexes, &columns(&["height"])));
+        assert!(!is_covered(
+            &indexes,
+            &columns(&["universal_chain_id", "channel_id"])
+        ));
+    }
+
+    #[test]
+    fn create_index_statement_quotes_identifiers() {
+        assert_eq!(
+            create_index_statement(
+                "v2_sync",
+                "packet_send_sync",
+                &columns(&["universal_chain_id", "height"])
+            ),
+            r#"CREATE INDEX CONCURRENTLY IF NOT EXISTS "packet_send_sync_universal_chain_id_height_advised_idx" ON "v2_sync"."packet_send_sync" ("universal_chain_id", "height")"#
+        );
+        assert_eq!(
+            drop_index_statement(
+                "v2_sync",
+                "packet_send_sync",
+                &columns(&["universal_chain_id", "height"])
+            ),
+            r#"DROP INDEX CONCURRENTLY IF EXISTS "v2_sync"."packet_send_sync_universal_chain_id_height_advised_idx""#
+        );
+        assert_eq!(
+            index_name(
+                "a_very_long_table_name_for_testing",
+                &columns(&["some_column", "another_column"])
+            )
+            .len(),
+            MAX_IDENTIFIER_LEN
+        );
+    }
+}
@@ -0,0 +1,352 @@
+use sqlx::{Executor, PgPool, Row};
+
+use crate::index_advisor::IndexRecommendation;
+
+/// A statement of `pg_stat_statements`, with its parameters normalized to `$n`.
+#[derive(Clone, Debug)]
+pub struct SlowStatement {
+    pub queryid: i64,
+    pub query: String,
+    pub calls: i64,
+    pub mean_exec_ms: f64,
+}
+
+/// The size, columns and indexes (by their columns) of a table.
+#[derive(Clone, Debug)]
+pub struct TableInfo {
+    pub rows: i64,
+    pub columns: Vec<String>,
+    pub indexes: Vec<Vec<String>>,
+}
+
+/// An index recommendation that is not recorded yet.
+#[derive(Clone, Debug)]
+pub struct NewRecommendation<'a> {
+    pub schema_name: &'a str,
+    pub table_name: &'a str,
+    pub columns: &'a [String],
+    pub statement: &'a str,
+    pub sample: &'a SlowStatement,
+    pub table_rows: i64,
+}
+
+/// The slowest top-level `SELECT` statements of the database user of the api, by their total
+/// execution time. Requires the `pg_stat_statements` extension.
+pub async fn get_slow_statements(
+    db: &PgPool,
+    min_calls: i64,
+    min_mean_exec_ms: f64,
+    limit: i64,
+) -> sqlx::Result<Vec<SlowStatement>> {
+    // pg_stat_statements is an extension view, which is not part of the schema the query macros
+    // are checked against
+    sqlx::query(
+        r#"
+        SELECT queryid, query, calls, mean_exec_time
+        FROM pg_stat_statements
+        WHERE userid = (SELECT oid FROM pg_roles WHERE rolname = current_user)
+        AND dbid = (SELECT oid FROM pg_database WHERE datname = current_database())
+        AND toplevel
+        AND queryid IS NOT NULL
+        AND query ~* '^\s*(select|with)\s'
+        AND calls >= $1
+        AND mean_exec_time >= $2
+        ORDER BY total_exec_time DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(min_calls)
+    .bind(min_mean_exec_ms)
+    .bind(limit)
+    .map(|row: sqlx::postgres::PgRow| SlowStatement {
+        queryid: row.get("queryid"),
+        query: row.get("query"),
+        calls: row.get("calls"),
+        mean_exec_ms: row.get("mean_exec_time"),
+    })
+    .fetch_all(db)
+    .await
+}
+
+/// The generic plan of `query` as json, without running it. Requires postgres 16 or later for
+/// plans of statements with parameters.
+pub async fn explain(db: &PgPool, query: &str) -> sqlx::Result<serde_json::Value> {
+    // sent as a simple query, since the parameters of the statement are not bound
+    let row = db
+        .fetch_one(&*format!(
+            "EXPLAIN (GENERIC_PLAN, VERBOSE, FORMAT JSON) {query}"
+        ))
+        .await?;
+
+    serde_json::from_str(&row.try_get_unchecked::<String, _>(0)?)
+        .map_err(|error| sqlx::Error::Decode(error.into()))
+}
+
+/// The size, columns and indexes of `schema.table`, or `None` if the table does not exist.
+pub async fn get_table_info(
+    db: &PgPool,
+    schema: &str,
+    table: &str,
+) -> sqlx::Result<Option<TableInfo>> {
+    sqlx::query(
+        r#"
+        SELECT
+            c.reltuples::BIGINT AS table_rows,
+            ARRAY(
+                SELECT a.attname::TEXT
+                FROM pg_attribute a
+                WHERE a.attrelid = c.oid
+                AND a.attnum > 0
+                AND NOT a.attisdropped
+                ORDER BY a.attnum
+            ) AS columns,
+            ARRAY(
+                SELECT array_to_string(ARRAY(
+                    SELECT a.attname::TEXT
+                    FROM unnest(i.indkey) WITH ORDINALITY AS k(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = k.attnum
+                    ORDER BY k.ord
+                ), ',')
+                FROM pg_index i
+                WHERE i.indrelid = c.oid
+                -- invalid indexes (of failed concurrent builds) are not used by the planner
+                AND i.indisvalid
+            ) AS indexes
+        FROM pg_class c
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1
+        AND c.relname = $2
+        "#,
+    )
+    .bind(schema)
+    .bind(table)
+    .map(|row: sqlx::postgres::PgRow| TableInfo {
+        rows: row.get("table_rows"),
+        columns: row.get("columns"),
+        indexes: row
+            .get::<Vec<String>, _>("indexes")
+            .into_iter()
+            .map(|index| index.split(',').map(ToOwned::to_owned).collect())
+            .collect(),
+    })
+    .fetch_optional(db)
+    .await
+}
+
+/// Whether the index `schema.index` exists and is invalid, which is left behind by a failed
+/// `CREATE INDEX CONCURRENTLY`.
+pub async fn is_invalid_index(db: &PgPool, schema: &str, index: &str) -> sqlx::Result<bool> {
+    sqlx::query(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM pg_index i
+            JOIN pg_class c ON c.oid = i.indexrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1
+            AND c.relname = $2
+            AND NOT i.indisvalid
+        ) AS invalid
+        "#,
+    )
+    .bind(schema)
+    .bind(index)
+    .map(|row: sqlx::postgres::PgRow| row.get("invalid"))
+    .fetch_one(db)
+    .await
+}
+
+/// Records a recommendation, or refreshes the sample of a recommendation for the same columns
+/// without changing its status. Returns the id, and whether the recommendation is new.
+pub async fn upsert_recommendation(
+    db: &PgPool,
+    recommendation: &NewRecommendation<'_>,
+) -> sqlx::Result<(i64, bool)> {
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO hubble.index_recommendations (
+            schema_name,
+            table_name,
+            columns,
+            statement,
+            queryid,
+            sample_query,
+            calls,
+            mean_exec_ms,
+            table_rows
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT (schema_name, table_name, columns) DO UPDATE SET
+            queryid = excluded.queryid,
+            sample_query = excluded.sample_query,
+            calls = excluded.calls,
+            mean_exec_ms = excluded.mean_exec_ms,
+            table_rows = excluded.table_rows,
+            updated_at = now()
+        RETURNING id, (xmax = 0) AS "inserted!"
+        "#,
+        recommendation.schema_name,
+        recommendation.table_name,
+        recommendation.columns,
+        recommendation.statement,
+        recommendation.sample.queryid,
+        recommendation.sample.query,
+        recommendation.sample.calls,
+        recommendation.sample.mean_exec_ms,
+        recommendation.table_rows,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok((record.id, record.inserted))
+}
+
+/// All recommendations, optionally only those with `status`, the most expensive statements first.
+pub async fn get_recommendations(
+    db: &PgPool,
+    status: Option<&str>,
+) -> sqlx::Result<Vec<IndexRecommendation>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            id,
+            schema_name,
+            table_name,
+            columns,
+            statement,
+            sample_query,
+            calls,
+            mean_exec_ms,
+            table_rows,
+            status,
+            error,
+            created_at,
+            updated_at,
+            applied_at
+        FROM hubble.index_recommendations
+        WHERE ($1::TEXT IS NULL OR status = $1)
+        ORDER BY calls * mean_exec_ms DESC, id
+        "#,
+        status,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| IndexRecommendation {
+        id: record.id,
+        schema_name: record.schema_name,
+        table_name: record.table_name,
+        columns: record.columns,
+        statement: record.statement,
+        sample_query: record.sample_query,
+        calls: record.calls,
+        mean_exec_ms: record.mean_exec_ms,
+        table_rows: record.table_rows,
+        status: record.status,
+        error: record.error,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+        applied_at: record.applied_at,
+    })
+    .collect())
+}
+
+/// Approves a recommendation to be created in the next maintenance window. Returns whether the
+/// recommendation exists and was proposed, dismissed or failed.
+pub async fn approve_recommendation(db: &PgPool, id: i64) -> sqlx::Result<bool> {
+    Ok(sqlx::query!(
+        r#"
+        UPDATE hubble.index_recommendations
+        SET
+            status = 'approved',
+            error = NULL,
+            updated_at = now()
+        WHERE id = $1
+        AND status IN ('proposed', 'dismissed', 'failed')
+        "#,
+        id,
+    )
+    .execute(db)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+/// Dismisses a recommendation, which keeps it from being proposed again. Returns whether the
+/// recommendation exists and was proposed or approved.
+pub async fn dismiss_recommendation(db: &PgPool, id: i64) -> sqlx::Result<bool> {
+    Ok(sqlx::query!(
+        r#"
+        UPDATE hubble.index_recommendations
+        SET
+            status = 'dismissed',
+            updated_at = now()
+        WHERE id = $1
+        AND status IN ('proposed', 'approved')
+        "#,
+        id,
+    )
+    .execute(db)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+/// Claims the oldest approved recommendation by marking it as `creating`, so that concurrent
+/// advisors don't create the same index. Returns its id, schema, table and columns.
+pub async fn claim_approved_recommendation(
+    db: &PgPool,
+) -> sqlx::Result<Option<(i64, String, String, Vec<String>)>> {
+    Ok(sqlx::query!(
+        r#"
+        UPDATE hubble.index_recommendations
+        SET
+            status = 'creating',
+            updated_at = now()
+        WHERE id = (
+            SELECT id
+            FROM hubble.index_recommendations
+            WHERE status = 'approved'
+            ORDER BY id
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, schema_name, table_name, columns
+        "#,
+    )
+    .fetch_optional(db)
+    .await?
+    .map(|record| {
+        (
+            record.id,
+            record.schema_name,
+            record.table_name,
+            record.columns,
+        )
+    }))
+}
+
+/// Records the outcome of creating the index of a claimed recommendation.
+pub async fn complete_recommendation(
+    db: &PgPool,
+    id: i64,
+    error: Option<&str>,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE hubble.index_recommendations
+        SET
+            status = CASE WHEN $2::TEXT IS NULL THEN 'created' ELSE 'failed' END,
+            error = $2,
+            applied_at = CASE WHEN $2::TEXT IS NULL THEN now() END,
+            updated_at = now()
+        WHERE id = $1
+        "#,
+        id,
+        error,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
@@ -23,6 +23,7 @@ use tracing::error;
 use crate::{
     github_client::GitCommitHash,
     indexer::{
+        decoder_version::UnsupportedDecoderVersion,
         event::types::{
             self, BlockEvents, ChannelId, NatsConsumerSequence, NatsStreamSequence, PacketHash,
             UniversalChainId,
@@ -35,6 +36,8 @@ use crate::{
 pub enum IndexerError {
     #[error("received unexpected height {0}: expecting {1}")]
     UnexpectedHeightSingle(BlockHeight, BlockHeight),
+    #[error("received unexpected hash at height {0} during reorg: {1} (expecting {2})")]
+    UnexpectedHashReorg(BlockHeight, BlockHash, BlockHash),
     #[error("received unexpected height {0} (range {1}): expecting {2}")]
     UnexpectedHeightRange(BlockHeight, BlockRange, BlockHeight),
     #[error("error reading block {0} (range {1}): {2}")]
@@ -103,8 +106,8 @@ pub enum IndexerError {
     NatsMissingUniversalChainId(NatsStreamSequence, NatsConsumerSequence),
     #[error("invalid commit hash for abi: {0}")]
     InvalidCommitHashForAbi(String),
-    #[error("no abi for address: {0}")]
-    AbiNoAbiForAddress(Address),
+    #[error("{0}")]
+    UnsupportedDecoderVersion(#[from] UnsupportedDecoderVersion),
     #[error("internal error: cannot map to database domain - {0}: {1}")]
     InternalCannotMapToDatabaseDomain(String, String),
     #[error("internal error: cannot map from database domain - {0}: {1}")]
@@ -169,6 +172,36 @@ pub enum AbiParsingError {
     DecodingError(#[from] alloy::dyn_abi::Error),
 }
 
+impl IndexerError {
+    /// Whether the error is caused by the state of the database (ie. a lost connection, a
+    /// conflicting transaction or exhausted resources) instead of by the data that is handled, so
+    /// handling the same data again can succeed.
+    pub fn is_transient(&self) -> bool {
+        let IndexerError::DatabaseError(error) = self else {
+            return false;
+        };
+
+        match error {
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::Protocol(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed => true,
+            sqlx::Error::Database(error) => error.code().is_some_and(|code| {
+                // 08: connection exception, 40: transaction rollback (ie. serialization failure,
+                // deadlock), 53: insufficient resources, 57: operator intervention (ie. statement
+                // timeout, shutdown), 55P03: lock not available
+                ["08", "40", "53", "57"]
+                    .iter()
+                    .any(|class| code.starts_with(class))
+                    || code == "55P03"
+            }),
+            _ => false,
+        }
+    }
+}
+
 impl From<Report> for IndexerError {
     fn from(error: Report) -> Self {
         Self::InternalError(Box::new(error))
@@ -331,6 +364,11 @@ impl Display for BlockReference {
 #[async_trait]
 pub trait BlockHandle: Send + Sync + Sized {
     fn reference(&self) -> BlockReference;
+    /// Hash of the parent block, used to detect reorgs while running to the tip. Returns `None`
+    /// when the chain doesn't reorg (or the parent hash is not available).
+    fn parent_hash(&self) -> Option<BlockHash> {
+        None
+    }
     fn fetch_range(
         &self,
         range: BlockRange,
@@ -345,3 +383,20 @@ pub trait BlockHandle: Send + Sync + Sized {
         tx: &mut sqlx::Transaction<'_, Postgres>,
     ) -> Result<Option<BlockEvents>, IndexerError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transient_errors() {
+        assert!(IndexerError::from(sqlx::Error::PoolTimedOut).is_transient());
+        assert!(
+            IndexerError::from(sqlx::Error::Io(std::io::ErrorKind::BrokenPipe.into()))
+                .is_transient()
+        );
+
+        assert!(!IndexerError::from(sqlx::Error::RowNotFound).is_transient());
+        assert!(!IndexerError::InvalidCommitHashForAbi("abc".to_string()).is_transient());
+    }
+}
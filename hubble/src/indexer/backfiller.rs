@@ -0,0 +1,288 @@
+use std::cmp::min;
+
+use color_eyre::eyre::{bail, eyre, Report};
+use futures::future::join_all;
+use tokio::time::sleep;
+use tracing::{debug, info, info_span, trace, warn, Instrument};
+
+use super::{
+    api::{BlockRange, FetcherClient, IndexerError, IndexerId},
+    Indexer,
+};
+use crate::{
+    indexer::{
+        api::{BlockHandle, BlockSelection, FetchMode},
+        postgres::backfill_job::{
+            get_active_backfill_jobs, get_backfill_job_statuses, insert_backfill_job,
+            lock_backfill_job, update_backfill_job_cursor, BackfillJob,
+        },
+        HappyRangeFetcher,
+    },
+    metrics::{BACKFILL_BLOCKS_COLLECTOR, BACKFILL_REMAINING_BLOCKS_COLLECTOR},
+};
+
+enum BackfillerLoopResult {
+    RunAgain,
+    TryAgainLater,
+}
+
+impl<T: FetcherClient> Indexer<T> {
+    pub async fn run_backfiller(&self, fetcher_client: T) -> Result<(), IndexerError> {
+        if self.drain {
+            return Ok(());
+        }
+
+        loop {
+            match self.run_backfiller_loop(&fetcher_client).await {
+                Ok(BackfillerLoopResult::RunAgain) => {
+                    debug!("run again");
+                }
+                Ok(BackfillerLoopResult::TryAgainLater) => {
+                    debug!(
+                        "try again later (sleep {}s)",
+                        self.backfill_config.retry_later_sleep.as_secs()
+                    );
+                    sleep(self.backfill_config.retry_later_sleep).await;
+                }
+                Err(error) => {
+                    warn!(
+                        "error in backfiller loop: {error} => try again later (sleep {}s)",
+                        self.backfill_config.retry_error_sleep.as_secs()
+                    );
+                    sleep(self.backfill_config.retry_error_sleep).await;
+                }
+            }
+        }
+    }
+
+    /// Backfill the next chunk of each of the first `max_concurrent_jobs` active jobs
+    /// concurrently. Jobs are picked up again in every loop, so jobs that are scheduled while
+    /// backfilling are started as soon as a slot is available.
+    async fn run_backfiller_loop(
+        &self,
+        fetcher_client: &T,
+    ) -> Result<BackfillerLoopResult, IndexerError> {
+        let chunk_size: u64 = self.chunk_size.try_into().unwrap();
+
+        let jobs = {
+            let mut tx = self.pg_pool.begin().await?;
+            let jobs = get_active_backfill_jobs(&mut tx, &self.indexer_id).await?;
+            tx.commit().await?;
+            jobs
+        };
+
+        BACKFILL_REMAINING_BLOCKS_COLLECTOR
+            .with_label_values(&[&self.indexer_id])
+            .set(
+                jobs.iter()
+                    .map(BackfillJob::remaining)
+                    .sum::<u64>()
+                    .try_into()
+                    .unwrap_or(i64::MAX),
+            );
+
+        if jobs.is_empty() {
+            debug!("nothing scheduled to backfill => retry later");
+            return Ok(BackfillerLoopResult::TryAgainLater);
+        }
+
+        let last_finalized = match fetcher_client
+            .fetch_single(BlockSelection::LastFinalized, FetchMode::Lazy)
+            .await
+        {
+            Ok(last_finalized) => last_finalized,
+            Err(IndexerError::NoBlock(_)) => {
+                info!("no finalized block => retry later");
+                return Ok(BackfillerLoopResult::TryAgainLater);
+            }
+            Err(error) => return Err(error),
+        };
+
+        let end_until_finalized = last_finalized.reference().height + 1;
+
+        // only finalized blocks are backfilled, such that backfilled blocks never need to be
+        // checked for reorgs
+        let chunks = jobs
+            .into_iter()
+            .take(self.backfill_config.max_concurrent_jobs)
+            .filter_map(|job| {
+                let end = min(
+                    job.cursor + chunk_size,
+                    min(job.range.end_exclusive, end_until_finalized),
+                );
+
+                (job.cursor < end).then(|| {
+                    let range: BlockRange = (job.cursor..end).into();
+                    (job, range)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if chunks.is_empty() {
+            info!(
+                "nothing to backfill below finalized height {end_until_finalized} => retry later"
+            );
+            return Ok(BackfillerLoopResult::TryAgainLater);
+        }
+
+        let results = join_all(chunks.into_iter().map(|(job, range)| {
+            let span = info_span!("job", id = job.id);
+
+            self.backfill_chunk(&last_finalized, job, range)
+                .instrument(span)
+        }))
+        .await;
+
+        match results.into_iter().find_map(Result::err) {
+            Some(error) => Err(error.into()),
+            None => Ok(BackfillerLoopResult::RunAgain),
+        }
+    }
+
+    async fn backfill_chunk(
+        &self,
+        last_finalized: &T::BlockHandle,
+        job: BackfillJob,
+        range: BlockRange,
+    ) -> Result<(), Report> {
+        info!("{job}: backfilling {range}");
+
+        let _permit = self
+            .fetch_scheduler
+            .acquire(&self.universal_chain_id, &range)
+            .await?;
+
+        last_finalized
+            .fetch_range_expect_all(range.clone(), FetchMode::Eager, |block| {
+                self.backfill_block(job.id, block)
+            })
+            .instrument(info_span!("backfill"))
+            .await?;
+
+        let backfilled = job.done() + range.len();
+        let total = job.range.len();
+
+        info!(
+            "{job}: backfilled {range} ({backfilled}/{total} blocks, {:.1}%)",
+            backfilled as f64 * 100.0 / total as f64
+        );
+
+        if range.end_exclusive == job.range.end_exclusive {
+            info!("{job}: completed");
+        }
+
+        Ok(())
+    }
+
+    async fn backfill_block(&self, job_id: i64, block: T::BlockHandle) -> Result<(), Report> {
+        let reference = block.reference();
+        trace!("{reference}: backfilling");
+
+        let mut tx = self.pg_pool.begin().await?;
+
+        let Some(job) = lock_backfill_job(&mut tx, job_id).await? else {
+            warn!("backfill job {job_id} disappeared or is locked, probably due to manual action or another instance");
+            return Err(eyre!("backfill job {job_id} disappeared or is locked"));
+        };
+
+        if job.cursor != reference.height {
+            warn!(
+                "{job}: cursor does not align with the expected height {}, probably due to manual action",
+                reference.height
+            );
+            return Err(eyre!(
+                "backfill job cursor {} does not align with the expected height {}",
+                job.cursor,
+                reference.height
+            ));
+        }
+
+        if let Some(events) = block
+            .insert(&mut tx)
+            .instrument(info_span!("insert"))
+            .await?
+        {
+            self.schedule_message(&mut tx, (&reference).into(), Some(events))
+                .await?;
+        }
+
+        update_backfill_job_cursor(&mut tx, &job, reference.height + 1).await?;
+
+        tx.commit().await?;
+
+        BACKFILL_BLOCKS_COLLECTOR
+            .with_label_values(&[&self.indexer_id])
+            .inc();
+
+        trace!("{reference}: backfilled");
+
+        Ok(())
+    }
+}
+
+/// Schedule a backfill of `range` for the indexer `indexer_id`, which is picked up by the indexer
+/// while it is running (or once it is started). Fails if the range overlaps with a backfill of the
+/// indexer that is not completed yet.
+pub async fn schedule_backfill(
+    pg_pool: &sqlx::PgPool,
+    indexer_id: &IndexerId,
+    range: BlockRange,
+) -> Result<i64, Report> {
+    if range.start_inclusive >= range.end_exclusive {
+        bail!("{indexer_id}: cannot backfill the empty range {range}");
+    }
+
+    let mut tx = pg_pool.begin().await?;
+
+    match insert_backfill_job(&mut tx, indexer_id, &range).await? {
+        Ok(id) => {
+            tx.commit().await?;
+
+            info!("{indexer_id}: scheduled backfill of {range} (id: {id})");
+
+            Ok(id)
+        }
+        Err(overlapping) => bail!(
+            "{indexer_id}: {range} overlaps with active backfill jobs (ids: {})",
+            overlapping
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Log the progress of all backfill jobs, optionally only those of `indexer_id`.
+pub async fn report_backfills(
+    pg_pool: &sqlx::PgPool,
+    indexer_id: Option<&IndexerId>,
+) -> Result<(), Report> {
+    let statuses = get_backfill_job_statuses(pg_pool, indexer_id).await?;
+
+    if statuses.is_empty() {
+        info!("no backfill jobs");
+    }
+
+    for status in statuses {
+        let job = &status.job;
+
+        match status.completed_at {
+            Some(completed_at) => info!(
+                "{}: {job}: completed at {completed_at} (scheduled at {})",
+                status.indexer_id, status.created_at
+            ),
+            None => info!(
+                "{}: {job}: {}/{} blocks ({:.1}%), last progress at {} (scheduled at {})",
+                status.indexer_id,
+                job.done(),
+                job.range.len(),
+                job.done() as f64 * 100.0 / job.range.len() as f64,
+                status.updated_at,
+                status.created_at
+            ),
+        }
+    }
+
+    Ok(())
+}
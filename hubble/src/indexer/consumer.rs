@@ -1,4 +1,9 @@
-use std::{collections::HashMap, fmt, future::Future, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    time::{Duration, Instant},
+};
 
 use async_nats::jetstream::{
     self,
@@ -31,10 +36,12 @@ use crate::{
                 get_block_updates, insert_block_update, max_event_height, update_block_update,
             },
             chain_context::fetch_chain_context_for_universal_chain_id,
+            failed_event::delete_failed_events_at_height,
             lock::try_lock_block,
             replication_reset::{schedule_enrich_reset, schedule_replication_reset},
         },
         record::{
+            bulk::BulkIngest,
             change_counter::{Changes, RecordKind},
             event_handler::{delete_event_data_at_height, handle_block_events},
             ChainContext,
@@ -69,6 +76,15 @@ impl fmt::Display for BlockUpdate {
     }
 }
 
+/// Messages that are ingested in bulk in one database transaction. The messages are acked once the
+/// transaction is committed.
+struct BulkBatch {
+    tx: sqlx::Transaction<'static, sqlx::Postgres>,
+    ingest: BulkIngest,
+    messages: Vec<(jetstream::Message, MessageMeta, Bytes)>,
+    started: Instant,
+}
+
 impl<T: FetcherClient> Indexer<T> {
     pub async fn run_consumer(&self) -> Result<(), IndexerError> {
         let Some(nats) = &self.nats else {
@@ -81,8 +97,9 @@ impl<T: FetcherClient> Indexer<T> {
         let consumer = nats.create_consumer(&self.universal_chain_id).await?;
 
         debug!("consumer: {consumer:?}");
+        let mut bulk_batch = None;
         loop {
-            match self.run_consumer_loop(&consumer).await {
+            match self.run_consumer_loop(&consumer, &mut bulk_batch).await {
                 Ok(_) => {
                     debug!("run again");
                 }
@@ -91,13 +108,23 @@ impl<T: FetcherClient> Indexer<T> {
                         "error in consumer loop: {error} => try again later (sleep {}ms)",
                         self.consumer_config.retry_error_sleep.as_millis()
                     );
+
+                    // the messages that are ingested in bulk are not kept waiting while sleeping
+                    if let Err(error) = self.commit_bulk_batch(&mut bulk_batch).await {
+                        warn!("error committing bulk batch: {error}");
+                    }
+
                     sleep(self.consumer_config.retry_error_sleep).await;
                 }
             }
         }
     }
 
-    async fn run_consumer_loop(&self, consumer: &Consumer<Config>) -> Result<(), IndexerError> {
+    async fn run_consumer_loop(
+        &self,
+        consumer: &Consumer<Config>,
+        bulk_batch: &mut Option<BulkBatch>,
+    ) -> Result<(), IndexerError> {
         debug!("begin");
 
         let mut messages = consumer
@@ -110,35 +137,188 @@ impl<T: FetcherClient> Indexer<T> {
 
         while let Some(message) = messages.next().await {
             let message = message.map_err(IndexerError::NatsNextError)?;
-            consume(message, |message_sequence, payload| {
-                self.handle_message(message_sequence, payload)
-            })
-            .await?;
+
+            // ingest in bulk when far behind the tip (ie. initial backfill)
+            let message_meta = get_message_meta(&message)?;
+            let bulk = self
+                .consumer_config
+                .bulk_ingest_min_pending
+                .is_some_and(|min_pending| message_meta.pending >= min_pending);
+
+            if bulk {
+                let payload = get_message_data(&message).await?;
+                self.bulk_ingest(bulk_batch, message, message_meta, payload)
+                    .await?;
+            } else {
+                // the messages that are ingested in bulk are handled before this message
+                self.commit_bulk_batch(bulk_batch).await?;
+
+                consume(message, |message_sequence, payload| {
+                    self.handle_message(message_sequence, payload)
+                })
+                .await?;
+            }
+        }
+
+        // don't keep the messages that are ingested in bulk waiting when no messages arrive
+        if bulk_batch.as_ref().is_some_and(|bulk_batch| {
+            bulk_batch.started.elapsed() >= self.consumer_config.bulk_ingest_max_wait
+        }) {
+            self.commit_bulk_batch(bulk_batch).await?;
         }
 
         debug!("done");
         Ok(())
     }
 
+    /// Handles the message in the transaction of the bulk batch, which is committed once it
+    /// reaches `bulk_ingest_max_rows` rows or `bulk_ingest_max_wait`. If the message fails, the
+    /// batch is rolled back and its messages are retried with inserts.
+    async fn bulk_ingest(
+        &self,
+        bulk_batch: &mut Option<BulkBatch>,
+        message: jetstream::Message,
+        message_meta: MessageMeta,
+        payload: Bytes,
+    ) -> Result<(), IndexerError> {
+        if bulk_batch.is_none() {
+            debug!("begin bulk batch");
+            *bulk_batch = Some(BulkBatch {
+                tx: self.pg_pool.begin().await?,
+                ingest: BulkIngest::default(),
+                messages: vec![],
+                started: Instant::now(),
+            });
+        }
+
+        let batch = bulk_batch.as_mut().expect("bulk batch is started");
+
+        match self
+            .handle_message_in_tx(
+                &mut batch.tx,
+                &message_meta,
+                &payload,
+                Some(&mut batch.ingest),
+            )
+            .await
+        {
+            Ok(()) => {
+                batch.messages.push((message, message_meta, payload));
+
+                if batch.ingest.rows() >= self.consumer_config.bulk_ingest_max_rows
+                    || batch.started.elapsed() >= self.consumer_config.bulk_ingest_max_wait
+                {
+                    self.commit_bulk_batch(bulk_batch).await?;
+                }
+
+                Ok(())
+            }
+            Err(error) => {
+                warn!("bulk ingesting {message_meta} failed: {error} => retry batch with inserts");
+
+                let BulkBatch {
+                    tx, mut messages, ..
+                } = bulk_batch.take().expect("bulk batch is started");
+
+                if let Err(error) = tx.rollback().await {
+                    warn!("error rolling back bulk batch: {error}");
+                }
+
+                messages.push((message, message_meta, payload));
+                self.retry_with_inserts(messages).await
+            }
+        }
+    }
+
+    /// Writes the records of the messages that are ingested in bulk, commits them and acks the
+    /// messages. If committing fails, the messages are retried with inserts.
+    async fn commit_bulk_batch(
+        &self,
+        bulk_batch: &mut Option<BulkBatch>,
+    ) -> Result<(), IndexerError> {
+        let Some(BulkBatch {
+            tx,
+            ingest,
+            messages,
+            started,
+        }) = bulk_batch.take()
+        else {
+            return Ok(());
+        };
+
+        match commit_bulk_ingest(tx, ingest).await {
+            Ok(rows) => {
+                info!(
+                    "committed {} bulk ingested messages with {rows} rows (took {:.2}ms)",
+                    messages.len(),
+                    started.elapsed().as_secs_f64() * 1000.0
+                );
+
+                for (message, message_meta, _) in messages {
+                    debug!("acking {message_meta}");
+                    message.ack().await.map_err(IndexerError::NatsAckError)?;
+                }
+
+                Ok(())
+            }
+            Err(error) => {
+                warn!(
+                    "committing {} bulk ingested messages failed: {error} => retry with inserts",
+                    messages.len()
+                );
+
+                self.retry_with_inserts(messages).await
+            }
+        }
+    }
+
+    async fn retry_with_inserts(
+        &self,
+        messages: Vec<(jetstream::Message, MessageMeta, Bytes)>,
+    ) -> Result<(), IndexerError> {
+        for (message, message_meta, payload) in messages {
+            acknowledge(&message, self.handle_message(message_meta, payload).await).await?;
+        }
+
+        Ok(())
+    }
+
     async fn handle_message(
         &self,
         message_meta: MessageMeta,
         payload: Bytes,
     ) -> Result<(), IndexerError> {
-        let start_time = std::time::Instant::now();
-        debug!("begin");
         let mut tx = self.pg_pool.begin().await?;
 
+        self.handle_message_in_tx(&mut tx, &message_meta, &payload, None)
+            .await?;
+
+        debug!("commit");
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn handle_message_in_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'static, sqlx::Postgres>,
+        message_meta: &MessageMeta,
+        payload: &Bytes,
+        mut bulk_ingest: Option<&mut BulkIngest>,
+    ) -> Result<(), IndexerError> {
+        let start_time = std::time::Instant::now();
+        debug!("begin (bulk: {})", bulk_ingest.is_some());
+
         // todo: after splitting hubble load upon begin
         let chain_context =
-            fetch_chain_context_for_universal_chain_id(&mut tx, &self.universal_chain_id).await?;
+            fetch_chain_context_for_universal_chain_id(tx, &self.universal_chain_id).await?;
 
         debug!(
             "got message {message_meta} with payload size {}",
             payload.len(),
         );
 
-        let message: HubbleEvent = serde_json::from_slice(&payload)?;
+        let message: HubbleEvent = serde_json::from_slice(payload)?;
 
         info!(
             "got message ({}) {message_meta} with details {message}",
@@ -148,7 +328,7 @@ impl<T: FetcherClient> Indexer<T> {
         let block_events = message.events_by_height();
 
         let block_updates: HashMap<BlockHeight, BlockUpdate> =
-            get_block_updates(&mut tx, &message.universal_chain_id, &message.range)
+            get_block_updates(tx, &message.universal_chain_id, &message.range)
                 .await?
                 .into_iter()
                 .map(|b| (b.height, b))
@@ -161,8 +341,8 @@ impl<T: FetcherClient> Indexer<T> {
             .filter(|block_update| block_update.message_sequence < message_meta.message_sequence)
             .map(
                 |block_update| match block_events.get(&block_update.height) {
-                    Some(block_events) => Action::Update(&message_meta, block_update, block_events),
-                    None => Action::Delete(&message_meta, block_update),
+                    Some(block_events) => Action::Update(message_meta, block_update, block_events),
+                    None => Action::Delete(message_meta, block_update),
                 },
             )
             .collect_vec();
@@ -188,7 +368,7 @@ impl<T: FetcherClient> Indexer<T> {
                             nats_consumer_sequence: message_meta.nats_consumer_sequence,
                         };
 
-                        Some(Action::Insert(&message_meta, block_update, block_events))
+                        Some(Action::Insert(message_meta, block_update, block_events))
                     }
                 }
             })
@@ -204,12 +384,12 @@ impl<T: FetcherClient> Indexer<T> {
         // the message will be nacked if one of the locks fail. they'll be
         // picked up later
         for action in &actions {
-            try_lock_block(&mut tx, action.universal_chain_id(), action.height()).await?;
+            try_lock_block(tx, action.universal_chain_id(), action.height()).await?;
         }
 
         // fetch the maximum height of currently stored data. we should trigger a sync when
         // changing data at or before this height
-        let max_event_height = &max_event_height(&mut tx, &message_meta.universal_chain_id).await?;
+        let max_event_height = &max_event_height(tx, &message_meta.universal_chain_id).await?;
         debug!(
             "handling {message_meta} - actions: {} (max_event_height: {max_event_height})",
             actions.len()
@@ -230,7 +410,7 @@ impl<T: FetcherClient> Indexer<T> {
         let mut did_schedule_enrich_reset = false;
 
         for action in &actions {
-            let changes = process(&mut tx, &chain_context, action).await?;
+            let changes = process(tx, &chain_context, action, bulk_ingest.as_deref_mut()).await?;
 
             let action_height = &action.height();
             let did_change_before_or_at_latest_height = action_height <= max_event_height;
@@ -244,7 +424,7 @@ impl<T: FetcherClient> Indexer<T> {
 
             debug!("handling {action} - reset replication => {should_schedule_replication_reset} (d: {did_schedule_replication_reset}, c: {changes}, h: {action_height}, m: {max_event_height})");
             if should_schedule_replication_reset {
-                schedule_replication_reset_for_action(&mut tx, &chain_context, action).await?;
+                schedule_replication_reset_for_action(tx, &chain_context, action).await?;
 
                 did_schedule_replication_reset = true;
             }
@@ -258,16 +438,12 @@ impl<T: FetcherClient> Indexer<T> {
 
             debug!("handling {action} - reset enrich => {should_schedule_enrich_reset} (d: {did_schedule_enrich_reset}, c: {changes}, h: {action_height}, m: {max_event_height})");
             if should_schedule_enrich_reset {
-                schedule_enrich_reset_for_action(&mut tx, action, max_event_height, &changes)
-                    .await?;
+                schedule_enrich_reset_for_action(tx, action, max_event_height, &changes).await?;
 
                 did_schedule_enrich_reset = true;
             }
         }
 
-        debug!("commit");
-        tx.commit().await?;
-
         let duration = start_time.elapsed();
         info!("done (took {:.2}ms)", duration.as_secs_f64() * 1000.0);
         Ok(())
@@ -275,7 +451,7 @@ impl<T: FetcherClient> Indexer<T> {
 }
 
 // filter on changes that affect enrichment
-fn should_trigger_enrich_reset(kind: RecordKind) -> bool {
+pub(super) fn should_trigger_enrich_reset(kind: RecordKind) -> bool {
     use RecordKind::*;
     match kind {
         // ignore legacy record changes
@@ -306,10 +482,13 @@ fn should_trigger_enrich_reset(kind: RecordKind) -> bool {
         // not related to packets
         TokenBucketUpdate => false,
         WalletMutationEntry => false,
+        ContractLifecycle => false,
+        GovernanceProposal => false,
         // ignore enriched records
         PacketSendDecoded => false,
         PacketSendTransfers => false,
         PacketSendInstructionsSearch => false,
+        PacketRecvPlacement => false,
     }
 }
 
@@ -318,6 +497,7 @@ async fn process<'a>(
     tx: &mut sqlx::Transaction<'static, sqlx::Postgres>,
     chain_context: &ChainContext,
     action: &Action<'a>,
+    mut bulk: Option<&mut BulkIngest>,
 ) -> Result<Changes, IndexerError> {
     let start_time = std::time::Instant::now();
 
@@ -325,6 +505,12 @@ async fn process<'a>(
 
     let height = action.height();
 
+    // the block may still be buffered by an earlier message of the bulk batch, so the buffer is
+    // written before the block is replaced
+    if let (Action::Delete(..) | Action::Update(..), Some(bulk)) = (action, bulk.as_deref_mut()) {
+        bulk.flush(tx).await?;
+    }
+
     let result = Ok(match action {
         Action::Delete(message_meta, current) => {
             let new = BlockUpdate {
@@ -354,7 +540,7 @@ async fn process<'a>(
             update_block_update(tx, new).await?;
 
             delete_block(tx, chain_context, height).await?
-                + insert_block(tx, chain_context, block_events).await?
+                + insert_block(tx, chain_context, block_events, bulk).await?
         }
         Action::Insert(_, new, block_events) => {
             insert_block_update(tx, new).await?;
@@ -363,7 +549,7 @@ async fn process<'a>(
             // old data exists. ultimately we can generate block-update records for each known
             // block so this it not required
             delete_block(tx, chain_context, height).await?
-                + insert_block(tx, chain_context, block_events).await?
+                + insert_block(tx, chain_context, block_events, bulk).await?
         }
     });
 
@@ -385,6 +571,9 @@ async fn delete_block(
     chain_context: &ChainContext,
     height: BlockHeight,
 ) -> Result<Changes, IndexerError> {
+    // failed events of the replaced block are obsolete
+    delete_failed_events_at_height(tx, chain_context.internal_chain_id, height).await?;
+
     delete_event_data_at_height(tx, chain_context.internal_chain_id, height).await
 }
 
@@ -392,8 +581,9 @@ async fn insert_block(
     tx: &mut sqlx::Transaction<'static, sqlx::Postgres>,
     chain_context: &ChainContext,
     block_events: &[&SupportedBlockEvent],
+    bulk: Option<&mut BulkIngest>,
 ) -> Result<Changes, IndexerError> {
-    handle_block_events(tx, chain_context, block_events).await
+    handle_block_events(tx, chain_context, block_events, bulk).await
 }
 
 async fn schedule_replication_reset_for_action<'a>(
@@ -494,7 +684,14 @@ where
     let message_data = get_message_data(&message).await?;
     trace!("consume - data: {} byte (decoded)", message_data.len());
 
-    match handler(message_meta, message_data).await {
+    acknowledge(&message, handler(message_meta, message_data).await).await
+}
+
+async fn acknowledge(
+    message: &async_nats::jetstream::Message,
+    result: Result<(), IndexerError>,
+) -> Result<(), IndexerError> {
+    match result {
         Ok(_) => {
             debug!("acking");
             message.ack().await.map_err(IndexerError::NatsAckError)?;
@@ -511,6 +708,18 @@ where
     Ok(())
 }
 
+async fn commit_bulk_ingest(
+    mut tx: sqlx::Transaction<'static, sqlx::Postgres>,
+    mut ingest: BulkIngest,
+) -> Result<u64, IndexerError> {
+    let rows = ingest.flush(&mut tx).await?;
+
+    debug!("commit");
+    tx.commit().await?;
+
+    Ok(rows)
+}
+
 async fn get_message_data(message: &async_nats::jetstream::Message) -> Result<Bytes, IndexerError> {
     let payload = &message.payload;
 
@@ -539,12 +748,13 @@ async fn get_message_data(message: &async_nats::jetstream::Message) -> Result<By
 }
 
 fn get_message_meta(message: &async_nats::jetstream::Message) -> Result<MessageMeta, IndexerError> {
-    let (nats_stream_sequence, nats_consumer_sequence) = message
+    let (nats_stream_sequence, nats_consumer_sequence, pending) = message
         .info()
         .map(|meta| {
             (
                 NatsStreamSequence::from(meta.stream_sequence),
                 NatsConsumerSequence::from(meta.consumer_sequence),
+                meta.pending,
             )
         })
         .map_err(IndexerError::NatsMetaError)?;
@@ -599,6 +809,7 @@ fn get_message_meta(message: &async_nats::jetstream::Message) -> Result<MessageM
             message_hash,
             nats_stream_sequence,
             nats_consumer_sequence,
+            pending,
         })
     } else {
         Err(IndexerError::NatsMissingMessageHeaders(
@@ -0,0 +1,152 @@
+//! Contracts registered from the config of an indexer.
+//!
+//! The contracts of a chain are registered per height range in `v2_evm.contracts` and
+//! `v2_cosmos.contracts`, and only the events of the contracts registered at the height of a block
+//! are indexed. Besides registering them in the database, contracts can be listed in the
+//! `contracts` section of the config of an indexer, which registers them when the indexer starts.
+//! A contract that is upgraded or redeployed at a new address is registered with a `to_height`,
+//! and its successor with the next `from_height`, so the history of the old contract stays valid
+//! and does not have to be re-indexed.
+
+use color_eyre::eyre::{eyre, Report};
+use itertools::Itertools;
+
+use crate::{
+    github_client::GitCommitHash,
+    indexer::{api::BlockHeight, decoder_version::DecoderVersion},
+};
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContractRegistration {
+    /// The flow of a cosmwasm contract (e.g. `ibc` or `cw20`), or the description of an evm
+    /// contract (e.g. `core` or `app/ucs03`).
+    pub contract_type: String,
+    pub address: String,
+    /// First height at which the events of the contract are indexed.
+    pub from_height: BlockHeight,
+    /// Last height (inclusive) at which the events of the contract are indexed, all later heights
+    /// if not set.
+    #[serde(default)]
+    pub to_height: Option<BlockHeight>,
+    /// Commit of the deployment of an evm contract, from which its abi is generated. Required for
+    /// evm contracts, whose logs are only fetched with an abi. Not supported for cosmwasm
+    /// contracts.
+    #[serde(default)]
+    pub commit: Option<GitCommitHash>,
+    /// Format of the events emitted by the contract in this height range.
+    #[serde(default)]
+    pub decoder_version: DecoderVersion,
+}
+
+impl ContractRegistration {
+    /// The last height at which the contract is registered, as stored in `end_height`.
+    pub fn end_height(&self) -> BlockHeight {
+        self.to_height.unwrap_or(i64::MAX as BlockHeight)
+    }
+}
+
+/// The start heights of the registrations of `address`, which replace the stored registrations with
+/// the same start height.
+pub fn replaced_start_heights(contracts: &[ContractRegistration], address: &str) -> Vec<i64> {
+    contracts
+        .iter()
+        .filter(|contract| contract.address == address)
+        .map(|contract| contract.from_height.try_into().unwrap())
+        .collect()
+}
+
+/// The error of a registration that overlaps a stored registration of the same address that is not
+/// replaced by the config (i.e. an open-ended registration from before an upgrade). The stored
+/// registration is closed by adding it to the config with a `to_height`.
+pub fn overlaps_stored_registration(
+    contract: &ContractRegistration,
+    stored_start_height: i64,
+    stored_end_height: i64,
+) -> Report {
+    eyre!(
+        "contract {}: registration from height {} overlaps the stored registration from height {} to {}, add the stored registration to the config with a to_height to close it",
+        contract.address,
+        contract.from_height,
+        stored_start_height,
+        stored_end_height
+    )
+}
+
+/// Checks that the height ranges of the registrations are not empty, and that registrations of the
+/// same address don't overlap.
+pub fn validate_contracts(contracts: &[ContractRegistration]) -> Result<(), Report> {
+    for contract in contracts {
+        if contract.end_height() < contract.from_height {
+            return Err(eyre!(
+                "contract {}: to_height ({}) is before from_height ({})",
+                contract.address,
+                contract.end_height(),
+                contract.from_height
+            ));
+        }
+    }
+
+    for (address, registrations) in &contracts
+        .iter()
+        .sorted_by_key(|contract| (&contract.address, contract.from_height))
+        .chunk_by(|contract| &contract.address)
+    {
+        for (previous, next) in registrations.tuple_windows() {
+            if next.from_height <= previous.end_height() {
+                return Err(eyre!(
+                    "contract {address}: registration from height {} overlaps the registration from height {}",
+                    next.from_height,
+                    previous.from_height
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(address: &str, from_height: u64, to_height: Option<u64>) -> ContractRegistration {
+        ContractRegistration {
+            contract_type: "ibc".to_string(),
+            address: address.to_string(),
+            from_height,
+            to_height,
+            commit: None,
+            decoder_version: DecoderVersion::V1,
+        }
+    }
+
+    #[test]
+    fn redeployments_are_valid() {
+        assert!(validate_contracts(&[
+            contract("union1new", 501, None),
+            contract("union1old", 100, Some(500)),
+            contract("union1old", 700, Some(700)),
+        ])
+        .is_ok());
+    }
+
+    #[test]
+    fn overlapping_registrations_are_invalid() {
+        assert!(validate_contracts(&[
+            contract("union1old", 100, None),
+            contract("union1old", 500, Some(600)),
+        ])
+        .is_err());
+        assert!(validate_contracts(&[
+            contract("union1old", 100, Some(500)),
+            contract("union1old", 500, None),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn empty_ranges_are_invalid() {
+        assert!(validate_contracts(&[contract("union1old", 100, Some(99))]).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! Versions of the formats of the events emitted by registered contracts.
+//!
+//! Contract registrations (`v2_evm.contracts` and `v2_cosmos.contracts`) are tracked per height
+//! range, each with the `decoder_version` of the events emitted by the contract in that range. When
+//! a contract upgrade changes the format of its events, the registration is split at the upgrade
+//! height and the new range is registered with the new version. Events are decoded with the version
+//! registered at the height of their block, so replays of historical blocks decode with the format
+//! of the time.
+//!
+//! Events of all versions decode into the same event types, tagged with the version they were
+//! decoded with in their [`Header`](crate::indexer::event::header::Header). The tag is stored on
+//! the records of the events (`decoder_version`), so rows that were indexed with an older format
+//! can be told apart (and re-indexed selectively). Rows indexed before the tag existed are
+//! retrofitted with `hubble migrate decoder-versions`, using the contract registrations.
+
+use std::fmt::{self, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(into = "i32", try_from = "i32")]
+pub enum DecoderVersion {
+    /// The event formats of the ibc-union contracts as initially deployed.
+    #[default]
+    V1,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unsupported decoder version: {0}")]
+pub struct UnsupportedDecoderVersion(pub i32);
+
+impl TryFrom<i32> for DecoderVersion {
+    type Error = UnsupportedDecoderVersion;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::V1),
+            version => Err(UnsupportedDecoderVersion(version)),
+        }
+    }
+}
+
+impl From<DecoderVersion> for i32 {
+    fn from(value: DecoderVersion) -> Self {
+        match value {
+            DecoderVersion::V1 => 1,
+        }
+    }
+}
+
+impl DecoderVersion {
+    pub fn is_v1(&self) -> bool {
+        *self == Self::V1
+    }
+}
+
+/// The `decoder_version` of a stored row. Rows indexed before records were tagged with it are v1
+/// until they are migrated.
+pub fn stored_decoder_version(decoder_version: Option<i32>) -> i32 {
+    decoder_version.unwrap_or_else(|| DecoderVersion::V1.into())
+}
+
+impl Display for DecoderVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V1 => write!(f, "v1"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from() {
+        assert_eq!(DecoderVersion::try_from(1).unwrap(), DecoderVersion::V1);
+        assert!(DecoderVersion::try_from(0).is_err());
+        assert!(DecoderVersion::try_from(2).is_err());
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        assert_eq!(serde_json::to_string(&DecoderVersion::V1).unwrap(), "1");
+        assert_eq!(
+            serde_json::from_str::<DecoderVersion>("1").unwrap(),
+            DecoderVersion::V1
+        );
+        assert!(serde_json::from_str::<DecoderVersion>("2").is_err());
+    }
+
+    #[test]
+    fn test_stored_decoder_version() {
+        assert_eq!(stored_decoder_version(None), 1);
+        assert_eq!(stored_decoder_version(Some(1)), 1);
+        // versions written by a newer hubble are kept as stored
+        assert_eq!(stored_decoder_version(Some(2)), 2);
+    }
+}
@@ -0,0 +1,160 @@
+use tracing::{debug, info};
+
+use crate::indexer::{
+    api::IndexerError,
+    event::types::BlockHeight,
+    record::{InternalChainId, PgValue},
+};
+
+/// The tables of the records mapped directly from events, which are tagged with the
+/// `decoder_version` of their event.
+const VERSIONED_TABLES: &[&str] = &[
+    "v2_sync.channel_open_ack_sync",
+    "v2_sync.channel_open_confirm_sync",
+    "v2_sync.channel_open_init_sync",
+    "v2_sync.channel_open_try_sync",
+    "v2_sync.connection_open_ack_sync",
+    "v2_sync.connection_open_confirm_sync",
+    "v2_sync.connection_open_init_sync",
+    "v2_sync.connection_open_try_sync",
+    "v2_sync.contract_lifecycle_sync",
+    "v2_sync.create_client_sync",
+    "v2_sync.create_lens_client_sync",
+    "v2_sync.governance_proposal_sync",
+    "v2_sync.packet_ack_sync",
+    "v2_sync.packet_recv_sync",
+    "v2_sync.packet_send_sync",
+    "v2_sync.packet_timeout_sync",
+    "v2_sync.token_bucket_update_sync",
+    "v2_sync.wallet_mutation_entry_sync",
+    "v2_sync.write_ack_sync",
+];
+
+/// Sets the `decoder_version` of rows that were indexed before records were tagged with it, to the
+/// version of the contracts that emitted the (stored) events of the transaction of the row at its
+/// height. Rows without stored events take the version of the contracts registered for their chain
+/// at their height (v1 if there are none). Rows whose version is ambiguous (contracts of different
+/// versions) are left untagged, so they can be re-indexed. Heights are processed in batches of
+/// `batch_size`, each in its own transaction, so an interrupted migration can be resumed from the
+/// last reported height.
+pub async fn retrofit_decoder_versions(
+    db: &sqlx::PgPool,
+    internal_chain_id: Option<InternalChainId>,
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+    batch_size: u64,
+) -> Result<u64, IndexerError> {
+    info!("retrofit decoder versions: {from_height}..{to_height} (batch size: {batch_size})");
+
+    let mut updated = 0;
+    let mut batch_from = from_height;
+
+    while batch_from < to_height {
+        let batch_to = BlockHeight(batch_from.0.saturating_add(batch_size).min(to_height.0));
+
+        let mut tx = db.begin().await?;
+        let mut batch_updated = 0;
+        for table in VERSIONED_TABLES {
+            let table_updated = retrofit_decoder_versions_batch(
+                &mut tx,
+                table,
+                internal_chain_id,
+                batch_from,
+                batch_to,
+            )
+            .await?;
+
+            debug!("retrofit decoder versions: {table} {batch_from}..{batch_to} => updated {table_updated}");
+
+            batch_updated += table_updated;
+        }
+        tx.commit().await?;
+
+        info!("retrofit decoder versions: {batch_from}..{batch_to} => updated {batch_updated}");
+
+        updated += batch_updated;
+        batch_from = batch_to;
+    }
+
+    info!("retrofit decoder versions: {from_height}..{to_height} => done (updated {updated})");
+
+    Ok(updated)
+}
+
+async fn retrofit_decoder_versions_batch(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    table: &str,
+    internal_chain_id: Option<InternalChainId>,
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+) -> Result<u64, IndexerError> {
+    // the table cannot be a parameter, hence no `query!`
+    let result = sqlx::query(&format!(
+        r#"
+        UPDATE {table} t
+        SET decoder_version = v.decoder_version
+        FROM (
+            SELECT
+                r.ctid AS row_id,
+                COALESCE(
+                    -- the contracts that emitted the events of the transaction of the row
+                    (
+                        SELECT CASE WHEN min(c.decoder_version) = max(c.decoder_version) THEN min(c.decoder_version) END
+                        FROM v2_evm.logs l
+                        CROSS JOIN LATERAL jsonb_array_elements(l.data->'transactions') tx
+                        CROSS JOIN LATERAL jsonb_array_elements(tx->'events') e
+                        JOIN v2_evm.contracts c
+                        ON c.internal_chain_id = l.internal_chain_id
+                        AND lower(c.address) = lower(e->'data'->>'address')
+                        AND l.height BETWEEN c.start_height AND c.end_height
+                        WHERE l.internal_chain_id = r.internal_chain_id
+                        AND l.height = r.height
+                        AND lower(regexp_replace(tx->>'hash', '^0x', '')) = encode(r.transaction_hash, 'hex')
+                    ),
+                    (
+                        SELECT CASE WHEN min(c.decoder_version) = max(c.decoder_version) THEN min(c.decoder_version) END
+                        FROM v2_cosmos.events e
+                        JOIN v2_cosmos.contracts c
+                        ON c.internal_chain_id = e.internal_chain_id
+                        AND e.data->'attributes' @> jsonb_build_array(jsonb_build_object('key', '_contract_address', 'value', c.address))
+                        AND e.height BETWEEN c.start_height AND c.end_height
+                        WHERE e.internal_chain_id = r.internal_chain_id
+                        AND e.height = r.height
+                        AND lower(regexp_replace(e.transaction_hash, '^0x', '')) = encode(r.transaction_hash, 'hex')
+                    ),
+                    -- without the events of the transaction, the contracts of the chain, if they agree
+                    (
+                        SELECT CASE
+                            WHEN count(*) = 0 THEN 1
+                            WHEN min(c.decoder_version) = max(c.decoder_version) THEN min(c.decoder_version)
+                        END
+                        FROM (
+                            SELECT decoder_version
+                            FROM v2_evm.contracts
+                            WHERE internal_chain_id = r.internal_chain_id
+                            AND r.height BETWEEN start_height AND end_height
+                            UNION ALL
+                            SELECT decoder_version
+                            FROM v2_cosmos.contracts
+                            WHERE internal_chain_id = r.internal_chain_id
+                            AND r.height BETWEEN start_height AND end_height
+                        ) c
+                    )
+                ) AS decoder_version
+            FROM {table} r
+            WHERE r.decoder_version IS NULL
+            AND r.height >= $1 AND r.height < $2
+            AND ($3::INT4 IS NULL OR r.internal_chain_id = $3)
+        ) v
+        WHERE t.ctid = v.row_id
+        AND v.decoder_version IS NOT NULL
+        "#
+    ))
+    .bind(from_height.pg_value()?)
+    .bind(to_height.pg_value()?)
+    .bind(internal_chain_id.map(|id| id.pg_value()).transpose()?)
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(result.rows_affected())
+}
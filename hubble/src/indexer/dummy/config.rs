@@ -3,8 +3,9 @@ use sqlx::PgPool;
 
 use super::dummy::{DummyContext, DummyFetcherClient};
 use crate::indexer::{
-    api::IndexerId, event::types::UniversalChainId, nats::NatsConnection, ConsumerConfig,
-    EnricherConfig, FinalizerConfig, FixerConfig, Indexer, PublisherConfig,
+    api::IndexerId, event::types::UniversalChainId, nats::NatsConnection,
+    scheduler::FetchScheduler, BackfillConfig, ConsumerConfig, EnricherConfig, FinalizerConfig,
+    FixerConfig, Indexer, PublisherConfig, RetrierConfig,
 };
 
 #[derive(Clone, Debug, serde::Deserialize)]
@@ -14,10 +15,14 @@ pub struct Config {
     pub start_height: u64,
     pub finalizer: FinalizerConfig,
     pub fixer: FixerConfig,
+    pub backfill: BackfillConfig,
     pub publisher: PublisherConfig,
     pub consumer: ConsumerConfig,
     pub enricher: EnricherConfig,
+    pub retrier: RetrierConfig,
     pub drain: bool,
+    pub database: Option<String>,
+    pub max_connections: Option<u32>,
 }
 
 impl Config {
@@ -25,6 +30,7 @@ impl Config {
         self,
         pg_pool: PgPool,
         nats: Option<NatsConnection>,
+        fetch_scheduler: FetchScheduler,
     ) -> Result<Indexer<DummyFetcherClient>, Report> {
         Ok(Indexer::new(
             pg_pool,
@@ -35,9 +41,12 @@ impl Config {
             5,
             self.finalizer,
             self.fixer,
+            self.backfill,
             self.publisher,
             self.consumer,
             self.enricher,
+            self.retrier,
+            fetch_scheduler,
             DummyContext { bla: 42 },
             self.drain,
         ))
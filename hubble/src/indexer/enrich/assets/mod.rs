@@ -0,0 +1,123 @@
+//! Canonical asset ids, which group the same economic asset across chains and wrap levels (e.g.
+//! usdc on ethereum and the usdc wrapped from it on other chains).
+//!
+//! Tokens are mapped to assets in `token.asset_tokens`. The enricher follows the token graph: when
+//! a transfer wraps or unwraps a token, the wrapped token is mapped to the asset of the token it
+//! wraps. Tokens that are not wrapped themselves get an asset id derived from their chain and denom.
+//! Operators can override the asset of a token by inserting or updating its mapping with source
+//! `manual`; manual mappings are never changed by the enricher.
+
+use std::fmt::Display;
+
+use tracing::debug;
+
+use crate::indexer::{
+    api::IndexerError,
+    enrich::assets::postgres::{get_asset_id, insert_asset_token},
+    event::types::{Denom, UniversalChainId},
+    handler::types::{ChannelMetaData, WrapDirection},
+    record::InternalChainId,
+};
+
+mod postgres;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetId(pub String);
+
+impl AssetId {
+    /// The asset id of a token that is not wrapped: `<universal chain id>/<0x-prefixed denom>`.
+    fn origin(universal_chain_id: &UniversalChainId, denom: &Denom) -> Self {
+        Self(format!(
+            "{}/0x{}",
+            universal_chain_id.0,
+            hex::encode(&denom.0)
+        ))
+    }
+}
+
+impl Display for AssetId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A token on a chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token<'a> {
+    internal_chain_id: InternalChainId,
+    universal_chain_id: &'a UniversalChainId,
+    denom: &'a Denom,
+}
+
+/// The token that is wrapped by a transfer and the wrapped token, or `None` if the transfer does
+/// not (un)wrap (i.e. it is a swap).
+fn wrap_link<'a>(
+    channel: &'a ChannelMetaData,
+    base_token: &'a Denom,
+    quote_token: &'a Denom,
+    wrap_direction: &Option<WrapDirection>,
+) -> Option<(Token<'a>, Token<'a>)> {
+    let source = Token {
+        internal_chain_id: channel.internal_chain_id,
+        universal_chain_id: &channel.universal_chain_id,
+        denom: base_token,
+    };
+    let destination = Token {
+        internal_chain_id: channel.internal_counterparty_chain_id,
+        universal_chain_id: &channel.universal_counterparty_chain_id,
+        denom: quote_token,
+    };
+
+    match wrap_direction {
+        Some(WrapDirection::Wrapping) => Some((source, destination)),
+        Some(WrapDirection::Unwrapping) => Some((destination, source)),
+        None => None,
+    }
+}
+
+/// Maps the tokens of a transfer that wraps or unwraps to the asset of the wrapped token, assigning
+/// a new asset id if the wrapped token has none yet.
+pub async fn assign_asset(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    channel: &ChannelMetaData,
+    base_token: &Denom,
+    quote_token: &Denom,
+    wrap_direction: &Option<WrapDirection>,
+) -> Result<(), IndexerError> {
+    let Some((origin, wrapped)) = wrap_link(channel, base_token, quote_token, wrap_direction)
+    else {
+        return Ok(());
+    };
+
+    let asset_id = match get_asset_id(tx, &origin.internal_chain_id, origin.denom).await? {
+        Some(asset_id) => asset_id,
+        None => {
+            let asset_id = AssetId::origin(origin.universal_chain_id, origin.denom);
+            debug!("new asset {asset_id}");
+
+            insert_asset_token(tx, &origin.internal_chain_id, origin.denom, &asset_id).await?;
+
+            asset_id
+        }
+    };
+
+    insert_asset_token(tx, &wrapped.internal_chain_id, wrapped.denom, &asset_id).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origin_asset_id() {
+        assert_eq!(
+            AssetId::origin(
+                &UniversalChainId("ethereum.1".to_string()),
+                &bytes::Bytes::from_static(&[0xa0, 0xb8, 0x69, 0x91]).into(),
+            ),
+            AssetId("ethereum.1/0xa0b86991".to_string())
+        );
+    }
+}
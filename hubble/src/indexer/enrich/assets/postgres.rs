@@ -0,0 +1,54 @@
+use tracing::trace;
+
+use crate::indexer::{
+    api::IndexerError,
+    enrich::assets::AssetId,
+    event::types::Denom,
+    record::{InternalChainId, PgValue},
+};
+
+pub async fn get_asset_id(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    internal_chain_id: &InternalChainId,
+    denom: &Denom,
+) -> Result<Option<AssetId>, IndexerError> {
+    trace!("get_asset_id: {internal_chain_id}");
+
+    Ok(sqlx::query!(
+        "
+        SELECT asset_id
+        FROM token.asset_tokens
+        WHERE internal_chain_id = $1 AND denom = $2
+        ",
+        internal_chain_id.pg_value()?,
+        denom.pg_value()?,
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .map(|record| AssetId(record.asset_id)))
+}
+
+/// Maps a token to an asset, unless the token is already mapped (by the enricher or manually).
+pub async fn insert_asset_token(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    internal_chain_id: &InternalChainId,
+    denom: &Denom,
+    asset_id: &AssetId,
+) -> Result<(), IndexerError> {
+    trace!("insert_asset_token: {internal_chain_id} => {asset_id}");
+
+    sqlx::query!(
+        "
+        INSERT INTO token.asset_tokens (internal_chain_id, denom, asset_id, source)
+        VALUES ($1, $2, $3, 'graph')
+        ON CONFLICT (internal_chain_id, denom) DO NOTHING
+        ",
+        internal_chain_id.pg_value()?,
+        denom.pg_value()?,
+        asset_id.0,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use tracing::{debug, info};
+
+use crate::indexer::{
+    api::IndexerError,
+    enrich::wrapping::{wrap_direction_chains, IntermediateChannelIds},
+    event::types::BlockHeight,
+    record::{InternalChainId, PgValue},
+};
+
+/// Recalculates the wrap direction of transfers that were indexed before wrapped-token
+/// enrichment existed. Heights are processed in batches of `batch_size`, each in its own
+/// transaction, so an interrupted backfill can be resumed from the last reported height.
+pub async fn backfill_wrap_direction(
+    db: &sqlx::PgPool,
+    internal_chain_id: Option<InternalChainId>,
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+    batch_size: u64,
+) -> Result<u64, IndexerError> {
+    info!("backfill wrap direction: {from_height}..{to_height} (batch size: {batch_size})");
+
+    let mut updated = 0;
+    let mut batch_from = from_height;
+
+    while batch_from < to_height {
+        let batch_to = BlockHeight(batch_from.0.saturating_add(batch_size).min(to_height.0));
+
+        let mut tx = db.begin().await?;
+        let batch_updated =
+            backfill_wrap_direction_batch(&mut tx, internal_chain_id, batch_from, batch_to).await?;
+        tx.commit().await?;
+
+        info!("backfill wrap direction: {batch_from}..{batch_to} => updated {batch_updated}");
+
+        updated += batch_updated;
+        batch_from = batch_to;
+    }
+
+    info!("backfill wrap direction: {from_height}..{to_height} => done (updated {updated})");
+
+    Ok(updated)
+}
+
+async fn backfill_wrap_direction_batch(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    internal_chain_id: Option<InternalChainId>,
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+) -> Result<u64, IndexerError> {
+    let transfers = sqlx::query!(
+        r#"
+        SELECT internal_chain_id, internal_counterparty_chain_id, source_channel_id, destination_channel_id, packet_hash, transfer_index, base_token, quote_token
+        FROM v2_sync.packet_send_transfers_sync
+        WHERE wrap_direction IS NULL
+        AND height >= $1 AND height < $2
+        AND ($3::INT4 IS NULL OR internal_chain_id = $3)
+        "#,
+        from_height.pg_value()?,
+        to_height.pg_value()?,
+        internal_chain_id.map(|id| id.pg_value()).transpose()?,
+    )
+    .fetch_all(tx.as_mut())
+    .await?;
+
+    let mut updated = 0;
+
+    for transfer in transfers {
+        let wrap_direction = wrap_direction_chains(
+            tx,
+            &transfer.internal_chain_id.into(),
+            &transfer.internal_counterparty_chain_id.into(),
+            &IntermediateChannelIds::default(), // no support for intermediate channel ids.
+            &transfer.source_channel_id.try_into()?,
+            &transfer.destination_channel_id.try_into()?,
+            &bytes::Bytes::from(transfer.base_token).into(),
+            &bytes::Bytes::from(transfer.quote_token).into(),
+            None, // only v0 and v1 orders are enriched, which carry no metadata.
+        )
+        .await?;
+
+        let Some(wrap_direction) = wrap_direction else {
+            continue;
+        };
+
+        debug!(
+            "backfill wrap direction: {}/{} => {wrap_direction:?}",
+            hex::encode(&transfer.packet_hash),
+            transfer.transfer_index,
+        );
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE v2_sync.packet_send_transfers_sync
+            SET wrap_direction = $3
+            WHERE packet_hash = $1 AND transfer_index = $2
+            "#,
+            &transfer.packet_hash[..],
+            transfer.transfer_index,
+            wrap_direction.pg_value()?,
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        updated += result.rows_affected();
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,167 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::indexer::{
+    api::IndexerError,
+    event::types::TransactionPlacement,
+    record::{
+        change_counter::Changes, packet_recv_placement_record::PacketRecvPlacementRecord,
+        packet_recv_record::PacketRecvRecord, Record,
+    },
+};
+
+/// Known mev patterns around a transaction, detected from its direct neighbors in the block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MevPattern {
+    /// The transactions before and after were sent by the same account, which is not the sender
+    /// of the transaction itself.
+    Sandwich,
+    /// The transaction before targets the same contract from another sender with a higher
+    /// priority fee, i.e. a competing submission that outbid the transaction.
+    Frontrun,
+    /// The transaction is the first in its block, which usually means it was included through a
+    /// bundle or private orderflow.
+    TopOfBlock,
+}
+
+impl Display for MevPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MevPattern::Sandwich => "sandwich",
+            MevPattern::Frontrun => "frontrun",
+            MevPattern::TopOfBlock => "top_of_block",
+        })
+    }
+}
+
+pub fn detect_mev_patterns(placement: &TransactionPlacement) -> Vec<MevPattern> {
+    let mut patterns = vec![];
+
+    if let (Some(previous), Some(next)) = (&placement.previous, &placement.next) {
+        if previous.transaction_sender == next.transaction_sender
+            && previous.transaction_sender != placement.transaction_sender
+        {
+            patterns.push(MevPattern::Sandwich);
+        }
+    }
+
+    if let Some(previous) = &placement.previous {
+        if previous.transaction_sender != placement.transaction_sender
+            && previous.to.is_some()
+            && previous.to == placement.to
+            && previous.priority_fee.0 > placement.priority_fee.0
+        {
+            patterns.push(MevPattern::Frontrun);
+        }
+    }
+
+    if placement.previous.is_none() {
+        patterns.push(MevPattern::TopOfBlock);
+    }
+
+    patterns
+}
+
+/// Records the placement of a recv transaction in its block, tagged with the mev patterns around
+/// it.
+pub async fn enrich_packet_recv(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    record: &PacketRecvRecord,
+    placement: &TransactionPlacement,
+) -> Result<Changes, IndexerError> {
+    let patterns = detect_mev_patterns(placement);
+
+    let placement_record: PacketRecvPlacementRecord = (record, placement, &patterns).try_into()?;
+
+    placement_record.upsert(tx).await
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::indexer::event::types::{
+        ContractAddress, NeighborTransaction, PriorityFee, TransactionSender,
+    };
+
+    fn sender(id: u8) -> TransactionSender {
+        TransactionSender(Bytes::from(vec![id; 20]))
+    }
+
+    fn contract(id: u8) -> Option<ContractAddress> {
+        Some(ContractAddress(Bytes::from(vec![id; 20])))
+    }
+
+    fn neighbor(
+        sender_id: u8,
+        to: Option<ContractAddress>,
+        priority_fee: u128,
+    ) -> NeighborTransaction {
+        NeighborTransaction {
+            transaction_hash: Bytes::from(vec![sender_id; 32]).into(),
+            transaction_sender: sender(sender_id),
+            to,
+            priority_fee: PriorityFee(priority_fee),
+        }
+    }
+
+    fn placement(
+        previous: Option<NeighborTransaction>,
+        next: Option<NeighborTransaction>,
+    ) -> TransactionPlacement {
+        TransactionPlacement {
+            block_transaction_count: 3,
+            transaction_sender: sender(1),
+            to: contract(100),
+            priority_fee: PriorityFee(10),
+            previous,
+            next,
+        }
+    }
+
+    #[test]
+    fn test_sandwich() {
+        let placement = placement(
+            Some(neighbor(2, contract(200), 50)),
+            Some(neighbor(2, contract(200), 0)),
+        );
+
+        assert_eq!(detect_mev_patterns(&placement), vec![MevPattern::Sandwich]);
+    }
+
+    #[test]
+    fn test_own_neighbors_are_not_a_sandwich() {
+        let placement = placement(
+            Some(neighbor(1, contract(100), 10)),
+            Some(neighbor(1, contract(100), 10)),
+        );
+
+        assert!(detect_mev_patterns(&placement).is_empty());
+    }
+
+    #[test]
+    fn test_frontrun() {
+        let outbid = placement(
+            Some(neighbor(2, contract(100), 20)),
+            Some(neighbor(3, contract(200), 0)),
+        );
+
+        assert_eq!(detect_mev_patterns(&outbid), vec![MevPattern::Frontrun]);
+
+        // a lower priority fee is ordinary ordering, not an outbid submission
+        let ordered = placement(Some(neighbor(2, contract(100), 5)), None);
+
+        assert!(detect_mev_patterns(&ordered).is_empty());
+    }
+
+    #[test]
+    fn test_top_of_block() {
+        let placement = placement(None, Some(neighbor(2, contract(200), 0)));
+
+        assert_eq!(
+            detect_mev_patterns(&placement),
+            vec![MevPattern::TopOfBlock]
+        );
+        assert_eq!(MevPattern::TopOfBlock.to_string(), "top_of_block");
+    }
+}
@@ -3,6 +3,10 @@ use serde_json::{Map, Value};
 use time::{macros::format_description, UtcOffset};
 use tracing::{debug, error, warn};
 
+mod assets;
+pub mod backfill;
+pub mod mev;
+pub mod pricing;
 mod ucs03_zkgm_0;
 mod wrapping;
 
@@ -10,13 +14,13 @@ use crate::indexer::{
     api::IndexerError,
     enrich::{
         ucs03_zkgm_0::{packet_ack::decode, PacketHash},
-        wrapping::{wrap_direction_chains, IntermediateChannelIds},
+        wrapping::{wrap_direction_chains, IntermediateChannelIds, MetadataImage},
     },
     event::types::{BlockHeight, ChannelId, UniversalChainId},
     handler::types::{
         string_0x_to_bytes, AddressCanonical, AddressZkgm, Amount, ChannelMetaData, Fee,
         Instruction, InstructionHash, InstructionOpcode, InstructionPath, InstructionRootPath,
-        InstructionRootSalt, InstructionVersion, PacketShape, Transfer,
+        InstructionRootSalt, InstructionVersion, PacketShape, Transfer, TransferIndex,
     },
     postgres::chain_context::fetch_chain_context_for_universal_chain_id,
     record::{
@@ -24,7 +28,7 @@ use crate::indexer::{
         packet_send_decoded_record::PacketSendDecodedRecord,
         packet_send_instructions_search_record::PacketSendInstructionsSearchRecord,
         packet_send_record::PacketSendRecord,
-        packet_send_transfers_record::PacketSendTransfersRecord, InternalChainId,
+        packet_send_transfers_record::PacketSendTransfersRecord, InternalChainId, PgValue, Record,
     },
 };
 
@@ -128,7 +132,7 @@ pub async fn enrich(
         &sort_order,
     )
         .try_into()?;
-    changes += packet_send_decoded_record.insert(tx).await?;
+    changes += packet_send_decoded_record.upsert(tx).await?;
 
     for transfer in get_transfers(tx, &record, &channel, &packet_structure, flatten).await? {
         let packet_send_transfers_record: PacketSendTransfersRecord = (
@@ -138,7 +142,7 @@ pub async fn enrich(
             &format!("{sort_order}-{:03}", transfer.transfer_index.0),
         )
             .try_into()?;
-        changes += packet_send_transfers_record.insert(tx).await?;
+        changes += packet_send_transfers_record.upsert(tx).await?;
     }
 
     // insert packet send transaction
@@ -155,7 +159,7 @@ pub async fn enrich(
         })
         .collect::<Result<Vec<PacketSendInstructionsSearchRecord>, IndexerError>>()?;
 
-    changes += PacketSendInstructionsSearchRecord::insert_batch(tx, &instructions).await?;
+    changes += PacketSendInstructionsSearchRecord::upsert_batch(tx, &instructions).await?;
 
     Ok(changes)
 }
@@ -185,22 +189,64 @@ async fn get_transfers(
         return Ok(vec![]);
     };
 
-    let (transfer_data, fee_data) = match packet_shape {
-        PacketShape::BatchV0TransferV0Fee | PacketShape::BatchV0TransferV1Fee => (
+    let transfers = match packet_shape {
+        PacketShape::BatchV0TransferV0Fee | PacketShape::BatchV0TransferV1Fee => vec![(
+            0,
             InstructionDecoder::from_values_with_index(flatten, 1)?,
             Some(InstructionDecoder::from_values_with_index(flatten, 2)?),
-        ),
-        PacketShape::BatchV0TransferV1 => (
+        )],
+        PacketShape::BatchV0TransferV1 => vec![(
+            0,
             InstructionDecoder::from_values_with_index(flatten, 1)?,
             None,
-        ),
-        PacketShape::TransferV0 | PacketShape::TransferV1 => (
+        )],
+        PacketShape::TransferV0 | PacketShape::TransferV1 => vec![(
+            0,
             InstructionDecoder::from_values_with_index(flatten, 0)?,
             None,
-        ),
+        )],
+        // every instruction of the batch is a transfer, indexed by its position in the batch. the
+        // transfers share the packet hash, which links them to the packet.
+        PacketShape::BatchV0TransfersV1 => (1..flatten.len())
+            .map(|index| {
+                Ok((
+                    index - 1,
+                    InstructionDecoder::from_values_with_index(flatten, index)?,
+                    None,
+                ))
+            })
+            .collect::<Result<_, IndexerError>>()?,
     };
 
-    let transfer_index = 0;
+    let mut result = Vec::with_capacity(transfers.len());
+
+    for (transfer_index, transfer_data, fee_data) in transfers {
+        result.push(
+            get_transfer(
+                tx,
+                record,
+                channel,
+                &packet_shape,
+                transfer_index.try_into()?,
+                transfer_data,
+                fee_data,
+            )
+            .await?,
+        );
+    }
+
+    Ok(result)
+}
+
+async fn get_transfer(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    record: &PacketSendRecord,
+    channel: &ChannelMetaData,
+    packet_shape: &PacketShape,
+    transfer_index: TransferIndex,
+    transfer_data: InstructionDecoder<'_>,
+    fee_data: Option<InstructionDecoder<'_>>,
+) -> Result<Transfer, IndexerError> {
     let sender_zkgm = &AddressZkgm::from_string_0x(
         transfer_data.get_string("sender")?,
         channel.rpc_type.clone(),
@@ -219,6 +265,16 @@ async fn get_transfers(
         .map(|d| d.into());
     let quote_token = transfer_data.get_string("quoteToken")?.try_into()?;
     let quote_amount: Amount = transfer_data.get_string("quoteAmount")?.try_into()?;
+    // only v2 orders carry metadata, which is committed to by the wrapped token
+    let metadata_image = match (
+        transfer_data.get_u32_opt("metadataType")?,
+        transfer_data.get_string_opt("metadata")?,
+    ) {
+        (Some(metadata_type), Some(metadata)) => {
+            Some(MetadataImage::from_order(metadata_type, metadata)?)
+        }
+        _ => None,
+    };
 
     let wrap_direction = wrap_direction_chains(
         tx,
@@ -229,6 +285,27 @@ async fn get_transfers(
         &record.destination_channel_id.try_into()?,
         &base_token,
         &quote_token,
+        metadata_image.as_ref(),
+    )
+    .await?;
+
+    assets::assign_asset(tx, channel, &base_token, &quote_token, &wrap_direction).await?;
+    crate::token_metadata::register_transfer_tokens(
+        tx,
+        channel.internal_chain_id.pg_value()?,
+        &base_token,
+        channel.internal_counterparty_chain_id.pg_value()?,
+        &quote_token,
+    )
+    .await?;
+
+    let amount_usd = pricing::amount_usd(
+        tx,
+        &channel.internal_chain_id,
+        &base_token,
+        &base_amount,
+        &base_token_decimals,
+        &record.timestamp,
     )
     .await?;
 
@@ -241,8 +318,8 @@ async fn get_transfers(
         &wrap_direction,
     )?;
 
-    Ok(vec![Transfer {
-        transfer_index: transfer_index.into(),
+    Ok(Transfer {
+        transfer_index,
         sender_zkgm: sender_zkgm.clone(),
         sender_canonical: sender_zkgm.try_into().unwrap_or_else(
             |_| // TODO: fallback to be compatible with pg implementation. we should actually not expose this packet as a transfer
@@ -265,8 +342,9 @@ async fn get_transfers(
         quote_amount,
         fee,
         wrap_direction,
-        packet_shape,
-    }])
+        packet_shape: packet_shape.clone(),
+        amount_usd,
+    })
 }
 
 fn get_instructions(flatten: &[Value]) -> Result<Vec<Instruction>, IndexerError> {
@@ -555,6 +633,10 @@ fn packet_shape(
             // one transfer
             Some(PacketShape::TransferV1)
         }
+        structure if is_batch_of_transfers_v1(structure) => {
+            // batch with two or more transfers without fee
+            Some(PacketShape::BatchV0TransfersV1)
+        }
         unsupported => {
             debug!("unsupported packet shape: {unsupported}");
 
@@ -563,6 +645,17 @@ fn packet_shape(
     })
 }
 
+/// A batch of at least two v1 transfers, ie `:2/0,0:3/1,1:3/1,...`
+fn is_batch_of_transfers_v1(packet_structure: &str) -> bool {
+    let mut instructions = packet_structure.split(',');
+
+    instructions.next() == Some(":2/0")
+        && instructions.clone().count() >= 2
+        && instructions
+            .enumerate()
+            .all(|(index, instruction)| instruction == format!("{index}:3/1"))
+}
+
 /// A batch has a fee if the second instruction of the batch (ie the third instruction, because
 /// the first one is the batch) has a zero quote amount
 fn has_fee(flatten: &[Value]) -> Result<bool, IndexerError> {
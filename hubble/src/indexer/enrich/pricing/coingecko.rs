@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use sqlx::types::BigDecimal;
+use tracing::debug;
+use url::Url;
+
+use crate::indexer::enrich::pricing::{price_from_parts, PriceClientError};
+
+#[derive(Clone, Debug)]
+pub struct Coingecko {
+    client: reqwest::Client,
+    url: Url,
+    api_key: Option<String>,
+}
+
+impl Coingecko {
+    pub fn new(url: Url, api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            api_key,
+        }
+    }
+
+    /// Fetches the usd prices of the given coin ids. Coins unknown to coingecko are absent from
+    /// the result.
+    pub async fn prices(
+        &self,
+        coin_ids: &[&str],
+    ) -> Result<HashMap<String, BigDecimal>, PriceClientError> {
+        let url = format!("{}/simple/price", self.url.as_str().trim_end_matches('/'));
+
+        debug!("fetching {} prices from: {url}", coin_ids.len());
+
+        let mut request = self.client.get(&url).query(&[
+            ("ids", coin_ids.join(",")),
+            ("vs_currencies", "usd".to_string()),
+        ]);
+
+        if let Some(api_key) = &self.api_key {
+            request = request.header(self.api_key_header(), api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| PriceClientError::Request(url.clone(), error))?;
+
+        if !response.status().is_success() {
+            return Err(PriceClientError::ErrorResponse(url, response.status()));
+        }
+
+        let prices: HashMap<String, HashMap<String, f64>> = response
+            .json()
+            .await
+            .map_err(|error| PriceClientError::Request(url.clone(), error))?;
+
+        prices
+            .into_iter()
+            .filter_map(|(coin_id, prices)| prices.get("usd").map(|usd| (coin_id, *usd)))
+            .map(|(coin_id, usd)| {
+                let price = price_from_parts(&coin_id, &usd.to_string(), 0)?;
+                Ok((coin_id, price))
+            })
+            .collect()
+    }
+
+    /// Keys are sent as pro keys to the pro api, and as demo keys to the public api.
+    fn api_key_header(&self) -> &'static str {
+        match self.url.host_str() {
+            Some("pro-api.coingecko.com") => "x-cg-pro-api-key",
+            _ => "x-cg-demo-api-key",
+        }
+    }
+}
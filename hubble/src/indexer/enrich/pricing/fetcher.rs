@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use alloy::primitives::Address;
+use sqlx::types::BigDecimal;
+use time::OffsetDateTime;
+use tracing::{debug, error, info, warn};
+
+use crate::indexer::enrich::pricing::{
+    bucket,
+    oracle::oracle_price,
+    postgres::{annotate_transfers, get_price_feeds, snapshot_daily_prices, upsert_price},
+    pyth::normalize_feed_id,
+    PriceClientError, PriceFeed, PriceProvider, PriceProviders,
+};
+
+pub async fn update_prices(
+    db: &sqlx::PgPool,
+    providers: &PriceProviders,
+) -> color_eyre::Result<()> {
+    info!("Starting price update process.");
+
+    let price_feeds = get_price_feeds(&mut db.begin().await?).await?;
+    let bucket = bucket(OffsetDateTime::now_utc());
+
+    let prices = fetch_prices(providers, &price_feeds).await;
+
+    let mut tx = db.begin().await?;
+
+    for (price_feed, price_usd) in &prices {
+        debug!("price: {price_feed} => {price_usd} at {bucket}");
+        upsert_price(&mut tx, price_feed, bucket, price_usd).await?;
+    }
+
+    let updated = annotate_transfers(&mut tx).await?;
+    let snapshots = snapshot_daily_prices(&mut tx).await?;
+
+    tx.commit().await?;
+
+    info!(
+        "Finished price update process (fetched {} of {} prices, updated {updated} transfers, pinned {snapshots} daily prices).",
+        prices.len(),
+        price_feeds.len()
+    );
+    Ok(())
+}
+
+/// Fetches the prices of all feeds, batched per provider where possible. Feeds that cannot be
+/// fetched are logged and skipped.
+async fn fetch_prices<'a>(
+    providers: &PriceProviders,
+    price_feeds: &'a [PriceFeed],
+) -> Vec<(&'a PriceFeed, BigDecimal)> {
+    let mut prices = vec![];
+
+    let by_provider = price_feeds.iter().fold(
+        HashMap::<PriceProvider, Vec<&PriceFeed>>::new(),
+        |mut acc, price_feed| {
+            acc.entry(price_feed.provider).or_default().push(price_feed);
+            acc
+        },
+    );
+
+    for (provider, price_feeds) in by_provider {
+        match provider {
+            PriceProvider::Coingecko => {
+                let Some(coingecko) = &providers.coingecko else {
+                    warn!(
+                        "coingecko is not configured: skipping {} feeds",
+                        price_feeds.len()
+                    );
+                    continue;
+                };
+
+                let coin_ids = price_feeds
+                    .iter()
+                    .map(|price_feed| price_feed.feed_id.as_str())
+                    .collect::<Vec<_>>();
+
+                match coingecko.prices(&coin_ids).await {
+                    Ok(result) => prices.extend(price_feeds.into_iter().filter_map(|price_feed| {
+                        result
+                            .get(&price_feed.feed_id)
+                            .map(|price| (price_feed, price.clone()))
+                    })),
+                    Err(error) => error!("coingecko => error: {error:?}"),
+                }
+            }
+            PriceProvider::Pyth => {
+                let Some(pyth) = &providers.pyth else {
+                    warn!(
+                        "pyth is not configured: skipping {} feeds",
+                        price_feeds.len()
+                    );
+                    continue;
+                };
+
+                let feed_ids = price_feeds
+                    .iter()
+                    .map(|price_feed| price_feed.feed_id.as_str())
+                    .collect::<Vec<_>>();
+
+                match pyth.prices(&feed_ids).await {
+                    Ok(result) => prices.extend(price_feeds.into_iter().filter_map(|price_feed| {
+                        result
+                            .get(&normalize_feed_id(&price_feed.feed_id))
+                            .map(|price| (price_feed, price.clone()))
+                    })),
+                    Err(error) => error!("pyth => error: {error:?}"),
+                }
+            }
+            PriceProvider::Oracle => {
+                for price_feed in price_feeds {
+                    match fetch_oracle_price(providers, price_feed).await {
+                        Ok(Some(price)) => prices.push((price_feed, price)),
+                        Ok(None) => warn!("{price_feed} => no rpc configured for the oracle chain"),
+                        Err(error) => error!("{price_feed} => error: {error:?}"),
+                    }
+                }
+            }
+        }
+    }
+
+    prices
+}
+
+async fn fetch_oracle_price(
+    providers: &PriceProviders,
+    price_feed: &PriceFeed,
+) -> Result<Option<BigDecimal>, PriceClientError> {
+    let Some(provider) = price_feed
+        .oracle_universal_chain_id
+        .as_ref()
+        .and_then(|universal_chain_id| providers.oracles.get(universal_chain_id))
+    else {
+        return Ok(None);
+    };
+
+    let address: Address = price_feed.feed_id.parse().map_err(|_| {
+        PriceClientError::InvalidPrice(
+            price_feed.feed_id.clone(),
+            "oracle feed id is not an address".to_string(),
+        )
+    })?;
+
+    oracle_price(provider, address).await.map(Some)
+}
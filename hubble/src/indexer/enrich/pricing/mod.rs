@@ -0,0 +1,254 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use alloy::{
+    primitives::Address,
+    transports::{RpcError, TransportErrorKind},
+};
+use reqwest::StatusCode;
+use sqlx::types::BigDecimal;
+use time::OffsetDateTime;
+use url::Url;
+
+use crate::indexer::{
+    enrich::pricing::{coingecko::Coingecko, pyth::Pyth},
+    ethereum::provider::Provider as EthProvider,
+    event::types::UniversalChainId,
+};
+
+mod coingecko;
+mod fetcher;
+mod oracle;
+mod postgres;
+mod pyth;
+
+pub use postgres::amount_usd;
+
+/// Prices are stored per bucket of this many seconds; the last price fetched in a bucket wins.
+const BUCKET_SECONDS: i64 = 5 * 60;
+
+/// Price providers, passed as json (e.g. `{"coingecko": {}, "pyth": {}}`). Providers that are not
+/// configured are skipped. On-chain oracles are read through the rpc urls of the ethereum
+/// indexers and do not need to be configured here.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PricingConfig {
+    pub coingecko: Option<CoingeckoConfig>,
+    pub pyth: Option<PythConfig>,
+}
+
+impl FromStr for PricingConfig {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(item: &str) -> Result<Self, <Self as FromStr>::Err> {
+        serde_json::from_str(item).map_err(Into::into)
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CoingeckoConfig {
+    #[serde(default = "default_coingecko_url")]
+    pub url: Url,
+}
+
+fn default_coingecko_url() -> Url {
+    "https://api.coingecko.com/api/v3"
+        .parse()
+        .expect("valid url")
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PythConfig {
+    #[serde(default = "default_pyth_url")]
+    pub url: Url,
+}
+
+fn default_pyth_url() -> Url {
+    "https://hermes.pyth.network".parse().expect("valid url")
+}
+
+#[derive(Clone, Debug)]
+pub struct PriceProviders {
+    coingecko: Option<Coingecko>,
+    pyth: Option<Pyth>,
+    oracles: HashMap<UniversalChainId, EthProvider>,
+}
+
+impl PriceProviders {
+    pub fn new(
+        config: PricingConfig,
+        coingecko_api_key: Option<String>,
+        oracle_rpc_urls: impl IntoIterator<Item = (UniversalChainId, Vec<Url>)>,
+    ) -> Self {
+        Self {
+            coingecko: config
+                .coingecko
+                .map(|config| Coingecko::new(config.url, coingecko_api_key)),
+            pyth: config.pyth.map(|config| Pyth::new(config.url)),
+            oracles: oracle_rpc_urls
+                .into_iter()
+                .map(|(universal_chain_id, rpc_urls)| {
+                    (universal_chain_id, EthProvider::new(rpc_urls))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A price feed of a token, as configured by operators in `token.price_feeds`.
+#[derive(Clone, Debug)]
+pub struct PriceFeed {
+    id: i32,
+    provider: PriceProvider,
+    /// Coingecko coin id, pyth price feed id or oracle (chainlink aggregator) address.
+    feed_id: String,
+    /// Chain of the oracle, only used by `oracle` feeds.
+    oracle_universal_chain_id: Option<UniversalChainId>,
+}
+
+impl Display for PriceFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{}: {} {}{}",
+            self.id,
+            self.provider,
+            self.feed_id,
+            self.oracle_universal_chain_id
+                .as_ref()
+                .map(|id| format!(" on {id}"))
+                .unwrap_or_default(),
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PriceProvider {
+    Coingecko,
+    Pyth,
+    Oracle,
+}
+
+impl PriceProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceProvider::Coingecko => "coingecko",
+            PriceProvider::Pyth => "pyth",
+            PriceProvider::Oracle => "oracle",
+        }
+    }
+}
+
+impl Display for PriceProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown price provider: {0}")]
+pub struct UnknownPriceProvider(String);
+
+impl FromStr for PriceProvider {
+    type Err = UnknownPriceProvider;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "coingecko" => Ok(PriceProvider::Coingecko),
+            "pyth" => Ok(PriceProvider::Pyth),
+            "oracle" => Ok(PriceProvider::Oracle),
+            _ => Err(UnknownPriceProvider(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PriceClientError {
+    #[error("error fetching prices from {0}: {1}")]
+    Request(String, reqwest::Error),
+    #[error("error response fetching prices from {0}: {1}")]
+    ErrorResponse(String, StatusCode),
+    #[error("invalid price for {0}: {1}")]
+    InvalidPrice(String, String),
+    #[error("error calling oracle {0}: {1}")]
+    OracleCall(Address, RpcError<TransportErrorKind>),
+    #[error("error decoding response of oracle {0}: {1}")]
+    OracleDecode(Address, alloy_sol_types::Error),
+}
+
+/// Parses a price represented as `mantissa * 10^exponent`.
+fn price_from_parts(
+    feed_id: &str,
+    mantissa: &str,
+    exponent: i32,
+) -> Result<BigDecimal, PriceClientError> {
+    let price = BigDecimal::from_str(&format!("{mantissa}e{exponent}"))
+        .map_err(|error| PriceClientError::InvalidPrice(feed_id.to_string(), error.to_string()))?;
+
+    if price <= BigDecimal::from(0) {
+        return Err(PriceClientError::InvalidPrice(
+            feed_id.to_string(),
+            price.to_string(),
+        ));
+    }
+
+    Ok(price)
+}
+
+/// The start of the bucket that contains `timestamp`.
+fn bucket(timestamp: OffsetDateTime) -> OffsetDateTime {
+    let seconds = timestamp.unix_timestamp();
+
+    OffsetDateTime::from_unix_timestamp(seconds - seconds.rem_euclid(BUCKET_SECONDS))
+        .expect("bucket is within range")
+}
+
+pub async fn update_prices(
+    db: &sqlx::PgPool,
+    providers: &PriceProviders,
+) -> color_eyre::Result<()> {
+    crate::indexer::enrich::pricing::fetcher::update_prices(db, providers).await
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn test_bucket() {
+        assert_eq!(
+            bucket(datetime!(2025-06-01 12:34:56 UTC)),
+            datetime!(2025-06-01 12:30:00 UTC)
+        );
+        assert_eq!(
+            bucket(datetime!(2025-06-01 12:35:00 UTC)),
+            datetime!(2025-06-01 12:35:00 UTC)
+        );
+    }
+
+    #[test]
+    fn test_price_from_parts() {
+        assert_eq!(
+            price_from_parts("btc", "6718734000000", -8).unwrap(),
+            BigDecimal::from_str("67187.34").unwrap()
+        );
+        assert_eq!(
+            price_from_parts("uno", "5", 0).unwrap(),
+            BigDecimal::from(5)
+        );
+    }
+
+    #[test]
+    fn test_price_from_parts_rejects_non_positive() {
+        assert!(matches!(
+            price_from_parts("btc", "0", -8),
+            Err(PriceClientError::InvalidPrice(..))
+        ));
+        assert!(matches!(
+            price_from_parts("btc", "-1", -8),
+            Err(PriceClientError::InvalidPrice(..))
+        ));
+    }
+}
@@ -0,0 +1,53 @@
+use alloy::{
+    primitives::Address,
+    rpc::types::{TransactionInput, TransactionRequest},
+    serde::WithOtherFields,
+};
+use alloy_sol_types::{sol, SolCall};
+use sqlx::types::BigDecimal;
+
+use crate::indexer::{
+    enrich::pricing::{price_from_parts, PriceClientError},
+    ethereum::provider::Provider as EthProvider,
+};
+
+// source: chainlink AggregatorV3Interface
+sol! {
+    function decimals() external view returns (uint8);
+    function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+}
+
+/// Reads the latest answer of a chainlink compatible aggregator.
+pub async fn oracle_price(
+    provider: &EthProvider,
+    address: Address,
+) -> Result<BigDecimal, PriceClientError> {
+    let decimals = call(provider, address, decimalsCall {}).await?;
+    let round = call(provider, address, latestRoundDataCall {}).await?;
+
+    price_from_parts(
+        &address.to_string(),
+        &round.answer.to_string(),
+        -i32::from(decimals),
+    )
+}
+
+async fn call<C: SolCall>(
+    provider: &EthProvider,
+    address: Address,
+    call: C,
+) -> Result<C::Return, PriceClientError> {
+    let request = WithOtherFields::new(TransactionRequest {
+        to: Some(address.into()),
+        input: TransactionInput::new(call.abi_encode().into()),
+        ..Default::default()
+    });
+
+    let result = provider
+        .call(&request, None)
+        .await
+        .map_err(|error| PriceClientError::OracleCall(address, error))?;
+
+    C::abi_decode_returns(&result.response)
+        .map_err(|error| PriceClientError::OracleDecode(address, error))
+}
@@ -0,0 +1,154 @@
+use sqlx::{types::BigDecimal, Postgres};
+use time::OffsetDateTime;
+
+use crate::indexer::{
+    api::IndexerError,
+    enrich::pricing::PriceFeed,
+    event::types::Denom,
+    handler::types::{Amount, AmountUsd, TokenDecimals},
+    record::{InternalChainId, PgValue, PgValueExt},
+};
+
+pub async fn get_price_feeds(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> sqlx::Result<Vec<PriceFeed>> {
+    sqlx::query!(
+        r#"
+        SELECT id, provider, feed_id, oracle_universal_chain_id
+        FROM token.price_feeds
+        WHERE enabled = true
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| {
+        Ok(PriceFeed {
+            id: record.id,
+            provider: record
+                .provider
+                .parse()
+                .map_err(|error| sqlx::Error::Decode(Box::new(error)))?,
+            feed_id: record.feed_id,
+            oracle_universal_chain_id: record.oracle_universal_chain_id.map(Into::into),
+        })
+    })
+    .collect()
+}
+
+pub async fn upsert_price(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    price_feed: &PriceFeed,
+    bucket: OffsetDateTime,
+    price_usd: &BigDecimal,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        "
+        INSERT INTO token.prices (price_feed_id, bucket, price_usd, fetched_at)
+        VALUES ($1, $2, $3, now())
+        ON CONFLICT (price_feed_id, bucket) DO
+        UPDATE SET
+            price_usd = excluded.price_usd,
+            fetched_at = excluded.fetched_at
+        ",
+        price_feed.id,
+        bucket,
+        price_usd,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Pins the daily price of every feed for the completed (utc) days that do not have one yet, as the
+/// last price fetched on that day. Snapshots are never updated afterwards, so that historical
+/// valuations do not change when prices are refetched or feeds are reconfigured. Returns the
+/// number of new snapshots.
+pub async fn snapshot_daily_prices(tx: &mut sqlx::Transaction<'_, Postgres>) -> sqlx::Result<u64> {
+    Ok(sqlx::query!(
+        r#"
+        INSERT INTO token.daily_prices (price_feed_id, day, price_usd, bucket, snapshotted_at)
+        SELECT DISTINCT ON (p.price_feed_id, (p.bucket AT TIME ZONE 'UTC')::DATE)
+            p.price_feed_id, (p.bucket AT TIME ZONE 'UTC')::DATE, p.price_usd, p.bucket, now()
+        FROM token.prices p
+        WHERE p.bucket < date_trunc('day', now() AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+        AND NOT EXISTS (
+            SELECT 1 FROM token.daily_prices d
+            WHERE d.price_feed_id = p.price_feed_id AND d.day = (p.bucket AT TIME ZONE 'UTC')::DATE
+        )
+        ORDER BY p.price_feed_id, (p.bucket AT TIME ZONE 'UTC')::DATE, p.bucket DESC
+        ON CONFLICT (price_feed_id, day) DO NOTHING
+        "#,
+    )
+    .execute(tx.as_mut())
+    .await?
+    .rows_affected())
+}
+
+/// The usd value of `amount` at `timestamp`, based on the most recent price of the token at that
+/// time. Returns `None` if there is no price of at most an hour old, or if the decimals of the
+/// token are unknown (falling back to the cached token metadata).
+pub async fn amount_usd(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    internal_chain_id: &InternalChainId,
+    denom: &Denom,
+    amount: &Amount,
+    decimals: &Option<TokenDecimals>,
+    timestamp: &OffsetDateTime,
+) -> Result<Option<AmountUsd>, IndexerError> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT $3::NUMERIC / power(10::NUMERIC, COALESCE($4::INT4, m.decimals)) * p.price_usd AS amount_usd
+        FROM token.price_feeds f
+        JOIN token.prices p ON p.price_feed_id = f.id
+        LEFT JOIN token.token_metadata m ON m.internal_chain_id = f.internal_chain_id AND m.denom = f.denom
+        WHERE f.enabled = true
+        AND f.internal_chain_id = $1 AND f.denom = $2
+        AND p.bucket <= $5 AND p.bucket > $5 - INTERVAL '1 hour'
+        ORDER BY p.bucket DESC, f.id
+        LIMIT 1
+        "#,
+        internal_chain_id.pg_value()?,
+        denom.pg_value()?,
+        amount.pg_value()?,
+        decimals.pg_value()?,
+        timestamp,
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .and_then(|record| record.amount_usd)
+    .map(AmountUsd))
+}
+
+/// Annotates transfers of the last day that were enriched before a price was available. Only
+/// transfers of tokens with a price feed are joined with the prices (on the price feed and bucket),
+/// instead of looking up a price for every transfer. Returns the number of updated transfers.
+pub async fn annotate_transfers(tx: &mut sqlx::Transaction<'_, Postgres>) -> sqlx::Result<u64> {
+    Ok(sqlx::query!(
+        r#"
+        WITH priced AS (
+            SELECT DISTINCT ON (t.packet_hash, t.transfer_index)
+                t.packet_hash,
+                t.transfer_index,
+                t.base_amount / power(10::NUMERIC, COALESCE(t.base_token_decimals, m.decimals)) * p.price_usd AS amount_usd
+            FROM v2_sync.packet_send_transfers_sync t
+            JOIN token.price_feeds f ON f.enabled = true AND f.internal_chain_id = t.internal_chain_id AND f.denom = t.base_token
+            JOIN token.prices p ON p.price_feed_id = f.id AND p.bucket <= t.timestamp AND p.bucket > t.timestamp - INTERVAL '1 hour'
+            LEFT JOIN token.token_metadata m ON m.internal_chain_id = t.internal_chain_id AND m.denom = t.base_token
+            WHERE t.amount_usd IS NULL
+            AND t.timestamp > now() - INTERVAL '1 day'
+            AND COALESCE(t.base_token_decimals, m.decimals) IS NOT NULL
+            ORDER BY t.packet_hash, t.transfer_index, p.bucket DESC, f.id
+        )
+        UPDATE v2_sync.packet_send_transfers_sync t
+        SET amount_usd = priced.amount_usd
+        FROM priced
+        WHERE t.packet_hash = priced.packet_hash AND t.transfer_index = priced.transfer_index
+        "#,
+    )
+    .execute(tx.as_mut())
+    .await?
+    .rows_affected())
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use sqlx::types::BigDecimal;
+use tracing::debug;
+use url::Url;
+
+use crate::indexer::enrich::pricing::{price_from_parts, PriceClientError};
+
+/// Client of pyth's hermes api.
+#[derive(Clone, Debug)]
+pub struct Pyth {
+    client: reqwest::Client,
+    url: Url,
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestPriceUpdates {
+    parsed: Vec<ParsedPriceUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedPriceUpdate {
+    id: String,
+    price: Price,
+}
+
+#[derive(Debug, Deserialize)]
+struct Price {
+    price: String,
+    expo: i32,
+}
+
+impl Pyth {
+    pub fn new(url: Url) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    /// Fetches the latest prices of the given price feed ids. The ids in the result are lowercase
+    /// hex without `0x` prefix, as returned by hermes.
+    pub async fn prices(
+        &self,
+        feed_ids: &[&str],
+    ) -> Result<HashMap<String, BigDecimal>, PriceClientError> {
+        let url = format!(
+            "{}/v2/updates/price/latest",
+            self.url.as_str().trim_end_matches('/')
+        );
+
+        debug!("fetching {} prices from: {url}", feed_ids.len());
+
+        let query = feed_ids
+            .iter()
+            .map(|feed_id| ("ids[]", *feed_id))
+            .chain([("parsed", "true")])
+            .collect::<Vec<_>>();
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|error| PriceClientError::Request(url.clone(), error))?;
+
+        if !response.status().is_success() {
+            return Err(PriceClientError::ErrorResponse(url, response.status()));
+        }
+
+        let updates: LatestPriceUpdates = response
+            .json()
+            .await
+            .map_err(|error| PriceClientError::Request(url.clone(), error))?;
+
+        updates
+            .parsed
+            .into_iter()
+            .map(|update| {
+                let price = price_from_parts(&update.id, &update.price.price, update.price.expo)?;
+                Ok((update.id, price))
+            })
+            .collect()
+    }
+}
+
+/// Normalizes a configured feed id to the format returned by hermes.
+pub fn normalize_feed_id(feed_id: &str) -> String {
+    feed_id.trim_start_matches("0x").to_lowercase()
+}
@@ -6,9 +6,9 @@ use serde::{
     Serialize, Serializer,
 };
 use serde_json::Value;
-
-use crate::indexer::enrich::ucs03_zkgm_0::packet::{
-    Batch, Forward, FungibleAssetOrder, Instruction, Multiplex, Operand, ZkgmPacket,
+use unionlabs::{
+    encoding::{DecodeAs, EthAbi},
+    zkgm::{Instruction, ZkgmPacket},
 };
 
 const ACK_FAILURE: U256 = U256::ZERO;
@@ -47,12 +47,8 @@ impl Serialize for InstructionPacketAck {
         state.serialize_field("tag", &self.ack.tag)?;
 
         if self.ack.tag != ACK_FAILURE {
-            let operand = &self.instruction.decode_operand().map_err(|err| {
-                S::Error::custom(format!("error decoding operand (in ack): {err}"))
-            })?;
-
             // Custom serialization for operand based on version and opcode
-            let inner_ack = decode_inner_ack(operand, &self.ack.innerAck)
+            let inner_ack = decode_inner_ack(&self.instruction, &self.ack.innerAck)
                 .map_err(|err| S::Error::custom(format!("error decoding ack (in ack): {err}")))?;
             state.serialize_field("innerAck", &inner_ack)?;
         } else {
@@ -91,13 +87,7 @@ impl Serialize for BatchPacketAck {
             .zip(&self.acknowledgements)
             .enumerate()
             .map(|(index, (instruction, ack))| {
-                decode_inner_ack(
-                    &instruction
-                        .decode_operand()
-                        .context(format!("decoding operand packet {index}"))?,
-                    ack,
-                )
-                .context(format!("decoding ack packet {index}"))
+                decode_inner_ack(instruction, ack).context(format!("decoding ack packet {index}"))
             })
             .collect::<Result<Vec<_>>>()
             .map_err(|err| S::Error::custom(format!("error batch acks: {err}")))?;
@@ -108,28 +98,26 @@ impl Serialize for BatchPacketAck {
     }
 }
 
-fn decode_inner_ack(operand: &Operand, ack: &Bytes) -> Result<InnerAck> {
-    Ok(match operand {
-        Operand::Forward(Forward::V0(forward)) => InnerAck::Forward(InstructionPacketAck {
-            instruction: forward.instruction.clone(),
+fn decode_inner_ack(instruction: &Instruction, ack: &Bytes) -> Result<InnerAck> {
+    Ok(match instruction {
+        Instruction::Forward(forward) => InnerAck::Forward(InstructionPacketAck {
+            instruction: (*forward.instruction).clone(),
             ack: <Ack>::abi_decode_sequence(ack).context("decoding ForwardAck V0")?,
         }),
-        Operand::Multiplex(Multiplex::V0(_)) => InnerAck::Multiplex { data: ack.clone() },
-        Operand::Batch(Batch::V0(batch)) => InnerAck::Batch(BatchPacketAck {
+        Instruction::Multiplex(_) => InnerAck::Multiplex { data: ack.clone() },
+        Instruction::Batch(batch) => InnerAck::Batch(BatchPacketAck {
             instructions: batch.instructions.clone(),
             acknowledgements: <BatchAck>::abi_decode_sequence(ack)
                 .context("decoding BatchAck V0")?
                 .acknowledgements,
         }),
-        Operand::FungibleAssetOrder(FungibleAssetOrder::V0(_)) => InnerAck::FungibleAssetOrder(
-            <FungibleAssetOrderAck>::abi_decode_sequence(ack)
-                .context("decoding FungibleAssetOrderAck V0")?,
-        ),
-        Operand::FungibleAssetOrder(FungibleAssetOrder::V1(_)) => InnerAck::FungibleAssetOrder(
-            <FungibleAssetOrderAck>::abi_decode_sequence(ack)
-                .context("decoding FungibleAssetOrderAck V1")?,
+        Instruction::FungibleAssetOrder(_) => InnerAck::FungibleAssetOrder(
+            <FungibleAssetOrderAck>::abi_decode_sequence(ack).context(format!(
+                "decoding FungibleAssetOrderAck V{}",
+                instruction.version()
+            ))?,
         ),
-        Operand::Unsupported { data: _ } => InnerAck::Unsupported { data: ack.clone() },
+        Instruction::Unknown(_) => InnerAck::Unsupported { data: ack.clone() },
     })
 }
 
@@ -144,7 +132,7 @@ enum InnerAck {
 }
 
 pub fn decode(packet: &[u8], ack: &[u8]) -> Result<Value> {
-    let instruction = <ZkgmPacket>::abi_decode_sequence(packet)
+    let instruction = ZkgmPacket::decode_as::<EthAbi>(packet)
         .context("decoding zkgm packet")?
         .instruction;
     let ack = <Ack>::abi_decode_sequence(ack).context("decoding ack packet")?;
@@ -1,171 +1,102 @@
-use alloy_sol_types::{sol, SolType};
 use anyhow::{Context, Result};
-use serde::{
-    ser::{Error as SerdeError, SerializeStruct},
-    Serialize, Serializer,
+use serde_json::{json, Value};
+use unionlabs::{
+    encoding::{DecodeAs, EthAbi},
+    zkgm::{Forward, FungibleAssetOrder, Instruction, ZkgmPacket},
 };
-use serde_json::Value;
 
-// source: github:unionlabs/union/evm/contracts/apps/ucs/03-zkgm/Zkgm.sol
-const OP_FORWARD: u8 = 0x00;
-const OP_MULTIPLEX: u8 = 0x01;
-const OP_BATCH: u8 = 0x02;
-const OP_FUNGIBLE_ASSET_TRANSFER: u8 = 0x03;
-
-sol! {
-    #[derive(Serialize)]
-    struct ZkgmPacket {
-        bytes32 salt;
-        uint256 path;
-        Instruction instruction;
-    }
-
-    #[derive(Debug)]
-    struct Instruction {
-        uint8 version;
-        uint8 opcode;
-        bytes operand;
-    }
-
-    #[derive(Serialize, Debug)]
-    struct ForwardV0 {
-        uint32 channelId;
-        uint64 timeoutHeight;
-        uint64 timeoutTimestamp;
-        Instruction instruction;
-    }
-
-    #[derive(Serialize, Debug)]
-    struct MultiplexV0 {
-        bytes sender;
-        bool eureka;
-        bytes contractAddress;
-        bytes contractCalldata;
-    }
-
-    #[derive(Serialize, Debug)]
-    struct BatchV0 {
-        Instruction[] instructions;
-    }
-
-    #[derive(Serialize, Debug)]
-    struct FungibleAssetOrderV0 {
-        bytes sender;
-        bytes receiver;
-        bytes baseToken;
-        uint256 baseAmount;
-        string baseTokenSymbol;
-        string baseTokenName;
-        uint256 baseTokenPath;
-        bytes quoteToken;
-        uint256 quoteAmount;
-    }
+pub fn decode(input: &[u8]) -> Result<Value> {
+    let zkgm_packet = ZkgmPacket::decode_as::<EthAbi>(input).context("decoding zkgm packet")?;
 
-    #[derive(Serialize, Debug)]
-    struct FungibleAssetOrderV1 {
-        bytes sender;
-        bytes receiver;
-        bytes baseToken;
-        uint256 baseAmount;
-        string baseTokenSymbol;
-        string baseTokenName;
-        uint8 baseTokenDecimals;
-        uint256 baseTokenPath;
-        bytes quoteToken;
-        uint256 quoteAmount;
-    }
+    Ok(json!({
+        "salt": zkgm_packet.salt,
+        "path": zkgm_packet.path.to_be_hex_packed(),
+        "instruction": instruction_json(&zkgm_packet.instruction),
+    }))
 }
 
-impl Serialize for Instruction {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        // Create a struct with version, opcode, and operand
-        let mut state = serializer.serialize_struct("Instruction", 3)?;
-        state.serialize_field("version", &self.version)?;
-        state.serialize_field("opcode", &self.opcode)?;
-
-        // Custom serialization for operand based on version and opcode
-        let operand = &self.decode_operand().map_err(|err| {
-            S::Error::custom(format!("error decoding operand (in packet): {err}"))
-        })?;
-        state.serialize_field("operand", &operand)?;
-
-        state.end()
-    }
+/// The json representation of an instruction as stored by hubble, with the fields of the operand
+/// in camel case and the kind of operand in `_type`.
+pub fn instruction_json(instruction: &Instruction) -> Value {
+    json!({
+        "version": instruction.version(),
+        "opcode": instruction.opcode(),
+        "operand": operand_json(instruction),
+    })
 }
 
-impl Instruction {
-    pub fn decode_operand(&self) -> Result<Operand> {
-        Ok(match (self.version, self.opcode) {
-            (0, OP_FORWARD) => Operand::Forward(Forward::V0(
-                <ForwardV0>::abi_decode_sequence(&self.operand).context("decoding Forward")?,
-            )),
-            (0, OP_MULTIPLEX) => Operand::Multiplex(Multiplex::V0(
-                <MultiplexV0>::abi_decode_sequence(&self.operand).context("decoding Multiplex")?,
-            )),
-            (0, OP_BATCH) => Operand::Batch(Batch::V0(
-                <BatchV0>::abi_decode_sequence(&self.operand).context("decoding Batch")?,
-            )),
-            (0, OP_FUNGIBLE_ASSET_TRANSFER) => Operand::FungibleAssetOrder(FungibleAssetOrder::V0(
-                <FungibleAssetOrderV0>::abi_decode_sequence(&self.operand)
-                    .context("decoding FungibleAssetOrder")?,
-            )),
-            (1, OP_FUNGIBLE_ASSET_TRANSFER) => Operand::FungibleAssetOrder(FungibleAssetOrder::V1(
-                <FungibleAssetOrderV1>::abi_decode_sequence(&self.operand)
-                    .context("decoding FungibleAssetOrder")?,
-            )),
-            _ => Operand::Unsupported {
-                data: self.operand.clone(),
-            },
-        })
+fn operand_json(instruction: &Instruction) -> Value {
+    match instruction {
+        Instruction::Forward(forward) => forward_json(forward),
+        Instruction::Multiplex(multiplex) => json!({
+            "_type": "Multiplex",
+            "sender": multiplex.sender,
+            "eureka": multiplex.eureka,
+            "contractAddress": multiplex.contract_address,
+            "contractCalldata": multiplex.contract_calldata,
+        }),
+        Instruction::Batch(batch) => json!({
+            "_type": "Batch",
+            "instructions": batch.instructions.iter().map(instruction_json).collect::<Vec<_>>(),
+        }),
+        Instruction::FungibleAssetOrder(FungibleAssetOrder::V0(order)) => json!({
+            "_type": "FungibleAssetOrder",
+            "sender": order.sender,
+            "receiver": order.receiver,
+            "baseToken": order.base_token,
+            "baseAmount": order.base_amount.to_be_hex_packed(),
+            "baseTokenSymbol": order.base_token_symbol,
+            "baseTokenName": order.base_token_name,
+            "baseTokenPath": order.base_token_path.to_be_hex_packed(),
+            "quoteToken": order.quote_token,
+            "quoteAmount": order.quote_amount.to_be_hex_packed(),
+        }),
+        Instruction::FungibleAssetOrder(FungibleAssetOrder::V1(order)) => json!({
+            "_type": "FungibleAssetOrder",
+            "sender": order.sender,
+            "receiver": order.receiver,
+            "baseToken": order.base_token,
+            "baseAmount": order.base_amount.to_be_hex_packed(),
+            "baseTokenSymbol": order.base_token_symbol,
+            "baseTokenName": order.base_token_name,
+            "baseTokenDecimals": order.base_token_decimals,
+            "baseTokenPath": order.base_token_path.to_be_hex_packed(),
+            "quoteToken": order.quote_token,
+            "quoteAmount": order.quote_amount.to_be_hex_packed(),
+        }),
+        Instruction::FungibleAssetOrder(FungibleAssetOrder::V2(order)) => json!({
+            "_type": "FungibleAssetOrder",
+            "sender": order.sender,
+            "receiver": order.receiver,
+            "baseToken": order.base_token,
+            "baseAmount": order.base_amount.to_be_hex_packed(),
+            "metadataType": order.metadata_type,
+            "metadata": order.metadata,
+            "quoteToken": order.quote_token,
+            "quoteAmount": order.quote_amount.to_be_hex_packed(),
+        }),
+        Instruction::Unknown(raw) => json!({
+            "_type": "Unsupported",
+            "data": raw.operand,
+        }),
     }
 }
 
-#[derive(Serialize, Debug)]
-#[serde(untagged)]
-pub enum Forward {
-    V0(ForwardV0),
-}
-
-#[derive(Serialize, Debug)]
-#[serde(untagged)]
-pub enum Multiplex {
-    V0(MultiplexV0),
-}
-
-#[derive(Serialize, Debug)]
-#[serde(untagged)]
-pub enum Batch {
-    V0(BatchV0),
-}
-
-#[derive(Serialize, Debug)]
-#[serde(untagged)]
-pub enum FungibleAssetOrder {
-    V0(FungibleAssetOrderV0),
-    V1(FungibleAssetOrderV1),
-}
-
-#[derive(Serialize, Debug)]
-#[serde(tag = "_type")]
-pub enum Operand {
-    Forward(Forward),
-    Multiplex(Multiplex),
-    Batch(Batch),
-    FungibleAssetOrder(FungibleAssetOrder),
-    Unsupported {
-        data: alloy_sol_types::private::Bytes,
-    },
-}
-
-pub fn decode(input: &[u8]) -> Result<Value> {
-    let zkgm_packet = <ZkgmPacket>::abi_decode_sequence(input).context("decoding zkgm packet")?;
-
-    let value = serde_json::to_value(&zkgm_packet).context("formatting json")?;
+fn forward_json(forward: &Forward) -> Value {
+    let mut value = json!({
+        "_type": "Forward",
+        "timeoutHeight": forward.timeout_height,
+        "timeoutTimestamp": forward.timeout_timestamp,
+        "instruction": instruction_json(&forward.instruction),
+    });
+
+    // the first version of forward encoded a channel id in the same slot as the path
+    match u32::try_from(forward.path) {
+        Ok(channel_id) => value["channelId"] = channel_id.into(),
+        Err(_) => value["path"] = forward.path.to_be_hex_packed().into(),
+    }
 
-    Ok(value)
+    value
 }
 
 #[cfg(test)]
@@ -1,4 +1,4 @@
-use alloy_primitives::{keccak256, U256};
+use alloy_primitives::{keccak256, B256, U256};
 use alloy_sol_types::SolValue;
 use thiserror::Error;
 
@@ -8,6 +8,8 @@ mod copy;
 pub enum Create3Error {
     #[error("Failed to convert intermediate channel IDs to U256: invalid byte slice length")]
     InvalidChannelIdsLength,
+    #[error("Unknown fungible asset metadata type: {0}")]
+    UnknownMetadataType(u8),
 }
 
 pub fn create3_0_1(
@@ -29,6 +31,67 @@ pub fn create3_0_1(
     Ok(copy::predict_deterministic_address(deployer, &salt).into())
 }
 
+/// Metadata image that makes v0.2 deployments predict the wrapped token with the v0.1 salt.
+pub const FUNGIBLE_ASSET_METADATA_IMAGE_PREDICT_V1: [u8; 32] =
+    alloy_primitives::hex!("c0deeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+
+/// Metadata types of v2 fungible asset orders (`ZkgmLib.FUNGIBLE_ASSET_METADATA_TYPE_*`).
+pub const FUNGIBLE_ASSET_METADATA_TYPE_IMAGE: u8 = 0x00;
+pub const FUNGIBLE_ASSET_METADATA_TYPE_PREIMAGE: u8 = 0x01;
+pub const FUNGIBLE_ASSET_METADATA_TYPE_IMAGE_UNWRAP: u8 = 0x02;
+
+/// Metadata image that v0.2 deployments predict the wrapped token of a v2 order with. Image
+/// orders carry the image itself (`bytes32(order.metadata)`), preimage orders carry the encoded
+/// metadata, which is hashed.
+pub fn fungible_asset_metadata_image(
+    metadata_type: u8,
+    metadata: &[u8],
+) -> Result<[u8; 32], Create3Error> {
+    match metadata_type {
+        FUNGIBLE_ASSET_METADATA_TYPE_IMAGE | FUNGIBLE_ASSET_METADATA_TYPE_IMAGE_UNWRAP => {
+            // bytes to bytes32 conversion in solidity: truncated or right padded with zeros
+            let mut image = [0; 32];
+            let len = metadata.len().min(32);
+            image[..len].copy_from_slice(&metadata[..len]);
+            Ok(image)
+        }
+        FUNGIBLE_ASSET_METADATA_TYPE_PREIMAGE => Ok(keccak256(metadata).into()),
+        unknown => Err(Create3Error::UnknownMetadataType(unknown)),
+    }
+}
+
+/// Salt scheme of UCS03 v0.2 deployments, which additionally commits to the metadata image of the
+/// wrapped token: `keccak256(abi.encode(uint256 path, uint32 channel, bytes token, bytes32 image))`.
+pub fn create3_0_2(
+    intermediate_channel_ids: &[u8],
+    receiver_channel_id: u32,
+    original_token: &[u8],
+    metadata_image: &[u8; 32],
+    deployer: &[u8],
+) -> Result<Vec<u8>, Create3Error> {
+    if *metadata_image == FUNGIBLE_ASSET_METADATA_IMAGE_PREDICT_V1 {
+        return create3_0_1(
+            intermediate_channel_ids,
+            receiver_channel_id.into(),
+            original_token,
+            deployer,
+        );
+    }
+
+    let intermediate_channel_ids: U256 = U256::try_from_be_slice(intermediate_channel_ids)
+        .ok_or(Create3Error::InvalidChannelIdsLength)?;
+    let params = (
+        intermediate_channel_ids,
+        receiver_channel_id,
+        original_token,
+        B256::from(*metadata_image),
+    );
+    let encoded = params.abi_encode_params();
+    let salt = keccak256(encoded);
+
+    Ok(copy::predict_deterministic_address(deployer, &salt).into())
+}
+
 #[cfg(test)]
 // #[pg_schema]
 mod tests {
@@ -162,8 +225,146 @@ mod tests {
         let result = create3_0_1(&invalid_path, 1, wrapped_token, deployer);
 
         assert!(result.is_err());
-        match result.unwrap_err() {
-            Create3Error::InvalidChannelIdsLength => {}
-        }
+        assert!(matches!(
+            result.unwrap_err(),
+            Create3Error::InvalidChannelIdsLength
+        ));
+    }
+
+    #[test]
+    fn test_known_address_0_2() -> Result<(), Box<dyn std::error::Error>> {
+        let wrapped_token = hex::decode("779877A7B0D9E8603169DdbD7836e478b4624789")?;
+        let deployer = hex::decode("7b7872fec715c787a1be3f062adedc82b3b06144")?;
+        let metadata_image = keccak256(b"metadata");
+
+        let unwrapped_token = create3_0_2(&[], 5, &wrapped_token, &metadata_image, &deployer)?;
+
+        let expected = hex::decode("fd1ea878e56dc342e444808c57aa4756dfe08ffe")?;
+        assert_eq!(unwrapped_token, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_address_0_2() -> Result<(), Box<dyn std::error::Error>> {
+        let wrapped_token = hex::decode("dead77A7B0D9E8603169DdbD7836e478b4624789")?;
+        let deployer = hex::decode("7b7872fec715c787a1be3f062adedc82b3b06144")?;
+        let metadata_image = keccak256(b"metadata");
+
+        let unwrapped_token = create3_0_2(&[], 5, &wrapped_token, &metadata_image, &deployer)?;
+
+        let expected = hex::decode("8d927baaaaec92b148a6950c8e735d732f1b6ba5")?;
+        assert_eq!(unwrapped_token, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_deployer_0_2() -> Result<(), Box<dyn std::error::Error>> {
+        let wrapped_token = hex::decode("779877A7B0D9E8603169DdbD7836e478b4624789")?;
+        let deployer = hex::decode("dead72fec715c787a1be3f062adedc82b3b06144")?;
+        let metadata_image = keccak256(b"metadata");
+
+        let unwrapped_token = create3_0_2(&[], 5, &wrapped_token, &metadata_image, &deployer)?;
+
+        let expected = hex::decode("86593aee9b8fad72a79a237de12843c856e92fb6")?;
+        assert_eq!(unwrapped_token, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_intermediate_channel_ids_0_2() -> Result<(), Box<dyn std::error::Error>> {
+        let wrapped_token = hex::decode("779877A7B0D9E8603169DdbD7836e478b4624789")?;
+        let deployer = hex::decode("7b7872fec715c787a1be3f062adedc82b3b06144")?;
+        let metadata_image = keccak256(b"metadata");
+
+        let unwrapped_token = create3_0_2(&[1], 5, &wrapped_token, &metadata_image, &deployer)?;
+
+        let expected = hex::decode("f66cd77089094aec12e9301204bc644bfcf6d8bf")?;
+        assert_eq!(unwrapped_token, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_image_0_2() -> Result<(), Box<dyn std::error::Error>> {
+        let wrapped_token = hex::decode("779877A7B0D9E8603169DdbD7836e478b4624789")?;
+        let deployer = hex::decode("7b7872fec715c787a1be3f062adedc82b3b06144")?;
+
+        let unwrapped_token = create3_0_2(&[], 5, &wrapped_token, &[0x11; 32], &deployer)?;
+
+        let expected = hex::decode("e155668450581d9d9ebe0cd3a69e79eec802bea9")?;
+        assert_eq!(unwrapped_token, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_predict_v1_image_0_2() -> Result<(), Box<dyn std::error::Error>> {
+        let wrapped_token = hex::decode("779877A7B0D9E8603169DdbD7836e478b4624789")?;
+        let deployer = hex::decode("7b7872fec715c787a1be3f062adedc82b3b06144")?;
+
+        let unwrapped_token = create3_0_2(
+            &[],
+            5,
+            &wrapped_token,
+            &FUNGIBLE_ASSET_METADATA_IMAGE_PREDICT_V1,
+            &deployer,
+        )?;
+
+        assert_eq!(
+            unwrapped_token,
+            create3_0_1(&[], 5, &wrapped_token, &deployer)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_image() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            fungible_asset_metadata_image(FUNGIBLE_ASSET_METADATA_TYPE_IMAGE, &[0x11; 32])?,
+            [0x11; 32]
+        );
+        assert_eq!(
+            fungible_asset_metadata_image(
+                FUNGIBLE_ASSET_METADATA_TYPE_IMAGE_UNWRAP,
+                &FUNGIBLE_ASSET_METADATA_IMAGE_PREDICT_V1
+            )?,
+            FUNGIBLE_ASSET_METADATA_IMAGE_PREDICT_V1
+        );
+        assert_eq!(
+            fungible_asset_metadata_image(FUNGIBLE_ASSET_METADATA_TYPE_PREIMAGE, b"metadata")?,
+            keccak256(b"metadata")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_image_bytes32_conversion() -> Result<(), Box<dyn std::error::Error>> {
+        let mut short = [0; 32];
+        short[..2].copy_from_slice(&[0x11, 0x22]);
+
+        assert_eq!(
+            fungible_asset_metadata_image(FUNGIBLE_ASSET_METADATA_TYPE_IMAGE, &[0x11, 0x22])?,
+            short
+        );
+        assert_eq!(
+            fungible_asset_metadata_image(FUNGIBLE_ASSET_METADATA_TYPE_IMAGE, &[0x11; 40])?,
+            [0x11; 32]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_metadata_type() {
+        assert!(matches!(
+            fungible_asset_metadata_image(0x03, &[0x11; 32]),
+            Err(Create3Error::UnknownMetadataType(0x03))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_channel_ids_length_0_2() {
+        let invalid_path = vec![0u8; 33];
+
+        let result = create3_0_2(&invalid_path, 1, b"test", &[0x11; 32], b"test_deployer");
+
+        assert!(matches!(result, Err(Create3Error::InvalidChannelIdsLength)));
     }
 }
@@ -14,17 +14,19 @@ pub enum Instantiate2Error {
     SaltTooLong(usize),
 }
 
+/// Checksum of the code wrapped tokens are instantiated with, unless configured otherwise for the
+/// deployment. Based on the dummy contract.
+pub const DEFAULT_WRAPPER_CHECKSUM: [u8; 32] =
+    hex!("ec827349ed4c1fec5a9c3462ff7c979d4c40e7aa43b16ed34469d04ff835f2a1");
+
 /// copy from: https://docs.rs/cosmwasm-std/2.1.4/src/cosmwasm_std/addresses.rs.html#308-317
 pub fn instantiate2_0_1(
     intermediate_channel_ids: &[u8],
     receiver_channel_id: i64,
     original_token: &[u8],
+    checksum: &[u8; 32],
     creator: &[u8],
 ) -> Result<Vec<u8>, Instantiate2Error> {
-    // based on the dummy contract
-    const CHECKSUM: &[u8; 32] =
-        &hex!("ec827349ed4c1fec5a9c3462ff7c979d4c40e7aa43b16ed34469d04ff835f2a1");
-
     const MSG: &[u8] = b"";
 
     let intermediate_channel_ids: U256 = U256::try_from_be_slice(intermediate_channel_ids)
@@ -46,8 +48,8 @@ pub fn instantiate2_0_1(
 
     let mut key = Vec::<u8>::new();
     key.extend_from_slice(b"wasm\0");
-    key.extend_from_slice(&(CHECKSUM.len() as u64).to_be_bytes());
-    key.extend_from_slice(CHECKSUM);
+    key.extend_from_slice(&(checksum.len() as u64).to_be_bytes());
+    key.extend_from_slice(checksum);
     key.extend_from_slice(&(creator.len() as u64).to_be_bytes());
     key.extend_from_slice(creator);
     key.extend_from_slice(&(salt.len() as u64).to_be_bytes());
@@ -83,7 +85,13 @@ mod tests {
 
         let receiver_channel_id: i64 = 1;
 
-        let wrapped_token = instantiate2_0_1(&[], receiver_channel_id, &original_token, &deployer)?;
+        let wrapped_token = instantiate2_0_1(
+            &[],
+            receiver_channel_id,
+            &original_token,
+            &DEFAULT_WRAPPER_CHECKSUM,
+            &deployer,
+        )?;
 
         // bech32-decoded quote-token: union1surgyrm5xwfwughm6rfv76kd6vm2fc8vgpxxd6k6su6xsrxz0jgs7w967n
         // 0x8706820F743392EE22FBD0D2CF6ACDD336A4E0EC404C66EADA8734680CC27C91
@@ -93,6 +101,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_configured_checksum() -> Result<(), Box<dyn std::error::Error>> {
+        let original_token = hex::decode("685ce6742351ae9b618f383883d6d1e0c5a31b4b")?;
+        let deployer =
+            hex::decode("27F572027CC091A3A8E2B63D35CD19D61B3227CD06FEE95B13A1801A87AF2C32")?;
+
+        let wrapped_token = instantiate2_0_1(&[], 1, &original_token, &[0x11; 32], &deployer)?;
+
+        let expected =
+            hex::decode("D92B363F68E43D1705C5FD7D8EEDA2C33DEF71F047BAAB5DE62A77B5C1B6640B")?;
+        assert_eq!(wrapped_token, expected);
+        Ok(())
+    }
+
     #[test]
     fn test_u256_conversion() -> Result<(), Box<dyn std::error::Error>> {
         assert_decode_encode_equals("0x0")?;
@@ -127,7 +149,13 @@ mod tests {
         let original_token = b"test";
         let creator = b"test_creator";
 
-        let result = instantiate2_0_1(&invalid_path, 1, original_token, creator);
+        let result = instantiate2_0_1(
+            &invalid_path,
+            1,
+            original_token,
+            &DEFAULT_WRAPPER_CHECKSUM,
+            creator,
+        );
 
         assert!(result.is_err());
         match result.unwrap_err() {
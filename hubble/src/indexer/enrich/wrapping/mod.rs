@@ -8,11 +8,13 @@ use tracing::{debug, trace};
 use crate::indexer::{
     api::IndexerError,
     enrich::wrapping::{
-        create3::create3_0_1, instantiate2::instantiate2_0_1, osmosis::predict_osmosis_wrapper_0_1,
+        create3::{create3_0_1, create3_0_2, fungible_asset_metadata_image},
+        instantiate2::{instantiate2_0_1, DEFAULT_WRAPPER_CHECKSUM},
+        osmosis::predict_osmosis_wrapper_0_1,
         postgres::get_ibc_interface_and_ucs03,
     },
     event::types::{ChannelId, ContractAddress, Denom},
-    handler::types::WrapDirection,
+    handler::types::{string_0x_to_bytes, WrapDirection},
     record::InternalChainId,
 };
 
@@ -34,12 +36,17 @@ pub async fn wrap_direction_chains(
     destination_channel_id: &ChannelId,
     base_denom: &Denom,
     quote_denom: &Denom,
+    metadata_image: Option<&MetadataImage>,
 ) -> Result<Option<WrapDirection>, IndexerError> {
-    let (source_ibc_interface, source_contract_address_display, source_minter) =
+    let (source_ibc_interface, source_contract_address_display, source_minter, source_version) =
         &get_ibc_interface_and_ucs03(tx, internal_source_chain_id).await?;
 
-    let (destination_ibc_interface, destination_contract_address_display, destination_minter) =
-        &get_ibc_interface_and_ucs03(tx, internal_destination_chain_id).await?;
+    let (
+        destination_ibc_interface,
+        destination_contract_address_display,
+        destination_minter,
+        destination_version,
+    ) = &get_ibc_interface_and_ucs03(tx, internal_destination_chain_id).await?;
 
     wrap_direction_pure(
         source_ibc_interface,
@@ -49,10 +56,13 @@ pub async fn wrap_direction_chains(
         destination_channel_id,
         source_contract_address_display,
         source_minter,
+        source_version,
         destination_contract_address_display,
         destination_minter,
+        destination_version,
         base_denom,
         quote_denom,
+        metadata_image,
     )
 }
 
@@ -65,10 +75,13 @@ fn wrap_direction_pure(
     destination_channel_id: &ChannelId,
     source_contract_address_display: &ContractAddressDisplay,
     source_minter: &Option<Minter>,
+    source_version: &Ucs03Version,
     destination_contract_address_display: &ContractAddressDisplay,
     destination_minter: &Option<Minter>,
+    destination_version: &Ucs03Version,
     base_denom: &Denom,
     quote_denom: &Denom,
+    metadata_image: Option<&MetadataImage>,
 ) -> Result<Option<WrapDirection>, IndexerError> {
     Ok(
         if wrap_is_same_as_predict_wrapped_address(
@@ -79,6 +92,8 @@ fn wrap_direction_pure(
             quote_denom,
             destination_contract_address_display,
             destination_minter,
+            destination_version,
+            metadata_image,
         )? {
             Some(WrapDirection::Wrapping)
         } else if wrap_is_same_as_predict_wrapped_address(
@@ -89,6 +104,8 @@ fn wrap_direction_pure(
             base_denom,
             source_contract_address_display,
             source_minter,
+            source_version,
+            metadata_image,
         )? {
             Some(WrapDirection::Unwrapping)
         } else {
@@ -97,6 +114,7 @@ fn wrap_direction_pure(
     )
 }
 
+#[allow(clippy::too_many_arguments)] // migrating postgres code; will refactor later
 fn wrap_is_same_as_predict_wrapped_address(
     destination_ibc_interface: &IbcInterface,
     intermediate_channel_ids: &IntermediateChannelIds,
@@ -105,6 +123,8 @@ fn wrap_is_same_as_predict_wrapped_address(
     quote_denom: &Denom,
     contract_address_display: &ContractAddressDisplay,
     minter: &Option<Minter>,
+    version: &Ucs03Version,
+    metadata_image: Option<&MetadataImage>,
 ) -> Result<bool, IndexerError> {
     let (wrapped, quote) = match (destination_ibc_interface, minter) {
         (IbcInterface::IbcSolidity, _) => (
@@ -113,6 +133,8 @@ fn wrap_is_same_as_predict_wrapped_address(
                 receiver_channel_id,
                 base_denom,
                 &contract_address_display.to_contract_address_assume_hex()?,
+                version,
+                metadata_image,
             ),
             Ok(quote_denom.clone()), // evm wrapped address is a normal evm address
         ),
@@ -124,7 +146,7 @@ fn wrap_is_same_as_predict_wrapped_address(
                 minter,
             ),
             match minter {
-                Minter::Cw20(_) => quote_denom.to_bech32_decoded(), // cw20 wrapper is a bech32 encoded address => to canonical
+                Minter::Cw20(..) => quote_denom.to_bech32_decoded(), // cw20 wrapper is a bech32 encoded address => to canonical
                 Minter::OsmosisTokenfactory(_) => Ok(quote_denom.clone()), // osmosis wrapped address is something like 'factory/osmo13ulc6pq etc'
             },
         ),
@@ -148,13 +170,28 @@ fn wrap_evm(
     receiver_channel_id: &ChannelId,
     base_denom: &Denom,
     deployer: &ContractAddress,
+    version: &Ucs03Version,
+    metadata_image: Option<&MetadataImage>,
 ) -> Result<Denom, IndexerError> {
-    let result = create3_0_1(
-        &intermediate_channel_ids.0,
-        receiver_channel_id.0.into(),
-        base_denom.0.as_ref(),
-        deployer.0.as_ref(),
-    )
+    let result = match (version, metadata_image) {
+        // v0.2 deployments predict v2 orders with the metadata image of the order
+        // (`_predictWrappedTokenFromMetadataImageV2`)
+        (Ucs03Version::V0_2, Some(metadata_image)) => create3_0_2(
+            &intermediate_channel_ids.0,
+            receiver_channel_id.0,
+            base_denom.0.as_ref(),
+            &metadata_image.0,
+            deployer.0.as_ref(),
+        ),
+        // v0 and v1 orders are predicted with the v0.1 salt by both versions
+        // (`_predictWrappedToken`)
+        (Ucs03Version::V0_1 | Ucs03Version::V0_2, _) => create3_0_1(
+            &intermediate_channel_ids.0,
+            receiver_channel_id.0.into(),
+            base_denom.0.as_ref(),
+            deployer.0.as_ref(),
+        ),
+    }
     .map_err(|e| IndexerError::WrapperPredictionError("create3".to_string(), e.to_string()))?;
 
     Ok(bytes::Bytes::from(result).into())
@@ -167,10 +204,11 @@ fn wrap_cosmos(
     minter: &Minter,
 ) -> Result<Denom, IndexerError> {
     let result = match minter {
-        Minter::Cw20(minter_address_display) => instantiate2_0_1(
+        Minter::Cw20(minter_address_display, checksum) => instantiate2_0_1(
             &intermediate_channel_ids.0,
             receiver_channel_id.0.into(),
             base_denom.0.as_ref(),
+            &checksum.0,
             minter_address_display
                 .to_contract_address_assume_bech32()?
                 .0
@@ -228,11 +266,51 @@ pub enum IbcInterface {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Minter {
     #[serde(rename = "cw20")]
-    Cw20(ContractAddressDisplay),
+    Cw20(ContractAddressDisplay, WrapperChecksum),
     #[serde(rename = "osmosis_tokenfactory")]
     OsmosisTokenfactory(ContractAddressDisplay),
 }
 
+/// Version of the UCS03 deployment, which determines the salt scheme of wrapped tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ucs03Version {
+    V0_1,
+    V0_2,
+}
+
+/// Metadata image of a v2 fungible asset order, which v0.2 deployments commit to in the salt of
+/// the wrapped token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataImage(pub [u8; 32]);
+
+impl MetadataImage {
+    /// The image of an order, from its `metadataType` and (0x-prefixed) `metadata`.
+    pub fn from_order(metadata_type: u32, metadata: &str) -> Result<Self, IndexerError> {
+        let metadata_type = u8::try_from(metadata_type).map_err(|_| {
+            IndexerError::WrapperPredictionError(
+                "metadata-image".to_string(),
+                format!("metadata type out of range: {metadata_type}"),
+            )
+        })?;
+
+        fungible_asset_metadata_image(metadata_type, &string_0x_to_bytes(metadata, "metadata")?)
+            .map(Self)
+            .map_err(|e| {
+                IndexerError::WrapperPredictionError("metadata-image".to_string(), e.to_string())
+            })
+    }
+}
+
+/// Checksum of the code the cw20 minter instantiates wrapped tokens with (`instantiate2`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrapperChecksum(pub [u8; 32]);
+
+impl Default for WrapperChecksum {
+    fn default() -> Self {
+        Self(DEFAULT_WRAPPER_CHECKSUM)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContractAddressDisplay(pub String);
 
@@ -275,3 +353,61 @@ impl From<String> for ContractAddressDisplay {
         Self(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap_evm_0_2(metadata_image: Option<&MetadataImage>) -> Result<Denom, IndexerError> {
+        wrap_evm(
+            &IntermediateChannelIds(Bytes::new()),
+            &ChannelId(5),
+            &Bytes::from(hex::decode("779877A7B0D9E8603169DdbD7836e478b4624789").unwrap()).into(),
+            &Bytes::from(hex::decode("7b7872fec715c787a1be3f062adedc82b3b06144").unwrap()).into(),
+            &Ucs03Version::V0_2,
+            metadata_image,
+        )
+    }
+
+    #[test]
+    fn test_v1_order_on_0_2() -> Result<(), Box<dyn std::error::Error>> {
+        // same wrapped token as the v0.1 salt of `create3::tests::test_known_address`
+        assert_eq!(
+            wrap_evm_0_2(None)?,
+            Denom::from(Bytes::from(hex::decode(
+                "d1b482d1b947a96e96c9b76d15de34f7f70a20a1"
+            )?))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_v2_order_on_0_2() -> Result<(), Box<dyn std::error::Error>> {
+        let preimage = MetadataImage::from_order(0x01, "0x6d65746164617461")?; // "metadata"
+
+        // same wrapped token as `create3::tests::test_known_address_0_2`
+        assert_eq!(
+            wrap_evm_0_2(Some(&preimage))?,
+            Denom::from(Bytes::from(hex::decode(
+                "fd1ea878e56dc342e444808c57aa4756dfe08ffe"
+            )?))
+        );
+
+        let image = MetadataImage::from_order(0x00, &format!("0x{}", "11".repeat(32)))?;
+
+        // same wrapped token as `create3::tests::test_metadata_image_0_2`
+        assert_eq!(
+            wrap_evm_0_2(Some(&image))?,
+            Denom::from(Bytes::from(hex::decode(
+                "e155668450581d9d9ebe0cd3a69e79eec802bea9"
+            )?))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_metadata_type() {
+        assert!(MetadataImage::from_order(0x03, "0x").is_err());
+        assert!(MetadataImage::from_order(0x100, "0x").is_err());
+    }
+}
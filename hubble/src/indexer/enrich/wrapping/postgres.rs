@@ -2,14 +2,24 @@ use tracing::trace;
 
 use crate::indexer::{
     api::IndexerError,
-    enrich::wrapping::{ContractAddressDisplay, IbcInterface, Minter},
+    enrich::wrapping::{
+        ContractAddressDisplay, IbcInterface, Minter, Ucs03Version, WrapperChecksum,
+    },
     record::{InternalChainId, PgValue},
 };
 
 pub async fn get_ibc_interface_and_ucs03(
     tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     internal_chain_id: &InternalChainId,
-) -> Result<(IbcInterface, ContractAddressDisplay, Option<Minter>), IndexerError> {
+) -> Result<
+    (
+        IbcInterface,
+        ContractAddressDisplay,
+        Option<Minter>,
+        Ucs03Version,
+    ),
+    IndexerError,
+> {
     trace!("get_ibc_interface_and_ucs03: {internal_chain_id}");
 
     let ibc_interface = sqlx::query!(
@@ -38,9 +48,9 @@ pub async fn get_ibc_interface_and_ucs03(
         )
     })?;
 
-    let (contract_address_display, minter) = sqlx::query!(
+    let (contract_address_display, minter, version) = sqlx::query!(
         "
-            SELECT contract_address_display, minter_type, minter_address_display
+            SELECT contract_address_display, minter_type, minter_address_display, minter_code_checksum, contract_version
             FROM config.ucs03
             WHERE internal_chain_id = $1;
         ",
@@ -61,6 +71,18 @@ pub async fn get_ibc_interface_and_ucs03(
                         )
                     })?
                     .into(),
+                record
+                    .minter_code_checksum
+                    .map(|checksum| {
+                        checksum.try_into().map(WrapperChecksum).map_err(|checksum| {
+                            IndexerError::InternalCannotMapFromDatabaseDomain(
+                                "minter-code-checksum (cw20)".to_string(),
+                                hex::encode(checksum),
+                            )
+                        })
+                    })
+                    .transpose()?
+                    .unwrap_or_default(),
             ))),
             Some("osmosis_tokenfactory") => Ok(Some(Minter::OsmosisTokenfactory(
                 record
@@ -79,8 +101,16 @@ pub async fn get_ibc_interface_and_ucs03(
             )),
             None => Ok(None),
         }?;
+        let version = match record.contract_version.as_deref() {
+            None | Some("0.1") => Ok(Ucs03Version::V0_1),
+            Some("0.2") => Ok(Ucs03Version::V0_2),
+            Some(unsupported) => Err(IndexerError::InternalCannotMapFromDatabaseDomain(
+                "contract-version".to_string(),
+                unsupported.to_string(),
+            )),
+        }?;
 
-        Ok::<_, IndexerError>((contract_address_display, minter))
+        Ok::<_, IndexerError>((contract_address_display, minter, version))
     })
     .transpose()?
     .ok_or_else(|| {
@@ -90,5 +120,5 @@ pub async fn get_ibc_interface_and_ucs03(
         )
     })?;
 
-    Ok((ibc_interface, contract_address_display, minter))
+    Ok((ibc_interface, contract_address_display, minter, version))
 }
@@ -16,6 +16,7 @@ use crate::{
     github_client::GitCommitHash,
     indexer::{
         api::{AbiParsingError, IndexerError},
+        decoder_version::DecoderVersion,
         ethereum::log_parser::Parser,
         record::InternalChainId,
     },
@@ -27,6 +28,8 @@ pub struct Abi {
     pub description: String,
     pub definition: String,
     pub commit: GitCommitHash,
+    /// The version of the format of the events emitted by the contract at this height.
+    pub decoder_version: DecoderVersion,
 }
 
 impl Abi {
@@ -1,7 +1,10 @@
 use alloy::network::AnyRpcBlock;
 use axum::async_trait;
 use color_eyre::eyre::Report;
-use futures::{stream::FuturesOrdered, Stream};
+use futures::{
+    stream::{self, FuturesOrdered},
+    Stream, StreamExt, TryStreamExt,
+};
 use serde::{Deserialize, Serialize};
 use sqlx::Postgres;
 use time::OffsetDateTime;
@@ -9,7 +12,10 @@ use tracing::{debug, trace};
 
 use crate::{
     indexer::{
-        api::{BlockHandle, BlockRange, BlockReference, BlockSelection, FetchMode, IndexerError},
+        api::{
+            BlockHandle, BlockHash, BlockRange, BlockReference, BlockSelection, FetchMode,
+            IndexerError,
+        },
         ethereum::{
             fetcher_client::EthFetcherClient, mapping::legacy::get_legacy_events,
             provider::RpcProviderId,
@@ -56,6 +62,7 @@ pub struct EventInsert {
 #[derive(Clone)]
 pub struct EthBlockHandle {
     pub reference: BlockReference,
+    pub parent_hash: BlockHash,
     pub details: BlockDetails,
     pub eth_client: EthFetcherClient,
     pub provider_id: RpcProviderId,
@@ -80,6 +87,10 @@ impl BlockHandle for EthBlockHandle {
         self.reference.clone()
     }
 
+    fn parent_hash(&self) -> Option<BlockHash> {
+        Some(self.parent_hash.clone())
+    }
+
     fn fetch_range(
         &self,
         block_range: BlockRange,
@@ -87,8 +98,21 @@ impl BlockHandle for EthBlockHandle {
     ) -> Result<impl Stream<Item = Result<Self, IndexerError>>, IndexerError> {
         debug!("{}: fetching", block_range);
 
-        Ok(FuturesOrdered::from_iter(
-            block_range.clone().into_iter().map(|height| async move {
+        // blocks are only fetched by their logs when all of their details are needed, ie. not
+        // when only their references are checked by the finalizer
+        if let (Some(log_range), FetchMode::Eager) = (&self.eth_client.log_range, fetch_mode) {
+            return Ok(stream::once(async move {
+                self.eth_client
+                    .fetch_range_by_logs(log_range, block_range, self.provider_id)
+                    .await
+                    .map(|blocks| stream::iter(blocks.into_iter().map(Ok::<_, IndexerError>)))
+            })
+            .try_flatten()
+            .left_stream());
+        }
+
+        Ok(
+            FuturesOrdered::from_iter(block_range.clone().into_iter().map(|height| async move {
                 self.eth_client
                     .fetch_single_with_provider(
                         BlockSelection::Height(height),
@@ -96,8 +120,9 @@ impl BlockHandle for EthBlockHandle {
                         Some(self.provider_id),
                     )
                     .await
-            }),
-        ))
+            }))
+            .right_stream(),
+        )
     }
 
     async fn insert(
@@ -1,13 +1,20 @@
-use color_eyre::eyre::Report;
+use alloy::primitives::Address;
+use color_eyre::eyre::{eyre, Report};
 use sqlx::PgPool;
 use url::Url;
 
 use crate::indexer::{
     api::{BlockHeight, IndexerId},
-    ethereum::{context::EthContext, fetcher_client::EthFetcherClient},
+    contracts::{validate_contracts, ContractRegistration},
+    ethereum::{
+        context::{EthContext, Fetcher},
+        fetcher_client::EthFetcherClient,
+    },
     event::types::UniversalChainId,
     nats::NatsConnection,
-    ConsumerConfig, EnricherConfig, FinalizerConfig, FixerConfig, Indexer, PublisherConfig,
+    scheduler::FetchScheduler,
+    BackfillConfig, ConsumerConfig, EnricherConfig, Finality, FinalizerConfig, FixerConfig,
+    Indexer, PublisherConfig, RetrierConfig,
 };
 
 const DEFAULT_CHUNK_SIZE: usize = 200;
@@ -19,26 +26,89 @@ pub struct Config {
     pub start_height: BlockHeight,
     pub chunk_size: Option<usize>,
     pub rpc_urls: Vec<Url>,
+    /// How to determine the last finalized block, the block with the `finalized` tag if not set.
+    #[serde(default)]
+    pub finality: Finality,
+    /// How to fetch the logs while catching up, fetching every block if not set.
+    #[serde(default)]
+    pub fetcher: Fetcher,
     #[serde(default)]
     pub finalizer: FinalizerConfig,
     #[serde(default)]
     pub fixer: FixerConfig,
     #[serde(default)]
+    pub backfill: BackfillConfig,
+    #[serde(default)]
     pub publisher: PublisherConfig,
     #[serde(default)]
     pub consumer: ConsumerConfig,
     #[serde(default)]
     pub enricher: EnricherConfig,
     #[serde(default)]
+    pub retrier: RetrierConfig,
+    #[serde(default)]
     pub drain: bool,
+    /// Name of the database (see `--databases`) to write to, the default database if not set.
+    #[serde(default)]
+    pub database: Option<String>,
+    /// Size of a connection pool dedicated to this indexer, the shared pool of its database if not
+    /// set.
+    #[serde(default)]
+    pub max_connections: Option<u32>,
+    /// Contracts registered when the indexer starts, in addition to those registered in
+    /// `v2_evm.contracts`. The `contract_type` is the description of the contract.
+    #[serde(default)]
+    pub contracts: Vec<ContractRegistration>,
 }
 
 impl Config {
     pub async fn build(
-        self,
+        mut self,
         pg_pool: PgPool,
         nats: Option<NatsConnection>,
+        fetch_scheduler: FetchScheduler,
     ) -> Result<Indexer<EthFetcherClient>, Report> {
+        if let Fetcher::Logs {
+            min_range,
+            max_range,
+        } = self.fetcher
+        {
+            if min_range == 0 || min_range > max_range {
+                return Err(eyre!(
+                    "{}: invalid log range of the logs fetcher ({min_range}..={max_range})",
+                    self.indexer_id
+                ));
+            }
+        }
+
+        // addresses are registered in lower case hex, as they are formatted when looking up abis
+        for contract in &mut self.contracts {
+            contract.address = format!(
+                "{:#x}",
+                contract.address.parse::<Address>().map_err(|err| eyre!(
+                    "{}: invalid contract address {}: {err}",
+                    self.indexer_id,
+                    contract.address
+                ))?
+            );
+        }
+
+        // without a commit no abi is generated, so the logs of the contract would never be fetched
+        if let Some(contract) = self
+            .contracts
+            .iter()
+            .find(|contract| contract.commit.is_none())
+        {
+            return Err(eyre!(
+                "{}: contract {}: commit is required for evm contracts",
+                self.indexer_id,
+                contract.address
+            ));
+        }
+
+        validate_contracts(&self.contracts)
+            .map_err(|err| eyre!("{}: invalid contracts: {err}", self.indexer_id))?;
+
         Ok(Indexer::new(
             pg_pool,
             nats,
@@ -48,11 +118,17 @@ impl Config {
             self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
             self.finalizer,
             self.fixer,
+            self.backfill,
             self.publisher,
             self.consumer,
             self.enricher,
+            self.retrier,
+            fetch_scheduler,
             EthContext {
                 rpc_urls: self.rpc_urls,
+                finality: self.finality,
+                fetcher: self.fetcher,
+                contracts: self.contracts,
             },
             self.drain,
         ))
@@ -2,22 +2,72 @@ use std::fmt::Display;
 
 use url::Url;
 
+use crate::indexer::{contracts::ContractRegistration, Finality};
+
 #[derive(Clone)]
 pub struct EthContext {
     pub rpc_urls: Vec<Url>,
+    pub finality: Finality,
+    pub fetcher: Fetcher,
+    pub contracts: Vec<ContractRegistration>,
+}
+
+/// Determines how the logs of the registered contracts are fetched while catching up to the last
+/// finalized block. Blocks near the tip are always fetched one by one.
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", deny_unknown_fields)]
+pub enum Fetcher {
+    /// Fetch every block, and the logs of the blocks whose bloom filter matches any of the
+    /// registered contracts.
+    #[default]
+    Blocks,
+    /// Fetch the logs of a chunk with `eth_getLogs` over block ranges, and only fetch the blocks
+    /// that contain logs. The range is halved whenever the rpc rejects it (ie. because the
+    /// response is too large) and doubled after every successful request, between `min_range`
+    /// and `max_range` blocks.
+    Logs {
+        #[serde(default = "Fetcher::default_min_range")]
+        min_range: u64,
+        #[serde(default = "Fetcher::default_max_range")]
+        max_range: u64,
+    },
+}
+
+impl Fetcher {
+    fn default_min_range() -> u64 {
+        1
+    }
+
+    fn default_max_range() -> u64 {
+        10_000
+    }
+}
+
+impl Display for Fetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fetcher::Blocks => write!(f, "blocks"),
+            Fetcher::Logs {
+                min_range,
+                max_range,
+            } => write!(f, "logs: {min_range}..={max_range}"),
+        }
+    }
 }
 
 impl Display for EthContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "rpc_urls: {}",
+            "rpc_urls: {}, finality: {}, fetcher: {}",
             self.rpc_urls
                 .iter()
                 .enumerate()
                 .map(|(index, url)| format!("{}: {}", index, url.as_str()))
                 .collect::<Vec<_>>()
-                .join(", ")
+                .join(", "),
+            self.finality,
+            self.fetcher,
         )
     }
 }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display, sync::Arc};
 
 use alloy::{
     eips::BlockId,
@@ -17,20 +17,23 @@ use tracing::{debug, error, info, info_span, trace, warn, Instrument};
 use crate::{
     github_client::GitCommitHash,
     indexer::{
-        api::{BlockReference, BlockSelection, FetchMode, FetcherClient, IndexerError},
+        api::{BlockRange, BlockReference, BlockSelection, FetchMode, FetcherClient, IndexerError},
         ethereum::{
             abi::{AbiRegistration, GeneratedAbi},
             block_handle::{
                 BlockDetails, BlockInsert, EthBlockHandle, EventInsert, TransactionInsert,
             },
-            context::EthContext,
+            context::{EthContext, Fetcher},
+            log_fetcher::LogRange,
             mapping::legacy::ToLowerHex,
             postgres::{
-                ensure_abi_dependency, generated_abi, get_abi_registration, update_contract_abi,
+                ensure_abi_dependency, generated_abi, get_abi_registration,
+                get_contract_addresses_in_range, register_contracts, update_contract_abi,
             },
             provider::{Provider, RpcProviderId},
         },
         record::InternalChainId,
+        Finality,
     },
     postgres::{fetch_chain_id_tx, ChainId},
 };
@@ -57,6 +60,9 @@ pub struct EthFetcherClient {
     pub chain_id: ChainId,
     pub provider: Provider,
     pub transaction_filter: TransactionFilter,
+    pub finality: Finality,
+    /// Set when the blocks are fetched by their logs while catching up (see [`Fetcher::Logs`]).
+    pub log_range: Option<Arc<LogRange>>,
 }
 
 #[derive(Clone, Debug)]
@@ -77,6 +83,29 @@ impl TransactionFilter {
         .await
     }
 
+    pub(crate) async fn contract_addresses_in_range(
+        &self,
+        range: &BlockRange,
+    ) -> Result<Vec<Address>, IndexerError> {
+        let addresses = get_contract_addresses_in_range(
+            &mut self.pg_pool.begin().await?,
+            &self.chain_id.db.into(),
+            &range.start_inclusive.into(),
+            &range.end_exclusive.into(),
+        )
+        .await?;
+
+        for (address, _) in addresses.iter().filter(|(_, has_abi)| !has_abi) {
+            warn!("{range}: contract {address} is registered without an abi, its logs are not indexed until the abi of its commit is available");
+        }
+
+        Ok(addresses
+            .into_iter()
+            .filter_map(|(address, has_abi)| has_abi.then_some(address))
+            .unique()
+            .collect())
+    }
+
     pub(crate) async fn update_contract_abi(
         &self,
         internal_chain_id: &InternalChainId,
@@ -129,16 +158,14 @@ impl EthFetcherClient {
         mode: FetchMode,
         provider_id: Option<RpcProviderId>,
     ) -> Result<EthBlockHandle, IndexerError> {
+        let block_id = match selection {
+            BlockSelection::LastFinalized => self.last_finalized_block_id(provider_id).await?,
+            BlockSelection::Height(height) => BlockId::number(height),
+        };
+
         let block = self
             .provider
-            .get_block(
-                match selection {
-                    BlockSelection::LastFinalized => BlockId::finalized(),
-                    BlockSelection::Height(height) => BlockId::number(height),
-                },
-                BlockTransactionsKind::Full,
-                provider_id,
-            )
+            .get_block(block_id, BlockTransactionsKind::Full, provider_id)
             .await;
 
         match block {
@@ -152,6 +179,7 @@ impl EthFetcherClient {
 
                     Ok(EthBlockHandle {
                         reference: block.block_reference()?,
+                        parent_hash: block.header.parent_hash.to_lower_hex(),
                         details: match mode {
                             FetchMode::Lazy => BlockDetails::Lazy(block),
                             FetchMode::Eager => BlockDetails::Eager(
@@ -176,6 +204,25 @@ impl EthFetcherClient {
         }
     }
 
+    async fn last_finalized_block_id(
+        &self,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<BlockId, IndexerError> {
+        Ok(match self.finality {
+            Finality::Chain => BlockId::finalized(),
+            Finality::ConfirmationDepth { confirmation_depth } => {
+                let latest = self
+                    .provider
+                    .get_block_number(provider_id)
+                    .await
+                    .map_err(|err| IndexerError::ProviderError(Box::new(err.into())))?
+                    .response;
+
+                BlockId::number(latest.saturating_sub(confirmation_depth))
+            }
+        })
+    }
+
     pub async fn fetch_details(
         &self,
         block: &AnyRpcBlock,
@@ -226,9 +273,24 @@ impl EthFetcherClient {
             return Ok(None);
         }
 
+        self.block_insert(block, &abi_registration, &logs)
+            .await
+            .map(Some)
+    }
+
+    /// Convert the `logs` of the registered contracts in `block` into the [`BlockInsert`] of the
+    /// block.
+    pub async fn block_insert(
+        &self,
+        block: &AnyRpcBlock,
+        abi_registration: &AbiRegistration,
+        logs: &[Log],
+    ) -> Result<BlockInsert, IndexerError> {
+        let block_reference = block.block_reference()?;
+
         let events_by_transaction = {
             let mut map: HashMap<(_, _), Vec<Log>> = HashMap::with_capacity(logs.len());
-            for log in &logs {
+            for log in logs {
                 if log.removed {
                     continue;
                 }
@@ -287,7 +349,7 @@ impl EthFetcherClient {
         );
 
         // do ucs transformation
-        let ucs_events = match self.transform_logs_to_ucs_events(&abi_registration, block, &logs) {
+        let ucs_events = match self.transform_logs_to_ucs_events(abi_registration, block, logs) {
             Ok(events) => Ok(events),
             Err(IndexerError::AbiCannotParse(
                 err,
@@ -363,7 +425,7 @@ impl EthFetcherClient {
             ucs_events.len()
         );
 
-        Ok(Some(BlockInsert {
+        Ok(BlockInsert {
             chain_id: self.chain_id,
             hash: block_reference.hash,
             header: block.clone(),
@@ -371,7 +433,7 @@ impl EthFetcherClient {
             time: block_reference.timestamp,
             transactions,
             ucs_events,
-        }))
+        })
     }
 }
 
@@ -386,6 +448,14 @@ impl FetcherClient for EthFetcherClient {
         context: EthContext,
     ) -> Result<Self, IndexerError> {
         let provider = Provider::new(context.rpc_urls);
+        let finality = context.finality;
+        let log_range = match context.fetcher {
+            Fetcher::Blocks => None,
+            Fetcher::Logs {
+                min_range,
+                max_range,
+            } => Some(Arc::new(LogRange::new(min_range, max_range))),
+        };
 
         info!("fetching chain-id from node");
         let chain_id = provider.get_chain_id(None).await?.response;
@@ -398,6 +468,12 @@ impl FetcherClient for EthFetcherClient {
             let chain_id = fetch_chain_id_tx(&mut tx, chain_id.to_string()).await?;
             info!("fetched chain-id from database: {}", chain_id);
 
+            register_contracts(&mut tx, &chain_id.db.into(), &context.contracts).await?;
+            info!(
+                "registered {} contracts from config",
+                context.contracts.len()
+            );
+
             tx.commit().await?;
 
             let transaction_filter = TransactionFilter { chain_id, pg_pool };
@@ -406,6 +482,8 @@ impl FetcherClient for EthFetcherClient {
                 chain_id,
                 provider,
                 transaction_filter,
+                finality,
+                log_range,
             })
         }
         .instrument(indexing_span)
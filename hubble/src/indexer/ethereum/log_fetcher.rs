@@ -0,0 +1,331 @@
+use std::{
+    collections::BTreeMap,
+    iter::once,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use alloy::{
+    eips::BlockId,
+    network::AnyRpcBlock,
+    rpc::types::{BlockTransactionsKind, Filter, Log},
+    transports::{RpcError, TransportErrorKind},
+};
+use alloy_primitives::Address;
+use color_eyre::eyre::eyre;
+use futures::{stream::FuturesOrdered, TryStreamExt};
+use itertools::Itertools;
+use tracing::{debug, info};
+
+use crate::indexer::{
+    api::{BlockHeight, BlockRange, BlockReference, BlockSelection, IndexerError},
+    ethereum::{
+        block_handle::{BlockDetails, EthBlockHandle},
+        fetcher_client::{BlockReferenceProvider, EthFetcherClient},
+        mapping::legacy::ToLowerHex,
+        provider::RpcProviderId,
+    },
+};
+
+/// JSON-RPC error code used by most rpcs when a request exceeds one of their limits.
+const LIMIT_EXCEEDED_ERROR_CODE: i64 = -32005;
+
+/// The size of the block ranges requested with `eth_getLogs`. Shared by all clones of the fetcher
+/// client, so that the size that the rpc accepts is only found once.
+#[derive(Debug)]
+pub struct LogRange {
+    size: AtomicU64,
+    min: u64,
+    max: u64,
+}
+
+impl LogRange {
+    pub fn new(min: u64, max: u64) -> Self {
+        Self {
+            size: AtomicU64::new(max),
+            min,
+            max,
+        }
+    }
+
+    fn size(&self) -> u64 {
+        self.size.load(Ordering::Relaxed)
+    }
+
+    /// Halve the range after a request of `size` blocks was rejected. Returns `false` if the range
+    /// cannot be shrunk any further.
+    fn shrink(&self, size: u64) -> bool {
+        if size <= self.min {
+            return false;
+        }
+
+        self.size.store((size / 2).max(self.min), Ordering::Relaxed);
+
+        true
+    }
+
+    /// Double the range after a request of `size` blocks succeeded.
+    fn grow(&self, size: u64) {
+        self.size
+            .store(size.saturating_mul(2).min(self.max), Ordering::Relaxed);
+    }
+}
+
+/// Parts of the error messages with which rpcs reject `eth_getLogs` requests because of the size of
+/// their range or response, in lower case.
+const RANGE_REJECTED_MESSAGES: [&str; 7] = [
+    // geth, infura: "query returned more than 10000 results"
+    "query returned more than",
+    // alchemy, ankr, drpc: "up to a 2K block range", "block range is too wide", "exceed maximum
+    // block range"
+    "block range",
+    // alchemy: "log response size exceeded"
+    "response size exceeded",
+    // erigon, nethermind: "range is too large", "range too large"
+    "range is too large",
+    "range too large",
+    // quicknode: "eth_getLogs is limited to a 10,000 range"
+    "is limited to a",
+    // blast, publicnode: "query timeout exceeded"
+    "query timeout",
+];
+
+/// Whether the rpc rejected a `eth_getLogs` request because of the size of its range or response,
+/// such that a smaller range might succeed.
+fn is_range_rejected(err: &RpcError<TransportErrorKind>) -> bool {
+    match err {
+        RpcError::ErrorResp(payload) => {
+            payload.code == LIMIT_EXCEEDED_ERROR_CODE || is_range_rejected_message(&payload.message)
+        }
+        RpcError::Transport(TransportErrorKind::HttpError(err)) => {
+            // payload too large, gateway timeout
+            err.status == 413 || err.status == 504
+        }
+        _ => false,
+    }
+}
+
+fn is_range_rejected_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+
+    RANGE_REJECTED_MESSAGES
+        .into_iter()
+        .any(|needle| message.contains(needle))
+}
+
+impl EthFetcherClient {
+    /// Fetch the blocks in `range`, requesting the logs of the registered contracts with
+    /// `eth_getLogs` and only fetching the blocks that contain any of these logs, as well as the
+    /// last block of the range.
+    ///
+    /// The other blocks are not fetched at all, since they have nothing to insert. Their
+    /// references carry no hash, and the timestamp of the next fetched block (which is only used
+    /// for the status of the indexer).
+    pub async fn fetch_range_by_logs(
+        &self,
+        log_range: &LogRange,
+        range: BlockRange,
+        provider_id: RpcProviderId,
+    ) -> Result<Vec<EthBlockHandle>, IndexerError> {
+        let addresses = self
+            .transaction_filter
+            .contract_addresses_in_range(&range)
+            .await?;
+
+        let mut logs_by_height = BTreeMap::<BlockHeight, Vec<Log>>::new();
+
+        for log in self
+            .get_logs_in_range(log_range, &range, addresses, provider_id)
+            .await?
+            .into_iter()
+            .filter(|log| !log.removed)
+        {
+            // pending logs have no block number, which rpcs should never return for a range of
+            // mined blocks
+            let height = log.block_number.ok_or_else(|| {
+                IndexerError::ProviderError(Box::new(eyre!(
+                    "{}: log {:?} of transaction {:?} has no block number",
+                    range,
+                    log.log_index,
+                    log.transaction_hash
+                )))
+            })?;
+
+            logs_by_height.entry(height).or_default().push(log);
+        }
+
+        info!("{}: fetched logs (blocks: {})", range, logs_by_height.len());
+
+        let last_height = range.end_exclusive - 1;
+
+        let mut blocks = logs_by_height
+            .keys()
+            .copied()
+            .filter(|height| range.contains(*height))
+            .chain(once(last_height))
+            .dedup()
+            .map(|height| async move {
+                let block = self
+                    .provider
+                    .get_block(
+                        BlockId::number(height),
+                        BlockTransactionsKind::Full,
+                        Some(provider_id),
+                    )
+                    .await
+                    .map_err(|err| IndexerError::ProviderError(Box::new(err.into())))?
+                    .ok_or(IndexerError::NoBlock(BlockSelection::Height(height)))?
+                    .response;
+
+                Ok::<_, IndexerError>((height, block))
+            })
+            .collect::<FuturesOrdered<_>>()
+            .try_collect::<BTreeMap<_, _>>()
+            .await?;
+
+        let mut handles = Vec::with_capacity(range.len().try_into().unwrap());
+        let mut next_reference: Option<BlockReference> = None;
+
+        // in reverse, to know the timestamp of the next fetched block
+        for height in range.clone().into_iter().rev() {
+            let handle = match blocks.remove(&height) {
+                Some(block) => {
+                    let logs = logs_by_height.get(&height).map_or(&[][..], Vec::as_slice);
+
+                    self.handle_from_logs(block, logs, provider_id).await?
+                }
+                None => {
+                    let next = next_reference
+                        .as_ref()
+                        .expect("the last block of the range is always fetched");
+
+                    EthBlockHandle {
+                        reference: BlockReference::new(height, String::new(), next.timestamp),
+                        parent_hash: String::new(),
+                        details: BlockDetails::Eager(None),
+                        eth_client: self.clone(),
+                        provider_id,
+                    }
+                }
+            };
+
+            next_reference = Some(handle.reference.clone());
+            handles.push(handle);
+        }
+
+        handles.reverse();
+
+        Ok(handles)
+    }
+
+    async fn handle_from_logs(
+        &self,
+        block: AnyRpcBlock,
+        logs: &[Log],
+        provider_id: RpcProviderId,
+    ) -> Result<EthBlockHandle, IndexerError> {
+        let reference = block.block_reference()?;
+
+        // the logs were requested for all contracts registered anywhere in the range
+        let abi_registration = self
+            .transaction_filter
+            .abi_registration_at(&reference.height.into())
+            .await?;
+
+        let logs = logs
+            .iter()
+            .filter(|log| {
+                abi_registration
+                    .get_abi_for_address(&log.address())
+                    .is_some()
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let block_insert = match logs.is_empty() {
+            true => None,
+            false => Some(self.block_insert(&block, &abi_registration, &logs).await?),
+        };
+
+        Ok(EthBlockHandle {
+            reference,
+            parent_hash: block.header.parent_hash.to_lower_hex(),
+            details: BlockDetails::Eager(block_insert),
+            eth_client: self.clone(),
+            provider_id,
+        })
+    }
+
+    /// Fetch the logs of `addresses` in `range`, in requests of at most the current size of
+    /// `log_range` blocks each, adapting the size to the responses of the rpc.
+    async fn get_logs_in_range(
+        &self,
+        log_range: &LogRange,
+        range: &BlockRange,
+        addresses: Vec<Address>,
+        provider_id: RpcProviderId,
+    ) -> Result<Vec<Log>, IndexerError> {
+        // a filter without addresses matches the logs of all contracts
+        if addresses.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut logs = vec![];
+        let mut from = range.start_inclusive;
+
+        while from < range.end_exclusive {
+            let size = log_range.size();
+            let to = (from + size).min(range.end_exclusive) - 1;
+
+            let filter = Filter::new()
+                .from_block(from)
+                .to_block(to)
+                .address(addresses.clone());
+
+            match self.provider.get_logs(&filter, Some(provider_id)).await {
+                Ok(result) => {
+                    debug!("{from}..={to}: fetched logs ({})", result.response.len());
+
+                    logs.extend(result.response);
+                    log_range.grow(size);
+
+                    from = to + 1;
+                }
+                Err(err) if is_range_rejected(&err) && log_range.shrink(size) => {
+                    info!(
+                        "{from}..={to}: range rejected ({err}) => shrinking to {} blocks",
+                        log_range.size()
+                    );
+                }
+                Err(err) => return Err(IndexerError::ProviderError(Box::new(err.into()))),
+            }
+        }
+
+        Ok(logs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_rejected_messages() {
+        assert!(is_range_rejected_message(
+            "query returned more than 10000 results"
+        ));
+        assert!(is_range_rejected_message(
+            "Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range"
+        ));
+        assert!(is_range_rejected_message(
+            "eth_getLogs is limited to a 10,000 range"
+        ));
+    }
+
+    #[test]
+    fn other_messages_are_not_range_rejected() {
+        assert!(!is_range_rejected_message("rate limit exceeded"));
+        assert!(!is_range_rejected_message("request timed out"));
+        assert!(!is_range_rejected_message("block out of range"));
+        assert!(!is_range_rejected_message("invalid params"));
+    }
+}
@@ -1,6 +1,11 @@
 use std::{collections::HashMap, fmt::Display};
 
-use alloy::{dyn_abi::DynSolValue, network::AnyRpcBlock, primitives::FixedBytes, rpc::types::Log};
+use alloy::{
+    dyn_abi::DynSolValue,
+    network::{AnyRpcBlock, AnyRpcTransaction, TransactionResponse},
+    primitives::{Address, FixedBytes},
+    rpc::types::Log,
+};
 use bytes::Bytes;
 use itertools::Itertools;
 use ruint::aliases::U256;
@@ -10,13 +15,15 @@ use tracing::trace;
 use crate::{
     indexer::{
         api::IndexerError,
+        decoder_version::DecoderVersion,
         ethereum::abi::SolEvent,
         event::{
             header::Header,
             types::{
                 Acknowledgement, BlockHash, BlockHeight, CanonicalChainId, Capacity, ChannelId,
-                ChannelVersion, ClientId, ClientType, ConnectionId, Denom, Maker, MakerMsg,
-                PacketData, PacketHash, PortId, RefillRate, TimeoutTimestamp, TransactionHash,
+                ChannelVersion, ClientId, ClientType, ConnectionId, ContractAddress, Denom, Maker,
+                MakerMsg, PacketData, PacketHash, PortId, ProposalDelay, ProposalId, RefillRate,
+                TimeoutTimestamp, TransactionHash,
             },
         },
     },
@@ -29,6 +36,8 @@ pub struct Decoder<'a> {
     pub log: &'a Log,
     pub transaction_log_index: usize,
     pub event: &'a SolEvent,
+    /// The version of the format of the event, registered for the contract that emitted it.
+    pub decoder_version: DecoderVersion,
 }
 
 impl<'a> Display for Decoder<'a> {
@@ -38,6 +47,16 @@ impl<'a> Display for Decoder<'a> {
 }
 
 impl<'a> Decoder<'a> {
+    /// The transaction that emitted the log.
+    pub fn transaction(&self) -> Option<&'a AnyRpcTransaction> {
+        let transaction_hash = self.log.transaction_hash?;
+
+        self.block
+            .transactions
+            .txns()
+            .find(|transaction| transaction.tx_hash() == transaction_hash)
+    }
+
     pub fn header(&'a self) -> Result<Header, IndexerError> {
         Ok(Header {
             universal_chain_id: self.chain_id.universal_chain_id.to_string().into(),
@@ -96,10 +115,15 @@ impl<'a> Decoder<'a> {
                 })?
                 .into(),
             transaction_event_index: Some(self.transaction_log_index.try_into()?),
+            decoder_version: self.decoder_version,
         })
     }
 }
 
+pub fn address_bytes(address: Address) -> Bytes {
+    Bytes::copy_from_slice(address.as_slice())
+}
+
 impl From<FixedBytes<32>> for BlockHash {
     fn from(value: FixedBytes<32>) -> Self {
         Bytes::copy_from_slice(value.as_slice()).into()
@@ -229,6 +253,41 @@ impl SolEvent {
         self.get_packet("packet")
     }
 
+    /// operation id of a timelock call
+    pub fn operation_id(&self) -> Result<ProposalId, IndexerError> {
+        Ok(format!("0x{}", hex::encode(self.get_bytes("id", "operation-id")?)).into())
+    }
+
+    pub fn target(&self) -> Result<ContractAddress, IndexerError> {
+        Ok(self.get_bytes("target", "target")?.into())
+    }
+
+    /// function selector of the calldata of a timelock call (empty for plain transfers)
+    pub fn call_selector(&self) -> Result<Option<String>, IndexerError> {
+        let data = self.get_bytes("data", "calldata")?;
+
+        Ok(data
+            .get(..4)
+            .map(|selector| format!("0x{}", hex::encode(selector))))
+    }
+
+    pub fn delay(&self) -> Result<ProposalDelay, IndexerError> {
+        const EXPECTING: &str = "delay";
+
+        let delay = self.get_u256("delay", EXPECTING)?;
+
+        Ok(u64::try_from(delay)
+            .map_err(|_| {
+                IndexerError::CannotMapToEventDomainOutOfRange(
+                    self.name.to_string(),
+                    "delay".to_string(),
+                    delay.to_string(),
+                    "u64".to_string(),
+                )
+            })?
+            .into())
+    }
+
     fn get_height(&self, key: &str) -> Result<BlockHeight, IndexerError> {
         Ok(self.get_u64(key, "height")?.into())
     }
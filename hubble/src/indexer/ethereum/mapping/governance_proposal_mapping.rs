@@ -0,0 +1,54 @@
+use tracing::trace;
+
+use crate::indexer::{
+    api::IndexerError,
+    ethereum::{fetcher_client::EthFetcherClient, mapping::decoder::Decoder},
+    event::{
+        governance_proposal_event::GovernanceProposalEvent, supported::SupportedBlockEvent,
+        types::ProposalAction,
+    },
+};
+
+impl EthFetcherClient {
+    /// Maps the events of a (registered) openzeppelin `TimelockController`. Timelocks are only
+    /// registered when they govern bridge contracts, so all of their calls are indexed.
+    pub fn to_governance_proposal(
+        &self,
+        decoder: &Decoder,
+    ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
+        trace!("to_governance_proposal - {decoder}");
+
+        let (action, message_types, target_contract_canonical, delay) =
+            match decoder.event.name.as_str() {
+                "CallScheduled" => (
+                    ProposalAction::Scheduled,
+                    decoder.event.call_selector()?.into_iter().collect(),
+                    Some(decoder.event.target()?),
+                    Some(decoder.event.delay()?),
+                ),
+                "CallExecuted" => (
+                    ProposalAction::Executed,
+                    decoder.event.call_selector()?.into_iter().collect(),
+                    Some(decoder.event.target()?),
+                    None,
+                ),
+                "Cancelled" => (ProposalAction::Cancelled, vec![], None, None),
+                unsupported => {
+                    trace!("to_governance_proposal - {decoder} => ignore ({unsupported})");
+
+                    return Ok(vec![]);
+                }
+            };
+
+        Ok(vec![SupportedBlockEvent::GovernanceProposal {
+            inner: GovernanceProposalEvent {
+                header: decoder.header()?,
+                proposal_id: decoder.event.operation_id()?,
+                action,
+                message_types,
+                target_contract_canonical,
+                delay,
+            },
+        }])
+    }
+}
@@ -0,0 +1,401 @@
+use alloy::primitives::Address;
+use alloy_sol_types::{sol, SolCall};
+
+sol! {
+    // source: eth-infinitism/account-abstraction v0.6 EntryPoint
+    interface EntryPointV06 {
+        struct UserOperation {
+            address sender;
+            uint256 nonce;
+            bytes initCode;
+            bytes callData;
+            uint256 callGasLimit;
+            uint256 verificationGasLimit;
+            uint256 preVerificationGas;
+            uint256 maxFeePerGas;
+            uint256 maxPriorityFeePerGas;
+            bytes paymasterAndData;
+            bytes signature;
+        }
+
+        function handleOps(UserOperation[] calldata ops, address payable beneficiary);
+    }
+
+    // source: eth-infinitism/account-abstraction v0.7 EntryPoint
+    interface EntryPointV07 {
+        struct PackedUserOperation {
+            address sender;
+            uint256 nonce;
+            bytes initCode;
+            bytes callData;
+            bytes32 accountGasLimits;
+            uint256 preVerificationGas;
+            bytes32 gasFees;
+            bytes paymasterAndData;
+            bytes signature;
+        }
+
+        function handleOps(PackedUserOperation[] calldata ops, address payable beneficiary);
+    }
+
+    // source: safe-global/safe-smart-account Safe
+    interface Safe {
+        function execTransaction(
+            address to,
+            uint256 value,
+            bytes calldata data,
+            uint8 operation,
+            uint256 safeTxGas,
+            uint256 baseGas,
+            uint256 gasPrice,
+            address gasToken,
+            address payable refundReceiver,
+            bytes memory signatures
+        ) external payable returns (bool success);
+    }
+
+    // source: mds1/multicall Multicall3
+    interface Multicall3 {
+        struct Call {
+            address target;
+            bytes callData;
+        }
+
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Call3Value {
+            address target;
+            bool allowFailure;
+            uint256 value;
+            bytes callData;
+        }
+
+        function aggregate(Call[] calldata calls);
+        function tryAggregate(bool requireSuccess, Call[] calldata calls);
+        function aggregate3(Call3[] calldata calls);
+        function aggregate3Value(Call3Value[] calldata calls);
+    }
+
+    // source: Uniswap/v3-periphery IMulticall, the batching pattern of most routers
+    interface SelfMulticall {
+        function multicall(bytes[] calldata data);
+        function multicall(uint256 deadline, bytes[] calldata data);
+    }
+
+    // source: OpenZeppelin/openzeppelin-contracts MinimalForwarder (v4) and ERC2771Forwarder (v5)
+    interface MinimalForwarder {
+        struct ForwardRequest {
+            address from;
+            address to;
+            uint256 value;
+            uint256 gas;
+            uint256 nonce;
+            bytes data;
+        }
+
+        function execute(ForwardRequest calldata req, bytes calldata signature);
+    }
+
+    interface ERC2771Forwarder {
+        struct ForwardRequestData {
+            address from;
+            address to;
+            uint256 value;
+            uint256 gas;
+            uint48 deadline;
+            bytes data;
+            bytes signature;
+        }
+
+        function execute(ForwardRequestData calldata request);
+    }
+}
+
+/// Maximum nesting of multicalls and forwarded calls that is decoded.
+const MAX_DEPTH: usize = 4;
+
+/// Resolves the account on whose behalf a transaction was executed:
+/// - erc-4337 bundles: the smart account (sender) of the user operations
+/// - safe transactions: the safe
+/// - erc-2771 forwarders: the signer of the forwarded request
+/// - multicalls (multicall3 and the `multicall(bytes[])` of routers): the initiator of the batched
+///   calls, decoded up to `MAX_DEPTH` levels deep
+/// - anything else: the transaction sender
+///
+/// Returns `None` if the initiator is ambiguous, i.e. a bundle of user operations of multiple
+/// smart accounts, or a multicall batching calls of multiple initiators.
+pub fn resolve_initiator(sender: Address, to: Option<Address>, input: &[u8]) -> Option<Address> {
+    single(initiators(sender, to, input, MAX_DEPTH).into_iter())
+}
+
+/// The initiators of the calls made by `sender` to `to` with `input`, one for every (nested)
+/// call.
+fn initiators(sender: Address, to: Option<Address>, input: &[u8], depth: usize) -> Vec<Address> {
+    if depth == 0 {
+        return vec![sender];
+    }
+
+    let nested = |calls: Vec<(Option<Address>, &[u8])>| -> Vec<Address> {
+        calls
+            .into_iter()
+            .flat_map(|(to, input)| initiators(sender, to, input, depth - 1))
+            .collect()
+    };
+
+    if let Ok(call) = EntryPointV07::handleOpsCall::abi_decode(input) {
+        return call.ops.iter().map(|op| op.sender).collect();
+    }
+
+    if let Ok(call) = EntryPointV06::handleOpsCall::abi_decode(input) {
+        return call.ops.iter().map(|op| op.sender).collect();
+    }
+
+    if let (Some(safe), Ok(_)) = (to, Safe::execTransactionCall::abi_decode(input)) {
+        return vec![safe];
+    }
+
+    if let Ok(call) = MinimalForwarder::executeCall::abi_decode(input) {
+        return initiators(call.req.from, Some(call.req.to), &call.req.data, depth - 1);
+    }
+
+    if let Ok(call) = ERC2771Forwarder::executeCall::abi_decode(input) {
+        return initiators(
+            call.request.from,
+            Some(call.request.to),
+            &call.request.data,
+            depth - 1,
+        );
+    }
+
+    if let Ok(call) = Multicall3::aggregateCall::abi_decode(input) {
+        return nested(
+            call.calls
+                .iter()
+                .map(|call| (Some(call.target), &call.callData[..]))
+                .collect(),
+        );
+    }
+
+    if let Ok(call) = Multicall3::tryAggregateCall::abi_decode(input) {
+        return nested(
+            call.calls
+                .iter()
+                .map(|call| (Some(call.target), &call.callData[..]))
+                .collect(),
+        );
+    }
+
+    if let Ok(call) = Multicall3::aggregate3Call::abi_decode(input) {
+        return nested(
+            call.calls
+                .iter()
+                .map(|call| (Some(call.target), &call.callData[..]))
+                .collect(),
+        );
+    }
+
+    if let Ok(call) = Multicall3::aggregate3ValueCall::abi_decode(input) {
+        return nested(
+            call.calls
+                .iter()
+                .map(|call| (Some(call.target), &call.callData[..]))
+                .collect(),
+        );
+    }
+
+    // routers batch calls to themselves
+    if let Ok(call) = SelfMulticall::multicall_0Call::abi_decode(input) {
+        return nested(call.data.iter().map(|input| (to, &input[..])).collect());
+    }
+
+    if let Ok(call) = SelfMulticall::multicall_1Call::abi_decode(input) {
+        return nested(call.data.iter().map(|input| (to, &input[..])).collect());
+    }
+
+    vec![sender]
+}
+
+fn single(mut senders: impl Iterator<Item = Address>) -> Option<Address> {
+    let first = senders.next()?;
+
+    senders.all(|sender| sender == first).then_some(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{address, Bytes, FixedBytes, U256};
+
+    use super::*;
+
+    const EOA: Address = address!("0x1111111111111111111111111111111111111111");
+    const TARGET: Address = address!("0x2222222222222222222222222222222222222222");
+    const ACCOUNT_A: Address = address!("0x3333333333333333333333333333333333333333");
+    const ACCOUNT_B: Address = address!("0x4444444444444444444444444444444444444444");
+
+    fn packed_user_operation(sender: Address) -> EntryPointV07::PackedUserOperation {
+        EntryPointV07::PackedUserOperation {
+            sender,
+            nonce: U256::ZERO,
+            initCode: Bytes::new(),
+            callData: Bytes::new(),
+            accountGasLimits: FixedBytes::ZERO,
+            preVerificationGas: U256::ZERO,
+            gasFees: FixedBytes::ZERO,
+            paymasterAndData: Bytes::new(),
+            signature: Bytes::new(),
+        }
+    }
+
+    fn user_operation(sender: Address) -> EntryPointV06::UserOperation {
+        EntryPointV06::UserOperation {
+            sender,
+            nonce: U256::ZERO,
+            initCode: Bytes::new(),
+            callData: Bytes::new(),
+            callGasLimit: U256::ZERO,
+            verificationGasLimit: U256::ZERO,
+            preVerificationGas: U256::ZERO,
+            maxFeePerGas: U256::ZERO,
+            maxPriorityFeePerGas: U256::ZERO,
+            paymasterAndData: Bytes::new(),
+            signature: Bytes::new(),
+        }
+    }
+
+    #[test]
+    fn plain_call() {
+        assert_eq!(
+            resolve_initiator(EOA, Some(TARGET), &[0xde, 0xad, 0xbe, 0xef]),
+            Some(EOA)
+        );
+        assert_eq!(resolve_initiator(EOA, None, &[]), Some(EOA));
+    }
+
+    #[test]
+    fn entry_point_v07() {
+        let input = EntryPointV07::handleOpsCall {
+            ops: vec![packed_user_operation(ACCOUNT_A)],
+            beneficiary: EOA,
+        }
+        .abi_encode();
+
+        assert_eq!(
+            resolve_initiator(EOA, Some(TARGET), &input),
+            Some(ACCOUNT_A)
+        );
+    }
+
+    #[test]
+    fn entry_point_v06() {
+        let input = EntryPointV06::handleOpsCall {
+            ops: vec![user_operation(ACCOUNT_A), user_operation(ACCOUNT_A)],
+            beneficiary: EOA,
+        }
+        .abi_encode();
+
+        assert_eq!(
+            resolve_initiator(EOA, Some(TARGET), &input),
+            Some(ACCOUNT_A)
+        );
+    }
+
+    #[test]
+    fn entry_point_ambiguous() {
+        let input = EntryPointV07::handleOpsCall {
+            ops: vec![
+                packed_user_operation(ACCOUNT_A),
+                packed_user_operation(ACCOUNT_B),
+            ],
+            beneficiary: EOA,
+        }
+        .abi_encode();
+
+        assert_eq!(resolve_initiator(EOA, Some(TARGET), &input), None);
+    }
+
+    fn safe_call() -> Vec<u8> {
+        Safe::execTransactionCall {
+            to: TARGET,
+            value: U256::ZERO,
+            data: Bytes::new(),
+            operation: 0,
+            safeTxGas: U256::ZERO,
+            baseGas: U256::ZERO,
+            gasPrice: U256::ZERO,
+            gasToken: Address::ZERO,
+            refundReceiver: Address::ZERO,
+            signatures: Bytes::new(),
+        }
+        .abi_encode()
+    }
+
+    #[test]
+    fn safe() {
+        assert_eq!(
+            resolve_initiator(EOA, Some(ACCOUNT_A), &safe_call()),
+            Some(ACCOUNT_A)
+        );
+    }
+
+    #[test]
+    fn multicall() {
+        let multicall = |targets: &[Address]| {
+            Multicall3::aggregate3Call {
+                calls: targets
+                    .iter()
+                    .map(|target| Multicall3::Call3 {
+                        target: *target,
+                        allowFailure: false,
+                        callData: safe_call().into(),
+                    })
+                    .collect(),
+            }
+            .abi_encode()
+        };
+
+        assert_eq!(
+            resolve_initiator(EOA, Some(TARGET), &multicall(&[ACCOUNT_A, ACCOUNT_A])),
+            Some(ACCOUNT_A)
+        );
+        assert_eq!(
+            resolve_initiator(EOA, Some(TARGET), &multicall(&[ACCOUNT_A, ACCOUNT_B])),
+            None
+        );
+    }
+
+    #[test]
+    fn router_multicall() {
+        let input = SelfMulticall::multicall_1Call {
+            deadline: U256::MAX,
+            data: vec![vec![0xde, 0xad, 0xbe, 0xef].into()],
+        }
+        .abi_encode();
+
+        assert_eq!(resolve_initiator(EOA, Some(TARGET), &input), Some(EOA));
+    }
+
+    #[test]
+    fn forwarder() {
+        let input = ERC2771Forwarder::executeCall {
+            request: ERC2771Forwarder::ForwardRequestData {
+                from: ACCOUNT_B,
+                to: TARGET,
+                value: U256::ZERO,
+                gas: U256::ZERO,
+                deadline: Default::default(),
+                data: Bytes::new(),
+                signature: Bytes::new(),
+            },
+        }
+        .abi_encode();
+
+        assert_eq!(
+            resolve_initiator(EOA, Some(ACCOUNT_A), &input),
+            Some(ACCOUNT_B)
+        );
+    }
+}
@@ -6,6 +6,7 @@ use tracing::{trace, warn};
 
 use crate::indexer::{
     api::IndexerError,
+    decoder_version::DecoderVersion,
     ethereum::{
         abi::{Abi, AbiRegistration},
         fetcher_client::EthFetcherClient,
@@ -25,6 +26,8 @@ mod connection_open_try_mapping;
 mod create_client_mapping;
 mod create_lens_client_mapping;
 mod decoder;
+mod governance_proposal_mapping;
+mod initiator;
 pub(crate) mod legacy;
 mod packet_ack_mapping;
 mod packet_recv_mapping;
@@ -87,8 +90,17 @@ impl EthFetcherClient {
         transaction_log_index: usize,
         log: &Log,
     ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
+        // logs of a chunk are requested for the contracts registered anywhere in its range, so
+        // the logs of contracts that are not registered at the height of this block are ignored
         let Some(abi) = abi_registration.get_abi_for_address(&log.address()) else {
-            return Err(IndexerError::AbiNoAbiForAddress(log.address()));
+            trace!(
+                "{}: ignore log {:?} (contract {} is not registered)",
+                block.header.number,
+                log.log_index,
+                log.address()
+            );
+
+            return Ok(vec![]);
         };
 
         let mut events = self.to_ucs_events(abi, block, transaction_log_index, log)?;
@@ -113,30 +125,46 @@ impl EthFetcherClient {
             block,
             log,
             transaction_log_index,
+            decoder_version: abi.decoder_version,
         };
 
-        trace!("to_ucs_events - {log_decoder}");
-
-        Ok(match event.name.as_str() {
-            "ChannelOpenInit" => self.to_channel_open_init(&log_decoder)?,
-            "ChannelOpenTry" => self.to_channel_open_try(&log_decoder)?,
-            "ChannelOpenAck" => self.to_channel_open_ack(&log_decoder)?,
-            "ChannelOpenConfirm" => self.to_channel_open_confirm(&log_decoder)?,
-            "ConnectionOpenInit" => self.to_connection_open_init(&log_decoder)?,
-            "ConnectionOpenTry" => self.to_connection_open_try(&log_decoder)?,
-            "ConnectionOpenAck" => self.to_connection_open_ack(&log_decoder)?,
-            "ConnectionOpenConfirm" => self.to_connection_open_confirm(&log_decoder)?,
-            "CreateClient" => self.to_create_client(&log_decoder)?,
-            "CreateLensClient" => self.to_create_lens_client(&log_decoder)?,
-            "UpdateClient" => self.to_update_client(&log_decoder)?,
-            "PacketSend" => self.to_packet_send(&log_decoder)?,
-            "PacketRecv" => self.to_packet_recv(&log_decoder)?,
-            "WriteAck" => self.to_write_ack(&log_decoder)?,
-            "PacketAck" => self.to_packet_ack(&log_decoder)?,
-            "PacketTimeout" => self.to_packet_timeout(&log_decoder)?,
-            "TokenBucketUpdate" => self.to_token_bucket_update(&log_decoder)?,
+        trace!(
+            "to_ucs_events - {log_decoder} ({})",
+            log_decoder.decoder_version
+        );
+
+        match log_decoder.decoder_version {
+            DecoderVersion::V1 => self.to_ucs_events_v1(&log_decoder),
+        }
+    }
+
+    fn to_ucs_events_v1(
+        &self,
+        log_decoder: &Decoder<'_>,
+    ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
+        Ok(match log_decoder.event.name.as_str() {
+            "ChannelOpenInit" => self.to_channel_open_init(log_decoder)?,
+            "ChannelOpenTry" => self.to_channel_open_try(log_decoder)?,
+            "ChannelOpenAck" => self.to_channel_open_ack(log_decoder)?,
+            "ChannelOpenConfirm" => self.to_channel_open_confirm(log_decoder)?,
+            "ConnectionOpenInit" => self.to_connection_open_init(log_decoder)?,
+            "ConnectionOpenTry" => self.to_connection_open_try(log_decoder)?,
+            "ConnectionOpenAck" => self.to_connection_open_ack(log_decoder)?,
+            "ConnectionOpenConfirm" => self.to_connection_open_confirm(log_decoder)?,
+            "CreateClient" => self.to_create_client(log_decoder)?,
+            "CreateLensClient" => self.to_create_lens_client(log_decoder)?,
+            "UpdateClient" => self.to_update_client(log_decoder)?,
+            "PacketSend" => self.to_packet_send(log_decoder)?,
+            "PacketRecv" => self.to_packet_recv(log_decoder)?,
+            "WriteAck" => self.to_write_ack(log_decoder)?,
+            "PacketAck" => self.to_packet_ack(log_decoder)?,
+            "PacketTimeout" => self.to_packet_timeout(log_decoder)?,
+            "TokenBucketUpdate" => self.to_token_bucket_update(log_decoder)?,
+            "CallScheduled" | "CallExecuted" | "Cancelled" => {
+                self.to_governance_proposal(log_decoder)?
+            }
             name => {
-                warn!("unsupported event: {name} ({log:?})");
+                warn!("unsupported event: {name} ({:?})", log_decoder.log);
                 vec![]
             }
         })
@@ -1,9 +1,24 @@
+use alloy::{
+    consensus::Transaction,
+    network::{AnyRpcTransaction, TransactionResponse},
+};
+use itertools::Itertools;
 use tracing::trace;
 
 use crate::indexer::{
     api::IndexerError,
-    ethereum::{fetcher_client::EthFetcherClient, mapping::decoder::Decoder},
-    event::{packet_recv_event::PacketRecvEvent, supported::SupportedBlockEvent},
+    ethereum::{
+        fetcher_client::EthFetcherClient,
+        mapping::decoder::{address_bytes, Decoder},
+    },
+    event::{
+        packet_recv_event::PacketRecvEvent,
+        supported::SupportedBlockEvent,
+        types::{
+            ContractAddress, NeighborTransaction, PriorityFee, TransactionPlacement,
+            TransactionSender,
+        },
+    },
 };
 
 impl EthFetcherClient {
@@ -20,7 +35,55 @@ impl EthFetcherClient {
                 packet_hash: decoder.event.packet_hash()?,
                 maker: decoder.event.maker()?,
                 maker_msg: decoder.event.maker_msg()?,
+                placement: transaction_placement(decoder),
             },
         }])
     }
 }
+
+/// The placement of the recv transaction in its block, together with its direct neighbors.
+fn transaction_placement(decoder: &Decoder) -> Option<TransactionPlacement> {
+    let transaction_hash = decoder.log.transaction_hash?;
+    let transactions = decoder.block.transactions.txns().collect_vec();
+    let position = transactions
+        .iter()
+        .position(|transaction| transaction.tx_hash() == transaction_hash)?;
+
+    // chains without eip-1559 have no base fee, the full gas price is the tip
+    let base_fee = decoder.block.header.base_fee_per_gas.unwrap_or_default();
+    let transaction = transactions[position];
+
+    Some(TransactionPlacement {
+        block_transaction_count: transactions.len() as u64,
+        transaction_sender: TransactionSender(address_bytes(transaction.from())),
+        to: transaction
+            .to()
+            .map(|to| ContractAddress(address_bytes(to))),
+        priority_fee: priority_fee(transaction, base_fee),
+        previous: position
+            .checked_sub(1)
+            .map(|index| neighbor(transactions[index], base_fee)),
+        next: transactions
+            .get(position + 1)
+            .map(|transaction| neighbor(transaction, base_fee)),
+    })
+}
+
+fn neighbor(transaction: &AnyRpcTransaction, base_fee: u64) -> NeighborTransaction {
+    NeighborTransaction {
+        transaction_hash: transaction.tx_hash().into(),
+        transaction_sender: TransactionSender(address_bytes(transaction.from())),
+        to: transaction
+            .to()
+            .map(|to| ContractAddress(address_bytes(to))),
+        priority_fee: priority_fee(transaction, base_fee),
+    }
+}
+
+fn priority_fee(transaction: &AnyRpcTransaction, base_fee: u64) -> PriorityFee {
+    PriorityFee(
+        transaction
+            .effective_tip_per_gas(base_fee)
+            .unwrap_or_default(),
+    )
+}
@@ -1,9 +1,20 @@
+use alloy::{consensus::Transaction, network::TransactionResponse};
 use tracing::trace;
 
 use crate::indexer::{
     api::IndexerError,
-    ethereum::{fetcher_client::EthFetcherClient, mapping::decoder::Decoder},
-    event::{packet_send_event::PacketSendEvent, supported::SupportedBlockEvent},
+    ethereum::{
+        fetcher_client::EthFetcherClient,
+        mapping::{
+            decoder::{address_bytes, Decoder},
+            initiator::resolve_initiator,
+        },
+    },
+    event::{
+        packet_send_event::PacketSendEvent,
+        supported::SupportedBlockEvent,
+        types::{Initiator, TransactionSender},
+    },
 };
 
 impl EthFetcherClient {
@@ -15,6 +26,19 @@ impl EthFetcherClient {
 
         let packet = decoder.event.packet()?;
 
+        let (transaction_sender, initiator) = match decoder.transaction() {
+            Some(transaction) => {
+                let sender = transaction.from();
+                let initiator = resolve_initiator(sender, transaction.to(), transaction.input());
+
+                (
+                    Some(TransactionSender(address_bytes(sender))),
+                    initiator.map(|initiator| Initiator(address_bytes(initiator))),
+                )
+            }
+            None => (None, None),
+        };
+
         Ok(vec![SupportedBlockEvent::PacketSend {
             inner: PacketSendEvent {
                 header: decoder.header()?,
@@ -25,6 +49,8 @@ impl EthFetcherClient {
                 timeout_height: packet.timeout_height()?,
                 timeout_timestamp: packet.timeout_timestamp()?,
                 data: packet.data()?,
+                transaction_sender,
+                initiator,
             },
         }])
     }
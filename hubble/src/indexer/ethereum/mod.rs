@@ -8,6 +8,7 @@ mod block_handle;
 pub mod config;
 mod context;
 mod fetcher_client;
+mod log_fetcher;
 mod log_parser;
 mod mapping;
 mod postgres;
@@ -5,6 +5,7 @@ use crate::{
     github_client::GitCommitHash,
     indexer::{
         api::IndexerError,
+        contracts::{overlaps_stored_registration, replaced_start_heights, ContractRegistration},
         ethereum::abi::{Abi, AbiRegistration, GeneratedAbi},
         record::{InternalChainId, PgValue},
     },
@@ -17,7 +18,7 @@ pub async fn get_abi_registration(
 ) -> Result<AbiRegistration, IndexerError> {
     let result = sqlx::query!(
         r#"
-        SELECT    internal_chain_id, address, abi, description, commit
+        SELECT    internal_chain_id, address, abi, description, commit, decoder_version
         FROM      v2_evm.contracts
         WHERE     internal_chain_id = $1
         AND       $2 between start_height and end_height
@@ -37,6 +38,7 @@ pub async fn get_abi_registration(
             description: record.description.expect("description not null"),
             commit: GitCommitHash::from_slice(record.commit.as_slice())
                 .map_err(IndexerError::InvalidCommitHashForAbi)?,
+            decoder_version: record.decoder_version.try_into()?,
         })
     })
     .collect::<Result<Vec<Abi>, IndexerError>>()?
@@ -49,6 +51,33 @@ pub async fn get_abi_registration(
     })
 }
 
+/// The addresses of all contracts that are registered at any height in
+/// `start_inclusive..end_exclusive`, with whether the registration has an abi.
+pub async fn get_contract_addresses_in_range(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: &InternalChainId,
+    start_inclusive: &crate::indexer::event::types::BlockHeight,
+    end_exclusive: &crate::indexer::event::types::BlockHeight,
+) -> Result<Vec<(Address, bool)>, IndexerError> {
+    sqlx::query!(
+        r#"
+        SELECT    DISTINCT address, abi IS NOT NULL AS "has_abi!"
+        FROM      v2_evm.contracts
+        WHERE     internal_chain_id = $1
+        AND       start_height < $3
+        AND       end_height >= $2
+        "#,
+        internal_chain_id.pg_value()?,
+        start_inclusive.pg_value()?,
+        end_exclusive.pg_value()?,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| Ok((record.address.parse::<Address>()?, record.has_abi)))
+    .collect()
+}
+
 pub async fn ensure_abi_dependency(
     tx: &mut sqlx::Transaction<'_, Postgres>,
     commit: &GitCommitHash,
@@ -122,3 +151,96 @@ pub async fn update_contract_abi(
 
     Ok(result.rows_affected() > 0)
 }
+
+/// Register the contracts of the config of the indexer. A registration replaces the range,
+/// description, commit and decoder version of the registration of the same address and start
+/// height, other registrations are left as is (and must not overlap the registrations of the
+/// config). Registrations without an abi get the abi generated for their commit, once it is
+/// available.
+pub async fn register_contracts(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: &InternalChainId,
+    contracts: &[ContractRegistration],
+) -> Result<(), IndexerError> {
+    for contract in contracts {
+        let stored = sqlx::query!(
+            r#"
+            SELECT    start_height, end_height
+            FROM      v2_evm.contracts
+            WHERE     internal_chain_id = $1
+            AND       address = $2
+            AND       NOT (start_height = ANY($3))
+            AND       start_height <= $5
+            AND       end_height >= $4
+            ORDER BY  start_height
+            LIMIT     1
+            "#,
+            internal_chain_id.pg_value()?,
+            contract.address,
+            &replaced_start_heights(contracts, &contract.address),
+            i64::try_from(contract.from_height).unwrap(),
+            i64::try_from(contract.end_height()).unwrap(),
+        )
+        .fetch_optional(tx.as_mut())
+        .await?;
+
+        if let Some(stored) = stored {
+            return Err(overlaps_stored_registration(
+                contract,
+                stored.start_height,
+                stored.end_height,
+            )
+            .into());
+        }
+    }
+
+    for contract in contracts {
+        let start_height: i64 = contract.from_height.try_into().unwrap();
+        let end_height: i64 = contract.end_height().try_into().unwrap();
+        let decoder_version: i32 = contract.decoder_version.into();
+        let commit = contract.commit.as_ref().map(|commit| commit.0.to_vec());
+
+        if let Some(commit) = &contract.commit {
+            ensure_abi_dependency(tx, commit).await?;
+        }
+
+        sqlx::query!(
+            r#"
+            WITH generated AS (
+                SELECT abi, command
+                FROM abi.contract
+                WHERE commit = $6
+                AND contract = REPLACE($5, '/', '-') -- deployments use '/', but abis don't
+            ),
+            updated AS (
+                UPDATE v2_evm.contracts
+                SET
+                    end_height = $4,
+                    description = $5,
+                    commit = COALESCE($6, commit),
+                    decoder_version = $7,
+                    abi = COALESCE(abi, (SELECT abi FROM generated)),
+                    version = COALESCE(version, (SELECT command FROM generated))
+                WHERE internal_chain_id = $1
+                AND address = $2
+                AND start_height = $3
+                RETURNING 1
+            )
+            INSERT INTO v2_evm.contracts (internal_chain_id, address, start_height, end_height, description, commit, decoder_version, abi, version)
+            SELECT $1, $2, $3, $4, $5, $6, $7, (SELECT abi FROM generated), (SELECT command FROM generated)
+            WHERE NOT EXISTS (SELECT 1 FROM updated)
+            "#,
+            internal_chain_id.pg_value()?,
+            contract.address,
+            start_height,
+            end_height,
+            contract.contract_type,
+            commit,
+            decoder_version,
+        )
+        .execute(tx.as_mut())
+        .await?;
+    }
+
+    Ok(())
+}
@@ -3,19 +3,27 @@ use std::future::IntoFuture;
 use alloy::{
     eips::BlockId,
     network::{AnyNetwork, AnyRpcBlock},
+    primitives::Bytes,
     providers::{DynProvider, Provider as AlloyProvider, ProviderBuilder},
-    rpc::types::{BlockTransactionsKind, Filter, Log},
+    rpc::types::{BlockTransactionsKind, Filter, Log, TransactionRequest},
+    serde::WithOtherFields,
     transports::{RpcError, TransportErrorKind},
 };
 use url::Url;
 
-use crate::race_client::{RaceClient, RaceClientId, RaceClientResponse};
+use crate::race_client::{NoClients, RaceClient, RaceClientId, RaceClientResponse};
 
 #[derive(Clone, Debug)]
 pub struct Provider {
     pub rpc_client: RaceClient<DynProvider<AnyNetwork>>,
 }
 
+impl From<NoClients> for RpcError<TransportErrorKind> {
+    fn from(no_clients: NoClients) -> Self {
+        TransportErrorKind::custom_str(&no_clients.to_string())
+    }
+}
+
 #[derive(Clone, Debug, Copy)]
 pub struct RpcProviderId {
     race_client_id: RaceClientId,
@@ -76,6 +84,16 @@ impl Provider {
             .map(Into::into)
     }
 
+    pub async fn get_block_number(
+        &self,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<u64>, RpcError<TransportErrorKind>> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| c.get_block_number())
+            .await
+            .map(Into::into)
+    }
+
     pub async fn get_block(
         &self,
         id: BlockId,
@@ -100,4 +118,17 @@ impl Provider {
             .await
             .map(Into::into)
     }
+
+    pub async fn call(
+        &self,
+        request: &WithOtherFields<TransactionRequest>,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<Bytes>, RpcError<TransportErrorKind>> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.call(request.clone()).into_future()
+            })
+            .await
+            .map(Into::into)
+    }
 }
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use crate::indexer::event::{
+    header::Header,
+    types::{AdminAddress, CodeId, ContractAddress, ContractLifecycleAction},
+};
+
+/// Instantiate, migrate and admin changes of a registered CosmWasm contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ContractLifecycleEvent {
+    #[serde(flatten)]
+    pub header: Header,
+    pub contract_address_canonical: ContractAddress,
+    pub action: ContractLifecycleAction,
+    /// code id after the action (instantiate and migrate only)
+    pub code_id: Option<CodeId>,
+    /// admin after the action (update-admin only)
+    pub admin_address_canonical: Option<AdminAddress>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::event::test_utils::test_helpers::*;
+
+    /// Creates a test ContractLifecycleEvent with unique deterministic values
+    fn create_test_event(suffix: u32) -> ContractLifecycleEvent {
+        let (contract_address_canonical, action, code_id, admin_address_canonical) =
+            create_contract_lifecycle_test_values(suffix);
+
+        ContractLifecycleEvent {
+            header: create_test_header(suffix),
+            contract_address_canonical,
+            action,
+            code_id,
+            admin_address_canonical,
+        }
+    }
+
+    #[test]
+    fn test_json_serialization() {
+        let event = create_test_event(42);
+        test_roundtrip_serialization(&event);
+    }
+
+    #[test]
+    fn test_json_format_stability() {
+        let event = create_test_event(42);
+
+        // Test that the JSON format matches expectations
+
+        let expected_json = r#"{
+  "action": "migrate",
+  "admin_address_canonical": "0x61646d696e2d3432",
+  "block_hash": "0x424c4f434b5f484153485f3432",
+  "code_id": "1042",
+  "contract_address_canonical": "0x636f6e74726163742d3432",
+  "event_index": "42",
+  "height": "10042",
+  "timestamp": "2020-09-13T12:27:22Z",
+  "transaction_event_index": "242",
+  "transaction_hash": "0x54585f484153485f3432",
+  "transaction_index": "142",
+  "universal_chain_id": "test-chain-42"
+}"#;
+
+        test_json_format(&event, expected_json);
+    }
+}
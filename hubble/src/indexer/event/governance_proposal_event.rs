@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::indexer::event::{
+    header::Header,
+    types::{ContractAddress, ProposalAction, ProposalDelay, ProposalId},
+};
+
+/// Governance proposals (cosmos gov) and timelock operations (evm) that touch bridge parameters
+/// or contracts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceProposalEvent {
+    #[serde(flatten)]
+    pub header: Header,
+    pub proposal_id: ProposalId,
+    pub action: ProposalAction,
+    /// type urls of the proposal messages (cosmos) or the function selector of the call (evm)
+    pub message_types: Vec<String>,
+    /// contract called by the operation (evm scheduled and executed only)
+    pub target_contract_canonical: Option<ContractAddress>,
+    /// delay before the call can be executed (evm scheduled only)
+    pub delay: Option<ProposalDelay>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::event::test_utils::test_helpers::*;
+
+    /// Creates a test GovernanceProposalEvent with unique deterministic values
+    fn create_test_event(suffix: u32) -> GovernanceProposalEvent {
+        let (proposal_id, action, message_types, target_contract_canonical, delay) =
+            create_governance_proposal_test_values(suffix);
+
+        GovernanceProposalEvent {
+            header: create_test_header(suffix),
+            proposal_id,
+            action,
+            message_types,
+            target_contract_canonical,
+            delay,
+        }
+    }
+
+    #[test]
+    fn test_json_serialization() {
+        let event = create_test_event(42);
+        test_roundtrip_serialization(&event);
+    }
+
+    #[test]
+    fn test_json_format_stability() {
+        let event = create_test_event(42);
+
+        // Test that the JSON format matches expectations
+
+        let expected_json = r#"{
+  "action": "scheduled",
+  "block_hash": "0x424c4f434b5f484153485f3432",
+  "delay": "86442",
+  "event_index": "42",
+  "height": "10042",
+  "message_types": [
+    "0x3659cfe6"
+  ],
+  "proposal_id": "proposal-42",
+  "target_contract_canonical": "0x7461726765742d3432",
+  "timestamp": "2020-09-13T12:27:22Z",
+  "transaction_event_index": "242",
+  "transaction_hash": "0x54585f484153485f3432",
+  "transaction_index": "142",
+  "universal_chain_id": "test-chain-42"
+}"#;
+
+        test_json_format(&event, expected_json);
+    }
+}
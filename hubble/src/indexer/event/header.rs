@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-use crate::indexer::event::types::{
-    BlockHash, BlockHeight, BlockTimestamp, EventIndex, TransactionEventIndex, TransactionHash,
-    TransactionIndex, UniversalChainId,
+use crate::indexer::{
+    decoder_version::DecoderVersion,
+    event::types::{
+        BlockHash, BlockHeight, BlockTimestamp, EventIndex, TransactionEventIndex, TransactionHash,
+        TransactionIndex, UniversalChainId,
+    },
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -16,4 +19,26 @@ pub struct Header {
     pub transaction_index: TransactionIndex,
     /// deprecated
     pub transaction_event_index: Option<TransactionEventIndex>,
+    /// The version of the format the event was decoded from. Omitted for v1, which all events
+    /// published before versioning had.
+    #[serde(default, skip_serializing_if = "DecoderVersion::is_v1")]
+    pub decoder_version: DecoderVersion,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::event::test_utils::test_helpers::create_test_header;
+
+    #[test]
+    fn test_decoder_version_defaults_to_v1() {
+        let header = create_test_header(1);
+
+        let json = serde_json::to_value(&header).unwrap();
+        assert!(json.get("decoder_version").is_none());
+
+        let deserialized: Header = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized.decoder_version, DecoderVersion::V1);
+        assert_eq!(deserialized, header);
+    }
 }
@@ -6,8 +6,10 @@ pub(crate) mod connection_open_ack_event;
 pub(crate) mod connection_open_confirm_event;
 pub(crate) mod connection_open_init_event;
 pub(crate) mod connection_open_try_event;
+pub(crate) mod contract_lifecycle_event;
 pub(crate) mod create_client_event;
 pub(crate) mod create_lens_client_event;
+pub(crate) mod governance_proposal_event;
 pub(crate) mod header;
 pub(crate) mod hubble;
 pub(crate) mod packet_ack_event;
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::indexer::event::{
     header::Header,
-    types::{ChannelId, Maker, MakerMsg, PacketHash},
+    types::{ChannelId, Maker, MakerMsg, PacketHash, TransactionPlacement},
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -13,6 +13,8 @@ pub struct PacketRecvEvent {
     pub packet_hash: PacketHash,
     pub maker: Maker,
     pub maker_msg: MakerMsg,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placement: Option<TransactionPlacement>,
 }
 
 #[cfg(test)]
@@ -35,6 +37,7 @@ mod tests {
             packet_hash,
             maker,
             maker_msg,
+            placement: None,
         }
     }
 
@@ -2,7 +2,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::indexer::event::{
     header::Header,
-    types::{BlockHeight, ChannelId, PacketData, PacketHash, TimeoutTimestamp},
+    types::{
+        BlockHeight, ChannelId, Initiator, PacketData, PacketHash, TimeoutTimestamp,
+        TransactionSender,
+    },
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -16,6 +19,10 @@ pub struct PacketSendEvent {
     pub timeout_height: BlockHeight,
     pub timeout_timestamp: TimeoutTimestamp,
     pub data: PacketData,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_sender: Option<TransactionSender>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initiator: Option<Initiator>,
 }
 
 #[cfg(test)]
@@ -48,6 +55,8 @@ mod tests {
             timeout_height,
             timeout_timestamp,
             data,
+            transaction_sender: None,
+            initiator: None,
         }
     }
 
@@ -9,8 +9,10 @@ use crate::indexer::event::{
     connection_open_ack_event::ConnectionOpenAckEvent,
     connection_open_confirm_event::ConnectionOpenConfirmEvent,
     connection_open_init_event::ConnectionOpenInitEvent,
-    connection_open_try_event::ConnectionOpenTryEvent, create_client_event::CreateClientEvent,
-    create_lens_client_event::CreateLensClientEvent, packet_ack_event::PacketAckEvent,
+    connection_open_try_event::ConnectionOpenTryEvent,
+    contract_lifecycle_event::ContractLifecycleEvent, create_client_event::CreateClientEvent,
+    create_lens_client_event::CreateLensClientEvent,
+    governance_proposal_event::GovernanceProposalEvent, packet_ack_event::PacketAckEvent,
     packet_recv_event::PacketRecvEvent, packet_send_event::PacketSendEvent,
     packet_timeout_event::PacketTimeoutEvent, token_bucket_update_event::TokenBucketUpdateEvent,
     types::BlockHeight, update_client_event::UpdateClientEvent,
@@ -168,6 +170,16 @@ pub enum SupportedBlockEvent {
         #[serde(flatten)]
         inner: WalletMutationEntryEvent,
     },
+    #[serde(rename = "contract-lifecycle")]
+    ContractLifecycle {
+        #[serde(flatten)]
+        inner: ContractLifecycleEvent,
+    },
+    #[serde(rename = "governance-proposal")]
+    GovernanceProposal {
+        #[serde(flatten)]
+        inner: GovernanceProposalEvent,
+    },
 }
 
 impl SupportedBlockEvent {
@@ -196,6 +208,8 @@ impl SupportedBlockEvent {
             SupportedBlockEvent::PacketTimeout { inner, .. } => inner.header.height,
             SupportedBlockEvent::TokenBucketUpdate { inner, .. } => inner.header.height,
             SupportedBlockEvent::WalletMutationEntry { inner, .. } => inner.header.height,
+            SupportedBlockEvent::ContractLifecycle { inner, .. } => inner.header.height,
+            SupportedBlockEvent::GovernanceProposal { inner, .. } => inner.header.height,
         }
     }
 }
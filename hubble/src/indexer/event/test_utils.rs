@@ -4,14 +4,18 @@ pub mod test_helpers {
     use serde::{Deserialize, Serialize};
     use time::OffsetDateTime;
 
-    use crate::indexer::event::{
-        header::Header,
-        types::{
-            Acknowledgement, BlockHash, BlockHeight, BlockTimestamp, CanonicalChainId, Capacity,
-            ChannelId, ClientId, ClientType, ConnectionId, ContractAddress, Denom, EventIndex,
-            Maker, MakerMsg, MutationAmount, MutationDirection, PacketData, PacketHash, PortId,
-            RefillRate, TimeoutTimestamp, TransactionEventIndex, TransactionHash, TransactionIndex,
-            UniversalChainId, WalletAddress,
+    use crate::indexer::{
+        decoder_version::DecoderVersion,
+        event::{
+            header::Header,
+            types::{
+                Acknowledgement, AdminAddress, BlockHash, BlockHeight, BlockTimestamp,
+                CanonicalChainId, Capacity, ChannelId, ClientId, ClientType, CodeId, ConnectionId,
+                ContractAddress, ContractLifecycleAction, Denom, EventIndex, Maker, MakerMsg,
+                MutationAmount, MutationDirection, PacketData, PacketHash, PortId, ProposalAction,
+                ProposalDelay, ProposalId, RefillRate, TimeoutTimestamp, TransactionEventIndex,
+                TransactionHash, TransactionIndex, UniversalChainId, WalletAddress,
+            },
         },
     };
 
@@ -32,6 +36,7 @@ pub mod test_helpers {
             transaction_hash: TransactionHash(Bytes::from(format!("TX_HASH_{}", suffix))),
             transaction_index: TransactionIndex(100 + suffix as u64),
             transaction_event_index: Some(TransactionEventIndex(200 + suffix as u64)),
+            decoder_version: DecoderVersion::V1,
         }
     }
 
@@ -235,4 +240,44 @@ pub mod test_helpers {
             MutationDirection::In,
         )
     }
+
+    // =================== CONTRACT EVENT HELPERS ===================
+
+    /// Creates test values for contract lifecycle events
+    pub fn create_contract_lifecycle_test_values(
+        suffix: u32,
+    ) -> (
+        ContractAddress,
+        ContractLifecycleAction,
+        Option<CodeId>,
+        Option<AdminAddress>,
+    ) {
+        (
+            ContractAddress(Bytes::from(format!("contract-{}", suffix))),
+            ContractLifecycleAction::Migrate,
+            Some(CodeId(suffix as u64 + 1000)),
+            Some(AdminAddress(Bytes::from(format!("admin-{}", suffix)))),
+        )
+    }
+
+    // =================== GOVERNANCE EVENT HELPERS ===================
+
+    /// Creates test values for governance proposal events
+    pub fn create_governance_proposal_test_values(
+        suffix: u32,
+    ) -> (
+        ProposalId,
+        ProposalAction,
+        Vec<String>,
+        Option<ContractAddress>,
+        Option<ProposalDelay>,
+    ) {
+        (
+            ProposalId(format!("proposal-{}", suffix)),
+            ProposalAction::Scheduled,
+            vec!["0x3659cfe6".to_string()],
+            Some(ContractAddress(Bytes::from(format!("target-{}", suffix)))),
+            Some(ProposalDelay(suffix as u64 + 86400)),
+        )
+    }
 }
@@ -563,6 +563,63 @@ impl From<bytes::Bytes> for WalletAddress {
     }
 }
 
+/// The signer of the transaction (evm: `from`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionSender(#[serde(with = "bytes_as_hex")] pub bytes::Bytes);
+
+impl From<bytes::Bytes> for TransactionSender {
+    fn from(value: bytes::Bytes) -> Self {
+        Self(value)
+    }
+}
+
+/// The account on whose behalf a transaction was executed (i.e. the smart wallet of an erc-4337
+/// user operation), which can differ from the transaction sender.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Initiator(#[serde(with = "bytes_as_hex")] pub bytes::Bytes);
+
+impl From<bytes::Bytes> for Initiator {
+    fn from(value: bytes::Bytes) -> Self {
+        Self(value)
+    }
+}
+
+/// The tip per gas paid to the block producer on top of the base fee (evm).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriorityFee(#[serde(with = "flexible_u128")] pub u128);
+
+impl From<u128> for PriorityFee {
+    fn from(value: u128) -> Self {
+        Self(value)
+    }
+}
+
+/// A transaction directly before or after another transaction in the same block (evm).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NeighborTransaction {
+    pub transaction_hash: TransactionHash,
+    pub transaction_sender: TransactionSender,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<ContractAddress>,
+    pub priority_fee: PriorityFee,
+}
+
+/// Where a transaction was placed in its block and what surrounds it (evm). Used to detect mev
+/// patterns around relayer submissions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionPlacement {
+    #[serde(with = "flexible_u64")]
+    pub block_transaction_count: u64,
+    pub transaction_sender: TransactionSender,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<ContractAddress>,
+    pub priority_fee: PriorityFee,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<NeighborTransaction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<NeighborTransaction>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MutationAmount(#[serde(with = "flexible_u128")] pub u128);
 
@@ -580,6 +637,72 @@ pub enum MutationDirection {
     Out,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeId(#[serde(with = "flexible_u64")] pub u64);
+
+impl From<u64> for CodeId {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminAddress(#[serde(with = "bytes_as_hex")] pub bytes::Bytes);
+
+impl From<bytes::Bytes> for AdminAddress {
+    fn from(value: bytes::Bytes) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContractLifecycleAction {
+    #[serde(rename = "instantiate")]
+    Instantiate,
+    #[serde(rename = "migrate")]
+    Migrate,
+    #[serde(rename = "update_admin")]
+    UpdateAdmin,
+    #[serde(rename = "clear_admin")]
+    ClearAdmin,
+}
+
+/// Cosmos gov proposal id or evm timelock operation id (0x-prefixed hex).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalId(pub String);
+
+impl From<String> for ProposalId {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProposalAction {
+    /// cosmos gov proposal submitted
+    #[serde(rename = "submitted")]
+    Submitted,
+    /// evm timelock call scheduled
+    #[serde(rename = "scheduled")]
+    Scheduled,
+    /// evm timelock call executed
+    #[serde(rename = "executed")]
+    Executed,
+    /// evm timelock operation cancelled
+    #[serde(rename = "cancelled")]
+    Cancelled,
+}
+
+/// Minimum delay (in seconds) before a scheduled timelock call can be executed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalDelay(#[serde(with = "flexible_u64")] pub u64);
+
+impl From<u64> for ProposalDelay {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
 mod flexible_u64 {
     use super::*;
 
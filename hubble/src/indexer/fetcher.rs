@@ -85,6 +85,12 @@ impl<T: FetcherClient> Indexer<T> {
                 for slice in catch_up_range.range_chunks(self.chunk_size) {
                     info!("{slice}: handling chunk");
 
+                    let _permit = self
+                        .fetch_scheduler
+                        .acquire(&self.universal_chain_id, &slice)
+                        .await
+                        .map_err(Report::from)?;
+
                     last_finalized
                         .fetch_range_expect_all(slice.clone(), FetchMode::Eager, |block| {
                             self.store_block(block)
@@ -118,6 +124,7 @@ impl<T: FetcherClient> Indexer<T> {
         update_current_height(
             &mut tx,
             self.indexer_id.clone(),
+            &self.universal_chain_id,
             reference.height,
             reference.timestamp,
         )
@@ -172,6 +179,14 @@ impl<T: FetcherClient> Indexer<T> {
                     ));
                 }
 
+                if self
+                    .rollback_reorg(fetcher_client, &block_handle)
+                    .instrument(info_span!("reorg"))
+                    .await?
+                {
+                    return Ok(RunToTipLoopResult::RunAgain);
+                }
+
                 let mut tx = self.pg_pool.begin().await?;
                 let events = block_handle
                     .insert(&mut tx)
@@ -190,6 +205,7 @@ impl<T: FetcherClient> Indexer<T> {
                 update_current_height(
                     &mut tx,
                     self.indexer_id.clone(),
+                    &self.universal_chain_id,
                     reference.height,
                     reference.timestamp,
                 )
@@ -11,9 +11,12 @@ use crate::indexer::{
         FetcherClient, IndexerError,
     },
     event::types::{BlockEvents, MessageHash, Range},
-    postgres::block_status::{
-        delete_block_status, get_block_range_to_finalize, get_block_status_hash,
-        get_next_block_to_monitor, update_block_status,
+    postgres::{
+        block_status::{
+            delete_block_status, get_block_range_to_finalize, get_block_status_hash,
+            get_next_block_to_monitor, update_block_status,
+        },
+        block_update::update_block_update_finalized,
     },
     HappyRangeFetcher, Indexer,
 };
@@ -29,8 +32,14 @@ impl<T: FetcherClient> Indexer<T> {
             return Ok(());
         }
 
+        // height up to which the blocks are marked as finalized by this finalizer
+        let mut marked_finalized = None;
+
         loop {
-            match self.run_finalizer_loop(&fetcher_client).await {
+            match self
+                .run_finalizer_loop(&fetcher_client, &mut marked_finalized)
+                .await
+            {
                 Ok(FinalizerLoopResult::RunAgain) => {
                     debug!("run again");
                 }
@@ -55,6 +64,7 @@ impl<T: FetcherClient> Indexer<T> {
     async fn run_finalizer_loop(
         &self,
         fetcher_client: &T,
+        marked_finalized: &mut Option<BlockHeight>,
     ) -> Result<FinalizerLoopResult, IndexerError> {
         let chunk_size: u64 = self.chunk_size.try_into().unwrap();
 
@@ -78,7 +88,7 @@ impl<T: FetcherClient> Indexer<T> {
                         let some_blocks_needs_to_be_finalized = block_range_to_finalize
                             .start_inclusive
                             <= consensus_height_with_safety_margin;
-                        if some_blocks_needs_to_be_finalized {
+                        let first_tracked_height = if some_blocks_needs_to_be_finalized {
                             // find the end of the range to finalize
                             let end_of_chunk = block_range_to_finalize.start_inclusive + chunk_size;
                             let end_until_finalized = consensus_height_with_safety_margin + 1;
@@ -104,8 +114,26 @@ impl<T: FetcherClient> Indexer<T> {
                             )
                             .instrument(info_span!("finalize"))
                             .await?;
+
+                            range_to_finalize_end_exclusive
                         } else {
                             trace!("{block_range_to_finalize}: nothing to finalize (before finalized {reference})");
+
+                            block_range_to_finalize.start_inclusive
+                        };
+
+                        // blocks before the first tracked block are finalized. only the blocks
+                        // since the previous run are marked; blocks that are consumed after they
+                        // are finalized are marked when they are inserted.
+                        let finalized_height = min(
+                            consensus_height_with_safety_margin,
+                            first_tracked_height.saturating_sub(1),
+                        );
+
+                        if marked_finalized.is_none_or(|marked| finalized_height > marked) {
+                            self.mark_finalized(*marked_finalized, finalized_height)
+                                .await?;
+                            *marked_finalized = Some(finalized_height);
                         }
 
                         match self
@@ -247,7 +275,7 @@ impl<T: FetcherClient> Indexer<T> {
         Ok(())
     }
 
-    async fn update_block(
+    pub(super) async fn update_block(
         &self,
         tx: &mut sqlx::Transaction<'_, Postgres>,
         block: T::BlockHandle,
@@ -316,6 +344,30 @@ impl<T: FetcherClient> Indexer<T> {
         Ok(result)
     }
 
+    /// Marks the blocks after `from_exclusive` (all blocks when `None`) up to `height` as
+    /// finalized.
+    async fn mark_finalized(
+        &self,
+        from_exclusive: Option<BlockHeight>,
+        height: BlockHeight,
+    ) -> Result<(), Report> {
+        let mut tx = self.pg_pool.begin().await?;
+        let count = update_block_update_finalized(
+            &mut tx,
+            &self.universal_chain_id,
+            from_exclusive,
+            height,
+        )
+        .await?;
+        tx.commit().await?;
+
+        if count > 0 {
+            debug!("marked {count} blocks as finalized (until {height})");
+        }
+
+        Ok(())
+    }
+
     async fn next_block_to_monitor(
         &self,
         consensus_height: BlockHeight,
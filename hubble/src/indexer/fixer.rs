@@ -112,6 +112,11 @@ impl<T: FetcherClient> Indexer<T> {
         last_finalized: &T::BlockHandle,
         block_range: BlockRange,
     ) -> Result<(), Report> {
+        let _permit = self
+            .fetch_scheduler
+            .acquire(&self.universal_chain_id, &block_range)
+            .await?;
+
         last_finalized
             .fetch_range_expect_all(block_range.clone(), FetchMode::Eager, |block| {
                 self.fix_block(block)
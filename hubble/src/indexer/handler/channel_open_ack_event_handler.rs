@@ -6,6 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, channel_open_ack_record::ChannelOpenAckRecord, ChainContext,
+        Record,
     },
 };
 
@@ -16,6 +17,6 @@ impl<'a> EventContext<'a, ChainContext, ChannelOpenAckEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        ChannelOpenAckRecord::try_from(self)?.insert(tx).await
+        ChannelOpenAckRecord::try_from(self)?.upsert(tx).await
     }
 }
@@ -6,7 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, channel_open_confirm_record::ChannelOpenConfirmRecord,
-        ChainContext,
+        ChainContext, Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, ChannelOpenConfirmEvent> {
@@ -16,6 +16,6 @@ impl<'a> EventContext<'a, ChainContext, ChannelOpenConfirmEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        ChannelOpenConfirmRecord::try_from(self)?.insert(tx).await
+        ChannelOpenConfirmRecord::try_from(self)?.upsert(tx).await
     }
 }
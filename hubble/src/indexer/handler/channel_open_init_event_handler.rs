@@ -6,6 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, channel_open_init_record::ChannelOpenInitRecord, ChainContext,
+        Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, ChannelOpenInitEvent> {
@@ -15,6 +16,6 @@ impl<'a> EventContext<'a, ChainContext, ChannelOpenInitEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        ChannelOpenInitRecord::try_from(self)?.insert(tx).await
+        ChannelOpenInitRecord::try_from(self)?.upsert(tx).await
     }
 }
@@ -6,6 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, channel_open_try_record::ChannelOpenTryRecord, ChainContext,
+        Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, ChannelOpenTryEvent> {
@@ -15,6 +16,6 @@ impl<'a> EventContext<'a, ChainContext, ChannelOpenTryEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        ChannelOpenTryRecord::try_from(self)?.insert(tx).await
+        ChannelOpenTryRecord::try_from(self)?.upsert(tx).await
     }
 }
@@ -6,6 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, connection_open_ack_record::ConnectionOpenAckRecord, ChainContext,
+        Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, ConnectionOpenAckEvent> {
@@ -15,6 +16,6 @@ impl<'a> EventContext<'a, ChainContext, ConnectionOpenAckEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        ConnectionOpenAckRecord::try_from(self)?.insert(tx).await
+        ConnectionOpenAckRecord::try_from(self)?.upsert(tx).await
     }
 }
@@ -6,7 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, connection_open_confirm_record::ConnectionOpenConfirmRecord,
-        ChainContext,
+        ChainContext, Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, ConnectionOpenConfirmEvent> {
@@ -17,7 +17,7 @@ impl<'a> EventContext<'a, ChainContext, ConnectionOpenConfirmEvent> {
         trace!("handle({self:?})");
 
         ConnectionOpenConfirmRecord::try_from(self)?
-            .insert(tx)
+            .upsert(tx)
             .await
     }
 }
@@ -6,7 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, connection_open_init_record::ConnectionOpenInitRecord,
-        ChainContext,
+        ChainContext, Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, ConnectionOpenInitEvent> {
@@ -16,6 +16,6 @@ impl<'a> EventContext<'a, ChainContext, ConnectionOpenInitEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        ConnectionOpenInitRecord::try_from(self)?.insert(tx).await
+        ConnectionOpenInitRecord::try_from(self)?.upsert(tx).await
     }
 }
@@ -6,6 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, connection_open_try_record::ConnectionOpenTryRecord, ChainContext,
+        Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, ConnectionOpenTryEvent> {
@@ -15,6 +16,6 @@ impl<'a> EventContext<'a, ChainContext, ConnectionOpenTryEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        ConnectionOpenTryRecord::try_from(self)?.insert(tx).await
+        ConnectionOpenTryRecord::try_from(self)?.upsert(tx).await
     }
 }
@@ -0,0 +1,21 @@
+use tracing::trace;
+
+use crate::indexer::{
+    api::IndexerError,
+    event::contract_lifecycle_event::ContractLifecycleEvent,
+    handler::EventContext,
+    record::{
+        change_counter::Changes, contract_lifecycle_record::ContractLifecycleRecord, ChainContext,
+        Record,
+    },
+};
+impl<'a> EventContext<'a, ChainContext, ContractLifecycleEvent> {
+    pub async fn handle(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Changes, IndexerError> {
+        trace!("handle({self:?})");
+
+        ContractLifecycleRecord::try_from(self)?.upsert(tx).await
+    }
+}
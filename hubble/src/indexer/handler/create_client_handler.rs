@@ -4,7 +4,9 @@ use crate::indexer::{
     api::IndexerError,
     event::create_client_event::CreateClientEvent,
     handler::EventContext,
-    record::{change_counter::Changes, create_client_record::CreateClientRecord, ChainContext},
+    record::{
+        change_counter::Changes, create_client_record::CreateClientRecord, ChainContext, Record,
+    },
 };
 impl<'a> EventContext<'a, ChainContext, CreateClientEvent> {
     pub async fn handle(
@@ -13,6 +15,6 @@ impl<'a> EventContext<'a, ChainContext, CreateClientEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        CreateClientRecord::try_from(self)?.insert(tx).await
+        CreateClientRecord::try_from(self)?.upsert(tx).await
     }
 }
@@ -6,6 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, create_lens_client_record::CreateLensClientRecord, ChainContext,
+        Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, CreateLensClientEvent> {
@@ -15,6 +16,6 @@ impl<'a> EventContext<'a, ChainContext, CreateLensClientEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        CreateLensClientRecord::try_from(self)?.insert(tx).await
+        CreateLensClientRecord::try_from(self)?.upsert(tx).await
     }
 }
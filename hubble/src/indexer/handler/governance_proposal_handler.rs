@@ -0,0 +1,21 @@
+use tracing::trace;
+
+use crate::indexer::{
+    api::IndexerError,
+    event::governance_proposal_event::GovernanceProposalEvent,
+    handler::EventContext,
+    record::{
+        change_counter::Changes, governance_proposal_record::GovernanceProposalRecord,
+        ChainContext, Record,
+    },
+};
+impl<'a> EventContext<'a, ChainContext, GovernanceProposalEvent> {
+    pub async fn handle(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Changes, IndexerError> {
+        trace!("handle({self:?})");
+
+        GovernanceProposalRecord::try_from(self)?.upsert(tx).await
+    }
+}
@@ -4,7 +4,10 @@ use crate::indexer::{
     api::IndexerError,
     event::packet_ack_event::PacketAckEvent,
     handler::EventContext,
-    record::{change_counter::Changes, packet_ack_record::PacketAckRecord, ChainContext},
+    record::{
+        bulk::BulkIngest, change_counter::Changes, packet_ack_record::PacketAckRecord,
+        ChainContext, Record,
+    },
 };
 impl<'a> EventContext<'a, ChainContext, PacketAckEvent> {
     pub async fn handle(
@@ -13,6 +16,13 @@ impl<'a> EventContext<'a, ChainContext, PacketAckEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        PacketAckRecord::try_from(self)?.insert(tx).await
+        PacketAckRecord::try_from(self)?.upsert(tx).await
+    }
+
+    /// Buffers the record for bulk ingestion instead of upserting it.
+    pub fn buffer(&self, bulk: &mut BulkIngest) -> Result<Changes, IndexerError> {
+        trace!("buffer({self:?})");
+
+        Ok(bulk.push(&PacketAckRecord::try_from(self)?))
     }
 }
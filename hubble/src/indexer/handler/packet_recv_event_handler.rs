@@ -2,9 +2,13 @@ use tracing::trace;
 
 use crate::indexer::{
     api::IndexerError,
+    enrich::mev::enrich_packet_recv,
     event::packet_recv_event::PacketRecvEvent,
     handler::EventContext,
-    record::{change_counter::Changes, packet_recv_record::PacketRecvRecord, ChainContext},
+    record::{
+        bulk::BulkIngest, change_counter::Changes, packet_recv_record::PacketRecvRecord,
+        ChainContext, Record,
+    },
 };
 impl<'a> EventContext<'a, ChainContext, PacketRecvEvent> {
     pub async fn handle(
@@ -13,6 +17,22 @@ impl<'a> EventContext<'a, ChainContext, PacketRecvEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        PacketRecvRecord::try_from(self)?.insert(tx).await
+        let record = PacketRecvRecord::try_from(self)?;
+        let mut changes = Changes::default();
+        changes += record.upsert(tx).await?;
+
+        if let Some(placement) = &self.event.placement {
+            changes += enrich_packet_recv(tx, &record, placement).await?;
+        }
+
+        Ok(changes)
+    }
+
+    /// Buffers the record for bulk ingestion instead of upserting it. Only possible without a
+    /// placement, since the placement is enriched on insertion.
+    pub fn buffer(&self, bulk: &mut BulkIngest) -> Result<Changes, IndexerError> {
+        trace!("buffer({self:?})");
+
+        Ok(bulk.push(&PacketRecvRecord::try_from(self)?))
     }
 }
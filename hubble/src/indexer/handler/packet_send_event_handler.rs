@@ -5,7 +5,7 @@ use crate::indexer::{
     enrich::enrich,
     event::packet_send_event::PacketSendEvent,
     handler::EventContext,
-    record::{change_counter::Changes, packet_send_record::PacketSendRecord, ChainContext},
+    record::{change_counter::Changes, packet_send_record::PacketSendRecord, ChainContext, Record},
 };
 impl<'a> EventContext<'a, ChainContext, PacketSendEvent> {
     pub async fn handle(
@@ -16,7 +16,7 @@ impl<'a> EventContext<'a, ChainContext, PacketSendEvent> {
 
         let record = PacketSendRecord::try_from(self)?;
         let mut changes = Changes::default();
-        changes += record.insert(tx).await?;
+        changes += record.upsert(tx).await?;
         changes += enrich(tx, record).await?;
 
         Ok(changes)
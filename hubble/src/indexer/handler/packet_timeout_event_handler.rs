@@ -4,7 +4,10 @@ use crate::indexer::{
     api::IndexerError,
     event::packet_timeout_event::PacketTimeoutEvent,
     handler::EventContext,
-    record::{change_counter::Changes, packet_timeout_record::PacketTimeoutRecord, ChainContext},
+    record::{
+        bulk::BulkIngest, change_counter::Changes, packet_timeout_record::PacketTimeoutRecord,
+        ChainContext, Record,
+    },
 };
 impl<'a> EventContext<'a, ChainContext, PacketTimeoutEvent> {
     pub async fn handle(
@@ -13,6 +16,13 @@ impl<'a> EventContext<'a, ChainContext, PacketTimeoutEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        PacketTimeoutRecord::try_from(self)?.insert(tx).await
+        PacketTimeoutRecord::try_from(self)?.upsert(tx).await
+    }
+
+    /// Buffers the record for bulk ingestion instead of upserting it.
+    pub fn buffer(&self, bulk: &mut BulkIngest) -> Result<Changes, IndexerError> {
+        trace!("buffer({self:?})");
+
+        Ok(bulk.push(&PacketTimeoutRecord::try_from(self)?))
     }
 }
@@ -6,6 +6,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::Changes, token_bucket_update_record::TokenBucketUpdateRecord, ChainContext,
+        Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, TokenBucketUpdateEvent> {
@@ -15,6 +16,6 @@ impl<'a> EventContext<'a, ChainContext, TokenBucketUpdateEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        TokenBucketUpdateRecord::try_from(self)?.insert(tx).await
+        TokenBucketUpdateRecord::try_from(self)?.upsert(tx).await
     }
 }
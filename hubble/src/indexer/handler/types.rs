@@ -1,7 +1,6 @@
 use std::{fmt::Display, ops::Deref};
 
 use bytes::Bytes;
-use hex::encode;
 use ruint::aliases::U256;
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
@@ -11,6 +10,7 @@ use crate::indexer::{
         CanonicalChainId, ChannelId, ChannelVersion, ClientId, ClientType, ConnectionId, Denom,
         PortId, UniversalChainId,
     },
+    normalization::normalize_zkgm,
     record::{ChainNetwork, InternalChainId},
 };
 
@@ -64,6 +64,7 @@ pub struct Transfer {
     pub fee: Fee,
     pub wrap_direction: Option<WrapDirection>,
     pub packet_shape: PacketShape,
+    pub amount_usd: Option<AmountUsd>, // None if there is no recent price of the base token
 }
 
 pub struct Instruction {
@@ -381,22 +382,7 @@ impl TryFrom<&AddressZkgm> for AddressCanonical {
     type Error = IndexerError;
 
     fn try_from(value: &AddressZkgm) -> Result<Self, Self::Error> {
-        Ok(match value.1 {
-            RpcType::Cosmos => bech32::decode(std::str::from_utf8(&value.0).map_err(|_| {
-                IndexerError::Bech32DecodeErrorInvalidBech32(
-                    "denom not utf8".to_string(),
-                    encode(&value.0),
-                )
-            })?)
-            .map(|(_, data)| Bytes::from(data).into())
-            .map_err(|_| {
-                IndexerError::Bech32DecodeErrorInvalidBech32(
-                    "denom not bech".to_string(),
-                    encode(&value.0),
-                )
-            })?,
-            RpcType::Evm => value.0.clone().into(),
-        })
+        Ok(normalize_zkgm(value)?.canonical)
     }
 }
 
@@ -413,26 +399,18 @@ impl TryFrom<&AddressZkgm> for AddressDisplay {
     type Error = IndexerError;
 
     fn try_from(value: &AddressZkgm) -> Result<Self, Self::Error> {
-        Ok(match value.1 {
-            RpcType::Cosmos => {
-                match std::str::from_utf8(&value.0) {
-                    Ok(utf8_str) => {
-                        // If the string contains null bytes, output as hex instead
-                        // TODO: this is to be identical to the postgres implementation.
-                        // we should fail (and not convert the packet to a transfer).
-                        if utf8_str.contains('\0') {
-                            format!("0x{}", hex::encode(value.0.clone())).into()
-                        } else {
-                            utf8_str.to_string().into()
-                        }
-                    }
-                    Err(_) => {
-                        // Fallback to hex encoding if from_utf8 fails
-                        format!("0x{}", hex::encode(value.0.clone())).into()
-                    }
-                }
-            }
-            RpcType::Evm => format!("0x{}", hex::encode(value.0.clone())).into(),
+        if let Ok(normalized) = normalize_zkgm(value) {
+            return Ok(normalized.display);
+        }
+
+        // cosmos addresses that are not bech32 (or hex) are displayed as is
+        Ok(match std::str::from_utf8(&value.0) {
+            // If the string contains null bytes, output as hex instead
+            // TODO: this is to be identical to the postgres implementation.
+            // we should fail (and not convert the packet to a transfer).
+            Ok(utf8_str) if !utf8_str.contains('\0') => utf8_str.to_string().into(),
+            // Fallback to hex encoding if from_utf8 fails
+            _ => format!("0x{}", hex::encode(value.0.clone())).into(),
         })
     }
 }
@@ -512,6 +490,10 @@ impl From<u32> for TokenDecimals {
     }
 }
 
+/// Usd value of the base amount at the time of the transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmountUsd(pub sqlx::types::BigDecimal);
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Fee {
     #[serde(rename = "instruction")]
@@ -546,6 +528,8 @@ pub enum PacketShape {
     BatchV0TransferV1Fee,
     #[serde(rename = "transfer_v1")]
     TransferV1,
+    #[serde(rename = "batch_v0_transfers_v1")]
+    BatchV0TransfersV1,
 }
 
 pub mod bytes_as_hex {
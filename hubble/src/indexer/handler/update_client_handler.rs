@@ -4,7 +4,10 @@ use crate::indexer::{
     api::IndexerError,
     event::update_client_event::UpdateClientEvent,
     handler::EventContext,
-    record::{change_counter::Changes, update_client_record::UpdateClientRecord, ChainContext},
+    record::{
+        bulk::BulkIngest, change_counter::Changes, update_client_record::UpdateClientRecord,
+        ChainContext, Record,
+    },
 };
 impl<'a> EventContext<'a, ChainContext, UpdateClientEvent> {
     pub async fn handle(
@@ -13,6 +16,13 @@ impl<'a> EventContext<'a, ChainContext, UpdateClientEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        UpdateClientRecord::try_from(self)?.insert(tx).await
+        UpdateClientRecord::try_from(self)?.upsert(tx).await
+    }
+
+    /// Buffers the record for bulk ingestion instead of upserting it.
+    pub fn buffer(&self, bulk: &mut BulkIngest) -> Result<Changes, IndexerError> {
+        trace!("buffer({self:?})");
+
+        Ok(bulk.push(&UpdateClientRecord::try_from(self)?))
     }
 }
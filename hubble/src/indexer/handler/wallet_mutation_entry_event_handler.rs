@@ -5,8 +5,8 @@ use crate::indexer::{
     event::wallet_mutation_entry_event::WalletMutationEntryEvent,
     handler::EventContext,
     record::{
-        change_counter::Changes, wallet_mutation_entry_record::WalletMutationEntryRecord,
-        ChainContext,
+        bulk::BulkIngest, change_counter::Changes,
+        wallet_mutation_entry_record::WalletMutationEntryRecord, ChainContext, Record,
     },
 };
 impl<'a> EventContext<'a, ChainContext, WalletMutationEntryEvent> {
@@ -16,6 +16,13 @@ impl<'a> EventContext<'a, ChainContext, WalletMutationEntryEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        WalletMutationEntryRecord::try_from(self)?.insert(tx).await
+        WalletMutationEntryRecord::try_from(self)?.upsert(tx).await
+    }
+
+    /// Buffers the record for bulk ingestion instead of upserting it.
+    pub fn buffer(&self, bulk: &mut BulkIngest) -> Result<Changes, IndexerError> {
+        trace!("buffer({self:?})");
+
+        Ok(bulk.push(&WalletMutationEntryRecord::try_from(self)?))
     }
 }
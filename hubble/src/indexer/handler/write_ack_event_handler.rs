@@ -4,7 +4,10 @@ use crate::indexer::{
     api::IndexerError,
     event::write_ack_event::WriteAckEvent,
     handler::EventContext,
-    record::{change_counter::Changes, write_ack_record::WriteAckRecord, ChainContext},
+    record::{
+        bulk::BulkIngest, change_counter::Changes, write_ack_record::WriteAckRecord, ChainContext,
+        Record,
+    },
 };
 impl<'a> EventContext<'a, ChainContext, WriteAckEvent> {
     pub async fn handle(
@@ -13,6 +16,13 @@ impl<'a> EventContext<'a, ChainContext, WriteAckEvent> {
     ) -> Result<Changes, IndexerError> {
         trace!("handle({self:?})");
 
-        WriteAckRecord::try_from(self)?.insert(tx).await
+        WriteAckRecord::try_from(self)?.upsert(tx).await
+    }
+
+    /// Buffers the record for bulk ingestion instead of upserting it.
+    pub fn buffer(&self, bulk: &mut BulkIngest) -> Result<Changes, IndexerError> {
+        trace!("buffer({self:?})");
+
+        Ok(bulk.push(&WriteAckRecord::try_from(self)?))
     }
 }
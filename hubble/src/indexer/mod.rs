@@ -1,8 +1,12 @@
 pub mod api;
+pub mod backfiller;
+pub(crate) mod contracts;
+pub(crate) mod decoder_version;
+pub(crate) mod decoder_version_migration;
 // pub mod aptos;
 mod consumer;
 pub mod dummy;
-mod enrich;
+pub mod enrich;
 mod enricher;
 pub mod ethereum;
 pub mod event;
@@ -11,12 +15,16 @@ mod finalizer;
 mod fixer;
 mod handler;
 pub mod nats;
+pub(crate) mod normalization;
 mod postgres;
 mod publisher;
 mod record;
+mod reorg;
+mod retrier;
+pub mod scheduler;
 pub mod tendermint;
 
-use std::{future::Future, time::Duration};
+use std::{fmt::Display, future::Future, time::Duration};
 
 use api::{
     BlockHandle, BlockHeight, BlockRange, FetchMode, FetcherClient, IndexerError, IndexerId,
@@ -27,7 +35,15 @@ use serde::{Deserialize, Deserializer};
 use tokio::{task::JoinSet, time::sleep};
 use tracing::{error, info, info_span, Instrument};
 
-use crate::indexer::{event::types::UniversalChainId, nats::NatsConnection};
+use crate::indexer::{
+    event::types::UniversalChainId, nats::NatsConnection, scheduler::FetchScheduler,
+};
+
+/// Postgres channel on which new transfers and packet events are published (see `hubble serve
+/// --stream`).
+pub const RECORD_NOTIFICATION_CHANNEL: &str = "hubble_records";
+
+pub use postgres::notify::set_publish_records;
 
 enum EndOfRunResult {
     Exit,
@@ -44,9 +60,12 @@ pub struct Indexer<T: FetcherClient> {
     pub chunk_size: usize,
     pub finalizer_config: FinalizerConfig,
     pub fixer_config: FixerConfig,
+    pub backfill_config: BackfillConfig,
     pub publisher_config: PublisherConfig,
     pub consumer_config: ConsumerConfig,
     pub enricher_config: EnricherConfig,
+    pub retrier_config: RetrierConfig,
+    pub fetch_scheduler: FetchScheduler,
     pub context: T::Context,
     pub drain: bool,
 }
@@ -121,6 +140,26 @@ pub struct ConsumerConfig {
     // default: 1
     #[serde(default = "ConsumerConfig::default_batch_size")]
     pub batch_size: usize,
+    // minimum number of pending messages to ingest records in bulk (using copy instead of inserts),
+    // ie. during an initial backfill. switches back to inserts when getting closer to the tip.
+    // null disables bulk ingestion.
+    // default: 100
+    #[serde(default = "ConsumerConfig::default_bulk_ingest_min_pending")]
+    pub bulk_ingest_min_pending: Option<u64>,
+    // messages that are ingested in bulk are written in one database transaction, which is
+    // committed (and the messages acked) once this number of rows is buffered.
+    // default: 50000
+    #[serde(default = "ConsumerConfig::default_bulk_ingest_max_rows")]
+    pub bulk_ingest_max_rows: u64,
+    // time (in milliseconds) after which messages that are ingested in bulk are committed, even if
+    // fewer rows are buffered. must stay below the ack wait of the nats consumer (30 seconds).
+    // default: 10 seconds
+    #[serde(
+        rename = "bulk_ingest_max_wait_millis",
+        default = "ConsumerConfig::default_bulk_ingest_max_wait",
+        deserialize_with = "ConsumerConfig::deserialize_millis"
+    )]
+    pub bulk_ingest_max_wait: Duration,
 }
 
 impl ConsumerConfig {
@@ -132,6 +171,18 @@ impl ConsumerConfig {
         1
     }
 
+    pub fn default_bulk_ingest_min_pending() -> Option<u64> {
+        Some(100)
+    }
+
+    pub fn default_bulk_ingest_max_rows() -> u64 {
+        50_000
+    }
+
+    pub fn default_bulk_ingest_max_wait() -> Duration {
+        Duration::from_secs(10)
+    }
+
     fn deserialize_millis<'de, D>(deserializer: D) -> Result<Duration, D::Error>
     where
         D: Deserializer<'de>,
@@ -146,6 +197,34 @@ impl Default for ConsumerConfig {
         ConsumerConfig {
             retry_error_sleep: ConsumerConfig::default_retry_error_sleep(),
             batch_size: ConsumerConfig::default_batch_size(),
+            bulk_ingest_min_pending: ConsumerConfig::default_bulk_ingest_min_pending(),
+            bulk_ingest_max_rows: ConsumerConfig::default_bulk_ingest_max_rows(),
+            bulk_ingest_max_wait: ConsumerConfig::default_bulk_ingest_max_wait(),
+        }
+    }
+}
+
+/// Determines which block is considered to be the last finalized block of a chain.
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", deny_unknown_fields)]
+pub enum Finality {
+    /// The finality of the chain: the block with the `finalized` tag on evm chains (ie. beacon
+    /// chain finality on ethereum), the latest block on chains with instant finality (tendermint).
+    #[default]
+    #[serde(alias = "finalized_tag")]
+    Chain,
+    /// The latest block minus `confirmation_depth` blocks, for chains without (reliable) support
+    /// for the `finalized` tag, or to wait for a number of confirmations regardless.
+    ConfirmationDepth { confirmation_depth: u64 },
+}
+
+impl Display for Finality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finality::Chain => write!(f, "chain"),
+            Finality::ConfirmationDepth { confirmation_depth } => {
+                write!(f, "confirmation-depth: {confirmation_depth}")
+            }
         }
     }
 }
@@ -293,6 +372,65 @@ impl Default for FixerConfig {
     }
 }
 
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct BackfillConfig {
+    // sleep time (in seconds) when there is nothing to backfill.
+    // default: 5 seconds
+    #[serde(
+        rename = "retry_later_sleep_seconds",
+        default = "BackfillConfig::default_retry_later_sleep",
+        deserialize_with = "BackfillConfig::deserialize_seconds"
+    )]
+    pub retry_later_sleep: Duration,
+
+    // sleep time (in seconds) when there is an error.
+    // default: 5 seconds
+    #[serde(
+        rename = "retry_error_sleep_seconds",
+        default = "BackfillConfig::default_retry_error_sleep",
+        deserialize_with = "BackfillConfig::deserialize_seconds"
+    )]
+    pub retry_error_sleep: Duration,
+
+    // maximum number of backfill jobs of the indexer that are worked on at the same time. Every
+    // job fetches one chunk at a time.
+    // default: 2
+    #[serde(default = "BackfillConfig::default_max_concurrent_jobs")]
+    pub max_concurrent_jobs: usize,
+}
+
+impl BackfillConfig {
+    pub fn default_retry_later_sleep() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    pub fn default_retry_error_sleep() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    pub fn default_max_concurrent_jobs() -> usize {
+        2
+    }
+
+    fn deserialize_seconds<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let seconds = u64::deserialize(deserializer)?;
+        Ok(Duration::from_secs(seconds))
+    }
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        BackfillConfig {
+            retry_later_sleep: BackfillConfig::default_retry_later_sleep(),
+            retry_error_sleep: BackfillConfig::default_retry_error_sleep(),
+            max_concurrent_jobs: BackfillConfig::default_max_concurrent_jobs(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct EnricherConfig {
     // sleep time (in seconds) when there is nothing to enrich.
@@ -341,6 +479,89 @@ impl Default for EnricherConfig {
     }
 }
 
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct RetrierConfig {
+    // sleep time (in seconds) when there are no failed events to retry.
+    // default: 5 seconds
+    #[serde(
+        rename = "retry_later_sleep_seconds",
+        default = "RetrierConfig::default_retry_later_sleep",
+        deserialize_with = "RetrierConfig::deserialize_seconds"
+    )]
+    pub retry_later_sleep: Duration,
+
+    // sleep time (in seconds) when there is an error.
+    // default: 5 seconds
+    #[serde(
+        rename = "retry_error_sleep_seconds",
+        default = "RetrierConfig::default_retry_error_sleep",
+        deserialize_with = "RetrierConfig::deserialize_seconds"
+    )]
+    pub retry_error_sleep: Duration,
+
+    // backoff (in seconds) after the first failed retry, doubled after each next failed retry.
+    // default: 10 seconds
+    #[serde(
+        rename = "min_backoff_seconds",
+        default = "RetrierConfig::default_min_backoff",
+        deserialize_with = "RetrierConfig::deserialize_seconds"
+    )]
+    pub min_backoff: Duration,
+
+    // maximum backoff (in seconds) between retries.
+    // default: 1 hour
+    #[serde(
+        rename = "max_backoff_seconds",
+        default = "RetrierConfig::default_max_backoff",
+        deserialize_with = "RetrierConfig::deserialize_seconds"
+    )]
+    pub max_backoff: Duration,
+}
+
+impl RetrierConfig {
+    pub fn default_retry_later_sleep() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    pub fn default_retry_error_sleep() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    pub fn default_min_backoff() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    pub fn default_max_backoff() -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    /// Backoff until the next retry, given the number of failed retries.
+    pub fn backoff(&self, failed_retries: u32) -> Duration {
+        self.min_backoff
+            .saturating_mul(2u32.saturating_pow(failed_retries.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+
+    fn deserialize_seconds<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let seconds = u64::deserialize(deserializer)?;
+        Ok(Duration::from_secs(seconds))
+    }
+}
+
+impl Default for RetrierConfig {
+    fn default() -> Self {
+        RetrierConfig {
+            retry_later_sleep: RetrierConfig::default_retry_later_sleep(),
+            retry_error_sleep: RetrierConfig::default_retry_error_sleep(),
+            min_backoff: RetrierConfig::default_min_backoff(),
+            max_backoff: RetrierConfig::default_max_backoff(),
+        }
+    }
+}
+
 impl<T> Indexer<T>
 where
     T: FetcherClient,
@@ -355,9 +576,12 @@ where
         chunk_size: usize,
         finalizer_config: FinalizerConfig,
         fixer_config: FixerConfig,
+        backfill_config: BackfillConfig,
         publisher_config: PublisherConfig,
         consumer_config: ConsumerConfig,
         enricher_config: EnricherConfig,
+        retrier_config: RetrierConfig,
+        fetch_scheduler: FetchScheduler,
         context: T::Context,
         drain: bool,
     ) -> Self {
@@ -370,9 +594,12 @@ where
             chunk_size,
             finalizer_config,
             fixer_config,
+            backfill_config,
             publisher_config,
             consumer_config,
             enricher_config,
+            retrier_config,
+            fetch_scheduler,
             context,
             drain,
         }
@@ -409,6 +636,13 @@ where
                             .instrument(info_span!("fixer")),
                     );
 
+                    let self_clone = self.clone();
+                    let fetcher_client_clone = fetcher_client.clone();
+                    join_set.spawn(
+                        async move { self_clone.run_backfiller(fetcher_client_clone).await }
+                            .instrument(info_span!("backfiller")),
+                    );
+
                     let self_clone = self.clone();
                     let fetcher_client_clone = fetcher_client.clone();
                     join_set.spawn(
@@ -416,6 +650,12 @@ where
                             .instrument(info_span!("enricher")),
                     );
 
+                    let self_clone = self.clone();
+                    join_set.spawn(
+                        async move { self_clone.run_retrier().await }
+                            .instrument(info_span!("retrier")),
+                    );
+
                     let self_clone = self.clone();
                     join_set.spawn(
                         async move { self_clone.run_publisher().await }
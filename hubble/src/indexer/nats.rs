@@ -136,6 +136,8 @@ pub struct MessageMeta {
     pub message_hash: MessageHash,
     pub nats_stream_sequence: NatsStreamSequence,
     pub nats_consumer_sequence: NatsConsumerSequence,
+    // number of messages pending for the consumer after this message
+    pub pending: u64,
 }
 
 impl fmt::Display for MessageMeta {
@@ -0,0 +1,134 @@
+//! Canonical and display forms of addresses, independent of the format emitted by the chain.
+//!
+//! The canonical form is the raw address bytes, which records are keyed and queried on. The display
+//! form is what is shown to users and stored alongside the canonical bytes:
+//! - evm: lowercase 0x-prefixed hex (no eip-55 checksum)
+//! - cosmos: lowercase bech32 with the human readable part of the address
+//!
+//! Cosmos addresses that are emitted as 0x-prefixed hex are accepted as well, but can only be
+//! displayed as hex since the human readable part is unknown.
+
+use bytes::Bytes;
+use unionlabs::primitives::Bech32;
+
+use crate::indexer::{
+    api::IndexerError,
+    handler::types::{string_0x_to_bytes, AddressCanonical, AddressDisplay, AddressZkgm, RpcType},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedAddress {
+    pub canonical: AddressCanonical,
+    pub display: AddressDisplay,
+}
+
+pub fn normalize_evm(bytes: &[u8]) -> NormalizedAddress {
+    NormalizedAddress {
+        canonical: Bytes::copy_from_slice(bytes).into(),
+        display: format!("0x{}", hex::encode(bytes)).into(),
+    }
+}
+
+pub fn normalize_cosmos(encoded: &str) -> Result<NormalizedAddress, IndexerError> {
+    if encoded.starts_with("0x") {
+        let bytes = string_0x_to_bytes(encoded, "cosmos-address")?;
+
+        return Ok(normalize_evm(&bytes));
+    }
+
+    let (decoded, _) = Bech32::<Vec<u8>>::decode_any(encoded).map_err(|_| {
+        IndexerError::Bech32DecodeErrorInvalidBech32(
+            "cosmos address not bech32".to_string(),
+            encoded.to_string(),
+        )
+    })?;
+
+    // a valid bech32 string is either all lowercase or all uppercase, which decode the same
+    Ok(NormalizedAddress {
+        canonical: Bytes::from(decoded.into_data()).into(),
+        display: encoded.to_lowercase().into(),
+    })
+}
+
+/// Normalizes an address as encoded in a zkgm packet: raw bytes on evm, the utf8 encoded address
+/// on cosmos.
+pub fn normalize_zkgm(address: &AddressZkgm) -> Result<NormalizedAddress, IndexerError> {
+    match address.1 {
+        RpcType::Evm => Ok(normalize_evm(&address.0)),
+        RpcType::Cosmos => normalize_cosmos(std::str::from_utf8(&address.0).map_err(|_| {
+            IndexerError::Bech32DecodeErrorInvalidBech32(
+                "cosmos address not utf8".to_string(),
+                hex::encode(&address.0),
+            )
+        })?),
+    }
+}
+
+/// Parses an address in any supported format (0x-prefixed hex or bech32) to its canonical form,
+/// i.e. to look up the records of an address.
+pub fn parse_address(input: &str) -> Result<AddressCanonical, IndexerError> {
+    normalize_cosmos(input.trim()).map(|normalized| normalized.canonical)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // bech32 encoding of the bytes 1..=20
+    const BECH32: &str = "union1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc544dkgd";
+
+    #[test]
+    fn test_evm_display_is_lowercase_hex() {
+        let bytes =
+            string_0x_to_bytes("0x742d35Cc6634C0532925a3b8D4ee7c9c2b1e732A", "test").unwrap();
+
+        let normalized = normalize_evm(&bytes);
+
+        assert_eq!(normalized.canonical.0, bytes);
+        assert_eq!(
+            normalized.display.0,
+            "0x742d35cc6634c0532925a3b8d4ee7c9c2b1e732a"
+        );
+    }
+
+    #[test]
+    fn test_cosmos_bech32_is_case_insensitive() {
+        let lower = normalize_cosmos(BECH32).unwrap();
+        let upper = normalize_cosmos(&BECH32.to_uppercase()).unwrap();
+
+        assert_eq!(lower, upper);
+        assert_eq!(lower.display.0, BECH32);
+        assert_eq!(lower.canonical.0, (1..=20).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_cosmos_hex_matches_bech32_canonical() {
+        let bech32 = normalize_cosmos(BECH32).unwrap();
+        let from_hex =
+            normalize_cosmos(&format!("0x{}", hex::encode(&bech32.canonical.0))).unwrap();
+
+        assert_eq!(from_hex.canonical, bech32.canonical);
+    }
+
+    #[test]
+    fn test_zkgm_cosmos_address() {
+        let zkgm = AddressZkgm(Bytes::from(BECH32.as_bytes().to_vec()), RpcType::Cosmos);
+
+        assert_eq!(
+            normalize_zkgm(&zkgm).unwrap(),
+            normalize_cosmos(BECH32).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_address_across_formats() {
+        let canonical = parse_address(BECH32).unwrap();
+
+        assert_eq!(parse_address(&BECH32.to_uppercase()).unwrap(), canonical);
+        assert_eq!(
+            parse_address(&format!(" 0x{} ", hex::encode_upper(&canonical.0))).unwrap(),
+            canonical
+        );
+        assert!(parse_address("not an address").is_err());
+    }
+}
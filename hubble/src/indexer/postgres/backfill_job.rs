@@ -0,0 +1,222 @@
+use std::fmt::Display;
+
+use sqlx::{PgPool, Postgres};
+use time::OffsetDateTime;
+use tracing::trace;
+
+use crate::indexer::api::{BlockHeight, BlockRange, IndexerId};
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct BackfillJob {
+    pub id: i64,
+    pub range: BlockRange,
+    pub cursor: BlockHeight,
+}
+
+impl BackfillJob {
+    /// Number of blocks of the range that are backfilled.
+    pub fn done(&self) -> u64 {
+        self.cursor - self.range.start_inclusive
+    }
+
+    /// Number of blocks of the range that are not backfilled yet.
+    pub fn remaining(&self) -> u64 {
+        self.range.end_exclusive - self.cursor
+    }
+}
+
+impl Display for BackfillJob {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{} (id: {})", self.range, self.cursor, self.id)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BackfillJobStatus {
+    pub indexer_id: IndexerId,
+    pub job: BackfillJob,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+    pub completed_at: Option<OffsetDateTime>,
+}
+
+/// All backfill jobs of `indexer_id` that are not completed, in the order they were scheduled.
+pub async fn get_active_backfill_jobs(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: &IndexerId,
+) -> sqlx::Result<Vec<BackfillJob>> {
+    trace!("get_active_backfill_jobs: {indexer_id}");
+
+    Ok(sqlx::query!(
+        "
+        SELECT id, start_height, end_height, cursor_height
+        FROM hubble.backfill_jobs
+        WHERE indexer_id = $1
+        AND completed_at IS NULL
+        ORDER BY id
+        ",
+        indexer_id,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|r| BackfillJob {
+        id: r.id,
+        range: BlockRange {
+            start_inclusive: BlockHeight::try_from(r.start_height).expect("start_height fits"),
+            end_exclusive: BlockHeight::try_from(r.end_height).expect("end_height fits"),
+        },
+        cursor: BlockHeight::try_from(r.cursor_height).expect("cursor_height fits"),
+    })
+    .collect())
+}
+
+/// Lock the backfill job with `id` until the end of the transaction. Returns `None` if the job
+/// does not exist (anymore), or is locked by another transaction.
+pub async fn lock_backfill_job(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    id: i64,
+) -> sqlx::Result<Option<BackfillJob>> {
+    trace!("lock_backfill_job: {id}");
+
+    Ok(sqlx::query!(
+        "
+        SELECT id, start_height, end_height, cursor_height
+        FROM hubble.backfill_jobs
+        WHERE id = $1
+        FOR UPDATE SKIP LOCKED
+        ",
+        id,
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .map(|r| BackfillJob {
+        id: r.id,
+        range: BlockRange {
+            start_inclusive: BlockHeight::try_from(r.start_height).expect("start_height fits"),
+            end_exclusive: BlockHeight::try_from(r.end_height).expect("end_height fits"),
+        },
+        cursor: BlockHeight::try_from(r.cursor_height).expect("cursor_height fits"),
+    }))
+}
+
+/// Move the cursor of `backfill_job` to `new_cursor`, completing the job when it reaches the end
+/// of its range.
+pub async fn update_backfill_job_cursor(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    backfill_job: &BackfillJob,
+    new_cursor: BlockHeight,
+) -> sqlx::Result<()> {
+    trace!("update_backfill_job_cursor: {backfill_job} - {new_cursor}");
+
+    let new_cursor: i64 = new_cursor.try_into().unwrap();
+
+    sqlx::query!(
+        "
+        UPDATE hubble.backfill_jobs
+        SET cursor_height = $2
+          , updated_at = now()
+          , completed_at = CASE WHEN $2 >= end_height THEN now() END
+        WHERE id = $1
+        ",
+        backfill_job.id,
+        new_cursor,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Schedule a backfill of `range` for `indexer_id`. Returns the ids of the active jobs of the
+/// indexer that overlap with `range` instead of scheduling it, if there are any.
+pub async fn insert_backfill_job(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: &IndexerId,
+    range: &BlockRange,
+) -> sqlx::Result<Result<i64, Vec<i64>>> {
+    trace!("insert_backfill_job: {indexer_id} - {range}");
+
+    let start_height: i64 = range.start_inclusive.try_into().unwrap();
+    let end_height: i64 = range.end_exclusive.try_into().unwrap();
+
+    // serialize scheduling per indexer, such that concurrent inserts cannot both pass the check
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(format!("hubble.backfill_jobs:{indexer_id}"))
+        .execute(tx.as_mut())
+        .await?;
+
+    let overlapping = sqlx::query!(
+        "
+        SELECT id
+        FROM hubble.backfill_jobs
+        WHERE indexer_id = $1
+        AND completed_at IS NULL
+        AND start_height < $3
+        AND end_height > $2
+        ORDER BY id
+        ",
+        indexer_id,
+        start_height,
+        end_height,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|r| r.id)
+    .collect::<Vec<_>>();
+
+    if !overlapping.is_empty() {
+        return Ok(Err(overlapping));
+    }
+
+    let id = sqlx::query!(
+        "
+        INSERT INTO hubble.backfill_jobs(indexer_id, start_height, end_height, cursor_height)
+        VALUES ($1, $2, $3, $2)
+        RETURNING id
+        ",
+        indexer_id,
+        start_height,
+        end_height,
+    )
+    .fetch_one(tx.as_mut())
+    .await?
+    .id;
+
+    Ok(Ok(id))
+}
+
+/// All backfill jobs, optionally only those of `indexer_id`, in the order they were scheduled.
+pub async fn get_backfill_job_statuses(
+    pg_pool: &PgPool,
+    indexer_id: Option<&IndexerId>,
+) -> sqlx::Result<Vec<BackfillJobStatus>> {
+    Ok(sqlx::query!(
+        "
+        SELECT id, indexer_id, start_height, end_height, cursor_height, created_at, updated_at, completed_at
+        FROM hubble.backfill_jobs
+        WHERE ($1::text IS NULL OR indexer_id = $1)
+        ORDER BY id
+        ",
+        indexer_id,
+    )
+    .fetch_all(pg_pool)
+    .await?
+    .into_iter()
+    .map(|r| BackfillJobStatus {
+        indexer_id: r.indexer_id,
+        job: BackfillJob {
+            id: r.id,
+            range: BlockRange {
+                start_inclusive: BlockHeight::try_from(r.start_height).expect("start_height fits"),
+                end_exclusive: BlockHeight::try_from(r.end_height).expect("end_height fits"),
+            },
+            cursor: BlockHeight::try_from(r.cursor_height).expect("cursor_height fits"),
+        },
+        created_at: r.created_at,
+        updated_at: r.updated_at,
+        completed_at: r.completed_at,
+    })
+    .collect())
+}
@@ -61,8 +61,16 @@ pub async fn insert_block_update(
             delete,
             message_hash, 
             nats_stream_sequence, 
-            nats_consumer_sequence
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            nats_consumer_sequence,
+            is_finalized
+        ) VALUES (
+            $1, $2, $3, $4, $5, $6, $7,
+            -- blocks that are consumed after a later block was finalized are finalized as well
+            EXISTS (
+                SELECT 1 FROM hubble.block_update f
+                WHERE f.universal_chain_id = $1 AND f.height > $2 AND f.is_finalized
+            )
+        )
         ",
         block_update.universal_chain_id.pg_value()?,
         block_update.height.pg_value()?,
@@ -107,6 +115,35 @@ pub async fn update_block_update(
     Ok(())
 }
 
+/// Marks the blocks of a chain after `from_exclusive` (all blocks when `None`) up to (and
+/// including) `height` as finalized. Returns the number of blocks that were marked.
+pub async fn update_block_update_finalized(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    universal_chain_id: &UniversalChainId,
+    from_exclusive: Option<u64>,
+    height: u64,
+) -> Result<u64, IndexerError> {
+    let from_exclusive: Option<i64> = from_exclusive.map(|height| height.try_into().unwrap());
+    let height: i64 = height.try_into().unwrap();
+
+    Ok(sqlx::query!(
+        "
+        UPDATE hubble.block_update
+            SET is_finalized = true
+        WHERE universal_chain_id = $1
+          AND ($2::BIGINT IS NULL OR height > $2)
+          AND height <= $3
+          AND NOT is_finalized
+        ",
+        universal_chain_id.pg_value()?,
+        from_exclusive,
+        height,
+    )
+    .execute(tx.as_mut())
+    .await?
+    .rows_affected())
+}
+
 pub async fn max_event_height(
     tx: &mut Transaction<'_, Postgres>,
     universal_chain_id: &UniversalChainId,
@@ -0,0 +1,145 @@
+use std::fmt::{self, Display};
+
+use sqlx::Postgres;
+use time::OffsetDateTime;
+
+use crate::indexer::{
+    api::IndexerError,
+    event::{supported::SupportedBlockEvent, types::BlockHeight},
+    record::{InternalChainId, PgValue},
+};
+
+/// An event that could not be handled, waiting to be retried.
+pub struct FailedEvent {
+    pub id: i64,
+    pub internal_chain_id: InternalChainId,
+    pub height: BlockHeight,
+    pub event: serde_json::Value,
+    pub attempts: i32,
+}
+
+impl Display for FailedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed-event {} ({}@{}, attempts: {})",
+            self.id, self.internal_chain_id, self.height, self.attempts
+        )
+    }
+}
+
+/// Stores an event that could not be handled, to be retried right away by the retrier.
+pub async fn insert_failed_event(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    internal_chain_id: InternalChainId,
+    event: &SupportedBlockEvent,
+    error: &str,
+) -> Result<(), IndexerError> {
+    sqlx::query!(
+        "
+        INSERT INTO hubble.failed_events (internal_chain_id, height, event, error, attempts, next_attempt_at, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, 0, now(), now(), now())
+        ",
+        internal_chain_id.pg_value()?,
+        event.height().pg_value()?,
+        serde_json::to_value(event)?,
+        error,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// The failed event that is due the longest, skipping events that are being retried by others.
+pub async fn next_failed_event(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    internal_chain_id: InternalChainId,
+) -> Result<Option<FailedEvent>, IndexerError> {
+    sqlx::query!(
+        "
+        SELECT id, height, event, attempts
+        FROM hubble.failed_events
+        WHERE internal_chain_id = $1
+        AND next_attempt_at <= now()
+        ORDER BY next_attempt_at
+        LIMIT 1
+        FOR UPDATE SKIP LOCKED
+        ",
+        internal_chain_id.pg_value()?,
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .map(|record| {
+        Ok(FailedEvent {
+            id: record.id,
+            internal_chain_id,
+            height: record.height.try_into()?,
+            event: record.event,
+            attempts: record.attempts,
+        })
+    })
+    .transpose()
+}
+
+pub async fn update_failed_event(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    failed_event: &FailedEvent,
+    error: &str,
+    next_attempt_at: OffsetDateTime,
+) -> Result<(), IndexerError> {
+    sqlx::query!(
+        "
+        UPDATE hubble.failed_events
+        SET attempts = attempts + 1,
+            error = $2,
+            next_attempt_at = $3,
+            updated_at = now()
+        WHERE id = $1
+        ",
+        failed_event.id,
+        error,
+        next_attempt_at,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_failed_event(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    failed_event: &FailedEvent,
+) -> Result<(), IndexerError> {
+    sqlx::query!(
+        "
+        DELETE FROM hubble.failed_events
+        WHERE id = $1
+        ",
+        failed_event.id,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Deletes the failed events of a block that is being replaced. Returns the number of deleted
+/// events.
+pub async fn delete_failed_events_at_height(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    internal_chain_id: InternalChainId,
+    height: BlockHeight,
+) -> Result<u64, IndexerError> {
+    Ok(sqlx::query!(
+        "
+        DELETE FROM hubble.failed_events
+        WHERE internal_chain_id = $1 AND height = $2
+        ",
+        internal_chain_id.pg_value()?,
+        height.pg_value()?,
+    )
+    .execute(tx.as_mut())
+    .await?
+    .rows_affected())
+}
@@ -1,7 +1,10 @@
 use sqlx::Postgres;
 use time::OffsetDateTime;
 
-use crate::indexer::api::{BlockHeight, IndexerId};
+use crate::indexer::{
+    api::{BlockHeight, IndexerId},
+    event::types::UniversalChainId,
+};
 
 pub async fn get_current_height(
     tx: &mut sqlx::Transaction<'_, Postgres>,
@@ -22,9 +25,12 @@ pub async fn get_current_height(
     Ok(record.map(|h| h.height.try_into().unwrap()))
 }
 
+/// Records the last block of an indexer, with the chain it indexes (as the indexer id is an
+/// arbitrary name).
 pub async fn update_current_height(
     tx: &mut sqlx::Transaction<'_, Postgres>,
     indexer_id: IndexerId,
+    universal_chain_id: &UniversalChainId,
     height: BlockHeight,
     timestamp: OffsetDateTime,
 ) -> sqlx::Result<()> {
@@ -32,14 +38,16 @@ pub async fn update_current_height(
 
     sqlx::query!(
         "
-        INSERT INTO hubble.indexer_status (indexer_id, height, timestamp)
-        VALUES ($1, $2, $3)
+        INSERT INTO hubble.indexer_status (indexer_id, universal_chain_id, height, timestamp)
+        VALUES ($1, $2, $3, $4)
         ON CONFLICT (indexer_id) DO 
         UPDATE SET
+            universal_chain_id = excluded.universal_chain_id,
             height = excluded.height,
             timestamp = excluded.timestamp
         ",
         indexer_id,
+        universal_chain_id.0,
         height,
         timestamp,
     )
@@ -1,9 +1,13 @@
+pub(crate) mod backfill_job;
 pub(crate) mod block_enrich;
 pub(crate) mod block_fix;
 pub(crate) mod block_status;
 pub(crate) mod block_update;
 pub(crate) mod chain_context;
+pub(crate) mod failed_event;
 pub(crate) mod indexer_status;
 pub(crate) mod lock;
 pub(crate) mod nats;
+pub(crate) mod notify;
+pub(crate) mod reorg;
 pub(crate) mod replication_reset;
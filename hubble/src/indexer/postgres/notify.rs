@@ -0,0 +1,96 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::Value;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+
+use crate::indexer::{api::IndexerError, RECORD_NOTIFICATION_CHANNEL};
+
+/// Whether records are published (`--publish-records`). Every published record costs a
+/// `pg_notify` in the transaction of its block, so records are not published by default.
+static PUBLISH_RECORDS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_publish_records(publish_records: bool) {
+    PUBLISH_RECORDS.store(publish_records, Ordering::Relaxed);
+}
+
+pub fn publish_records() -> bool {
+    PUBLISH_RECORDS.load(Ordering::Relaxed)
+}
+
+/// Publish a new transfer to `hubble serve --stream` subscribers, if records are published.
+/// Notifications are only delivered when the transaction commits.
+pub async fn notify_transfer(
+    tx: &mut Transaction<'_, Postgres>,
+    transfer: &Value,
+) -> Result<(), IndexerError> {
+    if !publish_records() {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        "SELECT FROM pg_notify($1, $2)",
+        RECORD_NOTIFICATION_CHANNEL,
+        transfer.to_string(),
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// A packet event of a record that is ingested in bulk, published when the record is flushed.
+pub struct PacketEventNotification {
+    pub kind: &'static str,
+    pub internal_chain_id: i32,
+    pub channel_id: i32,
+    pub packet_hash: Vec<u8>,
+    pub transaction_hash: Vec<u8>,
+    pub height: i64,
+    pub timestamp: OffsetDateTime,
+}
+
+/// Publish a new packet event to `hubble serve --stream` subscribers, if records are published.
+/// Notifications are only delivered when the transaction commits.
+#[allow(clippy::too_many_arguments)]
+pub async fn notify_packet_event(
+    tx: &mut Transaction<'_, Postgres>,
+    kind: &str,
+    internal_chain_id: i32,
+    channel_id: i32,
+    packet_hash: &[u8],
+    transaction_hash: &[u8],
+    height: i64,
+    timestamp: OffsetDateTime,
+) -> Result<(), IndexerError> {
+    if !publish_records() {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+        SELECT FROM pg_notify($1, json_build_object(
+            'type', 'packet_event',
+            'kind', $2::TEXT,
+            'universal_chain_id', (SELECT family || '.' || chain_id FROM config.chains WHERE id = $3),
+            'channel_id', $4::INT4,
+            'packet_hash', '0x' || encode($5, 'hex'),
+            'transaction_hash', '0x' || encode($6, 'hex'),
+            'height', $7::INT8,
+            'timestamp', $8::TIMESTAMPTZ
+        )::TEXT)
+        "#,
+        RECORD_NOTIFICATION_CHANNEL,
+        kind,
+        internal_chain_id,
+        channel_id,
+        packet_hash,
+        transaction_hash,
+        height,
+        timestamp,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use sqlx::Postgres;
+
+use crate::indexer::api::{BlockHash, BlockHeight, IndexerId};
+
+/// A reorg that was rolled back while running to the tip.
+pub struct Reorg {
+    /// Highest height that was not affected by the reorg.
+    pub fork_height: BlockHeight,
+    /// Height of the replaced tip.
+    pub tip_height: BlockHeight,
+    pub old_tip_hash: BlockHash,
+    pub new_tip_hash: BlockHash,
+}
+
+pub async fn insert_reorg(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: IndexerId,
+    reorg: &Reorg,
+) -> sqlx::Result<()> {
+    let fork_height: i64 = reorg.fork_height.try_into().unwrap();
+    let tip_height: i64 = reorg.tip_height.try_into().unwrap();
+
+    sqlx::query!(
+        "
+        INSERT INTO hubble.reorgs (indexer_id, fork_height, tip_height, old_tip_hash, new_tip_hash, detected_at)
+        VALUES ($1, $2, $3, $4, $5, now())
+        ",
+        indexer_id,
+        fork_height,
+        tip_height,
+        reorg.old_tip_hash,
+        reorg.new_tip_hash,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,311 @@
+//! Bulk ingestion of records using `COPY`, used while the consumer is far behind the tip (ie.
+//! during an initial backfill).
+//!
+//! Rows are buffered per table in the text format of `COPY`, across the messages of a bulk batch
+//! (see `ConsumerConfig::bulk_ingest_max_rows`), and flushed before the batch is committed: they're
+//! copied into a temporary staging table and then upserted into the target table on its natural
+//! key, so bulk ingestion is as idempotent as [`Record::upsert`].
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use itertools::Itertools;
+use sqlx::{types::BigDecimal, Postgres, Transaction};
+use time::{OffsetDateTime, UtcOffset};
+use tracing::debug;
+
+use crate::indexer::{
+    api::IndexerError,
+    postgres::notify::{notify_packet_event, publish_records, PacketEventNotification},
+    record::{change_counter::Changes, Record},
+};
+
+/// A record that can be ingested with `COPY`.
+///
+/// Only records without side effects (ie. other tables that are maintained when upserting the
+/// record) can be ingested in bulk.
+pub trait CopyRecord: Record {
+    /// The table the record is upserted into.
+    const TABLE: &'static str;
+    /// The natural key of the table, matching the `ON CONFLICT` clause of [`Record::upsert`].
+    const KEY: &'static [&'static str];
+    /// The columns written by [`CopyRecord::write_row`], in order.
+    const COLUMNS: &'static [&'static str];
+
+    fn write_row(&self, row: &mut CopyRow<'_>);
+
+    /// The packet event that [`Record::upsert`] publishes for the record, if any. It is published
+    /// when the record is flushed.
+    fn packet_event(&self) -> Option<PacketEventNotification> {
+        None
+    }
+}
+
+/// A value in the text format of `COPY`.
+pub trait CopyValue {
+    fn write_copy(&self, out: &mut String);
+}
+
+impl CopyValue for i32 {
+    fn write_copy(&self, out: &mut String) {
+        write!(out, "{self}").expect("writing to string succeeds");
+    }
+}
+
+impl CopyValue for i64 {
+    fn write_copy(&self, out: &mut String) {
+        write!(out, "{self}").expect("writing to string succeeds");
+    }
+}
+
+impl CopyValue for BigDecimal {
+    fn write_copy(&self, out: &mut String) {
+        write!(out, "{self}").expect("writing to string succeeds");
+    }
+}
+
+impl CopyValue for Vec<u8> {
+    fn write_copy(&self, out: &mut String) {
+        // bytea hex format, with the backslash escaped for copy
+        out.push_str("\\\\x");
+        out.push_str(&hex::encode(self));
+    }
+}
+
+impl CopyValue for String {
+    fn write_copy(&self, out: &mut String) {
+        for c in self.chars() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '\t' => out.push_str("\\t"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                c => out.push(c),
+            }
+        }
+    }
+}
+
+impl CopyValue for OffsetDateTime {
+    fn write_copy(&self, out: &mut String) {
+        let utc = self.to_offset(UtcOffset::UTC);
+
+        write!(
+            out,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:06}+00",
+            utc.year(),
+            u8::from(utc.month()),
+            utc.day(),
+            utc.hour(),
+            utc.minute(),
+            utc.second(),
+            utc.microsecond(),
+        )
+        .expect("writing to string succeeds");
+    }
+}
+
+impl<T: CopyValue> CopyValue for Option<T> {
+    fn write_copy(&self, out: &mut String) {
+        match self {
+            Some(value) => value.write_copy(out),
+            None => out.push_str("\\N"),
+        }
+    }
+}
+
+/// A single row being written in the text format of `COPY`.
+pub struct CopyRow<'a> {
+    out: &'a mut String,
+    columns: usize,
+}
+
+impl CopyRow<'_> {
+    pub fn push(&mut self, value: &impl CopyValue) -> &mut Self {
+        if self.columns > 0 {
+            self.out.push('\t');
+        }
+
+        value.write_copy(self.out);
+        self.columns += 1;
+
+        self
+    }
+}
+
+struct TableBuffer {
+    key: &'static [&'static str],
+    columns: &'static [&'static str],
+    data: String,
+    rows: u64,
+}
+
+impl TableBuffer {
+    async fn flush(
+        self,
+        tx: &mut Transaction<'_, Postgres>,
+        table: &'static str,
+    ) -> Result<u64, IndexerError> {
+        let staging = format!("bulk_{}", table.replace('.', "_"));
+        let columns = self.columns.join(", ");
+        let key = self.key.join(", ");
+        let updates = self
+            .columns
+            .iter()
+            .filter(|column| !self.key.contains(column))
+            .map(|column| format!("{column} = excluded.{column}"))
+            .join(", ");
+
+        // the staging table is dropped after the upsert, as a batch can be flushed more than once
+        sqlx::query(&format!(
+            "CREATE TEMP TABLE {staging} ON COMMIT DROP AS SELECT {columns} FROM {table} WITH NO DATA"
+        ))
+        .execute(tx.as_mut())
+        .await?;
+
+        // keeps the order of the rows, so the last row wins when a key is written more than once
+        sqlx::query(&format!(
+            "ALTER TABLE {staging} ADD COLUMN bulk_ordinal BIGINT GENERATED ALWAYS AS IDENTITY"
+        ))
+        .execute(tx.as_mut())
+        .await?;
+
+        let mut copy = tx
+            .as_mut()
+            .copy_in_raw(&format!("COPY {staging} ({columns}) FROM STDIN"))
+            .await?;
+        copy.send(self.data.into_bytes()).await?;
+        copy.finish().await?;
+
+        sqlx::query(&format!(
+            "
+            INSERT INTO {table} ({columns})
+            SELECT DISTINCT ON ({key}) {columns} FROM {staging}
+            ORDER BY {key}, bulk_ordinal DESC
+            ON CONFLICT ({key}) DO UPDATE SET {updates}
+            "
+        ))
+        .execute(tx.as_mut())
+        .await?;
+
+        sqlx::query(&format!("DROP TABLE {staging}"))
+            .execute(tx.as_mut())
+            .await?;
+
+        Ok(self.rows)
+    }
+}
+
+/// Buffers records of one or more messages, to be written with a single `COPY` per table.
+#[derive(Default)]
+pub struct BulkIngest {
+    tables: BTreeMap<&'static str, TableBuffer>,
+    packet_events: Vec<PacketEventNotification>,
+}
+
+impl BulkIngest {
+    /// Buffers the record, returning the changes it will cause once flushed.
+    pub fn push<R: CopyRecord>(&mut self, record: &R) -> Changes {
+        let buffer = self.tables.entry(R::TABLE).or_insert_with(|| TableBuffer {
+            key: R::KEY,
+            columns: R::COLUMNS,
+            data: String::new(),
+            rows: 0,
+        });
+
+        let mut row = CopyRow {
+            out: &mut buffer.data,
+            columns: 0,
+        };
+        record.write_row(&mut row);
+
+        debug_assert_eq!(
+            row.columns,
+            R::COLUMNS.len(),
+            "{} row does not match its columns",
+            R::TABLE
+        );
+
+        buffer.data.push('\n');
+        buffer.rows += 1;
+
+        if publish_records() {
+            self.packet_events.extend(record.packet_event());
+        }
+
+        Changes::with_single_insert::<R>()
+    }
+
+    /// The number of buffered rows.
+    pub fn rows(&self) -> u64 {
+        self.tables.values().map(|buffer| buffer.rows).sum()
+    }
+
+    /// Writes all buffered records and publishes their packet events. Must be called before
+    /// committing the transaction, and before replacing a block that may still be buffered.
+    pub async fn flush(&mut self, tx: &mut Transaction<'_, Postgres>) -> Result<u64, IndexerError> {
+        let mut rows = 0;
+
+        for (table, buffer) in std::mem::take(&mut self.tables) {
+            let table_rows = buffer.flush(tx, table).await?;
+            debug!("flushed {table_rows} rows into {table}");
+
+            rows += table_rows;
+        }
+
+        for packet_event in std::mem::take(&mut self.packet_events) {
+            notify_packet_event(
+                tx,
+                packet_event.kind,
+                packet_event.internal_chain_id,
+                packet_event.channel_id,
+                &packet_event.packet_hash,
+                &packet_event.transaction_hash,
+                packet_event.height,
+                packet_event.timestamp,
+            )
+            .await?;
+        }
+
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn copy(value: &impl CopyValue) -> String {
+        let mut out = String::new();
+        value.write_copy(&mut out);
+        out
+    }
+
+    #[test]
+    fn test_copy_values() {
+        assert_eq!(copy(&vec![0x01u8, 0xab]), "\\\\x01ab");
+        assert_eq!(copy(&"a\tb\\c\nd".to_string()), "a\\tb\\\\c\\nd");
+        assert_eq!(copy(&None::<i64>), "\\N");
+        assert_eq!(copy(&Some(42i64)), "42");
+        assert_eq!(
+            copy(&datetime!(2025-01-02 03:04:05.123456 +02:00)),
+            "2025-01-02 01:04:05.123456+00"
+        );
+    }
+
+    #[test]
+    fn test_copy_row() {
+        let mut out = String::new();
+        let mut row = CopyRow {
+            out: &mut out,
+            columns: 0,
+        };
+        row.push(&1i32)
+            .push(&None::<Vec<u8>>)
+            .push(&"x".to_string());
+
+        assert_eq!(row.columns, 3);
+        assert_eq!(out, "1\t\\N\tx");
+    }
+}
@@ -326,9 +326,12 @@ pub enum RecordKind {
     PacketTimeout,
     TokenBucketUpdate,
     WalletMutationEntry,
+    ContractLifecycle,
+    GovernanceProposal,
     PacketSendDecoded,
     PacketSendTransfers,
     PacketSendInstructionsSearch,
+    PacketRecvPlacement,
 }
 
 /// Trait for types that can be associated with a specific `RecordKind`.
@@ -8,7 +8,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue, PgValueExt,
+        ChainContext, InternalChainId, PgValue, PgValueExt, Record,
     },
 };
 
@@ -20,6 +20,7 @@ pub struct ChannelOpenConfirmRecord {
     pub timestamp: OffsetDateTime,
     pub transaction_hash: Vec<u8>,
     pub transaction_index: i64,
+    pub decoder_version: i32,
     pub transaction_event_index: Option<i64>,
     pub port_id: Vec<u8>,
     pub channel_id: i32,
@@ -49,6 +50,7 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, ChannelOpenConfirmEvent>>
             timestamp: value.event.header.timestamp.pg_value()?,
             transaction_hash: value.event.header.transaction_hash.pg_value()?,
             transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
             transaction_event_index: value.event.header.transaction_event_index.pg_value()?,
             port_id: value.event.port_id.pg_value()?,
             channel_id: value.event.channel_id.pg_value()?,
@@ -59,12 +61,9 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, ChannelOpenConfirmEvent>>
     }
 }
 
-impl ChannelOpenConfirmRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for ChannelOpenConfirmRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -76,13 +75,26 @@ impl ChannelOpenConfirmRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 transaction_event_index,
                 port_id,
                 channel_id,
                 connection_id,
                 counterparty_port_id,
                 counterparty_channel_id
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (internal_chain_id, height, event_index) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                transaction_event_index = excluded.transaction_event_index,
+                port_id = excluded.port_id,
+                channel_id = excluded.channel_id,
+                connection_id = excluded.connection_id,
+                counterparty_port_id = excluded.counterparty_port_id,
+                counterparty_channel_id = excluded.counterparty_channel_id
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -91,6 +103,7 @@ impl ChannelOpenConfirmRecord {
             self.timestamp,
             &self.transaction_hash[..],
             self.transaction_index,
+            self.decoder_version,
             self.transaction_event_index,
             &self.port_id[..],
             self.channel_id,
@@ -103,7 +116,9 @@ impl ChannelOpenConfirmRecord {
 
         Ok(Changes::with_single_insert::<Self>())
     }
+}
 
+impl ChannelOpenConfirmRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
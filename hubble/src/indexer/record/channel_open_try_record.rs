@@ -8,7 +8,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue,
+        ChainContext, InternalChainId, PgValue, Record,
     },
 };
 
@@ -19,6 +19,7 @@ pub struct ChannelOpenTryRecord {
     pub timestamp: OffsetDateTime,
     pub transaction_hash: Vec<u8>,
     pub transaction_index: i64,
+    pub decoder_version: i32,
     pub port_id: Vec<u8>,
     pub channel_id: i32,
     pub connection_id: i32,
@@ -45,6 +46,7 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, ChannelOpenTryEvent>> for Ch
             timestamp: value.event.header.timestamp.pg_value()?,
             transaction_hash: value.event.header.transaction_hash.pg_value()?,
             transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
             port_id: value.event.port_id.pg_value()?,
             channel_id: value.event.channel_id.pg_value()?,
             connection_id: value.event.connection_id.pg_value()?,
@@ -55,12 +57,9 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, ChannelOpenTryEvent>> for Ch
     }
 }
 
-impl ChannelOpenTryRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for ChannelOpenTryRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -71,13 +70,26 @@ impl ChannelOpenTryRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 port_id,
                 channel_id,
                 connection_id,
                 counterparty_port_id,
                 counterparty_channel_id,
                 counterparty_version
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (internal_chain_id, channel_id) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                height = excluded.height,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                port_id = excluded.port_id,
+                connection_id = excluded.connection_id,
+                counterparty_port_id = excluded.counterparty_port_id,
+                counterparty_channel_id = excluded.counterparty_channel_id,
+                counterparty_version = excluded.counterparty_version
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -85,6 +97,7 @@ impl ChannelOpenTryRecord {
             self.timestamp,
             &self.transaction_hash[..],
             self.transaction_index,
+            self.decoder_version,
             &self.port_id[..],
             self.channel_id,
             self.connection_id,
@@ -97,7 +110,9 @@ impl ChannelOpenTryRecord {
 
         Ok(Changes::with_single_insert::<Self>())
     }
+}
 
+impl ChannelOpenTryRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
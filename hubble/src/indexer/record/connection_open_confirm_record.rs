@@ -8,7 +8,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue,
+        ChainContext, InternalChainId, PgValue, Record,
     },
 };
 
@@ -19,6 +19,7 @@ pub struct ConnectionOpenConfirmRecord {
     pub timestamp: OffsetDateTime,
     pub transaction_hash: Vec<u8>,
     pub transaction_index: i64,
+    pub decoder_version: i32,
     pub connection_id: i32,
     pub client_id: i32,
     pub counterparty_client_id: i32,
@@ -45,6 +46,7 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, ConnectionOpenConfirmEvent>>
             timestamp: value.event.header.timestamp.pg_value()?,
             transaction_hash: value.event.header.transaction_hash.pg_value()?,
             transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
             // missing transaction_event_index in datamodel
             connection_id: value.event.connection_id.pg_value()?,
             client_id: value.event.client_id.pg_value()?,
@@ -54,12 +56,9 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, ConnectionOpenConfirmEvent>>
     }
 }
 
-impl ConnectionOpenConfirmRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for ConnectionOpenConfirmRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -70,11 +69,22 @@ impl ConnectionOpenConfirmRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 connection_id,
                 client_id,
                 counterparty_client_id,
                 counterparty_connection_id
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (internal_chain_id, connection_id) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                height = excluded.height,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                client_id = excluded.client_id,
+                counterparty_client_id = excluded.counterparty_client_id,
+                counterparty_connection_id = excluded.counterparty_connection_id
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -82,6 +92,7 @@ impl ConnectionOpenConfirmRecord {
             self.timestamp,
             &self.transaction_hash[..],
             self.transaction_index,
+            self.decoder_version,
             self.connection_id,
             self.client_id,
             self.counterparty_client_id,
@@ -92,7 +103,9 @@ impl ConnectionOpenConfirmRecord {
 
         Ok(Changes::with_single_insert::<Self>())
     }
+}
 
+impl ConnectionOpenConfirmRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
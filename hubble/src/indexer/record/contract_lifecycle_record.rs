@@ -0,0 +1,137 @@
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use tracing::trace;
+
+use crate::indexer::{
+    api::IndexerError,
+    event::{contract_lifecycle_event::ContractLifecycleEvent, types::BlockHeight},
+    handler::EventContext,
+    record::{
+        change_counter::{Changes, HasKind, RecordKind},
+        ChainContext, InternalChainId, PgValue, PgValueExt, Record,
+    },
+};
+
+pub struct ContractLifecycleRecord {
+    pub internal_chain_id: i32,
+    pub block_hash: Vec<u8>,
+    pub height: i64,
+    pub event_index: i64,
+    pub timestamp: OffsetDateTime,
+    pub transaction_hash: Vec<u8>,
+    pub transaction_index: i64,
+    pub decoder_version: i32,
+    pub transaction_event_index: Option<i64>,
+    pub contract_address_canonical: Vec<u8>,
+    pub action: String,
+    pub code_id: Option<i64>,
+    pub admin_address_canonical: Option<Vec<u8>>,
+}
+impl HasKind for ContractLifecycleRecord {
+    fn kind() -> RecordKind {
+        RecordKind::ContractLifecycle
+    }
+}
+
+impl<'a> TryFrom<&'a EventContext<'a, ChainContext, ContractLifecycleEvent>>
+    for ContractLifecycleRecord
+{
+    type Error = IndexerError;
+
+    fn try_from(
+        value: &'a EventContext<'a, ChainContext, ContractLifecycleEvent>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            internal_chain_id: value.context.internal_chain_id.pg_value()?,
+            block_hash: value.event.header.block_hash.pg_value()?,
+            height: value.event.header.height.pg_value()?,
+            event_index: value.event.header.event_index.pg_value()?,
+            timestamp: value.event.header.timestamp.pg_value()?,
+            transaction_hash: value.event.header.transaction_hash.pg_value()?,
+            transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
+            transaction_event_index: value.event.header.transaction_event_index.pg_value()?,
+            contract_address_canonical: value.event.contract_address_canonical.pg_value()?,
+            action: value.event.action.pg_value()?,
+            code_id: value.event.code_id.pg_value()?,
+            admin_address_canonical: value.event.admin_address_canonical.pg_value()?,
+        })
+    }
+}
+
+impl Record for ContractLifecycleRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
+
+        sqlx::query!(
+            r#"
+            INSERT INTO v2_sync.contract_lifecycle_sync (
+                internal_chain_id,
+                block_hash,
+                height,
+                event_index,
+                timestamp,
+                transaction_hash,
+                transaction_index,
+                decoder_version,
+                transaction_event_index,
+                contract_address_canonical,
+                action,
+                code_id,
+                admin_address_canonical
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (internal_chain_id, height, event_index) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                transaction_event_index = excluded.transaction_event_index,
+                contract_address_canonical = excluded.contract_address_canonical,
+                action = excluded.action,
+                code_id = excluded.code_id,
+                admin_address_canonical = excluded.admin_address_canonical
+            "#,
+            self.internal_chain_id,
+            &self.block_hash[..],
+            self.height,
+            self.event_index,
+            self.timestamp,
+            &self.transaction_hash[..],
+            self.transaction_index,
+            self.decoder_version,
+            self.transaction_event_index,
+            &self.contract_address_canonical[..],
+            self.action,
+            self.code_id,
+            self.admin_address_canonical.as_deref(),
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(Changes::with_single_insert::<Self>())
+    }
+}
+
+impl ContractLifecycleRecord {
+    pub async fn delete_by_chain_and_height(
+        tx: &mut Transaction<'_, Postgres>,
+        internal_chain_id: InternalChainId,
+        height: BlockHeight,
+    ) -> Result<Changes, IndexerError> {
+        trace!("delete_by_chain_and_height({internal_chain_id}, {height})");
+
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM v2_sync.contract_lifecycle_sync
+            WHERE internal_chain_id = $1 AND height = $2
+            "#,
+            internal_chain_id.pg_value()?,
+            height.pg_value()?
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(Changes::with_deletes::<Self>(result.rows_affected()))
+    }
+}
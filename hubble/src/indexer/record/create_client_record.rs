@@ -8,7 +8,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue,
+        ChainContext, InternalChainId, PgValue, Record,
     },
 };
 
@@ -19,6 +19,7 @@ pub struct CreateClientRecord {
     pub timestamp: OffsetDateTime,
     pub transaction_hash: Vec<u8>,
     pub transaction_index: i64,
+    pub decoder_version: i32,
     pub client_id: i64,
     pub client_type: String,
     pub counterparty_chain_id: String,
@@ -42,6 +43,7 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, CreateClientEvent>> for Crea
             timestamp: value.event.header.timestamp.pg_value()?,
             transaction_hash: value.event.header.transaction_hash.pg_value()?,
             transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
             client_id: value.event.client_id.pg_value()? as i64, // stored as bigint in postgres, should be i32
             client_type: value.event.client_type.pg_value()?,
             counterparty_chain_id: value.event.counterparty_chain_id.pg_value()?,
@@ -49,12 +51,9 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, CreateClientEvent>> for Crea
     }
 }
 
-impl CreateClientRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for CreateClientRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -65,10 +64,20 @@ impl CreateClientRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 client_id,
                 client_type,
                 counterparty_chain_id
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (internal_chain_id, client_id) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                height = excluded.height,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                client_type = excluded.client_type,
+                counterparty_chain_id = excluded.counterparty_chain_id
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -76,6 +85,7 @@ impl CreateClientRecord {
             self.timestamp,
             &self.transaction_hash[..],
             self.transaction_index,
+            self.decoder_version,
             self.client_id,
             self.client_type,
             self.counterparty_chain_id,
@@ -85,7 +95,9 @@ impl CreateClientRecord {
 
         Ok(Changes::with_single_insert::<Self>())
     }
+}
 
+impl CreateClientRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
@@ -8,7 +8,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue,
+        ChainContext, InternalChainId, PgValue, Record,
     },
 };
 
@@ -19,6 +19,7 @@ pub struct CreateLensClientRecord {
     pub timestamp: OffsetDateTime,
     pub transaction_hash: Vec<u8>,
     pub transaction_index: i64,
+    pub decoder_version: i32,
     pub client_id: i32,
     pub l1_client_id: i32,
     pub l2_client_id: i32,
@@ -45,6 +46,7 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, CreateLensClientEvent>>
             timestamp: value.event.header.timestamp.pg_value()?,
             transaction_hash: value.event.header.transaction_hash.pg_value()?,
             transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
             client_id: value.event.client_id.pg_value()?,
             l1_client_id: value.event.l1_client_id.pg_value()?,
             l2_client_id: value.event.l2_client_id.pg_value()?,
@@ -53,12 +55,9 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, CreateLensClientEvent>>
     }
 }
 
-impl CreateLensClientRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for CreateLensClientRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -69,11 +68,22 @@ impl CreateLensClientRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 client_id,
                 l1_client_id,
                 l2_client_id,
                 l2_chain_id
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (internal_chain_id, client_id) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                height = excluded.height,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                l1_client_id = excluded.l1_client_id,
+                l2_client_id = excluded.l2_client_id,
+                l2_chain_id = excluded.l2_chain_id
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -81,6 +91,7 @@ impl CreateLensClientRecord {
             self.timestamp,
             &self.transaction_hash[..],
             self.transaction_index,
+            self.decoder_version,
             self.client_id,
             self.l1_client_id,
             self.l2_client_id,
@@ -91,7 +102,9 @@ impl CreateLensClientRecord {
 
         Ok(Changes::with_single_insert::<Self>())
     }
+}
 
+impl CreateLensClientRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
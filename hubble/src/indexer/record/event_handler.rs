@@ -1,34 +1,42 @@
-use sqlx::{Postgres, Transaction};
-use tracing::{debug, trace};
+use sqlx::{Acquire, Postgres, Transaction};
+use tracing::{debug, trace, warn};
 
-use crate::indexer::{
-    api::IndexerError,
-    event::{supported::SupportedBlockEvent, types::BlockHeight},
-    record::{
-        change_counter::{Changes, LegacyRecord},
-        channel_open_ack_record::ChannelOpenAckRecord,
-        channel_open_confirm_record::ChannelOpenConfirmRecord,
-        channel_open_init_record::ChannelOpenInitRecord,
-        channel_open_try_record::ChannelOpenTryRecord,
-        connection_open_ack_record::ConnectionOpenAckRecord,
-        connection_open_confirm_record::ConnectionOpenConfirmRecord,
-        connection_open_init_record::ConnectionOpenInitRecord,
-        connection_open_try_record::ConnectionOpenTryRecord,
-        create_client_record::CreateClientRecord,
-        create_lens_client_record::CreateLensClientRecord,
-        packet_ack_record::PacketAckRecord,
-        packet_recv_record::PacketRecvRecord,
-        packet_send_decoded_record::PacketSendDecodedRecord,
-        packet_send_instructions_search_record::PacketSendInstructionsSearchRecord,
-        packet_send_record::PacketSendRecord,
-        packet_send_transfers_record::PacketSendTransfersRecord,
-        packet_timeout_record::PacketTimeoutRecord,
-        token_bucket_update_record::TokenBucketUpdateRecord,
-        update_client_record::UpdateClientRecord,
-        wallet_mutation_entry_record::WalletMutationEntryRecord,
-        write_ack_record::WriteAckRecord,
-        ChainContext, InternalChainId, PgValue,
+use crate::{
+    indexer::{
+        api::IndexerError,
+        event::{supported::SupportedBlockEvent, types::BlockHeight},
+        postgres::failed_event::insert_failed_event,
+        record::{
+            bulk::BulkIngest,
+            change_counter::{Changes, LegacyRecord},
+            channel_open_ack_record::ChannelOpenAckRecord,
+            channel_open_confirm_record::ChannelOpenConfirmRecord,
+            channel_open_init_record::ChannelOpenInitRecord,
+            channel_open_try_record::ChannelOpenTryRecord,
+            connection_open_ack_record::ConnectionOpenAckRecord,
+            connection_open_confirm_record::ConnectionOpenConfirmRecord,
+            connection_open_init_record::ConnectionOpenInitRecord,
+            connection_open_try_record::ConnectionOpenTryRecord,
+            contract_lifecycle_record::ContractLifecycleRecord,
+            create_client_record::CreateClientRecord,
+            create_lens_client_record::CreateLensClientRecord,
+            governance_proposal_record::GovernanceProposalRecord,
+            packet_ack_record::PacketAckRecord,
+            packet_recv_placement_record::PacketRecvPlacementRecord,
+            packet_recv_record::PacketRecvRecord,
+            packet_send_decoded_record::PacketSendDecodedRecord,
+            packet_send_instructions_search_record::PacketSendInstructionsSearchRecord,
+            packet_send_record::PacketSendRecord,
+            packet_send_transfers_record::PacketSendTransfersRecord,
+            packet_timeout_record::PacketTimeoutRecord,
+            token_bucket_update_record::TokenBucketUpdateRecord,
+            update_client_record::UpdateClientRecord,
+            wallet_mutation_entry_record::WalletMutationEntryRecord,
+            write_ack_record::WriteAckRecord,
+            ChainContext, InternalChainId, PgValue,
+        },
     },
+    metrics::FAILED_EVENT_COLLECTOR,
 };
 
 pub async fn delete_event_data_at_height(
@@ -100,6 +108,9 @@ pub async fn delete_event_data_at_height(
             PacketSendRecord::delete_by_chain_and_height(tx, internal_chain_id, height).await?;
         changes +=
             PacketRecvRecord::delete_by_chain_and_height(tx, internal_chain_id, height).await?;
+        changes +=
+            PacketRecvPlacementRecord::delete_by_chain_and_height(tx, internal_chain_id, height)
+                .await?;
         changes +=
             WriteAckRecord::delete_by_chain_and_height(tx, internal_chain_id, height).await?;
         changes +=
@@ -112,6 +123,12 @@ pub async fn delete_event_data_at_height(
         changes +=
             WalletMutationEntryRecord::delete_by_chain_and_height(tx, internal_chain_id, height)
                 .await?;
+        changes +=
+            ContractLifecycleRecord::delete_by_chain_and_height(tx, internal_chain_id, height)
+                .await?;
+        changes +=
+            GovernanceProposalRecord::delete_by_chain_and_height(tx, internal_chain_id, height)
+                .await?;
         changes +=
             PacketSendDecodedRecord::delete_by_chain_and_height(tx, internal_chain_id, height)
                 .await?;
@@ -167,17 +184,100 @@ pub async fn handle_block_events(
     tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     chain_context: &ChainContext,
     block_events: &[&SupportedBlockEvent],
+    mut bulk: Option<&mut BulkIngest>,
 ) -> Result<Changes, IndexerError> {
     let mut changes = Changes::default();
 
     for block_event in block_events {
-        changes += handle_block_event(tx, chain_context, block_event).await?;
+        // in bulk mode, buffer the records that can be copied. they are written when the bulk
+        // ingest is flushed
+        if let Some(bulk) = bulk.as_deref_mut() {
+            match buffer_block_event(bulk, chain_context, block_event) {
+                Ok(Some(event_changes)) => {
+                    changes += event_changes;
+                    continue;
+                }
+                Ok(None) => {}
+                Err(error) if error.is_transient() => return Err(error),
+                Err(error) => {
+                    handle_failed_block_event(tx, chain_context, block_event, error).await?;
+                    continue;
+                }
+            }
+        }
+
+        // handle each event in a savepoint, so a failing event can be set aside (and retried
+        // later) without blocking the other events of the chain
+        let mut savepoint = tx.begin().await?;
+
+        match handle_block_event(&mut savepoint, chain_context, block_event).await {
+            Ok(event_changes) => {
+                savepoint.commit().await?;
+                changes += event_changes;
+            }
+            // transient errors fail the block, which is then handled again as a whole instead
+            // of setting the event aside as failed
+            Err(error) if error.is_transient() => return Err(error),
+            Err(error) => {
+                savepoint.rollback().await?;
+
+                handle_failed_block_event(tx, chain_context, block_event, error).await?;
+            }
+        }
     }
 
     Ok(changes)
 }
 
-async fn handle_block_event(
+async fn handle_failed_block_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    chain_context: &ChainContext,
+    block_event: &SupportedBlockEvent,
+    error: IndexerError,
+) -> Result<(), IndexerError> {
+    warn!("failed handling {block_event:?}: {error} => storing as failed event");
+    insert_failed_event(
+        tx,
+        chain_context.internal_chain_id,
+        block_event,
+        &error.to_string(),
+    )
+    .await?;
+
+    FAILED_EVENT_COLLECTOR
+        .with_label_values(&[&chain_context.internal_chain_id.to_string(), "failed"])
+        .inc();
+
+    Ok(())
+}
+
+/// Buffers the records of events without side effects for bulk ingestion, returns `None` if the
+/// event has to be handled in the transaction.
+fn buffer_block_event(
+    bulk: &mut BulkIngest,
+    chain_context: &ChainContext,
+    block_event: &SupportedBlockEvent,
+) -> Result<Option<Changes>, IndexerError> {
+    Ok(Some(match block_event {
+        SupportedBlockEvent::UpdateClient { inner } => {
+            chain_context.with_event(inner).buffer(bulk)?
+        }
+        SupportedBlockEvent::PacketRecv { inner } if inner.placement.is_none() => {
+            chain_context.with_event(inner).buffer(bulk)?
+        }
+        SupportedBlockEvent::WriteAck { inner } => chain_context.with_event(inner).buffer(bulk)?,
+        SupportedBlockEvent::PacketAck { inner } => chain_context.with_event(inner).buffer(bulk)?,
+        SupportedBlockEvent::PacketTimeout { inner } => {
+            chain_context.with_event(inner).buffer(bulk)?
+        }
+        SupportedBlockEvent::WalletMutationEntry { inner } => {
+            chain_context.with_event(inner).buffer(bulk)?
+        }
+        _ => return Ok(None),
+    }))
+}
+
+pub async fn handle_block_event(
     tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     chain_context: &ChainContext,
     block_event: &SupportedBlockEvent,
@@ -196,6 +296,10 @@ async fn handle_block_event(
                     "
                 INSERT INTO v2_evm.logs (internal_chain_id, block_hash, data, height, time)
                 VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (internal_chain_id, height) DO UPDATE SET
+                    block_hash = excluded.block_hash,
+                    data = excluded.data,
+                    time = excluded.time
                 ",
                     internal_chain_id,
                     block_hash,
@@ -219,6 +323,10 @@ async fn handle_block_event(
                     "
                 INSERT INTO v2_cosmos.blocks (internal_chain_id, hash, data, height, time)
                 VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (internal_chain_id, height) DO UPDATE SET
+                    hash = excluded.hash,
+                    data = excluded.data,
+                    time = excluded.time
                 ",
                     internal_chain_id,
                     hash,
@@ -242,6 +350,11 @@ async fn handle_block_event(
                 sqlx::query!("
                 INSERT INTO v2_cosmos.transactions (internal_chain_id, block_hash, height, hash, data, index) 
                 VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (internal_chain_id, hash) DO UPDATE SET
+                    block_hash = excluded.block_hash,
+                    height = excluded.height,
+                    data = excluded.data,
+                    index = excluded.index
                 ",
                     internal_chain_id, block_hash, height.pg_value()?, hash, data, index)
                 .execute(tx.as_mut())
@@ -263,6 +376,13 @@ async fn handle_block_event(
                 sqlx::query!("
                 INSERT INTO v2_cosmos.events (internal_chain_id, block_hash, height, transaction_hash, index, transaction_index, data, time, flow)
                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (internal_chain_id, height, index) DO UPDATE SET
+                    block_hash = excluded.block_hash,
+                    transaction_hash = excluded.transaction_hash,
+                    transaction_index = excluded.transaction_index,
+                    data = excluded.data,
+                    time = excluded.time,
+                    flow = excluded.flow
                 ",
                     internal_chain_id,
                     block_hash,
@@ -336,5 +456,11 @@ async fn handle_block_event(
         SupportedBlockEvent::WalletMutationEntry { inner } => {
             chain_context.with_event(inner).handle(tx).await?
         },
+        SupportedBlockEvent::ContractLifecycle { inner } => {
+            chain_context.with_event(inner).handle(tx).await?
+        },
+        SupportedBlockEvent::GovernanceProposal { inner } => {
+            chain_context.with_event(inner).handle(tx).await?
+        },
     })
 }
@@ -0,0 +1,142 @@
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use tracing::trace;
+
+use crate::indexer::{
+    api::IndexerError,
+    event::{governance_proposal_event::GovernanceProposalEvent, types::BlockHeight},
+    handler::EventContext,
+    record::{
+        change_counter::{Changes, HasKind, RecordKind},
+        ChainContext, InternalChainId, PgValue, PgValueExt, Record,
+    },
+};
+
+pub struct GovernanceProposalRecord {
+    pub internal_chain_id: i32,
+    pub block_hash: Vec<u8>,
+    pub height: i64,
+    pub event_index: i64,
+    pub timestamp: OffsetDateTime,
+    pub transaction_hash: Vec<u8>,
+    pub transaction_index: i64,
+    pub decoder_version: i32,
+    pub transaction_event_index: Option<i64>,
+    pub proposal_id: String,
+    pub action: String,
+    pub message_types: Vec<String>,
+    pub target_contract_canonical: Option<Vec<u8>>,
+    pub delay: Option<i64>,
+}
+impl HasKind for GovernanceProposalRecord {
+    fn kind() -> RecordKind {
+        RecordKind::GovernanceProposal
+    }
+}
+
+impl<'a> TryFrom<&'a EventContext<'a, ChainContext, GovernanceProposalEvent>>
+    for GovernanceProposalRecord
+{
+    type Error = IndexerError;
+
+    fn try_from(
+        value: &'a EventContext<'a, ChainContext, GovernanceProposalEvent>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            internal_chain_id: value.context.internal_chain_id.pg_value()?,
+            block_hash: value.event.header.block_hash.pg_value()?,
+            height: value.event.header.height.pg_value()?,
+            event_index: value.event.header.event_index.pg_value()?,
+            timestamp: value.event.header.timestamp.pg_value()?,
+            transaction_hash: value.event.header.transaction_hash.pg_value()?,
+            transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
+            transaction_event_index: value.event.header.transaction_event_index.pg_value()?,
+            proposal_id: value.event.proposal_id.pg_value()?,
+            action: value.event.action.pg_value()?,
+            message_types: value.event.message_types.clone(),
+            target_contract_canonical: value.event.target_contract_canonical.pg_value()?,
+            delay: value.event.delay.pg_value()?,
+        })
+    }
+}
+
+impl Record for GovernanceProposalRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
+
+        sqlx::query!(
+            r#"
+            INSERT INTO v2_sync.governance_proposal_sync (
+                internal_chain_id,
+                block_hash,
+                height,
+                event_index,
+                timestamp,
+                transaction_hash,
+                transaction_index,
+                decoder_version,
+                transaction_event_index,
+                proposal_id,
+                action,
+                message_types,
+                target_contract_canonical,
+                delay
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (internal_chain_id, height, event_index) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                transaction_event_index = excluded.transaction_event_index,
+                proposal_id = excluded.proposal_id,
+                action = excluded.action,
+                message_types = excluded.message_types,
+                target_contract_canonical = excluded.target_contract_canonical,
+                delay = excluded.delay
+            "#,
+            self.internal_chain_id,
+            &self.block_hash[..],
+            self.height,
+            self.event_index,
+            self.timestamp,
+            &self.transaction_hash[..],
+            self.transaction_index,
+            self.decoder_version,
+            self.transaction_event_index,
+            self.proposal_id,
+            self.action,
+            &self.message_types[..],
+            self.target_contract_canonical.as_deref(),
+            self.delay,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(Changes::with_single_insert::<Self>())
+    }
+}
+
+impl GovernanceProposalRecord {
+    pub async fn delete_by_chain_and_height(
+        tx: &mut Transaction<'_, Postgres>,
+        internal_chain_id: InternalChainId,
+        height: BlockHeight,
+    ) -> Result<Changes, IndexerError> {
+        trace!("delete_by_chain_and_height({internal_chain_id}, {height})");
+
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM v2_sync.governance_proposal_sync
+            WHERE internal_chain_id = $1 AND height = $2
+            "#,
+            internal_chain_id.pg_value()?,
+            height.pg_value()?
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(Changes::with_deletes::<Self>(result.rows_affected()))
+    }
+}
@@ -1,21 +1,26 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    future::Future,
+};
 
-use sqlx::types::BigDecimal;
+use sqlx::{types::BigDecimal, Postgres, Transaction};
 use time::OffsetDateTime;
 
 use crate::indexer::{
     api::IndexerError,
+    decoder_version::DecoderVersion,
     event::types::{
-        Acknowledgement, BlockHash, BlockHeight, BlockTimestamp, CanonicalChainId, Capacity,
-        ChannelId, ChannelVersion, ClientId, ClientType, ConnectionId, ContractAddress, Denom,
-        EventIndex, Maker, MakerMsg, MessageHash, MessageSequence, MutationAmount,
-        MutationDirection, NatsConsumerSequence, NatsStreamSequence, PacketData, PacketHash,
-        PortId, RefillRate, TimeoutTimestamp, TransactionEventIndex, TransactionHash,
-        TransactionIndex, UniversalChainId, WalletAddress,
+        Acknowledgement, AdminAddress, BlockHash, BlockHeight, BlockTimestamp, CanonicalChainId,
+        Capacity, ChannelId, ChannelVersion, ClientId, ClientType, CodeId, ConnectionId,
+        ContractAddress, ContractLifecycleAction, Denom, EventIndex, Initiator, Maker, MakerMsg,
+        MessageHash, MessageSequence, MutationAmount, MutationDirection, NatsConsumerSequence,
+        NatsStreamSequence, PacketData, PacketHash, PortId, PriorityFee, ProposalAction,
+        ProposalDelay, ProposalId, RefillRate, TimeoutTimestamp, TransactionEventIndex,
+        TransactionHash, TransactionIndex, TransactionSender, UniversalChainId, WalletAddress,
     },
     handler::{
         types::{
-            AddressCanonical, AddressDisplay, AddressZkgm, Amount, Fee, InstructionHash,
+            AddressCanonical, AddressDisplay, AddressZkgm, Amount, AmountUsd, Fee, InstructionHash,
             InstructionIndex, InstructionOpcode, InstructionPath, InstructionRootPath,
             InstructionRootSalt, InstructionType, InstructionVersion, OperandContractAddress,
             OperandSender, PacketShape, RpcType, TokenDecimals, TokenName, TokenPath, TokenSymbol,
@@ -23,8 +28,10 @@ use crate::indexer::{
         },
         EventContext,
     },
+    record::change_counter::{Changes, HasKind},
 };
 
+pub(crate) mod bulk;
 pub(crate) mod change_counter;
 pub(crate) mod channel_meta_data;
 pub(crate) mod channel_open_ack_record;
@@ -35,10 +42,13 @@ pub(crate) mod connection_open_ack_record;
 pub(crate) mod connection_open_confirm_record;
 pub(crate) mod connection_open_init_record;
 pub(crate) mod connection_open_try_record;
+pub(crate) mod contract_lifecycle_record;
 pub(crate) mod create_client_record;
 pub(crate) mod create_lens_client_record;
 pub(crate) mod event_handler;
+pub(crate) mod governance_proposal_record;
 pub(crate) mod packet_ack_record;
+pub(crate) mod packet_recv_placement_record;
 pub(crate) mod packet_recv_record;
 pub(crate) mod packet_send_decoded_record;
 pub(crate) mod packet_send_instructions_search_record;
@@ -50,6 +60,18 @@ pub(crate) mod update_client_record;
 pub(crate) mod wallet_mutation_entry_record;
 pub(crate) mod write_ack_record;
 
+/// A record that is written to the database when its event is indexed.
+///
+/// Records are upserted on their natural key, so replaying a block range (for example after a
+/// crash between writing the records and committing the height) overwrites the existing rows
+/// instead of failing on a unique constraint.
+pub trait Record: HasKind {
+    fn upsert(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> impl Future<Output = Result<Changes, IndexerError>> + Send;
+}
+
 pub trait PgValue<T, E = IndexerError> {
     fn pg_value(&self) -> Result<T, E>;
 }
@@ -526,6 +548,11 @@ impl PgValue<i64> for TransactionEventIndex {
         })
     }
 }
+impl PgValue<i32> for DecoderVersion {
+    fn pg_value(&self) -> Result<i32, IndexerError> {
+        Ok((*self).into())
+    }
+}
 impl PgValue<OffsetDateTime> for BlockTimestamp {
     fn pg_value(&self) -> Result<OffsetDateTime, IndexerError> {
         Ok(self.0)
@@ -565,11 +592,26 @@ impl PgValue<Vec<u8>> for WalletAddress {
         Ok(self.0.to_vec())
     }
 }
+impl PgValue<Vec<u8>> for TransactionSender {
+    fn pg_value(&self) -> Result<Vec<u8>, IndexerError> {
+        Ok(self.0.to_vec())
+    }
+}
+impl PgValue<Vec<u8>> for Initiator {
+    fn pg_value(&self) -> Result<Vec<u8>, IndexerError> {
+        Ok(self.0.to_vec())
+    }
+}
 impl PgValue<BigDecimal> for MutationAmount {
     fn pg_value(&self) -> Result<BigDecimal, IndexerError> {
         Ok(BigDecimal::new(self.0.into(), 0))
     }
 }
+impl PgValue<BigDecimal> for PriorityFee {
+    fn pg_value(&self) -> Result<BigDecimal, IndexerError> {
+        Ok(BigDecimal::new(self.0.into(), 0))
+    }
+}
 impl PgValue<String> for MutationDirection {
     fn pg_value(&self) -> Result<String, IndexerError> {
         Ok(match self {
@@ -579,6 +621,60 @@ impl PgValue<String> for MutationDirection {
     }
 }
 
+impl PgValue<i64> for CodeId {
+    fn pg_value(&self) -> Result<i64, IndexerError> {
+        i64::try_from(self.0).map_err(|_| {
+            IndexerError::InternalCannotMapToDatabaseDomain(
+                "code-id".to_string(),
+                self.0.to_string(),
+            )
+        })
+    }
+}
+impl PgValue<Vec<u8>> for AdminAddress {
+    fn pg_value(&self) -> Result<Vec<u8>, IndexerError> {
+        Ok(self.0.to_vec())
+    }
+}
+impl PgValue<String> for ContractLifecycleAction {
+    fn pg_value(&self) -> Result<String, IndexerError> {
+        Ok(match self {
+            ContractLifecycleAction::Instantiate => "instantiate",
+            ContractLifecycleAction::Migrate => "migrate",
+            ContractLifecycleAction::UpdateAdmin => "update_admin",
+            ContractLifecycleAction::ClearAdmin => "clear_admin",
+        }
+        .to_string())
+    }
+}
+
+impl PgValue<String> for ProposalId {
+    fn pg_value(&self) -> Result<String, IndexerError> {
+        Ok(self.0.clone())
+    }
+}
+impl PgValue<String> for ProposalAction {
+    fn pg_value(&self) -> Result<String, IndexerError> {
+        Ok(match self {
+            ProposalAction::Submitted => "submitted",
+            ProposalAction::Scheduled => "scheduled",
+            ProposalAction::Executed => "executed",
+            ProposalAction::Cancelled => "cancelled",
+        }
+        .to_string())
+    }
+}
+impl PgValue<i64> for ProposalDelay {
+    fn pg_value(&self) -> Result<i64, IndexerError> {
+        i64::try_from(self.0).map_err(|_| {
+            IndexerError::InternalCannotMapToDatabaseDomain(
+                "proposal-delay".to_string(),
+                self.0.to_string(),
+            )
+        })
+    }
+}
+
 impl PgValue<i32> for TransferIndex {
     fn pg_value(&self) -> Result<i32, IndexerError> {
         i32::try_from(self.0).map_err(|_| {
@@ -609,6 +705,11 @@ impl PgValue<BigDecimal> for Amount {
         Ok(BigDecimal::new(self.0.into(), 0))
     }
 }
+impl PgValue<BigDecimal> for AmountUsd {
+    fn pg_value(&self) -> Result<BigDecimal, IndexerError> {
+        Ok(self.0.clone())
+    }
+}
 impl PgValue<String> for TokenName {
     fn pg_value(&self) -> Result<String, IndexerError> {
         Ok(self.0.clone())
@@ -688,6 +789,7 @@ impl PgValue<String> for PacketShape {
             PacketShape::BatchV0TransferV1 => "batch_v0_transfer_v1",
             PacketShape::BatchV0TransferV1Fee => "batch_v0_transfer_v1_fee",
             PacketShape::TransferV1 => "transfer_v1",
+            PacketShape::BatchV0TransfersV1 => "batch_v0_transfers_v1",
         }
         .to_string())
     }
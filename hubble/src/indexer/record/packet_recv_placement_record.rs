@@ -0,0 +1,187 @@
+use sqlx::{types::BigDecimal, Postgres, Transaction};
+use time::OffsetDateTime;
+use tracing::trace;
+
+use crate::indexer::{
+    api::IndexerError,
+    enrich::mev::MevPattern,
+    event::types::{BlockHeight, TransactionPlacement},
+    record::{
+        change_counter::{Changes, HasKind, RecordKind},
+        packet_recv_record::PacketRecvRecord,
+        InternalChainId, PgValue, Record,
+    },
+};
+
+pub struct PacketRecvPlacementRecord {
+    pub internal_chain_id: i32,
+    pub block_hash: Vec<u8>,
+    pub height: i64,
+    pub event_index: i64,
+    pub timestamp: OffsetDateTime,
+    pub transaction_hash: Vec<u8>,
+    pub transaction_index: i64,
+    pub channel_id: i32,
+    pub packet_hash: Vec<u8>,
+    pub block_transaction_count: i64,
+    pub transaction_sender: Vec<u8>,
+    pub priority_fee: BigDecimal,
+    pub previous_transaction_hash: Option<Vec<u8>>,
+    pub previous_transaction_sender: Option<Vec<u8>>,
+    pub previous_priority_fee: Option<BigDecimal>,
+    pub next_transaction_hash: Option<Vec<u8>>,
+    pub next_transaction_sender: Option<Vec<u8>>,
+    pub next_priority_fee: Option<BigDecimal>,
+    pub mev_patterns: Vec<String>,
+    pub network: String,
+}
+impl HasKind for PacketRecvPlacementRecord {
+    fn kind() -> RecordKind {
+        RecordKind::PacketRecvPlacement
+    }
+}
+
+impl TryFrom<(&PacketRecvRecord, &TransactionPlacement, &Vec<MevPattern>)>
+    for PacketRecvPlacementRecord
+{
+    type Error = IndexerError;
+
+    fn try_from(
+        (record, placement, patterns): (&PacketRecvRecord, &TransactionPlacement, &Vec<MevPattern>),
+    ) -> Result<Self, Self::Error> {
+        let previous = placement.previous.as_ref();
+        let next = placement.next.as_ref();
+
+        Ok(Self {
+            internal_chain_id: record.internal_chain_id,
+            block_hash: record.block_hash.clone(),
+            height: record.height,
+            event_index: record.event_index,
+            timestamp: record.timestamp,
+            transaction_hash: record.transaction_hash.clone(),
+            transaction_index: record.transaction_index,
+            channel_id: record.channel_id,
+            packet_hash: record.packet_hash.clone(),
+            block_transaction_count: i64::try_from(placement.block_transaction_count).map_err(
+                |_| {
+                    IndexerError::InternalCannotMapToDatabaseDomain(
+                        "block-transaction-count-i64".to_string(),
+                        placement.block_transaction_count.to_string(),
+                    )
+                },
+            )?,
+            transaction_sender: placement.transaction_sender.pg_value()?,
+            priority_fee: placement.priority_fee.pg_value()?,
+            previous_transaction_hash: previous
+                .map(|p| p.transaction_hash.pg_value())
+                .transpose()?,
+            previous_transaction_sender: previous
+                .map(|p| p.transaction_sender.pg_value())
+                .transpose()?,
+            previous_priority_fee: previous.map(|p| p.priority_fee.pg_value()).transpose()?,
+            next_transaction_hash: next.map(|n| n.transaction_hash.pg_value()).transpose()?,
+            next_transaction_sender: next.map(|n| n.transaction_sender.pg_value()).transpose()?,
+            next_priority_fee: next.map(|n| n.priority_fee.pg_value()).transpose()?,
+            mev_patterns: patterns.iter().map(ToString::to_string).collect(),
+            network: record.network.clone(),
+        })
+    }
+}
+
+impl Record for PacketRecvPlacementRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
+
+        sqlx::query!(
+            r#"
+            INSERT INTO v2_sync.packet_recv_placement_sync (
+                internal_chain_id,
+                block_hash,
+                height,
+                event_index,
+                timestamp,
+                transaction_hash,
+                transaction_index,
+                channel_id,
+                packet_hash,
+                block_transaction_count,
+                transaction_sender,
+                priority_fee,
+                previous_transaction_hash,
+                previous_transaction_sender,
+                previous_priority_fee,
+                next_transaction_hash,
+                next_transaction_sender,
+                next_priority_fee,
+                mev_patterns,
+                network
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
+            ON CONFLICT (internal_chain_id, height, event_index) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                channel_id = excluded.channel_id,
+                packet_hash = excluded.packet_hash,
+                block_transaction_count = excluded.block_transaction_count,
+                transaction_sender = excluded.transaction_sender,
+                priority_fee = excluded.priority_fee,
+                previous_transaction_hash = excluded.previous_transaction_hash,
+                previous_transaction_sender = excluded.previous_transaction_sender,
+                previous_priority_fee = excluded.previous_priority_fee,
+                next_transaction_hash = excluded.next_transaction_hash,
+                next_transaction_sender = excluded.next_transaction_sender,
+                next_priority_fee = excluded.next_priority_fee,
+                mev_patterns = excluded.mev_patterns,
+                network = excluded.network
+            "#,
+            self.internal_chain_id,
+            &self.block_hash[..],
+            self.height,
+            self.event_index,
+            self.timestamp,
+            &self.transaction_hash[..],
+            self.transaction_index,
+            self.channel_id,
+            &self.packet_hash[..],
+            self.block_transaction_count,
+            &self.transaction_sender[..],
+            self.priority_fee,
+            self.previous_transaction_hash,
+            self.previous_transaction_sender,
+            self.previous_priority_fee,
+            self.next_transaction_hash,
+            self.next_transaction_sender,
+            self.next_priority_fee,
+            &self.mev_patterns[..],
+            self.network,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(Changes::with_single_insert::<Self>())
+    }
+}
+
+impl PacketRecvPlacementRecord {
+    pub async fn delete_by_chain_and_height(
+        tx: &mut Transaction<'_, Postgres>,
+        internal_chain_id: InternalChainId,
+        height: BlockHeight,
+    ) -> Result<Changes, IndexerError> {
+        trace!("delete_by_chain_and_height({internal_chain_id}, {height})");
+
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM v2_sync.packet_recv_placement_sync
+            WHERE internal_chain_id = $1 AND height = $2
+            "#,
+            internal_chain_id.pg_value()?,
+            height.pg_value()?
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(Changes::with_deletes::<Self>(result.rows_affected()))
+    }
+}
@@ -10,7 +10,7 @@ use crate::indexer::{
     record::{
         change_counter::{Changes, HasKind, RecordKind},
         packet_send_record::PacketSendRecord,
-        InternalChainId, PgValue,
+        InternalChainId, PgValue, Record,
     },
 };
 
@@ -121,12 +121,9 @@ impl
     }
 }
 
-impl PacketSendDecodedRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for PacketSendDecodedRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -175,6 +172,40 @@ impl PacketSendDecodedRecord {
 
                 counterparty_network
             ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36)
+            ON CONFLICT (internal_chain_id, height, event_index) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                transaction_event_index = excluded.transaction_event_index,
+                channel_id = excluded.channel_id,
+                packet_hash = excluded.packet_hash,
+                source_channel_id = excluded.source_channel_id,
+                destination_channel_id = excluded.destination_channel_id,
+                timeout_height = excluded.timeout_height,
+                timeout_timestamp = excluded.timeout_timestamp,
+                data = excluded.data,
+                data_decoded = excluded.data_decoded,
+                data_decoded_flattened = excluded.data_decoded_flattened,
+                chain_id = excluded.chain_id,
+                counterparty_chain_id = excluded.counterparty_chain_id,
+                client_id = excluded.client_id,
+                counterparty_client_id = excluded.counterparty_client_id,
+                connection_id = excluded.connection_id,
+                counterparty_connection_id = excluded.counterparty_connection_id,
+                port_id = excluded.port_id,
+                counterparty_port_id = excluded.counterparty_port_id,
+                channel_version = excluded.channel_version,
+                client_type = excluded.client_type,
+                internal_counterparty_chain_id = excluded.internal_counterparty_chain_id,
+                sort_order = excluded.sort_order,
+                rpc_type = excluded.rpc_type,
+                counterparty_rpc_type = excluded.counterparty_rpc_type,
+                universal_chain_id = excluded.universal_chain_id,
+                counterparty_universal_chain_id = excluded.counterparty_universal_chain_id,
+                structure = excluded.structure,
+                network = excluded.network,
+                counterparty_network = excluded.counterparty_network
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -218,7 +249,9 @@ impl PacketSendDecodedRecord {
 
         Ok(Changes::with_single_insert::<Self>())
     }
+}
 
+impl PacketSendDecodedRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
@@ -80,11 +80,11 @@ impl TryFrom<(&PacketSendRecord, &Instruction, &ChannelMetaData, &String)>
 }
 
 impl PacketSendInstructionsSearchRecord {
-    pub async fn insert_batch(
+    pub async fn upsert_batch(
         tx: &mut Transaction<'_, Postgres>,
         records: &[PacketSendInstructionsSearchRecord],
     ) -> Result<Changes, IndexerError> {
-        trace!("insert_batch({} records)", records.len());
+        trace!("upsert_batch({} records)", records.len());
 
         if records.is_empty() {
             return Ok(Changes::default());
@@ -141,6 +141,27 @@ impl PacketSendInstructionsSearchRecord {
                 .push_bind(&record.sort_order);
         });
 
+        query_builder.push(
+            " ON CONFLICT (internal_chain_id, packet_hash, instruction_index) DO UPDATE SET
+                internal_counterparty_chain_id = excluded.internal_counterparty_chain_id,
+                height = excluded.height,
+                transaction_hash = excluded.transaction_hash,
+                block_hash = excluded.block_hash,
+                timestamp = excluded.timestamp,
+                instruction_hash = excluded.instruction_hash,
+                instruction_type = excluded.instruction_type,
+                path = excluded.path,
+                salt = excluded.salt,
+                instruction_path = excluded.instruction_path,
+                version = excluded.version,
+                opcode = excluded.opcode,
+                operand_sender = excluded.operand_sender,
+                operand_contract_address = excluded.operand_contract_address,
+                network = excluded.network,
+                counterparty_network = excluded.counterparty_network,
+                sort_order = excluded.sort_order",
+        );
+
         let query = query_builder.build();
         query.execute(&mut **tx).await?;
 
@@ -4,14 +4,16 @@ use tracing::trace;
 
 use crate::indexer::{
     api::IndexerError,
+    decoder_version::stored_decoder_version,
     event::{
         packet_send_event::PacketSendEvent,
         types::{BlockHeight, UniversalChainId},
     },
     handler::EventContext,
+    postgres::notify::notify_packet_event,
     record::{
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue, PgValueExt,
+        ChainContext, InternalChainId, PgValue, PgValueExt, Record,
     },
 };
 
@@ -24,6 +26,7 @@ pub struct PacketSendRecord {
     pub timestamp: OffsetDateTime,
     pub transaction_hash: Vec<u8>,
     pub transaction_index: i64,
+    pub decoder_version: i32,
     pub transaction_event_index: Option<i64>,
     pub channel_id: i32,
     pub packet_hash: Vec<u8>,
@@ -33,6 +36,8 @@ pub struct PacketSendRecord {
     pub timeout_timestamp: BigDecimal,
     pub data: Vec<u8>,
     pub network: String,
+    pub transaction_sender: Option<Vec<u8>>,
+    pub initiator: Option<Vec<u8>>,
 }
 impl HasKind for PacketSendRecord {
     fn kind() -> RecordKind {
@@ -54,6 +59,7 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, PacketSendEvent>> for Packet
             timestamp: value.event.header.timestamp.pg_value()?,
             transaction_hash: value.event.header.transaction_hash.pg_value()?,
             transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
             transaction_event_index: value.event.header.transaction_event_index.pg_value()?,
             channel_id: value.event.channel_id.pg_value()?,
             packet_hash: value.event.packet_hash.pg_value()?,
@@ -63,16 +69,15 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, PacketSendEvent>> for Packet
             timeout_timestamp: value.event.timeout_timestamp.pg_value()?,
             data: value.event.data.pg_value()?,
             network: value.context.network.pg_value()?,
+            transaction_sender: value.event.transaction_sender.pg_value()?,
+            initiator: value.event.initiator.pg_value()?,
         })
     }
 }
 
-impl PacketSendRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for PacketSendRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -84,6 +89,7 @@ impl PacketSendRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 transaction_event_index,
                 channel_id,
                 packet_hash,
@@ -92,8 +98,27 @@ impl PacketSendRecord {
                 timeout_height,
                 timeout_timestamp,
                 data,
-                network
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+                network,
+                transaction_sender,
+                initiator
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
+            ON CONFLICT (internal_chain_id, height, event_index) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                transaction_event_index = excluded.transaction_event_index,
+                channel_id = excluded.channel_id,
+                packet_hash = excluded.packet_hash,
+                source_channel_id = excluded.source_channel_id,
+                destination_channel_id = excluded.destination_channel_id,
+                timeout_height = excluded.timeout_height,
+                timeout_timestamp = excluded.timeout_timestamp,
+                data = excluded.data,
+                network = excluded.network,
+                transaction_sender = excluded.transaction_sender,
+                initiator = excluded.initiator
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -102,6 +127,7 @@ impl PacketSendRecord {
             self.timestamp,
             &self.transaction_hash[..],
             self.transaction_index,
+            self.decoder_version,
             self.transaction_event_index,
             self.channel_id,
             &self.packet_hash[..],
@@ -111,13 +137,29 @@ impl PacketSendRecord {
             self.timeout_timestamp,
             &self.data[..],
             self.network,
+            self.transaction_sender,
+            self.initiator,
         )
         .execute(&mut **tx)
         .await?;
 
+        notify_packet_event(
+            tx,
+            "packet_send",
+            self.internal_chain_id,
+            self.channel_id,
+            &self.packet_hash,
+            &self.transaction_hash,
+            self.height,
+            self.timestamp,
+        )
+        .await?;
+
         Ok(Changes::with_single_insert::<Self>())
     }
+}
 
+impl PacketSendRecord {
     pub async fn find_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         universal_chain_id: &UniversalChainId,
@@ -135,6 +177,7 @@ impl PacketSendRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 transaction_event_index,
                 channel_id,
                 packet_hash,
@@ -143,7 +186,9 @@ impl PacketSendRecord {
                 timeout_height,
                 timeout_timestamp,
                 data,
-                network
+                network,
+                transaction_sender,
+                initiator
             FROM v2_sync.packet_send_sync
             WHERE internal_chain_id = (SELECT id FROM config.chains WHERE family || '.' || chain_id = $1) AND height = $2
             "#,
@@ -163,6 +208,7 @@ impl PacketSendRecord {
                 timestamp: record.timestamp.ok_or_else(||IndexerError::InternalCannotMapToDatabaseDomain("timestamp".to_string(), "packet_send_sync".to_string()))?,
                 transaction_hash: record.transaction_hash.ok_or_else(||IndexerError::InternalCannotMapToDatabaseDomain("transaction_hash".to_string(), "packet_send_sync".to_string()))?,
                 transaction_index: record.transaction_index.ok_or_else(||IndexerError::InternalCannotMapToDatabaseDomain("transaction_index".to_string(), "packet_send_sync".to_string()))?,
+                decoder_version: stored_decoder_version(record.decoder_version),
                 transaction_event_index: record.transaction_event_index,
                 channel_id: record.channel_id.ok_or_else(||IndexerError::InternalCannotMapToDatabaseDomain("channel_id".to_string(), "packet_send_sync".to_string()))?,
                 packet_hash: record.packet_hash.ok_or_else(||IndexerError::InternalCannotMapToDatabaseDomain("packet_hash".to_string(), "packet_send_sync".to_string()))?,
@@ -172,6 +218,8 @@ impl PacketSendRecord {
                 timeout_timestamp: record.timeout_timestamp.ok_or_else(||IndexerError::InternalCannotMapToDatabaseDomain("timeout_timestamp".to_string(), "packet_send_sync".to_string()))?,
                 data: record.data.ok_or_else(||IndexerError::InternalCannotMapToDatabaseDomain("data".to_string(), "packet_send_sync".to_string()))?,
                 network: record.network,
+                transaction_sender: record.transaction_sender,
+                initiator: record.initiator,
             })
         })
         .collect::<Result<Vec<PacketSendRecord>, IndexerError>>()
@@ -198,3 +246,61 @@ impl PacketSendRecord {
         Ok(Changes::with_deletes::<Self>(result.rows_affected()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[ignore] // Ignored by default since it requires a database connection
+    #[tokio::test]
+    async fn test_upsert_replay_is_idempotent() {
+        // This test requires a database connection
+        let database_url =
+            std::env::var("DATABASE_URL").expect("DATABASE_URL environment variable not set");
+
+        let pool = sqlx::PgPool::connect(&database_url)
+            .await
+            .expect("Failed to connect to database");
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+
+        let record = PacketSendRecord {
+            internal_chain_id: 999_999,
+            block_hash: vec![1; 32],
+            height: 12345,
+            event_index: 0,
+            timestamp: OffsetDateTime::now_utc(),
+            transaction_hash: vec![2; 32],
+            transaction_index: 0,
+            decoder_version: 1,
+            transaction_event_index: Some(0),
+            channel_id: 1,
+            packet_hash: vec![3; 32],
+            source_channel_id: 1,
+            destination_channel_id: 2,
+            timeout_height: BigDecimal::from(0),
+            timeout_timestamp: BigDecimal::from(0),
+            data: vec![4; 8],
+            network: "testnet".to_string(),
+            transaction_sender: None,
+            initiator: None,
+        };
+
+        // replaying the same event must not fail on the unique constraint
+        record.upsert(&mut tx).await.expect("first upsert");
+        record.upsert(&mut tx).await.expect("replayed upsert");
+
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM v2_sync.packet_send_sync WHERE internal_chain_id = $1 AND height = $2",
+        )
+        .bind(record.internal_chain_id)
+        .bind(record.height)
+        .fetch_one(&mut *tx)
+        .await
+        .expect("Failed to count rows");
+
+        assert_eq!(count, 1);
+
+        tx.rollback().await.expect("Failed to rollback transaction");
+    }
+}
@@ -1,15 +1,17 @@
+use serde_json::json;
 use sqlx::{types::BigDecimal, Postgres, Transaction};
-use time::OffsetDateTime;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use tracing::trace;
 
 use crate::indexer::{
     api::IndexerError,
     event::types::BlockHeight,
     handler::types::{ChannelMetaData, Transfer},
+    postgres::notify::notify_transfer,
     record::{
         change_counter::{Changes, HasKind, RecordKind},
         packet_send_record::PacketSendRecord,
-        InternalChainId, PgValue, PgValueExt,
+        InternalChainId, PgValue, PgValueExt, Record,
     },
 };
 
@@ -54,6 +56,9 @@ pub struct PacketSendTransfersRecord {
     pub sort_order: String,
     pub network: String,
     pub counterparty_network: String,
+    pub amount_usd: Option<BigDecimal>,
+    pub transaction_sender: Option<Vec<u8>>,
+    pub initiator: Option<Vec<u8>>,
 }
 impl HasKind for PacketSendTransfersRecord {
     fn kind() -> RecordKind {
@@ -115,16 +120,16 @@ impl TryFrom<(&PacketSendRecord, &Transfer, &ChannelMetaData, &String)>
             sort_order: sort_order.clone(),
             network: channel.network.pg_value()?,
             counterparty_network: channel.counterparty_network.pg_value()?,
+            amount_usd: transfer.amount_usd.pg_value()?,
+            transaction_sender: record.transaction_sender.clone(),
+            initiator: record.initiator.clone(),
         })
     }
 }
 
-impl PacketSendTransfersRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for PacketSendTransfersRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         // let x = &self.quote_token[..];
         // let x: Option<&[u8]> = self.fee_token.map(|f| &f[..]);
@@ -178,8 +183,53 @@ impl PacketSendTransfersRecord {
                 packet_shape,
                 sort_order,
                 network,
-                counterparty_network
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40)
+                counterparty_network,
+
+                amount_usd,
+                transaction_sender,
+                initiator
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43)
+            ON CONFLICT (internal_chain_id, packet_hash, transfer_index) DO UPDATE SET
+                universal_chain_id = excluded.universal_chain_id,
+                internal_counterparty_chain_id = excluded.internal_counterparty_chain_id,
+                counterparty_universal_chain_id = excluded.counterparty_universal_chain_id,
+                client_id = excluded.client_id,
+                counterparty_client_id = excluded.counterparty_client_id,
+                connection_id = excluded.connection_id,
+                counterparty_connection_id = excluded.counterparty_connection_id,
+                source_channel_id = excluded.source_channel_id,
+                destination_channel_id = excluded.destination_channel_id,
+                port_id = excluded.port_id,
+                counterparty_port_id = excluded.counterparty_port_id,
+                block_hash = excluded.block_hash,
+                transaction_hash = excluded.transaction_hash,
+                height = excluded.height,
+                timestamp = excluded.timestamp,
+                sender_canonical = excluded.sender_canonical,
+                sender_display = excluded.sender_display,
+                sender_zkgm = excluded.sender_zkgm,
+                receiver_canonical = excluded.receiver_canonical,
+                receiver_display = excluded.receiver_display,
+                receiver_zkgm = excluded.receiver_zkgm,
+                wrap_direction = excluded.wrap_direction,
+                base_token = excluded.base_token,
+                base_amount = excluded.base_amount,
+                base_token_name = excluded.base_token_name,
+                base_token_path = excluded.base_token_path,
+                base_token_symbol = excluded.base_token_symbol,
+                base_token_decimals = excluded.base_token_decimals,
+                quote_token = excluded.quote_token,
+                quote_amount = excluded.quote_amount,
+                fee_type = excluded.fee_type,
+                fee_token = excluded.fee_token,
+                fee_amount = excluded.fee_amount,
+                packet_shape = excluded.packet_shape,
+                sort_order = excluded.sort_order,
+                network = excluded.network,
+                counterparty_network = excluded.counterparty_network,
+                amount_usd = excluded.amount_usd,
+                transaction_sender = excluded.transaction_sender,
+                initiator = excluded.initiator
             "#,
             self.internal_chain_id,
             self.universal_chain_id,
@@ -221,13 +271,48 @@ impl PacketSendTransfersRecord {
             self.sort_order,
             self.network,
             self.counterparty_network,
+            self.amount_usd,
+            self.transaction_sender,
+            self.initiator,
         )
         .execute(&mut **tx)
         .await?;
 
+        notify_transfer(
+            tx,
+            &json!({
+                "type": "transfer",
+                "universal_chain_id": self.universal_chain_id,
+                "counterparty_universal_chain_id": self.counterparty_universal_chain_id,
+                "source_channel_id": self.source_channel_id,
+                "destination_channel_id": self.destination_channel_id,
+                "packet_hash": format!("0x{}", hex::encode(&self.packet_hash)),
+                "transaction_hash": format!("0x{}", hex::encode(&self.transaction_hash)),
+                "height": self.height,
+                "timestamp": self.timestamp.format(&Rfc3339).map_err(|error| {
+                    IndexerError::InternalCannotMapFromDatabaseDomain(
+                        "timestamp".to_string(),
+                        error.to_string(),
+                    )
+                })?,
+                "sender": self.sender_display,
+                "receiver": self.receiver_display,
+                "base_token": format!("0x{}", hex::encode(&self.base_token)),
+                "base_token_symbol": self.base_token_symbol,
+                "base_amount": self.base_amount.to_string(),
+                "quote_token": format!("0x{}", hex::encode(&self.quote_token)),
+                "quote_amount": self.quote_amount.to_string(),
+                "amount_usd": self.amount_usd.as_ref().map(ToString::to_string),
+                "sort_order": self.sort_order,
+            }),
+        )
+        .await?;
+
         Ok(Changes::with_single_insert::<Self>())
     }
+}
 
+impl PacketSendTransfersRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
@@ -8,7 +8,7 @@ use crate::indexer::{
     handler::EventContext,
     record::{
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue, PgValueExt,
+        ChainContext, InternalChainId, PgValue, PgValueExt, Record,
     },
 };
 
@@ -20,6 +20,7 @@ pub struct TokenBucketUpdateRecord {
     pub timestamp: OffsetDateTime,
     pub transaction_hash: Vec<u8>,
     pub transaction_index: i64,
+    pub decoder_version: i32,
     pub transaction_event_index: Option<i64>,
     pub denom: Vec<u8>,
     pub capacity: BigDecimal,
@@ -47,6 +48,7 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, TokenBucketUpdateEvent>>
             timestamp: value.event.header.timestamp.pg_value()?,
             transaction_hash: value.event.header.transaction_hash.pg_value()?,
             transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
             transaction_event_index: value.event.header.transaction_event_index.pg_value()?,
             denom: value.event.denom.pg_value()?,
             capacity: value.event.capacity.pg_value()?,
@@ -55,12 +57,9 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, TokenBucketUpdateEvent>>
     }
 }
 
-impl TokenBucketUpdateRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for TokenBucketUpdateRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -72,11 +71,22 @@ impl TokenBucketUpdateRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 transaction_event_index,
                 denom,
                 capacity,
                 refill_rate
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (internal_chain_id, height, event_index) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                transaction_event_index = excluded.transaction_event_index,
+                denom = excluded.denom,
+                capacity = excluded.capacity,
+                refill_rate = excluded.refill_rate
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -85,6 +95,7 @@ impl TokenBucketUpdateRecord {
             self.timestamp,
             &self.transaction_hash[..],
             self.transaction_index,
+            self.decoder_version,
             self.transaction_event_index,
             &self.denom[..],
             self.capacity,
@@ -93,8 +104,57 @@ impl TokenBucketUpdateRecord {
         .execute(&mut **tx)
         .await?;
 
+        self.project(tx).await?;
+
         Ok(Changes::with_single_insert::<Self>())
     }
+}
+
+impl TokenBucketUpdateRecord {
+    /// Makes this update the current state of the bucket in `v2_sync.token_bucket_state`,
+    /// unless a later update is already projected. The bucket is considered full after an update.
+    async fn project(&self, tx: &mut Transaction<'_, Postgres>) -> Result<(), IndexerError> {
+        trace!("project({})", self.height);
+
+        sqlx::query!(
+            r#"
+            INSERT INTO v2_sync.token_bucket_state (
+                internal_chain_id,
+                denom,
+                capacity,
+                refill_rate,
+                height,
+                event_index,
+                timestamp,
+                available,
+                available_at,
+                alerted
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $3, $7, false)
+            ON CONFLICT (internal_chain_id, denom) DO UPDATE SET
+                capacity = excluded.capacity,
+                refill_rate = excluded.refill_rate,
+                height = excluded.height,
+                event_index = excluded.event_index,
+                timestamp = excluded.timestamp,
+                available = excluded.available,
+                available_at = excluded.available_at,
+                alerted = false
+            WHERE (token_bucket_state.height, token_bucket_state.event_index)
+                < (excluded.height, excluded.event_index)
+            "#,
+            self.internal_chain_id,
+            &self.denom[..],
+            self.capacity,
+            self.refill_rate,
+            self.height,
+            self.event_index,
+            self.timestamp,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
@@ -114,6 +174,74 @@ impl TokenBucketUpdateRecord {
         .execute(&mut **tx)
         .await?;
 
+        Self::reproject_by_chain_and_height(tx, internal_chain_id, height).await?;
+
         Ok(Changes::with_deletes::<Self>(result.rows_affected()))
     }
+
+    /// Replaces the projected state of buckets that were updated at the deleted height with
+    /// their latest remaining update (if any).
+    async fn reproject_by_chain_and_height(
+        tx: &mut Transaction<'_, Postgres>,
+        internal_chain_id: InternalChainId,
+        height: BlockHeight,
+    ) -> Result<(), IndexerError> {
+        trace!("reproject_by_chain_and_height({internal_chain_id}, {height})");
+
+        let denoms = sqlx::query!(
+            r#"
+            DELETE FROM v2_sync.token_bucket_state
+            WHERE internal_chain_id = $1 AND height = $2
+            RETURNING denom
+            "#,
+            internal_chain_id.pg_value()?,
+            height.pg_value()?
+        )
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|record| record.denom)
+        .collect::<Vec<_>>();
+
+        if denoms.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO v2_sync.token_bucket_state (
+                internal_chain_id,
+                denom,
+                capacity,
+                refill_rate,
+                height,
+                event_index,
+                timestamp,
+                available,
+                available_at,
+                alerted
+            )
+            SELECT DISTINCT ON (denom)
+                internal_chain_id,
+                denom,
+                capacity,
+                refill_rate,
+                height,
+                event_index,
+                timestamp,
+                capacity,
+                timestamp,
+                false
+            FROM v2_sync.token_bucket_update_sync
+            WHERE internal_chain_id = $1 AND denom = ANY($2)
+            ORDER BY denom, height DESC, event_index DESC
+            "#,
+            internal_chain_id.pg_value()?,
+            &denoms[..],
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 }
@@ -7,8 +7,9 @@ use crate::indexer::{
     event::{types::BlockHeight, update_client_event::UpdateClientEvent},
     handler::EventContext,
     record::{
+        bulk::{CopyRecord, CopyRow},
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue,
+        ChainContext, InternalChainId, PgValue, Record,
     },
 };
 
@@ -45,12 +46,9 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, UpdateClientEvent>> for Upda
     }
 }
 
-impl UpdateClientRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for UpdateClientRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -63,6 +61,11 @@ impl UpdateClientRecord {
                 timestamp,
                 counterparty_height
             ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (internal_chain_id, client_id, counterparty_height) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                height = excluded.height,
+                transaction_hash = excluded.transaction_hash,
+                timestamp = excluded.timestamp
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -77,7 +80,33 @@ impl UpdateClientRecord {
 
         Ok(Changes::with_single_insert::<Self>())
     }
+}
+
+impl CopyRecord for UpdateClientRecord {
+    const TABLE: &'static str = "v2_sync.update_client_sync";
+    const KEY: &'static [&'static str] = &["internal_chain_id", "client_id", "counterparty_height"];
+    const COLUMNS: &'static [&'static str] = &[
+        "internal_chain_id",
+        "block_hash",
+        "height",
+        "transaction_hash",
+        "client_id",
+        "timestamp",
+        "counterparty_height",
+    ];
 
+    fn write_row(&self, row: &mut CopyRow<'_>) {
+        row.push(&self.internal_chain_id)
+            .push(&self.block_hash)
+            .push(&self.height)
+            .push(&self.transaction_hash)
+            .push(&self.client_id)
+            .push(&self.timestamp)
+            .push(&self.counterparty_height);
+    }
+}
+
+impl UpdateClientRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
@@ -99,3 +128,49 @@ impl UpdateClientRecord {
         Ok(Changes::with_deletes::<Self>(result.rows_affected()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[ignore] // Ignored by default since it requires a database connection
+    #[tokio::test]
+    async fn test_upsert_replay_is_idempotent() {
+        // This test requires a database connection
+        let database_url =
+            std::env::var("DATABASE_URL").expect("DATABASE_URL environment variable not set");
+
+        let pool = sqlx::PgPool::connect(&database_url)
+            .await
+            .expect("Failed to connect to database");
+
+        let mut tx = pool.begin().await.expect("Failed to begin transaction");
+
+        let record = UpdateClientRecord {
+            internal_chain_id: 999_999,
+            block_hash: vec![1; 32],
+            height: 12345,
+            transaction_hash: vec![2; 32],
+            client_id: 1,
+            timestamp: OffsetDateTime::now_utc(),
+            counterparty_height: 54321,
+        };
+
+        // replaying the same event must not fail on the unique constraint
+        record.upsert(&mut tx).await.expect("first upsert");
+        record.upsert(&mut tx).await.expect("replayed upsert");
+
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM v2_sync.update_client_sync WHERE internal_chain_id = $1 AND client_id = $2",
+        )
+        .bind(record.internal_chain_id)
+        .bind(record.client_id)
+        .fetch_one(&mut *tx)
+        .await
+        .expect("Failed to count rows");
+
+        assert_eq!(count, 1);
+
+        tx.rollback().await.expect("Failed to rollback transaction");
+    }
+}
@@ -7,8 +7,9 @@ use crate::indexer::{
     event::{types::BlockHeight, wallet_mutation_entry_event::WalletMutationEntryEvent},
     handler::EventContext,
     record::{
+        bulk::{CopyRecord, CopyRow},
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue, PgValueExt,
+        ChainContext, InternalChainId, PgValue, PgValueExt, Record,
     },
 };
 
@@ -19,6 +20,7 @@ pub struct WalletMutationEntryRecord {
     pub timestamp: OffsetDateTime,
     pub transaction_hash: Vec<u8>,
     pub transaction_index: i64,
+    pub decoder_version: i32,
     pub transaction_event_index: Option<i64>,
     // missing event_index
     pub contract_address_canonical: Vec<u8>,
@@ -47,6 +49,7 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, WalletMutationEntryEvent>>
             timestamp: value.event.header.timestamp.pg_value()?,
             transaction_hash: value.event.header.transaction_hash.pg_value()?,
             transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
             transaction_event_index: value.event.header.transaction_event_index.pg_value()?,
             contract_address_canonical: value.event.contract_address_canonical.pg_value()?,
             wallet_address_canonical: value.event.wallet_address_canonical.pg_value()?,
@@ -56,12 +59,9 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, WalletMutationEntryEvent>>
     }
 }
 
-impl WalletMutationEntryRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for WalletMutationEntryRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -72,12 +72,21 @@ impl WalletMutationEntryRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 transaction_event_index,
                 contract_address_canonical,
                 wallet_address_canonical,
                 amount,
                 direction
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (internal_chain_id, transaction_hash, transaction_event_index, wallet_address_canonical, direction) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                height = excluded.height,
+                timestamp = excluded.timestamp,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                contract_address_canonical = excluded.contract_address_canonical,
+                amount = excluded.amount
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -85,6 +94,7 @@ impl WalletMutationEntryRecord {
             self.timestamp,
             &self.transaction_hash[..],
             self.transaction_index,
+            self.decoder_version,
             self.transaction_event_index,
             &self.contract_address_canonical[..],
             &self.wallet_address_canonical[..],
@@ -96,7 +106,49 @@ impl WalletMutationEntryRecord {
 
         Ok(Changes::with_single_insert::<Self>())
     }
+}
+
+impl CopyRecord for WalletMutationEntryRecord {
+    const TABLE: &'static str = "v2_sync.wallet_mutation_entry_sync";
+    const KEY: &'static [&'static str] = &[
+        "internal_chain_id",
+        "transaction_hash",
+        "transaction_event_index",
+        "wallet_address_canonical",
+        "direction",
+    ];
+    const COLUMNS: &'static [&'static str] = &[
+        "internal_chain_id",
+        "block_hash",
+        "height",
+        "timestamp",
+        "transaction_hash",
+        "transaction_index",
+        "decoder_version",
+        "transaction_event_index",
+        "contract_address_canonical",
+        "wallet_address_canonical",
+        "amount",
+        "direction",
+    ];
 
+    fn write_row(&self, row: &mut CopyRow<'_>) {
+        row.push(&self.internal_chain_id)
+            .push(&self.block_hash)
+            .push(&self.height)
+            .push(&self.timestamp)
+            .push(&self.transaction_hash)
+            .push(&self.transaction_index)
+            .push(&self.decoder_version)
+            .push(&self.transaction_event_index)
+            .push(&self.contract_address_canonical)
+            .push(&self.wallet_address_canonical)
+            .push(&self.amount)
+            .push(&self.direction);
+    }
+}
+
+impl WalletMutationEntryRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
@@ -6,9 +6,11 @@ use crate::indexer::{
     api::IndexerError,
     event::{types::BlockHeight, write_ack_event::WriteAckEvent},
     handler::EventContext,
+    postgres::notify::{notify_packet_event, PacketEventNotification},
     record::{
+        bulk::{CopyRecord, CopyRow},
         change_counter::{Changes, HasKind, RecordKind},
-        ChainContext, InternalChainId, PgValue, PgValueExt,
+        ChainContext, InternalChainId, PgValue, PgValueExt, Record,
     },
 };
 
@@ -20,6 +22,7 @@ pub struct WriteAckRecord {
     pub timestamp: OffsetDateTime,
     pub transaction_hash: Vec<u8>,
     pub transaction_index: i64,
+    pub decoder_version: i32,
     pub transaction_event_index: Option<i64>,
     pub channel_id: i32,
     pub packet_hash: Vec<u8>,
@@ -46,6 +49,7 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, WriteAckEvent>> for WriteAck
             timestamp: value.event.header.timestamp.pg_value()?,
             transaction_hash: value.event.header.transaction_hash.pg_value()?,
             transaction_index: value.event.header.transaction_index.pg_value()?,
+            decoder_version: value.event.header.decoder_version.pg_value()?,
             transaction_event_index: value.event.header.transaction_event_index.pg_value()?,
             channel_id: value.event.channel_id.pg_value()?,
             packet_hash: value.event.packet_hash.pg_value()?,
@@ -55,12 +59,9 @@ impl<'a> TryFrom<&'a EventContext<'a, ChainContext, WriteAckEvent>> for WriteAck
     }
 }
 
-impl WriteAckRecord {
-    pub async fn insert(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-    ) -> Result<Changes, IndexerError> {
-        trace!("insert({})", self.height);
+impl Record for WriteAckRecord {
+    async fn upsert(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Changes, IndexerError> {
+        trace!("upsert({})", self.height);
 
         sqlx::query!(
             r#"
@@ -72,12 +73,24 @@ impl WriteAckRecord {
                 timestamp,
                 transaction_hash,
                 transaction_index,
+                decoder_version,
                 transaction_event_index,
                 channel_id,
                 packet_hash,
                 acknowledgement,
                 network
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (internal_chain_id, height, event_index) DO UPDATE SET
+                block_hash = excluded.block_hash,
+                timestamp = excluded.timestamp,
+                transaction_hash = excluded.transaction_hash,
+                transaction_index = excluded.transaction_index,
+                decoder_version = excluded.decoder_version,
+                transaction_event_index = excluded.transaction_event_index,
+                channel_id = excluded.channel_id,
+                packet_hash = excluded.packet_hash,
+                acknowledgement = excluded.acknowledgement,
+                network = excluded.network
             "#,
             self.internal_chain_id,
             &self.block_hash[..],
@@ -86,6 +99,7 @@ impl WriteAckRecord {
             self.timestamp,
             &self.transaction_hash[..],
             self.transaction_index,
+            self.decoder_version,
             self.transaction_event_index,
             self.channel_id,
             &self.packet_hash[..],
@@ -95,9 +109,71 @@ impl WriteAckRecord {
         .execute(&mut **tx)
         .await?;
 
+        notify_packet_event(
+            tx,
+            "write_ack",
+            self.internal_chain_id,
+            self.channel_id,
+            &self.packet_hash,
+            &self.transaction_hash,
+            self.height,
+            self.timestamp,
+        )
+        .await?;
+
         Ok(Changes::with_single_insert::<Self>())
     }
+}
+
+impl CopyRecord for WriteAckRecord {
+    const TABLE: &'static str = "v2_sync.write_ack_sync";
+    const KEY: &'static [&'static str] = &["internal_chain_id", "height", "event_index"];
+    const COLUMNS: &'static [&'static str] = &[
+        "internal_chain_id",
+        "block_hash",
+        "height",
+        "event_index",
+        "timestamp",
+        "transaction_hash",
+        "transaction_index",
+        "decoder_version",
+        "transaction_event_index",
+        "channel_id",
+        "packet_hash",
+        "acknowledgement",
+        "network",
+    ];
 
+    fn write_row(&self, row: &mut CopyRow<'_>) {
+        row.push(&self.internal_chain_id)
+            .push(&self.block_hash)
+            .push(&self.height)
+            .push(&self.event_index)
+            .push(&self.timestamp)
+            .push(&self.transaction_hash)
+            .push(&self.transaction_index)
+            .push(&self.decoder_version)
+            .push(&self.transaction_event_index)
+            .push(&self.channel_id)
+            .push(&self.packet_hash)
+            .push(&self.acknowledgement)
+            .push(&self.network);
+    }
+
+    fn packet_event(&self) -> Option<PacketEventNotification> {
+        Some(PacketEventNotification {
+            kind: "write_ack",
+            internal_chain_id: self.internal_chain_id,
+            channel_id: self.channel_id,
+            packet_hash: self.packet_hash.clone(),
+            transaction_hash: self.transaction_hash.clone(),
+            height: self.height,
+            timestamp: self.timestamp,
+        })
+    }
+}
+
+impl WriteAckRecord {
     pub async fn delete_by_chain_and_height(
         tx: &mut Transaction<'_, Postgres>,
         internal_chain_id: InternalChainId,
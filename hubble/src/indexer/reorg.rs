@@ -0,0 +1,156 @@
+use tracing::{debug, info, warn};
+
+use crate::indexer::{
+    api::{BlockHandle, BlockHeight, BlockSelection, FetchMode, FetcherClient, IndexerError},
+    postgres::{
+        block_status::{get_block_status_hash, update_block_status, BlockStatus},
+        reorg::{insert_reorg, Reorg},
+    },
+    Indexer,
+};
+
+impl<T: FetcherClient> Indexer<T> {
+    /// Checks that `block` builds on the indexed tip. If it doesn't, the indexed blocks are
+    /// replaced by the canonical blocks back to the fork point (in one transaction) and a reorg
+    /// is recorded. Returns `true` if a reorg was rolled back (or the indexed blocks changed while
+    /// fetching the canonical blocks), in which case `block` should be fetched again.
+    ///
+    /// The fork point is searched in the block status, which only tracks blocks that are not
+    /// finalized yet; finalized blocks are never rolled back. The canonical blocks are fetched
+    /// before the transaction is started, so that it isn't held open during rpc calls.
+    pub(super) async fn rollback_reorg(
+        &self,
+        fetcher_client: &T,
+        block: &T::BlockHandle,
+    ) -> Result<bool, IndexerError> {
+        let reference = block.reference();
+
+        let (Some(parent_hash), Some(tip_height)) =
+            (block.parent_hash(), reference.height.checked_sub(1))
+        else {
+            return Ok(false);
+        };
+
+        let Some(tip) = self.get_block_status(tip_height).await? else {
+            return Ok(false);
+        };
+
+        if tip.block_hash == parent_hash {
+            return Ok(false);
+        }
+
+        warn!(
+            "{reference}: parent hash mismatch (indexed: {}, parent: {parent_hash}) => reorg",
+            tip.block_hash
+        );
+
+        // walk back until the indexed hash matches the canonical chain
+        let mut replaced = vec![];
+        let mut height = tip_height;
+        let mut expected_hash = parent_hash.clone();
+        while let Some(status) = self.get_block_status(height).await? {
+            if status.block_hash == expected_hash {
+                break;
+            }
+
+            let canonical = fetcher_client
+                .fetch_single(BlockSelection::Height(height), FetchMode::Eager)
+                .await?;
+            let canonical_reference = canonical.reference();
+
+            if canonical_reference.hash != expected_hash {
+                // the chain changed while rolling back; the next attempt will start over.
+                return Err(IndexerError::UnexpectedHashReorg(
+                    height,
+                    canonical_reference.hash,
+                    expected_hash,
+                ));
+            }
+
+            debug!(
+                "{height}: replaced ({} > {})",
+                status.block_hash, canonical_reference.hash
+            );
+
+            expected_hash = canonical.parent_hash().unwrap_or_default();
+            replaced.push((canonical, status));
+
+            match height.checked_sub(1) {
+                Some(parent_height) => height = parent_height,
+                None => break,
+            }
+        }
+
+        let reorg = Reorg {
+            fork_height: tip_height - replaced.len() as u64,
+            tip_height,
+            old_tip_hash: tip.block_hash,
+            new_tip_hash: parent_hash,
+        };
+
+        let mut tx = self.pg_pool.begin().await?;
+
+        for (canonical, status) in replaced.into_iter().rev() {
+            let canonical_reference = canonical.reference();
+
+            // the block was finalized or replaced by another task in the meantime
+            match get_block_status_hash(
+                &mut tx,
+                self.indexer_id.clone(),
+                canonical_reference.height,
+            )
+            .await?
+            {
+                Some(current) if current.block_hash == status.block_hash => {}
+                _ => {
+                    info!(
+                        "{reference}: block {} changed while rolling back reorg => retry",
+                        canonical_reference.height
+                    );
+
+                    return Ok(true);
+                }
+            }
+
+            let new_message_hash = self
+                .update_block(&mut tx, canonical, &status.message_hash)
+                .await?;
+
+            update_block_status(
+                &mut tx,
+                self.indexer_id.clone(),
+                canonical_reference.height,
+                canonical_reference.hash,
+                canonical_reference.timestamp,
+                new_message_hash,
+            )
+            .await?;
+        }
+
+        insert_reorg(&mut tx, self.indexer_id.clone(), &reorg).await?;
+
+        tx.commit().await?;
+
+        info!(
+            "{reference}: rolled back reorg (fork: {}, tip: {}, depth: {})",
+            reorg.fork_height,
+            reorg.tip_height,
+            reorg.tip_height - reorg.fork_height
+        );
+
+        Ok(true)
+    }
+
+    async fn get_block_status(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<BlockStatus>, IndexerError> {
+        let mut tx = self.pg_pool.begin().await?;
+
+        let status = get_block_status_hash(&mut tx, self.indexer_id.clone(), height).await?;
+
+        tx.commit().await?;
+
+        Ok(status)
+    }
+}
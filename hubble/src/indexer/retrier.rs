@@ -0,0 +1,166 @@
+use sqlx::Acquire;
+use time::OffsetDateTime;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::{
+    indexer::{
+        api::{FetcherClient, IndexerError},
+        consumer::should_trigger_enrich_reset,
+        event::{supported::SupportedBlockEvent, types::Range},
+        postgres::{
+            block_update::max_event_height,
+            chain_context::fetch_chain_context_for_universal_chain_id,
+            failed_event::{delete_failed_event, next_failed_event, update_failed_event},
+            lock::try_lock_block,
+            replication_reset::schedule_enrich_reset,
+        },
+        record::event_handler::handle_block_event,
+        Indexer,
+    },
+    metrics::FAILED_EVENT_COLLECTOR,
+};
+
+enum RetrierLoopResult {
+    RunAgain,
+    TryAgainLater,
+}
+
+impl<T: FetcherClient> Indexer<T> {
+    pub async fn run_retrier(&self) -> Result<(), IndexerError> {
+        if self.drain {
+            return Ok(());
+        }
+
+        loop {
+            match self.run_retrier_loop().await {
+                Ok(RetrierLoopResult::RunAgain) => {
+                    debug!("run again");
+                }
+                Ok(RetrierLoopResult::TryAgainLater) => {
+                    debug!(
+                        "try again later (sleep {}s)",
+                        self.retrier_config.retry_later_sleep.as_secs()
+                    );
+                    sleep(self.retrier_config.retry_later_sleep).await;
+                }
+                Err(error) => {
+                    warn!(
+                        "error in retrier loop: {error} => try again later (sleep {}s)",
+                        self.retrier_config.retry_error_sleep.as_secs()
+                    );
+                    sleep(self.retrier_config.retry_error_sleep).await;
+                }
+            }
+        }
+    }
+
+    async fn run_retrier_loop(&self) -> Result<RetrierLoopResult, IndexerError> {
+        let mut tx = self.pg_pool.begin().await?;
+
+        let chain_context =
+            fetch_chain_context_for_universal_chain_id(&mut tx, &self.universal_chain_id).await?;
+
+        let Some(failed_event) =
+            next_failed_event(&mut tx, chain_context.internal_chain_id).await?
+        else {
+            debug!("no failed events to retry => retry later");
+            return Ok(RetrierLoopResult::TryAgainLater);
+        };
+
+        info!("{failed_event}: retrying");
+
+        // we're locking to prevent that the consumer or enricher will write data for this block
+        try_lock_block(&mut tx, &self.universal_chain_id, failed_event.height).await?;
+
+        let max_event_height = max_event_height(&mut tx, &self.universal_chain_id).await?;
+
+        let mut savepoint = tx.begin().await?;
+
+        let result = match serde_json::from_value::<SupportedBlockEvent>(failed_event.event.clone())
+        {
+            Ok(block_event) => {
+                handle_block_event(&mut savepoint, &chain_context, &block_event).await
+            }
+            Err(error) => Err(error.into()),
+        };
+
+        match result {
+            Ok(changes) => {
+                savepoint.commit().await?;
+                delete_failed_event(&mut tx, &failed_event).await?;
+
+                // the event is handled after the blocks that follow it, so enriched data of
+                // those blocks may be outdated
+                if changes.has_changes_matching(should_trigger_enrich_reset)
+                    && failed_event.height <= max_event_height
+                {
+                    schedule_enrich_reset(
+                        &mut tx,
+                        &self.universal_chain_id,
+                        &Range::new_from_start_inclusive_end_inclusive(
+                            &failed_event.height,
+                            &max_event_height,
+                        ),
+                        &format!("{failed_event} => {changes}"),
+                    )
+                    .await?;
+                }
+
+                info!("{failed_event}: recovered ({changes})");
+
+                FAILED_EVENT_COLLECTOR
+                    .with_label_values(&[&chain_context.internal_chain_id.to_string(), "recovered"])
+                    .inc();
+            }
+            // transient errors are not caused by the event, so they don't count as an attempt
+            Err(error) if error.is_transient() => return Err(error),
+            Err(error) => {
+                savepoint.rollback().await?;
+
+                let backoff = self
+                    .retrier_config
+                    .backoff((failed_event.attempts + 1).try_into().unwrap_or(u32::MAX));
+
+                warn!(
+                    "{failed_event}: failed again: {error} => retry in {}s",
+                    backoff.as_secs()
+                );
+
+                update_failed_event(
+                    &mut tx,
+                    &failed_event,
+                    &error.to_string(),
+                    OffsetDateTime::now_utc() + backoff,
+                )
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(RetrierLoopResult::RunAgain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::indexer::RetrierConfig;
+
+    #[test]
+    fn backoff() {
+        let config = RetrierConfig {
+            min_backoff: Duration::from_secs(10),
+            max_backoff: Duration::from_secs(60),
+            ..Default::default()
+        };
+
+        assert_eq!(config.backoff(1), Duration::from_secs(10));
+        assert_eq!(config.backoff(2), Duration::from_secs(20));
+        assert_eq!(config.backoff(3), Duration::from_secs(40));
+        assert_eq!(config.backoff(4), Duration::from_secs(60));
+        assert_eq!(config.backoff(u32::MAX), Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,49 @@
+use std::{sync::Arc, time::Instant};
+
+use tokio::sync::{AcquireError, Semaphore, SemaphorePermit};
+use tracing::debug;
+
+use crate::{
+    indexer::{api::BlockRange, event::types::UniversalChainId},
+    metrics::FETCH_WAIT_COLLECTOR,
+};
+
+/// Limits the number of heavy fetches (catching up and fixing ranges of blocks) that run at the
+/// same time over all indexers, so a chain with a large backlog cannot starve the others.
+/// Following the tip is not limited.
+#[derive(Clone, Debug)]
+pub struct FetchScheduler {
+    permits: Arc<Semaphore>,
+}
+
+impl FetchScheduler {
+    pub fn new(max_concurrent_fetches: Option<usize>) -> Self {
+        FetchScheduler {
+            permits: Arc::new(Semaphore::new(
+                max_concurrent_fetches.unwrap_or(Semaphore::MAX_PERMITS),
+            )),
+        }
+    }
+
+    /// Wait until the range may be fetched. The permit is released when it's dropped.
+    pub async fn acquire(
+        &self,
+        universal_chain_id: &UniversalChainId,
+        range: &BlockRange,
+    ) -> Result<SemaphorePermit<'_>, AcquireError> {
+        let start = Instant::now();
+        let permit = self.permits.acquire().await?;
+        let waited = start.elapsed();
+
+        debug!(
+            "{range}: acquired fetch permit (waited {}ms)",
+            waited.as_millis()
+        );
+
+        FETCH_WAIT_COLLECTOR
+            .with_label_values(&[&universal_chain_id.to_string()])
+            .inc_by(waited.as_millis().try_into().unwrap_or(u64::MAX));
+
+        Ok(permit)
+    }
+}
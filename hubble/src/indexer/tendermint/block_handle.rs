@@ -8,6 +8,7 @@ use cometbft_rpc::{
 };
 use futures::Stream;
 use itertools::Itertools;
+use protos::cosmos::base::tendermint::v1beta1::{GetBlockByHeightResponse, GetLatestBlockResponse};
 use serde_json::Value;
 use sqlx::Postgres;
 use time::OffsetDateTime;
@@ -17,6 +18,7 @@ use crate::indexer::{
     api::{
         BlockHandle, BlockRange, BlockReference, BlockReferenceProvider, FetchMode, IndexerError,
     },
+    decoder_version::DecoderVersion,
     event::types::BlockEvents,
     tendermint::{
         fetcher_client::TmFetcherClient,
@@ -25,7 +27,7 @@ use crate::indexer::{
             PgTransaction,
         },
         postgres::active_contracts,
-        provider::RpcProviderId,
+        provider::ProviderId,
     },
 };
 
@@ -33,6 +35,9 @@ use crate::indexer::{
 pub struct BlockHeader {
     pub block_id: BlockId,
     pub header: Header,
+    /// The raw transactions of the block, if they were fetched together with the header (over
+    /// grpc), so that they don't have to be fetched again with the details of the block.
+    pub txs: Option<Vec<Vec<u8>>>,
 }
 
 impl From<BlockResponse> for BlockHeader {
@@ -40,6 +45,7 @@ impl From<BlockResponse> for BlockHeader {
         BlockHeader {
             block_id: block_response.block_id,
             header: block_response.block.header,
+            txs: None,
         }
     }
 }
@@ -49,15 +55,57 @@ impl From<BlockMeta> for BlockHeader {
         BlockHeader {
             block_id: block_meta.block_id,
             header: block_meta.header,
+            txs: None,
         }
     }
 }
 
+impl TryFrom<GetBlockByHeightResponse> for BlockHeader {
+    type Error = IndexerError;
+
+    fn try_from(response: GetBlockByHeightResponse) -> Result<Self, Self::Error> {
+        BlockHeader::from_grpc(response.block_id, response.block)
+    }
+}
+
+impl TryFrom<GetLatestBlockResponse> for BlockHeader {
+    type Error = IndexerError;
+
+    fn try_from(response: GetLatestBlockResponse) -> Result<Self, Self::Error> {
+        BlockHeader::from_grpc(response.block_id, response.block)
+    }
+}
+
+impl BlockHeader {
+    fn from_grpc(
+        block_id: Option<protos::cometbft::types::v1::BlockId>,
+        block: Option<protos::cometbft::types::v1::Block>,
+    ) -> Result<Self, IndexerError> {
+        let provider_error = |report: Report| IndexerError::ProviderError(Box::new(report));
+
+        let block = block.ok_or_else(|| provider_error(eyre!("expected block")))?;
+
+        Ok(BlockHeader {
+            block_id: block_id
+                .ok_or_else(|| provider_error(eyre!("expected block id")))?
+                .try_into()
+                .map_err(|err| provider_error(Report::new(err)))?,
+            header: block
+                .header
+                .ok_or_else(|| provider_error(eyre!("expected header")))?
+                .try_into()
+                .map_err(|err| provider_error(Report::new(err)))?,
+            txs: Some(block.data.map(|data| data.txs).unwrap_or_default()),
+        })
+    }
+}
+
 impl From<CommitResponse> for BlockHeader {
     fn from(commit_response: CommitResponse) -> Self {
         BlockHeader {
             block_id: commit_response.signed_header.commit.block_id,
             header: commit_response.signed_header.header,
+            txs: None,
         }
     }
 }
@@ -116,7 +164,7 @@ pub struct TmBlockHandle {
     pub reference: BlockReference,
     pub details: BlockDetails,
     pub tm_client: TmFetcherClient,
-    pub provider_id: RpcProviderId,
+    pub provider_id: ProviderId,
 }
 
 impl TmBlockHandle {
@@ -141,23 +189,38 @@ impl TmBlockHandle {
 }
 
 #[derive(Clone, Debug)]
-pub struct ActiveContracts(HashMap<String, HashSet<String>>);
+pub struct ActiveContracts {
+    flows: HashMap<String, HashSet<String>>,
+    decoder_versions: HashMap<String, DecoderVersion>,
+}
 
 impl ActiveContracts {
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self {
+            flows: HashMap::new(),
+            decoder_versions: HashMap::new(),
+        }
     }
 
-    pub fn register(&mut self, address: String, flow: String) {
-        self.0.entry(address).or_default().insert(flow);
+    pub fn register(&mut self, address: String, flow: String, decoder_version: DecoderVersion) {
+        self.flows.entry(address.clone()).or_default().insert(flow);
+        self.decoder_versions.insert(address, decoder_version);
     }
 
     pub fn flows(&self, address: &str) -> Option<&HashSet<String>> {
-        self.0.get(address)
+        self.flows.get(address)
+    }
+
+    /// The version of the format of the events emitted by the contract at this height.
+    pub fn decoder_version(&self, address: &str) -> DecoderVersion {
+        self.decoder_versions
+            .get(address)
+            .copied()
+            .unwrap_or_default()
     }
 
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.flows.len()
     }
 }
 
@@ -331,34 +394,50 @@ impl BlockHandle for TmBlockHandle {
             })
             .collect_vec();
 
-        let transaction_hashes_of_filtered_events = filtered_events
+        let has_filtered_events = !filtered_events.is_empty();
+
+        // generic events of blocks that are not sampled are dropped, but are still mapped below. The
+        // block itself is always inserted, since the fixer and `block_update` rely on its row
+        let sampled_events = filtered_events
+            .into_iter()
+            .filter(|event| match &self.tm_client.event_sampling {
+                Some(event_sampling) => event_sampling.includes(block.height, &event.flows),
+                None => true,
+            })
+            .collect_vec();
+
+        let transaction_hashes_of_sampled_events = sampled_events
             .iter()
             .filter_map(|event| event.event.transaction_hash.clone())
             .collect::<HashSet<String>>();
 
-        let filtered_transactions = transactions
+        let sampled_transactions = transactions
             .into_iter()
-            .filter(|transaction| transaction_hashes_of_filtered_events.contains(&transaction.hash))
+            .filter(|transaction| transaction_hashes_of_sampled_events.contains(&transaction.hash))
             .collect_vec();
 
-        let events = if !&filtered_events.is_empty() {
+        // governance proposals are emitted by the chain, so blocks without events of registered
+        // contracts are mapped as well
+        let mapped_events = self.tm_client.transform_to_ucs_events(
+            &reference,
+            &active_contracts,
+            &raw_block_header,
+            &raw_transactions,
+        )?;
+
+        let events = if has_filtered_events {
             trace!(
                 "{}: insert (transactions: {}, events:{})",
                 reference,
-                filtered_transactions.len(),
-                filtered_events.len(),
+                sampled_transactions.len(),
+                sampled_events.len(),
             );
 
             vec![
                 insert_batch_blocks(vec![block]).await?,
-                insert_batch_transactions(filtered_transactions).await?,
-                insert_batch_events(filtered_events).await?,
-                self.tm_client.transform_to_ucs_events(
-                    &reference,
-                    &active_contracts,
-                    &raw_block_header,
-                    &raw_transactions,
-                )?,
+                insert_batch_transactions(sampled_transactions).await?,
+                insert_batch_events(sampled_events).await?,
+                mapped_events,
             ]
             .into_iter()
             .flatten()
@@ -366,7 +445,7 @@ impl BlockHandle for TmBlockHandle {
         } else {
             trace!("{}: ignore (no events for registered contracts)", reference);
 
-            vec![]
+            mapped_events
         };
 
         debug!("{}: done", reference);
@@ -1,13 +1,17 @@
-use color_eyre::eyre::Report;
+use color_eyre::eyre::{eyre, Report};
+use cometbft_rpc::ClientOptions;
 use sqlx::PgPool;
 use url::Url;
 
 use crate::indexer::{
     api::{BlockHeight, IndexerId},
+    contracts::{validate_contracts, ContractRegistration},
     event::types::UniversalChainId,
     nats::NatsConnection,
-    tendermint::{context::TmContext, fetcher_client::TmFetcherClient},
-    ConsumerConfig, EnricherConfig, FinalizerConfig, FixerConfig, Indexer, PublisherConfig,
+    scheduler::FetchScheduler,
+    tendermint::{context::TmContext, fetcher_client::TmFetcherClient, sampling::EventSampling},
+    BackfillConfig, ConsumerConfig, EnricherConfig, Finality, FinalizerConfig, FixerConfig,
+    Indexer, PublisherConfig, RetrierConfig,
 };
 
 const DEFAULT_CHUNK_SIZE: usize = 20;
@@ -19,22 +23,73 @@ pub struct Config {
     pub universal_chain_id: UniversalChainId,
     pub start_height: BlockHeight,
     pub chunk_size: Option<usize>,
+    /// Cometbft rpc endpoints of the chain. The `grpc` fetcher only uses them for block events.
+    #[serde(default)]
     pub rpc_urls: Vec<Url>,
+    /// Cosmos sdk grpc endpoints of the chain, required by the `grpc` fetcher.
+    #[serde(default)]
+    pub grpc_urls: Vec<Url>,
+    /// How blocks and transactions are fetched, `rpc` by default.
+    #[serde(default)]
+    pub fetcher: Fetcher,
+    /// Do not index block events with the `grpc` fetcher, so that it does not need `rpc_urls`.
+    #[serde(default)]
+    pub skip_block_events: bool,
     pub tx_search_max_page_size: Option<u8>,
+    /// How to determine the last finalized block, the latest block if not set.
+    #[serde(default)]
+    pub finality: Finality,
     #[serde(default)]
     pub finalizer: FinalizerConfig,
     #[serde(default)]
     pub fixer: FixerConfig,
     #[serde(default)]
+    pub backfill: BackfillConfig,
+    #[serde(default)]
     pub publisher: PublisherConfig,
     #[serde(default)]
     pub consumer: ConsumerConfig,
     #[serde(default)]
     pub enricher: EnricherConfig,
     #[serde(default)]
+    pub retrier: RetrierConfig,
+    #[serde(default)]
     pub testnet: bool,
     #[serde(default)]
     pub drain: bool,
+    /// Name of the database (see `--databases`) to write to, the default database if not set.
+    #[serde(default)]
+    pub database: Option<String>,
+    /// Size of a connection pool dedicated to this indexer, the shared pool of its database if not
+    /// set.
+    #[serde(default)]
+    pub max_connections: Option<u32>,
+    /// Index only a sample of the generic events of this chain, the events of all blocks if not
+    /// set.
+    #[serde(default)]
+    pub event_sampling: Option<EventSampling>,
+    /// Contracts registered when the indexer starts, in addition to those registered in
+    /// `v2_cosmos.contracts`. The `contract_type` is the flow of the contract.
+    #[serde(default)]
+    pub contracts: Vec<ContractRegistration>,
+    /// Options for the connections to the rpc nodes. Set `stream_large_responses` for chains with
+    /// blocks of multiple megabytes.
+    #[serde(default)]
+    pub rpc_client: ClientOptions,
+}
+
+/// How the blocks and transactions of a chain are fetched.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fetcher {
+    /// Over the cometbft json-rpc of `rpc_urls`.
+    #[default]
+    Rpc,
+    /// Over the tendermint and tx grpc services of `grpc_urls`, with the requests of a chunk
+    /// multiplexed over one http/2 connection per endpoint. Block events (begin/end block and
+    /// finalize block events) are not exposed over grpc, and are fetched from `rpc_urls` unless
+    /// `skip_block_events` is set.
+    Grpc,
 }
 
 impl Config {
@@ -42,7 +97,45 @@ impl Config {
         self,
         pg_pool: PgPool,
         nats: Option<NatsConnection>,
+        fetch_scheduler: FetchScheduler,
     ) -> Result<Indexer<TmFetcherClient>, Report> {
+        match self.fetcher {
+            Fetcher::Rpc if self.rpc_urls.is_empty() => {
+                return Err(eyre!(
+                    "{}: the rpc fetcher requires rpc_urls",
+                    self.indexer_id
+                ))
+            }
+            Fetcher::Grpc if self.grpc_urls.is_empty() => {
+                return Err(eyre!(
+                    "{}: the grpc fetcher requires grpc_urls",
+                    self.indexer_id
+                ))
+            }
+            Fetcher::Grpc if self.rpc_urls.is_empty() && !self.skip_block_events => {
+                return Err(eyre!(
+                    "{}: the grpc fetcher requires rpc_urls for block events, unless skip_block_events is set",
+                    self.indexer_id
+                ))
+            }
+            _ => {}
+        }
+
+        if let Some(contract) = self
+            .contracts
+            .iter()
+            .find(|contract| contract.commit.is_some())
+        {
+            return Err(eyre!(
+                "{}: contract {}: commit is not supported for cosmwasm contracts",
+                self.indexer_id,
+                contract.address
+            ));
+        }
+
+        validate_contracts(&self.contracts)
+            .map_err(|err| eyre!("{}: invalid contracts: {err}", self.indexer_id))?;
+
         Ok(Indexer::new(
             pg_pool,
             nats,
@@ -52,15 +145,25 @@ impl Config {
             self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
             self.finalizer,
             self.fixer,
+            self.backfill,
             self.publisher,
             self.consumer,
             self.enricher,
+            self.retrier,
+            fetch_scheduler,
             TmContext {
                 rpc_urls: self.rpc_urls,
+                grpc_urls: self.grpc_urls,
+                fetcher: self.fetcher,
+                skip_block_events: self.skip_block_events,
+                finality: self.finality,
                 tx_search_max_page_size: self
                     .tx_search_max_page_size
                     .unwrap_or(DEFAULT_TRANSACTIONS_MAX_PAGE_SIZE),
                 testnet: self.testnet,
+                event_sampling: self.event_sampling,
+                contracts: self.contracts,
+                rpc_client: self.rpc_client,
             },
             self.drain,
         ))
@@ -1,20 +1,37 @@
 use std::fmt::Display;
 
+use cometbft_rpc::ClientOptions;
 use url::Url;
 
+use crate::indexer::{
+    contracts::ContractRegistration,
+    tendermint::{config::Fetcher, sampling::EventSampling},
+    Finality,
+};
+
 #[derive(Clone)]
 pub struct TmContext {
     pub rpc_urls: Vec<Url>,
+    pub grpc_urls: Vec<Url>,
+    pub fetcher: Fetcher,
+    pub skip_block_events: bool,
+    pub finality: Finality,
     pub tx_search_max_page_size: u8,
     pub testnet: bool,
+    pub event_sampling: Option<EventSampling>,
+    pub contracts: Vec<ContractRegistration>,
+    pub rpc_client: ClientOptions,
 }
 
 impl Display for TmContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "rpcs: {}, tx_search_max_page_size: {}",
+            "fetcher: {:?}, finality: {}, rpcs: {}, grpcs: {}, tx_search_max_page_size: {}",
+            self.fetcher,
+            self.finality,
             to_indexed_url_string(&self.rpc_urls),
+            to_indexed_url_string(&self.grpc_urls),
             self.tx_search_max_page_size,
         )
     }
@@ -1,4 +1,4 @@
-use std::{error::Error, fmt::Display};
+use std::{collections::HashMap, error::Error, fmt::Display, num::NonZeroU64};
 
 use axum::async_trait;
 use color_eyre::{
@@ -6,19 +6,24 @@ use color_eyre::{
     Result,
 };
 use cometbft_rpc::{
-    rpc_types::{BlockMeta, BlockResultsResponse, Order, TxResponse},
+    rpc_types::{BlockResultsResponse, TxResponse},
+    types::abci::{event::Event, exec_tx_result::ExecTxResult},
     JsonRpcError,
 };
 use futures::{
-    join,
+    future, join,
     stream::{BoxStream, FuturesOrdered},
     FutureExt, Stream, StreamExt, TryFutureExt,
 };
 use itertools::Itertools;
 use jsonrpsee::types::{error::INTERNAL_ERROR_CODE, ErrorObject};
+use protos::cosmos::base::abci::v1beta1::TxResponse as GrpcTxResponse;
+use sha2::{Digest, Sha256};
 use time::OffsetDateTime;
 use tokio::task::JoinSet;
+use tonic::Code;
 use tracing::{debug, info, info_span, trace, warn, Instrument};
+use unionlabs::primitives::{encoding::HexUnprefixed, H256};
 
 use crate::{
     indexer::{
@@ -28,10 +33,14 @@ use crate::{
         },
         tendermint::{
             block_handle::{BlockDetails, BlockHeader, TmBlockHandle},
+            config::Fetcher,
             context::TmContext,
             mapping::legacy::{PgBlock, PgEvent, PgTransaction},
-            provider::{Provider, RpcProviderId},
+            postgres::{register_contracts, update_event_sampling},
+            provider::{GrpcProviderId, Provider, ProviderId, RpcProviderId},
+            sampling::EventSampling,
         },
+        Finality,
     },
     postgres::{fetch_chain_id_tx, ChainId},
 };
@@ -40,8 +49,12 @@ use crate::{
 pub struct TmFetcherClient {
     pub chain_id: ChainId,
     pub provider: Provider,
+    pub fetcher: Fetcher,
+    pub skip_block_events: bool,
+    pub finality: Finality,
     pub tx_search_max_page_size: u8,
     pub testnet: bool,
+    pub event_sampling: Option<EventSampling>,
 }
 
 impl Display for TmFetcherClient {
@@ -55,7 +68,7 @@ impl TmFetcherClient {
         &self,
         block_range: BlockRange,
         fetch_mode: FetchMode,
-        provider_id: Option<RpcProviderId>,
+        provider_id: Option<ProviderId>,
     ) -> Result<impl Stream<Item = Result<TmBlockHandle, IndexerError>> + use<'_>, IndexerError>
     {
         debug!("{}: fetching", block_range);
@@ -63,48 +76,105 @@ impl TmFetcherClient {
         let block_range_clone = block_range.clone();
         let futures = async move {
             let block_range_clone = block_range_clone.clone();
-            let metas_response = self
-                .provider
-                .blockchain(
-                    block_range_clone.start_inclusive,
-                    block_range_clone.end_exclusive - 1,
-                    provider_id,
-                )
-                .inspect_err(|e| debug!(?e, "{}: error fetching blocks", block_range_clone))
-                .await;
-
-            match metas_response {
-                Ok(result) => self.handle_ok_fetching_metas(
-                    result.response.block_metas,
-                    fetch_mode,
-                    result.provider_id,
-                ),
-                Err(error) => Self::handle_err_fetching_metas(error),
+            let headers_response: Result<(Vec<BlockHeader>, ProviderId), IndexerError> =
+                match self.fetcher {
+                    Fetcher::Rpc => self
+                        .provider
+                        .blockchain(
+                            block_range_clone.start_inclusive,
+                            block_range_clone.end_exclusive - 1,
+                            provider_id.and_then(ProviderId::rpc),
+                        )
+                        .inspect_err(|e| debug!(?e, "{}: error fetching blocks", block_range_clone))
+                        .await
+                        .map(|result| {
+                            (
+                                result
+                                    .response
+                                    .block_metas
+                                    .into_iter()
+                                    .map(Into::into)
+                                    .collect(),
+                                ProviderId::Rpc(result.provider_id),
+                            )
+                        })
+                        .map_err(Into::into),
+                    Fetcher::Grpc => {
+                        self.fetch_headers_grpc(
+                            block_range_clone,
+                            provider_id.and_then(ProviderId::grpc),
+                        )
+                        .await
+                    }
+                };
+
+            match headers_response {
+                Ok((headers, provider_id)) => {
+                    self.handle_ok_fetching_headers(headers, fetch_mode, provider_id)
+                }
+                Err(error) => Self::handle_err_fetching_headers(error),
             }
         };
 
         Ok(futures.flatten_stream())
     }
 
-    pub fn handle_ok_fetching_metas(
+    /// The headers of the blocks in `block_range`. Only the first block is raced, the other blocks
+    /// are fetched concurrently (multiplexed over one connection) from the node that returned it.
+    async fn fetch_headers_grpc(
+        &self,
+        block_range: BlockRange,
+        provider_id: Option<GrpcProviderId>,
+    ) -> Result<(Vec<BlockHeader>, ProviderId), IndexerError> {
+        let first = self
+            .provider
+            .grpc_block(block_range.start_inclusive, provider_id)
+            .inspect_err(|e| debug!(?e, "{}: error fetching blocks", block_range))
+            .await?;
+
+        let provider_id = first.provider_id;
+
+        let mut headers = vec![BlockHeader::try_from(first.response)?];
+
+        headers.extend(
+            future::try_join_all(
+                (block_range.start_inclusive + 1..block_range.end_exclusive).map(
+                    |height| async move {
+                        BlockHeader::try_from(
+                            self.provider
+                                .grpc_block(height, Some(provider_id))
+                                .inspect_err(|e| debug!(?e, "{}: error fetching block", height))
+                                .await?
+                                .response,
+                        )
+                    },
+                ),
+            )
+            .await?,
+        );
+
+        Ok((headers, ProviderId::Grpc(provider_id)))
+    }
+
+    pub fn handle_ok_fetching_headers(
         &self,
-        block_metas: Vec<BlockMeta>,
+        headers: Vec<BlockHeader>,
         fetch_mode: FetchMode,
-        provider_id: RpcProviderId,
+        provider_id: ProviderId,
     ) -> BoxStream<Result<TmBlockHandle, IndexerError>> {
         FuturesOrdered::from_iter(
-            block_metas
+            headers
                 .into_iter()
-                .sorted_by_key(|meta| meta.header.height)
-                .map(|meta| async move {
+                .sorted_by_key(|header| header.header.height)
+                .map(|header| async move {
                     Ok(TmBlockHandle {
                         internal_chain_id: self.chain_id.db,
-                        reference: meta.block_reference()?,
+                        reference: header.block_reference()?,
                         details: match fetch_mode {
-                            FetchMode::Lazy => BlockDetails::Lazy(Box::new(meta.into())),
+                            FetchMode::Lazy => BlockDetails::Lazy(Box::new(header)),
                             FetchMode::Eager => {
                                 let (pg_data, raw_data) =
-                                    self.fetch_details(&meta.into(), provider_id).await?;
+                                    self.fetch_details(&header, provider_id).await?;
                                 BlockDetails::Eager(pg_data, raw_data)
                             }
                         },
@@ -119,7 +189,7 @@ impl TmFetcherClient {
     pub async fn fetch_details(
         &self,
         block_header: &BlockHeader,
-        provider_id: RpcProviderId,
+        provider_id: ProviderId,
     ) -> Result<
         (
             (PgBlock, Vec<PgTransaction>, Vec<PgEvent>),
@@ -131,13 +201,30 @@ impl TmFetcherClient {
 
         info!("{}: fetch details", block_reference);
 
-        let (block_results, transactions_response) = self
-            .fetch_details_from_provider(block_reference.height, provider_id)
-            .await?;
+        let (block_events, transactions_response) = match provider_id {
+            ProviderId::Rpc(provider_id) => {
+                let (block_results, transactions_response) = self
+                    .fetch_details_from_provider(block_reference.height, provider_id)
+                    .await?;
 
-        self.check_consistency(provider_id, &block_results, &transactions_response)?;
+                self.check_consistency(provider_id, &block_results, &transactions_response)?;
 
-        self.convert_to_pg_data(block_header, block_results, transactions_response)
+                (
+                    block_results.finalize_block_events.unwrap_or_default(),
+                    transactions_response,
+                )
+            }
+            ProviderId::Grpc(provider_id) => {
+                let (block_events, transactions_response) = join!(
+                    self.fetch_block_events_rpc(block_reference.height),
+                    self.fetch_transactions_grpc(block_header, provider_id),
+                );
+
+                (block_events?, transactions_response?)
+            }
+        };
+
+        self.convert_to_pg_data(block_header, block_events, transactions_response)
     }
 
     pub async fn fetch_details_from_provider(
@@ -152,7 +239,7 @@ impl TmFetcherClient {
             .inspect_err(|e| debug!(?e, ?height, "error fetching block results"));
 
         let transactions_response = self
-            .fetch_transactions_for_block(height, None, provider_id)
+            .fetch_transactions_for_block(height, provider_id)
             .inspect_err(|e| debug!(?e, "error fetching transactions for block"));
 
         // wait for results
@@ -174,14 +261,8 @@ impl TmFetcherClient {
             .sum();
 
         let block_tx_event_count: usize = block_results
-            .txs_results
-            .iter()
-            .map(|tx_results| {
-                tx_results
-                    .iter()
-                    .map(|tx_result| tx_result.events.len())
-                    .sum::<usize>()
-            })
+            .tx_events()
+            .map(|(_, events)| events.len())
             .sum();
 
         match txs_event_count == block_tx_event_count {
@@ -212,7 +293,7 @@ impl TmFetcherClient {
     pub fn convert_to_pg_data(
         &self,
         block_header: &BlockHeader,
-        block_results: BlockResultsResponse,
+        block_events: Vec<Event>,
         transactions_response: Vec<TxResponse>,
     ) -> Result<
         (
@@ -286,24 +367,19 @@ impl TmFetcherClient {
                 .collect::<Vec<_>>();
 
         // add all block events
-        pg_events.extend(
-            block_results
-                .clone()
-                .events(
-                    self.chain_id,
-                    block_reference.hash,
-                    block_reference.timestamp,
-                )
-                .into_iter()
-                .enumerate()
-                .map(|(i, e)| {
-                    let index: i32 = i.try_into().unwrap();
-                    PgEvent {
-                        block_index: index + block_index,
-                        ..e
-                    }
-                }),
-        );
+        pg_events.extend(block_events.iter().enumerate().map(|(i, event)| {
+            let index: i32 = i.try_into().unwrap();
+            PgEvent {
+                chain_id: self.chain_id,
+                block_hash: block_reference.hash.clone(),
+                block_height: block_reference.height,
+                time: block_reference.timestamp,
+                data: serde_json::to_value(event).unwrap().replace_escape_chars(),
+                transaction_hash: None,
+                transaction_index: None,
+                block_index: index + block_index,
+            }
+        }));
 
         Ok((
             (pg_block, pg_transactions, pg_events),
@@ -311,38 +387,41 @@ impl TmFetcherClient {
         ))
     }
 
-    pub fn handle_err_fetching_metas(
-        error: JsonRpcError,
+    pub fn handle_err_fetching_headers(
+        error: IndexerError,
     ) -> BoxStream<'static, Result<TmBlockHandle, IndexerError>> {
-        futures::stream::once(async move { Err(error.into()) }).boxed()
+        futures::stream::once(async move { Err(error) }).boxed()
     }
 
     pub async fn fetch_single_with_provider(
         &self,
         selection: BlockSelection,
         mode: FetchMode,
-        provider_id: Option<RpcProviderId>,
+        provider_id: Option<ProviderId>,
     ) -> Result<TmBlockHandle, IndexerError> {
         debug!("{}: fetching", selection);
 
-        let block_header: Result<Option<(RpcProviderId, BlockHeader)>, JsonRpcError> =
-            match selection {
-                BlockSelection::LastFinalized => self
-                    .provider
-                    .latest_block(provider_id)
-                    .inspect_err(|e| debug!(?e, "error fetching latest block"))
-                    .await
-                    .map(|response| Some((response.provider_id, response.response.into()))),
-                BlockSelection::Height(height) => match self
-                    .provider
-                    .block(height, provider_id)
-                    .inspect_err(|e| debug!(?e, "error fetching block at {}", height))
-                    .await
+        // with a confirmation depth, the last finalized block is that many blocks below the
+        // latest block
+        let selection = match (selection, self.finality) {
+            (BlockSelection::LastFinalized, Finality::ConfirmationDepth { confirmation_depth }) => {
+                match self
+                    .fetch_header(&BlockSelection::LastFinalized, provider_id)
+                    .await?
                 {
-                    Ok(result) => Ok(Some((result.provider_id, result.response.into()))),
-                    Err(err) => Self::detect_reading_beyond_tip(err, &selection),
-                },
-            };
+                    Some((_, header)) => BlockSelection::Height(
+                        header
+                            .block_reference()?
+                            .height
+                            .saturating_sub(confirmation_depth),
+                    ),
+                    None => return Err(IndexerError::NoBlock(BlockSelection::LastFinalized)),
+                }
+            }
+            (selection, _) => selection,
+        };
+
+        let block_header = self.fetch_header(&selection, provider_id).await;
 
         match block_header {
             Ok(Some((provider_id, header))) => {
@@ -372,18 +451,114 @@ impl TmFetcherClient {
 
                 Err(IndexerError::NoBlock(selection))
             }
-            Err(report) => {
-                info!("{}: error: {}", selection, report);
+            Err(error) => {
+                info!("{}: error: {}", selection, error);
 
-                Err(report.into())
+                Err(error)
             }
         }
     }
 
+    async fn fetch_header(
+        &self,
+        selection: &BlockSelection,
+        provider_id: Option<ProviderId>,
+    ) -> Result<Option<(ProviderId, BlockHeader)>, IndexerError> {
+        match self.fetcher {
+            Fetcher::Rpc => self
+                .fetch_header_rpc(selection, provider_id.and_then(ProviderId::rpc))
+                .await
+                .map_err(Into::into),
+            Fetcher::Grpc => {
+                self.fetch_header_grpc(selection, provider_id.and_then(ProviderId::grpc))
+                    .await
+            }
+        }
+    }
+
+    async fn fetch_header_rpc(
+        &self,
+        selection: &BlockSelection,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<Option<(ProviderId, BlockHeader)>, JsonRpcError> {
+        match selection {
+            BlockSelection::LastFinalized => self
+                .provider
+                .latest_block(provider_id)
+                .inspect_err(|e| debug!(?e, "error fetching latest block"))
+                .await
+                .map(|response| {
+                    Some((
+                        ProviderId::Rpc(response.provider_id),
+                        response.response.into(),
+                    ))
+                }),
+            BlockSelection::Height(height) => match self
+                .provider
+                .block(*height, provider_id)
+                .inspect_err(|e| debug!(?e, "error fetching block at {}", height))
+                .await
+            {
+                Ok(result) => Ok(Some((
+                    ProviderId::Rpc(result.provider_id),
+                    result.response.into(),
+                ))),
+                Err(err) => Self::detect_reading_beyond_tip(err, selection),
+            },
+        }
+    }
+
+    async fn fetch_header_grpc(
+        &self,
+        selection: &BlockSelection,
+        provider_id: Option<GrpcProviderId>,
+    ) -> Result<Option<(ProviderId, BlockHeader)>, IndexerError> {
+        match selection {
+            BlockSelection::LastFinalized => {
+                let result = self
+                    .provider
+                    .grpc_latest_block(provider_id)
+                    .inspect_err(|e| debug!(?e, "error fetching latest block"))
+                    .await?;
+
+                Ok(Some((
+                    ProviderId::Grpc(result.provider_id),
+                    result.response.try_into()?,
+                )))
+            }
+            BlockSelection::Height(height) => match self
+                .provider
+                .grpc_block(*height, provider_id)
+                .inspect_err(|e| debug!(?e, "error fetching block at {}", height))
+                .await
+            {
+                Ok(result) => Ok(Some((
+                    ProviderId::Grpc(result.provider_id),
+                    result.response.try_into()?,
+                ))),
+                // the sdk rejects heights above the latest height with "requested block height is
+                // bigger then the chain length"
+                Err(status)
+                    if status.code() == Code::InvalidArgument
+                        && status.message().contains("chain length") =>
+                {
+                    trace!(
+                        "{}: no block: beyond tip error: {}",
+                        selection,
+                        status.message()
+                    );
+
+                    Ok(None)
+                }
+                Err(status) => Err(status.into()),
+            },
+        }
+    }
+
     fn detect_reading_beyond_tip(
         error: JsonRpcError,
         selection: &BlockSelection,
-    ) -> Result<Option<(RpcProviderId, BlockHeader)>, JsonRpcError> {
+    ) -> Result<Option<(ProviderId, BlockHeader)>, JsonRpcError> {
         if let Some(source) = error.source() {
             if let Some(error_object) = source.downcast_ref::<ErrorObject>() {
                 if let (INTERNAL_ERROR_CODE, Some(message)) = (
@@ -407,97 +582,165 @@ impl TmFetcherClient {
     async fn fetch_transactions_for_block(
         &self,
         height: BlockHeight,
-        expected: impl Into<Option<usize>>,
         provider_id: RpcProviderId,
     ) -> Result<Vec<TxResponse>, Report> {
-        let expected = expected.into();
+        debug!("{}: fetching", height);
+
+        let txs = self
+            .provider
+            .tx_search_all(height, self.tx_search_max_page_size, Some(provider_id))
+            .await?
+            .response;
+
+        debug!("{height}: fetched all transactions ({})", txs.len());
+
+        Ok(txs)
+    }
+
+    /// The block events at `height` from any of the rpc nodes, as they are not exposed over grpc.
+    /// No block events are fetched with `skip_block_events`.
+    async fn fetch_block_events_rpc(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Vec<Event>, IndexerError> {
+        if self.skip_block_events {
+            return Ok(vec![]);
+        }
+
+        debug!("{}: fetching block results", height);
+
+        Ok(self
+            .provider
+            .block_results(height, None)
+            .inspect_err(|e| debug!(?e, ?height, "error fetching block results"))
+            .await?
+            .response
+            .finalize_block_events
+            .unwrap_or_default())
+    }
+
+    /// The transactions of the block, in the order of the block. The results of the transactions
+    /// are looked up in the tx index of the node by their hash. The block is only fetched again if
+    /// its transactions were not fetched with its header.
+    async fn fetch_transactions_grpc(
+        &self,
+        block_header: &BlockHeader,
+        provider_id: GrpcProviderId,
+    ) -> Result<Vec<TxResponse>, IndexerError> {
+        let height = block_header.block_reference()?.height;
 
         debug!("{}: fetching", height);
-        let mut txs = if let Some(expected) = expected {
-            Vec::with_capacity(expected)
-        } else {
-            vec![]
+
+        let raw_txs = async {
+            Ok::<_, tonic::Status>(match &block_header.txs {
+                Some(txs) => txs.clone(),
+                None => self
+                    .provider
+                    .grpc_block(height, Some(provider_id))
+                    .await?
+                    .response
+                    .block
+                    .and_then(|block| block.data)
+                    .map(|data| data.txs)
+                    .unwrap_or_default(),
+            })
         };
 
-        for page in 1..u32::MAX {
-            debug!("{height}: fetching transactions page {page}");
-            let response = self
-                .provider
-                .tx_search(
-                    height,
-                    false,
-                    page,
-                    self.tx_search_max_page_size,
-                    Order::Asc,
-                    Some(provider_id),
-                )
-                .await?
-                .response;
-            let len = response.txs.len();
-            txs.extend(response.txs);
-
-            // We always query for the maximum page size. If we get less items, we know pagination is done
-            let tx_search_max_page_size: u32 = self.tx_search_max_page_size.into();
-            let len: u32 = len.try_into().unwrap();
-            let current_count = (page - 1) * tx_search_max_page_size + len;
-            let total_count = response.total_count;
-
-            debug!("{height}: fetched transactions page {page} ({current_count}/{total_count})");
-
-            match current_count.cmp(&response.total_count) {
-                std::cmp::Ordering::Less => debug!("{height}: fetching transaction => next page"),
-                std::cmp::Ordering::Equal => {
-                    debug!("{height}: fetched all transactions");
-                    break;
-                }
-                std::cmp::Ordering::Greater => {
-                    debug!("{height}: fetched more transactions ({current_count}) than expected ({total_count})");
-                    let var_name = eyre!(
-                        "fetched more transactions ({current_count}) than expected ({total_count})"
-                    );
-                    return Err(var_name);
+        let (raw_txs, tx_responses) = join!(
+            raw_txs,
+            self.provider
+                .grpc_txs(height, self.tx_search_max_page_size, Some(provider_id)),
+        );
+
+        let mut tx_responses = tx_responses?
+            .response
+            .into_iter()
+            .filter_map(|tx_response| Some((hex::decode(&tx_response.txhash).ok()?, tx_response)))
+            .collect::<HashMap<_, _>>();
+
+        let raw_txs = raw_txs?;
+
+        let mut txs = Vec::with_capacity(raw_txs.len());
+
+        for (index, tx) in raw_txs.into_iter().enumerate() {
+            let hash = H256::<HexUnprefixed>::new(Sha256::digest(&tx).into());
+
+            let Some(tx_response) = tx_responses.remove(hash.get().as_slice()) else {
+                match self.testnet {
+                    true => {
+                        // testnet rpcs often have inconsistencies. accept them
+                        warn!(
+                            "provider: {:?} at height {} transaction {} is not indexed",
+                            provider_id, height, hash
+                        );
+                        continue;
+                    }
+                    false => {
+                        return Err(IndexerError::ProviderError(Box::new(eyre!(
+                            "provider: {:?} at height {} transaction {} is not indexed",
+                            provider_id,
+                            height,
+                            hash
+                        ))))
+                    }
                 }
-            }
+            };
 
-            // If we deduce the number from expected, we end pagination once we reach expected.
-            if txs.len() == expected.unwrap_or(usize::MAX) {
-                break;
-            }
+            txs.push(tx_response_from_grpc(
+                tx_response,
+                tx,
+                hash,
+                index.try_into().unwrap(),
+            )?);
         }
 
-        if let Some(expected) = expected {
-            assert_eq!(txs.len(), expected, "block {height}");
-        }
+        debug!("{height}: fetched all transactions ({})", txs.len());
+
         Ok(txs)
     }
 }
 
-pub trait BlockExt {
-    /// Returns the non-tx related events from a block formatted for insertion.
-    fn events(self, chain_id: ChainId, block_hash: String, time: OffsetDateTime) -> Vec<PgEvent>;
-}
-
-impl BlockExt for BlockResultsResponse {
-    fn events(self, chain_id: ChainId, block_hash: String, time: OffsetDateTime) -> Vec<PgEvent> {
-        let finalize_block_events = self.finalize_block_events.iter().map(|e| PgEvent {
-            chain_id,
-            block_hash: block_hash.clone(),
-            block_height: self.height,
-            time,
-            data: serde_json::to_value(e).unwrap().replace_escape_chars(),
-            transaction_hash: None,
-            transaction_index: None,
-            block_index: 0,
-        });
-
-        finalize_block_events
-            .enumerate()
-            .map(|(i, mut event)| {
-                event.block_index = i.try_into().unwrap();
-                event
-            })
-            .collect()
-    }
+/// Converts the result of a transaction in the tx index of a cosmos sdk node to the result of
+/// `tx_search`.
+fn tx_response_from_grpc(
+    tx_response: GrpcTxResponse,
+    tx: Vec<u8>,
+    hash: H256<HexUnprefixed>,
+    index: u32,
+) -> Result<TxResponse, IndexerError> {
+    let gas_wanted = tx_response.gas_wanted.try_into().map_err(|_| {
+        IndexerError::ProviderError(Box::new(eyre!(
+            "transaction {hash}: invalid gas wanted: {}",
+            tx_response.gas_wanted
+        )))
+    })?;
+
+    let gas_used = tx_response.gas_used.try_into().map_err(|_| {
+        IndexerError::ProviderError(Box::new(eyre!(
+            "transaction {hash}: invalid gas used: {}",
+            tx_response.gas_used
+        )))
+    })?;
+
+    Ok(TxResponse {
+        hash,
+        height: u64::try_from(tx_response.height)
+            .ok()
+            .and_then(NonZeroU64::new),
+        index,
+        tx_result: ExecTxResult {
+            code: tx_response.code.into(),
+            data: hex::decode(&tx_response.data).ok().map(Into::into),
+            log: tx_response.raw_log,
+            info: tx_response.info,
+            gas_wanted,
+            gas_used,
+            events: tx_response.events.into_iter().map(Into::into).collect(),
+            codespace: tx_response.codespace,
+        },
+        tx: tx.into(),
+        proof: None,
+    })
 }
 
 #[derive(serde::Serialize)]
@@ -559,17 +802,32 @@ impl FetcherClient for TmFetcherClient {
         _join_set: &mut JoinSet<Result<(), IndexerError>>,
         context: TmContext,
     ) -> Result<Self, IndexerError> {
-        let provider = Provider::new(context.rpc_urls).await?;
+        let provider =
+            Provider::new(context.rpc_urls, context.grpc_urls, context.rpc_client).await?;
 
         info!("fetching chain-id from node");
-        let chain_id = provider
-            .status(None)
-            .inspect_err(|e| debug!(?e, "error fetching chain-id: {}", e))
-            .await?
-            .response
-            .node_info
-            .network
-            .to_string();
+        let chain_id = match context.fetcher {
+            Fetcher::Rpc => provider
+                .status(None)
+                .inspect_err(|e| debug!(?e, "error fetching chain-id: {}", e))
+                .await?
+                .response
+                .node_info
+                .network
+                .to_string(),
+            Fetcher::Grpc => {
+                provider
+                    .grpc_node_info(None)
+                    .inspect_err(|e| debug!(?e, "error fetching chain-id: {}", e))
+                    .await?
+                    .response
+                    .default_node_info
+                    .ok_or_else(|| {
+                        IndexerError::ProviderError(Box::new(eyre!("expected node info")))
+                    })?
+                    .network
+            }
+        };
 
         info!("fetched chain-id from node: {}", chain_id);
 
@@ -579,13 +837,25 @@ impl FetcherClient for TmFetcherClient {
 
             let chain_id = fetch_chain_id_tx(&mut tx, chain_id.to_string()).await?;
 
+            update_event_sampling(&mut tx, chain_id.db, context.event_sampling.as_ref()).await?;
+
+            register_contracts(&mut tx, chain_id.db, &context.contracts).await?;
+            info!(
+                "registered {} contracts from config",
+                context.contracts.len()
+            );
+
             tx.commit().await?;
 
             Ok(TmFetcherClient {
                 chain_id,
                 provider,
+                fetcher: context.fetcher,
+                skip_block_events: context.skip_block_events,
+                finality: context.finality,
                 tx_search_max_page_size: context.tx_search_max_page_size,
                 testnet: context.testnet,
+                event_sampling: context.event_sampling,
             })
         }
         .instrument(indexing_span)
@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use protos::cosmos::{
+    base::{
+        abci::v1beta1::TxResponse,
+        tendermint::v1beta1::{
+            GetBlockByHeightRequest, GetBlockByHeightResponse, GetLatestBlockRequest,
+            GetLatestBlockResponse, GetNodeInfoRequest, GetNodeInfoResponse,
+        },
+    },
+    tx::v1beta1::{GetTxsEventRequest, GetTxsEventResponse, OrderBy},
+};
+use tonic::{
+    client::Grpc,
+    codec::ProstCodec,
+    transport::{Channel, ClientTlsConfig, Endpoint},
+    GrpcMethod, IntoRequest, Status,
+};
+use unionlabs::ErrorReporter;
+use url::Url;
+
+const TENDERMINT_SERVICE: &str = "cosmos.base.tendermint.v1beta1.Service";
+const TX_SERVICE: &str = "cosmos.tx.v1beta1.Service";
+
+/// Client for the tendermint and tx grpc services of a cosmos sdk node. All requests are
+/// multiplexed over a single http/2 connection, which is established on the first request.
+#[derive(Debug, Clone)]
+pub struct GrpcClient {
+    inner: Grpc<Channel>,
+}
+
+impl GrpcClient {
+    pub fn new(url: &Url) -> Result<Self, tonic::transport::Error> {
+        let endpoint = Endpoint::new(url.to_string())?
+            .http2_adaptive_window(true)
+            .http2_keep_alive_interval(Duration::from_secs(30))
+            .keep_alive_while_idle(true);
+
+        let endpoint = match url.scheme() {
+            "https" => endpoint.tls_config(ClientTlsConfig::new())?,
+            _ => endpoint,
+        };
+
+        Ok(Self {
+            inner: Grpc::new(endpoint.connect_lazy()),
+        })
+    }
+
+    pub async fn node_info(&self) -> Result<GetNodeInfoResponse, Status> {
+        self.unary(TENDERMINT_SERVICE, "GetNodeInfo", GetNodeInfoRequest {})
+            .await
+    }
+
+    pub async fn latest_block(&self) -> Result<GetLatestBlockResponse, Status> {
+        self.unary(
+            TENDERMINT_SERVICE,
+            "GetLatestBlock",
+            GetLatestBlockRequest {},
+        )
+        .await
+    }
+
+    pub async fn block_by_height(&self, height: i64) -> Result<GetBlockByHeightResponse, Status> {
+        self.unary(
+            TENDERMINT_SERVICE,
+            "GetBlockByHeight",
+            GetBlockByHeightRequest { height },
+        )
+        .await
+    }
+
+    /// All transactions at `height` that are indexed by the node, in the order of the block,
+    /// fetching `per_page` transactions per page.
+    pub async fn txs_by_height(
+        &self,
+        height: i64,
+        per_page: u64,
+    ) -> Result<Vec<TxResponse>, Status> {
+        let query = format!("tx.height={height}");
+
+        let mut txs = Vec::new();
+
+        for page in 1.. {
+            // cosmos sdk < v0.50 only supports `events`, later versions only `query`
+            #[allow(deprecated)]
+            let request = GetTxsEventRequest {
+                events: vec![query.clone()],
+                query: query.clone(),
+                order_by: OrderBy::Asc.into(),
+                page,
+                limit: per_page,
+                ..Default::default()
+            };
+
+            let response: GetTxsEventResponse =
+                self.unary(TX_SERVICE, "GetTxsEvent", request).await?;
+
+            let received = response.tx_responses.len();
+
+            txs.extend(response.tx_responses);
+
+            if received == 0 || txs.len() as u64 >= response.total {
+                break;
+            }
+        }
+
+        Ok(txs)
+    }
+
+    async fn unary<Q, R>(
+        &self,
+        service: &'static str,
+        method: &'static str,
+        request: Q,
+    ) -> Result<R, Status>
+    where
+        Q: unionlabs::prost::Message + 'static,
+        R: unionlabs::prost::Message + Default + 'static,
+    {
+        let mut inner = self.inner.clone();
+
+        inner
+            .ready()
+            .await
+            .map_err(|e| Status::unknown(format!("service was not ready: {}", ErrorReporter(e))))?;
+
+        let mut request = request.into_request();
+
+        request
+            .extensions_mut()
+            .insert(GrpcMethod::new(service, method));
+
+        Ok(inner
+            .unary::<_, R, _>(
+                request,
+                format!("/{service}/{method}").parse().unwrap(),
+                ProstCodec::default(),
+            )
+            .await?
+            .into_inner())
+    }
+}
@@ -0,0 +1,52 @@
+use tracing::trace;
+
+use crate::indexer::{
+    api::IndexerError,
+    event::{
+        contract_lifecycle_event::ContractLifecycleEvent, supported::SupportedBlockEvent,
+        types::ContractLifecycleAction,
+    },
+    tendermint::{fetcher_client::TmFetcherClient, mapping::decoder::Decoder},
+};
+
+impl TmFetcherClient {
+    pub fn to_contract_lifecycle(
+        &self,
+        log: &Decoder,
+    ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
+        trace!("to_contract_lifecycle - {log}");
+
+        let (action, code_id, admin_address_canonical) = match log.event.name.as_str() {
+            "instantiate" => (
+                ContractLifecycleAction::Instantiate,
+                Some(log.event.code_id()?),
+                None,
+            ),
+            "migrate" => (
+                ContractLifecycleAction::Migrate,
+                Some(log.event.code_id()?),
+                None,
+            ),
+            // wasmd emits an empty admin when the admin is cleared
+            "update_contract_admin" => match log.event.new_admin_opt()? {
+                Some(admin) => (ContractLifecycleAction::UpdateAdmin, None, Some(admin)),
+                None => (ContractLifecycleAction::ClearAdmin, None, None),
+            },
+            unsupported => {
+                trace!("to_contract_lifecycle - {log} => ignore ({unsupported})");
+
+                return Ok(vec![]);
+            }
+        };
+
+        Ok(vec![SupportedBlockEvent::ContractLifecycle {
+            inner: ContractLifecycleEvent {
+                header: log.header()?,
+                contract_address_canonical: log.event.contract_address()?,
+                action,
+                code_id,
+                admin_address_canonical,
+            },
+        }])
+    }
+}
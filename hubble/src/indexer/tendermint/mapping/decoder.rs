@@ -11,15 +11,17 @@ use unionlabs::primitives::{encoding::HexUnprefixed, FixedBytes};
 use crate::{
     indexer::{
         api::IndexerError,
+        decoder_version::DecoderVersion,
         event::{
             header::Header,
             types::{
-                Acknowledgement, BlockHash, BlockHeight, CanonicalChainId, Capacity, ChannelId,
-                ChannelVersion, ClientId, ClientType, ConnectionId, ContractAddress, Denom, Maker,
-                MakerMsg, MutationAmount, PacketData, PacketHash, PortId, RefillRate,
-                TimeoutTimestamp, TransactionHash, WalletAddress,
+                Acknowledgement, AdminAddress, BlockHash, BlockHeight, CanonicalChainId, Capacity,
+                ChannelId, ChannelVersion, ClientId, ClientType, CodeId, ConnectionId,
+                ContractAddress, Denom, Maker, MakerMsg, MutationAmount, PacketData, PacketHash,
+                PortId, ProposalId, RefillRate, TimeoutTimestamp, TransactionHash, WalletAddress,
             },
         },
+        normalization::normalize_cosmos,
         tendermint::block_handle::BlockHeader,
     },
     postgres::ChainId,
@@ -59,6 +61,9 @@ pub struct Decoder<'a> {
     pub transaction: &'a TxResponse,
     pub event: &'a TmEvent,
     pub event_index: usize,
+    /// The version of the format of the event, registered for the contract that emitted it (v1
+    /// for events emitted by the chain itself).
+    pub decoder_version: DecoderVersion,
 }
 
 impl<'a> Display for Decoder<'a> {
@@ -100,6 +105,7 @@ impl<'a> Decoder<'a> {
             transaction_hash: self.transaction.hash.into(),
             transaction_index: self.transaction.index.into(),
             transaction_event_index: None,
+            decoder_version: self.decoder_version,
         })
     }
 }
@@ -238,6 +244,48 @@ impl TmEvent {
         self.get_wallet_address_opt("to")
     }
 
+    pub fn code_id(&self) -> Result<CodeId, IndexerError> {
+        self.get_code_id("code_id")
+    }
+
+    /// empty when the admin is cleared
+    pub fn new_admin_opt(&self) -> Result<Option<AdminAddress>, IndexerError> {
+        self.get_admin_address_opt("new_admin_address")
+    }
+
+    /// The id of the proposal, absent on the `submit_proposal` events that sdk 0.45 emits
+    /// separately for the proposal type and the start of the voting period.
+    pub fn proposal_id_opt(&self) -> Result<Option<ProposalId>, IndexerError> {
+        self.get_value_opt("proposal_id", "proposal-id")?
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .map(|proposal_id| proposal_id.to_string().into())
+                    .map_err(|_| self.report_unexpected_type("proposal_id", &value, "proposal-id"))
+            })
+            .transpose()
+    }
+
+    /// type urls of the proposal messages (gov v1), or the proposal type of legacy (gov v1beta1)
+    /// proposals. `None` if the event carries neither (sdk 0.45 emits the proposal type in a
+    /// separate event).
+    pub fn proposal_message_types(&self) -> Result<Option<Vec<String>>, IndexerError> {
+        // gov v1 joins the type urls with a leading comma: ',/ibc.core.client.v1.MsgRecoverClient'
+        if let Some(messages) = self.get_value_opt("proposal_messages", "proposal-messages")? {
+            return Ok(Some(
+                messages
+                    .split(',')
+                    .filter(|message| !message.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ));
+        }
+
+        Ok(self
+            .get_value_opt("proposal_type", "proposal-type")?
+            .map(|proposal_type| vec![proposal_type]))
+    }
+
     fn get_height(&self, key: &str) -> Result<BlockHeight, IndexerError> {
         Ok(self.get_u64(key, "height")?.into())
     }
@@ -330,6 +378,21 @@ impl TmEvent {
             .map(|x| x.into()))
     }
 
+    fn get_code_id(&self, key: &str) -> Result<CodeId, IndexerError> {
+        Ok(self.get_u64(key, "code-id")?.into())
+    }
+
+    fn get_admin_address_opt(&self, key: &str) -> Result<Option<AdminAddress>, IndexerError> {
+        const EXPECTING: &str = "admin-address";
+
+        Ok(match self.get_value_opt(key, EXPECTING)? {
+            Some(bech32_encoded) if !bech32_encoded.is_empty() => {
+                Some(self.decode_bech32(key, EXPECTING, &bech32_encoded)?.into())
+            }
+            _ => None,
+        })
+    }
+
     fn get_u32(&self, key: &str, expecting: &str) -> Result<u32, IndexerError> {
         self.get_value(key, expecting).and_then(|value| {
             value
@@ -376,8 +439,8 @@ impl TmEvent {
         expecting: &str,
         bech32_encoded: &str,
     ) -> Result<bytes::Bytes, IndexerError> {
-        bech32::decode(bech32_encoded)
-            .map(|(_, data)| Bytes::from(data))
+        normalize_cosmos(bech32_encoded)
+            .map(|normalized| normalized.canonical.0)
             .map_err(|_| self.report_unexpected_type(key, bech32_encoded, expecting))
     }
 
@@ -0,0 +1,185 @@
+use tracing::trace;
+
+use crate::indexer::{
+    api::IndexerError,
+    event::{
+        governance_proposal_event::GovernanceProposalEvent,
+        supported::SupportedBlockEvent,
+        types::{ProposalAction, ProposalId},
+    },
+    tendermint::{
+        fetcher_client::TmFetcherClient,
+        mapping::{
+            decoder::{Decoder, TmEvent},
+            GOVERNANCE_PROPOSAL_EVENT_TYPE,
+        },
+    },
+};
+
+/// gov v1 message types that change ibc parameters, light clients, contracts or the chain itself
+const BRIDGE_MESSAGE_TYPE_PREFIXES: [&str; 5] = [
+    "/ibc.",
+    "/cosmos.upgrade.",
+    "/cosmwasm.wasm.v1.MsgMigrateContract",
+    "/cosmwasm.wasm.v1.MsgUpdateAdmin",
+    "/cosmwasm.wasm.v1.MsgClearAdmin",
+];
+
+/// gov v1beta1 proposal types with the same effect
+const BRIDGE_LEGACY_PROPOSAL_TYPES: [&str; 7] = [
+    "ClientUpdate",
+    "IBCUpgrade",
+    "SoftwareUpgrade",
+    "CancelSoftwareUpgrade",
+    "MigrateContract",
+    "UpdateAdmin",
+    "ClearAdmin",
+];
+
+impl TmFetcherClient {
+    pub fn to_governance_proposal(
+        &self,
+        log: &Decoder,
+    ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
+        trace!("to_governance_proposal - {log}");
+
+        let Some(proposal_id) = log.event.proposal_id_opt()? else {
+            trace!("to_governance_proposal - {log} => ignore (no proposal id)");
+
+            return Ok(vec![]);
+        };
+
+        let message_types = match log.event.proposal_message_types()? {
+            Some(message_types) => message_types,
+            None => {
+                let events = log
+                    .transaction
+                    .tx_result
+                    .events
+                    .iter()
+                    .filter(|event| event.ty == GOVERNANCE_PROPOSAL_EVENT_TYPE)
+                    .map(TmEvent::from)
+                    .collect::<Vec<_>>();
+
+                match split_proposal_message_types(&events, &proposal_id)? {
+                    Some(message_types) => message_types,
+                    None => {
+                        trace!("to_governance_proposal - {log} => ignore (no proposal type)");
+
+                        return Ok(vec![]);
+                    }
+                }
+            }
+        };
+
+        if !message_types
+            .iter()
+            .any(|message_type| is_bridge_message_type(message_type))
+        {
+            trace!("to_governance_proposal - {log} => ignore (not bridge related)");
+
+            return Ok(vec![]);
+        }
+
+        Ok(vec![SupportedBlockEvent::GovernanceProposal {
+            inner: GovernanceProposalEvent {
+                header: log.header()?,
+                proposal_id,
+                action: ProposalAction::Submitted,
+                message_types,
+                target_contract_canonical: None,
+                delay: None,
+            },
+        }])
+    }
+}
+
+/// sdk 0.45 emits the type of a proposal in a `submit_proposal` event without the proposal id,
+/// after the event with the id. Pairs the events in the order they are emitted in the transaction.
+fn split_proposal_message_types(
+    events: &[TmEvent],
+    proposal_id: &ProposalId,
+) -> Result<Option<Vec<String>>, IndexerError> {
+    let mut proposal_ids = vec![];
+    let mut message_types = vec![];
+
+    for event in events {
+        match (event.proposal_id_opt()?, event.proposal_message_types()?) {
+            (Some(proposal_id), _) => proposal_ids.push(proposal_id),
+            (None, Some(types)) => message_types.push(types),
+            // voting_period_start
+            (None, None) => {}
+        }
+    }
+
+    Ok(proposal_ids
+        .iter()
+        .position(|id| id == proposal_id)
+        .and_then(|position| message_types.into_iter().nth(position)))
+}
+
+fn is_bridge_message_type(message_type: &str) -> bool {
+    BRIDGE_MESSAGE_TYPE_PREFIXES
+        .iter()
+        .any(|prefix| message_type.starts_with(prefix))
+        || BRIDGE_LEGACY_PROPOSAL_TYPES.contains(&message_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn event(attributes: &[(&str, &str)]) -> TmEvent {
+        TmEvent {
+            name: GOVERNANCE_PROPOSAL_EVENT_TYPE.to_string(),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| (key.to_string(), vec![value.to_string()]))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_split_proposal_message_types() {
+        let events = [
+            event(&[("proposal_id", "1")]),
+            event(&[("proposal_type", "Text")]),
+            event(&[("voting_period_start", "1")]),
+            event(&[("proposal_id", "2")]),
+            event(&[("proposal_type", "ClientUpdate")]),
+        ];
+
+        assert_eq!(
+            split_proposal_message_types(&events, &"2".to_string().into()).unwrap(),
+            Some(vec!["ClientUpdate".to_string()])
+        );
+        assert_eq!(
+            split_proposal_message_types(&events, &"1".to_string().into()).unwrap(),
+            Some(vec!["Text".to_string()])
+        );
+        assert_eq!(
+            split_proposal_message_types(&events, &"3".to_string().into()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bridge_message_types() {
+        assert!(is_bridge_message_type(
+            "/ibc.core.client.v1.MsgRecoverClient"
+        ));
+        assert!(is_bridge_message_type(
+            "/cosmos.upgrade.v1beta1.MsgSoftwareUpgrade"
+        ));
+        assert!(is_bridge_message_type(
+            "/cosmwasm.wasm.v1.MsgMigrateContract"
+        ));
+        assert!(is_bridge_message_type("ClientUpdate"));
+
+        assert!(!is_bridge_message_type("/cosmos.bank.v1beta1.MsgSend"));
+        assert!(!is_bridge_message_type("/cosmwasm.wasm.v1.MsgStoreCode"));
+        assert!(!is_bridge_message_type("Text"));
+    }
+}
@@ -3,6 +3,7 @@ use tracing::{trace, warn};
 
 use crate::indexer::{
     api::{BlockReference, IndexerError},
+    decoder_version::DecoderVersion,
     event::supported::SupportedBlockEvent,
     tendermint::{
         block_handle::{ActiveContracts, BlockHeader},
@@ -19,9 +20,11 @@ mod connection_open_ack_mapping;
 mod connection_open_confirm_mapping;
 mod connection_open_init_mapping;
 mod connection_open_try_mapping;
+mod contract_lifecycle_mapping;
 mod create_client_mapping;
 mod create_lens_client_mapping;
 mod decoder;
+mod governance_proposal_mapping;
 pub(crate) mod legacy;
 mod packet_ack_mapping;
 mod packet_recv_mapping;
@@ -98,6 +101,32 @@ impl TmFetcherClient {
         event: &Event,
         event_index: usize,
     ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
+        // gov proposals are emitted by the chain, not by a registered contract
+        if event.ty == GOVERNANCE_PROPOSAL_EVENT_TYPE {
+            let event = &event.into();
+
+            return self.to_governance_proposal(&Decoder {
+                chain_id: self.chain_id,
+                block_header,
+                transaction,
+                event,
+                event_index,
+                decoder_version: DecoderVersion::V1,
+            });
+        }
+
+        if let Some(contract_address) = lifecycle_contract_address(block_reference, event) {
+            return self.transform_contract_lifecycle_event_to_ucs_events(
+                block_reference,
+                active_contracts,
+                block_header,
+                transaction,
+                event,
+                event_index,
+                &contract_address,
+            );
+        }
+
         let Some(wasm_contract_address) = wasm_contract_address(block_reference, event) else {
             trace!(
                 "{block_reference}, {}-{} has no wasm contract address",
@@ -120,6 +149,7 @@ impl TmFetcherClient {
             transaction,
             event,
             event_index,
+            decoder_version: active_contracts.decoder_version(&wasm_contract_address),
         };
 
         flows.iter().map(|flow|match flow.as_str() {
@@ -134,12 +164,57 @@ impl TmFetcherClient {
             .map(|vecs| vecs.into_iter().flatten().collect())
     }
 
+    // lifecycle events are indexed once per registered contract, regardless of its flows
+    #[allow(clippy::too_many_arguments)]
+    fn transform_contract_lifecycle_event_to_ucs_events(
+        &self,
+        block_reference: &BlockReference,
+        active_contracts: &ActiveContracts,
+        block_header: &BlockHeader,
+        transaction: &TxResponse,
+        event: &Event,
+        event_index: usize,
+        contract_address: &str,
+    ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
+        if active_contracts.flows(contract_address).is_none() {
+            trace!(
+                "{block_reference}, {}-{} has contract address {contract_address} is not registered",
+                transaction.hash,
+                event.ty
+            );
+            return Ok(vec![]);
+        }
+
+        let event = &event.into();
+
+        self.to_contract_lifecycle(&Decoder {
+            chain_id: self.chain_id,
+            block_header,
+            transaction,
+            event,
+            event_index,
+            decoder_version: active_contracts.decoder_version(contract_address),
+        })
+    }
+
     fn transform_ibc_event_to_ucs_events(
         &self,
         event_decoder: &Decoder<'_>,
     ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
-        trace!("to_ibc_event - {event_decoder}");
+        trace!(
+            "to_ibc_event - {event_decoder} ({})",
+            event_decoder.decoder_version
+        );
+
+        match event_decoder.decoder_version {
+            DecoderVersion::V1 => self.transform_ibc_event_to_ucs_events_v1(event_decoder),
+        }
+    }
 
+    fn transform_ibc_event_to_ucs_events_v1(
+        &self,
+        event_decoder: &Decoder<'_>,
+    ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
         Ok(match event_decoder.event.name.as_str() {
             "wasm-channel_open_init" => self.to_channel_open_init(event_decoder)?,
             "wasm-channel_open_try" => self.to_channel_open_try(event_decoder)?,
@@ -169,8 +244,20 @@ impl TmFetcherClient {
         &self,
         event_decoder: &Decoder<'_>,
     ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
-        trace!("to_cw20_event - {event_decoder}");
+        trace!(
+            "to_cw20_event - {event_decoder} ({})",
+            event_decoder.decoder_version
+        );
+
+        match event_decoder.decoder_version {
+            DecoderVersion::V1 => self.transform_cw20_event_to_ucs_events_v1(event_decoder),
+        }
+    }
 
+    fn transform_cw20_event_to_ucs_events_v1(
+        &self,
+        event_decoder: &Decoder<'_>,
+    ) -> Result<Vec<SupportedBlockEvent>, IndexerError> {
         #[allow(clippy::match_single_binding)] // remove after adding support
         Ok(match event_decoder.event.name.as_str() {
             "wasm" => self.to_wallet_mutation_entry(event_decoder)?,
@@ -182,6 +269,36 @@ impl TmFetcherClient {
     }
 }
 
+const GOVERNANCE_PROPOSAL_EVENT_TYPE: &str = "submit_proposal";
+
+// extracting the _contract_address of contract lifecycle events emitted by wasmd (instantiate,
+// migrate and admin updates). these are not prefixed with 'wasm-'.
+fn lifecycle_contract_address(reference: &BlockReference, event: &Event) -> Option<String> {
+    const LIFECYCLE_EVENT_TYPES: [&str; 3] = ["instantiate", "migrate", "update_contract_admin"];
+
+    // attribute we're looking for
+    const ATTRIBUTE_KEY_FOR_CONTRACT_ADDRESS: &str = "_contract_address";
+
+    if !LIFECYCLE_EVENT_TYPES.contains(&event.ty.as_str()) {
+        return None;
+    }
+
+    let result = event
+        .attributes
+        .iter()
+        .find(|attribute| attribute.key == ATTRIBUTE_KEY_FOR_CONTRACT_ADDRESS)
+        .map(|attribute| attribute.value.clone());
+
+    if result.is_none() {
+        trace!(
+            "{reference}: unexpected: there is no contract address in a {} event => do not include",
+            event.ty
+        );
+    }
+
+    result
+}
+
 // extracting the _contract_address of the event (if it exists in active_contracts).
 // evaluating a json like below. we only include if:
 // - event type starts with 'wasm-'
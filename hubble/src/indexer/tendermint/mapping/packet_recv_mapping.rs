@@ -17,6 +17,7 @@ impl TmFetcherClient {
                 packet_hash: log.event.packet_hash()?,
                 maker: log.event.maker()?,
                 maker_msg: log.event.maker_msg()?,
+                placement: None,
             },
         }])
     }
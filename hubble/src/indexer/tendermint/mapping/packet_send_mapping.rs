@@ -20,6 +20,8 @@ impl TmFetcherClient {
                 timeout_height: log.event.timeout_height()?,
                 timeout_timestamp: log.event.timeout_timestamp()?,
                 data: log.event.data()?,
+                transaction_sender: None,
+                initiator: None,
             },
         }])
     }
@@ -7,9 +7,11 @@ mod block_handle;
 pub mod config;
 mod context;
 mod fetcher_client;
+mod grpc_client;
 mod mapping;
 mod postgres;
 mod provider;
+mod sampling;
 
 impl From<JsonRpcError> for IndexerError {
     fn from(error: JsonRpcError) -> Self {
@@ -1,6 +1,12 @@
+use itertools::Itertools;
 use sqlx::{Postgres, Transaction};
 
-use crate::indexer::{api::BlockHeight, tendermint::block_handle::ActiveContracts};
+use crate::indexer::{
+    api::{BlockHeight, IndexerError},
+    contracts::{overlaps_stored_registration, replaced_start_heights, ContractRegistration},
+    decoder_version::DecoderVersion,
+    tendermint::{block_handle::ActiveContracts, sampling::EventSampling},
+};
 
 pub async fn active_contracts(
     tx: &mut Transaction<'_, Postgres>,
@@ -13,7 +19,7 @@ pub async fn active_contracts(
 
     sqlx::query!(
         r#"
-        SELECT    address, flow
+        SELECT    address, flow, decoder_version
         FROM      v2_cosmos.contracts
         WHERE     internal_chain_id = $1
         AND       $2 between start_height and end_height
@@ -24,9 +30,133 @@ pub async fn active_contracts(
     .fetch_all(tx.as_mut())
     .await?
     .into_iter()
-    .for_each(|record| {
-        result.register(record.address, record.flow);
-    });
+    .try_for_each(|record| {
+        let decoder_version = DecoderVersion::try_from(record.decoder_version)
+            .map_err(|err| sqlx::Error::Decode(Box::new(err)))?;
+
+        result.register(record.address, record.flow, decoder_version);
+
+        Ok::<_, sqlx::Error>(())
+    })?;
 
     Ok(result)
 }
+
+/// Record the event sampling of a chain, so that aggregates over its events can be flagged as
+/// sampled.
+pub async fn update_event_sampling(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    event_sampling: Option<&EventSampling>,
+) -> sqlx::Result<()> {
+    match event_sampling {
+        Some(event_sampling) => {
+            let rate: i64 = event_sampling.rate.get().try_into().unwrap();
+            let full_flows = event_sampling.full_flows.iter().cloned().collect_vec();
+
+            sqlx::query!(
+                r#"
+                INSERT INTO hubble.event_sampling (internal_chain_id, rate, full_flows)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (internal_chain_id) DO UPDATE SET
+                    rate = excluded.rate,
+                    full_flows = excluded.full_flows
+                "#,
+                internal_chain_id,
+                rate,
+                &full_flows,
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+        None => {
+            sqlx::query!(
+                r#"
+                DELETE FROM hubble.event_sampling
+                WHERE internal_chain_id = $1
+                "#,
+                internal_chain_id,
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Register the contracts of the config of the indexer. A registration replaces the range, flow and
+/// decoder version of the registration of the same address and start height, other registrations
+/// are left as is (and must not overlap the registrations of the config).
+pub async fn register_contracts(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    contracts: &[ContractRegistration],
+) -> Result<(), IndexerError> {
+    for contract in contracts {
+        let stored = sqlx::query!(
+            r#"
+            SELECT    start_height, end_height
+            FROM      v2_cosmos.contracts
+            WHERE     internal_chain_id = $1
+            AND       address = $2
+            AND       NOT (start_height = ANY($3))
+            AND       start_height <= $5
+            AND       end_height >= $4
+            ORDER BY  start_height
+            LIMIT     1
+            "#,
+            internal_chain_id,
+            contract.address,
+            &replaced_start_heights(contracts, &contract.address),
+            i64::try_from(contract.from_height).unwrap(),
+            i64::try_from(contract.end_height()).unwrap(),
+        )
+        .fetch_optional(tx.as_mut())
+        .await?;
+
+        if let Some(stored) = stored {
+            return Err(overlaps_stored_registration(
+                contract,
+                stored.start_height,
+                stored.end_height,
+            )
+            .into());
+        }
+    }
+
+    for contract in contracts {
+        let start_height: i64 = contract.from_height.try_into().unwrap();
+        let end_height: i64 = contract.end_height().try_into().unwrap();
+        let decoder_version: i32 = contract.decoder_version.into();
+
+        sqlx::query!(
+            r#"
+            WITH updated AS (
+                UPDATE v2_cosmos.contracts
+                SET
+                    end_height = $4,
+                    flow = $5,
+                    decoder_version = $6
+                WHERE internal_chain_id = $1
+                AND address = $2
+                AND start_height = $3
+                RETURNING 1
+            )
+            INSERT INTO v2_cosmos.contracts (internal_chain_id, address, start_height, end_height, flow, decoder_version)
+            SELECT $1, $2, $3, $4, $5, $6
+            WHERE NOT EXISTS (SELECT 1 FROM updated)
+            "#,
+            internal_chain_id,
+            contract.address,
+            start_height,
+            end_height,
+            contract.contract_type,
+            decoder_version,
+        )
+        .execute(tx.as_mut())
+        .await?;
+    }
+
+    Ok(())
+}
@@ -1,28 +1,68 @@
 use std::{
-    num::{NonZeroU32, NonZeroU64, NonZeroU8},
+    num::{NonZeroU64, NonZeroU8},
     result::Result,
 };
 
 use color_eyre::eyre::Report;
 use cometbft_rpc::{
     rpc_types::{
-        BlockResponse, BlockResultsResponse, BlockchainResponse, Order, StatusResponse,
-        TxSearchResponse,
+        BlockResponse, BlockResultsResponse, BlockchainResponse, GrpcAbciQueryResponse, Order,
+        StatusResponse, TxResponse,
     },
-    Client, JsonRpcError,
+    Client, ClientOptions, JsonRpcError,
 };
 use futures::future;
+use protos::cosmos::base::{
+    abci::v1beta1::TxResponse as GrpcTxResponse,
+    tendermint::v1beta1::{GetBlockByHeightResponse, GetLatestBlockResponse, GetNodeInfoResponse},
+};
 use unionlabs::aptos::block_info::BlockHeight;
 use url::Url;
 
 use crate::{
-    indexer::api::IndexerError,
-    race_client::{RaceClient, RaceClientId, RaceClientResponse},
+    indexer::{api::IndexerError, tendermint::grpc_client::GrpcClient},
+    race_client::{NoClients, RaceClient, RaceClientId, RaceClientResponse},
 };
 
 #[derive(Clone, Debug)]
 pub struct Provider {
     pub rpc_client: RaceClient<Client>,
+    pub grpc_client: RaceClient<GrpcClient>,
+}
+
+/// The node that a block was fetched from, so that its details are fetched from the same node.
+#[derive(Clone, Debug, Copy)]
+pub enum ProviderId {
+    Rpc(RpcProviderId),
+    Grpc(GrpcProviderId),
+}
+
+impl ProviderId {
+    pub fn rpc(self) -> Option<RpcProviderId> {
+        match self {
+            ProviderId::Rpc(provider_id) => Some(provider_id),
+            ProviderId::Grpc(_) => None,
+        }
+    }
+
+    pub fn grpc(self) -> Option<GrpcProviderId> {
+        match self {
+            ProviderId::Rpc(_) => None,
+            ProviderId::Grpc(provider_id) => Some(provider_id),
+        }
+    }
+}
+
+impl From<NoClients> for JsonRpcError {
+    fn from(no_clients: NoClients) -> Self {
+        JsonRpcError::Custom(no_clients.to_string())
+    }
+}
+
+impl From<NoClients> for tonic::Status {
+    fn from(no_clients: NoClients) -> Self {
+        tonic::Status::failed_precondition(no_clients.to_string())
+    }
 }
 
 #[derive(Clone, Debug, Copy)]
@@ -68,21 +108,46 @@ impl<T> From<RaceClientResponse<T>> for RpcResult<T> {
     }
 }
 
+#[derive(Debug)]
+pub struct GrpcResult<T> {
+    pub provider_id: GrpcProviderId,
+    pub response: T,
+}
+
+impl<T> From<RaceClientResponse<T>> for GrpcResult<T> {
+    fn from(value: RaceClientResponse<T>) -> Self {
+        Self {
+            provider_id: GrpcProviderId {
+                race_client_id: value.race_client_id,
+            },
+            response: value.response,
+        }
+    }
+}
+
 impl Provider {
-    pub async fn new(rpc_urls: Vec<Url>) -> Result<Self, IndexerError> {
+    pub async fn new(
+        rpc_urls: Vec<Url>,
+        grpc_urls: Vec<Url>,
+        options: ClientOptions,
+    ) -> Result<Self, IndexerError> {
         Ok(Self {
             rpc_client: {
                 RaceClient::new(
-                    future::join_all(
-                        rpc_urls
-                            .into_iter()
-                            .map(|rpc_url| Client::new(rpc_url.as_str().to_owned())),
-                    )
+                    future::join_all(rpc_urls.into_iter().map(|rpc_url| {
+                        Client::new_with_options(rpc_url.as_str().to_owned(), options.clone())
+                    }))
                     .await
                     .into_iter()
                     .collect::<Result<Vec<_>, _>>()?,
                 )
             },
+            grpc_client: RaceClient::new(
+                grpc_urls
+                    .iter()
+                    .map(GrpcClient::new)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
         })
     }
 
@@ -149,23 +214,90 @@ impl Provider {
             .map(Into::into)
     }
 
-    pub async fn tx_search(
+    /// All transactions at `height`, fetching `per_page` transactions per page.
+    pub async fn tx_search_all(
         &self,
         height: BlockHeight,
-        prove: bool,
-        page: u32,
         per_page: u8,
-        order: Order,
         provider_id: Option<RpcProviderId>,
-    ) -> Result<RpcResult<TxSearchResponse>, JsonRpcError> {
+    ) -> Result<RpcResult<Vec<TxResponse>>, JsonRpcError> {
         self.rpc_client
             .race(provider_id.map(Into::into), |c| {
-                c.tx_search(
+                c.tx_search_all(
                     format!("tx.height={}", height),
-                    prove,
-                    NonZeroU32::try_from(page).expect("non-zero page"),
+                    false,
                     NonZeroU8::try_from(per_page).expect("non-zero per-page"),
-                    order.clone(),
+                    Order::Asc,
+                    None,
+                )
+            })
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn grpc_abci_query<
+        'a,
+        Q: unionlabs::prost::Message,
+        R: unionlabs::prost::Message + Default,
+    >(
+        &'a self,
+        path: &'a str,
+        data: &'a Q,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<GrpcAbciQueryResponse<R>>, JsonRpcError> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.grpc_abci_query(path, data, None, false)
+            })
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn grpc_node_info(
+        &self,
+        provider_id: Option<GrpcProviderId>,
+    ) -> Result<GrpcResult<GetNodeInfoResponse>, tonic::Status> {
+        self.grpc_client
+            .race(provider_id.map(Into::into), |c| c.node_info())
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn grpc_latest_block(
+        &self,
+        provider_id: Option<GrpcProviderId>,
+    ) -> Result<GrpcResult<GetLatestBlockResponse>, tonic::Status> {
+        self.grpc_client
+            .race(provider_id.map(Into::into), |c| c.latest_block())
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn grpc_block(
+        &self,
+        height: BlockHeight,
+        provider_id: Option<GrpcProviderId>,
+    ) -> Result<GrpcResult<GetBlockByHeightResponse>, tonic::Status> {
+        self.grpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.block_by_height(height.try_into().expect("height fits in i64"))
+            })
+            .await
+            .map(Into::into)
+    }
+
+    /// All transactions at `height`, fetching `per_page` transactions per page.
+    pub async fn grpc_txs(
+        &self,
+        height: BlockHeight,
+        per_page: u8,
+        provider_id: Option<GrpcProviderId>,
+    ) -> Result<GrpcResult<Vec<GrpcTxResponse>>, tonic::Status> {
+        self.grpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.txs_by_height(
+                    height.try_into().expect("height fits in i64"),
+                    per_page.into(),
                 )
             })
             .await
@@ -0,0 +1,69 @@
+use std::{collections::HashSet, num::NonZeroU64};
+
+use crate::indexer::api::BlockHeight;
+
+/// Index the generic events of only one in `rate` blocks, to bound the storage of very busy
+/// chains. Events of contracts in `full_flows` are always indexed, as are the records mapped from
+/// them (packets, transfers, ...).
+///
+/// Blocks are selected by height, so re-indexing a block (i.e. by the fixer) selects the same
+/// events again.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventSampling {
+    /// Generic events are indexed for blocks with a height that is a multiple of `rate`.
+    pub rate: NonZeroU64,
+    /// Flows of the contracts whose events are always indexed.
+    #[serde(default = "default_full_flows")]
+    pub full_flows: HashSet<String>,
+}
+
+fn default_full_flows() -> HashSet<String> {
+    ["ibc".to_string(), "cw20".to_string()].into()
+}
+
+impl EventSampling {
+    /// Whether an event at `height` of a contract registered for `flows` is indexed.
+    pub fn includes(&self, height: BlockHeight, flows: &HashSet<String>) -> bool {
+        height % self.rate.get() == 0 || !self.full_flows.is_disjoint(flows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flows(flows: &[&str]) -> HashSet<String> {
+        flows.iter().map(|flow| flow.to_string()).collect()
+    }
+
+    #[test]
+    fn samples_generic_events() {
+        let sampling = serde_json::from_str::<EventSampling>(r#"{ "rate": 10 }"#).unwrap();
+
+        assert!(sampling.includes(20, &flows(&["generic"])));
+        assert!(!sampling.includes(21, &flows(&["generic"])));
+    }
+
+    #[test]
+    fn always_includes_full_flows() {
+        let sampling = serde_json::from_str::<EventSampling>(r#"{ "rate": 10 }"#).unwrap();
+
+        assert!(sampling.includes(21, &flows(&["ibc"])));
+        assert!(sampling.includes(21, &flows(&["cw20"])));
+        assert!(sampling.includes(21, &flows(&["generic", "ibc"])));
+
+        let sampling = serde_json::from_str::<EventSampling>(
+            r#"{ "rate": 10, "full_flows": ["ibc", "gov"] }"#,
+        )
+        .unwrap();
+
+        assert!(sampling.includes(21, &flows(&["gov"])));
+        assert!(!sampling.includes(21, &flows(&["cw20"])));
+    }
+
+    #[test]
+    fn rejects_zero_rate() {
+        assert!(serde_json::from_str::<EventSampling>(r#"{ "rate": 0 }"#).is_err());
+    }
+}
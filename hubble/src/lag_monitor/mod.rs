@@ -0,0 +1,97 @@
+use std::{fmt::Display, str::FromStr};
+
+use url::Url;
+
+use crate::indexer::api::IndexerId;
+
+mod monitor;
+mod postgres;
+
+/// Window that lets an alert resolve soon after the lag recovers.
+const SHORT_WINDOW_SECONDS: i32 = 5 * 60;
+/// Window that keeps short lag spikes from firing an alert. Samples older than this are pruned.
+const LONG_WINDOW_SECONDS: i32 = 60 * 60;
+
+/// Ingestion lag slo of the indexers, passed as json (e.g.
+/// `{"target_lag_seconds": 60, "objective": 0.99, "webhook": "https://..."}`).
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LagSloConfig {
+    /// Lag (age of the last indexed block) up to which an indexer is considered in sync.
+    pub target_lag_seconds: f64,
+    /// Fraction of the time in which indexers should be in sync.
+    #[serde(default = "default_objective")]
+    pub objective: f64,
+    /// Burn rate of the error budget above which an alert fires, in both windows.
+    #[serde(default = "default_burn_rate_threshold")]
+    pub burn_rate_threshold: f64,
+    /// Receives a json `POST` when an alert fires and when it resolves. Alerts are only recorded
+    /// when not configured.
+    #[serde(default)]
+    pub webhook: Option<Url>,
+}
+
+fn default_objective() -> f64 {
+    0.99
+}
+
+/// Spends 2% of a 30 day error budget within the long window.
+fn default_burn_rate_threshold() -> f64 {
+    14.4
+}
+
+impl FromStr for LagSloConfig {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(item: &str) -> Result<Self, <Self as FromStr>::Err> {
+        serde_json::from_str(item).map_err(Into::into)
+    }
+}
+
+/// Age of the last block of an indexer, as recorded in `hubble.indexer_status`.
+#[derive(Clone, Debug)]
+pub struct IndexerLag {
+    indexer_id: IndexerId,
+    lag_seconds: f64,
+}
+
+impl Display for IndexerLag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}: {}s", self.indexer_id, self.lag_seconds))
+    }
+}
+
+/// Rate at which an indexer spends its error budget in each window. At a burn rate of 1, the
+/// budget lasts exactly as long as the slo period.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BurnRates {
+    short: f64,
+    long: f64,
+}
+
+impl Display for BurnRates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{:.2}x ({}s), {:.2}x ({}s)",
+            self.short, SHORT_WINDOW_SECONDS, self.long, LONG_WINDOW_SECONDS,
+        ))
+    }
+}
+
+/// Alert of an indexer that did not resolve yet, as recorded in `hubble.lag_alerts`.
+#[derive(Clone, Debug)]
+pub struct OpenLagAlert {
+    id: i64,
+    /// Highest burn rates observed while the alert is open.
+    burn_rates: BurnRates,
+}
+
+impl Display for OpenLagAlert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}: {}", self.id, self.burn_rates))
+    }
+}
+
+pub async fn monitor_lag(db: &sqlx::PgPool, slo: &LagSloConfig) -> color_eyre::Result<()> {
+    crate::lag_monitor::monitor::monitor_lag(db, slo).await
+}
@@ -0,0 +1,209 @@
+use serde_json::{json, Value};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    lag_monitor::{
+        postgres::{
+            delete_lag_samples_before, get_bad_sample_fractions, get_indexer_lags,
+            get_open_lag_alert, insert_lag_alert, insert_lag_sample, update_lag_alert,
+        },
+        BurnRates, IndexerLag, LagSloConfig, OpenLagAlert, LONG_WINDOW_SECONDS,
+        SHORT_WINDOW_SECONDS,
+    },
+    metrics::INGESTION_LAG_COLLECTOR,
+    webhook::enqueue_delivery,
+};
+
+/// Samples the lag of every indexer into `hubble.ingestion_lag_samples`, and compares the rate
+/// at which it spends its error budget over a short and a long window with the threshold. An
+/// alert in `hubble.lag_alerts` fires when both windows burn faster than the threshold, and
+/// resolves once either of them drops below it. The long window keeps short spikes from firing,
+/// the short window lets the alert resolve soon after the indexer catches up.
+pub async fn monitor_lag(db: &sqlx::PgPool, slo: &LagSloConfig) -> color_eyre::Result<()> {
+    let lags = get_indexer_lags(&mut db.begin().await?).await?;
+
+    for lag in lags {
+        debug!("monitor: {lag}");
+
+        INGESTION_LAG_COLLECTOR
+            .with_label_values(&[&lag.indexer_id])
+            .set(lag.lag_seconds);
+
+        match monitor_indexer_lag(db, slo, &lag).await {
+            Ok(_) => debug!("monitor: {lag} => success"),
+            Err(error) => error!("monitor: {lag} => error: {error:?}"),
+        }
+    }
+
+    let mut tx = db.begin().await?;
+    let deleted = delete_lag_samples_before(&mut tx, LONG_WINDOW_SECONDS).await?;
+    tx.commit().await?;
+
+    debug!("monitor: deleted {deleted} expired samples");
+
+    Ok(())
+}
+
+async fn monitor_indexer_lag(
+    db: &sqlx::PgPool,
+    slo: &LagSloConfig,
+    lag: &IndexerLag,
+) -> color_eyre::Result<()> {
+    let mut tx = db.begin().await?;
+
+    insert_lag_sample(&mut tx, lag).await?;
+
+    let (short_fraction, long_fraction) = get_bad_sample_fractions(
+        &mut tx,
+        &lag.indexer_id,
+        slo.target_lag_seconds,
+        SHORT_WINDOW_SECONDS,
+        LONG_WINDOW_SECONDS,
+    )
+    .await?;
+    let burn_rates = burn_rates(
+        short_fraction.unwrap_or_default(),
+        long_fraction.unwrap_or_default(),
+        slo.objective,
+    );
+    let open_alert = get_open_lag_alert(&mut tx, &lag.indexer_id).await?;
+
+    let alert = match (open_alert, exceeds(burn_rates, slo.burn_rate_threshold)) {
+        (None, false) => None,
+        (None, true) => {
+            let id = insert_lag_alert(&mut tx, &lag.indexer_id, slo, burn_rates).await?;
+
+            warn!("alert: {lag} => {id}: burning {burn_rates}");
+
+            Some((id, "firing"))
+        }
+        (Some(open_alert), true) => {
+            let alert = OpenLagAlert {
+                id: open_alert.id,
+                burn_rates: peak(open_alert.burn_rates, burn_rates),
+            };
+
+            update_lag_alert(&mut tx, &alert, false).await?;
+
+            None
+        }
+        (Some(open_alert), false) => {
+            update_lag_alert(&mut tx, &open_alert, true).await?;
+
+            info!("alert: {lag} => resolved {open_alert}");
+
+            Some((open_alert.id, "resolved"))
+        }
+    };
+
+    if let (Some(webhook), Some((id, status))) = (&slo.webhook, alert) {
+        enqueue_delivery(
+            &mut tx,
+            webhook,
+            "ingestion_lag_alert",
+            &format!("ingestion_lag:{}:{id}:{status}", lag.indexer_id),
+            &alert_payload(slo, lag, id, status, burn_rates)?,
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Burn rates for the fraction of samples over the target lag in each window.
+fn burn_rates(short_fraction: f64, long_fraction: f64, objective: f64) -> BurnRates {
+    // an objective of 1 has no budget, so any lag over the target burns "infinitely" fast
+    let budget = (1.0 - objective).max(f64::EPSILON);
+
+    BurnRates {
+        short: short_fraction / budget,
+        long: long_fraction / budget,
+    }
+}
+
+fn exceeds(burn_rates: BurnRates, threshold: f64) -> bool {
+    burn_rates.short >= threshold && burn_rates.long >= threshold
+}
+
+fn peak(a: BurnRates, b: BurnRates) -> BurnRates {
+    BurnRates {
+        short: a.short.max(b.short),
+        long: a.long.max(b.long),
+    }
+}
+
+fn alert_payload(
+    slo: &LagSloConfig,
+    lag: &IndexerLag,
+    id: i64,
+    status: &str,
+    burn_rates: BurnRates,
+) -> color_eyre::Result<Value> {
+    Ok(json!({
+        "status": status,
+        "alert_id": id,
+        "indexer_id": lag.indexer_id,
+        "lag_seconds": lag.lag_seconds,
+        "target_lag_seconds": slo.target_lag_seconds,
+        "objective": slo.objective,
+        "burn_rate_threshold": slo.burn_rate_threshold,
+        "short_window_seconds": SHORT_WINDOW_SECONDS,
+        "short_burn_rate": burn_rates.short,
+        "long_window_seconds": LONG_WINDOW_SECONDS,
+        "long_burn_rate": burn_rates.long,
+        "timestamp": OffsetDateTime::now_utc().format(&Rfc3339)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burn_rate_is_relative_to_budget() {
+        let burn_rates = burn_rates(0.5, 0.1, 0.99);
+
+        assert!((burn_rates.short - 50.0).abs() < 1e-9);
+        assert!((burn_rates.long - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn burn_rate_without_budget() {
+        assert!(burn_rates(0.01, 0.01, 1.0).long > 1e9);
+        assert!(!exceeds(burn_rates(0.0, 0.0, 1.0), 14.4));
+    }
+
+    #[test]
+    fn fires_only_when_both_windows_exceed() {
+        let threshold = 14.4;
+
+        // short spike, the long window is still within budget
+        assert!(!exceeds(burn_rates(1.0, 0.1, 0.99), threshold));
+        // recovered, the long window still reflects the earlier lag
+        assert!(!exceeds(burn_rates(0.0, 0.5, 0.99), threshold));
+        assert!(exceeds(burn_rates(1.0, 0.2, 0.99), threshold));
+    }
+
+    #[test]
+    fn peak_is_per_window() {
+        assert_eq!(
+            peak(
+                BurnRates {
+                    short: 20.0,
+                    long: 15.0
+                },
+                BurnRates {
+                    short: 10.0,
+                    long: 30.0
+                },
+            ),
+            BurnRates {
+                short: 20.0,
+                long: 30.0
+            }
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use sqlx::Postgres;
+
+use crate::lag_monitor::{BurnRates, IndexerLag, LagSloConfig, OpenLagAlert};
+
+pub async fn get_indexer_lags(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> sqlx::Result<Vec<IndexerLag>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT indexer_id, EXTRACT(EPOCH FROM now() - timestamp)::FLOAT8 AS "lag_seconds!"
+        FROM hubble.indexer_status
+        ORDER BY indexer_id
+        "#,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| IndexerLag {
+        indexer_id: record.indexer_id,
+        lag_seconds: record.lag_seconds,
+    })
+    .collect())
+}
+
+pub async fn insert_lag_sample(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    lag: &IndexerLag,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO hubble.ingestion_lag_samples (indexer_id, sampled_at, lag_seconds)
+        VALUES ($1, now(), $2)
+        "#,
+        lag.indexer_id,
+        lag.lag_seconds,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_lag_samples_before(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    age_seconds: i32,
+) -> sqlx::Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM hubble.ingestion_lag_samples
+        WHERE sampled_at < now() - $1::INT4 * INTERVAL '1 second'
+        "#,
+        age_seconds,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Fraction of the samples of an indexer over the target lag, within the last `short_seconds`
+/// and `long_seconds`. `None` for a window without samples.
+pub async fn get_bad_sample_fractions(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: &str,
+    target_lag_seconds: f64,
+    short_seconds: i32,
+    long_seconds: i32,
+) -> sqlx::Result<(Option<f64>, Option<f64>)> {
+    let record = sqlx::query!(
+        r#"
+        SELECT
+            AVG((CASE WHEN lag_seconds > $2 THEN 1 ELSE 0 END)::FLOAT8)
+                FILTER (WHERE sampled_at >= now() - $3::INT4 * INTERVAL '1 second') AS short_fraction,
+            AVG((CASE WHEN lag_seconds > $2 THEN 1 ELSE 0 END)::FLOAT8) AS long_fraction
+        FROM hubble.ingestion_lag_samples
+        WHERE indexer_id = $1
+        AND sampled_at >= now() - $4::INT4 * INTERVAL '1 second'
+        "#,
+        indexer_id,
+        target_lag_seconds,
+        short_seconds,
+        long_seconds,
+    )
+    .fetch_one(tx.as_mut())
+    .await?;
+
+    Ok((record.short_fraction, record.long_fraction))
+}
+
+pub async fn get_open_lag_alert(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: &str,
+) -> sqlx::Result<Option<OpenLagAlert>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT id, short_burn_rate, long_burn_rate
+        FROM hubble.lag_alerts
+        WHERE indexer_id = $1 AND ended_at IS NULL
+        "#,
+        indexer_id,
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .map(|record| OpenLagAlert {
+        id: record.id,
+        burn_rates: BurnRates {
+            short: record.short_burn_rate,
+            long: record.long_burn_rate,
+        },
+    }))
+}
+
+pub async fn insert_lag_alert(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    indexer_id: &str,
+    slo: &LagSloConfig,
+    burn_rates: BurnRates,
+) -> sqlx::Result<i64> {
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO hubble.lag_alerts (indexer_id, target_lag_seconds, objective, burn_rate_threshold, short_burn_rate, long_burn_rate, started_at)
+        VALUES ($1, $2, $3, $4, $5, $6, now())
+        RETURNING id
+        "#,
+        indexer_id,
+        slo.target_lag_seconds,
+        slo.objective,
+        slo.burn_rate_threshold,
+        burn_rates.short,
+        burn_rates.long,
+    )
+    .fetch_one(tx.as_mut())
+    .await?;
+
+    Ok(record.id)
+}
+
+/// Updates the peak burn rates of an open alert. The alert is closed when `resolved` is set.
+pub async fn update_lag_alert(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    alert: &OpenLagAlert,
+    resolved: bool,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE hubble.lag_alerts
+        SET
+            short_burn_rate = $2,
+            long_burn_rate = $3,
+            ended_at = CASE WHEN $4 THEN now() END
+        WHERE id = $1
+        "#,
+        alert.id,
+        alert.burn_rates.short,
+        alert.burn_rates.long,
+        resolved,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
@@ -1,31 +1,45 @@
 #![allow(clippy::manual_async_fn, clippy::needless_lifetimes)]
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use axum::{routing::get, Router};
 use backon::{ConstantBuilder, ExponentialBuilder};
 use clap::Parser;
+use color_eyre::eyre::eyre;
 use sqlx::postgres::PgPoolOptions;
 use tokio::task::JoinSet;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 mod abi_fetcher;
+mod api;
 mod cli;
 mod github_client;
 mod github_fetcher;
 mod healthz;
+mod index_advisor;
 mod indexer;
+mod lag_monitor;
 mod logging;
 mod metrics;
+mod onboard;
 mod postgres;
 mod race_client;
+mod sla_monitor;
+mod stuck_packets;
+mod token_bucket_monitor;
 mod token_fetcher;
+mod token_metadata;
 mod utils;
+mod webhook;
 
 #[cfg(not(target_env = "msvc"))]
 use tikv_jemallocator::Jemalloc;
 
-use crate::indexer::nats::NatsConnection;
+use crate::{
+    api::{AdminToken, Role},
+    cli::{BackfillCommand, Command, EnrichCommand, MigrateCommand},
+    indexer::{nats::NatsConnection, scheduler::FetchScheduler},
+};
 
 #[cfg(not(target_env = "msvc"))]
 #[global_allocator]
@@ -38,12 +52,56 @@ async fn main() -> color_eyre::eyre::Result<()> {
     crate::logging::init(args.log_format);
     metrics::register_custom_metrics();
 
+    // onboarding only talks to the chain and the registry, so it doesn't need a database
+    if let Some(Command::OnboardChain {
+        rpc_url,
+        registry,
+        family,
+        testnet,
+        migration_file,
+    }) = &args.command
+    {
+        return onboard::onboard_chain(
+            rpc_url,
+            registry,
+            family.as_deref(),
+            *testnet,
+            migration_file,
+        )
+        .await;
+    }
+
     info!("connecting to database");
     let db = PgPoolOptions::new()
-        .max_connections(40)
+        .max_connections(args.database_max_connections)
         .connect(&args.database_url.unwrap())
         .await?;
 
+    let mut databases = HashMap::new();
+    for (name, url) in args.databases.into_iter().flatten() {
+        info!("connecting to database {name}");
+        let pool = PgPoolOptions::new()
+            .max_connections(args.database_max_connections)
+            .connect(&url)
+            .await?;
+        databases.insert(name, pool);
+    }
+
+    if let Some(command) = args.command {
+        let api_databases = api::Databases::new(
+            db.clone(),
+            &databases,
+            args.indexers
+                .iter()
+                .flat_map(|indexers| indexers.chain_databases())
+                .map(|(universal_chain_id, name)| (universal_chain_id.0, name)),
+        )?;
+
+        return run_command(db, api_databases, command).await;
+    }
+
+    indexer::set_publish_records(args.publish_records);
+
     info!("connecting to nats");
     let nats = match args.nats {
         Some(nats) => Some(
@@ -53,6 +111,23 @@ async fn main() -> color_eyre::eyre::Result<()> {
         None => None,
     };
 
+    let token_metadata_providers = token_metadata::Providers::new(
+        args.indexers
+            .iter()
+            .flat_map(|indexers| indexers.token_metadata_endpoints()),
+    )
+    .await?;
+
+    let price_providers = args.pricing.map(|pricing| {
+        indexer::enrich::pricing::PriceProviders::new(
+            pricing,
+            args.coingecko_api_key,
+            args.indexers
+                .iter()
+                .flat_map(|indexers| indexers.ethereum_rpc_urls()),
+        )
+    });
+
     let mut set = JoinSet::new();
 
     if let Some(addr) = args.metrics_addr {
@@ -67,18 +142,46 @@ async fn main() -> color_eyre::eyre::Result<()> {
                 .map_err(Into::into)
         });
     }
-    args.indexers.clone().into_iter().for_each(|indexer| {
-        let db: sqlx::Pool<sqlx::Postgres> = db.clone();
+    let fetch_scheduler = FetchScheduler::new(args.max_concurrent_fetches);
+
+    for indexer in args.indexers.into_iter().flatten() {
+        let db: sqlx::Pool<sqlx::Postgres> = match indexer.database() {
+            Some(name) => databases.get(name).cloned().ok_or_else(|| {
+                eyre!(
+                    "indexer {} is configured with unknown database {name}",
+                    indexer.label()
+                )
+            })?,
+            None => db.clone(),
+        };
+        // a dedicated pool keeps a busy indexer from taking all connections of a shared pool
+        let db = match indexer.max_connections() {
+            Some(max_connections) => {
+                info!(
+                    "connecting to database for indexer {} ({max_connections} connections)",
+                    indexer.label()
+                );
+                PgPoolOptions::new()
+                    .max_connections(max_connections)
+                    .connect_with((*db.connect_options()).clone())
+                    .await?
+            }
+            None => db,
+        };
         let nats = nats.clone();
+        let fetch_scheduler = fetch_scheduler.clone();
         set.spawn(async move {
             info!("starting indexer {:?}", indexer);
             let label = indexer.label().to_owned();
             // indexer should never return with Ok, thus we log the error.
-            indexer.index(db, nats).await.inspect_err(|err| {
-                warn!("indexer {label} exited with: {:?}", err);
-            })
+            indexer
+                .index(db, nats, fetch_scheduler)
+                .await
+                .inspect_err(|err| {
+                    warn!("indexer {label} exited with: {:?}", err);
+                })
         });
-    });
+    }
 
     let token_fetcher_db = db.clone();
     let token_fetcher = async move {
@@ -128,6 +231,145 @@ async fn main() -> color_eyre::eyre::Result<()> {
 
     set.spawn(abi_fetcher);
 
+    let sla_monitor_db = db.clone();
+    let sla_monitor = async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        interval.tick().await;
+        loop {
+            info!("evaluating slas");
+            match sla_monitor::evaluate_slas(&sla_monitor_db).await {
+                Ok(()) => info!("evaluated slas"),
+                Err(err) => error!("failed to evaluate slas: {:?}", err),
+            };
+            interval.tick().await;
+        }
+    };
+
+    set.spawn(sla_monitor);
+
+    let token_bucket_monitor_db = db.clone();
+    let token_bucket_alerts = args.token_bucket_alerts;
+    let token_bucket_monitor = async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        interval.tick().await;
+        loop {
+            info!("monitoring token buckets");
+            match token_bucket_monitor::monitor_token_buckets(
+                &token_bucket_monitor_db,
+                token_bucket_alerts.as_ref(),
+            )
+            .await
+            {
+                Ok(()) => info!("monitored token buckets"),
+                Err(err) => error!("failed to monitor token buckets: {:?}", err),
+            };
+            interval.tick().await;
+        }
+    };
+
+    set.spawn(token_bucket_monitor);
+
+    if let Some(lag_slo) = args.lag_slo {
+        let lag_monitor_db = db.clone();
+        let lag_monitor = async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(30));
+            interval.tick().await;
+            loop {
+                debug!("monitoring ingestion lag");
+                match lag_monitor::monitor_lag(&lag_monitor_db, &lag_slo).await {
+                    Ok(()) => debug!("monitored ingestion lag"),
+                    Err(err) => error!("failed to monitor ingestion lag: {:?}", err),
+                };
+                interval.tick().await;
+            }
+        };
+
+        set.spawn(lag_monitor);
+    }
+
+    if let Some(stuck_packets) = args.stuck_packets {
+        let stuck_packets_db = db.clone();
+        let stuck_packet_detector = async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(5 * 60));
+            interval.tick().await;
+            loop {
+                debug!("detecting stuck packets");
+                match stuck_packets::detect_stuck_packets(&stuck_packets_db, &stuck_packets).await {
+                    Ok(()) => debug!("detected stuck packets"),
+                    Err(err) => error!("failed to detect stuck packets: {:?}", err),
+                };
+                interval.tick().await;
+            }
+        };
+
+        set.spawn(stuck_packet_detector);
+    }
+
+    let webhook_dispatcher_db = db.clone();
+    let webhook_secret = args.webhook_secret;
+    let webhook_client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+    let webhook_dispatcher = async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(5));
+        interval.tick().await;
+        loop {
+            debug!("dispatching webhooks");
+            match webhook::dispatch_webhooks(
+                &webhook_dispatcher_db,
+                &webhook_client,
+                webhook_secret.as_deref(),
+            )
+            .await
+            {
+                Ok(()) => debug!("dispatched webhooks"),
+                Err(err) => error!("failed to dispatch webhooks: {:?}", err),
+            };
+            interval.tick().await;
+        }
+    };
+
+    set.spawn(webhook_dispatcher);
+
+    let token_metadata_db = db.clone();
+    let token_metadata = async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        interval.tick().await;
+        loop {
+            info!("updating token metadata");
+            match token_metadata::update_token_metadata(
+                &token_metadata_db,
+                &token_metadata_providers,
+            )
+            .await
+            {
+                Ok(()) => info!("updated token metadata"),
+                Err(err) => error!("failed to update token metadata: {:?}", err),
+            };
+            interval.tick().await;
+        }
+    };
+
+    set.spawn(token_metadata);
+
+    if let Some(price_providers) = price_providers {
+        let prices_db = db.clone();
+        let prices = async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60));
+            interval.tick().await;
+            loop {
+                info!("updating prices");
+                match indexer::enrich::pricing::update_prices(&prices_db, &price_providers).await {
+                    Ok(()) => info!("updated prices"),
+                    Err(err) => error!("failed to update prices: {:?}", err),
+                };
+                interval.tick().await;
+            }
+        };
+
+        set.spawn(prices);
+    }
+
     while let Some(res) = set.join_next().await {
         match res {
             Ok(Err(err)) => {
@@ -153,6 +395,150 @@ async fn main() -> color_eyre::eyre::Result<()> {
     Ok(())
 }
 
+async fn run_command(
+    db: sqlx::PgPool,
+    databases: api::Databases,
+    command: Command,
+) -> color_eyre::eyre::Result<()> {
+    match command {
+        Command::Enrich(EnrichCommand::Backfill {
+            internal_chain_id,
+            from_height,
+            to_height,
+            batch_size,
+        }) => {
+            let updated = indexer::enrich::backfill::backfill_wrap_direction(
+                &db,
+                internal_chain_id.map(Into::into),
+                from_height.into(),
+                to_height.into(),
+                batch_size,
+            )
+            .await?;
+
+            info!("backfill completed: updated {updated} transfers");
+        }
+        Command::Backfill(BackfillCommand::Schedule {
+            indexer_id,
+            start_height,
+            end_height,
+        }) => {
+            indexer::backfiller::schedule_backfill(
+                &db,
+                &indexer_id,
+                (start_height..end_height).into(),
+            )
+            .await?;
+        }
+        Command::Backfill(BackfillCommand::Status { indexer_id }) => {
+            indexer::backfiller::report_backfills(&db, indexer_id.as_ref()).await?;
+        }
+        Command::Migrate(MigrateCommand::DecoderVersions {
+            internal_chain_id,
+            from_height,
+            to_height,
+            batch_size,
+        }) => {
+            let updated = indexer::decoder_version_migration::retrofit_decoder_versions(
+                &db,
+                internal_chain_id.map(Into::into),
+                from_height.into(),
+                to_height.into(),
+                batch_size,
+            )
+            .await?;
+
+            info!("migration completed: updated {updated} records");
+        }
+        Command::Migrate(MigrateCommand::TokenMetadata {
+            internal_chain_id,
+            from_height,
+            to_height,
+            batch_size,
+        }) => {
+            let registered = token_metadata::register_tokens_of_transfers(
+                &db,
+                internal_chain_id,
+                from_height.into(),
+                to_height.into(),
+                batch_size,
+            )
+            .await?;
+
+            info!("migration completed: registered {registered} tokens");
+        }
+        Command::Serve {
+            addr,
+            stream,
+            admin_token,
+            admin_tokens,
+            api_keys,
+            cold_storage,
+            index_advisor,
+            query_budget_ms,
+        } => {
+            let tokens: Vec<AdminToken> = admin_token
+                .map(|token| AdminToken {
+                    name: "admin".to_owned(),
+                    role: Role::Admin,
+                    token,
+                })
+                .into_iter()
+                .chain(admin_tokens.unwrap_or_default().0)
+                .chain(api_keys.unwrap_or_default().0.into_iter().map(Into::into))
+                .collect();
+
+            api::check_tokens(&tokens).map_err(|e| eyre!("invalid api tokens: {e}"))?;
+
+            if let Some(index_advisor) = index_advisor {
+                let index_advisor_db = db.clone();
+                tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(Duration::from_secs(10 * 60));
+                    interval.tick().await;
+                    loop {
+                        info!("advising indexes");
+                        match index_advisor::advise_indexes(&index_advisor_db, &index_advisor).await
+                        {
+                            Ok(()) => info!("advised indexes"),
+                            Err(err) => error!("failed to advise indexes: {:?}", err),
+                        };
+                        interval.tick().await;
+                    }
+                });
+            }
+
+            api::serve(
+                databases.with_statement_timeout(Duration::from_millis(query_budget_ms)),
+                addr,
+                stream,
+                tokens,
+                cold_storage,
+            )
+            .await?
+        }
+        Command::Archive {
+            table,
+            universal_chain_id,
+            start_height,
+            end_height,
+            uri,
+        } => {
+            api::archive_partition(
+                &databases,
+                table,
+                &universal_chain_id,
+                start_height.try_into()?,
+                end_height.try_into()?,
+                uri,
+            )
+            .await?;
+        }
+        Command::OnboardChain { .. } => unreachable!("onboarding is handled before connecting"),
+    }
+
+    Ok(())
+}
+
 /// Our ExponentialBackoff that we use everywhere.
 pub fn expo_backoff() -> ExponentialBuilder {
     ExponentialBuilder::default()
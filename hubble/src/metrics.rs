@@ -1,5 +1,5 @@
 use lazy_static::lazy_static;
-use prometheus::{IntCounterVec, Opts, Registry};
+use prometheus::{GaugeVec, IntCounterVec, IntGaugeVec, Opts, Registry};
 use reqwest::StatusCode;
 
 lazy_static! {
@@ -25,6 +25,57 @@ lazy_static! {
         &["chain_id"]
     )
     .expect("register TRANSACTION_COLLECTOR");
+    pub static ref FAILED_EVENT_COLLECTOR: IntCounterVec = IntCounterVec::new(
+        Opts::new("failed_events", "Events that failed to be handled")
+            .namespace("hubble")
+            .subsystem("index"),
+        &["chain_id", "status"]
+    )
+    .expect("register FAILED_EVENT_COLLECTOR");
+    pub static ref FETCH_WAIT_COLLECTOR: IntCounterVec = IntCounterVec::new(
+        Opts::new(
+            "fetch_wait_milliseconds",
+            "Time spent waiting for a permit to fetch a range of blocks"
+        )
+        .namespace("hubble")
+        .subsystem("index"),
+        &["chain_id"]
+    )
+    .expect("register FETCH_WAIT_COLLECTOR");
+    pub static ref INGESTION_LAG_COLLECTOR: GaugeVec = GaugeVec::new(
+        Opts::new("lag_seconds", "Age of the last indexed block")
+            .namespace("hubble")
+            .subsystem("index"),
+        &["indexer_id"]
+    )
+    .expect("register INGESTION_LAG_COLLECTOR");
+    pub static ref BACKFILL_BLOCKS_COLLECTOR: IntCounterVec = IntCounterVec::new(
+        Opts::new("blocks", "Blocks backfilled by backfill jobs")
+            .namespace("hubble")
+            .subsystem("backfill"),
+        &["indexer_id"]
+    )
+    .expect("register BACKFILL_BLOCKS_COLLECTOR");
+    pub static ref BACKFILL_REMAINING_BLOCKS_COLLECTOR: IntGaugeVec = IntGaugeVec::new(
+        Opts::new(
+            "remaining_blocks",
+            "Blocks left to backfill over all active backfill jobs"
+        )
+        .namespace("hubble")
+        .subsystem("backfill"),
+        &["indexer_id"]
+    )
+    .expect("register BACKFILL_REMAINING_BLOCKS_COLLECTOR");
+    pub static ref STUCK_PACKETS_COLLECTOR: IntGaugeVec = IntGaugeVec::new(
+        Opts::new(
+            "stuck",
+            "Packets that are stuck, by the reason they are stuck"
+        )
+        .namespace("hubble")
+        .subsystem("packets"),
+        &["universal_chain_id", "reason"]
+    )
+    .expect("register STUCK_PACKETS_COLLECTOR");
 }
 
 pub fn register_custom_metrics() {
@@ -37,6 +88,24 @@ pub fn register_custom_metrics() {
     REGISTRY
         .register(Box::new(TRANSACTION_COLLECTOR.clone()))
         .expect("TRANSACTION_COLLECTOR can be registered");
+    REGISTRY
+        .register(Box::new(FAILED_EVENT_COLLECTOR.clone()))
+        .expect("FAILED_EVENT_COLLECTOR can be registered");
+    REGISTRY
+        .register(Box::new(FETCH_WAIT_COLLECTOR.clone()))
+        .expect("FETCH_WAIT_COLLECTOR can be registered");
+    REGISTRY
+        .register(Box::new(INGESTION_LAG_COLLECTOR.clone()))
+        .expect("INGESTION_LAG_COLLECTOR can be registered");
+    REGISTRY
+        .register(Box::new(BACKFILL_BLOCKS_COLLECTOR.clone()))
+        .expect("BACKFILL_BLOCKS_COLLECTOR can be registered");
+    REGISTRY
+        .register(Box::new(BACKFILL_REMAINING_BLOCKS_COLLECTOR.clone()))
+        .expect("BACKFILL_REMAINING_BLOCKS_COLLECTOR can be registered");
+    REGISTRY
+        .register(Box::new(STUCK_PACKETS_COLLECTOR.clone()))
+        .expect("STUCK_PACKETS_COLLECTOR can be registered");
 }
 
 #[axum::debug_handler]
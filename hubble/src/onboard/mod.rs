@@ -0,0 +1,305 @@
+use std::{fmt::Write, path::Path};
+
+use color_eyre::eyre::Result;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+use url::Url;
+
+use crate::onboard::{
+    probe::probe,
+    registry::{fetch_registry, find_deployment, Deployment},
+};
+
+mod probe;
+mod registry;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChainKind {
+    Ethereum,
+    Tendermint,
+}
+
+/// A contract to register for indexing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Contract {
+    /// Name of the contract in the registry (e.g. `core` or `app/ucs03`).
+    name: String,
+    address: String,
+    start_height: u64,
+    commit: Option<String>,
+    /// Cosmwasm flow of the contract, only set for contracts with a supported flow.
+    flow: Option<&'static str>,
+}
+
+/// Probe `rpc_url`, look up the contracts of the chain in the registry and print an indexer config
+/// for review. The rows to register the chain and its contracts are written to `migration_file`.
+pub async fn onboard_chain(
+    rpc_url: &Url,
+    registry: &str,
+    family: Option<&str>,
+    testnet: bool,
+    migration_file: &Path,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+
+    let probe = probe(&client, rpc_url).await?;
+    info!(
+        "{rpc_url}: {:?} chain {} at height {}",
+        probe.kind, probe.chain_id, probe.latest_height
+    );
+
+    let registry = fetch_registry(&client, registry).await?;
+    let (family, deployment) = find_deployment(&registry, &probe.chain_id, family)?;
+    let universal_chain_id = format!("{family}.{}", probe.chain_id);
+
+    let contracts = contracts(probe.kind, &deployment);
+    let start_height = contracts
+        .iter()
+        .map(|contract| contract.start_height)
+        .min()
+        .unwrap_or(deployment.core.height);
+
+    if let Some(earliest_height) = probe.earliest_height {
+        if earliest_height > start_height {
+            warn!("{rpc_url}: pruned below height {earliest_height}, which is after the first deployment at {start_height}. use an archive node to index from the start");
+        }
+    }
+
+    std::fs::write(
+        migration_file,
+        migration(probe.kind, &family, &probe.chain_id, testnet, &contracts),
+    )?;
+    info!("wrote migration to {}", migration_file.display());
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&indexer_config(
+            probe.kind,
+            &universal_chain_id,
+            start_height,
+            rpc_url,
+            testnet,
+        ))?
+    );
+
+    Ok(())
+}
+
+/// The contracts to index. Cosmwasm contracts are only indexed for the flows hubble supports.
+fn contracts(kind: ChainKind, deployment: &Deployment) -> Vec<Contract> {
+    let contract = |name: &str, address: &str, height: u64, commit: &str, flow| Contract {
+        name: name.to_string(),
+        address: address.to_string(),
+        start_height: height,
+        commit: (commit != "unknown").then(|| commit.to_string()),
+        flow,
+    };
+
+    let core = &deployment.core;
+
+    match kind {
+        ChainKind::Tendermint => std::iter::once(contract(
+            "core",
+            &core.address,
+            core.height,
+            &core.commit,
+            Some("ibc"),
+        ))
+        .chain(deployment.app.iter().filter_map(|(name, app)| {
+            let minter = app.minter.as_ref().filter(|minter| minter.kind == "cw20")?;
+
+            Some(contract(
+                &format!("app/{name}/minter"),
+                &minter.address,
+                app.contract.height,
+                &app.contract.commit,
+                Some("cw20"),
+            ))
+        }))
+        .collect(),
+        ChainKind::Ethereum => std::iter::once(contract(
+            "core",
+            &core.address,
+            core.height,
+            &core.commit,
+            None,
+        ))
+        .chain(deployment.lightclient.iter().map(|(name, lightclient)| {
+            contract(
+                &format!("lightclient/{name}"),
+                &lightclient.address,
+                lightclient.height,
+                &lightclient.commit,
+                None,
+            )
+        }))
+        .chain(deployment.app.iter().map(|(name, app)| {
+            contract(
+                &format!("app/{name}"),
+                &app.contract.address,
+                app.contract.height,
+                &app.contract.commit,
+                None,
+            )
+        }))
+        .collect(),
+    }
+}
+
+fn indexer_config(
+    kind: ChainKind,
+    universal_chain_id: &str,
+    start_height: u64,
+    rpc_url: &Url,
+    testnet: bool,
+) -> Value {
+    match kind {
+        ChainKind::Ethereum => json!({
+            "type": "ethereum",
+            "indexer_id": universal_chain_id,
+            "universal_chain_id": universal_chain_id,
+            "start_height": start_height,
+            "rpc_urls": [rpc_url],
+        }),
+        ChainKind::Tendermint => json!({
+            "type": "tendermint",
+            "indexer_id": universal_chain_id,
+            "universal_chain_id": universal_chain_id,
+            "start_height": start_height,
+            "rpc_urls": [rpc_url],
+            "testnet": testnet,
+        }),
+    }
+}
+
+fn migration(
+    kind: ChainKind,
+    family: &str,
+    chain_id: &str,
+    testnet: bool,
+    contracts: &[Contract],
+) -> String {
+    let internal_chain_id = format!(
+        "(SELECT id FROM config.chains WHERE family = {} AND chain_id = {})",
+        quote(family),
+        quote(chain_id)
+    );
+
+    let mut sql = format!(
+        "INSERT INTO config.chains (family, chain_id, testnet)\nVALUES ({}, {}, {testnet})\nON CONFLICT DO NOTHING;\n",
+        quote(family),
+        quote(chain_id),
+    );
+
+    for contract in contracts {
+        sql.push('\n');
+
+        match kind {
+            ChainKind::Tendermint => write!(
+                sql,
+                "-- {}\nINSERT INTO v2_cosmos.contracts (internal_chain_id, address, start_height, end_height, flow)\nVALUES ({internal_chain_id}, {}, {}, {}, {});\n",
+                contract.name,
+                quote(&contract.address),
+                contract.start_height,
+                i64::MAX,
+                quote(contract.flow.expect("cosmwasm contracts have a flow")),
+            ),
+            ChainKind::Ethereum => write!(
+                sql,
+                "-- {}{}\nINSERT INTO v2_evm.contracts (internal_chain_id, address, start_height, end_height, description, commit)\nVALUES ({internal_chain_id}, {}, {}, {}, {}, {});\n",
+                contract.name,
+                match contract.commit {
+                    Some(_) => "",
+                    None => " (commit unknown: set the abi manually)",
+                },
+                quote(&contract.address),
+                contract.start_height,
+                i64::MAX,
+                quote(&contract.name),
+                contract
+                    .commit
+                    .as_ref()
+                    .map_or("NULL".to_string(), |commit| format!("decode({}, 'hex')", quote(commit))),
+            ),
+        }
+        .expect("writing to a string is infallible");
+    }
+
+    sql
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn deployment() -> Deployment {
+        serde_json::from_value(json!({
+            "ibc_interface": "ibc-cosmwasm",
+            "core": { "address": "bbn1core", "height": 100, "commit": "aa" },
+            "lightclient": {
+                "cometbls": { "address": "bbn1cometbls", "height": 110, "commit": "bb" }
+            },
+            "app": {
+                "ucs03": {
+                    "address": "bbn1ucs03",
+                    "height": 120,
+                    "commit": "unknown",
+                    "minter": { "type": "cw20", "address": "bbn1minter", "commit": "cc", "code_id": 1 }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn cosmwasm_contracts_with_supported_flows() {
+        assert_eq!(
+            contracts(ChainKind::Tendermint, &deployment()),
+            vec![
+                Contract {
+                    name: "core".to_string(),
+                    address: "bbn1core".to_string(),
+                    start_height: 100,
+                    commit: Some("aa".to_string()),
+                    flow: Some("ibc"),
+                },
+                Contract {
+                    name: "app/ucs03/minter".to_string(),
+                    address: "bbn1minter".to_string(),
+                    start_height: 120,
+                    commit: None,
+                    flow: Some("cw20"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn evm_contracts() {
+        let contracts = contracts(ChainKind::Ethereum, &deployment());
+
+        assert_eq!(
+            contracts
+                .iter()
+                .map(|contract| contract.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["core", "lightclient/cometbls", "app/ucs03"]
+        );
+
+        let migration = migration(ChainKind::Ethereum, "ethereum", "1", false, &contracts);
+        assert!(migration.contains("VALUES ('ethereum', '1', false)"));
+        assert!(migration.contains("'core', decode('aa', 'hex'));"));
+        assert!(migration.contains("-- app/ucs03 (commit unknown: set the abi manually)"));
+    }
+
+    #[test]
+    fn quotes() {
+        assert_eq!(quote("it's"), "'it''s'");
+    }
+}
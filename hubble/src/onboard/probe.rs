@@ -0,0 +1,110 @@
+use color_eyre::eyre::{bail, eyre, Result};
+use serde_json::{json, Value};
+use tracing::debug;
+use url::Url;
+
+use crate::onboard::ChainKind;
+
+/// What an rpc endpoint tells about its chain.
+#[derive(Clone, Debug)]
+pub struct Probe {
+    pub kind: ChainKind,
+    pub chain_id: String,
+    pub latest_height: u64,
+    /// Lowest height that is still available on the node (pruned nodes only keep recent blocks).
+    pub earliest_height: Option<u64>,
+}
+
+/// Detect the kind of chain behind `rpc_url` by trying the evm and cometbft json-rpc apis.
+pub async fn probe(client: &reqwest::Client, rpc_url: &Url) -> Result<Probe> {
+    match request(client, rpc_url, "eth_chainId", json!([])).await {
+        Ok(chain_id) => {
+            let latest_height = request(client, rpc_url, "eth_blockNumber", json!([])).await?;
+
+            return Ok(Probe {
+                kind: ChainKind::Ethereum,
+                chain_id: parse_quantity(&chain_id)?.to_string(),
+                latest_height: parse_quantity(&latest_height)?,
+                earliest_height: None,
+            });
+        }
+        Err(error) => debug!("{rpc_url}: not an evm rpc ({error})"),
+    }
+
+    match request(client, rpc_url, "status", json!({})).await {
+        Ok(status) => {
+            let chain_id = status["node_info"]["network"]
+                .as_str()
+                .ok_or_else(|| eyre!("{rpc_url}: status without network"))?;
+
+            return Ok(Probe {
+                kind: ChainKind::Tendermint,
+                chain_id: chain_id.to_string(),
+                latest_height: parse_height(&status["sync_info"]["latest_block_height"])?,
+                earliest_height: Some(parse_height(&status["sync_info"]["earliest_block_height"])?),
+            });
+        }
+        Err(error) => debug!("{rpc_url}: not a cometbft rpc ({error})"),
+    }
+
+    bail!("{rpc_url}: not an evm or cometbft rpc, other chain families are not supported")
+}
+
+async fn request(
+    client: &reqwest::Client,
+    rpc_url: &Url,
+    method: &str,
+    params: Value,
+) -> Result<Value> {
+    let mut response = client
+        .post(rpc_url.clone())
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Value>()
+        .await?;
+
+    match response.get("error") {
+        Some(error) if !error.is_null() => bail!("{method}: {error}"),
+        _ => Ok(response["result"].take()),
+    }
+}
+
+/// Parse a hex encoded evm quantity (e.g. `0x1`).
+fn parse_quantity(value: &Value) -> Result<u64> {
+    let quantity = value
+        .as_str()
+        .and_then(|quantity| quantity.strip_prefix("0x"))
+        .ok_or_else(|| eyre!("expected a hex quantity, got {value}"))?;
+
+    Ok(u64::from_str_radix(quantity, 16)?)
+}
+
+/// Parse a cometbft height, which is encoded as a string.
+fn parse_height(value: &Value) -> Result<u64> {
+    Ok(value
+        .as_str()
+        .ok_or_else(|| eyre!("expected a height, got {value}"))?
+        .parse()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{parse_height, parse_quantity};
+
+    #[test]
+    fn parses_quantities_and_heights() {
+        assert_eq!(parse_quantity(&json!("0xaa36a7")).unwrap(), 11155111);
+        assert!(parse_quantity(&json!("11155111")).is_err());
+        assert_eq!(parse_height(&json!("123")).unwrap(), 123);
+        assert!(parse_height(&json!(123)).is_err());
+    }
+}
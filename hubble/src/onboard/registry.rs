@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use color_eyre::eyre::{bail, Result};
+use serde::Deserialize;
+
+/// The deployments of a chain in the registry (see `deployments/deployments.json`).
+#[derive(Clone, Debug, Deserialize)]
+pub struct Deployment {
+    pub core: DeployedContract,
+    #[serde(default)]
+    pub lightclient: BTreeMap<String, DeployedContract>,
+    #[serde(default)]
+    pub app: BTreeMap<String, DeployedApp>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DeployedContract {
+    pub address: String,
+    pub height: u64,
+    pub commit: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct DeployedApp {
+    #[serde(flatten)]
+    pub contract: DeployedContract,
+    pub minter: Option<Minter>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Minter {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub address: String,
+}
+
+/// Read the registry from a file or an http(s) url.
+pub async fn fetch_registry(
+    client: &reqwest::Client,
+    registry: &str,
+) -> Result<BTreeMap<String, serde_json::Value>> {
+    Ok(
+        match registry.starts_with("http://") || registry.starts_with("https://") {
+            true => {
+                client
+                    .get(registry)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?
+            }
+            false => serde_json::from_str(&std::fs::read_to_string(registry)?)?,
+        },
+    )
+}
+
+/// Find the deployment of `chain_id` in the registry, which is keyed by universal chain id
+/// (`<family>.<chain id>`). The family is required when multiple families use the chain id.
+pub fn find_deployment(
+    registry: &BTreeMap<String, serde_json::Value>,
+    chain_id: &str,
+    family: Option<&str>,
+) -> Result<(String, Deployment)> {
+    let candidates = registry
+        .iter()
+        .filter_map(|(universal_chain_id, deployment)| {
+            let (candidate_family, candidate_chain_id) = universal_chain_id.split_once('.')?;
+
+            (candidate_chain_id == chain_id
+                && family.is_none_or(|family| family == candidate_family))
+            .then_some((candidate_family, deployment))
+        })
+        .collect::<Vec<_>>();
+
+    match candidates.as_slice() {
+        [(family, deployment)] => Ok((
+            family.to_string(),
+            serde_json::from_value((*deployment).clone())?,
+        )),
+        [] => bail!("chain {chain_id} is not in the registry"),
+        _ => bail!(
+            "chain {chain_id} is used by multiple families in the registry ({}), select one with --family",
+            candidates
+                .iter()
+                .map(|(family, _)| *family)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
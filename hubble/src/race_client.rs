@@ -1,4 +1,7 @@
-use core::{fmt::Debug, future::Future};
+use core::{
+    fmt::{Debug, Display},
+    future::Future,
+};
 
 use futures::{stream::FuturesUnordered, StreamExt};
 use tracing::debug;
@@ -8,6 +11,16 @@ pub struct RaceClient<C> {
     pub clients: Vec<C>,
 }
 
+/// The error of a race without any client to run it on, such as with an empty list of endpoints.
+#[derive(Clone, Debug, Copy)]
+pub struct NoClients;
+
+impl Display for NoClients {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no clients to race")
+    }
+}
+
 #[derive(Clone, Debug, Copy)]
 pub struct RaceClientId {
     index: usize,
@@ -40,11 +53,11 @@ impl<C> RaceClient<C> {
     }
 
     /// Run the provided closure over the clients, returning the first encountered Ok, or if all error, the first
-    /// encountered Err.
+    /// encountered Err. Fails with [`NoClients`] if there are no clients.
     pub async fn race<
         'a,
         T,
-        E: Debug,
+        E: Debug + From<NoClients>,
         FUT: Future<Output = Result<T, E>> + 'a,
         F: Fn(&'a C) -> FUT,
     >(
@@ -65,6 +78,11 @@ impl<C> RaceClient<C> {
                 }
             })
             .collect();
+
+        if futures.is_empty() {
+            return Err(NoClients.into());
+        }
+
         let mut error = None;
 
         loop {
@@ -82,15 +100,15 @@ impl<C> RaceClient<C> {
             }
         }
 
-        Err(error.unwrap())
+        Err(error.expect("every client returned an error"))
     }
 
     /// Run the provided closure over the clients, returning the first encountered Ok, or if all error, the first
-    /// encountered Err.
+    /// encountered Err. Fails with [`NoClients`] if there are no clients.
     pub async fn race_some<
         'a,
         T,
-        E: Debug,
+        E: Debug + From<NoClients>,
         FUT: Future<Output = Result<Option<T>, E>> + 'a,
         F: Fn(&'a C) -> FUT,
     >(
@@ -111,6 +129,11 @@ impl<C> RaceClient<C> {
                 }
             })
             .collect();
+
+        if futures.is_empty() {
+            return Err(NoClients.into());
+        }
+
         let mut error = None;
 
         loop {
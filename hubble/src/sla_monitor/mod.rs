@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+use crate::sla_monitor::severity::Severity;
+
+mod monitor;
+mod postgres;
+mod severity;
+
+/// Target latency of a channel, as configured by operators in `config.channel_slas`.
+#[derive(Clone, Debug)]
+pub struct ChannelSla {
+    id: i32,
+    internal_chain_id: i32,
+    channel_id: i32,
+    /// Percentile of the packet latency that is compared to the target (e.g. 0.95).
+    percentile: f64,
+    target_latency_seconds: f64,
+    /// Only packets sent within this window are taken into account.
+    window_seconds: i32,
+}
+
+impl Display for ChannelSla {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{}: {}:{} p{} <= {}s ({}s window)",
+            self.id,
+            self.internal_chain_id,
+            self.channel_id,
+            self.percentile * 100.0,
+            self.target_latency_seconds,
+            self.window_seconds,
+        ))
+    }
+}
+
+/// Observed send-to-receive latency of the packets of a channel within the sla window.
+/// Packets that are not received yet count with their current age.
+#[derive(Clone, Debug)]
+pub struct ChannelLatency {
+    latency_seconds: f64,
+    packets: i64,
+}
+
+/// A breach that has not recovered yet.
+#[derive(Clone, Debug)]
+pub struct OpenBreach {
+    id: i64,
+    severity: Severity,
+    observed_latency_seconds: f64,
+}
+
+impl Display for OpenBreach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{}: {} ({}s)",
+            self.id, self.severity, self.observed_latency_seconds,
+        ))
+    }
+}
+
+pub async fn evaluate_slas(db: &sqlx::PgPool) -> color_eyre::Result<()> {
+    crate::sla_monitor::monitor::evaluate_slas(db).await
+}
@@ -0,0 +1,76 @@
+use tracing::{debug, error, info, warn};
+
+use crate::sla_monitor::{
+    postgres::{
+        get_channel_latency, get_channel_slas, get_open_breach, insert_breach, update_breach,
+    },
+    severity::Severity,
+    ChannelSla, OpenBreach,
+};
+
+/// Compares the observed latency of every configured channel with its target and records
+/// breaches in `sla.breaches`. A breach stays open (`ended_at` is null) while the target is
+/// missed; its severity and observed latency only escalate during that time, so consumers
+/// (notifier, status page) can react to both new and worsening breaches.
+pub async fn evaluate_slas(db: &sqlx::PgPool) -> color_eyre::Result<()> {
+    let channel_slas = get_channel_slas(&mut db.begin().await?).await?;
+
+    for channel_sla in channel_slas {
+        debug!("evaluate: {channel_sla}");
+
+        match evaluate_sla(db, &channel_sla).await {
+            Ok(_) => debug!("evaluate: {channel_sla} => success"),
+            Err(error) => error!("evaluate: {channel_sla} => error: {error:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+async fn evaluate_sla(db: &sqlx::PgPool, channel_sla: &ChannelSla) -> sqlx::Result<()> {
+    let mut tx = db.begin().await?;
+
+    let latency = get_channel_latency(&mut tx, channel_sla).await?;
+    let open_breach = get_open_breach(&mut tx, channel_sla).await?;
+
+    let breach = latency.and_then(|latency| {
+        Severity::classify(latency.latency_seconds, channel_sla.target_latency_seconds)
+            .map(|severity| (latency, severity))
+    });
+
+    match (open_breach, breach) {
+        (None, None) => {}
+        (None, Some((latency, severity))) => {
+            let id = insert_breach(&mut tx, channel_sla, latency.latency_seconds, severity).await?;
+
+            warn!(
+                "breach: {channel_sla} => {id}: {severity} ({}s over {} packets)",
+                latency.latency_seconds, latency.packets
+            );
+        }
+        (Some(open_breach), Some((latency, severity))) => {
+            let breach = OpenBreach {
+                id: open_breach.id,
+                severity: open_breach.severity.max(severity),
+                observed_latency_seconds: open_breach
+                    .observed_latency_seconds
+                    .max(latency.latency_seconds),
+            };
+
+            if breach.severity != open_breach.severity {
+                warn!("breach: {channel_sla} => escalated {open_breach} to {breach}");
+            }
+
+            update_breach(&mut tx, &breach, false).await?;
+        }
+        (Some(open_breach), None) => {
+            update_breach(&mut tx, &open_breach, true).await?;
+
+            info!("breach: {channel_sla} => recovered {open_breach}");
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use sqlx::Postgres;
+
+use crate::sla_monitor::{severity::Severity, ChannelLatency, ChannelSla, OpenBreach};
+
+pub async fn get_channel_slas(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> sqlx::Result<Vec<ChannelSla>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT id, internal_chain_id, channel_id, percentile, target_latency_seconds, window_seconds
+        FROM config.channel_slas
+        WHERE enabled = true
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| ChannelSla {
+        id: record.id,
+        internal_chain_id: record.internal_chain_id,
+        channel_id: record.channel_id,
+        percentile: record.percentile,
+        target_latency_seconds: record.target_latency_seconds,
+        window_seconds: record.window_seconds,
+    })
+    .collect())
+}
+
+pub async fn get_channel_latency(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    channel_sla: &ChannelSla,
+) -> sqlx::Result<Option<ChannelLatency>> {
+    let record = sqlx::query!(
+        r#"
+        SELECT
+            percentile_cont($3) WITHIN GROUP (
+                ORDER BY EXTRACT(EPOCH FROM COALESCE(recv.timestamp, now()) - send.timestamp)::FLOAT8
+            ) AS latency_seconds,
+            COUNT(*) AS packets
+        FROM v2_sync.packet_send_sync send
+        LEFT JOIN v2_sync.packet_recv_sync recv ON recv.packet_hash = send.packet_hash
+        WHERE send.internal_chain_id = $1
+        AND send.source_channel_id = $2
+        AND send.timestamp >= now() - $4::INT4 * INTERVAL '1 second'
+        "#,
+        channel_sla.internal_chain_id,
+        channel_sla.channel_id,
+        channel_sla.percentile,
+        channel_sla.window_seconds,
+    )
+    .fetch_one(tx.as_mut())
+    .await?;
+
+    Ok(match (record.latency_seconds, record.packets) {
+        (Some(latency_seconds), Some(packets)) if packets > 0 => Some(ChannelLatency {
+            latency_seconds,
+            packets,
+        }),
+        _ => None,
+    })
+}
+
+pub async fn get_open_breach(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    channel_sla: &ChannelSla,
+) -> sqlx::Result<Option<OpenBreach>> {
+    sqlx::query!(
+        r#"
+        SELECT id, severity, observed_latency_seconds
+        FROM sla.breaches
+        WHERE channel_sla_id = $1 AND ended_at IS NULL
+        "#,
+        channel_sla.id,
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .map(|record| {
+        Ok(OpenBreach {
+            id: record.id,
+            severity: record
+                .severity
+                .parse()
+                .map_err(|error| sqlx::Error::Decode(Box::new(error)))?,
+            observed_latency_seconds: record.observed_latency_seconds,
+        })
+    })
+    .transpose()
+}
+
+pub async fn insert_breach(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    channel_sla: &ChannelSla,
+    observed_latency_seconds: f64,
+    severity: Severity,
+) -> sqlx::Result<i64> {
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO sla.breaches (channel_sla_id, internal_chain_id, channel_id, percentile, target_latency_seconds, observed_latency_seconds, severity, started_at, duration_seconds)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, now(), 0)
+        RETURNING id
+        "#,
+        channel_sla.id,
+        channel_sla.internal_chain_id,
+        channel_sla.channel_id,
+        channel_sla.percentile,
+        channel_sla.target_latency_seconds,
+        observed_latency_seconds,
+        severity.as_str(),
+    )
+    .fetch_one(tx.as_mut())
+    .await?;
+
+    Ok(record.id)
+}
+
+/// Updates the observed latency, severity and duration of an open breach. The breach is
+/// closed when `recovered` is set.
+pub async fn update_breach(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    breach: &OpenBreach,
+    recovered: bool,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE sla.breaches
+        SET
+            observed_latency_seconds = $2,
+            severity = $3,
+            duration_seconds = EXTRACT(EPOCH FROM now() - started_at)::FLOAT8,
+            ended_at = CASE WHEN $4 THEN now() END
+        WHERE id = $1
+        "#,
+        breach.id,
+        breach.observed_latency_seconds,
+        breach.severity.as_str(),
+        recovered,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use std::{fmt::Display, str::FromStr};
+
+/// Observed latency (relative to the target) from which a breach is considered major.
+const MAJOR_BREACH_RATIO: f64 = 1.5;
+/// Observed latency (relative to the target) from which a breach is considered critical.
+const CRITICAL_BREACH_RATIO: f64 = 3.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Minor,
+    Major,
+    Critical,
+}
+
+impl Severity {
+    /// Classifies the observed latency against the target. Returns `None` when the target is met.
+    pub fn classify(observed_latency_seconds: f64, target_latency_seconds: f64) -> Option<Self> {
+        if observed_latency_seconds <= target_latency_seconds {
+            return None;
+        }
+
+        let ratio = observed_latency_seconds / target_latency_seconds;
+
+        Some(if ratio >= CRITICAL_BREACH_RATIO {
+            Severity::Critical
+        } else if ratio >= MAJOR_BREACH_RATIO {
+            Severity::Major
+        } else {
+            Severity::Minor
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Minor => "minor",
+            Severity::Major => "major",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown severity: {0}")]
+pub struct UnknownSeverity(String);
+
+impl FromStr for Severity {
+    type Err = UnknownSeverity;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minor" => Ok(Severity::Minor),
+            "major" => Ok(Severity::Major),
+            "critical" => Ok(Severity::Critical),
+            _ => Err(UnknownSeverity(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within_target() {
+        assert_eq!(Severity::classify(10.0, 30.0), None);
+        assert_eq!(Severity::classify(30.0, 30.0), None);
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(Severity::classify(31.0, 30.0), Some(Severity::Minor));
+        assert_eq!(Severity::classify(45.0, 30.0), Some(Severity::Major));
+        assert_eq!(Severity::classify(89.0, 30.0), Some(Severity::Major));
+        assert_eq!(Severity::classify(90.0, 30.0), Some(Severity::Critical));
+    }
+
+    #[test]
+    fn test_severity_ordering() {
+        assert!(Severity::Minor < Severity::Major);
+        assert!(Severity::Major < Severity::Critical);
+    }
+
+    #[test]
+    fn test_severity_roundtrip() {
+        for severity in [Severity::Minor, Severity::Major, Severity::Critical] {
+            assert_eq!(severity.as_str().parse::<Severity>().unwrap(), severity);
+        }
+
+        assert!("fatal".parse::<Severity>().is_err());
+    }
+}
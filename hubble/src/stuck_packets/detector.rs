@@ -0,0 +1,95 @@
+use time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::{
+    metrics::STUCK_PACKETS_COLLECTOR,
+    stuck_packets::{
+        postgres::{
+            count_stuck_packets, get_pending_packets, resolve_stuck_packets, upsert_stuck_packet,
+        },
+        reason::StuckReason,
+        StuckPacket, StuckPacketsConfig,
+    },
+};
+
+/// Number of pending packets that are fetched and classified at a time.
+const PENDING_PACKETS_PAGE_SIZE: i64 = 10_000;
+
+/// Scans for packets that are neither acknowledged nor timed out after `min_age_seconds`, and
+/// records them in `hubble.stuck_packets` with the reason they are stuck, classified by the
+/// indexed state of the counterparty chain. Recorded packets are resolved once they are
+/// acknowledged or timed out. The unresolved packets are exported per chain and reason, so
+/// relayer outages can be alerted on.
+pub async fn detect_stuck_packets(
+    db: &sqlx::PgPool,
+    config: &StuckPacketsConfig,
+) -> color_eyre::Result<()> {
+    let min_age = Duration::seconds(config.min_age_seconds.into());
+
+    // all pending packets are paged through, so packets that are stuck for long do not hide newly
+    // stuck packets. every page is committed on its own, so a run does not hold one transaction
+    // open over all pending packets
+    let mut tx = db.begin().await?;
+    let mut pending_packets =
+        get_pending_packets(&mut tx, config, None, PENDING_PACKETS_PAGE_SIZE).await?;
+    let mut total = 0;
+
+    loop {
+        total += pending_packets.len();
+
+        for packet in &pending_packets {
+            let stuck_packet = StuckPacket {
+                packet,
+                reason: StuckReason::classify(packet, min_age),
+            };
+
+            if upsert_stuck_packet(&mut tx, &stuck_packet).await? {
+                warn!(
+                    "detect: {packet} => stuck since {}: {}",
+                    packet.sent_at, stuck_packet.reason
+                );
+            }
+        }
+
+        tx.commit().await?;
+
+        match pending_packets.last() {
+            Some(last) if pending_packets.len() as i64 == PENDING_PACKETS_PAGE_SIZE => {
+                tx = db.begin().await?;
+                pending_packets = get_pending_packets(
+                    &mut tx,
+                    config,
+                    Some((last.sent_at, last.packet_hash.as_slice())),
+                    PENDING_PACKETS_PAGE_SIZE,
+                )
+                .await?;
+            }
+            _ => break,
+        }
+    }
+
+    debug!("detect: {total} pending packets");
+
+    let mut tx = db.begin().await?;
+
+    let resolved = resolve_stuck_packets(&mut tx).await?;
+
+    if resolved > 0 {
+        info!("detect: resolved {resolved} stuck packets");
+    }
+
+    let counts = count_stuck_packets(&mut tx).await?;
+
+    tx.commit().await?;
+
+    // chains and reasons without stuck packets are not exported
+    STUCK_PACKETS_COLLECTOR.reset();
+
+    for (universal_chain_id, reason, packets) in counts {
+        STUCK_PACKETS_COLLECTOR
+            .with_label_values(&[&universal_chain_id, &reason])
+            .set(packets);
+    }
+
+    Ok(())
+}
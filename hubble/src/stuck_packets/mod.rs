@@ -0,0 +1,83 @@
+use std::{fmt::Display, str::FromStr};
+
+use time::OffsetDateTime;
+
+use crate::stuck_packets::reason::StuckReason;
+
+mod detector;
+mod postgres;
+mod reason;
+
+/// Detection of stuck packets, passed as json (e.g. `{"min_age_seconds": 3600}`).
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StuckPacketsConfig {
+    /// Age after which a packet that is neither acknowledged nor timed out is considered stuck.
+    #[serde(default = "default_min_age_seconds")]
+    pub min_age_seconds: i32,
+    /// Packets sent before this age are not scanned anymore. Packets that were detected as stuck
+    /// before stay recorded until they are acknowledged or timed out.
+    #[serde(default = "default_max_age_seconds")]
+    pub max_age_seconds: i32,
+}
+
+fn default_min_age_seconds() -> i32 {
+    60 * 60
+}
+
+fn default_max_age_seconds() -> i32 {
+    7 * 24 * 60 * 60
+}
+
+impl FromStr for StuckPacketsConfig {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(item: &str) -> Result<Self, <Self as FromStr>::Err> {
+        serde_json::from_str(item).map_err(Into::into)
+    }
+}
+
+/// A packet that was sent at least `min_age_seconds` ago and is neither acknowledged nor timed
+/// out on its source chain, with the indexed state of its counterparty chain.
+#[derive(Clone, Debug)]
+pub struct PendingPacket {
+    packet_hash: Vec<u8>,
+    internal_chain_id: i32,
+    universal_chain_id: String,
+    internal_counterparty_chain_id: Option<i32>,
+    source_channel_id: i32,
+    destination_channel_id: i32,
+    height: i64,
+    sent_at: OffsetDateTime,
+    /// Timestamp of the last block indexed on the counterparty chain, if it is indexed.
+    counterparty_indexed_at: Option<OffsetDateTime>,
+    /// Whether the timeout of the packet passed on the counterparty chain, as far as it is indexed.
+    timeout_passed: bool,
+    received: bool,
+    ack_written: bool,
+}
+
+impl Display for PendingPacket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{}:{} 0x{}",
+            self.universal_chain_id,
+            self.source_channel_id,
+            hex::encode(&self.packet_hash),
+        ))
+    }
+}
+
+/// A pending packet that is classified as stuck.
+#[derive(Clone, Debug)]
+pub struct StuckPacket<'a> {
+    packet: &'a PendingPacket,
+    reason: StuckReason,
+}
+
+pub async fn detect_stuck_packets(
+    db: &sqlx::PgPool,
+    config: &StuckPacketsConfig,
+) -> color_eyre::Result<()> {
+    crate::stuck_packets::detector::detect_stuck_packets(db, config).await
+}
@@ -0,0 +1,159 @@
+use sqlx::Postgres;
+use time::OffsetDateTime;
+
+use crate::stuck_packets::{PendingPacket, StuckPacket, StuckPacketsConfig};
+
+/// Packets sent between `min_age_seconds` and `max_age_seconds` ago that are neither acknowledged
+/// nor timed out, oldest first, starting after the packet `after` (the send time and hash of the
+/// last packet of the previous page). The indexed state of the counterparty chain is taken from the
+/// indexer of that chain with the highest height.
+pub async fn get_pending_packets(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    config: &StuckPacketsConfig,
+    after: Option<(OffsetDateTime, &[u8])>,
+    limit: i64,
+) -> sqlx::Result<Vec<PendingPacket>> {
+    let (after_sent_at, after_packet_hash) = after.unzip();
+
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            send.packet_hash,
+            send.internal_chain_id,
+            c.family || '.' || c.chain_id AS "universal_chain_id!",
+            decoded.internal_counterparty_chain_id AS "internal_counterparty_chain_id?",
+            send.source_channel_id,
+            send.destination_channel_id,
+            send.height,
+            send.timestamp AS sent_at,
+            status.timestamp AS "counterparty_indexed_at?",
+            COALESCE(
+                (send.timeout_timestamp > 0 AND send.timeout_timestamp <= EXTRACT(EPOCH FROM status.timestamp) * 1000000000)
+                OR (send.timeout_height > 0 AND send.timeout_height <= status.height),
+                false
+            ) AS "timeout_passed!",
+            EXISTS (SELECT 1 FROM v2_sync.packet_recv_sync recv WHERE recv.packet_hash = send.packet_hash) AS "received!",
+            EXISTS (SELECT 1 FROM v2_sync.write_ack_sync ack WHERE ack.packet_hash = send.packet_hash) AS "ack_written!"
+        FROM v2_sync.packet_send_sync send
+        JOIN config.chains c ON c.id = send.internal_chain_id
+        LEFT JOIN v2_sync.packet_send_decoded_sync decoded
+            ON decoded.internal_chain_id = send.internal_chain_id
+            AND decoded.packet_hash = send.packet_hash
+        LEFT JOIN config.chains counterparty ON counterparty.id = decoded.internal_counterparty_chain_id
+        LEFT JOIN LATERAL (
+            SELECT s.height, s.timestamp
+            FROM hubble.indexer_status s
+            WHERE s.universal_chain_id = counterparty.family || '.' || counterparty.chain_id
+            ORDER BY s.height DESC
+            LIMIT 1
+        ) status ON true
+        WHERE send.timestamp < now() - $1::INT4 * INTERVAL '1 second'
+        AND send.timestamp >= now() - $2::INT4 * INTERVAL '1 second'
+        AND ($3::TIMESTAMPTZ IS NULL OR (send.timestamp, send.packet_hash) > ($3, $4::BYTEA))
+        AND NOT EXISTS (SELECT 1 FROM v2_sync.packet_ack_sync ack WHERE ack.packet_hash = send.packet_hash)
+        AND NOT EXISTS (SELECT 1 FROM v2_sync.packet_timeout_sync timeout WHERE timeout.packet_hash = send.packet_hash)
+        ORDER BY send.timestamp, send.packet_hash
+        LIMIT $5
+        "#,
+        config.min_age_seconds,
+        config.max_age_seconds,
+        after_sent_at,
+        after_packet_hash,
+        limit,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| PendingPacket {
+        packet_hash: record.packet_hash,
+        internal_chain_id: record.internal_chain_id,
+        universal_chain_id: record.universal_chain_id,
+        internal_counterparty_chain_id: record.internal_counterparty_chain_id,
+        source_channel_id: record.source_channel_id,
+        destination_channel_id: record.destination_channel_id,
+        height: record.height,
+        sent_at: record.sent_at,
+        counterparty_indexed_at: record.counterparty_indexed_at,
+        timeout_passed: record.timeout_passed,
+        received: record.received,
+        ack_written: record.ack_written,
+    })
+    .collect())
+}
+
+/// Records a stuck packet, or updates the reason of a packet that was recorded before. Returns
+/// whether the packet is newly detected.
+pub async fn upsert_stuck_packet(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    stuck_packet: &StuckPacket<'_>,
+) -> sqlx::Result<bool> {
+    let packet = stuck_packet.packet;
+
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO hubble.stuck_packets (packet_hash, internal_chain_id, internal_counterparty_chain_id, source_channel_id, destination_channel_id, height, sent_at, reason)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (packet_hash) DO UPDATE SET
+            reason = excluded.reason,
+            updated_at = now()
+        RETURNING (xmax = 0) AS "inserted!"
+        "#,
+        packet.packet_hash,
+        packet.internal_chain_id,
+        packet.internal_counterparty_chain_id,
+        packet.source_channel_id,
+        packet.destination_channel_id,
+        packet.height,
+        packet.sent_at,
+        stuck_packet.reason.as_str(),
+    )
+    .fetch_one(tx.as_mut())
+    .await?;
+
+    Ok(record.inserted)
+}
+
+/// Marks the stuck packets that got acknowledged or timed out since they were detected as
+/// resolved. Returns the number of resolved packets.
+pub async fn resolve_stuck_packets(tx: &mut sqlx::Transaction<'_, Postgres>) -> sqlx::Result<u64> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE hubble.stuck_packets stuck
+        SET
+            resolved_at = now(),
+            updated_at = now()
+        WHERE resolved_at IS NULL
+        AND (
+            EXISTS (SELECT 1 FROM v2_sync.packet_ack_sync ack WHERE ack.packet_hash = stuck.packet_hash)
+            OR EXISTS (SELECT 1 FROM v2_sync.packet_timeout_sync timeout WHERE timeout.packet_hash = stuck.packet_hash)
+        )
+        "#,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Number of unresolved stuck packets per source chain and reason.
+pub async fn count_stuck_packets(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> sqlx::Result<Vec<(String, String, i64)>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            c.family || '.' || c.chain_id AS "universal_chain_id!",
+            stuck.reason,
+            COUNT(*) AS "packets!"
+        FROM hubble.stuck_packets stuck
+        JOIN config.chains c ON c.id = stuck.internal_chain_id
+        WHERE stuck.resolved_at IS NULL
+        GROUP BY c.family, c.chain_id, stuck.reason
+        "#,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| (record.universal_chain_id, record.reason, record.packets))
+    .collect())
+}
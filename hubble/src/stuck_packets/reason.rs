@@ -0,0 +1,149 @@
+use std::fmt::Display;
+
+use time::Duration;
+
+use crate::stuck_packets::PendingPacket;
+
+/// Why a packet is stuck. Only `recv_not_relayed`, `ack_not_relayed` and `timeout_not_relayed`
+/// point at a relayer; the others are caused by the counterparty chain (or its indexer).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StuckReason {
+    /// The counterparty chain of the packet is not known, or not indexed.
+    CounterpartyNotIndexed,
+    /// The indexer of the counterparty chain is behind, so the packet may have been received
+    /// without being indexed yet.
+    CounterpartyLagging,
+    /// The packet is not received on the counterparty chain, and did not time out.
+    RecvNotRelayed,
+    /// The packet timed out on the counterparty chain, but the timeout is not relayed back.
+    TimeoutNotRelayed,
+    /// The packet is received, but the counterparty did not write an acknowledgement (ie. the
+    /// application acknowledges asynchronously).
+    AckNotWritten,
+    /// The acknowledgement is written on the counterparty chain, but not relayed back.
+    AckNotRelayed,
+}
+
+impl StuckReason {
+    /// Classifies a packet that is pending for at least `min_age`.
+    pub fn classify(packet: &PendingPacket, min_age: Duration) -> Self {
+        if packet.received {
+            return if packet.ack_written {
+                StuckReason::AckNotRelayed
+            } else {
+                StuckReason::AckNotWritten
+            };
+        }
+
+        match packet.counterparty_indexed_at {
+            None => StuckReason::CounterpartyNotIndexed,
+            Some(indexed_at) if indexed_at < packet.sent_at + min_age => {
+                StuckReason::CounterpartyLagging
+            }
+            Some(_) if packet.timeout_passed => StuckReason::TimeoutNotRelayed,
+            Some(_) => StuckReason::RecvNotRelayed,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StuckReason::CounterpartyNotIndexed => "counterparty_not_indexed",
+            StuckReason::CounterpartyLagging => "counterparty_lagging",
+            StuckReason::RecvNotRelayed => "recv_not_relayed",
+            StuckReason::TimeoutNotRelayed => "timeout_not_relayed",
+            StuckReason::AckNotWritten => "ack_not_written",
+            StuckReason::AckNotRelayed => "ack_not_relayed",
+        }
+    }
+}
+
+impl Display for StuckReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    const MIN_AGE: Duration = Duration::hours(1);
+
+    fn packet(counterparty_indexed_at: Option<OffsetDateTime>) -> PendingPacket {
+        PendingPacket {
+            packet_hash: vec![0; 32],
+            internal_chain_id: 1,
+            universal_chain_id: "union.union-testnet-10".to_string(),
+            internal_counterparty_chain_id: Some(2),
+            source_channel_id: 1,
+            destination_channel_id: 2,
+            height: 100,
+            sent_at: OffsetDateTime::UNIX_EPOCH,
+            counterparty_indexed_at,
+            timeout_passed: false,
+            received: false,
+            ack_written: false,
+        }
+    }
+
+    #[test]
+    fn test_counterparty_state() {
+        assert_eq!(
+            StuckReason::classify(&packet(None), MIN_AGE),
+            StuckReason::CounterpartyNotIndexed
+        );
+        assert_eq!(
+            StuckReason::classify(
+                &packet(Some(OffsetDateTime::UNIX_EPOCH + Duration::minutes(59))),
+                MIN_AGE
+            ),
+            StuckReason::CounterpartyLagging
+        );
+        assert_eq!(
+            StuckReason::classify(
+                &packet(Some(OffsetDateTime::UNIX_EPOCH + Duration::hours(2))),
+                MIN_AGE
+            ),
+            StuckReason::RecvNotRelayed
+        );
+    }
+
+    #[test]
+    fn test_timeout_not_relayed() {
+        let packet = PendingPacket {
+            timeout_passed: true,
+            ..packet(Some(OffsetDateTime::UNIX_EPOCH + Duration::hours(2)))
+        };
+
+        assert_eq!(
+            StuckReason::classify(&packet, MIN_AGE),
+            StuckReason::TimeoutNotRelayed
+        );
+    }
+
+    #[test]
+    fn test_received() {
+        // received packets are classified regardless of the lag of the counterparty indexer
+        let packet = PendingPacket {
+            received: true,
+            ..packet(None)
+        };
+
+        assert_eq!(
+            StuckReason::classify(&packet, MIN_AGE),
+            StuckReason::AckNotWritten
+        );
+        assert_eq!(
+            StuckReason::classify(
+                &PendingPacket {
+                    ack_written: true,
+                    ..packet
+                },
+                MIN_AGE
+            ),
+            StuckReason::AckNotRelayed
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use std::{fmt::Display, str::FromStr};
+
+use sqlx::types::BigDecimal;
+use time::OffsetDateTime;
+use url::Url;
+
+mod monitor;
+mod postgres;
+
+/// Alerting on token buckets, passed as json (e.g. `{"webhook": "https://...", "threshold": 0.1}`).
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenBucketAlertConfig {
+    /// Receives a json `POST` when a bucket drops below the threshold and when it recovers.
+    pub webhook: Url,
+    /// Fraction of the capacity below which a bucket is reported.
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+}
+
+fn default_threshold() -> f64 {
+    0.1
+}
+
+impl FromStr for TokenBucketAlertConfig {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(item: &str) -> Result<Self, <Self as FromStr>::Err> {
+        serde_json::from_str(item).map_err(Into::into)
+    }
+}
+
+/// Current state of the rate limit of a token on a chain, as projected from its last update in
+/// `v2_sync.token_bucket_state`.
+#[derive(Clone, Debug)]
+pub struct TokenBucketState {
+    internal_chain_id: i32,
+    universal_chain_id: String,
+    denom: Vec<u8>,
+    capacity: BigDecimal,
+    /// Tokens per second that are added to the bucket, up to its capacity.
+    refill_rate: BigDecimal,
+    /// Position of the update the state is projected from.
+    height: i64,
+    event_index: i32,
+    /// Estimated tokens in the bucket at `available_at`.
+    available: BigDecimal,
+    available_at: OffsetDateTime,
+    /// Whether the bucket was reported to be below the threshold and did not recover yet.
+    alerted: bool,
+}
+
+impl Display for TokenBucketState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{}: 0x{} ({}/{} +{}/s)",
+            self.universal_chain_id,
+            hex::encode(&self.denom),
+            self.available,
+            self.capacity,
+            self.refill_rate,
+        ))
+    }
+}
+
+/// Tokens taken from a bucket by a transfer that was received on the chain of the bucket.
+#[derive(Clone, Debug)]
+pub struct Consumption {
+    timestamp: OffsetDateTime,
+    amount: BigDecimal,
+}
+
+pub async fn monitor_token_buckets(
+    db: &sqlx::PgPool,
+    alerts: Option<&TokenBucketAlertConfig>,
+) -> color_eyre::Result<()> {
+    crate::token_bucket_monitor::monitor::monitor_token_buckets(db, alerts).await
+}
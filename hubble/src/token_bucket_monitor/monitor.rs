@@ -0,0 +1,222 @@
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+use sqlx::types::BigDecimal;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    token_bucket_monitor::{
+        postgres::{get_consumptions, get_token_bucket_states, update_token_bucket_state},
+        Consumption, TokenBucketAlertConfig, TokenBucketState,
+    },
+    webhook::enqueue_delivery,
+};
+
+/// Estimates the tokens left in every bucket by replaying the transfers received since the last
+/// estimate, and reports buckets that drop below the configured threshold to the webhook. Buckets
+/// are considered full after an update. The estimate is approximate: it only takes transfers into
+/// account that hubble indexed, and misses transfers that are indexed after a later estimate.
+pub async fn monitor_token_buckets(
+    db: &sqlx::PgPool,
+    alerts: Option<&TokenBucketAlertConfig>,
+) -> color_eyre::Result<()> {
+    let states = get_token_bucket_states(&mut db.begin().await?).await?;
+
+    for state in states {
+        debug!("monitor: {state}");
+
+        match monitor_token_bucket(db, alerts, &state).await {
+            Ok(_) => debug!("monitor: {state} => success"),
+            Err(error) => error!("monitor: {state} => error: {error:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+async fn monitor_token_bucket(
+    db: &sqlx::PgPool,
+    alerts: Option<&TokenBucketAlertConfig>,
+    state: &TokenBucketState,
+) -> color_eyre::Result<()> {
+    let mut tx = db.begin().await?;
+
+    let consumptions = get_consumptions(&mut tx, state).await?;
+    let (available, available_at) = consume(state, &consumptions);
+    let current = refill(state, &available, available_at, OffsetDateTime::now_utc());
+
+    let below = match alerts {
+        Some(alerts) => {
+            current < &state.capacity * BigDecimal::from_str(&alerts.threshold.to_string())?
+        }
+        None => false,
+    };
+
+    let alert = match (alerts, state.alerted, below) {
+        (Some(alerts), false, true) => {
+            warn!("alert: {state} => below threshold ({current})");
+            Some((alerts, "below_threshold"))
+        }
+        (Some(alerts), true, false) => {
+            info!("alert: {state} => recovered ({current})");
+            Some((alerts, "recovered"))
+        }
+        _ => None,
+    };
+
+    let updated =
+        update_token_bucket_state(&mut tx, state, &available, available_at, below).await?;
+
+    // the alert is sent once the transaction commits, and not at all if the bucket was updated
+    // on chain in the meantime
+    if let (true, Some((alerts, status))) = (updated, alert) {
+        enqueue_delivery(
+            &mut tx,
+            &alerts.webhook,
+            "token_bucket_alert",
+            &alert_idempotency_key(state, status, available_at),
+            &alert_payload(alerts, state, &current, status)?,
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Takes the consumptions from the bucket in order, refilling it in between. Returns the tokens
+/// left after the last consumption and its time.
+fn consume(state: &TokenBucketState, consumptions: &[Consumption]) -> (BigDecimal, OffsetDateTime) {
+    consumptions.iter().fold(
+        (state.available.clone(), state.available_at),
+        |(available, available_at), consumption| {
+            let available = refill(state, &available, available_at, consumption.timestamp)
+                - &consumption.amount;
+            let zero = BigDecimal::from(0);
+
+            (available.max(zero), consumption.timestamp)
+        },
+    )
+}
+
+/// Tokens in the bucket at `at`, given the tokens available at `available_at`.
+fn refill(
+    state: &TokenBucketState,
+    available: &BigDecimal,
+    available_at: OffsetDateTime,
+    at: OffsetDateTime,
+) -> BigDecimal {
+    let elapsed_milliseconds = (at - available_at).whole_milliseconds().max(0);
+    let elapsed = BigDecimal::from(i64::try_from(elapsed_milliseconds).unwrap_or(i64::MAX))
+        / BigDecimal::from(1000);
+
+    (available + &state.refill_rate * elapsed).min(state.capacity.clone())
+}
+
+/// Identifies an alert of a bucket across retries. A bucket changes its status at most once per
+/// estimate, so the time of the estimate tells alerts with the same status apart.
+fn alert_idempotency_key(
+    state: &TokenBucketState,
+    status: &str,
+    available_at: OffsetDateTime,
+) -> String {
+    format!(
+        "token_bucket:{}:0x{}:{}:{}",
+        state.universal_chain_id,
+        hex::encode(&state.denom),
+        status,
+        available_at.unix_timestamp_nanos(),
+    )
+}
+
+fn alert_payload(
+    alerts: &TokenBucketAlertConfig,
+    state: &TokenBucketState,
+    available: &BigDecimal,
+    status: &str,
+) -> color_eyre::Result<Value> {
+    Ok(json!({
+        "status": status,
+        "universal_chain_id": state.universal_chain_id,
+        "denom": format!("0x{}", hex::encode(&state.denom)),
+        "capacity": state.capacity.to_string(),
+        "refill_rate": state.refill_rate.to_string(),
+        "available": available.to_string(),
+        "threshold": alerts.threshold,
+        "timestamp": OffsetDateTime::now_utc().format(&Rfc3339)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+
+    use super::*;
+
+    fn state(available_at: OffsetDateTime) -> TokenBucketState {
+        TokenBucketState {
+            internal_chain_id: 1,
+            universal_chain_id: "ethereum.1".to_string(),
+            denom: vec![0xaa],
+            capacity: BigDecimal::from(100),
+            refill_rate: BigDecimal::from(2),
+            height: 1,
+            event_index: 0,
+            available: BigDecimal::from(100),
+            available_at,
+            alerted: false,
+        }
+    }
+
+    fn consumption(timestamp: OffsetDateTime, amount: i64) -> Consumption {
+        Consumption {
+            timestamp,
+            amount: BigDecimal::from(amount),
+        }
+    }
+
+    #[test]
+    fn consume_refills_between_transfers() {
+        let start = OffsetDateTime::UNIX_EPOCH;
+        let state = state(start);
+
+        let (available, available_at) = consume(
+            &state,
+            &[
+                consumption(start + Duration::seconds(1), 80),
+                consumption(start + Duration::milliseconds(5500), 50),
+                consumption(start + Duration::seconds(6), 10),
+            ],
+        );
+
+        // 100 - 80 = 20, + 4.5 * 2 - 50 => 0 (clamped), + 0.5 * 2 - 10 => 0
+        assert_eq!(available, BigDecimal::from(0));
+        assert_eq!(available_at, start + Duration::seconds(6));
+
+        assert_eq!(
+            refill(
+                &state,
+                &available,
+                available_at,
+                start + Duration::milliseconds(10250)
+            ),
+            BigDecimal::from_str("8.5").unwrap()
+        );
+    }
+
+    #[test]
+    fn refill_is_capped_at_capacity() {
+        let start = OffsetDateTime::UNIX_EPOCH;
+        let state = state(start);
+
+        let (available, _) = consume(&state, &[consumption(start, 30)]);
+
+        assert_eq!(available, BigDecimal::from(70));
+        assert_eq!(
+            refill(&state, &available, start, start + Duration::hours(1)),
+            BigDecimal::from(100)
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use sqlx::{types::BigDecimal, Postgres};
+use time::OffsetDateTime;
+
+use crate::token_bucket_monitor::{Consumption, TokenBucketState};
+
+pub async fn get_token_bucket_states(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> sqlx::Result<Vec<TokenBucketState>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            state.internal_chain_id,
+            chains.family || '.' || chains.chain_id AS "universal_chain_id!",
+            state.denom,
+            state.capacity,
+            state.refill_rate,
+            state.height,
+            state.event_index,
+            state.available,
+            state.available_at,
+            state.alerted
+        FROM v2_sync.token_bucket_state state
+        JOIN config.chains chains ON chains.id = state.internal_chain_id
+        ORDER BY state.internal_chain_id, state.denom
+        "#,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| TokenBucketState {
+        internal_chain_id: record.internal_chain_id,
+        universal_chain_id: record.universal_chain_id,
+        denom: record.denom,
+        capacity: record.capacity,
+        refill_rate: record.refill_rate,
+        height: record.height,
+        event_index: record.event_index,
+        available: record.available,
+        available_at: record.available_at,
+        alerted: record.alerted,
+    })
+    .collect())
+}
+
+/// Transfers of the token of the bucket that were received on its chain after `available_at`,
+/// in the order they were received.
+pub async fn get_consumptions(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    state: &TokenBucketState,
+) -> sqlx::Result<Vec<Consumption>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT recv.timestamp, transfer.quote_amount
+        FROM v2_sync.packet_send_transfers_sync transfer
+        JOIN v2_sync.packet_recv_sync recv
+            ON recv.packet_hash = transfer.packet_hash
+            AND recv.internal_chain_id = transfer.internal_counterparty_chain_id
+        WHERE transfer.internal_counterparty_chain_id = $1
+        AND transfer.quote_token = $2
+        AND recv.timestamp > $3
+        ORDER BY recv.timestamp, recv.height, recv.event_index
+        "#,
+        state.internal_chain_id,
+        &state.denom[..],
+        state.available_at,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| Consumption {
+        timestamp: record.timestamp,
+        amount: record.quote_amount,
+    })
+    .collect())
+}
+
+/// Updates the estimate of a bucket, unless it was updated on chain in the meantime. Returns
+/// whether the estimate was updated.
+pub async fn update_token_bucket_state(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    state: &TokenBucketState,
+    available: &BigDecimal,
+    available_at: OffsetDateTime,
+    alerted: bool,
+) -> sqlx::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE v2_sync.token_bucket_state
+        SET available = $3, available_at = $4, alerted = $5
+        WHERE internal_chain_id = $1 AND denom = $2 AND height = $6 AND event_index = $7
+        "#,
+        state.internal_chain_id,
+        &state.denom[..],
+        available,
+        available_at,
+        alerted,
+        state.height,
+        state.event_index,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
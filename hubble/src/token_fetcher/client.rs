@@ -4,6 +4,8 @@ use reqwest::StatusCode;
 use serde::{de, Deserialize, Deserializer};
 use tracing::{debug, info};
 
+use crate::token_fetcher::formatting::SymbolPosition;
+
 #[derive(Debug, thiserror::Error)]
 pub enum FetcherClientError {
     #[error("error fetching tokens from {0}: {1}")]
@@ -106,14 +108,24 @@ impl Display for Extensions {
 pub struct HubbleExtension {
     #[serde(with = "::serde_utils::string_opt")]
     pub instantiate_height: Option<u64>,
+    #[serde(default)]
+    pub display_precision: Option<u32>,
+    #[serde(default)]
+    pub symbol_position: Option<SymbolPosition>,
 }
 
 impl Display for HubbleExtension {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "instantiate_height: {}",
+            "instantiate_height: {}, display_precision: {}, symbol_position: {}",
             self.instantiate_height
                 .map(|h| h.to_string())
+                .unwrap_or_else(|| "None".to_string()),
+            self.display_precision
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "None".to_string()),
+            self.symbol_position
+                .map(|p| p.to_string())
                 .unwrap_or_else(|| "None".to_string())
         ))
     }
@@ -6,6 +6,7 @@ use tracing::{debug, error, info};
 
 use crate::token_fetcher::{
     client::{get_tokens, FetcherClientError, Token},
+    formatting::FormattingHints,
     postgres::{
         delete_token_representation, get_internal_chain_id_by_chain_id, get_token_representations,
         get_token_sources, update_token_source, upsert_token_representation,
@@ -151,11 +152,15 @@ pub async fn update_tokens_for_source(
             .and_then(|e| e.hubble.as_ref())
             .and_then(|h| h.instantiate_height);
 
+        let formatting_hints_from_source = FormattingHints::from_token(token_from_source);
+
         if token_from_source.decimals != token_from_db.decimals
             || token_from_source.logo_uri != token_from_db.logo_uri
             || token_from_source.name != token_from_db.name
             || token_from_source.symbol != token_from_db.symbol
             || instantiate_height_from_source != token_from_db.instantiate_height
+            || formatting_hints_from_source.display_precision != token_from_db.display_precision
+            || formatting_hints_from_source.symbol_position != token_from_db.symbol_position
         {
             debug!("update token: {token_from_db} <> {token_from_source}");
 
@@ -168,6 +173,8 @@ pub async fn update_tokens_for_source(
                 decimals: token_from_source.decimals,
                 logo_uri: token_from_source.logo_uri.clone(),
                 instantiate_height: instantiate_height_from_source,
+                display_precision: formatting_hints_from_source.display_precision,
+                symbol_position: formatting_hints_from_source.symbol_position,
             };
 
             upsert_token_representation(&mut tx, &token_representation)
@@ -190,6 +197,8 @@ pub async fn update_tokens_for_source(
 
         debug!("new token: {token_from_source}");
 
+        let formatting_hints = FormattingHints::from_token(token_from_source);
+
         let token_representation = TokenRepresentation {
             token_source_id: token_source.id,
             internal_chain_id: new_token_key.internal_chain_id,
@@ -203,6 +212,8 @@ pub async fn update_tokens_for_source(
                 .as_ref()
                 .and_then(|e| e.hubble.as_ref())
                 .and_then(|h| h.instantiate_height),
+            display_precision: formatting_hints.display_precision,
+            symbol_position: formatting_hints.symbol_position,
         };
 
         upsert_token_representation(&mut tx, &token_representation)
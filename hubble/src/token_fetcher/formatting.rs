@@ -0,0 +1,167 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::token_fetcher::client::Token;
+
+/// Upper bound for the number of fractional digits shown by default, regardless of the
+/// number of decimals of the token.
+const MAX_DEFAULT_DISPLAY_PRECISION: i32 = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolPosition {
+    Prefix,
+    Suffix,
+}
+
+impl SymbolPosition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SymbolPosition::Prefix => "prefix",
+            SymbolPosition::Suffix => "suffix",
+        }
+    }
+}
+
+impl Display for SymbolPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown symbol position: {0}")]
+pub struct UnknownSymbolPosition(String);
+
+impl FromStr for SymbolPosition {
+    type Err = UnknownSymbolPosition;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prefix" => Ok(SymbolPosition::Prefix),
+            "suffix" => Ok(SymbolPosition::Suffix),
+            _ => Err(UnknownSymbolPosition(s.to_string())),
+        }
+    }
+}
+
+/// Hints that allow frontends to render amounts of a token identically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormattingHints {
+    /// Preferred number of fractional digits when displaying an amount.
+    pub display_precision: i32,
+    pub symbol_position: SymbolPosition,
+}
+
+impl FormattingHints {
+    /// Derives the hints from the token metadata. Overrides in the hubble extension take
+    /// precedence, but the display precision never exceeds the decimals of the token.
+    pub fn from_token(token: &Token) -> Self {
+        let hubble = token.extensions.as_ref().and_then(|e| e.hubble.as_ref());
+
+        let decimals = token.decimals.max(0);
+
+        let display_precision = hubble
+            .and_then(|h| h.display_precision)
+            .map(|precision| i32::try_from(precision).unwrap_or(i32::MAX))
+            .unwrap_or(MAX_DEFAULT_DISPLAY_PRECISION)
+            .min(decimals);
+
+        let symbol_position = hubble
+            .and_then(|h| h.symbol_position)
+            .unwrap_or(SymbolPosition::Suffix);
+
+        FormattingHints {
+            display_precision,
+            symbol_position,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token_fetcher::client::{Extensions, HubbleExtension};
+
+    fn token(decimals: i32, extension: Option<HubbleExtension>) -> Token {
+        Token {
+            chain_id: "1".to_string(),
+            address: vec![1, 2, 3],
+            name: "Token".to_string(),
+            symbol: "TKN".to_string(),
+            decimals,
+            logo_uri: None,
+            extensions: extension.map(|hubble| Extensions {
+                hubble: Some(hubble),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_default_hints() {
+        assert_eq!(
+            FormattingHints::from_token(&token(18, None)),
+            FormattingHints {
+                display_precision: 6,
+                symbol_position: SymbolPosition::Suffix,
+            }
+        );
+
+        assert_eq!(
+            FormattingHints::from_token(&token(2, None)).display_precision,
+            2
+        );
+
+        assert_eq!(
+            FormattingHints::from_token(&token(0, None)).display_precision,
+            0
+        );
+    }
+
+    #[test]
+    fn test_hints_from_extension() {
+        let hints = FormattingHints::from_token(&token(
+            18,
+            Some(HubbleExtension {
+                instantiate_height: None,
+                display_precision: Some(2),
+                symbol_position: Some(SymbolPosition::Prefix),
+            }),
+        ));
+
+        assert_eq!(
+            hints,
+            FormattingHints {
+                display_precision: 2,
+                symbol_position: SymbolPosition::Prefix,
+            }
+        );
+    }
+
+    #[test]
+    fn test_display_precision_bounded_by_decimals() {
+        let hints = FormattingHints::from_token(&token(
+            6,
+            Some(HubbleExtension {
+                instantiate_height: None,
+                display_precision: Some(8),
+                symbol_position: None,
+            }),
+        ));
+
+        assert_eq!(hints.display_precision, 6);
+    }
+
+    #[test]
+    fn test_symbol_position_roundtrip() {
+        for position in [SymbolPosition::Prefix, SymbolPosition::Suffix] {
+            assert_eq!(
+                position.as_str().parse::<SymbolPosition>().unwrap(),
+                position
+            );
+        }
+
+        assert!("middle".parse::<SymbolPosition>().is_err());
+    }
+}
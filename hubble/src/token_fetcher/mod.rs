@@ -1,7 +1,10 @@
 use std::fmt::Display;
 
+use crate::token_fetcher::formatting::SymbolPosition;
+
 mod client;
 mod fetcher;
+mod formatting;
 mod postgres;
 
 #[derive(Clone, Debug)]
@@ -28,18 +31,22 @@ pub struct TokenRepresentation {
     decimals: i32,
     logo_uri: Option<String>,
     instantiate_height: Option<u64>,
+    display_precision: i32,
+    symbol_position: SymbolPosition,
 }
 
 impl Display for TokenRepresentation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "{}.{}:{}, {} ({}) [{}] - {}{}",
+            "{}.{}:{}, {} ({}) [{} / {} {}] - {}{}",
             self.token_source_id,
             self.internal_chain_id,
             hex::encode(&self.address),
             self.symbol,
             self.name,
             self.decimals,
+            self.display_precision,
+            self.symbol_position,
             self.logo_uri.as_deref().unwrap_or("None"),
             self.instantiate_height
                 .map(|h| format!(" instantiated at {h}"))
@@ -31,9 +31,9 @@ pub async fn get_token_representations(
     tx: &mut sqlx::Transaction<'_, Postgres>,
     token_source: &TokenSource,
 ) -> sqlx::Result<Vec<TokenRepresentation>> {
-    Ok(sqlx::query!(
+    sqlx::query!(
         r#"
-        SELECT token_source_id, internal_chain_id, address, symbol, name, decimals, logo_uri, instantiate_height
+        SELECT token_source_id, internal_chain_id, address, symbol, name, decimals, logo_uri, instantiate_height, display_precision, symbol_position
         FROM token.token_source_representations
         WHERE token_source_id = $1
         "#,
@@ -42,17 +42,24 @@ pub async fn get_token_representations(
     .fetch_all(tx.as_mut())
     .await?
     .into_iter()
-    .map(|record| TokenRepresentation {
-        token_source_id: record.token_source_id,
-        internal_chain_id: record.internal_chain_id,
-        address: record.address,
-        symbol: record.symbol,
-        name: record.name,
-        decimals: record.decimals,
-        logo_uri: record.logo_uri,
-        instantiate_height: record.instantiate_height.map(|h| h.try_into().unwrap()),
+    .map(|record| {
+        Ok(TokenRepresentation {
+            token_source_id: record.token_source_id,
+            internal_chain_id: record.internal_chain_id,
+            address: record.address,
+            symbol: record.symbol,
+            name: record.name,
+            decimals: record.decimals,
+            logo_uri: record.logo_uri,
+            instantiate_height: record.instantiate_height.map(|h| h.try_into().unwrap()),
+            display_precision: record.display_precision,
+            symbol_position: record
+                .symbol_position
+                .parse()
+                .map_err(|error| sqlx::Error::Decode(Box::new(error)))?,
+        })
     })
-    .collect())
+    .collect()
 }
 
 pub async fn delete_token_representation(
@@ -80,15 +87,17 @@ pub async fn upsert_token_representation(
 ) -> sqlx::Result<()> {
     sqlx::query!(
         "
-        INSERT INTO token.token_source_representations (token_source_id, internal_chain_id, address, symbol, name, decimals, logo_uri, instantiate_height)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        INSERT INTO token.token_source_representations (token_source_id, internal_chain_id, address, symbol, name, decimals, logo_uri, instantiate_height, display_precision, symbol_position)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
         ON CONFLICT (token_source_id, internal_chain_id, address) DO 
         UPDATE SET
             symbol = excluded.symbol,
             name = excluded.name,
             decimals = excluded.decimals,
             logo_uri = excluded.logo_uri,
-            instantiate_height = excluded.instantiate_height
+            instantiate_height = excluded.instantiate_height,
+            display_precision = excluded.display_precision,
+            symbol_position = excluded.symbol_position
         ",
         token_representation.token_source_id,
         token_representation.internal_chain_id,
@@ -98,6 +107,8 @@ pub async fn upsert_token_representation(
         token_representation.decimals,
         token_representation.logo_uri,
         token_representation.instantiate_height.map(|h| h.try_into().unwrap()) as std::option::Option<i64>,
+        token_representation.display_precision,
+        token_representation.symbol_position.as_str(),
     )
     .execute(tx.as_mut())
     .await?;
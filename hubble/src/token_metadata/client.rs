@@ -0,0 +1,188 @@
+use alloy::{
+    primitives::Address,
+    rpc::types::{TransactionInput, TransactionRequest},
+    serde::WithOtherFields,
+    transports::{RpcError, TransportErrorKind},
+};
+use alloy_sol_types::{sol, SolCall};
+use cometbft_rpc::JsonRpcError;
+use protos::cosmos::bank::v1beta1::{
+    Metadata, QueryDenomMetadataRequest, QueryDenomMetadataResponse,
+};
+use tracing::debug;
+
+use crate::{
+    indexer::{
+        ethereum::provider::Provider as EthProvider, tendermint::provider::Provider as TmProvider,
+    },
+    token_metadata::{MetadataSource, TokenMetadata},
+};
+
+sol! {
+    function name() external view returns (string);
+    function symbol() external view returns (string);
+    function decimals() external view returns (uint8);
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MetadataClientError {
+    #[error("error calling erc20 {0}: {1}")]
+    Erc20Call(Address, RpcError<TransportErrorKind>),
+    #[error("error querying denom metadata of {0}: {1}")]
+    DenomMetadataQuery(String, JsonRpcError),
+}
+
+/// Fetches `name`, `symbol` and `decimals` of an erc20. Tokens that do not implement
+/// `decimals` are reported as unavailable; `name` and `symbol` are optional in the standard.
+pub async fn get_erc20_metadata(
+    provider: &EthProvider,
+    denom: &[u8],
+) -> Result<TokenMetadata, MetadataClientError> {
+    let Ok(address) = Address::try_from(denom) else {
+        debug!("not an erc20 address: {}", hex::encode(denom));
+        return Ok(TokenMetadata::unavailable());
+    };
+
+    let Some(decimals) = erc20_call(provider, address, decimalsCall {}).await? else {
+        return Ok(TokenMetadata::unavailable());
+    };
+
+    Ok(TokenMetadata {
+        symbol: erc20_call(provider, address, symbolCall {}).await?,
+        name: erc20_call(provider, address, nameCall {}).await?,
+        decimals: Some(decimals.into()),
+        source: MetadataSource::Erc20,
+    })
+}
+
+/// Returns `None` if the call reverts or its result cannot be decoded.
+async fn erc20_call<C: SolCall>(
+    provider: &EthProvider,
+    address: Address,
+    call: C,
+) -> Result<Option<C::Return>, MetadataClientError> {
+    let request = WithOtherFields::new(TransactionRequest {
+        to: Some(address.into()),
+        input: TransactionInput::new(call.abi_encode().into()),
+        ..Default::default()
+    });
+
+    match provider.call(&request, None).await {
+        Ok(result) => Ok(C::abi_decode_returns(&result.response).ok()),
+        Err(RpcError::ErrorResp(error)) => {
+            debug!("{address}: {} reverted: {error}", C::SIGNATURE);
+            Ok(None)
+        }
+        Err(error) => Err(MetadataClientError::Erc20Call(address, error)),
+    }
+}
+
+/// Fetches the bank denom metadata of a native denom. Denoms without registered metadata (such
+/// as cw20 addresses) are reported as unavailable.
+pub async fn get_bank_denom_metadata(
+    provider: &TmProvider,
+    denom: &[u8],
+) -> Result<TokenMetadata, MetadataClientError> {
+    let Ok(denom) = std::str::from_utf8(denom) else {
+        debug!("not a utf8 denom: {}", hex::encode(denom));
+        return Ok(TokenMetadata::unavailable());
+    };
+
+    let result = provider
+        .grpc_abci_query::<_, QueryDenomMetadataResponse>(
+            "/cosmos.bank.v1beta1.Query/DenomMetadata",
+            &QueryDenomMetadataRequest {
+                denom: denom.to_string(),
+            },
+            None,
+        )
+        .await
+        .map_err(|error| MetadataClientError::DenomMetadataQuery(denom.to_string(), error))?
+        .response;
+
+    if result.code.is_err() {
+        debug!("no denom metadata for {denom}: {}", result.log);
+        return Ok(TokenMetadata::unavailable());
+    }
+
+    Ok(result
+        .value
+        .and_then(|response| response.metadata)
+        .map(from_bank_metadata)
+        .unwrap_or_else(TokenMetadata::unavailable))
+}
+
+/// The decimals of a denom are the exponent of its display unit.
+fn from_bank_metadata(metadata: Metadata) -> TokenMetadata {
+    let decimals = metadata
+        .denom_units
+        .iter()
+        .find(|unit| unit.denom == metadata.display)
+        .and_then(|unit| i32::try_from(unit.exponent).ok());
+
+    TokenMetadata {
+        symbol: Some(metadata.symbol).filter(|symbol| !symbol.is_empty()),
+        name: Some(metadata.name).filter(|name| !name.is_empty()),
+        decimals,
+        source: MetadataSource::BankDenomMetadata,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protos::cosmos::bank::v1beta1::DenomUnit;
+
+    use super::*;
+
+    fn denom_unit(denom: &str, exponent: u32) -> DenomUnit {
+        DenomUnit {
+            denom: denom.to_string(),
+            exponent,
+            aliases: vec![],
+        }
+    }
+
+    #[test]
+    fn test_bank_metadata_decimals_from_display_unit() {
+        let metadata = Metadata {
+            description: "The native staking token of Union.".to_string(),
+            denom_units: vec![denom_unit("muno", 0), denom_unit("uno", 6)],
+            base: "muno".to_string(),
+            display: "uno".to_string(),
+            name: "Union".to_string(),
+            symbol: "U".to_string(),
+            uri: String::new(),
+            uri_hash: String::new(),
+        };
+
+        assert_eq!(
+            from_bank_metadata(metadata),
+            TokenMetadata {
+                symbol: Some("U".to_string()),
+                name: Some("Union".to_string()),
+                decimals: Some(6),
+                source: MetadataSource::BankDenomMetadata,
+            }
+        );
+    }
+
+    #[test]
+    fn test_bank_metadata_without_display_unit() {
+        let metadata = Metadata {
+            denom_units: vec![denom_unit("muno", 0)],
+            base: "muno".to_string(),
+            display: "uno".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_bank_metadata(metadata),
+            TokenMetadata {
+                symbol: None,
+                name: None,
+                decimals: None,
+                source: MetadataSource::BankDenomMetadata,
+            }
+        );
+    }
+}
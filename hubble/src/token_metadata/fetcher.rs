@@ -0,0 +1,93 @@
+use tracing::{debug, error, info, trace};
+
+use crate::token_metadata::{
+    client::{get_bank_denom_metadata, get_erc20_metadata, MetadataClientError},
+    postgres::{
+        apply_token_metadata_to_transfers, get_tokens_without_metadata, upsert_token_metadata,
+    },
+    ChainProvider, MissingToken, Providers,
+};
+
+/// Maximum number of tokens fetched per run, to limit the load on the rpc endpoints.
+const BATCH_SIZE: i64 = 100;
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateTokenMetadataError {
+    #[error("client error fetching metadata of {0}: {1}")]
+    FetchMetadata(MissingToken, MetadataClientError),
+
+    #[error("database error creating transaction for {0}: {1}")]
+    CreateTransaction(MissingToken, sqlx::Error),
+
+    #[error("database error inserting metadata of {0}: {1}")]
+    InsertTokenMetadata(MissingToken, sqlx::Error),
+
+    #[error("database error committing transaction for {0}: {1}")]
+    CommitTransaction(MissingToken, sqlx::Error),
+}
+
+pub async fn update_token_metadata(
+    db: &sqlx::PgPool,
+    providers: &Providers,
+) -> color_eyre::Result<()> {
+    info!("Starting token metadata update process.");
+
+    let missing_tokens = get_tokens_without_metadata(
+        &mut db.begin().await?,
+        &providers.universal_chain_ids(),
+        BATCH_SIZE,
+    )
+    .await?;
+
+    for missing_token in missing_tokens {
+        debug!("process: {missing_token}");
+
+        let Some(provider) = providers.get(&missing_token.universal_chain_id) else {
+            trace!("process: {missing_token} => no provider");
+            continue;
+        };
+
+        match update_token_metadata_for_token(db, provider, &missing_token).await {
+            Ok(_) => debug!("process: {missing_token} => success"),
+            Err(error) => error!("process: {missing_token} => error: {error:?}"),
+        }
+    }
+
+    let mut tx = db.begin().await?;
+    let updated = apply_token_metadata_to_transfers(&mut tx).await?;
+    tx.commit().await?;
+
+    info!("Finished token metadata update process (updated {updated} transfers).");
+    Ok(())
+}
+
+async fn update_token_metadata_for_token(
+    db: &sqlx::PgPool,
+    provider: &ChainProvider,
+    token: &MissingToken,
+) -> Result<(), UpdateTokenMetadataError> {
+    let metadata = match provider {
+        ChainProvider::Ethereum(provider) => get_erc20_metadata(provider, &token.denom).await,
+        ChainProvider::Tendermint(provider) => {
+            get_bank_denom_metadata(provider, &token.denom).await
+        }
+    }
+    .map_err(|error| UpdateTokenMetadataError::FetchMetadata(token.clone(), error))?;
+
+    debug!("metadata: {token} => {metadata}");
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|error| UpdateTokenMetadataError::CreateTransaction(token.clone(), error))?;
+
+    upsert_token_metadata(&mut tx, token, &metadata)
+        .await
+        .map_err(|error| UpdateTokenMetadataError::InsertTokenMetadata(token.clone(), error))?;
+
+    tx.commit()
+        .await
+        .map_err(|error| UpdateTokenMetadataError::CommitTransaction(token.clone(), error))?;
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use tracing::info;
+
+use crate::{
+    indexer::{api::IndexerError, event::types::BlockHeight},
+    token_metadata::postgres::insert_pending_tokens_of_transfers,
+};
+
+/// Registers the tokens of transfers that were enriched before tokens were registered for metadata
+/// fetching. Heights are processed in batches of `batch_size`, each in its own transaction, so an
+/// interrupted migration can be resumed from the last reported height.
+pub async fn register_tokens_of_transfers(
+    db: &sqlx::PgPool,
+    internal_chain_id: Option<i32>,
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+    batch_size: u64,
+) -> Result<u64, IndexerError> {
+    info!("register tokens: {from_height}..{to_height} (batch size: {batch_size})");
+
+    let mut registered = 0;
+    let mut batch_from = from_height;
+
+    while batch_from < to_height {
+        let batch_to = BlockHeight(batch_from.0.saturating_add(batch_size).min(to_height.0));
+
+        let mut tx = db.begin().await?;
+        let batch_registered = insert_pending_tokens_of_transfers(
+            &mut tx,
+            internal_chain_id,
+            height_pg_value(batch_from)?,
+            height_pg_value(batch_to)?,
+        )
+        .await?;
+        tx.commit().await?;
+
+        info!("register tokens: {batch_from}..{batch_to} => registered {batch_registered}");
+
+        registered += batch_registered;
+        batch_from = batch_to;
+    }
+
+    info!("register tokens: {from_height}..{to_height} => done (registered {registered})");
+
+    Ok(registered)
+}
+
+fn height_pg_value(height: BlockHeight) -> Result<i64, IndexerError> {
+    i64::try_from(height.0).map_err(|_| {
+        IndexerError::InternalCannotMapToDatabaseDomain(
+            "block-height-i64".to_string(),
+            height.to_string(),
+        )
+    })
+}
@@ -0,0 +1,175 @@
+use std::{collections::HashMap, fmt::Display};
+
+use cometbft_rpc::ClientOptions;
+use url::Url;
+
+use crate::{
+    indexer::{
+        api::IndexerError,
+        ethereum::provider::Provider as EthProvider,
+        event::types::{BlockHeight, Denom, UniversalChainId},
+        tendermint::provider::Provider as TmProvider,
+    },
+    token_metadata::postgres::insert_pending_token,
+};
+
+mod client;
+mod fetcher;
+mod migration;
+mod postgres;
+
+/// Rpc endpoints of a chain, used to fetch the metadata of its tokens.
+#[derive(Clone, Debug)]
+pub enum Endpoint {
+    /// Erc20 metadata, fetched with `eth_call`.
+    Ethereum(Vec<Url>),
+    /// Bank denom metadata, fetched with a grpc query over abci.
+    Tendermint(Vec<Url>),
+}
+
+#[derive(Clone, Debug)]
+pub enum ChainProvider {
+    Ethereum(EthProvider),
+    Tendermint(TmProvider),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Providers(HashMap<UniversalChainId, ChainProvider>);
+
+impl Providers {
+    pub async fn new(
+        endpoints: impl IntoIterator<Item = (UniversalChainId, Endpoint)>,
+    ) -> Result<Self, IndexerError> {
+        let mut providers = HashMap::new();
+
+        for (universal_chain_id, endpoint) in endpoints {
+            let provider = match endpoint {
+                Endpoint::Ethereum(rpc_urls) => ChainProvider::Ethereum(EthProvider::new(rpc_urls)),
+                Endpoint::Tendermint(rpc_urls) => ChainProvider::Tendermint(
+                    TmProvider::new(rpc_urls, ClientOptions::default()).await?,
+                ),
+            };
+
+            providers.insert(universal_chain_id, provider);
+        }
+
+        Ok(Self(providers))
+    }
+
+    fn get(&self, universal_chain_id: &UniversalChainId) -> Option<&ChainProvider> {
+        self.0.get(universal_chain_id)
+    }
+
+    fn universal_chain_ids(&self) -> Vec<String> {
+        self.0.keys().map(|id| id.0.clone()).collect()
+    }
+}
+
+/// A token used in a transfer for which no metadata has been fetched yet.
+#[derive(Clone, Debug)]
+pub struct MissingToken {
+    internal_chain_id: i32,
+    universal_chain_id: UniversalChainId,
+    denom: Vec<u8>,
+}
+
+impl Display for MissingToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{}:{}",
+            self.universal_chain_id,
+            hex::encode(&self.denom),
+        ))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenMetadata {
+    symbol: Option<String>,
+    name: Option<String>,
+    decimals: Option<i32>,
+    source: MetadataSource,
+}
+
+impl TokenMetadata {
+    /// Metadata of a token that does not expose any, which is retried after a day.
+    fn unavailable() -> Self {
+        Self {
+            symbol: None,
+            name: None,
+            decimals: None,
+            source: MetadataSource::Unavailable,
+        }
+    }
+}
+
+impl Display for TokenMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{} ({}) [{}] - {}",
+            self.symbol.as_deref().unwrap_or("None"),
+            self.name.as_deref().unwrap_or("None"),
+            self.decimals
+                .map(|decimals| decimals.to_string())
+                .unwrap_or("None".to_string()),
+            self.source.as_str(),
+        ))
+    }
+}
+
+/// Source of the fetched metadata of a token. Tokens that are registered but not fetched yet have
+/// source `pending`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataSource {
+    Erc20,
+    BankDenomMetadata,
+    Unavailable,
+}
+
+impl MetadataSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetadataSource::Erc20 => "erc20",
+            MetadataSource::BankDenomMetadata => "bank_denom_metadata",
+            MetadataSource::Unavailable => "unavailable",
+        }
+    }
+}
+
+pub async fn update_token_metadata(
+    db: &sqlx::PgPool,
+    providers: &Providers,
+) -> color_eyre::Result<()> {
+    crate::token_metadata::fetcher::update_token_metadata(db, providers).await
+}
+
+/// Registers the tokens of a transfer, such that their metadata is fetched.
+pub async fn register_transfer_tokens(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    internal_chain_id: i32,
+    base_token: &Denom,
+    internal_counterparty_chain_id: i32,
+    quote_token: &Denom,
+) -> sqlx::Result<()> {
+    insert_pending_token(tx, internal_chain_id, &base_token.0).await?;
+    insert_pending_token(tx, internal_counterparty_chain_id, &quote_token.0).await?;
+
+    Ok(())
+}
+
+pub async fn register_tokens_of_transfers(
+    db: &sqlx::PgPool,
+    internal_chain_id: Option<i32>,
+    from_height: BlockHeight,
+    to_height: BlockHeight,
+    batch_size: u64,
+) -> Result<u64, IndexerError> {
+    crate::token_metadata::migration::register_tokens_of_transfers(
+        db,
+        internal_chain_id,
+        from_height,
+        to_height,
+        batch_size,
+    )
+    .await
+}
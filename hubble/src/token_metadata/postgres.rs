@@ -0,0 +1,141 @@
+use sqlx::Postgres;
+
+use crate::token_metadata::{MissingToken, TokenMetadata};
+
+/// Registered tokens of the given chains without metadata, and tokens that did not expose
+/// metadata more than a day ago, least recently fetched first.
+pub async fn get_tokens_without_metadata(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    universal_chain_ids: &[String],
+    limit: i64,
+) -> sqlx::Result<Vec<MissingToken>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT m.internal_chain_id, c.family || '.' || c.chain_id AS "universal_chain_id!", m.denom
+        FROM token.token_metadata m
+        JOIN config.chains c ON c.id = m.internal_chain_id
+        WHERE c.family || '.' || c.chain_id = ANY($1)
+        AND (m.source = 'pending' OR (m.source = 'unavailable' AND m.fetched_at < now() - INTERVAL '1 day'))
+        ORDER BY m.fetched_at
+        LIMIT $2
+        "#,
+        universal_chain_ids,
+        limit,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| MissingToken {
+        internal_chain_id: record.internal_chain_id,
+        universal_chain_id: record.universal_chain_id.into(),
+        denom: record.denom,
+    })
+    .collect())
+}
+
+/// Registers a token used in a transfer, such that its metadata is fetched. Tokens that are
+/// registered already are left as is.
+pub async fn insert_pending_token(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    denom: &[u8],
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        "
+        INSERT INTO token.token_metadata (internal_chain_id, denom, source, fetched_at)
+        VALUES ($1, $2, 'pending', now())
+        ON CONFLICT (internal_chain_id, denom) DO NOTHING
+        ",
+        internal_chain_id,
+        denom,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Registers the tokens of the transfers sent in `from_height..to_height`. Returns the number of
+/// newly registered tokens.
+pub async fn insert_pending_tokens_of_transfers(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    internal_chain_id: Option<i32>,
+    from_height: i64,
+    to_height: i64,
+) -> sqlx::Result<u64> {
+    Ok(sqlx::query!(
+        "
+        INSERT INTO token.token_metadata (internal_chain_id, denom, source, fetched_at)
+        SELECT internal_chain_id, base_token, 'pending', now()
+        FROM v2_sync.packet_send_transfers_sync
+        WHERE height >= $1 AND height < $2
+        AND ($3::INT4 IS NULL OR internal_chain_id = $3)
+        UNION
+        SELECT internal_counterparty_chain_id, quote_token, 'pending', now()
+        FROM v2_sync.packet_send_transfers_sync
+        WHERE height >= $1 AND height < $2
+        AND ($3::INT4 IS NULL OR internal_chain_id = $3)
+        ON CONFLICT (internal_chain_id, denom) DO NOTHING
+        ",
+        from_height,
+        to_height,
+        internal_chain_id,
+    )
+    .execute(tx.as_mut())
+    .await?
+    .rows_affected())
+}
+
+pub async fn upsert_token_metadata(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    token: &MissingToken,
+    metadata: &TokenMetadata,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        "
+        INSERT INTO token.token_metadata (internal_chain_id, denom, symbol, name, decimals, source, fetched_at)
+        VALUES ($1, $2, $3, $4, $5, $6, now())
+        ON CONFLICT (internal_chain_id, denom) DO
+        UPDATE SET
+            symbol = excluded.symbol,
+            name = excluded.name,
+            decimals = excluded.decimals,
+            source = excluded.source,
+            fetched_at = excluded.fetched_at
+        ",
+        token.internal_chain_id,
+        token.denom,
+        metadata.symbol,
+        metadata.name,
+        metadata.decimals,
+        metadata.source.as_str(),
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Fills in the decimals of transfers that did not carry them (transfer v0), together with the
+/// symbol and name if those are empty. Returns the number of updated transfers.
+pub async fn apply_token_metadata_to_transfers(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+) -> sqlx::Result<u64> {
+    Ok(sqlx::query!(
+        r#"
+        UPDATE v2_sync.packet_send_transfers_sync t
+        SET
+            base_token_decimals = m.decimals,
+            base_token_symbol = CASE WHEN t.base_token_symbol = '' THEN COALESCE(m.symbol, '') ELSE t.base_token_symbol END,
+            base_token_name = CASE WHEN t.base_token_name = '' THEN COALESCE(m.name, '') ELSE t.base_token_name END
+        FROM token.token_metadata m
+        WHERE t.base_token_decimals IS NULL
+        AND m.decimals IS NOT NULL
+        AND m.internal_chain_id = t.internal_chain_id
+        AND m.denom = t.base_token
+        "#,
+    )
+    .execute(tx.as_mut())
+    .await?
+    .rows_affected())
+}
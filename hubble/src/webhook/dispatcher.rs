@@ -0,0 +1,118 @@
+use rand::Rng;
+use reqwest::header::CONTENT_TYPE;
+use time::OffsetDateTime;
+use tracing::{debug, info, warn};
+
+use crate::webhook::{
+    postgres::{claim_next_due_delivery, record_attempt},
+    retry_delay, sign, DeliveryStatus, WebhookDelivery, EVENT_TYPE_HEADER, IDEMPOTENCY_KEY_HEADER,
+    MAX_ATTEMPTS, SIGNATURE_HEADER,
+};
+
+/// Seconds a delivery is claimed for while it is sent, which is well above the timeout of the
+/// request.
+const CLAIM_LEASE_SECONDS: i32 = 60;
+
+/// Sends all pending deliveries that are due. Each delivery is claimed (see
+/// [`claim_next_due_delivery`]) before it is sent, so concurrent dispatchers never send the same
+/// delivery at the same time, without holding a lock during the request. A delivery is only sent
+/// again when recording its outcome fails, in which case consumers can drop the duplicate by its
+/// idempotency key.
+///
+/// Payloads are signed with `secret` when it is configured (see [`sign`]).
+pub async fn dispatch_webhooks(
+    db: &sqlx::PgPool,
+    client: &reqwest::Client,
+    secret: Option<&str>,
+) -> color_eyre::Result<()> {
+    loop {
+        let Some(delivery) = claim_next_due_delivery(db, CLAIM_LEASE_SECONDS).await? else {
+            return Ok(());
+        };
+
+        debug!(
+            "webhook: delivering {} to {} (attempt {})",
+            delivery.idempotency_key,
+            delivery.endpoint,
+            delivery.attempts + 1
+        );
+
+        let (status_code, error) = send(client, secret, &delivery).await;
+        let attempts = delivery.attempts + 1;
+
+        let (status, next_attempt_at) = match &error {
+            None => {
+                info!(
+                    "webhook: delivered {} to {}",
+                    delivery.idempotency_key, delivery.endpoint
+                );
+                (DeliveryStatus::Delivered, None)
+            }
+            Some(error) if attempts >= MAX_ATTEMPTS => {
+                warn!(
+                    "webhook: giving up on {} to {} after {attempts} attempts: {error}",
+                    delivery.idempotency_key, delivery.endpoint
+                );
+                (DeliveryStatus::Failed, None)
+            }
+            Some(error) => {
+                let delay = retry_delay(attempts, rand::thread_rng().gen_range(0.0..=1.0));
+                warn!(
+                    "webhook: failed to deliver {} to {} (attempt {attempts}), retrying in {delay:?}: {error}",
+                    delivery.idempotency_key, delivery.endpoint
+                );
+                (
+                    DeliveryStatus::Pending,
+                    Some(OffsetDateTime::now_utc() + delay),
+                )
+            }
+        };
+
+        record_attempt(
+            db,
+            delivery.id,
+            status,
+            status_code,
+            error.as_deref(),
+            next_attempt_at,
+        )
+        .await?;
+    }
+}
+
+/// Posts the payload to the endpoint. Returns the status code of the response (if any) and the
+/// error, if the endpoint did not accept the delivery.
+async fn send(
+    client: &reqwest::Client,
+    secret: Option<&str>,
+    delivery: &WebhookDelivery,
+) -> (Option<i32>, Option<String>) {
+    let body = match serde_json::to_vec(&delivery.payload) {
+        Ok(body) => body,
+        Err(error) => return (None, Some(error.to_string())),
+    };
+
+    let mut request = client
+        .post(&delivery.endpoint)
+        .header(CONTENT_TYPE, "application/json")
+        .header(IDEMPOTENCY_KEY_HEADER, &delivery.idempotency_key)
+        .header(EVENT_TYPE_HEADER, &delivery.event_type);
+
+    if let Some(secret) = secret {
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        request = request.header(SIGNATURE_HEADER, sign(secret.as_bytes(), timestamp, &body));
+    }
+
+    match request.body(body).send().await {
+        Ok(response) => {
+            let status = response.status();
+            let error = (!status.is_success()).then(|| format!("unexpected status {status}"));
+
+            (Some(i32::from(status.as_u16())), error)
+        }
+        Err(error) => (
+            error.status().map(|status| i32::from(status.as_u16())),
+            Some(error.to_string()),
+        ),
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use time::OffsetDateTime;
+
+mod dispatcher;
+mod postgres;
+
+pub use crate::webhook::{
+    dispatcher::dispatch_webhooks,
+    postgres::{enqueue_delivery, get_deliveries, redrive_delivery, redrive_failed_deliveries},
+};
+
+/// Signature of the payload, `t=<unix timestamp>,v1=<hex hmac-sha256 of "<timestamp>.<body>">`.
+pub const SIGNATURE_HEADER: &str = "x-hubble-signature";
+/// Key that is the same for every attempt of a delivery, used by consumers to drop duplicates.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const EVENT_TYPE_HEADER: &str = "x-hubble-event";
+
+/// Deliveries are given up after this many failed attempts.
+pub const MAX_ATTEMPTS: i32 = 12;
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(10);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// A payload for a webhook endpoint, as recorded in `hubble.webhook_deliveries`.
+#[derive(Clone, Debug, Serialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub endpoint: String,
+    pub event_type: String,
+    pub idempotency_key: String,
+    pub payload: Value,
+    /// One of `pending`, `delivered` or `failed` (given up after [`MAX_ATTEMPTS`]).
+    pub status: String,
+    pub attempts: i32,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub next_attempt_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub delivered_at: Option<OffsetDateTime>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Signs the body of a delivery sent at `timestamp` (unix seconds). The timestamp is part of the
+/// signature so consumers can reject replayed deliveries.
+pub fn sign(secret: &[u8], timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("hmac accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    format!(
+        "t={timestamp},v1={}",
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+/// Delay before the next attempt after `attempts` failed attempts. The delay doubles with every
+/// attempt (capped at [`MAX_RETRY_DELAY`]), and `jitter` (in `0.0..=1.0`) picks a point in its
+/// upper half, so endpoints that recover are not hit by all retries at once.
+pub fn retry_delay(attempts: i32, jitter: f64) -> Duration {
+    let exponent = u32::try_from(attempts.saturating_sub(1)).unwrap_or_default();
+    let delay = MIN_RETRY_DELAY
+        .saturating_mul(2u32.saturating_pow(exponent))
+        .min(MAX_RETRY_DELAY);
+
+    delay / 2 + (delay / 2).mul_f64(jitter.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_includes_timestamp() {
+        assert_eq!(
+            sign(b"secret", 1700000000, br#"{"status":"recovered"}"#),
+            "t=1700000000,v1=20d6b5c5a10047bce0d94724a4ff65a8ec4746f0cc5208509cc66489e36bdeb9"
+        );
+    }
+
+    #[test]
+    fn retry_delay_grows_exponentially() {
+        assert_eq!(retry_delay(1, 0.0), Duration::from_secs(5));
+        assert_eq!(retry_delay(1, 1.0), Duration::from_secs(10));
+        assert_eq!(retry_delay(2, 1.0), Duration::from_secs(20));
+        assert_eq!(retry_delay(4, 0.5), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn retry_delay_is_capped() {
+        assert_eq!(retry_delay(MAX_ATTEMPTS, 1.0), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(i32::MAX, 0.0), MAX_RETRY_DELAY / 2);
+    }
+}
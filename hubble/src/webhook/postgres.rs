@@ -0,0 +1,220 @@
+use serde_json::Value;
+use sqlx::{PgPool, Postgres};
+use time::OffsetDateTime;
+use url::Url;
+
+use crate::webhook::{DeliveryStatus, WebhookDelivery};
+
+/// Schedules a payload for delivery to the endpoint. Payloads with an idempotency key that was
+/// already scheduled for the endpoint are ignored. Returns whether the delivery was scheduled.
+///
+/// The delivery is only sent once `tx` is committed, so it should be scheduled in the same
+/// transaction as the change it reports.
+pub async fn enqueue_delivery(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    endpoint: &Url,
+    event_type: &str,
+    idempotency_key: &str,
+    payload: &Value,
+) -> sqlx::Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO hubble.webhook_deliveries (endpoint, event_type, idempotency_key, payload)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (endpoint, idempotency_key) DO NOTHING
+        "#,
+        endpoint.as_str(),
+        event_type,
+        idempotency_key,
+        payload,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Claims the pending delivery that is due the longest, by moving its next attempt `lease_seconds`
+/// into the future. Other dispatchers skip the delivery while it is claimed, and it is attempted
+/// again once the lease expires if its outcome is never recorded (ie. the dispatcher stopped
+/// while sending it).
+pub async fn claim_next_due_delivery(
+    db: &PgPool,
+    lease_seconds: i32,
+) -> sqlx::Result<Option<WebhookDelivery>> {
+    Ok(sqlx::query!(
+        r#"
+        UPDATE hubble.webhook_deliveries
+        SET next_attempt_at = now() + $1::INT4 * INTERVAL '1 second'
+        WHERE id = (
+            SELECT id
+            FROM hubble.webhook_deliveries
+            WHERE status = 'pending' AND next_attempt_at <= now()
+            ORDER BY next_attempt_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            id,
+            endpoint,
+            event_type,
+            idempotency_key,
+            payload,
+            status,
+            attempts,
+            last_status_code,
+            last_error,
+            created_at,
+            next_attempt_at,
+            delivered_at
+        "#,
+        lease_seconds,
+    )
+    .fetch_optional(db)
+    .await?
+    .map(|record| WebhookDelivery {
+        id: record.id,
+        endpoint: record.endpoint,
+        event_type: record.event_type,
+        idempotency_key: record.idempotency_key,
+        payload: record.payload,
+        status: record.status,
+        attempts: record.attempts,
+        last_status_code: record.last_status_code,
+        last_error: record.last_error,
+        created_at: record.created_at,
+        next_attempt_at: record.next_attempt_at,
+        delivered_at: record.delivered_at,
+    }))
+}
+
+/// Records the outcome of an attempt. `next_attempt_at` is only set for deliveries that are
+/// still pending.
+pub async fn record_attempt(
+    db: &PgPool,
+    id: i64,
+    status: DeliveryStatus,
+    status_code: Option<i32>,
+    error: Option<&str>,
+    next_attempt_at: Option<OffsetDateTime>,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE hubble.webhook_deliveries
+        SET
+            status = $2,
+            attempts = attempts + 1,
+            last_status_code = $3,
+            last_error = $4,
+            last_attempt_at = now(),
+            next_attempt_at = $5,
+            delivered_at = CASE WHEN $2 = 'delivered' THEN now() END
+        WHERE id = $1
+        "#,
+        id,
+        status.as_str(),
+        status_code,
+        error,
+        next_attempt_at,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Deliveries, newest first, optionally filtered by endpoint and status. Pages continue before
+/// `before_id`.
+pub async fn get_deliveries(
+    db: &PgPool,
+    endpoint: Option<&str>,
+    status: Option<&str>,
+    before_id: Option<i64>,
+    limit: i64,
+) -> sqlx::Result<Vec<WebhookDelivery>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT
+            id,
+            endpoint,
+            event_type,
+            idempotency_key,
+            payload,
+            status,
+            attempts,
+            last_status_code,
+            last_error,
+            created_at,
+            next_attempt_at,
+            delivered_at
+        FROM hubble.webhook_deliveries
+        WHERE ($1::TEXT IS NULL OR endpoint = $1)
+        AND ($2::TEXT IS NULL OR status = $2)
+        AND ($3::BIGINT IS NULL OR id < $3)
+        ORDER BY id DESC
+        LIMIT $4
+        "#,
+        endpoint,
+        status,
+        before_id,
+        limit,
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|record| WebhookDelivery {
+        id: record.id,
+        endpoint: record.endpoint,
+        event_type: record.event_type,
+        idempotency_key: record.idempotency_key,
+        payload: record.payload,
+        status: record.status,
+        attempts: record.attempts,
+        last_status_code: record.last_status_code,
+        last_error: record.last_error,
+        created_at: record.created_at,
+        next_attempt_at: record.next_attempt_at,
+        delivered_at: record.delivered_at,
+    })
+    .collect())
+}
+
+/// Schedules a failed delivery to be sent again, with a fresh retry budget. Returns whether the
+/// delivery exists and had failed.
+pub async fn redrive_delivery(db: &PgPool, id: i64) -> sqlx::Result<bool> {
+    Ok(sqlx::query!(
+        r#"
+        UPDATE hubble.webhook_deliveries
+        SET
+            status = 'pending',
+            attempts = 0,
+            next_attempt_at = now()
+        WHERE id = $1 AND status = 'failed'
+        RETURNING id
+        "#,
+        id,
+    )
+    .fetch_optional(db)
+    .await?
+    .is_some())
+}
+
+/// Schedules all failed deliveries (optionally only to `endpoint`) to be sent again, with a fresh
+/// retry budget. Returns the number of deliveries that are scheduled.
+pub async fn redrive_failed_deliveries(db: &PgPool, endpoint: Option<&str>) -> sqlx::Result<u64> {
+    Ok(sqlx::query!(
+        r#"
+        UPDATE hubble.webhook_deliveries
+        SET
+            status = 'pending',
+            attempts = 0,
+            next_attempt_at = now()
+        WHERE status = 'failed'
+        AND ($1::TEXT IS NULL OR endpoint = $1)
+        "#,
+        endpoint,
+    )
+    .execute(db)
+    .await?
+    .rows_affected())
+}
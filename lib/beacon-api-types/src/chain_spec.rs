@@ -18,6 +18,7 @@ pub struct Mainnet;
     serde(rename_all = "snake_case")
 )]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub enum PresetBaseKind {
     Minimal,
     Mainnet,
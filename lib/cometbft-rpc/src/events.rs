@@ -0,0 +1,103 @@
+use ::serde::de::DeserializeOwned;
+use cometbft_types::abci::event::Event;
+use cosmos_sdk_event::CosmosSdkEvent;
+use tracing::trace;
+
+use crate::rpc_types::BlockResultsResponse;
+
+/// An event decoded as a `T`, see [`decode_events`].
+pub type DecodedEvent<T> = Result<CosmosSdkEvent<T>, cosmos_sdk_event::Error>;
+
+/// Where an event in the results of a block was emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventOrigin {
+    /// Emitted by the transaction at this index in the block.
+    Tx(u32),
+    /// Emitted outside of any transaction (i.e. by a begin, end or finalize block hook).
+    Block,
+}
+
+impl BlockResultsResponse {
+    /// The events of every transaction, along with the index of the transaction in the block.
+    /// Attributes are already decoded for nodes that base64 encode them, see
+    /// [`Client::block_results`].
+    ///
+    /// [`Client::block_results`]: crate::Client::block_results
+    pub fn tx_events(&self) -> impl Iterator<Item = (u32, &[Event])> {
+        self.txs_results
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(idx, tx_result)| {
+                (
+                    idx.try_into().expect("too many transactions"),
+                    &*tx_result.events,
+                )
+            })
+    }
+
+    /// The events that were not emitted by a transaction. See [`Client::block_results`] for how
+    /// these are returned for nodes running CometBFT < v0.38.
+    ///
+    /// [`Client::block_results`]: crate::Client::block_results
+    pub fn block_events(&self) -> &[Event] {
+        self.finalize_block_events.as_deref().unwrap_or_default()
+    }
+
+    /// All events of the block, the events of the transactions first.
+    pub fn events(&self) -> impl Iterator<Item = (EventOrigin, &Event)> {
+        self.tx_events()
+            .flat_map(|(idx, events)| {
+                events
+                    .iter()
+                    .map(move |event| (EventOrigin::Tx(idx), event))
+            })
+            .chain(
+                self.block_events()
+                    .iter()
+                    .map(|event| (EventOrigin::Block, event)),
+            )
+    }
+
+    /// All events of the block that are a `T`, see [`decode_events`].
+    pub fn typed_events<'a, T: DeserializeOwned + 'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (EventOrigin, DecodedEvent<T>)> + 'a {
+        self.events()
+            .filter_map(|(origin, event)| decode_event(event.clone()).map(|event| (origin, event)))
+    }
+}
+
+/// Decode `events` as `T` (i.e. an enum of IBC events). Events that are not a `T` are skipped,
+/// while events with malformed well-known attributes (such as `msg_index`) are returned as errors.
+pub fn decode_events<T: DeserializeOwned>(
+    events: impl IntoIterator<Item = Event>,
+) -> impl Iterator<Item = DecodedEvent<T>> {
+    events.into_iter().filter_map(decode_event)
+}
+
+fn decode_event<T: DeserializeOwned>(event: Event) -> Option<DecodedEvent<T>> {
+    let ty = event.ty.clone();
+
+    match CosmosSdkEvent::new(event) {
+        Err(cosmos_sdk_event::Error::Deserialize(error)) => {
+            trace!(%ty, "unable to parse event: {error}");
+            None
+        }
+        res => Some(res),
+    }
+}
+
+/// The values of all attributes with the key `key`, in events of type `ty` if provided.
+pub fn attribute_values<'a>(
+    events: &'a [Event],
+    ty: Option<&'a str>,
+    key: &'a str,
+) -> impl Iterator<Item = &'a str> + 'a {
+    events
+        .iter()
+        .filter(move |event| ty.is_none_or(|ty| event.ty == ty))
+        .flat_map(|event| &event.attributes)
+        .filter(move |attribute| attribute.key == key)
+        .map(|attribute| &*attribute.value)
+}
@@ -2,21 +2,27 @@ use core::fmt;
 use std::{
     fmt::Debug,
     num::{NonZeroU32, NonZeroU64, NonZeroU8},
+    sync::{Arc, OnceLock},
     time::Duration,
 };
 
-use ::serde::de::DeserializeOwned;
+use ::serde::{de::DeserializeOwned, Deserialize, Serialize};
+use futures::TryFutureExt;
 use jsonrpsee::{
     core::{
         client::{BatchResponse, ClientT},
+        middleware::layer::RpcLogger,
         params::BatchRequestBuilder,
         traits::ToRpcParams,
     },
-    http_client::{HttpClient, HttpClientBuilder},
+    http_client::{transport::HttpBackend, HttpClient, HttpClientBuilder, RpcService},
     rpc_params,
     ws_client::{PingConfig, WsClientBuilder},
 };
-use tracing::{debug, debug_span, instrument, trace, Instrument};
+use rate_limiter::{RateLimit, RateLimiter};
+use tokio_util::sync::CancellationToken;
+use tower_http::decompression::{Decompression, DecompressionLayer};
+use tracing::{debug, debug_span, instrument, trace, warn, Instrument};
 use unionlabs::{
     bounded::{BoundedI64, BoundedU8},
     option_unwrap,
@@ -24,18 +30,31 @@ use unionlabs::{
     result_unwrap, ErrorReporter,
 };
 
-use crate::rpc_types::{
-    AbciQueryResponse, AllValidatorsResponse, BlockResponse, BlockResultsResponse,
-    BlockchainResponse, BroadcastTxSyncResponse, CommitResponse, GrpcAbciQueryResponse, Order,
-    StatusResponse, TxResponse, TxSearchResponse, ValidatorsResponse,
+use crate::{
+    rpc_types::{
+        AbciQueryResponse, AllValidatorsResponse, BlockResponse, BlockResultsResponse,
+        BlockSearchResponse, BlockchainResponse, BroadcastTxSyncResponse, CommitResponse,
+        ConsensusParamsResponse, GrpcAbciQueryResponse, NewBlockEvent, Order, StatusResponse,
+        TxEvent, TxResponse, TxSearchResponse, ValidatorsResponse,
+    },
+    search::search_all,
+    stream::StreamingClient,
+    subscription::Subscription,
+    version::decode_event_attributes,
 };
 
 #[cfg(test)]
 mod tests;
 
+pub mod events;
 pub mod rpc_types;
+mod search;
 pub mod serde;
+mod stream;
+pub mod subscription;
+pub mod version;
 pub use cometbft_types as types;
+pub use version::CometbftVersion;
 
 pub type JsonRpcError = jsonrpsee::core::client::Error;
 
@@ -44,15 +63,52 @@ pub struct Client {
     inner: ClientInner,
 }
 
+/// Options for the connection to the node, see [`Client::new_with_options`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientOptions {
+    /// The maximum size of a response, in bytes. For compressed responses, this is the size after
+    /// decompression.
+    pub max_response_size: u32,
+    /// Deserialize the responses of [`Client::block`] and [`Client::block_results`] while they are
+    /// being received, instead of buffering the full response first. This bounds the memory used
+    /// for fetching chains with blocks of multiple megabytes. Only supported for http(s) urls.
+    pub stream_large_responses: bool,
+    /// Request gzip compressed responses. Only supported for http(s) urls.
+    pub gzip: bool,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            max_response_size: 100 * 1024 * 1024,
+            stream_large_responses: false,
+            gzip: true,
+        }
+    }
+}
+
 impl Client {
     pub async fn new(url: impl AsRef<str>) -> Result<Self, JsonRpcError> {
+        Self::new_with_options(url, ClientOptions::default()).await
+    }
+
+    pub async fn new_with_options(
+        url: impl AsRef<str>,
+        options: ClientOptions,
+    ) -> Result<Self, JsonRpcError> {
         let url = url.as_ref().to_owned();
 
-        let inner = match url.split_once("://") {
+        let mut streaming_client = None;
+
+        let transport = match url.split_once("://") {
             Some(("ws" | "wss", _)) => {
+                let ws_url = url.clone();
+
                 let client = reconnecting_jsonrpc_ws_client::Client::new(move || {
                     WsClientBuilder::default()
                         .enable_ws_ping(PingConfig::new())
+                        .max_response_size(options.max_response_size)
                         .build(url.clone())
                         .instrument(debug_span!("cometbft_rpc_client", %url))
                 });
@@ -63,17 +119,74 @@ impl Client {
                     .await
                     .map_err(|e| JsonRpcError::Custom(e.to_string()))?;
 
-                ClientInner::Ws(client)
+                Transport::Ws(client, ws_url)
+            }
+            Some(("http" | "https", _)) => {
+                if options.stream_large_responses {
+                    streaming_client = Some(StreamingClient::new(url.clone(), &options)?);
+                }
+
+                Transport::Http(Box::new(
+                    HttpClientBuilder::default()
+                        .max_response_size(options.max_response_size)
+                        .set_http_middleware(
+                            tower::ServiceBuilder::new()
+                                .layer(DecompressionLayer::new().gzip(options.gzip)),
+                        )
+                        .build(url)?,
+                ))
             }
-            Some(("http" | "https", _)) => ClientInner::Http(Box::new(
-                HttpClientBuilder::default()
-                    .max_response_size(100 * 1024 * 1024)
-                    .build(url)?,
-            )),
             _ => return Err(JsonRpcError::Custom(format!("invalid url {url}"))),
         };
 
-        Ok(Self { inner })
+        if options.stream_large_responses && streaming_client.is_none() {
+            warn!("streaming responses is only supported for http(s) urls");
+        }
+
+        Ok(Self {
+            inner: ClientInner {
+                transport,
+                streaming_client,
+                rate_limiter: None,
+                version: Arc::new(OnceLock::new()),
+            },
+        })
+    }
+
+    /// Limit the requests of this client to `rate_limit`, if set. Requests over the limit are
+    /// queued until they are allowed.
+    #[must_use]
+    pub fn with_rate_limit(mut self, rate_limit: Option<RateLimit>) -> Self {
+        self.inner.rate_limiter = rate_limit.map(RateLimiter::new);
+        self
+    }
+
+    /// Use the response formats of `version`, instead of detecting the version of the node.
+    #[must_use]
+    pub fn with_version(mut self, version: CometbftVersion) -> Self {
+        self.inner.version = Arc::new(OnceLock::from(version));
+        self
+    }
+
+    /// The CometBFT version of the node, as reported by the `status` endpoint. This is queried once
+    /// and then cached for the lifetime of the client (and all of its clones).
+    ///
+    /// Unrecognized versions are treated as the latest supported version.
+    pub async fn version(&self) -> Result<CometbftVersion, JsonRpcError> {
+        if let Some(version) = self.inner.version.get() {
+            return Ok(*version);
+        }
+
+        let node_version = self.status().await?.node_info.version;
+
+        let version = CometbftVersion::from_node_version(&node_version).unwrap_or_else(|| {
+            warn!(%node_version, "unknown cometbft version, assuming the latest version");
+            CometbftVersion::V1
+        });
+
+        debug!(%node_version, ?version, "detected cometbft version");
+
+        Ok(*self.inner.version.get_or_init(|| version))
     }
 
     pub async fn commit(&self, height: Option<NonZeroU64>) -> Result<CommitResponse, JsonRpcError> {
@@ -82,6 +195,30 @@ impl Client {
             .await
     }
 
+    /// Fetch the commit at `height`, returning [`FetchCommitError::HeightNotAvailable`] if the
+    /// height has been pruned by the node (see [`height_not_available`]).
+    pub async fn fetch_commit(&self, height: u64) -> Result<CommitResponse, FetchCommitError> {
+        let height = NonZeroU64::new(height).ok_or(FetchCommitError::ZeroHeight)?;
+
+        self.commit(Some(height))
+            .await
+            .map_err(|e| match height_not_available(&e) {
+                Some(height_not_available) => {
+                    FetchCommitError::HeightNotAvailable(height_not_available)
+                }
+                None => FetchCommitError::JsonRpc(e),
+            })
+    }
+
+    pub async fn consensus_params(
+        &self,
+        height: Option<NonZeroU64>,
+    ) -> Result<ConsensusParamsResponse, JsonRpcError> {
+        self.inner
+            .request("consensus_params", (height.map(|x| x.to_string()),))
+            .await
+    }
+
     pub async fn validators(
         &self,
         height: Option<NonZeroU64>,
@@ -216,26 +353,10 @@ impl Client {
     ) -> Result<GrpcAbciQueryResponse<R>, JsonRpcError> {
         debug!("fetching grpc abci query");
 
-        let res = self
-            .abci_query(path, data.encode_to_vec(), height, prove)
+        self.abci_query(path, data.encode_to_vec(), height, prove)
             .await?
-            .response;
-
-        Ok(GrpcAbciQueryResponse {
-            code: res.code,
-            log: res.log,
-            info: res.info,
-            index: res.index,
-            key: res.key,
-            value: res
-                .value
-                .map(|value| R::decode(&*value))
-                .transpose()
-                .map_err(|e| JsonRpcError::Custom(ErrorReporter(e).to_string()))?,
-            proof_ops: res.proof_ops,
-            height: res.height,
-            codespace: res.codespace,
-        })
+            .decode_grpc()
+            .map_err(|e| JsonRpcError::Custom(ErrorReporter(e).to_string()))
     }
 
     pub async fn status(&self) -> Result<StatusResponse, JsonRpcError> {
@@ -244,7 +365,7 @@ impl Client {
 
     pub async fn block(&self, height: Option<NonZeroU64>) -> Result<BlockResponse, JsonRpcError> {
         self.inner
-            .request("block", (height.map(|x| x.to_string()),))
+            .request_large("block", (height.map(|x| x.to_string()),))
             .await
     }
 
@@ -287,7 +408,9 @@ impl Client {
         // REVIEW: There is the enum `cosmos.tx.v1beta.OrderBy`, is that related to this?
         order_by: Order,
     ) -> Result<TxSearchResponse, JsonRpcError> {
-        let response = self
+        let version = self.version().await?;
+
+        let mut response = self
             .inner
             .request::<TxSearchResponse, _>(
                 "tx_search",
@@ -303,16 +426,137 @@ impl Client {
 
         debug!(total_count = response.total_count, "tx_search");
 
+        if version.base64_event_attributes() {
+            for tx in &mut response.txs {
+                decode_event_attributes(&mut tx.tx_result.events);
+            }
+        }
+
         Ok(response)
     }
 
+    /// Auto-paginated version of [`Self::tx_search`], fetching `per_page` transactions per page
+    /// until all results have been fetched or `cancellation_token` is cancelled.
+    pub async fn tx_search_all(
+        &self,
+        query: impl AsRef<str>,
+        prove: bool,
+        per_page: NonZeroU8,
+        order_by: Order,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<Vec<TxResponse>, JsonRpcError> {
+        let query = query.as_ref();
+
+        search_all(cancellation_token, |page| {
+            self.tx_search(query, prove, page, per_page, order_by.clone())
+                .map_ok(|response| (response.txs, response.total_count))
+        })
+        .await
+    }
+
+    #[instrument(
+        skip_all,
+        fields(
+            query = query.as_ref(),
+            page,
+            per_page,
+            ?order_by
+        )
+    )]
+    pub async fn block_search(
+        &self,
+        query: impl AsRef<str>,
+        page: NonZeroU32,
+        per_page: NonZeroU8,
+        order_by: Order,
+    ) -> Result<BlockSearchResponse, JsonRpcError> {
+        let response = self
+            .inner
+            .request::<BlockSearchResponse, _>(
+                "block_search",
+                rpc_params![
+                    query.as_ref(),
+                    page.to_string(),
+                    per_page.to_string(),
+                    order_by
+                ],
+            )
+            .await?;
+
+        debug!(total_count = response.total_count, "block_search");
+
+        Ok(response)
+    }
+
+    /// Auto-paginated version of [`Self::block_search`], fetching `per_page` blocks per page until
+    /// all results have been fetched or `cancellation_token` is cancelled.
+    pub async fn block_search_all(
+        &self,
+        query: impl AsRef<str>,
+        per_page: NonZeroU8,
+        order_by: Order,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> Result<Vec<BlockResponse>, JsonRpcError> {
+        let query = query.as_ref();
+
+        search_all(cancellation_token, |page| {
+            self.block_search(query, page, per_page, order_by.clone())
+                .map_ok(|response| (response.blocks, response.total_count))
+        })
+        .await
+    }
+
     // TODO: support order_by
     pub async fn tx(&self, hash: H256, prove: bool) -> Result<TxResponse, JsonRpcError> {
         use base64::prelude::*;
 
-        self.inner
+        let version = self.version().await?;
+
+        let mut response: TxResponse = self
+            .inner
             .request("tx", rpc_params![BASE64_STANDARD.encode(hash), prove])
-            .await
+            .await?;
+
+        if version.base64_event_attributes() {
+            decode_event_attributes(&mut response.tx_result.events);
+        }
+
+        Ok(response)
+    }
+
+    /// Fetches the transaction with its inclusion proof, and verifies the proof against the data
+    /// hash of the block the transaction was included in. The `proof` of the returned transaction
+    /// is always `Some`.
+    pub async fn tx_with_proof(&self, hash: H256) -> Result<TxResponse, JsonRpcError> {
+        let tx = self.tx(hash, true).await?;
+
+        let proof = tx
+            .proof
+            .as_ref()
+            .ok_or_else(|| JsonRpcError::Custom(format!("no proof returned for tx {hash}")))?;
+
+        if proof.data != tx.tx {
+            return Err(JsonRpcError::Custom(format!(
+                "proof of tx {hash} is for a different transaction"
+            )));
+        }
+
+        let height = tx
+            .height
+            .ok_or_else(|| JsonRpcError::Custom(format!("no height returned for tx {hash}")))?;
+
+        let block = self.block(Some(height)).await?;
+
+        proof
+            .validate(&block.block.header.data_hash.into_encoding())
+            .map_err(|e| {
+                JsonRpcError::Custom(format!(
+                    "invalid proof for tx {hash} at height {height}: {}",
+                    ErrorReporter(e)
+                ))
+            })?;
+
+        Ok(tx)
     }
 
     pub async fn broadcast_tx_sync(
@@ -326,20 +570,183 @@ impl Client {
             .await
     }
 
+    /// Fetches the results of the block at `height`. For nodes running CometBFT < v0.38, the
+    /// begin and end block events are returned as the `finalize_block_events`, in that order.
     pub async fn block_results(
         &self,
         height: Option<NonZeroU64>,
     ) -> Result<BlockResultsResponse, JsonRpcError> {
-        self.inner
-            .request("block_results", rpc_params![height.map(|x| x.to_string())])
-            .await
+        let version = self.version().await?;
+
+        let mut response: BlockResultsResponse = self
+            .inner
+            .request_large("block_results", (height.map(|x| x.to_string()),))
+            .await?;
+
+        if !version.has_finalize_block_events() {
+            let events = response
+                .begin_block_events
+                .take()
+                .into_iter()
+                .chain(response.end_block_events.take())
+                .flatten()
+                .collect::<Vec<_>>();
+
+            response
+                .finalize_block_events
+                .get_or_insert_default()
+                .extend(events);
+        }
+
+        if version.base64_event_attributes() {
+            for tx_result in response.txs_results.iter_mut().flatten() {
+                decode_event_attributes(&mut tx_result.events);
+            }
+            if let Some(events) = &mut response.finalize_block_events {
+                decode_event_attributes(events);
+            }
+        }
+
+        Ok(response)
     }
+
+    /// Subscribe to the `NewBlock` events of the node. This requires a websocket connection.
+    pub async fn subscribe_new_block(&self) -> Result<Subscription<NewBlockEvent>, JsonRpcError> {
+        Subscription::new(self.ws_url()?, "tm.event='NewBlock'".to_owned(), |_| {}).await
+    }
+
+    /// Subscribe to the `Tx` events of the node, optionally restricted further by `query` (i.e.
+    /// `wasm._contract_address='union1...'`). This requires a websocket connection.
+    pub async fn subscribe_tx(
+        &self,
+        query: Option<&str>,
+    ) -> Result<Subscription<TxEvent>, JsonRpcError> {
+        let query = match query {
+            Some(query) => format!("tm.event='Tx' AND {query}"),
+            None => "tm.event='Tx'".to_owned(),
+        };
+
+        let normalize: fn(&mut TxEvent) = if self.version().await?.base64_event_attributes() {
+            |event| decode_event_attributes(&mut event.tx_result.result.events)
+        } else {
+            |_| {}
+        };
+
+        Subscription::new(self.ws_url()?, query, normalize).await
+    }
+
+    fn ws_url(&self) -> Result<&str, JsonRpcError> {
+        match &self.inner.transport {
+            Transport::Ws(_, url) => Ok(url),
+            Transport::Http(_) => Err(JsonRpcError::Custom(
+                "subscriptions require a websocket connection".to_owned(),
+            )),
+        }
+    }
+}
+
+/// The requested height is not available on the node, most likely because it has been pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("height {requested_height} is not available, lowest height is {lowest_height}")]
+pub struct HeightNotAvailable {
+    pub requested_height: u64,
+    pub lowest_height: u64,
+}
+
+/// An error fetching a commit with [`Client::fetch_commit`].
+#[derive(Debug, thiserror::Error)]
+pub enum FetchCommitError {
+    #[error("height must be non-zero")]
+    ZeroHeight,
+    #[error(transparent)]
+    HeightNotAvailable(HeightNotAvailable),
+    #[error("error fetching commit")]
+    JsonRpc(#[source] JsonRpcError),
+}
+
+/// Check whether `error` is the error returned by CometBFT when a height lower than the node's
+/// earliest retained block is requested.
+///
+/// The node responds with an internal error containing
+/// `height <requested> is not available, lowest height is <lowest>` in this case, see
+/// <https://github.com/cometbft/cometbft/blob/v0.38.17/rpc/core/blocks.go>.
+pub fn height_not_available(error: &JsonRpcError) -> Option<HeightNotAvailable> {
+    const PATTERN: &str = " is not available, lowest height is ";
+
+    let JsonRpcError::Call(error) = error else {
+        return None;
+    };
+
+    [Some(error.message()), error.data().map(|data| data.get())]
+        .into_iter()
+        .flatten()
+        .find_map(|s| {
+            let (prefix, suffix) = s.split_once(PATTERN)?;
+
+            let requested_height = prefix.rsplit_once("height ")?.1.parse().ok()?;
+
+            let lowest_height = suffix
+                .split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse()
+                .ok()?;
+
+            Some(HeightNotAvailable {
+                requested_height,
+                lowest_height,
+            })
+        })
 }
 
 #[derive(Debug, Clone)]
-enum ClientInner {
-    Http(Box<HttpClient>),
-    Ws(reconnecting_jsonrpc_ws_client::Client),
+struct ClientInner {
+    transport: Transport,
+    /// Used instead of the transport for large responses if set, see
+    /// [`ClientOptions::stream_large_responses`].
+    streaming_client: Option<StreamingClient>,
+    rate_limiter: Option<RateLimiter>,
+    /// Lazily detected by [`Client::version`].
+    version: Arc<OnceLock<CometbftVersion>>,
+}
+
+impl ClientInner {
+    async fn rate_limit(&self) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+    }
+
+    /// A request for a response that may be too large to be buffered, which is streamed if
+    /// [`ClientOptions::stream_large_responses`] is set.
+    async fn request_large<R, Params>(
+        &self,
+        method: &str,
+        params: Params,
+    ) -> Result<R, JsonRpcError>
+    where
+        R: DeserializeOwned + Send + 'static,
+        Params: ToRpcParams + Serialize + Send,
+    {
+        match &self.streaming_client {
+            Some(streaming_client) => {
+                self.rate_limit().await;
+
+                streaming_client.request(method, params).await
+            }
+            None => self.request(method, params).await,
+        }
+    }
+}
+
+/// The service of the http client, which decompresses the responses if
+/// [`ClientOptions::gzip`] is set.
+type HttpService = RpcLogger<RpcService<Decompression<HttpBackend>>>;
+
+#[derive(Debug, Clone)]
+enum Transport {
+    Http(Box<HttpClient<HttpService>>),
+    /// The client, and the url it is connected to (used for subscriptions).
+    Ws(reconnecting_jsonrpc_ws_client::Client, String),
 }
 
 impl ClientT for ClientInner {
@@ -347,9 +754,11 @@ impl ClientT for ClientInner {
     where
         Params: ToRpcParams + Send,
     {
-        match self {
-            ClientInner::Http(client) => client.notification(method, params).await,
-            ClientInner::Ws(client) => client.notification(method, params).await,
+        self.rate_limit().await;
+
+        match &self.transport {
+            Transport::Http(client) => client.notification(method, params).await,
+            Transport::Ws(client, _) => client.notification(method, params).await,
         }
     }
 
@@ -358,9 +767,11 @@ impl ClientT for ClientInner {
         R: DeserializeOwned,
         Params: ToRpcParams + Send,
     {
-        match self {
-            ClientInner::Http(client) => client.request(method, params).await,
-            ClientInner::Ws(client) => client.request(method, params).await,
+        self.rate_limit().await;
+
+        match &self.transport {
+            Transport::Http(client) => client.request(method, params).await,
+            Transport::Ws(client, _) => client.request(method, params).await,
         }
     }
 
@@ -371,9 +782,11 @@ impl ClientT for ClientInner {
     where
         R: DeserializeOwned + fmt::Debug + 'a,
     {
-        match self {
-            ClientInner::Http(client) => client.batch_request(batch).await,
-            ClientInner::Ws(client) => client.batch_request(batch).await,
+        self.rate_limit().await;
+
+        match &self.transport {
+            Transport::Http(client) => client.batch_request(batch).await,
+            Transport::Ws(client, _) => client.batch_request(batch).await,
         }
     }
 }
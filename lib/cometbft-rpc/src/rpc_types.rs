@@ -120,6 +120,27 @@ pub struct AbciQueryResponse {
     pub response: QueryResponse,
 }
 
+impl AbciQueryResponse {
+    /// Decode the value of the response of an abci query to a grpc query path.
+    pub fn decode_grpc<R: unionlabs::prost::Message + Default>(
+        self,
+    ) -> Result<GrpcAbciQueryResponse<R>, unionlabs::prost::DecodeError> {
+        let res = self.response;
+
+        Ok(GrpcAbciQueryResponse {
+            code: res.code,
+            log: res.log,
+            info: res.info,
+            index: res.index,
+            key: res.key,
+            value: res.value.map(|value| R::decode(&*value)).transpose()?,
+            proof_ops: res.proof_ops,
+            height: res.height,
+            codespace: res.codespace,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct GrpcAbciQueryResponse<T> {
@@ -164,6 +185,16 @@ pub struct CommitResponse {
     pub canonical: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConsensusParamsResponse {
+    #[serde(with = "::serde_utils::string")]
+    pub block_height: NonZeroU64,
+    /// The fields of the consensus params differ between CometBFT versions (`abci` was added in
+    /// v0.38, and replaced by `feature` in v1), so they are kept as returned by the node.
+    pub consensus_params: serde_json::Value,
+}
+
 #[derive(macros::Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct TxResponse {
@@ -186,12 +217,32 @@ pub struct TxSearchResponse {
     pub total_count: u32,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockSearchResponse {
+    pub blocks: Vec<BlockResponse>,
+    #[serde(with = "::serde_utils::string")]
+    pub total_count: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BlockResultsResponse {
     #[serde(with = "::serde_utils::string")]
     pub height: u64,
     pub txs_results: Option<Vec<ExecTxResult>>,
     pub finalize_block_events: Option<Vec<Event>>,
+    /// Only returned by nodes running CometBFT < v0.38. [`Client::block_results`] merges these
+    /// into [`Self::finalize_block_events`].
+    ///
+    /// [`Client::block_results`]: crate::Client::block_results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub begin_block_events: Option<Vec<Event>>,
+    /// Only returned by nodes running CometBFT < v0.38. [`Client::block_results`] merges these
+    /// into [`Self::finalize_block_events`].
+    ///
+    /// [`Client::block_results`]: crate::Client::block_results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_block_events: Option<Vec<Event>>,
 }
 
 #[derive(macros::Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -203,3 +254,35 @@ pub struct BroadcastTxSyncResponse {
     pub log: String,
     pub hash: H256<HexUnprefixed>,
 }
+
+/// The data of a `NewBlock` event, as received through [`Client::subscribe_new_block`].
+///
+/// [`Client::subscribe_new_block`]: crate::Client::subscribe_new_block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewBlockEvent {
+    pub block: Block,
+    /// Only included by nodes running CometBFT >= v0.38.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<BlockId>,
+}
+
+/// The data of a `Tx` event, as received through [`Client::subscribe_tx`].
+///
+/// [`Client::subscribe_tx`]: crate::Client::subscribe_tx
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TxEvent {
+    #[serde(rename = "TxResult")]
+    pub tx_result: TxEventResult,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TxEventResult {
+    #[serde(with = "::serde_utils::string")]
+    pub height: u64,
+    #[serde(default)]
+    pub index: u32,
+    pub tx: Bytes<Base64>,
+    pub result: ExecTxResult,
+}
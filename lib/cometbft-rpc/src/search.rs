@@ -0,0 +1,69 @@
+use std::{cmp::Ordering, future::Future, num::NonZeroU32};
+
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+use unionlabs::option_unwrap;
+
+use crate::JsonRpcError;
+
+/// Walk the pages of a paginated search endpoint, starting at the first page, until all
+/// `total_count` results have been fetched. `fetch_page` returns the results of a page along with
+/// the `total_count` reported by the node. If the node returns more results than it reports, the
+/// search is complete once `total_count` is reached and all of the returned results are kept.
+///
+/// If `cancellation_token` is cancelled, the page that is currently being fetched is abandoned and
+/// an error is returned.
+pub(crate) async fn search_all<T, F>(
+    cancellation_token: Option<&CancellationToken>,
+    mut fetch_page: impl FnMut(NonZeroU32) -> F,
+) -> Result<Vec<T>, JsonRpcError>
+where
+    F: Future<Output = Result<(Vec<T>, u32), JsonRpcError>>,
+{
+    let mut page = const { option_unwrap!(NonZeroU32::new(1)) };
+
+    let mut out = vec![];
+
+    loop {
+        let (items, total_count) = match cancellation_token {
+            Some(cancellation_token) => cancellation_token
+                .run_until_cancelled(fetch_page(page))
+                .await
+                .ok_or_else(|| JsonRpcError::Custom("search was cancelled".to_owned()))??,
+            None => fetch_page(page).await?,
+        };
+
+        let is_empty = items.is_empty();
+
+        out.extend(items);
+
+        debug!(%page, fetched = out.len(), total_count, "fetched page");
+
+        match (out.len() as u64).cmp(&total_count.into()) {
+            Ordering::Equal => return Ok(out),
+            Ordering::Greater => {
+                warn!(
+                    %page,
+                    fetched = out.len(),
+                    total_count,
+                    "fetched more results than expected"
+                );
+
+                return Ok(out);
+            }
+            // the node reported more results than it returns, stop instead of walking the pages
+            // forever
+            Ordering::Less if is_empty => {
+                return Err(JsonRpcError::Custom(format!(
+                    "page {page} is empty, but only {} of {total_count} results were fetched",
+                    out.len()
+                )))
+            }
+            Ordering::Less => {
+                page = page
+                    .checked_add(1)
+                    .expect("result count will always be < u32 max");
+            }
+        }
+    }
+}
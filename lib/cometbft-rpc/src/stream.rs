@@ -0,0 +1,109 @@
+use std::io;
+
+use ::serde::{de::DeserializeOwned, Deserialize, Serialize};
+use futures::{StreamExt, TryStreamExt};
+use jsonrpsee::types::ErrorObjectOwned;
+use serde_json::json;
+use tokio_util::io::{StreamReader, SyncIoBridge};
+use tracing::debug;
+use unionlabs::ErrorReporter;
+
+use crate::{ClientOptions, JsonRpcError};
+
+/// A client for the http endpoint of a CometBFT node that deserializes responses while they are
+/// being received, instead of buffering the full response (and then a copy of the `result`) first
+/// as the jsonrpsee client does. Used for the responses that can grow to multiple megabytes, such
+/// as `block` and `block_results`.
+#[derive(Debug, Clone)]
+pub(crate) struct StreamingClient {
+    client: reqwest::Client,
+    url: String,
+    max_response_size: u64,
+}
+
+#[derive(Deserialize)]
+struct Response<R> {
+    result: Option<R>,
+    error: Option<ErrorObjectOwned>,
+}
+
+impl StreamingClient {
+    pub(crate) fn new(url: String, options: &ClientOptions) -> Result<Self, JsonRpcError> {
+        let client = reqwest::Client::builder()
+            .gzip(options.gzip)
+            .build()
+            .map_err(|e| JsonRpcError::Custom(ErrorReporter(e).to_string()))?;
+
+        Ok(Self {
+            client,
+            url,
+            max_response_size: options.max_response_size.into(),
+        })
+    }
+
+    pub(crate) async fn request<R: DeserializeOwned + Send + 'static>(
+        &self,
+        method: &str,
+        params: impl Serialize,
+    ) -> Result<R, JsonRpcError> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| JsonRpcError::Custom(ErrorReporter(e).to_string()))?;
+
+        debug!(
+            %method,
+            content_length = response.content_length(),
+            "streaming response"
+        );
+
+        let max_response_size = self.max_response_size;
+        let mut received = 0;
+
+        // the size is checked after decompression, so that a small compressed response cannot be
+        // used to exhaust the memory
+        let body = response
+            .bytes_stream()
+            .map_err(io::Error::other)
+            .map(move |chunk| {
+                let chunk = chunk?;
+
+                received += chunk.len() as u64;
+
+                if received > max_response_size {
+                    Err(io::Error::other(format!(
+                        "response exceeds the maximum size of {max_response_size} bytes"
+                    )))
+                } else {
+                    Ok(chunk)
+                }
+            })
+            .boxed();
+
+        // every read of the bridge blocks on the runtime, so read in chunks instead of per byte
+        let reader = io::BufReader::new(SyncIoBridge::new(StreamReader::new(body)));
+
+        let response =
+            tokio::task::spawn_blocking(move || serde_json::from_reader::<_, Response<R>>(reader))
+                .await
+                .map_err(|e| JsonRpcError::Custom(ErrorReporter(e).to_string()))?
+                .map_err(JsonRpcError::ParseError)?;
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(JsonRpcError::Call(error)),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(JsonRpcError::Custom(
+                "response contains neither a result nor an error".to_owned(),
+            )),
+        }
+    }
+}
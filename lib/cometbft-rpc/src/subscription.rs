@@ -0,0 +1,258 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use ::serde::{de::DeserializeOwned, Deserialize};
+use futures::{
+    channel::{mpsc, oneshot},
+    stream, SinkExt, Stream, StreamExt,
+};
+use jsonrpsee::{
+    client_transport::ws::{Url, WsTransportClientBuilder},
+    core::client::{ReceivedMessage, TransportReceiverT, TransportSenderT},
+};
+use serde_json::{json, Value};
+use tokio::time::{interval_at, sleep, Instant, MissedTickBehavior};
+use tracing::{debug, debug_span, warn, Instrument};
+use unionlabs::ErrorReporter;
+
+use crate::JsonRpcError;
+
+const MIN_RETRY_MS: u64 = 100;
+const MAX_RETRY_MS: u64 = 8_000;
+
+/// How often the connection is pinged.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+/// The connection is considered dead (and the subscription is renewed) if nothing was received for
+/// this long, not even a pong.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// The number of events buffered for the consumer. Once the buffer is full, the connection is not
+/// read until the consumer catches up.
+const EVENT_BUFFER: usize = 1024;
+
+/// A stream of the events matching a query, received over the websocket endpoint of a CometBFT
+/// node.
+///
+/// The subscription is renewed automatically if the connection is lost, if the node does not answer
+/// pings anymore, or if the node cancels it (i.e. because the events were not consumed fast
+/// enough, since at most 1024 events are buffered before the connection stops being read). Events
+/// emitted while the subscription is being renewed are not received, so consumers that cannot miss
+/// any events have to fill the gap themselves (i.e. by checking the heights of the received
+/// events).
+///
+/// The subscription is closed once this is dropped.
+#[derive(Debug)]
+pub struct Subscription<T> {
+    events: mpsc::Receiver<Result<T, JsonRpcError>>,
+}
+
+impl<T> Stream for Subscription<T> {
+    type Item = Result<T, JsonRpcError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_next_unpin(cx)
+    }
+}
+
+impl<T: DeserializeOwned + Send + 'static> Subscription<T> {
+    /// Subscribe to the events matching `query` (i.e. `tm.event='NewBlock'`). `normalize` is
+    /// applied to every event before it is yielded.
+    ///
+    /// This only returns once the first subscription request has been sent, so that an
+    /// unreachable node is reported to the caller instead of being retried forever.
+    pub(crate) async fn new(
+        url: &str,
+        query: String,
+        normalize: fn(&mut T),
+    ) -> Result<Self, JsonRpcError> {
+        let url =
+            Url::parse(url).map_err(|e| JsonRpcError::Custom(format!("invalid url {url}: {e}")))?;
+
+        let (events_tx, events) = mpsc::channel(EVENT_BUFFER);
+        let (connected_tx, connected) = oneshot::channel();
+
+        tokio::spawn(
+            subscribe(url, query.clone(), normalize, events_tx, connected_tx)
+                .instrument(debug_span!("cometbft_subscription", %query)),
+        );
+
+        connected
+            .await
+            .map_err(|_| JsonRpcError::Custom("subscription task exited".to_owned()))??;
+
+        Ok(Self { events })
+    }
+}
+
+async fn subscribe<T: DeserializeOwned>(
+    url: Url,
+    query: String,
+    normalize: fn(&mut T),
+    mut events: mpsc::Sender<Result<T, JsonRpcError>>,
+    connected: oneshot::Sender<Result<(), JsonRpcError>>,
+) {
+    let mut connected = Some(connected);
+    let mut retry_ms = MIN_RETRY_MS;
+
+    loop {
+        let error = match connect(&url, &query).await {
+            Ok((mut sender, receiver)) => {
+                if let Some(connected) = connected.take() {
+                    let _ = connected.send(Ok(()));
+                }
+
+                retry_ms = MIN_RETRY_MS;
+
+                // the pending receive is kept in the stream, such that a ping doesn't cancel it
+                let mut messages = Box::pin(stream::unfold(receiver, |mut receiver| async move {
+                    Some((receiver.receive().await, receiver))
+                }));
+                let mut ping = interval_at(Instant::now() + PING_INTERVAL, PING_INTERVAL);
+                ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                let mut last_received = Instant::now();
+
+                loop {
+                    let message = tokio::select! {
+                        Some(message) = messages.next() => message,
+                        _ = ping.tick() => {
+                            if last_received.elapsed() > IDLE_TIMEOUT {
+                                break format!(
+                                    "nothing received for {}s",
+                                    last_received.elapsed().as_secs()
+                                );
+                            }
+
+                            match sender.send_ping().await {
+                                Ok(()) => continue,
+                                Err(error) => break ErrorReporter(error).to_string(),
+                            }
+                        }
+                    };
+
+                    last_received = Instant::now();
+
+                    let event = match message {
+                        Ok(ReceivedMessage::Text(text)) => match parse_message(&text) {
+                            Ok(Some(value)) => serde_json::from_value::<T>(value)
+                                .map(|mut event| {
+                                    normalize(&mut event);
+                                    event
+                                })
+                                .map_err(|e| {
+                                    JsonRpcError::Custom(format!(
+                                        "unable to decode event: {}",
+                                        ErrorReporter(e)
+                                    ))
+                                }),
+                            // the response to the subscription request
+                            Ok(None) => continue,
+                            Err(error) => break error,
+                        },
+                        Ok(ReceivedMessage::Bytes(_) | ReceivedMessage::Pong) => continue,
+                        Err(error) => break ErrorReporter(error).to_string(),
+                    };
+
+                    if events.send(event).await.is_err() {
+                        debug!("subscription dropped");
+
+                        return;
+                    }
+
+                    // waiting for the consumer doesn't count as idle
+                    last_received = Instant::now();
+                }
+            }
+            Err(error) => match connected.take() {
+                Some(connected) => {
+                    let _ = connected.send(Err(JsonRpcError::Custom(error)));
+
+                    return;
+                }
+                None => error,
+            },
+        };
+
+        if events.is_closed() {
+            debug!("subscription dropped");
+
+            return;
+        }
+
+        warn!(%error, "subscription interrupted, resubscribing in {retry_ms} ms");
+
+        sleep(Duration::from_millis(retry_ms)).await;
+
+        retry_ms = std::cmp::min(retry_ms * 2, MAX_RETRY_MS);
+    }
+}
+
+/// Connect to the node and send the subscription request. The sender has to be kept alive for as
+/// long as the subscription is used, since dropping it closes the connection.
+async fn connect(
+    url: &Url,
+    query: &str,
+) -> Result<(impl TransportSenderT, impl TransportReceiverT), String> {
+    let (mut sender, receiver) = WsTransportClientBuilder::default()
+        .build(url.clone())
+        .await
+        .map_err(|e| ErrorReporter(e).to_string())?;
+
+    sender
+        .send(subscribe_request(query))
+        .await
+        .map_err(|e| ErrorReporter(e).to_string())?;
+
+    debug!("subscribed");
+
+    Ok((sender, receiver))
+}
+
+fn subscribe_request(query: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "subscribe",
+        "params": {
+            "query": query,
+        },
+    })
+    .to_string()
+}
+
+#[derive(Deserialize)]
+struct RawMessage {
+    #[serde(default)]
+    result: Option<RawResult>,
+    #[serde(default)]
+    error: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct RawResult {
+    #[serde(default)]
+    data: Option<RawEventData>,
+}
+
+#[derive(Deserialize)]
+struct RawEventData {
+    value: Value,
+}
+
+/// Extract the event data from a message received on a subscription. CometBFT does not send events
+/// as JSON-RPC notifications, but as responses with the id of the subscription request, the first
+/// of which (the response to the request itself) does not contain any event.
+pub(crate) fn parse_message(text: &str) -> Result<Option<Value>, String> {
+    let message = serde_json::from_str::<RawMessage>(text)
+        .map_err(|e| format!("invalid message: {}", ErrorReporter(e)))?;
+
+    if let Some(error) = message.error {
+        return Err(format!("subscription error: {error}"));
+    }
+
+    Ok(message
+        .result
+        .and_then(|result| result.data)
+        .map(|data| data.value))
+}
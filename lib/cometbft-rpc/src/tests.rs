@@ -423,3 +423,483 @@ mod status {
         );
     }
 }
+
+mod height_not_available {
+    use jsonrpsee::types::ErrorObject;
+
+    use crate::{height_not_available, HeightNotAvailable, JsonRpcError};
+
+    #[test]
+    fn pruned_height() {
+        let error = JsonRpcError::Call(ErrorObject::owned(
+            -32603,
+            "Internal error",
+            Some("height 1 is not available, lowest height is 7654321"),
+        ));
+
+        assert_eq!(
+            height_not_available(&error),
+            Some(HeightNotAvailable {
+                requested_height: 1,
+                lowest_height: 7654321,
+            })
+        );
+    }
+
+    #[test]
+    fn other_error() {
+        let error = JsonRpcError::Call(ErrorObject::owned(
+            -32603,
+            "Internal error",
+            Some("height 100 must be less than or equal to the current blockchain height 50"),
+        ));
+
+        assert_eq!(height_not_available(&error), None);
+
+        assert_eq!(
+            height_not_available(&JsonRpcError::Custom("error".to_owned())),
+            None
+        );
+    }
+}
+
+mod version {
+    use cometbft_types::abci::{event::Event, event_attribute::EventAttribute};
+
+    use crate::{version::decode_event_attributes, CometbftVersion};
+
+    #[test]
+    fn from_node_version() {
+        assert_eq!(
+            CometbftVersion::from_node_version("0.34.35"),
+            Some(CometbftVersion::V0_34)
+        );
+        assert_eq!(
+            CometbftVersion::from_node_version("v0.37.4"),
+            Some(CometbftVersion::V0_37)
+        );
+        assert_eq!(
+            CometbftVersion::from_node_version("0.38.17-rc1"),
+            Some(CometbftVersion::V0_38)
+        );
+        assert_eq!(
+            CometbftVersion::from_node_version("1.0.1"),
+            Some(CometbftVersion::V1)
+        );
+        assert_eq!(CometbftVersion::from_node_version("0.33.9"), None);
+        assert_eq!(CometbftVersion::from_node_version("unknown"), None);
+    }
+
+    #[test]
+    fn decode_base64_event_attributes() {
+        let mut events = vec![Event {
+            ty: "message".to_owned(),
+            attributes: vec![
+                EventAttribute {
+                    key: "YWN0aW9u".to_owned(),
+                    value: "c2VuZA==".to_owned(),
+                    index: true,
+                },
+                EventAttribute {
+                    key: "c2VuZGVy".to_owned(),
+                    value: "not base64!".to_owned(),
+                    index: false,
+                },
+            ],
+        }];
+
+        decode_event_attributes(&mut events);
+
+        assert_eq!(
+            events[0].attributes,
+            vec![
+                EventAttribute {
+                    key: "action".to_owned(),
+                    value: "send".to_owned(),
+                    index: true,
+                },
+                EventAttribute {
+                    key: "sender".to_owned(),
+                    value: "not base64!".to_owned(),
+                    index: false,
+                },
+            ]
+        );
+    }
+}
+
+mod subscription {
+    use cometbft_types::code::Code;
+    use serde_json::json;
+
+    use crate::{rpc_types::TxEvent, subscription::parse_message};
+
+    #[test]
+    fn subscribe_response() {
+        assert_eq!(
+            parse_message(r#"{"jsonrpc":"2.0","id":0,"result":{}}"#),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn subscription_cancelled() {
+        assert!(parse_message(
+            r#"{"jsonrpc":"2.0","id":0,"error":{"code":-32000,"message":"Server error","data":"subscription was cancelled (reason: client is not pulling messages fast enough)"}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn tx_event() {
+        let message = json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "result": {
+                "query": "tm.event='Tx'",
+                "data": {
+                    "type": "tendermint/event/Tx",
+                    "value": {
+                        "TxResult": {
+                            "height": "1234",
+                            "index": 1,
+                            "tx": "CgQKAggB",
+                            "result": {
+                                "code": 0,
+                                "data": null,
+                                "log": "",
+                                "info": "",
+                                "gas_wanted": "200000",
+                                "gas_used": "81234",
+                                "events": [],
+                                "codespace": ""
+                            }
+                        }
+                    }
+                },
+                "events": {
+                    "tm.event": ["Tx"]
+                }
+            }
+        });
+
+        let event = serde_json::from_value::<TxEvent>(
+            parse_message(&message.to_string()).unwrap().unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(event.tx_result.height, 1234);
+        assert_eq!(event.tx_result.index, 1);
+        assert_eq!(event.tx_result.result.code, Code::Ok);
+        assert_eq!(event.tx_result.result.gas_used.inner(), 81234);
+    }
+}
+
+mod events {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use crate::{
+        events::{attribute_values, EventOrigin},
+        rpc_types::BlockResultsResponse,
+    };
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "snake_case", tag = "type", content = "attributes")]
+    enum IbcEvent {
+        #[serde(rename = "wasm-packet_send")]
+        WasmPacketSend { packet_hash: String },
+    }
+
+    fn tx_result(events: serde_json::Value) -> serde_json::Value {
+        json!({
+            "code": 0,
+            "data": null,
+            "log": "",
+            "info": "",
+            "gas_wanted": "200000",
+            "gas_used": "81234",
+            "events": events,
+            "codespace": ""
+        })
+    }
+
+    fn block_results() -> BlockResultsResponse {
+        serde_json::from_value(json!({
+            "height": "1234",
+            "txs_results": [
+                tx_result(json!([
+                    {
+                        "type": "message",
+                        "attributes": [
+                            { "key": "sender", "value": "union1sender", "index": true }
+                        ]
+                    },
+                    {
+                        "type": "wasm-packet_send",
+                        "attributes": [
+                            { "key": "_contract_address", "value": "union17e93ukhcyesrvu72cgfvamdhyracghrx4f7ww89rqjg944ntdegscxepme", "index": true },
+                            { "key": "packet_hash", "value": "0x01", "index": true },
+                            { "key": "msg_index", "value": "0", "index": true }
+                        ]
+                    }
+                ])),
+                tx_result(json!([])),
+                tx_result(json!([
+                    {
+                        "type": "wasm-packet_send",
+                        "attributes": [
+                            { "key": "packet_hash", "value": "0x02", "index": true },
+                            { "key": "msg_index", "value": "not a number", "index": true }
+                        ]
+                    }
+                ]))
+            ],
+            "finalize_block_events": [
+                {
+                    "type": "wasm-packet_send",
+                    "attributes": [
+                        { "key": "packet_hash", "value": "0x03", "index": true }
+                    ]
+                }
+            ],
+            "validator_updates": null,
+            "consensus_param_updates": null,
+            "app_hash": ""
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn groups_events_per_tx() {
+        let block_results = block_results();
+
+        assert_eq!(
+            block_results
+                .tx_events()
+                .map(|(idx, events)| (idx, events.len()))
+                .collect::<Vec<_>>(),
+            vec![(0, 2), (1, 0), (2, 1)]
+        );
+        assert_eq!(block_results.block_events().len(), 1);
+        assert_eq!(
+            block_results
+                .events()
+                .map(|(origin, event)| (origin, &*event.ty))
+                .collect::<Vec<_>>(),
+            vec![
+                (EventOrigin::Tx(0), "message"),
+                (EventOrigin::Tx(0), "wasm-packet_send"),
+                (EventOrigin::Tx(2), "wasm-packet_send"),
+                (EventOrigin::Block, "wasm-packet_send"),
+            ]
+        );
+    }
+
+    #[test]
+    fn typed_events() {
+        let events = block_results()
+            .typed_events::<IbcEvent>()
+            .collect::<Vec<_>>();
+
+        assert_eq!(events.len(), 3);
+
+        let (origin, event) = &events[0];
+        let event = event.as_ref().unwrap();
+        assert_eq!(*origin, EventOrigin::Tx(0));
+        assert_eq!(event.msg_index, Some(0));
+        assert!(event.contract_address.is_some());
+        assert_eq!(
+            event.event,
+            IbcEvent::WasmPacketSend {
+                packet_hash: "0x01".to_owned()
+            }
+        );
+
+        assert_eq!(events[1].0, EventOrigin::Tx(2));
+        assert!(events[1].1.is_err());
+
+        assert_eq!(events[2].0, EventOrigin::Block);
+        assert_eq!(
+            events[2].1.as_ref().unwrap().event,
+            IbcEvent::WasmPacketSend {
+                packet_hash: "0x03".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn attribute_values_by_type() {
+        let block_results = block_results();
+        let (_, events) = block_results.tx_events().next().unwrap();
+
+        assert_eq!(
+            attribute_values(events, Some("message"), "sender").collect::<Vec<_>>(),
+            vec!["union1sender"]
+        );
+        assert_eq!(
+            attribute_values(events, Some("wasm-packet_send"), "sender").count(),
+            0
+        );
+        assert_eq!(
+            attribute_values(events, None, "packet_hash").collect::<Vec<_>>(),
+            vec!["0x01"]
+        );
+    }
+}
+
+mod search {
+    use std::num::NonZeroU32;
+
+    use tokio_util::sync::CancellationToken;
+
+    use crate::{search::search_all, JsonRpcError};
+
+    /// Pages of 2 results out of `total_count`, where the node only has `available` results.
+    async fn page(
+        page: NonZeroU32,
+        total_count: u32,
+        available: u32,
+    ) -> Result<(Vec<u32>, u32), JsonRpcError> {
+        let start = (page.get() - 1) * 2;
+
+        Ok(((start..(start + 2).min(available)).collect(), total_count))
+    }
+
+    #[tokio::test]
+    async fn walks_all_pages() {
+        let mut pages = vec![];
+
+        let results = search_all(None, |p| {
+            pages.push(p.get());
+            page(p, 5, 5)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(results, vec![0, 1, 2, 3, 4]);
+        assert_eq!(pages, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn stops_at_empty_page() {
+        assert!(search_all(None, |p| page(p, 5, 3)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn more_results_than_expected() {
+        let mut pages = vec![];
+
+        let results = search_all(None, |p| {
+            pages.push(p.get());
+            page(p, 3, 4)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(results, vec![0, 1, 2, 3]);
+        assert_eq!(pages, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn cancelled() {
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+
+        assert!(search_all(Some(&cancellation_token), |_| {
+            std::future::pending::<Result<(Vec<u32>, u32), JsonRpcError>>()
+        })
+        .await
+        .is_err());
+    }
+}
+
+mod stream {
+    use serde_json::{json, Value};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use crate::{stream::StreamingClient, ClientOptions, JsonRpcError};
+
+    /// Serve `body` as the response to the first request, returning the url of the server.
+    async fn serve(body: Value) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+
+            let _ = socket.read(&mut [0; 4096]).await.unwrap();
+
+            let body = body.to_string();
+
+            socket
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\n\
+                        content-type: application/json\r\n\
+                        content-length: {}\r\n\
+                        connection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+        });
+
+        url
+    }
+
+    async fn request(body: Value, options: ClientOptions) -> Result<Value, JsonRpcError> {
+        StreamingClient::new(serve(body).await, &options)
+            .unwrap()
+            .request("block", (Some("1"),))
+            .await
+    }
+
+    #[tokio::test]
+    async fn result() {
+        let result = request(
+            json!({ "jsonrpc": "2.0", "id": 0, "result": { "block": "block" } }),
+            ClientOptions::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result, json!({ "block": "block" }));
+    }
+
+    #[tokio::test]
+    async fn error() {
+        let error = request(
+            json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "error": { "code": -32603, "message": "Internal error" },
+            }),
+            ClientOptions::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error, JsonRpcError::Call(error) if error.code() == -32603));
+    }
+
+    #[tokio::test]
+    async fn exceeds_max_response_size() {
+        let error = request(
+            json!({ "jsonrpc": "2.0", "id": 0, "result": { "block": "a".repeat(64) } }),
+            ClientOptions {
+                max_response_size: 32,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(error, JsonRpcError::ParseError(_)));
+    }
+}
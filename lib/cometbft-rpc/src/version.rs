@@ -0,0 +1,75 @@
+use base64::prelude::*;
+use cometbft_types::abci::event::Event;
+
+/// The CometBFT versions (or rather, the RPC response formats) supported by the client.
+///
+/// Responses are normalized to the format of the latest version, so that the same client can be
+/// used against nodes running any of these versions:
+///
+/// - in v0.34, the keys and values of event attributes are base64 encoded.
+/// - before v0.38, `block_results` contains `begin_block_events` and `end_block_events` instead of
+///   `finalize_block_events`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CometbftVersion {
+    V0_34,
+    V0_37,
+    V0_38,
+    V1,
+}
+
+impl CometbftVersion {
+    /// Parse the version from the `node_info.version` reported by the `status` endpoint (i.e.
+    /// `0.38.17` or `v1.0.1`). Returns `None` if the version is not recognized.
+    #[must_use]
+    pub fn from_node_version(version: &str) -> Option<Self> {
+        let mut parts = version.trim_start_matches('v').split('.');
+
+        let major = parts.next()?.parse::<u64>().ok()?;
+        let minor = parts
+            .next()?
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse::<u64>()
+            .ok()?;
+
+        match (major, minor) {
+            (0, 34) => Some(Self::V0_34),
+            (0, 37) => Some(Self::V0_37),
+            (0, 38) => Some(Self::V0_38),
+            (1, _) => Some(Self::V1),
+            _ => None,
+        }
+    }
+
+    /// Whether the keys and values of event attributes are base64 encoded.
+    #[must_use]
+    pub fn base64_event_attributes(&self) -> bool {
+        *self == Self::V0_34
+    }
+
+    /// Whether `block_results` contains `finalize_block_events` (as opposed to
+    /// `begin_block_events` and `end_block_events`).
+    #[must_use]
+    pub fn has_finalize_block_events(&self) -> bool {
+        *self >= Self::V0_38
+    }
+}
+
+/// Decode the base64 encoded keys and values of the attributes of `events`. Keys and values that
+/// are not valid base64 encoded utf8 strings are left as is.
+pub(crate) fn decode_event_attributes(events: &mut [Event]) {
+    let decode = |s: &mut String| {
+        if let Some(decoded) = BASE64_STANDARD
+            .decode(&*s)
+            .ok()
+            .and_then(|bz| String::from_utf8(bz).ok())
+        {
+            *s = decoded;
+        }
+    };
+
+    for attribute in events.iter_mut().flat_map(|event| &mut event.attributes) {
+        decode(&mut attribute.key);
+        decode(&mut attribute.value);
+    }
+}
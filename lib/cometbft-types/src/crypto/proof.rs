@@ -14,6 +14,119 @@ pub struct Proof {
     pub aunts: Vec<Bytes<Base64>>,
 }
 
+#[cfg(feature = "hash")]
+impl Proof {
+    /// Verifies that `leaf` is included in the merkle tree with the root `root_hash`.
+    ///
+    /// See <https://github.com/cometbft/cometbft/blob/v0.38.15/crypto/merkle/proof.go#L51>.
+    pub fn verify(&self, root_hash: &H256, leaf: &[u8]) -> Result<(), VerifyError> {
+        let leaf_hash = leaf_hash(leaf);
+
+        if self.leaf_hash.get() != leaf_hash.get() {
+            return Err(VerifyError::LeafHash {
+                expected: self.leaf_hash.into_encoding(),
+                found: leaf_hash,
+            });
+        }
+
+        let computed_root_hash = self.compute_root_hash().ok_or(VerifyError::InvalidProof)?;
+
+        if &computed_root_hash != root_hash {
+            return Err(VerifyError::RootHash {
+                expected: *root_hash,
+                found: computed_root_hash,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Computes the root hash from the leaf hash and the aunts. Returns `None` if the proof is
+    /// malformed.
+    #[must_use]
+    pub fn compute_root_hash(&self) -> Option<H256> {
+        compute_hash_from_aunts(
+            self.index.inner(),
+            self.total.inner(),
+            self.leaf_hash.into_encoding(),
+            &self.aunts,
+        )
+    }
+}
+
+#[cfg(feature = "hash")]
+#[derive(Debug, PartialEq, Clone, thiserror::Error)]
+pub enum VerifyError {
+    #[error("invalid leaf hash: expected {expected}, found {found}")]
+    LeafHash { expected: H256, found: H256 },
+    #[error("invalid root hash: expected {expected}, found {found}")]
+    RootHash { expected: H256, found: H256 },
+    #[error("invalid proof")]
+    InvalidProof,
+}
+
+#[cfg(feature = "hash")]
+fn compute_hash_from_aunts(
+    index: i64,
+    total: i64,
+    leaf_hash: H256,
+    inner_hashes: &[Bytes<Base64>],
+) -> Option<H256> {
+    if index >= total || total <= 0 {
+        return None;
+    }
+
+    match total {
+        1 => inner_hashes.is_empty().then_some(leaf_hash),
+        _ => {
+            let (last, rest) = inner_hashes.split_last()?;
+            let num_left = split_point(total);
+
+            if index < num_left {
+                let left = compute_hash_from_aunts(index, num_left, leaf_hash, rest)?;
+                Some(inner_hash(left, last))
+            } else {
+                let right =
+                    compute_hash_from_aunts(index - num_left, total - num_left, leaf_hash, rest)?;
+                Some(inner_hash(last, right))
+            }
+        }
+    }
+}
+
+/// The largest power of 2 less than `length`.
+#[cfg(feature = "hash")]
+fn split_point(length: i64) -> i64 {
+    let k = 1_i64 << (i64::BITS - length.leading_zeros() - 1);
+
+    if k == length {
+        k >> 1
+    } else {
+        k
+    }
+}
+
+#[cfg(feature = "hash")]
+pub(crate) fn leaf_hash(leaf: &[u8]) -> H256 {
+    use unionlabs::primitives::hash::{HashFunction, Sha256};
+
+    Sha256::default()
+        .chain_update([0])
+        .chain_update(leaf)
+        .finalize()
+}
+
+#[cfg(feature = "hash")]
+fn inner_hash(left: impl AsRef<[u8]>, right: impl AsRef<[u8]>) -> H256 {
+    use unionlabs::primitives::hash::{HashFunction, Sha256};
+
+    Sha256::default()
+        .chain_update([1])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+}
+
 #[cfg(feature = "proto")]
 pub mod proto {
     use unionlabs::{bounded::BoundedIntError, primitives::FixedBytesError};
@@ -51,16 +51,16 @@ impl Header {
     pub fn calculate_merkle_root(&self) -> Option<H256> {
         use prost::Message;
         use protos::google::protobuf::{BytesValue, Int64Value, StringValue};
-        use sha2::{digest::FixedOutput, Digest, Sha256};
+        use unionlabs::primitives::hash::{HashFunction, Sha256};
 
         const LEAF_PREFIX: u8 = 0;
         const INNER_PREFIX: u8 = 1;
 
         fn do_hash(prefix: u8, v: impl AsRef<[u8]>) -> [u8; 32] {
-            Sha256::new()
+            Sha256::default()
                 .chain_update([prefix])
                 .chain_update(v)
-                .finalize_fixed()
+                .finalize()
                 .into()
         }
 
@@ -69,11 +69,11 @@ impl Header {
         }
 
         fn inner_hash(l: impl AsRef<[u8]>, r: impl AsRef<[u8]>) -> [u8; 32] {
-            Sha256::new()
+            Sha256::default()
                 .chain_update([INNER_PREFIX])
                 .chain_update(l)
                 .chain_update(r)
-                .finalize_fixed()
+                .finalize()
                 .into()
         }
 
@@ -13,6 +13,39 @@ pub struct TxProof {
     pub proof: Proof,
 }
 
+#[cfg(feature = "hash")]
+impl TxProof {
+    /// Verifies that the transaction in `data` is included in the block with the data hash
+    /// `data_hash`. The leaves of the data hash merkle tree are the hashes of the transactions.
+    ///
+    /// See <https://github.com/cometbft/cometbft/blob/v0.38.15/types/tx.go#L131>.
+    pub fn validate(&self, data_hash: &H256) -> Result<(), ValidateError> {
+        use unionlabs::primitives::hash::{HashFunction, Sha256};
+
+        if self.root_hash.get() != data_hash.get() {
+            return Err(ValidateError::RootHash {
+                expected: *data_hash,
+                found: self.root_hash.into_encoding(),
+            });
+        }
+
+        let tx_hash = Sha256::hash(&self.data);
+
+        self.proof.verify(data_hash, tx_hash.get())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "hash")]
+#[derive(Debug, PartialEq, Clone, thiserror::Error)]
+pub enum ValidateError {
+    #[error("invalid root hash: expected {expected}, found {found}")]
+    RootHash { expected: H256, found: H256 },
+    #[error("invalid proof")]
+    Proof(#[from] crate::crypto::proof::VerifyError),
+}
+
 #[cfg(feature = "proto")]
 pub mod proto {
     use unionlabs::{errors::MissingField, primitives::FixedBytesError, required};
@@ -51,3 +84,93 @@ pub mod proto {
         Proof(#[from] proof::proto::Error),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+
+    use super::*;
+    use crate::crypto::proof::VerifyError;
+
+    // block with the transactions "tx0", "tx1" and "tx2"
+    const DATA_HASH: H256 = H256::new(hex!(
+        "4C13E5E804CF591F35C2BEABA7BFA3A284E107F9DAE70A729FF99A1C5E8B4E61"
+    ));
+
+    fn left_subtree_proof() -> TxProof {
+        serde_json::from_str(
+            r#"
+{
+  "root_hash": "4C13E5E804CF591F35C2BEABA7BFA3A284E107F9DAE70A729FF99A1C5E8B4E61",
+  "data": "dHgx",
+  "proof": {
+    "total": "3",
+    "index": "1",
+    "leaf_hash": "k0B+K/lMD3fpDwXFxvtZ8yVmxYVc1YrgWf0RIj6P7l0=",
+    "aunts": [
+      "XgvuOwoueDoOQ6W5PF12mtB5actiE9AJdjFT8HE0/KM=",
+      "/oM0/wOCYVahUKKYzuwrLssSPTpwn/UJoXgEupMwP+Y="
+    ]
+  }
+}
+"#,
+        )
+        .unwrap()
+    }
+
+    fn right_subtree_proof() -> TxProof {
+        serde_json::from_str(
+            r#"
+{
+  "root_hash": "4C13E5E804CF591F35C2BEABA7BFA3A284E107F9DAE70A729FF99A1C5E8B4E61",
+  "data": "dHgy",
+  "proof": {
+    "total": "3",
+    "index": "2",
+    "leaf_hash": "/oM0/wOCYVahUKKYzuwrLssSPTpwn/UJoXgEupMwP+Y=",
+    "aunts": [
+      "zY6aGS8cK446fjbb7275DKwS/tfy0Y5NrxaaME9rJDg="
+    ]
+  }
+}
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(left_subtree_proof().validate(&DATA_HASH), Ok(()));
+        assert_eq!(right_subtree_proof().validate(&DATA_HASH), Ok(()));
+    }
+
+    #[test]
+    fn validate_tampered_tx() {
+        let mut proof = right_subtree_proof();
+        proof.data = b"tx3".into();
+
+        assert!(matches!(
+            proof.validate(&DATA_HASH),
+            Err(ValidateError::Proof(VerifyError::LeafHash { .. }))
+        ));
+    }
+
+    #[test]
+    fn validate_tampered_aunts() {
+        let mut proof = left_subtree_proof();
+        proof.proof.aunts.swap(0, 1);
+
+        assert!(matches!(
+            proof.validate(&DATA_HASH),
+            Err(ValidateError::Proof(VerifyError::RootHash { .. }))
+        ));
+    }
+
+    #[test]
+    fn validate_wrong_data_hash() {
+        assert!(matches!(
+            right_subtree_proof().validate(&H256::new([0; 32])),
+            Err(ValidateError::RootHash { .. })
+        ));
+    }
+}
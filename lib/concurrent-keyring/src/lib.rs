@@ -3,7 +3,13 @@
 pub mod private_key;
 
 use std::{
-    collections::HashMap, fmt::Display, hash::Hash, panic::UnwindSafe, path::PathBuf, sync::Arc,
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    hash::Hash,
+    panic::UnwindSafe,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use crossbeam_queue::ArrayQueue;
@@ -39,8 +45,17 @@ pub struct ConcurrentKeyring<A: Hash + Eq, S> {
     addresses_buffer: Arc<ArrayQueue<A>>,
 
     signers: Arc<HashMap<A, S>>,
+
+    /// Signers that ran into account sequence (nonce) contention, and until when they should be
+    /// avoided. See [`Self::report_contention`].
+    contended: Arc<Mutex<HashMap<A, Instant>>>,
+
+    contention_cooldown: Duration,
 }
 
+/// How long a signer is avoided after contention is reported for it, if not configured otherwise.
+pub const DEFAULT_CONTENTION_COOLDOWN: Duration = Duration::from_secs(12);
+
 pub struct KeyringEntry<A, S> {
     pub address: A,
     pub signer: S,
@@ -74,20 +89,157 @@ impl<A: Hash + Eq + Clone + Display, S: 'static> ConcurrentKeyring<A, S> {
             name: Arc::new(name.into()),
             addresses_buffer: Arc::new(addresses_buffer),
             signers: Arc::new(signers),
+            contended: Arc::new(Mutex::new(HashMap::new())),
+            contention_cooldown: DEFAULT_CONTENTION_COOLDOWN,
         }
     }
 
+    /// Set how long a signer is avoided after contention is reported for it.
+    #[must_use]
+    pub fn with_contention_cooldown(mut self, contention_cooldown: Duration) -> Self {
+        self.contention_cooldown = contention_cooldown;
+        self
+    }
+
     pub fn keys(&self) -> impl Iterator<Item = &A> {
         self.signers.keys()
     }
 
+    /// The number of signers that are currently in use.
+    pub fn in_flight(&self) -> usize {
+        self.addresses_buffer.capacity() - self.addresses_buffer.len()
+    }
+
+    /// Report that a transaction of `address` failed due to account sequence (nonce) contention,
+    /// i.e. the signer is used by another process as well or the node has not caught up with its
+    /// previous transaction yet. Other signers are preferred over this one until the contention
+    /// cooldown elapses.
+    pub fn report_contention(&self, address: &A) {
+        warn!(
+            keyring = %self.name,
+            %address,
+            cooldown = ?self.contention_cooldown,
+            "account sequence contention reported for signer"
+        );
+
+        self.contended
+            .lock()
+            .expect("lock is not poisoned; qed;")
+            .insert(address.clone(), Instant::now() + self.contention_cooldown);
+    }
+
+    fn is_contended(&self, address: &A) -> bool {
+        let mut contended = self.contended.lock().expect("lock is not poisoned; qed;");
+
+        match contended.get(address) {
+            Some(until) if *until > Instant::now() => true,
+            Some(_) => {
+                contended.remove(address);
+                false
+            }
+            None => false,
+        }
+    }
+
     pub async fn with<'a, F, Fut>(&'a self, f: F) -> Option<Fut::Output>
     where
         F: FnOnce(&'a S) -> Fut + 'a,
         Fut: Future<Output: 'a> + Sized + UnwindSafe + 'a,
     {
-        let Some(address) = self.addresses_buffer.pop() else {
-            debug!(keyring = %self.name, "high traffic in keyring");
+        self.with_filtered(|_| true, f).await
+    }
+
+    /// Same as [`Self::with`], but only uses one of the `allowed` signers (compared by their
+    /// string representation, case-insensitively). All signers are allowed if `allowed` is `None`.
+    pub async fn with_any_of<'a, F, Fut>(
+        &'a self,
+        allowed: Option<&BTreeSet<String>>,
+        f: F,
+    ) -> Option<Fut::Output>
+    where
+        F: FnOnce(&'a S) -> Fut + 'a,
+        Fut: Future<Output: 'a> + Sized + UnwindSafe + 'a,
+    {
+        match allowed {
+            Some(allowed) => {
+                self.with_filtered(
+                    |address| {
+                        let address = address.to_string();
+                        allowed
+                            .iter()
+                            .any(|allowed| allowed.eq_ignore_ascii_case(&address))
+                    },
+                    f,
+                )
+                .await
+            }
+            None => self.with(f).await,
+        }
+    }
+
+    /// Same as [`Self::with`], but only uses a signer whose address matches `filter`.
+    ///
+    /// Signers that don't match the filter are returned to the ring buffer untouched. Signers that
+    /// are not in use are rotated through in order of least recent use, and signers with reported
+    /// contention are only used if no other matching signer is available.
+    pub async fn with_filtered<'a, F, Fut>(
+        &'a self,
+        filter: impl Fn(&A) -> bool,
+        f: F,
+    ) -> Option<Fut::Output>
+    where
+        F: FnOnce(&'a S) -> Fut + 'a,
+        Fut: Future<Output: 'a> + Sized + UnwindSafe + 'a,
+    {
+        let mut skipped = vec![];
+        let mut contended = None;
+
+        let address = loop {
+            if skipped.len() + usize::from(contended.is_some()) == self.addresses_buffer.capacity()
+            {
+                if contended.is_none() {
+                    warn!(keyring = %self.name, "no signer in keyring matches the filter");
+                }
+                break None;
+            }
+
+            match self.addresses_buffer.pop() {
+                Some(address) if filter(&address) => {
+                    if !self.is_contended(&address) {
+                        break Some(address);
+                    } else if contended.is_none() {
+                        contended = Some(address);
+                    } else {
+                        skipped.push(address);
+                    }
+                }
+                Some(address) => skipped.push(address),
+                None => break None,
+            }
+        };
+
+        // fall back to a contended signer, rather than waiting for one to become available
+        let address = match (address, contended) {
+            (Some(address), contended) => {
+                skipped.extend(contended);
+                Some(address)
+            }
+            (None, contended) => contended,
+        };
+
+        for address in skipped {
+            self.addresses_buffer
+                .push(address)
+                .ok()
+                .expect("no additional items are added; qed;");
+        }
+
+        let Some(address) = address else {
+            debug!(
+                keyring = %self.name,
+                in_flight = self.in_flight(),
+                "high traffic in keyring"
+            );
             return None;
         };
 
@@ -126,10 +278,23 @@ impl<A: Hash + Eq + Clone + Display, S: 'static> ConcurrentKeyring<A, S> {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct KeyringConfig {
     pub name: String,
     pub keys: Vec<KeyringConfigEntry>,
+    /// How long (in seconds) a signer is avoided after it ran into account sequence (nonce)
+    /// contention. Defaults to [`DEFAULT_CONTENTION_COOLDOWN`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contention_cooldown_seconds: Option<u64>,
+}
+
+impl KeyringConfig {
+    #[must_use]
+    pub fn contention_cooldown(&self) -> Duration {
+        self.contention_cooldown_seconds
+            .map_or(DEFAULT_CONTENTION_COOLDOWN, Duration::from_secs)
+    }
 }
 
 impl KeyringConfigEntry {
@@ -147,6 +312,7 @@ impl KeyringConfigEntry {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum KeyringConfigEntry {
     File {
@@ -155,6 +321,7 @@ pub enum KeyringConfigEntry {
     Raw {
         name: String,
         #[serde(with = "::serde_utils::hex_string")]
+        #[cfg_attr(feature = "schemars", schemars(with = "String"))]
         key: Vec<u8>,
     },
 }
@@ -5,11 +5,14 @@ use super::GasFillerT;
 use crate::gas::u128_saturating_mul_f64;
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct GasFiller {
     #[serde(with = "::serde_utils::string")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub gas_price: f64,
     pub gas_denom: String,
     #[serde(with = "::serde_utils::string")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub gas_multiplier: f64,
     pub max_gas: u64,
     #[serde(default)]
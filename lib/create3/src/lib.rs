@@ -1,5 +1,5 @@
 use alloy_primitives::{hex, Address, B256};
-use sha3::{Digest, Keccak256};
+use unionlabs_primitives::hash::{HashFunction, Keccak256};
 
 const PROXY_INITCODE_HASH: [u8; 32] =
     hex!("21c35dbe1b344a2488cf3321d6ce542f8e9f305544ff09e4993a62319a497c1f");
@@ -8,15 +8,14 @@ const PROXY_INITCODE_HASH: [u8; 32] =
 ///
 /// https://github.com/Vectorized/solady/blob/de9aee59648862bb98affd578248d1e75c7073ad/src/utils/CREATE3.sol#L106
 pub fn predict_deterministic_address(deployer: Address, salt: B256) -> Address {
-    let mut bytes: Vec<u8> = Vec::new();
-    bytes.push(0xff);
-    bytes.extend_from_slice(deployer.as_slice());
-    bytes.extend_from_slice(salt.as_slice());
-    bytes.extend_from_slice(&PROXY_INITCODE_HASH);
-
-    let hash = Keccak256::digest(&bytes);
+    let hash = Keccak256::default()
+        .chain_update([0xff])
+        .chain_update(deployer.as_slice())
+        .chain_update(salt.as_slice())
+        .chain_update(PROXY_INITCODE_HASH)
+        .finalize();
     let mut proxy_bytes = [0u8; 20];
-    proxy_bytes.copy_from_slice(&hash[12..]);
+    proxy_bytes.copy_from_slice(&hash.get()[12..]);
 
     // Use proxy address to compute the final contract address.
     // keccak256(rlp(proxy_bytes ++ 0x01)) More here -> https://ethereum.stackexchange.com/a/761/66849
@@ -27,10 +26,10 @@ pub fn predict_deterministic_address(deployer: Address, salt: B256) -> Address {
     // The proxy address
     bytes2.push(0x01);
     // The nonce of the contract
-    let hash2 = Keccak256::digest(&bytes2);
+    let hash2 = Keccak256::hash(&bytes2);
 
     // resulting hash -> The last 20 bytes (40 characters) of the hash.
-    Address::from_slice(&hash2[12..])
+    Address::from_slice(&hash2.get()[12..])
 }
 
 #[cfg(test)]
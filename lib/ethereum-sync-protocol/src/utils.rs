@@ -5,10 +5,12 @@ use beacon_api_types::{
     phase0::{ForkData, SigningData},
 };
 use fork_schedules::{ForkSchedule, Forks};
-use sha2::{Digest, Sha256};
 use ssz::Ssz;
 use typenum::Unsigned;
-use unionlabs::primitives::H256;
+use unionlabs::primitives::{
+    hash::{HashFunction, Sha256},
+    H256,
+};
 
 use crate::{error::InvalidMerkleBranch, GENESIS_SLOT};
 
@@ -162,15 +164,11 @@ pub fn validate_merkle_branch<'a>(
         for (b, i) in branch.iter().zip(0..depth) {
             if let Some(v) = 2u64.checked_pow(i as u32) {
                 let val = index / v % 2;
-                value = Sha256::digest(
-                    if val == 1 {
-                        [*b.get(), *value.get()]
-                    } else {
-                        [*value.get(), *b.get()]
-                    }
-                    .concat(),
-                )
-                .into();
+                let (left, right) = if val == 1 { (b, &value) } else { (&value, b) };
+                value = Sha256::default()
+                    .chain_update(left)
+                    .chain_update(right)
+                    .finalize();
             } else {
                 break 'block false;
             }
@@ -0,0 +1,242 @@
+//! Checks that the IBC contracts an EVM module is configured with are the ones it expects, before
+//! the module starts serving requests.
+
+use alloy::{
+    network::AnyNetwork,
+    primitives::{keccak256, Address, Bytes},
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    serde::WithOtherFields,
+    sol_types::{sol_data, SolCall, SolType},
+};
+use anyhow::{bail, Context as _};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use unionlabs::primitives::{H160, H32};
+
+alloy::sol! {
+    function supportsInterface(bytes4 interfaceId) external view returns (bool);
+    function ibcAddress() external view returns (address);
+}
+
+/// Checks on the IBC contracts of the chain of a module, which are run when the module starts.
+/// The module fails to start if any of them does not pass.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ContractChecks {
+    /// Checks on the `IBCHandler` at the address configured for the module.
+    #[serde(default)]
+    pub ibc_handler: ContractCheck,
+    /// The `UCS03Zkgm` contract, which must be connected to the `IBCHandler`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zkgm: Option<ExpectedContract>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ContractCheck {
+    /// An ERC-165 interface id that the contract must report as supported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface_id: Option<H32>,
+    /// A version that the contract must report.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<ExpectedVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedContract {
+    pub address: H160,
+    /// An ERC-165 interface id that the contract must report as supported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface_id: Option<H32>,
+    /// A version that the contract must report.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<ExpectedVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedVersion {
+    /// The name of a view function without arguments that returns a `string`, such as `version`.
+    pub getter: String,
+    pub value: String,
+}
+
+impl ContractChecks {
+    /// Run the checks against the `IBCHandler` at `ibc_handler_address`.
+    pub async fn verify(
+        &self,
+        provider: &impl Provider<AnyNetwork>,
+        ibc_handler_address: H160,
+    ) -> anyhow::Result<()> {
+        verify_contract(provider, ibc_handler_address, &self.ibc_handler)
+            .await
+            .with_context(|| format!("ibc handler at {ibc_handler_address}"))?;
+
+        if let Some(zkgm) = &self.zkgm {
+            verify_contract(
+                provider,
+                zkgm.address,
+                &ContractCheck {
+                    interface_id: zkgm.interface_id,
+                    version: zkgm.version.clone(),
+                },
+            )
+            .await
+            .with_context(|| format!("zkgm at {}", zkgm.address))?;
+
+            let output = eth_call(
+                provider,
+                zkgm.address.into(),
+                ibcAddressCall {}.abi_encode(),
+            )
+            .await
+            .context("ibcAddress")?;
+
+            let zkgm_ibc_handler = H160::from(ibcAddressCall::abi_decode_returns(&output)?);
+
+            if zkgm_ibc_handler != ibc_handler_address {
+                bail!(
+                    "zkgm at {} is connected to ibc handler {zkgm_ibc_handler}, not \
+                    {ibc_handler_address}",
+                    zkgm.address,
+                );
+            }
+        }
+
+        info!(%ibc_handler_address, "verified contracts");
+
+        Ok(())
+    }
+}
+
+async fn verify_contract(
+    provider: &impl Provider<AnyNetwork>,
+    address: H160,
+    check: &ContractCheck,
+) -> anyhow::Result<()> {
+    let address = Address::from(address);
+
+    if provider.get_code_at(address).await?.is_empty() {
+        bail!("no contract is deployed");
+    }
+
+    if let Some(interface_id) = check.interface_id {
+        let output = eth_call(provider, address, supports_interface_calldata(interface_id))
+            .await
+            .context("supportsInterface")?;
+
+        if !supportsInterfaceCall::abi_decode_returns(&output)? {
+            bail!("interface {interface_id} is not supported");
+        }
+    }
+
+    if let Some(version) = &check.version {
+        let output = eth_call(provider, address, getter_calldata(&version.getter))
+            .await
+            .with_context(|| version.getter.clone())?;
+
+        let actual = decode_string(&output)?;
+
+        if actual != version.value {
+            bail!(
+                "expected {} to be {}, found {actual}",
+                version.getter,
+                version.value
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn eth_call(
+    provider: &impl Provider<AnyNetwork>,
+    address: Address,
+    calldata: Vec<u8>,
+) -> anyhow::Result<Bytes> {
+    Ok(provider
+        .call(WithOtherFields::new(
+            TransactionRequest::default()
+                .to(address)
+                .input(calldata.into()),
+        ))
+        .await?)
+}
+
+fn supports_interface_calldata(interface_id: H32) -> Vec<u8> {
+    supportsInterfaceCall {
+        interfaceId: interface_id.into(),
+    }
+    .abi_encode()
+}
+
+fn getter_calldata(getter: &str) -> Vec<u8> {
+    keccak256(format!("{getter}()"))[..4].to_vec()
+}
+
+fn decode_string(output: &[u8]) -> anyhow::Result<String> {
+    Ok(<(sol_data::String,)>::abi_decode_params(output)
+        .context("getter did not return a string")?
+        .0)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::sol_types::SolValue;
+    use hex_literal::hex;
+
+    use super::*;
+
+    #[test]
+    fn supports_interface() {
+        assert_eq!(
+            supports_interface_calldata(H32::new(hex!("01ffc9a7"))),
+            hex!(
+                "01ffc9a7"
+                "01ffc9a700000000000000000000000000000000000000000000000000000000"
+            )
+        );
+    }
+
+    #[test]
+    fn getter() {
+        assert_eq!(getter_calldata("ibcAddress"), ibcAddressCall::SELECTOR);
+
+        assert_eq!(
+            decode_string(&("ucs03-zkgm-0".to_owned(),).abi_encode_params()).unwrap(),
+            "ucs03-zkgm-0"
+        );
+        assert!(decode_string(&hex!("01")).is_err());
+    }
+
+    #[test]
+    fn config() {
+        let json = r#"{
+            "ibc_handler": {
+                "interface_id": "0x01ffc9a7"
+            },
+            "zkgm": {
+                "address": "0x5fbe74a283f7954f10aa04c2edf55578811aeb03",
+                "version": { "getter": "version", "value": "1.0.0" }
+            }
+        }"#;
+
+        let checks = serde_json::from_str::<ContractChecks>(json).unwrap();
+
+        assert!(checks.ibc_handler.version.is_none());
+        assert_eq!(
+            checks.zkgm.unwrap().version,
+            Some(ExpectedVersion {
+                getter: "version".to_owned(),
+                value: "1.0.0".to_owned(),
+            })
+        );
+        assert_eq!(
+            serde_json::from_str::<ContractChecks>("{}").unwrap(),
+            ContractChecks::default()
+        );
+    }
+}
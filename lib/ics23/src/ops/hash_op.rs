@@ -1,5 +1,5 @@
-use sha2::Digest;
 use unionlabs::cosmos::ics23::hash_op::HashOp;
+use unionlabs_primitives::hash::{HashFunction, Ripemd160, Sha256, Sha512, Sha512Trunc256};
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub enum HashError {
@@ -11,26 +11,11 @@ pub enum HashError {
 
 pub fn do_hash(hash_op: HashOp, preimage: &[u8]) -> Result<Vec<u8>, HashError> {
     let hash = match hash_op {
-        HashOp::Sha256 => sha2::Sha256::new()
-            .chain_update(preimage)
-            .finalize()
-            .to_vec(),
-        HashOp::Sha512 => sha2::Sha512::new()
-            .chain_update(preimage)
-            .finalize()
-            .to_vec(),
-        HashOp::Ripemd160 => ripemd::Ripemd160::new()
-            .chain_update(preimage)
-            .finalize()
-            .to_vec(),
-        HashOp::Bitcoin => ripemd::Ripemd160::new()
-            .chain_update(sha2::Sha256::new().chain_update(preimage).finalize())
-            .finalize()
-            .to_vec(),
-        HashOp::Sha512256 => sha2::Sha512_256::new()
-            .chain_update(preimage)
-            .finalize()
-            .to_vec(),
+        HashOp::Sha256 => Sha256::hash(preimage).into(),
+        HashOp::Sha512 => Sha512::hash(preimage).into(),
+        HashOp::Ripemd160 => Ripemd160::hash(preimage).into(),
+        HashOp::Bitcoin => Ripemd160::hash(Sha256::hash(preimage)).into(),
+        HashOp::Sha512256 => Sha512Trunc256::hash(preimage).into(),
         op => return Err(HashError::UnsupportedOp(op)),
     };
 
@@ -134,7 +134,130 @@ pub struct FailedRecord<T: QueueMessage> {
     // pub created_at: sqlx::types::time::OffsetDateTime,
 }
 
+/// The tables of the queue that items can be inspected in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Table {
+    /// Items that are ready to be processed.
+    Queue,
+    /// Items that are waiting to be optimized.
+    Optimize,
+    /// Items that failed to be processed.
+    Failed,
+}
+
+impl Table {
+    fn select(self) -> &'static str {
+        match self {
+            Table::Queue => {
+                "SELECT id, parents, item, NULL::TEXT AS tag, NULL::TEXT AS message FROM queue"
+            }
+            Table::Optimize => "SELECT id, parents, item, tag, NULL::TEXT AS message FROM optimize",
+            Table::Failed => "SELECT id, parents, item, NULL::TEXT AS tag, message FROM failed",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Table::Queue => "queue",
+            Table::Optimize => "optimize",
+            Table::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(bound(serialize = ""))]
+pub struct ItemRecord<T: QueueMessage> {
+    pub id: i64,
+    pub parents: Vec<i64>,
+    pub item: Json<Op<T>>,
+    /// The tag of the optimizer, for items waiting to be optimized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// The error message, for failed items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
 impl<T: QueueMessage> PgQueue<T> {
+    /// The items in `table` whose JSON representation contains all of `needles`, ordered by id.
+    pub async fn list_items(
+        &self,
+        table: Table,
+        needles: Vec<String>,
+        page: i64,
+        per_page: i64,
+    ) -> Result<Vec<ItemRecord<T>>, sqlx::Error> {
+        sqlx::query(&format!(
+            r#"
+            {select}
+            WHERE
+                (
+                    SELECT
+                        COALESCE(bool_and(strpos(item::TEXT, needle) > 0), TRUE)
+                    FROM
+                        UNNEST($1::TEXT[]) AS needle
+                )
+            ORDER BY
+                id ASC
+            LIMIT
+                $2
+            OFFSET
+                $3
+            "#,
+            select = table.select()
+        ))
+        .bind(needles)
+        .bind(per_page)
+        .bind((page - 1) * per_page)
+        .map(|row| ItemRecord::<T>::from_row(&row))
+        .fetch_all(&self.client)
+        .await?
+        .into_iter()
+        .collect()
+    }
+
+    pub async fn get_item(
+        &self,
+        table: Table,
+        id: i64,
+    ) -> Result<Option<ItemRecord<T>>, sqlx::Error> {
+        sqlx::query(&format!("{select} WHERE id = $1", select = table.select()))
+            .bind(id)
+            .map(|row| ItemRecord::<T>::from_row(&row))
+            .fetch_optional(&self.client)
+            .await?
+            .transpose()
+    }
+
+    /// Removes the items with `ids` from `table`, returning the ids of the removed items. Items
+    /// that are currently being processed or optimized are locked, and are not removed.
+    pub async fn delete_items(&self, table: Table, ids: Vec<i64>) -> Result<Vec<i64>, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            r#"
+            DELETE FROM
+                {table}
+            WHERE
+                id = ANY(
+                    SELECT
+                        id
+                    FROM
+                        {table}
+                    WHERE
+                        id = ANY($1)
+                    FOR UPDATE
+                        SKIP LOCKED
+                )
+            RETURNING
+                id
+            "#,
+            table = table.name()
+        ))
+        .bind(ids)
+        .fetch_all(&self.client)
+        .await
+    }
+
     pub async fn query_failed(
         &self,
         page: i64,
@@ -280,6 +403,11 @@ impl<T: QueueMessage> voyager_vm::Queue<T> for PgQueue<T> {
             CREATE INDEX IF NOT EXISTS index_queue_handle_at ON queue(handle_at DESC) INCLUDE (id);
 
             CREATE INDEX IF NOT EXISTS optimize_tag_id_idx ON optimize(tag, id);
+
+            -- all string values of the items, for counting the pending items of a chain without scanning the tables (see pending_count)
+            CREATE INDEX IF NOT EXISTS index_queue_item_strings ON queue USING GIN (jsonb_path_query_array(item, 'strict $.** ? (@.type() == "string")'));
+
+            CREATE INDEX IF NOT EXISTS index_optimize_item_strings ON optimize USING GIN (jsonb_path_query_array(item, 'strict $.** ? (@.type() == "string")'));
             "#,
         )
         .try_for_each(|result| async move {
@@ -648,6 +776,31 @@ impl<T: QueueMessage> voyager_vm::Queue<T> for PgQueue<T> {
 
         Ok(())
     }
+
+    #[instrument(skip_all, fields(%value))]
+    async fn pending_count<'a>(&'a self, value: &'a str) -> Result<u64, Self::Error> {
+        trace!(%value, "pending count");
+
+        // the expressions must match the indexes on the string values of the items exactly
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT
+                (
+                    SELECT COUNT(*) FROM queue
+                    WHERE jsonb_path_query_array(item, 'strict $.** ? (@.type() == "string")') @> jsonb_build_array($1::TEXT)
+                )
+                + (
+                    SELECT COUNT(*) FROM optimize
+                    WHERE jsonb_path_query_array(item, 'strict $.** ? (@.type() == "string")') @> jsonb_build_array($1::TEXT)
+                )
+            "#,
+        )
+        .bind(value)
+        .fetch_one(&self.client)
+        .await?;
+
+        Ok(count.try_into().expect("count is non-negative"))
+    }
 }
 
 #[instrument(
@@ -0,0 +1,95 @@
+//! Rate limiting of alloy rpc clients, as a [tower layer](Layer) over the transport.
+
+use std::task::{Context, Poll};
+
+use ::alloy::{
+    rpc::{
+        client::{ClientBuilder, RpcClient},
+        json_rpc::{RequestPacket, ResponsePacket},
+    },
+    transports::{TransportError, TransportFut, TransportResult},
+};
+use tower::{Layer, Service};
+
+use crate::{RateLimit, RateLimiter};
+
+/// Connect to `url` (http, ws or ipc), limiting the requests of the client to `rate_limit` if set.
+///
+/// ```rust,ignore
+/// let provider = ProviderBuilder::new()
+///     .connect_client(rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?);
+/// ```
+pub async fn connect(url: &str, rate_limit: Option<RateLimit>) -> TransportResult<RpcClient> {
+    match rate_limit {
+        Some(rate_limit) => {
+            ClientBuilder::default()
+                .layer(RateLimitLayer::new(rate_limit))
+                .connect(url)
+                .await
+        }
+        None => ClientBuilder::default().connect(url).await,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    limiter: RateLimiter,
+}
+
+impl RateLimitLayer {
+    #[must_use]
+    pub fn new(rate_limit: RateLimit) -> Self {
+        Self {
+            limiter: RateLimiter::new(rate_limit),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: RateLimiter,
+}
+
+impl<S> Service<RequestPacket> for RateLimitService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            limiter.acquire().await;
+
+            inner.call(request).await
+        })
+    }
+}
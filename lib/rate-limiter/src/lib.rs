@@ -0,0 +1,168 @@
+//! Token bucket rate limiting of requests to upstream rpc endpoints.
+//!
+//! Requests over the limit are queued (delayed until a token is available) rather than rejected,
+//! so that clients can be pointed at heavily throttled (i.e. free tier) endpoints without having
+//! to handle rate limit errors.
+
+use std::{
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+#[cfg(feature = "alloy")]
+pub mod alloy;
+
+/// Rate limit of the requests to upstream rpc endpoints, configured as the `rate_limit` of a
+/// voyager module. The limit applies to each endpoint of the module separately, and requests over
+/// the limit are queued until they are allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    /// The sustained amount of requests per second.
+    pub max_requests_per_second: NonZeroU32,
+    /// The amount of requests that can be sent at once after being idle. Defaults to
+    /// `max_requests_per_second`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<NonZeroU32>,
+}
+
+impl RateLimit {
+    fn burst(&self) -> NonZeroU32 {
+        self.burst.unwrap_or(self.max_requests_per_second)
+    }
+}
+
+/// A token bucket shared by all clones of the limiter.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    rate_limit: RateLimit,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// The available tokens. Negative if requests are queued, in which case this is the amount of
+    /// tokens reserved by the queued requests.
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(rate_limit: RateLimit) -> Self {
+        Self {
+            rate_limit,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: rate_limit.burst().get().into(),
+                refilled_at: Instant::now(),
+            })),
+        }
+    }
+
+    #[must_use]
+    pub fn rate_limit(&self) -> RateLimit {
+        self.rate_limit
+    }
+
+    /// Wait until a request may be sent. Callers are served in the order they called this.
+    pub async fn acquire(&self) {
+        let wait = self
+            .bucket
+            .lock()
+            .expect("lock is not poisoned; qed;")
+            .reserve(self.rate_limit, Instant::now());
+
+        if !wait.is_zero() {
+            trace!(wait = ?wait, "rate limited");
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl Bucket {
+    /// Reserve a token at `now`, returning the time until it is available.
+    fn reserve(&mut self, rate_limit: RateLimit, now: Instant) -> Duration {
+        let rate = f64::from(rate_limit.max_requests_per_second.get());
+
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * rate).min(rate_limit.burst().get().into());
+        self.refilled_at = now;
+
+        self.tokens -= 1.0;
+
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limit(max_requests_per_second: u32, burst: Option<u32>) -> RateLimit {
+        RateLimit {
+            max_requests_per_second: NonZeroU32::new(max_requests_per_second).unwrap(),
+            burst: burst.map(|burst| NonZeroU32::new(burst).unwrap()),
+        }
+    }
+
+    #[test]
+    fn burst_then_queue() {
+        let rate_limit = rate_limit(10, Some(2));
+        let now = Instant::now();
+        let mut bucket = Bucket {
+            tokens: 2.0,
+            refilled_at: now,
+        };
+
+        assert_eq!(bucket.reserve(rate_limit, now), Duration::ZERO);
+        assert_eq!(bucket.reserve(rate_limit, now), Duration::ZERO);
+        assert_eq!(bucket.reserve(rate_limit, now), Duration::from_millis(100));
+        assert_eq!(bucket.reserve(rate_limit, now), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn refill_is_capped_at_burst() {
+        let rate_limit = rate_limit(10, None);
+        let now = Instant::now();
+        let mut bucket = Bucket {
+            tokens: 0.0,
+            refilled_at: now,
+        };
+
+        for _ in 0..10 {
+            assert_eq!(
+                bucket.reserve(rate_limit, now + Duration::from_secs(60)),
+                Duration::ZERO
+            );
+        }
+
+        assert_eq!(
+            bucket.reserve(rate_limit, now + Duration::from_secs(60)),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[test]
+    fn serde() {
+        assert_eq!(
+            serde_json::from_str::<RateLimit>(r#"{"max_requests_per_second":5}"#).unwrap(),
+            rate_limit(5, None)
+        );
+        assert_eq!(
+            serde_json::from_str::<RateLimit>(r#"{"max_requests_per_second":5,"burst":20}"#)
+                .unwrap(),
+            rate_limit(5, Some(20))
+        );
+    }
+}
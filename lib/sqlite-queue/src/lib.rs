@@ -0,0 +1,657 @@
+use std::{
+    fmt::Write,
+    future::Future,
+    marker::PhantomData,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures_util::TryStreamExt;
+use itertools::Itertools;
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::{
+    prelude::FromRow,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+    Either, Executor, Sqlite, SqlitePool, Transaction,
+};
+use tracing::{debug, debug_span, error, info, info_span, instrument, trace, warn, Instrument};
+use voyager_vm::{
+    filter::{FilterResult, Interest, InterestFilter},
+    pass::{Pass, PassResult},
+    BoxDynError, Captures, EnqueueResult, ItemId, Op, QueueError, QueueMessage,
+};
+
+/// A fifo queue backed by an embedded sqlite database.
+///
+/// Items that are ready to be processed and items waiting to be optimized are stored in the same
+/// table (the latter with the tag of the optimizer), so that they share one id sequence. Items are
+/// claimed while they are being processed or optimized instead of being locked in a transaction,
+/// since sqlite only allows one writer at a time. The database must therefore only be used by a
+/// single process; claims are released on startup.
+#[derive(Debug, Clone)]
+pub struct SqliteQueue<T> {
+    client: SqlitePool,
+    optimize_batch_limit: Option<i64>,
+    retryable_error_expo_backoff_max: f64,
+    retryable_error_expo_backoff_multiplier: f64,
+
+    __marker: PhantomData<fn() -> T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteQueueConfig {
+    /// Path to the database file, created if it does not exist.
+    pub path: PathBuf,
+    #[serde(default)]
+    pub optimize_batch_limit: Option<i64>,
+    #[serde(default = "default_retryable_error_expo_backoff_max")]
+    pub retryable_error_expo_backoff_max: f64,
+    #[serde(default = "default_retryable_error_expo_backoff_multiplier")]
+    pub retryable_error_expo_backoff_multiplier: f64,
+}
+
+pub const fn default_retryable_error_expo_backoff_max() -> f64 {
+    60.0 * 5.0
+}
+
+pub const fn default_retryable_error_expo_backoff_multiplier() -> f64 {
+    2.0
+}
+
+impl SqliteQueueConfig {
+    pub async fn into_sqlite_pool(self) -> sqlx::Result<SqlitePool> {
+        SqlitePoolOptions::new()
+            // sqlite only allows one writer at a time, and all transactions of this queue write
+            .max_connections(1)
+            .connect_with(
+                SqliteConnectOptions::new()
+                    .filename(self.path)
+                    .create_if_missing(true)
+                    .journal_mode(SqliteJournalMode::Wal),
+            )
+            .await
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct QueueRecord {
+    id: i64,
+    parents: String,
+    item: String,
+    created_at: i64,
+    attempt: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct OptimizeRecord {
+    id: i64,
+    item: String,
+}
+
+impl<T: QueueMessage> voyager_vm::Queue<T> for SqliteQueue<T> {
+    type Config = SqliteQueueConfig;
+    type Error = sqlx::Error;
+
+    async fn new(config: Self::Config) -> Result<Self, Self::Error> {
+        let optimize_batch_limit = config.optimize_batch_limit;
+        let retryable_error_expo_backoff_multiplier =
+            config.retryable_error_expo_backoff_multiplier;
+        let retryable_error_expo_backoff_max = config.retryable_error_expo_backoff_max;
+
+        let pool = config.into_sqlite_pool().await?;
+
+        pool.execute_many(
+            r#"
+            CREATE TABLE IF NOT EXISTS
+              queue (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                item TEXT NOT NULL,
+                -- the tag of the optimizer for items waiting to be optimized, null for ready items
+                tag TEXT,
+                parents TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                handle_at INTEGER NOT NULL,
+                attempt INTEGER NOT NULL DEFAULT 0,
+                claimed INTEGER NOT NULL DEFAULT 0
+              );
+
+            CREATE TABLE IF NOT EXISTS
+              done (
+                id INTEGER NOT NULL,
+                item TEXT NOT NULL,
+                parents TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL
+              );
+
+            CREATE TABLE IF NOT EXISTS
+              failed (
+                id INTEGER PRIMARY KEY,
+                item TEXT NOT NULL,
+                parents TEXT NOT NULL DEFAULT '[]',
+                message TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+              );
+
+            CREATE INDEX IF NOT EXISTS index_queue_handle_at ON queue (tag, claimed, handle_at);
+
+            CREATE INDEX IF NOT EXISTS index_queue_tag_id ON queue (tag, claimed, id);
+            "#,
+        )
+        .try_for_each(|result| async move {
+            trace!("rows affected: {}", result.rows_affected());
+            Ok(())
+        })
+        .instrument(info_span!("init"))
+        .await?;
+
+        // items that were claimed when the process stopped were never finished
+        let released = pool
+            .execute("UPDATE queue SET claimed = 0 WHERE claimed = 1")
+            .instrument(info_span!("release"))
+            .await?;
+
+        if released.rows_affected() > 0 {
+            info!(
+                count = released.rows_affected(),
+                "released items claimed by a previous run"
+            );
+        }
+
+        Ok(Self {
+            client: pool,
+            optimize_batch_limit,
+            retryable_error_expo_backoff_max,
+            retryable_error_expo_backoff_multiplier,
+            __marker: PhantomData,
+        })
+    }
+
+    async fn enqueue<'a, Filter: InterestFilter<T>>(
+        &'a self,
+        op: Op<T>,
+        filter: &'a Filter,
+    ) -> Result<EnqueueResult, Self::Error> {
+        trace!("enqueue");
+
+        let mut tx = self.client.begin().await?;
+
+        let mut result = EnqueueResult {
+            queue: vec![],
+            optimize: vec![],
+        };
+
+        for (op, tag) in route(filter, op.normalize()) {
+            let id = insert(&mut tx, &op, tag, &[]).await?;
+
+            match tag {
+                Some(_) => {
+                    debug!(id, "enqueued optimize item");
+                    result.optimize.push(item_id(id));
+                }
+                None => {
+                    debug!(id, "enqueued ready item");
+                    result.queue.push(item_id(id));
+                }
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(result)
+    }
+
+    #[instrument(skip_all)]
+    async fn process<'a, F, Fut, R, Filter>(
+        &'a self,
+        filter: &'a Filter,
+        f: F,
+    ) -> Result<Option<R>, Self::Error>
+    where
+        F: (FnOnce(Op<T>, ItemId) -> Fut) + Send + Captures<'a>,
+        Fut: Future<Output = (R, Result<Vec<Op<T>>, QueueError>)> + Send + Captures<'a>,
+        R: Send + Sync + 'static,
+        Filter: InterestFilter<T>,
+    {
+        trace!("process");
+
+        let row = sqlx::query(
+            r#"
+            UPDATE
+              queue
+            SET
+              claimed = 1
+            WHERE
+              id = (
+                SELECT
+                  id
+                FROM
+                  queue
+                WHERE
+                  tag IS NULL
+                  AND claimed = 0
+                  AND handle_at <= $1
+                ORDER BY
+                  handle_at ASC
+                LIMIT 1)
+            RETURNING
+              id,
+              parents,
+              item,
+              created_at,
+              attempt
+            "#,
+        )
+        .bind(now_millis())
+        .try_map(|x| QueueRecord::from_row(&x))
+        .fetch_optional(&self.client)
+        .await?;
+
+        match row {
+            Some(record) => {
+                process_item(
+                    &self.client,
+                    record,
+                    f,
+                    filter,
+                    self.retryable_error_expo_backoff_max,
+                    self.retryable_error_expo_backoff_multiplier,
+                )
+                .await
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[instrument(skip_all, fields(%tag))]
+    async fn optimize<'a, O, Filter>(
+        &'a self,
+        tag: &'a str,
+        filter: &'a Filter,
+        optimizer: &'a O,
+    ) -> Result<(), Either<Self::Error, O::Error>>
+    where
+        O: Pass<T>,
+        Filter: InterestFilter<T>,
+    {
+        trace!(%tag, "optimize");
+
+        let mut msgs = sqlx::query(
+            r#"
+            UPDATE
+              queue
+            SET
+              claimed = 1
+            WHERE
+              id IN (
+                SELECT
+                  id
+                FROM
+                  queue
+                WHERE
+                  tag = $1
+                  AND claimed = 0
+                ORDER BY
+                  id ASC
+                LIMIT $2)
+            RETURNING
+              id,
+              item
+            "#,
+        )
+        .bind(tag)
+        // a negative limit is no limit
+        .bind(self.optimize_batch_limit.unwrap_or(-1))
+        .try_map(|x| OptimizeRecord::from_row(&x))
+        .fetch_all(&self.client)
+        .await
+        .map_err(Either::Left)?;
+
+        if msgs.is_empty() {
+            trace!("optimizer queue is empty");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            return Ok(());
+        }
+
+        // the order of the returned rows is unspecified
+        msgs.sort_by_key(|r| r.id);
+
+        let ids = msgs.iter().map(|r| r.id).collect::<Vec<_>>();
+
+        let res: Result<PassResult<T>, Either<Self::Error, O::Error>> = async {
+            let msgs = msgs
+                .iter()
+                .map(|r| de(&r.item).map_err(|e| sqlx::Error::Decode(Box::new(e))))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(Either::Left)?;
+
+            optimizer
+                .run_pass(msgs)
+                .instrument(debug_span!(
+                    "optimizing items",
+                    ids = ids.iter().map(|id| id.to_string()).join(",")
+                ))
+                .await
+                .map_err(Either::Right)
+        }
+        .await;
+
+        let PassResult {
+            optimize_further,
+            ready,
+        } = match res {
+            Ok(res) => res,
+            Err(err) => {
+                release(&self.client, &ids).await.map_err(Either::Left)?;
+                return Err(err);
+            }
+        };
+
+        trace!(
+            ready = ready.len(),
+            optimize_further = optimize_further.len(),
+            "optimized items"
+        );
+
+        let get_parent_ids = |parent_idxs: &[usize]| {
+            ids.iter()
+                .enumerate()
+                .filter_map(|(idx, id)| parent_idxs.contains(&idx).then_some(*id))
+                .collect::<Vec<_>>()
+        };
+
+        let mut tx = self.client.begin().await.map_err(Either::Left)?;
+
+        for id in &ids {
+            sqlx::query("DELETE FROM queue WHERE id = $1")
+                .bind(id)
+                .execute(tx.as_mut())
+                .await
+                .map_err(Either::Left)?;
+        }
+
+        for (parent_idxs, new_msg, tag) in optimize_further {
+            let parents = get_parent_ids(&parent_idxs);
+            trace!(parent_idxs = ?&parent_idxs, parents = ?&parents);
+
+            let id = insert(&mut tx, &new_msg, Some(&tag), &parents)
+                .await
+                .map_err(Either::Left)?;
+
+            debug!(id, "inserted new optimizer message");
+        }
+
+        for (parent_idxs, op) in ready {
+            let parents = get_parent_ids(&parent_idxs);
+            trace!(parent_idxs = ?&parent_idxs, parents = ?&parents);
+
+            for (op, tag) in route(filter, op.normalize()) {
+                let id = insert(&mut tx, &op, tag, &parents)
+                    .await
+                    .map_err(Either::Left)?;
+
+                match tag {
+                    Some(_) => debug!(id, "inserted new optimizer message"),
+                    None => debug!(id, "enqueued ready item"),
+                }
+            }
+        }
+
+        tx.commit().await.map_err(Either::Left)?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(%value))]
+    async fn pending_count<'a>(&'a self, value: &'a str) -> Result<u64, Self::Error> {
+        trace!(%value, "pending count");
+
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM queue
+            WHERE EXISTS (SELECT 1 FROM json_tree(queue.item) WHERE type = 'text' AND value = $1)
+            "#,
+        )
+        .bind(value)
+        .fetch_one(&self.client)
+        .await?;
+
+        Ok(count.try_into().expect("count is non-negative"))
+    }
+}
+
+#[instrument(
+    skip_all,
+    fields(
+        item_id = record.id,
+        attempt = record.attempt
+    )
+)]
+async fn process_item<'a, T, F, Fut, R, Filter>(
+    client: &SqlitePool,
+    record: QueueRecord,
+    f: F,
+    filter: &'a Filter,
+    retryable_error_expo_backoff_max: f64,
+    retryable_error_expo_backoff_multiplier: f64,
+) -> Result<Option<R>, sqlx::Error>
+where
+    T: QueueMessage,
+    F: (FnOnce(Op<T>, ItemId) -> Fut) + Send + Captures<'a>,
+    Fut: Future<Output = (R, Result<Vec<Op<T>>, QueueError>)> + Send + Captures<'a>,
+    R: Send + Sync + 'static,
+    Filter: InterestFilter<T>,
+{
+    trace!(%record.item);
+
+    let op = match de::<Op<T>>(&record.item) {
+        Ok(op) => op,
+        Err(e) => {
+            // the item would never be decodable, so it is failed instead of being released
+            let error = format!("error decoding item: {e}");
+            error!(%error);
+
+            let mut tx = client.begin().await?;
+            fail(&mut tx, record, error).await?;
+            tx.commit().await?;
+
+            return Ok(None);
+        }
+    };
+
+    let (r, res) = f(op, item_id(record.id)).await;
+
+    let mut tx = client.begin().await?;
+
+    match res {
+        Err(QueueError::Fatal(error)) => {
+            let error = full_error_string(error);
+            error!(%error, "fatal error");
+            fail(&mut tx, record, error).await?;
+        }
+        Err(QueueError::Unprocessable(error)) => {
+            let error = full_error_string(error);
+            info!(%error, "unprocessable message");
+            fail(&mut tx, record, error).await?;
+        }
+        Err(QueueError::Retry(error)) => {
+            warn!(error = %full_error_string(error), "retryable error");
+
+            let backoff = Duration::try_from_secs_f64(
+                (record.attempt as f64)
+                    .powf(retryable_error_expo_backoff_multiplier)
+                    .clamp(f64::MIN, retryable_error_expo_backoff_max),
+            )
+            .unwrap_or(Duration::MAX);
+
+            sqlx::query(
+                "
+                UPDATE queue
+                SET claimed = 0, attempt = $2, handle_at = $3
+                WHERE id = $1
+                ",
+            )
+            .bind(record.id)
+            .bind(record.attempt.saturating_add(1))
+            .bind(
+                now_millis().saturating_add(i64::try_from(backoff.as_millis()).unwrap_or(i64::MAX)),
+            )
+            .execute(tx.as_mut())
+            .await?;
+
+            tx.commit().await?;
+
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            return Ok(Some(r));
+        }
+        Ok(ops) => {
+            sqlx::query("DELETE FROM queue WHERE id = $1")
+                .bind(record.id)
+                .execute(tx.as_mut())
+                .await?;
+
+            // insert the op we just processed into done
+            sqlx::query(
+                "
+                INSERT INTO
+                done   (id, parents, item, created_at)
+                VALUES ($1, $2,      $3,   $4        )
+                ",
+            )
+            .bind(record.id)
+            .bind(&record.parents)
+            .bind(&record.item)
+            .bind(record.created_at)
+            .execute(tx.as_mut())
+            .await?;
+
+            for (op, tag) in route(filter, ops.into_iter().flat_map(Op::normalize)) {
+                insert(&mut tx, &op, tag, &[record.id]).await?;
+            }
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(Some(r))
+}
+
+/// Pairs every op with the tags of the optimizers that are interested in it, and `None` if it is
+/// ready to be processed.
+fn route<'a, T: QueueMessage, Filter: InterestFilter<T>>(
+    filter: &'a Filter,
+    ops: impl IntoIterator<Item = Op<T>>,
+) -> Vec<(Op<T>, Option<&'a str>)> {
+    ops.into_iter()
+        .flat_map(|op| match filter.check_interest(&op) {
+            FilterResult::Interest(Interest { tags, remove }) => tags
+                .into_iter()
+                .map(|tag| (op.clone(), Some(tag)))
+                .chain((!remove).then(|| (op.clone(), None)))
+                .collect::<Vec<_>>(),
+            FilterResult::NoInterest => vec![(op, None)],
+        })
+        .collect()
+}
+
+async fn insert<T: QueueMessage>(
+    tx: &mut Transaction<'static, Sqlite>,
+    op: &Op<T>,
+    tag: Option<&str>,
+    parents: &[i64],
+) -> Result<i64, sqlx::Error> {
+    let now = now_millis();
+
+    sqlx::query_scalar(
+        "
+        INSERT INTO
+        queue  (item, tag, parents, created_at, handle_at)
+        VALUES ($1,   $2,  $3,      $4,         $4       )
+        RETURNING id
+        ",
+    )
+    .bind(serde_json::to_string(op).expect("serialization is infallible; qed;"))
+    .bind(tag)
+    .bind(serde_json::to_string(parents).expect("serialization is infallible; qed;"))
+    .bind(now)
+    .fetch_one(tx.as_mut())
+    .await
+}
+
+async fn fail(
+    tx: &mut Transaction<'static, Sqlite>,
+    record: QueueRecord,
+    error: String,
+) -> Result<(), sqlx::Error> {
+    // move the op into failed, along with the error message
+
+    sqlx::query("DELETE FROM queue WHERE id = $1")
+        .bind(record.id)
+        .execute(tx.as_mut())
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO
+        failed (id, parents, item, created_at, message)
+        VALUES ($1, $2,      $3,   $4,         $5     )
+        "#,
+    )
+    .bind(record.id)
+    .bind(record.parents)
+    .bind(record.item)
+    .bind(record.created_at)
+    .bind(error)
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+async fn release(client: &SqlitePool, ids: &[i64]) -> Result<(), sqlx::Error> {
+    let mut tx = client.begin().await?;
+
+    for id in ids {
+        sqlx::query("UPDATE queue SET claimed = 0 WHERE id = $1")
+            .bind(id)
+            .execute(tx.as_mut())
+            .await?;
+    }
+
+    tx.commit().await
+}
+
+fn item_id(id: i64) -> ItemId {
+    ItemId::new(id).expect("invalid id returned from database")
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_millis()
+        .try_into()
+        .expect("how many milliseconds can there be man")
+}
+
+// copied from unionlabs::ErrorReporter
+fn full_error_string(error: BoxDynError) -> String {
+    let mut s = String::new();
+
+    write!(s, "{}", error).unwrap();
+
+    for e in core::iter::successors(error.source(), |e| (*e).source()) {
+        write!(s, ": {e}").unwrap();
+    }
+
+    s
+}
+
+fn de<T: DeserializeOwned>(s: &str) -> Result<T, serde_json::Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(s);
+    deserializer.disable_recursion_limit();
+    let json = T::deserialize(&mut deserializer)?;
+    Ok(json)
+}
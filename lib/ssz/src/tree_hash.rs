@@ -5,9 +5,11 @@ mod merkleize_standard;
 pub use merkle_hasher::{Error, MerkleHasher};
 pub use merkleize_padded::merkleize_padded;
 pub use merkleize_standard::merkleize_standard;
-use sha2::{digest::FixedOutput, Digest, Sha256};
 use smallvec::SmallVec;
-use unionlabs_primitives::H256;
+use unionlabs_primitives::{
+    hash::{HashFunction, Sha256},
+    H256,
+};
 
 pub const BYTES_PER_CHUNK: usize = 32;
 pub const HASHSIZE: usize = 32;
@@ -19,16 +21,15 @@ pub type PackedEncoding = SmallVec<[u8; SMALLVEC_SIZE]>;
 
 #[must_use]
 pub fn hash_fixed(input: &[u8]) -> H256 {
-    Sha256::new().chain_update(input).finalize_fixed().into()
+    Sha256::hash(input)
 }
 
 #[must_use]
 pub fn hash_concat(h1: &[u8], h2: &[u8]) -> H256 {
-    sha2::Sha256::new()
+    Sha256::default()
         .chain_update(h1)
         .chain_update(h2)
         .finalize()
-        .into()
 }
 
 /// Convenience method for `MerkleHasher` which also provides some fast-paths for small trees.
@@ -1,8 +1,10 @@
 use std::mem;
 
-use sha2::Digest;
 use smallvec::{smallvec, SmallVec};
-use unionlabs_primitives::H256;
+use unionlabs_primitives::{
+    hash::{HashFunction, Sha256},
+    H256,
+};
 
 use crate::tree_hash::{get_zero_hash, HASHSIZE};
 
@@ -35,7 +37,7 @@ impl Preimage<'_> {
 /// A node that has had a left child supplied, but not a right child.
 struct HalfNode {
     /// The hasher context.
-    context: sha2::Sha256,
+    context: Sha256,
     /// The tree id of the node. The root node has in id of `1` and ids increase moving down the
     /// tree from left to right.
     id: usize,
@@ -44,7 +46,7 @@ struct HalfNode {
 impl HalfNode {
     /// Create a new half-node from the given `left` value.
     fn new(id: usize, left: Preimage) -> Self {
-        let mut context = sha2::Sha256::new();
+        let mut context = Sha256::default();
         context.update(left.as_bytes());
 
         Self { context, id }
@@ -54,7 +56,7 @@ impl HalfNode {
     /// nodes.
     fn finish(mut self, right: Preimage) -> H256 {
         self.context.update(right.as_bytes());
-        self.context.finalize().into()
+        self.context.finalize()
     }
 }
 
@@ -12,6 +12,7 @@ use crate::Fraction;
     serde(deny_unknown_fields)
 )]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ClientState {
     pub chain_id: String,
     pub trust_level: Fraction,
@@ -118,6 +119,7 @@ pub mod proto {
 mod tests {
     use std::num::NonZeroU64;
 
+    use arbitrary::Unstructured;
     use hex_literal::hex;
     use ics23::ibc_api::SDK_SPECS;
     use unionlabs::{
@@ -125,6 +127,7 @@ mod tests {
             hash_op::HashOp, inner_spec::InnerSpec, leaf_op::LeafOp, length_op::LengthOp,
         },
         encoding::{Bincode, Json, Proto},
+        fuzzing::encode_roundtrip,
         google::protobuf::any::Any,
         primitives::Bytes,
         test_utils::{assert_codec_iso, assert_codec_iso_bytes},
@@ -364,4 +367,19 @@ mod tests {
             &bz,
         );
     }
+
+    #[test]
+    fn arbitrary_roundtrip() {
+        let data = (0..=u8::MAX)
+            .flat_map(|a| (0..=u8::MAX).map(move |b| a.wrapping_mul(b) ^ b))
+            .collect::<Vec<_>>();
+
+        let mut u = Unstructured::new(&data);
+
+        for _ in 0..32 {
+            encode_roundtrip::<ClientState, Bincode>(&mut u).unwrap();
+            encode_roundtrip::<ClientState, Json>(&mut u).unwrap();
+            encode_roundtrip::<ClientState, Proto>(&mut u).unwrap();
+        }
+    }
 }
@@ -11,6 +11,7 @@ use unionlabs::{
     serde(deny_unknown_fields)
 )]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ConsensusState {
     pub timestamp: Timestamp,
     pub root: MerkleRoot,
@@ -106,8 +107,10 @@ pub mod proto {
 
 #[cfg(test)]
 mod tests {
+    use arbitrary::Unstructured;
     use unionlabs::{
         encoding::{Bincode, EthAbi, Json, Proto},
+        fuzzing::encode_roundtrip,
         test_utils::assert_codec_iso,
     };
 
@@ -145,4 +148,19 @@ mod tests {
     fn proto_iso() {
         assert_codec_iso::<_, Proto>(&mk_consensus_state());
     }
+
+    #[test]
+    fn arbitrary_roundtrip() {
+        let data = (0..=u8::MAX)
+            .flat_map(|a| (0..=u8::MAX).map(move |b| a.wrapping_mul(b) ^ b))
+            .collect::<Vec<_>>();
+
+        let mut u = Unstructured::new(&data);
+
+        for _ in 0..32 {
+            encode_roundtrip::<ConsensusState, Bincode>(&mut u).unwrap();
+            encode_roundtrip::<ConsensusState, Json>(&mut u).unwrap();
+            encode_roundtrip::<ConsensusState, Proto>(&mut u).unwrap();
+        }
+    }
 }
@@ -7,6 +7,7 @@ use core::num::NonZeroU64;
     serde(deny_unknown_fields)
 )]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Fraction {
     pub numerator: u64,
     pub denominator: NonZeroU64,
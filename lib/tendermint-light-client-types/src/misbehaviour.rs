@@ -0,0 +1,63 @@
+use crate::Header;
+
+/// Two conflicting headers, both signed by a trusted quorum of validators: either for the same
+/// height with different block hashes, or with `header_1` at a higher height than `header_2` but
+/// not after it in time. Submitting this freezes the client.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct Misbehaviour {
+    pub header_1: Header,
+    pub header_2: Header,
+}
+
+#[cfg(feature = "proto")]
+pub mod proto {
+    use unionlabs::{errors::MissingField, impl_proto_via_try_from_into, required};
+
+    use crate::{header, Misbehaviour};
+
+    impl_proto_via_try_from_into!(Misbehaviour => protos::ibc::lightclients::tendermint::v1::Misbehaviour);
+
+    impl From<Misbehaviour> for protos::ibc::lightclients::tendermint::v1::Misbehaviour {
+        fn from(value: Misbehaviour) -> Self {
+            #[allow(deprecated)]
+            Self {
+                client_id: String::new(),
+                header_1: Some(value.header_1.into()),
+                header_2: Some(value.header_2.into()),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Clone, thiserror::Error)]
+    pub enum Error {
+        #[error(transparent)]
+        MissingField(#[from] MissingField),
+        #[error("invalid header 1")]
+        Header1(#[source] header::proto::Error),
+        #[error("invalid header 2")]
+        Header2(#[source] header::proto::Error),
+    }
+
+    impl TryFrom<protos::ibc::lightclients::tendermint::v1::Misbehaviour> for Misbehaviour {
+        type Error = Error;
+
+        fn try_from(
+            value: protos::ibc::lightclients::tendermint::v1::Misbehaviour,
+        ) -> Result<Self, Self::Error> {
+            Ok(Self {
+                header_1: required!(value.header_1)?
+                    .try_into()
+                    .map_err(Error::Header1)?,
+                header_2: required!(value.header_2)?
+                    .try_into()
+                    .map_err(Error::Header2)?,
+            })
+        }
+    }
+}
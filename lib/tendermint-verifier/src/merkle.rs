@@ -1,5 +1,7 @@
-use sha2::{Digest, Sha256};
-use unionlabs::primitives::H256;
+use unionlabs::primitives::{
+    hash::{HashFunction, Sha256},
+    H256,
+};
 
 const LEAF_PREFIX: &[u8] = &[0];
 const INNER_PREFIX: &[u8] = &[1];
@@ -37,21 +39,19 @@ pub fn calculate_merkle_root<Inner: AsRef<[u8]>>(leaves: &[Inner]) -> H256 {
 }
 
 fn inner_hash(left: &[u8], right: &[u8]) -> H256 {
-    Sha256::new()
+    Sha256::default()
         .chain_update(INNER_PREFIX)
         .chain_update(left)
         .chain_update(right)
         .finalize()
-        .into()
 }
 
 // returns tm_hash(0x00 || leaf)
 fn leaf_hash(leaf: &[u8]) -> H256 {
-    Sha256::new()
+    Sha256::default()
         .chain_update(LEAF_PREFIX)
         .chain_update(leaf)
         .finalize()
-        .into()
 }
 
 #[test]
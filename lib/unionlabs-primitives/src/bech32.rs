@@ -1,17 +1,29 @@
 use core::{convert::Infallible, fmt, marker::PhantomData, str::FromStr};
 
+use bech32::{primitives::decode::CheckedHrpstring, Checksum};
 #[cfg(feature = "serde")]
 use serde::{de, Deserialize, Serialize};
 
-use crate::Bytes;
+use crate::{encoding::Encoding, Bytes, FixedBytes, FixedBytesError};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Bech32<Data = Bytes, Hrp = String> {
-    // TODO: Validate the HRP
+    // NOTE: The HRP is only validated when decoding or when changed with `with_hrp`, since `new` is const
     hrp: Hrp,
     data: Data,
 }
 
+/// The checksum algorithm of a bech32 string, as specified in [BIP-173] (`bech32`) and [BIP-350]
+/// (`bech32m`).
+///
+/// [BIP-173]: https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki
+/// [BIP-350]: https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bech32Variant {
+    Bech32,
+    Bech32m,
+}
+
 impl<Data, Hrp> Bech32<Data, Hrp> {
     pub const fn new(hrp: Hrp, data: Data) -> Self {
         Self { hrp, data }
@@ -36,6 +48,20 @@ impl<Data, Hrp> Bech32<Data, Hrp> {
     pub fn map_hrp<NewHrp>(self, f: impl FnOnce(Hrp) -> NewHrp) -> Bech32<Data, NewHrp> {
         Bech32::<Data, NewHrp>::new(f(self.hrp), self.data)
     }
+
+    /// Replace the HRP, i.e. to re-encode an address with the prefix of another chain.
+    ///
+    /// # Errors
+    ///
+    /// This will error if `hrp` is not a valid bech32 HRP.
+    pub fn with_hrp<NewHrp: AsRef<str>>(
+        self,
+        hrp: NewHrp,
+    ) -> Result<Bech32<Data, NewHrp>, Bech32HrpError> {
+        bech32::Hrp::parse(hrp.as_ref()).map_err(|e| Bech32HrpError(e.to_string()))?;
+
+        Ok(Bech32::<Data, NewHrp>::new(hrp, self.data))
+    }
 }
 
 impl<Data, Hrp> Bech32<Data, Hrp>
@@ -49,22 +75,144 @@ where
     ///
     /// This will error if the encoded string is invalid bech32, or if the decoded bytes cannot be converted to `Data`.
     pub fn decode(encoded: impl AsRef<str>) -> Result<Self, Bech32DecodeError<Data::Error>> {
-        let (hrp, bytes) =
-            subtle_encoding::bech32::decode(encoded).map_err(Bech32DecodeError::Decode)?;
+        Self::decode_variant(encoded, Bech32Variant::Bech32)
+    }
 
-        Ok(Self {
-            hrp: Hrp::from(hrp),
-            data: bytes.try_into().map_err(Bech32DecodeError::Data)?,
-        })
+    /// Decode the provided string, which must be checksummed with `variant`.
+    ///
+    /// # Errors
+    ///
+    /// This will error if the encoded string is invalid, has a checksum of a different variant, or if the decoded bytes cannot be converted to `Data`.
+    pub fn decode_variant(
+        encoded: impl AsRef<str>,
+        variant: Bech32Variant,
+    ) -> Result<Self, Bech32DecodeError<Data::Error>> {
+        match variant {
+            Bech32Variant::Bech32 => decode_checked::<bech32::Bech32, _, _>(encoded.as_ref()),
+            Bech32Variant::Bech32m => decode_checked::<bech32::Bech32m, _, _>(encoded.as_ref()),
+        }
+    }
+
+    /// Decode the provided string, which can be checksummed with either variant. The variant of the
+    /// checksum is returned along with the decoded value.
+    ///
+    /// # Errors
+    ///
+    /// This will error if the encoded string is neither valid bech32 nor valid bech32m, or if the decoded bytes cannot be converted to `Data`.
+    pub fn decode_any(
+        encoded: impl AsRef<str>,
+    ) -> Result<(Self, Bech32Variant), Bech32DecodeError<Data::Error>> {
+        match Self::decode_variant(encoded.as_ref(), Bech32Variant::Bech32) {
+            Err(Bech32DecodeError::Decode(_)) => {
+                Self::decode_variant(encoded, Bech32Variant::Bech32m)
+                    .map(|decoded| (decoded, Bech32Variant::Bech32m))
+            }
+            res => res.map(|decoded| (decoded, Bech32Variant::Bech32)),
+        }
+    }
+
+    /// Decode the provided bech32 string, which must have the HRP `expected_hrp`.
+    ///
+    /// # Errors
+    ///
+    /// This will error if the encoded string is invalid bech32, if the HRP is not `expected_hrp`, or if the decoded bytes cannot be converted to `Data`.
+    pub fn decode_with_hrp(
+        encoded: impl AsRef<str>,
+        expected_hrp: &str,
+    ) -> Result<Self, Bech32DecodeError<Data::Error>>
+    where
+        Hrp: AsRef<str>,
+    {
+        let decoded = Self::decode(encoded)?;
+
+        if decoded.hrp.as_ref() == expected_hrp {
+            Ok(decoded)
+        } else {
+            Err(Bech32DecodeError::UnexpectedHrp {
+                expected: expected_hrp.into(),
+                found: decoded.hrp.as_ref().into(),
+            })
+        }
+    }
+}
+
+fn decode_checked<Ck: Checksum, Data: TryFrom<Vec<u8>>, Hrp: From<String>>(
+    encoded: &str,
+) -> Result<Bech32<Data, Hrp>, Bech32DecodeError<Data::Error>> {
+    let checked = CheckedHrpstring::new::<Ck>(encoded)
+        .map_err(|e| Bech32DecodeError::Decode(e.to_string()))?;
+
+    Ok(Bech32 {
+        // uppercase strings are valid, but always displayed in lowercase
+        hrp: Hrp::from(checked.hrp().to_lowercase()),
+        data: checked
+            .byte_iter()
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(Bech32DecodeError::Data)?,
+    })
+}
+
+impl<Data, Hrp> Bech32<Data, Hrp>
+where
+    Data: AsRef<[u8]>,
+    Hrp: AsRef<str>,
+{
+    /// Encode with the checksum of `variant`. [`Display`](fmt::Display) always encodes as bech32.
+    ///
+    /// # Errors
+    ///
+    /// This will error if the HRP is invalid, or if the encoded string would be too long.
+    pub fn encode(&self, variant: Bech32Variant) -> Result<String, Bech32EncodeError> {
+        let hrp =
+            bech32::Hrp::parse(self.hrp.as_ref()).map_err(|e| Bech32EncodeError(e.to_string()))?;
+
+        match variant {
+            Bech32Variant::Bech32 => {
+                bech32::encode_lower::<bech32::Bech32>(hrp, self.data.as_ref())
+            }
+            Bech32Variant::Bech32m => {
+                bech32::encode_lower::<bech32::Bech32m>(hrp, self.data.as_ref())
+            }
+        }
+        .map_err(|e| Bech32EncodeError(e.to_string()))
     }
 }
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub enum Bech32DecodeError<E = Infallible> {
     #[error("error decoding bech32 string: {0}")]
-    Decode(subtle_encoding::Error),
+    Decode(String),
     #[error("unable to convert decoded data")]
     Data(#[source] E),
+    #[error("expected hrp `{expected}`, found `{found}`")]
+    UnexpectedHrp { expected: String, found: String },
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("invalid bech32 hrp: {0}")]
+pub struct Bech32HrpError(String);
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("error encoding bech32 string: {0}")]
+pub struct Bech32EncodeError(String);
+
+impl<E: Encoding, const BYTES: usize, Hrp> From<Bech32<FixedBytes<BYTES, E>, Hrp>>
+    for Bech32<Bytes<E>, Hrp>
+{
+    fn from(value: Bech32<FixedBytes<BYTES, E>, Hrp>) -> Self {
+        value.map_data(FixedBytes::into_bytes)
+    }
+}
+
+impl<E: Encoding, const BYTES: usize, Hrp> TryFrom<Bech32<Bytes<E>, Hrp>>
+    for Bech32<FixedBytes<BYTES, E>, Hrp>
+{
+    type Error = FixedBytesError;
+
+    fn try_from(value: Bech32<Bytes<E>, Hrp>) -> Result<Self, Self::Error> {
+        Ok(Bech32::new(value.hrp, value.data.try_into()?))
+    }
 }
 
 impl<Data, Hrp> FromStr for Bech32<Data, Hrp>
@@ -84,14 +232,81 @@ where
     Hrp: AsRef<str>,
     Data: AsRef<[u8]>,
 {
+    /// Always encodes as bech32. Unlike [`Bech32::encode`], this neither validates the HRP nor
+    /// limits the length of the encoded string, since a `Bech32` with an invalid HRP can be
+    /// constructed with [`Bech32::new`] and displaying it must not fail.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&subtle_encoding::bech32::encode(
-            self.hrp.as_ref(),
-            self.data.as_ref(),
-        ))
+        write_bech32_unchecked(f, self.hrp.as_ref(), self.data.as_ref())
     }
 }
 
+/// Write the bech32 ([BIP-173]) encoding of `hrp` and `data` to `f`, with the HRP in lowercase.
+///
+/// [BIP-173]: https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki
+fn write_bech32_unchecked(f: &mut fmt::Formatter<'_>, hrp: &str, data: &[u8]) -> fmt::Result {
+    const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+    const GENERATOR: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+
+    fn polymod(checksum: u32, value: u8) -> u32 {
+        GENERATOR
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| (checksum >> (25 + i)) & 1 == 1)
+            .fold(
+                ((checksum & 0x01ff_ffff) << 5) ^ u32::from(value),
+                |c, (_, g)| c ^ g,
+            )
+    }
+
+    fn group(value: u32) -> u8 {
+        u8::try_from(value & 31).expect("value is masked to 5 bits; qed;")
+    }
+
+    // regroup the bytes into groups of 5 bits, padding the last group with zeros
+    let mut groups = Vec::with_capacity((data.len() * 8).div_ceil(5));
+    let (mut acc, mut bits) = (0_u32, 0_u32);
+    for byte in data {
+        acc = ((acc << 8) | u32::from(*byte)) & 0x0fff;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            groups.push(group(acc >> bits));
+        }
+    }
+    if bits > 0 {
+        groups.push(group(acc << (5 - bits)));
+    }
+
+    let hrp = hrp.bytes().map(|c| c.to_ascii_lowercase());
+
+    let checksum = hrp
+        .clone()
+        .map(|c| c >> 5)
+        .chain([0])
+        .chain(hrp.clone().map(|c| c & 31))
+        .chain(groups.iter().copied())
+        .chain([0; 6])
+        .fold(1, polymod)
+        ^ 1;
+
+    for c in hrp.chain([b'1']).chain(
+        groups
+            .into_iter()
+            .chain((0..6).rev().map(|i| group(checksum >> (5 * i))))
+            .map(|group| CHARSET[usize::from(group)]),
+    ) {
+        fmt::Write::write_char(f, char::from(c))?;
+    }
+
+    Ok(())
+}
+
 #[cfg(feature = "serde")]
 impl<Data, Hrp> Serialize for Bech32<Data, Hrp>
 where
@@ -155,6 +370,31 @@ where
     }
 }
 
+#[cfg(feature = "schemars")]
+impl<Data, Hrp> schemars::JsonSchema for Bech32<Data, Hrp> {
+    fn schema_name() -> String {
+        "Bech32".to_owned()
+    }
+
+    fn schema_id() -> alloc::borrow::Cow<'static, str> {
+        concat!(module_path!(), "::Bech32").into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, Metadata, SchemaObject, SingleOrVec};
+
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some("A bech32 encoded string".to_owned()),
+                ..Default::default()
+            })),
+            instance_type: Some(SingleOrVec::Single(Box::new(InstanceType::String))),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 #[cfg(feature = "bincode")]
 impl<Hrp, Data> bincode::Encode for Bech32<Data, Hrp>
 where
@@ -201,6 +441,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{encoding::HexPrefixed, H160, H256};
 
     const CONST_BECH32: Bech32<[u8; 4], &'static str> = Bech32::new("hrp", *b"data");
 
@@ -218,6 +459,121 @@ mod tests {
         assert_eq!(s, decoded.to_string());
     }
 
+    #[test]
+    fn variants() {
+        // test vectors from BIP-173 and BIP-350
+        assert_eq!(
+            Bech32::<Bytes>::decode_variant("a12uel5l", Bech32Variant::Bech32).unwrap(),
+            Bech32::new("a".to_owned(), Bytes::default())
+        );
+        assert_eq!(
+            Bech32::<Bytes>::decode_variant("a1lqfn3a", Bech32Variant::Bech32m).unwrap(),
+            Bech32::new("a".to_owned(), Bytes::default())
+        );
+
+        assert!(matches!(
+            Bech32::<Bytes>::decode("a1lqfn3a"),
+            Err(Bech32DecodeError::Decode(_))
+        ));
+        assert!(matches!(
+            Bech32::<Bytes>::decode_variant("a12uel5l", Bech32Variant::Bech32m),
+            Err(Bech32DecodeError::Decode(_))
+        ));
+
+        assert_eq!(
+            Bech32::<Bytes>::decode_any("a1lqfn3a").unwrap().1,
+            Bech32Variant::Bech32m
+        );
+        assert_eq!(
+            Bech32::<Bytes>::decode_any("A12UEL5L").unwrap(),
+            (
+                Bech32::new("a".to_owned(), Bytes::default()),
+                Bech32Variant::Bech32
+            )
+        );
+
+        assert_eq!(
+            Bech32::new("a", Bytes::<HexPrefixed>::default())
+                .encode(Bech32Variant::Bech32m)
+                .unwrap(),
+            "a1lqfn3a"
+        );
+    }
+
+    #[test]
+    fn hrp() {
+        // bech32 encoding of the bytes 1..=20
+        let s = "union1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc544dkgd";
+
+        let decoded = Bech32::<H160>::decode_with_hrp(s, "union").unwrap();
+
+        assert_eq!(decoded.data().get(), &core::array::from_fn(|i| i as u8 + 1));
+
+        assert_eq!(
+            Bech32::<H160>::decode_with_hrp(s, "osmo"),
+            Err(Bech32DecodeError::UnexpectedHrp {
+                expected: "osmo".to_owned(),
+                found: "union".to_owned(),
+            })
+        );
+
+        let reencoded = decoded.clone().with_hrp("osmo").unwrap().to_string();
+
+        assert!(reencoded.starts_with("osmo1"));
+        assert_eq!(
+            Bech32::<H160>::decode_with_hrp(&reencoded, "osmo")
+                .unwrap()
+                .into_data(),
+            decoded.into_data()
+        );
+
+        assert!(Bech32::new("union", H160::default())
+            .with_hrp("invalid hrp")
+            .is_err());
+    }
+
+    #[test]
+    fn display_invalid_hrp() {
+        // the hrp is invalid (empty, or contains a space), and the data is too long for a valid
+        // bech32 string, but they can still be displayed
+        assert_eq!(
+            Bech32::new("", Bytes::<HexPrefixed>::default()).to_string(),
+            "10a06t8"
+        );
+        assert!(Bech32::new("invalid hrp", Bytes::<HexPrefixed>::default())
+            .to_string()
+            .starts_with("invalid hrp1"));
+        assert_eq!(
+            Bech32::new("a", Bytes::<HexPrefixed>::new(vec![0; 1000]))
+                .to_string()
+                .len(),
+            1 + 1 + 1600 + 6
+        );
+
+        // uppercase hrps are displayed in lowercase, as when encoding
+        assert_eq!(
+            Bech32::new("A", Bytes::<HexPrefixed>::default()).to_string(),
+            "a12uel5l"
+        );
+    }
+
+    #[test]
+    fn hash_conversions() {
+        let s = "union1nc5tatafv6eyq7llkr2gv50ff9e22mnf70qgjlv737ktmt4eswrqavqtmv";
+
+        let bytes = s.parse::<Bech32>().unwrap();
+        let hash = Bech32::<H256>::try_from(bytes.clone()).unwrap();
+
+        assert_eq!(Bech32::<Bytes>::from(hash), bytes);
+        assert_eq!(
+            Bech32::<H160>::try_from(bytes),
+            Err(FixedBytesError {
+                expected_len: 20,
+                found_len: 32,
+            })
+        );
+    }
+
     #[test]
     fn json_roundtrip() {
         let s = r#""union1nc5tatafv6eyq7llkr2gv50ff9e22mnf70qgjlv737ktmt4eswrqavqtmv""#;
@@ -184,6 +184,17 @@ impl<'de, E: Encoding> serde::Deserialize<'de> for Bytes<E> {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a, E: Encoding> arbitrary::Arbitrary<'a> for Bytes<E> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u.arbitrary::<Vec<u8>>().map(Self::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <Vec<u8> as arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
 #[cfg(feature = "bincode")]
 impl<Enc: Encoding> bincode::Encode for Bytes<Enc> {
     fn encode<E: bincode::enc::Encoder>(
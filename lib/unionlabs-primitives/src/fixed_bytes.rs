@@ -398,6 +398,17 @@ impl<'de, Context, Enc: Encoding, const BYTES: usize> bincode::BorrowDecode<'de,
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a, E: Encoding, const BYTES: usize> arbitrary::Arbitrary<'a> for FixedBytes<BYTES, E> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u.arbitrary().map(Self::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u8; BYTES] as arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
 #[cfg(feature = "schemars")]
 impl<E: Encoding, const BYTES: usize> schemars::JsonSchema for FixedBytes<BYTES, E> {
     fn schema_name() -> String {
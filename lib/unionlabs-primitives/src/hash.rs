@@ -0,0 +1,186 @@
+//! Hash functions behind a common [`HashFunction`] trait.
+//!
+//! All of the implementations are `no_std` compatible, such that the same hashing is used both
+//! on-chain (in light clients) and off-chain. Each hash function is behind a feature of the same
+//! name.
+
+/// A hash function producing a digest of type [`Self::Output`].
+///
+/// ```rust
+/// # #[cfg(feature = "sha256")] {
+/// # use unionlabs_primitives::hash::{HashFunction, Sha256};
+/// let hash = Sha256::default()
+///     .chain_update(b"hello ")
+///     .chain_update(b"world")
+///     .finalize();
+///
+/// assert_eq!(hash, Sha256::hash(b"hello world"));
+/// # }
+/// ```
+pub trait HashFunction: Default {
+    type Output;
+
+    /// Feed `data` into the hasher.
+    fn update(&mut self, data: impl AsRef<[u8]>);
+
+    /// Consume the hasher, returning the digest of all of the data fed into it.
+    fn finalize(self) -> Self::Output;
+
+    /// Feed `data` into the hasher, returning the hasher.
+    #[must_use]
+    fn chain_update(mut self, data: impl AsRef<[u8]>) -> Self {
+        self.update(data);
+        self
+    }
+
+    /// Hash `data` in one step.
+    #[must_use]
+    fn hash(data: impl AsRef<[u8]>) -> Self::Output {
+        Self::default().chain_update(data).finalize()
+    }
+}
+
+macro_rules! digest_hasher {
+    (
+        $(#[$meta:meta])*
+        #[cfg(feature = $feature:literal)]
+        pub struct $Hasher:ident($Inner:ty) -> $Output:ty via $digest:ident;
+    ) => {
+        $(#[$meta])*
+        #[cfg(feature = $feature)]
+        #[derive(Clone, Default)]
+        pub struct $Hasher($Inner);
+
+        #[cfg(feature = $feature)]
+        impl core::fmt::Debug for $Hasher {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str(stringify!($Hasher))
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl HashFunction for $Hasher {
+            type Output = $Output;
+
+            fn update(&mut self, data: impl AsRef<[u8]>) {
+                $digest::Digest::update(&mut self.0, data);
+            }
+
+            fn finalize(self) -> Self::Output {
+                <$Output>::new($digest::Digest::finalize(self.0).into())
+            }
+        }
+    };
+}
+
+digest_hasher! {
+    /// Keccak-256, as used by the EVM (*not* the finalized SHA3-256).
+    #[cfg(feature = "keccak256")]
+    pub struct Keccak256(sha3::Keccak256) -> crate::H256 via sha3;
+}
+
+digest_hasher! {
+    /// SHA-256.
+    #[cfg(feature = "sha256")]
+    pub struct Sha256(sha2::Sha256) -> crate::H256 via sha2;
+}
+
+digest_hasher! {
+    /// SHA-512.
+    #[cfg(feature = "sha512")]
+    pub struct Sha512(sha2::Sha512) -> crate::H512 via sha2;
+}
+
+digest_hasher! {
+    /// SHA-512/256, SHA-512 truncated to 256 bits (with its own initial values).
+    #[cfg(feature = "sha512-trunc256")]
+    pub struct Sha512Trunc256(sha2::Sha512_256) -> crate::H256 via sha2;
+}
+
+digest_hasher! {
+    /// RIPEMD-160, as used by bitcoin addresses.
+    #[cfg(feature = "ripemd160")]
+    pub struct Ripemd160(ripemd::Ripemd160) -> crate::H160 via ripemd;
+}
+
+digest_hasher! {
+    /// BLAKE2b with a 256-bit digest, as used by Sui.
+    #[cfg(feature = "blake2b")]
+    pub struct Blake2b256(blake2::Blake2b<blake2::digest::consts::U32>) -> crate::H256 via blake2;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_known_vectors<H: HashFunction>(empty: &str, abc: &str)
+    where
+        H::Output: core::str::FromStr + PartialEq + core::fmt::Debug,
+        <H::Output as core::str::FromStr>::Err: core::fmt::Debug,
+    {
+        assert_eq!(H::hash(b""), empty.parse().unwrap());
+        assert_eq!(H::hash(b"abc"), abc.parse().unwrap());
+        assert_eq!(
+            H::default()
+                .chain_update(b"a")
+                .chain_update(b"")
+                .chain_update(b"bc")
+                .finalize(),
+            abc.parse().unwrap()
+        );
+    }
+
+    #[cfg(feature = "keccak256")]
+    #[test]
+    fn keccak256() {
+        assert_known_vectors::<Keccak256>(
+            "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            "0x4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+        );
+    }
+
+    #[cfg(feature = "sha256")]
+    #[test]
+    fn sha256() {
+        assert_known_vectors::<Sha256>(
+            "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            "0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        );
+    }
+
+    #[cfg(feature = "sha512")]
+    #[test]
+    fn sha512() {
+        assert_known_vectors::<Sha512>(
+            "0xcf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+            "0xddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        );
+    }
+
+    #[cfg(feature = "sha512-trunc256")]
+    #[test]
+    fn sha512_trunc256() {
+        assert_known_vectors::<Sha512Trunc256>(
+            "0xc672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a",
+            "0x53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23",
+        );
+    }
+
+    #[cfg(feature = "ripemd160")]
+    #[test]
+    fn ripemd160() {
+        assert_known_vectors::<Ripemd160>(
+            "0x9c1185a5c5e9fc54612808977ee8f548b2258d31",
+            "0x8eb208f7e05d987a9b044a8e98c6b087f15a0bfc",
+        );
+    }
+
+    #[cfg(feature = "blake2b")]
+    #[test]
+    fn blake2b256() {
+        assert_known_vectors::<Blake2b256>(
+            "0x0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8",
+            "0xbddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319",
+        );
+    }
+}
@@ -24,8 +24,10 @@ pub mod uint;
 
 mod compat;
 
+pub mod hash;
+
 pub use crate::{
-    bech32::{Bech32, Bech32DecodeError},
+    bech32::{Bech32, Bech32DecodeError, Bech32EncodeError, Bech32HrpError, Bech32Variant},
     bytes::Bytes,
     fixed_bytes::{FixedBytes, FixedBytesError},
     uint::U256,
@@ -132,6 +132,31 @@ impl bincode::Encode for U256 {
     }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for U256 {
+    fn schema_name() -> String {
+        "U256".to_owned()
+    }
+
+    fn schema_id() -> alloc::borrow::Cow<'static, str> {
+        concat!(module_path!(), "::U256").into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, Metadata, SchemaObject, SingleOrVec};
+
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some("A 256-bit unsigned integer, as a decimal string".to_owned()),
+                ..Default::default()
+            })),
+            instance_type: Some(SingleOrVec::Single(Box::new(InstanceType::String))),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 impl fmt::LowerHex for U256 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if f.alternate() {
@@ -169,6 +194,12 @@ impl From<u64> for U256 {
     }
 }
 
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        Self(primitive_types::U256::from(value))
+    }
+}
+
 impl TryFrom<U256> for u32 {
     type Error = ();
 
@@ -193,6 +224,18 @@ impl TryFrom<U256> for u64 {
     }
 }
 
+impl TryFrom<U256> for u128 {
+    type Error = ();
+
+    fn try_from(value: U256) -> Result<Self, Self::Error> {
+        if value > U256::from(u128::MAX) {
+            Err(())
+        } else {
+            Ok(value.0.as_u128())
+        }
+    }
+}
+
 impl From<primitive_types::U256> for U256 {
     fn from(value: primitive_types::U256) -> Self {
         Self(value)
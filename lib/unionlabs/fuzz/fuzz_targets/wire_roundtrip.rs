@@ -0,0 +1,61 @@
+#![no_main]
+
+use core::fmt::Debug;
+
+use arbitrary::{Arbitrary, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use tendermint_light_client_types::{ClientState, ConsensusState, Fraction};
+use unionlabs::{
+    cosmos::ics23::proof_spec::ProofSpec,
+    encoding::{Bincode, Decode, Encode, Encoding, EthAbi, Json, Proto},
+    fuzzing::{decode_roundtrip, encode_roundtrip},
+    google::protobuf::{duration::Duration, timestamp::Timestamp},
+    ibc::core::{client::height::Height, commitment::merkle_root::MerkleRoot},
+};
+
+/// Decode a `T` from the input, and encode a `T` built from the same input.
+fn roundtrip<T, E: Encoding>(data: &[u8])
+where
+    T: for<'a> Arbitrary<'a> + Encode<E> + Decode<E> + Clone + Debug + PartialEq,
+{
+    decode_roundtrip::<T, E>(data);
+
+    // running out of input is not a failure
+    let _ = encode_roundtrip::<T, E>(&mut Unstructured::new(data));
+}
+
+const TARGETS: &[fn(&[u8])] = &[
+    roundtrip::<Height, Bincode>,
+    roundtrip::<Height, Json>,
+    roundtrip::<Height, Proto>,
+    roundtrip::<MerkleRoot, Bincode>,
+    roundtrip::<MerkleRoot, Json>,
+    roundtrip::<MerkleRoot, Proto>,
+    roundtrip::<Duration, Bincode>,
+    roundtrip::<Duration, Json>,
+    roundtrip::<Duration, Proto>,
+    roundtrip::<Timestamp, Bincode>,
+    roundtrip::<Timestamp, Json>,
+    roundtrip::<Timestamp, Proto>,
+    roundtrip::<ProofSpec, Bincode>,
+    roundtrip::<ProofSpec, Json>,
+    roundtrip::<ProofSpec, Proto>,
+    roundtrip::<Fraction, Bincode>,
+    roundtrip::<Fraction, Json>,
+    roundtrip::<Fraction, Proto>,
+    roundtrip::<ClientState, Bincode>,
+    roundtrip::<ClientState, Json>,
+    roundtrip::<ClientState, Proto>,
+    roundtrip::<ConsensusState, Bincode>,
+    roundtrip::<ConsensusState, Json>,
+    roundtrip::<ConsensusState, Proto>,
+    // only timestamps between 1970 and 2554 can be encoded with ethabi, so only the decoding is
+    // fuzzed
+    decode_roundtrip::<ConsensusState, EthAbi>,
+];
+
+fuzz_target!(|input: (u8, &[u8])| {
+    let (target, data) = input;
+
+    TARGETS[usize::from(target) % TARGETS.len()](data);
+});
@@ -12,6 +12,7 @@ use crate::primitives::{encoding::HexUnprefixed, FixedBytes, FixedBytesError, H2
 )]
 #[debug("AccountAddress({})", self)]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct AccountAddress(pub H256<HexUnprefixed>);
 
 impl AccountAddress {
@@ -61,6 +61,17 @@ macro_rules! bounded_int {
                 }
             }
 
+            #[cfg(feature = "arbitrary")]
+            impl<'a, const MIN: $ty, const MAX: $ty> arbitrary::Arbitrary<'a> for $Struct<MIN, MAX> {
+                fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                    u.int_in_range(MIN..=MAX).map(Self)
+                }
+
+                fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                    <$ty as arbitrary::Arbitrary>::size_hint(depth)
+                }
+            }
+
             impl<const MIN: $ty, const MAX: $ty> serde::Serialize for $Struct<MIN, MAX> {
                 fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
                 where
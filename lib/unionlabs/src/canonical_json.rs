@@ -0,0 +1,151 @@
+//! A canonical JSON encoding, where equal values are always encoded to the same bytes. Digests of
+//! client and consensus states are computed over this encoding, so that they match across services
+//! regardless of the JSON implementation (or its configuration) used by each.
+//!
+//! The canonical encoding of a value is its compact JSON encoding, with:
+//!
+//! - object keys sorted by their UTF-8 bytes, at every level,
+//! - integers written in plain decimal notation.
+//!
+//! Floating point numbers are rejected, since their formatting is not consistent across
+//! implementations.
+
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+use crate::primitives::{
+    hash::{HashFunction, Sha256},
+    H256,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CanonicalJsonError {
+    #[error("unable to serialize value to json")]
+    Serialize(#[from] serde_json::Error),
+    #[error("floating point numbers cannot be canonically encoded (found {0})")]
+    Float(Number),
+}
+
+/// Encode `value` as canonical JSON.
+pub fn to_canonical_json<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, CanonicalJsonError> {
+    let mut out = vec![];
+
+    write_value(&serde_json::to_value(value)?, &mut out)?;
+
+    Ok(out)
+}
+
+/// The sha256 digest of the canonical JSON encoding of `value`.
+pub fn canonical_json_digest<T: Serialize + ?Sized>(value: &T) -> Result<H256, CanonicalJsonError> {
+    to_canonical_json(value).map(Sha256::hash)
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) -> Result<(), CanonicalJsonError> {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => {
+            out.extend(serde_json::to_vec(value)?);
+        }
+        Value::Number(number) => {
+            if number.is_f64() {
+                return Err(CanonicalJsonError::Float(number.clone()));
+            }
+
+            out.extend(number.to_string().as_bytes());
+        }
+        Value::Array(values) => {
+            out.push(b'[');
+
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+
+                write_value(value, out)?;
+            }
+
+            out.push(b']');
+        }
+        Value::Object(map) => {
+            // the iteration order of the map depends on whether serde_json/preserve_order is enabled
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+
+            out.push(b'{');
+
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+
+                out.extend(serde_json::to_vec(key)?);
+                out.push(b':');
+                write_value(value, out)?;
+            }
+
+            out.push(b'}');
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{google::protobuf::duration::Duration, ibc::core::client::height::Height};
+
+    #[derive(Serialize)]
+    struct Unsorted {
+        b: bool,
+        a: (u64, i64, Value),
+    }
+
+    const UNSORTED: &str = r#"{"a":[1,-2,{"c":null,"d":"e"}],"b":true}"#;
+
+    #[test]
+    fn keys_are_sorted() {
+        let value = Unsorted {
+            b: true,
+            a: (1, -2, json!({ "d": "e", "c": null })),
+        };
+
+        assert_eq!(to_canonical_json(&value).unwrap(), UNSORTED.as_bytes());
+        assert_eq!(
+            canonical_json_digest(&value).unwrap(),
+            H256::new(hex!(
+                "81d534d0edfa40d6b7007076ac1fca329eae0566f47e5286556f6d20a32f62d3"
+            ))
+        );
+    }
+
+    #[test]
+    fn matches_reparsed() {
+        let value = serde_json::from_str::<Value>(UNSORTED).unwrap();
+
+        assert_eq!(to_canonical_json(&value).unwrap(), UNSORTED.as_bytes());
+    }
+
+    #[test]
+    fn unionlabs_types() {
+        assert_eq!(
+            to_canonical_json(&Height::new_with_revision(1, 100)).unwrap(),
+            br#""1-100""#
+        );
+
+        assert_eq!(
+            to_canonical_json(&Duration::new(1, 500).unwrap()).unwrap(),
+            br#""1.0000005s""#
+        );
+    }
+
+    #[test]
+    fn floats_are_rejected() {
+        assert!(matches!(
+            to_canonical_json(&json!({ "a": 1.5 })),
+            Err(CanonicalJsonError::Float(_))
+        ));
+    }
+}
@@ -0,0 +1,194 @@
+//! ```rust
+//! # use unionlabs::{chain_id, chain_id::ChainIdLiteral};
+//! const UNION_TESTNET: ChainIdLiteral = chain_id!("union-testnet-10");
+//!
+//! assert_eq!(UNION_TESTNET.as_str(), "union-testnet-10");
+//! assert_eq!(UNION_TESTNET.revision(), Some(10));
+//! assert_eq!(chain_id!("11155111").revision(), None);
+//! ```
+//!
+//! Malformed chain ids fail to compile:
+//!
+//! ```rust,compile_fail
+//! # use unionlabs::chain_id;
+//! let _ = chain_id!("union testnet");
+//! ```
+
+use crate::errors::{ExpectedLength, InvalidLength};
+
+/// A chain id that has been validated to be well-formed, see [`chain_id!`].
+///
+/// A chain id is well-formed if it is non-empty, at most [`Self::MAX_LEN`] bytes long, and only
+/// contains ascii alphanumerics, `-`, `_` and `.`. Chain ids in the `<chain>-<revision>` format
+/// (as defined by ibc-go) additionally have their revision number extracted.
+///
+/// [`chain_id!`]: crate::chain_id!
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChainIdLiteral {
+    id: &'static str,
+    revision: Option<u64>,
+}
+
+impl ChainIdLiteral {
+    pub const MIN_LEN: usize = 1;
+    /// <https://github.com/cosmos/ibc-go/blob/73061ac6b3a1f0bb24e8dd78eb1004c8dcd4fe8f/modules/light-clients/07-tendermint/client_state.go#L23>
+    pub const MAX_LEN: usize = 50;
+
+    pub const fn new(id: &'static str) -> Result<Self, ChainIdLiteralError> {
+        let len = id.len();
+
+        if len < Self::MIN_LEN || len > Self::MAX_LEN {
+            return Err(ChainIdLiteralError::InvalidLength(InvalidLength {
+                expected: ExpectedLength::Between(Self::MIN_LEN, Self::MAX_LEN),
+                found: len,
+            }));
+        }
+
+        let bz = id.as_bytes();
+
+        let mut i = 0;
+        while i < len {
+            match bz[i] {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' => {}
+                c => return Err(ChainIdLiteralError::InvalidCharacter(c)),
+            }
+
+            i += 1;
+        }
+
+        if bz[0] == b'-' || bz[len - 1] == b'-' {
+            return Err(ChainIdLiteralError::LeadingOrTrailingDelimiter);
+        }
+
+        match parse_revision(bz) {
+            Ok(revision) => Ok(Self { id, revision }),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        self.id
+    }
+
+    /// The revision number of this chain id, if it is in the `<chain>-<revision>` format.
+    #[must_use]
+    pub const fn revision(&self) -> Option<u64> {
+        self.revision
+    }
+}
+
+/// Parse the revision number from a `<chain>-<revision>` formatted chain id, where the revision
+/// is a decimal number without leading zeros.
+///
+/// <https://github.com/cosmos/ibc-go/blob/73061ac6b3a1f0bb24e8dd78eb1004c8dcd4fe8f/modules/core/02-client/types/height.go#L22>
+const fn parse_revision(bz: &[u8]) -> Result<Option<u64>, ChainIdLiteralError> {
+    let mut start = bz.len();
+
+    while start > 0 && bz[start - 1].is_ascii_digit() {
+        start -= 1;
+    }
+
+    // not in the revision format: either no delimiter before the trailing digits, the revision
+    // has leading zeros, or the chain id is entirely numeric (i.e. evm chain ids)
+    if start == 0 || start == bz.len() || bz[start - 1] != b'-' || bz[start] == b'0' {
+        return Ok(None);
+    }
+
+    let mut revision: u64 = 0;
+    let mut i = start;
+    while i < bz.len() {
+        revision = match revision.checked_mul(10) {
+            Some(revision) => match revision.checked_add((bz[i] - b'0') as u64) {
+                Some(revision) => revision,
+                None => return Err(ChainIdLiteralError::RevisionOverflow),
+            },
+            None => return Err(ChainIdLiteralError::RevisionOverflow),
+        };
+
+        i += 1;
+    }
+
+    Ok(Some(revision))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ChainIdLiteralError {
+    #[error("invalid chain id length")]
+    InvalidLength(#[source] InvalidLength),
+    #[error("invalid character `{}` in chain id", char::from(*.0))]
+    InvalidCharacter(u8),
+    #[error("chain id cannot start or end with `-`")]
+    LeadingOrTrailingDelimiter,
+    #[error("chain id revision number overflows u64")]
+    RevisionOverflow,
+}
+
+impl ChainIdLiteralError {
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn const_message(&self) -> &'static str {
+        match self {
+            Self::InvalidLength(_) => "invalid chain id length",
+            Self::InvalidCharacter(_) => "invalid character in chain id",
+            Self::LeadingOrTrailingDelimiter => "chain id cannot start or end with `-`",
+            Self::RevisionOverflow => "chain id revision number overflows u64",
+        }
+    }
+}
+
+/// Construct a [`ChainIdLiteral`] from a string literal, failing to compile if the chain id is
+/// malformed.
+///
+/// [`ChainIdLiteral`]: crate::chain_id::ChainIdLiteral
+#[macro_export]
+macro_rules! chain_id {
+    ($id:literal) => {{
+        const CHAIN_ID: $crate::chain_id::ChainIdLiteral =
+            match $crate::chain_id::ChainIdLiteral::new($id) {
+                Ok(chain_id) => chain_id,
+                Err(err) => panic!("{}", err.const_message()),
+            };
+
+        CHAIN_ID
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revision() {
+        assert_eq!(chain_id!("union-testnet-10").revision(), Some(10));
+        assert_eq!(chain_id!("union-1").revision(), Some(1));
+        assert_eq!(chain_id!("stargaze-1").revision(), Some(1));
+        assert_eq!(chain_id!("1").revision(), None);
+        assert_eq!(chain_id!("11155111").revision(), None);
+        assert_eq!(chain_id!("bbn-test-05").revision(), None);
+        assert_eq!(chain_id!("union").revision(), None);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            ChainIdLiteral::new(""),
+            Err(ChainIdLiteralError::InvalidLength(InvalidLength {
+                expected: ExpectedLength::Between(ChainIdLiteral::MIN_LEN, ChainIdLiteral::MAX_LEN),
+                found: 0
+            }))
+        );
+        assert_eq!(
+            ChainIdLiteral::new("union testnet"),
+            Err(ChainIdLiteralError::InvalidCharacter(b' '))
+        );
+        assert_eq!(
+            ChainIdLiteral::new("union-"),
+            Err(ChainIdLiteralError::LeadingOrTrailingDelimiter)
+        );
+        assert_eq!(
+            ChainIdLiteral::new("union-99999999999999999999"),
+            Err(ChainIdLiteralError::RevisionOverflow)
+        );
+    }
+}
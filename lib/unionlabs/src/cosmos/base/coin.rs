@@ -1,12 +1,14 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(::schemars::JsonSchema))]
 pub struct Coin {
     // REVIEW: Is this bounded?
     pub denom: String,
     // NOTE: According to the proto docs: "Exists in range from -(2^256 - 1) to 2^256 - 1"
     // If we ever have a use for amounts outside the range, you probably have other issues
     #[serde(with = "::serde_utils::string")]
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
     pub amount: u128,
 }
 
@@ -4,6 +4,7 @@ wrapper_enum! {
     #[model(proto(protos::cosmos::ics23::v1::HashOp))]
     #[derive(Default)]
     #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
     pub enum HashOp {
         #[default]
         NoHash = 0,
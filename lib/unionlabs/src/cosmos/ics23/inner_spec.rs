@@ -56,6 +56,21 @@ impl<'de, Context> bincode::BorrowDecode<'de, Context> for InnerSpec {
     }
 }
 
+// implemented manually since `Arbitrary` is only implemented for `Cow<'a, _>`, not `Cow<'static, _>`
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for InnerSpec {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            child_order: u.arbitrary::<Vec<_>>()?.into(),
+            child_size: u.arbitrary()?,
+            min_prefix_length: u.arbitrary()?,
+            max_prefix_length: u.arbitrary()?,
+            empty_child: u.arbitrary()?,
+            hash: u.arbitrary()?,
+        })
+    }
+}
+
 impl From<InnerSpec> for protos::cosmos::ics23::v1::InnerSpec {
     fn from(value: InnerSpec) -> Self {
         Self {
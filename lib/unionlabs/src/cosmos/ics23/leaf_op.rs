@@ -8,6 +8,7 @@ use crate::{
 
 #[model(proto(raw(protos::cosmos::ics23::v1::LeafOp), into, from))]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct LeafOp {
     pub hash: HashOp,
     pub prehash_key: HashOp,
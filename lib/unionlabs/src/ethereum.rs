@@ -1,9 +1,9 @@
-use sha2::Digest;
-use sha3::Keccak256;
-
 use crate::{
     ethereum::slot::{MappingKey, Slot},
-    primitives::{H256, U256},
+    primitives::{
+        hash::{HashFunction, Keccak256},
+        H256, U256,
+    },
 };
 
 pub mod slot;
@@ -11,7 +11,7 @@ pub mod slot;
 #[inline]
 #[must_use]
 pub fn keccak256(bytes: impl AsRef<[u8]>) -> H256 {
-    Keccak256::new().chain_update(bytes).finalize().into()
+    Keccak256::hash(bytes)
 }
 
 /// The slot of the `mapping(bytes32 => bytes32) public commitments` mapping in the `IBCStore` contract.
@@ -0,0 +1,51 @@
+//! Harnesses for fuzzing the wire encodings of types, used by the fuzz targets in
+//! `lib/unionlabs/fuzz`.
+
+use core::fmt::Debug;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::encoding::{Decode, DecodeAs, Encode, EncodeAs, Encoding};
+
+/// Decode `bz` as a `T`, which must never panic. If `bz` is a valid encoding of a `T`, the decoded
+/// value must roundtrip through the encoding.
+#[track_caller]
+pub fn decode_roundtrip<T, E: Encoding>(bz: &[u8])
+where
+    T: Encode<E> + Decode<E> + Clone + Debug + PartialEq,
+{
+    let Ok(t) = T::decode_as::<E>(bz) else {
+        return;
+    };
+
+    let iso = T::decode_as::<E>(&t.clone().encode_as::<E>())
+        .unwrap_or_else(|e| panic!("unable to decode the encoding of {t:?}: {e:?}"));
+
+    assert_eq!(t, iso, "roundtrip failed");
+}
+
+/// Encode an arbitrary `T`, which must always be decodable.
+///
+/// Some encodings do not carry every field of a value (i.e. the proto encoding of a tendermint
+/// client state does not contain the contract address), so the encodings are compared instead of
+/// the values.
+#[track_caller]
+pub fn encode_roundtrip<'a, T, E: Encoding>(u: &mut Unstructured<'a>) -> arbitrary::Result<()>
+where
+    T: Arbitrary<'a> + Encode<E> + Decode<E> + Clone + Debug,
+{
+    let t = u.arbitrary::<T>()?;
+
+    let bz = t.clone().encode_as::<E>();
+
+    let decoded = T::decode_as::<E>(&bz)
+        .unwrap_or_else(|e| panic!("unable to decode the encoding of {t:?}: {e:?}"));
+
+    assert_eq!(
+        bz,
+        decoded.encode_as::<E>(),
+        "encoding of {t:?} is not stable"
+    );
+
+    Ok(())
+}
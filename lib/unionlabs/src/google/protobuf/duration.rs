@@ -54,6 +54,7 @@ type DurationInner = BoundedI128<
 #[derive(PartialOrd, Ord, Copy)]
 #[debug("Duration({})", self)]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Duration(DurationInner);
 
 impl Neg for Duration {
@@ -308,6 +309,9 @@ pub enum DurationError {
 }
 
 impl Duration {
+    /// The longest representable [`Duration`], `315576000000.999999999s`.
+    pub const MAX: Self = Self::new_private(DURATION_MAX_SECONDS, DURATION_MAX_NANOS);
+
     #[must_use]
     pub fn checked_add(self, rhs: Duration) -> Option<Duration> {
         self.0
@@ -317,6 +321,74 @@ impl Duration {
             .map(Self)
             .ok()
     }
+
+    /// Convert a [`core::time::Duration`] into a [`Duration`], saturating at [`Duration::MAX`].
+    #[must_use]
+    pub fn from_std_saturating(duration: core::time::Duration) -> Self {
+        duration.try_into().unwrap_or(Self::MAX)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, thiserror::Error)]
+pub enum TryFromDurationError {
+    #[error("duration is negative")]
+    Negative,
+    #[error("duration is too large")]
+    TooLarge,
+}
+
+impl TryFrom<core::time::Duration> for Duration {
+    type Error = DurationError;
+
+    fn try_from(value: core::time::Duration) -> Result<Self, Self::Error> {
+        Self::new(
+            // out of bounds seconds are reported by Duration::new
+            i64::try_from(value.as_secs()).unwrap_or(i64::MAX),
+            value
+                .subsec_nanos()
+                .try_into()
+                .expect("subsec_nanos is always < 1_000_000_000; qed;"),
+        )
+    }
+}
+
+impl TryFrom<Duration> for core::time::Duration {
+    type Error = TryFromDurationError;
+
+    fn try_from(value: Duration) -> Result<Self, Self::Error> {
+        match (
+            u64::try_from(value.seconds().inner()),
+            u32::try_from(value.nanos().inner()),
+        ) {
+            (Ok(seconds), Ok(nanos)) => Ok(Self::new(seconds, nanos)),
+            _ => Err(TryFromDurationError::Negative),
+        }
+    }
+}
+
+#[cfg(feature = "consensus-primitives")]
+impl From<consensus_primitives::Duration> for Duration {
+    fn from(value: consensus_primitives::Duration) -> Self {
+        i128::from(value.as_nanos())
+            .try_into()
+            .map(Self)
+            .expect("u64::MAX nanoseconds is less than the max duration; qed;")
+    }
+}
+
+#[cfg(feature = "consensus-primitives")]
+impl TryFrom<Duration> for consensus_primitives::Duration {
+    type Error = TryFromDurationError;
+
+    fn try_from(value: Duration) -> Result<Self, Self::Error> {
+        if value.0.inner().is_negative() {
+            Err(TryFromDurationError::Negative)
+        } else {
+            u64::try_from(value.0.inner())
+                .map(Self::from_nanos)
+                .map_err(|_| TryFromDurationError::TooLarge)
+        }
+    }
 }
 
 impl From<Duration> for protos::google::protobuf::Duration {
@@ -399,6 +471,61 @@ mod tests {
         assert_proto_roundtrip(&Duration::new(-789, -101_112).unwrap());
     }
 
+    #[test]
+    fn std_conversions() {
+        assert_eq!(
+            Duration::try_from(core::time::Duration::new(123, 456)).unwrap(),
+            Duration::new(123, 456).unwrap()
+        );
+        assert_eq!(
+            core::time::Duration::try_from(Duration::new(123, 456).unwrap()).unwrap(),
+            core::time::Duration::new(123, 456)
+        );
+        assert_eq!(
+            core::time::Duration::try_from(Duration::new(0, -1).unwrap()),
+            Err(TryFromDurationError::Negative)
+        );
+
+        let too_large = core::time::Duration::from_secs(u64::MAX);
+
+        assert!(matches!(
+            Duration::try_from(too_large),
+            Err(DurationError::Seconds(_))
+        ));
+        assert_eq!(Duration::from_std_saturating(too_large), Duration::MAX);
+        assert_eq!(
+            Duration::from_std_saturating(core::time::Duration::from_secs(1)),
+            Duration::new(1, 0).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "consensus-primitives")]
+    fn consensus_primitives_conversions() {
+        assert_eq!(
+            Duration::from(consensus_primitives::Duration::from_nanos(1_000_000_001)),
+            Duration::new(1, 1).unwrap()
+        );
+        assert_eq!(
+            Duration::from(consensus_primitives::Duration::from_nanos(u64::MAX))
+                .seconds()
+                .inner(),
+            18_446_744_073
+        );
+        assert_eq!(
+            consensus_primitives::Duration::try_from(Duration::new(1, 1).unwrap()),
+            Ok(consensus_primitives::Duration::from_nanos(1_000_000_001))
+        );
+        assert_eq!(
+            consensus_primitives::Duration::try_from(Duration::new(-1, 0).unwrap()),
+            Err(TryFromDurationError::Negative)
+        );
+        assert_eq!(
+            consensus_primitives::Duration::try_from(Duration::MAX),
+            Err(TryFromDurationError::TooLarge)
+        );
+    }
+
     #[test]
     fn checked_add() {
         let ensure_commutative = |a: Duration, b: Duration| {
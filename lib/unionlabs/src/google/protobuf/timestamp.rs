@@ -20,15 +20,17 @@ pub const TIMESTAMP_SECONDS_MIN: i64 = -62_135_596_800;
 
 const NANOS_MAX: i32 = NANOS_PER_SECOND - 1;
 
-pub type ValidTimestampUnixNanos = BoundedI128<
-    { TIMESTAMP_SECONDS_MIN as i128 * NANOS_PER_SECOND as i128 },
-    { (TIMESTAMP_SECONDS_MAX as i128 * NANOS_PER_SECOND as i128) + NANOS_MAX as i128 },
->;
+const VALID_UNIX_NANOS_MIN: i128 = TIMESTAMP_SECONDS_MIN as i128 * NANOS_PER_SECOND as i128;
+const VALID_UNIX_NANOS_MAX: i128 =
+    (TIMESTAMP_SECONDS_MAX as i128 * NANOS_PER_SECOND as i128) + NANOS_MAX as i128;
+
+pub type ValidTimestampUnixNanos = BoundedI128<VALID_UNIX_NANOS_MIN, VALID_UNIX_NANOS_MAX>;
 
 #[model(proto(raw(protos::google::protobuf::Timestamp), into, from), no_serde)]
 #[debug("Timestamp({})", self)]
 #[derive(Copy)]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Timestamp {
     /// As per the proto docs: "Must be from 0001-01-01T00:00:00Z to
     /// 9999-12-31T23:59:59Z inclusive."
@@ -138,6 +140,40 @@ impl Timestamp {
         }
     }
 
+    /// Construct a [`Timestamp`] from unix nanoseconds, saturating at the minimum and maximum
+    /// representable timestamps.
+    #[must_use]
+    pub fn from_unix_nanos_saturating(nanos: i128) -> Self {
+        Self::try_from_unix_nanos_floor(nanos.clamp(VALID_UNIX_NANOS_MIN, VALID_UNIX_NANOS_MAX))
+            .expect("value is clamped to the valid range; qed;")
+    }
+
+    /// Like [`Self::try_from_unix_nanos`], but rounds towards negative infinity instead of towards
+    /// zero, such that timestamps before the unix epoch that are not a whole number of seconds
+    /// can be represented (`-1ns` is `-1s + 999_999_999ns`).
+    fn try_from_unix_nanos_floor(nanos: i128) -> Option<Self> {
+        Some(Self {
+            seconds: i64::try_from(nanos.div_euclid(i128::from(NANOS_PER_SECOND)))
+                .ok()?
+                .try_into()
+                .ok()?,
+            nanos: i32::try_from(nanos.rem_euclid(i128::from(NANOS_PER_SECOND)))
+                .ok()?
+                .try_into()
+                .ok()?,
+        })
+    }
+
+    /// Returns the timestamp as unix nanoseconds, including timestamps before the unix epoch.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // panics are impossible
+    pub fn as_valid_unix_nanos(&self) -> ValidTimestampUnixNanos {
+        (i128::from(self.seconds.inner()) * i128::from(NANOS_PER_SECOND)
+            + i128::from(self.nanos.inner()))
+        .try_into()
+        .expect("type is bounded; qed;")
+    }
+
     /// Returns the timestamp as unix timestamp in nanoseconds.
     #[allow(clippy::missing_panics_doc)] // panics are impossible
     #[must_use]
@@ -370,6 +406,58 @@ impl TryFrom<protos::google::protobuf::Timestamp> for Timestamp {
     }
 }
 
+/// The timestamp is before the unix epoch, or too far after it to be represented as a `u64` of
+/// nanoseconds (~2554).
+#[cfg(feature = "consensus-primitives")]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("timestamp {0} is out of range")]
+pub struct TimestampOutOfRangeError(pub Timestamp);
+
+#[cfg(feature = "consensus-primitives")]
+impl From<consensus_primitives::Timestamp> for Timestamp {
+    fn from(value: consensus_primitives::Timestamp) -> Self {
+        Self::from_unix_nanos(
+            i128::from(value.as_nanos())
+                .try_into()
+                .expect("u64::MAX nanoseconds is before the max timestamp; qed;"),
+        )
+    }
+}
+
+#[cfg(feature = "consensus-primitives")]
+impl TryFrom<Timestamp> for consensus_primitives::Timestamp {
+    type Error = TimestampOutOfRangeError;
+
+    fn try_from(value: Timestamp) -> Result<Self, Self::Error> {
+        u64::try_from(value.as_valid_unix_nanos().inner())
+            .map(Self::from_nanos)
+            .map_err(|_| TimestampOutOfRangeError(value))
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<std::time::SystemTime> for Timestamp {
+    type Error = TryFromSystemTimeError;
+
+    fn try_from(value: std::time::SystemTime) -> Result<Self, Self::Error> {
+        let nanos = match value.duration_since(std::time::UNIX_EPOCH) {
+            Ok(duration) => i128::try_from(duration.as_nanos()).ok(),
+            Err(before_epoch) => i128::try_from(before_epoch.duration().as_nanos())
+                .ok()
+                .map(Neg::neg),
+        };
+
+        nanos
+            .and_then(Self::try_from_unix_nanos_floor)
+            .ok_or(TryFromSystemTimeError)
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("system time is out of range")]
+pub struct TryFromSystemTimeError;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -553,6 +641,64 @@ mod tests {
         .unwrap_err();
     }
 
+    #[test]
+    fn unix_nanos_saturating() {
+        assert_eq!(Timestamp::from_unix_nanos_saturating(1), ts!(0, 1));
+        assert_eq!(
+            Timestamp::from_unix_nanos_saturating(-1),
+            ts!(-1, NANOS_MAX)
+        );
+        assert_eq!(
+            Timestamp::from_unix_nanos_saturating(i128::MIN),
+            MIN_TIMESTAMP
+        );
+        assert_eq!(
+            Timestamp::from_unix_nanos_saturating(i128::MAX),
+            ts!(TIMESTAMP_SECONDS_MAX, NANOS_MAX)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "consensus-primitives")]
+    fn consensus_primitives_conversions() {
+        assert_eq!(
+            Timestamp::from(consensus_primitives::Timestamp::from_nanos(1_000_000_001)),
+            ts!(1, 1)
+        );
+        assert_eq!(
+            consensus_primitives::Timestamp::try_from(ts!(1, 1)),
+            Ok(consensus_primitives::Timestamp::from_nanos(1_000_000_001))
+        );
+        assert_eq!(
+            consensus_primitives::Timestamp::try_from(ts!(-1, 0)),
+            Err(TimestampOutOfRangeError(ts!(-1, 0)))
+        );
+        assert_eq!(
+            consensus_primitives::Timestamp::try_from(ts!(TIMESTAMP_SECONDS_MAX, 0)),
+            Err(TimestampOutOfRangeError(ts!(TIMESTAMP_SECONDS_MAX, 0)))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn system_time() {
+        use core::time::Duration as StdDuration;
+        use std::time::UNIX_EPOCH;
+
+        assert_eq!(
+            Timestamp::try_from(UNIX_EPOCH + StdDuration::new(1, 1)),
+            Ok(ts!(1, 1))
+        );
+        assert_eq!(
+            Timestamp::try_from(UNIX_EPOCH - StdDuration::from_secs(1)),
+            Ok(ts!(-1, 0))
+        );
+        assert_eq!(
+            Timestamp::try_from(UNIX_EPOCH - StdDuration::from_millis(500)),
+            Ok(ts!(-1, 500_000_000))
+        );
+    }
+
     #[test]
     #[cfg(feature = "cosmwasm")]
     fn cosmwasm_timestamp() {
@@ -15,6 +15,7 @@ use macros::model;
 #[debug("Height({self})")]
 #[derive(Hash)]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 // TODO: Implement Valuable via Display once https://github.com/tokio-rs/valuable/pull/133 is merged
 pub struct Height {
     revision: Option<NonZeroU64>,
@@ -91,6 +92,49 @@ impl Height {
         }
     }
 
+    /// The height `height` in the same revision as this height.
+    #[must_use]
+    pub const fn with_height(self, height: u64) -> Self {
+        Self {
+            revision: self.revision,
+            height,
+        }
+    }
+
+    /// The height `height` blocks after this one in the same revision, or `None` on overflow.
+    #[must_use]
+    pub const fn checked_add(self, height: u64) -> Option<Self> {
+        match self.height.checked_add(height) {
+            Some(height) => Some(self.with_height(height)),
+            None => None,
+        }
+    }
+
+    /// The height `height` blocks before this one in the same revision, saturating at zero.
+    #[must_use]
+    pub const fn saturating_sub(self, height: u64) -> Self {
+        self.with_height(self.height.saturating_sub(height))
+    }
+
+    /// The height `height` in the revision after this one (i.e. after a chain upgrade that resets
+    /// the height), or `None` if the revision number overflows.
+    #[must_use]
+    pub const fn increment_revision(self, height: u64) -> Option<Self> {
+        match self.revision().checked_add(1) {
+            Some(revision) => Some(Self::new_with_revision(revision, height)),
+            None => None,
+        }
+    }
+
+    /// Compare the heights of `self` and `other` if they are in the same revision (see
+    /// [`Self::revision_matches`]), or `None` if they are not. Prefer this over [`Ord`], which
+    /// orders heights in different revisions by their revision alone.
+    #[must_use]
+    pub fn same_revision_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.revision_matches(other)
+            .then(|| self.height.cmp(&other.height))
+    }
+
     pub fn from_str_allow_zero_revision(s: &str) -> Result<Self, HeightFromStrError> {
         match s.split_once('-') {
             Some((n, h)) => Ok(Self::new_with_revision(n.parse()?, h.parse()?)),
@@ -464,4 +508,72 @@ mod tests {
         assert_eq!(h1_2.cmp(&h2_2), Ordering::Less);
         assert_eq!(h2_1.cmp(&h1_1), Ordering::Greater);
     }
+
+    #[test]
+    fn with_height() {
+        assert_eq!(
+            Height::new_with_revision(1, 1).with_height(5),
+            Height::new_with_revision(1, 5)
+        );
+        assert_eq!(Height::new(1).with_height(5), Height::new(5));
+    }
+
+    #[test]
+    fn checked_add() {
+        assert_eq!(
+            Height::new_with_revision(1, 1).checked_add(2),
+            Some(Height::new_with_revision(1, 3))
+        );
+        assert_eq!(Height::new(1).checked_add(2), Some(Height::new(3)));
+        assert_eq!(Height::new_with_revision(1, u64::MAX).checked_add(1), None);
+    }
+
+    #[test]
+    fn saturating_sub() {
+        assert_eq!(
+            Height::new_with_revision(1, 3).saturating_sub(2),
+            Height::new_with_revision(1, 1)
+        );
+        assert_eq!(
+            Height::new_with_revision(1, 1).saturating_sub(2),
+            Height::new_with_revision(1, 0)
+        );
+    }
+
+    #[test]
+    fn increment_revision() {
+        assert_eq!(
+            Height::new(10).increment_revision(1),
+            Some(Height::new_with_revision(1, 1))
+        );
+        assert_eq!(
+            Height::new_with_revision(1, 10).increment_revision(1),
+            Some(Height::new_with_revision(2, 1))
+        );
+        assert_eq!(
+            Height::new_with_revision(u64::MAX, 10).increment_revision(1),
+            None
+        );
+    }
+
+    #[test]
+    fn same_revision_cmp() {
+        let h1_1 = Height::new_with_revision(1, 1);
+        let h1_2 = Height::new_with_revision(1, 2);
+        let h2_1 = Height::new_with_revision(2, 1);
+
+        assert_eq!(h1_1.same_revision_cmp(&h1_1), Some(Ordering::Equal));
+        assert_eq!(h1_1.same_revision_cmp(&h1_2), Some(Ordering::Less));
+        assert_eq!(h1_2.same_revision_cmp(&h1_1), Some(Ordering::Greater));
+
+        // no revision matches any revision
+        assert_eq!(
+            Height::new(2).same_revision_cmp(&h1_1),
+            Some(Ordering::Greater)
+        );
+
+        // different revisions are not compared
+        assert_eq!(h1_2.same_revision_cmp(&h2_1), None);
+        assert_eq!(h2_1.same_revision_cmp(&h1_2), None);
+    }
 }
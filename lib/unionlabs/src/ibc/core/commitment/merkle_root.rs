@@ -4,6 +4,7 @@ use crate::primitives::{encoding::Base64, FixedBytesError, H256};
 
 #[model(proto(raw(protos::ibc::core::commitment::v1::MerkleRoot), into, from))]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MerkleRoot {
     pub hash: H256<Base64>,
 }
@@ -58,8 +58,14 @@ pub mod berachain;
 
 pub mod aptos;
 
+/// Types for the zkgm (ucs03) protocol, with ethabi encoding for every supported opcode version.
+pub mod zkgm;
+
 pub mod bounded;
 
+/// Compile time validated chain id literals.
+pub mod chain_id;
+
 pub mod constants;
 
 /// Stable replacement for [`!`].
@@ -78,6 +84,10 @@ pub mod errors;
 
 pub mod tuple;
 
+/// Canonical JSON encoding and digests of values, i.e. client and consensus states.
+#[cfg(any(feature = "canonical_json", test))]
+pub mod canonical_json;
+
 #[cfg(feature = "proto")]
 pub use ::prost;
 
@@ -85,6 +95,10 @@ pub use ::prost;
 #[allow(clippy::missing_panics_doc)]
 pub mod test_utils;
 
+#[cfg(feature = "fuzzing")]
+#[allow(clippy::missing_panics_doc, clippy::missing_errors_doc)]
+pub mod fuzzing;
+
 #[cfg(feature = "ethabi")]
 pub use unionlabs_encoding::TryFromEthAbiBytesError;
 #[cfg(feature = "proto")]
@@ -104,6 +118,53 @@ impl<T: core::error::Error> ErrorReporter<T> {
     pub fn with_message(&self, message: &str) -> String {
         format!("{message}: {self}")
     }
+
+    /// The error and its complete [`source()`](core::error::Error::source) chain, outermost error
+    /// first.
+    ///
+    /// The type name of the outermost error is exact. Sources are only available as trait
+    /// objects, so their type name is the leading identifier of their [`Debug`](core::fmt::Debug)
+    /// output (usually the struct or enum variant name), if any.
+    #[must_use]
+    pub fn chain(&self) -> Vec<ErrorChainEntry> {
+        iter::once(ErrorChainEntry {
+            message: self.0.to_string(),
+            type_name: Some(core::any::type_name::<T>().to_owned()),
+        })
+        .chain(
+            iter::successors(self.0.source(), |e| (*e).source()).map(|e| ErrorChainEntry {
+                message: e.to_string(),
+                type_name: debug_name(e),
+            }),
+        )
+        .collect()
+    }
+
+    /// Renders [`Self::chain`] as a JSON array, suitable for embedding in the data of a JSON-RPC
+    /// error.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.chain()).expect("serialization is infallible; qed;")
+    }
+}
+
+/// One level of an error source chain, see [`ErrorReporter::chain`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ErrorChainEntry {
+    pub message: String,
+    #[serde(rename = "type")]
+    pub type_name: Option<String>,
+}
+
+fn debug_name(e: &dyn core::error::Error) -> Option<String> {
+    let debug = format!("{e:?}");
+
+    let name = debug
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .next()
+        .unwrap_or_default();
+
+    (!name.is_empty()).then(|| name.to_owned())
 }
 
 impl<T: core::error::Error> Display for ErrorReporter<T> {
@@ -160,6 +221,70 @@ impl core::iter::DoubleEndedIterator for BytesBitIterator<'_> {
     }
 }
 
+#[cfg(test)]
+mod error_reporter {
+    use super::*;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("outer")]
+    struct Outer(#[source] Middle);
+
+    #[derive(Debug, thiserror::Error)]
+    enum Middle {
+        #[error("middle")]
+        Variant(#[source] Inner),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("inner {0}")]
+    struct Inner(u8);
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            ErrorReporter(Outer(Middle::Variant(Inner(1)))).to_string(),
+            "outer: middle: inner 1"
+        );
+    }
+
+    #[test]
+    fn to_json() {
+        assert_eq!(
+            ErrorReporter(Outer(Middle::Variant(Inner(1)))).to_json(),
+            serde_json::json!([
+                { "message": "outer", "type": "unionlabs::error_reporter::Outer" },
+                { "message": "middle", "type": "Variant" },
+                { "message": "inner 1", "type": "Inner" },
+            ])
+        );
+    }
+
+    #[test]
+    fn to_json_no_debug_name() {
+        #[derive(thiserror::Error)]
+        #[error("opaque")]
+        struct Opaque;
+
+        impl core::fmt::Debug for Opaque {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("\"opaque\"")
+            }
+        }
+
+        #[derive(Debug, thiserror::Error)]
+        #[error("outer")]
+        struct Wrapper(#[source] Opaque);
+
+        assert_eq!(
+            ErrorReporter(Wrapper(Opaque)).chain()[1],
+            ErrorChainEntry {
+                message: "opaque".to_owned(),
+                type_name: None,
+            }
+        );
+    }
+}
+
 #[cfg(test)]
 #[allow(dead_code)]
 mod debug {
@@ -0,0 +1,617 @@
+use serde::{Deserialize, Serialize};
+
+use crate::primitives::{Bytes, H256, U256};
+
+pub const INSTR_VERSION_0: u8 = 0x00;
+pub const INSTR_VERSION_1: u8 = 0x01;
+pub const INSTR_VERSION_2: u8 = 0x02;
+
+pub const OP_FORWARD: u8 = 0x00;
+pub const OP_MULTIPLEX: u8 = 0x01;
+pub const OP_BATCH: u8 = 0x02;
+pub const OP_FUNGIBLE_ASSET_ORDER: u8 = 0x03;
+
+/// The payload of a packet sent over a zkgm channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ZkgmPacket {
+    pub salt: H256,
+    pub path: U256,
+    pub instruction: Instruction,
+}
+
+/// A zkgm instruction, with its operand decoded according to the opcode and version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Instruction {
+    Forward(Forward),
+    Multiplex(Multiplex),
+    Batch(Batch),
+    FungibleAssetOrder(FungibleAssetOrder),
+    /// An instruction with an opcode or version that is not modeled here. The operand is kept as
+    /// is, such that the instruction can be encoded again without loss.
+    Unknown(RawInstruction),
+}
+
+/// An instruction with an undecoded operand, as it is encoded on the wire.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RawInstruction {
+    pub version: u8,
+    pub opcode: u8,
+    pub operand: Bytes,
+}
+
+/// [`OP_FORWARD`], [`INSTR_VERSION_0`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Forward {
+    pub path: U256,
+    pub timeout_height: u64,
+    pub timeout_timestamp: u64,
+    pub instruction: Box<Instruction>,
+}
+
+/// [`OP_MULTIPLEX`], [`INSTR_VERSION_0`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Multiplex {
+    pub sender: Bytes,
+    pub eureka: bool,
+    pub contract_address: Bytes,
+    pub contract_calldata: Bytes,
+}
+
+/// [`OP_BATCH`], [`INSTR_VERSION_0`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Batch {
+    pub instructions: Vec<Instruction>,
+}
+
+/// [`OP_FUNGIBLE_ASSET_ORDER`], in all of its versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FungibleAssetOrder {
+    V0(FungibleAssetOrderV0),
+    V1(FungibleAssetOrderV1),
+    V2(FungibleAssetOrderV2),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FungibleAssetOrderV0 {
+    pub sender: Bytes,
+    pub receiver: Bytes,
+    pub base_token: Bytes,
+    pub base_amount: U256,
+    pub base_token_symbol: String,
+    pub base_token_name: String,
+    pub base_token_path: U256,
+    pub quote_token: Bytes,
+    pub quote_amount: U256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FungibleAssetOrderV1 {
+    pub sender: Bytes,
+    pub receiver: Bytes,
+    pub base_token: Bytes,
+    pub base_amount: U256,
+    pub base_token_symbol: String,
+    pub base_token_name: String,
+    pub base_token_decimals: u8,
+    pub base_token_path: U256,
+    pub quote_token: Bytes,
+    pub quote_amount: U256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FungibleAssetOrderV2 {
+    pub sender: Bytes,
+    pub receiver: Bytes,
+    pub base_token: Bytes,
+    pub base_amount: U256,
+    pub metadata_type: u8,
+    pub metadata: Bytes,
+    pub quote_token: Bytes,
+    pub quote_amount: U256,
+}
+
+impl Instruction {
+    #[must_use]
+    pub fn version(&self) -> u8 {
+        match self {
+            Instruction::Forward(_)
+            | Instruction::Multiplex(_)
+            | Instruction::Batch(_)
+            | Instruction::FungibleAssetOrder(FungibleAssetOrder::V0(_)) => INSTR_VERSION_0,
+            Instruction::FungibleAssetOrder(FungibleAssetOrder::V1(_)) => INSTR_VERSION_1,
+            Instruction::FungibleAssetOrder(FungibleAssetOrder::V2(_)) => INSTR_VERSION_2,
+            Instruction::Unknown(raw) => raw.version,
+        }
+    }
+
+    #[must_use]
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Instruction::Forward(_) => OP_FORWARD,
+            Instruction::Multiplex(_) => OP_MULTIPLEX,
+            Instruction::Batch(_) => OP_BATCH,
+            Instruction::FungibleAssetOrder(_) => OP_FUNGIBLE_ASSET_ORDER,
+            Instruction::Unknown(raw) => raw.opcode,
+        }
+    }
+
+    /// All fungible asset orders in this instruction, recursing into batches and forwards.
+    #[must_use]
+    pub fn fungible_asset_orders(&self) -> Vec<&FungibleAssetOrder> {
+        let mut orders = vec![];
+        self.collect_fungible_asset_orders(&mut orders);
+        orders
+    }
+
+    fn collect_fungible_asset_orders<'a>(&'a self, orders: &mut Vec<&'a FungibleAssetOrder>) {
+        match self {
+            Instruction::Forward(forward) => {
+                forward.instruction.collect_fungible_asset_orders(orders)
+            }
+            Instruction::Batch(batch) => batch
+                .instructions
+                .iter()
+                .for_each(|instruction| instruction.collect_fungible_asset_orders(orders)),
+            Instruction::FungibleAssetOrder(order) => orders.push(order),
+            Instruction::Multiplex(_) | Instruction::Unknown(_) => {}
+        }
+    }
+}
+
+impl FungibleAssetOrder {
+    #[must_use]
+    pub fn sender(&self) -> &Bytes {
+        match self {
+            FungibleAssetOrder::V0(order) => &order.sender,
+            FungibleAssetOrder::V1(order) => &order.sender,
+            FungibleAssetOrder::V2(order) => &order.sender,
+        }
+    }
+
+    #[must_use]
+    pub fn receiver(&self) -> &Bytes {
+        match self {
+            FungibleAssetOrder::V0(order) => &order.receiver,
+            FungibleAssetOrder::V1(order) => &order.receiver,
+            FungibleAssetOrder::V2(order) => &order.receiver,
+        }
+    }
+
+    #[must_use]
+    pub fn base_token(&self) -> &Bytes {
+        match self {
+            FungibleAssetOrder::V0(order) => &order.base_token,
+            FungibleAssetOrder::V1(order) => &order.base_token,
+            FungibleAssetOrder::V2(order) => &order.base_token,
+        }
+    }
+
+    #[must_use]
+    pub fn base_amount(&self) -> U256 {
+        match self {
+            FungibleAssetOrder::V0(order) => order.base_amount,
+            FungibleAssetOrder::V1(order) => order.base_amount,
+            FungibleAssetOrder::V2(order) => order.base_amount,
+        }
+    }
+
+    #[must_use]
+    pub fn quote_token(&self) -> &Bytes {
+        match self {
+            FungibleAssetOrder::V0(order) => &order.quote_token,
+            FungibleAssetOrder::V1(order) => &order.quote_token,
+            FungibleAssetOrder::V2(order) => &order.quote_token,
+        }
+    }
+
+    #[must_use]
+    pub fn quote_amount(&self) -> U256 {
+        match self {
+            FungibleAssetOrder::V0(order) => order.quote_amount,
+            FungibleAssetOrder::V1(order) => order.quote_amount,
+            FungibleAssetOrder::V2(order) => order.quote_amount,
+        }
+    }
+}
+
+#[cfg(feature = "ethabi")]
+pub mod ethabi {
+    use alloy_sol_types::SolValue;
+
+    use super::*;
+    use crate::impl_ethabi_via_try_from_into;
+
+    impl_ethabi_via_try_from_into!(ZkgmPacket => SolZkgmPacket);
+    impl_ethabi_via_try_from_into!(Instruction => SolInstruction);
+
+    alloy_sol_types::sol! {
+        struct SolZkgmPacket {
+            bytes32 salt;
+            uint256 path;
+            SolInstruction instruction;
+        }
+
+        struct SolInstruction {
+            uint8 version;
+            uint8 opcode;
+            bytes operand;
+        }
+
+        struct SolForward {
+            uint256 path;
+            uint64 timeout_height;
+            uint64 timeout_timestamp;
+            SolInstruction instruction;
+        }
+
+        struct SolMultiplex {
+            bytes sender;
+            bool eureka;
+            bytes contract_address;
+            bytes contract_calldata;
+        }
+
+        struct SolBatch {
+            SolInstruction[] instructions;
+        }
+
+        struct SolFungibleAssetOrderV0 {
+            bytes sender;
+            bytes receiver;
+            bytes base_token;
+            uint256 base_amount;
+            string base_token_symbol;
+            string base_token_name;
+            uint256 base_token_path;
+            bytes quote_token;
+            uint256 quote_amount;
+        }
+
+        struct SolFungibleAssetOrderV1 {
+            bytes sender;
+            bytes receiver;
+            bytes base_token;
+            uint256 base_amount;
+            string base_token_symbol;
+            string base_token_name;
+            uint8 base_token_decimals;
+            uint256 base_token_path;
+            bytes quote_token;
+            uint256 quote_amount;
+        }
+
+        struct SolFungibleAssetOrderV2 {
+            bytes sender;
+            bytes receiver;
+            bytes base_token;
+            uint256 base_amount;
+            uint8 metadata_type;
+            bytes metadata;
+            bytes quote_token;
+            uint256 quote_amount;
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, thiserror::Error)]
+    #[error("unable to decode the operand of instruction (opcode {opcode}, version {version})")]
+    pub struct Error {
+        pub opcode: u8,
+        pub version: u8,
+        #[source]
+        pub err: alloy_sol_types::Error,
+    }
+
+    impl From<ZkgmPacket> for SolZkgmPacket {
+        fn from(value: ZkgmPacket) -> Self {
+            Self {
+                salt: value.salt.into(),
+                path: value.path.into(),
+                instruction: value.instruction.into(),
+            }
+        }
+    }
+
+    impl TryFrom<SolZkgmPacket> for ZkgmPacket {
+        type Error = Error;
+
+        fn try_from(value: SolZkgmPacket) -> Result<Self, Self::Error> {
+            Ok(Self {
+                salt: value.salt.into(),
+                path: value.path.into(),
+                instruction: value.instruction.try_into()?,
+            })
+        }
+    }
+
+    impl From<Instruction> for SolInstruction {
+        fn from(value: Instruction) -> Self {
+            let version = value.version();
+            let opcode = value.opcode();
+
+            let operand = match value {
+                Instruction::Forward(forward) => SolForward {
+                    path: forward.path.into(),
+                    timeout_height: forward.timeout_height,
+                    timeout_timestamp: forward.timeout_timestamp,
+                    instruction: (*forward.instruction).into(),
+                }
+                .abi_encode_params(),
+                Instruction::Multiplex(multiplex) => SolMultiplex {
+                    sender: multiplex.sender.into(),
+                    eureka: multiplex.eureka,
+                    contract_address: multiplex.contract_address.into(),
+                    contract_calldata: multiplex.contract_calldata.into(),
+                }
+                .abi_encode_params(),
+                Instruction::Batch(batch) => SolBatch {
+                    instructions: batch.instructions.into_iter().map(Into::into).collect(),
+                }
+                .abi_encode_params(),
+                Instruction::FungibleAssetOrder(FungibleAssetOrder::V0(order)) => {
+                    SolFungibleAssetOrderV0 {
+                        sender: order.sender.into(),
+                        receiver: order.receiver.into(),
+                        base_token: order.base_token.into(),
+                        base_amount: order.base_amount.into(),
+                        base_token_symbol: order.base_token_symbol,
+                        base_token_name: order.base_token_name,
+                        base_token_path: order.base_token_path.into(),
+                        quote_token: order.quote_token.into(),
+                        quote_amount: order.quote_amount.into(),
+                    }
+                    .abi_encode_params()
+                }
+                Instruction::FungibleAssetOrder(FungibleAssetOrder::V1(order)) => {
+                    SolFungibleAssetOrderV1 {
+                        sender: order.sender.into(),
+                        receiver: order.receiver.into(),
+                        base_token: order.base_token.into(),
+                        base_amount: order.base_amount.into(),
+                        base_token_symbol: order.base_token_symbol,
+                        base_token_name: order.base_token_name,
+                        base_token_decimals: order.base_token_decimals,
+                        base_token_path: order.base_token_path.into(),
+                        quote_token: order.quote_token.into(),
+                        quote_amount: order.quote_amount.into(),
+                    }
+                    .abi_encode_params()
+                }
+                Instruction::FungibleAssetOrder(FungibleAssetOrder::V2(order)) => {
+                    SolFungibleAssetOrderV2 {
+                        sender: order.sender.into(),
+                        receiver: order.receiver.into(),
+                        base_token: order.base_token.into(),
+                        base_amount: order.base_amount.into(),
+                        metadata_type: order.metadata_type,
+                        metadata: order.metadata.into(),
+                        quote_token: order.quote_token.into(),
+                        quote_amount: order.quote_amount.into(),
+                    }
+                    .abi_encode_params()
+                }
+                Instruction::Unknown(raw) => raw.operand.into_vec(),
+            };
+
+            Self {
+                version,
+                opcode,
+                operand: operand.into(),
+            }
+        }
+    }
+
+    impl TryFrom<SolInstruction> for Instruction {
+        type Error = Error;
+
+        fn try_from(value: SolInstruction) -> Result<Self, Self::Error> {
+            fn decode<T: SolValue + From<<T::SolType as alloy_sol_types::SolType>::RustType>>(
+                value: &SolInstruction,
+            ) -> Result<T, Error> {
+                T::abi_decode_params_validate(&value.operand).map_err(|err| Error {
+                    opcode: value.opcode,
+                    version: value.version,
+                    err,
+                })
+            }
+
+            Ok(match (value.opcode, value.version) {
+                (OP_FORWARD, INSTR_VERSION_0) => {
+                    let forward = decode::<SolForward>(&value)?;
+                    Instruction::Forward(Forward {
+                        path: forward.path.into(),
+                        timeout_height: forward.timeout_height,
+                        timeout_timestamp: forward.timeout_timestamp,
+                        instruction: Box::new(forward.instruction.try_into()?),
+                    })
+                }
+                (OP_MULTIPLEX, INSTR_VERSION_0) => {
+                    let multiplex = decode::<SolMultiplex>(&value)?;
+                    Instruction::Multiplex(Multiplex {
+                        sender: multiplex.sender.into(),
+                        eureka: multiplex.eureka,
+                        contract_address: multiplex.contract_address.into(),
+                        contract_calldata: multiplex.contract_calldata.into(),
+                    })
+                }
+                (OP_BATCH, INSTR_VERSION_0) => Instruction::Batch(Batch {
+                    instructions: decode::<SolBatch>(&value)?
+                        .instructions
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()?,
+                }),
+                (OP_FUNGIBLE_ASSET_ORDER, INSTR_VERSION_0) => {
+                    let order = decode::<SolFungibleAssetOrderV0>(&value)?;
+                    Instruction::FungibleAssetOrder(FungibleAssetOrder::V0(FungibleAssetOrderV0 {
+                        sender: order.sender.into(),
+                        receiver: order.receiver.into(),
+                        base_token: order.base_token.into(),
+                        base_amount: order.base_amount.into(),
+                        base_token_symbol: order.base_token_symbol,
+                        base_token_name: order.base_token_name,
+                        base_token_path: order.base_token_path.into(),
+                        quote_token: order.quote_token.into(),
+                        quote_amount: order.quote_amount.into(),
+                    }))
+                }
+                (OP_FUNGIBLE_ASSET_ORDER, INSTR_VERSION_1) => {
+                    let order = decode::<SolFungibleAssetOrderV1>(&value)?;
+                    Instruction::FungibleAssetOrder(FungibleAssetOrder::V1(FungibleAssetOrderV1 {
+                        sender: order.sender.into(),
+                        receiver: order.receiver.into(),
+                        base_token: order.base_token.into(),
+                        base_amount: order.base_amount.into(),
+                        base_token_symbol: order.base_token_symbol,
+                        base_token_name: order.base_token_name,
+                        base_token_decimals: order.base_token_decimals,
+                        base_token_path: order.base_token_path.into(),
+                        quote_token: order.quote_token.into(),
+                        quote_amount: order.quote_amount.into(),
+                    }))
+                }
+                (OP_FUNGIBLE_ASSET_ORDER, INSTR_VERSION_2) => {
+                    let order = decode::<SolFungibleAssetOrderV2>(&value)?;
+                    Instruction::FungibleAssetOrder(FungibleAssetOrder::V2(FungibleAssetOrderV2 {
+                        sender: order.sender.into(),
+                        receiver: order.receiver.into(),
+                        base_token: order.base_token.into(),
+                        base_amount: order.base_amount.into(),
+                        metadata_type: order.metadata_type,
+                        metadata: order.metadata.into(),
+                        quote_token: order.quote_token.into(),
+                        quote_amount: order.quote_amount.into(),
+                    }))
+                }
+                _ => Instruction::Unknown(RawInstruction {
+                    version: value.version,
+                    opcode: value.opcode,
+                    operand: value.operand.into(),
+                }),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_sol_types::SolValue;
+    use hex_literal::hex;
+
+    use super::{ethabi::SolInstruction, *};
+    use crate::{
+        encoding::{Decode, EthAbi, Json},
+        test_utils::assert_codec_iso,
+        TryFromEthAbiBytesError,
+    };
+
+    fn mk_order_v1(amount: u64) -> Instruction {
+        Instruction::FungibleAssetOrder(FungibleAssetOrder::V1(FungibleAssetOrderV1 {
+            sender: b"union1jk9psyhvgkrt2cumz8eytll2244m2nnz4yt2g2".into(),
+            receiver: hex!("be68fc2d8249eb60bfcf0e71d5a0d2f2e292c4ed").into(),
+            base_token: b"muno".into(),
+            base_amount: amount.into(),
+            base_token_symbol: "muno".to_owned(),
+            base_token_name: "muno".to_owned(),
+            base_token_decimals: 6,
+            base_token_path: U256::ZERO,
+            quote_token: hex!("74d5b8eacfeb0dadaaf66403f40e304b3ef968b3").into(),
+            quote_amount: amount.into(),
+        }))
+    }
+
+    fn mk_packet() -> ZkgmPacket {
+        ZkgmPacket {
+            salt: H256::new([0xAA; 32]),
+            path: U256::ZERO,
+            instruction: Instruction::Batch(Batch {
+                instructions: vec![
+                    mk_order_v1(1),
+                    Instruction::Multiplex(Multiplex {
+                        sender: hex!("be68fc2d8249eb60bfcf0e71d5a0d2f2e292c4ed").into(),
+                        eureka: true,
+                        contract_address: hex!("74d5b8eacfeb0dadaaf66403f40e304b3ef968b3").into(),
+                        contract_calldata: b"some smart contract data".into(),
+                    }),
+                    Instruction::Forward(Forward {
+                        path: 1_u64.into(),
+                        timeout_height: 10_000_000,
+                        timeout_timestamp: 0,
+                        instruction: Box::new(mk_order_v1(2)),
+                    }),
+                    Instruction::FungibleAssetOrder(FungibleAssetOrder::V2(FungibleAssetOrderV2 {
+                        sender: b"sender".into(),
+                        receiver: b"receiver".into(),
+                        base_token: b"muno".into(),
+                        base_amount: 3_u64.into(),
+                        metadata_type: 0,
+                        metadata: b"metadata".into(),
+                        quote_token: b"quote".into(),
+                        quote_amount: 3_u64.into(),
+                    })),
+                    Instruction::Unknown(RawInstruction {
+                        version: INSTR_VERSION_0,
+                        opcode: 0x04,
+                        operand: b"stake".into(),
+                    }),
+                ],
+            }),
+        }
+    }
+
+    #[test]
+    fn ethabi_iso() {
+        assert_codec_iso::<_, EthAbi>(&mk_packet());
+    }
+
+    #[test]
+    fn json_iso() {
+        assert_codec_iso::<_, Json>(&mk_packet());
+    }
+
+    #[test]
+    fn fungible_asset_orders() {
+        let packet = mk_packet();
+
+        assert_eq!(
+            packet
+                .instruction
+                .fungible_asset_orders()
+                .into_iter()
+                .map(FungibleAssetOrder::base_amount)
+                .collect::<Vec<_>>(),
+            [1_u64.into(), 2_u64.into(), 3_u64.into()]
+        );
+    }
+
+    #[test]
+    fn invalid_operand() {
+        let err = <Instruction as Decode<EthAbi>>::decode(
+            &SolInstruction {
+                version: INSTR_VERSION_1,
+                opcode: OP_FUNGIBLE_ASSET_ORDER,
+                operand: b"garbage".to_vec().into(),
+            }
+            .abi_encode_params(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            TryFromEthAbiBytesError::Convert(ethabi::Error {
+                opcode: OP_FUNGIBLE_ASSET_ORDER,
+                version: INSTR_VERSION_1,
+                ..
+            })
+        ));
+    }
+}
@@ -20,11 +20,13 @@ use voyager_primitives::{
 use voyager_rpc::{
     json_rpc_error_to_error_object,
     types::{
-        IbcProofResponse, IbcStateResponse, SelfClientStateResponse, SelfConsensusStateResponse,
+        BackpressureStatus, IbcProofResponse, IbcStateResponse, ProofVerification, SelfClientMeta,
+        SelfClientStateResponse, SelfConsensusStateResponse,
     },
     VoyagerRpcClient, FATAL_JSONRPC_ERROR_CODE, MISSING_STATE_ERROR_CODE,
 };
 use voyager_types::RawClientId;
+use voyager_vm::{defer, now, seq, Op, QueueMessage};
 
 #[derive(Debug, Clone)]
 pub struct VoyagerClient<C: ClientT>(C);
@@ -65,6 +67,20 @@ impl<C: VoyagerRpcClient> VoyagerClient<C> {
         Ok(client_state)
     }
 
+    pub async fn self_client_meta(
+        &self,
+        chain_id: ChainId,
+        client_type: ClientType,
+        height: QueryHeight,
+    ) -> RpcResult<SelfClientMeta> {
+        let meta = self
+            .0
+            .self_client_meta(chain_id, client_type, height)
+            .await
+            .map_err(json_rpc_error_to_error_object)?;
+        Ok(meta)
+    }
+
     pub async fn self_consensus_state(
         &self,
         chain_id: ChainId,
@@ -118,6 +134,27 @@ impl<C: VoyagerRpcClient> VoyagerClient<C> {
         Ok(proof)
     }
 
+    #[instrument(
+        skip_all,
+        name = "voyager_client_verify_proof",
+        fields(
+            %client_type,
+            %ibc_interface,
+            key = %verification.key
+        )
+    )]
+    pub async fn verify_proof<V: IbcSpec>(
+        &self,
+        client_type: ClientType,
+        ibc_interface: IbcInterface,
+        verification: ProofVerification,
+    ) -> RpcResult<()> {
+        self.0
+            .verify_proof(client_type, ibc_interface, V::ID, verification)
+            .await
+            .map_err(json_rpc_error_to_error_object)
+    }
+
     #[instrument(
         skip_all,
         name = "voyager_client_encode_header",
@@ -320,6 +357,36 @@ impl<C: VoyagerRpcClient> VoyagerClient<C> {
             .map_err(json_rpc_error_to_error_object)
     }
 
+    pub async fn backpressure(&self, chain_id: ChainId) -> RpcResult<BackpressureStatus> {
+        self.0
+            .backpressure(chain_id)
+            .await
+            .map_err(json_rpc_error_to_error_object)
+    }
+
+    pub async fn finality_delay(&self, chain_id: ChainId) -> RpcResult<Option<u64>> {
+        self.0
+            .finality_delay(chain_id)
+            .await
+            .map_err(json_rpc_error_to_error_object)
+    }
+
+    /// Hold `op` back for the finality delay configured for `chain_id`, if any.
+    ///
+    /// `op` must only update clients on `chain_id` to counterparty heights that are already
+    /// finalized, such that these heights have been finalized for at least the delay once `op` is
+    /// run.
+    pub async fn with_finality_delay<T: QueueMessage>(
+        &self,
+        chain_id: ChainId,
+        op: Op<T>,
+    ) -> RpcResult<Op<T>> {
+        Ok(match self.finality_delay(chain_id).await? {
+            Some(finality_delay) => seq([defer(now() + finality_delay), op]),
+            None => op,
+        })
+    }
+
     pub async fn client_info<V: IbcSpec>(
         &self,
         chain_id: ChainId,
@@ -0,0 +1,129 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use opentelemetry::{metrics::Gauge, KeyValue};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{info, trace};
+use voyager_message::VoyagerMessage;
+use voyager_primitives::ChainId;
+use voyager_rpc::types::BackpressureStatus;
+use voyager_vm::Queue;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BackpressureConfig {
+    /// How often the pending operations of each configured chain are counted.
+    #[serde(default = "default_sample_interval_milliseconds")]
+    pub sample_interval_milliseconds: u64,
+    /// Thresholds per chain. Event sources of chains that are not configured here are never
+    /// paused.
+    #[serde(default)]
+    pub chains: HashMap<ChainId, Thresholds>,
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval_milliseconds: default_sample_interval_milliseconds(),
+            chains: Default::default(),
+        }
+    }
+}
+
+#[must_use]
+pub const fn default_sample_interval_milliseconds() -> u64 {
+    10_000
+}
+
+/// Event ingestion is paused once the pending operations reach `pause_at`, and resumed once they
+/// have drained to `resume_at` or below. Paused event sources check whether they can resume every
+/// [`BackpressureStatus::RETRY_DELAY`] seconds, so ingestion resumes at most that long (plus the
+/// sample interval) after the queue has drained.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Thresholds {
+    pub pause_at: u64,
+    pub resume_at: u64,
+}
+
+impl Thresholds {
+    fn paused(&self, currently_paused: bool, pending: u64) -> bool {
+        if currently_paused {
+            pending > self.resume_at
+        } else {
+            pending >= self.pause_at
+        }
+    }
+}
+
+/// The backpressure state of all configured chains, as of the last sample.
+#[derive(Clone)]
+pub struct Backpressure {
+    config: BackpressureConfig,
+    statuses: Arc<RwLock<HashMap<ChainId, BackpressureStatus>>>,
+    pending_gauge: Gauge<u64>,
+}
+
+impl Backpressure {
+    pub fn new(config: BackpressureConfig) -> Self {
+        Self {
+            config,
+            statuses: Default::default(),
+            pending_gauge: opentelemetry::global::meter("voyager")
+                .u64_gauge("queue.pending_operations")
+                .build(),
+        }
+    }
+
+    pub fn config(&self) -> &BackpressureConfig {
+        &self.config
+    }
+
+    pub fn status(&self, chain_id: &ChainId) -> BackpressureStatus {
+        self.statuses
+            .read()
+            .expect("lock is not poisoned; qed;")
+            .get(chain_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Count the pending operations of all configured chains and update their statuses.
+    pub async fn sample<Q: Queue<VoyagerMessage>>(&self, queue: &Q) -> Result<(), Q::Error> {
+        for (chain_id, thresholds) in &self.config.chains {
+            let pending = queue.pending_count(chain_id.as_str()).await?;
+
+            trace!(%chain_id, pending, "sampled pending operations");
+
+            self.pending_gauge
+                .record(pending, &[KeyValue::new("chain_id", chain_id.to_string())]);
+
+            let mut statuses = self.statuses.write().expect("lock is not poisoned; qed;");
+
+            let status = statuses.entry(chain_id.clone()).or_default();
+
+            let paused = thresholds.paused(status.paused, pending);
+
+            if paused != status.paused {
+                info!(
+                    %chain_id,
+                    pending,
+                    pause_at = thresholds.pause_at,
+                    resume_at = thresholds.resume_at,
+                    "{} event ingestion",
+                    if paused { "pausing" } else { "resuming" }
+                );
+            }
+
+            *status = BackpressureStatus {
+                paused,
+                pending: Some(pending),
+            };
+        }
+
+        Ok(())
+    }
+}
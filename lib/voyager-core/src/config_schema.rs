@@ -0,0 +1,131 @@
+//! Validation of the configs of plugins and modules against the JSON schemas they export via
+//! `--config-schema`, so that invalid configs are reported before anything is started instead of
+//! as a deserialization error from the process of the module.
+
+use std::{fmt::Write, path::Path};
+
+use anyhow::{anyhow, bail, Context as _};
+use futures::future::join_all;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{debug, trace, warn};
+
+use crate::context::{ModuleConfig, ModulesConfig, PluginConfig};
+
+/// Query the JSON schema of the config of the plugin or module at `path`.
+///
+/// Returns `None` if the plugin or module predates `--config-schema`, or is an older build that
+/// printed `null` for configs without a schema.
+pub async fn get_config_schema(path: &Path) -> anyhow::Result<Option<Value>> {
+    debug!(
+        "querying config schema from plugin at {}",
+        path.to_string_lossy()
+    );
+
+    let output = tokio::process::Command::new(path)
+        .arg("--config-schema")
+        .output()
+        .await
+        .with_context(|| format!("spawning plugin at {}", path.display()))?;
+
+    if !output.status.success() {
+        warn!(
+            path = %path.to_string_lossy(),
+            stderr = %String::from_utf8_lossy(&output.stderr),
+            "unable to query config schema, the config will not be validated"
+        );
+
+        return Ok(None);
+    }
+
+    trace!("plugin stdout: {}", String::from_utf8_lossy(&output.stdout));
+
+    serde_json::from_slice(&output.stdout).with_context(|| {
+        format!(
+            "invalid config schema returned by plugin at {}",
+            path.display()
+        )
+    })
+}
+
+/// Validate `config` against the config schema exported by the plugin or module at `path`, if
+/// any.
+pub async fn validate_config(path: &Path, config: &Value) -> anyhow::Result<()> {
+    let Some(schema) = get_config_schema(path).await? else {
+        return Ok(());
+    };
+
+    let validator = jsonschema::validator_for(&schema).map_err(|err| {
+        anyhow!(
+            "invalid config schema returned by plugin at {}: {err}",
+            path.display()
+        )
+    })?;
+
+    let errors = validator
+        .iter_errors(config)
+        .fold(String::new(), |mut errors, err| {
+            let _ = write!(
+                errors,
+                "\n- {}: {err}",
+                display_instance_path(&err.instance_path)
+            );
+            errors
+        });
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        bail!(
+            "invalid config for plugin at path {}:{errors}",
+            path.to_string_lossy()
+        )
+    }
+}
+
+/// Validate the configs of all enabled plugins and modules, reporting all invalid configs at once.
+/// The schemas of all plugins and modules are queried concurrently.
+pub async fn validate_configs(
+    plugins: &[PluginConfig],
+    modules: &ModulesConfig,
+) -> anyhow::Result<()> {
+    fn module_configs<Info: Serialize>(
+        configs: &[ModuleConfig<Info>],
+    ) -> impl Iterator<Item = (&Path, &Value)> {
+        configs
+            .iter()
+            .filter(|config| config.enabled)
+            .map(|config| (config.path.as_path(), &config.config))
+    }
+
+    let errors = join_all(
+        plugins
+            .iter()
+            .filter(|config| config.enabled)
+            .map(|config| (config.path.as_path(), &config.config))
+            .chain(module_configs(&modules.state))
+            .chain(module_configs(&modules.proof))
+            .chain(module_configs(&modules.consensus))
+            .chain(module_configs(&modules.client))
+            .chain(module_configs(&modules.client_bootstrap))
+            .map(|(path, config)| validate_config(path, config)),
+    )
+    .await
+    .into_iter()
+    .filter_map(Result::err)
+    .map(|err| format!("{err:#}"))
+    .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        bail!("{}", errors.join("\n\n"))
+    }
+}
+
+fn display_instance_path(instance_path: &impl ToString) -> String {
+    match instance_path.to_string() {
+        path if path.is_empty() => "<root>".to_owned(),
+        path => path,
+    }
+}
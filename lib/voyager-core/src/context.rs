@@ -1,4 +1,4 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, net::SocketAddr, path::PathBuf};
 
 use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
 use schemars::JsonSchema;
@@ -15,7 +15,10 @@ use voyager_rpc::{
 };
 use voyager_vm::QueueError;
 
-use crate::{equivalent_chain_ids::EquivalentChainIds, ibc_spec_handlers::IbcSpecHandlers};
+use crate::{
+    backpressure::Backpressure, equivalent_chain_ids::EquivalentChainIds,
+    health::ModuleHealthMonitor, ibc_spec_handlers::IbcSpecHandlers,
+};
 
 pub struct Context {
     pub(crate) state_modules: HashMap<(ChainId, IbcSpecId), WorkerClient>,
@@ -38,6 +41,16 @@ pub struct Context {
 
     // ibc version id => handler
     pub(crate) ibc_spec_handlers: IbcSpecHandlers,
+
+    pub(crate) backpressure: Backpressure,
+
+    /// map of chain id to the time client updates on that chain are held back for.
+    pub(crate) finality_delay_seconds: HashMap<ChainId, u64>,
+
+    pub(crate) module_health: ModuleHealthMonitor,
+
+    /// The config this instance was started with.
+    pub(crate) config: Value,
 }
 
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
@@ -47,6 +60,10 @@ pub struct PluginConfig {
     pub config: Value,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// Serve the metrics of the plugin in the prometheus text format on `/metrics` at this
+    /// address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_laddr: Option<SocketAddr>,
 }
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
@@ -68,6 +85,10 @@ pub struct ModuleConfig<T> {
     pub config: Value,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// Serve the metrics of the module in the prometheus text format on `/metrics` at this
+    /// address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_laddr: Option<SocketAddr>,
 }
 
 fn default_config() -> Value {
@@ -151,6 +172,22 @@ impl Context {
         &self.equivalent_chain_ids
     }
 
+    pub fn backpressure(&self) -> &Backpressure {
+        &self.backpressure
+    }
+
+    pub fn finality_delay_seconds(&self, chain_id: &ChainId) -> Option<u64> {
+        self.finality_delay_seconds.get(chain_id).copied()
+    }
+
+    pub fn module_health(&self) -> &ModuleHealthMonitor {
+        &self.module_health
+    }
+
+    pub fn config(&self) -> &Value {
+        &self.config
+    }
+
     pub fn chain_consensus_type<'a, 'b, 'c: 'a>(
         &'a self,
         chain_id: &ChainId,
@@ -0,0 +1,179 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use opentelemetry::{metrics::Gauge, KeyValue};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{trace, warn};
+use unionlabs::ErrorReporter;
+use voyager_rpc::{
+    types::{ModuleHealth, ModuleHealthStatus},
+    ClientBootstrapModuleClient, ClientModuleClient, FinalityModuleClient, RawProofModuleClient,
+    RawStateModuleClient,
+};
+use voyager_vm::now;
+
+use crate::context::Context;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HealthConfig {
+    /// How often the health of each module is polled.
+    #[serde(default = "default_poll_interval_milliseconds")]
+    pub poll_interval_milliseconds: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_milliseconds: default_poll_interval_milliseconds(),
+        }
+    }
+}
+
+#[must_use]
+pub const fn default_poll_interval_milliseconds() -> u64 {
+    30_000
+}
+
+/// The health of all modules, as of the last poll.
+#[derive(Clone)]
+pub struct ModuleHealthMonitor {
+    config: HealthConfig,
+    statuses: Arc<RwLock<BTreeMap<String, ModuleHealthStatus>>>,
+    healthy_gauge: Gauge<u64>,
+    latest_height_gauge: Gauge<u64>,
+}
+
+impl ModuleHealthMonitor {
+    pub fn new(config: HealthConfig) -> Self {
+        Self {
+            config,
+            statuses: Default::default(),
+            healthy_gauge: opentelemetry::global::meter("voyager")
+                .u64_gauge("module.healthy")
+                .build(),
+            latest_height_gauge: opentelemetry::global::meter("voyager")
+                .u64_gauge("module.latest_height")
+                .build(),
+        }
+    }
+
+    pub fn config(&self) -> &HealthConfig {
+        &self.config
+    }
+
+    pub fn statuses(&self) -> Vec<ModuleHealthStatus> {
+        self.statuses
+            .read()
+            .expect("lock is not poisoned; qed;")
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Poll the health of all modules and update their statuses. Modules that don't respond are
+    /// reported as unhealthy.
+    pub async fn poll(&self, context: &Context) {
+        let info = context.info();
+
+        let mut polls = FuturesUnordered::<BoxFuture<_>>::new();
+
+        for info in info.state {
+            let client = &context.state_modules[&(info.chain_id.clone(), info.ibc_spec_id.clone())];
+            polls.push(client.health_raw().map(move |res| (info.id(), res)).boxed());
+        }
+
+        for info in info.proof {
+            let client = &context.proof_modules[&(info.chain_id.clone(), info.ibc_spec_id.clone())];
+            polls.push(client.health_raw().map(move |res| (info.id(), res)).boxed());
+        }
+
+        for info in info.consensus {
+            let client = &context.finality_modules[&info.chain_id];
+            polls.push(
+                FinalityModuleClient::health(client)
+                    .map(move |res| (info.id(), res))
+                    .boxed(),
+            );
+        }
+
+        for info in info.client {
+            let client = &context.client_modules[&(
+                info.client_type.clone(),
+                info.ibc_interface.clone(),
+                info.ibc_spec_id.clone(),
+            )];
+            polls.push(
+                ClientModuleClient::health(client)
+                    .map(move |res| (info.id(), res))
+                    .boxed(),
+            );
+        }
+
+        for info in info.client_bootstrap {
+            let client = &context.client_bootstrap_modules
+                [&(info.chain_id.clone(), info.client_type.clone())];
+            polls.push(
+                ClientBootstrapModuleClient::health(client)
+                    .map(move |res| (info.id(), res))
+                    .boxed(),
+            );
+        }
+
+        while let Some((id, res)) = polls.next().await {
+            let status = match res {
+                Ok(health) => {
+                    trace!(module = %id, ?health, "polled module health");
+
+                    if let Some(error) = &health.error {
+                        warn!(module = %id, %error, "upstream of module is not reachable");
+                    }
+
+                    ModuleHealthStatus {
+                        id,
+                        health: Some(health),
+                        error: None,
+                        checked_at: now(),
+                    }
+                }
+                Err(error) => {
+                    warn!(module = %id, error = %ErrorReporter(&error), "module did not respond to health check");
+
+                    ModuleHealthStatus {
+                        id,
+                        health: None,
+                        error: Some(ErrorReporter(error).to_string()),
+                        checked_at: now(),
+                    }
+                }
+            };
+
+            self.record(status);
+        }
+    }
+
+    fn record(&self, status: ModuleHealthStatus) {
+        let attributes = [KeyValue::new("module", status.id.clone())];
+
+        self.healthy_gauge
+            .record(status.is_healthy().into(), &attributes);
+
+        if let Some(ModuleHealth {
+            latest_height: Some(latest_height),
+            ..
+        }) = &status.health
+        {
+            self.latest_height_gauge
+                .record(latest_height.height(), &attributes);
+        }
+
+        self.statuses
+            .write()
+            .expect("lock is not poisoned; qed;")
+            .insert(status.id.clone(), status);
+    }
+}
@@ -4,6 +4,7 @@ use std::{
     collections::{HashMap, VecDeque},
     net::{IpAddr, Ipv4Addr, SocketAddr},
     panic::AssertUnwindSafe,
+    path::PathBuf,
     sync::{Arc, OnceLock},
     time::Duration,
 };
@@ -12,14 +13,14 @@ use anyhow::{anyhow, Context as _};
 use futures::{
     future::{self, BoxFuture},
     stream::{self, FuturesUnordered},
-    Future, FutureExt, StreamExt, TryFutureExt, TryStreamExt,
+    Future, FutureExt, Stream, StreamExt, TryFutureExt, TryStreamExt,
 };
 use itertools::Itertools;
 use jsonrpsee::core::middleware::{RpcServiceBuilder, RpcServiceT};
 use opentelemetry::{metrics::Counter, KeyValue};
 use serde::Serialize;
 use serde_json::Value;
-use tokio_util::sync::CancellationToken;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use tracing::{
     debug, debug_span, error, info, info_span, instrument, trace, trace_span, warn, Instrument,
 };
@@ -36,7 +37,7 @@ use voyager_message::{
 use voyager_plugin_protocol::{
     coordinator_server, worker_child_process, WithId, WorkerClient, INVALID_CONFIG_EXIT_CODE,
 };
-use voyager_primitives::{ClientInfo, IbcSpec, QueryHeight};
+use voyager_primitives::{ChainId, ClientInfo, IbcSpec, QueryHeight};
 use voyager_rpc::{
     error_object_to_queue_error, json_rpc_error_to_queue_error, missing_state,
     types::{
@@ -54,19 +55,25 @@ use voyager_vm::{
 };
 
 use crate::{
+    backpressure::Backpressure,
     context::{Context, ModuleConfig, ModulesConfig, PluginConfig},
     equivalent_chain_ids::EquivalentChainIds,
     filter::InterestFilters,
+    health::ModuleHealthMonitor,
     ibc_spec_handlers::IbcSpecHandlers,
     server::Server,
 };
 
+pub mod backpressure;
 pub mod cache;
+pub mod config_schema;
 pub mod context;
 pub mod equivalent_chain_ids;
 pub mod filter;
+pub mod health;
 pub mod ibc_spec_handlers;
 pub mod server;
+pub mod template;
 
 pub struct Engine<Q: Queue<VoyagerMessage>> {
     context: Arc<OnceLock<Context>>,
@@ -74,6 +81,10 @@ pub struct Engine<Q: Queue<VoyagerMessage>> {
     cache: cache::Cache,
     queue: Q,
     cancellation_token: CancellationToken,
+    /// Cancelled by [`Engine::stop`], after which the workers stop pulling new messages from the
+    /// queue.
+    stop_token: CancellationToken,
+    child_processes: ChildProcesses,
     // NOTE: non-zero
     num_workers: usize,
     rest_laddr: SocketAddr,
@@ -92,12 +103,17 @@ impl Engine<InMemoryQueue<VoyagerMessage>> {
             module_configs: Default::default(),
             equivalent_chain_ids: Default::default(),
             ipc_client_request_timeout: Default::default(),
+            shutdown_timeout: default_shutdown_timeout(),
             cache_config: Default::default(),
             metrics_endpoint: Default::default(),
             num_workers: 1,
             rest_laddr: default_rest_laddr(),
             rpc_laddr: default_rpc_laddr(),
             optimizer_delay_milliseconds: default_optimizer_delay_milliseconds(),
+            backpressure_config: Default::default(),
+            finality_delay_seconds: Default::default(),
+            health_config: Default::default(),
+            config: Value::Null,
             queue_config: (),
         }
     }
@@ -114,8 +130,23 @@ impl HandlerFactory<VoyagerMessage> for Server {
 }
 
 impl<Q: Queue<VoyagerMessage>> Engine<Q> {
-    pub fn shutdown(self) {
+    /// Stop processing the queue. The workers finish the messages they are currently processing
+    /// (along with the requests to plugins and modules this involves), after which the future
+    /// returned by [`Self::run`] resolves.
+    pub fn stop(&self) {
+        self.stop_token.cancel();
+    }
+
+    /// Stop the engine and shut down all plugins and modules, waiting for them to exit.
+    ///
+    /// Each plugin and module is given the shutdown timeout to finish its in-flight requests
+    /// before it is killed. Call [`Self::stop`] and wait for [`Self::run`] first to not drop any
+    /// messages that are being processed.
+    pub async fn shutdown(self) {
         self.cancellation_token.cancel();
+
+        self.child_processes.tracker.close();
+        self.child_processes.tracker.wait().await;
     }
 
     pub fn server(&self) -> Server {
@@ -124,9 +155,11 @@ impl<Q: Queue<VoyagerMessage>> Engine<Q> {
 
     #[allow(clippy::too_many_lines)]
     pub fn run(&self) -> impl Future<Output = ()> + use<'_, Q> {
-        let queue_rx = api::run(&self.rest_laddr);
+        let queue_rx = api::run(&self.rest_laddr, self.server());
 
         let mut tasks = FuturesUnordered::<BoxFuture<Result<Result<(), BoxDynError>, _>>>::new();
+        // the workers are kept separately from the other tasks, since they are drained on stop
+        let mut workers = FuturesUnordered::<BoxFuture<Result<Result<(), BoxDynError>, _>>>::new();
 
         {
             tasks.push(Box::pin(
@@ -183,29 +216,40 @@ impl<Q: Queue<VoyagerMessage>> Engine<Q> {
             for id in 0..self.num_workers {
                 debug!("spawning worker {id}");
 
-                tasks.push(Box::pin(
+                workers.push(Box::pin(
                     AssertUnwindSafe(
-                        voyager_vm::engine::Engine::new(
-                            self.server(),
-                            &self.queue,
-                            &self.interest_filters,
-                        )
-                        .run()
-                        .for_each(async |res| match res {
-                            Ok(data) => {
-                                debug!(
-                                    data = %serde_json::to_value(&data).unwrap(),
-                                    "received data outside of an aggregation",
-                                );
-                            }
-                            Err(error) => {
-                                error!(
-                                    error = %ErrorReporter(&*error),
-                                    "error processing message"
-                                );
+                        async {
+                            let results = voyager_vm::engine::Engine::new(
+                                self.server(),
+                                &self.queue,
+                                &self.interest_filters,
+                            )
+                            .run();
+
+                            pin_utils::pin_mut!(results);
+
+                            // only check for the stop signal between messages, so that the message
+                            // being processed is not dropped
+                            while !self.stop_token.is_cancelled() {
+                                match results.next().await {
+                                    Some(Ok(data)) => {
+                                        debug!(
+                                            data = %serde_json::to_value(&data).unwrap(),
+                                            "received data outside of an aggregation",
+                                        );
+                                    }
+                                    Some(Err(error)) => {
+                                        error!(
+                                            error = %ErrorReporter(&*error),
+                                            "error processing message"
+                                        );
+                                    }
+                                    None => break,
+                                }
                             }
-                        })
-                        .map(Ok)
+
+                            Ok(())
+                        }
                         .instrument(trace_span!("engine task", %id)),
                     )
                     .catch_unwind(),
@@ -262,40 +306,116 @@ impl<Q: Queue<VoyagerMessage>> Engine<Q> {
                     .catch_unwind(),
                 ));
             }
-        }
 
-        self.cancellation_token
-            .run_until_cancelled(async move {
-                while let Some(res) = tasks.next().await {
-                    match res {
-                        Ok(Ok(())) => {
-                            info!("task exited gracefully");
-                        }
-                        Ok(Err(error)) => {
-                            error!(
-                                error = %ErrorReporter(&*error),
-                                "task returned with an error"
-                            );
-                            break;
+            let backpressure = self.context.get().unwrap().backpressure();
+
+            if !backpressure.config().chains.is_empty() {
+                info!(
+                    "spawning backpressure sampler for {} chains",
+                    backpressure.config().chains.len()
+                );
+
+                tasks.push(Box::pin(
+                    AssertUnwindSafe(
+                        async {
+                            loop {
+                                if let Err(error) = backpressure.sample(&self.queue).await {
+                                    error!(
+                                        error = %ErrorReporter(&error),
+                                        "error sampling pending operations"
+                                    );
+                                }
+
+                                tokio::time::sleep(std::time::Duration::from_millis(
+                                    backpressure.config().sample_interval_milliseconds,
+                                ))
+                                .await;
+                            }
                         }
-                        Err(_err) => {
-                            // can't do anything with dyn Any
-                            error!("task panicked");
-                            break;
+                        .instrument(info_span!("backpressure")),
+                    )
+                    .catch_unwind(),
+                ));
+            }
+
+            let module_health = self.context.get().unwrap().module_health();
+
+            info!("spawning module health monitor");
+
+            tasks.push(Box::pin(
+                AssertUnwindSafe(
+                    async {
+                        loop {
+                            module_health.poll(self.context.get().unwrap()).await;
+
+                            tokio::time::sleep(std::time::Duration::from_millis(
+                                module_health.config().poll_interval_milliseconds,
+                            ))
+                            .await;
                         }
                     }
+                    .instrument(info_span!("module_health")),
+                )
+                .catch_unwind(),
+            ));
+        }
+
+        self.cancellation_token
+            .run_until_cancelled(async move {
+                if self
+                    .stop_token
+                    .run_until_cancelled(join_tasks(stream::select(&mut tasks, &mut workers)))
+                    .await
+                    .is_some()
+                {
+                    return;
                 }
+
+                // stop accepting new messages and drop all other tasks, but let the workers finish
+                // the messages they are processing
+                drop(tasks);
+
+                info!("stopping, waiting for {} workers to finish", workers.len());
+
+                join_tasks(workers).await;
+
+                info!("all workers finished");
             })
             .map(|_| ())
     }
 }
 
+/// Wait for the tasks of the engine, returning once all tasks have exited or any task errors or
+/// panics.
+async fn join_tasks<E>(mut tasks: impl Stream<Item = Result<Result<(), BoxDynError>, E>> + Unpin) {
+    while let Some(res) = tasks.next().await {
+        match res {
+            Ok(Ok(())) => {
+                info!("task exited gracefully");
+            }
+            Ok(Err(error)) => {
+                error!(
+                    error = %ErrorReporter(&*error),
+                    "task returned with an error"
+                );
+                break;
+            }
+            Err(_err) => {
+                // can't do anything with dyn Any
+                error!("task panicked");
+                break;
+            }
+        }
+    }
+}
+
 pub struct EngineBuilder<Q: Queue<VoyagerMessage> = InMemoryQueue<VoyagerMessage>> {
     queue_config: Q::Config,
     plugin_configs: Vec<PluginConfig>,
     module_configs: ModulesConfig,
     equivalent_chain_ids: EquivalentChainIds,
     ipc_client_request_timeout: Duration,
+    shutdown_timeout: Duration,
     cache_config: cache::Config,
     metrics_endpoint: Option<String>,
     ibc_spec_handlers: IbcSpecHandlers,
@@ -303,6 +423,10 @@ pub struct EngineBuilder<Q: Queue<VoyagerMessage> = InMemoryQueue<VoyagerMessage
     rest_laddr: SocketAddr,
     rpc_laddr: SocketAddr,
     optimizer_delay_milliseconds: u64,
+    backpressure_config: backpressure::BackpressureConfig,
+    finality_delay_seconds: HashMap<ChainId, u64>,
+    health_config: health::HealthConfig,
+    config: Value,
 }
 
 impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
@@ -334,6 +458,13 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
         }
     }
 
+    pub fn with_shutdown_timeout(self, shutdown_timeout: Duration) -> Self {
+        Self {
+            shutdown_timeout,
+            ..self
+        }
+    }
+
     pub fn with_cache_config(self, cache_config: cache::Config) -> Self {
         Self {
             cache_config,
@@ -370,6 +501,38 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
         }
     }
 
+    pub fn with_backpressure_config(
+        self,
+        backpressure_config: backpressure::BackpressureConfig,
+    ) -> Self {
+        Self {
+            backpressure_config,
+            ..self
+        }
+    }
+
+    pub fn with_finality_delay_seconds(
+        self,
+        finality_delay_seconds: HashMap<ChainId, u64>,
+    ) -> Self {
+        Self {
+            finality_delay_seconds,
+            ..self
+        }
+    }
+
+    pub fn with_health_config(self, health_config: health::HealthConfig) -> Self {
+        Self {
+            health_config,
+            ..self
+        }
+    }
+
+    /// The config the engine is built from, as returned by the `voyager_config` rpc method.
+    pub fn with_config(self, config: Value) -> Self {
+        Self { config, ..self }
+    }
+
     pub fn register_ibc_spec_handler<S: IbcSpec>(mut self) -> Self {
         self.ibc_spec_handlers.register::<S>();
         self
@@ -385,6 +548,7 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
             module_configs: self.module_configs,
             equivalent_chain_ids: self.equivalent_chain_ids,
             ipc_client_request_timeout: self.ipc_client_request_timeout,
+            shutdown_timeout: self.shutdown_timeout,
             cache_config: self.cache_config,
             metrics_endpoint: self.metrics_endpoint,
             ibc_spec_handlers: self.ibc_spec_handlers,
@@ -392,14 +556,26 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
             rest_laddr: self.rest_laddr,
             rpc_laddr: self.rpc_laddr,
             optimizer_delay_milliseconds: self.optimizer_delay_milliseconds,
+            backpressure_config: self.backpressure_config,
+            finality_delay_seconds: self.finality_delay_seconds,
+            health_config: self.health_config,
+            config: self.config,
         }
     }
 }
 
 impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
     pub async fn build(self) -> anyhow::Result<Engine<Q>> {
+        config_schema::validate_configs(&self.plugin_configs, &self.module_configs).await?;
+
         let cancellation_token = CancellationToken::new();
 
+        let child_processes = ChildProcesses {
+            cancellation_token: cancellation_token.clone(),
+            tracker: TaskTracker::new(),
+            shutdown_timeout: self.shutdown_timeout,
+        };
+
         let queue = Q::new(self.queue_config).await?;
 
         let mut context_inner = Context {
@@ -413,6 +589,10 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
             plugins: Default::default(),
             equivalent_chain_ids: self.equivalent_chain_ids,
             ibc_spec_handlers: self.ibc_spec_handlers,
+            backpressure: Backpressure::new(self.backpressure_config),
+            finality_delay_seconds: self.finality_delay_seconds,
+            module_health: ModuleHealthMonitor::new(self.health_config),
+            config: self.config,
         };
 
         let logger_middleware_layer = LoggerMiddlewareLayer::new();
@@ -465,14 +645,14 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
                 )| {
                     debug!("registering plugin {}", name);
 
-                    tokio::spawn(worker_child_process(
+                    child_processes.spawn(
                         name.clone(),
                         plugin_config.path,
-                        cancellation_token.clone(),
                         [plugin_config.config.to_string()]
                             .into_iter()
-                            .chain(self.metrics_endpoint.clone()),
-                    ));
+                            .chain(self.metrics_endpoint.clone())
+                            .chain(metrics_laddr_arg(plugin_config.metrics_laddr)),
+                    );
 
                     let rpc_client = WorkerClient::new(&name, self.ipc_client_request_timeout);
 
@@ -498,7 +678,7 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
         modules_startup(
             self.module_configs.state,
             logger_middleware_layer.clone(),
-            cancellation_token.clone(),
+            child_processes.clone(),
             Server::new(cache.clone(), context.clone()),
             self.ipc_client_request_timeout,
             |info| info.id(),
@@ -534,7 +714,7 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
         modules_startup(
             self.module_configs.proof,
             logger_middleware_layer.clone(),
-            cancellation_token.clone(),
+            child_processes.clone(),
             Server::new(cache.clone(), context.clone()),
             self.ipc_client_request_timeout,
             |info| info.id(),
@@ -570,7 +750,7 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
         modules_startup(
             self.module_configs.consensus,
             logger_middleware_layer.clone(),
-            cancellation_token.clone(),
+            child_processes.clone(),
             Server::new(cache.clone(), context.clone()),
             self.ipc_client_request_timeout,
             |info| info.id(),
@@ -612,7 +792,7 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
         modules_startup(
             self.module_configs.client,
             logger_middleware_layer.clone(),
-            cancellation_token.clone(),
+            child_processes.clone(),
             Server::new(cache.clone(), context.clone()),
             self.ipc_client_request_timeout,
             |info| info.id(),
@@ -673,7 +853,7 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
         modules_startup(
             self.module_configs.client_bootstrap,
             logger_middleware_layer.clone(),
-            cancellation_token.clone(),
+            child_processes.clone(),
             Server::new(cache.clone(), context.clone()),
             self.ipc_client_request_timeout,
             |info| info.id(),
@@ -774,6 +954,8 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
         Ok(Engine {
             interest_filters,
             cancellation_token,
+            stop_token: CancellationToken::new(),
+            child_processes,
             context,
             cache,
             queue,
@@ -854,7 +1036,7 @@ impl voyager_vm::Handler<VoyagerMessage> for Handler {
                     .await
                     .map_err(error_object_to_queue_error)?;
 
-                if !chain_height.revision_matches(&height) {
+                let Some(ordering) = chain_height.same_revision_cmp(&height) else {
                     return Err(QueueError::Fatal(
                         format!(
                             "revision number mismatch, \
@@ -862,11 +1044,11 @@ impl voyager_vm::Handler<VoyagerMessage> for Handler {
                         )
                         .into(),
                     ));
-                }
+                };
 
                 trace!("latest height is {chain_height}, waiting for {height}");
 
-                if chain_height.height() >= height.height() {
+                if ordering.is_ge() {
                     Ok(noop())
                 } else {
                     Ok(seq([
@@ -1216,6 +1398,7 @@ impl voyager_vm::Handler<VoyagerMessage> for Handler {
                         })
                         .try_collect::<Vec<_>>()
                         .await?,
+                    signers: None,
                 }))
             }
             Callback::Plugin(PluginMessage { plugin, message }) => {
@@ -1280,7 +1463,7 @@ pub fn get_plugin_info(plugin_config: &PluginConfig) -> anyhow::Result<PluginInf
 async fn modules_startup<Info: Serialize + Clone + Unpin + Send + 'static>(
     configs: Vec<ModuleConfig<Info>>,
     logger_middleware_layer: LoggerMiddlewareLayer,
-    cancellation_token: CancellationToken,
+    child_processes: ChildProcesses,
     server: Server,
     ipc_client_request_timeout: Duration,
     id_f: fn(&Info) -> String,
@@ -1338,17 +1521,17 @@ async fn modules_startup<Info: Serialize + Clone + Unpin + Send + 'static>(
 
             debug!("registering module {}", id);
 
-            tokio::spawn(worker_child_process(
+            child_processes.spawn(
                 id.clone(),
                 module_config.path,
-                cancellation_token.clone(),
                 [
                     module_config.config.to_string(),
                     serde_json::to_string(&module_config.info).unwrap(),
                 ]
                 .into_iter()
-                .chain(metrics_endpoint.clone()),
-            ));
+                .chain(metrics_endpoint.clone())
+                .chain(metrics_laddr_arg(module_config.metrics_laddr)),
+            );
 
             let rpc_client = WorkerClient::new(&id, ipc_client_request_timeout);
 
@@ -1360,6 +1543,30 @@ async fn modules_startup<Info: Serialize + Clone + Unpin + Send + 'static>(
         })
 }
 
+/// The plugin and module processes spawned by the engine.
+#[derive(Clone)]
+struct ChildProcesses {
+    cancellation_token: CancellationToken,
+    tracker: TaskTracker,
+    shutdown_timeout: Duration,
+}
+
+impl ChildProcesses {
+    fn spawn(&self, name: String, path: PathBuf, args: impl IntoIterator<Item = String>) {
+        self.tracker.spawn(worker_child_process(
+            name,
+            path,
+            self.cancellation_token.clone(),
+            self.shutdown_timeout,
+            args.into_iter().collect::<Vec<_>>(),
+        ));
+    }
+}
+
+fn metrics_laddr_arg(metrics_laddr: Option<SocketAddr>) -> Option<String> {
+    metrics_laddr.map(|metrics_laddr| format!("--metrics-laddr={metrics_laddr}"))
+}
+
 pub mod api {
     use std::net::SocketAddr;
 
@@ -1373,16 +1580,30 @@ pub mod api {
         channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
         SinkExt,
     };
+    use tracing::info;
+    use unionlabs::ErrorReporter;
     use voyager_message::VoyagerMessage;
     use voyager_vm::Op;
 
-    pub fn run(laddr: &SocketAddr) -> UnboundedReceiver<Op<VoyagerMessage>> {
+    use crate::{
+        server::Server,
+        template::{OpTemplate, TemplateError},
+    };
+
+    #[derive(Clone)]
+    struct ApiState {
+        queue_tx: UnboundedSender<Op<VoyagerMessage>>,
+        server: Server,
+    }
+
+    pub fn run(laddr: &SocketAddr, server: Server) -> UnboundedReceiver<Op<VoyagerMessage>> {
         let (queue_tx, queue_rx) = unbounded::<Op<VoyagerMessage>>();
 
         let app = axum::Router::new()
             .route("/enqueue", post(enqueue))
+            .route("/enqueue/template", post(enqueue_template))
             .route("/health", get(async || StatusCode::OK))
-            .with_state(queue_tx.clone());
+            .with_state(ApiState { queue_tx, server });
 
         tokio::spawn(axum::Server::bind(laddr).serve(app.into_make_service()));
 
@@ -1391,13 +1612,47 @@ pub mod api {
 
     // #[axum::debug_handler]
     async fn enqueue(
-        State(mut sender): State<UnboundedSender<Op<VoyagerMessage>>>,
+        State(mut state): State<ApiState>,
         Json(op): Json<Op<VoyagerMessage>>,
     ) -> StatusCode {
-        sender.send(op).await.expect("receiver should not close");
+        state
+            .queue_tx
+            .send(op)
+            .await
+            .expect("receiver should not close");
 
         StatusCode::OK
     }
+
+    /// Expand the template and enqueue the resulting op, which is returned in the response.
+    async fn enqueue_template(
+        State(mut state): State<ApiState>,
+        Json(template): Json<OpTemplate>,
+    ) -> Result<Json<Op<VoyagerMessage>>, (StatusCode, String)> {
+        let op = template.expand(&state.server).await.map_err(|e| {
+            let status = match &e {
+                TemplateError::ClientNotFound { .. } | TemplateError::ChannelNotFound { .. } => {
+                    StatusCode::NOT_FOUND
+                }
+                TemplateError::NotFinalized { .. }
+                | TemplateError::ChannelNotOpen { .. }
+                | TemplateError::RevisionMismatch { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+                TemplateError::Rpc(_) => StatusCode::BAD_GATEWAY,
+            };
+
+            (status, ErrorReporter(e).to_string())
+        })?;
+
+        info!(op = %serde_json::to_value(&op).unwrap(), "expanded template");
+
+        state
+            .queue_tx
+            .send(op.clone())
+            .await
+            .expect("receiver should not close");
+
+        Ok(Json(op))
+    }
 }
 
 pub struct PluginOptPass<T> {
@@ -1450,6 +1705,12 @@ pub const fn default_ipc_client_request_timeout() -> Duration {
     Duration::new(60, 0)
 }
 
+#[must_use]
+#[inline]
+pub const fn default_shutdown_timeout() -> Duration {
+    Duration::new(30, 0)
+}
+
 #[derive(Clone)]
 pub struct LoggerMiddlewareLayer {
     request_counter: Counter<u64>,
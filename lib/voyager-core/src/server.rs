@@ -10,19 +10,19 @@ use jsonrpsee::{
     Extensions,
 };
 use opentelemetry::{metrics::Gauge, KeyValue};
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::{debug, info_span, instrument, trace};
 use unionlabs::{ibc::core::client::height::Height, primitives::Bytes, ErrorReporter};
 use voyager_plugin_protocol::WithId;
 use voyager_primitives::{
-    ChainId, ClientInfo, ClientStateMeta, ClientType, ConsensusStateMeta, IbcInterface, IbcSpec,
-    IbcSpecId, IbcStorePathKey, QueryHeight, Timestamp,
+    ChainId, ClientInfo, ClientStateMeta, ClientType, ConsensusStateMeta, IbcInterface, IbcQuery,
+    IbcSpec, IbcSpecId, IbcStorePathKey, QueryHeight, Timestamp,
 };
 use voyager_rpc::{
     json_rpc_error_to_error_object,
     types::{
-        IbcProofResponse, IbcStateResponse, InfoResponse, SelfClientStateResponse,
-        SelfConsensusStateResponse,
+        BackpressureStatus, IbcProofResponse, IbcStateResponse, InfoResponse, ModuleHealthStatus,
+        ProofVerification, SelfClientMeta, SelfClientStateResponse, SelfConsensusStateResponse,
     },
     ClientBootstrapModuleClient, ClientModuleClient, FinalityModuleClient, PluginClient,
     RawProofModuleClient, RawStateModuleClient, VoyagerRpcServer, FATAL_JSONRPC_ERROR_CODE,
@@ -470,6 +470,28 @@ impl Server {
             .await
     }
 
+    /// Run `query` against the state module of `chain_id`.
+    pub async fn query<Q: IbcQuery>(&self, chain_id: &ChainId, query: Q) -> RpcResult<Q::Value> {
+        let value = self
+            .query_raw(
+                chain_id.clone(),
+                <Q::Spec as IbcSpec>::ID,
+                serde_json::to_value(<Q::Spec as IbcSpec>::Query::from(query.clone().into()))
+                    .unwrap(),
+            )
+            .await?;
+
+        serde_json::from_value(value).map_err(|e| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("error decoding query return value: {}", ErrorReporter(e)),
+                Some(json!({
+                    "query": query
+                })),
+            )
+        })
+    }
+
     #[instrument(skip_all, fields(%chain_id, %ibc_spec_id, %query))]
     async fn query_raw(
         &self,
@@ -706,6 +728,35 @@ impl Server {
             .await
     }
 
+    #[instrument(skip_all, fields(%chain_id, %client_type, %height))]
+    pub async fn self_client_meta(
+        &self,
+        chain_id: ChainId,
+        client_type: ClientType,
+        height: Height,
+    ) -> RpcResult<SelfClientMeta> {
+        self.span()
+            .in_scope(|| async {
+                trace!("querying self client meta");
+
+                let client_bootstrap_module = self
+                    .context()?
+                    .client_bootstrap_module(&chain_id, &client_type)?
+                    .with_id(self.item_id);
+
+                let meta = client_bootstrap_module
+                    .self_client_meta(height)
+                    .await
+                    .map_err(json_rpc_error_to_error_object)?;
+
+                // TODO: Use valuable here
+                trace!(meta = %serde_json::to_value(&meta).unwrap(), "fetched self client meta");
+
+                Ok(meta)
+            })
+            .await
+    }
+
     #[instrument(skip_all, fields(%chain_id, %client_type, %height))]
     pub async fn self_consensus_state(
         &self,
@@ -768,6 +819,35 @@ impl Server {
             .await
     }
 
+    #[instrument(skip_all, fields(%client_type, %ibc_interface, %ibc_spec_id, key = %verification.key))]
+    pub async fn verify_proof(
+        &self,
+        client_type: &ClientType,
+        ibc_interface: &IbcInterface,
+        ibc_spec_id: &IbcSpecId,
+        verification: ProofVerification,
+    ) -> RpcResult<()> {
+        self.span()
+            .in_scope(|| async {
+                trace!("verifying proof");
+
+                let client_module = self
+                    .context()?
+                    .client_module(client_type, ibc_interface, ibc_spec_id)?
+                    .with_id(self.item_id);
+
+                client_module
+                    .verify_proof(verification)
+                    .await
+                    .map_err(json_rpc_error_to_error_object)?;
+
+                trace!("verified proof");
+
+                Ok(())
+            })
+            .await
+    }
+
     // TODO: Use valuable here
     #[instrument(skip_all, fields(%client_type, %ibc_interface, %ibc_spec_id, %header))]
     pub async fn encode_header(
@@ -934,6 +1014,26 @@ impl VoyagerRpcServer for Server {
             .collect())
     }
 
+    async fn backpressure(
+        &self,
+        _: &Extensions,
+        chain_id: ChainId,
+    ) -> RpcResult<BackpressureStatus> {
+        Ok(self.context()?.backpressure().status(&chain_id))
+    }
+
+    async fn finality_delay(&self, _: &Extensions, chain_id: ChainId) -> RpcResult<Option<u64>> {
+        Ok(self.context()?.finality_delay_seconds(&chain_id))
+    }
+
+    async fn module_health(&self, _: &Extensions) -> RpcResult<Vec<ModuleHealthStatus>> {
+        Ok(self.context()?.module_health().statuses())
+    }
+
+    async fn config(&self, _: &Extensions) -> RpcResult<Value> {
+        Ok(self.context()?.config().clone())
+    }
+
     // =========
     // CONSENSUS
     // =========
@@ -1082,6 +1182,20 @@ impl VoyagerRpcServer for Server {
             .await
     }
 
+    async fn self_client_meta(
+        &self,
+        e: &Extensions,
+        chain_id: ChainId,
+        client_type: ClientType,
+        height: QueryHeight,
+    ) -> RpcResult<SelfClientMeta> {
+        let this = self.with_id(e.try_get().ok().cloned());
+
+        let height = this.query_height(&chain_id, height).await?;
+
+        this.self_client_meta(chain_id, client_type, height).await
+    }
+
     // =====
     // CODEC
     // =====
@@ -1100,6 +1214,19 @@ impl VoyagerRpcServer for Server {
             .await
     }
 
+    async fn verify_proof(
+        &self,
+        e: &Extensions,
+        client_type: ClientType,
+        ibc_interface: IbcInterface,
+        ibc_spec_id: IbcSpecId,
+        verification: ProofVerification,
+    ) -> RpcResult<()> {
+        self.with_id(e.try_get().ok().cloned())
+            .verify_proof(&client_type, &ibc_interface, &ibc_spec_id, verification)
+            .await
+    }
+
     async fn encode_header(
         &self,
         e: &Extensions,
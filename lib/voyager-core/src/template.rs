@@ -0,0 +1,371 @@
+//! Templates for common admin actions, which are expanded into the ops that perform them when they
+//! are enqueued via `/enqueue/template`.
+
+use std::cmp::Ordering;
+
+use ibc_union_spec::{
+    event::{ChannelMetadata, ConnectionMetadata, PacketMetadata, WriteAck},
+    path::{ChannelPath, ConnectionPath},
+    query::PacketByHash,
+    ChannelId, IbcUnion,
+};
+use jsonrpsee::types::ErrorObjectOwned;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use unionlabs::{
+    ibc::core::client::height::Height,
+    primitives::{Bytes, H256},
+};
+use voyager_message::{
+    call::{FetchUpdateHeaders, WaitForClientUpdate, WaitForHeight},
+    callback::AggregateSubmitTxFromOrderedHeaders,
+    data::{ChainEvent, EventProvableHeight},
+    VoyagerMessage,
+};
+use voyager_primitives::{ChainId, IbcSpec, IbcSpecId, QueryHeight};
+use voyager_types::RawClientId;
+use voyager_vm::{call, data, defer, noop, now, promise, seq, Op};
+
+use crate::server::Server;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    deny_unknown_fields,
+    tag = "@type",
+    content = "@value",
+    rename_all = "snake_case"
+)]
+pub enum OpTemplate {
+    UpdateClient(UpdateClientTemplate),
+    ResendAck(ResendAckTemplate),
+}
+
+/// Update the client `client_id` on `chain_id` to trust at least `height` of the counterparty
+/// chain.
+///
+/// If `height` is not finalized yet, the update waits for it. If the client already trusts
+/// `height`, nothing is done. If a finality delay is configured for `chain_id`, the update is held
+/// back for the delay, and `height` must already be finalized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateClientTemplate {
+    pub chain_id: ChainId,
+    pub ibc_spec_id: IbcSpecId,
+    pub client_id: RawClientId,
+    /// Defaults to the latest finalized height of the counterparty chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<Height>,
+}
+
+/// Relay the acknowledgement `acknowledgement` of the IBC union packet `packet_hash` again, which
+/// was written on `channel_id` of `chain_id` (the destination chain of the packet) at `height` in
+/// the transaction `tx_hash`.
+///
+/// Only the write ack event of this packet is emitted again, and is handled as usual by the
+/// plugins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResendAckTemplate {
+    pub chain_id: ChainId,
+    pub channel_id: ChannelId,
+    pub packet_hash: H256,
+    pub acknowledgement: Bytes,
+    pub height: Height,
+    pub tx_hash: H256,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+    #[error("client {client_id} not found on chain {chain_id}")]
+    ClientNotFound {
+        chain_id: ChainId,
+        client_id: RawClientId,
+    },
+    #[error(
+        "height {height} of {chain_id} is not finalized yet, client updates are held back for \
+        {finality_delay} seconds after the height is finalized"
+    )]
+    NotFinalized {
+        chain_id: ChainId,
+        height: Height,
+        finality_delay: u64,
+    },
+    #[error("channel {channel_id} not found on chain {chain_id}")]
+    ChannelNotFound {
+        chain_id: ChainId,
+        channel_id: ChannelId,
+    },
+    #[error("channel {channel_id} on chain {chain_id} is not open")]
+    ChannelNotOpen {
+        chain_id: ChainId,
+        channel_id: ChannelId,
+    },
+    #[error("height {height} is not in the same revision as {other_height}")]
+    RevisionMismatch {
+        height: Height,
+        other_height: Height,
+    },
+    #[error("error querying voyager")]
+    Rpc(#[from] ErrorObjectOwned),
+}
+
+impl OpTemplate {
+    /// Expand this template into the op that performs it, querying the current state of the
+    /// involved chains as necessary.
+    pub async fn expand(self, server: &Server) -> Result<Op<VoyagerMessage>, TemplateError> {
+        match self {
+            Self::UpdateClient(template) => template.expand(server).await,
+            Self::ResendAck(template) => template.expand(server).await,
+        }
+    }
+}
+
+impl UpdateClientTemplate {
+    async fn expand(self, server: &Server) -> Result<Op<VoyagerMessage>, TemplateError> {
+        let client_not_found = || TemplateError::ClientNotFound {
+            chain_id: self.chain_id.clone(),
+            client_id: self.client_id.clone(),
+        };
+
+        let client_info = server
+            .client_info(&self.chain_id, &self.ibc_spec_id, self.client_id.clone())
+            .await?
+            .ok_or_else(client_not_found)?;
+
+        let client_state_meta = server
+            .client_state_meta(
+                &self.chain_id,
+                &self.ibc_spec_id,
+                QueryHeight::Latest,
+                self.client_id.clone(),
+            )
+            .await?
+            .ok_or_else(client_not_found)?;
+
+        let latest_height = server
+            .query_latest_height(&client_state_meta.counterparty_chain_id, true)
+            .await?;
+
+        let height = self.height.unwrap_or(latest_height);
+
+        if cmp_heights(client_state_meta.counterparty_height, height)?.is_ge() {
+            debug!(
+                trusted_height = %client_state_meta.counterparty_height,
+                %height,
+                "client is already updated"
+            );
+
+            return Ok(noop());
+        }
+
+        let finality_delay = server.context()?.finality_delay_seconds(&self.chain_id);
+
+        // the delay is measured from when the height is finalized, which is only known once it is
+        if let Some(finality_delay) = finality_delay {
+            if cmp_heights(height, latest_height)?.is_gt() {
+                return Err(TemplateError::NotFinalized {
+                    chain_id: client_state_meta.counterparty_chain_id,
+                    height,
+                    finality_delay,
+                });
+            }
+        }
+
+        Ok(seq(wait_for_finalized(
+            &client_state_meta.counterparty_chain_id,
+            height,
+            latest_height,
+        )?
+        .into_iter()
+        .chain(finality_delay.map(|finality_delay| defer(now() + finality_delay)))
+        .chain([
+            promise(
+                [call(FetchUpdateHeaders {
+                    client_type: client_info.client_type,
+                    chain_id: client_state_meta.counterparty_chain_id,
+                    counterparty_chain_id: self.chain_id.clone(),
+                    client_id: self.client_id.clone(),
+                    update_from: client_state_meta.counterparty_height,
+                    update_to: height,
+                })],
+                [],
+                AggregateSubmitTxFromOrderedHeaders {
+                    ibc_spec_id: self.ibc_spec_id.clone(),
+                    chain_id: self.chain_id.clone(),
+                    client_id: self.client_id.clone(),
+                },
+            ),
+            call(WaitForClientUpdate {
+                chain_id: self.chain_id,
+                ibc_spec_id: self.ibc_spec_id,
+                client_id: self.client_id,
+                height,
+            }),
+        ])))
+    }
+}
+
+impl ResendAckTemplate {
+    async fn expand(self, server: &Server) -> Result<Op<VoyagerMessage>, TemplateError> {
+        let channel_not_open = || TemplateError::ChannelNotOpen {
+            chain_id: self.chain_id.clone(),
+            channel_id: self.channel_id,
+        };
+
+        let latest_height = server.query_latest_height(&self.chain_id, true).await?;
+
+        // channels and connections don't change once open, so they are read at the latest height
+        let destination_channel = server
+            .query_ibc_state::<ChannelPath>(
+                &self.chain_id,
+                latest_height,
+                ChannelPath {
+                    channel_id: self.channel_id,
+                }
+                .into(),
+            )
+            .await?
+            .state
+            .ok_or_else(|| TemplateError::ChannelNotFound {
+                chain_id: self.chain_id.clone(),
+                channel_id: self.channel_id,
+            })?;
+
+        let destination_connection = server
+            .query_ibc_state::<ConnectionPath>(
+                &self.chain_id,
+                latest_height,
+                ConnectionPath {
+                    connection_id: destination_channel.connection_id,
+                }
+                .into(),
+            )
+            .await?
+            .state
+            .ok_or_else(channel_not_open)?;
+
+        let client_id = RawClientId::new(destination_connection.client_id);
+
+        let client_not_found = || TemplateError::ClientNotFound {
+            chain_id: self.chain_id.clone(),
+            client_id: client_id.clone(),
+        };
+
+        let client_info = server
+            .client_info(&self.chain_id, &IbcUnion::ID, client_id.clone())
+            .await?
+            .ok_or_else(client_not_found)?;
+
+        let counterparty_chain_id = server
+            .client_state_meta(
+                &self.chain_id,
+                &IbcUnion::ID,
+                QueryHeight::Latest,
+                client_id.clone(),
+            )
+            .await?
+            .ok_or_else(client_not_found)?
+            .counterparty_chain_id;
+
+        let source_channel_id = destination_channel
+            .counterparty_channel_id
+            .ok_or_else(channel_not_open)?;
+
+        let counterparty_latest_height = server
+            .query_latest_height(&counterparty_chain_id, false)
+            .await?;
+
+        let source_channel = server
+            .query_ibc_state::<ChannelPath>(
+                &counterparty_chain_id,
+                counterparty_latest_height,
+                ChannelPath {
+                    channel_id: source_channel_id,
+                }
+                .into(),
+            )
+            .await?
+            .state
+            .ok_or_else(channel_not_open)?;
+
+        let packet = server
+            .query(
+                &counterparty_chain_id,
+                PacketByHash {
+                    channel_id: source_channel_id,
+                    packet_hash: self.packet_hash,
+                },
+            )
+            .await?;
+
+        let event = WriteAck {
+            packet_data: packet.data,
+            acknowledgement: self.acknowledgement,
+            packet: PacketMetadata {
+                source_channel: ChannelMetadata {
+                    channel_id: source_channel_id,
+                    version: source_channel.version,
+                    connection: ConnectionMetadata {
+                        client_id: destination_connection.counterparty_client_id,
+                        connection_id: destination_connection
+                            .counterparty_connection_id
+                            .ok_or_else(channel_not_open)?,
+                    },
+                },
+                destination_channel: ChannelMetadata {
+                    channel_id: self.channel_id,
+                    version: destination_channel.version,
+                    connection: ConnectionMetadata {
+                        client_id: destination_connection.client_id,
+                        connection_id: destination_channel.connection_id,
+                    },
+                },
+                timeout_height: packet.timeout_height,
+                timeout_timestamp: packet.timeout_timestamp,
+            },
+        };
+
+        Ok(seq(wait_for_finalized(
+            &self.chain_id,
+            self.height,
+            latest_height,
+        )?
+        .into_iter()
+        .chain([data(ChainEvent::new::<IbcUnion>(
+            self.chain_id,
+            client_info,
+            counterparty_chain_id,
+            self.tx_hash,
+            // the ack is written to the state at height, which is provable from the next height
+            // on all chains
+            EventProvableHeight::Min(self.height.increment()),
+            event.into(),
+        ))])))
+    }
+}
+
+/// Compare `height` to `other_height`, which must be in the same revision.
+fn cmp_heights(height: Height, other_height: Height) -> Result<Ordering, TemplateError> {
+    height
+        .same_revision_cmp(&other_height)
+        .ok_or(TemplateError::RevisionMismatch {
+            height,
+            other_height,
+        })
+}
+
+/// Wait for `height` to be finalized on `chain_id`, if it is not yet finalized as of
+/// `latest_height`.
+fn wait_for_finalized(
+    chain_id: &ChainId,
+    height: Height,
+    latest_height: Height,
+) -> Result<Option<Op<VoyagerMessage>>, TemplateError> {
+    Ok(cmp_heights(height, latest_height)?.is_gt().then(|| {
+        call(WaitForHeight {
+            chain_id: chain_id.clone(),
+            height,
+            finalized: true,
+        })
+    }))
+}
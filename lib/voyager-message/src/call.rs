@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use enumorph::Enumorph;
 use macros::model;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -183,6 +185,10 @@ pub struct SubmitTx {
     pub chain_id: ChainId,
     // TODO: Ensure this is non-empty
     pub datagrams: Vec<IbcDatagram>,
+    /// Only submit the transaction with one of these signers (by address). If `None`, any signer
+    /// of the plugin handling the submission can be used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signers: Option<BTreeSet<String>>,
 }
 
 #[model]
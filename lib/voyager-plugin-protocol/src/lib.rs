@@ -17,17 +17,28 @@
 //! 3. Worker starts it's server, listening on [`worker_socket_path`].
 //! 4. Worker creates a client connecting to [`coordinator_socket_path`].
 //! 5. Coordinator client now connects to the booted worker.
+//!
+//! # Shutdown Sequence
+//!
+//! 1. Coordinator sends `SIGTERM` to the worker.
+//! 2. Worker rejects all new requests, and waits for the requests that are already in flight to finish.
+//! 3. Worker runs the tasks registered with [`on_shutdown`] (flushing caches, metrics, etc).
+//! 4. Worker stops its server, closing its connection to the coordinator, and exits.
+//! 5. If the worker has not exited within the shutdown timeout, the coordinator kills it.
 
 use std::{
     borrow::Cow,
     fmt::Debug,
     future::Future,
     path::{Path, PathBuf},
-    sync::Arc,
-    time::Duration,
+    sync::{Arc, LazyLock},
+    time::{Duration, Instant},
 };
 
-use futures::FutureExt;
+use futures::{
+    future::{self, Either},
+    FutureExt,
+};
 use jsonrpsee::{
     core::{
         client::{BatchResponse, ClientT},
@@ -36,19 +47,30 @@ use jsonrpsee::{
         TEN_MB_SIZE_BYTES,
     },
     server::middleware::rpc::RpcServiceT,
-    types::{ErrorObject, Response, ResponsePayload},
+    types::{error::ErrorCode, ErrorObject, Response, ResponsePayload},
     MethodResponse, RpcModule,
 };
+use nix::{
+    sys::signal::{kill, Signal},
+    unistd::Pid,
+};
+use opentelemetry::{
+    metrics::{Counter, Histogram},
+    KeyValue,
+};
 use reth_ipc::{
     client::IpcClientBuilder,
     server::{RpcService, RpcServiceBuilder},
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::value::RawValue;
-use tokio::time::sleep;
-use tokio_util::sync::CancellationToken;
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    time::{sleep, timeout},
+};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use tower::Layer;
-use tracing::{debug, debug_span, error, info, info_span, instrument, trace, Instrument};
+use tracing::{debug, debug_span, error, info, info_span, instrument, trace, warn, Instrument};
 use unionlabs::{ethereum::slot::keccak256, primitives::encoding::HexUnprefixed, ErrorReporter};
 use voyager_client::VoyagerClient;
 use voyager_rpc::VoyagerRpcServer;
@@ -57,6 +79,24 @@ use voyager_vm::ItemId;
 pub const INVALID_CONFIG_EXIT_CODE: u8 = 13;
 pub const STARTUP_ERROR_EXIT_CODE: u8 = 14;
 
+/// Tasks to run when the worker shuts down, see [`on_shutdown`].
+static SHUTDOWN_TASKS: LazyLock<(CancellationToken, TaskTracker)> =
+    LazyLock::new(|| (CancellationToken::new(), TaskTracker::new()));
+
+/// Register a task to run when the worker shuts down, after all of its in-flight requests have finished.
+///
+/// The worker waits for all registered tasks to complete before exiting. Note that the coordinator kills the worker if it takes longer than the shutdown timeout to exit, so these tasks should be short.
+///
+/// This must be called from within a tokio runtime.
+pub fn on_shutdown(fut: impl Future<Output = ()> + Send + 'static) {
+    let (shutdown, tasks) = &*SHUTDOWN_TASKS;
+
+    tasks.spawn(async move {
+        shutdown.cancelled().await;
+        fut.await;
+    });
+}
+
 /// Run the coordinator server.
 ///
 /// This will listen to messages on [`coordinator_socket_path`]`(name)`.
@@ -128,11 +168,28 @@ pub async fn worker_server<T>(
 
     trace!("connected to voyager socket");
 
+    // the requests currently being handled by the worker, closed once the worker starts draining
+    let in_flight = TaskTracker::new();
+
     let ipc_server = reth_ipc::server::Builder::default()
         .max_request_body_size(TEN_MB_SIZE_BYTES * 10)
         .max_response_body_size(TEN_MB_SIZE_BYTES * 10)
         .set_rpc_middleware(
             RpcServiceBuilder::new()
+                .layer_fn({
+                    let in_flight = in_flight.clone();
+                    move |service| DrainService {
+                        service,
+                        in_flight: in_flight.clone(),
+                    }
+                })
+                .layer_fn({
+                    let metrics = RequestMetrics::new(&id);
+                    move |service| RequestMetricsService {
+                        service,
+                        metrics: metrics.clone(),
+                    }
+                })
                 .layer_fn(move |service| ExtractItemIdService { service })
                 .layer_fn(move |service| InjectVoyagerClientService {
                     client: voyager_client.clone(),
@@ -155,10 +212,34 @@ pub async fn worker_server<T>(
     let server_handle = ipc_server.start(rpcs).await.unwrap();
     debug!("listening on {addr}");
 
-    server_handle
-        .stopped()
-        .instrument(debug_span!("{id}"))
-        .await
+    let mut sigterm = signal(SignalKind::terminate()).expect("unable to listen for SIGTERM");
+
+    tokio::select! {
+        () = server_handle.clone().stopped() => {}
+        _ = sigterm.recv() => {
+            info!("received shutdown signal, draining");
+
+            in_flight.close();
+            in_flight.wait().await;
+
+            debug!("in-flight requests finished, running shutdown tasks");
+
+            let (shutdown, tasks) = &*SHUTDOWN_TASKS;
+            shutdown.cancel();
+            tasks.close();
+            tasks.wait().await;
+
+            // this also closes the connection to the coordinator, which is held by the server
+            let _ = server_handle.stop();
+
+            server_handle
+                .stopped()
+                .instrument(debug_span!("{id}"))
+                .await;
+
+            info!("shut down");
+        }
+    }
 }
 
 /// The RPC client to communicate with a worker from the coordinator.
@@ -456,6 +537,140 @@ where
     }
 }
 
+/// An [`RpcServiceT`] layer to track the requests in flight, so that they can be waited for when the worker shuts down.
+///
+/// Once `in_flight` is closed, the worker is draining and all new requests are rejected. The coordinator retries them once the worker has been restarted.
+#[derive(Clone)]
+struct DrainService<S> {
+    service: S,
+    in_flight: TaskTracker,
+}
+
+impl<S> RpcServiceT for DrainService<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+{
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+    type BatchResponse = S::BatchResponse;
+
+    fn call<'a>(
+        &self,
+        request: jsonrpsee::types::Request<'a>,
+    ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        if self.in_flight.is_closed() {
+            Either::Left(future::ready(MethodResponse::error(
+                request.id,
+                ErrorObject::owned(
+                    ErrorCode::ServerIsBusy.code(),
+                    "worker is shutting down",
+                    None::<()>,
+                ),
+            )))
+        } else {
+            Either::Right(self.in_flight.track_future(self.service.call(request)))
+        }
+    }
+
+    fn batch<'a>(
+        &self,
+        requests: jsonrpsee::core::middleware::Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        self.service.batch(requests)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: jsonrpsee::core::middleware::Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.service.notification(n)
+    }
+}
+
+#[derive(Clone)]
+struct RequestMetrics {
+    id: KeyValue,
+    requests: Counter<u64>,
+    duration: Histogram<f64>,
+}
+
+impl RequestMetrics {
+    fn new(id: &str) -> Self {
+        let meter = opentelemetry::global::meter("voyager");
+
+        Self {
+            id: KeyValue::new("worker", id.to_owned()),
+            requests: meter.u64_counter("worker.requests").build(),
+            duration: meter
+                .f64_histogram("worker.request.duration")
+                .with_unit("s")
+                .build(),
+        }
+    }
+}
+
+/// An [`RpcServiceT`] layer to record the count and latency of the requests handled by the worker,
+/// per method and result.
+#[derive(Clone)]
+struct RequestMetricsService<S> {
+    service: S,
+    metrics: RequestMetrics,
+}
+
+impl<S> RpcServiceT for RequestMetricsService<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+{
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+    type BatchResponse = S::BatchResponse;
+
+    fn call<'a>(
+        &self,
+        request: jsonrpsee::types::Request<'a>,
+    ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        let metrics = self.metrics.clone();
+        let method = request.method_name().to_owned();
+        let start = Instant::now();
+
+        self.service.call(request).map(move |method_response| {
+            let attributes = [
+                metrics.id,
+                KeyValue::new("method", method),
+                KeyValue::new(
+                    "result",
+                    if method_response.is_error() {
+                        "error"
+                    } else {
+                        "ok"
+                    },
+                ),
+            ];
+
+            metrics.requests.add(1, &attributes);
+            metrics
+                .duration
+                .record(start.elapsed().as_secs_f64(), &attributes);
+
+            method_response
+        })
+    }
+
+    fn batch<'a>(
+        &self,
+        requests: jsonrpsee::core::middleware::Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        self.service.batch(requests)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: jsonrpsee::core::middleware::Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.service.notification(n)
+    }
+}
+
 /// Structure of a message containing a threaded item id.
 ///
 /// The field names are intentionally mangled in order to prevent collisions with real RPC request parameters. If your method parameters clash with this struct, you should probably re-think what you're doing.
@@ -563,6 +778,7 @@ pub async fn worker_child_process(
     name: String,
     path: PathBuf,
     cancellation_token: CancellationToken,
+    shutdown_timeout: Duration,
     args: impl IntoIterator<Item: Into<String>>,
 ) {
     let coordinator_to_worker_socket = worker_socket_path(&name);
@@ -581,13 +797,21 @@ pub async fn worker_child_process(
         .chain(args.into_iter().map(Into::into))
         .collect(),
         cancellation_token,
+        shutdown_timeout,
     )
     .await
 }
 
 /// Spawn a worker process with the given args, re-spawning it indefinitely unless it exits with [`INVALID_CONFIG_EXIT_CODE`] or the passed in cancellation token is cancelled.
+///
+/// Once the cancellation token is cancelled, the worker is shut down gracefully, and killed if it has not exited within `shutdown_timeout`.
 #[instrument(skip_all)]
-async fn lazarus_pit(cmd: &Path, args: Vec<String>, cancellation_token: CancellationToken) {
+async fn lazarus_pit(
+    cmd: &Path,
+    args: Vec<String>,
+    cancellation_token: CancellationToken,
+    shutdown_timeout: Duration,
+) {
     let mut attempt = 0;
 
     loop {
@@ -620,23 +844,7 @@ async fn lazarus_pit(cmd: &Path, args: Vec<String>, cancellation_token: Cancella
 
         tokio::select! {
             _ = cancellation_token.cancelled() => {
-                debug!(%id, "killing plugin");
-                match child.kill().await {
-                    Ok(()) => {
-                        debug!(%id, "plugin received kill signal");
-                        match child.wait().await {
-                            Ok(exit_status) => {
-                                debug!(%id, %exit_status, "child exited successfully")
-                            }
-                            Err(err) => {
-                                error!(%id, err = %ErrorReporter(err), "child exited unsuccessfully")
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        error!(%id, err = %ErrorReporter(err), "unable to kill plugin")
-                    }
-                }
+                shutdown(&mut child, id, shutdown_timeout).await;
 
                 break
             }
@@ -659,6 +867,11 @@ async fn lazarus_pit(cmd: &Path, args: Vec<String>, cancellation_token: Cancella
                     }
                 }
 
+                // the worker may have exited from a signal meant for the whole process group
+                if cancellation_token.is_cancelled() {
+                    break;
+                }
+
                 // TODO: Exponential backoff
                 sleep(Duration::from_secs(1)).await;
             }
@@ -668,6 +881,47 @@ async fn lazarus_pit(cmd: &Path, args: Vec<String>, cancellation_token: Cancella
     }
 }
 
+/// Shut down a worker process, giving it `shutdown_timeout` to drain before it is killed. See the [shutdown sequence](crate#shutdown-sequence).
+async fn shutdown(child: &mut tokio::process::Child, id: u32, shutdown_timeout: Duration) {
+    debug!(%id, "shutting down plugin");
+
+    if let Err(err) = kill(Pid::from_raw(id as i32), Signal::SIGTERM) {
+        error!(%id, err = %ErrorReporter(err), "unable to send shutdown signal to plugin");
+    } else {
+        match timeout(shutdown_timeout, child.wait()).await {
+            Ok(Ok(exit_status)) => {
+                debug!(%id, %exit_status, "child exited successfully");
+                return;
+            }
+            Ok(Err(err)) => {
+                error!(%id, err = %ErrorReporter(err), "child exited unsuccessfully");
+                return;
+            }
+            Err(_) => {
+                warn!(%id, "plugin did not shut down within {shutdown_timeout:?}");
+            }
+        }
+    }
+
+    debug!(%id, "killing plugin");
+    match child.kill().await {
+        Ok(()) => {
+            debug!(%id, "plugin received kill signal");
+            match child.wait().await {
+                Ok(exit_status) => {
+                    debug!(%id, %exit_status, "child exited successfully")
+                }
+                Err(err) => {
+                    error!(%id, err = %ErrorReporter(err), "child exited unsuccessfully")
+                }
+            }
+        }
+        Err(err) => {
+            error!(%id, err = %ErrorReporter(err), "unable to kill plugin")
+        }
+    }
+}
+
 // https://github.com/paritytech/jsonrpsee/issues/1578
 #[derive(Debug)]
 pub struct ArcClient<C>(Arc<C>);
@@ -1,9 +1,14 @@
-use std::{env::VarError, time::Duration};
+use std::{env::VarError, net::SocketAddr, time::Duration};
 
 use opentelemetry::KeyValue;
 use opentelemetry_otlp::WithExportConfig;
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::RootSchema,
+    JsonSchema,
+};
 use serde::de::DeserializeOwned;
-use tracing::{debug_span, instrument, Instrument};
+use tracing::{debug_span, error, instrument, Instrument};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 use unionlabs::ErrorReporter;
 pub use voyager_plugin_protocol as protocol;
@@ -18,14 +23,22 @@ use voyager_rpc::{
     ProofModuleServer, StateModuleServer,
 };
 
+pub mod metrics;
+
 #[allow(async_fn_in_trait)]
 pub trait Plugin: PluginServer<Self::Call, Self::Callback> + Sized {
     type Call: Member;
     type Callback: Member;
 
-    type Config: DeserializeOwned + Clone;
+    type Config: DeserializeOwned + Clone + JsonSchema;
     type Cmd: clap::Subcommand;
 
+    /// The JSON schema of [`Self::Config`], printed by `--config-schema`. Voyager validates the
+    /// config against this schema before starting anything, see [`config_schema`].
+    fn config_schema() -> RootSchema {
+        config_schema::<Self::Config>()
+    }
+
     async fn new(config: Self::Config) -> anyhow::Result<Self>;
 
     fn info(config: Self::Config) -> PluginInfo;
@@ -41,8 +54,9 @@ pub trait Plugin: PluginServer<Self::Call, Self::Callback> + Sized {
                 coordinator_socket,
                 config,
                 metrics_endpoint,
+                metrics_laddr,
             } => {
-                init(metrics_endpoint);
+                init(metrics_endpoint, metrics_laddr);
 
                 let config = must_parse::<Self::Config>(&config);
 
@@ -66,13 +80,19 @@ pub trait Plugin: PluginServer<Self::Call, Self::Callback> + Sized {
                 print!("{}", serde_json::to_string(&info).unwrap())
             }
             PluginApp::Cmd { cmd, config } => Self::cmd(must_parse(&config), cmd).await,
+            PluginApp::ConfigSchema => print_config_schema(Self::config_schema()),
         }
     }
 }
 
 #[allow(async_fn_in_trait)]
 pub trait StateModule<V: IbcSpec>: StateModuleServer<V> + Sized {
-    type Config: DeserializeOwned + Clone;
+    type Config: DeserializeOwned + Clone + JsonSchema;
+
+    /// The JSON schema of [`Self::Config`], see [`Plugin::config_schema`].
+    fn config_schema() -> RootSchema {
+        config_schema::<Self::Config>()
+    }
 
     async fn new(config: Self::Config, info: StateModuleInfo) -> anyhow::Result<Self>;
 
@@ -84,8 +104,9 @@ pub trait StateModule<V: IbcSpec>: StateModuleServer<V> + Sized {
                 config,
                 info,
                 metrics_endpoint,
+                metrics_laddr,
             } => {
-                init(metrics_endpoint);
+                init(metrics_endpoint, metrics_laddr);
 
                 let config = must_parse::<Self::Config>(&config);
 
@@ -103,13 +124,19 @@ pub trait StateModule<V: IbcSpec>: StateModuleServer<V> + Sized {
                 .instrument(debug_span!("run_state_module_server", %name))
                 .await
             }
+            ModuleApp::ConfigSchema => print_config_schema(Self::config_schema()),
         }
     }
 }
 
 #[allow(async_fn_in_trait)]
 pub trait ProofModule<V: IbcSpec>: ProofModuleServer<V> + Sized {
-    type Config: DeserializeOwned + Clone;
+    type Config: DeserializeOwned + Clone + JsonSchema;
+
+    /// The JSON schema of [`Self::Config`], see [`Plugin::config_schema`].
+    fn config_schema() -> RootSchema {
+        config_schema::<Self::Config>()
+    }
 
     async fn new(config: Self::Config, info: ProofModuleInfo) -> anyhow::Result<Self>;
 
@@ -121,8 +148,9 @@ pub trait ProofModule<V: IbcSpec>: ProofModuleServer<V> + Sized {
                 config,
                 info,
                 metrics_endpoint,
+                metrics_laddr,
             } => {
-                init(metrics_endpoint);
+                init(metrics_endpoint, metrics_laddr);
 
                 let config = must_parse::<Self::Config>(&config);
 
@@ -140,13 +168,19 @@ pub trait ProofModule<V: IbcSpec>: ProofModuleServer<V> + Sized {
                 .instrument(debug_span!("run_proof_module_server", %name))
                 .await
             }
+            ModuleApp::ConfigSchema => print_config_schema(Self::config_schema()),
         }
     }
 }
 
 #[allow(async_fn_in_trait)]
 pub trait FinalityModule: FinalityModuleServer + Sized {
-    type Config: DeserializeOwned + Clone;
+    type Config: DeserializeOwned + Clone + JsonSchema;
+
+    /// The JSON schema of [`Self::Config`], see [`Plugin::config_schema`].
+    fn config_schema() -> RootSchema {
+        config_schema::<Self::Config>()
+    }
 
     async fn new(config: Self::Config, info: FinalityModuleInfo) -> anyhow::Result<Self>;
 
@@ -158,8 +192,9 @@ pub trait FinalityModule: FinalityModuleServer + Sized {
                 config,
                 info,
                 metrics_endpoint,
+                metrics_laddr,
             } => {
-                init(metrics_endpoint);
+                init(metrics_endpoint, metrics_laddr);
 
                 let config = must_parse::<Self::Config>(&config);
 
@@ -177,13 +212,19 @@ pub trait FinalityModule: FinalityModuleServer + Sized {
                 .instrument(debug_span!("run_finality_module_server", %name))
                 .await
             }
+            ModuleApp::ConfigSchema => print_config_schema(Self::config_schema()),
         }
     }
 }
 
 #[allow(async_fn_in_trait)]
 pub trait ClientModule: ClientModuleServer + Sized {
-    type Config: DeserializeOwned + Clone;
+    type Config: DeserializeOwned + Clone + JsonSchema;
+
+    /// The JSON schema of [`Self::Config`], see [`Plugin::config_schema`].
+    fn config_schema() -> RootSchema {
+        config_schema::<Self::Config>()
+    }
 
     async fn new(config: Self::Config, info: ClientModuleInfo) -> anyhow::Result<Self>;
 
@@ -195,8 +236,9 @@ pub trait ClientModule: ClientModuleServer + Sized {
                 config,
                 info,
                 metrics_endpoint,
+                metrics_laddr,
             } => {
-                init(metrics_endpoint);
+                init(metrics_endpoint, metrics_laddr);
 
                 let config = must_parse::<Self::Config>(&config);
 
@@ -214,13 +256,19 @@ pub trait ClientModule: ClientModuleServer + Sized {
                 .instrument(debug_span!("run_client_module_server", %name))
                 .await
             }
+            ModuleApp::ConfigSchema => print_config_schema(Self::config_schema()),
         }
     }
 }
 
 #[allow(async_fn_in_trait)]
 pub trait ClientBootstrapModule: ClientBootstrapModuleServer + Sized {
-    type Config: DeserializeOwned + Clone;
+    type Config: DeserializeOwned + Clone + JsonSchema;
+
+    /// The JSON schema of [`Self::Config`], see [`Plugin::config_schema`].
+    fn config_schema() -> RootSchema {
+        config_schema::<Self::Config>()
+    }
 
     async fn new(config: Self::Config, info: ClientBootstrapModuleInfo) -> anyhow::Result<Self>;
 
@@ -232,8 +280,9 @@ pub trait ClientBootstrapModule: ClientBootstrapModuleServer + Sized {
                 config,
                 info,
                 metrics_endpoint,
+                metrics_laddr,
             } => {
-                init(metrics_endpoint);
+                init(metrics_endpoint, metrics_laddr);
 
                 let config = must_parse::<Self::Config>(&config);
 
@@ -251,6 +300,7 @@ pub trait ClientBootstrapModule: ClientBootstrapModuleServer + Sized {
                 .instrument(debug_span!("run_client_bootstrap_module_server", %name))
                 .await
             }
+            ModuleApp::ConfigSchema => print_config_schema(Self::config_schema()),
         }
     }
 }
@@ -262,6 +312,9 @@ enum PluginApp<Cmd: clap::Subcommand> {
         coordinator_socket: String,
         config: String,
         metrics_endpoint: Option<String>,
+        /// Serve metrics in the prometheus text format on `/metrics` at this address.
+        #[arg(long)]
+        metrics_laddr: Option<SocketAddr>,
     },
     Info {
         config: String,
@@ -272,6 +325,9 @@ enum PluginApp<Cmd: clap::Subcommand> {
         #[arg(long)]
         config: String,
     },
+    /// Print the JSON schema of the config.
+    #[command(long_flag = "config-schema")]
+    ConfigSchema,
 }
 
 #[derive(clap::Parser)]
@@ -282,11 +338,27 @@ enum ModuleApp {
         config: String,
         info: String,
         metrics_endpoint: Option<String>,
+        /// Serve metrics in the prometheus text format on `/metrics` at this address.
+        #[arg(long)]
+        metrics_laddr: Option<SocketAddr>,
     },
+    /// Print the JSON schema of the config.
+    #[command(long_flag = "config-schema")]
+    ConfigSchema,
+}
+
+/// Generate the JSON schema of a config type. This is the default `config_schema()` of plugins and
+/// modules.
+pub fn config_schema<T: JsonSchema>() -> RootSchema {
+    SchemaGenerator::new(SchemaSettings::draft2019_09()).into_root_schema_for::<T>()
+}
+
+fn print_config_schema(schema: RootSchema) {
+    print!("{}", serde_json::to_string(&schema).unwrap())
 }
 
 // set up logging and metrics
-fn init(metrics_endpoint: Option<String>) {
+fn init(metrics_endpoint: Option<String>, metrics_laddr: Option<SocketAddr>) {
     enum LogFormat {
         Text,
         Json,
@@ -305,25 +377,49 @@ fn init(metrics_endpoint: Option<String>) {
         }
     };
 
-    if let Some(metrics_endpoint) = metrics_endpoint {
-        let metric_exporter = opentelemetry_otlp::MetricExporter::builder()
-            .with_http()
-            .with_endpoint(&metrics_endpoint)
-            .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
-            .with_timeout(Duration::from_secs(3))
-            .build()
-            .expect("unable to build metrics exporter");
-
-        let provider = opentelemetry_sdk::metrics::SdkMeterProvider::builder()
-            .with_periodic_exporter(metric_exporter)
-            .with_resource(
-                opentelemetry_sdk::Resource::builder_empty()
-                    .with_attributes([KeyValue::new("process.name", "voyager")])
-                    .build(),
-            )
-            .build();
-
-        opentelemetry::global::set_meter_provider(provider);
+    let registry = metrics_laddr.map(|_| prometheus::Registry::new());
+
+    if metrics_endpoint.is_some() || registry.is_some() {
+        let mut provider = opentelemetry_sdk::metrics::SdkMeterProvider::builder().with_resource(
+            opentelemetry_sdk::Resource::builder_empty()
+                .with_attributes([KeyValue::new("process.name", "voyager")])
+                .build(),
+        );
+
+        if let Some(metrics_endpoint) = metrics_endpoint {
+            let metric_exporter = opentelemetry_otlp::MetricExporter::builder()
+                .with_http()
+                .with_endpoint(&metrics_endpoint)
+                .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+                .with_timeout(Duration::from_secs(3))
+                .build()
+                .expect("unable to build metrics exporter");
+
+            provider = provider.with_periodic_exporter(metric_exporter);
+        }
+
+        if let Some(registry) = &registry {
+            let prometheus_exporter = opentelemetry_prometheus::exporter()
+                .with_registry(registry.clone())
+                .build()
+                .expect("unable to build prometheus exporter");
+
+            provider = provider.with_reader(prometheus_exporter);
+        }
+
+        let provider = provider.build();
+
+        opentelemetry::global::set_meter_provider(provider.clone());
+
+        // export the metrics recorded since the last periodic export before exiting
+        protocol::on_shutdown(async move {
+            // the exporter blocks on the export
+            match tokio::task::spawn_blocking(move || provider.shutdown()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => error!(err = %ErrorReporter(err), "unable to flush metrics"),
+                Err(err) => error!(err = %ErrorReporter(err), "unable to flush metrics"),
+            }
+        });
     };
 
     match format {
@@ -342,6 +438,10 @@ fn init(metrics_endpoint: Option<String>) {
                 .init();
         }
     }
+
+    if let Some((metrics_laddr, registry)) = metrics_laddr.zip(registry) {
+        metrics::serve(metrics_laddr, registry).expect("unable to serve metrics");
+    }
 }
 
 #[instrument(level = "debug", fields(%config_str))]
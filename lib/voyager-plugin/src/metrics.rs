@@ -0,0 +1,85 @@
+//! Metrics of plugins and modules.
+//!
+//! All metrics are recorded with the [global meter provider](opentelemetry::global), which is set
+//! up by the `run` functions of the plugin and module traits. Metrics are pushed to the otlp
+//! endpoint passed by voyager (if any), and served in the prometheus text format on `/metrics` if
+//! `metrics_laddr` is configured for the plugin or module.
+//!
+//! Request counts and latencies of all rpc methods are recorded automatically. Upstream errors have
+//! to be recorded by the module itself, see [`UpstreamResultExt`].
+
+use std::net::SocketAddr;
+
+use anyhow::Context;
+use axum::{http::StatusCode, routing::get};
+use opentelemetry::KeyValue;
+use prometheus::{Encoder, Registry, TextEncoder};
+use tracing::{error, info};
+use unionlabs::ErrorReporter;
+
+/// Serve the metrics collected in `registry` on `http://{laddr}/metrics`.
+///
+/// Fails if `laddr` can't be bound. Errors of the server after that are logged.
+pub(crate) fn serve(laddr: SocketAddr, registry: Registry) -> anyhow::Result<()> {
+    let app = axum::Router::new().route(
+        "/metrics",
+        get(move || async move {
+            let mut buf = vec![];
+
+            match TextEncoder::new().encode(&registry.gather(), &mut buf) {
+                Ok(()) => Ok(buf),
+                Err(err) => {
+                    error!(error = %ErrorReporter(err), "unable to encode metrics");
+                    Err(StatusCode::INTERNAL_SERVER_ERROR)
+                }
+            }
+        }),
+    );
+
+    let server = axum::Server::try_bind(&laddr)
+        .with_context(|| format!("unable to bind metrics server to {laddr}"))?;
+
+    info!(%laddr, "serving metrics");
+
+    tokio::spawn(async move {
+        if let Err(err) = server.serve(app.into_make_service()).await {
+            error!(error = %ErrorReporter(err), %laddr, "metrics server failed");
+        }
+    });
+
+    Ok(())
+}
+
+/// Record errors returned from the upstream of a module (i.e. the rpc of the chain it connects
+/// to) in the `upstream.errors` counter.
+///
+/// ```rust,ignore
+/// let status = self
+///     .cometbft_client
+///     .status()
+///     .await
+///     .record_upstream_error("cometbft", "status")?;
+/// ```
+pub trait UpstreamResultExt: Sized {
+    #[must_use]
+    fn record_upstream_error(self, upstream: &'static str, method: &'static str) -> Self;
+}
+
+impl<T, E> UpstreamResultExt for Result<T, E> {
+    fn record_upstream_error(self, upstream: &'static str, method: &'static str) -> Self {
+        if self.is_err() {
+            opentelemetry::global::meter("voyager")
+                .u64_counter("upstream.errors")
+                .build()
+                .add(
+                    1,
+                    &[
+                        KeyValue::new("upstream", upstream),
+                        KeyValue::new("method", method),
+                    ],
+                );
+        }
+
+        self
+    }
+}
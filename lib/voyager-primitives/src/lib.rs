@@ -8,6 +8,7 @@ pub use consensus_primitives::{Duration, Timestamp};
 use macros::apply;
 use serde_json::Value;
 use unionlabs::{
+    chain_id::ChainIdLiteral,
     ibc::core::client::height::{Height, HeightFromStrError},
     primitives::{encoding::HexUnprefixed, Bytes, H256},
 };
@@ -361,6 +362,44 @@ impl IbcSpecId {
 #[apply(str_newtype)]
 pub struct ChainId;
 
+impl ChainId {
+    /// Construct a [`ChainId`] from a compile time validated [`ChainIdLiteral`]. See [`chain_id!`]
+    /// for constructing a [`ChainId`] directly from a string literal.
+    #[must_use = "constructing a ChainId has no effect"]
+    pub const fn from_literal(literal: ChainIdLiteral) -> Self {
+        Self::new_static(literal.as_str())
+    }
+}
+
+impl From<ChainIdLiteral> for ChainId {
+    fn from(value: ChainIdLiteral) -> Self {
+        Self::from_literal(value)
+    }
+}
+
+/// Construct a const [`ChainId`] from a string literal, failing to compile if the chain id is
+/// malformed.
+///
+/// ```rust
+/// # use voyager_primitives::{chain_id, ChainId};
+/// const UNION_TESTNET: ChainId = chain_id!("union-testnet-10");
+///
+/// assert_eq!(UNION_TESTNET, ChainId::new("union-testnet-10"));
+/// ```
+///
+/// See [`unionlabs::chain_id!`] for the validation rules.
+#[macro_export]
+macro_rules! chain_id {
+    ($id:literal) => {
+        $crate::ChainId::from_literal($crate::__private::unionlabs::chain_id!($id))
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use unionlabs;
+}
+
 /// The type of a light client on a chain, along with the IBC interface it's on
 /// (and any associated metadata).
 ///
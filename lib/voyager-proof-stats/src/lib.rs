@@ -0,0 +1,397 @@
+//! Proof sizes and fee costs of the ibc-union datagrams submitted by the ethereum and cosmos-sdk
+//! transaction plugins, per (source, destination) chain pair.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use futures::TryStreamExt;
+use ibc_union_spec::{datagram::Datagram, ChannelId, ClientId, ConnectionId, IbcUnion};
+use jsonrpsee::core::RpcResult;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgPoolOptions, Executor, PgPool, Row};
+use tracing::{debug, info_span, trace, Instrument};
+use unionlabs::primitives::H256;
+use voyager_sdk::{
+    anyhow,
+    primitives::{ChainId, QueryHeight},
+    VoyagerClient,
+};
+
+/// Persist the proof sizes and fee costs of submitted datagrams, per (source, destination)
+/// chain pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StatsConfig {
+    pub db_url: String,
+}
+
+#[derive(Debug)]
+pub struct Stats {
+    db: PgPool,
+    /// The counterparty chains of the clients, connections and channels on this chain. These never
+    /// change once the client, connection or channel exists.
+    source_chain_ids: Mutex<HashMap<ClientRef, ChainId>>,
+}
+
+/// A successfully submitted transaction.
+#[derive(Debug)]
+pub struct SubmittedTx {
+    pub tx_hash: H256,
+    pub gas_used: u64,
+    /// The fee paid for the transaction, in the smallest unit of the fee token of the chain (wei
+    /// on evm chains).
+    pub fee: u128,
+    /// The submitted datagrams, with the length of their encoding in the transaction (the
+    /// calldata on evm chains, the encoded msg on cosmos chains).
+    pub datagrams: Vec<(Datagram, usize)>,
+}
+
+/// Aggregated stats of the datagrams of one kind, submitted from `source_chain_id` to
+/// `destination_chain_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofStats {
+    pub source_chain_id: ChainId,
+    pub destination_chain_id: ChainId,
+    pub kind: String,
+    pub count: i64,
+    pub avg_proof_bytes: f64,
+    pub max_proof_bytes: i64,
+    pub avg_calldata_bytes: f64,
+    /// The gas used by the transactions, attributed to the datagrams by their share of the
+    /// calldata.
+    pub avg_gas_used: f64,
+    /// The total fee (in the smallest unit of the fee token of the destination chain), attributed
+    /// the same way as the gas.
+    pub total_fee: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ClientRef {
+    Client(ClientId),
+    Connection(ConnectionId),
+    Channel(ChannelId),
+}
+
+impl Stats {
+    pub async fn new(config: StatsConfig) -> anyhow::Result<Self> {
+        let db = PgPoolOptions::new().connect(&config.db_url).await?;
+
+        db.execute_many(
+            r#"
+            CREATE TABLE IF NOT EXISTS
+              proof_stats (
+                id BIGSERIAL PRIMARY KEY,
+                source_chain_id TEXT NOT NULL,
+                destination_chain_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                -- 0x + 32 byte hash
+                tx_hash CHAR(66) NOT NULL,
+                proof_bytes BIGINT NOT NULL,
+                calldata_bytes BIGINT NOT NULL,
+                -- gas and fee of the tx, attributed to the datagram by its share of the calldata
+                gas_used BIGINT NOT NULL,
+                fee NUMERIC NOT NULL,
+                created_at timestamptz NOT NULL DEFAULT now()
+              );
+
+            CREATE INDEX IF NOT EXISTS proof_stats_chains_created_at_idx ON proof_stats(destination_chain_id, source_chain_id, created_at);
+            "#,
+        )
+        .try_for_each(|result| async move {
+            trace!("rows affected: {}", result.rows_affected());
+            Ok(())
+        })
+        .instrument(info_span!("db_init"))
+        .await?;
+
+        Ok(Self {
+            db,
+            source_chain_ids: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Record the datagrams of `tx`, submitted on `chain_id`. Datagrams without a proof are
+    /// skipped.
+    pub async fn record(
+        &self,
+        voyager_client: &VoyagerClient,
+        chain_id: &ChainId,
+        tx: SubmittedTx,
+    ) -> anyhow::Result<()> {
+        let total_calldata_bytes = tx.datagrams.iter().map(|(_, len)| len).sum::<usize>();
+        let fee = tx.fee;
+
+        let mut rows = vec![];
+
+        for (datagram, calldata_bytes) in &tx.datagrams {
+            let Some((client_ref, proof_bytes)) = proof_of(datagram) else {
+                continue;
+            };
+
+            let source_chain_id = self
+                .source_chain_id(voyager_client, chain_id, client_ref)
+                .await?;
+
+            rows.push((
+                source_chain_id,
+                datagram.name(),
+                proof_bytes,
+                *calldata_bytes,
+                attribute(tx.gas_used.into(), *calldata_bytes, total_calldata_bytes),
+                attribute(fee, *calldata_bytes, total_calldata_bytes),
+            ));
+        }
+
+        let mut db_tx = self.db.begin().await?;
+
+        for (source_chain_id, kind, proof_bytes, calldata_bytes, gas_used, fee) in rows {
+            sqlx::query(
+                r#"
+                INSERT INTO
+                    proof_stats (source_chain_id, destination_chain_id, kind, tx_hash, proof_bytes, calldata_bytes, gas_used, fee)
+                VALUES
+                    ($1, $2, $3, $4, $5, $6, $7, $8::NUMERIC)
+                "#,
+            )
+            .bind(source_chain_id.to_string())
+            .bind(chain_id.to_string())
+            .bind(kind)
+            .bind(tx.tx_hash.to_string())
+            .bind(proof_bytes as i64)
+            .bind(calldata_bytes as i64)
+            .bind(gas_used as i64)
+            .bind(fee.to_string())
+            .execute(&mut *db_tx)
+            .await?;
+        }
+
+        db_tx.commit().await?;
+
+        debug!(tx_hash = %tx.tx_hash, "recorded proof stats");
+
+        Ok(())
+    }
+
+    /// Aggregate the stats of the datagrams submitted on `chain_id`, optionally only from
+    /// `source_chain_id` and within the last `since_seconds` seconds.
+    pub async fn aggregate(
+        &self,
+        chain_id: &ChainId,
+        source_chain_id: Option<&ChainId>,
+        since_seconds: Option<i64>,
+    ) -> Result<Vec<ProofStats>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            SELECT
+                source_chain_id,
+                destination_chain_id,
+                kind,
+                count(*) AS count,
+                avg(proof_bytes)::FLOAT8 AS avg_proof_bytes,
+                max(proof_bytes) AS max_proof_bytes,
+                avg(calldata_bytes)::FLOAT8 AS avg_calldata_bytes,
+                avg(gas_used)::FLOAT8 AS avg_gas_used,
+                sum(fee)::TEXT AS total_fee
+            FROM
+                proof_stats
+            WHERE
+                destination_chain_id = $1
+                AND ($2::TEXT IS NULL OR source_chain_id = $2)
+                AND ($3::BIGINT IS NULL OR created_at >= now() - $3 * interval '1 second')
+            GROUP BY
+                source_chain_id, destination_chain_id, kind
+            ORDER BY
+                source_chain_id, kind
+            "#,
+        )
+        .bind(chain_id.to_string())
+        .bind(source_chain_id.map(|source_chain_id| source_chain_id.to_string()))
+        .bind(since_seconds)
+        .map(|row| ProofStats {
+            source_chain_id: ChainId::new(row.get::<String, _>("source_chain_id")),
+            destination_chain_id: ChainId::new(row.get::<String, _>("destination_chain_id")),
+            kind: row.get("kind"),
+            count: row.get("count"),
+            avg_proof_bytes: row.get("avg_proof_bytes"),
+            max_proof_bytes: row.get("max_proof_bytes"),
+            avg_calldata_bytes: row.get("avg_calldata_bytes"),
+            avg_gas_used: row.get("avg_gas_used"),
+            total_fee: row.get("total_fee"),
+        })
+        .fetch_all(&self.db)
+        .await
+    }
+
+    async fn source_chain_id(
+        &self,
+        voyager_client: &VoyagerClient,
+        chain_id: &ChainId,
+        client_ref: ClientRef,
+    ) -> RpcResult<ChainId> {
+        if let Some(source_chain_id) = self.source_chain_ids.lock().unwrap().get(&client_ref) {
+            return Ok(source_chain_id.clone());
+        }
+
+        let client_id = match client_ref {
+            ClientRef::Client(client_id) => client_id,
+            ClientRef::Connection(connection_id) => {
+                connection_client_id(voyager_client, chain_id, connection_id).await?
+            }
+            ClientRef::Channel(channel_id) => {
+                let channel = voyager_client
+                    .query_ibc_state(
+                        chain_id.clone(),
+                        QueryHeight::Latest,
+                        ibc_union_spec::path::ChannelPath { channel_id },
+                    )
+                    .await?;
+
+                connection_client_id(voyager_client, chain_id, channel.connection_id).await?
+            }
+        };
+
+        let source_chain_id = voyager_client
+            .client_state_meta::<IbcUnion>(chain_id.clone(), QueryHeight::Latest, client_id)
+            .await?
+            .counterparty_chain_id;
+
+        self.source_chain_ids
+            .lock()
+            .unwrap()
+            .insert(client_ref, source_chain_id.clone());
+
+        Ok(source_chain_id)
+    }
+}
+
+async fn connection_client_id(
+    voyager_client: &VoyagerClient,
+    chain_id: &ChainId,
+    connection_id: ConnectionId,
+) -> RpcResult<ClientId> {
+    Ok(voyager_client
+        .query_ibc_state(
+            chain_id.clone(),
+            QueryHeight::Latest,
+            ibc_union_spec::path::ConnectionPath { connection_id },
+        )
+        .await?
+        .client_id)
+}
+
+/// The client, connection or channel on the destination chain that the proof of `datagram` is
+/// verified against, and the size of the proof (or header, for client updates) in bytes.
+fn proof_of(datagram: &Datagram) -> Option<(ClientRef, usize)> {
+    match datagram {
+        Datagram::UpdateClient(msg) => {
+            Some((ClientRef::Client(msg.client_id), msg.client_message.len()))
+        }
+        Datagram::ConnectionOpenTry(msg) => {
+            Some((ClientRef::Client(msg.client_id), msg.proof_init.len()))
+        }
+        Datagram::ConnectionOpenAck(msg) => Some((
+            ClientRef::Connection(msg.connection_id),
+            msg.proof_try.len(),
+        )),
+        Datagram::ConnectionOpenConfirm(msg) => Some((
+            ClientRef::Connection(msg.connection_id),
+            msg.proof_ack.len(),
+        )),
+        Datagram::ChannelOpenTry(msg) => Some((
+            ClientRef::Connection(msg.channel.connection_id),
+            msg.proof_init.len(),
+        )),
+        Datagram::ChannelOpenAck(msg) => {
+            Some((ClientRef::Channel(msg.channel_id), msg.proof_try.len()))
+        }
+        Datagram::ChannelOpenConfirm(msg) => {
+            Some((ClientRef::Channel(msg.channel_id), msg.proof_ack.len()))
+        }
+        Datagram::PacketRecv(msg) => Some((
+            ClientRef::Channel(msg.packets.first()?.destination_channel_id),
+            msg.proof.len(),
+        )),
+        Datagram::PacketAcknowledgement(msg) => Some((
+            ClientRef::Channel(msg.packets.first()?.source_channel_id),
+            msg.proof.len(),
+        )),
+        Datagram::PacketTimeout(msg) => Some((
+            ClientRef::Channel(msg.packet.source_channel_id),
+            msg.proof.len(),
+        )),
+        Datagram::CreateClient(_)
+        | Datagram::ConnectionOpenInit(_)
+        | Datagram::ChannelOpenInit(_)
+        | Datagram::ChannelCloseInit(_)
+        | Datagram::ChannelCloseConfirm(_)
+        | Datagram::IntentPacketRecv(_)
+        | Datagram::BatchSend(_)
+        | Datagram::BatchAcks(_) => None,
+    }
+}
+
+/// The share of `total` attributed to `part` out of `whole`.
+fn attribute(total: u128, part: usize, whole: usize) -> u128 {
+    if whole == 0 {
+        return 0;
+    }
+
+    total * part as u128 / whole as u128
+}
+
+#[cfg(test)]
+mod tests {
+    use ibc_union_spec::{
+        datagram::{MsgPacketRecv, MsgUpdateClient},
+        Packet, Timestamp,
+    };
+
+    use super::*;
+
+    #[test]
+    fn proof_of_datagrams() {
+        let client_id = ClientId::new(1.try_into().unwrap());
+        let channel_id = ChannelId::new(2.try_into().unwrap());
+
+        assert_eq!(
+            proof_of(&Datagram::UpdateClient(MsgUpdateClient {
+                client_id,
+                client_message: vec![0; 100].into(),
+            })),
+            Some((ClientRef::Client(client_id), 100))
+        );
+
+        assert_eq!(
+            proof_of(&Datagram::PacketRecv(MsgPacketRecv {
+                packets: vec![Packet {
+                    source_channel_id: ChannelId::new(1.try_into().unwrap()),
+                    destination_channel_id: channel_id,
+                    data: Default::default(),
+                    timeout_height: 0,
+                    timeout_timestamp: Timestamp::ZERO,
+                }],
+                relayer_msgs: vec![],
+                proof: vec![0; 32].into(),
+                proof_height: 1,
+            })),
+            Some((ClientRef::Channel(channel_id), 32))
+        );
+
+        assert_eq!(
+            proof_of(&Datagram::PacketRecv(MsgPacketRecv {
+                packets: vec![],
+                relayer_msgs: vec![],
+                proof: vec![0; 32].into(),
+                proof_height: 1,
+            })),
+            None
+        );
+    }
+
+    #[test]
+    fn attribute_by_calldata_share() {
+        assert_eq!(attribute(1000, 250, 1000), 250);
+        assert_eq!(attribute(1000, 1, 3), 333);
+        assert_eq!(attribute(1000, 0, 0), 0);
+    }
+}
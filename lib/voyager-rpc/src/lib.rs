@@ -27,7 +27,8 @@ use voyager_types::{ProofType, RawClientId};
 use voyager_vm::{pass::PassResult, Op, QueueError};
 
 use crate::types::{
-    IbcProofResponse, IbcStateResponse, InfoResponse, SelfClientStateResponse,
+    BackpressureStatus, IbcProofResponse, IbcStateResponse, InfoResponse, ModuleHealth,
+    ModuleHealthStatus, ProofVerification, SelfClientMeta, SelfClientStateResponse,
     SelfConsensusStateResponse,
 };
 
@@ -57,6 +58,29 @@ pub const UNPROCESSABLE_JSONRPC_ERROR_CODE: i32 = -0xDEADC0D; // 🐟
 /// requeued and retried.
 pub const MISSING_STATE_ERROR_CODE: i32 = -0xBADB10B;
 
+/// Error code for failed or unexpected responses from an upstream (such as the rpc of a chain). If
+/// a plugin or module responds with this error code, it will be retried.
+pub const RETRYABLE_UPSTREAM_ERROR_CODE: i32 = -0xCA11BAC;
+
+/// Error code for heights that are invalid or not available (such as pruned heights). If a plugin
+/// or module responds with this error code, it will be treated as fatal and not retried.
+pub const INVALID_HEIGHT_ERROR_CODE: i32 = -0xBAD4E16;
+
+/// Error code for configs (of the plugin or module, or passed along with the request) that are
+/// invalid or not supported. If a plugin or module responds with this error code, it will be
+/// treated as fatal and not retried.
+pub const UNSUPPORTED_CONFIG_ERROR_CODE: i32 = -0xBADC0F6;
+
+/// Error code for internal errors of a plugin or module, which indicate a bug. If a plugin or
+/// module responds with this error code, it will be treated as fatal and not retried.
+pub const INTERNAL_ERROR_CODE: i32 = -0xDEFEC7;
+
+/// Error code for client modules that do not support proof verification (see
+/// [`ClientModuleServer::verify_proof`]), either at all or for their IBC interface. Callers that
+/// verify proofs as an extra check should skip the verification when a module responds with this
+/// error code. If it is returned from a message, it will be treated as fatal and not retried.
+pub const PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE: i32 = -0xBADBF00;
+
 /// Convert a [`jsonrpsee::core::client::Error`] to a `voyager-vm` [`QueueError`].
 ///
 /// All errors are treated as retryable, unless `error` is a `Call` variant and the contained
@@ -106,6 +130,9 @@ pub fn rpc_error<E: Error>(
 ///
 /// - [`FATAL_JSONRPC_ERROR_CODE`]: Custom error code that can be returned by plugins and modules to
 ///   denote that a fatal error has occurred, and this message is not retryable.
+/// - [`INVALID_HEIGHT_ERROR_CODE`], [`UNSUPPORTED_CONFIG_ERROR_CODE`], [`INTERNAL_ERROR_CODE`] or
+///   [`PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE`]: Custom error codes that can be returned by
+///   plugins and modules for errors that will not be resolved by retrying the message.
 /// - [`METHOD_NOT_FOUND_CODE`]: The plugin or module does not expose the method that was attempted
 ///   to be called. This indicates a bug in the plugin or module.
 /// - [`PARSE_ERROR_CODE`] or [`INVALID_PARAMS_CODE`]: The custom message sent to the plugin or
@@ -118,8 +145,15 @@ pub fn rpc_error<E: Error>(
 ///
 /// - [`UNPROCESSABLE_JSONRPC_ERROR_CODE`]: Custom error code that can be returned by plugins and
 ///   modules to denote that a message cannot be processed.
+///
+/// All other error codes (including [`RETRYABLE_UPSTREAM_ERROR_CODE`] and
+/// [`MISSING_STATE_ERROR_CODE`]) are treated as retryable.
 pub fn error_object_to_queue_error(error: ErrorObject<'_>) -> QueueError {
     if error.code() == FATAL_JSONRPC_ERROR_CODE
+        || error.code() == INVALID_HEIGHT_ERROR_CODE
+        || error.code() == UNSUPPORTED_CONFIG_ERROR_CODE
+        || error.code() == INTERNAL_ERROR_CODE
+        || error.code() == PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE
         || error.code() == METHOD_NOT_FOUND_CODE
         || error.code() == INVALID_PARAMS_CODE
         || error.code() == PARSE_ERROR_CODE
@@ -147,6 +181,24 @@ pub trait VoyagerRpc {
     #[method(name = "equivalentChainIds", with_extensions)]
     async fn equivalent_chain_ids(&self, chain_id: ChainId) -> RpcResult<Vec<ChainId>>;
 
+    /// Whether the event source of this chain should pause ingesting new events, due to the
+    /// queue being saturated with pending operations for this chain.
+    #[method(name = "backpressure", with_extensions)]
+    async fn backpressure(&self, chain_id: ChainId) -> RpcResult<BackpressureStatus>;
+
+    /// The time (in seconds) that client updates of clients on this chain are held back for, if
+    /// configured.
+    #[method(name = "finalityDelay", with_extensions)]
+    async fn finality_delay(&self, chain_id: ChainId) -> RpcResult<Option<u64>>;
+
+    /// The health of all modules, as of the last poll.
+    #[method(name = "moduleHealth", with_extensions)]
+    async fn module_health(&self) -> RpcResult<Vec<ModuleHealthStatus>>;
+
+    /// The config this voyager instance was started with.
+    #[method(name = "config", with_extensions)]
+    async fn config(&self) -> RpcResult<Value>;
+
     // =========
     // consensus
     // =========
@@ -240,6 +292,14 @@ pub trait VoyagerRpc {
         config: Value,
     ) -> RpcResult<SelfConsensusStateResponse>;
 
+    #[method(name = "selfClientMeta", with_extensions)]
+    async fn self_client_meta(
+        &self,
+        chain_id: ChainId,
+        client_type: ClientType,
+        height: QueryHeight,
+    ) -> RpcResult<SelfClientMeta>;
+
     // ======================
     // state and proof codecs
     // ======================
@@ -253,6 +313,15 @@ pub trait VoyagerRpc {
         proof: Value,
     ) -> RpcResult<Bytes>;
 
+    #[method(name = "verifyProof", with_extensions)]
+    async fn verify_proof(
+        &self,
+        client_type: ClientType,
+        ibc_interface: IbcInterface,
+        ibc_spec_id: IbcSpecId,
+        verification: ProofVerification,
+    ) -> RpcResult<()>;
+
     #[method(name = "encodeHeader", with_extensions)]
     async fn encode_header(
         &self,
@@ -370,6 +439,10 @@ pub trait StateModule<V: IbcSpec> {
     /// Fetch the client info of a client on this chain.
     #[method(name = "clientInfo", with_extensions)]
     async fn client_info(&self, client_id: V::ClientId) -> RpcResult<ClientInfo>;
+
+    /// The health of this module, including whether its upstream is reachable.
+    #[method(name = "health", with_extensions)]
+    async fn health(&self) -> RpcResult<ModuleHealth>;
 }
 
 /// Type-erased version of [`StateModuleClient`].
@@ -383,6 +456,9 @@ pub trait RawStateModule {
 
     #[method(name = "clientInfo", with_extensions)]
     async fn client_info_raw(&self, client_id: RawClientId) -> RpcResult<Option<ClientInfo>>;
+
+    #[method(name = "health", with_extensions)]
+    async fn health_raw(&self) -> RpcResult<ModuleHealth>;
 }
 
 #[rpc(client,
@@ -400,6 +476,10 @@ pub trait ProofModule<V: IbcSpec> {
         at: Height,
         path: V::StorePath,
     ) -> RpcResult<Option<(Value, ProofType)>>;
+
+    /// The health of this module, including whether its upstream is reachable.
+    #[method(name = "health", with_extensions)]
+    async fn health(&self) -> RpcResult<ModuleHealth>;
 }
 
 /// Type-erased version of [`ProofModuleClient`].
@@ -411,6 +491,9 @@ pub trait RawProofModule {
         at: Height,
         path: Value,
     ) -> RpcResult<Option<(Value, ProofType)>>;
+
+    #[method(name = "health")]
+    async fn health_raw(&self) -> RpcResult<ModuleHealth>;
 }
 
 /// Client modules provide functionality to interact with a single light client
@@ -456,6 +539,25 @@ pub trait ClientModule {
     /// Encode the proof, provided as JSON.
     #[method(name = "encodeProof", with_extensions)]
     async fn encode_proof(&self, proof: Value) -> RpcResult<Bytes>;
+
+    /// The health of this module, including whether its upstream is reachable.
+    #[method(name = "health", with_extensions)]
+    async fn health(&self) -> RpcResult<ModuleHealth>;
+
+    /// Verify a proof against the client and consensus states it will be verified against on
+    /// chain. This is the same check that the light client performs, and allows for catching
+    /// invalid proofs before they are submitted.
+    ///
+    /// The default implementation returns an error with
+    /// [`PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE`].
+    #[method(name = "verifyProof", with_extensions)]
+    async fn verify_proof(&self, _verification: ProofVerification) -> RpcResult<()> {
+        Err(ErrorObject::owned(
+            PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE,
+            "proof verification is not supported by this client module",
+            None::<()>,
+        ))
+    }
 }
 
 /// Client modules provide functionality for interacting with a specific chain
@@ -469,6 +571,10 @@ pub trait FinalityModule {
     /// Query the latest finalized timestamp of this chain.
     #[method(name = "queryLatestTimestamp", with_extensions)]
     async fn query_latest_timestamp(&self, finalized: bool) -> RpcResult<Timestamp>;
+
+    /// The health of this module, including whether its upstream is reachable.
+    #[method(name = "health", with_extensions)]
+    async fn health(&self) -> RpcResult<ModuleHealth>;
 }
 
 /// Client bootstrap modules provide the initial client and consensus states for a client. This is
@@ -493,4 +599,21 @@ pub trait ClientBootstrapModule {
     /// This also accepts a config value as arbitrary json that can be used for client-specific configuration parameters.
     #[method(name = "selfConsensusState", with_extensions)]
     async fn self_consensus_state(&self, height: Height, config: Value) -> RpcResult<Value>;
+
+    /// The health of this module, including whether its upstream is reachable.
+    #[method(name = "health", with_extensions)]
+    async fn health(&self) -> RpcResult<ModuleHealth>;
+
+    /// Metadata about this chain at the specified [`Height`], used to sanity check configs before
+    /// creating a client.
+    ///
+    /// The default implementation returns an error.
+    #[method(name = "selfClientMeta", with_extensions)]
+    async fn self_client_meta(&self, _height: Height) -> RpcResult<SelfClientMeta> {
+        Err(ErrorObject::owned(
+            FATAL_JSONRPC_ERROR_CODE,
+            "unimplemented",
+            None::<()>,
+        ))
+    }
 }
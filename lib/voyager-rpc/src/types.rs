@@ -1,9 +1,11 @@
+use std::fmt::Display;
+
 use jsonrpsee::{core::RpcResult, types::ErrorObject};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use unionlabs::ibc::core::client::height::Height;
-use voyager_primitives::{ChainId, ClientType, ConsensusType, IbcInterface, IbcSpecId};
+use unionlabs::{ibc::core::client::height::Height, primitives::Bytes};
+use voyager_primitives::{ChainId, ClientType, ConsensusType, Duration, IbcInterface, IbcSpecId};
 use voyager_types::IbcProof;
 
 use crate::MISSING_STATE_ERROR_CODE;
@@ -334,6 +336,143 @@ pub struct SelfConsensusStateResponse {
     pub state: Value,
 }
 
+/// Metadata about a chain, as seen by a client bootstrap module. This is used to sanity check
+/// configs before creating a client, without having to construct the full client state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SelfClientMeta {
+    /// The revision of the chain, if the chain has a concept of revisions.
+    pub chain_revision: Option<u64>,
+    /// The latest height of the chain.
+    pub latest_height: Height,
+    /// The unbonding period of the chain, if it has one.
+    pub unbonding_period: Option<Duration>,
+    /// Chain-specific consensus parameters.
+    pub consensus_params: Value,
+    /// The type of the chain as detected by the module, if the module supports multiple chain
+    /// types.
+    pub chain_type: Option<String>,
+}
+
+/// The health of a module, as reported by its `health` method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ModuleHealth {
+    /// Whether the upstream of the module (i.e. the rpc endpoint of the chain) is reachable.
+    /// Modules without an upstream, such as client modules, are always connected.
+    pub connected: bool,
+    /// The latest height of the chain as queried from the upstream of the module, if it has one
+    /// and it is reachable.
+    pub latest_height: Option<Height>,
+    /// The error returned by the upstream, if it is not reachable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub version: ModuleVersion,
+}
+
+impl ModuleHealth {
+    /// The health of a module that does not have an upstream.
+    #[must_use]
+    pub fn without_upstream(version: ModuleVersion) -> Self {
+        Self {
+            connected: true,
+            latest_height: None,
+            error: None,
+            version,
+        }
+    }
+
+    /// The health of a module, given the result of querying the latest height from its upstream.
+    #[must_use]
+    pub fn from_latest_height(
+        version: ModuleVersion,
+        latest_height: Result<Height, impl Display>,
+    ) -> Self {
+        match latest_height {
+            Ok(latest_height) => Self {
+                connected: true,
+                latest_height: Some(latest_height),
+                error: None,
+                version,
+            },
+            Err(error) => Self {
+                connected: false,
+                latest_height: None,
+                error: Some(error.to_string()),
+                version,
+            },
+        }
+    }
+}
+
+/// The version of a module binary. See `voyager_sdk::module_version!`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ModuleVersion {
+    /// The name of the crate of the module.
+    pub name: String,
+    /// The version of the crate of the module.
+    pub version: String,
+    /// The git revision the module was built from, or `unknown`/`dirty`.
+    pub git_rev: String,
+}
+
+/// The health of a module as of the last poll by voyager.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ModuleHealthStatus {
+    /// The id of the module, i.e. `state/ibc-union/union-1`.
+    pub id: String,
+    /// The health as reported by the module, or `None` if the module did not respond.
+    pub health: Option<ModuleHealth>,
+    /// The error returned when polling the module, if it did not respond.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// When the module was last polled, in unix seconds.
+    pub checked_at: u64,
+}
+
+impl ModuleHealthStatus {
+    /// Whether the module responded and its upstream is reachable.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.health.as_ref().is_some_and(|health| health.connected)
+    }
+}
+
+/// Backpressure signal for the event source of a chain. While `paused` is set, event sources
+/// should stop scanning for new blocks until the queue has drained.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BackpressureStatus {
+    pub paused: bool,
+    /// The number of pending operations for the chain as of the last sample, if backpressure is
+    /// configured for the chain.
+    pub pending: Option<u64>,
+}
+
+impl BackpressureStatus {
+    /// How long a paused event source waits before checking again whether event ingestion can be
+    /// resumed, in seconds.
+    pub const RETRY_DELAY: u64 = 10;
+}
+
+/// A proof of a commitment on a chain, together with the states of the light client that will
+/// verify it on the counterparty chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProofVerification {
+    /// The raw client state of the light client.
+    pub client_state: Bytes,
+    /// The raw consensus state of the light client at the proof height.
+    pub consensus_state: Bytes,
+    /// The commitment key.
+    pub key: Bytes,
+    /// The committed value, or `None` if the proof is a proof of absence.
+    pub value: Option<Bytes>,
+    /// The proof, as returned by the proof module of the chain.
+    pub proof: Value,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum IbcProofResponse {
     Proof(IbcProof),
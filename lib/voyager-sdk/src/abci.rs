@@ -0,0 +1,214 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use cometbft_rpc::{rpc_types::GrpcAbciQueryResponse, JsonRpcError};
+use moka::{future::Cache, policy::EvictionPolicy, Expiry};
+use opentelemetry::KeyValue;
+use tracing::trace;
+use unionlabs::{bounded::BoundedI64, prost::Message, ErrorReporter};
+use voyager_plugin::metrics::UpstreamResultExt;
+
+/// The maximum amount of cached responses.
+const CAPACITY: u64 = 1024;
+
+/// How responses to queries to a path are cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CachePolicy {
+    /// Cache responses per queried height, including queries at the latest height, for queries
+    /// whose result rarely changes (such as module params).
+    Ttl(Duration),
+    /// Cache responses per queried height, for queries of state that is immutable at a given
+    /// height. Queries at the latest height are not cached.
+    PerHeight(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct AbciQueryKey {
+    path: String,
+    data: Vec<u8>,
+    /// The queried height, `None` for queries at the latest height.
+    height: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    ttl: Duration,
+    response: cometbft_rpc::rpc_types::AbciQueryResponse,
+}
+
+struct PerEntryExpiry;
+
+impl Expiry<AbciQueryKey, CachedResponse> for PerEntryExpiry {
+    fn expire_after_create(
+        &self,
+        _: &AbciQueryKey,
+        value: &CachedResponse,
+        _: Instant,
+    ) -> Option<Duration> {
+        Some(value.ttl)
+    }
+}
+
+/// Caches the responses of grpc abci queries, with a ttl configured per query path.
+///
+/// Queries to paths without a configured ttl, proven queries and failed queries are never cached.
+/// Failed queries are recorded in the `upstream.errors` metric.
+///
+/// ```rust,ignore
+/// let cached_abci_query = CachedAbciQuery::new(cometbft_client)
+///     .with_ttl("/cosmos.staking.v1beta1.Query/Params", Duration::from_secs(60 * 60));
+///
+/// let params = cached_abci_query
+///     .grpc_abci_query::<_, QueryParamsResponse>(
+///         "/cosmos.staking.v1beta1.Query/Params",
+///         &QueryParamsRequest {},
+///         None,
+///         false,
+///     )
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct CachedAbciQuery {
+    client: cometbft_rpc::Client,
+    policies: Arc<HashMap<String, CachePolicy>>,
+    cache: Cache<AbciQueryKey, CachedResponse>,
+    size_metric: opentelemetry::metrics::Gauge<u64>,
+    hit_counter_metric: opentelemetry::metrics::Counter<u64>,
+    miss_counter_metric: opentelemetry::metrics::Counter<u64>,
+}
+
+impl CachedAbciQuery {
+    #[must_use]
+    pub fn new(client: cometbft_rpc::Client) -> Self {
+        Self {
+            client,
+            policies: Arc::new(HashMap::new()),
+            cache: moka::future::CacheBuilder::new(CAPACITY)
+                .expire_after(PerEntryExpiry)
+                .eviction_policy(EvictionPolicy::lru())
+                .build(),
+            size_metric: opentelemetry::global::meter("voyager")
+                .u64_gauge("cache.abci_query.size")
+                .build(),
+            hit_counter_metric: opentelemetry::global::meter("voyager")
+                .u64_counter("cache.abci_query.hit")
+                .build(),
+            miss_counter_metric: opentelemetry::global::meter("voyager")
+                .u64_counter("cache.abci_query.miss")
+                .build(),
+        }
+    }
+
+    /// Cache responses to queries to `path` for `ttl`, per queried height. Responses at the latest
+    /// height are cached as well, and may be up to `ttl` old.
+    ///
+    /// Only use this for queries whose result rarely changes, such as module params.
+    #[must_use]
+    pub fn with_ttl(self, path: impl Into<String>, ttl: Duration) -> Self {
+        self.with_policy(path, CachePolicy::Ttl(ttl))
+    }
+
+    /// Cache responses to queries to `path` at a specific height for `ttl`.
+    #[must_use]
+    pub fn with_height_ttl(self, path: impl Into<String>, ttl: Duration) -> Self {
+        self.with_policy(path, CachePolicy::PerHeight(ttl))
+    }
+
+    fn with_policy(mut self, path: impl Into<String>, policy: CachePolicy) -> Self {
+        Arc::make_mut(&mut self.policies).insert(path.into(), policy);
+        self
+    }
+
+    /// Drop all cached responses. Modules call this on shutdown, see
+    /// [`on_shutdown`](crate::plugin::protocol::on_shutdown).
+    pub async fn flush(&self) {
+        self.cache.invalidate_all();
+        self.cache.run_pending_tasks().await;
+
+        self.size_metric.record(self.cache.entry_count(), &[]);
+    }
+
+    /// The underlying client, for uncached queries.
+    #[must_use]
+    pub fn client(&self) -> &cometbft_rpc::Client {
+        &self.client
+    }
+
+    /// Same as [`cometbft_rpc::Client::grpc_abci_query`], serving the response from the cache if
+    /// possible.
+    pub async fn grpc_abci_query<Q: Message, R: Message + Default>(
+        &self,
+        path: &str,
+        data: &Q,
+        height: Option<BoundedI64<1>>,
+        prove: bool,
+    ) -> Result<GrpcAbciQueryResponse<R>, JsonRpcError> {
+        let key_height = match (self.policies.get(path).copied(), prove) {
+            (Some(CachePolicy::Ttl(ttl)), false) => {
+                Some((ttl, height.map(|height| height.inner())))
+            }
+            (Some(CachePolicy::PerHeight(ttl)), false) => {
+                height.map(|height| (ttl, Some(height.inner())))
+            }
+            _ => None,
+        };
+
+        let Some((ttl, height_key)) = key_height else {
+            return self
+                .client
+                .grpc_abci_query(path, data, height, prove)
+                .await
+                .record_upstream_error("cometbft", "abci_query");
+        };
+
+        let attributes = &[KeyValue::new("path", path.to_owned())];
+
+        self.size_metric.record(self.cache.entry_count(), &[]);
+
+        let key = AbciQueryKey {
+            path: path.to_owned(),
+            data: data.encode_to_vec(),
+            height: height_key,
+        };
+
+        let response = match self.cache.get(&key).await {
+            Some(cached) => {
+                self.hit_counter_metric.add(1, attributes);
+                trace!(%path, "abci query cache hit");
+
+                cached.response
+            }
+            None => {
+                self.miss_counter_metric.add(1, attributes);
+                trace!(%path, "abci query cache miss");
+
+                let response = self
+                    .client
+                    .abci_query(path, &key.data, height, prove)
+                    .await
+                    .record_upstream_error("cometbft", "abci_query")?;
+
+                if response.response.code.is_ok() {
+                    self.cache
+                        .insert(
+                            key,
+                            CachedResponse {
+                                ttl,
+                                response: response.clone(),
+                            },
+                        )
+                        .await;
+                }
+
+                response
+            }
+        };
+
+        response
+            .decode_grpc()
+            .map_err(|e| JsonRpcError::Custom(ErrorReporter(e).to_string()))
+    }
+}
@@ -0,0 +1,54 @@
+//! Well-known JSON-RPC error codes for plugins and modules, and helpers to construct errors with
+//! them.
+//!
+//! Voyager decides whether a failed call is retried based on the code of the returned error (see
+//! [`error_object_to_queue_error`](voyager_rpc::error_object_to_queue_error)), so plugins and
+//! modules should return one of these codes instead of an ad-hoc one:
+//!
+//! | code                                          | retried |
+//! | --------------------------------------------- | ------- |
+//! | [`RETRYABLE_UPSTREAM_ERROR_CODE`]             | yes     |
+//! | [`MISSING_STATE_ERROR_CODE`]                  | yes     |
+//! | [`INVALID_HEIGHT_ERROR_CODE`]                 | no      |
+//! | [`UNSUPPORTED_CONFIG_ERROR_CODE`]             | no      |
+//! | [`INTERNAL_ERROR_CODE`]                       | no      |
+//! | [`PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE`] | no      |
+//! | [`FATAL_JSONRPC_ERROR_CODE`]                  | no      |
+
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use serde_json::Value;
+#[doc(no_inline)]
+pub use voyager_rpc::{
+    missing_state, FATAL_JSONRPC_ERROR_CODE, INTERNAL_ERROR_CODE, INVALID_HEIGHT_ERROR_CODE,
+    MISSING_STATE_ERROR_CODE, PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE,
+    RETRYABLE_UPSTREAM_ERROR_CODE, UNPROCESSABLE_JSONRPC_ERROR_CODE, UNSUPPORTED_CONFIG_ERROR_CODE,
+};
+
+/// An upstream (such as the rpc of a chain) failed or returned an unexpected response.
+pub fn retryable_upstream(message: impl Into<String>, data: Option<Value>) -> ErrorObjectOwned {
+    ErrorObject::owned(RETRYABLE_UPSTREAM_ERROR_CODE, message, data)
+}
+
+/// The requested height is invalid or not available.
+pub fn invalid_height(message: impl Into<String>, data: Option<Value>) -> ErrorObjectOwned {
+    ErrorObject::owned(INVALID_HEIGHT_ERROR_CODE, message, data)
+}
+
+/// A config (of the plugin or module, or passed along with the request) is invalid or not
+/// supported.
+pub fn unsupported_config(message: impl Into<String>, data: Option<Value>) -> ErrorObjectOwned {
+    ErrorObject::owned(UNSUPPORTED_CONFIG_ERROR_CODE, message, data)
+}
+
+/// The client module does not support proof verification, either at all or for its IBC interface.
+pub fn proof_verification_unsupported(
+    message: impl Into<String>,
+    data: Option<Value>,
+) -> ErrorObjectOwned {
+    ErrorObject::owned(PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE, message, data)
+}
+
+/// An internal error of the plugin or module, which indicates a bug.
+pub fn internal(message: impl Into<String>, data: Option<Value>) -> ErrorObjectOwned {
+    ErrorObject::owned(INTERNAL_ERROR_CODE, message, data)
+}
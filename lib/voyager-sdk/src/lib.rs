@@ -1,7 +1,12 @@
+#[cfg(feature = "cometbft")]
+pub mod abci;
+pub mod error;
 pub mod hook;
 
 use std::fmt::Debug;
 
+#[doc(hidden)]
+pub use embed_commit;
 use jsonrpsee::{async_client, core::RpcResult, types::ErrorObject, Extensions};
 use serde::Serialize;
 use serde_json::Value;
@@ -35,6 +40,19 @@ pub fn into_value<T: Debug + Serialize>(t: T) -> Value {
     }
 }
 
+/// The [`ModuleVersion`](voyager_rpc::types::ModuleVersion) of the calling crate, as reported by
+/// the `health` method of modules.
+#[macro_export]
+macro_rules! module_version {
+    () => {
+        $crate::rpc::types::ModuleVersion {
+            name: env!("CARGO_PKG_NAME").to_owned(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            git_rev: $crate::embed_commit::GIT_REV.to_string(),
+        }
+    };
+}
+
 pub fn ensure_null(value: Value) -> RpcResult<()> {
     if value == Value::Null {
         Ok(())
@@ -58,10 +76,9 @@ impl ExtensionsExt for Extensions {
     fn voyager_client(&self) -> RpcResult<&VoyagerClient> {
         match self.get() {
             Some(t) => Ok(t),
-            None => Err(ErrorObject::owned(
-                -1,
+            None => Err(error::internal(
                 "failed to retrieve voyager client from extensions",
-                None::<()>,
+                None,
             )),
         }
     }
@@ -294,4 +294,45 @@ impl<T: QueueMessage> Queue<T> for InMemoryQueue<T> {
             Ok(())
         }
     }
+
+    fn pending_count<'a>(
+        &'a self,
+        value: &'a str,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send + 'a {
+        fn contains_string(json: &serde_json::Value, value: &str) -> bool {
+            match json {
+                serde_json::Value::String(string) => string == value,
+                serde_json::Value::Array(array) => {
+                    array.iter().any(|json| contains_string(json, value))
+                }
+                serde_json::Value::Object(object) => {
+                    object.values().any(|json| contains_string(json, value))
+                }
+                _ => false,
+            }
+        }
+
+        let matches = |item: &Item<T>| {
+            serde_json::to_value(&item.op).is_ok_and(|json| contains_string(&json, value))
+        };
+
+        let ready = self
+            .ready
+            .lock()
+            .expect("mutex is poisoned")
+            .values()
+            .filter(|item| matches(item))
+            .count();
+
+        let optimize = self
+            .optimizer_queue
+            .lock()
+            .expect("mutex is poisoned")
+            .values()
+            .flat_map(BTreeMap::values)
+            .filter(|item| matches(item))
+            .count();
+
+        futures::future::ok((ready + optimize) as u64)
+    }
 }
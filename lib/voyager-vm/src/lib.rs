@@ -73,6 +73,15 @@ pub trait Queue<T: QueueMessage>: Debug + Clone + Send + Sync + Sized + 'static
     where
         O: Pass<T>,
         Filter: InterestFilter<T>;
+
+    /// The number of items that are either ready to be processed or waiting to be optimized, and
+    /// whose JSON representation contains the string `value` (anywhere, but as a whole string, not
+    /// as a substring of another string). Items waiting to be optimized under multiple tags are
+    /// counted once per tag.
+    fn pending_count<'a>(
+        &'a self,
+        value: &'a str,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send + 'a;
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
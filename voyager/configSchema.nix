@@ -1,6 +1,18 @@
 { types, mkOption }:
 let
   definitions = {
+    "#/definitions/BackpressureConfig" = types.submodule {
+      options = {
+        "chains" = mkOption {
+          type = types.attrsOf definitions."#/definitions/Thresholds";
+          default = { };
+        };
+        "sample_interval_milliseconds" = mkOption {
+          type = types.int;
+          default = 10000;
+        };
+      };
+    };
     "#/definitions/CacheConfig" = types.submodule {
       options = {
         "capacity" = mkOption { type = types.int; };
@@ -39,6 +51,14 @@ let
         "consensus_type" = mkOption { type = types.str; };
       };
     };
+    "#/definitions/HealthConfig" = types.submodule {
+      options = {
+        "poll_interval_milliseconds" = mkOption {
+          type = types.int;
+          default = 30000;
+        };
+      };
+    };
     "#/definitions/ModuleConfig_for_ClientBootstrapModuleInfo" = types.submodule {
       options = {
         "config" = mkOption {
@@ -50,6 +70,10 @@ let
           default = true;
         };
         "info" = mkOption { type = definitions."#/definitions/ClientBootstrapModuleInfo"; };
+        "metrics_laddr" = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+        };
         "path" = mkOption { type = types.str; };
       };
     };
@@ -64,6 +88,10 @@ let
           default = true;
         };
         "info" = mkOption { type = definitions."#/definitions/ClientModuleInfo"; };
+        "metrics_laddr" = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+        };
         "path" = mkOption { type = types.str; };
       };
     };
@@ -78,6 +106,10 @@ let
           default = true;
         };
         "info" = mkOption { type = definitions."#/definitions/FinalityModuleInfo"; };
+        "metrics_laddr" = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+        };
         "path" = mkOption { type = types.str; };
       };
     };
@@ -92,6 +124,10 @@ let
           default = true;
         };
         "info" = mkOption { type = definitions."#/definitions/ProofModuleInfo"; };
+        "metrics_laddr" = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+        };
         "path" = mkOption { type = types.str; };
       };
     };
@@ -106,6 +142,10 @@ let
           default = true;
         };
         "info" = mkOption { type = definitions."#/definitions/StateModuleInfo"; };
+        "metrics_laddr" = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+        };
         "path" = mkOption { type = types.str; };
       };
     };
@@ -135,6 +175,10 @@ let
           type = types.bool;
           default = true;
         };
+        "metrics_laddr" = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+        };
         "path" = mkOption { type = types.str; };
       };
     };
@@ -151,9 +195,28 @@ let
         "ibc_spec_id" = mkOption { type = types.str; };
       };
     };
+    "#/definitions/Thresholds" = types.submodule {
+      options = {
+        "pause_at" = mkOption { type = types.int; };
+        "resume_at" = mkOption { type = types.int; };
+      };
+    };
     "#/definitions/VoyagerConfig" = types.submodule {
       options = {
+        "backpressure" = mkOption {
+          type = definitions."#/definitions/BackpressureConfig";
+          default = {
+            "chains" = { };
+            "sample_interval_milliseconds" = 10000;
+          };
+        };
         "cache" = mkOption { type = definitions."#/definitions/Config"; };
+        "health" = mkOption {
+          type = definitions."#/definitions/HealthConfig";
+          default = {
+            "poll_interval_milliseconds" = 30000;
+          };
+        };
         "ipc_client_request_timeout" = mkOption {
           type = definitions."#/definitions/Duration";
           default = {
@@ -179,6 +242,13 @@ let
           type = types.str;
           default = "0.0.0.0:7178";
         };
+        "shutdown_timeout" = mkOption {
+          type = definitions."#/definitions/Duration";
+          default = {
+            "nanos" = 0;
+            "secs" = 30;
+          };
+        };
       };
     };
   };
@@ -6,19 +6,23 @@ use arbitrum_light_client_types::{ClientState, ClientStateV1, ConsensusState};
 use ibc_union_spec::{ClientId, IbcUnion, Timestamp};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{info, instrument};
 use unionlabs::{ibc::core::client::height::Height, primitives::H160, ErrorReporter};
 use voyager_sdk::{
-    anyhow, into_value,
-    plugin::ClientBootstrapModule,
+    anyhow,
+    error::{retryable_upstream, unsupported_config},
+    into_value, module_version,
+    plugin::{metrics::UpstreamResultExt, ClientBootstrapModule},
     primitives::{ChainId, ClientType, QueryHeight},
     rpc::{
-        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
     },
     ExtensionsExt, VoyagerClient,
 };
@@ -41,7 +45,7 @@ pub struct Module {
     pub provider: DynProvider<AnyNetwork>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub l1_chain_id: ChainId,
@@ -55,6 +59,8 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,8 +93,7 @@ impl Module {
         if self.l1_chain_id == l1_client_state_meta.counterparty_chain_id {
             Ok(())
         } else {
-            Err(ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            Err(unsupported_config(
                 format!(
                     "l1 client {l1_client_id} tracks {l1_counterparty_chain_id}, \
                     but this arbitrum chain ({l2_chain_id}) settles on {l1_chain_id}",
@@ -96,7 +101,7 @@ impl Module {
                     l2_chain_id = self.chain_id,
                     l1_chain_id = self.l1_chain_id
                 ),
-                None::<()>,
+                None,
             ))
         }
     }
@@ -110,8 +115,9 @@ impl ClientBootstrapModule for Module {
             ProviderBuilder::new()
                 .network::<AnyNetwork>()
                 .layer(CacheLayer::new(config.max_cache_size))
-                .connect(&config.rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let l2_chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
@@ -139,10 +145,9 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 ErrorReporter(err).with_message("unable to deserialize client state config"),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -176,10 +181,9 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 ErrorReporter(err).with_message("unable to deserialize client state config"),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -194,11 +198,11 @@ impl ClientBootstrapModuleServer for Module {
             .provider
             .get_block(height.height().into())
             .await
+            .record_upstream_error("eth", "get_block")
             .map_err(|err| {
-                ErrorObject::owned(
-                    FATAL_JSONRPC_ERROR_CODE,
+                retryable_upstream(
                     ErrorReporter(err).with_message("error fetching l2 block"),
-                    None::<()>,
+                    None,
                 )
             })?
             .unwrap();
@@ -217,4 +221,19 @@ impl ClientBootstrapModuleServer for Module {
             timestamp: Timestamp::from_secs(l2_block.header.timestamp),
         }))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .provider
+            .get_block_number()
+            .await
+            .map(Height::new)
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
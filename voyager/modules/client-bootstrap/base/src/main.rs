@@ -7,9 +7,10 @@ use base_light_client_types::{ClientState, ClientStateV1, ConsensusState};
 use ibc_union_spec::{ClientId, Timestamp};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::instrument;
@@ -19,11 +20,14 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
-    plugin::ClientBootstrapModule,
+    anyhow,
+    error::{retryable_upstream, unsupported_config},
+    into_value, module_version,
+    plugin::{metrics::UpstreamResultExt, ClientBootstrapModule},
     primitives::{ChainId, ClientType},
     rpc::{
-        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
     },
 };
 
@@ -46,7 +50,7 @@ pub struct Module {
     pub provider: DynProvider<AnyNetwork>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub dispute_game_factory_address: H160,
@@ -60,6 +64,8 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,8 +84,9 @@ impl ClientBootstrapModule for Module {
             ProviderBuilder::new()
                 .network::<AnyNetwork>()
                 .layer(CacheLayer::new(config.max_cache_size))
-                .connect(&config.rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let l2_chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
@@ -110,13 +117,12 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 format!(
                     "unable to deserialize client state config: {}",
                     ErrorReporter(err)
                 ),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -150,11 +156,11 @@ impl ClientBootstrapModuleServer for Module {
             .provider
             .get_block(height.height().into())
             .await
+            .record_upstream_error("eth", "get_block")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     ErrorReporter(e).with_message("error fetching l2 block"),
-                    None::<()>,
+                    None,
                 )
             })?
             .unwrap();
@@ -172,4 +178,19 @@ impl ClientBootstrapModuleServer for Module {
             timestamp: Timestamp::from_secs(l2_block.header.timestamp),
         }))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .provider
+            .get_block_number()
+            .await
+            .map(Height::new)
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
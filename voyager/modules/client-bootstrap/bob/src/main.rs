@@ -7,9 +7,10 @@ use bob_types::L2_OUTPUTS_SLOT;
 use ibc_union_spec::{ClientId, Timestamp};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::instrument;
@@ -19,11 +20,14 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
-    plugin::ClientBootstrapModule,
+    anyhow,
+    error::{retryable_upstream, unsupported_config},
+    into_value, module_version,
+    plugin::{metrics::UpstreamResultExt, ClientBootstrapModule},
     primitives::{ChainId, ClientType},
     rpc::{
-        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
     },
 };
 
@@ -44,7 +48,7 @@ pub struct Module {
     pub provider: DynProvider<AnyNetwork>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub l2_oracle_address: H160,
@@ -56,6 +60,8 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,8 +80,9 @@ impl ClientBootstrapModule for Module {
             ProviderBuilder::new()
                 .network::<AnyNetwork>()
                 .layer(CacheLayer::new(config.max_cache_size))
-                .connect(&config.rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let l2_chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
@@ -102,13 +109,12 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 format!(
                     "unable to deserialize client state config: {}",
                     ErrorReporter(err)
                 ),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -139,11 +145,11 @@ impl ClientBootstrapModuleServer for Module {
             .provider
             .get_block(height.height().into())
             .await
+            .record_upstream_error("eth", "get_block")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     ErrorReporter(e).with_message("error fetching l2 block"),
-                    None::<()>,
+                    None,
                 )
             })?
             .unwrap();
@@ -161,4 +167,19 @@ impl ClientBootstrapModuleServer for Module {
             timestamp: Timestamp::from_secs(l2_block.header.timestamp),
         }))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .provider
+            .get_block_number()
+            .await
+            .map(Height::new)
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
@@ -3,14 +3,16 @@ use std::{
     num::{NonZeroU64, ParseIntError},
 };
 
+use cometbft_rpc::{rpc_types::CommitResponse, FetchCommitError, HeightNotAvailable};
 use cometbls_light_client_types::{ClientState, ConsensusState};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::{error, instrument};
 use unionlabs::{
     ibc::core::{client::height::Height, commitment::merkle_root::MerkleRoot},
@@ -20,10 +22,13 @@ use unionlabs::{
 };
 use voyager_sdk::{
     anyhow, ensure_null,
-    plugin::ClientBootstrapModule,
+    error::{invalid_height, retryable_upstream},
+    module_version,
+    plugin::{metrics::UpstreamResultExt, ClientBootstrapModule},
     primitives::{ChainId, ClientType, Duration, Timestamp},
     rpc::{
-        json_rpc_error_to_error_object, types::ClientBootstrapModuleInfo,
+        json_rpc_error_to_error_object,
+        types::{ClientBootstrapModuleInfo, ModuleHealth, SelfClientMeta},
         ClientBootstrapModuleServer,
     },
 };
@@ -38,24 +43,28 @@ pub struct Module {
     pub chain_id: ChainId,
 
     pub cometbft_client: cometbft_rpc::Client,
-    pub chain_revision: u64,
+    pub revision_start: Height,
 
     pub ibc_host_contract_address: H256,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ibc_host_contract_address: Option<Bech32<H256>>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl ClientBootstrapModule for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: ClientBootstrapModuleInfo) -> anyhow::Result<Self> {
-        let tm_client = cometbft_rpc::Client::new(config.rpc_url).await?;
+        let tm_client = cometbft_rpc::Client::new(config.rpc_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
 
         let chain_id = tm_client.status().await?.node_info.network.to_string();
 
@@ -78,7 +87,7 @@ impl ClientBootstrapModule for Module {
         Ok(Self {
             cometbft_client: tm_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            revision_start: Height::new_with_revision(chain_revision, 0),
             ibc_host_contract_address: config
                 .ibc_host_contract_address
                 .map(|a| *a.data())
@@ -98,21 +107,41 @@ pub struct ChainIdParseError {
 impl Module {
     #[must_use]
     pub fn make_height(&self, height: u64) -> Height {
-        Height::new_with_revision(self.chain_revision, height)
+        self.revision_start.with_height(height)
     }
-}
 
-#[async_trait]
-impl ClientBootstrapModuleServer for Module {
-    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
-    async fn self_client_state(
-        &self,
-        _: &Extensions,
-        height: Height,
-        config: Value,
-    ) -> RpcResult<Value> {
-        ensure_null(config)?;
+    /// Fetch the commit at `height`, returning a structured error containing the earliest available
+    /// height if the requested height has been pruned by the node.
+    async fn fetch_commit(&self, height: Height) -> RpcResult<CommitResponse> {
+        self.cometbft_client
+            .fetch_commit(height.height())
+            .await
+            .record_upstream_error("cometbft", "commit")
+            .map_err(|e| match e {
+                FetchCommitError::ZeroHeight => invalid_height(
+                    ErrorReporter(e).to_string(),
+                    Some(json!({ "requested_height": height })),
+                ),
+                FetchCommitError::HeightNotAvailable(HeightNotAvailable {
+                    lowest_height, ..
+                }) => invalid_height(
+                    format!(
+                        "height {height} is not available, earliest available height is {}",
+                        self.make_height(lowest_height)
+                    ),
+                    Some(json!({
+                        "requested_height": height,
+                        "earliest_available_height": self.make_height(lowest_height),
+                    })),
+                ),
+                FetchCommitError::JsonRpc(_) => {
+                    retryable_upstream(ErrorReporter(e).to_string(), None)
+                }
+            })
+    }
 
+    /// Fetch the unbonding period of the chain from the staking module, in seconds.
+    async fn fetch_unbonding_period(&self) -> RpcResult<u64> {
         let params = self
             .cometbft_client
             .grpc_abci_query::<_, protos::cosmos::staking::v1beta1::QueryParamsResponse>(
@@ -122,32 +151,42 @@ impl ClientBootstrapModuleServer for Module {
                 false,
             )
             .await
+            .record_upstream_error("cometbft", "abci_query")
             .map_err(json_rpc_error_to_error_object)?
             .into_result()
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
-                    ErrorReporter(e).with_message("error fetching params"),
-                    None::<()>,
-                )
-            })?
-            .ok_or_else(|| {
-                ErrorObject::owned(-1, "error fetching params: empty response", None::<()>)
+                retryable_upstream(ErrorReporter(e).with_message("error fetching params"), None)
             })?
+            .ok_or_else(|| retryable_upstream("error fetching params: empty response", None))?
             .params
             .unwrap_or_default();
 
-        let commit = self
-            .cometbft_client
-            .commit(Some(NonZeroU64::new(height.height()).unwrap()))
-            .await
-            .unwrap();
+        params
+            .unbonding_time
+            .and_then(|unbonding_time| u64::try_from(unbonding_time.seconds).ok())
+            .ok_or_else(|| {
+                retryable_upstream("error fetching params: invalid unbonding time", None)
+            })
+    }
+}
 
-        let height = commit.signed_header.header.height;
+#[async_trait]
+impl ClientBootstrapModuleServer for Module {
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn self_client_state(
+        &self,
+        _: &Extensions,
+        height: Height,
+        config: Value,
+    ) -> RpcResult<Value> {
+        ensure_null(config)?;
+
+        // fetch the commit first so that a pruned height is reported before any state queries
+        let commit = self.fetch_commit(height).await?;
 
-        // Expected to be nanos
-        let unbonding_period =
-            u64::try_from(params.unbonding_time.clone().unwrap().seconds).unwrap();
+        let unbonding_period = self.fetch_unbonding_period().await?;
+
+        let height = commit.signed_header.header.height;
 
         // Avoid low unbonding period preventing relayer from submitting slightly old headers
         let unbonding_period = unbonding_period.max(3 * 24 * 3600);
@@ -183,11 +222,7 @@ impl ClientBootstrapModuleServer for Module {
     ) -> RpcResult<Value> {
         ensure_null(config)?;
 
-        let commit = self
-            .cometbft_client
-            .commit(Some(NonZeroU64::new(height.height()).unwrap()))
-            .await
-            .unwrap();
+        let commit = self.fetch_commit(height).await?;
 
         Ok(serde_json::to_value(ConsensusState {
             timestamp: Timestamp::from_nanos(commit.signed_header.header.time.as_unix_nanos()),
@@ -198,4 +233,65 @@ impl ClientBootstrapModuleServer for Module {
         })
         .unwrap())
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn self_client_meta(&self, _: &Extensions, height: Height) -> RpcResult<SelfClientMeta> {
+        let commit = self.fetch_commit(height).await?;
+
+        let unbonding_period = self.fetch_unbonding_period().await?;
+
+        let mut consensus_params = self
+            .cometbft_client
+            .consensus_params(NonZeroU64::new(height.height()))
+            .await
+            .record_upstream_error("cometbft", "consensus_params")
+            .map_err(|e| {
+                retryable_upstream(
+                    format!("error fetching consensus params: {}", ErrorReporter(e)),
+                    None,
+                )
+            })?
+            .consensus_params;
+
+        if let Some(consensus_params) = consensus_params.as_object_mut() {
+            consensus_params.insert(
+                "consensus_hash".to_owned(),
+                json!(commit.signed_header.header.consensus_hash),
+            );
+        }
+
+        let latest_height = self
+            .cometbft_client
+            .status()
+            .await
+            .record_upstream_error("cometbft", "status")
+            .map_err(|e| {
+                retryable_upstream(format!("error fetching status: {}", ErrorReporter(e)), None)
+            })?
+            .sync_info
+            .latest_block_height;
+
+        Ok(SelfClientMeta {
+            chain_revision: Some(self.revision_start.revision()),
+            latest_height: self.make_height(latest_height),
+            unbonding_period: Some(Duration::from_secs(unbonding_period)),
+            consensus_params,
+            chain_type: None,
+        })
+    }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .cometbft_client
+            .status()
+            .await
+            .map(|status| self.make_height(status.sync_info.latest_block_height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
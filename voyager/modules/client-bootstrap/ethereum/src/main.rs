@@ -10,11 +10,12 @@ use ethereum_light_client_types::{
 };
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::{debug, instrument, trace};
 use unionlabs::{
     ibc::core::client::height::Height,
@@ -23,10 +24,15 @@ use unionlabs::{
 };
 use voyager_sdk::{
     anyhow::{self, bail},
-    ensure_null, into_value,
-    plugin::ClientBootstrapModule,
+    ensure_null,
+    error::retryable_upstream,
+    into_value, module_version,
+    plugin::{metrics::UpstreamResultExt, ClientBootstrapModule},
     primitives::{ChainId, ClientType, Timestamp},
-    rpc::{types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer},
+    rpc::{
+        types::{ClientBootstrapModuleInfo, ModuleHealth, SelfClientMeta},
+        ClientBootstrapModuleServer,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -47,7 +53,7 @@ pub struct Module {
     pub beacon_api_client: BeaconApiClient,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_spec: PresetBaseKind,
@@ -62,6 +68,8 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl Module {
@@ -75,11 +83,11 @@ impl Module {
             .get_block((block_number + 1).into())
             .hashes()
             .await
+            .record_upstream_error("eth", "get_block")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     format!("error fetching execution block: {}", ErrorReporter(e)),
-                    None::<()>,
+                    None,
                 )
             })?
             .expect("block should exist");
@@ -96,11 +104,11 @@ impl Module {
                 .into(),
             )
             .await
+            .record_upstream_error("beacon", "block")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     format!("error fetching beacon block: {}", ErrorReporter(e)),
-                    None::<()>,
+                    None,
                 )
             })?
             .response
@@ -126,8 +134,9 @@ impl ClientBootstrapModule for Module {
         let provider = DynProvider::new(
             ProviderBuilder::new()
                 .layer(CacheLayer::new(config.max_cache_size))
-                .connect(&config.rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
@@ -168,21 +177,29 @@ impl ClientBootstrapModuleServer for Module {
     ) -> RpcResult<Value> {
         ensure_null(config)?;
 
-        let genesis = self.beacon_api_client.genesis().await.map_err(|err| {
-            ErrorObject::owned(
-                -1,
-                ErrorReporter(err).with_message("error fetching beacon genesis"),
-                None::<()>,
-            )
-        })?;
+        let genesis = self
+            .beacon_api_client
+            .genesis()
+            .await
+            .record_upstream_error("beacon", "genesis")
+            .map_err(|err| {
+                retryable_upstream(
+                    ErrorReporter(err).with_message("error fetching beacon genesis"),
+                    None,
+                )
+            })?;
 
-        let spec = self.beacon_api_client.spec().await.map_err(|err| {
-            ErrorObject::owned(
-                -1,
-                ErrorReporter(err).with_message("error fetching beacon spec"),
-                None::<()>,
-            )
-        })?;
+        let spec = self
+            .beacon_api_client
+            .spec()
+            .await
+            .record_upstream_error("beacon", "spec")
+            .map_err(|err| {
+                retryable_upstream(
+                    ErrorReporter(err).with_message("error fetching beacon spec"),
+                    None,
+                )
+            })?;
 
         let beacon_slot = self
             .beacon_slot_of_execution_block_number(height.height())
@@ -197,22 +214,21 @@ impl ClientBootstrapModuleServer for Module {
                 .beacon_api_client
                 .light_client_updates(current_period, 1)
                 .await
+                .record_upstream_error("beacon", "light_client_updates")
                 .map_err(|e| {
-                    ErrorObject::owned(
-                        -1,
+                    retryable_upstream(
                         format!("error fetching light client update: {}", ErrorReporter(e)),
-                        None::<()>,
+                        None,
                     )
                 })?;
 
             let [light_client_update] = &*light_client_updates else {
-                return Err(ErrorObject::owned(
-                    -1,
+                return Err(retryable_upstream(
                     format!(
                         "received invalid light client updates, expected \
                         1 but received {light_client_updates:?}"
                     ),
-                    None::<()>,
+                    None,
                 ));
             };
 
@@ -232,11 +248,11 @@ impl ClientBootstrapModuleServer for Module {
             .beacon_api_client
             .header(beacon_api::client::BlockId::Slot(beacon_slot))
             .await
+            .record_upstream_error("beacon", "header")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     format!("error fetching beacon header: {}", ErrorReporter(e)),
-                    None::<()>,
+                    None,
                 )
             })?
             .data;
@@ -245,11 +261,11 @@ impl ClientBootstrapModuleServer for Module {
             .beacon_api_client
             .bootstrap(trusted_header.root)
             .await
+            .record_upstream_error("beacon", "bootstrap")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     format!("error fetching beacon bootstrap: {}", ErrorReporter(e)),
-                    None::<()>,
+                    None,
                 )
             })?
             .fold::<SyncCommittee>(
@@ -301,11 +317,11 @@ impl ClientBootstrapModuleServer for Module {
             .beacon_api_client
             .header(beacon_api::client::BlockId::Slot(beacon_slot))
             .await
+            .record_upstream_error("beacon", "header")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     format!("error fetching beacon header: {}", ErrorReporter(e)),
-                    None::<()>,
+                    None,
                 )
             })?
             .data;
@@ -314,11 +330,11 @@ impl ClientBootstrapModuleServer for Module {
             .beacon_api_client
             .bootstrap(trusted_header.root)
             .await
+            .record_upstream_error("beacon", "bootstrap")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     format!("error fetching beacon bootstrap: {}", ErrorReporter(e)),
-                    None::<()>,
+                    None,
                 )
             })?
             .fold::<ethereum_sync_protocol_types::LightClientHeader>(
@@ -340,11 +356,11 @@ impl ClientBootstrapModuleServer for Module {
                 .get_proof(self.ibc_handler_address.into(), vec![])
                 .block_id(bootstrap_header.execution.block_number.into())
                 .await
+                .record_upstream_error("eth", "get_proof")
                 .map_err(|err| {
-                    ErrorObject::owned(
-                        -1,
+                    retryable_upstream(
                         ErrorReporter(err).with_message("error fetching ibc storage root"),
-                        None::<()>,
+                        None,
                     )
                 })?
                 .storage_hash
@@ -353,4 +369,80 @@ impl ClientBootstrapModuleServer for Module {
             timestamp: Timestamp::from_secs(bootstrap_header.execution.timestamp),
         }))
     }
+
+    /// The ethereum chain has no revisions and no unbonding period, the consensus params are the
+    /// parameters of the beacon chain that the light client depends on.
+    #[instrument(skip_all, fields(chain_id = %self.chain_id, %height))]
+    async fn self_client_meta(&self, _: &Extensions, height: Height) -> RpcResult<SelfClientMeta> {
+        let genesis = self
+            .beacon_api_client
+            .genesis()
+            .await
+            .record_upstream_error("beacon", "genesis")
+            .map_err(|err| {
+                retryable_upstream(
+                    ErrorReporter(err).with_message("error fetching beacon genesis"),
+                    None,
+                )
+            })?;
+
+        let spec = self
+            .beacon_api_client
+            .spec()
+            .await
+            .record_upstream_error("beacon", "spec")
+            .map_err(|err| {
+                retryable_upstream(
+                    ErrorReporter(err).with_message("error fetching beacon spec"),
+                    None,
+                )
+            })?;
+
+        let beacon_slot = self
+            .beacon_slot_of_execution_block_number(height.height())
+            .await?;
+
+        let latest_height = self
+            .provider
+            .get_block_number()
+            .await
+            .record_upstream_error("eth", "get_block_number")
+            .map_err(|err| {
+                retryable_upstream(
+                    ErrorReporter(err).with_message("error fetching latest block number"),
+                    None,
+                )
+            })?;
+
+        Ok(SelfClientMeta {
+            chain_revision: None,
+            latest_height: Height::new(latest_height),
+            unbonding_period: None,
+            consensus_params: json!({
+                "chain_spec": spec.preset_base,
+                "genesis_validators_root": genesis.genesis_validators_root,
+                "genesis_time": genesis.genesis_time,
+                "seconds_per_slot": spec.seconds_per_slot,
+                "slots_per_epoch": spec.slots_per_epoch,
+                "epochs_per_sync_committee_period": spec.epochs_per_sync_committee_period,
+                "sync_committee_period": beacon_slot.get().div(spec.period()),
+            }),
+            chain_type: None,
+        })
+    }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .provider
+            .get_block_number()
+            .await
+            .map(Height::new)
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
@@ -1,15 +1,17 @@
 use std::{
     fmt::Debug,
     num::{NonZeroU64, ParseIntError},
+    time::Duration,
 };
 
 use ethermint_light_client_types::ClientState;
 use ics23::ibc_api::SDK_SPECS;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tendermint_light_client_types::{ConsensusState, Fraction};
@@ -22,10 +24,13 @@ use unionlabs::{
 };
 use voyager_sdk::{
     anyhow, ensure_null,
-    plugin::ClientBootstrapModule,
+    error::retryable_upstream,
+    module_version,
+    plugin::{metrics::UpstreamResultExt, ClientBootstrapModule},
     primitives::{ChainId, ClientType},
     rpc::{
-        json_rpc_error_to_error_object, types::ClientBootstrapModuleInfo,
+        json_rpc_error_to_error_object,
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
         ClientBootstrapModuleServer,
     },
 };
@@ -40,27 +45,31 @@ pub struct Module {
     pub chain_id: ChainId,
 
     pub cometbft_client: cometbft_rpc::Client,
-    pub chain_revision: u64,
+    pub revision_start: Height,
 
     pub ibc_handler_address: H160,
     pub store_key: Bytes,
     pub key_prefix_storage: Bytes,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
     pub ibc_handler_address: H160,
     pub store_key: Bytes,
     pub key_prefix_storage: Bytes,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl ClientBootstrapModule for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: ClientBootstrapModuleInfo) -> anyhow::Result<Self> {
-        let cometbft_client = cometbft_rpc::Client::new(config.rpc_url).await?;
+        let cometbft_client = cometbft_rpc::Client::new(config.rpc_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
 
         let chain_id = cometbft_client
             .status()
@@ -88,7 +97,7 @@ impl ClientBootstrapModule for Module {
         Ok(Self {
             cometbft_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            revision_start: Height::new_with_revision(chain_revision, 0),
             ibc_handler_address: config.ibc_handler_address,
             store_key: config.store_key,
             key_prefix_storage: config.key_prefix_storage,
@@ -128,7 +137,7 @@ pub struct MinimalParams {
 impl Module {
     #[must_use]
     pub fn make_height(&self, height: u64) -> Height {
-        Height::new_with_revision(self.chain_revision, height)
+        self.revision_start.with_height(height)
     }
 }
 
@@ -166,22 +175,7 @@ impl ClientBootstrapModuleServer for Module {
 
         let height = commit.signed_header.header.height;
 
-        let unbonding_period = std::time::Duration::new(
-            params
-                .unbonding_time
-                .clone()
-                .unwrap()
-                .seconds
-                .try_into()
-                .unwrap(),
-            params
-                .unbonding_time
-                .clone()
-                .unwrap()
-                .nanos
-                .try_into()
-                .unwrap(),
-        );
+        let unbonding_period = parse_unbonding_period(params.unbonding_time)?;
 
         Ok(serde_json::to_value(ClientState {
             tendermint_client_state: tendermint_light_client_types::ClientState {
@@ -192,19 +186,14 @@ impl ClientBootstrapModuleServer for Module {
                     denominator: const { option_unwrap!(NonZeroU64::new(3)) },
                 },
                 // https://github.com/cosmos/relayer/blob/23d1e5c864b35d133cad6a0ef06970a2b1e1b03f/relayer/chains/cosmos/provider.go#L177
-                trusting_period: unionlabs::google::protobuf::duration::Duration::new(
-                    (unbonding_period * 85 / 100).as_secs().try_into().unwrap(),
-                    (unbonding_period * 85 / 100)
-                        .subsec_nanos()
-                        .try_into()
-                        .unwrap(),
-                )
-                .unwrap(),
-                unbonding_period: unionlabs::google::protobuf::duration::Duration::new(
-                    unbonding_period.as_secs().try_into().unwrap(),
-                    unbonding_period.subsec_nanos().try_into().unwrap(),
-                )
-                .unwrap(),
+                trusting_period:
+                    unionlabs::google::protobuf::duration::Duration::from_std_saturating(
+                        unbonding_period * 85 / 100,
+                    ),
+                unbonding_period:
+                    unionlabs::google::protobuf::duration::Duration::from_std_saturating(
+                        unbonding_period,
+                    ),
                 // https://github.com/cosmos/relayer/blob/23d1e5c864b35d133cad6a0ef06970a2b1e1b03f/relayer/chains/cosmos/provider.go#L177
                 max_clock_drift: const {
                     result_unwrap!(unionlabs::google::protobuf::duration::Duration::new(
@@ -213,10 +202,9 @@ impl ClientBootstrapModuleServer for Module {
                     ))
                 },
                 frozen_height: None,
-                latest_height: Height::new_with_revision(
-                    self.chain_revision,
-                    height.inner().try_into().expect("is within bounds; qed;"),
-                ),
+                latest_height: self
+                    .revision_start
+                    .with_height(height.inner().try_into().expect("is within bounds; qed;")),
                 proof_specs: SDK_SPECS.into(),
                 upgrade_path: vec!["upgrade".into(), "upgradedIBCState".into()],
                 contract_address: Default::default(),
@@ -242,12 +230,9 @@ impl ClientBootstrapModuleServer for Module {
             .cometbft_client
             .commit(Some(height.height().try_into().unwrap()))
             .await
+            .record_upstream_error("cometbft", "commit")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
-                    format!("error fetching commit: {}", ErrorReporter(e)),
-                    None::<()>,
-                )
+                retryable_upstream(format!("error fetching commit: {}", ErrorReporter(e)), None)
             })?;
 
         Ok(serde_json::to_value(&ConsensusState {
@@ -259,4 +244,45 @@ impl ClientBootstrapModuleServer for Module {
         })
         .unwrap())
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .cometbft_client
+            .status()
+            .await
+            .map(|status| self.make_height(status.sync_info.latest_block_height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
+}
+
+/// Convert the unbonding period from the params of the chain into a [`Duration`].
+///
+/// Errors if the params don't contain an unbonding period, or it is negative or out of range.
+fn parse_unbonding_period(
+    unbonding_period: Option<protos::google::protobuf::Duration>,
+) -> RpcResult<Duration> {
+    let unbonding_period = unbonding_period
+        .ok_or_else(|| retryable_upstream("params do not contain an unbonding period", None))?;
+
+    let (seconds, nanos) = (unbonding_period.seconds, unbonding_period.nanos);
+
+    unionlabs::google::protobuf::duration::Duration::try_from(unbonding_period)
+        .map_err(|e| ErrorReporter(e).to_string())
+        .and_then(|unbonding_period| {
+            unbonding_period
+                .try_into()
+                .map_err(|e| ErrorReporter(e).to_string())
+        })
+        .map_err(|e| {
+            retryable_upstream(
+                format!("invalid unbonding period {seconds}s {nanos}ns: {e}"),
+                None,
+            )
+        })
 }
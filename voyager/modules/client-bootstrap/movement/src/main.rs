@@ -5,6 +5,7 @@ use jsonrpsee::{
     Extensions,
 };
 use movement_light_client_types::{ClientState, ConsensusState};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug, instrument};
@@ -15,12 +16,16 @@ use unionlabs::{
     },
     ibc::core::client::height::Height,
     primitives::{FixedBytes, H160, U256},
+    ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, ensure_null,
+    anyhow, ensure_null, module_version,
     plugin::ClientBootstrapModule,
     primitives::{ChainId, ClientType, Timestamp},
-    rpc::{types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer},
+    rpc::{
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
+    },
 };
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -87,7 +92,7 @@ impl aptos_move_ibc::ibc::ClientExt for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The address of the `IBCHandler` smart contract.
@@ -112,6 +117,7 @@ pub struct Config {
     /// the necessary proofs.
     ///
     /// [PR]: https://github.com/movementlabsxyz/movement/pull/645
+    #[schemars(with = "Vec<String>")]
     pub whitelisted_relayers: Vec<cosmwasm_std::Addr>,
 }
 
@@ -209,4 +215,19 @@ impl ClientBootstrapModuleServer for Module {
         })
         .expect("infallible"))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .aptos_client
+            .get_index()
+            .await
+            .map(|ledger_info| Height::new(ledger_info.inner().block_height.0))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
@@ -7,20 +7,24 @@ use alloy::{
 use ibc_union_spec::{Duration, Timestamp};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
 use parlia_light_client_types::{ClientState, ClientStateV1, ConsensusState};
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{info, instrument};
 use unionlabs::{ibc::core::client::height::Height, primitives::H160, ErrorReporter};
 use voyager_sdk::{
-    anyhow, into_value,
-    plugin::ClientBootstrapModule,
+    anyhow,
+    error::{retryable_upstream, unsupported_config},
+    into_value, module_version,
+    plugin::{metrics::UpstreamResultExt, ClientBootstrapModule},
     primitives::{ChainId, ClientType},
     rpc::{
-        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
     },
 };
 
@@ -39,7 +43,7 @@ pub struct Module {
     pub provider: DynProvider<AnyNetwork>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The address of the `IBCHandler` smart contract.
@@ -49,6 +53,8 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,8 +72,9 @@ impl ClientBootstrapModule for Module {
             ProviderBuilder::new()
                 .network::<AnyNetwork>()
                 .layer(CacheLayer::new(config.max_cache_size))
-                .connect(&config.rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
@@ -93,13 +100,12 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 format!(
                     "unable to deserialize client state config: {}",
                     ErrorReporter(err)
                 ),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -115,11 +121,11 @@ impl ClientBootstrapModuleServer for Module {
             .provider
             .get_block(valset_epoch_block_number.into())
             .await
+            .record_upstream_error("eth", "get_block")
             .map_err(|err| {
-                ErrorObject::owned(
-                    FATAL_JSONRPC_ERROR_CODE,
+                retryable_upstream(
                     ErrorReporter(err).with_message("error fetching initial valset"),
-                    None::<()>,
+                    None,
                 )
             })?
             .unwrap();
@@ -162,13 +168,12 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 format!(
                     "unable to deserialize client state config: {}",
                     ErrorReporter(err)
                 ),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -184,12 +189,9 @@ impl ClientBootstrapModuleServer for Module {
             .provider
             .get_block(height.height().into())
             .await
+            .record_upstream_error("eth", "get_block")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
-                    ErrorReporter(e).with_message("error fetching block"),
-                    None::<()>,
-                )
+                retryable_upstream(ErrorReporter(e).with_message("error fetching block"), None)
             })?
             .unwrap();
 
@@ -208,6 +210,21 @@ impl ClientBootstrapModuleServer for Module {
             valset_epoch_block_number,
         }))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .provider
+            .get_block_number()
+            .await
+            .map(Height::new)
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
 
 const STAKE_HUB_ADDRESS: alloy::primitives::Address =
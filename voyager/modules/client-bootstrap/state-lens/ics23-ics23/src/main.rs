@@ -1,9 +1,9 @@
 use ibc_union_spec::{path::ConsensusStatePath, ClientId, IbcUnion};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use state_lens_ics23_ics23_light_client_types::{client_state::Extra, ClientState, ConsensusState};
@@ -14,11 +14,14 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow,
+    error::{internal, invalid_height, unsupported_config},
+    into_value, module_version,
     plugin::ClientBootstrapModule,
     primitives::{ChainId, ClientStateMeta, QueryHeight},
     rpc::{
-        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
     },
     ExtensionsExt, VoyagerClient,
 };
@@ -43,7 +46,7 @@ pub struct ClientStateConfig {
     pub extra: Extra,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {}
 
@@ -92,8 +95,7 @@ impl Module {
         );
 
         if l2_client_meta.counterparty_chain_id != self.l2_chain_id {
-            return Err(ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            return Err(unsupported_config(
                 format!(
                     "this module is configured for {}, but {} on {} tracks {}",
                     self.l2_chain_id,
@@ -101,7 +103,7 @@ impl Module {
                     l1_client_meta.counterparty_chain_id,
                     l2_client_meta.counterparty_chain_id
                 ),
-                None::<()>,
+                None,
             ));
         }
 
@@ -119,13 +121,12 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 format!(
                     "unable to deserialize client state config: {}",
                     ErrorReporter(err)
                 ),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -151,13 +152,12 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 format!(
                     "unable to deserialize client state config: {}",
                     ErrorReporter(err)
                 ),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -203,15 +203,14 @@ impl ClientBootstrapModuleServer for Module {
                     .await?
             }
             None => {
-                return Err(ErrorObject::owned(
-                    FATAL_JSONRPC_ERROR_CODE,
+                return Err(invalid_height(
                     format!(
                         "there is no consensus state for client {} on {} at height {}",
                         config.l2_client_id,
                         l1_client_meta.counterparty_chain_id,
                         height.height()
                     ),
-                    None::<()>,
+                    None,
                 ))
             }
         };
@@ -236,8 +235,7 @@ impl ClientBootstrapModuleServer for Module {
                 consensus_state
                     .pointer("/root/hash")
                     .ok_or_else(|| {
-                        ErrorObject::owned(
-                            FATAL_JSONRPC_ERROR_CODE,
+                        internal(
                             "unable to read /root/hash value of decoded l2 consensus state",
                             Some(json!({
                                 "decoded_consensus_state": consensus_state
@@ -247,8 +245,7 @@ impl ClientBootstrapModuleServer for Module {
                     .clone(),
             )
             .map_err(|e| {
-                ErrorObject::owned(
-                    FATAL_JSONRPC_ERROR_CODE,
+                internal(
                     ErrorReporter(e).with_message(
                         "unable to decode /root/hash value of decoded l2 consensus state",
                     ),
@@ -260,4 +257,9 @@ impl ClientBootstrapModuleServer for Module {
             .into_encoding(),
         }))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.l2_chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::without_upstream(module_version!()))
+    }
 }
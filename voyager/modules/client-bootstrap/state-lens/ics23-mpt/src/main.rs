@@ -1,9 +1,9 @@
 use ibc_union_spec::{path::ConsensusStatePath, ClientId, IbcUnion};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use state_lens_ics23_mpt_light_client::client::extract_consensus_state;
@@ -11,11 +11,14 @@ use state_lens_ics23_mpt_light_client_types::{client_state::Extra, ClientState};
 use tracing::{info, instrument};
 use unionlabs::{ibc::core::client::height::Height, ErrorReporter};
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow,
+    error::unsupported_config,
+    into_value, module_version,
     plugin::ClientBootstrapModule,
     primitives::{ChainId, ClientType, QueryHeight},
     rpc::{
-        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
     },
     ExtensionsExt,
 };
@@ -49,7 +52,7 @@ pub struct ClientStateConfig {
     pub storage_root_offset: u16,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {}
 
@@ -76,13 +79,12 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 format!(
                     "unable to deserialize client state config: {}",
                     ErrorReporter(err)
                 ),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -108,13 +110,12 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 format!(
                     "unable to deserialize consensus state config: {}",
                     ErrorReporter(err)
                 ),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -155,8 +156,7 @@ impl ClientBootstrapModuleServer for Module {
         );
 
         if l2_client_meta.counterparty_chain_id != self.l2_chain_id {
-            return Err(ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            return Err(unsupported_config(
                 format!(
                     "l2 client {} on {} tracks {}, but this module is for {}",
                     config.l2_client_id,
@@ -164,7 +164,7 @@ impl ClientBootstrapModuleServer for Module {
                     l2_client_meta.counterparty_chain_id,
                     self.l2_chain_id
                 ),
-                None::<()>,
+                None,
             ));
         }
 
@@ -212,4 +212,9 @@ impl ClientBootstrapModuleServer for Module {
 
         Ok(into_value(&consensus_state))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.l2_chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::without_upstream(module_version!()))
+    }
 }
@@ -1,21 +1,24 @@
 use ibc_union_spec::ClientId;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
 use movement_light_client_types::ConsensusState as MovementConsensusState;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use state_lens_ics23_smt_light_client_types::{client_state::Extra, ClientState, ConsensusState};
 use tracing::instrument;
 use unionlabs::{aptos::account::AccountAddress, ibc::core::client::height::Height, ErrorReporter};
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow,
+    error::unsupported_config,
+    into_value, module_version,
     plugin::ClientBootstrapModule,
     primitives::{ChainId, ClientType, QueryHeight},
     rpc::{
-        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
     },
     ExtensionsExt,
 };
@@ -38,7 +41,7 @@ pub struct ClientStateConfig {
     pub table_handle: AccountAddress,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {}
 
@@ -62,13 +65,12 @@ impl ClientBootstrapModuleServer for Module {
         config: Value,
     ) -> RpcResult<Value> {
         let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
-            ErrorObject::owned(
-                FATAL_JSONRPC_ERROR_CODE,
+            unsupported_config(
                 format!(
                     "unable to deserialize client state config: {}",
                     ErrorReporter(err)
                 ),
-                None::<()>,
+                None,
             )
         })?;
 
@@ -108,4 +110,9 @@ impl ClientBootstrapModuleServer for Module {
             state_root: consensus_state.state_root,
         }))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.l2_chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::without_upstream(module_version!()))
+    }
 }
@@ -1,8 +1,8 @@
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sui_light_client_types::{
@@ -22,9 +22,14 @@ use tracing::instrument;
 use unionlabs::{ibc::core::client::height::Height, ErrorReporter};
 use voyager_sdk::{
     anyhow, ensure_null,
-    plugin::ClientBootstrapModule,
+    error::retryable_upstream,
+    module_version,
+    plugin::{metrics::UpstreamResultExt, ClientBootstrapModule},
     primitives::{ChainId, ClientType},
-    rpc::{types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer},
+    rpc::{
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -70,18 +75,21 @@ impl ClientBootstrapModule for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The address of the `IBCHandler` smart contract.
+    #[schemars(with = "String")]
     pub ibc_contract: ObjectID,
 
+    #[schemars(with = "String")]
     pub ibc_store: ObjectID,
 
     pub rpc_url: String,
 
     pub sui_object_store_rpc_url: String,
 
+    #[schemars(with = "String")]
     pub ibc_commitments_object_id: ObjectID,
 }
 
@@ -108,21 +116,24 @@ impl ClientBootstrapModuleServer for Module {
             .read_api()
             .get_latest_checkpoint_sequence_number()
             .await
-            .map_err(|e| ErrorObject::owned(-1, ErrorReporter(e).to_string(), None::<()>))?;
+            .record_upstream_error("sui", "get_latest_checkpoint_sequence_number")
+            .map_err(|e| retryable_upstream(ErrorReporter(e).to_string(), None))?;
 
         let latest_checkpoint = self
             .sui_client
             .read_api()
             .get_checkpoint(CheckpointId::SequenceNumber(latest_checkpoint_number))
             .await
-            .map_err(|e| ErrorObject::owned(-1, ErrorReporter(e).to_string(), None::<()>))?;
+            .record_upstream_error("sui", "get_checkpoint")
+            .map_err(|e| retryable_upstream(ErrorReporter(e).to_string(), None))?;
 
         let committee = self
             .sui_client
             .governance_api()
             .get_committee_info(Some(latest_checkpoint.epoch.into()))
             .await
-            .map_err(|e| ErrorObject::owned(-1, ErrorReporter(e).to_string(), None::<()>))?;
+            .record_upstream_error("sui", "get_committee_info")
+            .map_err(|e| retryable_upstream(ErrorReporter(e).to_string(), None))?;
 
         Ok(serde_json::to_value(ClientState::V1(ClientStateV1 {
             chain_id: self.chain_id.to_string(),
@@ -153,20 +164,20 @@ impl ClientBootstrapModuleServer for Module {
             .get(req)
             .send()
             .await
+            .record_upstream_error("sui_object_store", "get")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     ErrorReporter(e).with_message("error fetching the checkpoint"),
-                    None::<()>,
+                    None,
                 )
             })?
             .bytes()
             .await
+            .record_upstream_error("sui_object_store", "get")
             .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
+                retryable_upstream(
                     ErrorReporter(e).with_message("error fetching the checkpoint"),
-                    None::<()>,
+                    None,
                 )
             })?;
 
@@ -179,6 +190,22 @@ impl ClientBootstrapModuleServer for Module {
         })
         .expect("infallible"))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .sui_client
+            .read_api()
+            .get_latest_checkpoint_sequence_number()
+            .await
+            .map(Height::new)
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
 
 fn convert_committee(committee: SuiCommittee) -> Committee {
@@ -1,30 +1,41 @@
 use std::{
     fmt::Debug,
     num::{NonZeroU64, ParseIntError},
-    time::Duration,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
+use cometbft_rpc::{rpc_types::CommitResponse, FetchCommitError, HeightNotAvailable};
 use ics23::ibc_api::SDK_SPECS;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    types::ErrorObject,
     Extensions,
 };
+use opentelemetry::{metrics::Counter, KeyValue};
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use tendermint_light_client_types::{ClientState, ConsensusState, Fraction};
-use tracing::{error, info, instrument};
+use tracing::{error, info, instrument, warn};
 use unionlabs::{
+    bounded::BoundedI64,
     ibc::core::{client::height::Height, commitment::merkle_root::MerkleRoot},
     option_unwrap,
-    primitives::{Bech32, H256},
+    primitives::{encoding::HexUnprefixed, Bech32, Bytes, H256},
     result_unwrap, ErrorReporter,
 };
 use voyager_sdk::{
+    abci::CachedAbciQuery,
     anyhow, ensure_null,
-    plugin::ClientBootstrapModule,
-    primitives::{ChainId, ClientType},
-    rpc::{types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer},
+    error::{internal, invalid_height, retryable_upstream},
+    module_version,
+    plugin::{metrics::UpstreamResultExt, protocol::on_shutdown, ClientBootstrapModule},
+    primitives::{self, ChainId, ClientType},
+    rpc::{
+        types::{ClientBootstrapModuleInfo, ModuleHealth, SelfClientMeta},
+        ClientBootstrapModuleServer,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -37,14 +48,22 @@ pub struct Module {
     pub chain_id: ChainId,
 
     pub cometbft_client: cometbft_rpc::Client,
-    pub chain_revision: u64,
+    pub cached_abci_query: CachedAbciQuery,
+    pub revision_start: Height,
 
     pub tendermint_chain_type: Option<TendermintChainType>,
 
+    pub bitcoin_block_time: Option<u64>,
+    /// The last estimate of the bitcoin block time, if `bitcoin_block_time` is not configured.
+    pub bitcoin_block_time_estimate: Arc<Mutex<Option<(Instant, u64)>>>,
+
     pub ibc_host_contract_address: H256,
+
+    pub bootstrap_calls_metric: Counter<u64>,
+    pub unbonding_period_fetches_metric: Counter<u64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields, rename_all = "snake_case")]
 pub enum TendermintChainType {
     CcvConsumer,
@@ -52,21 +71,31 @@ pub enum TendermintChainType {
     Babylon,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
     #[serde(default)]
     pub tendermint_chain_type: Option<TendermintChainType>,
+    /// The average bitcoin block time, in seconds. Only used for babylon chains.
+    ///
+    /// If not set, this will be estimated from the recent bitcoin headers in babylon's
+    /// `btclightclient` module.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bitcoin_block_time: Option<u64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ibc_host_contract_address: Option<Bech32<H256>>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl ClientBootstrapModule for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: ClientBootstrapModuleInfo) -> anyhow::Result<Self> {
-        let tm_client = cometbft_rpc::Client::new(config.rpc_url).await?;
+        let tm_client = cometbft_rpc::Client::new(config.rpc_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
 
         let chain_id = tm_client.status().await?.node_info.network.to_string();
 
@@ -86,16 +115,50 @@ impl ClientBootstrapModule for Module {
                 source: Some(err),
             })?;
 
-        Ok(Self {
+        let module = Self {
+            cached_abci_query: CachedAbciQuery::new(tm_client.clone())
+                .with_ttl(STAKING_PARAMS_PATH, PARAMS_CACHE_TTL)
+                .with_ttl(CCV_CONSUMER_PARAMS_PATH, PARAMS_CACHE_TTL)
+                .with_ttl(BTCCHECKPOINT_PARAMS_PATH, PARAMS_CACHE_TTL),
             cometbft_client: tm_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            revision_start: Height::new_with_revision(chain_revision, 0),
             tendermint_chain_type: config.tendermint_chain_type,
+            bitcoin_block_time: config.bitcoin_block_time,
+            bitcoin_block_time_estimate: Arc::new(Mutex::new(None)),
             ibc_host_contract_address: config
                 .ibc_host_contract_address
                 .map(|a| *a.data())
                 .unwrap_or_default(),
-        })
+            bootstrap_calls_metric: opentelemetry::global::meter("voyager")
+                .u64_counter("client_bootstrap.tendermint.calls")
+                .build(),
+            unbonding_period_fetches_metric: opentelemetry::global::meter("voyager")
+                .u64_counter("client_bootstrap.tendermint.unbonding_period_fetches")
+                .build(),
+        };
+
+        on_shutdown({
+            let cached_abci_query = module.cached_abci_query.clone();
+            let bitcoin_block_time_estimate = module.bitcoin_block_time_estimate.clone();
+
+            async move {
+                if let Some((_, bitcoin_block_time)) = *bitcoin_block_time_estimate
+                    .lock()
+                    .expect("lock is poisoned")
+                {
+                    info!(
+                        bitcoin_block_time,
+                        "last bitcoin block time estimate, configure `bitcoin_block_time` to use a \
+                         fixed value instead"
+                    );
+                }
+
+                cached_abci_query.flush().await;
+            }
+        });
+
+        Ok(module)
     }
 }
 
@@ -110,16 +173,168 @@ pub struct ChainIdParseError {
 impl Module {
     #[must_use]
     pub fn make_height(&self, height: u64) -> Height {
-        Height::new_with_revision(self.chain_revision, height)
+        self.revision_start.with_height(height)
+    }
+
+    fn record_bootstrap_call(&self, method: &'static str) {
+        self.bootstrap_calls_metric.add(
+            1,
+            &[
+                KeyValue::new("chain_id", self.chain_id.to_string()),
+                KeyValue::new("method", method),
+            ],
+        );
+    }
+
+    /// Fetch the commit at `height`, returning a structured error containing the earliest available
+    /// height if the requested height has been pruned by the node.
+    async fn fetch_commit(&self, height: Height) -> RpcResult<CommitResponse> {
+        self.cometbft_client
+            .fetch_commit(height.height())
+            .await
+            .record_upstream_error("cometbft", "commit")
+            .map_err(|e| match e {
+                FetchCommitError::ZeroHeight => invalid_height(
+                    ErrorReporter(e).to_string(),
+                    Some(json!({ "requested_height": height })),
+                ),
+                FetchCommitError::HeightNotAvailable(HeightNotAvailable {
+                    lowest_height, ..
+                }) => invalid_height(
+                    format!(
+                        "height {height} is not available, earliest available height is {}",
+                        self.make_height(lowest_height)
+                    ),
+                    Some(json!({
+                        "requested_height": height,
+                        "earliest_available_height": self.make_height(lowest_height),
+                    })),
+                ),
+                FetchCommitError::JsonRpc(_) => {
+                    retryable_upstream(ErrorReporter(e).to_string(), None)
+                }
+            })
+    }
+
+    /// Estimate the average bitcoin block time from the most recent headers in the main chain of
+    /// babylon's `btclightclient` module, falling back to [`DEFAULT_BITCOIN_BLOCK_TIME`] if there are
+    /// not enough headers to derive an estimate.
+    ///
+    /// The estimate is cached for [`PARAMS_CACHE_TTL`], since it only changes slowly.
+    async fn estimate_bitcoin_block_time(&self, height: Height) -> RpcResult<u64> {
+        if let Some((estimated_at, bitcoin_block_time)) = *self
+            .bitcoin_block_time_estimate
+            .lock()
+            .expect("lock is poisoned")
+        {
+            if estimated_at.elapsed() < PARAMS_CACHE_TTL {
+                return Ok(bitcoin_block_time);
+            }
+        }
+
+        let headers = self
+            .cometbft_client
+            .grpc_abci_query::<_, protos::babylon::btclightclient::v1::QueryMainChainResponse>(
+                "/babylon.btclightclient.v1.Query/MainChain",
+                &protos::babylon::btclightclient::v1::QueryMainChainRequest {
+                    pagination: Some(protos::cosmos::base::query::v1beta1::PageRequest {
+                        limit: BITCOIN_BLOCK_TIME_SAMPLE_SIZE,
+                        reverse: true,
+                        ..Default::default()
+                    }),
+                },
+                Some(abci_query_height(height)?),
+                false,
+            )
+            .await
+            .record_upstream_error("cometbft", "abci_query")
+            .map_err(|e| {
+                retryable_upstream(
+                    format!("error fetching bitcoin headers: {}", ErrorReporter(e)),
+                    None,
+                )
+            })?
+            .into_result()
+            .map_err(|e| {
+                retryable_upstream(
+                    ErrorReporter(e).with_message("error fetching bitcoin headers"),
+                    None,
+                )
+            })?
+            .ok_or_else(|| {
+                retryable_upstream("error fetching bitcoin headers: empty response", None)
+            })?
+            .headers
+            .into_iter()
+            .map(|header| {
+                header
+                    .header_hex
+                    .parse::<Bytes<HexUnprefixed>>()
+                    .ok()
+                    .and_then(|raw| bitcoin_header_timestamp(&raw))
+                    .map(|timestamp| (header.height, timestamp))
+                    .ok_or_else(|| {
+                        retryable_upstream(
+                            format!(
+                                "invalid bitcoin header at height {}: {}",
+                                header.height, header.header_hex
+                            ),
+                            None,
+                        )
+                    })
+            })
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        let bitcoin_block_time = match estimate_block_time(&headers) {
+            Some(bitcoin_block_time) => {
+                info!(
+                    %bitcoin_block_time,
+                    sample_size = headers.len(),
+                    "estimated bitcoin block time"
+                );
+
+                bitcoin_block_time
+            }
+            None => {
+                warn!(
+                    sample_size = headers.len(),
+                    "unable to estimate bitcoin block time, falling back to the default"
+                );
+
+                DEFAULT_BITCOIN_BLOCK_TIME
+            }
+        };
+
+        *self
+            .bitcoin_block_time_estimate
+            .lock()
+            .expect("lock is poisoned") = Some((Instant::now(), bitcoin_block_time));
+
+        Ok(bitcoin_block_time)
     }
 
-    async fn fetch_unbonding_period(&self, height: Height) -> Duration {
-        match self.tendermint_chain_type {
+    async fn fetch_unbonding_period(&self, height: Height) -> RpcResult<Duration> {
+        self.unbonding_period_fetches_metric.add(
+            1,
+            &[
+                KeyValue::new("chain_id", self.chain_id.to_string()),
+                KeyValue::new(
+                    "chain_type",
+                    match self.tendermint_chain_type {
+                        Some(TendermintChainType::CcvConsumer) => "ccv_consumer",
+                        Some(TendermintChainType::Babylon) => "babylon",
+                        None => "cosmos_sdk",
+                    },
+                ),
+            ],
+        );
+
+        Ok(match self.tendermint_chain_type {
             Some(TendermintChainType::CcvConsumer) => {
                 let params = self
-                .cometbft_client
+                .cached_abci_query
                 .grpc_abci_query::<_, protos::interchain_security::ccv::consumer::v1::QueryParamsResponse>(
-                    "/interchain_security.ccv.consumer.v1.Query/QueryParams",
+                    CCV_CONSUMER_PARAMS_PATH,
                     &protos::interchain_security::ccv::consumer::v1::QueryParamsRequest {},
                     Some(i64::try_from(height.height()).unwrap().try_into().unwrap()),
                     false,
@@ -131,20 +346,18 @@ impl Module {
                 .params
                 .unwrap();
 
-                let unbonding_period = params.unbonding_period.clone().unwrap();
-
-                Duration::new(
-                    unbonding_period.seconds.try_into().unwrap(),
-                    unbonding_period.nanos.try_into().unwrap(),
-                )
+                parse_unbonding_period(params.unbonding_period)?
             }
             Some(TendermintChainType::Babylon) => {
-                const BITCOIN_BLOCK_TIME: u32 = 10 * 60; // 10 minutes
+                let bitcoin_block_time = match self.bitcoin_block_time {
+                    Some(bitcoin_block_time) => bitcoin_block_time,
+                    None => self.estimate_bitcoin_block_time(height).await?,
+                };
 
                 let checkpointing_params = self
-                    .cometbft_client
+                    .cached_abci_query
                     .grpc_abci_query::<_, protos::babylon::btccheckpoint::v1::QueryParamsResponse>(
-                        "/babylon.btccheckpoint.v1.Query/Params",
+                        BTCCHECKPOINT_PARAMS_PATH,
                         &protos::babylon::btccheckpoint::v1::QueryParamsRequest {},
                         Some(i64::try_from(height.height()).unwrap().try_into().unwrap()),
                         false,
@@ -165,15 +378,15 @@ impl Module {
                 );
 
                 Duration::from_secs(
-                    (checkpointing_params.checkpoint_finalization_timeout * BITCOIN_BLOCK_TIME)
-                        as u64,
+                    checkpointing_params.checkpoint_finalization_timeout as u64
+                        * bitcoin_block_time,
                 )
             }
             None => {
                 let params = self
-                    .cometbft_client
+                    .cached_abci_query
                     .grpc_abci_query::<_, protos::cosmos::staking::v1beta1::QueryParamsResponse>(
-                        "/cosmos.staking.v1beta1.Query/Params",
+                        STAKING_PARAMS_PATH,
                         &protos::cosmos::staking::v1beta1::QueryParamsRequest {},
                         Some(i64::try_from(height.height()).unwrap().try_into().unwrap()),
                         false,
@@ -185,17 +398,88 @@ impl Module {
                     .params
                     .unwrap();
 
-                let unbonding_period = params.unbonding_time.clone().unwrap();
-
-                Duration::new(
-                    unbonding_period.seconds.try_into().unwrap(),
-                    unbonding_period.nanos.try_into().unwrap(),
-                )
+                parse_unbonding_period(params.unbonding_time)?
             }
-        }
+        })
     }
 }
 
+/// How long to cache module params, which rarely change.
+const PARAMS_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+const STAKING_PARAMS_PATH: &str = "/cosmos.staking.v1beta1.Query/Params";
+const CCV_CONSUMER_PARAMS_PATH: &str = "/interchain_security.ccv.consumer.v1.Query/QueryParams";
+const BTCCHECKPOINT_PARAMS_PATH: &str = "/babylon.btccheckpoint.v1.Query/Params";
+
+/// The target block time of bitcoin mainnet, in seconds.
+const DEFAULT_BITCOIN_BLOCK_TIME: u64 = 10 * 60;
+
+/// The amount of recent bitcoin headers to sample when estimating the bitcoin block time.
+const BITCOIN_BLOCK_TIME_SAMPLE_SIZE: u64 = 100;
+
+/// Read the timestamp out of a raw 80 byte bitcoin block header.
+///
+/// Returns `None` if the header is not 80 bytes long.
+fn bitcoin_header_timestamp(header: &[u8]) -> Option<u32> {
+    let header = <&[u8; 80]>::try_from(header).ok()?;
+
+    Some(u32::from_le_bytes(
+        header[68..72].try_into().expect("slice is 4 bytes; qed;"),
+    ))
+}
+
+/// The height to make an abci query at.
+fn abci_query_height(height: Height) -> RpcResult<BoundedI64<1>> {
+    i64::try_from(height.height())
+        .ok()
+        .and_then(|height| height.try_into().ok())
+        .ok_or_else(|| invalid_height(format!("height {height} is out of range"), None))
+}
+
+/// Convert the unbonding period from the params of the chain into a [`Duration`].
+///
+/// Errors if the params don't contain an unbonding period, or it is negative or out of range.
+fn parse_unbonding_period(
+    unbonding_period: Option<protos::google::protobuf::Duration>,
+) -> RpcResult<Duration> {
+    let unbonding_period = unbonding_period
+        .ok_or_else(|| retryable_upstream("params do not contain an unbonding period", None))?;
+
+    let (seconds, nanos) = (unbonding_period.seconds, unbonding_period.nanos);
+
+    unionlabs::google::protobuf::duration::Duration::try_from(unbonding_period)
+        .map_err(|e| ErrorReporter(e).to_string())
+        .and_then(|unbonding_period| {
+            unbonding_period
+                .try_into()
+                .map_err(|e| ErrorReporter(e).to_string())
+        })
+        .map_err(|e| {
+            retryable_upstream(
+                format!("invalid unbonding period {seconds}s {nanos}ns: {e}"),
+                None,
+            )
+        })
+}
+
+/// Estimate the average block time from a list of `(height, timestamp)` pairs.
+///
+/// Returns `None` if the headers don't span more than one height or the timestamps don't increase
+/// over the sampled range.
+fn estimate_block_time(headers: &[(u32, u32)]) -> Option<u64> {
+    let (min_height, min_time) = headers.iter().min_by_key(|(height, _)| *height)?;
+    let (max_height, max_time) = headers.iter().max_by_key(|(height, _)| *height)?;
+
+    let blocks = max_height
+        .checked_sub(*min_height)
+        .filter(|blocks| *blocks > 0)?;
+    let elapsed = max_time
+        .checked_sub(*min_time)
+        .filter(|elapsed| *elapsed > 0)?;
+
+    Some((elapsed / blocks).into())
+}
+
 #[async_trait]
 impl ClientBootstrapModuleServer for Module {
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
@@ -207,13 +491,12 @@ impl ClientBootstrapModuleServer for Module {
     ) -> RpcResult<Value> {
         ensure_null(config)?;
 
-        let unbonding_period = self.fetch_unbonding_period(height).await;
+        self.record_bootstrap_call("self_client_state");
 
-        let commit = self
-            .cometbft_client
-            .commit(Some(height.height().try_into().unwrap()))
-            .await
-            .unwrap();
+        // fetch the commit first so that a pruned height is reported before any state queries
+        let commit = self.fetch_commit(height).await?;
+
+        let unbonding_period = self.fetch_unbonding_period(height).await?;
 
         let height = commit.signed_header.header.height;
 
@@ -225,19 +508,12 @@ impl ClientBootstrapModuleServer for Module {
                 denominator: const { option_unwrap!(NonZeroU64::new(3)) },
             },
             // https://github.com/cosmos/relayer/blob/23d1e5c864b35d133cad6a0ef06970a2b1e1b03f/relayer/chains/cosmos/provider.go#L177
-            trusting_period: unionlabs::google::protobuf::duration::Duration::new(
-                (unbonding_period * 85 / 100).as_secs().try_into().unwrap(),
-                (unbonding_period * 85 / 100)
-                    .subsec_nanos()
-                    .try_into()
-                    .unwrap(),
-            )
-            .unwrap(),
-            unbonding_period: unionlabs::google::protobuf::duration::Duration::new(
-                unbonding_period.as_secs().try_into().unwrap(),
-                unbonding_period.subsec_nanos().try_into().unwrap(),
-            )
-            .unwrap(),
+            trusting_period: unionlabs::google::protobuf::duration::Duration::from_std_saturating(
+                unbonding_period * 85 / 100,
+            ),
+            unbonding_period: unionlabs::google::protobuf::duration::Duration::from_std_saturating(
+                unbonding_period,
+            ),
             // https://github.com/cosmos/relayer/blob/23d1e5c864b35d133cad6a0ef06970a2b1e1b03f/relayer/chains/cosmos/provider.go#L177
             max_clock_drift: const {
                 result_unwrap!(unionlabs::google::protobuf::duration::Duration::new(
@@ -246,10 +522,9 @@ impl ClientBootstrapModuleServer for Module {
                 ))
             },
             frozen_height: None,
-            latest_height: Height::new_with_revision(
-                self.chain_revision,
-                height.inner().try_into().expect("is within bounds; qed;"),
-            ),
+            latest_height: self
+                .revision_start
+                .with_height(height.inner().try_into().expect("is within bounds; qed;")),
             proof_specs: SDK_SPECS.into(),
             upgrade_path: vec!["upgrade".into(), "upgradedIBCState".into()],
             contract_address: self.ibc_host_contract_address,
@@ -267,17 +542,9 @@ impl ClientBootstrapModuleServer for Module {
     ) -> RpcResult<Value> {
         ensure_null(config)?;
 
-        let commit = self
-            .cometbft_client
-            .commit(Some(height.height().try_into().unwrap()))
-            .await
-            .map_err(|e| {
-                ErrorObject::owned(
-                    -1,
-                    format!("error fetching commit: {}", ErrorReporter(e)),
-                    None::<()>,
-                )
-            })?;
+        self.record_bootstrap_call("self_consensus_state");
+
+        let commit = self.fetch_commit(height).await?;
 
         Ok(serde_json::to_value(&ConsensusState {
             root: MerkleRoot {
@@ -288,4 +555,105 @@ impl ClientBootstrapModuleServer for Module {
         })
         .unwrap())
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn self_client_meta(&self, _: &Extensions, height: Height) -> RpcResult<SelfClientMeta> {
+        self.record_bootstrap_call("self_client_meta");
+
+        let commit = self.fetch_commit(height).await?;
+
+        let unbonding_period = self.fetch_unbonding_period(height).await?;
+
+        let mut consensus_params = self
+            .cometbft_client
+            .consensus_params(NonZeroU64::new(height.height()))
+            .await
+            .record_upstream_error("cometbft", "consensus_params")
+            .map_err(|e| {
+                retryable_upstream(
+                    format!("error fetching consensus params: {}", ErrorReporter(e)),
+                    None,
+                )
+            })?
+            .consensus_params;
+
+        if let Some(consensus_params) = consensus_params.as_object_mut() {
+            consensus_params.insert(
+                "consensus_hash".to_owned(),
+                json!(commit.signed_header.header.consensus_hash),
+            );
+        }
+
+        let latest_height = self
+            .cometbft_client
+            .status()
+            .await
+            .record_upstream_error("cometbft", "status")
+            .map_err(|e| {
+                retryable_upstream(format!("error fetching status: {}", ErrorReporter(e)), None)
+            })?
+            .sync_info
+            .latest_block_height;
+
+        let unbonding_period = u64::try_from(unbonding_period.as_nanos()).map_err(|_| {
+            internal(
+                format!("unbonding period {unbonding_period:?} does not fit in u64 nanoseconds"),
+                None,
+            )
+        })?;
+
+        Ok(SelfClientMeta {
+            chain_revision: Some(self.revision_start.revision()),
+            latest_height: self.make_height(latest_height),
+            unbonding_period: Some(primitives::Duration::from_nanos(unbonding_period)),
+            consensus_params,
+            chain_type: self
+                .tendermint_chain_type
+                .as_ref()
+                .map(|chain_type| serde_json::to_value(chain_type).unwrap())
+                .and_then(|chain_type| chain_type.as_str().map(ToOwned::to_owned)),
+        })
+    }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .cometbft_client
+            .status()
+            .await
+            .map(|status| self.make_height(status.sync_info.latest_block_height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_bitcoin_block_time() {
+        assert_eq!(estimate_block_time(&[]), None);
+        assert_eq!(estimate_block_time(&[(10, 1000)]), None);
+        assert_eq!(
+            estimate_block_time(&[(12, 2200), (11, 1600), (10, 1000)]),
+            Some(600)
+        );
+        assert_eq!(estimate_block_time(&[(11, 1000), (10, 1000)]), None);
+    }
+
+    #[test]
+    fn bitcoin_header_timestamp_genesis() {
+        // bitcoin genesis block header
+        let header = "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c"
+            .parse::<Bytes<HexUnprefixed>>()
+            .unwrap();
+
+        assert_eq!(bitcoin_header_timestamp(&header), Some(1231006505));
+        assert_eq!(bitcoin_header_timestamp(&header[..72]), None);
+    }
 }
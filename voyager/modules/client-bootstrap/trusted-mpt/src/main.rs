@@ -4,6 +4,8 @@ use jsonrpsee::{
     core::{async_trait, RpcResult},
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::instrument;
@@ -11,12 +13,16 @@ use trusted_mpt_light_client_types::{ClientState, ClientStateV1, ConsensusState}
 use unionlabs::{
     ibc::core::client::height::Height,
     primitives::{H160, H256},
+    ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, ensure_null, into_value,
+    anyhow, ensure_null, into_value, module_version,
     plugin::ClientBootstrapModule,
     primitives::{ChainId, ClientType, Timestamp},
-    rpc::{types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer},
+    rpc::{
+        types::{ClientBootstrapModuleInfo, ModuleHealth},
+        ClientBootstrapModuleServer,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -36,7 +42,7 @@ pub struct Module {
     pub private_key: SigningKey,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The address of the `IBCHandler` smart contract.
@@ -49,6 +55,8 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl ClientBootstrapModule for Module {
@@ -58,8 +66,9 @@ impl ClientBootstrapModule for Module {
         let provider = DynProvider::new(
             ProviderBuilder::new()
                 .layer(CacheLayer::new(config.max_cache_size))
-                .connect(&config.rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
@@ -134,4 +143,19 @@ impl ClientBootstrapModuleServer for Module {
             timestamp: Timestamp::from_secs(header.timestamp),
         }))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .provider
+            .get_block_number()
+            .await
+            .map(Height::new)
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
@@ -5,6 +5,7 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::instrument;
@@ -16,12 +17,15 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow,
+    anyhow, module_version,
     plugin::ClientModule,
     primitives::{
         ChainId, ClientStateMeta, ClientType, ConsensusStateMeta, ConsensusType, IbcInterface,
     },
-    rpc::{types::ClientModuleInfo, ClientModuleServer, FATAL_JSONRPC_ERROR_CODE},
+    rpc::{
+        types::{ClientModuleInfo, ModuleHealth},
+        ClientModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -32,7 +36,7 @@ async fn main() {
 #[derive(Debug, Clone)]
 pub struct Module {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {}
 
@@ -195,4 +199,9 @@ impl ClientModuleServer for Module {
             .map(|storage_proof| storage_proof.encode_as::<Bincode>())
             .map(Into::into)
     }
+
+    #[instrument(skip_all)]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::without_upstream(module_version!()))
+    }
 }
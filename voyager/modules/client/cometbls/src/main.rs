@@ -1,12 +1,15 @@
 use alloy_sol_types::SolValue;
 use ark_serialize::{CanonicalSerialize, SerializationError, Valid};
 use cometbls_light_client_types::{ClientState, ConsensusState, Header};
+use ibc_union_spec::path::IBC_UNION_COSMWASM_COMMITMENT_PREFIX;
+use ics23::ibc_api::SDK_SPECS;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::ErrorObject,
     Extensions,
 };
 use macros::model;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{debug, instrument};
@@ -21,12 +24,16 @@ use unionlabs::{
 };
 use voyager_sdk::{
     anyhow::{self, anyhow},
+    module_version,
     plugin::ClientModule,
     primitives::{
         ChainId, ClientStateMeta, ClientType, ConsensusStateMeta, ConsensusType,
         IbcGo08WasmClientMetadata, IbcInterface,
     },
-    rpc::{types::ClientModuleInfo, ClientModuleServer, FATAL_JSONRPC_ERROR_CODE},
+    rpc::{
+        types::{ClientModuleInfo, ModuleHealth, ProofVerification},
+        ClientModuleServer, FATAL_JSONRPC_ERROR_CODE, PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -80,7 +87,7 @@ pub struct Module {
     pub ibc_interface: SupportedIbcInterface,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {}
 
@@ -382,6 +389,75 @@ impl ClientModuleServer for Module {
             })
             .map(Into::into)
     }
+
+    #[instrument(skip_all, fields(key = %verification.key))]
+    async fn verify_proof(&self, _: &Extensions, verification: ProofVerification) -> RpcResult<()> {
+        // the contract address is not available in the client state of ibc-go clients, and the
+        // commitments are stored under a different path
+        if self.ibc_interface == SupportedIbcInterface::IbcGoV8_08Wasm {
+            return Err(ErrorObject::owned(
+                PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE,
+                format!(
+                    "proof verification is not supported for {}",
+                    self.ibc_interface.as_str()
+                ),
+                None::<()>,
+            ));
+        }
+
+        let client_state = self.decode_client_state(&verification.client_state)?;
+        let consensus_state = self.decode_consensus_state(&verification.consensus_state)?;
+
+        let proof = serde_json::from_value::<
+            unionlabs::ibc::core::commitment::merkle_proof::MerkleProof,
+        >(verification.proof)
+        .map_err(|err| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("unable to deserialize proof: {}", ErrorReporter(err)),
+                None::<()>,
+            )
+        })?;
+
+        // the commitments of the ibc-union contract are stored in the wasm store of union
+        let path = [
+            b"wasm".to_vec(),
+            [0x03]
+                .into_iter()
+                .chain(client_state.contract_address)
+                .chain(IBC_UNION_COSMWASM_COMMITMENT_PREFIX)
+                .chain(verification.key.iter().copied())
+                .collect::<Vec<_>>(),
+        ];
+
+        match verification.value {
+            Some(value) => ics23::ibc_api::verify_membership(
+                &proof,
+                &SDK_SPECS,
+                &consensus_state.app_hash,
+                &path,
+                value.into(),
+            ),
+            None => ics23::ibc_api::verify_non_membership(
+                &proof,
+                &SDK_SPECS,
+                &consensus_state.app_hash,
+                &path,
+            ),
+        }
+        .map_err(|err| {
+            ErrorObject::owned(
+                -1,
+                format!("invalid proof: {}", ErrorReporter(err)),
+                None::<()>,
+            )
+        })
+    }
+
+    #[instrument(skip_all)]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::without_upstream(module_version!()))
+    }
 }
 
 fn encode_merkle_proof_for_evm(
@@ -4,6 +4,7 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tendermint_light_client_types::{ConsensusState, Header};
@@ -15,13 +16,16 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow,
+    anyhow, module_version,
     plugin::ClientModule,
     primitives::{
         ChainId, ClientStateMeta, ClientType, ConsensusStateMeta, ConsensusType, IbcInterface,
         IbcSpecId, Timestamp,
     },
-    rpc::{types::ClientModuleInfo, ClientModuleServer, FATAL_JSONRPC_ERROR_CODE},
+    rpc::{
+        types::{ClientModuleInfo, ModuleHealth},
+        ClientModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -32,7 +36,7 @@ async fn main() {
 #[derive(Debug, Clone)]
 pub struct Module {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {}
 
@@ -190,4 +194,9 @@ impl ClientModuleServer for Module {
             })
             .map(|cs| cs.encode_as::<Bincode>().into())
     }
+
+    #[instrument(skip_all)]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::without_upstream(module_version!()))
+    }
 }
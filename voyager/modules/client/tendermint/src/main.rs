@@ -4,6 +4,7 @@ use jsonrpsee::{
     Extensions,
 };
 use macros::model;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tendermint_light_client_types::{ClientState, ConsensusState, Header};
@@ -17,12 +18,16 @@ use unionlabs::{
 };
 use voyager_sdk::{
     anyhow::{self, anyhow},
+    module_version,
     plugin::ClientModule,
     primitives::{
         ChainId, ClientStateMeta, ClientType, ConsensusStateMeta, ConsensusType, IbcInterface,
         Timestamp,
     },
-    rpc::{types::ClientModuleInfo, ClientModuleServer, FATAL_JSONRPC_ERROR_CODE},
+    rpc::{
+        types::{ClientModuleInfo, ModuleHealth},
+        ClientModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -71,7 +76,7 @@ pub struct Module {
     pub ibc_interface: SupportedIbcInterface,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {}
 
@@ -272,4 +277,9 @@ impl ClientModuleServer for Module {
                 SupportedIbcInterface::IbcCosmwasm => cs.encode_as::<Bincode>().into(),
             })
     }
+
+    #[instrument(skip_all)]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::without_upstream(module_version!()))
+    }
 }
@@ -10,14 +10,19 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use unionlabs::{ibc::core::client::height::Height, primitives::H160, ErrorReporter};
 use voyager_sdk::{
-    anyhow,
+    anyhow, module_version,
     plugin::FinalityModule,
     primitives::{ChainId, ConsensusType, Timestamp},
-    rpc::{types::FinalityModuleInfo, FinalityModuleServer},
+    rpc::{
+        types::{FinalityModuleInfo, ModuleHealth},
+        FinalityModuleServer,
+    },
     ExtensionsExt,
 };
 
@@ -38,7 +43,7 @@ pub struct Module {
     pub l2_provider: DynProvider<AnyNetwork>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The chain id of the chain this arbitrum chain chain settles on.
@@ -55,6 +60,8 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl FinalityModule for Module {
@@ -64,16 +71,18 @@ impl FinalityModule for Module {
         let l1_provider = DynProvider::new(
             ProviderBuilder::new()
                 .layer(CacheLayer::new(config.max_cache_size))
-                .connect(&config.l1_rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.l1_rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let l2_provider = DynProvider::new(
             ProviderBuilder::new()
                 .layer(CacheLayer::new(config.max_cache_size))
                 .network::<AnyNetwork>()
-                .connect(&config.l2_rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.l2_rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let l1_chain_id = ChainId::new(l1_provider.get_chain_id().await?.to_string());
@@ -168,4 +177,12 @@ impl FinalityModuleServer for Module {
                 .map_err(|err| ErrorObject::owned(-1, ErrorReporter(err).to_string(), None::<()>))
         }
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, e: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            self.query_latest_height(e, false).await,
+        ))
+    }
 }
@@ -0,0 +1,268 @@
+use std::{fmt::Debug, num::ParseIntError};
+
+use cometbft_rpc::{rpc_types::CommitResponse, JsonRpcError};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    types::ErrorObject,
+    Extensions,
+};
+use protos::babylon::{
+    checkpointing::v1::{
+        CheckpointStatus, QueryLastCheckpointWithStatusRequest,
+        QueryLastCheckpointWithStatusResponse,
+    },
+    epoching::v1::{QueryEpochInfoRequest, QueryEpochInfoResponse},
+};
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, trace};
+use unionlabs::{ibc::core::client::height::Height, ErrorReporter};
+use voyager_sdk::{
+    anyhow, module_version,
+    plugin::FinalityModule,
+    primitives::{ChainId, ConsensusType, Timestamp},
+    rpc::{
+        types::{FinalityModuleInfo, ModuleHealth},
+        FinalityModuleServer,
+    },
+};
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    Module::run().await
+}
+
+/// Finality module for babylon, where a height is only considered finalized once the checkpoint of
+/// the epoch containing it has been finalized on bitcoin.
+///
+/// Babylon's unbonding period is bounded by the checkpoint finalization timeout (see the babylon
+/// chain type in the tendermint client bootstrap module), so client updates must not be proposed
+/// for heights past the last BTC-finalized epoch.
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub chain_id: ChainId,
+
+    pub cometbft_client: cometbft_rpc::Client,
+    pub revision_start: Height,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub rpc_url: String,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl FinalityModule for Module {
+    type Config = Config;
+
+    async fn new(config: Self::Config, info: FinalityModuleInfo) -> anyhow::Result<Self> {
+        let tm_client = cometbft_rpc::Client::new(config.rpc_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
+
+        let chain_id = tm_client.status().await?.node_info.network.to_string();
+
+        info.ensure_chain_id(&chain_id)?;
+        info.ensure_consensus_type(ConsensusType::TENDERMINT)?;
+
+        let chain_revision = chain_id
+            .split('-')
+            .next_back()
+            .ok_or_else(|| ChainIdParseError {
+                found: chain_id.clone(),
+                source: None,
+            })?
+            .parse()
+            .map_err(|err| ChainIdParseError {
+                found: chain_id.clone(),
+                source: Some(err),
+            })?;
+
+        Ok(Self {
+            cometbft_client: tm_client,
+            chain_id: ChainId::new(chain_id),
+            revision_start: Height::new_with_revision(chain_revision, 0),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unable to parse chain id: expected format `<chain>-<revision-number>`, found `{found}`")]
+pub struct ChainIdParseError {
+    found: String,
+    #[source]
+    source: Option<ParseIntError>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FinalityError {
+    #[error(transparent)]
+    Rpc(#[from] JsonRpcError),
+    #[error("no btc-finalized checkpoint found ({log})")]
+    NoFinalizedCheckpoint { log: String },
+    #[error("epoch info not found for epoch {epoch} ({log})")]
+    EpochInfoNotFound { epoch: u64, log: String },
+}
+
+impl Module {
+    #[must_use]
+    pub fn make_height(&self, height: u64) -> Height {
+        self.revision_start.with_height(height)
+    }
+
+    /// The last height of the most recent epoch whose checkpoint has been finalized on bitcoin.
+    #[instrument(skip_all)]
+    async fn btc_finalized_height(&self) -> Result<u64, FinalityError> {
+        let checkpoint = self
+            .cometbft_client
+            .grpc_abci_query::<_, QueryLastCheckpointWithStatusResponse>(
+                "/babylon.checkpointing.v1.Query/LastCheckpointWithStatus",
+                &QueryLastCheckpointWithStatusRequest {
+                    status: CheckpointStatus::CkptStatusFinalized.into(),
+                },
+                None,
+                false,
+            )
+            .await?;
+
+        let epoch = checkpoint
+            .value
+            .and_then(|value| value.raw_checkpoint)
+            .ok_or(FinalityError::NoFinalizedCheckpoint {
+                log: checkpoint.log,
+            })?
+            .epoch_num;
+
+        let epoch_info = self
+            .cometbft_client
+            .grpc_abci_query::<_, QueryEpochInfoResponse>(
+                "/babylon.epoching.v1.Query/EpochInfo",
+                &QueryEpochInfoRequest { epoch_num: epoch },
+                None,
+                false,
+            )
+            .await?;
+
+        let epoch_info = epoch_info.value.and_then(|value| value.epoch).ok_or(
+            FinalityError::EpochInfoNotFound {
+                epoch,
+                log: epoch_info.log,
+            },
+        )?;
+
+        let height = epoch_last_height(
+            epoch_info.first_block_height,
+            epoch_info.current_epoch_interval,
+        );
+
+        debug!(epoch, height, "latest btc-finalized epoch");
+
+        Ok(height)
+    }
+
+    #[instrument(skip_all, fields(%finalized))]
+    async fn latest_commit(&self, finalized: bool) -> Result<CommitResponse, FinalityError> {
+        let commit_response = self.cometbft_client.commit(None).await?;
+
+        if !finalized {
+            return Ok(commit_response);
+        }
+
+        let latest_height = u64::try_from(commit_response.signed_header.header.height.inner())
+            .expect("value is >= 0; qed;");
+
+        let finalized_height = self.btc_finalized_height().await?;
+
+        // the epoch boundary is always a committed block, but be defensive in case the node is
+        // lagging behind the queried state
+        if finalized_height >= latest_height {
+            trace!(
+                finalized_height,
+                latest_height,
+                "btc-finalized height is not behind the latest commit"
+            );
+            return Ok(commit_response);
+        }
+
+        Ok(self
+            .cometbft_client
+            .commit(Some(
+                finalized_height
+                    .try_into()
+                    .expect("epoch boundary height is non-zero; qed;"),
+            ))
+            .await?)
+    }
+}
+
+/// The height of the last block in an epoch, i.e. the block sealing the epoch's checkpoint.
+fn epoch_last_height(first_block_height: u64, epoch_interval: u64) -> u64 {
+    (first_block_height + epoch_interval).saturating_sub(1)
+}
+
+fn finality_error_to_error_object(err: FinalityError) -> ErrorObject<'static> {
+    ErrorObject::owned(-1, ErrorReporter(err).to_string(), None::<()>)
+}
+
+#[async_trait]
+impl FinalityModuleServer for Module {
+    /// Query the latest finalized height of this chain.
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn query_latest_height(&self, _: &Extensions, finalized: bool) -> RpcResult<Height> {
+        let commit_response = self
+            .latest_commit(finalized)
+            .await
+            .map_err(finality_error_to_error_object)?;
+
+        let height = commit_response
+            .signed_header
+            .header
+            .height
+            .inner()
+            .try_into()
+            .expect("value is >= 0; qed;");
+
+        trace!(height, "latest height");
+
+        Ok(self.make_height(height))
+    }
+
+    /// Query the latest finalized timestamp of this chain.
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn query_latest_timestamp(
+        &self,
+        _: &Extensions,
+        finalized: bool,
+    ) -> RpcResult<Timestamp> {
+        let commit_response = self
+            .latest_commit(finalized)
+            .await
+            .map_err(finality_error_to_error_object)?;
+
+        Ok(Timestamp::from_nanos(
+            commit_response.signed_header.header.time.as_unix_nanos(),
+        ))
+    }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, e: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            self.query_latest_height(e, false).await,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epoch_last_height_is_inclusive() {
+        assert_eq!(epoch_last_height(1, 360), 360);
+        assert_eq!(epoch_last_height(361, 360), 720);
+    }
+}
@@ -6,6 +6,8 @@ use jsonrpsee::{
     core::{async_trait, RpcResult},
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use unionlabs::{
@@ -15,10 +17,13 @@ use unionlabs::{
     primitives::H160,
 };
 use voyager_sdk::{
-    anyhow,
+    anyhow, module_version,
     plugin::FinalityModule,
     primitives::{ChainId, ConsensusType, Timestamp},
-    rpc::{types::FinalityModuleInfo, FinalityModuleServer},
+    rpc::{
+        types::{FinalityModuleInfo, ModuleHealth},
+        FinalityModuleServer,
+    },
     ExtensionsExt,
 };
 
@@ -37,7 +42,7 @@ pub struct Module {
     pub tm_client: cometbft_rpc::Client,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub l1_client_id: u32,
@@ -45,15 +50,21 @@ pub struct Config {
     pub ibc_handler_address: H160,
     pub rpc_url: String,
     pub comet_ws_url: String,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl FinalityModule for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: FinalityModuleInfo) -> anyhow::Result<Self> {
-        let tm_client = cometbft_rpc::Client::new(config.comet_ws_url).await?;
+        let tm_client = cometbft_rpc::Client::new(config.comet_ws_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
 
-        let eth_provider = DynProvider::new(ProviderBuilder::new().connect(&config.rpc_url).await?);
+        let eth_provider = DynProvider::new(ProviderBuilder::new().connect_client(
+            rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+        ));
 
         let l2_chain_id = ChainId::new(eth_provider.get_chain_id().await?.to_string());
 
@@ -175,4 +186,12 @@ impl FinalityModuleServer for Module {
     //             .into(),
     //     }))
     // }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, e: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            self.query_latest_height(e, false).await,
+        ))
+    }
 }
@@ -16,14 +16,20 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument, trace};
 use unionlabs::{ibc::core::client::height::Height, primitives::H256, ErrorReporter};
 use voyager_sdk::{
     anyhow::{self, bail},
+    module_version,
     plugin::FinalityModule,
     primitives::{ChainId, ConsensusType, Timestamp},
-    rpc::{types::FinalityModuleInfo, FinalityModuleServer},
+    rpc::{
+        types::{FinalityModuleInfo, ModuleHealth},
+        FinalityModuleServer,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -42,7 +48,7 @@ pub struct Module {
         moka::future::Cache<(), VersionedResponse<LightClientFinalityUpdateResponseTypes>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_spec: PresetBaseKind,
@@ -54,6 +60,8 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl Module {
@@ -213,8 +221,9 @@ impl FinalityModule for Module {
         let provider = DynProvider::new(
             ProviderBuilder::new()
                 .layer(CacheLayer::new(config.max_cache_size))
-                .connect(&config.rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
@@ -292,4 +301,12 @@ impl FinalityModuleServer for Module {
         // Normalize to nanos in order to be compliant with cosmos
         Ok(Timestamp::from_secs(latest_timestamp))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, e: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            self.query_latest_height(e, false).await,
+        ))
+    }
 }
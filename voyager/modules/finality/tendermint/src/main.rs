@@ -5,14 +5,20 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{error, instrument, trace};
 use unionlabs::{ibc::core::client::height::Height, ErrorReporter};
 use voyager_sdk::{
-    anyhow,
+    anyhow, module_version,
     plugin::FinalityModule,
     primitives::{ChainId, ConsensusType, Timestamp},
-    rpc::{json_rpc_error_to_error_object, types::FinalityModuleInfo, FinalityModuleServer},
+    rpc::{
+        json_rpc_error_to_error_object,
+        types::{FinalityModuleInfo, ModuleHealth},
+        FinalityModuleServer,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -25,20 +31,24 @@ pub struct Module {
     pub chain_id: ChainId,
 
     pub cometbft_client: cometbft_rpc::Client,
-    pub chain_revision: u64,
+    pub revision_start: Height,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl FinalityModule for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: FinalityModuleInfo) -> anyhow::Result<Self> {
-        let tm_client = cometbft_rpc::Client::new(config.rpc_url).await?;
+        let tm_client = cometbft_rpc::Client::new(config.rpc_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
 
         let chain_id = tm_client.status().await?.node_info.network.to_string();
 
@@ -61,7 +71,7 @@ impl FinalityModule for Module {
         Ok(Self {
             cometbft_client: tm_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            revision_start: Height::new_with_revision(chain_revision, 0),
         })
     }
 }
@@ -77,7 +87,7 @@ pub struct ChainIdParseError {
 impl Module {
     #[must_use]
     pub fn make_height(&self, height: u64) -> Height {
-        Height::new_with_revision(self.chain_revision, height)
+        self.revision_start.with_height(height)
     }
 
     #[instrument(skip_all, fields(%finalized))]
@@ -159,4 +169,12 @@ impl FinalityModuleServer for Module {
             commit_response.signed_header.header.time.as_unix_nanos(),
         ))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, e: &Extensions) -> RpcResult<ModuleHealth> {
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            self.query_latest_height(e, false).await,
+        ))
+    }
 }
@@ -11,6 +11,8 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{error, instrument, warn};
@@ -22,10 +24,14 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow, into_value, module_version,
     plugin::ProofModule,
     primitives::ChainId,
-    rpc::{rpc_error, types::ProofModuleInfo, ProofModuleServer, FATAL_JSONRPC_ERROR_CODE},
+    rpc::{
+        rpc_error,
+        types::{ModuleHealth, ProofModuleInfo},
+        ProofModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
     types::ProofType,
 };
 
@@ -43,25 +49,29 @@ pub enum Cmd {
 #[derive(Debug, Clone)]
 pub struct Module {
     pub chain_id: ChainId,
-    pub chain_revision: u64,
+    pub revision_start: Height,
 
     pub cometbft_client: cometbft_rpc::Client,
 
     pub ibc_host_contract_address: Bech32<H256>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
     pub ibc_host_contract_address: Bech32<H256>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl ProofModule<IbcUnion> for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: ProofModuleInfo) -> anyhow::Result<Self> {
-        let tm_client = cometbft_rpc::Client::new(config.rpc_url).await?;
+        let tm_client = cometbft_rpc::Client::new(config.rpc_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
 
         let chain_id = tm_client.status().await?.node_info.network;
 
@@ -83,7 +93,7 @@ impl ProofModule<IbcUnion> for Module {
         Ok(Self {
             cometbft_client: tm_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            revision_start: Height::new_with_revision(chain_revision, 0),
             ibc_host_contract_address: config.ibc_host_contract_address,
         })
     }
@@ -92,7 +102,7 @@ impl ProofModule<IbcUnion> for Module {
 impl Module {
     #[must_use]
     pub fn make_height(&self, height: u64) -> Height {
-        Height::new_with_revision(self.chain_revision, height)
+        self.revision_start.with_height(height)
     }
 }
 
@@ -186,4 +196,19 @@ impl ProofModuleServer<IbcUnion> for Module {
 
         Ok(Some((into_value(proof), proof_type)))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .cometbft_client
+            .status()
+            .await
+            .map(|status| self.make_height(status.sync_info.latest_block_height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
@@ -8,19 +8,25 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{error, instrument};
 use unionlabs::{
     cosmos::ics23::commitment_proof::CommitmentProof,
     ibc::core::{client::height::Height, commitment::merkle_proof::MerkleProof},
-    option_unwrap,
+    option_unwrap, ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow, into_value, module_version,
     plugin::ProofModule,
     primitives::ChainId,
-    rpc::{rpc_error, types::ProofModuleInfo, ProofModuleServer},
+    rpc::{
+        rpc_error,
+        types::{ModuleHealth, ProofModuleInfo},
+        ProofModuleServer,
+    },
     types::ProofType,
 };
 
@@ -38,22 +44,26 @@ pub enum Cmd {
 #[derive(Debug, Clone)]
 pub struct Module {
     pub chain_id: ChainId,
-    pub chain_revision: u64,
+    pub revision_start: Height,
 
     pub cometbft_client: cometbft_rpc::Client,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl ProofModule<IbcClassic> for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: ProofModuleInfo) -> anyhow::Result<Self> {
-        let tm_client = cometbft_rpc::Client::new(config.rpc_url).await?;
+        let tm_client = cometbft_rpc::Client::new(config.rpc_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
 
         let chain_id = tm_client.status().await?.node_info.network;
 
@@ -75,7 +85,7 @@ impl ProofModule<IbcClassic> for Module {
         Ok(Self {
             cometbft_client: tm_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            revision_start: Height::new_with_revision(chain_revision, 0),
         })
     }
 }
@@ -83,7 +93,7 @@ impl ProofModule<IbcClassic> for Module {
 impl Module {
     #[must_use]
     pub fn make_height(&self, height: u64) -> Height {
-        Height::new_with_revision(self.chain_revision, height)
+        self.revision_start.with_height(height)
     }
 }
 
@@ -173,4 +183,19 @@ impl ProofModuleServer<IbcClassic> for Module {
 
         Ok(Some((into_value(proof), proof_type)))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .cometbft_client
+            .status()
+            .await
+            .map(|status| self.make_height(status.sync_info.latest_block_height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
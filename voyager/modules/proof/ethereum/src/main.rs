@@ -8,6 +8,8 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug, instrument};
@@ -18,10 +20,13 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow, into_value, module_version,
     plugin::ProofModule,
     primitives::ChainId,
-    rpc::{types::ProofModuleInfo, ProofModuleServer},
+    rpc::{
+        types::{ModuleHealth, ProofModuleInfo},
+        ProofModuleServer,
+    },
     types::ProofType,
 };
 
@@ -39,7 +44,7 @@ pub struct Module {
     pub provider: DynProvider,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The address of the `IBCHandler` smart contract.
@@ -50,6 +55,8 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl ProofModule<IbcUnion> for Module {
@@ -59,8 +66,9 @@ impl ProofModule<IbcUnion> for Module {
         let provider = DynProvider::new(
             ProviderBuilder::new()
                 // .layer(CacheLayer::new(config.max_cache_size))
-                .connect(&config.rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let chain_id = provider.get_chain_id().await?;
@@ -137,4 +145,19 @@ impl ProofModuleServer<IbcUnion> for Module {
 
         Ok(Some((into_value(proof), proof_type)))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .provider
+            .get_block_number()
+            .await
+            .map(|height| self.make_height(height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
@@ -9,6 +9,7 @@ use jsonrpsee::{
     types::{ErrorObject, ErrorObjectOwned},
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug, instrument};
@@ -22,10 +23,13 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow, into_value, module_version,
     plugin::ProofModule,
     primitives::ChainId,
-    rpc::{types::ProofModuleInfo, ProofModuleServer},
+    rpc::{
+        types::{ModuleHealth, ProofModuleInfo},
+        ProofModuleServer,
+    },
     types::ProofType,
 };
 
@@ -71,11 +75,12 @@ impl ProofModule<IbcUnion> for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
     pub movement_rpc_url: String,
+    #[schemars(with = "String")]
     pub ibc_handler_address: Address,
 }
 
@@ -162,6 +167,21 @@ impl ProofModuleServer<IbcUnion> for Module {
             ProofType::Membership,
         )))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .aptos_client
+            .get_index()
+            .await
+            .map(|ledger_info| self.make_height(ledger_info.inner().block_height.0))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
 
 pub fn rest_error_to_rpc_error(e: RestError) -> ErrorObjectOwned {
@@ -6,6 +6,7 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sui_light_client_types::{
@@ -27,10 +28,13 @@ use sui_sdk::{
 use tracing::instrument;
 use unionlabs::{ibc::core::client::height::Height, ErrorReporter};
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow, into_value, module_version,
     plugin::ProofModule,
     primitives::ChainId,
-    rpc::{types::ProofModuleInfo, ProofModuleServer, FATAL_JSONRPC_ERROR_CODE},
+    rpc::{
+        types::{ModuleHealth, ProofModuleInfo},
+        ProofModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
     types::ProofType,
 };
 
@@ -77,11 +81,12 @@ impl ProofModule<IbcUnion> for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
 
+    #[schemars(with = "String")]
     pub ibc_commitments_object_id: ObjectID,
 
     pub sui_object_store_rpc_url: String,
@@ -188,6 +193,22 @@ impl ProofModuleServer<IbcUnion> for Module {
             ProofType::Membership,
         )))
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .sui_client
+            .read_api()
+            .get_latest_checkpoint_sequence_number()
+            .await
+            .map(|height| self.make_height(height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1,9 +1,11 @@
 // #![warn(clippy::unwrap_used)]
 
-use std::num::{NonZeroU32, NonZeroU8, ParseIntError};
+use std::{
+    num::{NonZeroU32, NonZeroU8, ParseIntError},
+    time::Duration,
+};
 
-use cometbft_rpc::rpc_types::Order;
-use cosmos_sdk_event::CosmosSdkEvent;
+use cometbft_rpc::{events::decode_events, rpc_types::Order};
 use futures::{stream::FuturesUnordered, TryStreamExt};
 use ibc_union_spec::{
     path::StorePath,
@@ -16,6 +18,8 @@ use jsonrpsee::{
     Extensions,
 };
 use protos::cosmwasm::wasm::v1::{QuerySmartContractStateRequest, QuerySmartContractStateResponse};
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{error, instrument, trace};
@@ -26,10 +30,15 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
+    abci::CachedAbciQuery,
+    anyhow, into_value, module_version,
     plugin::StateModule,
     primitives::{ChainId, ClientInfo, ClientType, IbcInterface, IbcSpec},
-    rpc::{rpc_error, types::StateModuleInfo, StateModuleServer, FATAL_JSONRPC_ERROR_CODE},
+    rpc::{
+        rpc_error,
+        types::{ModuleHealth, StateModuleInfo},
+        StateModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -43,28 +52,39 @@ pub enum Cmd {
     LatestHeight,
 }
 
+const SMART_CONTRACT_STATE_PATH: &str = "/cosmwasm.wasm.v1.Query/SmartContractState";
+
+/// How long to cache smart queries at a specific height. State at a given height is immutable, so
+/// this only bounds the memory used by the cache.
+const SMART_QUERY_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
 #[derive(Debug, Clone)]
 pub struct Module {
     pub chain_id: ChainId,
-    pub chain_revision: u64,
+    pub revision_start: Height,
 
     pub cometbft_client: cometbft_rpc::Client,
+    pub cached_abci_query: CachedAbciQuery,
 
     pub ibc_host_contract_address: Bech32<H256>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
     pub ibc_host_contract_address: Bech32<H256>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl StateModule<IbcUnion> for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: StateModuleInfo) -> anyhow::Result<Self> {
-        let cometbft_client = cometbft_rpc::Client::new(config.rpc_url).await?;
+        let cometbft_client = cometbft_rpc::Client::new(config.rpc_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
 
         let chain_id = cometbft_client.status().await?.node_info.network;
 
@@ -85,9 +105,11 @@ impl StateModule<IbcUnion> for Module {
             })?;
 
         Ok(Self {
+            cached_abci_query: CachedAbciQuery::new(cometbft_client.clone())
+                .with_height_ttl(SMART_CONTRACT_STATE_PATH, SMART_QUERY_CACHE_TTL),
             cometbft_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            revision_start: Height::new_with_revision(chain_revision, 0),
             ibc_host_contract_address: config.ibc_host_contract_address,
         })
     }
@@ -96,7 +118,7 @@ impl StateModule<IbcUnion> for Module {
 impl Module {
     #[must_use]
     pub fn make_height(&self, height: u64) -> Height {
-        Height::new_with_revision(self.chain_revision, height)
+        self.revision_start.with_height(height)
     }
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id, %channel_id, %packet_hash))]
@@ -141,11 +163,11 @@ impl Module {
             packet_timeout_timestamp,
             channel_id: _,
             packet_hash: _,
-        }) = res.tx_result.events.into_iter().find_map(|event| {
-            CosmosSdkEvent::<IbcEvent>::new(event).ok().and_then(|e| {
+        }) = decode_events::<IbcEvent>(res.tx_result.events)
+            .filter_map(Result::ok)
+            .find_map(|e| {
                 (e.contract_address.unwrap() == self.ibc_host_contract_address).then_some(e.event)
             })
-        })
         else {
             panic!()
         };
@@ -167,9 +189,9 @@ impl Module {
     ) -> RpcResult<Option<R>> {
         let query_data = serde_json::to_string(query).expect("serialization is infallible; qed;");
         let response = self
-            .cometbft_client
+            .cached_abci_query
             .grpc_abci_query::<_, QuerySmartContractStateResponse>(
-                "/cosmwasm.wasm.v1.Query/SmartContractState",
+                SMART_CONTRACT_STATE_PATH,
                 &QuerySmartContractStateRequest {
                     address: self.ibc_host_contract_address.to_string(),
                     query_data: query_data.clone().into_bytes(),
@@ -395,42 +417,38 @@ impl StateModuleServer<IbcUnion> for Module {
             }) => {
                 let query = format!("wasm-batch_send.batch_hash='{batch_hash}' AND wasm-batch_send.channel_id={channel_id}");
 
-                let res = self
+                let txs = self
                     .cometbft_client
-                    .tx_search(
+                    .tx_search_all(
                         query,
                         false,
-                        option_unwrap!(NonZeroU32::new(1)),
-                        option_unwrap!(NonZeroU8::new(1)),
+                        option_unwrap!(NonZeroU8::new(100)),
                         Order::Asc,
+                        None,
                     )
                     .await
                     .map_err(rpc_error("error querying packet by packet hash", None))?;
 
-                if res.total_count < 2 {
+                if txs.len() < 2 {
                     return Err(ErrorObject::owned(
                         -1,
                         format!(
                             "error querying for batch {batch_hash}, \
                             expected at least 2 events but found {}",
-                            res.total_count,
+                            txs.len(),
                         ),
                         None::<()>,
                     ));
                 }
 
-                let packets = res
-                    .txs
+                let packets = txs
                     .into_iter()
                     .flat_map(|res| {
-                        res.tx_result
-                            .events
-                            .into_iter()
-                            .filter_map(|event| {
-                                CosmosSdkEvent::<IbcEvent>::new(event).ok().and_then(|e| {
-                                    (e.contract_address.unwrap() == self.ibc_host_contract_address)
-                                        .then_some(e.event)
-                                })
+                        decode_events::<IbcEvent>(res.tx_result.events)
+                            .filter_map(Result::ok)
+                            .filter_map(|e| {
+                                (e.contract_address.unwrap() == self.ibc_host_contract_address)
+                                    .then_some(e.event)
                             })
                             .map(|event| match event {
                                 IbcEvent::WasmBatchSend {
@@ -505,6 +523,21 @@ impl StateModuleServer<IbcUnion> for Module {
                 .map(into_value),
         }
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .cometbft_client
+            .status()
+            .await
+            .map(|status| self.make_height(status.sync_info.latest_block_height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,6 +17,8 @@ use jsonrpsee::{
     types::{ErrorObject, ErrorObjectOwned},
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{error, instrument};
@@ -32,10 +34,14 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow, into_value, module_version,
     plugin::StateModule,
     primitives::{ChainId, ClientInfo, ClientType, IbcInterface},
-    rpc::{rpc_error, types::StateModuleInfo, StateModuleServer, FATAL_JSONRPC_ERROR_CODE},
+    rpc::{
+        rpc_error,
+        types::{ModuleHealth, StateModuleInfo},
+        StateModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
 };
 
 const IBC_STORE_PATH: &str = "store/ibc/key";
@@ -54,17 +60,19 @@ pub enum Cmd {
 #[derive(Debug, Clone)]
 pub struct Module {
     pub chain_id: ChainId,
-    pub chain_revision: u64,
+    pub revision_start: Height,
 
     pub tm_client: cometbft_rpc::Client,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
     #[serde(default = "default_max_drift")]
     pub max_drift: u64,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
 }
 
 fn default_max_drift() -> u64 {
@@ -75,7 +83,9 @@ impl StateModule<IbcClassic> for Module {
     type Config = Config;
 
     async fn new(config: Self::Config, info: StateModuleInfo) -> anyhow::Result<Self> {
-        let tm_client = cometbft_rpc::Client::new(config.rpc_url).await?;
+        let tm_client = cometbft_rpc::Client::new(config.rpc_url)
+            .await?
+            .with_rate_limit(config.rate_limit);
 
         let chain_id = tm_client.status().await?.node_info.network;
 
@@ -97,7 +107,7 @@ impl StateModule<IbcClassic> for Module {
         Ok(Self {
             tm_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            revision_start: Height::new_with_revision(chain_revision, 0),
         })
     }
 }
@@ -105,7 +115,7 @@ impl StateModule<IbcClassic> for Module {
 impl Module {
     #[must_use]
     pub fn make_height(&self, height: u64) -> Height {
-        Height::new_with_revision(self.chain_revision, height)
+        self.revision_start.with_height(height)
     }
 
     async fn abci_query(&self, path_string: &str, height: Height) -> RpcResult<QueryResponse> {
@@ -494,6 +504,21 @@ impl StateModuleServer<IbcClassic> for Module {
             }
         }
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .tm_client
+            .status()
+            .await
+            .map(|status| self.make_height(status.sync_info.latest_block_height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
 
 fn fatal_rpc_error<E: Into<Box<dyn Error>>>(
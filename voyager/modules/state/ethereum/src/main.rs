@@ -10,6 +10,7 @@ use alloy::{
     serde::WithOtherFields,
     sol_types::{SolCall, SolValue},
 };
+use evm_contract_check::ContractChecks;
 use futures::{stream::FuturesUnordered, TryStreamExt};
 use ibc_solidity::{
     ILightClient,
@@ -26,6 +27,8 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use rate_limiter::RateLimit;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug, info, instrument, trace};
@@ -35,10 +38,13 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    self, anyhow, into_value,
+    self, anyhow, into_value, module_version,
     plugin::StateModule,
     primitives::{ChainId, ClientInfo, ClientType, IbcInterface},
-    rpc::{types::StateModuleInfo, StateModuleServer, MISSING_STATE_ERROR_CODE},
+    rpc::{
+        types::{ModuleHealth, StateModuleInfo},
+        StateModuleServer, MISSING_STATE_ERROR_CODE,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -60,7 +66,7 @@ pub struct Module {
     pub client_address_cache: moka::future::Cache<u32, alloy::primitives::Address>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The address of the `IBCHandler` smart contract.
@@ -74,6 +80,12 @@ pub struct Config {
 
     #[serde(default)]
     pub max_cache_size: u32,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+
+    /// Checks on the deployed `IBCHandler` (and optionally zkgm), run before the module starts.
+    #[serde(default)]
+    pub contract_checks: Option<ContractChecks>,
 }
 
 impl StateModule<IbcUnion> for Module {
@@ -84,14 +96,21 @@ impl StateModule<IbcUnion> for Module {
             ProviderBuilder::new()
                 .layer(CacheLayer::new(config.max_cache_size))
                 .network::<AnyNetwork>()
-                .connect(&config.rpc_url)
-                .await?,
+                .connect_client(
+                    rate_limiter::alloy::connect(&config.rpc_url, config.rate_limit).await?,
+                ),
         );
 
         let chain_id = provider.get_chain_id().await?;
 
         info.ensure_chain_id(chain_id.to_string())?;
 
+        if let Some(contract_checks) = &config.contract_checks {
+            contract_checks
+                .verify(&provider, config.ibc_handler_address)
+                .await?;
+        }
+
         Ok(Module {
             chain_id: ChainId::new(chain_id.to_string()),
             ibc_handler_address: config.ibc_handler_address,
@@ -657,6 +676,21 @@ impl StateModuleServer<IbcUnion> for Module {
             metadata: Default::default(),
         })
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .provider
+            .get_block_number()
+            .await
+            .map(|height| self.make_height(height))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
 
 #[cfg(test)]
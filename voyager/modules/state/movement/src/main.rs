@@ -14,6 +14,7 @@ use jsonrpsee::{
     types::{ErrorObject, ErrorObjectOwned},
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug, instrument, trace};
@@ -27,10 +28,13 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow, into_value, module_version,
     plugin::StateModule,
     primitives::{ChainId, ClientInfo, ClientType, IbcInterface, Timestamp},
-    rpc::{types::StateModuleInfo, StateModuleServer},
+    rpc::{
+        types::{ModuleHealth, StateModuleInfo},
+        StateModuleServer,
+    },
 };
 
 pub mod events;
@@ -77,11 +81,12 @@ impl StateModule<IbcUnion> for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
     pub movement_rpc_url: String,
+    #[schemars(with = "String")]
     pub ibc_handler_address: Address,
 }
 
@@ -268,6 +273,21 @@ impl StateModuleServer<IbcUnion> for Module {
             }
         })
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .aptos_client
+            .get_index()
+            .await
+            .map(|ledger_info| self.make_height(ledger_info.inner().block_height.0))
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
 
 pub fn rest_error_to_rpc_error(e: RestError) -> ErrorObjectOwned {
@@ -8,6 +8,7 @@ use jsonrpsee::{
     core::{async_trait, RpcResult},
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sui_sdk::{
@@ -25,12 +26,16 @@ use unionlabs::{
     encoding::{Bcs, DecodeAs as _},
     ibc::core::client::height::Height,
     primitives::Bytes,
+    ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow, into_value, module_version,
     plugin::StateModule,
     primitives::{ChainId, ClientInfo, ClientType, IbcInterface},
-    rpc::{types::StateModuleInfo, StateModuleServer},
+    rpc::{
+        types::{ModuleHealth, StateModuleInfo},
+        StateModuleServer,
+    },
 };
 
 #[tokio::main(flavor = "multi_thread")]
@@ -79,11 +84,13 @@ impl StateModule<IbcUnion> for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub rpc_url: String,
+    #[schemars(with = "String")]
     pub ibc_store: ObjectID,
+    #[schemars(with = "String")]
     pub ibc_contract: ObjectID,
 }
 
@@ -184,6 +191,22 @@ impl StateModuleServer<IbcUnion> for Module {
             _ => todo!(),
         })
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn health(&self, _: &Extensions) -> RpcResult<ModuleHealth> {
+        let latest_height = self
+            .sui_client
+            .read_api()
+            .get_latest_checkpoint_sequence_number()
+            .await
+            .map(Height::new)
+            .map_err(ErrorReporter);
+
+        Ok(ModuleHealth::from_latest_height(
+            module_version!(),
+            latest_height,
+        ))
+    }
 }
 
 struct SuiQuery<'a> {
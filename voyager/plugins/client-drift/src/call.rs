@@ -0,0 +1,43 @@
+use ibc_union_spec::ClientId;
+use macros::model;
+use voyager_sdk::{
+    primitives::{ChainId, IbcSpecId},
+    types::RawClientId,
+    vm::BoxDynError,
+};
+
+#[model]
+pub enum ModuleCall {
+    WatchClient(WatchClient),
+    WatchChain(WatchChain),
+}
+
+/// Snapshot the on-chain parameters of a client and record how they changed since the previous
+/// snapshot.
+///
+/// This message reschedules itself, so it only needs to be enqueued once per client.
+#[model]
+#[derive(clap::Args)]
+pub struct WatchClient {
+    #[arg(value_parser(|s: &str| Ok::<_, BoxDynError>(ChainId::new(s.to_owned()))))]
+    pub chain_id: ChainId,
+    #[arg(value_parser(|s: &str| Ok::<_, BoxDynError>(IbcSpecId::new(s.to_owned()))))]
+    pub ibc_spec_id: IbcSpecId,
+    pub client_id: RawClientId,
+}
+
+/// Discover the `ibc-union` clients of a chain, and watch every new client with [`WatchClient`].
+/// `ibc-union` client ids are sequential, so every id from `next_client_id` on is watched until the
+/// first client that does not exist (yet).
+///
+/// This message reschedules itself, so it only needs to be enqueued once per chain.
+#[model]
+#[derive(clap::Args)]
+pub struct WatchChain {
+    #[arg(value_parser(|s: &str| Ok::<_, BoxDynError>(ChainId::new(s.to_owned()))))]
+    pub chain_id: ChainId,
+    /// The first client id that was not discovered yet. Defaults to the client after the highest
+    /// client of the chain in the history.
+    #[arg(long)]
+    pub next_client_id: Option<ClientId>,
+}
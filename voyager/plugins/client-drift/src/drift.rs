@@ -0,0 +1,323 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use unionlabs::ibc::core::client::height::Height;
+
+/// The parameters of a client at one point in time: the counterparty height it has been updated to,
+/// and its client state as decoded by the client module of its client type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub counterparty_height: Height,
+    pub client_state: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The counterparty height moved to another revision, went backwards, or advanced by at least
+    /// the configured `min_height_jump`.
+    LatestHeight,
+    /// The frozen height of the client was set (or cleared).
+    FrozenHeight,
+    /// The trusting period of the client changed, i.e. after a client upgrade.
+    TrustingPeriod,
+    /// Any other field of the client state changed.
+    Parameter,
+}
+
+impl ChangeKind {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::LatestHeight => "latest_height",
+            ChangeKind::FrozenHeight => "frozen_height",
+            ChangeKind::TrustingPeriod => "trusting_period",
+            ChangeKind::Parameter => "parameter",
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ChangeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "latest_height" => Ok(ChangeKind::LatestHeight),
+            "frozen_height" => Ok(ChangeKind::FrozenHeight),
+            "trusting_period" => Ok(ChangeKind::TrustingPeriod),
+            "parameter" => Ok(ChangeKind::Parameter),
+            _ => Err(format!("unknown change kind `{s}`")),
+        }
+    }
+}
+
+/// A change of a single field of a client between two snapshots. Fields that were added or removed
+/// are `null` on the side they are missing from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// The path of the field in the client state (i.e. `frozen_height.revision_height`), or
+    /// `counterparty_height` for [`ChangeKind::LatestHeight`].
+    pub path: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// The changes between two snapshots of a client.
+///
+/// Fields of the client state that track the latest state of the counterparty (any field with
+/// `latest` in its path) change with every update and are not reported; the counterparty height is
+/// reported as [`ChangeKind::LatestHeight`] instead, but only if it jumped.
+#[must_use]
+pub fn diff(old: &Snapshot, new: &Snapshot, min_height_jump: u64) -> Vec<Change> {
+    let mut changes = vec![];
+
+    let (old_height, new_height) = (old.counterparty_height, new.counterparty_height);
+
+    if old_height != new_height
+        && (old_height.revision() != new_height.revision()
+            || new_height.height() < old_height.height()
+            || new_height.height() - old_height.height() >= min_height_jump)
+    {
+        changes.push(Change {
+            kind: ChangeKind::LatestHeight,
+            path: "counterparty_height".to_owned(),
+            old: Value::String(old_height.to_string()),
+            new: Value::String(new_height.to_string()),
+        });
+    }
+
+    let old_fields = flatten(&old.client_state);
+    let mut new_fields = flatten(&new.client_state);
+
+    for (path, old_value) in old_fields {
+        let new_value = new_fields.remove(&path).unwrap_or(Value::Null);
+
+        if old_value != new_value {
+            push_field_change(&mut changes, path, old_value, new_value);
+        }
+    }
+
+    // fields that only exist in the new client state
+    for (path, new_value) in new_fields {
+        push_field_change(&mut changes, path, Value::Null, new_value);
+    }
+
+    changes
+}
+
+fn push_field_change(changes: &mut Vec<Change>, path: String, old: Value, new: Value) {
+    if let Some(kind) = classify(&path) {
+        changes.push(Change {
+            kind,
+            path,
+            old,
+            new,
+        });
+    }
+}
+
+fn classify(path: &str) -> Option<ChangeKind> {
+    let path = path.to_lowercase();
+
+    if path.contains("latest") {
+        None
+    } else if path.contains("frozen") {
+        Some(ChangeKind::FrozenHeight)
+    } else if path.contains("trusting") {
+        Some(ChangeKind::TrustingPeriod)
+    } else {
+        Some(ChangeKind::Parameter)
+    }
+}
+
+/// The leaves of `value` by their dot-separated path, with array elements keyed by their index.
+fn flatten(value: &Value) -> BTreeMap<String, Value> {
+    fn go(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+        let path = |key: &dyn fmt::Display| match prefix {
+            "" => key.to_string(),
+            prefix => format!("{prefix}.{key}"),
+        };
+
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    go(&path(key), value, out);
+                }
+            }
+            Value::Array(values) if !values.is_empty() => {
+                for (index, value) in values.iter().enumerate() {
+                    go(&path(&index), value, out);
+                }
+            }
+            leaf => {
+                out.insert(prefix.to_owned(), leaf.clone());
+            }
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    go("", value, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn snapshot(height: u64, client_state: Value) -> Snapshot {
+        Snapshot {
+            counterparty_height: Height::new_with_revision(1, height),
+            client_state,
+        }
+    }
+
+    fn cometbls(latest_height: u64, frozen_height: u64, trusting_period: u64) -> Value {
+        json!({
+            "chain_id": "union-1",
+            "trusting_period": trusting_period,
+            "max_clock_drift": 600_000_000_000_u64,
+            "frozen_height": { "revision_number": 0, "revision_height": frozen_height },
+            "latest_height": { "revision_number": 1, "revision_height": latest_height },
+            "contract_address": "0x00",
+        })
+    }
+
+    #[test]
+    fn regular_updates_are_not_reported() {
+        assert_eq!(
+            diff(
+                &snapshot(100, cometbls(100, 0, 1000)),
+                &snapshot(150, cometbls(150, 0, 1000)),
+                1000,
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn height_jumps_are_reported() {
+        let changes = diff(
+            &snapshot(100, cometbls(100, 0, 1000)),
+            &snapshot(1100, cometbls(1100, 0, 1000)),
+            1000,
+        );
+
+        assert_eq!(
+            changes,
+            vec![Change {
+                kind: ChangeKind::LatestHeight,
+                path: "counterparty_height".to_owned(),
+                old: json!("1-100"),
+                new: json!("1-1100"),
+            }]
+        );
+
+        // revision changes and heights going backwards are always reported
+        assert_eq!(
+            diff(
+                &snapshot(100, cometbls(100, 0, 1000)),
+                &Snapshot {
+                    counterparty_height: Height::new_with_revision(2, 101),
+                    client_state: cometbls(101, 0, 1000),
+                },
+                1000,
+            )
+            .len(),
+            1
+        );
+        assert_eq!(
+            diff(
+                &snapshot(100, cometbls(100, 0, 1000)),
+                &snapshot(99, cometbls(99, 0, 1000)),
+                1000,
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn frozen_and_trusting_period_changes_are_classified() {
+        let changes = diff(
+            &snapshot(100, cometbls(100, 0, 1000)),
+            &snapshot(100, cometbls(100, 90, 2000)),
+            1000,
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                Change {
+                    kind: ChangeKind::FrozenHeight,
+                    path: "frozen_height.revision_height".to_owned(),
+                    old: json!(0),
+                    new: json!(90),
+                },
+                Change {
+                    kind: ChangeKind::TrustingPeriod,
+                    path: "trusting_period".to_owned(),
+                    old: json!(1000),
+                    new: json!(2000),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn added_and_removed_fields_are_parameters() {
+        let changes = diff(
+            &snapshot(100, json!({ "a": 1, "b": [1, 2] })),
+            &snapshot(100, json!({ "b": [1], "c": { "d": true } })),
+            1000,
+        );
+
+        assert_eq!(
+            changes,
+            vec![
+                Change {
+                    kind: ChangeKind::Parameter,
+                    path: "a".to_owned(),
+                    old: json!(1),
+                    new: Value::Null,
+                },
+                Change {
+                    kind: ChangeKind::Parameter,
+                    path: "b.1".to_owned(),
+                    old: json!(2),
+                    new: Value::Null,
+                },
+                Change {
+                    kind: ChangeKind::Parameter,
+                    path: "c.d".to_owned(),
+                    old: Value::Null,
+                    new: json!(true),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn change_kind_roundtrips_through_str() {
+        for kind in [
+            ChangeKind::LatestHeight,
+            ChangeKind::FrozenHeight,
+            ChangeKind::TrustingPeriod,
+            ChangeKind::Parameter,
+        ] {
+            assert_eq!(kind.as_str().parse::<ChangeKind>(), Ok(kind));
+            assert_eq!(
+                serde_json::to_value(kind).unwrap(),
+                Value::String(kind.to_string())
+            );
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use futures::TryStreamExt;
+use ibc_union_spec::ClientId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{postgres::PgPoolOptions, Executor, PgPool, Row};
+use tracing::{info_span, trace, Instrument};
+use unionlabs::ibc::core::client::height::Height;
+use voyager_sdk::{
+    anyhow,
+    primitives::{ChainId, IbcSpecId, Timestamp},
+    types::RawClientId,
+};
+
+use crate::drift::{Change, ChangeKind, Snapshot};
+
+/// The latest snapshot of every watched client, and the changes between consecutive snapshots.
+#[derive(Debug, Clone)]
+pub struct History {
+    db: PgPool,
+}
+
+/// A change of a client, as recorded in the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftEntry {
+    /// The id of the change, to request the changes after it.
+    pub id: i64,
+    pub chain_id: ChainId,
+    pub ibc_spec_id: IbcSpecId,
+    pub client_id: RawClientId,
+    /// The counterparty height of the client when the change was observed.
+    pub counterparty_height: Height,
+    #[serde(flatten)]
+    pub change: Change,
+    pub observed_at: Timestamp,
+}
+
+impl History {
+    pub async fn new(db_url: &str) -> anyhow::Result<Self> {
+        let db = PgPoolOptions::new().connect(db_url).await?;
+
+        db.execute_many(
+            r#"
+            CREATE TABLE IF NOT EXISTS
+              client_drift_snapshots (
+                chain_id TEXT NOT NULL,
+                ibc_spec_id TEXT NOT NULL,
+                client_id TEXT NOT NULL,
+                counterparty_height TEXT NOT NULL,
+                client_state JSONB NOT NULL,
+                updated_at timestamptz NOT NULL DEFAULT now(),
+                PRIMARY KEY (chain_id, ibc_spec_id, client_id)
+              );
+
+            CREATE TABLE IF NOT EXISTS
+              client_drift_changes (
+                id BIGSERIAL PRIMARY KEY,
+                chain_id TEXT NOT NULL,
+                ibc_spec_id TEXT NOT NULL,
+                client_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                path TEXT NOT NULL,
+                old_value JSONB NOT NULL,
+                new_value JSONB NOT NULL,
+                counterparty_height TEXT NOT NULL,
+                observed_at timestamptz NOT NULL DEFAULT now()
+              );
+
+            CREATE INDEX IF NOT EXISTS client_drift_changes_client_observed_at_idx ON client_drift_changes(chain_id, ibc_spec_id, client_id, observed_at);
+            "#,
+        )
+        .try_for_each(|result| async move {
+            trace!("rows affected: {}", result.rows_affected());
+            Ok(())
+        })
+        .instrument(info_span!("db_init"))
+        .await?;
+
+        Ok(Self { db })
+    }
+
+    /// The latest snapshot of the client, if it was snapshotted before.
+    pub async fn latest_snapshot(
+        &self,
+        chain_id: &ChainId,
+        ibc_spec_id: &IbcSpecId,
+        client_id: &RawClientId,
+    ) -> Result<Option<Snapshot>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            SELECT
+                counterparty_height,
+                client_state::TEXT AS client_state
+            FROM
+                client_drift_snapshots
+            WHERE
+                chain_id = $1
+                AND ibc_spec_id = $2
+                AND client_id = $3
+            "#,
+        )
+        .bind(chain_id.to_string())
+        .bind(ibc_spec_id.to_string())
+        .bind(client_id.to_string())
+        .try_map(|row| {
+            Ok(Snapshot {
+                counterparty_height: decode_height(&row, "counterparty_height")?,
+                client_state: decode_json(&row, "client_state")?,
+            })
+        })
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Replace the latest snapshot of the client with `snapshot`, and record the `changes` since
+    /// the previous one.
+    pub async fn record(
+        &self,
+        chain_id: &ChainId,
+        ibc_spec_id: &IbcSpecId,
+        client_id: &RawClientId,
+        snapshot: &Snapshot,
+        changes: &[Change],
+    ) -> Result<(), sqlx::Error> {
+        let mut db_tx = self.db.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO
+                client_drift_snapshots (chain_id, ibc_spec_id, client_id, counterparty_height, client_state)
+            VALUES
+                ($1, $2, $3, $4, $5::JSONB)
+            ON CONFLICT (chain_id, ibc_spec_id, client_id) DO UPDATE SET
+                counterparty_height = excluded.counterparty_height,
+                client_state = excluded.client_state,
+                updated_at = now()
+            "#,
+        )
+        .bind(chain_id.to_string())
+        .bind(ibc_spec_id.to_string())
+        .bind(client_id.to_string())
+        .bind(snapshot.counterparty_height.to_string())
+        .bind(snapshot.client_state.to_string())
+        .execute(&mut *db_tx)
+        .await?;
+
+        for change in changes {
+            sqlx::query(
+                r#"
+                INSERT INTO
+                    client_drift_changes (chain_id, ibc_spec_id, client_id, kind, path, old_value, new_value, counterparty_height)
+                VALUES
+                    ($1, $2, $3, $4, $5, $6::JSONB, $7::JSONB, $8)
+                "#,
+            )
+            .bind(chain_id.to_string())
+            .bind(ibc_spec_id.to_string())
+            .bind(client_id.to_string())
+            .bind(change.kind.as_str())
+            .bind(&change.path)
+            .bind(change.old.to_string())
+            .bind(change.new.to_string())
+            .bind(snapshot.counterparty_height.to_string())
+            .execute(&mut *db_tx)
+            .await?;
+        }
+
+        db_tx.commit().await
+    }
+
+    /// The `ibc-union` client after the highest client of the chain in the history, or the first
+    /// client if none of its clients were snapshotted yet.
+    pub async fn next_union_client_id(&self, chain_id: &ChainId) -> Result<ClientId, sqlx::Error> {
+        let max = sqlx::query(
+            r#"
+            SELECT
+                max(client_id::BIGINT) AS max
+            FROM
+                client_drift_snapshots
+            WHERE
+                chain_id = $1
+                AND ibc_spec_id = $2
+            "#,
+        )
+        .bind(chain_id.to_string())
+        .bind(IbcSpecId::UNION)
+        .fetch_one(&self.db)
+        .await?
+        .get::<Option<i64>, _>("max");
+
+        u32::try_from(max.unwrap_or(0) + 1)
+            .ok()
+            .and_then(ClientId::from_raw)
+            .ok_or_else(|| decode_error("max", "invalid client id"))
+    }
+
+    /// At most `limit` recorded changes after the change `after`, oldest first, optionally only
+    /// those of `chain_id` and `client_id` and within the last `since_seconds` seconds.
+    pub async fn changes(
+        &self,
+        chain_id: Option<&ChainId>,
+        client_id: Option<&RawClientId>,
+        since_seconds: Option<i64>,
+        after: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<DriftEntry>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            SELECT
+                id,
+                chain_id,
+                ibc_spec_id,
+                client_id,
+                kind,
+                path,
+                old_value::TEXT AS old_value,
+                new_value::TEXT AS new_value,
+                counterparty_height,
+                extract(epoch FROM observed_at)::BIGINT AS observed_at
+            FROM
+                client_drift_changes
+            WHERE
+                ($1::TEXT IS NULL OR chain_id = $1)
+                AND ($2::TEXT IS NULL OR client_id = $2)
+                AND ($3::BIGINT IS NULL OR observed_at >= now() - $3 * interval '1 second')
+                AND ($4::BIGINT IS NULL OR id > $4)
+            ORDER BY
+                id
+            LIMIT
+                $5
+            "#,
+        )
+        .bind(chain_id.map(|chain_id| chain_id.to_string()))
+        .bind(client_id.map(|client_id| client_id.to_string()))
+        .bind(since_seconds)
+        .bind(after)
+        .bind(limit)
+        .try_map(|row| {
+            Ok(DriftEntry {
+                id: row.get("id"),
+                chain_id: ChainId::new(row.get::<String, _>("chain_id")),
+                ibc_spec_id: IbcSpecId::new(row.get::<String, _>("ibc_spec_id")),
+                client_id: RawClientId::from(row.get::<String, _>("client_id")),
+                counterparty_height: decode_height(&row, "counterparty_height")?,
+                change: Change {
+                    kind: row
+                        .get::<String, _>("kind")
+                        .parse::<ChangeKind>()
+                        .map_err(|e| decode_error("kind", e))?,
+                    path: row.get("path"),
+                    old: decode_json(&row, "old_value")?,
+                    new: decode_json(&row, "new_value")?,
+                },
+                observed_at: Timestamp::from_secs(
+                    row.get::<i64, _>("observed_at").try_into().unwrap_or(0),
+                ),
+            })
+        })
+        .fetch_all(&self.db)
+        .await
+    }
+}
+
+fn decode_height(row: &sqlx::postgres::PgRow, column: &str) -> Result<Height, sqlx::Error> {
+    row.get::<String, _>(column)
+        .parse()
+        .map_err(|e| decode_error(column, e))
+}
+
+fn decode_json(row: &sqlx::postgres::PgRow, column: &str) -> Result<Value, sqlx::Error> {
+    serde_json::from_str(&row.get::<String, _>(column)).map_err(|e| decode_error(column, e))
+}
+
+fn decode_error(column: &str, error: impl std::fmt::Display) -> sqlx::Error {
+    sqlx::Error::ColumnDecode {
+        index: column.to_owned(),
+        source: error.to_string().into(),
+    }
+}
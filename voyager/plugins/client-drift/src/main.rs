@@ -0,0 +1,460 @@
+use std::collections::VecDeque;
+
+use ibc_classic_spec::IbcClassic;
+use ibc_union_spec::{path::ClientStatePath, IbcUnion};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    proc_macros::rpc,
+    types::ErrorObject,
+    Extensions, MethodsError,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, error, info, instrument, warn};
+use unionlabs::{never::Never, ErrorReporter};
+use voyager_sdk::{
+    anyhow::{self, bail},
+    into_value,
+    message::{data::Data, PluginMessage, VoyagerMessage},
+    plugin::Plugin,
+    primitives::{ChainId, ClientInfo, IbcSpecId, QueryHeight},
+    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
+    types::RawClientId,
+    vm::{call, conc, defer, now, pass::PassResult, seq, Op},
+    ExtensionsExt, VoyagerClient,
+};
+
+use crate::{
+    call::{ModuleCall, WatchChain, WatchClient},
+    drift::{diff, ChangeKind, Snapshot},
+    history::{DriftEntry, History},
+};
+
+pub mod call;
+pub mod drift;
+pub mod history;
+
+#[tokio::main]
+async fn main() {
+    Module::run().await
+}
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub history: History,
+    pub interval: u64,
+    pub min_height_jump: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The database the history of the watched clients is recorded in.
+    pub db_url: String,
+    /// The interval (in seconds) at which each watched client is snapshotted.
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    /// The minimum number of blocks the counterparty height of a client has to advance by between
+    /// two snapshots to be recorded as a change. Regular updates are not recorded.
+    #[serde(default = "default_min_height_jump")]
+    pub min_height_jump: u64,
+}
+
+fn default_interval() -> u64 {
+    10 * 60
+}
+
+fn default_min_height_jump() -> u64 {
+    100_000
+}
+
+/// Number of changes returned by `clientDrift` when the request does not specify a limit.
+const DEFAULT_DRIFT_LIMIT: i64 = 100;
+/// Maximum number of changes returned by one `clientDrift` request.
+const MAX_DRIFT_LIMIT: i64 = 1000;
+
+impl Plugin for Module {
+    type Call = ModuleCall;
+    type Callback = Never;
+
+    type Config = Config;
+    type Cmd = Cmd;
+
+    async fn new(config: Self::Config) -> anyhow::Result<Self> {
+        if config.interval == 0 {
+            bail!("interval must be non-zero");
+        }
+
+        Ok(Self {
+            history: History::new(&config.db_url).await?,
+            interval: config.interval,
+            min_height_jump: config.min_height_jump,
+        })
+    }
+
+    fn info(_: Self::Config) -> PluginInfo {
+        PluginInfo {
+            name: plugin_name(),
+            // never interested in any messages since this plugin does not utilize a queue
+            interest_filter: "null".to_owned(),
+        }
+    }
+
+    async fn cmd(_: Self::Config, cmd: Self::Cmd) {
+        match cmd {
+            Cmd::MakeMessage(msg) => {
+                let op = call::<VoyagerMessage>(PluginMessage::new(
+                    plugin_name(),
+                    ModuleCall::WatchClient(msg),
+                ));
+
+                println!("{}", into_value(op));
+            }
+            Cmd::MakeChainMessage(msg) => {
+                let op = call::<VoyagerMessage>(PluginMessage::new(
+                    plugin_name(),
+                    ModuleCall::WatchChain(msg),
+                ));
+
+                println!("{}", into_value(op));
+            }
+        }
+    }
+}
+
+#[derive(clap::Parser)]
+pub enum Cmd {
+    /// Make the message that watches a single client.
+    MakeMessage(WatchClient),
+    /// Make the message that discovers and watches the `ibc-union` clients of a chain.
+    MakeChainMessage(WatchChain),
+}
+
+fn plugin_name() -> String {
+    pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+    PLUGIN_NAME.to_owned()
+}
+
+impl Module {
+    /// The decoded client state of the watched client.
+    async fn client_state(
+        &self,
+        voyager_client: &VoyagerClient,
+        chain_id: &ChainId,
+        ibc_spec_id: &IbcSpecId,
+        client_id: &RawClientId,
+        client_info: &ClientInfo,
+    ) -> RpcResult<Value> {
+        let invalid_client_id = |e| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                ErrorReporter(e).with_message("invalid client id"),
+                None::<()>,
+            )
+        };
+
+        match ibc_spec_id.as_str() {
+            IbcSpecId::CLASSIC => {
+                let client_state = voyager_client
+                    .query_ibc_state(
+                        chain_id.clone(),
+                        QueryHeight::Latest,
+                        ibc_classic_spec::ClientStatePath {
+                            client_id: client_id
+                                .clone()
+                                .decode_spec::<IbcClassic>()
+                                .map_err(invalid_client_id)?,
+                        },
+                    )
+                    .await?;
+
+                voyager_client
+                    .decode_client_state::<IbcClassic, Value>(
+                        client_info.client_type.clone(),
+                        client_info.ibc_interface.clone(),
+                        client_state,
+                    )
+                    .await
+            }
+            IbcSpecId::UNION => {
+                let client_state = voyager_client
+                    .query_ibc_state(
+                        chain_id.clone(),
+                        QueryHeight::Latest,
+                        ClientStatePath {
+                            client_id: client_id
+                                .clone()
+                                .decode_spec::<IbcUnion>()
+                                .map_err(invalid_client_id)?,
+                        },
+                    )
+                    .await?;
+
+                voyager_client
+                    .decode_client_state::<IbcUnion, Value>(
+                        client_info.client_type.clone(),
+                        client_info.ibc_interface.clone(),
+                        client_state,
+                    )
+                    .await
+            }
+            _ => Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("unsupported ibc spec {ibc_spec_id}"),
+                None::<()>,
+            )),
+        }
+    }
+
+    #[instrument(skip_all, fields(%chain_id, %ibc_spec_id, client_id = %client_id.as_raw()))]
+    async fn watch_client(
+        &self,
+        voyager_client: &VoyagerClient,
+        WatchClient {
+            chain_id,
+            ibc_spec_id,
+            client_id,
+        }: WatchClient,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let client_state_meta = voyager_client
+            .client_state_meta_raw(
+                chain_id.clone(),
+                ibc_spec_id.clone(),
+                QueryHeight::Latest,
+                client_id.clone(),
+            )
+            .await?;
+
+        let client_info = voyager_client
+            .client_info_raw(chain_id.clone(), ibc_spec_id.clone(), client_id.clone())
+            .await?;
+
+        let snapshot = Snapshot {
+            counterparty_height: client_state_meta.counterparty_height,
+            client_state: self
+                .client_state(
+                    voyager_client,
+                    &chain_id,
+                    &ibc_spec_id,
+                    &client_id,
+                    &client_info,
+                )
+                .await?,
+        };
+
+        let changes = match self
+            .history
+            .latest_snapshot(&chain_id, &ibc_spec_id, &client_id)
+            .await
+            .map_err(|e| db_error(e, "error reading the latest snapshot"))?
+        {
+            Some(previous) => diff(&previous, &snapshot, self.min_height_jump),
+            None => {
+                info!(
+                    counterparty_height = %snapshot.counterparty_height,
+                    "first snapshot of client, recording baseline"
+                );
+
+                vec![]
+            }
+        };
+
+        for change in &changes {
+            match change.kind {
+                ChangeKind::FrozenHeight => warn!(
+                    kind = %change.kind,
+                    path = %change.path,
+                    old = %change.old,
+                    new = %change.new,
+                    "client frozen height changed"
+                ),
+                _ => info!(
+                    kind = %change.kind,
+                    path = %change.path,
+                    old = %change.old,
+                    new = %change.new,
+                    "client parameter changed"
+                ),
+            }
+        }
+
+        self.history
+            .record(&chain_id, &ibc_spec_id, &client_id, &snapshot, &changes)
+            .await
+            .map_err(|e| db_error(e, "error recording snapshot"))?;
+
+        debug!(
+            counterparty_height = %snapshot.counterparty_height,
+            changes = changes.len(),
+            "recorded snapshot"
+        );
+
+        Ok(seq([
+            defer(now() + self.interval),
+            call(PluginMessage::new(
+                plugin_name(),
+                ModuleCall::WatchClient(WatchClient {
+                    chain_id,
+                    ibc_spec_id,
+                    client_id,
+                }),
+            )),
+        ]))
+    }
+
+    #[instrument(skip_all, fields(%chain_id))]
+    async fn watch_chain(
+        &self,
+        voyager_client: &VoyagerClient,
+        WatchChain {
+            chain_id,
+            next_client_id,
+        }: WatchChain,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let mut client_id = match next_client_id {
+            Some(client_id) => client_id,
+            None => self
+                .history
+                .next_union_client_id(&chain_id)
+                .await
+                .map_err(|e| db_error(e, "error reading the next client id"))?,
+        };
+
+        let mut watch = vec![];
+
+        while voyager_client
+            .maybe_query_ibc_state(
+                chain_id.clone(),
+                QueryHeight::Latest,
+                ClientStatePath { client_id },
+            )
+            .await?
+            .state
+            .is_some()
+        {
+            info!(%client_id, "discovered client");
+
+            watch.push(call(PluginMessage::new(
+                plugin_name(),
+                ModuleCall::WatchClient(WatchClient {
+                    chain_id: chain_id.clone(),
+                    ibc_spec_id: IbcSpecId::new(IbcSpecId::UNION),
+                    client_id: RawClientId::new(client_id),
+                }),
+            )));
+
+            client_id = client_id.checked_add(1).ok_or_else(|| {
+                ErrorObject::owned(FATAL_JSONRPC_ERROR_CODE, "client id overflow", None::<()>)
+            })?;
+        }
+
+        debug!(%client_id, discovered = watch.len(), "discovered clients");
+
+        Ok(conc(watch.into_iter().chain([seq([
+            defer(now() + self.interval),
+            call(PluginMessage::new(
+                plugin_name(),
+                ModuleCall::WatchChain(WatchChain {
+                    chain_id,
+                    next_client_id: Some(client_id),
+                }),
+            )),
+        ])])))
+    }
+}
+
+fn db_error(e: sqlx::Error, message: &str) -> ErrorObject<'static> {
+    ErrorObject::owned(-1, ErrorReporter(e).with_message(message), None::<()>)
+}
+
+#[rpc(server)]
+trait ClientDriftPlugin {
+    /// The recorded changes of the watched clients, oldest first, optionally only those of
+    /// `chain_id` and `client_id` and within the last `since_seconds` seconds. At most `limit`
+    /// changes (100 by default, at most 1000) are returned; the next page starts `after` the id of
+    /// the last change of the previous one.
+    #[method(name = "clientDrift")]
+    async fn client_drift(
+        &self,
+        chain_id: Option<ChainId>,
+        client_id: Option<RawClientId>,
+        since_seconds: Option<i64>,
+        after: Option<i64>,
+        limit: Option<i64>,
+    ) -> RpcResult<Vec<DriftEntry>>;
+}
+
+#[async_trait]
+impl ClientDriftPluginServer for Module {
+    async fn client_drift(
+        &self,
+        chain_id: Option<ChainId>,
+        client_id: Option<RawClientId>,
+        since_seconds: Option<i64>,
+        after: Option<i64>,
+        limit: Option<i64>,
+    ) -> RpcResult<Vec<DriftEntry>> {
+        self.history
+            .changes(
+                chain_id.as_ref(),
+                client_id.as_ref(),
+                since_seconds,
+                after,
+                limit
+                    .unwrap_or(DEFAULT_DRIFT_LIMIT)
+                    .clamp(1, MAX_DRIFT_LIMIT),
+            )
+            .await
+            .map_err(|e| db_error(e, "error querying client drift"))
+    }
+}
+
+#[async_trait]
+impl PluginServer<ModuleCall, Never> for Module {
+    async fn run_pass(
+        &self,
+        _: &Extensions,
+        msgs: Vec<Op<VoyagerMessage>>,
+    ) -> RpcResult<PassResult<VoyagerMessage>> {
+        error!(?msgs, "this plugin does not utilize a queue");
+
+        Ok(PassResult::default())
+    }
+
+    async fn call(&self, e: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
+        match msg {
+            ModuleCall::WatchClient(msg) => self.watch_client(e.voyager_client()?, msg).await,
+            ModuleCall::WatchChain(msg) => self.watch_chain(e.voyager_client()?, msg).await,
+        }
+    }
+
+    async fn callback(
+        &self,
+        _: &Extensions,
+        cb: Never,
+        _data: VecDeque<Data>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        match cb {}
+    }
+
+    async fn custom(&self, _: &Extensions, method: String, params: Vec<Value>) -> RpcResult<Value> {
+        ClientDriftPluginServer::into_rpc(self.clone())
+            .call::<Vec<Value>, Value>(&method, params)
+            .await
+            .map_err(|e| match e {
+                MethodsError::Parse(error) => ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    ErrorReporter(error).with_message("error parsing args"),
+                    None::<()>,
+                ),
+                MethodsError::JsonRpc(error_object) => error_object,
+                MethodsError::InvalidSubscriptionId(_) => ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    "subscriptions are not supported",
+                    None::<()>,
+                ),
+            })
+    }
+}
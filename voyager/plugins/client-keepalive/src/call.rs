@@ -0,0 +1,25 @@
+use macros::model;
+use voyager_sdk::{
+    primitives::{ChainId, IbcSpecId},
+    types::RawClientId,
+    vm::BoxDynError,
+};
+
+#[model]
+pub enum ModuleCall {
+    KeepClientAlive(KeepClientAlive),
+}
+
+/// Keep a client from expiring, by updating it once the configured fraction of its trusting period
+/// has elapsed since its latest consensus state.
+///
+/// This message reschedules itself, so it only needs to be enqueued once per client.
+#[model]
+#[derive(clap::Args)]
+pub struct KeepClientAlive {
+    #[arg(value_parser(|s: &str| Ok::<_, BoxDynError>(ChainId::new(s.to_owned()))))]
+    pub chain_id: ChainId,
+    #[arg(value_parser(|s: &str| Ok::<_, BoxDynError>(IbcSpecId::new(s.to_owned()))))]
+    pub ibc_spec_id: IbcSpecId,
+    pub client_id: RawClientId,
+}
@@ -0,0 +1,88 @@
+use serde_json::Value;
+use voyager_sdk::primitives::{Duration, Timestamp};
+
+/// The trusting period in a client state, as decoded by the client module of its client type.
+/// Client types without a trusting period (i.e. clients that do not expire) return `None`.
+#[must_use]
+pub fn trusting_period(client_state: &Value) -> Option<Duration> {
+    match client_state.get("trusting_period")? {
+        // protobuf durations (i.e. "1209600s"), or nanoseconds as a string
+        Value::String(raw) => raw
+            .parse::<unionlabs::google::protobuf::duration::Duration>()
+            .ok()
+            .and_then(|duration| u64::try_from(duration.as_nanos().inner()).ok())
+            .map(Duration::from_nanos)
+            .or_else(|| raw.parse().ok()),
+        // nanoseconds
+        Value::Number(nanos) => nanos.as_u64().map(Duration::from_nanos),
+        _ => None,
+    }
+}
+
+/// The time after which a client should be updated, given the timestamp of its latest consensus
+/// state.
+#[must_use]
+pub fn update_deadline(
+    consensus_timestamp: Timestamp,
+    trusting_period: Duration,
+    trusting_period_fraction: f64,
+) -> Timestamp {
+    // lossy, but the deadline does not need to be exact to the nanosecond
+    let after = Duration::from_nanos(
+        (trusting_period.as_nanos() as f64 * trusting_period_fraction).round() as u64,
+    );
+
+    consensus_timestamp
+        .plus_duration(after)
+        .unwrap_or(Timestamp::from_nanos(u64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_trusting_period() {
+        // tendermint
+        assert_eq!(
+            trusting_period(&json!({ "trusting_period": "1209600s" })),
+            Some(Duration::from_secs(1_209_600))
+        );
+        assert_eq!(
+            trusting_period(&json!({ "trusting_period": "1.5s" })),
+            Some(Duration::from_nanos(1_500_000_000))
+        );
+        // cometbls
+        assert_eq!(
+            trusting_period(&json!({ "trusting_period": 1_982_880_000_000_000_u64 })),
+            Some(Duration::from_secs(1_982_880))
+        );
+        assert_eq!(
+            trusting_period(&json!({ "trusting_period": "1982880000000000" })),
+            Some(Duration::from_secs(1_982_880))
+        );
+        // ethereum
+        assert_eq!(trusting_period(&json!({ "chain_id": "1" })), None);
+        assert_eq!(trusting_period(&json!({ "trusting_period": "-1s" })), None);
+    }
+
+    #[test]
+    fn deadline_is_fraction_of_trusting_period() {
+        let consensus_timestamp = Timestamp::from_secs(1_000);
+
+        assert_eq!(
+            update_deadline(consensus_timestamp, Duration::from_secs(300), 2.0 / 3.0),
+            Timestamp::from_secs(1_200)
+        );
+        assert_eq!(
+            update_deadline(consensus_timestamp, Duration::from_secs(300), 1.0),
+            Timestamp::from_secs(1_300)
+        );
+        assert_eq!(
+            update_deadline(consensus_timestamp, Duration::from_nanos(u64::MAX), 1.0),
+            Timestamp::from_nanos(u64::MAX)
+        );
+    }
+}
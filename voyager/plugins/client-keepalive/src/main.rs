@@ -0,0 +1,394 @@
+use std::collections::VecDeque;
+
+use ibc_classic_spec::IbcClassic;
+use ibc_union_spec::IbcUnion;
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    types::ErrorObject,
+    Extensions,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, error, info, instrument, warn};
+use unionlabs::{never::Never, ErrorReporter};
+use voyager_sdk::{
+    anyhow::{self, bail},
+    into_value,
+    message::{
+        call::{FetchUpdateHeaders, WaitForTrustedHeight},
+        callback::AggregateSubmitTxFromOrderedHeaders,
+        data::Data,
+        PluginMessage, VoyagerMessage,
+    },
+    plugin::Plugin,
+    primitives::{ChainId, ClientInfo, IbcSpecId, QueryHeight},
+    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
+    types::RawClientId,
+    vm::{call, conc, defer, now, pass::PassResult, promise, seq, Op},
+    ExtensionsExt, VoyagerClient,
+};
+
+use crate::{
+    call::{KeepClientAlive, ModuleCall},
+    keepalive::{trusting_period, update_deadline},
+};
+
+pub mod call;
+pub mod keepalive;
+
+/// Clients that are not due for an update are checked again after at most this many seconds, so
+/// that a deadline that moved (i.e. by an update from another relayer) is picked up.
+const MAX_CHECK_INTERVAL: u64 = 60 * 60;
+
+/// Clients that are due for an update are not checked again within this many seconds, in case the
+/// counterparty clock is ahead of ours.
+const MIN_CHECK_INTERVAL: u64 = 60;
+
+#[tokio::main]
+async fn main() {
+    Module::run().await
+}
+
+pub struct Module {
+    pub trusting_period_fraction: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Fraction of the trusting period of a client after which it is updated, if it was not updated
+    /// by other traffic in the meantime.
+    #[serde(default = "default_trusting_period_fraction")]
+    pub trusting_period_fraction: f64,
+}
+
+fn default_trusting_period_fraction() -> f64 {
+    2.0 / 3.0
+}
+
+impl Plugin for Module {
+    type Call = ModuleCall;
+    type Callback = Never;
+
+    type Config = Config;
+    type Cmd = Cmd;
+
+    async fn new(config: Self::Config) -> anyhow::Result<Self> {
+        if !(config.trusting_period_fraction > 0.0 && config.trusting_period_fraction <= 1.0) {
+            bail!(
+                "trusting_period_fraction must be within (0, 1], found {}",
+                config.trusting_period_fraction
+            );
+        }
+
+        Ok(Module::new(config))
+    }
+
+    fn info(config: Self::Config) -> PluginInfo {
+        let module = Module::new(config);
+
+        PluginInfo {
+            name: module.plugin_name(),
+            // never interested in any messages since this plugin does not utilize a queue
+            interest_filter: "null".to_owned(),
+        }
+    }
+
+    async fn cmd(config: Self::Config, cmd: Self::Cmd) {
+        let module = Self::new(config);
+
+        match cmd {
+            Cmd::MakeMessage(msg) => {
+                let op = call::<VoyagerMessage>(PluginMessage::new(
+                    module.plugin_name(),
+                    ModuleCall::KeepClientAlive(msg),
+                ));
+
+                println!("{}", into_value(op));
+            }
+        }
+    }
+}
+
+#[derive(clap::Parser)]
+pub enum Cmd {
+    MakeMessage(KeepClientAlive),
+}
+
+impl Module {
+    fn plugin_name(&self) -> String {
+        pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+        PLUGIN_NAME.to_owned()
+    }
+
+    pub fn new(config: Config) -> Self {
+        Self {
+            trusting_period_fraction: config.trusting_period_fraction,
+        }
+    }
+
+    fn keep_client_alive_later(&self, msg: KeepClientAlive, after: u64) -> Op<VoyagerMessage> {
+        seq([
+            defer(now() + after),
+            call(PluginMessage::new(
+                self.plugin_name(),
+                ModuleCall::KeepClientAlive(msg),
+            )),
+        ])
+    }
+
+    /// The decoded client state of the client being kept alive.
+    async fn client_state(
+        &self,
+        voyager_client: &VoyagerClient,
+        chain_id: &ChainId,
+        ibc_spec_id: &IbcSpecId,
+        client_id: &RawClientId,
+        client_info: &ClientInfo,
+    ) -> RpcResult<Value> {
+        let invalid_client_id = |e| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                ErrorReporter(e).with_message("invalid client id"),
+                None::<()>,
+            )
+        };
+
+        match ibc_spec_id.as_str() {
+            IbcSpecId::CLASSIC => {
+                let client_state = voyager_client
+                    .query_ibc_state(
+                        chain_id.clone(),
+                        QueryHeight::Latest,
+                        ibc_classic_spec::ClientStatePath {
+                            client_id: client_id
+                                .clone()
+                                .decode_spec::<IbcClassic>()
+                                .map_err(invalid_client_id)?,
+                        },
+                    )
+                    .await?;
+
+                voyager_client
+                    .decode_client_state::<IbcClassic, Value>(
+                        client_info.client_type.clone(),
+                        client_info.ibc_interface.clone(),
+                        client_state,
+                    )
+                    .await
+            }
+            IbcSpecId::UNION => {
+                let client_state = voyager_client
+                    .query_ibc_state(
+                        chain_id.clone(),
+                        QueryHeight::Latest,
+                        ibc_union_spec::path::ClientStatePath {
+                            client_id: client_id
+                                .clone()
+                                .decode_spec::<IbcUnion>()
+                                .map_err(invalid_client_id)?,
+                        },
+                    )
+                    .await?;
+
+                voyager_client
+                    .decode_client_state::<IbcUnion, Value>(
+                        client_info.client_type.clone(),
+                        client_info.ibc_interface.clone(),
+                        client_state,
+                    )
+                    .await
+            }
+            _ => Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("unsupported ibc spec {ibc_spec_id}"),
+                None::<()>,
+            )),
+        }
+    }
+
+    #[instrument(
+        skip_all,
+        fields(
+            %chain_id,
+            %ibc_spec_id,
+            client_id = %client_id.as_raw(),
+        )
+    )]
+    async fn keep_client_alive(
+        &self,
+        voyager_client: &VoyagerClient,
+        KeepClientAlive {
+            chain_id,
+            ibc_spec_id,
+            client_id,
+        }: KeepClientAlive,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let client_state_meta = voyager_client
+            .client_state_meta_raw(
+                chain_id.clone(),
+                ibc_spec_id.clone(),
+                QueryHeight::Latest,
+                client_id.clone(),
+            )
+            .await?;
+
+        let client_info = voyager_client
+            .client_info_raw(chain_id.clone(), ibc_spec_id.clone(), client_id.clone())
+            .await?;
+
+        let consensus_state_meta = voyager_client
+            .consensus_state_meta_raw(
+                chain_id.clone(),
+                ibc_spec_id.clone(),
+                QueryHeight::Latest,
+                client_id.clone(),
+                client_state_meta.counterparty_height,
+            )
+            .await?;
+
+        let client_state = self
+            .client_state(
+                voyager_client,
+                &chain_id,
+                &ibc_spec_id,
+                &client_id,
+                &client_info,
+            )
+            .await?;
+
+        let Some(trusting_period) = trusting_period(&client_state) else {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                "client type has no trusting period, clients of this type do not expire",
+                Some(json!({
+                    "client_type": client_info.client_type,
+                })),
+            ));
+        };
+
+        let latest_timestamp = voyager_client
+            .query_latest_timestamp(client_state_meta.counterparty_chain_id.clone(), true)
+            .await?;
+
+        let deadline = update_deadline(
+            consensus_state_meta.timestamp,
+            trusting_period,
+            self.trusting_period_fraction,
+        );
+
+        let msg = KeepClientAlive {
+            chain_id: chain_id.clone(),
+            ibc_spec_id: ibc_spec_id.clone(),
+            client_id: client_id.clone(),
+        };
+
+        if latest_timestamp < deadline {
+            let remaining = (deadline.as_nanos() - latest_timestamp.as_nanos()) / 1_000_000_000;
+
+            debug!(
+                %trusting_period,
+                %deadline,
+                remaining,
+                "client is not due for an update"
+            );
+
+            return Ok(self.keep_client_alive_later(
+                msg,
+                remaining.clamp(MIN_CHECK_INTERVAL, MAX_CHECK_INTERVAL),
+            ));
+        }
+
+        if latest_timestamp >= update_deadline(consensus_state_meta.timestamp, trusting_period, 1.0)
+        {
+            warn!(
+                %trusting_period,
+                consensus_timestamp = %consensus_state_meta.timestamp,
+                "client has expired, the update will likely fail"
+            );
+        }
+
+        let latest_finalized_height = voyager_client
+            .query_latest_height(client_state_meta.counterparty_chain_id.clone(), true)
+            .await?;
+
+        info!(
+            %trusting_period,
+            %deadline,
+            update_from = %client_state_meta.counterparty_height,
+            update_to = %latest_finalized_height,
+            "client is due for an update"
+        );
+
+        let update = voyager_client
+            .with_finality_delay(
+                chain_id.clone(),
+                promise(
+                    [call(FetchUpdateHeaders {
+                        client_type: client_info.client_type,
+                        chain_id: client_state_meta.counterparty_chain_id,
+                        counterparty_chain_id: chain_id.clone(),
+                        client_id: client_id.clone(),
+                        update_from: client_state_meta.counterparty_height,
+                        update_to: latest_finalized_height,
+                    })],
+                    [],
+                    AggregateSubmitTxFromOrderedHeaders {
+                        ibc_spec_id: ibc_spec_id.clone(),
+                        chain_id: chain_id.clone(),
+                        client_id: client_id.clone(),
+                    },
+                ),
+            )
+            .await?;
+
+        Ok(conc([
+            update,
+            seq([
+                call(WaitForTrustedHeight {
+                    chain_id,
+                    ibc_spec_id,
+                    client_id,
+                    height: latest_finalized_height,
+                    finalized: false,
+                }),
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::KeepClientAlive(msg),
+                )),
+            ]),
+        ]))
+    }
+}
+
+#[async_trait]
+impl PluginServer<ModuleCall, Never> for Module {
+    async fn run_pass(
+        &self,
+        _: &Extensions,
+        msgs: Vec<Op<VoyagerMessage>>,
+    ) -> RpcResult<PassResult<VoyagerMessage>> {
+        error!(?msgs, "this plugin does not utilize a queue");
+
+        Ok(PassResult::default())
+    }
+
+    async fn call(&self, e: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
+        match msg {
+            ModuleCall::KeepClientAlive(msg) => {
+                self.keep_client_alive(e.voyager_client()?, msg).await
+            }
+        }
+    }
+
+    async fn callback(
+        &self,
+        _: &Extensions,
+        cb: Never,
+        _data: VecDeque<Data>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        match cb {}
+    }
+}
@@ -2,7 +2,6 @@ use cometbls_light_client_types::{header::Header, light_header::LightHeader};
 use enumorph::Enumorph;
 use macros::model;
 use subset_of::SubsetOf;
-use unionlabs::ibc::core::client::height::Height;
 use voyager_sdk::{
     message::{
         data::{DecodedHeaderMeta, OrderedHeaders},
@@ -36,10 +35,7 @@ impl Module {
                            }| {
             (
                 DecodedHeaderMeta {
-                    height: Height::new_with_revision(
-                        update_from.revision(),
-                        header.height.inner().try_into().unwrap(),
-                    ),
+                    height: update_from.with_height(header.height.inner().try_into().unwrap()),
                 },
                 serde_json::to_value(Header {
                     signed_header: LightHeader {
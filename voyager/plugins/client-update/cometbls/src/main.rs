@@ -25,6 +25,7 @@ use jsonrpsee::{
     Extensions,
 };
 use num_bigint::BigUint;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::{debug, error, info, instrument, trace};
@@ -73,7 +74,7 @@ pub struct Module {
     pub prover_endpoints: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
@@ -1,15 +1,15 @@
-use std::{collections::VecDeque, fmt::Debug, num::ParseIntError};
+use std::{collections::VecDeque, fmt::Debug};
 
 use cometbft_types::types::{validator::Validator, validator_set::ValidatorSet};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tendermint_light_client_types::Header;
 use tracing::instrument;
 use unionlabs::{
-    ibc::core::client::height::Height,
     never::Never,
     primitives::{encoding::HexUnprefixed, H160},
 };
@@ -42,10 +42,9 @@ pub struct Module {
     pub chain_id: ChainId,
 
     pub cometbft_client: cometbft_rpc::Client,
-    pub chain_revision: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
@@ -73,23 +72,9 @@ impl Plugin for Module {
             );
         }
 
-        let chain_revision = chain_id
-            .split('-')
-            .next_back()
-            .ok_or_else(|| ChainIdParseError {
-                found: chain_id.clone(),
-                source: None,
-            })?
-            .parse()
-            .map_err(|err| ChainIdParseError {
-                found: chain_id.clone(),
-                source: Some(err),
-            })?;
-
         Ok(Self {
             cometbft_client: tm_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
         })
     }
 
@@ -120,14 +105,6 @@ impl Module {
     }
 }
 
-#[derive(Debug, thiserror::Error)]
-#[error("unable to parse chain id: expected format `<chain>-<revision-number>`, found `{found}`")]
-pub struct ChainIdParseError {
-    found: String,
-    #[source]
-    source: Option<ParseIntError>,
-}
-
 #[async_trait]
 impl PluginServer<ModuleCall, Never> for Module {
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
@@ -201,10 +178,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                         untrusted_commit.signed_header.header.proposer_address,
                     ),
                     signed_header: untrusted_commit.signed_header,
-                    trusted_height: Height::new_with_revision(
-                        self.chain_revision,
-                        update_from.height(),
-                    ),
+                    trusted_height: update_from,
                     trusted_validators: mk_validator_set(
                         trusted_validators.validators,
                         trusted_commit.signed_header.header.proposer_address,
@@ -7,6 +7,7 @@ use jsonrpsee::{
     core::{async_trait, RpcResult},
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument};
 use unionlabs::{
@@ -126,7 +127,7 @@ impl aptos_move_ibc::ibc::ClientExt for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The identifier of the chain
@@ -16,6 +16,7 @@ use jsonrpsee::{
 use parlia_light_client_types::{ConsensusState, Header};
 use parlia_types::ParliaHeader;
 use parlia_verifier::EPOCH_LENGTH;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 use unionlabs::{ibc::core::client::height::Height, never::Never, primitives::H160, ErrorReporter};
@@ -54,7 +55,7 @@ pub struct Module {
     pub ibc_handler_address: H160,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
@@ -6,6 +6,7 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sui_light_client_types::{checkpoint_summary::CheckpointContents, CertifiedCheckpointSummary};
 use sui_sdk::{
@@ -104,13 +105,14 @@ fn plugin_name(chain_id: &ChainId) -> String {
     format!("{PLUGIN_NAME}/{}", chain_id)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The identifier of the chain
     pub chain_id: ChainId,
 
     /// The address of the `IBCHandler` smart contract.
+    #[schemars(with = "String")]
     pub ibc_handler_address: ObjectID,
 
     pub sui_object_store_rpc_url: String,
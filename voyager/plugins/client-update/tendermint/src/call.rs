@@ -1,6 +1,7 @@
 use enumorph::Enumorph;
 use macros::model;
 use unionlabs::ibc::core::client::height::Height;
+use voyager_sdk::{primitives::ChainId, types::RawClientId};
 
 #[model]
 #[derive(Enumorph)]
@@ -12,4 +13,14 @@ pub enum ModuleCall {
 pub struct FetchUpdate {
     pub update_from: Height,
     pub update_to: Height,
+    /// The client that the update is for. Misbehaviour evidence is submitted to this client if the
+    /// update conflicts with the witness.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<UpdatedClient>,
+}
+
+#[model]
+pub struct UpdatedClient {
+    pub chain_id: ChainId,
+    pub client_id: RawClientId,
 }
@@ -1,39 +1,50 @@
 #![warn(clippy::unwrap_used)]
 
-use std::{collections::VecDeque, num::ParseIntError};
+use std::collections::VecDeque;
 
 use cometbft_types::types::{validator::Validator, validator_set::ValidatorSet};
+use ibc_classic_spec::IbcClassic;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
+    types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tendermint_light_client_types::Header;
-use tracing::{instrument, warn};
+use serde_json::json;
+use tendermint_light_client_types::{Header, Misbehaviour};
+use tracing::{error, instrument, warn};
 use unionlabs::{
+    encoding::{EncodeAs, Proto},
+    google::protobuf::any::Any,
     ibc::core::client::height::Height,
     never::Never,
     primitives::{encoding::HexUnprefixed, H160},
+    ErrorReporter,
 };
 use voyager_sdk::{
     anyhow::{self, bail},
     hook::UpdateHook,
     into_value,
     message::{
-        call::Call,
-        data::{Data, DecodedHeaderMeta, OrderedHeaders},
+        call::{Call, SubmitTx},
+        data::{Data, DecodedHeaderMeta, IbcDatagram, OrderedHeaders},
         PluginMessage, VoyagerMessage,
     },
     plugin::Plugin,
-    primitives::{ChainId, ClientType},
-    rpc::{rpc_error, types::PluginInfo, PluginServer},
-    vm::{data, pass::PassResult, Op, Visit},
+    primitives::{ChainId, ClientType, IbcSpec},
+    rpc::{rpc_error, types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
+    vm::{call, data, pass::PassResult, Op, Visit},
     DefaultCmd,
 };
 
-use crate::call::{FetchUpdate, ModuleCall};
+use crate::{
+    call::{FetchUpdate, ModuleCall, UpdatedClient},
+    misbehaviour::conflicting_block_hashes,
+};
 
 pub mod call;
+pub mod misbehaviour;
 
 #[tokio::main]
 async fn main() {
@@ -45,15 +56,22 @@ pub struct Module {
     pub chain_id: ChainId,
 
     pub cometbft_client: cometbft_rpc::Client,
-    pub chain_revision: u64,
+
+    pub witness_client: Option<cometbft_rpc::Client>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
 
     pub rpc_url: String,
+
+    /// A second RPC endpoint of the chain, operated independently of `rpc_url`. Every update is
+    /// cross-checked against it, and misbehaviour evidence is submitted (to ibc-go `07-tendermint`
+    /// clients) if the two disagree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub witness_rpc_url: Option<String>,
 }
 
 impl Plugin for Module {
@@ -76,23 +94,29 @@ impl Plugin for Module {
             );
         }
 
-        let chain_revision = chain_id
-            .split('-')
-            .next_back()
-            .ok_or_else(|| ChainIdParseError {
-                found: chain_id.clone(),
-                source: None,
-            })?
-            .parse()
-            .map_err(|err| ChainIdParseError {
-                found: chain_id.clone(),
-                source: Some(err),
-            })?;
+        let witness_client = match config.witness_rpc_url {
+            Some(witness_rpc_url) => {
+                let witness_client = cometbft_rpc::Client::new(witness_rpc_url).await?;
+
+                let witness_chain_id = witness_client.status().await?.node_info.network.to_string();
+
+                if witness_chain_id != chain_id {
+                    bail!(
+                        "incorrect witness chain id: expected `{}`, but found `{}`",
+                        chain_id,
+                        witness_chain_id
+                    );
+                }
+
+                Some(witness_client)
+            }
+            None => None,
+        };
 
         Ok(Self {
             cometbft_client: tm_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            witness_client,
         })
     }
 
@@ -121,14 +145,111 @@ impl Module {
     fn plugin_name(&self) -> String {
         plugin_name(&self.chain_id)
     }
-}
 
-#[derive(Debug, thiserror::Error)]
-#[error("unable to parse chain id: expected format `<chain>-<revision-number>`, found `{found}`")]
-pub struct ChainIdParseError {
-    found: String,
-    #[source]
-    source: Option<ParseIntError>,
+    async fn fetch_header(
+        &self,
+        cometbft_client: &cometbft_rpc::Client,
+        update_from: Height,
+        update_to: Height,
+    ) -> RpcResult<Header> {
+        let trusted_height = update_from
+            .increment()
+            .height()
+            .try_into()
+            .expect("valid height");
+        let untrusted_height = update_to.height().try_into().expect("valid height");
+
+        let trusted_commit = cometbft_client
+            .commit(Some(trusted_height))
+            .await
+            .map_err(rpc_error("trusted commit", None))?;
+
+        let untrusted_commit = cometbft_client
+            .commit(Some(untrusted_height))
+            .await
+            .map_err(rpc_error("untrusted commit", None))?;
+
+        let trusted_validators = cometbft_client
+            .all_validators(Some(trusted_height))
+            .await
+            .map_err(rpc_error("trusted validators", None))?;
+
+        let untrusted_validators = cometbft_client
+            .all_validators(Some(untrusted_height))
+            .await
+            .map_err(rpc_error("untrusted validators", None))?;
+
+        Ok(Header {
+            validator_set: mk_validator_set(
+                untrusted_validators.validators,
+                untrusted_commit.signed_header.header.proposer_address,
+            ),
+            signed_header: untrusted_commit.signed_header,
+            trusted_height: update_from,
+            trusted_validators: mk_validator_set(
+                trusted_validators.validators,
+                trusted_commit.signed_header.header.proposer_address,
+            ),
+        })
+    }
+
+    /// Submit `misbehaviour` to the client that is being updated, which freezes it. The update
+    /// itself is not completed, since it is not known which of the conflicting headers is
+    /// canonical.
+    ///
+    /// Misbehaviour is submitted to ibc-go `07-tendermint` clients as a client update, which ibc-go
+    /// checks for misbehaviour. The ibc-union core contracts do not accept misbehaviour, so for any
+    /// other client the evidence is only reported.
+    fn submit_misbehaviour(
+        &self,
+        client: Option<UpdatedClient>,
+        misbehaviour: Misbehaviour,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let height = misbehaviour.header_1.signed_header.header.height;
+        let block_hash = misbehaviour.header_1.signed_header.commit.block_id.hash;
+        let witness_block_hash = misbehaviour.header_2.signed_header.commit.block_id.hash;
+
+        error!(
+            %height,
+            ?block_hash,
+            ?witness_block_hash,
+            "the rpc and the witness disagree on the block, submitting misbehaviour"
+        );
+
+        let Some((chain_id, client_id)) = client.and_then(|client| {
+            Some((
+                client.chain_id,
+                client
+                    .client_id
+                    .decode_spec::<IbcClassic>()
+                    .ok()
+                    .filter(|client_id| client_id.to_string().starts_with("07-tendermint-"))?,
+            ))
+        }) else {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                "the rpc and the witness disagree on the block, but the misbehaviour \
+                cannot be submitted to the client being updated",
+                Some(json!({
+                    "height": height,
+                    "block_hash": block_hash,
+                    "witness_block_hash": witness_block_hash,
+                    "misbehaviour": into_value(misbehaviour),
+                })),
+            ));
+        };
+
+        Ok(call(SubmitTx {
+            chain_id,
+            datagrams: vec![IbcDatagram::new::<IbcClassic>(
+                IbcClassic::update_client_datagram(
+                    client_id,
+                    Any(misbehaviour).encode_as::<Proto>().into(),
+                ),
+            )],
+            signers: None,
+        }))
+    }
 }
 
 #[async_trait]
@@ -153,6 +274,10 @@ impl PluginServer<ModuleCall, Never> for Module {
                                 ModuleCall::from(FetchUpdate {
                                     update_from: fetch.update_from,
                                     update_to: fetch.update_to,
+                                    client: Some(UpdatedClient {
+                                        chain_id: fetch.counterparty_chain_id.clone(),
+                                        client_id: fetch.client_id.clone(),
+                                    }),
                                 }),
                             ))
                         },
@@ -173,53 +298,40 @@ impl PluginServer<ModuleCall, Never> for Module {
             ModuleCall::FetchUpdate(FetchUpdate {
                 update_from,
                 update_to,
+                client,
             }) => {
-                let trusted_height = update_from
-                    .increment()
-                    .height()
-                    .try_into()
-                    .expect("valid height");
-                let untrusted_height = update_to.height().try_into().expect("valid height");
-
-                let trusted_commit = self
-                    .cometbft_client
-                    .commit(Some(trusted_height))
-                    .await
-                    .map_err(rpc_error("trusted commit", None))?;
-
-                let untrusted_commit = self
-                    .cometbft_client
-                    .commit(Some(untrusted_height))
-                    .await
-                    .map_err(rpc_error("untrusted commit", None))?;
-
-                let trusted_validators = self
-                    .cometbft_client
-                    .all_validators(Some(trusted_height))
-                    .await
-                    .map_err(rpc_error("trusted validators", None))?;
-
-                let untrusted_validators = self
-                    .cometbft_client
-                    .all_validators(Some(untrusted_height))
-                    .await
-                    .map_err(rpc_error("untrusted validators", None))?;
-
-                let header = Header {
-                    validator_set: mk_validator_set(
-                        untrusted_validators.validators,
-                        untrusted_commit.signed_header.header.proposer_address,
-                    ),
-                    signed_header: untrusted_commit.signed_header,
-                    trusted_height: Height::new_with_revision(
-                        self.chain_revision,
-                        update_from.height(),
-                    ),
-                    trusted_validators: mk_validator_set(
-                        trusted_validators.validators,
-                        trusted_commit.signed_header.header.proposer_address,
-                    ),
-                };
+                let header = self
+                    .fetch_header(&self.cometbft_client, update_from, update_to)
+                    .await?;
+
+                if let Some(witness_client) = &self.witness_client {
+                    match self
+                        .fetch_header(witness_client, update_from, update_to)
+                        .await
+                    {
+                        Ok(witness_header)
+                            if conflicting_block_hashes(
+                                &header.signed_header,
+                                &witness_header.signed_header,
+                            ) =>
+                        {
+                            return self.submit_misbehaviour(
+                                client,
+                                Misbehaviour {
+                                    header_1: header,
+                                    header_2: witness_header,
+                                },
+                            );
+                        }
+                        Ok(_) => {}
+                        // the witness being unavailable does not block updates
+                        Err(err) => warn!(
+                            error = %ErrorReporter(err),
+                            %update_to,
+                            "unable to cross-check the update with the witness"
+                        ),
+                    }
+                }
 
                 Ok(data(OrderedHeaders {
                     headers: vec![(DecodedHeaderMeta { height: update_to }, into_value(header))],
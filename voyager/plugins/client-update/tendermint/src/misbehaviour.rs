@@ -0,0 +1,7 @@
+use cometbft_types::types::signed_header::SignedHeader;
+
+/// Whether `a` and `b` are commits to different blocks at the same height, which is evidence of a
+/// fork (or of one of the RPC endpoints serving a forged chain).
+pub fn conflicting_block_hashes(a: &SignedHeader, b: &SignedHeader) -> bool {
+    a.header.height == b.header.height && a.commit.block_id.hash != b.commit.block_id.hash
+}
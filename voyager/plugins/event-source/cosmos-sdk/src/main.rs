@@ -3,11 +3,11 @@
 use std::{
     cmp::Ordering,
     collections::{btree_map::Entry, BTreeMap, BTreeSet, VecDeque},
-    num::{NonZeroU32, NonZeroU8, ParseIntError},
+    num::{NonZeroU8, ParseIntError},
     sync::Arc,
 };
 
-use cosmos_sdk_event::CosmosSdkEvent;
+use cometbft_rpc::events::{attribute_values, decode_events};
 use dashmap::DashMap;
 use ibc_classic_spec::IbcClassic;
 use ibc_union_spec::{path::ChannelPath, query::PacketByHash, IbcUnion, Packet};
@@ -16,9 +16,10 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing::{debug, error, info, info_span, instrument, trace, warn};
+use tracing::{debug, error, info, info_span, instrument, warn};
 use unionlabs::{
     ibc::core::{
         channel::{self},
@@ -27,7 +28,7 @@ use unionlabs::{
     id::{ChannelId, ConnectionId, PortId},
     never::Never,
     option_unwrap,
-    primitives::{Bech32, H256},
+    primitives::{Bech32, Bytes, H256},
     ErrorReporter,
 };
 use voyager_sdk::{
@@ -41,8 +42,12 @@ use voyager_sdk::{
     },
     plugin::Plugin,
     primitives::{ChainId, ClientInfo, ClientType, QueryHeight},
-    rpc::{rpc_error, types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
-    vm::{call, conc, data, noop, pass::PassResult, seq, Op},
+    rpc::{
+        rpc_error,
+        types::{BackpressureStatus, PluginInfo},
+        PluginServer, FATAL_JSONRPC_ERROR_CODE,
+    },
+    vm::{call, conc, data, defer, noop, now, pass::PassResult, seq, Op},
     ExtensionsExt, VoyagerClient,
 };
 use wasm_client_type::WasmClientType;
@@ -66,7 +71,7 @@ async fn main() {
 #[derive(Debug, Clone)]
 pub struct Module {
     pub chain_id: ChainId,
-    pub chain_revision: u64,
+    pub revision_start: Height,
 
     pub cometbft_client: cometbft_rpc::Client,
 
@@ -77,9 +82,12 @@ pub struct Module {
     pub index_trivial_events: bool,
 
     pub ibc_host_contract_address: Option<Bech32<H256>>,
+
+    pub ignored_contracts: BTreeSet<Bech32<Bytes>>,
+    pub ignored_senders: BTreeSet<Bech32<Bytes>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
@@ -98,6 +106,14 @@ pub struct Config {
 
     #[serde(default)]
     pub ibc_host_contract_address: Option<Bech32<H256>>,
+
+    /// Events in transactions that execute any of these contracts are not indexed.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub ignored_contracts: BTreeSet<Bech32<Bytes>>,
+
+    /// Trivial events (see [`Self::index_trivial_events`]) in transactions sent by any of these addresses are not indexed, even if `index_trivial_events` is set. This is intended to be used with the addresses of the relayer itself, to ignore the events of its own housekeeping transactions (i.e. client updates).
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub ignored_senders: BTreeSet<Bech32<Bytes>>,
 }
 
 fn default_chunk_block_fetch_size() -> u64 {
@@ -142,12 +158,14 @@ impl Plugin for Module {
         Ok(Self {
             cometbft_client: tm_client,
             chain_id: ChainId::new(chain_id),
-            chain_revision,
+            revision_start: Height::new_with_revision(chain_revision, 0),
             chunk_block_fetch_size: config.chunk_block_fetch_size,
             refetch_delay: config.refetch_delay,
             checksum_cache: Arc::new(DashMap::default()),
             index_trivial_events: config.index_trivial_events,
             ibc_host_contract_address: config.ibc_host_contract_address,
+            ignored_contracts: config.ignored_contracts,
+            ignored_senders: config.ignored_senders,
         })
     }
 
@@ -192,7 +210,7 @@ impl Module {
 
     #[must_use]
     pub fn make_height(&self, height: u64) -> Height {
-        Height::new_with_revision(self.chain_revision, height)
+        self.revision_start.with_height(height)
     }
 
     #[allow(clippy::too_many_arguments)] // pls
@@ -398,13 +416,30 @@ impl Module {
             }
         }
 
+        let backpressure = voyager_client.backpressure(self.chain_id.clone()).await?;
+
+        if backpressure.paused {
+            info!(
+                pending = backpressure.pending,
+                "queue is saturated, pausing event ingestion"
+            );
+
+            return Ok(seq([
+                defer(now() + BackpressureStatus::RETRY_DELAY),
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::from(FetchBlocks { height, until }),
+                )),
+            ]));
+        }
+
         let latest_height = voyager_client
             .query_latest_height(self.chain_id.clone(), true)
             .await?;
 
         info!(%latest_height, %height, ?until, "fetching blocks");
 
-        if !height.revision_matches(&latest_height) {
+        let Some(ordering) = height.same_revision_cmp(&latest_height) else {
             return Err(ErrorObject::owned(
                 FATAL_JSONRPC_ERROR_CODE,
                 format!(
@@ -413,7 +448,7 @@ impl Module {
                 ),
                 None::<()>,
             ));
-        }
+        };
 
         let continuation = |next_height: Height| {
             seq([
@@ -433,7 +468,7 @@ impl Module {
             ])
         };
 
-        match height.cmp(&latest_height) {
+        match ordering {
             // height < latest_height
             // fetch transactions on all blocks height..next_height (*exclusive* on the upper bound!)
             // and then queue the continuation starting at next_height
@@ -459,14 +494,11 @@ impl Module {
                                 self.plugin_name(),
                                 ModuleCall::from(FetchBlock {
                                     already_seen_events: None,
-                                    height: Height::new_with_revision(height.revision(), h),
+                                    height: height.with_height(h),
                                 }),
                             ))
                         })
-                        .chain([continuation(Height::new_with_revision(
-                            height.revision(),
-                            next_height,
-                        ))]),
+                        .chain([continuation(height.with_height(next_height))]),
                 ))
             }
             Ordering::Greater => {
@@ -512,130 +544,117 @@ impl Module {
         // event hashes found while fetching this block
         let mut found_events = BTreeSet::new();
 
-        let mut page = const { option_unwrap!(NonZeroU32::new(1)) };
+        let mut seen_batches = BTreeSet::new();
 
-        let mut total_count = 0;
+        info!(%height, "fetching transactions");
 
-        let mut seen_batches = BTreeSet::new();
+        let txs = self
+            .cometbft_client
+            .tx_search_all(
+                format!("tx.height={}", height.height()),
+                false,
+                PER_PAGE_LIMIT,
+                cometbft_rpc::rpc_types::Order::Desc,
+                None,
+            )
+            .await
+            .map_err(rpc_error(
+                format_args!("error fetching transactions at height {height}"),
+                Some(json!({ "height": height })),
+            ))?;
+
+        for tx_response in txs {
+            let _span = info_span!("tx_result.events", tx_hash = %tx_response.hash).entered();
+
+            if self.executes_ignored_contract(&tx_response.tx_result.events) {
+                debug!("not indexing events of transaction executing an ignored contract");
+                continue;
+            }
 
-        loop {
-            info!(%height, %page, "fetching page {page}");
-
-            let response = self
-                .cometbft_client
-                .tx_search(
-                    format!("tx.height={}", height.height()),
-                    false,
-                    page,
-                    PER_PAGE_LIMIT,
-                    cometbft_rpc::rpc_types::Order::Desc,
-                )
-                .await
-                .map_err(rpc_error(
-                    format_args!("error fetching transactions at height {height}"),
-                    Some(json!({ "height": height })),
-                ))?;
-
-            total_count += response.txs.len();
-
-            for tx_response in response.txs {
-                let _span = info_span!("tx_result.events", tx_hash = %tx_response.hash).entered();
-                for event in tx_response.tx_result.events {
-                    trace!(%event.ty, "observed event");
-
-                    let event = match CosmosSdkEvent::<IbcEvent>::new(event) {
-                        Ok(event) => event,
-                        Err(cosmos_sdk_event::Error::Deserialize(error)) => {
-                            trace!("unable to parse event: {error}");
-                            continue;
-                        }
-                        Err(err) => {
-                            error!("error parsing event: {}", ErrorReporter(err));
-                            continue;
-                        }
-                    };
+            let index_trivial_events = self.index_trivial_events
+                && !self.sent_by_ignored_sender(&tx_response.tx_result.events);
 
-                    match (&event.contract_address, &self.ibc_host_contract_address) {
-                        (None, _) => {}
-                        (Some(addr), None) => {
+            for event in decode_events::<IbcEvent>(tx_response.tx_result.events) {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(err) => {
+                        error!("error parsing event: {}", ErrorReporter(err));
+                        continue;
+                    }
+                };
+
+                match (&event.contract_address, &self.ibc_host_contract_address) {
+                    (None, _) => {}
+                    (Some(addr), None) => {
+                        debug!(
+                            "found ibc-union event for contract {addr}, but no contract address is configured",
+                        );
+                        continue;
+                    }
+                    (Some(event_addr), Some(configured_addr)) => {
+                        if event_addr == configured_addr {
+                        } else {
                             debug!(
-                                "found ibc-union event for contract {addr}, but no contract address is configured",
+                                "found ibc-union event for contract {event_addr}, but the configured contract address is {configured_addr}",
                             );
                             continue;
                         }
-                        (Some(event_addr), Some(configured_addr)) => {
-                            if event_addr == configured_addr {
-                            } else {
-                                debug!(
-                                    "found ibc-union event for contract {event_addr}, but the configured contract address is {configured_addr}",
-                                );
-                                continue;
-                            }
-                        }
                     }
+                }
 
-                    let mut make_chain_event = || {
-                        if event.event.is_trivial() && !self.index_trivial_events {
-                            debug!("not indexing trivial event");
-                            None
-                        } else {
-                            let event = match event.event {
-                                IbcEvent::WasmBatchSend {
-                                    channel_id,
-                                    batch_hash,
-                                    packet_hash,
-                                } => {
-                                    debug!(%packet_hash, %batch_hash, %channel_id, "found batch send event");
-                                    if seen_batches.insert((channel_id, batch_hash)) {
-                                        info!(%batch_hash, %channel_id, "found batch send event");
-                                        event.clone()
-                                    } else {
-                                        return None;
-                                    }
+                let mut make_chain_event = || {
+                    if event.event.is_trivial() && !index_trivial_events {
+                        debug!("not indexing trivial event");
+                        None
+                    } else {
+                        let event = match event.event {
+                            IbcEvent::WasmBatchSend {
+                                channel_id,
+                                batch_hash,
+                                packet_hash,
+                            } => {
+                                debug!(%packet_hash, %batch_hash, %channel_id, "found batch send event");
+                                if seen_batches.insert((channel_id, batch_hash)) {
+                                    info!(%batch_hash, %channel_id, "found batch send event");
+                                    event.clone()
+                                } else {
+                                    return None;
                                 }
-                                _ => event.clone(),
-                            };
-                            Some(call(PluginMessage::new(
-                                self.plugin_name(),
-                                ModuleCall::from(MakeChainEvent {
-                                    height,
-                                    tx_hash: tx_response.hash.into_encoding(),
-                                    event: event.event,
-                                }),
-                            )))
-                        }
-                    };
-
-                    if let Some(ref mut already_seen_events) = already_seen_events {
-                        match already_seen_events.entry(event.event.hash()) {
-                            Entry::Vacant(vacant_entry) => {
-                                info!("found previously missed event");
-                                vacant_entry.insert(EventState::SeenNow);
-                                make_chain_event_ops.push(make_chain_event());
                             }
-                            Entry::Occupied(mut occupied_entry) => match occupied_entry.get() {
-                                EventState::SeenPreviously => {
-                                    info!("found previously seen event");
-                                    occupied_entry.insert(EventState::SeenNow);
-                                }
-                                EventState::SeenNow => {
-                                    warn!("found duplicate event, likely due to a load-balanced rpc with poor nodes. additional data may have been missed!");
-                                }
-                            },
+                            _ => event.clone(),
                         };
-                    } else {
-                        found_events.insert(event.event.hash());
-                        make_chain_event_ops.push(make_chain_event());
+                        Some(call(PluginMessage::new(
+                            self.plugin_name(),
+                            ModuleCall::from(MakeChainEvent {
+                                height,
+                                tx_hash: tx_response.hash.into_encoding(),
+                                event: event.event,
+                            }),
+                        )))
                     }
-                }
-            }
+                };
 
-            if total_count >= (response.total_count as usize) {
-                break;
-            } else {
-                page = page
-                    .checked_add(1)
-                    .expect("how many events does this block have???");
+                if let Some(ref mut already_seen_events) = already_seen_events {
+                    match already_seen_events.entry(event.event.hash()) {
+                        Entry::Vacant(vacant_entry) => {
+                            info!("found previously missed event");
+                            vacant_entry.insert(EventState::SeenNow);
+                            make_chain_event_ops.push(make_chain_event());
+                        }
+                        Entry::Occupied(mut occupied_entry) => match occupied_entry.get() {
+                            EventState::SeenPreviously => {
+                                info!("found previously seen event");
+                                occupied_entry.insert(EventState::SeenNow);
+                            }
+                            EventState::SeenNow => {
+                                warn!("found duplicate event, likely due to a load-balanced rpc with poor nodes. additional data may have been missed!");
+                            }
+                        },
+                    };
+                } else {
+                    found_events.insert(event.event.hash());
+                    make_chain_event_ops.push(make_chain_event());
+                }
             }
         }
 
@@ -659,6 +678,27 @@ impl Module {
         )))
     }
 
+    /// Whether any of the `_contract_address` attributes of the events of a transaction is one of
+    /// [`Self::ignored_contracts`].
+    fn executes_ignored_contract(
+        &self,
+        events: &[cometbft_rpc::types::abci::event::Event],
+    ) -> bool {
+        !self.ignored_contracts.is_empty()
+            && attribute_values(events, None, "_contract_address")
+                .filter_map(|address| address.parse::<Bech32<Bytes>>().ok())
+                .any(|address| self.ignored_contracts.contains(&address))
+    }
+
+    /// Whether any of the `message.sender` attributes of the events of a transaction is one of
+    /// [`Self::ignored_senders`].
+    fn sent_by_ignored_sender(&self, events: &[cometbft_rpc::types::abci::event::Event]) -> bool {
+        !self.ignored_senders.is_empty()
+            && attribute_values(events, Some("message"), "sender")
+                .filter_map(|address| address.parse::<Bech32<Bytes>>().ok())
+                .any(|address| self.ignored_senders.contains(&address))
+    }
+
     #[instrument(level = "info", skip_all, fields(%height, %tx_hash))]
     async fn make_chain_event(
         &self,
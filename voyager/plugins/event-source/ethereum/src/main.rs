@@ -1,8 +1,12 @@
 // #![warn(clippy::unwrap_used)] // allow for now
 
-use std::{cmp::Ordering, collections::VecDeque};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, VecDeque},
+};
 
 use alloy::{
+    consensus::Transaction,
     providers::{layers::CacheLayer, DynProvider, Provider, ProviderBuilder},
     rpc::types::Filter,
     sol_types::SolEventInterface,
@@ -24,6 +28,7 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, info_span, instrument, trace, warn};
 use unionlabs::{
@@ -43,8 +48,11 @@ use voyager_sdk::{
     },
     plugin::Plugin,
     primitives::{ChainId, ClientInfo, IbcSpec, QueryHeight},
-    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
-    vm::{call, conc, data, noop, pass::PassResult, seq, Op},
+    rpc::{
+        types::{BackpressureStatus, PluginInfo},
+        PluginServer, FATAL_JSONRPC_ERROR_CODE,
+    },
+    vm::{call, conc, data, defer, noop, now, pass::PassResult, seq, Op},
     DefaultCmd, ExtensionsExt, VoyagerClient,
 };
 
@@ -66,10 +74,13 @@ pub struct Module {
     pub chunk_block_fetch_size: u64,
     pub index_trivial_events: bool,
 
+    pub ignored_contracts: BTreeSet<H160>,
+    pub ignored_senders: BTreeSet<H160>,
+
     pub provider: DynProvider,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// The expected chain id of this ethereum-like chain.
@@ -88,6 +99,14 @@ pub struct Config {
     #[serde(default)]
     pub index_trivial_events: bool,
 
+    /// Events in transactions calling any of these contracts are not indexed.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub ignored_contracts: BTreeSet<H160>,
+
+    /// Trivial events (see [`Self::index_trivial_events`]) in transactions sent by any of these addresses are not indexed, even if `index_trivial_events` is set. This is intended to be used with the addresses of the relayer itself, to ignore the events of its own housekeeping transactions (i.e. client updates).
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub ignored_senders: BTreeSet<H160>,
+
     #[serde(default)]
     pub max_cache_size: u32,
 }
@@ -148,6 +167,8 @@ impl Module {
             chain_id: ChainId::new(chain_id.to_string()),
             ibc_handler_address: config.ibc_handler_address,
             index_trivial_events: config.index_trivial_events,
+            ignored_contracts: config.ignored_contracts,
+            ignored_senders: config.ignored_senders,
             chunk_block_fetch_size: config.chunk_block_fetch_size,
             provider,
         })
@@ -332,6 +353,26 @@ impl Module {
             }
         }
 
+        let backpressure = voyager_client.backpressure(self.chain_id.clone()).await?;
+
+        if backpressure.paused {
+            info!(
+                pending = backpressure.pending,
+                "queue is saturated, pausing event ingestion"
+            );
+
+            return Ok(seq([
+                defer(now() + BackpressureStatus::RETRY_DELAY),
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::from(FetchBlocks {
+                        block_number,
+                        until,
+                    }),
+                )),
+            ]));
+        }
+
         let latest_height = voyager_client
             .query_latest_height(self.chain_id.clone(), true)
             .await?
@@ -425,20 +466,68 @@ impl Module {
 
         info!(logs_count = logs.len(), "found logs");
 
+        // transactions calling an ignored contract, and transactions sent by an ignored sender
+        let mut ignored_txs = BTreeSet::new();
+        let mut housekeeping_txs = BTreeSet::new();
+
+        if !self.ignored_contracts.is_empty() || !self.ignored_senders.is_empty() {
+            for tx_hash in logs
+                .iter()
+                .filter_map(|log| log.transaction_hash)
+                .collect::<BTreeSet<_>>()
+            {
+                let tx = self
+                    .provider
+                    .get_transaction_by_hash(tx_hash)
+                    .await
+                    .map_err(|e| {
+                        ErrorObject::owned(
+                            -1,
+                            format!("error fetching transaction {tx_hash}: {}", ErrorReporter(e)),
+                            None::<()>,
+                        )
+                    })?
+                    .ok_or_else(|| {
+                        ErrorObject::owned(
+                            -1,
+                            format!("transaction {tx_hash} not found"),
+                            None::<()>,
+                        )
+                    })?;
+
+                if tx
+                    .to()
+                    .is_some_and(|to| self.ignored_contracts.contains(&to.into()))
+                {
+                    ignored_txs.insert(tx_hash);
+                } else if self.ignored_senders.contains(&tx.inner.signer().into()) {
+                    housekeeping_txs.insert(tx_hash);
+                }
+            }
+        }
+
         let events = logs.into_iter().flat_map(|log| {
-            let tx_hash = log
+            let raw_tx_hash = log
                 .transaction_hash
-                .expect("log should have transaction_hash")
-                .into();
+                .expect("log should have transaction_hash");
+            let tx_hash = raw_tx_hash.into();
+
+            let index_trivial_events =
+                self.index_trivial_events && !housekeeping_txs.contains(&raw_tx_hash);
 
             info_span!("tx_hash", %tx_hash).in_scope(|| {
+                if ignored_txs.contains(&raw_tx_hash) {
+                    debug!("not indexing event of transaction calling an ignored contract");
+                    return None;
+                }
+
                 match Ibc::IbcEvents::decode_log(&log.inner) {
                     Ok(event) => {
                         trace!(?event, "found IbcHandler event");
 
                         let event = match event.data {
                             Ibc::IbcEvents::RegisterClient(e) => {
-                                if self.index_trivial_events {
+                                if index_trivial_events {
                                     Some(IbcEvents::RegisterClient(e))
                                 } else {
                                     debug!("not indexing trivial event");
@@ -446,7 +535,7 @@ impl Module {
                                 }
                             }
                             Ibc::IbcEvents::CreateClient(e) => {
-                                if self.index_trivial_events {
+                                if index_trivial_events {
                                     Some(IbcEvents::CreateClient(e))
                                 } else {
                                     debug!("not indexing trivial event");
@@ -454,7 +543,7 @@ impl Module {
                                 }
                             }
                             Ibc::IbcEvents::UpdateClient(e) => {
-                                if self.index_trivial_events {
+                                if index_trivial_events {
                                     Some(IbcEvents::UpdateClient(e))
                                 } else {
                                     debug!("not indexing trivial event");
@@ -496,7 +585,7 @@ impl Module {
                                 Some(IbcEvents::BatchAck(e))
                             }
                             Ibc::IbcEvents::PacketRecv(e) => {
-                                if self.index_trivial_events {
+                                if index_trivial_events {
                                     Some(IbcEvents::PacketRecv(e))
                                 } else {
                                     debug!("not indexing trivial event");
@@ -504,7 +593,7 @@ impl Module {
                                 }
                             }
                             Ibc::IbcEvents::IntentPacketRecv(e) => {
-                                if self.index_trivial_events {
+                                if index_trivial_events {
                                     Some(IbcEvents::IntentPacketRecv(e))
                                 } else {
                                     debug!("not indexing trivial event");
@@ -513,7 +602,7 @@ impl Module {
                             }
                             Ibc::IbcEvents::WriteAck(e) => Some(IbcEvents::WriteAck(e)),
                             Ibc::IbcEvents::PacketAck(e) => {
-                                if self.index_trivial_events {
+                                if index_trivial_events {
                                     Some(IbcEvents::PacketAck(e))
                                 } else {
                                     debug!("not indexing trivial event");
@@ -521,7 +610,7 @@ impl Module {
                                 }
                             }
                             Ibc::IbcEvents::PacketTimeout(e) => {
-                                if self.index_trivial_events {
+                                if index_trivial_events {
                                     Some(IbcEvents::PacketTimeout(e))
                                 } else {
                                     debug!("not indexing trivial event");
@@ -25,6 +25,7 @@ use jsonrpsee::{
     Extensions,
 };
 use move_bindgen::MoveOutputType;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug, info, instrument};
@@ -40,8 +41,11 @@ use voyager_sdk::{
     },
     plugin::Plugin,
     primitives::{ChainId, ClientInfo, ClientType, IbcSpec, QueryHeight},
-    rpc::{types::PluginInfo, PluginServer},
-    vm::{call, conc, data, pass::PassResult, seq, Op},
+    rpc::{
+        types::{BackpressureStatus, PluginInfo},
+        PluginServer,
+    },
+    vm::{call, conc, data, defer, now, pass::PassResult, seq, Op},
     DefaultCmd, ExtensionsExt, VoyagerClient,
 };
 
@@ -109,12 +113,13 @@ impl Plugin for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
     pub rpc_url: String,
     pub movement_rpc_url: String,
+    #[schemars(with = "String")]
     pub ibc_handler_address: Address,
 }
 
@@ -355,53 +360,74 @@ impl PluginServer<ModuleCall, Never> for Module {
 
                 Ok(conc(events))
             }
-            ModuleCall::FetchBlocks(FetchBlocks { height }) => Ok(conc([
-                call(PluginMessage::new(
-                    self.plugin_name(),
-                    ModuleCall::from(FetchTransactions { height }),
-                )),
-                {
-                    let latest_height = self
-                        .aptos_client
-                        .get_index()
-                        .await
-                        .unwrap()
-                        .into_inner()
-                        .block_height
-                        .0;
-                    match height.cmp(&latest_height) {
-                        Ordering::Less => {
-                            let next_height = (latest_height - height).clamp(1, 10) + height;
-                            conc(
-                                ((height + 1)..next_height)
-                                    .map(|height| {
-                                        call(PluginMessage::new(
+            ModuleCall::FetchBlocks(FetchBlocks { height }) => {
+                let voyager_client = e.voyager_client()?;
+
+                let backpressure = voyager_client.backpressure(self.chain_id.clone()).await?;
+
+                if backpressure.paused {
+                    info!(
+                        pending = backpressure.pending,
+                        "queue is saturated, pausing event ingestion"
+                    );
+
+                    return Ok(seq([
+                        defer(now() + BackpressureStatus::RETRY_DELAY),
+                        call(PluginMessage::new(
+                            self.plugin_name(),
+                            ModuleCall::from(FetchBlocks { height }),
+                        )),
+                    ]));
+                }
+
+                Ok(conc([
+                    call(PluginMessage::new(
+                        self.plugin_name(),
+                        ModuleCall::from(FetchTransactions { height }),
+                    )),
+                    {
+                        let latest_height = self
+                            .aptos_client
+                            .get_index()
+                            .await
+                            .unwrap()
+                            .into_inner()
+                            .block_height
+                            .0;
+                        match height.cmp(&latest_height) {
+                            Ordering::Less => {
+                                let next_height = (latest_height - height).clamp(1, 10) + height;
+                                conc(
+                                    ((height + 1)..next_height)
+                                        .map(|height| {
+                                            call(PluginMessage::new(
+                                                self.plugin_name(),
+                                                ModuleCall::from(FetchTransactions { height }),
+                                            ))
+                                        })
+                                        .chain([call(PluginMessage::new(
                                             self.plugin_name(),
-                                            ModuleCall::from(FetchTransactions { height }),
-                                        ))
-                                    })
-                                    .chain([call(PluginMessage::new(
-                                        self.plugin_name(),
-                                        ModuleCall::from(FetchBlocks {
-                                            height: next_height,
-                                        }),
-                                    ))]),
-                            )
+                                            ModuleCall::from(FetchBlocks {
+                                                height: next_height,
+                                            }),
+                                        ))]),
+                                )
+                            }
+                            Ordering::Equal | Ordering::Greater => seq([
+                                call(WaitForHeight {
+                                    chain_id: self.chain_id.clone(),
+                                    height: Height::new(height + 1),
+                                    finalized: true,
+                                }),
+                                call(PluginMessage::new(
+                                    self.plugin_name(),
+                                    ModuleCall::from(FetchBlocks { height: height + 1 }),
+                                )),
+                            ]),
                         }
-                        Ordering::Equal | Ordering::Greater => seq([
-                            call(WaitForHeight {
-                                chain_id: self.chain_id.clone(),
-                                height: Height::new(height + 1),
-                                finalized: true,
-                            }),
-                            call(PluginMessage::new(
-                                self.plugin_name(),
-                                ModuleCall::from(FetchBlocks { height: height + 1 }),
-                            )),
-                        ]),
-                    }
-                },
-            ])),
+                    },
+                ]))
+            }
             ModuleCall::MakeFullEvent(MakeFullEvent {
                 event,
                 tx_hash,
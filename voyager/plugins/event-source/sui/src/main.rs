@@ -14,6 +14,7 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sui_sdk::{
     rpc_types::SuiTransactionBlockResponseOptions, types::base_types::SuiAddress, SuiClientBuilder,
@@ -29,8 +30,11 @@ use voyager_sdk::{
     },
     plugin::Plugin,
     primitives::{ChainId, ClientInfo, ClientType, QueryHeight},
-    rpc::{types::PluginInfo, PluginServer},
-    vm::{call, conc, data, pass::PassResult, seq, Op},
+    rpc::{
+        types::{BackpressureStatus, PluginInfo},
+        PluginServer,
+    },
+    vm::{call, conc, data, defer, now, pass::PassResult, seq, Op},
     DefaultCmd, ExtensionsExt, VoyagerClient,
 };
 
@@ -101,11 +105,12 @@ impl Plugin for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
     pub rpc_url: String,
+    #[schemars(with = "String")]
     pub ibc_handler_address: SuiAddress,
 }
 
@@ -125,6 +130,23 @@ impl Module {
         voyager_client: &VoyagerClient,
         height: u64,
     ) -> RpcResult<Op<VoyagerMessage>> {
+        let backpressure = voyager_client.backpressure(self.chain_id.clone()).await?;
+
+        if backpressure.paused {
+            info!(
+                pending = backpressure.pending,
+                "queue is saturated, pausing event ingestion"
+            );
+
+            return Ok(seq([
+                defer(now() + BackpressureStatus::RETRY_DELAY),
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::from(FetchBlocks { height }),
+                )),
+            ]));
+        }
+
         Ok(conc([
             call(PluginMessage::new(
                 self.plugin_name(),
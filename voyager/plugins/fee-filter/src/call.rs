@@ -0,0 +1,24 @@
+use enumorph::Enumorph;
+use ibc_union_spec::{event::PacketSend, ClientId};
+use macros::model;
+use voyager_sdk::{message::data::EventProvableHeight, primitives::ChainId};
+
+#[model]
+#[derive(Enumorph)]
+pub enum ModuleCall {
+    CheckFee(CheckFee),
+}
+
+/// Check the expected fee of a packet against the estimated cost of relaying it to the
+/// counterparty chain.
+#[model]
+pub struct CheckFee {
+    pub event: PacketSend,
+    pub chain_id: ChainId,
+    pub counterparty_chain_id: ChainId,
+    /// The counterparty client of the event.
+    pub client_id: ClientId,
+    pub provable_height: EventProvableHeight,
+    /// Unix timestamp (in seconds) of when the fee of the packet was first checked.
+    pub first_checked_at: u64,
+}
@@ -0,0 +1,225 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use unionlabs::{
+    encoding::{DecodeAs, EthAbi},
+    primitives::{Bytes, U256},
+    zkgm::ZkgmPacket,
+};
+
+/// The fee requirements of the packets relayed to a chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FeeConfig {
+    /// The plugin used to estimate the cost of relaying to this chain (i.e. the transaction plugin
+    /// of the chain). It must expose an `estimateFee` method, returning the fee for the given
+    /// amount of gas.
+    pub transaction_plugin: String,
+    /// The estimated gas used to receive a single packet on this chain.
+    pub gas_per_packet: u64,
+    /// The prices of the tokens the fees are paid in.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub token_prices: Vec<TokenPrice>,
+    /// Packets are relayed once their expected fee is at least this multiple of the estimated cost
+    /// of relaying them.
+    #[serde(default = "default_min_fee_ratio")]
+    pub min_fee_ratio: f64,
+    /// How long to wait before checking the fee of an unprofitable packet again.
+    pub retry_delay: Duration,
+    /// How long unprofitable packets are retried for, after which [`Self::on_expiry`] is applied.
+    pub max_wait: Duration,
+    #[serde(default)]
+    pub on_expiry: ExpiryAction,
+}
+
+fn default_min_fee_ratio() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TokenPrice {
+    /// The quote token of the order (i.e. the token on this chain).
+    pub token: Bytes,
+    /// The value of the smallest unit of the token, in the smallest unit of the gas token of this
+    /// chain.
+    pub price: f64,
+}
+
+/// What to do with packets that are still unprofitable after [`FeeConfig::max_wait`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpiryAction {
+    #[default]
+    Relay,
+    Drop,
+}
+
+impl FeeConfig {
+    /// The fee paid to the relayer for a zkgm packet, in the smallest unit of the gas token of this
+    /// chain.
+    ///
+    /// The fee of a fungible asset order is the difference between its base and quote amounts, which
+    /// is paid to the relayer in the quote token. Orders of tokens without a configured price are
+    /// considered to pay no fee. Returns `None` if the packet cannot be decoded.
+    #[must_use]
+    pub fn expected_fee(&self, packet_data: &[u8]) -> Option<f64> {
+        let packet = ZkgmPacket::decode_as::<EthAbi>(packet_data).ok()?;
+
+        Some(
+            packet
+                .instruction
+                .fungible_asset_orders()
+                .into_iter()
+                .filter_map(|order| {
+                    let price = self
+                        .token_prices
+                        .iter()
+                        .find(|p| &p.token == order.quote_token())?
+                        .price;
+
+                    let fee = if order.base_amount() > order.quote_amount() {
+                        order.base_amount() - order.quote_amount()
+                    } else {
+                        U256::ZERO
+                    };
+
+                    Some(to_f64(fee) * price)
+                })
+                .sum(),
+        )
+    }
+
+    /// Whether a packet paying `fee` is worth relaying at `cost`.
+    #[must_use]
+    pub fn is_profitable(&self, fee: f64, cost: U256) -> bool {
+        fee >= to_f64(cost) * self.min_fee_ratio
+    }
+}
+
+fn to_f64(amount: U256) -> f64 {
+    // lossy, but this is only used for estimates
+    u128::try_from(amount).unwrap_or(u128::MAX) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use unionlabs::{
+        encoding::EncodeAs,
+        primitives::H256,
+        zkgm::{Batch, FungibleAssetOrder, FungibleAssetOrderV1, Instruction},
+    };
+
+    use super::*;
+
+    const MUNO: &[u8] = b"muno";
+    const USDC: &[u8] = b"usdc";
+
+    fn config() -> FeeConfig {
+        FeeConfig {
+            transaction_plugin: "voyager-plugin-transaction-cosmos-sdk/union-1".to_owned(),
+            gas_per_packet: 200_000,
+            token_prices: vec![
+                TokenPrice {
+                    token: MUNO.into(),
+                    price: 1.0,
+                },
+                TokenPrice {
+                    token: USDC.into(),
+                    price: 2.5,
+                },
+            ],
+            min_fee_ratio: 1.5,
+            retry_delay: Duration::from_secs(60),
+            max_wait: Duration::from_secs(3600),
+            on_expiry: ExpiryAction::Relay,
+        }
+    }
+
+    fn order(quote_token: &[u8], base_amount: u64, quote_amount: u64) -> Instruction {
+        Instruction::FungibleAssetOrder(FungibleAssetOrder::V1(FungibleAssetOrderV1 {
+            sender: Default::default(),
+            receiver: Default::default(),
+            base_token: Default::default(),
+            base_amount: base_amount.into(),
+            base_token_symbol: String::new(),
+            base_token_name: String::new(),
+            base_token_decimals: 6,
+            base_token_path: U256::ZERO,
+            quote_token: quote_token.into(),
+            quote_amount: quote_amount.into(),
+        }))
+    }
+
+    fn batch(instructions: Vec<Instruction>) -> Instruction {
+        Instruction::Batch(Batch { instructions })
+    }
+
+    fn packet(instruction: Instruction) -> Vec<u8> {
+        ZkgmPacket {
+            salt: H256::default(),
+            path: U256::ZERO,
+            instruction,
+        }
+        .encode_as::<EthAbi>()
+    }
+
+    #[test]
+    fn expected_fee() {
+        let config = config();
+
+        assert_eq!(
+            config.expected_fee(&packet(order(MUNO, 1_000, 900))),
+            Some(100.0)
+        );
+        assert_eq!(
+            config.expected_fee(&packet(order(USDC, 1_000, 900))),
+            Some(250.0)
+        );
+        // quote amount above base amount, i.e. the order is filled by a market maker
+        assert_eq!(
+            config.expected_fee(&packet(order(MUNO, 900, 1_000))),
+            Some(0.0)
+        );
+        // no price configured for this token
+        assert_eq!(
+            config.expected_fee(&packet(order(b"other", 1_000, 0))),
+            Some(0.0)
+        );
+        assert_eq!(
+            config.expected_fee(&packet(batch(vec![
+                order(MUNO, 1_000, 900),
+                order(USDC, 1_000, 900)
+            ]))),
+            Some(350.0)
+        );
+        assert_eq!(config.expected_fee(b"not a zkgm packet"), None);
+    }
+
+    #[test]
+    fn is_profitable() {
+        let config = config();
+
+        assert!(config.is_profitable(150.0, 100_u64.into()));
+        assert!(!config.is_profitable(149.0, 100_u64.into()));
+        assert!(config.is_profitable(0.0, U256::ZERO));
+    }
+
+    #[test]
+    fn config_serde() {
+        let json = r#"{
+            "transaction_plugin": "voyager-plugin-transaction-cosmos-sdk/union-1",
+            "gas_per_packet": 200000,
+            "token_prices": [
+                { "token": "0x6d756e6f", "price": 1.0 },
+                { "token": "0x75736463", "price": 2.5 }
+            ],
+            "min_fee_ratio": 1.5,
+            "retry_delay": { "secs": 60, "nanos": 0 },
+            "max_wait": { "secs": 3600, "nanos": 0 }
+        }"#;
+
+        assert_eq!(serde_json::from_str::<FeeConfig>(json).unwrap(), config());
+    }
+}
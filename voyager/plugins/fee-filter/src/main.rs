@@ -0,0 +1,295 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ibc_union_spec::{event::FullEvent, IbcUnion};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    proc_macros::rpc,
+    types::ErrorObject,
+    Extensions,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, info, instrument};
+use unionlabs::{never::Never, primitives::U256, ErrorReporter};
+use voyager_plugin_transaction_batch::data::{BatchableEvent, EventBatch};
+use voyager_sdk::{
+    anyhow,
+    message::{data::Data, PluginMessage, VoyagerMessage},
+    plugin::Plugin,
+    primitives::{ChainId, IbcSpec},
+    rpc::{
+        json_rpc_error_to_error_object, types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE,
+    },
+    vm::{call, data, defer, noop, now, pass::PassResult, seq, Op},
+    DefaultCmd, ExtensionsExt,
+};
+
+use crate::{
+    call::{CheckFee, ModuleCall},
+    fee::{ExpiryAction, FeeConfig},
+};
+
+pub mod call;
+pub mod fee;
+
+#[tokio::main]
+async fn main() {
+    Module::run().await
+}
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    /// chain id -> fee requirements of the packets relayed to that chain
+    pub chains: BTreeMap<ChainId, FeeConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// chain id -> fee requirements of the packets relayed to that chain
+    ///
+    /// packets relayed to any unconfigured chain are not seen by this plugin.
+    pub chains: BTreeMap<ChainId, FeeConfig>,
+}
+
+/// The methods of the transaction plugins used to estimate the cost of relaying.
+#[rpc(client)]
+trait TransactionPlugin {
+    #[method(name = "estimateFee")]
+    async fn estimate_fee(&self, gas: u64) -> RpcResult<U256>;
+}
+
+impl Plugin for Module {
+    type Call = ModuleCall;
+    type Callback = Never;
+
+    type Config = Config;
+    type Cmd = DefaultCmd;
+
+    async fn new(config: Self::Config) -> anyhow::Result<Self> {
+        Ok(Self {
+            chains: config.chains,
+        })
+    }
+
+    fn info(config: Self::Config) -> PluginInfo {
+        let chain_filter = ["false".to_owned()]
+            .into_iter()
+            .chain(
+                config
+                    .chains
+                    .keys()
+                    .map(|chain_id| format!(r#"$event.counterparty_chain_id == "{chain_id}""#)),
+            )
+            .collect::<Vec<_>>()
+            .join(" or ");
+
+        PluginInfo {
+            name: PLUGIN_NAME.to_owned(),
+            interest_filter: format!(
+                r#"
+if ."@type" == "data" and ."@value"."@type" == "ibc_event" then
+    ."@value"."@value" as $event |
+
+    if $event.ibc_spec_id == "{ibc_union_id}"
+        and $event.event."@type" == "packet_send"
+        and $event.event."@value".packet.source_channel.version == "{zkgm_version}"
+        and ({chain_filter})
+    then
+        true
+    else
+        null
+    end
+else
+    null
+end
+"#,
+                ibc_union_id = IbcUnion::ID,
+                zkgm_version = ucs03_zkgm::contract::PROTOCOL_VERSION,
+            ),
+        }
+    }
+
+    async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
+        match cmd {}
+    }
+}
+
+pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+impl Module {
+    #[instrument(
+        skip_all,
+        fields(
+            packet_hash = %msg.event.packet().hash(),
+            counterparty_chain_id = %msg.counterparty_chain_id,
+        )
+    )]
+    async fn check_fee(&self, e: &Extensions, msg: CheckFee) -> RpcResult<Op<VoyagerMessage>> {
+        let Some(config) = self.chains.get(&msg.counterparty_chain_id) else {
+            return Ok(relay(msg));
+        };
+
+        let Some(fee) = config.expected_fee(&msg.event.packet_data) else {
+            debug!("unable to decode packet, relaying");
+            return Ok(relay(msg));
+        };
+
+        let cost = e
+            .voyager_client()?
+            .plugin_client(&config.transaction_plugin)
+            .estimate_fee(config.gas_per_packet)
+            .await
+            .map_err(json_rpc_error_to_error_object)?;
+
+        if config.is_profitable(fee, cost) {
+            debug!(%fee, %cost, "packet is profitable");
+            return Ok(relay(msg));
+        }
+
+        if now() >= msg.first_checked_at + config.max_wait.as_secs() {
+            return Ok(match config.on_expiry {
+                ExpiryAction::Relay => {
+                    info!(%fee, %cost, "packet is unprofitable, relaying after max wait");
+                    relay(msg)
+                }
+                ExpiryAction::Drop => {
+                    info!(%fee, %cost, "packet is unprofitable, dropping after max wait");
+                    noop()
+                }
+            });
+        }
+
+        info!(%fee, %cost, "packet is unprofitable, checking again later");
+
+        Ok(seq([
+            defer(now() + config.retry_delay.as_secs()),
+            call(PluginMessage::new(PLUGIN_NAME, ModuleCall::from(msg))),
+        ]))
+    }
+}
+
+/// Hand the packet to the transaction-batch plugin of the counterparty chain. The event can't be
+/// passed on as is, since it would then match this plugin's interest filter again.
+fn relay(msg: CheckFee) -> Op<VoyagerMessage> {
+    let first_seen_at: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+        .try_into()
+        .expect("how many milliseconds can there be man");
+
+    data(PluginMessage::new(
+        voyager_plugin_transaction_batch::plugin_name(&msg.counterparty_chain_id),
+        voyager_plugin_transaction_batch::data::ModuleData::BatchEventsUnion(EventBatch {
+            client_id: msg.client_id,
+            events: vec![BatchableEvent {
+                first_seen_at,
+                provable_height: msg.provable_height,
+                event: msg.event.into(),
+            }],
+        }),
+    ))
+}
+
+#[async_trait]
+impl PluginServer<ModuleCall, Never> for Module {
+    #[instrument(skip_all)]
+    async fn run_pass(
+        &self,
+        _: &Extensions,
+        msgs: Vec<Op<VoyagerMessage>>,
+    ) -> RpcResult<PassResult<VoyagerMessage>> {
+        let ready = msgs
+            .into_iter()
+            .enumerate()
+            .map(|(idx, msg)| {
+                let Op::Data(Data::IbcEvent(ref chain_event)) = msg else {
+                    return Err(ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        "unexpected message in queue",
+                        Some(json!({
+                            "msg": msg,
+                        })),
+                    ));
+                };
+
+                let full_event = chain_event
+                    .decode_event::<IbcUnion>()
+                    .ok_or_else(|| {
+                        ErrorObject::owned(
+                            FATAL_JSONRPC_ERROR_CODE,
+                            "unexpected data message in queue",
+                            Some(json!({
+                                "msg": msg.clone(),
+                            })),
+                        )
+                    })?
+                    .map_err(|err| {
+                        ErrorObject::owned(
+                            FATAL_JSONRPC_ERROR_CODE,
+                            "unable to parse ibc datagram",
+                            Some(json!({
+                                "err": ErrorReporter(err).to_string(),
+                                "msg": msg,
+                            })),
+                        )
+                    })?;
+
+                let client_id = full_event.counterparty_client_id();
+
+                let (FullEvent::PacketSend(packet_send), Some(client_id)) = (full_event, client_id)
+                else {
+                    return Err(ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        "unexpected ibc datagram",
+                        Some(json!({
+                            "msg": msg,
+                        })),
+                    ));
+                };
+
+                Ok((
+                    vec![idx],
+                    call(PluginMessage::new(
+                        PLUGIN_NAME,
+                        ModuleCall::from(CheckFee {
+                            event: packet_send,
+                            chain_id: chain_event.chain_id.clone(),
+                            counterparty_chain_id: chain_event.counterparty_chain_id.clone(),
+                            client_id,
+                            provable_height: chain_event.provable_height,
+                            first_checked_at: now(),
+                        }),
+                    )),
+                ))
+            })
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        Ok(PassResult {
+            optimize_further: vec![],
+            ready,
+        })
+    }
+
+    #[instrument(skip_all)]
+    async fn call(&self, e: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
+        match msg {
+            ModuleCall::CheckFee(msg) => self.check_fee(e, msg).await,
+        }
+    }
+
+    #[instrument]
+    async fn callback(
+        &self,
+        _: &Extensions,
+        cb: Never,
+        _data: VecDeque<Data>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        match cb {}
+    }
+}
@@ -1,5 +1,3 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-
 use ibc_union_spec::Packet;
 use macros::model;
 use unionlabs::primitives::Bytes;
@@ -18,14 +16,9 @@ pub struct BatchSend {
 }
 
 impl BatchSend {
-    pub fn new(packet: Packet) -> Self {
+    pub fn new(packet: Packet, first_seen_at: u64) -> Self {
         Self {
-            first_seen_at: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_millis()
-                .try_into()
-                .expect("how many milliseconds can there be man"),
+            first_seen_at,
             packet,
         }
     }
@@ -40,14 +33,9 @@ pub struct BatchAck {
 }
 
 impl BatchAck {
-    pub fn new(packet: Packet, ack: Bytes) -> Self {
+    pub fn new(packet: Packet, ack: Bytes, first_seen_at: u64) -> Self {
         Self {
-            first_seen_at: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_millis()
-                .try_into()
-                .expect("how many milliseconds can there be man"),
+            first_seen_at,
             packet,
             ack,
         }
@@ -14,6 +14,7 @@ use jsonrpsee::{
     core::{async_trait, RpcResult},
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 use unionlabs::{never::Never, traits::Member};
@@ -36,6 +37,9 @@ use crate::data::{BatchAck, BatchSend, ModuleData};
 
 pub mod data;
 
+#[cfg(test)]
+mod tests;
+
 #[tokio::main]
 async fn main() {
     Module::run().await
@@ -48,9 +52,12 @@ pub struct Module {
     pub max_batch_count: usize,
     pub max_batch_size_bytes: usize,
     pub max_wait_time: Duration,
+    /// The current unix timestamp (in ms), which events are timestamped with and batches are
+    /// checked against. Replaced in tests to control the passing of time.
+    pub clock: fn() -> u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
@@ -133,10 +140,20 @@ impl Module {
             max_batch_count: config.max_batch_count,
             max_batch_size_bytes: config.max_batch_size_bytes,
             max_wait_time: config.max_wait_time,
+            clock: unix_millis,
         }
     }
 }
 
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+        .try_into()
+        .expect("how many milliseconds can there be man")
+}
+
 #[async_trait]
 impl PluginServer<Never, Never> for Module {
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
@@ -145,6 +162,8 @@ impl PluginServer<Never, Never> for Module {
         _: &Extensions,
         msgs: Vec<Op<VoyagerMessage>>,
     ) -> RpcResult<PassResult<VoyagerMessage>> {
+        let now = (self.clock)();
+
         let (send, ack) =
             msgs.into_iter()
                 .enumerate()
@@ -155,10 +174,10 @@ impl PluginServer<Never, Never> for Module {
                             Data::IbcEvent(chain_event) => {
                                 match chain_event.decode_event::<IbcUnion>().unwrap().unwrap() {
                                     FullEvent::PacketSend(e) => Some(ModuleData::BatchSendPacket(
-                                        vec![BatchSend::new(e.packet())],
+                                        vec![BatchSend::new(e.packet(), now)],
                                     )),
                                     FullEvent::WriteAck(e) => Some(ModuleData::BatchAckPacket(
-                                        vec![BatchAck::new(e.packet(), e.acknowledgement)],
+                                        vec![BatchAck::new(e.packet(), e.acknowledgement, now)],
                                     )),
                                     event => panic!("{event:?}"),
                                 }
@@ -172,13 +191,6 @@ impl PluginServer<Never, Never> for Module {
                 })
                 .partition_map::<Vec<_>, Vec<_>, _, _, _>(std::convert::identity);
 
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis()
-            .try_into()
-            .expect("how many milliseconds can there be man");
-
         fn f<T>(
             msgs: Vec<(usize, Vec<T>)>,
             now: u64,
@@ -293,6 +305,7 @@ impl PluginServer<Never, Never> for Module {
                         call(SubmitTx {
                             chain_id: self.chain_id.clone(),
                             datagrams: vec![IbcDatagram::new::<IbcUnion>(MsgBatchSend { packets })],
+                            signers: None,
                         }),
                     )
                 })
@@ -311,6 +324,7 @@ impl PluginServer<Never, Never> for Module {
                                 packets,
                                 acks,
                             })],
+                            signers: None,
                         }),
                     )
                 }))
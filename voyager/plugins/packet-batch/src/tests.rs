@@ -0,0 +1,289 @@
+//! Runs the batching pass of this plugin through voyager's in-memory queue, with the interest
+//! filter the plugin registers with voyager. Events are enqueued as they would be by an event
+//! source plugin, and the exact transactions that become ready to be submitted are asserted on.
+//!
+//! This covers the batching stage of the relay pipeline only. The messages that are batched here
+//! are constructed (with their proofs) by the transaction plugins, which are not run.
+//!
+//! Time is controlled with the clock of the plugin, so batches never depend on how long the test
+//! takes to run.
+
+use std::{cell::Cell, time::Duration};
+
+use ibc_union_spec::{
+    datagram::{MsgBatchAcks, MsgBatchSend},
+    event::{ChannelMetadata, ConnectionMetadata, FullEvent, PacketMetadata, PacketSend, WriteAck},
+    ChannelId, ClientId, ConnectionId, IbcUnion, Packet, Timestamp,
+};
+use jsonrpsee::{types::ErrorObjectOwned, Extensions};
+use unionlabs::{
+    ibc::core::client::height::Height,
+    primitives::{Bytes, H256},
+};
+use voyager_core::filter::InterestFilters;
+use voyager_sdk::{
+    message::{
+        call::SubmitTx,
+        data::{ChainEvent, EventProvableHeight, IbcDatagram},
+        VoyagerMessage,
+    },
+    primitives::{chain_id, ClientInfo, ClientType, IbcInterface},
+    rpc::PluginServer,
+    vm::{
+        call, data,
+        in_memory::InMemoryQueue,
+        pass::{Pass, PassResult},
+        Op, Queue,
+    },
+};
+
+use crate::{Config, Module};
+
+thread_local! {
+    /// The time of the clock of the plugin, per test (the tests run on their own thread).
+    static NOW: Cell<u64> = const { Cell::new(1_000_000) };
+}
+
+fn clock() -> u64 {
+    NOW.with(Cell::get)
+}
+
+fn advance(duration: Duration) {
+    NOW.with(|now| now.set(now.get() + u64::try_from(duration.as_millis()).unwrap()));
+}
+
+/// Runs the pass of the plugin as voyager does when optimizing the items tagged with its name.
+struct ModulePass<'a>(&'a Module);
+
+impl Pass<VoyagerMessage> for ModulePass<'_> {
+    type Error = ErrorObjectOwned;
+
+    async fn run_pass(
+        &self,
+        ops: Vec<Op<VoyagerMessage>>,
+    ) -> Result<PassResult<VoyagerMessage>, Self::Error> {
+        PluginServer::run_pass(self.0, &Extensions::new(), ops).await
+    }
+}
+
+struct Harness {
+    module: Module,
+    filter: InterestFilters,
+    queue: InMemoryQueue<VoyagerMessage>,
+}
+
+impl Harness {
+    async fn new(max_batch_count: usize, max_wait_time: Duration) -> Self {
+        let config = Config {
+            chain_id: chain_id!("union-devnet-1"),
+            channel_id: channel_id(1),
+            max_batch_count,
+            max_batch_size_bytes: 1024,
+            max_wait_time,
+        };
+
+        Self {
+            module: Module {
+                clock,
+                ..Module::new(config.clone())
+            },
+            filter: InterestFilters::new(vec![Module::info(config)]).unwrap(),
+            queue: InMemoryQueue::new(()).await.unwrap(),
+        }
+    }
+
+    async fn event(&self, event: impl Into<FullEvent>) -> Op<VoyagerMessage> {
+        let op = data(ChainEvent::new::<IbcUnion>(
+            chain_id!("union-devnet-1"),
+            ClientInfo {
+                client_type: ClientType::new(ClientType::ETHEREUM),
+                ibc_interface: IbcInterface::new(IbcInterface::IBC_COSMWASM),
+                metadata: Default::default(),
+            },
+            chain_id!("1"),
+            H256::default(),
+            EventProvableHeight::Min(Height::new(1)),
+            event.into(),
+        ));
+
+        self.queue.enqueue(op.clone(), &self.filter).await.unwrap();
+
+        op
+    }
+
+    /// Run the pass of the plugin over all items it is interested in, and return all items that
+    /// are ready to be processed afterwards, in the order they will be processed in.
+    async fn run(&self) -> Vec<Op<VoyagerMessage>> {
+        self.queue
+            .optimize(
+                &self.module.plugin_name(),
+                &self.filter,
+                &ModulePass(&self.module),
+            )
+            .await
+            .unwrap();
+
+        let mut ready = vec![];
+
+        while let Some(op) = self
+            .queue
+            .process(&self.filter, |op, _| async move { (op, Ok(vec![])) })
+            .await
+            .unwrap()
+        {
+            ready.push(op);
+        }
+
+        ready
+    }
+}
+
+fn channel_id(id: u32) -> ChannelId {
+    ChannelId::new(id.try_into().unwrap())
+}
+
+fn channel(channel_id: ChannelId) -> ChannelMetadata {
+    ChannelMetadata {
+        channel_id,
+        version: "ucs03-zkgm-0".to_owned(),
+        connection: ConnectionMetadata {
+            client_id: ClientId::new(1.try_into().unwrap()),
+            connection_id: ConnectionId::new(1.try_into().unwrap()),
+        },
+    }
+}
+
+fn packet(source_channel_id: u32, destination_channel_id: u32, sequence: u8) -> Packet {
+    Packet {
+        source_channel_id: channel_id(source_channel_id),
+        destination_channel_id: channel_id(destination_channel_id),
+        data: Bytes::new(vec![sequence]),
+        timeout_height: 0,
+        timeout_timestamp: Timestamp::from_secs(1_000),
+    }
+}
+
+fn metadata(packet: &Packet) -> PacketMetadata {
+    PacketMetadata {
+        source_channel: channel(packet.source_channel_id),
+        destination_channel: channel(packet.destination_channel_id),
+        timeout_height: packet.timeout_height,
+        timeout_timestamp: packet.timeout_timestamp,
+    }
+}
+
+fn packet_send(packet: &Packet) -> PacketSend {
+    PacketSend {
+        packet_data: packet.data.clone(),
+        packet: metadata(packet),
+    }
+}
+
+fn write_ack(packet: &Packet, ack: u8) -> WriteAck {
+    WriteAck {
+        packet_data: packet.data.clone(),
+        packet: metadata(packet),
+        acknowledgement: Bytes::new(vec![ack]),
+    }
+}
+
+fn submit_tx(datagram: IbcDatagram) -> Op<VoyagerMessage> {
+    call(SubmitTx {
+        chain_id: chain_id!("union-devnet-1"),
+        datagrams: vec![datagram],
+        signers: None,
+    })
+}
+
+fn sorted_by_hash(mut packets: Vec<Packet>) -> Vec<Packet> {
+    packets.sort_by_cached_key(Packet::hash);
+    packets
+}
+
+#[tokio::test]
+async fn full_batches_are_ready() {
+    let harness = Harness::new(2, Duration::from_secs(60)).await;
+
+    let packets = [packet(1, 2, 1), packet(1, 2, 2), packet(1, 2, 3)];
+
+    for packet in &packets {
+        harness.event(packet_send(packet)).await;
+    }
+
+    // the third packet doesn't fit in the first batch, so the first batch is full and the second
+    // one waits for more packets
+    assert_eq!(
+        harness.run().await,
+        vec![submit_tx(IbcDatagram::new::<IbcUnion>(MsgBatchSend {
+            packets: sorted_by_hash(packets[..2].to_vec()),
+        }))]
+    );
+
+    // the second batch is not full yet
+    harness.event(packet_send(&packet(1, 2, 4))).await;
+
+    assert_eq!(harness.run().await, vec![]);
+}
+
+#[tokio::test]
+async fn batches_are_ready_after_max_wait_time() {
+    let harness = Harness::new(10, Duration::from_millis(50)).await;
+
+    harness.event(packet_send(&packet(1, 2, 1))).await;
+
+    assert_eq!(harness.run().await, vec![]);
+
+    // exactly at the max wait time, the batch still waits
+    advance(Duration::from_millis(50));
+
+    assert_eq!(harness.run().await, vec![]);
+
+    advance(Duration::from_millis(1));
+
+    assert_eq!(
+        harness.run().await,
+        vec![submit_tx(IbcDatagram::new::<IbcUnion>(MsgBatchSend {
+            packets: vec![packet(1, 2, 1)],
+        }))]
+    );
+}
+
+#[tokio::test]
+async fn acks_are_batched_on_the_destination_channel() {
+    let harness = Harness::new(2, Duration::from_secs(60)).await;
+
+    let packets = [packet(2, 1, 1), packet(2, 1, 2), packet(2, 1, 3)];
+
+    for (packet, ack) in packets.iter().zip([1, 2, 3]) {
+        harness.event(write_ack(packet, ack)).await;
+    }
+
+    let mut batch = packets[..2]
+        .iter()
+        .cloned()
+        .zip([1, 2].map(|ack| Bytes::new(vec![ack])))
+        .collect::<Vec<_>>();
+    batch.sort_by_cached_key(|(packet, _)| packet.hash());
+
+    let (packets, acks) = batch.into_iter().unzip();
+
+    assert_eq!(
+        harness.run().await,
+        vec![submit_tx(IbcDatagram::new::<IbcUnion>(MsgBatchAcks {
+            packets,
+            acks
+        }))]
+    );
+}
+
+#[tokio::test]
+async fn events_of_other_channels_are_not_batched() {
+    let harness = Harness::new(2, Duration::from_secs(60)).await;
+
+    // sent from another channel
+    let send = harness.event(packet_send(&packet(3, 2, 1))).await;
+    // acknowledged on another channel
+    let ack = harness.event(write_ack(&packet(1, 2, 2), 1)).await;
+
+    assert_eq!(harness.run().await, vec![send, ack]);
+}
@@ -6,6 +6,11 @@ use jsonrpsee::{
     Extensions,
 };
 use regex::Regex;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Metadata, ObjectValidation, Schema, SchemaObject, SubschemaValidation},
+    JsonSchema,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use tracing::{debug, instrument, trace};
@@ -34,7 +39,7 @@ pub struct Module {
     pub packet_event_filters: Vec<PacketEventFilter>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub connection_event_filters: Vec<ConnectionEventFilter>,
@@ -68,7 +73,7 @@ impl Plugin for Module {
 }
 
 #[serde_as]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ConnectionEventFilter {
     #[serde(default)]
     pub chain_id: FieldFilter,
@@ -105,7 +110,7 @@ impl ConnectionEventFilter {
 }
 
 #[serde_as]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ChannelEventFilter {
     #[serde(default)]
     pub chain_id: FieldFilter,
@@ -153,7 +158,7 @@ impl ChannelEventFilter {
 }
 
 #[serde_as]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PacketEventFilter {
     #[serde(default)]
     pub chain_id: FieldFilter,
@@ -250,6 +255,44 @@ fn match_any() -> Regex {
     Regex::new(".*").unwrap()
 }
 
+impl JsonSchema for FieldFilter {
+    fn schema_name() -> String {
+        "FieldFilter".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let not = SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(ObjectValidation {
+                properties: [("not".to_owned(), gen.subschema_for::<String>())]
+                    .into_iter()
+                    .collect(),
+                required: ["not".to_owned()].into_iter().collect(),
+                additional_properties: Some(Box::new(Schema::Bool(false))),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "A regex that the field must match, or `{ \"not\": <regex> }` for a regex that \
+                     it must not match"
+                        .to_owned(),
+                ),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![gen.subschema_for::<String>(), not.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 impl FieldFilter {
     fn to_jaq(&self) -> String {
         match self {
@@ -11,6 +11,7 @@ use jsonrpsee::{
     types::ErrorObject,
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::{debug, info, instrument, warn};
@@ -43,7 +44,7 @@ async fn main() {
 
 pub struct Module {}
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Config {}
 
 impl Plugin for Module {
@@ -221,6 +222,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                                     proof_height: client_meta.counterparty_height.height(),
                                 },
                             ))],
+                            signers: None,
                         }))
                     }
                     ProofType::Membership => {
@@ -4,6 +4,7 @@ use jsonrpsee::{
     core::{async_trait, RpcResult},
     Extensions,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, instrument};
 use unionlabs::never::Never;
@@ -34,7 +35,7 @@ async fn main() {
 
 pub struct Module {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {}
 
@@ -130,23 +131,30 @@ impl Module {
         {
             info!("client is older than threshold");
 
+            let update = voyager_client
+                .with_finality_delay(
+                    chain_id.clone(),
+                    promise(
+                        [call(FetchUpdateHeaders {
+                            client_type: client_info.client_type,
+                            chain_id: client_state_meta.counterparty_chain_id,
+                            counterparty_chain_id: chain_id.clone(),
+                            client_id: client_id.clone(),
+                            update_from: client_state_meta.counterparty_height,
+                            update_to: latest_finalized_height,
+                        })],
+                        [],
+                        AggregateSubmitTxFromOrderedHeaders {
+                            ibc_spec_id: ibc_spec_id.clone(),
+                            chain_id: chain_id.clone(),
+                            client_id: client_id.clone(),
+                        },
+                    ),
+                )
+                .await?;
+
             Ok(conc([
-                promise(
-                    [call(FetchUpdateHeaders {
-                        client_type: client_info.client_type,
-                        chain_id: client_state_meta.counterparty_chain_id,
-                        counterparty_chain_id: chain_id.clone(),
-                        client_id: client_id.clone(),
-                        update_from: client_state_meta.counterparty_height,
-                        update_to: latest_finalized_height,
-                    })],
-                    [],
-                    AggregateSubmitTxFromOrderedHeaders {
-                        ibc_spec_id: ibc_spec_id.clone(),
-                        chain_id: chain_id.clone(),
-                        client_id: client_id.clone(),
-                    },
-                ),
+                update,
                 seq([
                     call(WaitForTrustedHeight {
                         chain_id: chain_id.clone(),
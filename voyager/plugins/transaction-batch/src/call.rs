@@ -1,8 +1,13 @@
 use std::cmp::Ordering;
 
+use alloy::sol_types::SolValue;
 use enumorph::Enumorph;
 use ibc_classic_spec::IbcClassic;
-use ibc_union_spec::{query::PacketsByBatchHash, IbcUnion};
+use ibc_union_spec::{
+    event::{PacketMetadata, PacketSend},
+    query::PacketsByBatchHash,
+    IbcUnion,
+};
 use jsonrpsee::{core::RpcResult, types::ErrorObject};
 use macros::model;
 use serde_json::json;
@@ -17,7 +22,7 @@ use voyager_sdk::{
     primitives::{ChainId, QueryHeight},
     rpc::MISSING_STATE_ERROR_CODE,
     types::RawClientId,
-    vm::{data, now, promise, Op},
+    vm::{conc, data, defer, now, promise, seq, Op},
     VoyagerClient,
 };
 
@@ -25,6 +30,7 @@ use crate::{
     call,
     callback::{make_msgs, MakeBatchTransaction, MakeIbcMessagesFromUpdate, ModuleCallback},
     data::{BatchableEvent, EventClassic, EventUnion},
+    verify::{commit, ProofVerifier},
     IbcSpecExt, Module,
 };
 
@@ -122,6 +128,42 @@ where
             }
         }
 
+        let needs_update = match target_height {
+            EventProvableHeight::Min(target_height) => {
+                client_state_meta.counterparty_height < target_height
+            }
+            EventProvableHeight::Exactly(target_height) => {
+                client_state_meta.counterparty_height != target_height
+            }
+        };
+
+        if needs_update {
+            let policy = &module
+                .client_configs
+                .config_for_client::<V>(&self.client_id)
+                .policy;
+
+            if let Err(allowed_at) = module.update_history.try_record(
+                &RawClientId::new(self.client_id.clone()),
+                policy,
+                now(),
+            ) {
+                info!(
+                    client_id = %self.client_id,
+                    allowed_at,
+                    "client update is limited by the policy of the client, deferring"
+                );
+
+                return Ok(seq([
+                    defer(allowed_at),
+                    call(PluginMessage::new(
+                        module.plugin_name(),
+                        ModuleCall::from(self),
+                    )),
+                ]));
+            }
+        }
+
         match target_height {
             EventProvableHeight::Min(target_height) => {
                 if client_state_meta.counterparty_height >= target_height {
@@ -141,7 +183,7 @@ where
                         client_state_meta.counterparty_height,
                     )
                 } else {
-                    Ok(promise(
+                    let update = promise(
                         [call(FetchUpdateHeaders {
                             client_type: client_info.client_type,
                             counterparty_chain_id: module.chain_id.clone(),
@@ -169,7 +211,11 @@ where
                                 batches: self.batches,
                             }),
                         ),
-                    ))
+                    );
+
+                    voyager_client
+                        .with_finality_delay(module.chain_id.clone(), update)
+                        .await
                 }
             }
             EventProvableHeight::Exactly(target_height) => {
@@ -190,24 +236,30 @@ where
                             client_state_meta.counterparty_height,
                         )
                     }
-                    Ordering::Less => Ok(promise(
-                        [call(FetchUpdateHeaders {
-                            client_type: client_info.client_type,
-                            counterparty_chain_id: module.chain_id.clone(),
-                            chain_id: client_state_meta.counterparty_chain_id,
-                            client_id: RawClientId::new(self.client_id.clone()),
-                            update_from: client_state_meta.counterparty_height,
-                            update_to: latest_height,
-                        })],
-                        [],
-                        PluginMessage::new(
-                            module.plugin_name(),
-                            ModuleCallback::from(MakeIbcMessagesFromUpdate::<V> {
-                                client_id: self.client_id.clone(),
-                                batches: self.batches,
-                            }),
-                        ),
-                    )),
+                    Ordering::Less => {
+                        let update = promise(
+                            [call(FetchUpdateHeaders {
+                                client_type: client_info.client_type,
+                                counterparty_chain_id: module.chain_id.clone(),
+                                chain_id: client_state_meta.counterparty_chain_id,
+                                client_id: RawClientId::new(self.client_id.clone()),
+                                update_from: client_state_meta.counterparty_height,
+                                update_to: latest_height,
+                            })],
+                            [],
+                            PluginMessage::new(
+                                module.plugin_name(),
+                                ModuleCallback::from(MakeIbcMessagesFromUpdate::<V> {
+                                    client_id: self.client_id.clone(),
+                                    batches: self.batches,
+                                }),
+                            ),
+                        );
+
+                        voyager_client
+                            .with_finality_delay(module.chain_id.clone(), update)
+                            .await
+                    }
                     // update backwards
                     // currently this is only supported in sui, and as such has some baked-in assumptions about the semantics of when this branch is hit
                     Ordering::Greater => {
@@ -216,7 +268,7 @@ where
                             client_state_meta.counterparty_height
                         );
 
-                        Ok(promise(
+                        let update = promise(
                             [call(FetchUpdateHeaders {
                                 client_type: client_info.client_type,
                                 counterparty_chain_id: module.chain_id.clone(),
@@ -233,7 +285,11 @@ where
                                     batches: self.batches,
                                 }),
                             ),
-                        ))
+                        );
+
+                        voyager_client
+                            .with_finality_delay(module.chain_id.clone(), update)
+                            .await
                     }
                 }
             }
@@ -263,7 +319,11 @@ impl MakeMsg<IbcUnion> {
             msg = IbcUnion::event_name(&self.event)
         )
     )]
-    pub async fn call(self, voyager_client: &VoyagerClient) -> RpcResult<Op<VoyagerMessage>> {
+    pub async fn call(
+        self,
+        module: &Module,
+        voyager_client: &VoyagerClient,
+    ) -> RpcResult<Op<VoyagerMessage>> {
         let MakeMsg {
             origin_chain_id,
             origin_chain_proof_height,
@@ -271,6 +331,13 @@ impl MakeMsg<IbcUnion> {
             event,
         } = self;
 
+        let verifier = module.verify_proofs.then_some(ProofVerifier {
+            voyager_client,
+            origin_chain_id: &origin_chain_id,
+            origin_chain_proof_height,
+            target_chain_id: &target_chain_id,
+        });
+
         match event {
             EventUnion::ConnectionOpenInit(connection_open_init_event) => {
                 let counterparty_client_id = connection_open_init_event.counterparty_client_id;
@@ -316,6 +383,18 @@ impl MakeMsg<IbcUnion> {
                     .proof;
                 debug!(%connection_proof, "connection proof");
 
+                if let Some(verifier) = &verifier {
+                    verifier
+                        .verify(
+                            counterparty_client_id,
+                            &target_client_info,
+                            ibc_union_spec::path::ConnectionPath { connection_id }.key(),
+                            Some(commit(connection_state.abi_encode_params())),
+                            connection_proof.clone(),
+                        )
+                        .await?;
+                }
+
                 let encoded_connection_state_proof = voyager_client
                     .encode_proof::<IbcUnion>(
                         target_client_info.client_type.clone(),
@@ -382,6 +461,18 @@ impl MakeMsg<IbcUnion> {
                     .proof;
                 debug!(%connection_proof, "connection proof");
 
+                if let Some(verifier) = &verifier {
+                    verifier
+                        .verify(
+                            counterparty_client_id,
+                            &target_client_info,
+                            ibc_union_spec::path::ConnectionPath { connection_id }.key(),
+                            Some(commit(connection_state.abi_encode_params())),
+                            connection_proof.clone(),
+                        )
+                        .await?;
+                }
+
                 let encoded_connection_state_proof = voyager_client
                     .encode_proof::<IbcUnion>(
                         target_client_info.client_type.clone(),
@@ -446,6 +537,18 @@ impl MakeMsg<IbcUnion> {
                     .proof;
                 debug!(%connection_proof, "connection proof");
 
+                if let Some(verifier) = &verifier {
+                    verifier
+                        .verify(
+                            counterparty_client_id,
+                            &target_client_info,
+                            ibc_union_spec::path::ConnectionPath { connection_id }.key(),
+                            Some(commit(connection_state.abi_encode_params())),
+                            connection_proof.clone(),
+                        )
+                        .await?;
+                }
+
                 let encoded_connection_state_proof = voyager_client
                     .encode_proof::<IbcUnion>(
                         target_client_info.client_type.clone(),
@@ -469,7 +572,7 @@ impl MakeMsg<IbcUnion> {
             EventUnion::ChannelOpenInit(event) => {
                 let proof = voyager_client
                     .query_ibc_proof(
-                        origin_chain_id,
+                        origin_chain_id.clone(),
                         QueryHeight::Specific(origin_chain_proof_height),
                         ibc_union_spec::path::ChannelPath {
                             channel_id: event.channel_id,
@@ -480,11 +583,36 @@ impl MakeMsg<IbcUnion> {
 
                 let client_info = voyager_client
                     .client_info::<IbcUnion>(
-                        target_chain_id,
+                        target_chain_id.clone(),
                         event.connection.counterparty_client_id,
                     )
                     .await?;
 
+                if let Some(verifier) = &verifier {
+                    let channel = voyager_client
+                        .query_ibc_state(
+                            origin_chain_id.clone(),
+                            QueryHeight::Specific(origin_chain_proof_height),
+                            ibc_union_spec::path::ChannelPath {
+                                channel_id: event.channel_id,
+                            },
+                        )
+                        .await?;
+
+                    verifier
+                        .verify(
+                            event.connection.counterparty_client_id,
+                            &client_info,
+                            ibc_union_spec::path::ChannelPath {
+                                channel_id: event.channel_id,
+                            }
+                            .key(),
+                            Some(commit(channel.abi_encode())),
+                            proof.proof.clone(),
+                        )
+                        .await?;
+                }
+
                 let encoded_proof = voyager_client
                     .encode_proof::<IbcUnion>(
                         client_info.client_type,
@@ -515,7 +643,7 @@ impl MakeMsg<IbcUnion> {
             EventUnion::ChannelOpenTry(event) => {
                 let proof = voyager_client
                     .query_ibc_proof(
-                        origin_chain_id,
+                        origin_chain_id.clone(),
                         QueryHeight::Specific(origin_chain_proof_height),
                         ibc_union_spec::path::ChannelPath {
                             channel_id: event.channel_id,
@@ -526,11 +654,36 @@ impl MakeMsg<IbcUnion> {
 
                 let client_info = voyager_client
                     .client_info::<IbcUnion>(
-                        target_chain_id,
+                        target_chain_id.clone(),
                         event.connection.counterparty_client_id,
                     )
                     .await?;
 
+                if let Some(verifier) = &verifier {
+                    let channel = voyager_client
+                        .query_ibc_state(
+                            origin_chain_id.clone(),
+                            QueryHeight::Specific(origin_chain_proof_height),
+                            ibc_union_spec::path::ChannelPath {
+                                channel_id: event.channel_id,
+                            },
+                        )
+                        .await?;
+
+                    verifier
+                        .verify(
+                            event.connection.counterparty_client_id,
+                            &client_info,
+                            ibc_union_spec::path::ChannelPath {
+                                channel_id: event.channel_id,
+                            }
+                            .key(),
+                            Some(commit(channel.abi_encode())),
+                            proof.proof.clone(),
+                        )
+                        .await?;
+                }
+
                 let encoded_proof = voyager_client
                     .encode_proof::<IbcUnion>(
                         client_info.client_type,
@@ -555,7 +708,7 @@ impl MakeMsg<IbcUnion> {
             EventUnion::ChannelOpenAck(event) => {
                 let proof = voyager_client
                     .query_ibc_proof(
-                        origin_chain_id,
+                        origin_chain_id.clone(),
                         QueryHeight::Specific(origin_chain_proof_height),
                         ibc_union_spec::path::ChannelPath {
                             channel_id: event.channel_id,
@@ -566,11 +719,36 @@ impl MakeMsg<IbcUnion> {
 
                 let client_info = voyager_client
                     .client_info::<IbcUnion>(
-                        target_chain_id,
+                        target_chain_id.clone(),
                         event.connection.counterparty_client_id,
                     )
                     .await?;
 
+                if let Some(verifier) = &verifier {
+                    let channel = voyager_client
+                        .query_ibc_state(
+                            origin_chain_id.clone(),
+                            QueryHeight::Specific(origin_chain_proof_height),
+                            ibc_union_spec::path::ChannelPath {
+                                channel_id: event.channel_id,
+                            },
+                        )
+                        .await?;
+
+                    verifier
+                        .verify(
+                            event.connection.counterparty_client_id,
+                            &client_info,
+                            ibc_union_spec::path::ChannelPath {
+                                channel_id: event.channel_id,
+                            }
+                            .key(),
+                            Some(commit(channel.abi_encode())),
+                            proof.proof.clone(),
+                        )
+                        .await?;
+                }
+
                 let encoded_proof = voyager_client
                     .encode_proof::<IbcUnion>(
                         client_info.client_type,
@@ -595,7 +773,7 @@ impl MakeMsg<IbcUnion> {
 
                 let proof = voyager_client
                     .query_ibc_proof(
-                        origin_chain_id,
+                        origin_chain_id.clone(),
                         QueryHeight::Specific(origin_chain_proof_height),
                         ibc_union_spec::path::BatchPacketsPath::from_packets(&[packet.clone()]),
                     )
@@ -604,11 +782,22 @@ impl MakeMsg<IbcUnion> {
 
                 let client_info = voyager_client
                     .client_info::<IbcUnion>(
-                        target_chain_id,
+                        target_chain_id.clone(),
                         event.packet.destination_channel.connection.client_id,
                     )
                     .await?;
 
+                if let Some(verifier) = &verifier {
+                    verifier
+                        .verify_batch(
+                            event.packet.destination_channel.connection.client_id,
+                            &client_info,
+                            ibc_union_spec::path::BatchPacketsPath::from_packets(&[packet.clone()]),
+                            proof.proof.clone(),
+                        )
+                        .await?;
+                }
+
                 let encoded_proof = voyager_client
                     .encode_proof::<IbcUnion>(
                         client_info.client_type,
@@ -644,7 +833,7 @@ impl MakeMsg<IbcUnion> {
 
                 let proof = voyager_client
                     .query_ibc_proof(
-                        origin_chain_id,
+                        origin_chain_id.clone(),
                         QueryHeight::Specific(origin_chain_proof_height),
                         ibc_union_spec::path::BatchPacketsPath {
                             batch_hash: event.batch_hash,
@@ -655,11 +844,24 @@ impl MakeMsg<IbcUnion> {
 
                 let client_info = voyager_client
                     .client_info::<IbcUnion>(
-                        target_chain_id,
+                        target_chain_id.clone(),
                         event.destination_channel.connection.client_id,
                     )
                     .await?;
 
+                if let Some(verifier) = &verifier {
+                    verifier
+                        .verify_batch(
+                            event.destination_channel.connection.client_id,
+                            &client_info,
+                            ibc_union_spec::path::BatchPacketsPath {
+                                batch_hash: event.batch_hash,
+                            },
+                            proof.proof.clone(),
+                        )
+                        .await?;
+                }
+
                 let encoded_proof = voyager_client
                     .encode_proof::<IbcUnion>(
                         client_info.client_type,
@@ -668,16 +870,68 @@ impl MakeMsg<IbcUnion> {
                     )
                     .await?;
 
-                Ok(data(IbcDatagram::new::<IbcUnion>(
-                    ibc_union_spec::datagram::Datagram::from(
-                        ibc_union_spec::datagram::MsgPacketRecv {
-                            relayer_msgs: vec![vec![].into(); packets.len()],
-                            packets,
-                            proof: encoded_proof,
-                            proof_height: origin_chain_proof_height.height(),
-                        },
-                    ),
-                )))
+                let datagram = ibc_union_spec::datagram::Datagram::from(
+                    ibc_union_spec::datagram::MsgPacketRecv {
+                        relayer_msgs: vec![vec![].into(); packets.len()],
+                        packets: packets.clone(),
+                        proof: encoded_proof,
+                        proof_height: origin_chain_proof_height.height(),
+                    },
+                );
+
+                let size = IbcUnion::encoded_size(&datagram);
+
+                match module.max_message_size {
+                    // the packets of a batch are each committed on their own when they are sent,
+                    // so they can also be received individually if the message with the proof of
+                    // the batch would be too large
+                    Some(max_message_size) if size > max_message_size && packets.len() > 1 => {
+                        // the order of the packets of ordered channels is tracked by the batch hash
+                        if module
+                            .ordered_channels
+                            .contains(&event.destination_channel.channel_id)
+                        {
+                            warn!(
+                                size,
+                                max_message_size,
+                                batch_hash = %event.batch_hash,
+                                "batch exceeds the max message size, but is sent over an ordered \
+                                channel and cannot be received packet by packet"
+                            );
+
+                            return Ok(data(IbcDatagram::new::<IbcUnion>(datagram)));
+                        }
+
+                        info!(
+                            size,
+                            max_message_size,
+                            batch_hash = %event.batch_hash,
+                            packets = packets.len(),
+                            "batch exceeds the max message size, proving its packets individually"
+                        );
+
+                        Ok(conc(packets.into_iter().map(|packet| {
+                            call(PluginMessage::new(
+                                module.plugin_name(),
+                                ModuleCall::from(MakeMsg::<IbcUnion> {
+                                    origin_chain_id: origin_chain_id.clone(),
+                                    origin_chain_proof_height,
+                                    target_chain_id: target_chain_id.clone(),
+                                    event: EventUnion::PacketSend(PacketSend {
+                                        packet_data: packet.data,
+                                        packet: PacketMetadata {
+                                            source_channel: event.source_channel.clone(),
+                                            destination_channel: event.destination_channel.clone(),
+                                            timeout_height: packet.timeout_height,
+                                            timeout_timestamp: packet.timeout_timestamp,
+                                        },
+                                    }),
+                                }),
+                            ))
+                        })))
+                    }
+                    _ => Ok(data(IbcDatagram::new::<IbcUnion>(datagram))),
+                }
             }
 
             EventUnion::WriteAck(event) => {
@@ -685,7 +939,7 @@ impl MakeMsg<IbcUnion> {
 
                 let proof = voyager_client
                     .query_ibc_proof(
-                        origin_chain_id,
+                        origin_chain_id.clone(),
                         QueryHeight::Specific(origin_chain_proof_height),
                         ibc_union_spec::path::BatchReceiptsPath::from_packets(&[packet.clone()]),
                     )
@@ -694,11 +948,24 @@ impl MakeMsg<IbcUnion> {
 
                 let client_info = voyager_client
                     .client_info::<IbcUnion>(
-                        target_chain_id,
+                        target_chain_id.clone(),
                         event.packet.source_channel.connection.client_id,
                     )
                     .await?;
 
+                if let Some(verifier) = &verifier {
+                    verifier
+                        .verify_batch(
+                            event.packet.source_channel.connection.client_id,
+                            &client_info,
+                            ibc_union_spec::path::BatchReceiptsPath::from_packets(
+                                &[packet.clone()],
+                            ),
+                            proof.proof.clone(),
+                        )
+                        .await?;
+                }
+
                 let encoded_proof = voyager_client
                     .encode_proof::<IbcUnion>(
                         client_info.client_type,
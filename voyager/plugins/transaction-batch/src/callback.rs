@@ -1,4 +1,7 @@
-use std::collections::VecDeque;
+use std::{
+    collections::{BTreeSet, VecDeque},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use either::Either;
 use enumorph::Enumorph;
@@ -22,13 +25,15 @@ use voyager_sdk::{
     },
     primitives::{ChainId, ClientStateMeta, IbcSpec, QueryHeight},
     types::RawClientId,
-    vm::{call, conc, noop, promise, seq, Op},
+    vm::{call, conc, data, noop, promise, seq, Op},
     VoyagerClient,
 };
 
 use crate::{
     call::{MakeMsg, MakeTransactionBatchesWithUpdate, ModuleCall},
+    coalesce::PendingTransaction,
     data::{BatchableEvent, ModuleData, ProofUnavailable},
+    size::split_by_size,
     IbcSpecExt, Module,
 };
 
@@ -42,7 +47,7 @@ pub enum ModuleCallback {
     MakeBatchTransactionUnion(MakeBatchTransaction<IbcUnion>),
 }
 
-/// Given an [`OrderedMsgUpdateClients`], returns [`Op`]s that generate [`IbcMessage`]s with proofs at the highest height of the updates.
+/// Given an [`OrderedMsgUpdateClients`], returns [`Op`]s that submit the updates, and then generate [`IbcMessage`]s with proofs at the height the client trusts once the updates are confirmed.
 #[model]
 #[serde(bound(serialize = "", deserialize = ""))]
 pub struct MakeIbcMessagesFromUpdate<V: IbcSpecExt> {
@@ -128,10 +133,72 @@ where
     ModuleCall: From<MakeMsg<V>> + From<MakeTransactionBatchesWithUpdate<V>>,
     ModuleCallback: From<MakeBatchTransaction<V>>,
 {
-    let head = batches.pop();
+    if let Some(updates) = updates {
+        // the update is submitted on its own, and the proofs are only fetched once it is confirmed
+        // on this chain, at the height the client then trusts. fetching them concurrently with the
+        // update is wasted work if the update doesn't land, and the proofs don't match the client
+        // if it lands at a different height than expected.
+        let update = promise(
+            [],
+            [],
+            PluginMessage::new(
+                module_server.plugin_name(),
+                ModuleCallback::from(MakeBatchTransaction::<V> {
+                    client_id: client_id.clone(),
+                    updates: Some(updates),
+                    ordered_packets: vec![],
+                }),
+            ),
+        );
+
+        // no messages, only updates: thread the updates through
+        if batches.is_empty() {
+            return Ok(update);
+        }
+
+        return Ok(seq([
+            update,
+            call(WaitForTrustedHeight {
+                chain_id: module_server.chain_id.clone(),
+                ibc_spec_id: V::ID,
+                client_id: RawClientId::new(client_id.clone()),
+                height: new_trusted_height,
+                finalized: false,
+            }),
+            // wait for 1 extra block to ensure that the transaction containing the update is in state, and the messages will not end up in the same block (and potentially get reordered)
+            call(WaitForHeightRelative {
+                chain_id: module_server.chain_id.clone(),
+                height_diff: 1,
+                finalized: false,
+            }),
+            // the client is updated at this point, so the batches are made at the height it trusts
+            call(PluginMessage::new(
+                module_server.plugin_name(),
+                ModuleCall::from(MakeTransactionBatchesWithUpdate::<V> { client_id, batches }),
+            )),
+        ]));
+    }
+
+    // packets that have to be received in order must be submitted in the order of the batches they
+    // are in, so the first batch is sent first and all batches are submitted in sequence
+    let ordered = batches
+        .iter()
+        .flatten()
+        .any(|e| V::ordered_packet_key(module_server, &e.event).is_some());
+
+    let head = if ordered {
+        (!batches.is_empty()).then(|| batches.remove(0))
+    } else {
+        batches.pop()
+    };
     let tail = batches;
 
-    let mk_batch_promise = |batch: Vec<BatchableEvent<_>>, updates: Option<OrderedHeaders>| {
+    let mk_batch_promise = |batch: Vec<BatchableEvent<_>>| {
+        let ordered_packets = batch
+            .iter()
+            .filter_map(|e| V::ordered_packet_key(module_server, &e.event))
+            .collect();
+
         promise(
             batch.into_iter().map(|batchable_event| {
                 if let EventProvableHeight::Min(provable_height) = batchable_event.provable_height {
@@ -171,53 +238,24 @@ where
                 module_server.plugin_name(),
                 ModuleCallback::from(MakeBatchTransaction {
                     client_id: client_id.clone(),
-                    updates,
+                    updates: None,
+                    ordered_packets,
                 }),
             ),
         )
     };
 
-    match (head, updates) {
-        // both messages and updates: make one batch of messages including the updates, and then queue a separate message that waits for the effect of that update to be included
-        (Some(head), Some(updates)) => {
-            Ok(conc(
-                [mk_batch_promise(head, Some(updates))]
-                    .into_iter()
-                    .chain((!tail.is_empty()).then(|| {
-                        seq([
-                            call(WaitForTrustedHeight {
-                                chain_id: module_server.chain_id.clone(),
-                                ibc_spec_id: IbcUnion::ID,
-                                client_id: RawClientId::new(client_id.clone()),
-                                height: new_trusted_height,
-                                finalized: false,
-                            }),
-                            // wait for 1 extra block to ensure that the transaction containing the update is in state, and these messages will not end up in the same block (and potentially get reordered)
-                            call(WaitForHeightRelative {
-                                chain_id: module_server.chain_id.clone(),
-                                height_diff: 1,
-                                finalized: false,
-                            }),
-                            call(PluginMessage::new(
-                                module_server.plugin_name(),
-                                ModuleCall::from(MakeTransactionBatchesWithUpdate::<V> {
-                                    client_id,
-                                    batches: tail,
-                                }),
-                            )),
-                        ])
-                    })),
-            ))
-        }
-        // no messages, only updates: thread the updates through
-        (None, Some(updates)) => Ok(mk_batch_promise(vec![], Some(updates))),
-        // only messages, no updates: the client is assumed to already be updated to this height, so we can safely batch the updates without waiting
-        (Some(head), None) => Ok(conc([
-            mk_batch_promise(head, None),
-            conc(tail.into_iter().map(|batch| mk_batch_promise(batch, None))),
+    // the client is already updated to this height, so we can safely batch the messages without waiting
+    match head {
+        Some(head) if ordered => Ok(seq([mk_batch_promise(head)]
+            .into_iter()
+            .chain(tail.into_iter().map(mk_batch_promise)))),
+        Some(head) => Ok(conc([
+            mk_batch_promise(head),
+            conc(tail.into_iter().map(mk_batch_promise)),
         ])),
         // neither updates nor messages?
-        (None, None) => {
+        None => {
             warn!("neither updates nor messages passed to make_msgs, noop");
 
             Ok(noop())
@@ -229,8 +267,11 @@ where
 pub struct MakeBatchTransaction<V: IbcSpecExt> {
     // NOTE: We could technically fetch this from the information in the callback data messages, but this is just so much easier
     pub client_id: V::ClientId,
-    /// Updates to send before the messages in this message's callback data. If this is `None`, then that means the updates have been included in a previous batch, and this will instead be enqueued with a WaitForTrustedHeight in front of it.
+    /// Updates to send before the messages in this message's callback data. Updates are sent in a batch of their own, and the messages that need them are only made once they are confirmed. If this is `None`, then that means the updates have been included in a previous batch, and the messages will be submitted after a WaitForClientUpdate for their proof height.
     pub updates: Option<OrderedHeaders>,
+    /// The packets in this batch that have to be received in the order they were sent in, in that order. The messages are built concurrently and are passed to the callback in the order they were built in, so they are sorted by this before being submitted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ordered_packets: Vec<V::PacketKey>,
 }
 
 impl<V: IbcSpecExt> MakeBatchTransaction<V>
@@ -246,6 +287,13 @@ where
         chain_id: ChainId,
         datas: VecDeque<Data>,
     ) -> RpcResult<Op<VoyagerMessage>> {
+        let signers = module_server
+            .client_configs
+            .config_for_client::<V>(&self.client_id)
+            .policy
+            .allowed_relayers
+            .clone();
+
         if datas.is_empty() {
             warn!("no IBC messages in queue! this likely means that all of the IBC messages that were queued to be sent were already sent to the destination chain");
         }
@@ -265,20 +313,9 @@ where
                     })
             });
 
-        let mut msgs = msgs.into_iter().peekable();
-
-        // TODO: We may need to sort packet messages when we support ordered channels
-        // msgs.sort_unstable_by(|a, b| match (a, b) {
-        //     (IbcMessage::RecvPacket(_), IbcMessage::RecvPacket(_)) => todo!(),
-        //     (IbcMessage::RecvPacket(_), IbcMessage::AcknowledgePacket(_)) => todo!(),
-        //     (IbcMessage::RecvPacket(_), IbcMessage::TimeoutPacket(_)) => todo!(),
-        //     (IbcMessage::AcknowledgePacket(_), IbcMessage::RecvPacket(_)) => todo!(),
-        //     (IbcMessage::AcknowledgePacket(_), IbcMessage::AcknowledgePacket(_)) => todo!(),
-        //     (IbcMessage::AcknowledgePacket(_), IbcMessage::TimeoutPacket(_)) => todo!(),
-        //     (IbcMessage::TimeoutPacket(_), IbcMessage::RecvPacket(_)) => todo!(),
-        //     (IbcMessage::TimeoutPacket(_), IbcMessage::AcknowledgePacket(_)) => todo!(),
-        //     (IbcMessage::TimeoutPacket(_), IbcMessage::TimeoutPacket(_)) => todo!(),
-        // });
+        let mut msgs = sort_ordered_packets::<V>(msgs, &self.ordered_packets)
+            .into_iter()
+            .peekable();
 
         let client_info = voyager_client
             .client_info::<V>(chain_id.clone(), self.client_id.clone())
@@ -302,7 +339,7 @@ where
                 )
                 .await?;
 
-            Some(promise(
+            let update = promise(
                 [call(FetchUpdateHeaders {
                     client_type: client_info.client_type.clone(),
                     counterparty_chain_id: module_server.chain_id.clone(),
@@ -322,15 +359,20 @@ where
                             .collect()],
                     }),
                 ),
-            ))
+            );
+
+            Some(
+                voyager_client
+                    .with_finality_delay(module_server.chain_id.clone(), update)
+                    .await?,
+            )
         } else {
             None
         };
 
         let msg = match self.updates {
-            Some(updates) => call(SubmitTx {
-                chain_id: chain_id.clone(),
-                datagrams: updates
+            Some(updates) => {
+                let datagrams = updates
                     .headers
                     .into_iter()
                     .map(|(_, header)| {
@@ -349,9 +391,42 @@ where
                     .await?
                     .into_iter()
                     .chain(msgs)
-                    .map(|e| IbcDatagram::new::<V>(e))
-                    .collect::<Vec<_>>(),
-            }),
+                    .collect::<Vec<_>>();
+
+                let size = datagrams.iter().map(V::encoded_size).sum::<usize>();
+
+                // the messages of ordered packets are not coalesced, since the transactions they
+                // are in have to land in the order they were built in. transactions that have to
+                // be split to fit into the max message size are not coalesced either.
+                if module_server.multi_client_batching.is_some()
+                    && self.ordered_packets.is_empty()
+                    && module_server
+                        .max_message_size
+                        .is_none_or(|max_message_size| size <= max_message_size)
+                {
+                    data(PluginMessage::new(
+                        module_server.plugin_name(),
+                        ModuleData::from(PendingTransaction {
+                            first_seen_at: SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .unwrap()
+                                .as_millis()
+                                .try_into()
+                                .expect("how many milliseconds can there be man"),
+                            ibc_spec_id: V::ID,
+                            client_id: RawClientId::new(self.client_id.clone()),
+                            datagrams: datagrams
+                                .into_iter()
+                                .map(|e| IbcDatagram::new::<V>(e))
+                                .collect(),
+                            size,
+                            signers,
+                        }),
+                    ))
+                } else {
+                    submit_txs::<V>(module_server, chain_id, datagrams, signers)
+                }
+            }
             None => {
                 if msgs.len() == 0 {
                     noop()
@@ -368,10 +443,7 @@ where
                             ibc_spec_id: V::ID,
                             height: required_consensus_height,
                         }),
-                        call(SubmitTx {
-                            chain_id,
-                            datagrams: msgs.map(IbcDatagram::new::<V>).collect::<Vec<_>>(),
-                        }),
+                        submit_txs::<V>(module_server, chain_id, msgs.collect(), signers),
                     ])
                 }
             }
@@ -380,3 +452,125 @@ where
         Ok(conc(events_no_proof_available_msg.into_iter().chain([msg])))
     }
 }
+
+/// Submit `datagrams` in one transaction, or in multiple transactions (in order) if they would
+/// exceed the max message size of this chain.
+fn submit_txs<V: IbcSpecExt>(
+    module_server: &Module,
+    chain_id: ChainId,
+    datagrams: Vec<V::Datagram>,
+    signers: Option<BTreeSet<String>>,
+) -> Op<VoyagerMessage> {
+    let Some(max_message_size) = module_server.max_message_size else {
+        return call(SubmitTx {
+            chain_id,
+            datagrams: datagrams.into_iter().map(IbcDatagram::new::<V>).collect(),
+            signers,
+        });
+    };
+
+    let len = datagrams.len();
+
+    let chunks = split_by_size(datagrams, max_message_size, V::encoded_size);
+
+    if chunks.len() > 1 {
+        info!(
+            datagrams = len,
+            transactions = chunks.len(),
+            max_message_size,
+            "datagrams exceed the max message size, splitting them into multiple transactions"
+        );
+    }
+
+    for chunk in &chunks {
+        if let [datagram] = &chunk[..] {
+            let size = V::encoded_size(datagram);
+
+            if size > max_message_size {
+                warn!(
+                    size,
+                    max_message_size,
+                    "datagram exceeds the max message size on its own and cannot be split, \
+                    submitting it anyways"
+                );
+            }
+        }
+    }
+
+    seq(chunks.into_iter().map(|chunk| {
+        call(SubmitTx {
+            chain_id: chain_id.clone(),
+            datagrams: chunk.into_iter().map(IbcDatagram::new::<V>).collect(),
+            signers: signers.clone(),
+        })
+    }))
+}
+
+/// Sorts the messages receiving packets that have to be received in order by the position of their
+/// packets in `ordered_packets`. All other messages are put before them, in their original order.
+fn sort_ordered_packets<V: IbcSpecExt>(
+    mut msgs: Vec<V::Datagram>,
+    ordered_packets: &[V::PacketKey],
+) -> Vec<V::Datagram> {
+    if !ordered_packets.is_empty() {
+        msgs.sort_by_cached_key(|msg| {
+            V::received_packet_key(msg)
+                .and_then(|key| ordered_packets.iter().position(|k| *k == key))
+        });
+    }
+
+    msgs
+}
+
+#[cfg(test)]
+mod tests {
+    use ibc_union_spec::{
+        datagram::{Datagram, MsgPacketRecv},
+        path::BatchPacketsPath,
+        ChannelId, Packet,
+    };
+
+    use super::*;
+
+    fn packet(data: &[u8]) -> Packet {
+        Packet {
+            source_channel_id: ChannelId::from_raw(1).unwrap(),
+            destination_channel_id: ChannelId::from_raw(2).unwrap(),
+            data: data.to_vec().into(),
+            timeout_height: 0,
+            timeout_timestamp: Default::default(),
+        }
+    }
+
+    fn recv(packets: Vec<Packet>) -> Datagram {
+        Datagram::from(MsgPacketRecv {
+            relayer_msgs: vec![vec![].into(); packets.len()],
+            packets,
+            proof: vec![].into(),
+            proof_height: 1,
+        })
+    }
+
+    #[test]
+    fn sort_ordered_packets_by_send_order() {
+        let (a, b, c) = (packet(b"a"), packet(b"b"), packet(b"c"));
+        let unordered = recv(vec![packet(b"unordered")]);
+
+        let ordered_packets = [
+            a.hash(),
+            BatchPacketsPath::from_packets(&[b.clone(), c.clone()]).batch_hash,
+        ];
+
+        assert_eq!(
+            sort_ordered_packets::<IbcUnion>(
+                vec![
+                    recv(vec![b.clone(), c.clone()]),
+                    unordered.clone(),
+                    recv(vec![a.clone()]),
+                ],
+                &ordered_packets,
+            ),
+            vec![unordered, recv(vec![a]), recv(vec![b, c])]
+        );
+    }
+}
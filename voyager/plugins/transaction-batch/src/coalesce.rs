@@ -0,0 +1,288 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use itertools::Itertools;
+use macros::model;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use voyager_sdk::{message::data::IbcDatagram, primitives::IbcSpecId, types::RawClientId};
+
+/// Coalesce the transactions of different clients on this chain into one transaction.
+///
+/// Only transactions that include the update of their client are coalesced, since they do not
+/// depend on any other transaction having landed first. A transaction is held back for at most
+/// [`Self::max_wait_time`] waiting for transactions of other clients to join it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MultiClientBatching {
+    /// The maximum time a transaction is held back for.
+    pub max_wait_time: Duration,
+    /// The maximum amount of datagrams in a coalesced transaction. Transactions with more datagrams
+    /// than this are submitted on their own.
+    pub max_datagrams: usize,
+}
+
+/// A transaction for a single client, waiting to be coalesced with the transactions of other
+/// clients.
+#[model]
+pub struct PendingTransaction {
+    /// unix timestamp (in ms) of when this transaction was first seen by this plugin.
+    pub first_seen_at: u64,
+    pub ibc_spec_id: IbcSpecId,
+    pub client_id: RawClientId,
+    /// The client updates, followed by the messages that were proven against them.
+    pub datagrams: Vec<IbcDatagram>,
+    /// The estimated encoded size of the datagrams, see [`crate::size`].
+    #[serde(default)]
+    pub size: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signers: Option<BTreeSet<String>>,
+}
+
+/// Transactions that were merged into one, along with the indexes of the messages they came from.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Coalesced {
+    pub idxs: Vec<usize>,
+    pub transactions: Vec<PendingTransaction>,
+    full: bool,
+}
+
+impl Coalesced {
+    fn len(&self) -> usize {
+        self.transactions.iter().map(|tx| tx.datagrams.len()).sum()
+    }
+
+    fn size(&self) -> usize {
+        self.transactions.iter().map(|tx| tx.size).sum()
+    }
+
+    /// The datagrams of all transactions, in the order they were first seen in.
+    pub fn datagrams(&self) -> Vec<IbcDatagram> {
+        self.transactions
+            .iter()
+            .flat_map(|tx| tx.datagrams.clone())
+            .collect()
+    }
+}
+
+/// Group the pending transactions into coalesced transactions, returning the ones that are ready
+/// to be submitted and the ones that should keep waiting.
+///
+/// Transactions are only coalesced with transactions of the same IBC spec and signers, and never
+/// with another transaction of the same client, since its update would be built against a client
+/// state that the other update has already advanced. If `max_message_size` is set, the datagrams
+/// of a coalesced transaction are additionally kept within it.
+pub fn coalesce(
+    pending: Vec<(usize, PendingTransaction)>,
+    config: &MultiClientBatching,
+    max_message_size: Option<usize>,
+    now: u64,
+) -> (Vec<Coalesced>, Vec<Coalesced>) {
+    let is_overdue = |tx: &PendingTransaction| {
+        Duration::from_millis(tx.first_seen_at) + config.max_wait_time < Duration::from_millis(now)
+    };
+
+    let is_full = |len: usize, size: usize| {
+        len >= config.max_datagrams || max_message_size.is_some_and(|max| size >= max)
+    };
+
+    let fits = |len: usize, size: usize| {
+        len <= config.max_datagrams && max_message_size.is_none_or(|max| size <= max)
+    };
+
+    pending
+        .into_iter()
+        .sorted_by_key(|(_, tx)| tx.first_seen_at)
+        .into_group_map_by(|(_, tx)| (tx.ibc_spec_id.clone(), tx.signers.clone()))
+        .into_values()
+        .flat_map(|txs| {
+            txs.into_iter()
+                .fold(Vec::<Coalesced>::new(), |mut acc, (idx, tx)| {
+                    // first fit, so that the transactions are kept in the order they were seen in
+                    let mut slot = None;
+
+                    for coalesced in &mut acc {
+                        if !fits(
+                            coalesced.len() + tx.datagrams.len(),
+                            coalesced.size() + tx.size,
+                        ) {
+                            // no room left for this transaction, submit it as is (unless the
+                            // transaction would not fit into any coalesced transaction anyways)
+                            coalesced.full |= !is_full(tx.datagrams.len(), tx.size);
+                        } else if slot.is_none()
+                            && !coalesced
+                                .transactions
+                                .iter()
+                                .any(|other| other.client_id == tx.client_id)
+                        {
+                            slot = Some(coalesced);
+                        }
+                    }
+
+                    match slot {
+                        Some(coalesced) => {
+                            coalesced.idxs.push(idx);
+                            coalesced.transactions.push(tx);
+                        }
+                        None => acc.push(Coalesced {
+                            idxs: vec![idx],
+                            full: is_full(tx.datagrams.len(), tx.size),
+                            transactions: vec![tx],
+                        }),
+                    }
+
+                    acc
+                })
+        })
+        .partition(|coalesced| {
+            coalesced.full
+                || is_full(coalesced.len(), coalesced.size())
+                || coalesced.transactions.iter().any(is_overdue)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use ibc_union_spec::IbcUnion;
+    use serde_json::json;
+    use voyager_sdk::primitives::IbcSpec;
+
+    use super::*;
+
+    const CONFIG: MultiClientBatching = MultiClientBatching {
+        max_wait_time: Duration::from_secs(10),
+        max_datagrams: 4,
+    };
+
+    fn tx(client_id: u32, datagrams: usize, first_seen_at: u64) -> PendingTransaction {
+        PendingTransaction {
+            first_seen_at,
+            ibc_spec_id: IbcUnion::ID,
+            client_id: RawClientId::new(client_id),
+            datagrams: (0..datagrams)
+                .map(|i| IbcDatagram {
+                    ibc_spec_id: IbcUnion::ID,
+                    datagram: json!([client_id, i]),
+                })
+                .collect(),
+            size: datagrams * 100,
+            signers: None,
+        }
+    }
+
+    fn clients(coalesced: &[Coalesced]) -> Vec<Vec<RawClientId>> {
+        coalesced
+            .iter()
+            .map(|c| {
+                c.transactions
+                    .iter()
+                    .map(|tx| tx.client_id.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn coalesces_different_clients() {
+        let (ready, wait) = coalesce(
+            vec![(0, tx(1, 2, 1_000)), (1, tx(2, 2, 2_000))],
+            &CONFIG,
+            None,
+            3_000,
+        );
+
+        assert!(wait.is_empty());
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].idxs, vec![0, 1]);
+        assert_eq!(
+            ready[0].datagrams(),
+            [tx(1, 2, 0).datagrams, tx(2, 2, 0).datagrams].concat()
+        );
+    }
+
+    #[test]
+    fn waits_until_overdue() {
+        let (ready, wait) = coalesce(vec![(0, tx(1, 1, 1_000))], &CONFIG, None, 5_000);
+
+        assert!(ready.is_empty());
+        assert_eq!(clients(&wait), vec![vec![RawClientId::new(1)]]);
+
+        let (ready, wait) = coalesce(vec![(0, tx(1, 1, 1_000))], &CONFIG, None, 12_000);
+
+        assert_eq!(clients(&ready), vec![vec![RawClientId::new(1)]]);
+        assert!(wait.is_empty());
+    }
+
+    #[test]
+    fn splits_by_max_datagrams() {
+        let (ready, wait) = coalesce(
+            vec![
+                (0, tx(1, 3, 1_000)),
+                (1, tx(2, 3, 2_000)),
+                (2, tx(3, 1, 3_000)),
+            ],
+            &CONFIG,
+            None,
+            4_000,
+        );
+
+        assert_eq!(
+            clients(&ready),
+            vec![vec![RawClientId::new(1), RawClientId::new(3)]]
+        );
+        assert_eq!(clients(&wait), vec![vec![RawClientId::new(2)]]);
+    }
+
+    #[test]
+    fn splits_by_max_message_size() {
+        let (ready, wait) = coalesce(
+            vec![
+                (0, tx(1, 1, 1_000)),
+                (1, tx(2, 2, 2_000)),
+                (2, tx(3, 1, 3_000)),
+            ],
+            &CONFIG,
+            Some(200),
+            4_000,
+        );
+
+        assert!(wait.is_empty());
+        assert_eq!(
+            clients(&ready),
+            vec![
+                vec![RawClientId::new(1), RawClientId::new(3)],
+                vec![RawClientId::new(2)]
+            ]
+        );
+    }
+
+    #[test]
+    fn never_coalesces_same_client() {
+        let (ready, wait) = coalesce(
+            vec![(0, tx(1, 1, 1_000)), (1, tx(1, 1, 2_000))],
+            &CONFIG,
+            None,
+            12_000,
+        );
+
+        assert!(wait.is_empty());
+        assert_eq!(
+            clients(&ready),
+            vec![vec![RawClientId::new(1)], vec![RawClientId::new(1)]]
+        );
+    }
+
+    #[test]
+    fn never_coalesces_different_signers() {
+        let mut other = tx(2, 1, 2_000);
+        other.signers = Some(["union1relayer".to_owned()].into());
+
+        let (ready, _) = coalesce(
+            vec![(0, tx(1, 1, 1_000)), (1, other)],
+            &CONFIG,
+            None,
+            12_000,
+        );
+
+        assert_eq!(ready.len(), 2);
+    }
+}
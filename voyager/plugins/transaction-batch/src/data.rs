@@ -5,7 +5,7 @@ use macros::model;
 use subset_of::SubsetOf;
 use voyager_sdk::message::data::EventProvableHeight;
 
-use crate::IbcSpecExt;
+use crate::{coalesce::PendingTransaction, IbcSpecExt};
 
 #[model]
 #[derive(Enumorph, SubsetOf)]
@@ -15,6 +15,7 @@ pub enum ModuleData {
     BatchEventsUnion(EventBatch<IbcUnion>),
     ProofUnavailableClassic(ProofUnavailable<IbcClassic>),
     ProofUnavailableUnion(ProofUnavailable<IbcUnion>),
+    PendingTransaction(PendingTransaction),
 }
 
 #[model]
@@ -1,7 +1,8 @@
 use std::{
-    collections::{BTreeSet, HashMap, VecDeque},
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     convert,
     future::Future,
+    num::NonZeroU64,
     pin::Pin,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
@@ -9,20 +10,30 @@ use std::{
 use either::Either;
 use futures::{stream::FuturesOrdered, StreamExt};
 use ibc_classic_spec::IbcClassic;
-use ibc_union_spec::IbcUnion;
+use ibc_union_spec::{path::BatchPacketsPath, IbcUnion};
 use itertools::Itertools;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
-    Extensions,
+    proc_macros::rpc,
+    types::ErrorObject,
+    Extensions, MethodsError,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tracing::{debug, error, info, instrument, trace, warn};
-use unionlabs::{ibc::core::client::height::Height, id::ClientId, traits::Member, ErrorReporter};
+use unionlabs::{
+    ibc::core::{channel::order::Order, client::height::Height},
+    id::{ChannelId, ClientId},
+    primitives::H256,
+    traits::Member,
+    ErrorReporter,
+};
 use voyager_sdk::{
     anyhow,
     hook::simple_take_filter,
     message::{
-        call::WaitForHeight,
+        call::{SubmitTx, WaitForHeight},
         data::{ChainEvent, Data, EventProvableHeight},
         PluginMessage, VoyagerMessage,
     },
@@ -30,25 +41,39 @@ use voyager_sdk::{
     primitives::{ChainId, IbcSpec, QueryHeight},
     rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
     types::RawClientId,
-    vm::{call, conc, data, noop, pass::PassResult, seq, Op},
+    vm::{call, conc, data, noop, now, pass::PassResult, seq, Op},
     DefaultCmd, ExtensionsExt, VoyagerClient,
 };
 
 use crate::{
     call::{MakeTransactionBatchesWithUpdate, ModuleCall},
     callback::ModuleCallback,
+    coalesce::{coalesce, Coalesced, MultiClientBatching, PendingTransaction},
     data::{BatchableEvent, EventBatch, EventClassic, EventUnion, ModuleData},
+    policy::{ClientPolicy, ClientPolicyStatus, UpdateHistory},
+    priority::{ChannelPriority, Priority},
 };
 
 pub mod call;
 pub mod callback;
+pub mod coalesce;
 pub mod data;
+pub mod policy;
+pub mod priority;
+pub mod size;
+pub mod verify;
 
 #[derive(Debug, Clone)]
 pub struct Module {
     // The destination chain (i.e. where the messages will be sent to)
     pub chain_id: ChainId,
     pub client_configs: ClientConfigs,
+    pub verify_proofs: bool,
+    pub update_history: UpdateHistory,
+    pub priorities: HashMap<ibc_union_spec::ChannelId, ChannelPriority>,
+    pub ordered_channels: HashSet<ibc_union_spec::ChannelId>,
+    pub multi_client_batching: Option<MultiClientBatching>,
+    pub max_message_size: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -57,35 +82,69 @@ pub enum ClientConfigs {
     Many(HashMap<RawClientId, ClientConfig>),
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
     pub client_configs: ClientConfigsSerde,
+    /// Verify the proofs of IBC union messages against the consensus state of the client on this
+    /// chain before submitting them, catching proofs that do not match the consensus state (i.e.
+    /// at a wrong height or against a different root) before paying gas for them. Proofs are only
+    /// verified for clients whose client module supports `verifyProof`, and are submitted as is
+    /// for all other clients. This costs an extra round of state queries and the verification
+    /// itself per message.
+    #[serde(default)]
+    pub verify_proofs: bool,
+    /// Prioritize the packets sent over these IBC union zkgm channels by the tokens and amounts
+    /// they transfer. Packets over other channels are not affected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub priorities: Vec<ChannelPriority>,
+    /// IBC union channels on this chain that receive packets in the order they were sent in (i.e.
+    /// the receiving contract enforces this). Packets destined for these channels are never
+    /// reordered by their priority, and are submitted in the order they were sent in even if their
+    /// proofs complete out of order. Packets over ordered IBC classic channels are always treated
+    /// this way.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ordered_channels: Vec<ibc_union_spec::ChannelId>,
+    /// Coalesce the transactions of different clients on this chain (i.e. of different source
+    /// chains) into one transaction. This must only be enabled if the IBC contracts on this chain
+    /// accept updates and messages for multiple clients in one transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multi_client_batching: Option<MultiClientBatching>,
+    /// The maximum size (in bytes) of the messages of a transaction on this chain, i.e. the max
+    /// calldata or tx size of the chain. Batches whose messages would exceed this are split into
+    /// multiple transactions, and batch proofs that would exceed this on their own are replaced by
+    /// a proof per packet. See [`size`] for how the size of the messages is estimated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_message_size: Option<usize>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ClientConfig {
     pub min_batch_size: usize,
     pub max_batch_size: usize,
     pub max_wait_time: Duration,
+    #[serde(default)]
+    pub policy: ClientPolicy,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum ClientConfigsSerde {
     Any(ClientConfig),
     Many(Vec<SpecificClientConfig>),
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SpecificClientConfig {
     pub client_id: RawClientId,
     pub min_batch_size: usize,
     pub max_batch_size: usize,
     pub max_wait_time: Duration,
+    #[serde(default)]
+    pub policy: ClientPolicy,
 }
 
 impl SpecificClientConfig {
@@ -96,6 +155,7 @@ impl SpecificClientConfig {
                 min_batch_size: self.min_batch_size,
                 max_batch_size: self.max_batch_size,
                 max_wait_time: self.max_wait_time,
+                policy: self.policy,
             },
         )
     }
@@ -106,7 +166,32 @@ pub trait IbcSpecExt: IbcSpec + Clone {
 
     fn proof_height(msg: &Self::Datagram) -> Height;
 
+    /// An estimate of the size of the message once encoded for this chain, see [`size`].
+    fn encoded_size(msg: &Self::Datagram) -> usize;
+
     fn event_name(msg: &Self::BatchableEvent) -> &'static str;
+
+    fn priority(module: &Module, msg: &Self::BatchableEvent) -> Priority;
+
+    /// Identifies the packets of a message, such that the packets sent by an event can be matched
+    /// to the message receiving them.
+    type PacketKey: Eq + Member;
+
+    /// The key of the packets sent by this event, if they have to be received in the order they
+    /// were sent in.
+    fn ordered_packet_key(module: &Module, msg: &Self::BatchableEvent) -> Option<Self::PacketKey>;
+
+    /// The destination channel and sequence of the packets sent by this event, if they have to be
+    /// received in the order they were sent in and carry a sequence to order them by.
+    type PacketSequence: Ord;
+
+    fn ordered_packet_sequence(
+        module: &Module,
+        msg: &Self::BatchableEvent,
+    ) -> Option<Self::PacketSequence>;
+
+    /// The key of the packets received by this message, if it receives packets.
+    fn received_packet_key(msg: &Self::Datagram) -> Option<Self::PacketKey>;
 }
 
 impl IbcSpecExt for IbcClassic {
@@ -117,6 +202,10 @@ impl IbcSpecExt for IbcClassic {
             .expect("all batchable messages have a proof")
     }
 
+    fn encoded_size(msg: &Self::Datagram) -> usize {
+        size::classic_datagram_size(msg)
+    }
+
     fn event_name(msg: &Self::BatchableEvent) -> &'static str {
         match msg {
             EventClassic::ConnectionOpenInit(_) => "connection_open_init",
@@ -129,6 +218,44 @@ impl IbcSpecExt for IbcClassic {
             EventClassic::WriteAcknowledgement(_) => "write_ack",
         }
     }
+
+    fn priority(_: &Module, _: &Self::BatchableEvent) -> Priority {
+        Priority::Normal
+    }
+
+    type PacketKey = (ChannelId, NonZeroU64);
+
+    fn ordered_packet_key(_: &Module, msg: &Self::BatchableEvent) -> Option<Self::PacketKey> {
+        match msg {
+            EventClassic::SendPacket(send_packet)
+                if send_packet.packet.channel_ordering == Order::Ordered =>
+            {
+                Some((
+                    send_packet.packet.destination_channel.channel_id.clone(),
+                    send_packet.packet.sequence,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    type PacketSequence = (ChannelId, NonZeroU64);
+
+    fn ordered_packet_sequence(
+        module: &Module,
+        msg: &Self::BatchableEvent,
+    ) -> Option<Self::PacketSequence> {
+        Self::ordered_packet_key(module, msg)
+    }
+
+    fn received_packet_key(msg: &Self::Datagram) -> Option<Self::PacketKey> {
+        match msg {
+            ibc_classic_spec::Datagram::RecvPacket(msg) => {
+                Some((msg.packet.destination_channel.clone(), msg.packet.sequence))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl IbcSpecExt for IbcUnion {
@@ -139,6 +266,10 @@ impl IbcSpecExt for IbcUnion {
             .expect("all batchable messages have a proof")
     }
 
+    fn encoded_size(msg: &Self::Datagram) -> usize {
+        size::union_datagram_size(msg)
+    }
+
     fn event_name(msg: &Self::BatchableEvent) -> &'static str {
         match msg {
             EventUnion::ConnectionOpenInit(_) => "connection_open_init",
@@ -152,6 +283,61 @@ impl IbcSpecExt for IbcUnion {
             EventUnion::WriteAck(_) => "write_ack",
         }
     }
+
+    fn priority(module: &Module, msg: &Self::BatchableEvent) -> Priority {
+        match msg {
+            EventUnion::PacketSend(packet_send) => module
+                .priorities
+                .get(&packet_send.packet.destination_channel.channel_id)
+                .map_or(Priority::Normal, |priority| {
+                    priority.classify(&packet_send.packet_data)
+                }),
+            _ => Priority::Normal,
+        }
+    }
+
+    /// The batch hash of the packets, which for a single packet is the hash of the packet.
+    type PacketKey = H256;
+
+    fn ordered_packet_key(module: &Module, msg: &Self::BatchableEvent) -> Option<Self::PacketKey> {
+        match msg {
+            EventUnion::PacketSend(packet_send)
+                if module
+                    .ordered_channels
+                    .contains(&packet_send.packet.destination_channel.channel_id) =>
+            {
+                Some(packet_send.packet().hash())
+            }
+            EventUnion::BatchSend(batch_send)
+                if module
+                    .ordered_channels
+                    .contains(&batch_send.destination_channel.channel_id) =>
+            {
+                Some(batch_send.batch_hash)
+            }
+            _ => None,
+        }
+    }
+
+    /// IBC union packets have no sequence, the packets of ordered channels are kept in the order
+    /// they were seen in.
+    type PacketSequence = convert::Infallible;
+
+    fn ordered_packet_sequence(
+        _: &Module,
+        _: &Self::BatchableEvent,
+    ) -> Option<Self::PacketSequence> {
+        None
+    }
+
+    fn received_packet_key(msg: &Self::Datagram) -> Option<Self::PacketKey> {
+        match msg {
+            ibc_union_spec::datagram::Datagram::PacketRecv(msg) => {
+                Some(BatchPacketsPath::from_packets(&msg.packets).batch_hash)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl ClientConfigs {
@@ -214,6 +400,7 @@ if ."@type" == "data" then
         and (
             $data."@value".message."@type" == "batch_events_union"
             or $data."@value".message."@type" == "batch_events_v1"
+            or $data."@value".message."@type" == "pending_transaction"
     )) or
 
     # ibc v1
@@ -318,10 +505,64 @@ impl Module {
         Self {
             chain_id: config.chain_id,
             client_configs: ClientConfigs::new(config.client_configs),
+            verify_proofs: config.verify_proofs,
+            update_history: UpdateHistory::default(),
+            priorities: config
+                .priorities
+                .into_iter()
+                .map(|priority| (priority.channel_id, priority))
+                .collect(),
+            ordered_channels: config.ordered_channels.into_iter().collect(),
+            multi_client_batching: config.multi_client_batching,
+            max_message_size: config.max_message_size,
         }
     }
 }
 
+#[rpc(server)]
+trait TransactionBatchPlugin {
+    /// The relay policies of the clients on this chain, along with their recent updates.
+    #[method(name = "clientPolicies")]
+    async fn client_policies(&self) -> RpcResult<Vec<ClientPolicyStatus>>;
+}
+
+#[async_trait]
+impl TransactionBatchPluginServer for Module {
+    async fn client_policies(&self) -> RpcResult<Vec<ClientPolicyStatus>> {
+        let now = now();
+
+        let status = |client_id: Option<RawClientId>, policy: &ClientPolicy| {
+            let recent_updates = client_id
+                .as_ref()
+                .map(|client_id| self.update_history.recent(client_id, now))
+                .unwrap_or_default();
+
+            ClientPolicyStatus {
+                next_update_allowed_at: policy.next_update_allowed_at(&recent_updates, now),
+                client_id,
+                policy: policy.clone(),
+                recent_updates,
+            }
+        };
+
+        Ok(match &self.client_configs {
+            ClientConfigs::Any(config) => [status(None, &config.policy)]
+                .into_iter()
+                .chain(
+                    self.update_history
+                        .clients()
+                        .into_iter()
+                        .map(|client_id| status(Some(client_id), &config.policy)),
+                )
+                .collect(),
+            ClientConfigs::Many(configs) => configs
+                .iter()
+                .map(|(client_id, config)| status(Some(client_id.clone()), &config.policy))
+                .collect(),
+        })
+    }
+}
+
 #[async_trait]
 impl PluginServer<ModuleCall, ModuleCallback> for Module {
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
@@ -345,7 +586,7 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                 mk.call(self, e.voyager_client()?).await
             }
             ModuleCall::MakeMsgClassic(mk) => mk.call(voyager_client).await,
-            ModuleCall::MakeMsgUnion(mk) => mk.call(voyager_client).await,
+            ModuleCall::MakeMsgUnion(mk) => mk.call(self, voyager_client).await,
         }
     }
 
@@ -373,6 +614,26 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
             }
         }
     }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn custom(&self, _: &Extensions, method: String, params: Vec<Value>) -> RpcResult<Value> {
+        TransactionBatchPluginServer::into_rpc(self.clone())
+            .call::<Vec<Value>, Value>(&method, params)
+            .await
+            .map_err(|e| match e {
+                MethodsError::Parse(error) => ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    ErrorReporter(error).with_message("error parsing args"),
+                    None::<()>,
+                ),
+                MethodsError::JsonRpc(error_object) => error_object,
+                MethodsError::InvalidSubscriptionId(_) => ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    "subscriptions are not supported",
+                    None::<()>,
+                ),
+            })
+    }
 }
 
 impl Module {
@@ -387,6 +648,7 @@ impl Module {
                 HashMap::<ClientId, Vec<(usize, BatchableEvent<IbcClassic>)>>::new();
             let mut batchers_union =
                 HashMap::<ibc_union_spec::ClientId, Vec<(usize, BatchableEvent<IbcUnion>)>>::new();
+            let mut pending_transactions = Vec::<(usize, PendingTransaction)>::new();
 
             for (idx, msg) in msgs.into_iter().enumerate() {
                 let Op::Data(msg) = msg else {
@@ -475,6 +737,15 @@ impl Module {
                                     .or_default()
                                     .extend(message.events.into_iter().map(|event| (idx, event)));
                             }
+                            Ok(ModuleData::PendingTransaction(transaction)) => {
+                                trace!(
+                                    client_id = %transaction.client_id,
+                                    datagrams.len = %transaction.datagrams.len(),
+                                    "coalescing transaction"
+                                );
+
+                                pending_transactions.push((idx, transaction));
+                            }
 
                             Ok(msg) => {
                                 error!("unexpected message: {msg:?}");
@@ -522,17 +793,113 @@ impl Module {
                 .into_iter()
                 .partition_map::<Vec<_>, Vec<_>, _, _, _>(Either::from);
 
+            let (ready_coalesced, optimize_further_coalesced) =
+                self.coalesce_transactions(pending_transactions);
+
             Ok(PassResult {
                 optimize_further: optimize_further_v1
                     .into_iter()
                     .chain(optimize_further_union)
                     .chain(ready_v1_errored.into_iter().flatten())
                     .chain(ready_union_errored.into_iter().flatten())
+                    .chain(optimize_further_coalesced)
+                    .collect(),
+                ready: ready_v1
+                    .into_iter()
+                    .chain(ready_union)
+                    .chain(ready_coalesced)
                     .collect(),
-                ready: ready_v1.into_iter().chain(ready_union).collect(),
             })
         })
     }
+
+    /// Merge the pending transactions of different clients into as few transactions as possible.
+    #[allow(clippy::type_complexity)]
+    fn coalesce_transactions(
+        &self,
+        pending_transactions: Vec<(usize, PendingTransaction)>,
+    ) -> (
+        Vec<(Vec<usize>, Op<VoyagerMessage>)>,
+        Vec<(Vec<usize>, Op<VoyagerMessage>, String)>,
+    ) {
+        if pending_transactions.is_empty() {
+            return (vec![], vec![]);
+        }
+
+        let Some(config) = &self.multi_client_batching else {
+            // multi client batching was disabled since these transactions were queued, submit them
+            // as they are
+            return (
+                pending_transactions
+                    .into_iter()
+                    .map(|(idx, transaction)| {
+                        (
+                            vec![idx],
+                            call(SubmitTx {
+                                chain_id: self.chain_id.clone(),
+                                datagrams: transaction.datagrams,
+                                signers: transaction.signers,
+                            }),
+                        )
+                    })
+                    .collect(),
+                vec![],
+            );
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+            .try_into()
+            .expect("how many milliseconds can there be man");
+
+        let (ready, wait) = coalesce(pending_transactions, config, self.max_message_size, now);
+
+        (
+            ready
+                .into_iter()
+                .map(|coalesced| {
+                    let datagrams = coalesced.datagrams();
+
+                    info!(
+                        clients = coalesced.transactions.len(),
+                        datagrams = datagrams.len(),
+                        "coalesced transactions"
+                    );
+
+                    let Coalesced {
+                        idxs,
+                        mut transactions,
+                        ..
+                    } = coalesced;
+
+                    (
+                        idxs,
+                        call(SubmitTx {
+                            chain_id: self.chain_id.clone(),
+                            datagrams,
+                            // all coalesced transactions have the same signers
+                            signers: transactions.remove(0).signers,
+                        }),
+                    )
+                })
+                .collect(),
+            wait.into_iter()
+                .flat_map(|coalesced| coalesced.idxs.into_iter().zip(coalesced.transactions))
+                .map(|(idx, transaction)| {
+                    (
+                        vec![idx],
+                        data(PluginMessage::new(
+                            self.plugin_name(),
+                            ModuleData::from(transaction),
+                        )),
+                        self.plugin_name(),
+                    )
+                })
+                .collect(),
+        )
+    }
 }
 
 #[allow(clippy::type_complexity)] // skill issue
@@ -555,13 +922,31 @@ where
 
     events.sort_by_key(|e| e.1.first_seen_at);
 
+    // packets that have to be received in order are never reordered by their priority, since a
+    // packet relayed ahead of the packets sent before it would be rejected by the destination
+    let priority = |e: &BatchableEvent<V>| {
+        if V::ordered_packet_key(this, &e.event).is_some() {
+            Priority::Normal
+        } else {
+            V::priority(this, &e.event)
+        }
+    };
+
     let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-    let is_overdue =
-        |first_seen_at| Duration::from_millis(first_seen_at) + client_config.max_wait_time < now;
+    // high priority events are overdue as soon as they are seen, dust is held back for longer
+    let is_overdue = |e: &BatchableEvent<V>| {
+        let max_wait_time = match priority(e) {
+            Priority::High => Duration::ZERO,
+            Priority::Normal => client_config.max_wait_time,
+            Priority::Dust(delay) => client_config.max_wait_time + delay,
+        };
+
+        Duration::from_millis(e.first_seen_at) + max_wait_time < now
+    };
 
     let (mut overdue_events, mut events): (Vec<_>, Vec<_>) =
         events.into_iter().partition_map(|e| {
-            if is_overdue(e.1.first_seen_at) {
+            if is_overdue(&e.1) {
                 Either::Left(e)
             } else {
                 Either::Right(e)
@@ -569,7 +954,14 @@ where
         });
 
     events.sort_by_key(|e| *e.1.provable_height.height());
-    overdue_events.sort_by_key(|e| *e.1.provable_height.height());
+    // high priority events first, so that they are in the first batch if there are more overdue
+    // events than fit into one
+    overdue_events.sort_by_key(|e| {
+        (
+            priority(&e.1) != Priority::High,
+            *e.1.provable_height.height(),
+        )
+    });
 
     if !overdue_events.is_empty()
         && overdue_events.len() + events.len() < client_config.min_batch_size
@@ -583,18 +975,16 @@ where
         );
     }
 
-    // [...overdue_events_sorted_by_provable_height, ...events_sorted_by_provable_height]
-    overdue_events
+    // [...overdue_events_sorted_by_priority_and_provable_height, ...events_sorted_by_provable_height],
+    // with the packets of ordered channels in sequence
+    sort_by_packet_sequence(this, overdue_events.into_iter().chain(events).collect())
         .into_iter()
-        .chain(events)
         .chunks(client_config.max_batch_size)
         .into_iter()
         .map(move |chunk| {
             let (idxs, events): (Vec<_>, Vec<_>) = chunk.into_iter().unzip();
 
-            if events.len() == client_config.max_batch_size
-                || events.iter().any(|e| is_overdue(e.first_seen_at))
-            {
+            if events.len() == client_config.max_batch_size || events.iter().any(&is_overdue) {
                 // this batch is ready to send out, we need to fetch an update for the client on our chain and turn the events into `IbcMessage`s.
                 //
                 // in order to do this, we first need to figure out what height the client is at, and request an update from that height to a height >= the highest height of all of the messages in this batch.
@@ -731,12 +1121,111 @@ where
     ))
 }
 
+/// Sorts the events sending packets that have to be received in order by their destination channel
+/// and sequence, in the positions taken by them. All other events are kept where they are.
+///
+/// Events are otherwise ordered by when they were seen and their provable height, neither of which
+/// follows the sequence of the packets (i.e. two packets sent in the same block).
+fn sort_by_packet_sequence<V: IbcSpecExt>(
+    this: &Module,
+    events: Vec<(usize, BatchableEvent<V>)>,
+) -> Vec<(usize, BatchableEvent<V>)> {
+    let mut sequenced = vec![];
+
+    let events = events
+        .into_iter()
+        .map(|e| match V::ordered_packet_sequence(this, &e.1.event) {
+            Some(sequence) => {
+                sequenced.push((sequence, e));
+                None
+            }
+            None => Some(e),
+        })
+        .collect::<Vec<_>>();
+
+    sequenced.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut sequenced = sequenced.into_iter().map(|(_, e)| e);
+
+    events
+        .into_iter()
+        .map(|e| {
+            e.or_else(|| sequenced.next())
+                .expect("there is a sequenced event for every position taken by one; qed;")
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
+    use voyager_sdk::primitives::chain_id;
 
     use super::*;
 
+    fn send_packet(sequence: u64, channel_ordering: Order) -> EventClassic {
+        let channel = |channel_id| ibc_classic_spec::ChannelMetadata {
+            port_id: unionlabs::id::PortId::new("transfer").unwrap(),
+            channel_id: ChannelId::new(channel_id),
+            version: "ics20-1".to_owned(),
+            connection: ibc_classic_spec::ConnectionMetadata {
+                client_id: ClientId::new("07-tendermint", 0),
+                connection_id: unionlabs::id::ConnectionId::new(0),
+            },
+        };
+
+        EventClassic::SendPacket(ibc_classic_spec::SendPacket {
+            packet_data: vec![].into(),
+            packet: ibc_classic_spec::PacketMetadata {
+                sequence: NonZeroU64::new(sequence).unwrap(),
+                source_channel: channel(0),
+                destination_channel: channel(1),
+                channel_ordering,
+                timeout_height: Height::new(0),
+                timeout_timestamp: 0,
+            },
+        })
+    }
+
+    #[test]
+    fn packets_at_the_same_height_are_sorted_by_sequence() {
+        let module = Module::new(Config {
+            chain_id: chain_id!("union-devnet-1"),
+            client_configs: ClientConfigsSerde::Any(ClientConfig {
+                min_batch_size: 1,
+                max_batch_size: 3,
+                max_wait_time: Duration::from_secs(10),
+                policy: ClientPolicy::default(),
+            }),
+            verify_proofs: false,
+            priorities: vec![],
+            ordered_channels: vec![],
+            multi_client_batching: None,
+            max_message_size: None,
+        });
+
+        let event = |first_seen_at, event| BatchableEvent::<IbcClassic> {
+            first_seen_at,
+            provable_height: EventProvableHeight::Min(Height::new(10)),
+            event,
+        };
+
+        // the second packet is seen before the first one, in the same block
+        let events = vec![
+            (0, event(1, send_packet(2, Order::Ordered))),
+            (1, event(2, send_packet(7, Order::Unordered))),
+            (2, event(3, send_packet(1, Order::Ordered))),
+        ];
+
+        assert_eq!(
+            sort_by_packet_sequence(&module, events)
+                .into_iter()
+                .map(|(idx, _)| idx)
+                .collect::<Vec<_>>(),
+            vec![2, 1, 0]
+        );
+    }
+
     #[test]
     fn config_serde() {
         let config_json = json!({
@@ -756,12 +1245,18 @@ mod tests {
         assert_eq!(
             config,
             Config {
-                chain_id: ChainId::new("union-devnet-1"),
+                chain_id: chain_id!("union-devnet-1"),
                 client_configs: ClientConfigsSerde::Any(ClientConfig {
                     min_batch_size: 1,
                     max_batch_size: 3,
-                    max_wait_time: Duration::from_secs(10)
-                })
+                    max_wait_time: Duration::from_secs(10),
+                    policy: ClientPolicy::default(),
+                }),
+                verify_proofs: false,
+                priorities: vec![],
+                ordered_channels: vec![],
+                multi_client_batching: None,
+                max_message_size: None,
             }
         );
     }
@@ -0,0 +1,205 @@
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use voyager_sdk::types::RawClientId;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Controls how aggressively a client is updated, and which signers may submit transactions for
+/// it.
+///
+/// Updates that would violate the policy are deferred until they are allowed; messages that do not
+/// require an update of the client are not affected.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ClientPolicy {
+    /// The minimum time between two updates of the client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_update_interval: Option<Duration>,
+    /// The maximum amount of updates of the client within any 24 hour window.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_updates_per_day: Option<NonZeroU32>,
+    /// The addresses of the signers that may submit transactions for this client. If not set, any
+    /// signer of the transaction plugin for this chain may be used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_relayers: Option<BTreeSet<String>>,
+}
+
+impl ClientPolicy {
+    /// The earliest time (in unix seconds) an update is allowed at, given the times of the previous
+    /// updates within the last day (in ascending order). Returns `None` if an update is allowed at
+    /// `now`.
+    #[must_use]
+    pub fn next_update_allowed_at(&self, updates: &VecDeque<u64>, now: u64) -> Option<u64> {
+        let by_interval = self
+            .min_update_interval
+            .zip(updates.back())
+            .map(|(interval, last)| last + interval.as_secs());
+
+        let by_daily_limit = self.max_updates_per_day.and_then(|max| {
+            let max = usize::try_from(max.get()).expect("u32 fits in usize; qed;");
+
+            // the update that has to leave the 24 hour window before another one is allowed
+            updates.len().checked_sub(max).map(|idx| updates[idx] + DAY)
+        });
+
+        by_interval
+            .into_iter()
+            .chain(by_daily_limit)
+            .max()
+            .filter(|allowed_at| *allowed_at > now)
+    }
+}
+
+/// The updates planned for each client by this plugin, used to enforce [`ClientPolicy`].
+///
+/// This is only kept in memory, so the limits of the policies are reset when the plugin restarts.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateHistory(Arc<Mutex<HashMap<RawClientId, VecDeque<u64>>>>);
+
+impl UpdateHistory {
+    /// Record an update of the client at `now` if it is allowed by the policy, otherwise return the
+    /// time the next update is allowed at.
+    pub fn try_record(
+        &self,
+        client_id: &RawClientId,
+        policy: &ClientPolicy,
+        now: u64,
+    ) -> Result<(), u64> {
+        let mut history = self.0.lock().expect("lock is not poisoned; qed;");
+
+        let updates = history.entry(client_id.clone()).or_default();
+
+        prune(updates, now);
+
+        match policy.next_update_allowed_at(updates, now) {
+            Some(allowed_at) => Err(allowed_at),
+            None => {
+                updates.push_back(now);
+                Ok(())
+            }
+        }
+    }
+
+    /// The updates of the client within the last day.
+    #[must_use]
+    pub fn recent(&self, client_id: &RawClientId, now: u64) -> VecDeque<u64> {
+        let mut history = self.0.lock().expect("lock is not poisoned; qed;");
+
+        history
+            .get_mut(client_id)
+            .map(|updates| {
+                prune(updates, now);
+                updates.clone()
+            })
+            .unwrap_or_default()
+    }
+
+    /// All clients with a recorded update.
+    #[must_use]
+    pub fn clients(&self) -> Vec<RawClientId> {
+        self.0
+            .lock()
+            .expect("lock is not poisoned; qed;")
+            .keys()
+            .cloned()
+            .collect()
+    }
+}
+
+fn prune(updates: &mut VecDeque<u64>, now: u64) {
+    while updates.front().is_some_and(|at| at + DAY <= now) {
+        updates.pop_front();
+    }
+}
+
+/// The policy of a client along with its recent updates, as returned by the `clientPolicies` rpc
+/// method.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientPolicyStatus {
+    /// The client this policy applies to, or `None` if it is the policy applied to all clients.
+    pub client_id: Option<RawClientId>,
+    pub policy: ClientPolicy,
+    /// The times (in unix seconds) of the updates planned within the last day.
+    pub recent_updates: VecDeque<u64>,
+    /// The earliest time (in unix seconds) the next update is allowed at, if it is not allowed now.
+    pub next_update_allowed_at: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(interval: Option<u64>, max_per_day: Option<u32>) -> ClientPolicy {
+        ClientPolicy {
+            min_update_interval: interval.map(Duration::from_secs),
+            max_updates_per_day: max_per_day.map(|max| NonZeroU32::new(max).unwrap()),
+            allowed_relayers: None,
+        }
+    }
+
+    #[test]
+    fn min_update_interval() {
+        let history = UpdateHistory::default();
+        let client_id = RawClientId::new(1);
+        let policy = policy(Some(600), None);
+
+        assert_eq!(history.try_record(&client_id, &policy, 1000), Ok(()));
+        assert_eq!(history.try_record(&client_id, &policy, 1200), Err(1600));
+        assert_eq!(history.try_record(&client_id, &policy, 1600), Ok(()));
+    }
+
+    #[test]
+    fn max_updates_per_day() {
+        let history = UpdateHistory::default();
+        let client_id = RawClientId::new(1);
+        let policy = policy(None, Some(2));
+
+        assert_eq!(history.try_record(&client_id, &policy, 1000), Ok(()));
+        assert_eq!(history.try_record(&client_id, &policy, 2000), Ok(()));
+        assert_eq!(
+            history.try_record(&client_id, &policy, 3000),
+            Err(1000 + DAY)
+        );
+        assert_eq!(history.try_record(&client_id, &policy, 1000 + DAY), Ok(()));
+        assert_eq!(history.recent(&client_id, 1000 + DAY), [2000, 1000 + DAY]);
+    }
+
+    #[test]
+    fn no_limits() {
+        let history = UpdateHistory::default();
+        let client_id = RawClientId::new(1);
+
+        for now in 0..10 {
+            assert_eq!(
+                history.try_record(&client_id, &ClientPolicy::default(), now),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn serde() {
+        let policy = serde_json::from_value::<ClientPolicy>(serde_json::json!({
+            "min_update_interval": { "secs": 600, "nanos": 0 },
+            "max_updates_per_day": 24,
+            "allowed_relayers": ["union1abc"],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            policy,
+            ClientPolicy {
+                min_update_interval: Some(Duration::from_secs(600)),
+                max_updates_per_day: NonZeroU32::new(24),
+                allowed_relayers: Some(["union1abc".to_owned()].into()),
+            }
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use ibc_union_spec::ChannelId;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use unionlabs::{
+    encoding::{DecodeAs, EthAbi},
+    primitives::{Bytes, U256},
+    zkgm::ZkgmPacket,
+};
+
+/// Prioritization of the transfers sent over a zkgm channel, based on the tokens and amounts of the
+/// fungible asset orders in the packets.
+///
+/// High priority packets are treated as overdue as soon as they are seen, and are therefore relayed
+/// in the next batch. Dust is held back for [`Self::dust_delay`] on top of the `max_wait_time` of
+/// the client, so that it is only relayed along with other packets (or once it has waited long
+/// enough). All other packets are batched as usual.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelPriority {
+    /// The channel on this chain (i.e. the destination channel of the packets).
+    pub channel_id: ChannelId,
+    /// Tokens (by the base token of the order) that are always relayed with high priority,
+    /// regardless of the amount.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub allowlist: BTreeSet<Bytes>,
+    /// Amount thresholds per token.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thresholds: Vec<TokenThreshold>,
+    /// The time dust is held back for, in addition to the `max_wait_time` of the client.
+    #[serde(default)]
+    pub dust_delay: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TokenThreshold {
+    /// The base token of the order.
+    pub token: Bytes,
+    /// Orders of at least this amount are relayed with high priority.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_value: Option<U256>,
+    /// Orders below this amount are dust.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dust: Option<U256>,
+}
+
+/// The priority of a batchable event. Ordered from the highest to the lowest priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    High,
+    Normal,
+    /// The event is dust, and is held back for the contained delay.
+    Dust(Duration),
+}
+
+impl ChannelPriority {
+    /// Classify a zkgm packet by the fungible asset orders it contains.
+    ///
+    /// A packet is of high priority if any of its orders is, and dust only if all of its orders are.
+    /// Packets that cannot be decoded or do not contain any orders are of normal priority.
+    #[must_use]
+    pub fn classify(&self, packet_data: &[u8]) -> Priority {
+        let Ok(packet) = ZkgmPacket::decode_as::<EthAbi>(packet_data) else {
+            return Priority::Normal;
+        };
+
+        let orders = packet.instruction.fungible_asset_orders();
+        if orders.is_empty() {
+            return Priority::Normal;
+        }
+
+        let priorities = orders
+            .iter()
+            .map(|order| self.classify_order(order.base_token(), order.base_amount()))
+            .collect::<Vec<_>>();
+
+        if priorities.contains(&Priority::High) {
+            Priority::High
+        } else if priorities.iter().all(|p| matches!(p, Priority::Dust(_))) {
+            Priority::Dust(self.dust_delay)
+        } else {
+            Priority::Normal
+        }
+    }
+
+    fn classify_order(&self, token: &[u8], amount: U256) -> Priority {
+        if self.allowlist.iter().any(|t| &**t == token) {
+            return Priority::High;
+        }
+
+        let Some(threshold) = self.thresholds.iter().find(|t| &*t.token == token) else {
+            return Priority::Normal;
+        };
+
+        if threshold.high_value.is_some_and(|high| amount >= high) {
+            Priority::High
+        } else if threshold.dust.is_some_and(|dust| amount < dust) {
+            Priority::Dust(self.dust_delay)
+        } else {
+            Priority::Normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use unionlabs::{
+        encoding::EncodeAs,
+        primitives::H256,
+        zkgm::{Batch, FungibleAssetOrder, FungibleAssetOrderV1, Instruction},
+    };
+
+    use super::*;
+
+    const MUNO: &[u8] = b"muno";
+    const USDC: [u8; 20] = hex!("1c7d4b196cb0c7b01d743fbc6116a902379c7238");
+
+    fn priority() -> ChannelPriority {
+        ChannelPriority {
+            channel_id: ChannelId::from_raw(1).unwrap(),
+            allowlist: [Bytes::from(USDC.to_vec())].into(),
+            thresholds: vec![TokenThreshold {
+                token: MUNO.into(),
+                high_value: Some(1_000_000_u64.into()),
+                dust: Some(1_000_u64.into()),
+            }],
+            dust_delay: Duration::from_secs(60),
+        }
+    }
+
+    fn order(token: &[u8], amount: u64) -> Instruction {
+        Instruction::FungibleAssetOrder(FungibleAssetOrder::V1(FungibleAssetOrderV1 {
+            sender: Default::default(),
+            receiver: Default::default(),
+            base_token: token.into(),
+            base_amount: amount.into(),
+            base_token_symbol: String::new(),
+            base_token_name: String::new(),
+            base_token_decimals: 6,
+            base_token_path: U256::ZERO,
+            quote_token: Default::default(),
+            quote_amount: amount.into(),
+        }))
+    }
+
+    fn batch(instructions: Vec<Instruction>) -> Instruction {
+        Instruction::Batch(Batch { instructions })
+    }
+
+    fn packet(instruction: Instruction) -> Vec<u8> {
+        ZkgmPacket {
+            salt: H256::default(),
+            path: U256::ZERO,
+            instruction,
+        }
+        .encode_as::<EthAbi>()
+    }
+
+    #[test]
+    fn thresholds() {
+        let priority = priority();
+
+        assert_eq!(
+            priority.classify(&packet(order(MUNO, 1_000_000))),
+            Priority::High
+        );
+        assert_eq!(
+            priority.classify(&packet(order(MUNO, 1_000))),
+            Priority::Normal
+        );
+        assert_eq!(
+            priority.classify(&packet(order(MUNO, 999))),
+            Priority::Dust(Duration::from_secs(60))
+        );
+        // no threshold configured for this token
+        assert_eq!(
+            priority.classify(&packet(order(b"other", 1))),
+            Priority::Normal
+        );
+    }
+
+    #[test]
+    fn allowlist() {
+        assert_eq!(
+            priority().classify(&packet(order(&USDC, 1))),
+            Priority::High
+        );
+    }
+
+    #[test]
+    fn batch_orders() {
+        let priority = priority();
+
+        assert_eq!(
+            priority.classify(&packet(batch(vec![order(MUNO, 1), order(MUNO, 1_000_000)]))),
+            Priority::High
+        );
+        assert_eq!(
+            priority.classify(&packet(batch(vec![order(MUNO, 1), order(MUNO, 1_000)]))),
+            Priority::Normal
+        );
+        assert_eq!(
+            priority.classify(&packet(batch(vec![order(MUNO, 1), order(MUNO, 2)]))),
+            Priority::Dust(Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn not_zkgm() {
+        assert_eq!(priority().classify(b"not a zkgm packet"), Priority::Normal);
+        assert_eq!(
+            priority().classify(&packet(batch(vec![]))),
+            Priority::Normal
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Estimation of the encoded size of messages, used to keep the transactions submitted to a chain
+//! within its [`max_message_size`](crate::Config::max_message_size).
+//!
+//! The exact encoding of a message depends on the chain it is submitted to (abi encoded calldata,
+//! protobuf, bcs, ...), so the estimates are the lengths of all of the variable-length fields of
+//! the message (proofs, packet data, client messages, etc), plus a fixed overhead for the rest of
+//! the message and for each packet in it. The overheads are chosen such that they are an upper
+//! bound for abi encoding, which is the least compact of the supported encodings.
+
+use ibc_union_spec::datagram::Datagram;
+
+/// The size of a message without any of its variable-length fields (the type or selector, ids,
+/// heights, and the offsets and lengths of the variable-length fields).
+pub const MESSAGE_OVERHEAD: usize = 256;
+
+/// The size of a packet in a message without its data.
+pub const PACKET_OVERHEAD: usize = 256;
+
+/// The size of a variable-length field of `len` bytes, padded to 32 bytes as in abi encoding and
+/// prefixed with its length.
+const fn bytes(len: usize) -> usize {
+    32 + len.div_ceil(32) * 32
+}
+
+pub fn union_datagram_size(msg: &Datagram) -> usize {
+    let packets = |packets: &[ibc_union_spec::Packet]| {
+        packets
+            .iter()
+            .map(|packet| PACKET_OVERHEAD + bytes(packet.data.len()))
+            .sum::<usize>()
+    };
+
+    MESSAGE_OVERHEAD
+        + match msg {
+            Datagram::CreateClient(msg) => {
+                bytes(msg.client_state_bytes.len()) + bytes(msg.consensus_state_bytes.len())
+            }
+            Datagram::UpdateClient(msg) => bytes(msg.client_message.len()),
+            Datagram::ConnectionOpenInit(_) => 0,
+            Datagram::ConnectionOpenTry(msg) => bytes(msg.proof_init.len()),
+            Datagram::ConnectionOpenAck(msg) => bytes(msg.proof_try.len()),
+            Datagram::ConnectionOpenConfirm(msg) => bytes(msg.proof_ack.len()),
+            Datagram::ChannelOpenInit(msg) => {
+                bytes(msg.port_id.len())
+                    + bytes(msg.counterparty_port_id.len())
+                    + bytes(msg.version.len())
+            }
+            Datagram::ChannelOpenTry(msg) => {
+                bytes(msg.port_id.len())
+                    + bytes(msg.channel.counterparty_port_id.len())
+                    + bytes(msg.channel.version.len())
+                    + bytes(msg.counterparty_version.len())
+                    + bytes(msg.proof_init.len())
+            }
+            Datagram::ChannelOpenAck(msg) => {
+                bytes(msg.counterparty_version.len()) + bytes(msg.proof_try.len())
+            }
+            Datagram::ChannelOpenConfirm(msg) => bytes(msg.proof_ack.len()),
+            Datagram::ChannelCloseInit(_) | Datagram::ChannelCloseConfirm(_) => 0,
+            Datagram::PacketRecv(msg) => {
+                packets(&msg.packets)
+                    + msg
+                        .relayer_msgs
+                        .iter()
+                        .map(|relayer_msg| bytes(relayer_msg.len()))
+                        .sum::<usize>()
+                    + bytes(msg.proof.len())
+            }
+            Datagram::PacketAcknowledgement(msg) => {
+                packets(&msg.packets)
+                    + msg
+                        .acknowledgements
+                        .iter()
+                        .map(|ack| bytes(ack.len()))
+                        .sum::<usize>()
+                    + bytes(msg.proof.len())
+            }
+            Datagram::PacketTimeout(msg) => {
+                packets(std::slice::from_ref(&msg.packet)) + bytes(msg.proof.len())
+            }
+            Datagram::IntentPacketRecv(_) => 0,
+            Datagram::BatchSend(msg) => packets(&msg.packets),
+            Datagram::BatchAcks(msg) => {
+                packets(&msg.packets) + msg.acks.iter().map(|ack| bytes(ack.len())).sum::<usize>()
+            }
+        }
+}
+
+pub fn classic_datagram_size(msg: &ibc_classic_spec::Datagram) -> usize {
+    use ibc_classic_spec::Datagram;
+
+    MESSAGE_OVERHEAD
+        + match msg {
+            Datagram::CreateClient(msg) => {
+                bytes(msg.msg.client_state.len()) + bytes(msg.msg.consensus_state.len())
+            }
+            Datagram::UpdateClient(msg) => bytes(msg.client_message.len()),
+            Datagram::ConnectionOpenInit(_) => 0,
+            Datagram::ConnectionOpenTry(msg) => bytes(msg.proof_init.len()),
+            Datagram::ConnectionOpenAck(msg) => {
+                bytes(msg.client_state.len())
+                    + bytes(msg.proof_try.len())
+                    + bytes(msg.proof_client.len())
+                    + bytes(msg.proof_consensus.len())
+            }
+            Datagram::ConnectionOpenConfirm(msg) => bytes(msg.proof_ack.len()),
+            Datagram::ChannelOpenInit(_) => 0,
+            Datagram::ChannelOpenTry(msg) => bytes(msg.proof_init.len()),
+            Datagram::ChannelOpenAck(msg) => bytes(msg.proof_try.len()),
+            Datagram::ChannelOpenConfirm(msg) => bytes(msg.proof_ack.len()),
+            Datagram::RecvPacket(msg) => {
+                PACKET_OVERHEAD + bytes(msg.packet.data.len()) + bytes(msg.proof_commitment.len())
+            }
+            Datagram::AcknowledgePacket(msg) => {
+                PACKET_OVERHEAD
+                    + bytes(msg.packet.data.len())
+                    + bytes(msg.acknowledgement.len())
+                    + bytes(msg.proof_acked.len())
+            }
+            Datagram::TimeoutPacket(msg) => {
+                PACKET_OVERHEAD + bytes(msg.packet.data.len()) + bytes(msg.proof_unreceived.len())
+            }
+        }
+}
+
+/// Split `msgs` into consecutive chunks with a total size of at most `max_size` each, keeping the
+/// messages in order. Messages that are larger than `max_size` on their own are put into a chunk of
+/// their own.
+pub fn split_by_size<T>(
+    msgs: impl IntoIterator<Item = T>,
+    max_size: usize,
+    size: impl Fn(&T) -> usize,
+) -> Vec<Vec<T>> {
+    let mut chunks = Vec::<(usize, Vec<T>)>::new();
+
+    for msg in msgs {
+        let msg_size = size(&msg);
+
+        match chunks.last_mut() {
+            Some((chunk_size, chunk)) if *chunk_size + msg_size <= max_size => {
+                *chunk_size += msg_size;
+                chunk.push(msg);
+            }
+            _ => chunks.push((msg_size, vec![msg])),
+        }
+    }
+
+    chunks.into_iter().map(|(_, chunk)| chunk).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_by_size_keeps_order() {
+        assert_eq!(
+            split_by_size([3, 4, 2, 5, 1, 1], 7, |n| *n),
+            vec![vec![3, 4], vec![2, 5], vec![1, 1]]
+        );
+    }
+
+    #[test]
+    fn split_by_size_oversized_message_is_alone() {
+        assert_eq!(
+            split_by_size([1, 10, 2, 3], 5, |n| *n),
+            vec![vec![1], vec![10], vec![2, 3]]
+        );
+    }
+
+    #[test]
+    fn split_by_size_empty() {
+        assert_eq!(
+            split_by_size(Vec::<usize>::new(), 5, |n| *n),
+            Vec::<Vec<usize>>::new()
+        );
+    }
+
+    #[test]
+    fn bytes_is_padded() {
+        assert_eq!(bytes(0), 32);
+        assert_eq!(bytes(1), 64);
+        assert_eq!(bytes(32), 64);
+        assert_eq!(bytes(33), 96);
+    }
+}
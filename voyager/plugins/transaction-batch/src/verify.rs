@@ -0,0 +1,163 @@
+use alloy::primitives::keccak256;
+use ibc_union_spec::{
+    path::{ClientStatePath, ConsensusStatePath, StorePath},
+    ClientId, IbcUnion,
+};
+use serde_json::Value;
+use tracing::{debug, instrument};
+use unionlabs::{ibc::core::client::height::Height, primitives::H256};
+use voyager_sdk::{
+    jsonrpsee::core::RpcResult,
+    primitives::{ChainId, ClientInfo, IbcStorePathKey, QueryHeight},
+    rpc::{types::ProofVerification, PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE},
+    VoyagerClient,
+};
+
+/// Verifies proofs of the origin chain against the states of the client tracking it on the target
+/// chain, before the proofs are submitted.
+pub struct ProofVerifier<'a> {
+    pub voyager_client: &'a VoyagerClient,
+    pub origin_chain_id: &'a ChainId,
+    pub origin_chain_proof_height: Height,
+    pub target_chain_id: &'a ChainId,
+}
+
+impl ProofVerifier<'_> {
+    /// Verify `proof` of the `value` committed under `key` (or of its absence) with the client
+    /// `client_id` on the target chain.
+    ///
+    /// The consensus state at the proof height is read from the client if it exists. Otherwise, the
+    /// client will be updated to the proof height in the same transaction, and the consensus state
+    /// is built from the origin chain instead.
+    ///
+    /// Verification is skipped if the client module does not support it.
+    #[instrument(
+        skip_all,
+        fields(
+            origin_chain_id = %self.origin_chain_id,
+            origin_chain_proof_height = %self.origin_chain_proof_height,
+            target_chain_id = %self.target_chain_id,
+            %client_id,
+            %key,
+        )
+    )]
+    pub async fn verify(
+        &self,
+        client_id: ClientId,
+        client_info: &ClientInfo,
+        key: H256,
+        value: Option<H256>,
+        proof: Value,
+    ) -> RpcResult<()> {
+        let client_state = self
+            .voyager_client
+            .query_ibc_state(
+                self.target_chain_id.clone(),
+                QueryHeight::Latest,
+                ClientStatePath { client_id },
+            )
+            .await?;
+
+        let consensus_state = match self
+            .voyager_client
+            .maybe_query_ibc_state(
+                self.target_chain_id.clone(),
+                QueryHeight::Latest,
+                ConsensusStatePath {
+                    client_id,
+                    height: self.origin_chain_proof_height.height(),
+                },
+            )
+            .await?
+            .state
+        {
+            Some(consensus_state) => consensus_state,
+            None => {
+                debug!("consensus state does not exist yet, building it from the origin chain");
+
+                let self_consensus_state = self
+                    .voyager_client
+                    .self_consensus_state(
+                        self.origin_chain_id.clone(),
+                        client_info.client_type.clone(),
+                        QueryHeight::Specific(self.origin_chain_proof_height),
+                        Value::Null,
+                    )
+                    .await?;
+
+                self.voyager_client
+                    .encode_consensus_state::<IbcUnion>(
+                        client_info.client_type.clone(),
+                        client_info.ibc_interface.clone(),
+                        self_consensus_state.state,
+                    )
+                    .await?
+            }
+        };
+
+        let result = self
+            .voyager_client
+            .verify_proof::<IbcUnion>(
+                client_info.client_type.clone(),
+                client_info.ibc_interface.clone(),
+                ProofVerification {
+                    client_state,
+                    consensus_state,
+                    key: key.into_bytes(),
+                    value: value.map(H256::into_bytes),
+                    proof,
+                },
+            )
+            .await;
+
+        match result {
+            Ok(()) => debug!("verified proof"),
+            Err(error) if error.code() == PROOF_VERIFICATION_UNSUPPORTED_ERROR_CODE => {
+                debug!(
+                    client_type = %client_info.client_type,
+                    ibc_interface = %client_info.ibc_interface,
+                    "client module does not support proof verification, skipping"
+                );
+            }
+            Err(error) => return Err(error),
+        }
+
+        Ok(())
+    }
+
+    /// Verify `proof` of the commitment stored under a batch path (packets or receipts) on the
+    /// origin chain, which is the commitment itself.
+    pub async fn verify_batch<P>(
+        &self,
+        client_id: ClientId,
+        client_info: &ClientInfo,
+        path: P,
+        proof: Value,
+    ) -> RpcResult<()>
+    where
+        P: IbcStorePathKey<Spec = IbcUnion, Value = H256>,
+    {
+        let commitment = self
+            .voyager_client
+            .query_ibc_state(
+                self.origin_chain_id.clone(),
+                QueryHeight::Specific(self.origin_chain_proof_height),
+                path.clone(),
+            )
+            .await?;
+
+        self.verify(
+            client_id,
+            client_info,
+            Into::<StorePath>::into(path).key(),
+            Some(commitment),
+            proof,
+        )
+        .await
+    }
+}
+
+/// The commitment of an IBC union connection or channel end, as stored by the origin chain.
+pub fn commit(bytes: impl AsRef<[u8]>) -> H256 {
+    H256::new(keccak256(bytes).0)
+}
@@ -16,6 +16,7 @@ use move_core_types::{
     identifier::Identifier,
     language_storage::{StructTag, TypeTag},
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha3::Digest;
 use tracing::instrument;
@@ -103,11 +104,12 @@ impl Plugin for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
     pub rpc_url: String,
+    #[schemars(with = "String")]
     pub ibc_handler_address: Address,
 
     pub keyring: KeyringConfig,
@@ -165,7 +167,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                     SubmitTxHook::new(&self.chain_id, |submit_tx| {
                         PluginMessage::new(
                             self.plugin_name(),
-                            ModuleCall::SubmitTransaction(
+                            ModuleCall::submit_transaction(
                                 submit_tx
                                     .datagrams
                                     .iter()
@@ -173,6 +175,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                                         message.decode_datagram::<IbcUnion>().unwrap().unwrap()
                                     })
                                     .collect(), // .collect::<Result<_, _>>()?,
+                                submit_tx.signers.clone(),
                             ),
                         )
                         .into()
@@ -188,79 +191,77 @@ impl PluginServer<ModuleCall, Never> for Module {
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
     async fn call(&self, _: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
-        match msg {
-            ModuleCall::SubmitTransaction(msgs) => self
-                .keyring
-                .with(|pk| {
-                    let msgs = msgs.clone();
-                    AssertUnwindSafe(async move {
-                        let sender = (*<H256>::from(
-                            sha3::Sha3_256::new()
-                                .chain_update(pk.public_key().to_bytes())
-                                .chain_update([0])
-                                .finalize(),
-                        )
-                        .get())
-                        .into();
+        let (msgs, signers) = match msg {
+            ModuleCall::SubmitTransaction(msgs) => (msgs, None),
+            ModuleCall::SubmitTransactionWithSigners { signers, msgs } => (msgs, Some(signers)),
+        };
+
+        self.keyring
+            .with_any_of(signers.as_ref(), |pk| {
+                let msgs = msgs.clone();
+                AssertUnwindSafe(async move {
+                    let sender = (*<H256>::from(
+                        sha3::Sha3_256::new()
+                            .chain_update(pk.public_key().to_bytes())
+                            .chain_update([0])
+                            .finalize(),
+                    )
+                    .get())
+                    .into();
+
+                    let account = self
+                        .aptos_client
+                        .get_account(sender)
+                        .await
+                        .unwrap()
+                        .into_inner();
 
-                        let account = self
+                    dbg!(&account);
+
+                    let msgs =
+                        process_msgs(self.ibc_handler_address.into(), self, msgs.clone(), sender)
+                            .await;
+
+                    let mut txs = vec![];
+
+                    for (i, (_, entry_fn)) in msgs.into_iter().enumerate() {
+                        let raw = RawTransaction::new_entry_function(
+                            sender,
+                            account.sequence_number + (i as u64),
+                            entry_fn,
+                            400000,
+                            100,
+                            vm::now() + 100,
+                            self.chain_id.as_str().parse().unwrap(),
+                        );
+
+                        let signed_tx = raw.sign(pk, pk.public_key()).unwrap();
+
+                        // TODO(aeryz): we normally should've send a batch transaction but
+                        // movement don't allow it now.
+                        dbg!(&signed_tx);
+                        let res = self
                             .aptos_client
-                            .get_account(sender)
+                            .submit(&signed_tx.clone().into_inner())
                             .await
-                            .unwrap()
-                            .into_inner();
+                            .unwrap();
 
-                        dbg!(&account);
+                        dbg!(&res);
+                        txs.push(signed_tx.into_inner());
+                    }
 
-                        let msgs = process_msgs(
-                            self.ibc_handler_address.into(),
-                            self,
-                            msgs.clone(),
-                            sender,
-                        )
-                        .await;
-
-                        let mut txs = vec![];
-
-                        for (i, (_, entry_fn)) in msgs.into_iter().enumerate() {
-                            let raw = RawTransaction::new_entry_function(
-                                sender,
-                                account.sequence_number + (i as u64),
-                                entry_fn,
-                                400000,
-                                100,
-                                vm::now() + 100,
-                                self.chain_id.as_str().parse().unwrap(),
-                            );
-
-                            let signed_tx = raw.sign(pk, pk.public_key()).unwrap();
-
-                            // TODO(aeryz): we normally should've send a batch transaction but
-                            // movement don't allow it now.
-                            dbg!(&signed_tx);
-                            let res = self
-                                .aptos_client
-                                .submit(&signed_tx.clone().into_inner())
-                                .await
-                                .unwrap();
-
-                            dbg!(&res);
-                            txs.push(signed_tx.into_inner());
-                        }
-
-                        // res.into_inner().transaction_failures
-
-                        Ok(noop())
-                    })
+                    // res.into_inner().transaction_failures
+
+                    Ok(noop())
                 })
-                .await
-                .unwrap_or_else(|| {
-                    Ok(call(PluginMessage::new(
-                        self.plugin_name(),
-                        ModuleCall::SubmitTransaction(msgs),
-                    )))
-                }),
-        }
+            })
+            .await
+            .unwrap_or_else(|| {
+                Ok(call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::submit_transaction(msgs, signers),
+                )))
+            })
     }
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use enumorph::Enumorph;
 use ibc_classic_spec::IbcClassic;
 use ibc_union_spec::IbcUnion;
@@ -10,6 +12,21 @@ use voyager_sdk::{message::data::IbcDatagram, rpc::FATAL_JSONRPC_ERROR_CODE};
 #[derive(Enumorph)]
 pub enum ModuleCall {
     SubmitTransaction(Vec<IbcMessage>),
+    /// Same as [`ModuleCall::SubmitTransaction`], but only submitted by one of `signers`.
+    SubmitTransactionWithSigners {
+        signers: BTreeSet<String>,
+        msgs: Vec<IbcMessage>,
+    },
+}
+
+impl ModuleCall {
+    #[must_use]
+    pub fn submit_transaction(msgs: Vec<IbcMessage>, signers: Option<BTreeSet<String>>) -> Self {
+        match signers {
+            Some(signers) => Self::SubmitTransactionWithSigners { signers, msgs },
+            None => Self::SubmitTransaction(msgs),
+        }
+    }
 }
 
 #[model]
@@ -2,14 +2,15 @@
 #![feature(if_let_guard)]
 
 use std::{
-    collections::{BTreeMap, HashMap, HashSet, VecDeque},
-    num::NonZeroU32,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
     ops::Deref,
     panic::AssertUnwindSafe,
-    sync::{Arc, LazyLock},
+    sync::{Arc, LazyLock, Mutex},
+    time::Duration,
 };
 
-use cometbft_rpc::rpc_types::GrpcAbciQueryError;
+use cometbft_rpc::rpc_types::{GrpcAbciQueryError, TxResponse};
 use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
 use cosmos_client::{
     gas::{any, feemarket, fixed, osmosis_eip1559_feemarket, GasFillerT},
@@ -25,6 +26,7 @@ use jsonrpsee::{
     Extensions, MethodsError,
 };
 use prost::Message;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug, error, info, info_span, instrument, trace, warn};
@@ -34,19 +36,21 @@ use unionlabs::{
     google::protobuf::any::mk_any,
     never::Never,
     option_unwrap,
-    primitives::{Bech32, Bytes, H160, H256},
+    primitives::{Bech32, Bytes, H160, H256, U256},
     ErrorReporter,
 };
+use voyager_proof_stats::{ProofStats, Stats, StatsConfig, SubmittedTx};
 use voyager_sdk::{
+    abci::CachedAbciQuery,
     anyhow::{self, anyhow, bail},
     hook::SubmitTxHook,
     into_value,
-    message::{data::Data, PluginMessage, VoyagerMessage},
+    message::{call::Call, data::Data, PluginMessage, VoyagerMessage},
     plugin::Plugin,
     primitives::ChainId,
     rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
     vm::{call, noop, pass::PassResult, seq, BoxDynError, Op, Visit},
-    DefaultCmd,
+    DefaultCmd, ExtensionsExt, VoyagerClient,
 };
 
 use crate::call::{IbcMessage, ModuleCall};
@@ -73,6 +77,13 @@ pub struct ModuleInner {
     pub gas_station_config: Vec<Coin>,
     pub fee_recipient: Option<Bech32<Bytes>>,
     pub max_tx_size: u32,
+    pub batch_gas_limit: Option<BatchGasLimitConfig>,
+    pub max_msgs_per_tx: Option<NonZeroUsize>,
+    pub cached_abci_query: CachedAbciQuery,
+    /// The gas used in the blocks sampled for the batch gas budget, by height. Blocks are only
+    /// fetched once, and dropped when they leave the sampled range.
+    pub recent_gas_used: Mutex<BTreeMap<u64, u64>>,
+    pub stats: Option<Stats>,
 }
 
 impl Deref for Module {
@@ -83,7 +94,7 @@ impl Deref for Module {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
@@ -99,9 +110,51 @@ pub struct Config {
     #[serde(default)]
     pub fee_recipient: Option<Bech32<Bytes>>,
     pub max_tx_size: u32,
+    /// If set, batches will be split if their simulated gas usage exceeds the gas that is safely
+    /// available in a block on this chain.
+    #[serde(default)]
+    pub batch_gas_limit: Option<BatchGasLimitConfig>,
+    /// If set, transaction submissions that are queued at the same time are merged into
+    /// transactions of up to this many messages, instead of submitting one transaction per
+    /// submission. Merged transactions that exceed `max_tx_size` or the `batch_gas_limit` are split
+    /// again before they are submitted.
+    #[serde(default)]
+    pub max_msgs_per_tx: Option<NonZeroUsize>,
+    /// Record the proof sizes and fee costs of submitted ibc-union datagrams, aggregated over the
+    /// `proofStats` rpc method.
+    #[serde(default)]
+    pub stats: Option<StatsConfig>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BatchGasLimitConfig {
+    /// The block gas limit of the chain. If not set, this will be read from the consensus params
+    /// of the chain.
+    #[serde(default)]
+    pub max_block_gas: Option<u64>,
+    /// The amount of recent blocks to sample when calculating block fullness.
+    #[serde(default = "default_recent_blocks")]
+    pub recent_blocks: u64,
+    /// The maximum fraction of the remaining gas in a block that a single transaction is allowed
+    /// to use.
+    #[serde(
+        with = "::serde_utils::string",
+        default = "default_max_block_utilization"
+    )]
+    #[schemars(with = "String")]
+    pub max_block_utilization: f64,
+}
+
+fn default_recent_blocks() -> u64 {
+    5
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+fn default_max_block_utilization() -> f64 {
+    0.5
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case", tag = "type", content = "config")]
 pub enum GasFillerConfig {
     // fixed gas filler is it's own config
@@ -110,22 +163,25 @@ pub enum GasFillerConfig {
     OsmosisEip1559Feemarket(OsmosisEip1559FeemarketConfig),
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct FeemarketConfig {
     pub max_gas: u64,
     #[serde(with = "::serde_utils::string_opt")]
+    #[schemars(with = "Option<String>")]
     pub gas_multiplier: Option<f64>,
     pub denom: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct OsmosisEip1559FeemarketConfig {
     pub max_gas: u64,
     #[serde(with = "::serde_utils::string_opt")]
+    #[schemars(with = "Option<String>")]
     pub gas_multiplier: Option<f64>,
     #[serde(with = "::serde_utils::string_opt")]
+    #[schemars(with = "Option<String>")]
     pub base_fee_multiplier: Option<f64>,
     pub denom: Option<String>,
 }
@@ -175,6 +231,11 @@ static ACCOUNT_SEQUENCE_ERRORS: LazyLock<HashSet<(&str, NonZeroU32)>> = LazyLock
     .collect()
 });
 
+fn is_account_sequence_error(codespace: &str, error_code: NonZeroU32, log: &str) -> bool {
+    ACCOUNT_SEQUENCE_ERRORS.contains(&(codespace, error_code))
+        || log.contains("account sequence mismatch")
+}
+
 impl Plugin for Module {
     type Call = ModuleCall;
     type Callback = Never;
@@ -208,6 +269,13 @@ impl Plugin for Module {
             .unwrap()
             .bech32_prefix;
 
+        let stats = match config.stats {
+            Some(config) => Some(Stats::new(config).await?),
+            None => None,
+        };
+
+        let contention_cooldown = config.keyring.contention_cooldown();
+
         Ok(Self(Arc::new(ModuleInner {
             ibc_host_contract_address: config.ibc_host_contract_address,
             keyring: ConcurrentKeyring::new(
@@ -221,7 +289,8 @@ impl Plugin for Module {
                         signer,
                     }
                 }),
-            ),
+            )
+            .with_contention_cooldown(contention_cooldown),
             rpc,
             chain_id: ChainId::new(chain_id),
             gas_config: config
@@ -242,6 +311,12 @@ impl Plugin for Module {
             gas_station_config: config.gas_station_config,
             fee_recipient: config.fee_recipient,
             max_tx_size: config.max_tx_size,
+            batch_gas_limit: config.batch_gas_limit,
+            max_msgs_per_tx: config.max_msgs_per_tx,
+            cached_abci_query: CachedAbciQuery::new(rpc.client().clone())
+                .with_ttl(CONSENSUS_PARAMS_PATH, CONSENSUS_PARAMS_CACHE_TTL),
+            recent_gas_used: Mutex::new(BTreeMap::new()),
+            stats,
         })))
     }
 
@@ -265,6 +340,21 @@ trait TransactionPlugin {
 
     #[method(name = "signerBalances")]
     async fn signer_balances(&self) -> RpcResult<BTreeMap<Bech32<H160>, String>>;
+
+    /// The fee for `gas` units of gas as computed by the configured gas filler, in the smallest
+    /// unit of the fee denom.
+    #[method(name = "estimateFee")]
+    async fn estimate_fee(&self, gas: u64) -> RpcResult<U256>;
+
+    /// Proof sizes and fee costs of the ibc-union datagrams submitted on this chain, per source
+    /// chain and datagram kind. Optionally only from `source_chain_id`, and within the last
+    /// `since_seconds` seconds.
+    #[method(name = "proofStats")]
+    async fn proof_stats(
+        &self,
+        source_chain_id: Option<ChainId>,
+        since_seconds: Option<i64>,
+    ) -> RpcResult<Vec<ProofStats>>;
 }
 
 #[async_trait]
@@ -319,6 +409,41 @@ impl TransactionPluginServer for Module {
 
         Ok(out)
     }
+
+    async fn estimate_fee(&self, gas: u64) -> RpcResult<U256> {
+        Ok(self
+            .gas_config
+            .mk_fee(gas)
+            .await
+            .amount
+            .first()
+            .map_or(U256::ZERO, |coin| coin.amount.into()))
+    }
+
+    async fn proof_stats(
+        &self,
+        source_chain_id: Option<ChainId>,
+        since_seconds: Option<i64>,
+    ) -> RpcResult<Vec<ProofStats>> {
+        let Some(stats) = &self.stats else {
+            return Err(ErrorObject::owned(
+                -1,
+                "proof stats are not enabled",
+                None::<()>,
+            ));
+        };
+
+        stats
+            .aggregate(&self.chain_id, source_chain_id.as_ref(), since_seconds)
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error querying proof stats"),
+                    None::<()>,
+                )
+            })
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
@@ -332,12 +457,131 @@ impl Module {
         plugin_name(&self.chain_id)
     }
 
+    /// Split `msgs` into two halves, to be submitted in separate transactions.
+    fn split_batch(
+        &self,
+        mut msgs: Vec<IbcMessage>,
+        signers: Option<&BTreeSet<String>>,
+    ) -> Op<VoyagerMessage> {
+        let new_msgs = msgs.split_off(msgs.len().div_ceil(2));
+
+        seq([
+            call(PluginMessage::new(
+                self.plugin_name(),
+                ModuleCall::submit_transaction(msgs, signers.cloned()),
+            )),
+            call(PluginMessage::new(
+                self.plugin_name(),
+                ModuleCall::submit_transaction(new_msgs, signers.cloned()),
+            )),
+        ])
+    }
+
+    /// Calculate the maximum amount of gas a single transaction can safely use, based on the
+    /// block gas limit and the gas used in the most recent blocks.
+    ///
+    /// Returns `None` if the chain has no block gas limit.
+    async fn batch_gas_budget(&self, config: &BatchGasLimitConfig) -> anyhow::Result<Option<u64>> {
+        let max_block_gas = match config.max_block_gas {
+            Some(max_block_gas) => max_block_gas,
+            None => {
+                let max_gas = self
+                    .cached_abci_query
+                    .grpc_abci_query::<_, protos::cosmos::consensus::v1::QueryParamsResponse>(
+                        CONSENSUS_PARAMS_PATH,
+                        &protos::cosmos::consensus::v1::QueryParamsRequest {},
+                        None,
+                        false,
+                    )
+                    .await?
+                    .into_result()?
+                    .and_then(|res| res.params)
+                    .and_then(|params| params.block)
+                    .ok_or_else(|| anyhow!("block params not found"))?
+                    .max_gas;
+
+                // -1 indicates no limit
+                match u64::try_from(max_gas) {
+                    Ok(max_gas) => max_gas,
+                    Err(_) => return Ok(None),
+                }
+            }
+        };
+
+        let latest_height = self
+            .rpc
+            .client()
+            .status()
+            .await?
+            .sync_info
+            .latest_block_height;
+
+        let first_height = latest_height.saturating_sub(config.recent_blocks.saturating_sub(1));
+
+        let missing_heights = {
+            let recent_gas_used = self
+                .recent_gas_used
+                .lock()
+                .expect("lock is not poisoned; qed;");
+
+            (first_height..=latest_height)
+                .filter_map(NonZeroU64::new)
+                .filter(|height| !recent_gas_used.contains_key(&height.get()))
+                .collect::<Vec<_>>()
+        };
+
+        let mut fetched_gas_used = vec![];
+        for height in missing_heights {
+            let block_results = self.rpc.client().block_results(Some(height)).await?;
+
+            let gas_used = block_results
+                .txs_results
+                .unwrap_or_default()
+                .iter()
+                .try_fold(0_u64, |total, tx_result| {
+                    total.checked_add(u64::try_from(tx_result.gas_used.inner()).ok()?)
+                })
+                .ok_or_else(|| anyhow!("gas used in block {height} overflows u64"))?;
+
+            fetched_gas_used.push((height.get(), gas_used));
+        }
+
+        let recent_gas_used = {
+            let mut recent_gas_used = self
+                .recent_gas_used
+                .lock()
+                .expect("lock is not poisoned; qed;");
+
+            recent_gas_used.extend(fetched_gas_used);
+            recent_gas_used.retain(|height, _| (first_height..=latest_height).contains(height));
+
+            recent_gas_used.values().copied().collect::<Vec<_>>()
+        };
+
+        let budget = batch_gas_budget(
+            max_block_gas,
+            &recent_gas_used,
+            config.max_block_utilization,
+        );
+
+        debug!(
+            %max_block_gas,
+            ?recent_gas_used,
+            %budget,
+            "calculated batch gas budget"
+        );
+
+        Ok(Some(budget))
+    }
+
     pub async fn do_send_transaction(
         &self,
+        voyager_client: &VoyagerClient,
         msgs: Vec<IbcMessage>,
+        signers: Option<&BTreeSet<String>>,
     ) -> Option<Result<Option<Op<VoyagerMessage>>, BroadcastTxCommitError>> {
         self.keyring
-            .with(|signer| {
+            .with_any_of(signers, |signer| {
                 let msgs = msgs.clone();
 
                 trace!(?msgs);
@@ -400,23 +644,54 @@ impl Module {
                                 "tx is too large, splitting messages"
                             );
 
-                            let mut msgs = msgs.into_iter().map(|x| x.0).collect::<Vec<_>>();
-
-                            let new_msgs = msgs.split_off(msgs.len().div_ceil(2));
-
-                            return Ok(Some(seq([
-                                call(PluginMessage::new(
-                                    self.plugin_name(),
-                                    ModuleCall::SubmitTransaction(msgs),
-                                )),
-                                call(PluginMessage::new(
-                                    self.plugin_name(),
-                                    ModuleCall::SubmitTransaction(new_msgs),
-                                )),
-                            ])));
+                            return Ok(Some(
+                                self.split_batch(msgs.into_iter().map(|x| x.0).collect(), signers),
+                            ));
                         }
                     };
 
+                    if let Some(batch_gas_limit) =
+                        self.batch_gas_limit.as_ref().filter(|_| msgs.len() > 1)
+                    {
+                        match self.batch_gas_budget(batch_gas_limit).await {
+                            Ok(Some(budget)) => match tx_client
+                                .simulate_tx(
+                                    msgs.iter().map(|x| x.1.clone()).collect::<Vec<_>>(),
+                                    &memo,
+                                )
+                                .await
+                            {
+                                Ok((_, _, gas_info)) if gas_info.gas_used > budget => {
+                                    warn!(
+                                        gas_used = %gas_info.gas_used,
+                                        %budget,
+                                        "tx exceeds the batch gas budget, splitting messages"
+                                    );
+
+                                    return Ok(Some(self.split_batch(
+                                        msgs.into_iter().map(|x| x.0).collect(),
+                                        signers,
+                                    )));
+                                }
+                                Ok(_) => {}
+                                // the tx is simulated again on submission, errors are handled there
+                                Err(err) => {
+                                    debug!(
+                                        error = %ErrorReporter(err),
+                                        "unable to simulate tx for batch gas budget"
+                                    );
+                                }
+                            },
+                            Ok(None) => {}
+                            Err(err) => {
+                                warn!(
+                                    error = %ErrorReporter(&*err),
+                                    "unable to calculate batch gas budget"
+                                );
+                            }
+                        }
+                    }
+
                     match tx_client
                         .broadcast_tx_commit(
                             msgs.iter().map(move |x| x.1.clone()).collect::<Vec<_>>(),
@@ -437,10 +712,46 @@ impl Module {
                                 info!(tx_hash = %tx_response.hash, %msg, "cosmos msg");
                             }
 
+                            if let Some(stats) = &self.stats {
+                                // stats are best effort, the transaction has been submitted at
+                                // this point
+                                if let Err(err) = stats
+                                    .record(
+                                        voyager_client,
+                                        &self.chain_id,
+                                        submitted_tx(&tx_response, &msgs),
+                                    )
+                                    .await
+                                {
+                                    warn!(
+                                        error = %ErrorReporter(&*err),
+                                        "error recording proof stats"
+                                    );
+                                }
+                            }
+
                             Ok(None)
                         }
                         Err(err) => {
                             info!(error = %ErrorReporter(&err), "cosmos tx failed");
+
+                            // prefer other signers until this one's sequence has settled
+                            if let BroadcastTxCommitError::Query(GrpcAbciQueryError {
+                                error_code,
+                                codespace,
+                                log,
+                            })
+                            | BroadcastTxCommitError::TxFailed {
+                                codespace,
+                                error_code,
+                                log,
+                            } = &err
+                            {
+                                if is_account_sequence_error(codespace, *error_code, log) {
+                                    self.keyring.report_contention(&signer.address());
+                                }
+                            }
+
                             Err(err)
                         }
                     }
@@ -450,6 +761,89 @@ impl Module {
     }
 }
 
+/// The ibc-union datagrams of a submitted transaction with the size of their encoded msgs, and the
+/// fee paid for it (read from the fee of the transaction, in its first fee denom).
+fn submitted_tx(
+    tx_response: &TxResponse,
+    msgs: &[(IbcMessage, protos::google::protobuf::Any)],
+) -> SubmittedTx {
+    let fee = protos::cosmos::tx::v1beta1::TxRaw::decode(&*tx_response.tx)
+        .ok()
+        .and_then(|tx_raw| {
+            protos::cosmos::tx::v1beta1::AuthInfo::decode(&*tx_raw.auth_info_bytes).ok()
+        })
+        .and_then(|auth_info| auth_info.fee?.amount.first()?.amount.parse().ok())
+        .unwrap_or_default();
+
+    SubmittedTx {
+        tx_hash: tx_response.hash.into_encoding(),
+        gas_used: tx_response
+            .tx_result
+            .gas_used
+            .inner()
+            .try_into()
+            .unwrap_or_default(),
+        fee,
+        datagrams: msgs
+            .iter()
+            .filter_map(|(msg, any)| match msg {
+                IbcMessage::IbcUnion(datagram) => Some((datagram.clone(), any.encoded_len())),
+                IbcMessage::IbcV1(_) => None,
+            })
+            .collect(),
+    }
+}
+
+/// Merge the submissions (queue index, signers and msgs) with the same signers into batches of
+/// up to `max_msgs` msgs, in order. Submissions that exceed `max_msgs` on their own are kept as is.
+fn merge_submissions<T>(
+    submissions: Vec<(usize, Option<BTreeSet<String>>, Vec<T>)>,
+    max_msgs: usize,
+) -> Vec<(Vec<usize>, Option<BTreeSet<String>>, Vec<T>)> {
+    let mut merged = vec![];
+    let mut pending = BTreeMap::<Option<BTreeSet<String>>, (Vec<usize>, Vec<T>)>::new();
+
+    for (idx, signers, msgs) in submissions {
+        let (idxs, batch) = pending.entry(signers.clone()).or_default();
+
+        if !batch.is_empty() && batch.len() + msgs.len() > max_msgs {
+            merged.push((std::mem::take(idxs), signers, std::mem::take(batch)));
+        }
+
+        idxs.push(idx);
+        batch.extend(msgs);
+    }
+
+    merged.extend(
+        pending
+            .into_iter()
+            .map(|(signers, (idxs, batch))| (idxs, signers, batch)),
+    );
+
+    merged
+}
+
+const CONSENSUS_PARAMS_PATH: &str = "/cosmos.consensus.v1.Query/Params";
+
+/// How long to cache the consensus params, which rarely change.
+const CONSENSUS_PARAMS_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The maximum amount of gas a single transaction can safely use in a block with a gas limit of
+/// `max_block_gas`, given the gas used in recent blocks.
+fn batch_gas_budget(
+    max_block_gas: u64,
+    recent_gas_used: &[u64],
+    max_block_utilization: f64,
+) -> u64 {
+    let average_gas_used = recent_gas_used
+        .iter()
+        .sum::<u64>()
+        .checked_div(recent_gas_used.len() as u64)
+        .unwrap_or_default();
+
+    (max_block_gas.saturating_sub(average_gas_used) as f64 * max_block_utilization) as u64
+}
+
 // {
 //     Ok((tx_hash, gas_used)) => {
 //         info!(
@@ -503,7 +897,7 @@ impl PluginServer<ModuleCall, Never> for Module {
         let mut hook = SubmitTxHook::new(&self.chain_id, |submit_tx| {
             PluginMessage::new(
                 self.plugin_name(),
-                ModuleCall::SubmitTransaction(
+                ModuleCall::submit_transaction(
                     submit_tx
                         .datagrams
                         .clone()
@@ -511,6 +905,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                         .map(IbcMessage::from_raw_datagram)
                         .collect::<Result<_, _>>()
                         .unwrap(),
+                    submit_tx.signers.clone(),
                 ),
             )
             .into()
@@ -518,177 +913,224 @@ impl PluginServer<ModuleCall, Never> for Module {
 
         debug!(msgs = msgs.len(), "optimizing messages");
 
-        Ok(PassResult {
-            optimize_further: vec![],
-            ready: msgs
-                .into_iter()
-                .enumerate()
-                .map(|(idx, mut op)| {
+        let Some(max_msgs_per_tx) = self.max_msgs_per_tx else {
+            return Ok(PassResult {
+                optimize_further: vec![],
+                ready: msgs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, mut op)| {
+                        hook.visit_op(&mut op);
+
+                        (vec![idx], op)
+                    })
+                    .collect(),
+            });
+        };
+
+        let mut ready = vec![];
+        let mut submissions = vec![];
+
+        for (idx, mut op) in msgs.into_iter().enumerate() {
+            match op {
+                // only top level submissions can be merged, nested ones are ordered by their parent
+                Op::Call(Call::SubmitTx(submit_tx)) if submit_tx.chain_id == self.chain_id => {
+                    submissions.push((
+                        idx,
+                        submit_tx.signers,
+                        submit_tx
+                            .datagrams
+                            .into_iter()
+                            .map(IbcMessage::from_raw_datagram)
+                            .collect::<Result<Vec<_>, _>>()?,
+                    ));
+                }
+                _ => {
                     hook.visit_op(&mut op);
 
-                    (vec![idx], op)
-                })
-                .collect(),
+                    ready.push((vec![idx], op));
+                }
+            }
+        }
+
+        for (idxs, signers, msgs) in merge_submissions(submissions, max_msgs_per_tx.get()) {
+            if idxs.len() > 1 {
+                info!(
+                    submissions = idxs.len(),
+                    msgs = msgs.len(),
+                    "merged transaction submissions"
+                );
+            }
+
+            ready.push((
+                idxs,
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::submit_transaction(msgs, signers),
+                )),
+            ));
+        }
+
+        Ok(PassResult {
+            optimize_further: vec![],
+            ready,
         })
     }
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
-    async fn call(&self, _: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
-        match msg {
-            ModuleCall::SubmitTransaction(mut msgs) => {
-                let batch_submission_result = self.do_send_transaction(msgs.clone()).await;
-
-                match batch_submission_result {
-                    None => Err(ErrorObject::owned(-1, "no signers available", None::<()>)),
-                    Some(Ok(None)) => {
-                        for (idx, msg) in msgs.into_iter().enumerate() {
-                            info!(
-                                msg = msg.name(),
-                                %idx,
-                                data = %into_value(&msg),
-                                "cosmos tx",
-                            );
-                        }
-                        Ok(noop())
+    async fn call(&self, e: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
+        let (mut msgs, signers) = match msg {
+            ModuleCall::SubmitTransaction(msgs) => (msgs, None),
+            ModuleCall::SubmitTransactionWithSigners { signers, msgs } => (msgs, Some(signers)),
+        };
+
+        let batch_submission_result = self
+            .do_send_transaction(e.voyager_client()?, msgs.clone(), signers.as_ref())
+            .await;
+
+        match batch_submission_result {
+            None => Err(ErrorObject::owned(-1, "no signers available", None::<()>)),
+            Some(Ok(None)) => {
+                for (idx, msg) in msgs.into_iter().enumerate() {
+                    info!(
+                        msg = msg.name(),
+                        %idx,
+                        data = %into_value(&msg),
+                        "cosmos tx",
+                    );
+                }
+                Ok(noop())
+            }
+            Some(Ok(Some(op))) => Ok(op),
+            Some(Err(err)) => {
+                match err {
+                    _ if let Some(err) = err.as_json_rpc_error() => {
+                        return Err(ErrorObject::owned(
+                            -1,
+                            ErrorReporter(err).with_message("jsonrpc error"),
+                            None::<()>,
+                        ))
                     }
-                    Some(Ok(Some(op))) => Ok(op),
-                    Some(Err(err)) => {
-                        match err {
-                            _ if let Some(err) = err.as_json_rpc_error() => {
-                                return Err(ErrorObject::owned(
-                                    -1,
-                                    ErrorReporter(err).with_message("jsonrpc error"),
-                                    None::<()>,
-                                ))
-                            }
 
-                            BroadcastTxCommitError::Query(GrpcAbciQueryError {
-                                error_code,
-                                codespace,
-                                log,
-                            })
-                            | BroadcastTxCommitError::TxFailed {
-                                codespace,
-                                error_code,
-                                log,
-                            } if ACCOUNT_SEQUENCE_ERRORS.contains(&(&codespace, error_code))
-                                || log.contains("account sequence mismatch") =>
-                            {
-                                return Err(ErrorObject::owned(
-                                    -1,
-                                    format!("account sequence mismatch ({codespace}, {error_code}): {log}"),
-                                    None::<()>,
-                                ));
-                            }
-
-                            BroadcastTxCommitError::Query(GrpcAbciQueryError {
-                                error_code,
-                                codespace,
-                                log,
-                            })
-                            | BroadcastTxCommitError::TxFailed {
-                                codespace,
-                                error_code,
-                                log,
-                            } => {
-                                info!(%log, "error submitting cosmos tx");
-
-                                if let Some((msg_idx, log)) = parse_msg_idx_from_log(&log) {
-                                    let _span = info_span!("cosmos msg failed", msg_idx).entered();
-                                    info!(%log, "tx log");
+                    BroadcastTxCommitError::Query(GrpcAbciQueryError {
+                        error_code,
+                        codespace,
+                        log,
+                    })
+                    | BroadcastTxCommitError::TxFailed {
+                        codespace,
+                        error_code,
+                        log,
+                    } if is_account_sequence_error(&codespace, error_code, &log) => {
+                        return Err(ErrorObject::owned(
+                            -1,
+                            format!("account sequence mismatch ({codespace}, {error_code}): {log}"),
+                            None::<()>,
+                        ));
+                    }
 
-                                    match self.fatal_errors.get(&(codespace.clone(), error_code)) {
-                                        // no msg
-                                        Some(None) => {
-                                            error!(codespace, error_code, %log, "fatal error");
+                    BroadcastTxCommitError::Query(GrpcAbciQueryError {
+                        error_code,
+                        codespace,
+                        log,
+                    })
+                    | BroadcastTxCommitError::TxFailed {
+                        codespace,
+                        error_code,
+                        log,
+                    } => {
+                        info!(%log, "error submitting cosmos tx");
+
+                        if let Some((msg_idx, log)) = parse_msg_idx_from_log(&log) {
+                            let _span = info_span!("cosmos msg failed", msg_idx).entered();
+                            info!(%log, "tx log");
+
+                            match self.fatal_errors.get(&(codespace.clone(), error_code)) {
+                                // no msg
+                                Some(None) => {
+                                    error!(codespace, error_code, %log, "fatal error");
+                                }
+                                // provided msg
+                                Some(Some(msg)) => {
+                                    error!(codespace, error_code, %log, "fatal error: {msg}");
+                                }
+                                // unknown error, retry
+                                None => match parse_wasm_failure(log) {
+                                    Some(err) => match err {
+                                        ContractErrorKind::ReceivedTimedOutPacketHeight => {
+                                            info!("packet timed out (height)");
                                         }
-                                        // provided msg
-                                        Some(Some(msg)) => {
-                                            error!(codespace, error_code, %log, "fatal error: {msg}");
+                                        ContractErrorKind::ReceivedTimedOutPacketTimestamp => {
+                                            info!("packet timed out (timestamp)");
                                         }
-                                        // unknown error, retry
-                                        None => match parse_wasm_failure(log) {
-                                            Some(err) => match err {
-                                                ContractErrorKind::ReceivedTimedOutPacketHeight => {
-                                                    info!("packet timed out (height)");
-                                                }
-                                                ContractErrorKind::ReceivedTimedOutPacketTimestamp => {
-                                                    info!("packet timed out (timestamp)");
-                                                }
-                                                ContractErrorKind::AlreadyAcknowledged => {
-                                                    info!("packet already acknowledged");
-                                                }
-                                                ContractErrorKind::PacketCommitmentNotFound => {
-                                                    info!("packet commitment not found");
-                                                }
-                                                _ => {
-                                                    warn!("ibc-union error ({err}): {log}");
-                                                }
-                                            },
-                                            None => {
-                                                warn!("error submitting transaction ({codespace}, {error_code}): {log}");
-                                            }
-                                        },
-                                    }
-
-                                    if msgs.len() == 1 {
-                                        warn!(msg = %into_value(msgs.pop().unwrap()), "cosmos msg failed");
-
-                                        Ok(noop())
-                                    } else {
-                                        let failed_msg = msgs.remove(msg_idx);
-
-                                        if matches!(
-                                            failed_msg,
-                                            IbcMessage::IbcV1(
-                                                ibc_classic_spec::Datagram::UpdateClient(_)
-                                            ) | IbcMessage::IbcUnion(
-                                                ibc_union_spec::datagram::Datagram::UpdateClient(_)
-                                            )
-                                        ) {
-                                            warn!("update client failed, this may cause other messages to fail as well");
+                                        ContractErrorKind::AlreadyAcknowledged => {
+                                            info!("packet already acknowledged");
+                                        }
+                                        ContractErrorKind::PacketCommitmentNotFound => {
+                                            info!("packet commitment not found");
+                                        }
+                                        _ => {
+                                            warn!("ibc-union error ({err}): {log}");
                                         }
+                                    },
+                                    None => {
+                                        warn!("error submitting transaction ({codespace}, {error_code}): {log}");
+                                    }
+                                },
+                            }
 
-                                        warn!(msg = %into_value(failed_msg), "dropping failed msg");
+                            if msgs.len() == 1 {
+                                warn!(msg = %into_value(msgs.pop().unwrap()), "cosmos msg failed");
+
+                                Ok(noop())
+                            } else {
+                                let failed_msg = msgs.remove(msg_idx);
+
+                                if matches!(
+                                    failed_msg,
+                                    IbcMessage::IbcV1(ibc_classic_spec::Datagram::UpdateClient(_))
+                                        | IbcMessage::IbcUnion(
+                                            ibc_union_spec::datagram::Datagram::UpdateClient(_)
+                                        )
+                                ) {
+                                    warn!("update client failed, this may cause other messages to fail as well");
+                                }
 
-                                        if msgs.is_empty() {
-                                            info!("no messages to submit after dropping failed messages");
+                                warn!(msg = %into_value(failed_msg), "dropping failed msg");
 
-                                            Ok(noop())
-                                        } else {
-                                            Ok(call(PluginMessage::new(
-                                                self.plugin_name(),
-                                                ModuleCall::SubmitTransaction(msgs),
-                                            )))
-                                        }
-                                    }
-                                } else if log.contains("insufficient funds") {
-                                    warn!("out of gas");
+                                if msgs.is_empty() {
+                                    info!("no messages to submit after dropping failed messages");
 
-                                    return Err(ErrorObject::owned(-1, "out of gas", None::<()>));
+                                    Ok(noop())
                                 } else {
-                                    warn!("unable to parse message index from tx failure ({codespace}, {error_code}): {log}");
-
-                                    if msgs.len() == 1 {
-                                        warn!(msg = %into_value(msgs.pop().unwrap()), "cosmos msg failed");
-                                        Ok(noop())
-                                    } else {
-                                        Ok(seq(msgs.into_iter().map(|msg| {
-                                            call(PluginMessage::new(
-                                                self.plugin_name(),
-                                                ModuleCall::SubmitTransaction(vec![msg]),
-                                            ))
-                                        })))
-                                    }
+                                    Ok(call(PluginMessage::new(
+                                        self.plugin_name(),
+                                        ModuleCall::submit_transaction(msgs, signers),
+                                    )))
                                 }
                             }
-                            _ => Err(ErrorObject::owned(
-                                -1,
-                                ErrorReporter(err).with_message("error submitting tx"),
-                                None::<()>,
-                            )),
+                        } else if log.contains("insufficient funds") {
+                            warn!("out of gas");
+
+                            return Err(ErrorObject::owned(-1, "out of gas", None::<()>));
+                        } else {
+                            warn!("unable to parse message index from tx failure ({codespace}, {error_code}): {log}");
+
+                            if msgs.len() == 1 {
+                                warn!(msg = %into_value(msgs.pop().unwrap()), "cosmos msg failed");
+                                Ok(noop())
+                            } else {
+                                // split the batch until the failing msg is submitted on its own,
+                                // so that the other msgs are still submitted together
+                                Ok(self.split_batch(msgs, signers.as_ref()))
+                            }
                         }
                     }
+                    _ => Err(ErrorObject::owned(
+                        -1,
+                        ErrorReporter(err).with_message("error submitting tx"),
+                        None::<()>,
+                    )),
                 }
             }
         }
@@ -1245,7 +1687,8 @@ mod tests {
                     keys: vec![KeyringConfigEntry::Raw {
                         name: "name".to_string(),
                         key: vec![0; 32],
-                    }]
+                    }],
+                    contention_cooldown_seconds: None,
                 },
                 rpc_url: "rpc_url".to_string(),
                 gas_config: GasFillerConfig::Feemarket(FeemarketConfig {
@@ -1256,8 +1699,46 @@ mod tests {
                 fatal_errors: HashMap::default(),
                 gas_station_config: vec![],
                 fee_recipient: None,
-                max_tx_size: 1000000
+                max_tx_size: 1000000,
+                batch_gas_limit: None,
+                max_msgs_per_tx: None,
+                stats: None,
             }
         );
     }
+
+    #[test]
+    fn test_batch_gas_budget() {
+        assert_eq!(batch_gas_budget(100_000_000, &[], 0.5), 50_000_000);
+        assert_eq!(
+            batch_gas_budget(100_000_000, &[20_000_000, 40_000_000], 0.5),
+            35_000_000
+        );
+        assert_eq!(batch_gas_budget(100_000_000, &[150_000_000], 0.5), 0);
+    }
+
+    #[test]
+    fn test_merge_submissions() {
+        let signers = Some(["a".to_owned()].into_iter().collect::<BTreeSet<_>>());
+
+        assert_eq!(
+            merge_submissions(
+                vec![
+                    (0, None, vec![1, 2]),
+                    (1, signers.clone(), vec![3]),
+                    (2, None, vec![4]),
+                    (3, None, vec![5, 6]),
+                    (4, None, vec![7, 8, 9, 10]),
+                    (5, signers.clone(), vec![11]),
+                ],
+                3
+            ),
+            vec![
+                (vec![0, 2], None, vec![1, 2, 4]),
+                (vec![3], None, vec![5, 6]),
+                (vec![4], None, vec![7, 8, 9, 10]),
+                (vec![1, 5], signers, vec![3, 11]),
+            ]
+        );
+    }
 }
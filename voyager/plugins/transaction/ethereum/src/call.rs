@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use enumorph::Enumorph;
 use macros::model;
 
@@ -5,4 +7,22 @@ use macros::model;
 #[derive(Enumorph)]
 pub enum ModuleCall {
     SubmitMulticall(Vec<ibc_union_spec::datagram::Datagram>),
+    /// Same as [`ModuleCall::SubmitMulticall`], but only submitted by one of `signers`.
+    SubmitMulticallWithSigners {
+        signers: BTreeSet<String>,
+        msgs: Vec<ibc_union_spec::datagram::Datagram>,
+    },
+}
+
+impl ModuleCall {
+    #[must_use]
+    pub fn submit_multicall(
+        msgs: Vec<ibc_union_spec::datagram::Datagram>,
+        signers: Option<BTreeSet<String>>,
+    ) -> Self {
+        match signers {
+            Some(signers) => Self::SubmitMulticallWithSigners { signers, msgs },
+            None => Self::SubmitMulticall(msgs),
+        }
+    }
 }
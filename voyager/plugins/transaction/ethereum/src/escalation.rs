@@ -0,0 +1,336 @@
+use std::time::Duration;
+
+use alloy::{
+    network::{AnyNetwork, Ethereum, TransactionBuilderError},
+    primitives::{hex, keccak256, Bytes},
+    providers::DynProvider,
+    signers::{local::LocalSigner, Signer},
+    transports::{http::reqwest, TransportError},
+};
+use bip32::secp256k1::ecdsa::SigningKey;
+use ibc_union_spec::datagram::Datagram;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use voyager_sdk::primitives::Timestamp;
+
+use crate::fees::{FeeError, Fees, MIN_BUMP_MULTIPLIER};
+
+/// The header carrying the signature of a bundle, as required by flashbots compatible relays.
+const BUNDLE_SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+
+/// Escalation path for packets that are close to their timeout. If a transaction containing such
+/// packets is not included within the inclusion deadline, it is replaced by a transaction with the
+/// same nonce and higher fees, submitted through a private relay instead of the public mempool.
+/// Neither transaction is waited for after the packets time out.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EscalationConfig {
+    /// The RPC endpoint of the private transaction relay or bundle RPC.
+    pub relay_url: String,
+
+    #[serde(default)]
+    pub method: EscalationMethod,
+
+    /// Transactions containing packets that time out within this many seconds are eligible for
+    /// escalation.
+    pub timeout_window_seconds: u64,
+
+    /// How long to wait for inclusion via the public mempool before escalating.
+    pub inclusion_deadline_seconds: u64,
+
+    /// Multiplier applied to the current fee estimate for the escalated transaction. The fees are
+    /// bumped by at least 10% over the replaced transaction regardless, as nodes don't accept
+    /// smaller bumps.
+    #[serde(with = "::serde_utils::string")]
+    #[schemars(with = "String")]
+    pub fee_multiplier: f64,
+
+    /// Cap on the max fee per gas of the escalated transaction, or on its gas price on chains
+    /// that are paid by gas price (`legacy` or `fixed_gas_price`).
+    pub max_fee_per_gas: u128,
+
+    /// Cap on the max priority fee per gas of the escalated transaction.
+    pub max_priority_fee_per_gas: u128,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type", deny_unknown_fields)]
+pub enum EscalationMethod {
+    /// Submit the escalated transaction with `eth_sendRawTransaction` to the relay.
+    #[default]
+    PrivateTransaction,
+    /// Submit the escalated transaction as a single transaction bundle with `eth_sendBundle`,
+    /// targeting each of the next `target_blocks` blocks. Bundles are signed with the key of the
+    /// transaction, which the relay uses to identify the sender.
+    Bundle { target_blocks: u64 },
+}
+
+/// The fees of a transaction. Chains that are configured as `legacy` or with a `fixed_gas_price`
+/// are paid by gas price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxFees {
+    Legacy { gas_price: u128 },
+    Eip1559(Fees),
+}
+
+impl EscalationConfig {
+    pub fn inclusion_deadline(&self) -> Duration {
+        Duration::from_secs(self.inclusion_deadline_seconds)
+    }
+
+    /// The earliest timeout (in seconds) of the packets received by `msgs` that time out within
+    /// the timeout window of `now` (in seconds), if any. Only timestamp timeouts are considered.
+    pub fn earliest_timeout(&self, msgs: &[Datagram], now: u64) -> Option<u64> {
+        let deadline = Timestamp::from_secs(now.saturating_add(self.timeout_window_seconds));
+
+        msgs.iter()
+            .flat_map(|msg| match msg {
+                Datagram::PacketRecv(msg) => &msg.packets[..],
+                _ => &[],
+            })
+            .map(|packet| packet.timeout_timestamp)
+            .filter(|timeout| *timeout != Timestamp::ZERO && *timeout <= deadline)
+            .min()
+            .map(|timeout| timeout.as_secs())
+    }
+
+    /// The fees of the escalated replacement of a transaction with `fees`, given the current
+    /// estimate. Every fee is bumped by at least 10% over the replaced transaction. Returns `None`
+    /// if the cost caps don't allow for such a bump, or for a fee of at least the current
+    /// estimate.
+    pub fn escalated_fees(&self, fees: TxFees, current: TxFees) -> Option<TxFees> {
+        let escalate = |fee: u128, current: u128, cap: u128| {
+            let min_bump = (fee as f64 * MIN_BUMP_MULTIPLIER).ceil() as u128;
+
+            let escalated = ((current as f64 * self.fee_multiplier) as u128)
+                .max(min_bump)
+                .min(cap);
+
+            (escalated >= min_bump && escalated >= current).then_some(escalated)
+        };
+
+        match (fees, current) {
+            (TxFees::Legacy { gas_price }, TxFees::Legacy { gas_price: current }) => {
+                Some(TxFees::Legacy {
+                    gas_price: escalate(gas_price, current, self.max_fee_per_gas)?,
+                })
+            }
+            (TxFees::Eip1559(fees), TxFees::Eip1559(current)) => {
+                let max_fee_per_gas = escalate(
+                    fees.max_fee_per_gas,
+                    current.max_fee_per_gas,
+                    self.max_fee_per_gas,
+                )?;
+
+                Some(TxFees::Eip1559(Fees {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas: escalate(
+                        fees.max_priority_fee_per_gas,
+                        current.max_priority_fee_per_gas,
+                        self.max_priority_fee_per_gas.min(max_fee_per_gas),
+                    )?,
+                }))
+            }
+            // the fees of a chain are always estimated the same way
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Escalation {
+    pub config: EscalationConfig,
+    pub relay: DynProvider<AnyNetwork>,
+    /// Bundles are submitted over plain http, as they are signed over the raw request body.
+    pub http: reqwest::Client,
+}
+
+impl Escalation {
+    /// Submits `raw_tx` as a bundle for `target_block`, signed by `signer`.
+    pub async fn send_bundle(
+        &self,
+        signer: &LocalSigner<SigningKey>,
+        raw_tx: &Bytes,
+        target_block: u64,
+    ) -> Result<(), EscalationError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_sendBundle",
+            "params": [{
+                "txs": [raw_tx],
+                "blockNumber": format!("{target_block:#x}"),
+            }],
+        })
+        .to_string();
+
+        // the relay verifies an EIP-191 signature of the hex encoded hash of the body
+        let signature = signer
+            .sign_message(hex::encode_prefixed(keccak256(&body)).as_bytes())
+            .await?;
+
+        let response = self
+            .http
+            .post(&self.config.relay_url)
+            .header("Content-Type", "application/json")
+            .header(
+                BUNDLE_SIGNATURE_HEADER,
+                format!(
+                    "{}:{}",
+                    signer.address(),
+                    hex::encode_prefixed(signature.as_bytes())
+                ),
+            )
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Value>()
+            .await?;
+
+        match response.get("error") {
+            Some(error) => Err(EscalationError::BundleRejected(error.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EscalationError {
+    #[error("error fetching escalation parameters")]
+    Transport(#[from] TransportError),
+    #[error("error estimating fees")]
+    Fees(#[from] FeeError),
+    #[error("error signing escalated transaction")]
+    Sign(#[from] TransactionBuilderError<Ethereum>),
+    #[error("error signing bundle")]
+    SignBundle(#[from] alloy::signers::Error),
+    #[error("error submitting escalated transaction to the relay")]
+    Relay(#[source] TransportError),
+    #[error("error submitting bundle to the relay")]
+    Bundle(#[from] reqwest::Error),
+    #[error("bundle rejected by the relay: {0}")]
+    BundleRejected(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use ibc_union_spec::{datagram::MsgPacketRecv, ChannelId, Packet};
+
+    use super::*;
+
+    fn config() -> EscalationConfig {
+        EscalationConfig {
+            relay_url: "http://localhost:8545".to_owned(),
+            method: EscalationMethod::PrivateTransaction,
+            timeout_window_seconds: 60,
+            inclusion_deadline_seconds: 12,
+            fee_multiplier: 2.0,
+            max_fee_per_gas: 100,
+            max_priority_fee_per_gas: 10,
+        }
+    }
+
+    fn packet_recv(timeout_timestamp: Timestamp) -> Datagram {
+        Datagram::PacketRecv(MsgPacketRecv {
+            packets: vec![Packet {
+                source_channel_id: ChannelId::new(1.try_into().unwrap()),
+                destination_channel_id: ChannelId::new(2.try_into().unwrap()),
+                data: Default::default(),
+                timeout_height: 0,
+                timeout_timestamp,
+            }],
+            relayer_msgs: vec![],
+            proof: Default::default(),
+            proof_height: 1,
+        })
+    }
+
+    #[test]
+    fn earliest_timeout() {
+        let config = config();
+
+        assert_eq!(
+            config.earliest_timeout(
+                &[
+                    packet_recv(Timestamp::from_secs(1_060)),
+                    packet_recv(Timestamp::from_secs(1_030))
+                ],
+                1_000
+            ),
+            Some(1_030)
+        );
+        assert_eq!(
+            config.earliest_timeout(&[packet_recv(Timestamp::from_secs(1_061))], 1_000),
+            None
+        );
+        assert_eq!(
+            config.earliest_timeout(&[packet_recv(Timestamp::ZERO)], 1_000),
+            None
+        );
+    }
+
+    fn eip1559(max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> TxFees {
+        TxFees::Eip1559(Fees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+
+    #[test]
+    fn escalated_fees() {
+        let config = config();
+
+        assert_eq!(
+            config.escalated_fees(eip1559(20, 2), eip1559(20, 2)),
+            Some(eip1559(40, 4))
+        );
+        assert_eq!(
+            config.escalated_fees(eip1559(60, 6), eip1559(80, 8)),
+            Some(eip1559(100, 10))
+        );
+        // the current estimate is exceeded by the cap
+        assert_eq!(config.escalated_fees(eip1559(60, 6), eip1559(120, 8)), None);
+        // the current estimate dropped below the fees of the replaced transaction
+        assert_eq!(
+            config.escalated_fees(eip1559(80, 8), eip1559(20, 2)),
+            Some(eip1559(88, 9))
+        );
+        // the cap does not allow for a bump of at least 10%
+        assert_eq!(config.escalated_fees(eip1559(95, 8), eip1559(20, 2)), None);
+    }
+
+    #[test]
+    fn escalated_legacy_fees() {
+        let config = config();
+
+        assert_eq!(
+            config.escalated_fees(
+                TxFees::Legacy { gas_price: 30 },
+                TxFees::Legacy { gas_price: 40 }
+            ),
+            Some(TxFees::Legacy { gas_price: 80 })
+        );
+        assert_eq!(
+            config.escalated_fees(
+                TxFees::Legacy { gas_price: 95 },
+                TxFees::Legacy { gas_price: 40 }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn method_serde() {
+        assert_eq!(
+            serde_json::from_str::<EscalationMethod>(r#"{"type":"private_transaction"}"#).unwrap(),
+            EscalationMethod::PrivateTransaction
+        );
+        assert_eq!(
+            serde_json::from_str::<EscalationMethod>(r#"{"type":"bundle","target_blocks":3}"#)
+                .unwrap(),
+            EscalationMethod::Bundle { target_blocks: 3 }
+        );
+    }
+}
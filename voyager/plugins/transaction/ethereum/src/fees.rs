@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use alloy::{
+    network::{Ethereum, TransactionBuilderError},
+    transports::TransportError,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// EIP-1559 fee strategy. Fees are estimated from the fee history of recent blocks, and
+/// transactions that are not included within the stall timeout are rebroadcast with the same
+/// nonce and bumped fees.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FeeConfig {
+    /// The number of recent blocks to estimate the priority fee from.
+    #[serde(default = "default_fee_history_blocks")]
+    pub fee_history_blocks: u64,
+
+    /// The percentile of the priority fees paid in each of the recent blocks to use, between 0 and
+    /// 100.
+    #[serde(default = "default_priority_fee_percentile")]
+    pub priority_fee_percentile: f64,
+
+    /// The max fee per gas is the base fee of the next block times this multiplier, plus the
+    /// priority fee, so that the transaction stays includable if the base fee rises.
+    #[serde(
+        default = "default_base_fee_multiplier",
+        with = "::serde_utils::string"
+    )]
+    #[schemars(with = "String")]
+    pub base_fee_multiplier: f64,
+
+    /// Rebroadcast transactions that are not included within this many seconds.
+    pub stall_timeout_seconds: u64,
+
+    /// Multiplier applied to both fees on every rebroadcast. Nodes only accept replacements that
+    /// bump the fees by at least 10%.
+    #[serde(default = "default_bump_multiplier", with = "::serde_utils::string")]
+    #[schemars(with = "String")]
+    pub bump_multiplier: f64,
+
+    /// Transactions are rebroadcast at most this many times, after which the last one is waited
+    /// for.
+    #[serde(default = "default_max_rebroadcasts")]
+    pub max_rebroadcasts: u32,
+
+    /// Cap on the max fee per gas, both for the estimate and the bumped fees.
+    #[serde(default)]
+    pub max_fee_per_gas: Option<u128>,
+}
+
+fn default_fee_history_blocks() -> u64 {
+    10
+}
+
+fn default_priority_fee_percentile() -> f64 {
+    50.0
+}
+
+fn default_base_fee_multiplier() -> f64 {
+    2.0
+}
+
+fn default_bump_multiplier() -> f64 {
+    1.125
+}
+
+fn default_max_rebroadcasts() -> u32 {
+    5
+}
+
+/// The minimum fee bump of a replacement transaction accepted by geth and most other clients.
+pub const MIN_BUMP_MULTIPLIER: f64 = 1.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fees {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl FeeConfig {
+    pub fn stall_timeout(&self) -> Duration {
+        Duration::from_secs(self.stall_timeout_seconds)
+    }
+
+    /// The fees for the next block, given its base fee and the priority fees paid at the
+    /// configured percentile in recent blocks. Blocks without transactions (a reward of zero) are
+    /// ignored.
+    pub fn estimate(&self, next_base_fee_per_gas: u128, rewards: &[u128]) -> Fees {
+        let mut rewards = rewards
+            .iter()
+            .copied()
+            .filter(|reward| *reward > 0)
+            .collect::<Vec<_>>();
+        rewards.sort_unstable();
+
+        let max_priority_fee_per_gas = rewards.get(rewards.len() / 2).copied().unwrap_or(0);
+
+        let max_fee_per_gas = ((next_base_fee_per_gas as f64 * self.base_fee_multiplier) as u128)
+            .saturating_add(max_priority_fee_per_gas);
+
+        self.capped(Fees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+
+    /// The fees of the replacement of a transaction with `fees`. Returns `None` if the cap on the
+    /// max fee per gas does not allow for a bump that nodes would accept.
+    pub fn bumped(&self, fees: Fees) -> Option<Fees> {
+        let bump = |fee: u128| (fee as f64 * self.bump_multiplier).ceil() as u128;
+        let min_bump = |fee: u128| (fee as f64 * MIN_BUMP_MULTIPLIER).ceil() as u128;
+
+        let bumped = self.capped(Fees {
+            max_fee_per_gas: bump(fees.max_fee_per_gas),
+            max_priority_fee_per_gas: bump(fees.max_priority_fee_per_gas),
+        });
+
+        (bumped.max_fee_per_gas >= min_bump(fees.max_fee_per_gas)
+            && bumped.max_priority_fee_per_gas >= min_bump(fees.max_priority_fee_per_gas))
+        .then_some(bumped)
+    }
+
+    fn capped(&self, fees: Fees) -> Fees {
+        let max_fee_per_gas = self
+            .max_fee_per_gas
+            .map_or(fees.max_fee_per_gas, |cap| fees.max_fee_per_gas.min(cap));
+
+        Fees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas.min(max_fee_per_gas),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FeeError {
+    #[error("error fetching fee history")]
+    Transport(#[from] TransportError),
+    #[error("fee history is empty")]
+    EmptyFeeHistory,
+    #[error("error signing replacement transaction")]
+    Sign(#[from] TransactionBuilderError<Ethereum>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> FeeConfig {
+        FeeConfig {
+            fee_history_blocks: 10,
+            priority_fee_percentile: 50.0,
+            base_fee_multiplier: 2.0,
+            stall_timeout_seconds: 30,
+            bump_multiplier: 1.125,
+            max_rebroadcasts: 5,
+            max_fee_per_gas: Some(1000),
+        }
+    }
+
+    #[test]
+    fn estimate() {
+        let config = config();
+
+        assert_eq!(
+            config.estimate(100, &[0, 3, 1, 2, 0]),
+            Fees {
+                max_fee_per_gas: 202,
+                max_priority_fee_per_gas: 2
+            }
+        );
+        assert_eq!(
+            config.estimate(100, &[]),
+            Fees {
+                max_fee_per_gas: 200,
+                max_priority_fee_per_gas: 0
+            }
+        );
+        assert_eq!(
+            config.estimate(600, &[10]),
+            Fees {
+                max_fee_per_gas: 1000,
+                max_priority_fee_per_gas: 10
+            }
+        );
+    }
+
+    #[test]
+    fn bumped() {
+        let config = config();
+
+        assert_eq!(
+            config.bumped(Fees {
+                max_fee_per_gas: 200,
+                max_priority_fee_per_gas: 8
+            }),
+            Some(Fees {
+                max_fee_per_gas: 225,
+                max_priority_fee_per_gas: 9
+            })
+        );
+        // the cap leaves no room for a bump of at least 10%
+        assert_eq!(
+            config.bumped(Fees {
+                max_fee_per_gas: 950,
+                max_priority_fee_per_gas: 8
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn config_defaults() {
+        let config = serde_json::from_str::<FeeConfig>(r#"{"stall_timeout_seconds": 30}"#).unwrap();
+
+        assert_eq!(config.fee_history_blocks, 10);
+        assert_eq!(config.bump_multiplier, 1.125);
+        assert_eq!(config.max_rebroadcasts, 5);
+        assert_eq!(config.max_fee_per_gas, None);
+    }
+}
@@ -3,23 +3,27 @@ use std::{
     ops::Deref,
     panic::AssertUnwindSafe,
     sync::Arc,
+    time::Duration,
 };
 
 use alloy::{
     contract::{Error, RawCallBuilder},
-    network::{AnyNetwork, EthereumWallet},
-    primitives::Address,
+    eips::{eip2718::Encodable2718, BlockNumberOrTag},
+    network::{AnyNetwork, Ethereum, EthereumWallet, TransactionBuilder},
+    primitives::{Address, Bytes, B256},
     providers::{
-        fillers::RecommendedFillers, layers::CacheLayer, DynProvider, PendingTransactionError,
-        Provider, ProviderBuilder,
+        fillers::RecommendedFillers, layers::CacheLayer, DynProvider, PendingTransactionBuilder,
+        PendingTransactionError, Provider, ProviderBuilder,
     },
+    rpc::types::{AnyTransactionReceipt, TransactionRequest},
     signers::local::LocalSigner,
     sol_types::{SolEvent, SolInterface},
-    transports::TransportError,
+    transports::{http::reqwest, TransportError},
 };
 use bip32::secp256k1::ecdsa::{self, SigningKey};
 use clap::Subcommand;
 use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
+use evm_contract_check::ContractChecks;
 use ibc_solidity::Ibc::{self, IbcErrors};
 use ibc_union_spec::{datagram::Datagram, IbcUnion};
 use jsonrpsee::{
@@ -28,6 +32,7 @@ use jsonrpsee::{
     types::{ErrorObject, ErrorObjectOwned},
     Extensions, MethodsError,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{error, info, info_span, instrument, trace, warn, Instrument};
@@ -36,6 +41,7 @@ use unionlabs::{
     primitives::{H160, H256, U256},
     ErrorReporter,
 };
+use voyager_proof_stats::{ProofStats, Stats, StatsConfig, SubmittedTx};
 use voyager_sdk::{
     anyhow::{self, bail},
     hook::SubmitTxHook,
@@ -45,14 +51,22 @@ use voyager_sdk::{
     primitives::ChainId,
     rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
     vm::{call, defer, now, pass::PassResult, seq, Op, Visit},
+    ExtensionsExt,
 };
 
 use crate::{
     call::ModuleCall,
+    escalation::{Escalation, EscalationConfig, EscalationError, EscalationMethod, TxFees},
+    fees::{FeeConfig, FeeError, Fees, MIN_BUMP_MULTIPLIER},
     multicall::{Call3, Multicall, MulticallResult},
 };
 
 pub mod call;
+pub mod escalation;
+pub mod fees;
+
+/// Interval at which receipts are polled while racing the public and escalated transactions.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 #[tokio::main]
 async fn main() {
@@ -92,9 +106,15 @@ pub struct ModuleInner {
     pub legacy: bool,
 
     pub fee_recipient: Option<alloy::primitives::Address>,
+
+    pub escalation: Option<Escalation>,
+
+    pub fees: Option<FeeConfig>,
+
+    pub stats: Option<Stats>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
@@ -118,6 +138,7 @@ pub struct Config {
     pub fixed_gas_price: Option<u128>,
 
     #[serde(with = "::serde_utils::string")]
+    #[schemars(with = "String")]
     pub gas_multiplier: f64,
 
     #[serde(default)]
@@ -127,7 +148,26 @@ pub struct Config {
     pub max_cache_size: u32,
 
     #[serde(default)]
+    #[schemars(with = "Option<String>")]
     pub fee_recipient: Option<alloy::primitives::Address>,
+
+    /// Escalate near-timeout packets through a private relay if they are not included in time.
+    #[serde(default)]
+    pub escalation: Option<EscalationConfig>,
+
+    /// Estimate EIP-1559 fees from recent blocks and rebroadcast stalled transactions with bumped
+    /// fees. Not used with `legacy` or `fixed_gas_price`.
+    #[serde(default)]
+    pub fees: Option<FeeConfig>,
+
+    /// Record the proof sizes and calldata costs of submitted datagrams, aggregated over the
+    /// `proofStats` rpc method.
+    #[serde(default)]
+    pub stats: Option<StatsConfig>,
+
+    /// Checks on the deployed `IBCHandler` (and optionally zkgm), run before the plugin starts.
+    #[serde(default)]
+    pub contract_checks: Option<ContractChecks>,
 }
 
 #[derive(Subcommand)]
@@ -163,6 +203,42 @@ impl Plugin for Module {
             );
         }
 
+        if let Some(contract_checks) = &config.contract_checks {
+            contract_checks
+                .verify(&provider, config.ibc_handler_address)
+                .await?;
+        }
+
+        let escalation = match config.escalation {
+            Some(config) => Some(Escalation {
+                relay: DynProvider::new(
+                    ProviderBuilder::new()
+                        .network::<AnyNetwork>()
+                        .connect(&config.relay_url)
+                        .await?,
+                ),
+                http: reqwest::Client::new(),
+                config,
+            }),
+            None => None,
+        };
+
+        if let Some(fees) = &config.fees {
+            if fees.bump_multiplier < MIN_BUMP_MULTIPLIER {
+                bail!(
+                    "the fee bump multiplier must be at least {MIN_BUMP_MULTIPLIER}, but is {}",
+                    fees.bump_multiplier
+                );
+            }
+        }
+
+        let stats = match config.stats {
+            Some(config) => Some(Stats::new(config).await?),
+            None => None,
+        };
+
+        let contention_cooldown = config.keyring.contention_cooldown();
+
         Ok(Self(Arc::new(ModuleInner {
             chain_id,
             additional_chain_ids: config.additional_chain_ids,
@@ -184,12 +260,16 @@ impl Plugin for Module {
                         signer,
                     }
                 }),
-            ),
+            )
+            .with_contention_cooldown(contention_cooldown),
             max_gas_price: config.max_gas_price,
             fixed_gas_price: config.fixed_gas_price,
             legacy: config.legacy,
             gas_multiplier: config.gas_multiplier,
             fee_recipient: config.fee_recipient,
+            escalation,
+            fees: config.fees,
+            stats,
         })))
     }
 
@@ -233,6 +313,21 @@ trait TransactionPlugin {
 
     #[method(name = "signerBalances")]
     async fn signer_balances(&self) -> RpcResult<BTreeMap<Address, U256>>;
+
+    /// The fee for `gas` units of gas at the current gas price (or the fixed gas price, if
+    /// configured), in wei.
+    #[method(name = "estimateFee")]
+    async fn estimate_fee(&self, gas: u64) -> RpcResult<U256>;
+
+    /// Proof sizes and calldata costs of the datagrams submitted on this chain, per source chain
+    /// and datagram kind. Optionally only from `source_chain_id`, and within the last
+    /// `since_seconds` seconds.
+    #[method(name = "proofStats")]
+    async fn proof_stats(
+        &self,
+        source_chain_id: Option<ChainId>,
+        since_seconds: Option<i64>,
+    ) -> RpcResult<Vec<ProofStats>>;
 }
 
 #[async_trait]
@@ -258,6 +353,46 @@ impl TransactionPluginServer for Module {
 
         Ok(out)
     }
+
+    async fn estimate_fee(&self, gas: u64) -> RpcResult<U256> {
+        let gas_price = match self.fixed_gas_price {
+            Some(fixed_gas_price) => fixed_gas_price,
+            None => self.provider.get_gas_price().await.map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching gas price"),
+                    None::<()>,
+                )
+            })?,
+        };
+
+        Ok(U256::from(gas_price) * U256::from(gas))
+    }
+
+    async fn proof_stats(
+        &self,
+        source_chain_id: Option<ChainId>,
+        since_seconds: Option<i64>,
+    ) -> RpcResult<Vec<ProofStats>> {
+        let Some(stats) = &self.stats else {
+            return Err(ErrorObject::owned(
+                -1,
+                "proof stats are not enabled",
+                None::<()>,
+            ));
+        };
+
+        stats
+            .aggregate(&self.chain_id, source_chain_id.as_ref(), since_seconds)
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error querying proof stats"),
+                    None::<()>,
+                )
+            })
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
@@ -290,6 +425,16 @@ pub enum TxSubmitError {
     RpcError(#[from] ErrorObjectOwned),
     #[error("batch too large")]
     BatchTooLarge,
+    #[error("nonce contention: {0}")]
+    NonceContention(String),
+    #[error("error estimating fees")]
+    Fees(#[from] FeeError),
+    #[error("error escalating transaction")]
+    Escalation(#[from] EscalationError),
+    #[error("transaction was not included before the timeout of its packets")]
+    NotIncluded,
+    #[error("transaction was not included after {rebroadcasts} rebroadcasts")]
+    Stalled { rebroadcasts: usize },
 }
 
 #[async_trait]
@@ -312,7 +457,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                         |submit_tx| {
                             PluginMessage::new(
                                 self.plugin_name(),
-                                ModuleCall::SubmitMulticall(
+                                ModuleCall::submit_multicall(
                                     submit_tx
                                         .datagrams
                                         .clone()
@@ -321,6 +466,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                                             message.decode_datagram::<IbcUnion>().unwrap().unwrap()
                                         })
                                         .collect(),
+                                    submit_tx.signers.clone(),
                                 ),
                             )
                             .into()
@@ -335,63 +481,80 @@ impl PluginServer<ModuleCall, Never> for Module {
     }
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
-    async fn call(&self, _: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
-        match msg {
-            ModuleCall::SubmitMulticall(mut msgs) => {
-                let res = self
-                    .keyring
-                    .with({
-                        let msgs = msgs.clone();
-                        move |wallet| -> _ {
-                            // let call = if self.legacy { call.legacy() } else { call };
-                            AssertUnwindSafe(self.submit_transaction(wallet, msgs))
-                        }
-                    })
-                    .await;
-
-                match res {
-                    Some(Ok(())) => Ok(Op::Noop),
-                    Some(Err(TxSubmitError::GasPriceTooHigh { max, price })) => {
-                        Err(ErrorObject::owned(
-                            -1,
-                            "gas price too high",
-                            Some(json!({
-                                "max": max,
-                                "price": price
-                            })),
-                        ))
-                    }
-                    Some(Err(TxSubmitError::OutOfGas)) => {
-                        Err(ErrorObject::owned(-1, "out of gas", None::<()>))
-                    }
-                    Some(Err(TxSubmitError::EmptyRevert(msgs))) => Ok(seq([
-                        defer(now() + 12),
-                        call(PluginMessage::new(
-                            self.plugin_name(),
-                            ModuleCall::SubmitMulticall(msgs),
-                        )),
-                    ])),
-                    Some(Err(TxSubmitError::BatchTooLarge)) => {
-                        let new = msgs.split_off(msgs.len() / 2);
-                        Ok(seq([
-                            call(PluginMessage::new(
-                                self.plugin_name(),
-                                ModuleCall::SubmitMulticall(msgs),
-                            )),
-                            call(PluginMessage::new(
-                                self.plugin_name(),
-                                ModuleCall::SubmitMulticall(new),
-                            )),
-                        ]))
+    async fn call(&self, e: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
+        let (mut msgs, signers) = match msg {
+            ModuleCall::SubmitMulticall(msgs) => (msgs, None),
+            ModuleCall::SubmitMulticallWithSigners { signers, msgs } => (msgs, Some(signers)),
+        };
+
+        // fetched before submitting, as the result of a submitted transaction must not be lost
+        let voyager_client = e.voyager_client()?;
+
+        let res = self
+            .keyring
+            .with_any_of(signers.as_ref(), {
+                let msgs = msgs.clone();
+                move |wallet| -> _ {
+                    // let call = if self.legacy { call.legacy() } else { call };
+                    AssertUnwindSafe(self.submit_transaction(wallet, msgs))
+                }
+            })
+            .await;
+
+        match res {
+            Some(Ok(submitted)) => {
+                if let (Some(stats), Some(submitted)) = (&self.stats, submitted) {
+                    // stats are best effort, the transaction has been submitted at this point
+                    if let Err(err) = stats
+                        .record(voyager_client, &self.chain_id, submitted)
+                        .await
+                    {
+                        warn!(error = %ErrorReporter(&*err), "error recording proof stats");
                     }
-                    Some(Err(err)) => Err(ErrorObject::owned(
-                        -1,
-                        ErrorReporter(err).to_string(),
-                        None::<()>,
-                    )),
-                    None => Err(ErrorObject::owned(-1, "no signers available", None::<()>)),
                 }
+
+                Ok(Op::Noop)
+            }
+            Some(Err(TxSubmitError::GasPriceTooHigh { max, price })) => Err(ErrorObject::owned(
+                -1,
+                "gas price too high",
+                Some(json!({
+                    "max": max,
+                    "price": price
+                })),
+            )),
+            Some(Err(TxSubmitError::OutOfGas)) => {
+                Err(ErrorObject::owned(-1, "out of gas", None::<()>))
+            }
+            Some(Err(TxSubmitError::EmptyRevert(msgs))) => Ok(seq([
+                defer(now() + 12),
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::submit_multicall(msgs, signers),
+                )),
+            ])),
+            Some(Err(TxSubmitError::BatchTooLarge)) => {
+                let new = msgs.split_off(msgs.len() / 2);
+                Ok(seq([
+                    call(PluginMessage::new(
+                        self.plugin_name(),
+                        ModuleCall::submit_multicall(msgs, signers.clone()),
+                    )),
+                    call(PluginMessage::new(
+                        self.plugin_name(),
+                        ModuleCall::submit_multicall(new, signers),
+                    )),
+                ]))
             }
+            Some(Err(err)) => {
+                let err = ErrorReporter(err);
+                Err(ErrorObject::owned(
+                    -1,
+                    err.to_string(),
+                    Some(json!({ "chain": err.to_json() })),
+                ))
+            }
+            None => Err(ErrorObject::owned(-1, "no signers available", None::<()>)),
         }
     }
 
@@ -431,7 +594,7 @@ impl Module {
         &self,
         wallet: &LocalSigner<SigningKey>,
         ibc_messages: Vec<Datagram>,
-    ) -> Result<(), TxSubmitError> {
+    ) -> Result<Option<SubmittedTx>, TxSubmitError> {
         let signer = DynProvider::new(
             ProviderBuilder::new()
                 .network::<AnyNetwork>()
@@ -465,6 +628,13 @@ impl Module {
 
         let ibc = Ibc::new(self.ibc_handler_address.into(), &self.provider);
 
+        let escalation = self.escalation.as_ref().and_then(|escalation| {
+            escalation
+                .config
+                .earliest_timeout(&ibc_messages, now())
+                .map(|timeout| (escalation, timeout))
+        });
+
         let msgs = process_msgs(
             &ibc,
             ibc_messages,
@@ -479,6 +649,14 @@ impl Module {
             .map(|x| (x.0.clone(), x.0.name()))
             .collect::<Vec<_>>();
 
+        let datagrams = match &self.stats {
+            Some(_) => msgs
+                .iter()
+                .map(|(msg, call)| (msg.clone(), call.calldata().len()))
+                .collect(),
+            None => vec![],
+        };
+
         let mut call = multicall.multicall(
             msgs.clone()
                 .into_iter()
@@ -516,13 +694,91 @@ impl Module {
             call = call.gas_price(fixed_gas_price);
         }
 
-        match call.gas(gas_to_use).send().await {
+        let fees = match &self.fees {
+            Some(fee_config) if self.fixed_gas_price.is_none() && !self.legacy => {
+                let fees = self.estimate_fees(fee_config).await?;
+
+                call = call
+                    .max_fee_per_gas(fees.max_fee_per_gas)
+                    .max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+
+                Some((fee_config, fees))
+            }
+            _ => None,
+        };
+
+        // the escalated transaction replaces the submitted one, so the fees of the submitted one
+        // are set explicitly to be able to bump them
+        let escalation = match escalation {
+            Some((escalation, timeout)) => {
+                let tx_fees = match fees {
+                    Some((_, fees)) => TxFees::Eip1559(fees),
+                    None => {
+                        let tx_fees = self.current_fees().await?;
+
+                        call = match tx_fees {
+                            TxFees::Legacy { gas_price } => call.gas_price(gas_price),
+                            TxFees::Eip1559(fees) => call
+                                .max_fee_per_gas(fees.max_fee_per_gas)
+                                .max_priority_fee_per_gas(fees.max_priority_fee_per_gas),
+                        };
+
+                        tx_fees
+                    }
+                };
+
+                Some((escalation, timeout, tx_fees))
+            }
+            None => None,
+        };
+
+        // the nonce is set explicitly so that the transaction can be replaced on escalation or
+        // rebroadcast
+        if escalation.is_some() || fees.is_some() {
+            let nonce = self
+                .provider
+                .get_transaction_count(wallet.address())
+                .pending()
+                .await
+                .map_err(Error::TransportError)?;
+
+            call = call.nonce(nonce);
+        }
+
+        let call = call.gas(gas_to_use);
+
+        let escalation_request = escalation.map(|(escalation, timeout, _)| {
+            info!(
+                timeout,
+                timeout_window_seconds = escalation.config.timeout_window_seconds,
+                "batch contains near-timeout packets, escalation enabled"
+            );
+
+            call.clone().into_transaction_request().inner
+        });
+
+        let rebroadcast_request =
+            fees.map(|fees| (fees, call.clone().into_transaction_request().inner));
+
+        match call.send().await {
             Ok(ok) => {
                 let tx_hash = <H256>::from(*ok.tx_hash());
                 async move {
-                    let receipt = ok.get_receipt().await?;
+                    let receipt = match (escalation.zip(escalation_request), rebroadcast_request) {
+                        (Some(((escalation, timeout, tx_fees), request)), _) => {
+                            self.get_receipt_or_escalate(
+                                wallet, ok, request, escalation, timeout, tx_fees,
+                            )
+                            .await?
+                        }
+                        (None, Some(((fee_config, fees), request))) => {
+                            self.get_receipt_or_rebroadcast(wallet, ok, request, fee_config, fees)
+                                .await?
+                        }
+                        (None, None) => ok.get_receipt().await?,
+                    };
 
-                    info!(%tx_hash, "tx included");
+                    info!(tx_hash = %receipt.transaction_hash, "tx included");
 
                     let result = MulticallResult::decode_log_data(
                         receipt
@@ -583,7 +839,12 @@ impl Module {
                         }
                     }
 
-                    Ok(())
+                    Ok(Some(SubmittedTx {
+                        tx_hash: receipt.transaction_hash.into(),
+                        gas_used: receipt.gas_used,
+                        fee: u128::from(receipt.gas_used) * receipt.effective_gas_price,
+                        datagrams,
+                    }))
                 }
                 .instrument(info_span!(
                     "evm tx",
@@ -614,15 +875,339 @@ impl Module {
             {
                 if msgs.len() == 1 {
                     error!(error = %e.message, msg = ?msgs[0], "message is too large");
-                    Ok(()) // drop the message
+                    Ok(None) // drop the message
                 } else {
                     warn!(error = %e.message, "batch is too large");
                     Err(TxSubmitError::BatchTooLarge)
                 }
             }
+            Err(
+                Error::PendingTransactionError(PendingTransactionError::TransportError(
+                    TransportError::ErrorResp(e),
+                ))
+                | Error::TransportError(TransportError::ErrorResp(e)),
+            ) if e.message.contains("nonce too low")
+                || e.message.contains("nonce too high")
+                || e.message.contains("replacement transaction underpriced") =>
+            {
+                // another tx of this signer is pending or the rpc has not caught up with it yet,
+                // prefer other signers until it has settled
+                self.keyring.report_contention(&wallet.address());
+                Err(TxSubmitError::NonceContention(e.message.to_string()))
+            }
             Err(err) => Err(TxSubmitError::Error(err)),
         }
     }
+
+    /// Waits for the inclusion of `pending` until the inclusion deadline, after which a
+    /// replacement transaction is submitted through the private relay. The receipt of whichever
+    /// transaction is included first is returned. If escalation is not possible, this falls back
+    /// to waiting for the public transaction. Neither transaction is waited for after `timeout`
+    /// (the earliest timeout of the packets, in seconds), since the packets can't be received
+    /// anymore.
+    async fn get_receipt_or_escalate(
+        &self,
+        wallet: &LocalSigner<SigningKey>,
+        pending: PendingTransactionBuilder<AnyNetwork>,
+        request: TransactionRequest,
+        escalation: &Escalation,
+        timeout: u64,
+        tx_fees: TxFees,
+    ) -> Result<AnyTransactionReceipt, TxSubmitError> {
+        let until_timeout = || Duration::from_secs(timeout.saturating_sub(now()));
+
+        let mut tx_hashes = vec![*pending.tx_hash()];
+
+        if let Some(receipt) = self
+            .wait_for_receipt(
+                &tx_hashes,
+                escalation.config.inclusion_deadline().min(until_timeout()),
+            )
+            .await?
+        {
+            return Ok(receipt);
+        }
+
+        warn!(
+            tx_hash = %pending.tx_hash(),
+            inclusion_deadline_seconds = escalation.config.inclusion_deadline_seconds,
+            "tx not included before the inclusion deadline, escalating"
+        );
+
+        match self.escalate(wallet, request, escalation, tx_fees).await {
+            Ok(Some(escalated_tx_hash)) => {
+                info!(tx_hash = %pending.tx_hash(), %escalated_tx_hash, "escalated tx");
+
+                tx_hashes.push(escalated_tx_hash);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                error!(
+                    tx_hash = %pending.tx_hash(),
+                    err = %ErrorReporter(err),
+                    "error escalating tx"
+                );
+            }
+        }
+
+        // all transactions have the same nonce, so only one of them can be included
+        self.wait_for_receipt(&tx_hashes, until_timeout())
+            .await?
+            .ok_or(TxSubmitError::NotIncluded)
+    }
+
+    /// Signs `request` with the escalated fees of a transaction with `tx_fees`, and submits it to
+    /// the relay. Returns `None` if the escalation is not allowed by the cost caps.
+    async fn escalate(
+        &self,
+        wallet: &LocalSigner<SigningKey>,
+        mut request: TransactionRequest,
+        escalation: &Escalation,
+        tx_fees: TxFees,
+    ) -> Result<Option<B256>, EscalationError> {
+        let current_fees = self.current_fees().await?;
+
+        let Some(escalated_fees) = escalation.config.escalated_fees(tx_fees, current_fees) else {
+            warn!(
+                ?tx_fees,
+                ?current_fees,
+                max_fee_per_gas_cap = escalation.config.max_fee_per_gas,
+                max_priority_fee_per_gas_cap = escalation.config.max_priority_fee_per_gas,
+                "the escalation cost caps do not allow for a replacement, not escalating"
+            );
+
+            return Ok(None);
+        };
+
+        info!(?escalated_fees, "escalated fees");
+
+        request.from = Some(wallet.address());
+        request.chain_id = Some(self.provider.get_chain_id().await?);
+
+        match escalated_fees {
+            TxFees::Legacy { gas_price } => {
+                request.gas_price = Some(gas_price);
+                request.max_fee_per_gas = None;
+                request.max_priority_fee_per_gas = None;
+            }
+            TxFees::Eip1559(fees) => {
+                request.gas_price = None;
+                request.max_fee_per_gas = Some(fees.max_fee_per_gas);
+                request.max_priority_fee_per_gas = Some(fees.max_priority_fee_per_gas);
+            }
+        }
+
+        let tx = <TransactionRequest as TransactionBuilder<Ethereum>>::build(
+            request,
+            &EthereumWallet::new(wallet.clone()),
+        )
+        .await?;
+
+        let raw_tx = Bytes::from(tx.encoded_2718());
+
+        match escalation.config.method {
+            EscalationMethod::PrivateTransaction => {
+                escalation
+                    .relay
+                    .raw_request::<_, B256>("eth_sendRawTransaction".into(), (raw_tx,))
+                    .await
+                    .map_err(EscalationError::Relay)?;
+            }
+            EscalationMethod::Bundle { target_blocks } => {
+                let block_number = self.provider.get_block_number().await?;
+
+                for target_block in (block_number + 1)..=(block_number + target_blocks) {
+                    escalation
+                        .send_bundle(wallet, &raw_tx, target_block)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(Some(*tx.tx_hash()))
+    }
+
+    /// The current fees of the chain, by gas price if it is `legacy` or has a `fixed_gas_price`.
+    async fn current_fees(&self) -> Result<TxFees, EscalationError> {
+        if let Some(gas_price) = self.fixed_gas_price {
+            return Ok(TxFees::Legacy { gas_price });
+        }
+
+        if self.legacy {
+            return Ok(TxFees::Legacy {
+                gas_price: self.provider.get_gas_price().await?,
+            });
+        }
+
+        let fees = match &self.fees {
+            Some(fee_config) => self.estimate_fees(fee_config).await?,
+            None => {
+                let fees = self.provider.estimate_eip1559_fees().await?;
+
+                Fees {
+                    max_fee_per_gas: fees.max_fee_per_gas,
+                    max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+                }
+            }
+        };
+
+        Ok(TxFees::Eip1559(fees))
+    }
+
+    /// Estimates the fees for the next block from the fee history of recent blocks.
+    async fn estimate_fees(&self, fee_config: &FeeConfig) -> Result<Fees, FeeError> {
+        let fee_history = self
+            .provider
+            .get_fee_history(
+                fee_config.fee_history_blocks,
+                BlockNumberOrTag::Latest,
+                &[fee_config.priority_fee_percentile],
+            )
+            .await?;
+
+        // the fee history includes the base fee of the block after the newest block
+        let next_base_fee_per_gas = *fee_history
+            .base_fee_per_gas
+            .last()
+            .ok_or(FeeError::EmptyFeeHistory)?;
+
+        let rewards = fee_history
+            .reward
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|rewards| rewards.first().copied())
+            .collect::<Vec<_>>();
+
+        let fees = fee_config.estimate(next_base_fee_per_gas, &rewards);
+
+        info!(
+            %next_base_fee_per_gas,
+            max_fee_per_gas = %fees.max_fee_per_gas,
+            max_priority_fee_per_gas = %fees.max_priority_fee_per_gas,
+            "estimated fees"
+        );
+
+        Ok(fees)
+    }
+
+    /// Waits for the inclusion of `pending`, and rebroadcasts it with bumped fees whenever it is
+    /// not included within the stall timeout, up to the configured number of rebroadcasts. The
+    /// receipt of whichever transaction is included first is returned. Bumped fees never exceed
+    /// the max gas price of the module, and the last transaction is waited for one more stall
+    /// timeout.
+    async fn get_receipt_or_rebroadcast(
+        &self,
+        wallet: &LocalSigner<SigningKey>,
+        pending: PendingTransactionBuilder<AnyNetwork>,
+        request: TransactionRequest,
+        fee_config: &FeeConfig,
+        mut fees: Fees,
+    ) -> Result<AnyTransactionReceipt, TxSubmitError> {
+        let mut tx_hashes = vec![*pending.tx_hash()];
+
+        for rebroadcast in 1..=fee_config.max_rebroadcasts {
+            if let Some(receipt) = self
+                .wait_for_receipt(&tx_hashes, fee_config.stall_timeout())
+                .await?
+            {
+                return Ok(receipt);
+            }
+
+            let Some(bumped) = fee_config.bumped(fees).filter(|bumped| {
+                self.max_gas_price
+                    .is_none_or(|max_gas_price| bumped.max_fee_per_gas <= max_gas_price)
+            }) else {
+                warn!(
+                    max_fee_per_gas = %fees.max_fee_per_gas,
+                    max_gas_price = ?self.max_gas_price,
+                    "tx is stalled, but the fee cap does not allow for a bump"
+                );
+                break;
+            };
+
+            match self.rebroadcast(wallet, request.clone(), bumped).await {
+                Ok(tx_hash) => {
+                    info!(
+                        %tx_hash,
+                        rebroadcast,
+                        max_fee_per_gas = %bumped.max_fee_per_gas,
+                        max_priority_fee_per_gas = %bumped.max_priority_fee_per_gas,
+                        "rebroadcast stalled tx with bumped fees"
+                    );
+
+                    tx_hashes.push(tx_hash);
+                    fees = bumped;
+                }
+                // i.e. the previous transaction has been included in the meantime
+                Err(err) => {
+                    warn!(err = %ErrorReporter(err), "error rebroadcasting stalled tx");
+                }
+            }
+        }
+
+        // all transactions have the same nonce, so only one of them can be included
+        self.wait_for_receipt(&tx_hashes, fee_config.stall_timeout())
+            .await?
+            .ok_or(TxSubmitError::Stalled {
+                rebroadcasts: tx_hashes.len() - 1,
+            })
+    }
+
+    /// Signs `request` with `fees` and submits it to the public mempool, replacing the pending
+    /// transaction with the same nonce.
+    async fn rebroadcast(
+        &self,
+        wallet: &LocalSigner<SigningKey>,
+        mut request: TransactionRequest,
+        fees: Fees,
+    ) -> Result<B256, FeeError> {
+        request.from = Some(wallet.address());
+        request.chain_id = Some(self.provider.get_chain_id().await?);
+        request.gas_price = None;
+        request.max_fee_per_gas = Some(fees.max_fee_per_gas);
+        request.max_priority_fee_per_gas = Some(fees.max_priority_fee_per_gas);
+
+        let tx = <TransactionRequest as TransactionBuilder<Ethereum>>::build(
+            request,
+            &EthereumWallet::new(wallet.clone()),
+        )
+        .await?;
+
+        self.provider
+            .send_raw_transaction(&tx.encoded_2718())
+            .await?;
+
+        Ok(*tx.tx_hash())
+    }
+
+    /// Polls the receipts of `tx_hashes` until one of them is included, or until `deadline` has
+    /// passed.
+    async fn wait_for_receipt(
+        &self,
+        tx_hashes: &[B256],
+        deadline: Duration,
+    ) -> Result<Option<AnyTransactionReceipt>, TxSubmitError> {
+        let started = tokio::time::Instant::now();
+
+        loop {
+            for tx_hash in tx_hashes {
+                if let Some(receipt) = self
+                    .provider
+                    .get_transaction_receipt(*tx_hash)
+                    .await
+                    .map_err(Error::TransportError)?
+                {
+                    return Ok(Some(receipt));
+                }
+            }
+
+            if started.elapsed() >= deadline {
+                return Ok(None);
+            }
+
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
 }
 
 #[allow(clippy::type_complexity)]
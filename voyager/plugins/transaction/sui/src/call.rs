@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use enumorph::Enumorph;
 use macros::model;
 
@@ -5,4 +7,22 @@ use macros::model;
 #[derive(Enumorph)]
 pub enum ModuleCall {
     SubmitTransaction(Vec<ibc_union_spec::datagram::Datagram>),
+    /// Same as [`ModuleCall::SubmitTransaction`], but only submitted by one of `signers`.
+    SubmitTransactionWithSigners {
+        signers: BTreeSet<String>,
+        msgs: Vec<ibc_union_spec::datagram::Datagram>,
+    },
+}
+
+impl ModuleCall {
+    #[must_use]
+    pub fn submit_transaction(
+        msgs: Vec<ibc_union_spec::datagram::Datagram>,
+        signers: Option<BTreeSet<String>>,
+    ) -> Self {
+        match signers {
+            Some(signers) => Self::SubmitTransactionWithSigners { signers, msgs },
+            None => Self::SubmitTransaction(msgs),
+        }
+    }
 }
@@ -3,7 +3,6 @@ use std::{
     time::Duration,
 };
 
-use alloy::sol_types::SolValue;
 use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
 use fastcrypto::{hash::HashFunction, traits::Signer};
 use hex_literal::hex;
@@ -19,6 +18,7 @@ use move_core_types_sui::{
     identifier::Identifier as MoveIdentifier,
     language_storage::{StructTag, TypeTag},
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use shared_crypto::intent::{Intent, IntentMessage};
@@ -41,9 +41,10 @@ use sui_sdk::{
     SuiClient, SuiClientBuilder,
 };
 use tracing::{info, instrument};
-use ucs03_zkgm::com::{FungibleAssetOrder, ZkgmPacket};
 use unionlabs::{
+    encoding::{DecodeAs, EthAbi},
     primitives::{encoding::HexPrefixed, Bytes, U256},
+    zkgm::{FungibleAssetOrder, Instruction, ZkgmPacket},
     ErrorReporter,
 };
 use voyager_sdk::{
@@ -153,12 +154,14 @@ impl Plugin for Module {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     pub chain_id: ChainId,
     pub rpc_url: String,
+    #[schemars(with = "String")]
     pub ibc_handler_address: SuiAddress,
+    #[schemars(with = "String")]
     pub ibc_store: SuiAddress,
 
     pub keyring: KeyringConfig,
@@ -192,7 +195,7 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                     SubmitTxHook::new(&self.chain_id, |submit_tx| {
                         PluginMessage::new(
                             self.plugin_name(),
-                            ModuleCall::SubmitTransaction(
+                            ModuleCall::submit_transaction(
                                 submit_tx
                                     .datagrams
                                     .iter()
@@ -200,6 +203,7 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                                         message.decode_datagram::<IbcUnion>().unwrap().unwrap()
                                     })
                                     .collect(),
+                                submit_tx.signers.clone(),
                             ),
                         )
                         .into()
@@ -214,46 +218,48 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
     async fn call(&self, _: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
-        match msg {
-            ModuleCall::SubmitTransaction(msgs) => self
-                .keyring
-                .with(|pk| {
-                    let sender = SuiAddress::from(&pk.public());
-                    let msgs = msgs.clone();
-                    AssertUnwindSafe(async move {
-                        let msgs = process_msgs(self, pk, msgs, sender).await;
-
-                        let mut ptb = ProgrammableTransactionBuilder::new();
-
-                        for (contract_addr, _, module, entry_fn, arguments, type_args) in
-                            msgs.into_iter()
-                        {
-                            let arguments = arguments
-                                .into_iter()
-                                .map(|arg| ptb.input(arg).expect("input works"))
-                                .collect();
-                            ptb.command(Command::move_call(
-                                contract_addr.into(),
-                                module,
-                                entry_fn,
-                                type_args,
-                                arguments,
-                            ));
-                        }
-
-                        let builder = ptb.finish();
-                        let _ = send_transactions(self, pk, builder).await?;
-                        Ok(noop())
-                    })
+        let (msgs, signers) = match msg {
+            ModuleCall::SubmitTransaction(msgs) => (msgs, None),
+            ModuleCall::SubmitTransactionWithSigners { signers, msgs } => (msgs, Some(signers)),
+        };
+
+        self.keyring
+            .with_any_of(signers.as_ref(), |pk| {
+                let sender = SuiAddress::from(&pk.public());
+                let msgs = msgs.clone();
+                AssertUnwindSafe(async move {
+                    let msgs = process_msgs(self, pk, msgs, sender).await;
+
+                    let mut ptb = ProgrammableTransactionBuilder::new();
+
+                    for (contract_addr, _, module, entry_fn, arguments, type_args) in
+                        msgs.into_iter()
+                    {
+                        let arguments = arguments
+                            .into_iter()
+                            .map(|arg| ptb.input(arg).expect("input works"))
+                            .collect();
+                        ptb.command(Command::move_call(
+                            contract_addr.into(),
+                            module,
+                            entry_fn,
+                            type_args,
+                            arguments,
+                        ));
+                    }
+
+                    let builder = ptb.finish();
+                    let _ = send_transactions(self, pk, builder).await?;
+                    Ok(noop())
                 })
-                .await
-                .unwrap_or_else(|| {
-                    Ok(call(PluginMessage::new(
-                        self.plugin_name(),
-                        ModuleCall::SubmitTransaction(msgs),
-                    )))
-                }),
-        }
+            })
+            .await
+            .unwrap_or_else(|| {
+                Ok(call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::submit_transaction(msgs, signers),
+                )))
+            })
     }
 
     #[instrument(skip_all, fields(chain_id = %self.chain_id))]
@@ -655,16 +661,17 @@ async fn register_token_if_zkgm(
     module_info: &ModuleInfo,
     store_initial_seq: SequenceNumber,
 ) {
-    let Ok(zkgm_packet) = ZkgmPacket::abi_decode_params(&packet.data) else {
+    let Ok(zkgm_packet) = ZkgmPacket::decode_as::<EthAbi>(&packet.data) else {
         return;
     };
 
-    let Ok(fao) = FungibleAssetOrder::abi_decode_params(&zkgm_packet.instruction.operand) else {
+    let Instruction::FungibleAssetOrder(FungibleAssetOrder::V1(fao)) = zkgm_packet.instruction
+    else {
         return;
     };
 
     let wrapped_token = predict_wrapped_denom(
-        fao.base_token_path.into(),
+        fao.base_token_path,
         packet.destination_channel_id,
         fao.base_token.to_vec(),
     );
@@ -0,0 +1,251 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ibc_union_spec::{event::FullEvent, IbcUnion};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    types::ErrorObject,
+    Extensions,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, instrument};
+use unionlabs::{never::Never, ErrorReporter};
+use voyager_plugin_transaction_batch::data::{BatchableEvent, EventBatch};
+use voyager_sdk::{
+    anyhow,
+    message::{data::Data, PluginMessage, VoyagerMessage},
+    plugin::Plugin,
+    primitives::{ChainId, IbcSpec},
+    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
+    vm::{data, defer, noop, now, pass::PassResult, seq, Op},
+    DefaultCmd,
+};
+
+use crate::rules::{TransferRules, Verdict};
+
+pub mod rules;
+
+#[tokio::main]
+async fn main() {
+    Module::run().await
+}
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    /// chain id -> rules for the packets sent from that chain
+    pub rules: BTreeMap<ChainId, TransferRules>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// chain id -> rules for the packets sent from that chain
+    ///
+    /// packets sent from any unconfigured chain are not seen by this plugin.
+    pub rules: BTreeMap<ChainId, TransferRules>,
+}
+
+impl Plugin for Module {
+    type Call = Never;
+    type Callback = Never;
+
+    type Config = Config;
+    type Cmd = DefaultCmd;
+
+    async fn new(config: Self::Config) -> anyhow::Result<Self> {
+        Ok(Self {
+            rules: config.rules,
+        })
+    }
+
+    fn info(config: Self::Config) -> PluginInfo {
+        let chain_filter = ["false".to_owned()]
+            .into_iter()
+            .chain(
+                config
+                    .rules
+                    .keys()
+                    .map(|chain_id| format!(r#"$event.chain_id == "{chain_id}""#)),
+            )
+            .collect::<Vec<_>>()
+            .join(" or ");
+
+        PluginInfo {
+            name: PLUGIN_NAME.to_owned(),
+            interest_filter: format!(
+                r#"
+if ."@type" == "data" and ."@value"."@type" == "ibc_event" then
+    ."@value"."@value" as $event |
+
+    if $event.ibc_spec_id == "{ibc_union_id}"
+        and $event.event."@type" == "packet_send"
+        and $event.event."@value".packet.source_channel.version == "{zkgm_version}"
+        and ({chain_filter})
+    then
+        true
+    else
+        null
+    end
+else
+    null
+end
+"#,
+                ibc_union_id = IbcUnion::ID,
+                zkgm_version = ucs03_zkgm::contract::PROTOCOL_VERSION,
+            ),
+        }
+    }
+
+    async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
+        match cmd {}
+    }
+}
+
+pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+#[async_trait]
+impl PluginServer<Never, Never> for Module {
+    #[instrument(skip_all)]
+    async fn run_pass(
+        &self,
+        _: &Extensions,
+        msgs: Vec<Op<VoyagerMessage>>,
+    ) -> RpcResult<PassResult<VoyagerMessage>> {
+        let ready = msgs
+            .into_iter()
+            .enumerate()
+            .map(|(idx, msg)| {
+                let Op::Data(Data::IbcEvent(ref chain_event)) = msg else {
+                    return Err(ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        "unexpected message in queue",
+                        Some(json!({
+                            "msg": msg,
+                        })),
+                    ));
+                };
+
+                let full_event = chain_event
+                    .decode_event::<IbcUnion>()
+                    .ok_or_else(|| {
+                        ErrorObject::owned(
+                            FATAL_JSONRPC_ERROR_CODE,
+                            "unexpected data message in queue",
+                            Some(json!({
+                                "msg": msg.clone(),
+                            })),
+                        )
+                    })?
+                    .map_err(|err| {
+                        ErrorObject::owned(
+                            FATAL_JSONRPC_ERROR_CODE,
+                            "unable to parse ibc datagram",
+                            Some(json!({
+                                "err": ErrorReporter(err).to_string(),
+                                "msg": msg,
+                            })),
+                        )
+                    })?;
+
+                let client_id = full_event.counterparty_client_id();
+
+                let (FullEvent::PacketSend(packet_send), Some(client_id)) = (full_event, client_id)
+                else {
+                    return Err(ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        "unexpected ibc datagram",
+                        Some(json!({
+                            "msg": msg,
+                        })),
+                    ));
+                };
+
+                let verdict =
+                    self.rules
+                        .get(&chain_event.chain_id)
+                        .map_or(Verdict::Relay, |rules| {
+                            rules.check(
+                                packet_send.packet.source_channel.channel_id,
+                                &packet_send.packet_data,
+                            )
+                        });
+
+                let packet_hash = packet_send.packet().hash();
+
+                let first_seen_at: u64 = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis()
+                    .try_into()
+                    .expect("how many milliseconds can there be man");
+
+                // relayed packets are handed to the transaction-batch plugin directly, since
+                // passing the event on as is would make it match this plugin's interest filter
+                // again
+                let relay = data(PluginMessage::new(
+                    voyager_plugin_transaction_batch::plugin_name(
+                        &chain_event.counterparty_chain_id,
+                    ),
+                    voyager_plugin_transaction_batch::data::ModuleData::BatchEventsUnion(
+                        EventBatch {
+                            client_id,
+                            events: vec![BatchableEvent {
+                                first_seen_at,
+                                provable_height: chain_event.provable_height,
+                                event: packet_send.into(),
+                            }],
+                        },
+                    ),
+                ));
+
+                Ok(match verdict {
+                    Verdict::Relay => (vec![idx], relay),
+                    Verdict::Drop(reason) => {
+                        info!(
+                            %packet_hash,
+                            chain_id = %chain_event.chain_id,
+                            reason,
+                            "dropping packet"
+                        );
+
+                        (vec![idx], noop())
+                    }
+                    Verdict::Defer(delay) => {
+                        info!(
+                            %packet_hash,
+                            chain_id = %chain_event.chain_id,
+                            ?delay,
+                            "deferring packet"
+                        );
+
+                        (vec![idx], seq([defer(now() + delay.as_secs()), relay]))
+                    }
+                })
+            })
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        Ok(PassResult {
+            optimize_further: vec![],
+            ready,
+        })
+    }
+
+    #[instrument]
+    async fn call(&self, _: &Extensions, msg: Never) -> RpcResult<Op<VoyagerMessage>> {
+        match msg {}
+    }
+
+    #[instrument]
+    async fn callback(
+        &self,
+        _: &Extensions,
+        cb: Never,
+        _data: VecDeque<Data>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        match cb {}
+    }
+}
@@ -0,0 +1,271 @@
+use std::{collections::BTreeSet, time::Duration};
+
+use ibc_union_spec::ChannelId;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use unionlabs::{
+    encoding::{DecodeAs, EthAbi},
+    primitives::{Bytes, U256},
+    zkgm::ZkgmPacket,
+};
+
+/// The rules for the zkgm packets sent from a chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TransferRules {
+    /// The channels (on the sending chain) that packets are relayed for. Packets sent over any other
+    /// channel are dropped. If empty, packets on all channels are relayed.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub channel_allowlist: BTreeSet<ChannelId>,
+    /// Tokens (by the base token of the order) that are never relayed.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub deny_tokens: BTreeSet<Bytes>,
+    /// Minimum amounts per token.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub min_amounts: Vec<MinAmount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MinAmount {
+    /// The base token of the order.
+    pub token: Bytes,
+    /// Orders below this amount are not relayed immediately.
+    pub amount: U256,
+    /// What to do with packets containing orders below [`Self::amount`].
+    #[serde(default)]
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Action {
+    /// Never relay the packet.
+    #[default]
+    Drop,
+    /// Hold the packet back for `delay` before relaying it.
+    Defer { delay: Duration },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Relay,
+    /// The packet is dropped, for the contained reason.
+    Drop(&'static str),
+    Defer(Duration),
+}
+
+impl TransferRules {
+    /// Check a zkgm packet sent over `channel_id` against the rules.
+    ///
+    /// Packets are dropped if any of their orders is, and otherwise deferred for the longest delay of
+    /// their orders. Packets that cannot be decoded are relayed, as are packets without any orders.
+    #[must_use]
+    pub fn check(&self, channel_id: ChannelId, packet_data: &[u8]) -> Verdict {
+        if !self.channel_allowlist.is_empty() && !self.channel_allowlist.contains(&channel_id) {
+            return Verdict::Drop("channel not allowed");
+        }
+
+        let Ok(packet) = ZkgmPacket::decode_as::<EthAbi>(packet_data) else {
+            return Verdict::Relay;
+        };
+
+        let mut verdict = Verdict::Relay;
+
+        for order in packet.instruction.fungible_asset_orders() {
+            match self.check_order(order.base_token(), order.base_amount()) {
+                Verdict::Relay => {}
+                drop @ Verdict::Drop(_) => return drop,
+                Verdict::Defer(delay) => {
+                    verdict = match verdict {
+                        Verdict::Defer(d) => Verdict::Defer(d.max(delay)),
+                        _ => Verdict::Defer(delay),
+                    }
+                }
+            }
+        }
+
+        verdict
+    }
+
+    fn check_order(&self, token: &[u8], amount: U256) -> Verdict {
+        if self.deny_tokens.iter().any(|t| &**t == token) {
+            return Verdict::Drop("token denied");
+        }
+
+        match self
+            .min_amounts
+            .iter()
+            .find(|m| &*m.token == token && amount < m.amount)
+            .map(|m| m.action)
+        {
+            Some(Action::Drop) => Verdict::Drop("amount below minimum"),
+            Some(Action::Defer { delay }) => Verdict::Defer(delay),
+            None => Verdict::Relay,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use unionlabs::{
+        encoding::EncodeAs,
+        primitives::H256,
+        zkgm::{Batch, FungibleAssetOrder, FungibleAssetOrderV1, Instruction},
+    };
+
+    use super::*;
+
+    const MUNO: &[u8] = b"muno";
+    const USDC: [u8; 20] = hex!("1c7d4b196cb0c7b01d743fbc6116a902379c7238");
+    const SCAM: &[u8] = b"scam";
+
+    fn channel(id: u32) -> ChannelId {
+        ChannelId::from_raw(id).unwrap()
+    }
+
+    fn rules() -> TransferRules {
+        TransferRules {
+            channel_allowlist: [channel(1)].into(),
+            deny_tokens: [Bytes::from(SCAM)].into(),
+            min_amounts: vec![
+                MinAmount {
+                    token: MUNO.into(),
+                    amount: 1_000_u64.into(),
+                    action: Action::Drop,
+                },
+                MinAmount {
+                    token: USDC.to_vec().into(),
+                    amount: 1_000_u64.into(),
+                    action: Action::Defer {
+                        delay: Duration::from_secs(60),
+                    },
+                },
+            ],
+        }
+    }
+
+    fn order(token: &[u8], amount: u64) -> Instruction {
+        Instruction::FungibleAssetOrder(FungibleAssetOrder::V1(FungibleAssetOrderV1 {
+            sender: Default::default(),
+            receiver: Default::default(),
+            base_token: token.into(),
+            base_amount: amount.into(),
+            base_token_symbol: String::new(),
+            base_token_name: String::new(),
+            base_token_decimals: 6,
+            base_token_path: U256::ZERO,
+            quote_token: Default::default(),
+            quote_amount: amount.into(),
+        }))
+    }
+
+    fn batch(instructions: Vec<Instruction>) -> Instruction {
+        Instruction::Batch(Batch { instructions })
+    }
+
+    fn packet(instruction: Instruction) -> Vec<u8> {
+        ZkgmPacket {
+            salt: H256::default(),
+            path: U256::ZERO,
+            instruction,
+        }
+        .encode_as::<EthAbi>()
+    }
+
+    #[test]
+    fn channel_allowlist() {
+        let packet = packet(order(MUNO, 1_000));
+
+        assert_eq!(rules().check(channel(1), &packet), Verdict::Relay);
+        assert_eq!(
+            rules().check(channel(2), &packet),
+            Verdict::Drop("channel not allowed")
+        );
+        assert_eq!(
+            TransferRules::default().check(channel(2), &packet),
+            Verdict::Relay
+        );
+    }
+
+    #[test]
+    fn deny_tokens() {
+        assert_eq!(
+            rules().check(channel(1), &packet(order(SCAM, 1_000_000))),
+            Verdict::Drop("token denied")
+        );
+    }
+
+    #[test]
+    fn min_amounts() {
+        let rules = rules();
+
+        assert_eq!(
+            rules.check(channel(1), &packet(order(MUNO, 999))),
+            Verdict::Drop("amount below minimum")
+        );
+        assert_eq!(
+            rules.check(channel(1), &packet(order(&USDC, 999))),
+            Verdict::Defer(Duration::from_secs(60))
+        );
+        assert_eq!(
+            rules.check(channel(1), &packet(order(&USDC, 1_000))),
+            Verdict::Relay
+        );
+        // no minimum configured for this token
+        assert_eq!(
+            rules.check(channel(1), &packet(order(b"other", 1))),
+            Verdict::Relay
+        );
+    }
+
+    #[test]
+    fn batch_orders() {
+        let rules = rules();
+
+        assert_eq!(
+            rules.check(
+                channel(1),
+                &packet(batch(vec![order(&USDC, 1), order(MUNO, 1)]))
+            ),
+            Verdict::Drop("amount below minimum")
+        );
+        assert_eq!(
+            rules.check(
+                channel(1),
+                &packet(batch(vec![order(&USDC, 1), order(MUNO, 1_000)]))
+            ),
+            Verdict::Defer(Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn not_zkgm() {
+        assert_eq!(
+            rules().check(channel(1), b"not a zkgm packet"),
+            Verdict::Relay
+        );
+    }
+
+    #[test]
+    fn config_serde() {
+        let json = r#"{
+            "channel_allowlist": [1],
+            "deny_tokens": ["0x7363616d"],
+            "min_amounts": [
+                { "token": "0x6d756e6f", "amount": "1000" },
+                {
+                    "token": "0x1c7d4b196cb0c7b01d743fbc6116a902379c7238",
+                    "amount": "1000",
+                    "action": { "type": "defer", "delay": { "secs": 60, "nanos": 0 } }
+                }
+            ]
+        }"#;
+
+        assert_eq!(
+            serde_json::from_str::<TransferRules>(json).unwrap(),
+            rules()
+        );
+    }
+}
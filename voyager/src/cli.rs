@@ -9,12 +9,16 @@ use anyhow::{anyhow, Context};
 use clap::{self, Parser, Subcommand};
 use ibc_union_spec::IbcUnion;
 use unionlabs::{self, bounded::BoundedI64, ibc::core::client::height::Height, result_unwrap};
+use voyager_core::template::OpTemplate;
 use voyager_message::VoyagerMessage;
 use voyager_primitives::{ChainId, ClientType, IbcInterface, IbcSpec, IbcSpecId, QueryHeight};
 use voyager_types::RawClientId;
 use voyager_vm::{BoxDynError, Op};
 
-use crate::config::Config;
+use crate::{
+    config::Config,
+    doctor::{ClientCheck, TransactionPluginCheck},
+};
 
 #[derive(Debug, Parser)]
 #[command(arg_required_else_help = true)]
@@ -36,6 +40,19 @@ pub struct App {
         help_heading = "Global options"
     )]
     pub log_format: LogFormat,
+    /// The format of the command output.
+    ///
+    /// With `json`, every command prints exactly one json document to stdout, including commands
+    /// that only report a status and errors (`{"error": ..., "causes": [...]}`). Logs are written
+    /// to stderr instead. `plugin call` prints the output of the plugin as is.
+    #[arg(
+        long,
+        short = 'o',
+        global = true,
+        default_value_t = OutputFormat::default(),
+        help_heading = "Global options"
+    )]
+    pub output: OutputFormat,
     #[arg(
         long,
         // env = "VOYAGER_STACK_SIZE",
@@ -88,6 +105,15 @@ pub enum LogFormat {
     Json,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum, derive_more::Display)]
+pub enum OutputFormat {
+    #[default]
+    #[display(fmt = "text")]
+    Text,
+    #[display(fmt = "json")]
+    Json,
+}
+
 #[derive(Debug, Subcommand)]
 #[allow(clippy::large_enum_variant)]
 pub enum Command {
@@ -97,6 +123,8 @@ pub enum Command {
     /// Construct an op to index events on a chain.
     ///
     /// This will build the necessary op to index blocks on a chain. Note that the voyager instance this is queued on must have a plugin loaded that will pick this up in it's interest filter in order for the op to do anything.
+    ///
+    /// With `--from` and `--to`, the events in the range are fetched again by the event source plugin of the chain, and any packets that were not relayed yet are picked up as usual. This can be used to recover after an outage of the event source plugin without reindexing the entire chain, e.g. `voyager index <chain-id> --from <height> --to latest --enqueue`.
     Index {
         /// The chain to create an index op for.
         #[arg(value_parser(|s: &str| Ok::<_, BoxDynError>(ChainId::new(s.to_owned()))))]
@@ -107,8 +135,10 @@ pub enum Command {
         #[arg(long, short = 'H', default_value_t = QueryHeight::Latest)]
         from: QueryHeight,
         /// The height to index blocks until. If provided, this will fetch blocks in the range from..=to.
+        ///
+        /// `latest` and `finalized` are resolved to the current latest (finalized) height of the chain.
         #[arg(long, requires = "from")]
-        to: Option<Height>,
+        to: Option<QueryHeight>,
         /// Index a specific block.
         #[arg(long, conflicts_with_all(["from", "to"]))]
         exact: Option<Height>,
@@ -136,6 +166,43 @@ pub enum Command {
     },
     #[command(subcommand)]
     Msg(MsgCmd),
+    /// Check a running voyager instance and all chains it has modules for, and print the problems
+    /// found along with suggested fixes, most severe first.
+    ///
+    /// Checks the health of all modules, the age of the latest finalized block and the backpressure
+    /// of every chain, and optionally the signer balances of transaction plugins and the expiry of
+    /// clients. With the text output, exits with an error if any critical problems are found.
+    Doctor {
+        /// A client to check the expiry of, as `<chain id>/<client id>`. Can be specified multiple
+        /// times. Defaults to the clients configured in the transaction-batch plugins.
+        #[arg(long = "client")]
+        clients: Vec<ClientCheck>,
+        /// The IBC spec of the clients to check.
+        #[arg(
+            long,
+            short = 's',
+            default_value_t = IbcUnion::ID,
+            value_parser(|s: &str| ok(IbcSpecId::new(s.to_owned())))
+        )]
+        ibc_spec_id: IbcSpecId,
+        /// A transaction plugin to check the signer balances of, as `<plugin name>[=<min balance>]`.
+        /// The minimum balance is in the smallest unit of the gas token of the chain. Can be
+        /// specified multiple times. Defaults to all transaction plugins in the config, without a
+        /// minimum balance.
+        #[arg(long = "transaction-plugin")]
+        transaction_plugins: Vec<TransactionPluginCheck>,
+        /// The maximum age in seconds of the latest finalized block of a chain.
+        #[arg(long, default_value_t = 300)]
+        max_block_age: u64,
+        /// The maximum number of pending operations of a chain.
+        #[arg(long, default_value_t = 1000)]
+        max_pending: u64,
+        /// The minimum time in seconds left before a client expires.
+        #[arg(long, default_value_t = 24 * 60 * 60)]
+        client_expiry_margin: u64,
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -146,6 +213,44 @@ pub enum ConfigCmd {
     Default,
     /// Print the JSON Schema for the voyager config, to be used in the top-level `$schema` field.
     Schema,
+    /// Validate the configs of all enabled plugins and modules against the config schemas they
+    /// export. This is also done on startup.
+    Validate,
+    /// Print the changes between the config of a running voyager instance and a new config,
+    /// grouped per chain.
+    Diff {
+        /// The path to the new config file.
+        new_config_file_path: OsString,
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
+    /// Apply a new config to a running voyager instance.
+    ///
+    /// The new config is written to the config file being used by voyager, after which voyager is
+    /// restarted with the restart command. If voyager does not become healthy within the health
+    /// timeout, the previous config is restored and voyager is restarted again.
+    ///
+    /// The restart command is run with `VOYAGER_CONFIG_FILE_PATH` set to the config file voyager
+    /// must be started with (e.g. `voyager -c "$VOYAGER_CONFIG_FILE_PATH" start`), which is a
+    /// separate stage file for the intermediate stages of `--staged`.
+    ///
+    /// The new config is validated before it is written, see `voyager config validate`.
+    Apply {
+        /// The path to the new config file.
+        new_config_file_path: OsString,
+        /// Apply the changes chain by chain, waiting for voyager to become healthy after each
+        /// chain before applying the next one.
+        #[arg(long, default_value_t = false)]
+        staged: bool,
+        /// The shell command to run to restart voyager after the config file has been written.
+        #[arg(long)]
+        restart_command: String,
+        /// The time in seconds to wait for voyager to become healthy after a restart.
+        #[arg(long, default_value_t = 60)]
+        health_timeout: u64,
+        #[arg(long)]
+        rpc_url: Option<String>,
+    },
 }
 
 type Pg64 = BoundedI64<1, { i64::MAX }>;
@@ -161,6 +266,42 @@ pub enum QueueCmd {
         #[arg(long, global = true)]
         rest_url: Option<String>,
     },
+    /// Enqueue an op to rescan a range of blocks on a chain, on an already running voyager instance.
+    ///
+    /// The events in the range are fetched again by the event source plugin of the chain, and
+    /// any packets that were not relayed yet are picked up as usual. This can be used to recover
+    /// after an outage of the event source plugin, without reindexing the entire chain.
+    EnqueueRange {
+        /// The chain to rescan.
+        #[arg(long, value_parser(|s: &str| ok(ChainId::new(s.to_owned()))))]
+        chain: ChainId,
+        /// The first height of the range (inclusive).
+        #[arg(long)]
+        from: Height,
+        /// The last height of the range (inclusive).
+        #[arg(long)]
+        to: Height,
+        #[arg(long)]
+        rest_url: Option<String>,
+    },
+    /// Enqueue a template for a common admin action on an already running voyager instance.
+    ///
+    /// The template is expanded by voyager into the ops that perform the action, which are
+    /// printed once enqueued. Examples:
+    ///
+    /// Update a client to at least height 1-100:
+    ///
+    /// {"@type":"update_client","@value":{"chain_id":"union-1","ibc_spec_id":"ibc-union","client_id":1,"height":"1-100"}}
+    ///
+    /// Relay the acknowledgement of a packet again, which was written on channel 1 at height 1-100:
+    ///
+    /// {"@type":"resend_ack","@value":{"chain_id":"union-1","channel_id":1,"packet_hash":"0x...","acknowledgement":"0x...","height":"1-100","tx_hash":"0x..."}}
+    EnqueueTemplate {
+        #[arg(value_parser(|s: &str| serde_json::from_str::<OpTemplate>(s)))]
+        template: OpTemplate,
+        #[arg(long)]
+        rest_url: Option<String>,
+    },
 
     // History {
     //     id: PgId,
@@ -201,6 +342,100 @@ pub enum QueueCmd {
         #[arg(long)]
         rest_url: Option<String>,
     },
+    /// List the items in the queue, ordered by id. Only a summary of each item is printed, use
+    /// `peek` to print an item in full.
+    Ls {
+        #[arg(long, value_enum, default_value_t = QueueTable::default())]
+        table: QueueTable,
+        #[command(flatten)]
+        filter: ItemFilter,
+        #[arg(long, default_value_t = result_unwrap!(Pg64::new_const(1)))]
+        page: Pg64,
+        #[arg(long, default_value_t = result_unwrap!(Pg64::new_const(50)))]
+        per_page: Pg64,
+    },
+    /// Print an item in the queue.
+    Peek {
+        id: Pg64,
+        #[arg(long, value_enum, default_value_t = QueueTable::default())]
+        table: QueueTable,
+    },
+    /// Remove items from the queue. Items that are currently being processed are not removed.
+    Drop {
+        #[arg(required = true)]
+        ids: Vec<Pg64>,
+        #[arg(long, value_enum, default_value_t = QueueTable::default())]
+        table: QueueTable,
+    },
+    /// Enqueue failed items again on an already running voyager instance, removing them from the
+    /// failed items.
+    Requeue {
+        #[arg(required = true)]
+        ids: Vec<Pg64>,
+        #[arg(long)]
+        rest_url: Option<String>,
+    },
+}
+
+/// The part of the queue to inspect.
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum, derive_more::Display)]
+pub enum QueueTable {
+    /// Items that are ready to be processed.
+    #[default]
+    #[display(fmt = "queue")]
+    Queue,
+    /// Items that are waiting to be optimized by a plugin.
+    #[display(fmt = "optimize")]
+    Optimize,
+    /// Items that failed to be processed.
+    #[display(fmt = "failed")]
+    Failed,
+}
+
+impl From<QueueTable> for pg_queue::Table {
+    fn from(value: QueueTable) -> Self {
+        match value {
+            QueueTable::Queue => pg_queue::Table::Queue,
+            QueueTable::Optimize => pg_queue::Table::Optimize,
+            QueueTable::Failed => pg_queue::Table::Failed,
+        }
+    }
+}
+
+/// Filters on the contents of queue items. Items must match all of the given filters.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct ItemFilter {
+    /// Only items for this plugin.
+    #[arg(long)]
+    pub plugin: Option<String>,
+    /// Only items containing a message of this type (i.e. `submit_tx` or `fetch_update_headers`).
+    #[arg(long, short = 't')]
+    pub message_type: Option<String>,
+    /// Only items for this chain.
+    #[arg(long)]
+    pub chain: Option<String>,
+}
+
+impl ItemFilter {
+    /// The strings that the JSON of a matching item contains, formatted as postgres formats JSONB.
+    #[must_use]
+    pub fn needles(&self) -> Vec<String> {
+        let needle = |key: &str, value: &String| {
+            format!(
+                "\"{key}\": {}",
+                serde_json::to_string(value).expect("serialization is infallible; qed;")
+            )
+        };
+
+        [
+            self.plugin.as_ref().map(|plugin| needle("plugin", plugin)),
+            self.message_type.as_ref().map(|ty| needle("@type", ty)),
+            self.chain.as_ref().map(|chain| needle("chain_id", chain)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 }
 
 #[derive(Debug, Subcommand)]
@@ -225,6 +460,8 @@ pub enum PluginCmd {
 #[derive(Debug, Subcommand)]
 pub enum RpcCmd {
     Info,
+    /// The health of all modules, as of the last poll.
+    ModuleHealth,
     ClientState {
         #[arg(value_parser(|s: &str| ok(ChainId::new(s.to_owned()))))]
         on: ChainId,
@@ -300,6 +537,15 @@ pub enum RpcCmd {
         #[arg(long, short = 'd', default_value_t = false)]
         decode: bool,
     },
+    /// Query the chain metadata used to sanity check configs before creating a client.
+    SelfClientMeta {
+        #[arg(value_parser(|s: &str| ok(ChainId::new(s.to_owned()))))]
+        on: ChainId,
+        #[arg(long, value_parser(|s: &str| ok(ClientType::new(s.to_owned()))))]
+        client_type: ClientType,
+        #[arg(long, default_value_t = QueryHeight::Latest)]
+        height: QueryHeight,
+    },
     LatestHeight {
         #[arg(value_parser(|s: &str| ok(ChainId::new(s.to_owned()))))]
         on: ChainId,
@@ -366,13 +612,16 @@ pub enum RpcCmd {
 #[derive(Debug, Subcommand)]
 #[allow(clippy::large_enum_variant)]
 pub enum MsgCmd {
+    /// Create a new client tracking a chain with the configured client bootstrap module.
+    ///
+    /// With `--dry-run`, the client and consensus states are only fetched and printed (along with the durations and proof specs decoded from them), without building a message. This can be used to validate the config of a client bootstrap module before creating any clients with it.
     CreateClient {
-        #[arg(long, value_parser(|s: &str| ok(ChainId::new(s.to_owned()))))]
-        on: ChainId,
+        #[arg(long, required_unless_present = "dry_run", value_parser(|s: &str| ok(ChainId::new(s.to_owned()))))]
+        on: Option<ChainId>,
         #[arg(long, value_parser(|s: &str| ok(ChainId::new(s.to_owned()))))]
         tracking: ChainId,
-        #[arg(long, value_parser(|s: &str| ok(IbcInterface::new(s.to_owned()))))]
-        ibc_interface: IbcInterface,
+        #[arg(long, required_unless_present = "dry_run", value_parser(|s: &str| ok(IbcInterface::new(s.to_owned()))))]
+        ibc_interface: Option<IbcInterface>,
         #[arg(
             long,
             short = 's',
@@ -427,8 +676,11 @@ pub enum MsgCmd {
         )]
         config: serde_json::Value,
 
+        /// Only fetch and print the client and consensus states, without building a message.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
         /// Automatically enqueue the op.
-        #[arg(long, short = 'e', default_value_t = false)]
+        #[arg(long, short = 'e', default_value_t = false, conflicts_with = "dry_run")]
         enqueue: bool,
         #[arg(long, global = true)]
         rest_url: Option<String>,
@@ -459,6 +711,52 @@ pub enum MsgCmd {
         #[arg(long, global = true)]
         rpc_url: Option<String>,
     },
+    /// Prepare the recovery of an expired or frozen client, which replaces its state with the state of a substitute client. Nothing is submitted: the messages are printed (or enqueued with `-e`).
+    ///
+    /// Without `--substitute-client-id`, this builds the message creating the substitute: a client of the same type, created with the client bootstrap module at the latest finalized height of the tracked chain. Once the substitute is created, run this again with its id to print the governance proposal recovering the client, which is submitted with `<chain> tx gov submit-proposal`.
+    ///
+    /// The substitute can be created for ibc-go and ibc-union clients, but only ibc-go clients can be recovered with a proposal, since the ibc-union contracts do not support replacing the state of a client. The connections of an expired ibc-union client have to be opened again on the substitute instead.
+    PrepareClientRecovery {
+        #[arg(long, value_parser(|s: &str| ok(ChainId::new(s.to_owned()))))]
+        on: ChainId,
+        #[arg(
+            long,
+            short = 's',
+            default_value_t = IbcUnion::ID,
+            value_parser(|s: &str| ok(IbcSpecId::new(s.to_owned())))
+        )]
+        ibc_spec_id: IbcSpecId,
+        /// The expired or frozen client.
+        #[arg(long)]
+        client_id: RawClientId,
+        #[arg(long, requires = "authority")]
+        substitute_client_id: Option<RawClientId>,
+        /// The address of the governance module account on the chain the client is on (`<chain> q auth module-account gov`), which signs the recovery.
+        #[arg(long)]
+        authority: Option<String>,
+        /// The initial deposit of the proposal, i.e. `10000000au`.
+        #[arg(long, default_value = "")]
+        deposit: String,
+        /// The title of the proposal. Defaults to `Recover client <client-id>`.
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Additional config to pass to both `self_client_state()` `self_consensus_state()` when building the substitute client.
+        #[arg(
+            long,
+            value_parser(serde_json::Value::from_str),
+            default_value_t = serde_json::Value::Null,
+        )]
+        config: serde_json::Value,
+
+        /// Automatically enqueue the op creating the substitute client.
+        #[arg(long, short = 'e', default_value_t = false)]
+        enqueue: bool,
+        #[arg(long, global = true)]
+        rest_url: Option<String>,
+        #[arg(long, global = true)]
+        rpc_url: Option<String>,
+    },
 }
 
 #[allow(
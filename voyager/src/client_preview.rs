@@ -0,0 +1,221 @@
+use std::fmt::Write;
+
+use ics23::ibc_api::{IAVL_PROOF_SPEC, TENDERMINT_PROOF_SPEC};
+use serde::Serialize;
+use serde_json::Value;
+use unionlabs::{cosmos::ics23::proof_spec::ProofSpec, google::protobuf::duration::Duration};
+use voyager_primitives::{ChainId, ClientType};
+use voyager_rpc::types::{SelfClientStateResponse, SelfConsensusStateResponse};
+
+/// The states a client bootstrap module would produce for a new client, along with the
+/// human-readable values decoded from them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClientBootstrapPreview {
+    pub chain_id: ChainId,
+    pub client_type: ClientType,
+    pub client_state: SelfClientStateResponse,
+    pub consensus_state: SelfConsensusStateResponse,
+    /// All durations found in the states, keyed by their JSON pointer.
+    pub durations: Vec<DecodedDuration>,
+    /// All ICS23 proof specs found in the states, keyed by their JSON pointer.
+    pub proof_specs: Vec<DecodedProofSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DecodedDuration {
+    pub pointer: String,
+    pub raw: String,
+    pub decoded: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecodedProofSpec {
+    pub pointer: String,
+    /// The name of the well-known spec this is equal to, if any.
+    pub known: Option<&'static str>,
+    pub spec: ProofSpec,
+}
+
+impl ClientBootstrapPreview {
+    #[must_use]
+    pub fn new(
+        chain_id: ChainId,
+        client_type: ClientType,
+        client_state: SelfClientStateResponse,
+        consensus_state: SelfConsensusStateResponse,
+    ) -> Self {
+        let mut durations = vec![];
+        let mut proof_specs = vec![];
+
+        for (root, state) in [
+            ("/client_state", &client_state.state),
+            ("/consensus_state", &consensus_state.state),
+        ] {
+            decode_values(root.to_owned(), state, &mut durations, &mut proof_specs);
+        }
+
+        Self {
+            chain_id,
+            client_type,
+            client_state,
+            consensus_state,
+            durations,
+            proof_specs,
+        }
+    }
+}
+
+fn decode_values(
+    pointer: String,
+    value: &Value,
+    durations: &mut Vec<DecodedDuration>,
+    proof_specs: &mut Vec<DecodedProofSpec>,
+) {
+    match value {
+        Value::String(raw) => {
+            if let Ok(duration) = raw.parse::<Duration>() {
+                durations.push(DecodedDuration {
+                    pointer,
+                    raw: raw.clone(),
+                    decoded: format_duration(&duration),
+                });
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                decode_values(format!("{pointer}/{i}"), value, durations, proof_specs);
+            }
+        }
+        Value::Object(map) => {
+            if let Ok(spec) = serde_json::from_value::<ProofSpec>(value.clone()) {
+                proof_specs.push(DecodedProofSpec {
+                    pointer,
+                    known: known_proof_spec(&spec),
+                    spec,
+                });
+                return;
+            }
+
+            for (key, value) in map {
+                decode_values(
+                    format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1")),
+                    value,
+                    durations,
+                    proof_specs,
+                );
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+fn known_proof_spec(spec: &ProofSpec) -> Option<&'static str> {
+    if spec == &IAVL_PROOF_SPEC {
+        Some("iavl")
+    } else if spec == &TENDERMINT_PROOF_SPEC {
+        Some("tendermint")
+    } else {
+        None
+    }
+}
+
+/// Format a duration as its non-zero days, hours, minutes and seconds, i.e. `14d`, `1h30m` or
+/// `1.5s`.
+fn format_duration(duration: &Duration) -> String {
+    let seconds = duration.seconds().inner();
+    let nanos = duration.nanos().inner();
+
+    let mut out = String::new();
+
+    if seconds < 0 || nanos < 0 {
+        out.push('-');
+    }
+
+    let seconds = seconds.unsigned_abs();
+    let nanos = nanos.unsigned_abs();
+
+    for (count, unit) in [
+        (seconds / (24 * 60 * 60), "d"),
+        (seconds / (60 * 60) % 24, "h"),
+        (seconds / 60 % 60, "m"),
+    ] {
+        if count > 0 {
+            write!(out, "{count}{unit}").expect("writing to string succeeds");
+        }
+    }
+
+    let seconds = seconds % 60;
+    if seconds > 0 || nanos > 0 || out.trim_start_matches('-').is_empty() {
+        if nanos == 0 {
+            write!(out, "{seconds}s").expect("writing to string succeeds");
+        } else {
+            let nanos = format!("{nanos:09}");
+            write!(out, "{seconds}.{}s", nanos.trim_end_matches('0'))
+                .expect("writing to string succeeds");
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use unionlabs::ibc::core::client::height::Height;
+
+    use super::*;
+
+    fn duration(s: &str) -> String {
+        format_duration(&s.parse().unwrap())
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(duration("1209600s"), "14d");
+        assert_eq!(duration("5400s"), "1h30m");
+        assert_eq!(duration("1.5s"), "1.5s");
+        assert_eq!(duration("90061s"), "1d1h1m1s");
+        assert_eq!(duration("0s"), "0s");
+        assert_eq!(duration("-60s"), "-1m");
+    }
+
+    #[test]
+    fn decodes_durations_and_proof_specs() {
+        let preview = ClientBootstrapPreview::new(
+            ChainId::new("union-1"),
+            ClientType::new(ClientType::TENDERMINT),
+            SelfClientStateResponse {
+                height: Height::new(1),
+                state: json!({
+                    "trusting_period": "1209600s",
+                    "chain_id": "union-1",
+                    "proof_specs": [IAVL_PROOF_SPEC, TENDERMINT_PROOF_SPEC],
+                }),
+            },
+            SelfConsensusStateResponse {
+                height: Height::new(1),
+                state: json!({ "timestamp": "1" }),
+            },
+        );
+
+        assert_eq!(
+            preview.durations,
+            [DecodedDuration {
+                pointer: "/client_state/trusting_period".to_owned(),
+                raw: "1209600s".to_owned(),
+                decoded: "14d".to_owned(),
+            }]
+        );
+        assert_eq!(
+            preview
+                .proof_specs
+                .iter()
+                .map(|spec| (spec.pointer.as_str(), spec.known))
+                .collect::<Vec<_>>(),
+            [
+                ("/client_state/proof_specs/0", Some("iavl")),
+                ("/client_state/proof_specs/1", Some("tendermint")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use anyhow::bail;
+use ibc_classic_spec::IbcClassic;
+use ibc_union_spec::IbcUnion;
+use jsonrpsee::core::client::ClientT;
+use serde::Serialize;
+use unionlabs::ibc::core::client::height::Height;
+use voyager_client::VoyagerClient;
+use voyager_primitives::{ChainId, ClientInfo, ClientType, IbcSpec, IbcSpecId, QueryHeight};
+use voyager_types::RawClientId;
+
+/// The type url of ibc-go's `MsgRecoverClient`.
+pub const MSG_RECOVER_CLIENT_TYPE_URL: &str = "/ibc.core.client.v1.MsgRecoverClient";
+
+/// A governance proposal recovering an expired or frozen ibc-go client, in the format expected by
+/// `<chain> tx gov submit-proposal`. Once the proposal passes, the state of the subject client is
+/// replaced with the state of the substitute client.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecoverClientProposal {
+    pub messages: Vec<MsgRecoverClient>,
+    pub metadata: String,
+    pub deposit: String,
+    pub title: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MsgRecoverClient {
+    #[serde(rename = "@type")]
+    pub type_url: String,
+    pub subject_client_id: String,
+    pub substitute_client_id: String,
+    /// The governance module account, which is the only signer allowed to recover clients.
+    pub signer: String,
+}
+
+/// A client that takes part in a recovery, as it currently is on chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryClient {
+    pub ibc_spec_id: IbcSpecId,
+    pub client_id: String,
+    pub client_type: ClientType,
+    pub counterparty_chain_id: ChainId,
+    pub height: Height,
+}
+
+impl RecoverClientProposal {
+    /// Build the proposal for replacing `subject` with `substitute`. `authority` is the address of
+    /// the governance module account.
+    ///
+    /// Only ibc-go clients can be recovered: neither the ibc-union cosmwasm contract nor the
+    /// ibc-union EVM contracts can replace the state of a client, so the connections of an expired
+    /// ibc-union client have to be opened again on the substitute instead.
+    pub fn new(
+        subject: &RecoveryClient,
+        substitute: &RecoveryClient,
+        authority: String,
+        deposit: String,
+        title: Option<String>,
+    ) -> anyhow::Result<Self> {
+        check_substitute(subject, substitute)?;
+
+        if subject.ibc_spec_id != IbcClassic::ID {
+            bail!(
+                "the {} client {} cannot be recovered, since {} has no entrypoint replacing the \
+                state of a client; open new connections and channels on the substitute client {} \
+                instead",
+                subject.ibc_spec_id,
+                subject.client_id,
+                subject.ibc_spec_id,
+                substitute.client_id,
+            );
+        }
+
+        Ok(Self {
+            messages: vec![MsgRecoverClient {
+                type_url: MSG_RECOVER_CLIENT_TYPE_URL.to_owned(),
+                subject_client_id: subject.client_id.clone(),
+                substitute_client_id: substitute.client_id.clone(),
+                signer: authority,
+            }],
+            metadata: String::new(),
+            deposit,
+            title: title.unwrap_or_else(|| format!("Recover client {}", subject.client_id)),
+            summary: format!(
+                "Replace the state of the {} client {} tracking {} (at height {}) with the state \
+                of the substitute client {} (at height {}).",
+                subject.client_type,
+                subject.client_id,
+                subject.counterparty_chain_id,
+                subject.height,
+                substitute.client_id,
+                substitute.height,
+            ),
+        })
+    }
+}
+
+/// Fetch the `ibc_spec_id` client `client_id` on `chain_id`.
+pub async fn fetch_recovery_client<C: ClientT + Send + Sync>(
+    voyager_client: &VoyagerClient<C>,
+    chain_id: ChainId,
+    ibc_spec_id: IbcSpecId,
+    client_id: RawClientId,
+) -> anyhow::Result<(RecoveryClient, ClientInfo)> {
+    let decoded_client_id = match ibc_spec_id.as_str() {
+        IbcSpecId::CLASSIC => client_id.clone().decode_spec::<IbcClassic>()?.to_string(),
+        IbcSpecId::UNION => client_id.clone().decode_spec::<IbcUnion>()?.to_string(),
+        _ => bail!("unknown ibc spec {ibc_spec_id}"),
+    };
+
+    let client_info = voyager_client
+        .client_info_raw(chain_id.clone(), ibc_spec_id.clone(), client_id.clone())
+        .await?;
+
+    let client_state_meta = voyager_client
+        .client_state_meta_raw(
+            chain_id,
+            ibc_spec_id.clone(),
+            QueryHeight::Latest,
+            client_id,
+        )
+        .await?;
+
+    Ok((
+        RecoveryClient {
+            ibc_spec_id,
+            client_id: decoded_client_id,
+            client_type: client_info.client_type.clone(),
+            counterparty_chain_id: client_state_meta.counterparty_chain_id,
+            height: client_state_meta.counterparty_height,
+        },
+        client_info,
+    ))
+}
+
+/// Check that `substitute` can replace `subject`: it must be a client of the same type, tracking
+/// the same chain, and ibc-go requires it to be at a later height.
+pub fn check_substitute(
+    subject: &RecoveryClient,
+    substitute: &RecoveryClient,
+) -> anyhow::Result<()> {
+    if subject.ibc_spec_id != substitute.ibc_spec_id {
+        bail!(
+            "the substitute client {} is an {} client, but the client {} is an {} client",
+            substitute.client_id,
+            substitute.ibc_spec_id,
+            subject.client_id,
+            subject.ibc_spec_id
+        );
+    }
+
+    if subject.client_id == substitute.client_id {
+        bail!("a client cannot be its own substitute");
+    }
+
+    if subject.client_type != substitute.client_type {
+        bail!(
+            "the substitute client {} is of type {}, but the client {} is of type {}",
+            substitute.client_id,
+            substitute.client_type,
+            subject.client_id,
+            subject.client_type
+        );
+    }
+
+    if subject.counterparty_chain_id != substitute.counterparty_chain_id {
+        bail!(
+            "the substitute client {} tracks {}, but the client {} tracks {}",
+            substitute.client_id,
+            substitute.counterparty_chain_id,
+            subject.client_id,
+            subject.counterparty_chain_id
+        );
+    }
+
+    check_substitute_height(subject.height, substitute.height)
+}
+
+/// The substitute must have been updated past the height of the client it replaces.
+pub fn check_substitute_height(
+    subject_height: Height,
+    substitute_height: Height,
+) -> anyhow::Result<()> {
+    if substitute_height <= subject_height {
+        bail!(
+            "the substitute client is at height {substitute_height}, which is not after the \
+            height of the client it replaces ({subject_height})"
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(client_id: &str, height: u64) -> RecoveryClient {
+        RecoveryClient {
+            ibc_spec_id: IbcClassic::ID,
+            client_id: client_id.to_owned(),
+            client_type: ClientType::new(ClientType::TENDERMINT),
+            counterparty_chain_id: ChainId::new("union-1"),
+            height: Height::new_with_revision(1, height),
+        }
+    }
+
+    #[test]
+    fn proposal() {
+        let proposal = RecoverClientProposal::new(
+            &client("07-tendermint-1", 100),
+            &client("07-tendermint-2", 200),
+            "union10d07y265gmmuvt4z0w9aw880jnsr700jrkg4an".to_owned(),
+            "10000000au".to_owned(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&proposal.messages).unwrap(),
+            serde_json::json!([{
+                "@type": "/ibc.core.client.v1.MsgRecoverClient",
+                "subject_client_id": "07-tendermint-1",
+                "substitute_client_id": "07-tendermint-2",
+                "signer": "union10d07y265gmmuvt4z0w9aw880jnsr700jrkg4an",
+            }])
+        );
+        assert_eq!(proposal.title, "Recover client 07-tendermint-1");
+    }
+
+    #[test]
+    fn ibc_union_clients_cannot_be_replaced() {
+        let ibc_union_client = |client_id: &str, height| RecoveryClient {
+            ibc_spec_id: IbcUnion::ID,
+            ..client(client_id, height)
+        };
+
+        assert!(RecoverClientProposal::new(
+            &ibc_union_client("1", 100),
+            &ibc_union_client("2", 200),
+            "union10d07y265gmmuvt4z0w9aw880jnsr700jrkg4an".to_owned(),
+            "10000000au".to_owned(),
+            None,
+        )
+        .is_err());
+        assert!(check_substitute(&client("1", 100), &ibc_union_client("2", 200)).is_err());
+    }
+
+    #[test]
+    fn substitute_must_be_ahead() {
+        let subject = client("07-tendermint-1", 100);
+
+        assert!(check_substitute(&subject, &client("07-tendermint-2", 100)).is_err());
+        assert!(check_substitute(&subject, &client("07-tendermint-2", 101)).is_ok());
+        // a later revision is ahead regardless of the height
+        assert!(check_substitute_height(
+            Height::new_with_revision(1, 100),
+            Height::new_with_revision(2, 1)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn substitute_must_match() {
+        let subject = client("07-tendermint-1", 100);
+
+        assert!(check_substitute(&subject, &client("07-tendermint-1", 200)).is_err());
+        assert!(check_substitute(
+            &subject,
+            &RecoveryClient {
+                client_type: ClientType::new(ClientType::COMETBLS),
+                ..client("07-tendermint-2", 200)
+            }
+        )
+        .is_err());
+        assert!(check_substitute(
+            &subject,
+            &RecoveryClient {
+                counterparty_chain_id: ChainId::new("union-2"),
+                ..client("07-tendermint-2", 200)
+            }
+        )
+        .is_err());
+    }
+}
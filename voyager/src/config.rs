@@ -1,13 +1,17 @@
-use std::{net::SocketAddr, time::Duration};
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use voyager_core::{
+    backpressure::BackpressureConfig,
     context::{ModulesConfig, PluginConfig},
     default_ipc_client_request_timeout, default_metrics_endpoint,
     default_optimizer_delay_milliseconds, default_rest_laddr, default_rpc_laddr,
+    default_shutdown_timeout,
     equivalent_chain_ids::EquivalentChainIds,
+    health::HealthConfig,
 };
+use voyager_primitives::ChainId;
 
 use crate::queue::QueueConfig;
 
@@ -41,5 +45,20 @@ pub struct VoyagerConfig {
     pub optimizer_delay_milliseconds: u64,
     #[serde(default = "default_ipc_client_request_timeout")]
     pub ipc_client_request_timeout: Duration,
+    /// The time given to each plugin and module to finish its in-flight requests when voyager is
+    /// shut down, after which it is killed.
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: Duration,
     pub cache: voyager_core::cache::Config,
+    #[serde(default)]
+    pub backpressure: BackpressureConfig,
+    /// Client updates of clients on these chains are held back for this many seconds, such that
+    /// clients are only updated to (and messages are only proven against) counterparty heights that
+    /// have been finalized for at least this long. This is required when relaying to chains with
+    /// delayed execution, where state posted by optimistic rollups can still be challenged for the
+    /// duration of the challenge window.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub finality_delay_seconds: HashMap<ChainId, u64>,
+    #[serde(default)]
+    pub health: HealthConfig,
 }
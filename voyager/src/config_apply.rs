@@ -0,0 +1,738 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context as _};
+use jsonrpsee::http_client::HttpClient;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{debug, info, warn};
+use voyager_core::context::{ModuleConfig, PluginConfig};
+use voyager_primitives::ChainId;
+use voyager_rpc::{
+    types::{
+        ClientBootstrapModuleInfo, ClientModuleInfo, FinalityModuleInfo, ProofModuleInfo,
+        StateModuleInfo,
+    },
+    VoyagerRpcClient,
+};
+
+use crate::config::Config;
+
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The changes to the components of a single chain, applied together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Stage {
+    /// The chain of the changed components, or `None` for changes that are not specific to a
+    /// chain (voyager settings, client modules and plugins without a `chain_id`).
+    pub chain_id: Option<ChainId>,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    /// The id of the changed component, i.e. the module id or `plugin/<file name>[/<chain id>]`.
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "action")]
+pub enum ChangeKind {
+    /// The component is added (or enabled) and will be started.
+    Start,
+    /// The component is removed (or disabled) and will be stopped.
+    Stop,
+    /// The config of the component changed, it will be restarted.
+    Restart {
+        /// JSON pointers to the changed parameters.
+        parameters: Vec<String>,
+    },
+    /// Voyager settings changed.
+    Update {
+        /// JSON pointers to the changed parameters.
+        parameters: Vec<String>,
+    },
+}
+
+/// A module or plugin, which is diffed and staged as a whole.
+trait Component: Clone + Serialize {
+    fn id(&self) -> String;
+
+    fn chain_id(&self) -> Option<ChainId>;
+
+    fn enabled(&self) -> bool;
+}
+
+trait ModuleInfo: Clone + Serialize {
+    fn id(&self) -> String;
+
+    fn chain_id(&self) -> Option<ChainId>;
+}
+
+impl ModuleInfo for StateModuleInfo {
+    fn id(&self) -> String {
+        StateModuleInfo::id(self)
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        Some(self.chain_id.clone())
+    }
+}
+
+impl ModuleInfo for ProofModuleInfo {
+    fn id(&self) -> String {
+        ProofModuleInfo::id(self)
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        Some(self.chain_id.clone())
+    }
+}
+
+impl ModuleInfo for FinalityModuleInfo {
+    fn id(&self) -> String {
+        FinalityModuleInfo::id(self)
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        Some(self.chain_id.clone())
+    }
+}
+
+impl ModuleInfo for ClientModuleInfo {
+    fn id(&self) -> String {
+        ClientModuleInfo::id(self)
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        None
+    }
+}
+
+impl ModuleInfo for ClientBootstrapModuleInfo {
+    fn id(&self) -> String {
+        ClientBootstrapModuleInfo::id(self)
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        Some(self.chain_id.clone())
+    }
+}
+
+impl<T: ModuleInfo> Component for ModuleConfig<T> {
+    fn id(&self) -> String {
+        self.info.id()
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.info.chain_id()
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+impl Component for PluginConfig {
+    fn id(&self) -> String {
+        let name = self.path.file_name().map_or_else(
+            || self.path.to_string_lossy(),
+            |name| name.to_string_lossy(),
+        );
+
+        match self.chain_id() {
+            Some(chain_id) => format!("plugin/{name}/{chain_id}"),
+            None => format!("plugin/{name}"),
+        }
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.config
+            .get("chain_id")
+            .and_then(Value::as_str)
+            .map(|chain_id| ChainId::new(chain_id.to_owned()))
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Diff `new` against `current`, grouping the changes per chain. Changes that are not specific to
+/// a chain are grouped in the first stage.
+#[must_use]
+pub fn diff(current: &Config, new: &Config) -> Vec<Stage> {
+    let mut stages = BTreeMap::<Option<ChainId>, Vec<Change>>::new();
+
+    for (id, current, new) in [
+        (
+            "voyager",
+            to_value(&current.voyager),
+            to_value(&new.voyager),
+        ),
+        (
+            "equivalent_chain_ids",
+            to_value(&current.equivalent_chain_ids),
+            to_value(&new.equivalent_chain_ids),
+        ),
+    ] {
+        let parameters = changed_parameters(&current, &new);
+
+        if !parameters.is_empty() {
+            stages.entry(None).or_default().push(Change {
+                id: id.to_owned(),
+                kind: ChangeKind::Update { parameters },
+            });
+        }
+    }
+
+    diff_components(&current.modules.state, &new.modules.state, &mut stages);
+    diff_components(&current.modules.proof, &new.modules.proof, &mut stages);
+    diff_components(
+        &current.modules.consensus,
+        &new.modules.consensus,
+        &mut stages,
+    );
+    diff_components(&current.modules.client, &new.modules.client, &mut stages);
+    diff_components(
+        &current.modules.client_bootstrap,
+        &new.modules.client_bootstrap,
+        &mut stages,
+    );
+    diff_components(&current.plugins, &new.plugins, &mut stages);
+
+    stages
+        .into_iter()
+        .map(|(chain_id, changes)| Stage { chain_id, changes })
+        .collect()
+}
+
+fn diff_components<C: Component>(
+    current: &[C],
+    new: &[C],
+    stages: &mut BTreeMap<Option<ChainId>, Vec<Change>>,
+) {
+    let enabled = |components: &[C]| {
+        components
+            .iter()
+            .filter(|component| component.enabled())
+            .map(|component| (component.id(), component.clone()))
+            .collect::<BTreeMap<_, _>>()
+    };
+
+    let current = enabled(current);
+    let new = enabled(new);
+
+    for (id, component) in &current {
+        if !new.contains_key(id) {
+            stages
+                .entry(component.chain_id())
+                .or_default()
+                .push(Change {
+                    id: id.clone(),
+                    kind: ChangeKind::Stop,
+                });
+        }
+    }
+
+    for (id, component) in &new {
+        let kind = match current.get(id) {
+            Some(current) => {
+                let parameters = changed_parameters(&to_value(current), &to_value(component));
+
+                if parameters.is_empty() {
+                    continue;
+                }
+
+                ChangeKind::Restart { parameters }
+            }
+            None => ChangeKind::Start,
+        };
+
+        stages
+            .entry(component.chain_id())
+            .or_default()
+            .push(Change {
+                id: id.clone(),
+                kind,
+            });
+    }
+}
+
+/// The JSON pointers of all values that differ between `current` and `new`.
+fn changed_parameters(current: &Value, new: &Value) -> Vec<String> {
+    fn go(current: &Value, new: &Value, pointer: &str, parameters: &mut Vec<String>) {
+        match (current, new) {
+            (Value::Object(current), Value::Object(new)) => {
+                for key in current.keys().chain(new.keys()).collect::<BTreeSet<_>>() {
+                    let pointer =
+                        format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"));
+
+                    match (current.get(key), new.get(key)) {
+                        (Some(current), Some(new)) => go(current, new, &pointer, parameters),
+                        _ => parameters.push(pointer),
+                    }
+                }
+            }
+            (current, new) => {
+                if current != new {
+                    parameters.push(pointer.to_owned());
+                }
+            }
+        }
+    }
+
+    let mut parameters = vec![];
+    go(current, new, "", &mut parameters);
+    parameters
+}
+
+fn to_value<T: Serialize>(t: &T) -> Value {
+    serde_json::to_value(t).expect("serialization is infallible; qed;")
+}
+
+/// The config with the changes of the chains in `applied` applied to `current`.
+fn staged_config(current: &Config, new: &Config, applied: &BTreeSet<Option<ChainId>>) -> Config {
+    fn stage<C: Component>(
+        current: &[C],
+        new: &[C],
+        applied: &BTreeSet<Option<ChainId>>,
+    ) -> Vec<C> {
+        current
+            .iter()
+            .filter(|component| !applied.contains(&component.chain_id()))
+            .chain(
+                new.iter()
+                    .filter(|component| applied.contains(&component.chain_id())),
+            )
+            .cloned()
+            .collect()
+    }
+
+    let global = if applied.contains(&None) {
+        new
+    } else {
+        current
+    };
+
+    let mut config = global.clone();
+
+    config.modules.state = stage(&current.modules.state, &new.modules.state, applied);
+    config.modules.proof = stage(&current.modules.proof, &new.modules.proof, applied);
+    config.modules.consensus = stage(&current.modules.consensus, &new.modules.consensus, applied);
+    config.modules.client = stage(&current.modules.client, &new.modules.client, applied);
+    config.modules.client_bootstrap = stage(
+        &current.modules.client_bootstrap,
+        &new.modules.client_bootstrap,
+        applied,
+    );
+    config.plugins = stage(&current.plugins, &new.plugins, applied);
+
+    config
+}
+
+/// The config the running voyager instance was started with.
+pub async fn running_config(voyager_client: &HttpClient) -> anyhow::Result<Config> {
+    serde_json::from_value(
+        voyager_client
+            .config()
+            .await
+            .context("unable to query the config of the running voyager instance")?,
+    )
+    .context("unable to parse the config of the running voyager instance")
+}
+
+/// Apply `new` to the running voyager instance by restarting it with `restart_command`. The
+/// changes are diffed against the config voyager is running with, not against the config file.
+///
+/// If `staged` is set, the changes are applied chain by chain, and voyager must become healthy
+/// within `health_timeout` after each stage before the next stage is applied. Intermediate stages
+/// are written to a stage file next to the config file, so the config file is only written once:
+/// the last stage writes `new_raw` to `config_file_path` as is, keeping the comments of `.jsonc`
+/// configs. The restart command is run with `VOYAGER_CONFIG_FILE_PATH` set to the file voyager
+/// must be started with. If a stage fails, the config file is restored and voyager is restarted
+/// with the config of the previous stage.
+#[allow(clippy::too_many_arguments)]
+pub async fn apply(
+    voyager_client: &HttpClient,
+    config_file_path: &Path,
+    new: &Config,
+    new_raw: &str,
+    staged: bool,
+    restart_command: &str,
+    health_timeout: Duration,
+) -> anyhow::Result<()> {
+    let current = running_config(voyager_client).await?;
+
+    let stages = diff(&current, new);
+
+    if stages.is_empty() {
+        info!("no changes to apply");
+        return Ok(());
+    }
+
+    let stages = if staged {
+        stages
+            .into_iter()
+            .map(|stage| (BTreeSet::from([stage.chain_id.clone()]), stage))
+            .collect::<Vec<_>>()
+    } else {
+        vec![(
+            stages.iter().map(|stage| stage.chain_id.clone()).collect(),
+            Stage {
+                chain_id: None,
+                changes: stages.into_iter().flat_map(|stage| stage.changes).collect(),
+            },
+        )]
+    };
+
+    let stage_file_path = stage_file_path(config_file_path);
+
+    let original_raw = fs::read_to_string(config_file_path).with_context(|| {
+        format!(
+            "unable to read the config file at `{}`",
+            config_file_path.display()
+        )
+    })?;
+
+    let mut applied = BTreeSet::new();
+    let mut previous = current.clone();
+
+    for (i, (chains, stage)) in stages.iter().enumerate() {
+        applied.extend(chains.iter().cloned());
+
+        let is_last = i == stages.len() - 1;
+
+        info!(
+            "applying stage {}/{} ({}): {}",
+            i + 1,
+            stages.len(),
+            display_chain_id(stage.chain_id.as_ref()),
+            serde_json::to_string(&stage.changes)?
+        );
+
+        let (config, start_file_path) = if is_last {
+            write_file(config_file_path, new_raw)?;
+            (new.clone(), config_file_path)
+        } else {
+            let config = staged_config(&current, new, &applied);
+            write_file(&stage_file_path, &serde_json::to_string_pretty(&config)?)?;
+            (config, stage_file_path.as_path())
+        };
+
+        let result = async {
+            restart(restart_command, start_file_path).await?;
+            wait_until_healthy(voyager_client, &config, chains, health_timeout).await
+        }
+        .await;
+
+        if let Err(err) = result {
+            warn!("stage {} failed, rolling back: {err:#}", i + 1);
+
+            if is_last {
+                write_file(config_file_path, &original_raw)?;
+            }
+
+            // the config file may not match the config voyager was running with, so voyager is
+            // restarted with the config of the previous stage from the stage file
+            write_file(&stage_file_path, &serde_json::to_string_pretty(&previous)?)?;
+            restart(restart_command, &stage_file_path)
+                .await
+                .context("restarting voyager with the config of the previous stage")?;
+
+            return Err(err.context(format!(
+                "stage {} ({}) failed, voyager was restarted with the config of the previous \
+                 stage from `{}`",
+                i + 1,
+                display_chain_id(stage.chain_id.as_ref()),
+                stage_file_path.display(),
+            )));
+        }
+
+        info!("stage {}/{} applied", i + 1, stages.len());
+
+        previous = config;
+    }
+
+    if stage_file_path.exists() {
+        fs::remove_file(&stage_file_path).with_context(|| {
+            format!(
+                "unable to remove the stage file at `{}`",
+                stage_file_path.display()
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+/// The file the intermediate stages are written to, next to the config file. Stages are always
+/// written as plain JSON.
+fn stage_file_path(config_file_path: &Path) -> PathBuf {
+    let mut file_name = config_file_path
+        .file_name()
+        .unwrap_or_default()
+        .to_os_string();
+    file_name.push(".stage.json");
+
+    config_file_path.with_file_name(file_name)
+}
+
+fn write_file(path: &Path, contents: &str) -> anyhow::Result<()> {
+    fs::write(path, contents)
+        .with_context(|| format!("unable to write the config file at `{}`", path.display()))
+}
+
+fn display_chain_id(chain_id: Option<&ChainId>) -> String {
+    chain_id.map_or_else(|| "global".to_owned(), ToString::to_string)
+}
+
+async fn restart(restart_command: &str, config_file_path: &Path) -> anyhow::Result<()> {
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(restart_command)
+        .env("VOYAGER_CONFIG_FILE_PATH", config_file_path)
+        .status()
+        .await
+        .with_context(|| format!("unable to run the restart command `{restart_command}`"))?;
+
+    if !status.success() {
+        bail!("restart command `{restart_command}` failed ({status})");
+    }
+
+    Ok(())
+}
+
+async fn wait_until_healthy(
+    voyager_client: &HttpClient,
+    config: &Config,
+    chains: &BTreeSet<Option<ChainId>>,
+    health_timeout: Duration,
+) -> anyhow::Result<()> {
+    let deadline = Instant::now() + health_timeout;
+
+    loop {
+        match check_health(voyager_client, config, chains).await {
+            Ok(()) => return Ok(()),
+            Err(err) if Instant::now() >= deadline => {
+                return Err(err.context(format!(
+                    "voyager did not become healthy within {}s",
+                    health_timeout.as_secs()
+                )))
+            }
+            Err(err) => {
+                debug!("not healthy yet: {err:#}");
+                tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+            }
+        }
+    }
+}
+
+/// Voyager is healthy if it runs with `config`, all enabled modules of `chains` are running, and
+/// the latest height of the chains with a consensus module can be queried. Plugins are not
+/// checked.
+async fn check_health(
+    voyager_client: &HttpClient,
+    config: &Config,
+    chains: &BTreeSet<Option<ChainId>>,
+) -> anyhow::Result<()> {
+    // ensures that voyager was restarted, with the config of this stage
+    if to_value(&running_config(voyager_client).await?) != to_value(config) {
+        bail!("voyager is not running with the config of this stage");
+    }
+
+    let info = voyager_client.info().await?;
+
+    let running = info
+        .state
+        .iter()
+        .map(StateModuleInfo::id)
+        .chain(info.proof.iter().map(ProofModuleInfo::id))
+        .chain(info.consensus.iter().map(FinalityModuleInfo::id))
+        .chain(info.client.iter().map(ClientModuleInfo::id))
+        .chain(
+            info.client_bootstrap
+                .iter()
+                .map(ClientBootstrapModuleInfo::id),
+        )
+        .collect::<BTreeSet<_>>();
+
+    let expected = enabled_ids(&config.modules.state, chains)
+        .chain(enabled_ids(&config.modules.proof, chains))
+        .chain(enabled_ids(&config.modules.consensus, chains))
+        .chain(enabled_ids(&config.modules.client, chains))
+        .chain(enabled_ids(&config.modules.client_bootstrap, chains));
+
+    for id in expected {
+        if !running.contains(&id) {
+            bail!("module {id} is not running");
+        }
+    }
+
+    for chain_id in config
+        .modules
+        .consensus
+        .iter()
+        .filter(|module| module.enabled && chains.contains(&Some(module.info.chain_id.clone())))
+        .map(|module| &module.info.chain_id)
+    {
+        voyager_client
+            .query_latest_height(chain_id.clone(), false)
+            .await
+            .with_context(|| format!("unable to query the latest height of {chain_id}"))?;
+    }
+
+    Ok(())
+}
+
+fn enabled_ids<'a, C: Component>(
+    components: &'a [C],
+    chains: &'a BTreeSet<Option<ChainId>>,
+) -> impl Iterator<Item = String> + 'a {
+    components
+        .iter()
+        .filter(|component| component.enabled() && chains.contains(&component.chain_id()))
+        .map(Component::id)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use voyager_primitives::chain_id;
+
+    use super::*;
+
+    fn config(value: &Value) -> Config {
+        serde_json::from_value(value.clone()).unwrap()
+    }
+
+    fn base() -> Value {
+        json!({
+            "modules": {
+                "state": [
+                    {
+                        "path": "/bin/state-ethereum",
+                        "info": { "chain_id": "1", "ibc_spec_id": "ibc-union" },
+                        "config": { "rpc_url": "http://a" }
+                    },
+                    {
+                        "path": "/bin/state-cosmos",
+                        "info": { "chain_id": "union-1", "ibc_spec_id": "ibc-union" },
+                        "config": { "rpc_url": "http://b" }
+                    }
+                ],
+                "proof": [],
+                "consensus": [],
+                "client": [],
+                "client_bootstrap": []
+            },
+            "plugins": [
+                {
+                    "path": "/bin/transaction-ethereum",
+                    "config": { "chain_id": "1", "max_gas_price": 1 }
+                }
+            ],
+            "voyager": {
+                "num_workers": 1,
+                "queue": { "type": "in-memory" },
+                "cache": { "state": { "capacity": 1, "time_to_live": 1, "time_to_idle": 1 } }
+            }
+        })
+    }
+
+    #[test]
+    fn no_changes() {
+        assert_eq!(diff(&config(&base()), &config(&base())), vec![]);
+    }
+
+    #[test]
+    fn changes_per_chain() {
+        let mut new = base();
+        new["modules"]["state"][0]["config"]["rpc_url"] = json!("http://c");
+        new["modules"]["state"][1]["enabled"] = json!(false);
+        new["plugins"][0]["config"]["max_gas_price"] = json!(2);
+        new["voyager"]["num_workers"] = json!(2);
+
+        assert_eq!(
+            diff(&config(&base()), &config(&new)),
+            vec![
+                Stage {
+                    chain_id: None,
+                    changes: vec![Change {
+                        id: "voyager".to_owned(),
+                        kind: ChangeKind::Update {
+                            parameters: vec!["/num_workers".to_owned()]
+                        }
+                    }]
+                },
+                Stage {
+                    chain_id: Some(chain_id!("1")),
+                    changes: vec![
+                        Change {
+                            id: "state/ibc-union/1".to_owned(),
+                            kind: ChangeKind::Restart {
+                                parameters: vec!["/config/rpc_url".to_owned()]
+                            }
+                        },
+                        Change {
+                            id: "plugin/transaction-ethereum/1".to_owned(),
+                            kind: ChangeKind::Restart {
+                                parameters: vec!["/config/max_gas_price".to_owned()]
+                            }
+                        }
+                    ]
+                },
+                Stage {
+                    chain_id: Some(chain_id!("union-1")),
+                    changes: vec![Change {
+                        id: "state/ibc-union/union-1".to_owned(),
+                        kind: ChangeKind::Stop
+                    }]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn staged() {
+        let mut new = base();
+        new["modules"]["state"][0]["config"]["rpc_url"] = json!("http://c");
+        new["modules"]["state"][1]["config"]["rpc_url"] = json!("http://d");
+
+        let current = config(&base());
+        let new = config(&new);
+
+        let staged = staged_config(&current, &new, &BTreeSet::from([Some(chain_id!("1"))]));
+
+        assert_eq!(
+            diff(&current, &staged),
+            vec![Stage {
+                chain_id: Some(chain_id!("1")),
+                changes: vec![Change {
+                    id: "state/ibc-union/1".to_owned(),
+                    kind: ChangeKind::Restart {
+                        parameters: vec!["/config/rpc_url".to_owned()]
+                    }
+                }]
+            }]
+        );
+        assert_eq!(
+            diff(&staged, &new),
+            vec![Stage {
+                chain_id: Some(chain_id!("union-1")),
+                changes: vec![Change {
+                    id: "state/ibc-union/union-1".to_owned(),
+                    kind: ChangeKind::Restart {
+                        parameters: vec!["/config/rpc_url".to_owned()]
+                    }
+                }]
+            }]
+        );
+    }
+}
@@ -0,0 +1,917 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsStr,
+    path::Path,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::U256;
+use anyhow::{anyhow, Context};
+use ibc_classic_spec::IbcClassic;
+use ibc_union_spec::IbcUnion;
+use jsonrpsee::core::client::ClientT;
+use serde::Serialize;
+use serde_json::Value;
+use voyager_core::ibc_spec_handlers::IbcSpecHandler;
+use voyager_primitives::{ChainId, IbcSpec, IbcSpecId, QueryHeight, Timestamp};
+use voyager_rpc::{
+    types::{BackpressureStatus, ModuleHealthStatus},
+    VoyagerRpcClient,
+};
+use voyager_types::RawClientId;
+
+/// How urgently a problem found by `voyager doctor` needs to be looked at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, derive_more::Display)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Relaying is broken, or will break soon.
+    #[display(fmt = "critical")]
+    Critical,
+    /// Relaying works, but is degraded or close to breaking.
+    #[display(fmt = "warning")]
+    Warning,
+}
+
+/// A problem found by `voyager doctor`, along with a suggested fix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Problem {
+    pub severity: Severity,
+    /// What the problem was found on, i.e. a chain id, a module id or a plugin name.
+    pub subject: String,
+    pub problem: String,
+    pub fix: String,
+}
+
+impl Problem {
+    fn new(
+        severity: Severity,
+        subject: impl Into<String>,
+        problem: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            subject: subject.into(),
+            problem: problem.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+/// A transaction plugin to check the signer balances of, as `<plugin name>[=<min balance>]`. The
+/// minimum balance is in the smallest unit of the gas token of the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionPluginCheck {
+    pub plugin: String,
+    pub min_balance: U256,
+}
+
+impl FromStr for TransactionPluginCheck {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((plugin, min_balance)) => Ok(Self {
+                plugin: plugin.to_owned(),
+                min_balance: min_balance
+                    .parse()
+                    .with_context(|| format!("invalid minimum balance `{min_balance}`"))?,
+            }),
+            None => Ok(Self {
+                plugin: s.to_owned(),
+                min_balance: U256::ZERO,
+            }),
+        }
+    }
+}
+
+/// A client to check the expiry of, as `<chain id>/<client id>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientCheck {
+    pub chain_id: ChainId,
+    pub client_id: RawClientId,
+}
+
+impl FromStr for ClientCheck {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (chain_id, client_id) = s
+            .rsplit_once('/')
+            .ok_or_else(|| anyhow!("expected `<chain id>/<client id>`, found `{s}`"))?;
+
+        Ok(Self {
+            chain_id: ChainId::new(chain_id.to_owned()),
+            client_id: RawClientId::from(client_id.to_owned()),
+        })
+    }
+}
+
+/// The limits past which `voyager doctor` reports a problem.
+#[derive(Debug, Clone)]
+pub struct Thresholds {
+    /// The maximum age of the latest finalized block of a chain.
+    pub max_block_age: Duration,
+    /// The maximum number of pending operations of a chain.
+    pub max_pending: u64,
+    /// The minimum time left before a client expires.
+    pub client_expiry_margin: Duration,
+}
+
+/// Check the voyager instance behind `voyager_client` and all chains it has modules for, and
+/// return the problems found, most severe first.
+///
+/// If no `transaction_plugins` or no `clients` are passed, the ones found in the config of the
+/// voyager instance are checked instead (see [`checks_from_config`]).
+pub async fn diagnose<C: ClientT + Send + Sync>(
+    voyager_client: &C,
+    ibc_spec_id: &IbcSpecId,
+    transaction_plugins: &[TransactionPluginCheck],
+    clients: &[ClientCheck],
+    thresholds: &Thresholds,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    let statuses = match voyager_client.module_health().await {
+        Ok(statuses) => statuses,
+        Err(error) => {
+            // nothing else can be checked without voyager
+            return vec![Problem::new(
+                Severity::Critical,
+                "voyager",
+                format!("voyager is not reachable: {error}"),
+                "check that voyager is running, and that --rpc-url points to its rpc server",
+            )];
+        }
+    };
+
+    problems.extend(check_module_health(&statuses));
+
+    let chain_ids = match voyager_client.info().await {
+        Ok(info) => info
+            .consensus
+            .into_iter()
+            .map(|module| module.chain_id)
+            .collect::<BTreeSet<_>>(),
+        Err(error) => {
+            problems.push(Problem::new(
+                Severity::Critical,
+                "voyager",
+                format!("unable to query the loaded modules: {error}"),
+                "check the logs of voyager",
+            ));
+            BTreeSet::new()
+        }
+    };
+
+    let now = now();
+
+    for chain_id in chain_ids {
+        problems.extend(check_chain(voyager_client, &chain_id, now, thresholds).await);
+    }
+
+    let (transaction_plugins, clients) = if transaction_plugins.is_empty() || clients.is_empty() {
+        match voyager_client.config().await {
+            Ok(config) => {
+                let (configured_transaction_plugins, configured_clients) =
+                    checks_from_config(&config);
+
+                (
+                    if transaction_plugins.is_empty() {
+                        configured_transaction_plugins
+                    } else {
+                        transaction_plugins.to_vec()
+                    },
+                    if clients.is_empty() {
+                        configured_clients
+                    } else {
+                        clients.to_vec()
+                    },
+                )
+            }
+            Err(error) => {
+                problems.push(Problem::new(
+                    Severity::Warning,
+                    "voyager",
+                    format!("unable to query the config: {error}"),
+                    "pass the transaction plugins and clients to check with --transaction-plugin \
+                    and --client",
+                ));
+
+                (transaction_plugins.to_vec(), clients.to_vec())
+            }
+        }
+    } else {
+        (transaction_plugins.to_vec(), clients.to_vec())
+    };
+
+    for check in &transaction_plugins {
+        match voyager_client
+            .plugin_custom(check.plugin.clone(), "signerBalances".to_owned(), vec![])
+            .await
+        {
+            Ok(balances) => problems.extend(check_signer_balances(check, &balances)),
+            Err(error) => problems.push(Problem::new(
+                Severity::Warning,
+                &check.plugin,
+                format!("unable to query the signer balances: {error}"),
+                "check that the plugin is loaded and is a transaction plugin",
+            )),
+        }
+    }
+
+    for client in &clients {
+        let subject = format!("{}/{}", client.chain_id, client.client_id);
+
+        match fetch_client_expiry(voyager_client, ibc_spec_id, client).await {
+            Ok((consensus_timestamp, trusting_period)) => problems.extend(check_client_expiry(
+                &subject,
+                consensus_timestamp,
+                trusting_period,
+                now,
+                thresholds.client_expiry_margin,
+            )),
+            Err(error) => problems.push(Problem::new(
+                Severity::Warning,
+                subject,
+                format!("unable to query the client: {error:#}"),
+                "check that the client exists and that the state module of the chain is healthy",
+            )),
+        }
+    }
+
+    // stable, so problems of the same severity stay in the order they were checked in
+    problems.sort_by_key(|problem| problem.severity);
+
+    problems
+}
+
+/// The checks for everything in `config`, the config of a voyager instance: the signer balances of
+/// every enabled transaction plugin, and the expiry of every client with a config of its own in an
+/// enabled transaction-batch plugin.
+///
+/// Plugins are recognized by the name of their binary, and are named `<binary>/<chain id>`.
+/// Clients that a transaction-batch plugin only relays for through a config for any client can't
+/// be found, and have to be passed with `--client`.
+#[must_use]
+pub fn checks_from_config(config: &Value) -> (Vec<TransactionPluginCheck>, Vec<ClientCheck>) {
+    const TRANSACTION_PLUGIN_PREFIX: &str = "voyager-transaction-plugin-";
+    const TRANSACTION_BATCH_PLUGIN: &str = "voyager-plugin-transaction-batch";
+
+    let mut transaction_plugins = vec![];
+    let mut clients = vec![];
+
+    for plugin in config["plugins"].as_array().into_iter().flatten() {
+        if plugin["enabled"].as_bool() == Some(false) {
+            continue;
+        }
+
+        let Some(binary) = plugin["path"]
+            .as_str()
+            .and_then(|path| Path::new(path).file_name())
+            .and_then(OsStr::to_str)
+        else {
+            continue;
+        };
+
+        let Some(chain_id) = plugin["config"]["chain_id"].as_str() else {
+            continue;
+        };
+
+        if binary.starts_with(TRANSACTION_PLUGIN_PREFIX) {
+            transaction_plugins.push(TransactionPluginCheck {
+                plugin: format!("{binary}/{chain_id}"),
+                min_balance: U256::ZERO,
+            });
+        } else if binary == TRANSACTION_BATCH_PLUGIN {
+            // either a list of configs of specific clients, or one config for any client
+            for client_config in plugin["config"]["client_configs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+            {
+                if let Some(client_id) = client_config.get("client_id") {
+                    clients.push(ClientCheck {
+                        chain_id: ChainId::new(chain_id.to_owned()),
+                        client_id: RawClientId::new(client_id),
+                    });
+                }
+            }
+        }
+    }
+
+    (transaction_plugins, clients)
+}
+
+/// Format `problems` for the text output.
+#[must_use]
+pub fn format_problems(problems: &[Problem]) -> String {
+    if problems.is_empty() {
+        return "no problems found".to_owned();
+    }
+
+    problems
+        .iter()
+        .map(|problem| {
+            format!(
+                "[{}] {}: {}\n    fix: {}",
+                problem.severity, problem.subject, problem.problem, problem.fix
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn now() -> Timestamp {
+    Timestamp::from_nanos(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the system time is after the unix epoch; qed;")
+            .as_nanos()
+            .try_into()
+            .expect("the system time is before 2554; qed;"),
+    )
+}
+
+fn check_module_health(statuses: &[ModuleHealthStatus]) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    for status in statuses {
+        match &status.health {
+            None => problems.push(Problem::new(
+                Severity::Critical,
+                &status.id,
+                format!(
+                    "the module did not respond: {}",
+                    status.error.as_deref().unwrap_or("unknown error")
+                ),
+                "check the logs of the module, it may have crashed or be overloaded",
+            )),
+            Some(health) if !health.connected => problems.push(Problem::new(
+                Severity::Critical,
+                &status.id,
+                format!(
+                    "the rpc endpoint of the module is not reachable: {}",
+                    health.error.as_deref().unwrap_or("unknown error")
+                ),
+                "check the rpc endpoint in the config of the module, or switch to another endpoint",
+            )),
+            Some(_) => {}
+        }
+    }
+
+    // modules of the same crate should be built from the same revision
+    let mut revisions = BTreeMap::<&str, BTreeSet<&str>>::new();
+
+    for health in statuses.iter().filter_map(|status| status.health.as_ref()) {
+        revisions
+            .entry(&health.version.name)
+            .or_default()
+            .insert(&health.version.git_rev);
+    }
+
+    for (name, revisions) in revisions {
+        if revisions.len() > 1 {
+            problems.push(Problem::new(
+                Severity::Warning,
+                name,
+                format!(
+                    "the modules are built from different revisions ({})",
+                    revisions.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+                "rebuild the modules from the same revision and restart voyager",
+            ));
+        }
+    }
+
+    problems
+}
+
+async fn check_chain<C: ClientT + Send + Sync>(
+    voyager_client: &C,
+    chain_id: &ChainId,
+    now: Timestamp,
+    thresholds: &Thresholds,
+) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    match voyager_client
+        .query_latest_timestamp(chain_id.clone(), true)
+        .await
+    {
+        Ok(timestamp) => problems.extend(check_block_age(
+            chain_id,
+            timestamp,
+            now,
+            thresholds.max_block_age,
+        )),
+        Err(error) => problems.push(Problem::new(
+            Severity::Critical,
+            chain_id.as_str(),
+            format!("unable to query the latest finalized block: {error}"),
+            "check the health of the finality module of the chain and its rpc endpoint",
+        )),
+    }
+
+    match voyager_client.backpressure(chain_id.clone()).await {
+        Ok(status) => problems.extend(check_backpressure(
+            chain_id,
+            &status,
+            thresholds.max_pending,
+        )),
+        Err(error) => problems.push(Problem::new(
+            Severity::Warning,
+            chain_id.as_str(),
+            format!("unable to query the backpressure status: {error}"),
+            "check the logs of voyager",
+        )),
+    }
+
+    problems
+}
+
+fn check_block_age(
+    chain_id: &ChainId,
+    timestamp: Timestamp,
+    now: Timestamp,
+    max_block_age: Duration,
+) -> Option<Problem> {
+    let age = Duration::from_nanos(now.as_nanos().saturating_sub(timestamp.as_nanos()));
+
+    (age > max_block_age).then(|| {
+        Problem::new(
+            Severity::Warning,
+            chain_id.as_str(),
+            format!(
+                "the latest finalized block is {}s old (at {timestamp})",
+                age.as_secs()
+            ),
+            "check that the chain is producing blocks and that its rpc endpoint is synced",
+        )
+    })
+}
+
+fn check_backpressure(
+    chain_id: &ChainId,
+    status: &BackpressureStatus,
+    max_pending: u64,
+) -> Option<Problem> {
+    let pending = status.pending.unwrap_or_default();
+
+    if status.paused {
+        Some(Problem::new(
+            Severity::Warning,
+            chain_id.as_str(),
+            format!("the event source is paused by backpressure ({pending} pending operations)"),
+            format!(
+                "check for items that keep failing with `voyager queue ls --table failed --chain \
+                {chain_id}`, or raise the backpressure threshold of the chain"
+            ),
+        ))
+    } else if pending > max_pending {
+        Some(Problem::new(
+            Severity::Warning,
+            chain_id.as_str(),
+            format!("{pending} operations are pending, more than {max_pending}"),
+            format!(
+                "check what is queued with `voyager queue ls --chain {chain_id}`, and consider \
+                raising the number of workers"
+            ),
+        ))
+    } else {
+        None
+    }
+}
+
+/// `balances` is the response of the `signerBalances` method of a transaction plugin, an object of
+/// signer addresses to balances as decimal or 0x-prefixed hex strings.
+fn check_signer_balances(check: &TransactionPluginCheck, balances: &Value) -> Vec<Problem> {
+    let Some(balances) = balances.as_object() else {
+        return vec![Problem::new(
+            Severity::Warning,
+            &check.plugin,
+            format!("unexpected signer balances response: {balances}"),
+            "check that the plugin is a transaction plugin",
+        )];
+    };
+
+    balances
+        .iter()
+        .filter_map(|(signer, balance)| {
+            let Some(balance) = balance
+                .as_str()
+                .and_then(|balance| balance.parse::<U256>().ok())
+            else {
+                return Some(Problem::new(
+                    Severity::Warning,
+                    &check.plugin,
+                    format!("unexpected balance for signer {signer}: {balance}"),
+                    "check that the plugin is a transaction plugin",
+                ));
+            };
+
+            if balance.is_zero() {
+                Some(Problem::new(
+                    Severity::Critical,
+                    &check.plugin,
+                    format!("signer {signer} has no balance and cannot pay for transactions"),
+                    format!("fund {signer}"),
+                ))
+            } else if balance < check.min_balance {
+                Some(Problem::new(
+                    Severity::Warning,
+                    &check.plugin,
+                    format!(
+                        "signer {signer} has a balance of {balance}, less than {}",
+                        check.min_balance
+                    ),
+                    format!("fund {signer}"),
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// The timestamp of the latest consensus state of the client, and its trusting period if the
+/// decoded client state has one (i.e. tendermint and cometbls clients).
+async fn fetch_client_expiry<C: ClientT + Send + Sync>(
+    voyager_client: &C,
+    ibc_spec_id: &IbcSpecId,
+    client: &ClientCheck,
+) -> anyhow::Result<(Timestamp, Option<Duration>)> {
+    let client_state_path = [
+        (IbcClassic::ID, IbcSpecHandler::new::<IbcClassic>()),
+        (IbcUnion::ID, IbcSpecHandler::new::<IbcUnion>()),
+    ]
+    .into_iter()
+    .find_map(|(id, handler)| (&id == ibc_spec_id).then_some(handler.client_state_path))
+    .ok_or_else(|| anyhow!("unknown IBC spec `{ibc_spec_id}`"))?;
+
+    let client_info = voyager_client
+        .client_info(
+            client.chain_id.clone(),
+            ibc_spec_id.clone(),
+            client.client_id.clone(),
+        )
+        .await?
+        .ok_or_else(|| anyhow!("client not found"))?;
+
+    let client_state_meta = voyager_client
+        .client_state_meta(
+            client.chain_id.clone(),
+            ibc_spec_id.clone(),
+            QueryHeight::Latest,
+            client.client_id.clone(),
+        )
+        .await?
+        .ok_or_else(|| anyhow!("client state not found"))?;
+
+    let consensus_state_meta = voyager_client
+        .consensus_state_meta(
+            client.chain_id.clone(),
+            ibc_spec_id.clone(),
+            QueryHeight::Latest,
+            client.client_id.clone(),
+            client_state_meta.counterparty_height,
+        )
+        .await?
+        .ok_or_else(|| {
+            anyhow!(
+                "consensus state at {} not found",
+                client_state_meta.counterparty_height
+            )
+        })?;
+
+    let client_state = voyager_client
+        .query_ibc_state(
+            client.chain_id.clone(),
+            ibc_spec_id.clone(),
+            QueryHeight::Latest,
+            client_state_path(client.client_id.clone())?,
+        )
+        .await?
+        .state
+        .ok_or_else(|| anyhow!("client state not found"))?;
+
+    let client_state = voyager_client
+        .decode_client_state(
+            client_info.client_type,
+            client_info.ibc_interface,
+            ibc_spec_id.clone(),
+            serde_json::from_value(client_state).context("invalid client state bytes")?,
+        )
+        .await?;
+
+    Ok((
+        consensus_state_meta.timestamp,
+        find_trusting_period(&client_state),
+    ))
+}
+
+fn find_trusting_period(client_state: &Value) -> Option<Duration> {
+    match client_state {
+        Value::Object(map) => map
+            .iter()
+            .find_map(|(key, value)| match (key.as_str(), value) {
+                ("trusting_period" | "trustingPeriod", Value::String(raw)) => raw
+                    .parse::<unionlabs::google::protobuf::duration::Duration>()
+                    .ok()
+                    .and_then(|duration| u64::try_from(duration.as_nanos().inner()).ok())
+                    .map(Duration::from_nanos),
+                _ => find_trusting_period(value),
+            }),
+        Value::Array(values) => values.iter().find_map(find_trusting_period),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => None,
+    }
+}
+
+fn check_client_expiry(
+    subject: &str,
+    consensus_timestamp: Timestamp,
+    trusting_period: Option<Duration>,
+    now: Timestamp,
+    margin: Duration,
+) -> Option<Problem> {
+    // clients without a trusting period do not expire
+    let trusting_period = trusting_period?;
+
+    let expires_at = consensus_timestamp
+        .as_nanos()
+        .saturating_add(trusting_period.as_nanos().try_into().unwrap_or(u64::MAX));
+
+    let Some(remaining) = expires_at.checked_sub(now.as_nanos()).filter(|r| *r > 0) else {
+        return Some(Problem::new(
+            Severity::Critical,
+            subject,
+            format!("the client expired, its latest consensus state is from {consensus_timestamp}"),
+            "recover the client with `voyager msg prepare-client-recovery` (ibc-go clients only), or \
+                create a new one",
+        ));
+    };
+
+    let remaining = Duration::from_nanos(remaining);
+
+    (remaining < margin).then(|| {
+        Problem::new(
+            Severity::Warning,
+            subject,
+            format!("the client expires in {}s", remaining.as_secs()),
+            "update the client with `voyager msg update-client`, and check why the client \
+            update plugin is not updating it",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use voyager_rpc::types::{ModuleHealth, ModuleVersion};
+
+    use super::*;
+
+    fn status(id: &str, health: Option<ModuleHealth>) -> ModuleHealthStatus {
+        ModuleHealthStatus {
+            id: id.to_owned(),
+            error: health.is_none().then(|| "timed out".to_owned()),
+            health,
+            checked_at: 0,
+        }
+    }
+
+    fn health(connected: bool, git_rev: &str) -> ModuleHealth {
+        ModuleHealth {
+            connected,
+            latest_height: None,
+            error: (!connected).then(|| "connection refused".to_owned()),
+            version: ModuleVersion {
+                name: "voyager-state-module-cosmos-sdk".to_owned(),
+                version: "0.0.0".to_owned(),
+                git_rev: git_rev.to_owned(),
+            },
+        }
+    }
+
+    #[test]
+    fn checks_from_config() {
+        let config = json!({
+            "plugins": [
+                {
+                    "path": "./target/debug/voyager-transaction-plugin-ethereum",
+                    "config": { "chain_id": "11155111" },
+                    "enabled": true
+                },
+                {
+                    "path": "./target/debug/voyager-transaction-plugin-cosmos-sdk",
+                    "config": { "chain_id": "union-testnet-10" },
+                    "enabled": false
+                },
+                {
+                    "path": "./target/debug/voyager-plugin-transaction-batch",
+                    "config": {
+                        "chain_id": "union-testnet-10",
+                        "client_configs": [
+                            { "client_id": 5, "min_batch_size": 1, "max_batch_size": 3 },
+                            { "client_id": 7, "min_batch_size": 1, "max_batch_size": 3 }
+                        ]
+                    },
+                    "enabled": true
+                },
+                {
+                    "path": "./target/debug/voyager-plugin-transaction-batch",
+                    "config": {
+                        "chain_id": "11155111",
+                        "client_configs": { "min_batch_size": 1, "max_batch_size": 3 }
+                    },
+                    "enabled": true
+                }
+            ]
+        });
+
+        let (transaction_plugins, clients) = super::checks_from_config(&config);
+
+        assert_eq!(
+            transaction_plugins,
+            [TransactionPluginCheck {
+                plugin: "voyager-transaction-plugin-ethereum/11155111".to_owned(),
+                min_balance: U256::ZERO,
+            }]
+        );
+        assert_eq!(
+            clients,
+            [
+                ClientCheck {
+                    chain_id: ChainId::new("union-testnet-10"),
+                    client_id: RawClientId::new(5),
+                },
+                ClientCheck {
+                    chain_id: ChainId::new("union-testnet-10"),
+                    client_id: RawClientId::new(7),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn module_health() {
+        let problems = check_module_health(&[
+            status("state/ibc-union/union-1", Some(health(true, "abc"))),
+            status("state/ibc-union/union-2", Some(health(false, "abc"))),
+            status("state/ibc-union/union-3", None),
+        ]);
+
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (problem.severity, problem.subject.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (Severity::Critical, "state/ibc-union/union-2"),
+                (Severity::Critical, "state/ibc-union/union-3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn module_revisions() {
+        let problems = check_module_health(&[
+            status("state/ibc-union/union-1", Some(health(true, "abc"))),
+            status("state/ibc-union/union-2", Some(health(true, "def"))),
+        ]);
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].severity, Severity::Warning);
+        assert_eq!(problems[0].subject, "voyager-state-module-cosmos-sdk");
+    }
+
+    #[test]
+    fn block_age() {
+        let chain_id = ChainId::new("union-1");
+
+        assert_eq!(
+            check_block_age(
+                &chain_id,
+                Timestamp::from_secs(100),
+                Timestamp::from_secs(130),
+                Duration::from_secs(60)
+            ),
+            None
+        );
+
+        assert!(check_block_age(
+            &chain_id,
+            Timestamp::from_secs(100),
+            Timestamp::from_secs(200),
+            Duration::from_secs(60)
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn backpressure() {
+        let chain_id = ChainId::new("union-1");
+
+        let check = |paused, pending| {
+            check_backpressure(&chain_id, &BackpressureStatus { paused, pending }, 100)
+        };
+
+        assert_eq!(check(false, None), None);
+        assert_eq!(check(false, Some(100)), None);
+        assert!(check(false, Some(101)).is_some());
+        assert!(check(true, Some(10)).is_some());
+    }
+
+    #[test]
+    fn signer_balances() {
+        let check = TransactionPluginCheck {
+            plugin: "voyager-transaction-plugin-ethereum/1".to_owned(),
+            min_balance: U256::from(1000),
+        };
+
+        let problems = check_signer_balances(
+            &check,
+            &json!({
+                "0x01": "0x0",
+                "0x02": "999",
+                "0x03": "0x3e8",
+                "0x04": "100000",
+            }),
+        );
+
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| problem.severity)
+                .collect::<Vec<_>>(),
+            [Severity::Critical, Severity::Warning]
+        );
+        assert!(problems[0].problem.contains("0x01"));
+        assert!(problems[1].problem.contains("0x02"));
+    }
+
+    #[test]
+    fn transaction_plugin_check_from_str() {
+        assert_eq!(
+            "plugin".parse::<TransactionPluginCheck>().unwrap(),
+            TransactionPluginCheck {
+                plugin: "plugin".to_owned(),
+                min_balance: U256::ZERO,
+            }
+        );
+        assert_eq!(
+            "plugin/union-1=100"
+                .parse::<TransactionPluginCheck>()
+                .unwrap(),
+            TransactionPluginCheck {
+                plugin: "plugin/union-1".to_owned(),
+                min_balance: U256::from(100),
+            }
+        );
+        assert!("plugin=abc".parse::<TransactionPluginCheck>().is_err());
+    }
+
+    #[test]
+    fn trusting_period() {
+        assert_eq!(
+            find_trusting_period(&json!({
+                "data": {
+                    "chain_id": "union-1",
+                    "trusting_period": "1209600s",
+                }
+            })),
+            Some(Duration::from_secs(1_209_600))
+        );
+
+        assert_eq!(find_trusting_period(&json!({ "chain_id": "1" })), None);
+    }
+
+    #[test]
+    fn client_expiry() {
+        let check = |now| {
+            check_client_expiry(
+                "union-1/1",
+                Timestamp::from_secs(1000),
+                Some(Duration::from_secs(1000)),
+                Timestamp::from_secs(now),
+                Duration::from_secs(100),
+            )
+        };
+
+        assert_eq!(check(1800), None);
+        assert!(check(1950).unwrap().problem.contains("expires in 50s"));
+        assert!(check(2000).unwrap().problem.contains("expired"));
+
+        assert_eq!(
+            check_client_expiry(
+                "union-1/1",
+                Timestamp::from_secs(1000),
+                None,
+                Timestamp::from_secs(1_000_000),
+                Duration::from_secs(100),
+            ),
+            None
+        );
+    }
+}
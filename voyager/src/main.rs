@@ -24,17 +24,22 @@ use pg_queue::{
 use reqwest::Url;
 use schemars::gen::{SchemaGenerator, SchemaSettings};
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{json, Value};
 use tikv_jemallocator::Jemalloc;
 use tracing::info;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+use tracing_subscriber::{
+    fmt::writer::BoxMakeWriter, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
+};
 use voyager_client::VoyagerClient;
 use voyager_core::{
+    backpressure::BackpressureConfig,
+    config_schema,
     context::ModulesConfig,
-    default_metrics_endpoint, default_rest_laddr, default_rpc_laddr,
+    default_metrics_endpoint, default_rest_laddr, default_rpc_laddr, default_shutdown_timeout,
     equivalent_chain_ids::EquivalentChainIds,
     filter::{make_filter, run_filter, JaqFilterResult},
     get_plugin_info,
+    health::HealthConfig,
     ibc_spec_handlers::IbcSpecHandler,
     Engine,
 };
@@ -52,10 +57,14 @@ static GLOBAL: Jemalloc = Jemalloc;
 
 use crate::{
     cli::{
-        get_voyager_config, App, Command, ConfigCmd, LogFormat, MsgCmd, PluginCmd, QueueCmd, RpcCmd,
+        get_voyager_config, App, Command, ConfigCmd, LogFormat, MsgCmd, OutputFormat, PluginCmd,
+        QueueCmd, RpcCmd,
     },
+    client_preview::ClientBootstrapPreview,
+    client_recovery::{check_substitute_height, fetch_recovery_client, RecoverClientProposal},
     config::{Config, VoyagerConfig},
-    queue::{QueueConfig, QueueImpl},
+    doctor::{diagnose, format_problems, Severity, Thresholds},
+    queue::{ItemSummary, QueueConfig, QueueImpl},
 };
 
 #[cfg(windows)]
@@ -65,14 +74,20 @@ compile_error!(
 );
 
 pub mod cli;
+pub mod client_preview;
+pub mod client_recovery;
 pub mod config;
+pub mod config_apply;
+pub mod doctor;
 pub mod metrics;
 pub mod queue;
 
 fn main() -> ExitCode {
     let app = App::parse();
 
-    init_logging(app.log_format);
+    let output = app.output;
+
+    init_logging(app.log_format, output);
 
     let res = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
@@ -83,6 +98,16 @@ fn main() -> ExitCode {
 
     match res {
         Ok(()) => ExitCode::SUCCESS,
+        Err(err) if output == OutputFormat::Json => {
+            print_json(&serde_json::json!({
+                "error": err.to_string(),
+                "causes": iter::successors(err.source(), |e| (*e).source())
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+            }));
+
+            ExitCode::FAILURE
+        }
         Err(err) => {
             let errs = iter::successors(err.source(), |e| (*e).source())
                 .enumerate()
@@ -98,11 +123,21 @@ fn main() -> ExitCode {
     }
 }
 
-fn init_logging(log_format: LogFormat) {
+fn init_logging(log_format: LogFormat, output: OutputFormat) {
+    // keep stdout for the json output of the command
+    let writer = match output {
+        OutputFormat::Text => BoxMakeWriter::new(std::io::stdout),
+        OutputFormat::Json => BoxMakeWriter::new(std::io::stderr),
+    };
+
     match log_format {
         LogFormat::Text => {
             tracing_subscriber::registry()
-                .with(tracing_subscriber::fmt::layer().with_filter(EnvFilter::from_default_env()))
+                .with(
+                    tracing_subscriber::fmt::layer()
+                        .with_writer(writer)
+                        .with_filter(EnvFilter::from_default_env()),
+                )
                 .init();
         }
         LogFormat::Json => {
@@ -110,6 +145,7 @@ fn init_logging(log_format: LogFormat) {
                 .with(
                     tracing_subscriber::fmt::layer()
                         .json()
+                        .with_writer(writer)
                         .with_filter(EnvFilter::from_default_env()),
                 )
                 .init();
@@ -120,6 +156,7 @@ fn init_logging(log_format: LogFormat) {
 #[allow(clippy::too_many_lines)]
 // NOTE: This function is a mess, will be cleaned up
 async fn do_main(app: cli::App) -> anyhow::Result<()> {
+    let output = app.output;
     let get_voyager_config = || get_voyager_config(app.config_file_path.as_deref());
 
     let get_rest_url = |rest_url: Option<String>| match (get_voyager_config(), rest_url) {
@@ -172,7 +209,11 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                     }),
                     optimizer_delay_milliseconds: 100,
                     ipc_client_request_timeout: Duration::new(60, 0),
+                    shutdown_timeout: default_shutdown_timeout(),
                     cache: voyager_core::cache::Config::default(),
+                    backpressure: BackpressureConfig::default(),
+                    finality_delay_seconds: HashMap::new(),
+                    health: HealthConfig::default(),
                 },
             }),
             ConfigCmd::Schema => print_json(
@@ -182,6 +223,60 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                 }))
                 .into_root_schema_for::<Config>(),
             ),
+            ConfigCmd::Validate => {
+                let config = get_voyager_config()?;
+
+                config_schema::validate_configs(&config.plugins, &config.modules).await?;
+
+                println!("config is valid");
+            }
+            ConfigCmd::Diff {
+                new_config_file_path,
+                rpc_url,
+            } => print_json(&config_apply::diff(
+                &config_apply::running_config(
+                    &jsonrpsee::http_client::HttpClient::builder().build(get_rpc_url(rpc_url))?,
+                )
+                .await?,
+                &cli::get_voyager_config(Some(&new_config_file_path))?,
+            )),
+            ConfigCmd::Apply {
+                new_config_file_path,
+                staged,
+                restart_command,
+                health_timeout,
+                rpc_url,
+            } => {
+                let config_file_path = app
+                    .config_file_path
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("config file must be specified"))?;
+
+                let new_raw =
+                    std::fs::read_to_string(&new_config_file_path).with_context(|| {
+                        format!(
+                            "unable to read the config file at `{}`",
+                            new_config_file_path.to_string_lossy()
+                        )
+                    })?;
+
+                let new_config = cli::get_voyager_config(Some(&new_config_file_path))?;
+
+                config_schema::validate_configs(&new_config.plugins, &new_config.modules).await?;
+
+                config_apply::apply(
+                    &jsonrpsee::http_client::HttpClient::builder().build(get_rpc_url(rpc_url))?,
+                    config_file_path.as_ref(),
+                    &new_config,
+                    &new_raw,
+                    staged,
+                    &restart_command,
+                    Duration::from_secs(health_timeout),
+                )
+                .await?;
+
+                print_status(output, None, &json!({ "applied": true }));
+            }
         },
         Command::Start => {
             let config = get_voyager_config()?;
@@ -189,16 +284,21 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
             metrics::init(&config.voyager.metrics_endpoint);
 
             let voyager = Engine::builder()
+                .with_config(serde_json::to_value(&config)?)
                 .with_equivalent_chain_ids(config.equivalent_chain_ids)
                 .with_plugins(config.plugins)
                 .with_modules(config.modules)
                 .with_ipc_client_request_timeout(config.voyager.ipc_client_request_timeout)
+                .with_shutdown_timeout(config.voyager.shutdown_timeout)
                 .with_cache_config(config.voyager.cache)
                 .with_metrics_endpoint(config.voyager.metrics_endpoint)
                 .with_num_workers(config.voyager.num_workers.into())
                 .with_rest_laddr(config.voyager.rest_laddr)
                 .with_rpc_laddr(config.voyager.rpc_laddr)
                 .with_optimizer_delay_milliseconds(config.voyager.optimizer_delay_milliseconds)
+                .with_backpressure_config(config.voyager.backpressure)
+                .with_finality_delay_seconds(config.voyager.finality_delay_seconds)
+                .with_health_config(config.voyager.health)
                 .with_queue::<QueueImpl>(config.voyager.queue)
                 .register_ibc_spec_handler::<IbcUnion>()
                 .register_ibc_spec_handler::<IbcClassic>()
@@ -207,7 +307,30 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
 
             info!("starting relay service");
 
-            voyager.run().await;
+            {
+                let run = voyager.run();
+
+                tokio::pin!(run);
+
+                let signalled = tokio::select! {
+                    () = &mut run => false,
+                    () = shutdown_signal() => true,
+                };
+
+                if signalled {
+                    info!("received shutdown signal, finishing in-flight messages");
+
+                    voyager.stop();
+
+                    run.await;
+                }
+            }
+
+            info!("shutting down plugins and modules");
+
+            voyager.shutdown().await;
+
+            info!("shut down");
         }
         Command::Plugin(cmd) => match cmd {
             PluginCmd::Interest {
@@ -231,14 +354,22 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                 );
 
                 match result {
-                    Ok(JaqFilterResult::Take(tag)) => {
-                        println!("interest (take, {tag})");
+                    Ok(JaqFilterResult::Take(tag)) => print_status(
+                        output,
+                        Some(&format!("interest (take, {tag})")),
+                        &json!({ "interest": "take", "tag": tag }),
+                    ),
+                    Ok(JaqFilterResult::Copy(tag)) => print_status(
+                        output,
+                        Some(&format!("interest (copy, {tag})")),
+                        &json!({ "interest": "copy", "tag": tag }),
+                    ),
+                    Ok(JaqFilterResult::NoInterest) => {
+                        print_status(output, Some("no interest"), &json!({ "interest": "none" }))
                     }
-                    Ok(JaqFilterResult::Copy(tag)) => {
-                        println!("interest (copy, {tag})");
+                    Err(()) => {
+                        print_status(output, Some("failed"), &json!({ "interest": "failed" }));
                     }
-                    Ok(JaqFilterResult::NoInterest) => println!("no interest"),
-                    Err(()) => println!("failed"),
                 }
             }
             PluginCmd::Info { plugin_name } => {
@@ -284,10 +415,10 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
             let db = || {
                 Ok(match get_voyager_config()?.voyager.queue {
                     QueueConfig::PgQueue(cfg) => pg_queue::PgQueue::<VoyagerMessage>::new(cfg),
-                    QueueConfig::InMemory => {
+                    QueueConfig::InMemory | QueueConfig::Sqlite(_) => {
                         return Err(anyhow!(
-                            "no database set in config, queue commands \
-                            require the `pg-queue` database backend"
+                            "queue commands require the `pg-queue` \
+                            database backend"
                         ))
                     }
                 })
@@ -297,7 +428,60 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                 QueueCmd::Enqueue { op, rest_url } => {
                     let rest_url = get_rest_url(rest_url);
 
-                    send_enqueue(&rest_url, op).await?;
+                    send_enqueue(&rest_url, &op).await?;
+
+                    print_enqueued(output, &op);
+                }
+                QueueCmd::EnqueueRange {
+                    chain,
+                    from,
+                    to,
+                    rest_url,
+                } => {
+                    let rest_url = get_rest_url(rest_url);
+
+                    let op = call::<VoyagerMessage>(IndexRange {
+                        chain_id: chain,
+                        range: IndexRangeHeights::new(from, to)?,
+                    });
+
+                    if output == OutputFormat::Text {
+                        print_json(&op);
+                    }
+
+                    send_enqueue(&rest_url, &op).await?;
+
+                    print_enqueued(output, &op);
+                }
+                QueueCmd::EnqueueTemplate { template, rest_url } => {
+                    let rest_url = get_rest_url(rest_url);
+
+                    let response = reqwest::Client::new()
+                        .post(format!("{rest_url}/enqueue/template"))
+                        .json(&template)
+                        .send()
+                        .await?;
+
+                    // the body contains the reason the template could not be expanded
+                    let status = response.status();
+                    if !status.is_success() {
+                        return Err(anyhow!(
+                            "unable to enqueue template ({status}): {}",
+                            response.text().await?
+                        ));
+                    }
+
+                    let op = response.json::<Op<VoyagerMessage>>().await?;
+
+                    match output {
+                        OutputFormat::Text => println!(
+                            "enqueued {}",
+                            serde_json::to_string_pretty(&op)
+                                .expect("serialization is infallible; qed;")
+                        ),
+                        // the expanded op, which can be enqueued again with `voyager queue enqueue`
+                        OutputFormat::Json => print_json(&op),
+                    }
                 }
                 // NOTE: Temporarily disabled until i figure out a better way to implement this with the new queue design
                 // cli::QueueCmd::History { id, max_depth } => {
@@ -340,14 +524,124 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                     let record = q.query_failed_by_id(id.inner()).await?;
 
                     if requeue {
-                        if let Some(op) = record.as_ref().map(|r| r.item.0.clone()) {
+                        let op = record.as_ref().map(|r| r.item.0.clone());
+
+                        if let Some(op) = &op {
                             send_enqueue(&rest_url, op).await?;
-                            println!("requeued");
                         }
+
+                        print_status(
+                            output,
+                            op.is_some().then_some("requeued"),
+                            &json!({ "id": id.inner(), "requeued": op.is_some() }),
+                        );
                     } else {
                         print_json(&record);
                     }
                 }
+                QueueCmd::Ls {
+                    table,
+                    filter,
+                    page,
+                    per_page,
+                } => {
+                    let items = db()?
+                        .await?
+                        .list_items(
+                            table.into(),
+                            filter.needles(),
+                            page.into(),
+                            per_page.into(),
+                        )
+                        .await?
+                        .into_iter()
+                        .map(ItemSummary::new)
+                        .collect::<Vec<_>>();
+
+                    match output {
+                        OutputFormat::Text => {
+                            for item in &items {
+                                println!(
+                                    "{id}\t{ty}\t{plugin}\t{chain_id}{message}",
+                                    id = item.id,
+                                    ty = item.r#type,
+                                    plugin = item.plugin.as_deref().unwrap_or("-"),
+                                    chain_id = item.chain_id.as_deref().unwrap_or("-"),
+                                    message = item
+                                        .message
+                                        .as_ref()
+                                        .map(|message| format!("\t{message}"))
+                                        .unwrap_or_default(),
+                                );
+                            }
+                        }
+                        OutputFormat::Json => print_json(&items),
+                    }
+                }
+                QueueCmd::Peek { id, table } => {
+                    let item = db()?.await?.get_item(table.into(), id.inner()).await?;
+
+                    print_json(&item);
+                }
+                QueueCmd::Drop { ids, table } => {
+                    let ids = ids.into_iter().map(|id| id.inner()).collect::<Vec<_>>();
+
+                    let dropped = db()?.await?.delete_items(table.into(), ids.clone()).await?;
+
+                    let skipped = ids
+                        .into_iter()
+                        .filter(|id| !dropped.contains(id))
+                        .collect::<Vec<_>>();
+
+                    print_status(
+                        output,
+                        Some(&format!(
+                            "dropped {} item(s) from {table}{}",
+                            dropped.len(),
+                            if skipped.is_empty() {
+                                String::new()
+                            } else {
+                                format!(", skipped {skipped:?} (not found or in progress)")
+                            }
+                        )),
+                        &json!({ "dropped": dropped, "skipped": skipped }),
+                    );
+                }
+                QueueCmd::Requeue { ids, rest_url } => {
+                    let rest_url = get_rest_url(rest_url);
+
+                    let q = db()?.await?;
+
+                    let mut requeued = vec![];
+                    let mut missing = vec![];
+
+                    for id in ids.into_iter().map(|id| id.inner()) {
+                        let Some(record) = q.get_item(pg_queue::Table::Failed, id).await? else {
+                            missing.push(id);
+                            continue;
+                        };
+
+                        // enqueue before removing the failed item so that it is never lost
+                        send_enqueue(&rest_url, &record.item.0).await?;
+                        q.delete_items(pg_queue::Table::Failed, vec![id]).await?;
+
+                        requeued.push(id);
+                    }
+
+                    print_status(
+                        output,
+                        Some(&format!(
+                            "requeued {} item(s){}",
+                            requeued.len(),
+                            if missing.is_empty() {
+                                String::new()
+                            } else {
+                                format!(", {missing:?} not found")
+                            }
+                        )),
+                        &json!({ "requeued": requeued, "missing": missing }),
+                    );
+                }
             }
         }
         Command::Index {
@@ -370,24 +664,26 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                     range: IndexRangeHeights::new(exact, exact).expect("valid"),
                 })
             } else {
-                let start_height = match from {
+                let resolve_height = async |height: QueryHeight| match height {
                     QueryHeight::Latest => {
                         voyager_client
                             .query_latest_height(chain_id.clone(), false)
-                            .await?
+                            .await
                     }
                     QueryHeight::Finalized => {
                         voyager_client
                             .query_latest_height(chain_id.clone(), true)
-                            .await?
+                            .await
                     }
-                    QueryHeight::Specific(height) => height,
+                    QueryHeight::Specific(height) => Ok(height),
                 };
 
+                let start_height = resolve_height(from).await?;
+
                 if let Some(to) = to {
                     call(IndexRange {
                         chain_id: chain_id.clone(),
-                        range: IndexRangeHeights::new(start_height, to)?,
+                        range: IndexRangeHeights::new(start_height, resolve_height(to).await?)?,
                     })
                 } else {
                     call(Index {
@@ -397,10 +693,16 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                 }
             };
 
-            print_json(&op);
-
             if enqueue {
-                send_enqueue(&rest_url, op).await?;
+                if output == OutputFormat::Text {
+                    print_json(&op);
+                }
+
+                send_enqueue(&rest_url, &op).await?;
+
+                print_enqueued(output, &op);
+            } else {
+                print_json(&op);
             }
         }
         Command::Rpc { cmd, rpc_url } => {
@@ -417,6 +719,7 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
 
             match cmd {
                 RpcCmd::Info => print_json(&voyager_client.info().await?),
+                RpcCmd::ModuleHealth => print_json(&voyager_client.module_health().await?),
                 RpcCmd::ClientMeta {
                     on,
                     client_id,
@@ -567,6 +870,16 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                         }
                     }
                 }
+                RpcCmd::SelfClientMeta {
+                    on,
+                    client_type,
+                    height,
+                } => {
+                    let meta = voyager_client
+                        .self_client_meta(on, client_type, height)
+                        .await?;
+                    print_json(&meta);
+                }
                 RpcCmd::LatestHeight { on, finalized } => {
                     let height = voyager_client.query_latest_height(on, finalized).await?;
                     print_json(&height);
@@ -636,6 +949,7 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                 client_type,
                 height,
                 metadata,
+                dry_run,
                 enqueue,
                 rest_url,
                 rpc_url,
@@ -658,24 +972,54 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                     jsonrpsee::http_client::HttpClient::builder().build(get_rpc_url(rpc_url))?,
                 );
 
-                let op = utils::make_msg_create_client(
-                    &voyager_client,
-                    tracking,
-                    height,
-                    on,
-                    client_type,
-                    ibc_interface,
-                    ibc_spec_id,
-                    metadata,
-                    client_state_config,
-                    consensus_state_config,
-                )
-                .await?;
+                if dry_run {
+                    let (self_client_state, self_consensus_state) = utils::fetch_self_states(
+                        &voyager_client,
+                        tracking.clone(),
+                        height,
+                        client_type.clone(),
+                        client_state_config,
+                        consensus_state_config,
+                    )
+                    .await?;
 
-                if enqueue {
-                    send_enqueue(&get_rest_url(rest_url), op).await?;
+                    let preview = ClientBootstrapPreview::new(
+                        tracking,
+                        client_type,
+                        self_client_state,
+                        self_consensus_state,
+                    );
+
+                    match output {
+                        OutputFormat::Text => println!(
+                            "{}",
+                            serde_json::to_string_pretty(&preview)
+                                .expect("serialization is infallible; qed;")
+                        ),
+                        OutputFormat::Json => print_json(&preview),
+                    }
                 } else {
-                    print_json(&op);
+                    let op = utils::make_msg_create_client(
+                        &voyager_client,
+                        tracking,
+                        height,
+                        on.expect("guaranteed to exist by clap; qed;"),
+                        client_type,
+                        ibc_interface.expect("guaranteed to exist by clap; qed;"),
+                        ibc_spec_id,
+                        metadata,
+                        client_state_config,
+                        consensus_state_config,
+                    )
+                    .await?;
+
+                    if enqueue {
+                        send_enqueue(&get_rest_url(rest_url), &op).await?;
+
+                        print_enqueued(output, &op);
+                    } else {
+                        print_json(&op);
+                    }
                 }
             }
             MsgCmd::UpdateClient {
@@ -704,17 +1048,23 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                     )
                     .await?;
 
-                let update_to = match update_to {
-                    Some(update_to) => update_to,
-                    None => {
-                        voyager_client
-                            .query_latest_height(
-                                client_state_meta.counterparty_chain_id.clone(),
-                                true,
-                            )
-                            .await?
-                    }
-                };
+                let latest_height = voyager_client
+                    .query_latest_height(client_state_meta.counterparty_chain_id.clone(), true)
+                    .await?;
+
+                let update_to = update_to.unwrap_or(latest_height);
+
+                // the finality delay is measured from when the height is finalized, which is only
+                // known once it is
+                if update_to > latest_height
+                    && voyager_client.finality_delay(on.clone()).await?.is_some()
+                {
+                    return Err(anyhow!(
+                        "height {update_to} of {} is not finalized yet, client updates on {on} are \
+                        held back for the finality delay after the height is finalized",
+                        client_state_meta.counterparty_chain_id
+                    ));
+                }
 
                 let op = promise::<VoyagerMessage>(
                     [call(FetchUpdateHeaders {
@@ -733,13 +1083,135 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                     },
                 );
 
+                let op = voyager_client.with_finality_delay(on.clone(), op).await?;
+
                 if enqueue {
-                    send_enqueue(&get_rest_url(rest_url), op).await?;
+                    send_enqueue(&get_rest_url(rest_url), &op).await?;
+
+                    print_enqueued(output, &op);
                 } else {
                     print_json(&op);
                 }
             }
+            MsgCmd::PrepareClientRecovery {
+                on,
+                ibc_spec_id,
+                client_id,
+                substitute_client_id,
+                authority,
+                deposit,
+                title,
+                config,
+                enqueue,
+                rest_url,
+                rpc_url,
+            } => {
+                let voyager_client = VoyagerClient::new(
+                    jsonrpsee::http_client::HttpClient::builder().build(get_rpc_url(rpc_url))?,
+                );
+
+                let (subject, client_info) = fetch_recovery_client(
+                    &voyager_client,
+                    on.clone(),
+                    ibc_spec_id.clone(),
+                    client_id,
+                )
+                .await?;
+
+                match substitute_client_id {
+                    Some(substitute_client_id) => {
+                        let (substitute, _) = fetch_recovery_client(
+                            &voyager_client,
+                            on,
+                            ibc_spec_id,
+                            substitute_client_id,
+                        )
+                        .await?;
+
+                        let proposal = RecoverClientProposal::new(
+                            &subject,
+                            &substitute,
+                            authority.expect("authority is required with a substitute; qed;"),
+                            deposit,
+                            title,
+                        )?;
+
+                        print_json(&proposal);
+                    }
+                    None => {
+                        let height = voyager_client
+                            .query_latest_height(subject.counterparty_chain_id.clone(), true)
+                            .await?;
+
+                        check_substitute_height(subject.height, height)?;
+
+                        let op = utils::make_msg_create_client(
+                            &voyager_client,
+                            subject.counterparty_chain_id,
+                            QueryHeight::Specific(height),
+                            on,
+                            client_info.client_type,
+                            client_info.ibc_interface,
+                            ibc_spec_id,
+                            client_info.metadata,
+                            config.clone(),
+                            config,
+                        )
+                        .await?;
+
+                        if enqueue {
+                            send_enqueue(&get_rest_url(rest_url), &op).await?;
+
+                            print_enqueued(output, &op);
+                        } else {
+                            print_json(&op);
+                        }
+                    }
+                }
+            }
         },
+        Command::Doctor {
+            clients,
+            ibc_spec_id,
+            transaction_plugins,
+            max_block_age,
+            max_pending,
+            client_expiry_margin,
+            rpc_url,
+        } => {
+            let voyager_client =
+                jsonrpsee::http_client::HttpClient::builder().build(get_rpc_url(rpc_url))?;
+
+            let problems = diagnose(
+                &voyager_client,
+                &ibc_spec_id,
+                &transaction_plugins,
+                &clients,
+                &Thresholds {
+                    max_block_age: Duration::from_secs(max_block_age),
+                    max_pending,
+                    client_expiry_margin: Duration::from_secs(client_expiry_margin),
+                },
+            )
+            .await;
+
+            match output {
+                OutputFormat::Text => {
+                    println!("{}", format_problems(&problems));
+
+                    let critical = problems
+                        .iter()
+                        .filter(|problem| problem.severity == Severity::Critical)
+                        .count();
+
+                    if critical > 0 {
+                        return Err(anyhow!("found {critical} critical problem(s)"));
+                    }
+                }
+                // the problems are the only json document printed, the severities are in the output
+                OutputFormat::Json => print_json(&problems),
+            }
+        }
     }
 
     Ok(())
@@ -747,13 +1219,42 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
 
 async fn send_enqueue(
     rest_laddr: &str,
-    op: Op<VoyagerMessage>,
+    op: &Op<VoyagerMessage>,
 ) -> anyhow::Result<reqwest::Response> {
     Ok(reqwest::Client::new()
         .post(format!("{rest_laddr}/enqueue"))
-        .json(&op)
+        .json(op)
         .send()
-        .await?)
+        .await?
+        .error_for_status()?)
+}
+
+/// Report the status of a command that has no other output. Commands without text output
+/// (`text: None`) stay silent unless the output is json.
+fn print_status(output: OutputFormat, text: Option<&str>, json: &Value) {
+    match output {
+        OutputFormat::Text => {
+            if let Some(text) = text {
+                println!("{text}");
+            }
+        }
+        OutputFormat::Json => print_json(json),
+    }
+}
+
+fn print_enqueued(output: OutputFormat, op: &Op<VoyagerMessage>) {
+    print_status(output, None, &json!({ "enqueued": op }));
+}
+
+/// Resolves once voyager receives `SIGTERM` or `SIGINT`.
+async fn shutdown_signal() {
+    let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .expect("unable to listen for SIGTERM");
+
+    tokio::select! {
+        _ = sigterm.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
 }
 
 fn print_json<T: Serialize>(t: &T) {
@@ -774,21 +1275,19 @@ pub mod utils {
     use voyager_client::VoyagerClient;
     use voyager_message::{call::SubmitTx, data::IbcDatagram, VoyagerMessage};
     use voyager_primitives::{ChainId, ClientType, IbcInterface, IbcSpecId, QueryHeight};
+    use voyager_rpc::types::{SelfClientStateResponse, SelfConsensusStateResponse};
     use voyager_vm::{call, Op};
 
-    #[allow(clippy::too_many_arguments)]
-    pub(crate) async fn make_msg_create_client<C: ClientT + Send + Sync>(
+    /// Fetch the client and consensus states from the client bootstrap module for
+    /// `counterparty_chain_id`, at the same (finalized) height.
+    pub(crate) async fn fetch_self_states<C: ClientT + Send + Sync>(
         voyager_client: &VoyagerClient<C>,
         counterparty_chain_id: ChainId,
         height: QueryHeight,
-        chain_id: ChainId,
         client_type: ClientType,
-        ibc_interface: IbcInterface,
-        ibc_spec_id: IbcSpecId,
-        metadata: Value,
         client_state_config: Value,
         consensus_state_config: Value,
-    ) -> anyhow::Result<Op<VoyagerMessage>> {
+    ) -> anyhow::Result<(SelfClientStateResponse, SelfConsensusStateResponse)> {
         if height == QueryHeight::Latest {
             // TODO: Also check if a specific height was passed and ensure that that height is also finalized
             bail!("cannot create a client at a non-finalized height")
@@ -815,20 +1314,46 @@ pub mod utils {
                 QueryHeight::Specific(height),
                 client_state_config,
             )
-            .await?
-            .state;
-        trace!(%self_client_state);
+            .await?;
+        trace!(self_client_state = %self_client_state.state);
 
         let self_consensus_state = voyager_client
             .self_consensus_state(
-                counterparty_chain_id.clone(),
-                client_type.clone(),
+                counterparty_chain_id,
+                client_type,
                 QueryHeight::Specific(height),
                 consensus_state_config,
             )
-            .await?
-            .state;
-        trace!(%self_consensus_state);
+            .await?;
+        trace!(self_consensus_state = %self_consensus_state.state);
+
+        Ok((self_client_state, self_consensus_state))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn make_msg_create_client<C: ClientT + Send + Sync>(
+        voyager_client: &VoyagerClient<C>,
+        counterparty_chain_id: ChainId,
+        height: QueryHeight,
+        chain_id: ChainId,
+        client_type: ClientType,
+        ibc_interface: IbcInterface,
+        ibc_spec_id: IbcSpecId,
+        metadata: Value,
+        client_state_config: Value,
+        consensus_state_config: Value,
+    ) -> anyhow::Result<Op<VoyagerMessage>> {
+        let (self_client_state, self_consensus_state) = fetch_self_states(
+            voyager_client,
+            counterparty_chain_id,
+            height,
+            client_type.clone(),
+            client_state_config,
+            consensus_state_config,
+        )
+        .await?;
+        let self_client_state = self_client_state.state;
+        let self_consensus_state = self_consensus_state.state;
 
         // let consensus_type = ctx
         //     .rpc_server
@@ -899,6 +1424,7 @@ pub mod utils {
                 }
                 _ => bail!("unknown IBC version id `{ibc_spec_id}`"),
             }],
+            signers: None,
         }))
     }
 }
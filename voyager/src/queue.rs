@@ -3,9 +3,11 @@
 use std::fmt::Debug;
 
 use futures::Future;
-use pg_queue::{PgQueue, PgQueueConfig};
+use pg_queue::{ItemRecord, PgQueue, PgQueueConfig};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlite_queue::{SqliteQueue, SqliteQueueConfig};
 use voyager_message::VoyagerMessage;
 use voyager_vm::{
     filter::InterestFilter, in_memory::InMemoryQueue, pass::Pass, Captures, EnqueueResult, ItemId,
@@ -17,12 +19,14 @@ use voyager_vm::{
 pub enum QueueConfig {
     InMemory,
     PgQueue(PgQueueConfig),
+    Sqlite(SqliteQueueConfig),
 }
 
 #[derive(Debug, Clone)]
 pub enum QueueImpl {
     InMemory(InMemoryQueue<VoyagerMessage>),
     PgQueue(PgQueue<VoyagerMessage>),
+    Sqlite(SqliteQueue<VoyagerMessage>),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -30,6 +34,7 @@ pub enum QueueImpl {
 pub enum AnyQueueError {
     InMemory(std::convert::Infallible),
     PgQueue(sqlx::Error),
+    Sqlite(sqlx::Error),
 }
 
 impl Queue<VoyagerMessage> for QueueImpl {
@@ -46,6 +51,10 @@ impl Queue<VoyagerMessage> for QueueImpl {
                 .await
                 .map_err(AnyQueueError::PgQueue)
                 .map(Self::PgQueue),
+            QueueConfig::Sqlite(cfg) => SqliteQueue::new(cfg)
+                .await
+                .map_err(AnyQueueError::Sqlite)
+                .map(Self::Sqlite),
         }
     }
 
@@ -63,6 +72,10 @@ impl Queue<VoyagerMessage> for QueueImpl {
                 .enqueue(item, filter)
                 .await
                 .map_err(AnyQueueError::PgQueue),
+            QueueImpl::Sqlite(queue) => queue
+                .enqueue(item, filter)
+                .await
+                .map_err(AnyQueueError::Sqlite),
         }
     }
 
@@ -86,6 +99,10 @@ impl Queue<VoyagerMessage> for QueueImpl {
                 .process(filter, f)
                 .await
                 .map_err(AnyQueueError::PgQueue),
+            QueueImpl::Sqlite(queue) => queue
+                .process(filter, f)
+                .await
+                .map_err(AnyQueueError::Sqlite),
         }
     }
 
@@ -104,6 +121,145 @@ impl Queue<VoyagerMessage> for QueueImpl {
                 .optimize(tag, filter, optimizer)
                 .await
                 .map_err(|e| e.map_left(AnyQueueError::PgQueue)),
+            QueueImpl::Sqlite(queue) => queue
+                .optimize(tag, filter, optimizer)
+                .await
+                .map_err(|e| e.map_left(AnyQueueError::Sqlite)),
         }
     }
+
+    async fn pending_count<'a>(&'a self, value: &'a str) -> Result<u64, Self::Error> {
+        match self {
+            QueueImpl::InMemory(queue) => queue
+                .pending_count(value)
+                .await
+                .map_err(AnyQueueError::InMemory),
+            QueueImpl::PgQueue(queue) => queue
+                .pending_count(value)
+                .await
+                .map_err(AnyQueueError::PgQueue),
+            QueueImpl::Sqlite(queue) => queue
+                .pending_count(value)
+                .await
+                .map_err(AnyQueueError::Sqlite),
+        }
+    }
+}
+
+/// A summary of an item in the queue, as printed by `voyager queue ls`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemSummary {
+    pub id: i64,
+    pub parents: Vec<i64>,
+    /// The nested types of the op, from the outermost inwards (i.e. `call/plugin/make_msg_union`).
+    pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<String>,
+    /// The tag of the optimizer, for items waiting to be optimized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// The error message, for failed items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ItemSummary {
+    #[must_use]
+    pub fn new(record: ItemRecord<VoyagerMessage>) -> Self {
+        let item = serde_json::to_value(&record.item.0).expect("serialization is infallible; qed;");
+
+        let mut types = vec![];
+        let mut plugin = None;
+        let mut chain_id = None;
+
+        let mut value = &item;
+        while let Some(ty) = value.get("@type").and_then(Value::as_str) {
+            types.push(ty);
+
+            let Some(inner) = value.get("@value") else {
+                break;
+            };
+
+            let field = |key| inner.get(key).and_then(Value::as_str).map(ToOwned::to_owned);
+            plugin = plugin.or_else(|| field("plugin"));
+            chain_id = chain_id.or_else(|| field("chain_id"));
+
+            // descend into the message of plugin messages and the receiver of promises
+            value = inner
+                .get("message")
+                .or_else(|| inner.get("receiver"))
+                .unwrap_or(inner);
+        }
+
+        Self {
+            id: record.id,
+            parents: record.parents,
+            r#type: types.join("/"),
+            plugin,
+            chain_id,
+            tag: record.tag,
+            message: record.message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::types::Json;
+
+    use super::*;
+    use crate::cli::ItemFilter;
+
+    #[test]
+    fn item_summary() {
+        let item = serde_json::from_value::<Op<VoyagerMessage>>(serde_json::json!({
+            "@type": "call",
+            "@value": {
+                "@type": "plugin",
+                "@value": {
+                    "plugin": "voyager-plugin-transaction-batch/union-devnet-1",
+                    "message": {
+                        "@type": "make_msg_union",
+                        "@value": {}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let summary = ItemSummary::new(ItemRecord {
+            id: 2,
+            parents: vec![1],
+            item: Json(item),
+            tag: None,
+            message: None,
+        });
+
+        assert_eq!(summary.r#type, "call/plugin/make_msg_union");
+        assert_eq!(
+            summary.plugin.as_deref(),
+            Some("voyager-plugin-transaction-batch/union-devnet-1")
+        );
+        assert_eq!(summary.chain_id, None);
+    }
+
+    #[test]
+    fn item_filter_needles() {
+        let filter = ItemFilter {
+            plugin: None,
+            message_type: Some("submit_tx".to_owned()),
+            chain: Some("union-devnet-1".to_owned()),
+        };
+
+        assert_eq!(
+            filter.needles(),
+            [
+                r#""@type": "submit_tx""#,
+                r#""chain_id": "union-devnet-1""#
+            ]
+        );
+        assert!(ItemFilter::default().needles().is_empty());
+    }
 }
@@ -309,6 +309,8 @@
                 '';
                 Restart = lib.mkForce "always";
                 RestartSec = 10;
+                # only signal voyager on stop, it shuts down its plugins and modules itself
+                KillMode = "mixed";
                 RuntimeMaxSec = instance.settings.runtime-max-secs;
               };
               environment = {